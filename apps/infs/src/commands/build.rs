@@ -12,13 +12,84 @@
 //!
 //! Phases execute in canonical order (parse -> analyze -> codegen) regardless
 //! of the order flags appear on the command line. Each phase depends on the previous.
+//!
+//! ## Project Builds
+//!
+//! Without a path, or given a project directory or its `Inference.toml`, the whole
+//! project is compiled as one module: the root file `src/main.inf` or `src/lib.inf`
+//! followed by every other `.inf` file under `src/`. Artifacts of a project build are
+//! written to `target/` in the project directory, and those of a single file to `out/`
+//! in the current directory.
+//!
+//! The `[dependencies]` of the manifest are resolved before compiling, as described in
+//! [`crate::project::dependencies`], and passed to infc with `--extern`. The resolved
+//! commits of git dependencies are recorded in `Inference.lock`.
+//!
+//! A project build is skipped when nothing it depends on changed since the last
+//! successful one: the toolchain, the options and the sources of the project and its
+//! dependencies are fingerprinted as described in [`crate::project::fingerprint`]. Like
+//! cargo, the build prints `Fresh` or `Compiling` with the package name on stderr.
+//!
+//! ## Hooks
+//!
+//! The `prebuild` and `postbuild` commands of the `[hooks]` section run before and after
+//! a project build, with the package, output directory and artifacts in environment
+//! variables, as described in [`crate::project::hooks`].
+//!
+//! ## Workspaces
+//!
+//! Given the root of a [workspace](crate::project::workspace), every member is built in
+//! turn, into `target/<name>/` of the root; given a member, only that member is. The
+//! dependencies of all members are resolved together into the `Inference.lock` of the
+//! root, and each member compiles with its own profiles.
+//!
+//! ## Profiles
+//!
+//! The codegen phase compiles with the `dev` profile, or the `release` profile with
+//! `--release` or `[build] optimize = "release"`. A profile sets the optimization level,
+//! debug information, bounds checks and assert strategy; the `[profile.dev]` and
+//! `[profile.release]` sections of the manifest override the built-in profiles, and
+//! flags given on the command line override both.
+//!
+//! ## JSON Output
+//!
+//! With `--output json`, infc reports its diagnostics as JSON, and the build prints one
+//! document once done, with an entry for every package, or for the single file:
+//!
+//! ```json
+//! {
+//!   "success": true,
+//!   "builds": [{
+//!     "package": "demo",
+//!     "version": "0.1.0",
+//!     "path": "/home/me/demo",
+//!     "status": "compiled",
+//!     "outputs": ["/home/me/demo/target/demo.wasm"],
+//!     "diagnostics": []
+//!   }]
+//! }
+//! ```
+//!
+//! `status` is `fresh` for a skipped build, `compiled` or `failed`, and `diagnostics`
+//! holds the errors and warnings of infc as printed by `--message-format json`.
 
 use anyhow::{Context, Result, bail};
 use clap::Args;
-use std::path::PathBuf;
-use std::process::Command;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use crate::errors::InfsError;
+use crate::output::{OutputFormat, print_json};
+use crate::project::dependencies::{
+    ResolvedDependency, resolve_dependencies, resolve_workspace_dependencies,
+};
+use crate::project::fingerprint;
+use crate::project::hooks;
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE, Profile, Profiles, project_dir};
+use crate::project::workspace::Workspace;
 use crate::toolchain::find_infc;
 
 /// Arguments for the build command.
@@ -34,13 +105,23 @@ use crate::toolchain::find_infc;
 ///
 /// ## Output Flags
 ///
-/// - `-o`: Generate WASM binary file in `out/` directory
-/// - `-v`: Generate Rocq (.v) translation in `out/` directory
-#[derive(Args)]
+/// - `-o`: Generate WASM binary file in the output directory
+/// - `-v`: Generate Rocq (.v) translation in the output directory
+///
+/// The output directory is `target/` in the project directory for project builds, and
+/// `out/` for single files.
+///
+/// ## Codegen Flags
+///
+/// `-O`, `--module-name`, `-g`, `--source-map` and `--feature` are forwarded to infc only when
+/// given, so infc's defaults apply otherwise. For the codegen phase, `-O`, `-g`,
+/// `--bounds-checks` and `--assert` default to the settings of the selected profile.
+#[derive(Args, Clone)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct BuildArgs {
-    /// Path to the source file to compile.
-    pub path: PathBuf,
+    /// Source file or project directory to compile (defaults to the project in the
+    /// current directory).
+    pub path: Option<PathBuf>,
 
     /// Run the parse phase to build the typed AST.
     #[clap(long = "parse", action = clap::ArgAction::SetTrue)]
@@ -61,29 +142,228 @@ pub struct BuildArgs {
     /// Generate Rocq (.v) translation file.
     #[clap(short = 'v', action = clap::ArgAction::SetTrue)]
     pub generate_v_output: bool,
+
+    /// Format of the errors and warnings reported by infc (`human` or `json`).
+    #[clap(long = "message-format", value_parser = ["human", "json"])]
+    pub message_format: Option<String>,
+
+    /// Compile with the `release` profile instead of `dev`.
+    #[clap(long = "release", action = clap::ArgAction::SetTrue)]
+    pub release: bool,
+
+    /// Backend generating the WebAssembly module (`llvm` or `encoder`).
+    #[clap(long = "backend", value_parser = ["llvm", "encoder"])]
+    pub backend: Option<String>,
+
+    /// LLVM optimization level for the codegen phase (0-3, default 3).
+    #[clap(
+        short = 'O',
+        long = "opt-level",
+        value_parser = clap::value_parser!(u32).range(0..=3)
+    )]
+    pub opt_level: Option<u32>,
+
+    /// Name of the generated LLVM module.
+    #[clap(long = "module-name")]
+    pub module_name: Option<String>,
+
+    /// Keep debug information in the generated WebAssembly binary.
+    #[clap(short = 'g', long = "debug-info", action = clap::ArgAction::SetTrue)]
+    pub debug_info: bool,
+
+    /// Add a source map relating code offsets to source lines and columns.
+    #[clap(long = "source-map", action = clap::ArgAction::SetTrue)]
+    pub source_map: bool,
+
+    /// Enable an additional WebAssembly target feature, e.g. `bulk-memory`.
+    #[clap(long = "feature", value_delimiter = ',')]
+    pub features: Vec<String>,
+
+    /// Linker used to produce the WebAssembly module (`rust-lld` or `builtin`).
+    #[clap(long = "linker", value_parser = ["rust-lld", "builtin"])]
+    pub linker: Option<String>,
+
+    /// How array indices are checked (`trap` or `unchecked`).
+    #[clap(long = "bounds-checks", value_parser = ["trap", "unchecked"])]
+    pub bounds_checks: Option<String>,
+
+    /// How a failing `assert` stops execution: `trap`, `abort[=<module>.<name>]` or
+    /// `result-code[=<code>]`.
+    #[clap(long = "assert")]
+    pub assert_strategy: Option<String>,
+
+    /// Import module of `external fn` declarations (default `env`).
+    #[clap(long = "import-module")]
+    pub import_module: Option<String>,
+
+    /// Import one `external fn` as `<function>=<module>.<name>`; may be repeated.
+    #[clap(long = "import")]
+    pub imports: Vec<String>,
+
+    /// Export only the given function or constant instead of every `pub fn` and `pub const`;
+    /// may be repeated.
+    #[clap(long = "export")]
+    pub exports: Vec<String>,
+
+    /// Export a function or constant under another name as `<name>=<export>`; may be
+    /// repeated.
+    #[clap(long = "export-name")]
+    pub export_names: Vec<String>,
+
+    /// Run the given function when the module is instantiated.
+    #[clap(long = "start")]
+    pub start_function: Option<String>,
+
+    /// Number of codegen units compiled on parallel threads (default 1).
+    #[clap(
+        long = "codegen-units",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub codegen_units: Option<u64>,
+
+    /// Reuse the object code of unchanged functions from previous builds.
+    #[clap(long = "incremental", action = clap::ArgAction::SetTrue)]
+    pub incremental: bool,
+
+    /// Directory of the incremental build cache (default `cache/` in the output
+    /// directory); implies `--incremental`.
+    #[clap(long = "cache-dir")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Target `wasm64` with a 64-bit linear memory.
+    #[clap(long = "memory64", action = clap::ArgAction::SetTrue)]
+    pub memory64: bool,
+
+    /// Initial size of the linear memory in 64 KiB pages.
+    #[clap(long = "initial-memory-pages")]
+    pub initial_memory_pages: Option<u64>,
+
+    /// Maximum size of the linear memory in 64 KiB pages.
+    #[clap(long = "max-memory-pages")]
+    pub max_memory_pages: Option<u64>,
+
+    /// Size of the stack in bytes (default 65536).
+    #[clap(long = "stack-size")]
+    pub stack_size: Option<u64>,
+
+    /// Trap when the stack overflows, or skip the check (`trap` or `unchecked`).
+    #[clap(long = "stack-checks", value_parser = ["trap", "unchecked"])]
+    pub stack_checks: Option<String>,
+}
+
+/// Result of a build, for `--output json`.
+#[derive(Serialize)]
+struct BuildOutput {
+    success: bool,
+    builds: Vec<BuildUnit>,
+}
+
+/// Result of building one package, or a single file.
+#[derive(Serialize)]
+struct BuildUnit {
+    /// Name of the package; `None` for a single file.
+    package: Option<String>,
+    version: Option<String>,
+    path: PathBuf,
+    /// `fresh` if the build was skipped, `compiled` or `failed`.
+    status: &'static str,
+    /// Files the build wrote to the output directory.
+    outputs: Vec<PathBuf>,
+    /// Errors and warnings of infc, as printed by `--message-format json`.
+    diagnostics: Vec<Value>,
+}
+
+impl BuildUnit {
+    fn new(
+        manifest: Option<&InferenceToml>,
+        path: &Path,
+        status: &'static str,
+        outputs: Vec<PathBuf>,
+        diagnostics: Vec<Value>,
+    ) -> Self {
+        Self {
+            package: manifest.map(|manifest| manifest.package.name.clone()),
+            version: manifest.map(|manifest| manifest.package.version.clone()),
+            path: path.canonicalize().unwrap_or_else(|_| path.to_path_buf()),
+            status,
+            outputs,
+            diagnostics,
+        }
+    }
 }
 
 /// Executes the build command with the given arguments.
 ///
 /// ## Execution Flow
 ///
-/// 1. Validates that the source file exists
+/// 1. Validates that the source file or project exists
 /// 2. Ensures at least one phase flag is specified
 /// 3. Locates the infc compiler binary
-/// 4. Builds and executes the infc command with appropriate flags
-/// 5. Propagates exit code from infc
+/// 4. Resolves the dependencies of a project, or of every member of its workspace, and
+///    updates the `Inference.lock`
+/// 5. Selects the build profile for the codegen phase
+/// 6. Builds the infc command with appropriate flags
+/// 7. For a project, runs the `prebuild` hook and skips the build if its fingerprint is
+///    unchanged
+/// 8. Executes the infc command and propagates its exit code
+/// 9. For a project, runs the `postbuild` hook after a successful build
 ///
 /// ## Errors
 ///
 /// Returns an error if:
-/// - The source file does not exist
+/// - The source file or project directory does not exist
+/// - A project directory has no `Inference.toml`
+/// - The workspace of the project has an invalid member
 /// - No phase flags are specified
 /// - infc compiler cannot be found
+/// - A dependency cannot be resolved
+/// - The selected profile has an invalid setting
 /// - infc exits with non-zero code (as `InfsError::ProcessExitCode`)
-pub fn execute(args: &BuildArgs) -> Result<()> {
-    if !args.path.exists() {
-        bail!("Path not found: {}", args.path.display());
+/// - The fingerprint of a project build cannot be computed or recorded
+/// - A hook fails
+pub fn execute(args: &BuildArgs, output: OutputFormat) -> Result<()> {
+    if !output.is_json() {
+        return build(args, None);
     }
+    let args = BuildArgs {
+        message_format: Some(String::from("json")),
+        ..args.clone()
+    };
+    let mut builds = Vec::new();
+    let result = build(&args, Some(&mut builds));
+    // Errors before or around the builds themselves are reported by `main` instead.
+    let reported = match &result {
+        Ok(()) => true,
+        Err(e) => matches!(
+            e.downcast_ref::<InfsError>(),
+            Some(InfsError::ProcessExitCode { .. })
+        ),
+    };
+    if reported {
+        print_json(&BuildOutput {
+            success: result.is_ok(),
+            builds,
+        })?;
+    }
+    result
+}
+
+/// Runs the build, adding the result of every package or file to `report` if given.
+fn build(args: &BuildArgs, report: Option<&mut Vec<BuildUnit>>) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !path.exists() {
+        bail!("Path not found: {}", path.display());
+    }
+    let project_dir = project_dir(&path);
+    if let Some(dir) = &project_dir
+        && !dir.join(MANIFEST_FILE).is_file()
+    {
+        bail!("No {MANIFEST_FILE} found in {}", dir.display());
+    }
+    let out_dir = match &project_dir {
+        Some(dir) => dir.join("target"),
+        None => PathBuf::from("out"),
+    };
 
     let need_parse = args.parse;
     let need_analyze = args.analyze;
@@ -93,33 +373,234 @@ pub fn execute(args: &BuildArgs) -> Result<()> {
         bail!("At least one of --parse, --analyze, or --codegen must be specified");
     }
 
+    let workspace = project_dir.as_deref().map(Workspace::find).transpose()?;
     let infc_path = find_infc()?;
 
-    let mut cmd = Command::new(&infc_path);
-    cmd.arg(&args.path);
+    if let (Some(dir), Some(Some(workspace))) = (&project_dir, &workspace) {
+        return build_workspace(args, &infc_path, workspace, dir, report);
+    }
 
-    if need_parse {
+    let manifest = project_dir
+        .as_ref()
+        .map(|dir| InferenceToml::read_from_file(&dir.join(MANIFEST_FILE)))
+        .transpose()?;
+    let profile = if need_codegen {
+        select_profile(args, manifest.as_ref())?
+    } else {
+        Profile::default()
+    };
+
+    let mut dependencies = Vec::new();
+    if let (Some(dir), Some(manifest)) = (&project_dir, &manifest) {
+        dependencies = resolve_dependencies(dir, manifest)?;
+    }
+    let cmd = infc_command(
+        args,
+        &infc_path,
+        &path,
+        &out_dir,
+        project_dir.is_some(),
+        &dependencies,
+        &profile,
+    );
+
+    match (&project_dir, &manifest, report) {
+        (Some(dir), Some(manifest), report) => build_project(
+            cmd,
+            &infc_path,
+            dir,
+            manifest,
+            &dependencies,
+            &out_dir,
+            report,
+        ),
+        (_, _, Some(report)) => {
+            let before = fingerprint::snapshot(&out_dir);
+            let mut diagnostics = Vec::new();
+            let result = run_infc(cmd, &infc_path, Some(&mut diagnostics));
+            let status = if result.is_ok() { "compiled" } else { "failed" };
+            let outputs = fingerprint::changed_files(&out_dir, &before);
+            report.push(BuildUnit::new(None, &path, status, outputs, diagnostics));
+            result
+        }
+        _ => run_infc(cmd, &infc_path, None),
+    }
+}
+
+/// Builds the members of `workspace` that `dir` selects, one after the other, into
+/// their directories under the shared `target/`.
+fn build_workspace(
+    args: &BuildArgs,
+    infc_path: &Path,
+    workspace: &Workspace,
+    dir: &Path,
+    mut report: Option<&mut Vec<BuildUnit>>,
+) -> Result<()> {
+    let dependencies = resolve_workspace_dependencies(workspace)?;
+    for (member, dependencies) in workspace.members.iter().zip(&dependencies) {
+        if !workspace.selects(dir, member) {
+            continue;
+        }
+        let out_dir = workspace.target_dir(member);
+        let profile = if args.codegen {
+            select_profile(args, Some(&member.manifest))?
+        } else {
+            Profile::default()
+        };
+        let cmd = infc_command(
+            args,
+            infc_path,
+            &member.dir,
+            &out_dir,
+            true,
+            dependencies,
+            &profile,
+        );
+        build_project(
+            cmd,
+            infc_path,
+            &member.dir,
+            &member.manifest,
+            dependencies,
+            &out_dir,
+            report.as_deref_mut(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Returns the infc command compiling `path` with `dependencies`, writing the outputs
+/// of a project to `out_dir`.
+fn infc_command(
+    args: &BuildArgs,
+    infc_path: &Path,
+    path: &Path,
+    out_dir: &Path,
+    project: bool,
+    dependencies: &[ResolvedDependency],
+    profile: &Profile,
+) -> Command {
+    let mut cmd = Command::new(infc_path);
+    cmd.arg(path);
+    if project {
+        cmd.arg("--out-dir").arg(out_dir);
+    }
+    for dependency in dependencies {
+        cmd.arg("--extern").arg(dependency.extern_arg());
+    }
+
+    if args.parse {
         cmd.arg("--parse");
     }
-    if need_analyze {
+    if args.analyze {
         cmd.arg("--analyze");
     }
-    if need_codegen {
+    if args.codegen {
         cmd.arg("--codegen");
     }
     if args.generate_wasm_output {
-        cmd.arg("-o");
+        cmd.arg("--emit=wasm");
     }
     if args.generate_v_output {
-        cmd.arg("-v");
+        cmd.arg("--emit=v");
+    }
+    if let Some(message_format) = &args.message_format {
+        cmd.arg("--message-format").arg(message_format);
     }
+    add_codegen_flags(&mut cmd, args, profile, out_dir);
+    cmd
+}
 
-    let status = cmd
-        .stdin(std::process::Stdio::inherit())
-        .stdout(std::process::Stdio::inherit())
-        .stderr(std::process::Stdio::inherit())
-        .status()
-        .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+/// Runs `cmd` for the project in `dir`, unless the fingerprint of the last successful
+/// build in `out_dir` is unchanged, and records the fingerprint after a successful one.
+///
+/// The `prebuild` hook runs first, and the `postbuild` hook after a successful build.
+fn build_project(
+    cmd: Command,
+    infc_path: &Path,
+    dir: &Path,
+    manifest: &InferenceToml,
+    dependencies: &[ResolvedDependency],
+    out_dir: &Path,
+    report: Option<&mut Vec<BuildUnit>>,
+) -> Result<()> {
+    let package = &manifest.package;
+    let unit = format!(
+        "{} v{} ({})",
+        package.name,
+        package.version,
+        dir.canonicalize()
+            .unwrap_or_else(|_| dir.to_path_buf())
+            .display()
+    );
+    hooks::run_prebuild(manifest, dir, out_dir)?;
+    let hash = fingerprint::compute(infc_path, cmd.get_args(), dir, dependencies)?;
+    if fingerprint::is_fresh(out_dir, &package.name, &hash) {
+        eprintln!("{:>12} {unit}", "Fresh");
+        if let Some(report) = report {
+            let outputs = fingerprint::outputs(out_dir, &package.name);
+            report.push(BuildUnit::new(
+                Some(manifest),
+                dir,
+                "fresh",
+                outputs,
+                Vec::new(),
+            ));
+        }
+        return Ok(());
+    }
+
+    eprintln!("{:>12} {unit}", "Compiling");
+    let before = fingerprint::snapshot(out_dir);
+    let mut diagnostics = Vec::new();
+    let result = run_infc(cmd, infc_path, report.is_some().then_some(&mut diagnostics));
+    let outputs = fingerprint::changed_files(out_dir, &before);
+    if let Some(report) = report {
+        let status = if result.is_ok() { "compiled" } else { "failed" };
+        report.push(BuildUnit::new(
+            Some(manifest),
+            dir,
+            status,
+            outputs.clone(),
+            diagnostics,
+        ));
+    }
+    result?;
+    hooks::run_postbuild(manifest, dir, out_dir, &outputs)?;
+    fingerprint::record(out_dir, &package.name, &hash, outputs)
+}
+
+/// Runs infc with inherited standard streams, failing with its exit code.
+///
+/// With `diagnostics`, the JSON lines infc prints on stdout are collected there instead,
+/// and its other output goes to stderr.
+fn run_infc(
+    mut cmd: Command,
+    infc_path: &Path,
+    diagnostics: Option<&mut Vec<Value>>,
+) -> Result<()> {
+    cmd.stdin(Stdio::inherit()).stderr(Stdio::inherit());
+    let status = if let Some(diagnostics) = diagnostics {
+        let mut child = cmd
+            .stdout(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(diagnostic @ Value::Object(_)) => diagnostics.push(diagnostic),
+                    _ => eprintln!("{line}"),
+                }
+            }
+        }
+        child
+            .wait()
+            .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?
+    } else {
+        cmd.stdout(Stdio::inherit())
+            .status()
+            .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?
+    };
 
     if status.success() {
         Ok(())
@@ -128,3 +609,97 @@ pub fn execute(args: &BuildArgs) -> Result<()> {
         Err(InfsError::process_exit_code(code).into())
     }
 }
+
+/// Returns the build profile selected by `--release` or the manifest.
+///
+/// Single files have no manifest and use the built-in profiles.
+fn select_profile(args: &BuildArgs, manifest: Option<&InferenceToml>) -> Result<Profile> {
+    match manifest {
+        Some(manifest) => manifest
+            .profile
+            .select(args.release || manifest.build.optimize == "release"),
+        None => Profiles::default().select(args.release),
+    }
+}
+
+/// Forwards the codegen flags of `args` to the infc command line, with the settings of
+/// `profile` for the flags not given.
+fn add_codegen_flags(cmd: &mut Command, args: &BuildArgs, profile: &Profile, out_dir: &Path) {
+    if let Some(backend) = &args.backend {
+        cmd.arg("--backend").arg(backend);
+    }
+    if let Some(opt_level) = args.opt_level.or(profile.opt_level) {
+        cmd.arg("-O").arg(opt_level.to_string());
+    }
+    if let Some(module_name) = &args.module_name {
+        cmd.arg("--module-name").arg(module_name);
+    }
+    if args.debug_info || profile.debug_info == Some(true) {
+        cmd.arg("-g");
+    }
+    if args.source_map {
+        cmd.arg("--source-map");
+    }
+    for feature in &args.features {
+        cmd.arg("--feature").arg(feature);
+    }
+    if let Some(linker) = &args.linker {
+        cmd.arg("--linker").arg(linker);
+    }
+    if let Some(bounds_checks) = args
+        .bounds_checks
+        .as_ref()
+        .or(profile.bounds_checks.as_ref())
+    {
+        cmd.arg("--bounds-checks").arg(bounds_checks);
+    }
+    if let Some(assert_strategy) = args.assert_strategy.as_ref().or(profile.assert.as_ref()) {
+        cmd.arg("--assert").arg(assert_strategy);
+    }
+    if let Some(import_module) = &args.import_module {
+        cmd.arg("--import-module").arg(import_module);
+    }
+    for import in &args.imports {
+        cmd.arg("--import").arg(import);
+    }
+    for export in &args.exports {
+        cmd.arg("--export").arg(export);
+    }
+    for export_name in &args.export_names {
+        cmd.arg("--export-name").arg(export_name);
+    }
+    if let Some(start_function) = &args.start_function {
+        cmd.arg("--start").arg(start_function);
+    }
+    if let Some(codegen_units) = args.codegen_units {
+        cmd.arg("--codegen-units").arg(codegen_units.to_string());
+    }
+    if let Some(cache_dir) = incremental_cache_dir(args, out_dir) {
+        cmd.arg("--cache-dir").arg(cache_dir);
+    }
+    if args.memory64 {
+        cmd.arg("--memory64");
+    }
+    if let Some(pages) = args.initial_memory_pages {
+        cmd.arg("--initial-memory-pages").arg(pages.to_string());
+    }
+    if let Some(pages) = args.max_memory_pages {
+        cmd.arg("--max-memory-pages").arg(pages.to_string());
+    }
+    if let Some(stack_size) = args.stack_size {
+        cmd.arg("--stack-size").arg(stack_size.to_string());
+    }
+    if let Some(stack_checks) = &args.stack_checks {
+        cmd.arg("--stack-checks").arg(stack_checks);
+    }
+}
+
+/// Returns the cache directory passed to infc, or `None` for a non-incremental build.
+///
+/// `--cache-dir` implies `--incremental`; without it the cache lives in `cache/` under
+/// `out_dir`, next to the other build outputs.
+fn incremental_cache_dir(args: &BuildArgs, out_dir: &Path) -> Option<PathBuf> {
+    args.cache_dir
+        .clone()
+        .or_else(|| args.incremental.then(|| out_dir.join("cache")))
+}
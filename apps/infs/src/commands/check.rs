@@ -0,0 +1,137 @@
+//! Check command for the infs CLI.
+//!
+//! Parses, type checks and analyzes Inference source code without generating code,
+//! for quick feedback while editing. Only infc is needed: no object code is produced,
+//! so neither LLVM nor a linker runs, and nothing is written to the output directory.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs check                          # Check the project in the current directory
+//! infs check src/main.inf             # Check a single source file
+//! infs check --message-format json    # Report diagnostics as JSON lines on stdout
+//! infs check --watch                  # Check again whenever a source file changes
+//! ```
+//!
+//! Projects are checked as one module, with their `[dependencies]` resolved and passed
+//! to infc with `--extern`, exactly as `infs build` does. Diagnostics use the same
+//! formats as `infs build`. Like `infs build`, the root of a workspace checks every
+//! member, one after the other; `--watch` needs a single package.
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::errors::InfsError;
+use crate::project::dependencies::{
+    ResolvedDependency, resolve_dependencies, resolve_workspace_dependencies,
+};
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE, project_dir};
+use crate::project::workspace::Workspace;
+use crate::toolchain::find_infc;
+
+/// Arguments for the `check` command.
+#[derive(Args)]
+pub struct CheckArgs {
+    /// Source file or project directory to check (defaults to the project in the
+    /// current directory).
+    pub path: Option<PathBuf>,
+
+    /// Format of the errors and warnings reported by infc (`human` or `json`).
+    #[clap(long = "message-format", value_parser = ["human", "json"])]
+    pub message_format: Option<String>,
+
+    /// Keep running and check again whenever a source file changes.
+    #[clap(short = 'w', long = "watch", action = clap::ArgAction::SetTrue)]
+    pub watch: bool,
+}
+
+/// Executes the `check` command.
+///
+/// Runs infc with `--analyze`, which implies the parse phase and stops before codegen.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source file or project directory does not exist
+/// - A project directory has no `Inference.toml`
+/// - The workspace of the project has an invalid member
+/// - `--watch` is given for the root of a workspace with several members
+/// - infc compiler cannot be found
+/// - A dependency cannot be resolved
+/// - infc exits with non-zero code (as `InfsError::ProcessExitCode`)
+pub fn execute(args: &CheckArgs) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !path.exists() {
+        bail!("Path not found: {}", path.display());
+    }
+    let project_dir = project_dir(&path);
+    if let Some(dir) = &project_dir
+        && !dir.join(MANIFEST_FILE).is_file()
+    {
+        bail!("No {MANIFEST_FILE} found in {}", dir.display());
+    }
+
+    let workspace = project_dir.as_deref().map(Workspace::find).transpose()?;
+    let infc_path = find_infc()?;
+
+    if let (Some(dir), Some(Some(workspace))) = (&project_dir, workspace) {
+        let dependencies = resolve_workspace_dependencies(&workspace)?;
+        let selected: Vec<_> = workspace
+            .members
+            .iter()
+            .zip(&dependencies)
+            .filter(|(member, _)| workspace.selects(dir, member))
+            .collect();
+        if args.watch && selected.len() > 1 {
+            bail!("--watch checks a single package; run it in the directory of a member");
+        }
+        for (member, dependencies) in selected {
+            run_check(args, &infc_path, &member.dir, dependencies)?;
+        }
+        return Ok(());
+    }
+
+    let mut dependencies = Vec::new();
+    if let Some(dir) = &project_dir {
+        let manifest = InferenceToml::read_from_file(&dir.join(MANIFEST_FILE))?;
+        dependencies = resolve_dependencies(dir, &manifest)?;
+    }
+    run_check(args, &infc_path, &path, &dependencies)
+}
+
+/// Runs infc with `--analyze` on `path` and its `dependencies`.
+fn run_check(
+    args: &CheckArgs,
+    infc_path: &Path,
+    path: &Path,
+    dependencies: &[ResolvedDependency],
+) -> Result<()> {
+    let mut cmd = Command::new(infc_path);
+    cmd.arg(path);
+    for dependency in dependencies {
+        cmd.arg("--extern").arg(dependency.extern_arg());
+    }
+    cmd.arg("--analyze");
+    if let Some(message_format) = &args.message_format {
+        cmd.arg("--message-format").arg(message_format);
+    }
+    if args.watch {
+        cmd.arg("--watch");
+    }
+
+    let status = cmd
+        .stdin(std::process::Stdio::inherit())
+        .stdout(std::process::Stdio::inherit())
+        .stderr(std::process::Stdio::inherit())
+        .status()
+        .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        let code = status.code().unwrap_or(1);
+        Err(InfsError::process_exit_code(code).into())
+    }
+}
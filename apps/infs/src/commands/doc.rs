@@ -0,0 +1,189 @@
+//! Doc command for the infs CLI.
+//!
+//! Generates API documentation from the `//!` and `///` comments and the signatures of
+//! Inference source files with the `inference-doc` crate. Like `fmt`, it runs
+//! in-process and does not need infc.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs doc                       # Document the project in the current directory
+//! infs doc --format markdown     # Write Markdown pages instead of HTML
+//! infs doc --private             # Include items that are not `pub`
+//! ```
+//!
+//! ## Behavior
+//!
+//! - A project is documented from every `.inf` file under its `src/`, into `target/doc/`
+//! - A single file is documented into `out/doc/`
+//! - Each file is a module named after its path under `src/`, e.g. `math::vector`
+//! - `index.html` (or `index.md`) lists the modules; every item links to its source line
+//! - Files with syntax errors are reported and left out; the others are still documented
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE, project_dir};
+
+/// Arguments for the `doc` command.
+#[derive(Args)]
+pub struct DocArgs {
+    /// Source file or project directory to document (defaults to the project in the
+    /// current directory).
+    pub path: Option<PathBuf>,
+
+    /// Format of the generated pages (`html` or `markdown`).
+    #[clap(long = "format", value_parser = ["html", "markdown"], default_value = "html")]
+    pub format: String,
+
+    /// Document items that are not `pub` as well.
+    #[clap(long = "private", action = clap::ArgAction::SetTrue)]
+    pub private: bool,
+}
+
+/// Executes the `doc` command.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source file or project directory does not exist
+/// - A project directory has no `Inference.toml`
+/// - A file cannot be read, or a page cannot be written
+/// - A file has a syntax error
+pub fn execute(args: &DocArgs) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !path.exists() {
+        bail!("Path not found: {}", path.display());
+    }
+    let project_dir = project_dir(&path);
+    if let Some(dir) = &project_dir
+        && !dir.join(MANIFEST_FILE).is_file()
+    {
+        bail!("No {MANIFEST_FILE} found in {}", dir.display());
+    }
+
+    let (title, root, files, out_dir) = if let Some(dir) = &project_dir {
+        let manifest = InferenceToml::read_from_file(&dir.join(MANIFEST_FILE))?;
+        let src = dir.join("src");
+        let mut files = Vec::new();
+        if src.is_dir() {
+            collect_sources(&src, &mut files)?;
+        }
+        files.sort();
+        (
+            manifest.package.name,
+            dir.clone(),
+            files,
+            dir.join("target").join("doc"),
+        )
+    } else {
+        let title = path
+            .file_stem()
+            .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+        let root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        (
+            title,
+            root,
+            vec![path.clone()],
+            PathBuf::from("out").join("doc"),
+        )
+    };
+
+    let mut modules = Vec::new();
+    let mut failed = 0;
+    for file in &files {
+        let source = std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let relative = file.strip_prefix(&root).unwrap_or(file);
+        let name = module_name(relative, Path::new("src"));
+        let display_path = relative.to_string_lossy().replace('\\', "/");
+        match inference_doc::Module::parse(name, display_path, &source) {
+            Ok(mut module) => {
+                if !args.private {
+                    retain_public(&mut module.items);
+                }
+                modules.push(module);
+            }
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                failed += 1;
+            }
+        }
+    }
+
+    let format = if args.format == "markdown" {
+        inference_doc::Format::Markdown
+    } else {
+        inference_doc::Format::Html
+    };
+    let source_root = source_root(&out_dir, &root);
+    for page in inference_doc::render(&title, &modules, format, &source_root) {
+        let page_path = out_dir.join(&page.path);
+        if let Some(parent) = page_path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        std::fs::write(&page_path, page.contents)
+            .with_context(|| format!("Failed to write {}", page_path.display()))?;
+    }
+    println!(
+        "Documented {} module(s): {}",
+        modules.len(),
+        out_dir
+            .join(format!("index.{}", format.extension()))
+            .display()
+    );
+
+    if failed > 0 {
+        bail!("{failed} file(s) could not be documented");
+    }
+    Ok(())
+}
+
+/// Returns the module name of `file`: its path under `prefix` without the extension,
+/// with `::` between the components, e.g. `math::vector` for `src/math/vector.inf`.
+fn module_name(file: &Path, prefix: &Path) -> String {
+    let relative = file.strip_prefix(prefix).unwrap_or(file);
+    relative
+        .with_extension("")
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Removes the items that are not public, keeping the public members of the others.
+fn retain_public(items: &mut Vec<inference_doc::Item>) {
+    items.retain(|item| item.public);
+    for item in items {
+        retain_public(&mut item.members);
+    }
+}
+
+/// Returns the path from `out_dir` back to `root`, for the source links of Markdown
+/// pages, or the absolute path of `root` if there is no relative one.
+fn source_root(out_dir: &Path, root: &Path) -> String {
+    match out_dir.strip_prefix(root) {
+        Ok(relative) => vec![".."; relative.components().count()].join("/"),
+        Err(_) => root
+            .canonicalize()
+            .unwrap_or_else(|_| root.to_path_buf())
+            .to_string_lossy()
+            .replace('\\', "/"),
+    }
+}
+
+fn collect_sources(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sources(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "inf") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
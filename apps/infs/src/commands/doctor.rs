@@ -6,7 +6,8 @@
 //! ## Usage
 //!
 //! ```bash
-//! infs doctor
+//! infs doctor        # Report the health of the installation
+//! infs doctor --fix  # Also fix the managed components, then check again
 //! ```
 //!
 //! ## Checks Performed
@@ -14,29 +15,119 @@
 //! - Platform detection
 //! - Toolchain directory existence
 //! - Default toolchain configuration
-//! - inf-llc binary presence
-//! - rust-lld binary presence
-//! - libLLVM shared library (Linux only)
+//! - inf-llc and rust-lld binary presence and versions
+//! - libLLVM shared library and its version (Linux only)
+//! - wasmtime and coqc/rocq presence and versions
+//! - Writable download and cache directories
+//!
+//! A warning or failure is followed by its remedy when the message does not say it.
+//! With `--fix`, the findings about components managed by infs are fixed: missing
+//! directories are created, the latest toolchain is installed if there is none, and an
+//! installed toolchain is made the default if none is set. wasmtime and Rocq are not
+//! managed by infs, and are left to their remedies.
+//!
+//! With `--output json`, the results are printed as one document, with the overall
+//! status the worst of the checks:
+//!
+//! ```json
+//! {
+//!   "status": "warning",
+//!   "checks": [
+//!     { "name": "Platform", "status": "ok", "message": "linux-x64" },
+//!     { "name": "wasmtime", "status": "warning", "message": "Not found in PATH; 'infs run' needs it", "remedy": "Install it with ..." }
+//!   ],
+//!   "path_conflicts": [{ "binary": "infc", "found": "/usr/bin/infc", "expected": "/home/me/.inference/bin/infc" }]
+//! }
+//! ```
 
-use anyhow::Result;
+use anyhow::{Result, bail};
+use clap::Args;
+use serde::Serialize;
 
+use crate::commands::default::{self, DefaultArgs};
+use crate::commands::install::{self, InstallArgs};
+use crate::output::{OutputFormat, print_json};
 use crate::toolchain::ToolchainPaths;
-use crate::toolchain::conflict::{detect_path_conflicts, format_doctor_conflict_warning};
-use crate::toolchain::doctor::{DoctorCheckStatus, run_all_checks};
+use crate::toolchain::conflict::{
+    PathConflict, detect_path_conflicts, format_doctor_conflict_warning,
+};
+use crate::toolchain::doctor::{DoctorCheck, DoctorCheckStatus, DoctorFix, fixes, run_all_checks};
+
+/// Arguments for the doctor command.
+#[derive(Args)]
+pub struct DoctorArgs {
+    /// Fix the findings about components managed by infs, then check again.
+    #[clap(long)]
+    pub fix: bool,
+}
+
+/// Results of the checks, for `--output json`.
+#[derive(Serialize)]
+struct DoctorOutput {
+    status: DoctorCheckStatus,
+    checks: Vec<DoctorCheck>,
+    path_conflicts: Vec<PathConflict>,
+}
 
 /// Executes the doctor command.
 ///
-/// Runs all health checks and displays the results.
+/// Runs all health checks and displays the results, as JSON with `--output json`.
+/// With `--fix`, applies the fixes of the findings and runs the checks again.
 ///
 /// # Errors
 ///
-/// Returns an error if critical checks fail to execute (not if they report failures).
-#[allow(clippy::unnecessary_wraps, clippy::unused_async)]
-pub async fn execute() -> Result<()> {
+/// Returns an error if critical checks fail to execute (not if they report failures),
+/// if a fix fails, or if `--fix` is combined with `--output json`.
+pub async fn execute(args: &DoctorArgs, output: OutputFormat) -> Result<()> {
+    if output.is_json() {
+        if args.fix {
+            bail!("--fix cannot be combined with --output json");
+        }
+        let checks = run_all_checks();
+        let path_conflicts = ToolchainPaths::new()
+            .map(|paths| detect_path_conflicts(&paths.bin))
+            .unwrap_or_default();
+        let status = if checks
+            .iter()
+            .any(|check| check.status == DoctorCheckStatus::Error)
+        {
+            DoctorCheckStatus::Error
+        } else if !path_conflicts.is_empty()
+            || checks
+                .iter()
+                .any(|check| check.status == DoctorCheckStatus::Warning)
+        {
+            DoctorCheckStatus::Warning
+        } else {
+            DoctorCheckStatus::Ok
+        };
+        return print_json(&DoctorOutput {
+            status,
+            checks,
+            path_conflicts,
+        });
+    }
+
     println!("Checking Inference toolchain installation...");
     println!();
 
-    let checks = run_all_checks();
+    let mut checks = run_all_checks();
+    if args.fix {
+        let fixes = fixes(&checks);
+        if fixes.is_empty() {
+            println!("Nothing to fix automatically.");
+            println!();
+        } else {
+            for fix in &fixes {
+                println!("Fixing: {fix}...");
+                apply_fix(fix).await?;
+                println!();
+            }
+            println!("Checking again...");
+            println!();
+            checks = run_all_checks();
+        }
+    }
 
     let mut has_errors = false;
     let mut has_warnings = false;
@@ -44,6 +135,9 @@ pub async fn execute() -> Result<()> {
     for check in &checks {
         let prefix = check.prefix();
         println!("  {prefix} {}: {}", check.name, check.message);
+        if let Some(remedy) = &check.remedy {
+            println!("         {remedy}");
+        }
         match check.status {
             DoctorCheckStatus::Ok => {}
             DoctorCheckStatus::Warning => has_warnings = true,
@@ -74,6 +168,29 @@ pub async fn execute() -> Result<()> {
     } else {
         println!("All checks passed. The toolchain is ready to use.");
     }
+    if !args.fix && !fixes(&checks).is_empty() {
+        println!("Run 'infs doctor --fix' to fix the managed components automatically.");
+    }
 
     Ok(())
 }
+
+/// Applies `fix`.
+async fn apply_fix(fix: &DoctorFix) -> Result<()> {
+    match fix {
+        DoctorFix::CreateDirectories => ToolchainPaths::new()?.ensure_directories(),
+        DoctorFix::InstallToolchain => {
+            install::execute(&InstallArgs {
+                version: String::from("latest"),
+                offline: false,
+            })
+            .await
+        }
+        DoctorFix::SetDefault(version) => {
+            default::execute(&DefaultArgs {
+                version: version.clone(),
+            })
+            .await
+        }
+    }
+}
@@ -0,0 +1,418 @@
+//! Expand command for the infs CLI.
+//!
+//! Shows what the compiler makes of Inference source code: the parsed AST, the type
+//! inferred for every node, and the symbol table. Useful to find out why a program
+//! type-checks the way it does.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs expand src/main.inf             # Print the AST and symbol table as a tree
+//! infs expand                          # Expand the project in the current directory
+//! infs expand --format json            # Print everything as one JSON document
+//! ```
+//!
+//! ## Behavior
+//!
+//! - infc runs with `--analyze` and `--emit=ast,typed,symbols`, into a temporary directory
+//! - Projects are expanded as one module, with their `[dependencies]`, like `infs check`
+//! - The tree shows every AST node with its ID, its location and, for value expressions,
+//!   its inferred type after a colon; then every scope with the symbols declared in it
+//! - `--format json` prints `{"ast": [...], "types": [...], "symbols": [...]}`, with the
+//!   `ast`, `typed` and `symbols` dumps of infc; node IDs are shared by all three
+//! - If type checking fails, the AST is still printed and the diagnostics of infc are
+//!   reported on stderr
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::errors::InfsError;
+use crate::project::dependencies::{
+    ResolvedDependency, resolve_dependencies, resolve_workspace_dependencies,
+};
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE, project_dir};
+use crate::project::workspace::Workspace;
+use crate::toolchain::find_infc;
+
+/// Arguments for the `expand` command.
+#[derive(Args)]
+pub struct ExpandArgs {
+    /// Source file or project directory to expand (defaults to the project in the
+    /// current directory).
+    pub path: Option<PathBuf>,
+
+    /// Output format (`tree` or `json`).
+    #[clap(long = "format", value_parser = ["tree", "json"], default_value = "tree")]
+    pub format: String,
+}
+
+/// The dumps written by infc.
+struct Dumps {
+    ast: Option<Value>,
+    typed: Option<Value>,
+    symbols: Option<Value>,
+}
+
+/// Executes the `expand` command.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source file or project directory does not exist
+/// - A project directory has no `Inference.toml`
+/// - The path is the root of a workspace with several members
+/// - infc compiler cannot be found
+/// - A dependency cannot be resolved
+/// - A dump of infc cannot be read
+/// - infc exits with non-zero code (as `InfsError::ProcessExitCode`), after printing
+///   what it produced
+pub fn execute(args: &ExpandArgs) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !path.exists() {
+        bail!("Path not found: {}", path.display());
+    }
+    let project_dir = project_dir(&path);
+    if let Some(dir) = &project_dir
+        && !dir.join(MANIFEST_FILE).is_file()
+    {
+        bail!("No {MANIFEST_FILE} found in {}", dir.display());
+    }
+
+    let workspace = project_dir.as_deref().map(Workspace::find).transpose()?;
+    let infc_path = find_infc()?;
+
+    let mut dependencies = Vec::new();
+    let mut path = path;
+    if let (Some(dir), Some(Some(workspace))) = (&project_dir, &workspace) {
+        let resolved = resolve_workspace_dependencies(workspace)?;
+        let mut selected = workspace
+            .members
+            .iter()
+            .zip(resolved)
+            .filter(|(member, _)| workspace.selects(dir, member));
+        match (selected.next(), selected.next()) {
+            (Some((member, member_dependencies)), None) => {
+                path.clone_from(&member.dir);
+                dependencies = member_dependencies;
+            }
+            (Some(_), Some(_)) => bail!(
+                "expand shows a single package; run it in the directory of a workspace member"
+            ),
+            (None, _) => {}
+        }
+    } else if let Some(dir) = &project_dir {
+        let manifest = InferenceToml::read_from_file(&dir.join(MANIFEST_FILE))?;
+        dependencies = resolve_dependencies(dir, &manifest)?;
+    }
+
+    let out_dir = std::env::temp_dir().join(format!("infs-expand-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+    let result = run_infc(&infc_path, &path, &dependencies, &out_dir);
+    let dumps = read_dumps(&out_dir);
+    let _ = std::fs::remove_dir_all(&out_dir);
+    let success = result?;
+    let dumps = dumps?;
+
+    if dumps.ast.is_some() {
+        if args.format == "json" {
+            println!("{}", render_json(dumps));
+        } else {
+            print!("{}", render_tree(&dumps));
+        }
+    }
+
+    match success {
+        Ok(()) => Ok(()),
+        Err(code) => Err(InfsError::process_exit_code(code).into()),
+    }
+}
+
+/// Runs infc on `path`, writing the dumps to `out_dir`.
+///
+/// Returns the exit code of infc if it fails. Its progress messages go to stderr, so
+/// stdout only carries the expansion.
+fn run_infc(
+    infc_path: &Path,
+    path: &Path,
+    dependencies: &[ResolvedDependency],
+    out_dir: &Path,
+) -> Result<std::result::Result<(), i32>> {
+    let mut cmd = Command::new(infc_path);
+    cmd.arg(path);
+    for dependency in dependencies {
+        cmd.arg("--extern").arg(dependency.extern_arg());
+    }
+    cmd.arg("--analyze");
+    for kind in ["ast", "typed", "symbols"] {
+        cmd.arg(format!(
+            "--emit={kind}={}",
+            out_dir.join(format!("{kind}.json")).display()
+        ));
+    }
+
+    let status = cmd
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::from(std::io::stderr()))
+        .stderr(Stdio::inherit())
+        .status()
+        .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+    Ok(if status.success() {
+        Ok(())
+    } else {
+        Err(status.code().unwrap_or(1))
+    })
+}
+
+/// Reads the dumps infc wrote to `out_dir`; a dump it did not get to is `None`.
+fn read_dumps(out_dir: &Path) -> Result<Dumps> {
+    let read = |kind: &str| -> Result<Option<Value>> {
+        let path = out_dir.join(format!("{kind}.json"));
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read the {kind} dump of infc"))?;
+        let value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse the {kind} dump of infc"))?;
+        Ok(Some(value))
+    };
+    Ok(Dumps {
+        ast: read("ast")?,
+        typed: read("typed")?,
+        symbols: read("symbols")?,
+    })
+}
+
+fn render_json(dumps: Dumps) -> String {
+    let mut document = serde_json::Map::new();
+    document.insert("ast".to_string(), dumps.ast.unwrap_or(Value::Null));
+    document.insert(
+        "types".to_string(),
+        dumps
+            .typed
+            .map_or(Value::Null, |mut typed| typed["nodes"].take()),
+    );
+    document.insert(
+        "symbols".to_string(),
+        dumps
+            .symbols
+            .map_or(Value::Null, |mut symbols| symbols["scopes"].take()),
+    );
+    serde_json::to_string_pretty(&Value::Object(document)).unwrap_or_default()
+}
+
+/// Renders the AST, annotated with the inferred types, and the symbol table.
+fn render_tree(dumps: &Dumps) -> String {
+    let types: HashMap<u64, &str> = dumps
+        .typed
+        .as_ref()
+        .and_then(|typed| typed["nodes"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|node| Some((node["id"].as_u64()?, node["type"].as_str()?)))
+        .collect();
+
+    let mut out = String::from("AST\n");
+    if let Some(files) = dumps.ast.as_ref().and_then(Value::as_array) {
+        for file in files {
+            render_node(&mut out, 1, "SourceFile", file, &types);
+        }
+    }
+    if let Some(scopes) = dumps
+        .symbols
+        .as_ref()
+        .and_then(|symbols| symbols["scopes"].as_array())
+    {
+        out.push_str("\nSymbols\n");
+        for scope in scopes {
+            render_scope(&mut out, scope);
+        }
+    }
+    out
+}
+
+/// Renders `value` under `label` at `depth`, with its children indented below it.
+///
+/// Enum variants are serialized as single-key objects and are shown as a path, e.g.
+/// `Literal::Number`; AST nodes show their ID, location and inferred type inline.
+fn render_node(
+    out: &mut String,
+    depth: usize,
+    label: &str,
+    value: &Value,
+    types: &HashMap<u64, &str>,
+) {
+    let mut variants = Vec::new();
+    let mut value = value;
+    while let Value::Object(map) = value
+        && map.len() == 1
+        && let Some((variant, inner)) = map.iter().next()
+        && variant.starts_with(char::is_uppercase)
+    {
+        variants.push(variant.as_str());
+        value = inner;
+    }
+
+    let indent = "  ".repeat(depth);
+    let mut line = format!("{indent}{label}:");
+    if !variants.is_empty() {
+        let _ = write!(line, " {}", variants.join("::"));
+    }
+    let Value::Object(map) = value else {
+        if variants.is_empty() {
+            let _ = write!(line, " {value}");
+        } else if !value.is_null() {
+            let _ = write!(line, "({})", scalar(value));
+        }
+        let _ = writeln!(out, "{line}");
+        return;
+    };
+
+    if let Some(id) = map.get("id").and_then(Value::as_u64) {
+        let _ = write!(line, " #{id}");
+        if let Some(location) = map.get("location") {
+            let _ = write!(line, " {}", location_text(location));
+        }
+        if let Some(type_name) = types.get(&id) {
+            let _ = write!(line, " : {type_name}");
+        }
+    }
+    let children: Vec<(&String, &Value)> = map
+        .iter()
+        .filter(|(key, field)| {
+            !matches!(key.as_str(), "id" | "location" | "source")
+                && !field.is_null()
+                && field.as_array().is_none_or(|items| !items.is_empty())
+        })
+        .collect();
+    for (key, field) in &children {
+        if !field.is_object() && !field.is_array() {
+            let _ = write!(line, " {key}={}", scalar(field));
+        }
+    }
+    let _ = writeln!(out, "{line}");
+
+    for (key, field) in children {
+        match field {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    render_node(out, depth + 1, &format!("{key}[{index}]"), item, types);
+                }
+            }
+            Value::Object(_) => render_node(out, depth + 1, key, field, types),
+            _ => {}
+        }
+    }
+}
+
+/// Renders one scope of the symbol table and its symbols.
+fn render_scope(out: &mut String, scope: &Value) {
+    let path = scope["path"].as_str().unwrap_or_default();
+    let mut line = format!("  scope #{}", scope["id"]);
+    if path.is_empty() {
+        line.push_str(" (root)");
+    } else {
+        let _ = write!(line, " {path}");
+    }
+    if let Some(parent) = scope["parent"].as_u64() {
+        let _ = write!(line, " (parent #{parent})");
+    }
+    let _ = writeln!(out, "{line}");
+    for symbol in scope["symbols"].as_array().into_iter().flatten() {
+        let mut line = String::from("    ");
+        if symbol["public"].as_bool() == Some(true) {
+            line.push_str("pub ");
+        }
+        let _ = write!(
+            line,
+            "{} {}: {}",
+            symbol["kind"].as_str().unwrap_or_default(),
+            symbol["name"].as_str().unwrap_or_default(),
+            symbol["type"].as_str().unwrap_or_default()
+        );
+        if !symbol["location"].is_null() {
+            let _ = write!(line, " {}", location_text(&symbol["location"]));
+        }
+        let _ = writeln!(out, "{line}");
+    }
+}
+
+/// Formats a serialized `Location` as `line:column-line:column`.
+fn location_text(location: &Value) -> String {
+    format!(
+        "{}:{}-{}:{}",
+        location["start_line"],
+        location["start_column"],
+        location["end_line"],
+        location["end_column"]
+    )
+}
+
+/// Formats a scalar field: strings quoted, enum names such as `Private` as they are.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(text) if text.starts_with(char::is_uppercase) => text.clone(),
+        _ => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tree_shows_variants_locations_and_types() {
+        let location = json!({
+            "offset_start": 0, "offset_end": 2,
+            "start_line": 2, "start_column": 18, "end_line": 2, "end_column": 20
+        });
+        let dumps = Dumps {
+            ast: Some(json!([{
+                "id": 1,
+                "location": location,
+                "source": "hidden",
+                "directives": [],
+                "definitions": [{
+                    "Constant": {
+                        "id": 2,
+                        "location": location,
+                        "visibility": "Private",
+                        "ty": { "Simple": "I32" },
+                        "value": { "Literal": { "Number": {
+                            "id": 3, "location": location, "value": "42"
+                        } } }
+                    }
+                }]
+            }])),
+            typed: Some(json!({ "nodes": [{ "id": 3, "location": location, "type": "i32" }] })),
+            symbols: Some(json!({ "scopes": [{
+                "id": 0, "parent": null, "path": "",
+                "symbols": [{
+                    "name": "main", "kind": "function", "type": "fn() -> i32",
+                    "public": true, "location": location
+                }]
+            }] })),
+        };
+
+        let tree = render_tree(&dumps);
+        assert_eq!(
+            tree,
+            "AST\n\
+             \x20 SourceFile: #1 2:18-2:20\n\
+             \x20   definitions[0]: Constant #2 2:18-2:20 visibility=Private\n\
+             \x20     ty: Simple(I32)\n\
+             \x20     value: Literal::Number #3 2:18-2:20 : i32 value=\"42\"\n\
+             \n\
+             Symbols\n\
+             \x20 scope #0 (root)\n\
+             \x20   pub function main: fn() -> i32 2:18-2:20\n"
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! Fmt command for the infs CLI.
+//!
+//! Formats Inference source files in the canonical style of the `inference-fmt`
+//! crate. Unlike `build`, formatting runs in-process and does not need infc.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs fmt                   # Format the project in the current directory
+//! infs fmt src/main.inf      # Format a single file
+//! infs fmt --check           # Report unformatted files without changing them
+//! ```
+//!
+//! ## Behavior
+//!
+//! - A file path formats that file
+//! - A directory with an `Inference.toml` formats every `.inf` file under its `src/`
+//! - Any other directory formats every `.inf` file under it
+//! - Files with syntax errors are reported and left unchanged
+//! - With `--check`, the command fails if any file is not formatted
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use std::path::{Path, PathBuf};
+
+use crate::project::manifest::MANIFEST_FILE;
+
+/// Arguments for the `fmt` command.
+#[derive(Args)]
+pub struct FmtArgs {
+    /// Source file or directory to format (defaults to the current directory).
+    pub path: Option<PathBuf>,
+
+    /// Check formatting without changing files; fail if any file is not formatted.
+    #[clap(long = "check", action = clap::ArgAction::SetTrue)]
+    pub check: bool,
+}
+
+/// Executes the `fmt` command.
+///
+/// Every file is processed, so one file with a syntax error does not stop the others
+/// from being formatted or checked.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The path does not exist
+/// - A file cannot be read or written
+/// - A file has a syntax error
+/// - With `--check`, a file is not formatted
+pub fn execute(args: &FmtArgs) -> Result<()> {
+    let path = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    if !path.exists() {
+        bail!("Path not found: {}", path.display());
+    }
+
+    let files = source_files(&path)?;
+    let mut unformatted = 0;
+    let mut failed = 0;
+    for file in &files {
+        let source = std::fs::read_to_string(file)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        let formatted = match inference_fmt::format(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        if args.check {
+            println!("Would reformat: {}", file.display());
+            unformatted += 1;
+        } else {
+            std::fs::write(file, formatted)
+                .with_context(|| format!("Failed to write {}", file.display()))?;
+            println!("Formatted: {}", file.display());
+        }
+    }
+
+    if failed > 0 {
+        bail!("{failed} file(s) could not be formatted");
+    }
+    if unformatted > 0 {
+        bail!("{unformatted} file(s) would be reformatted");
+    }
+    Ok(())
+}
+
+/// Returns the `.inf` files to format for `path`, sorted.
+fn source_files(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let root = if path.join(MANIFEST_FILE).is_file() {
+        path.join("src")
+    } else {
+        path.to_path_buf()
+    };
+    let mut files = Vec::new();
+    if root.is_dir() {
+        collect_sources(&root, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect_sources(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_sources(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "inf") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -53,7 +53,7 @@ pub fn execute(args: &InitArgs) -> Result<()> {
     println!("Initialized Inference project in {display_name}");
     println!();
     println!("Next steps:");
-    println!("  infs build src/main.inf --codegen -o");
+    println!("  infs build --codegen -o");
     println!();
     println!("To learn more about Inference, visit:");
     println!("  https://inference-lang.org");
@@ -9,7 +9,17 @@
 //! infs install          # Install latest stable version
 //! infs install 0.1.0    # Install specific version
 //! infs install latest   # Explicitly install latest stable
+//! infs install nightly  # Install the latest version on the nightly channel
+//! infs install --offline # Use the cached manifest and downloads only
 //! ```
+//!
+//! `stable`, `beta` and `nightly` install the latest version on that release channel.
+//! The channel and release date are recorded in the toolchain metadata, and shown by
+//! `infs list`.
+//!
+//! The release manifest comes from the first of the `INFS_DIST_SERVER` mirrors that
+//! serves it. With `--offline`, the cached manifest is used, and the install fails
+//! fast if the archive or its signature still has to be downloaded.
 
 use anyhow::Result;
 use clap::Args;
@@ -17,18 +27,23 @@ use clap::Args;
 use crate::toolchain::conflict::{detect_path_conflicts, format_conflict_warning};
 use crate::toolchain::paths::ToolchainMetadata;
 use crate::toolchain::{
-    Platform, ToolchainPaths, download_file, extract_archive, fetch_artifact,
-    set_executable_permissions, verify_checksum,
+    Platform, ToolchainPaths, download_files, extract_archive, fetch_artifact, require_downloaded,
+    set_executable_permissions, verify_checksum, verify_signature,
 };
 
 /// Arguments for the install command.
 #[derive(Args)]
 pub struct InstallArgs {
-    /// Version to install (e.g., "0.1.0" or "latest").
+    /// Version to install (e.g., "0.1.0" or "latest"), or a release channel
+    /// (`stable`, `beta` or `nightly`).
     ///
     /// If omitted, installs the latest stable version.
     #[clap(default_value = "latest")]
     pub version: String,
+
+    /// Do not use the network; fail if anything is not cached or downloaded yet.
+    #[clap(long = "offline", action = clap::ArgAction::SetTrue)]
+    pub offline: bool,
 }
 
 /// Executes the install command.
@@ -37,9 +52,9 @@ pub struct InstallArgs {
 ///
 /// 1. Detect the current platform
 /// 2. Fetch the release manifest
-/// 3. Find the artifact for the requested version and platform
-/// 4. Download the archive with progress display
-/// 5. Verify the SHA256 checksum
+/// 3. Find the artifact for the requested version or channel and platform
+/// 4. Download the archive and its signature in parallel, with progress display
+/// 5. Verify the SHA256 checksum and the publisher signature
 /// 6. Extract to the toolchains directory
 /// 7. Set as default if it's the first installation
 ///
@@ -48,9 +63,10 @@ pub struct InstallArgs {
 /// Returns an error if:
 /// - Platform detection fails
 /// - Manifest fetch fails
-/// - Version is not found
+/// - Version is not found, or the channel has no version
 /// - Download fails
-/// - Checksum verification fails
+/// - In offline mode, the manifest is not cached or the archive is not downloaded
+/// - Checksum or signature verification fails
 /// - Extraction fails
 pub async fn execute(args: &InstallArgs) -> Result<()> {
     let platform = Platform::detect()?;
@@ -65,7 +81,8 @@ pub async fn execute(args: &InstallArgs) -> Result<()> {
     };
 
     println!("Fetching release manifest...");
-    let (version, artifact) = fetch_artifact(version_arg, platform).await?;
+    let (release, artifact) = fetch_artifact(version_arg, platform, args.offline).await?;
+    let version = release.version.clone();
 
     // Handle the case when the requested version is already installed.
     // If no default toolchain is set (e.g., user manually removed the default file
@@ -88,20 +105,32 @@ pub async fn execute(args: &InstallArgs) -> Result<()> {
 
     let archive_filename = artifact.filename();
     let archive_path = paths.download_path(archive_filename);
-
-    println!("Downloading from {}...", artifact.url);
-    download_file(&artifact.url, &archive_path).await?;
+    let signature_path = paths.download_path(&format!("{archive_filename}.minisig"));
+
+    let files = [
+        (artifact.url.clone(), archive_path.clone()),
+        (artifact.signature_url(), signature_path.clone()),
+    ];
+    if args.offline {
+        require_downloaded(&files)?;
+    } else {
+        println!("Downloading from {}...", artifact.url);
+        download_files(&files).await?;
+    }
 
     println!("Verifying checksum...");
     verify_checksum(&archive_path, &artifact.sha256)?;
 
+    println!("Verifying signature...");
+    verify_signature(&archive_path, &signature_path)?;
+
     println!("Extracting...");
     let toolchain_dir = paths.toolchain_dir(&version);
     extract_archive(&archive_path, &toolchain_dir)?;
 
     set_executable_permissions(&toolchain_dir)?;
 
-    let metadata = ToolchainMetadata::now();
+    let metadata = ToolchainMetadata::for_release(&release);
     paths.write_metadata(&version, &metadata)?;
 
     let installed_versions = paths.list_installed_versions()?;
@@ -132,6 +161,7 @@ pub async fn execute(args: &InstallArgs) -> Result<()> {
     }
 
     std::fs::remove_file(&archive_path).ok();
+    std::fs::remove_file(&signature_path).ok();
 
     Ok(())
 }
@@ -12,33 +12,117 @@
 //!
 //! ```text
 //! Installed toolchains:
-//!   0.1.0    (installed today)
-//! * 0.2.0    (default, installed yesterday)
+//!   0.1.0                    (stable 2026-01-05, installed today)
+//! * 0.3.0-nightly.20261015   (default, nightly 2026-10-15, installed yesterday)
+//! ```
+//!
+//! The release channel and date come from the manifest at install time; they are
+//! missing for toolchains installed by older versions of infs.
+//!
+//! A version pinned for the current directory with `INFS_TOOLCHAIN` or an
+//! `inference-toolchain.toml` file is reported after the list.
+//!
+//! With `--output json`, the same information is printed as one document:
+//!
+//! ```json
+//! {
+//!   "toolchains": [
+//!     { "version": "0.2.0", "default": true, "channel": "stable", "released_at": "2026-01-02", "installed_at": "2026-01-05", "path": "/home/me/.inference/toolchains/0.2.0" }
+//!   ],
+//!   "default": "0.2.0",
+//!   "pinned": { "version": "0.1.0", "source": "inference-toolchain.toml", "installed": false }
+//! }
 //! ```
 
 use anyhow::Result;
+use serde::Serialize;
+use std::path::PathBuf;
 
+use crate::output::{OutputFormat, print_json};
 use crate::toolchain::ToolchainPaths;
+use crate::toolchain::manifest::Channel;
+use crate::toolchain::overrides::find_override;
+use crate::toolchain::paths::ToolchainMetadata;
+
+/// Installed toolchains, for `--output json`.
+#[derive(Serialize)]
+struct ListOutput {
+    toolchains: Vec<ToolchainInfo>,
+    default: Option<String>,
+    pinned: Option<PinInfo>,
+}
+
+/// An installed toolchain.
+#[derive(Serialize)]
+struct ToolchainInfo {
+    version: String,
+    default: bool,
+    channel: Option<Channel>,
+    released_at: Option<String>,
+    installed_at: Option<String>,
+    path: PathBuf,
+}
+
+/// The toolchain pinned for the current directory.
+#[derive(Serialize)]
+struct PinInfo {
+    version: String,
+    /// The override file, or the `INFS_TOOLCHAIN` environment variable.
+    source: String,
+    installed: bool,
+}
 
 /// Executes the list command.
 ///
 /// Lists all installed toolchain versions and marks the default with an asterisk.
-/// Also displays the installation date for each version if available.
+/// Also displays the release channel, release date and installation date of each
+/// version if available.
+///
+/// With `--output json`, prints the versions, the default and the pinned version as
+/// JSON.
 ///
 /// # Errors
 ///
-/// Returns an error if the toolchains directory cannot be read.
+/// Returns an error if the toolchains directory cannot be read, or an override file
+/// is invalid.
 #[allow(clippy::unnecessary_wraps, clippy::unused_async)]
-pub async fn execute() -> Result<()> {
+pub async fn execute(output: OutputFormat) -> Result<()> {
     let paths = ToolchainPaths::new()?;
     let versions = paths.list_installed_versions()?;
     let default_version = paths.get_default_version()?;
 
+    if output.is_json() {
+        let pinned = find_override()?.map(|pin| PinInfo {
+            installed: paths.is_version_installed(&pin.version),
+            version: pin.version,
+            source: pin.source.to_string(),
+        });
+        let toolchains = versions
+            .iter()
+            .map(|version| {
+                let metadata = paths.read_metadata(version);
+                ToolchainInfo {
+                    version: version.clone(),
+                    default: default_version.as_deref() == Some(version.as_str()),
+                    channel: metadata.as_ref().and_then(|meta| meta.channel),
+                    released_at: metadata.as_ref().and_then(|meta| meta.released_at.clone()),
+                    installed_at: metadata.map(|meta| meta.installed_at),
+                    path: paths.toolchain_dir(version),
+                }
+            })
+            .collect();
+        return print_json(&ListOutput {
+            toolchains,
+            default: default_version,
+            pinned,
+        });
+    }
+
     if versions.is_empty() {
         println!("No toolchains installed.");
         println!();
         println!("Run 'infs install' to install the latest toolchain.");
-        return Ok(());
+        return print_override(&paths);
     }
 
     println!("Installed toolchains:");
@@ -53,6 +137,9 @@ pub async fn execute() -> Result<()> {
             info_parts.push("default".to_string());
         }
         if let Some(meta) = metadata {
+            if let Some(release) = release_info(&meta) {
+                info_parts.push(release);
+            }
             info_parts.push(format!("installed {}", meta.installed_ago()));
         }
 
@@ -69,5 +156,27 @@ pub async fn execute() -> Result<()> {
         println!("No default toolchain set. Run 'infs default <version>' to set one.");
     }
 
+    print_override(&paths)
+}
+
+/// Returns the release channel and date of a toolchain, e.g. `nightly 2026-10-15`.
+fn release_info(metadata: &ToolchainMetadata) -> Option<String> {
+    match (metadata.channel, &metadata.released_at) {
+        (Some(channel), Some(date)) => Some(format!("{channel} {date}")),
+        (Some(channel), None) => Some(channel.to_string()),
+        (None, Some(date)) => Some(format!("released {date}")),
+        (None, None) => None,
+    }
+}
+
+/// Reports the toolchain pinned for the current directory, if any.
+fn print_override(paths: &ToolchainPaths) -> Result<()> {
+    if let Some(pin) = find_override()? {
+        println!();
+        println!("Toolchain {} is pinned by {}.", pin.version, pin.source);
+        if !paths.is_version_installed(&pin.version) {
+            println!("It is not installed. Run 'infs install {}'.", pin.version);
+        }
+    }
     Ok(())
 }
@@ -5,7 +5,13 @@
 //! ## Compilation Commands
 //!
 //! - [`build`] - Compile Inference source files
+//! - [`check`] - Type check Inference source files without codegen
+//! - [`expand`] - Show the AST, inferred types and symbol table of source files
 //! - [`run`] - Build and execute WASM with wasmtime
+//! - [`repl`] - Evaluate definitions and expressions interactively
+//! - [`fmt`] - Format Inference source files
+//! - [`doc`] - Generate API documentation
+//! - [`verify`] - Check the Rocq proofs of a project
 //! - [`version`] - Display version information
 //!
 //! ## Project Management Commands
@@ -24,14 +30,20 @@
 //! - [`self_cmd`] - Manage infs itself
 
 pub mod build;
+pub mod check;
 pub mod default;
+pub mod doc;
 pub mod doctor;
+pub mod expand;
+pub mod fmt;
 pub mod init;
 pub mod install;
 pub mod list;
 pub mod new;
+pub mod repl;
 pub mod run;
 pub mod self_cmd;
 pub mod uninstall;
+pub mod verify;
 pub mod version;
 pub mod versions;
@@ -0,0 +1,461 @@
+//! Repl command for the infs CLI.
+//!
+//! An interactive session to try out Inference code: definitions entered at the prompt
+//! are kept, and expressions are type checked and evaluated against them.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs repl                  # Start an empty session
+//! infs repl src/main.inf     # Start with the definitions of a source file
+//! ```
+//!
+//! ```text
+//! >> fn double(x: i32) -> i32 { return x * 2; }
+//! >> double(21)
+//! 42 : i32
+//! >> :type double(21) > 40
+//! double(21) > 40 : bool
+//! ```
+//!
+//! ## Behavior
+//!
+//! - An input starting like a definition (`fn`, `pub`, `struct`, `enum`, `const`, `type`,
+//!   `spec`, `external` or `use`) is added to the session if the session still type
+//!   checks with it; otherwise the diagnostics of infc are reported and it is dropped
+//! - Any other input is an expression. Its type is the one infc infers for it in the
+//!   body of a generated function; it is then returned from an exported
+//!   `__repl_eval` function, compiled to WebAssembly and invoked with wasmtime
+//! - Without wasmtime in PATH, expressions are only type checked
+//! - An input with unclosed brackets continues on the next line
+//!
+//! Every input re-checks the whole session: infc compiles it from a temporary source
+//! file, with `--analyze` and `--emit=typed` to infer types, and `--codegen` to produce
+//! the artifacts.
+//!
+//! ## Commands
+//!
+//! | Command | Action |
+//! |---------|--------|
+//! | `:type <expr>` | Show the type of an expression without evaluating it |
+//! | `:wat [expr]` | Show the WebAssembly text of the session, with the expression if given |
+//! | `:v [expr]` | Show the Rocq translation of the session, with the expression if given |
+//! | `:items` | Show the definitions of the session |
+//! | `:reset` | Remove all definitions |
+//! | `:help` | List the commands |
+//! | `:quit` | Leave the session, like end of input |
+//!
+//! ## Prerequisites
+//!
+//! This command requires:
+//! - `infc` compiler (via toolchain or PATH)
+//! - `wasmtime` WebAssembly runtime (in PATH), to evaluate expressions
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use serde_json::Value;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::toolchain::find_infc;
+
+/// Arguments for the `repl` command.
+#[derive(Args)]
+pub struct ReplArgs {
+    /// Source file whose definitions start the session.
+    pub path: Option<PathBuf>,
+}
+
+/// Words an input starts with when it is a definition rather than an expression.
+const ITEM_KEYWORDS: [&str; 9] = [
+    "fn", "pub", "struct", "enum", "const", "type", "spec", "external", "use",
+];
+
+/// Name of the function an expression is evaluated in.
+const EVAL_FUNCTION: &str = "__repl_eval";
+
+const HELP: &str = "\
+Enter a definition to add it to the session, or an expression to evaluate it.
+
+  :type <expr>   Show the type of an expression
+  :wat [expr]    Show the WebAssembly text of the session
+  :v [expr]      Show the Rocq translation of the session
+  :items         Show the definitions of the session
+  :reset         Remove all definitions
+  :help          Show this help
+  :quit          Leave the session";
+
+/// A line of input, once complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Input<'a> {
+    /// A `:` command with its argument, which may be empty.
+    Command(&'a str, &'a str),
+    Item(&'a str),
+    Expression(&'a str),
+}
+
+/// The definitions entered so far, and the directory infc works in.
+struct Session {
+    infc_path: PathBuf,
+    items: Vec<String>,
+    work_dir: PathBuf,
+    wasmtime: bool,
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.work_dir);
+    }
+}
+
+/// Executes the `repl` command.
+///
+/// Reads inputs from stdin until end of input or `:quit`. Errors in an input are
+/// reported and the session goes on.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The source file cannot be read, or does not type check
+/// - infc compiler cannot be found
+/// - The temporary directory cannot be created
+/// - Stdin cannot be read
+pub fn execute(args: &ReplArgs) -> Result<()> {
+    let infc_path = find_infc()?;
+    let work_dir = std::env::temp_dir().join(format!("infs-repl-{}", rand::random::<u64>()));
+    std::fs::create_dir_all(&work_dir)
+        .with_context(|| format!("Failed to create {}", work_dir.display()))?;
+    let mut session = Session {
+        infc_path,
+        items: Vec::new(),
+        work_dir,
+        wasmtime: which::which("wasmtime").is_ok(),
+    };
+
+    if let Some(path) = &args.path {
+        let source = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        if !session.add_item(source.trim_end())? {
+            bail!("{} does not type check", path.display());
+        }
+    }
+
+    let interactive = std::io::stdin().is_terminal();
+    if interactive {
+        println!("Inference REPL; :help lists the commands, :quit leaves");
+        if !session.wasmtime {
+            println!("wasmtime not found in PATH; expressions are only type checked");
+        }
+    }
+
+    let mut lines = std::io::stdin().lock().lines();
+    let mut input = String::new();
+    loop {
+        if interactive {
+            print!("{}", if input.is_empty() { ">> " } else { ".. " });
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line.context("Failed to read stdin")?;
+        input.push_str(&line);
+        input.push('\n');
+        if !is_complete(&input) {
+            continue;
+        }
+        let entry = std::mem::take(&mut input);
+        let quit = session.handle(classify(&entry)).unwrap_or_else(|error| {
+            eprintln!("error: {error:#}");
+            false
+        });
+        if quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Returns how `input` is handled.
+fn classify(input: &str) -> Input<'_> {
+    let input = input.trim();
+    if let Some(command) = input.strip_prefix(':') {
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        return Input::Command(name, argument.trim());
+    }
+    let first_word = input
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default();
+    if ITEM_KEYWORDS.contains(&first_word) {
+        Input::Item(input)
+    } else {
+        Input::Expression(input.trim_end_matches(';'))
+    }
+}
+
+/// Returns true if every bracket opened in `input` is closed, outside of string
+/// literals and line comments.
+fn is_complete(input: &str) -> bool {
+    let mut depth = 0i32;
+    for line in input.lines() {
+        let mut in_string = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if in_string => {
+                    chars.next();
+                }
+                '"' => in_string = !in_string,
+                '/' if !in_string && chars.peek() == Some(&'/') => break,
+                '{' | '(' | '[' if !in_string => depth += 1,
+                '}' | ')' | ']' if !in_string => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    depth <= 0
+}
+
+/// Returns how a type of the `typed` dump of infc is written in source code.
+fn source_type(type_name: &str) -> String {
+    match type_name {
+        "Unit" => String::from("unit"),
+        "Bool" => String::from("bool"),
+        "String" => String::from("string"),
+        _ => type_name.to_string(),
+    }
+}
+
+impl Session {
+    /// Handles one complete input; returns true if the session is over.
+    fn handle(&mut self, input: Input) -> Result<bool> {
+        match input {
+            Input::Command("quit" | "q", _) => return Ok(true),
+            Input::Command("help" | "h", _) => println!("{HELP}"),
+            Input::Command("type" | "t", "") => bail!("Usage: :type <expr>"),
+            Input::Command("type" | "t", expr) => {
+                if let Some(type_name) = self.type_of(expr)? {
+                    println!("{expr} : {type_name}");
+                }
+            }
+            Input::Command("wat", expr) => self.print_artifact("wat", expr)?,
+            Input::Command("v", expr) => self.print_artifact("v", expr)?,
+            Input::Command("items", _) => {
+                for item in &self.items {
+                    println!("{item}");
+                }
+            }
+            Input::Command("reset", _) => {
+                self.items.clear();
+                println!("Session cleared");
+            }
+            Input::Command(name, _) => bail!("Unknown command :{name}; :help lists the commands"),
+            Input::Item(item) => {
+                self.add_item(item)?;
+            }
+            Input::Expression("") => {}
+            Input::Expression(expr) => self.evaluate(expr)?,
+        }
+        Ok(false)
+    }
+
+    /// Returns the source of the session followed by `extra`.
+    fn source(&self, extra: &str) -> String {
+        let mut source = String::new();
+        for item in self.items.iter().map(String::as_str).chain([extra]) {
+            if !item.is_empty() {
+                source.push_str(item);
+                source.push('\n');
+            }
+        }
+        source
+    }
+
+    /// Adds `item` to the session if the session type checks with it; returns false
+    /// otherwise, once infc reported why.
+    fn add_item(&mut self, item: &str) -> Result<bool> {
+        let source = self.source(item);
+        if !self.run_infc(&source, "--analyze", &[])? {
+            return Ok(false);
+        }
+        self.items.push(item.to_string());
+        Ok(true)
+    }
+
+    /// Returns the type infc infers for `expr`, as written in source code, or `None` once infc reported why it
+    /// could not.
+    fn type_of(&self, expr: &str) -> Result<Option<String>> {
+        let prefix = self.source("fn __repl_type() {\n    ");
+        let source = format!("{prefix}{expr};\n}}\n");
+        if !self.run_infc(&source, "--analyze", &["typed"])? {
+            return Ok(None);
+        }
+        let path = self.work_dir.join("session.typed.json");
+        let content =
+            std::fs::read_to_string(&path).context("Failed to read the typed dump of infc")?;
+        let typed: Value =
+            serde_json::from_str(&content).context("Failed to parse the typed dump of infc")?;
+        let (start, end) = (prefix.len() as u64, (prefix.len() + expr.len()) as u64);
+        let type_name = typed["nodes"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|node| {
+                node["location"]["offset_start"].as_u64() == Some(start)
+                    && node["location"]["offset_end"].as_u64() == Some(end)
+            })
+            .and_then(|node| node["type"].as_str());
+        match type_name {
+            Some(type_name) => Ok(Some(source_type(type_name))),
+            None => bail!("infc inferred no type for `{expr}`"),
+        }
+    }
+
+    /// Returns the exported function evaluating `expr`, or `None` once infc reported
+    /// why `expr` does not type check.
+    fn eval_function(&self, expr: &str) -> Result<Option<(String, String)>> {
+        let Some(type_name) = self.type_of(expr)? else {
+            return Ok(None);
+        };
+        let function = if type_name == "unit" {
+            format!("pub fn {EVAL_FUNCTION}() {{\n    {expr};\n}}")
+        } else {
+            format!("pub fn {EVAL_FUNCTION}() -> {type_name} {{\n    return {expr};\n}}")
+        };
+        Ok(Some((function, type_name)))
+    }
+
+    /// Evaluates `expr` with wasmtime and prints its value and type; only its type
+    /// without wasmtime.
+    fn evaluate(&self, expr: &str) -> Result<()> {
+        let Some((function, type_name)) = self.eval_function(expr)? else {
+            return Ok(());
+        };
+        if !self.wasmtime {
+            println!("{expr} : {type_name}");
+            return Ok(());
+        }
+        if !self.run_infc(&self.source(&function), "--codegen", &["wasm"])? {
+            return Ok(());
+        }
+        let output = Command::new("wasmtime")
+            .arg("--invoke")
+            .arg(EVAL_FUNCTION)
+            .arg(self.work_dir.join("session.wasm"))
+            .stdin(Stdio::null())
+            .output()
+            .context("Failed to execute wasmtime")?;
+        if !output.status.success() {
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            bail!("Evaluation of `{expr}` failed");
+        }
+        let value = String::from_utf8_lossy(&output.stdout);
+        let value = value.trim();
+        if value.is_empty() {
+            println!("{expr} : {type_name}");
+        } else {
+            println!("{value} : {type_name}");
+        }
+        Ok(())
+    }
+
+    /// Prints the `kind` artifact of the session, with the function evaluating `expr`
+    /// unless it is empty.
+    fn print_artifact(&self, kind: &str, expr: &str) -> Result<()> {
+        let function = if expr.is_empty() {
+            String::new()
+        } else {
+            match self.eval_function(expr)? {
+                Some((function, _)) => function,
+                None => return Ok(()),
+            }
+        };
+        if self.run_infc(&self.source(&function), "--codegen", &[kind])? {
+            let path = self.work_dir.join(format!("session.{kind}"));
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read the {kind} output of infc"))?;
+            print!("{content}");
+        }
+        Ok(())
+    }
+
+    /// Compiles `source` with infc up to `phase`, writing the artifacts of `kinds` to
+    /// the work directory; returns false if infc failed, its diagnostics on stderr.
+    fn run_infc(&self, source: &str, phase: &str, kinds: &[&str]) -> Result<bool> {
+        let source_path = self.work_dir.join("session.inf");
+        std::fs::write(&source_path, source)
+            .with_context(|| format!("Failed to write {}", source_path.display()))?;
+        let mut cmd = Command::new(&self.infc_path);
+        cmd.arg(&source_path).arg(phase);
+        for kind in kinds {
+            let file = match *kind {
+                "typed" => String::from("session.typed.json"),
+                kind => format!("session.{kind}"),
+            };
+            cmd.arg(format!(
+                "--emit={kind}={}",
+                self.work_dir.join(file).display()
+            ));
+        }
+        let output = cmd
+            .stdin(Stdio::null())
+            .output()
+            .with_context(|| format!("Failed to execute infc at {}", self.infc_path.display()))?;
+        if output.status.success() {
+            return Ok(true);
+        }
+        report_diagnostics(&output.stdout, &source_path);
+        report_diagnostics(&output.stderr, &source_path);
+        Ok(false)
+    }
+}
+
+/// Prints what infc reported, without the path of the temporary source file.
+fn report_diagnostics(output: &[u8], source_path: &Path) {
+    let output = String::from_utf8_lossy(output);
+    let output = output.replace(&*source_path.to_string_lossy(), "<repl>");
+    eprint!("{output}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_commands_items_and_expressions() {
+        assert_eq!(classify(":type 1 + 2\n"), Input::Command("type", "1 + 2"));
+        assert_eq!(classify(":quit"), Input::Command("quit", ""));
+        assert_eq!(
+            classify("fn one() -> i32 { return 1; }\n"),
+            Input::Item("fn one() -> i32 { return 1; }")
+        );
+        assert_eq!(
+            classify("pub struct Point { x: i32; }"),
+            Input::Item("pub struct Point { x: i32; }")
+        );
+        assert_eq!(classify("one() + 1;\n"), Input::Expression("one() + 1"));
+        assert_eq!(
+            classify("function_call()"),
+            Input::Expression("function_call()")
+        );
+    }
+
+    #[test]
+    fn input_is_complete_once_brackets_close() {
+        assert!(is_complete("1 + 2\n"));
+        assert!(!is_complete("fn f() -> i32 {\n"));
+        assert!(is_complete("fn f() -> i32 {\n    return 1;\n}\n"));
+        assert!(is_complete("\"{\" // (\n"));
+    }
+
+    #[test]
+    fn source_types_of_typed_dump() {
+        assert_eq!(source_type("Unit"), "unit");
+        assert_eq!(source_type("Bool"), "bool");
+        assert_eq!(source_type("[i32; 3]"), "[i32; 3]");
+    }
+}
@@ -9,9 +9,15 @@
 //! 1. **Validate** - Check source file exists
 //! 2. **Check** - Verify wasmtime is available in PATH
 //! 3. **Locate** - Find the infc compiler binary
-//! 4. **Compile** - Call infc with `--parse --codegen -o` to generate WASM
+//! 4. **Compile** - Call infc with `--parse --codegen --emit=wasm --source-map` to generate WASM
 //! 5. **Execute** - Run WASM with wasmtime using `--invoke`
 //!
+//! ## Trap Locations
+//!
+//! The module is compiled with a source map. When the program traps, every frame of
+//! the backtrace printed by wasmtime is followed by the `.inf` line and column it
+//! came from.
+//!
 //! ## Entry Points
 //!
 //! By default, the `main` function is invoked. Use `--entry-point` to call
@@ -25,6 +31,33 @@
 //! For `main`, argc/argv arguments (0, 0) are passed automatically.
 //! For other functions, trailing arguments are passed as function parameters.
 //!
+//! ## Host Functions
+//!
+//! Functions declared with `external fn` are imported from the `env` module under their
+//! own name. `--import-module` and `--import` change these names, and `--preload` loads a
+//! WebAssembly module that provides them:
+//!
+//! ```bash
+//! infs run program.inf --preload env=host.wasm
+//! infs run program.inf --import log=console.log_i32 --preload console=console.wasm
+//! ```
+//!
+//! ## Runtime Configuration
+//!
+//! The program runs under WASI. Flags configure the sandbox and limits of wasmtime:
+//!
+//! ```bash
+//! infs run program.inf --dir data              # Preopen ./data as `data`
+//! infs run program.inf --dir /tmp/out::out     # Preopen /tmp/out as `out`
+//! infs run program.inf --env LEVEL=3 --env HOME
+//! infs run program.inf --stdin input.txt       # Read stdin from a file
+//! infs run program.inf --fuel 1000000 --timeout 5
+//! ```
+//!
+//! `--env NAME` without a value passes the variable through from the environment of
+//! infs. `--fuel` limits the number of instructions executed and `--timeout` the
+//! wall-clock seconds; the program traps when either runs out.
+//!
 //! ## Prerequisites
 //!
 //! This command requires:
@@ -37,6 +70,7 @@ use std::path::PathBuf;
 use std::process::Command;
 
 use crate::errors::InfsError;
+use crate::source_map::SourceMap;
 use crate::toolchain::find_infc;
 
 /// Arguments for the run command.
@@ -55,6 +89,43 @@ pub struct RunArgs {
     #[clap(long, default_value = "main")]
     pub entry_point: String,
 
+    /// Import module of `external fn` declarations (default `env`).
+    #[clap(long = "import-module")]
+    pub import_module: Option<String>,
+
+    /// Import one `external fn` as `<function>=<module>.<name>`; may be repeated.
+    #[clap(long = "import")]
+    pub imports: Vec<String>,
+
+    /// Load a WebAssembly module providing host functions, as `<module>=<path>`.
+    ///
+    /// Forwarded to `wasmtime --preload`; may be repeated.
+    #[clap(long = "preload")]
+    pub preloads: Vec<String>,
+
+    /// Preopen a host directory for the program, as `<host>` or `<host>::<guest>`.
+    ///
+    /// Forwarded to `wasmtime --dir`; may be repeated.
+    #[clap(long = "dir")]
+    pub dirs: Vec<String>,
+
+    /// Set an environment variable for the program, as `<name>=<value>`, or `<name>`
+    /// to pass it through; may be repeated.
+    #[clap(long = "env")]
+    pub envs: Vec<String>,
+
+    /// Read the standard input of the program from a file.
+    #[clap(long = "stdin")]
+    pub stdin: Option<PathBuf>,
+
+    /// Trap after executing this many units of fuel (roughly, instructions).
+    #[clap(long = "fuel")]
+    pub fuel: Option<u64>,
+
+    /// Trap after running for this many seconds.
+    #[clap(long = "timeout")]
+    pub timeout: Option<u64>,
+
     /// Arguments to pass to the invoked function.
     ///
     /// For functions other than `main`, these are passed directly as function arguments.
@@ -67,7 +138,7 @@ pub struct RunArgs {
 ///
 /// ## Execution Flow
 ///
-/// 1. Validates source file exists
+/// 1. Validates source file exists, and the runtime configuration
 /// 2. Checks for wasmtime availability
 /// 3. Locates the infc compiler
 /// 4. Compiles source to WASM via infc subprocess
@@ -84,6 +155,8 @@ pub struct RunArgs {
 ///
 /// Returns an error if:
 /// - The source file does not exist
+/// - A `--dir` host directory or the `--stdin` file does not exist
+/// - An `--env` variable has no name
 /// - wasmtime is not found in PATH
 /// - infc compiler cannot be found
 /// - Compilation fails
@@ -92,14 +165,53 @@ pub fn execute(args: &RunArgs) -> Result<()> {
     if !args.path.exists() {
         bail!("Path not found: {}", args.path.display());
     }
+    validate_runtime_args(args)?;
 
     check_wasmtime_availability()?;
 
     let infc_path = find_infc()?;
 
-    let wasm_path = compile_to_wasm(&infc_path, &args.path)?;
+    let wasm_path = compile_to_wasm(&infc_path, args)?;
+
+    run_wasmtime(&wasm_path, args)
+}
+
+/// Checks the runtime configuration before anything is compiled.
+fn validate_runtime_args(args: &RunArgs) -> Result<()> {
+    for dir in &args.dirs {
+        let host = dir.split_once("::").map_or(dir.as_str(), |(host, _)| host);
+        if !std::path::Path::new(host).is_dir() {
+            bail!("Directory not found: {host}");
+        }
+    }
+    for env in &args.envs {
+        let name = env.split_once('=').map_or(env.as_str(), |(name, _)| name);
+        if name.is_empty() {
+            bail!("Invalid --env '{env}': expected <name>=<value> or <name>");
+        }
+    }
+    if let Some(stdin) = &args.stdin
+        && !stdin.is_file()
+    {
+        bail!("Stdin file not found: {}", stdin.display());
+    }
+    Ok(())
+}
 
-    run_wasmtime(&wasm_path, &args.entry_point, &args.args)
+/// Adds the WASI and limit flags of `args` to the wasmtime command `cmd`.
+fn add_runtime_flags(cmd: &mut Command, args: &RunArgs) {
+    for dir in &args.dirs {
+        cmd.arg("--dir").arg(dir);
+    }
+    for env in &args.envs {
+        cmd.arg("--env").arg(env);
+    }
+    if let Some(fuel) = args.fuel {
+        cmd.arg("-W").arg(format!("fuel={fuel}"));
+    }
+    if let Some(timeout) = args.timeout {
+        cmd.arg("-W").arg(format!("timeout={timeout}s"));
+    }
 }
 
 /// Checks if wasmtime is available in PATH.
@@ -119,14 +231,22 @@ fn check_wasmtime_availability() -> Result<()> {
 
 /// Compiles source file to WASM binary using infc subprocess.
 ///
-/// Calls infc with `--parse --codegen -o` flags to generate the WASM file
-/// in the `out/` directory.
-fn compile_to_wasm(infc_path: &PathBuf, source_path: &PathBuf) -> Result<PathBuf> {
+/// Calls infc with `--parse --codegen --emit=wasm --source-map` flags to generate the
+/// WASM file in the `out/` directory. Import naming flags are forwarded to infc.
+fn compile_to_wasm(infc_path: &PathBuf, args: &RunArgs) -> Result<PathBuf> {
+    let source_path = &args.path;
     let mut cmd = Command::new(infc_path);
     cmd.arg(source_path)
         .arg("--parse")
         .arg("--codegen")
-        .arg("-o");
+        .arg("--emit=wasm")
+        .arg("--source-map");
+    if let Some(import_module) = &args.import_module {
+        cmd.arg("--import-module").arg(import_module);
+    }
+    for import in &args.imports {
+        cmd.arg("--import").arg(import);
+    }
 
     let status = cmd
         .stdin(std::process::Stdio::inherit())
@@ -162,31 +282,47 @@ fn compile_to_wasm(infc_path: &PathBuf, source_path: &PathBuf) -> Result<PathBuf
 ///
 /// Uses `--invoke <entry_point>` to call the specified exported function.
 /// For `main`, automatically passes argc=0, argv=0 arguments.
-/// For other functions, passes user-provided arguments.
+/// For other functions, passes user-provided arguments. Each `--preload` is
+/// forwarded to wasmtime so preloaded modules can satisfy host imports, along with
+/// the preopened directories, environment variables and limits. With `--stdin`, the
+/// file is the standard input of the program.
 ///
 /// Stderr is captured and only displayed if wasmtime fails, to suppress
 /// the experimental feature warnings about `--invoke` that appear on success.
+/// Backtrace frames in it are annotated with their source location when the
+/// module has a source map.
 ///
 /// Returns `Ok(())` on success, or `Err(InfsError::ProcessExitCode)` if wasmtime
 /// exits with a non-zero code. This allows the caller to propagate the exit code
 /// without bypassing RAII cleanup.
-fn run_wasmtime(wasm_path: &PathBuf, entry_point: &str, args: &[String]) -> Result<()> {
+fn run_wasmtime(wasm_path: &PathBuf, args: &RunArgs) -> Result<()> {
+    let entry_point = args.entry_point.as_str();
     println!("Invoking '{entry_point}' with wasmtime...");
 
     let mut cmd = Command::new("wasmtime");
+    for preload in &args.preloads {
+        cmd.arg("--preload").arg(preload);
+    }
+    add_runtime_flags(&mut cmd, args);
     cmd.arg("--invoke").arg(entry_point).arg(wasm_path);
 
     if entry_point == "main" {
         // main(argc: i32, argv: i32) -> i32 requires two arguments
         cmd.arg("0").arg("0");
     } else {
-        for arg in args {
+        for arg in &args.args {
             cmd.arg(arg);
         }
     }
 
+    let stdin = match &args.stdin {
+        Some(path) => std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?
+            .into(),
+        None => std::process::Stdio::inherit(),
+    };
     let output = cmd
-        .stdin(std::process::Stdio::inherit())
+        .stdin(stdin)
         .output()
         .with_context(|| "Failed to execute wasmtime")?;
 
@@ -200,7 +336,14 @@ fn run_wasmtime(wasm_path: &PathBuf, entry_point: &str, args: &[String]) -> Resu
     } else {
         // Only show stderr on failure (hides experimental warnings on success)
         if !output.stderr.is_empty() {
-            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match std::fs::read(wasm_path)
+                .ok()
+                .and_then(|wasm| SourceMap::from_module(&wasm).ok().flatten())
+            {
+                Some(source_map) => eprint!("{}", source_map.annotate_backtrace(&stderr)),
+                None => eprint!("{stderr}"),
+            }
         }
         let code = output.status.code().unwrap_or(1);
         Err(InfsError::process_exit_code(code).into())
@@ -5,15 +5,21 @@
 //! ## Usage
 //!
 //! ```bash
-//! infs self update    # Update infs to the latest version
+//! infs self update                    # Update infs to the latest version
+//! infs self update --channel nightly  # Update to the latest nightly
+//! infs self update --offline          # Use the cached manifest and downloads only
 //! ```
+//!
+//! Without `--channel`, the latest stable version is installed, or the latest version
+//! if there is no stable one.
 
 use anyhow::{Context, Result, bail};
 use clap::{Args, Subcommand};
 
+use crate::toolchain::manifest::{Channel, latest_in_channel};
 use crate::toolchain::{
-    Platform, ToolchainPaths, download_file, extract_archive, fetch_manifest, latest_stable,
-    latest_version, verify_checksum,
+    Platform, ToolchainPaths, cached_manifest, download_files, extract_archive, fetch_manifest,
+    latest_stable, latest_version, require_downloaded, verify_checksum, verify_signature,
 };
 
 /// Arguments for the self command.
@@ -27,7 +33,15 @@ pub struct SelfArgs {
 #[derive(Subcommand)]
 pub enum SelfCommand {
     /// Update infs to the latest version.
-    Update,
+    Update {
+        /// Do not use the network; fail if anything is not cached or downloaded yet.
+        #[clap(long)]
+        offline: bool,
+
+        /// Release channel to update from (`stable`, `beta` or `nightly`).
+        #[clap(long, value_parser = Channel::NAMES)]
+        channel: Option<String>,
+    },
 }
 
 /// Executes the self command.
@@ -37,7 +51,9 @@ pub enum SelfCommand {
 /// Returns an error if the subcommand fails.
 pub async fn execute(args: &SelfArgs) -> Result<()> {
     match &args.command {
-        SelfCommand::Update => execute_update().await,
+        SelfCommand::Update { offline, channel } => {
+            execute_update(*offline, channel.as_deref().and_then(Channel::parse)).await
+        }
     }
 }
 
@@ -46,7 +62,7 @@ pub async fn execute(args: &SelfArgs) -> Result<()> {
 /// # Process
 ///
 /// 1. Fetch the release manifest
-/// 2. Compare current version with latest
+/// 2. Compare current version with latest, on `channel` if given
 /// 3. If newer version available, download it
 /// 4. Verify checksum
 /// 5. Replace current binary
@@ -62,11 +78,13 @@ pub async fn execute(args: &SelfArgs) -> Result<()> {
 ///
 /// Returns an error if:
 /// - Manifest fetch fails
+/// - The channel has no version
 /// - No infs artifact for current platform
 /// - Download fails
+/// - In offline mode, the manifest is not cached or the update is not downloaded
 /// - Checksum verification fails
 /// - Binary replacement fails
-async fn execute_update() -> Result<()> {
+async fn execute_update(offline: bool, channel: Option<Channel>) -> Result<()> {
     let platform = Platform::detect()?;
     let paths = ToolchainPaths::new()?;
     paths.ensure_directories()?;
@@ -75,11 +93,19 @@ async fn execute_update() -> Result<()> {
     println!("Current infs version: {current_version}");
 
     println!("Checking for updates...");
-    let manifest = fetch_manifest().await?;
-
-    let latest_entry = latest_stable(&manifest)
-        .or_else(|| latest_version(&manifest))
-        .context("No version found in manifest")?;
+    let manifest = if offline {
+        cached_manifest()?
+    } else {
+        fetch_manifest().await?
+    };
+
+    let latest_entry = match channel {
+        Some(channel) => latest_in_channel(&manifest, channel)
+            .with_context(|| format!("No version found on the {channel} channel"))?,
+        None => latest_stable(&manifest)
+            .or_else(|| latest_version(&manifest))
+            .context("No version found in manifest")?,
+    };
     let latest_version = &latest_entry.version;
 
     if latest_version == current_version {
@@ -107,13 +133,25 @@ async fn execute_update() -> Result<()> {
 
     let download_filename = artifact.filename();
     let download_path = paths.download_path(download_filename);
-
-    println!("Downloading from {}...", artifact.url);
-    download_file(&artifact.url, &download_path).await?;
+    let signature_path = paths.download_path(&format!("{download_filename}.minisig"));
+
+    let files = [
+        (artifact.url.clone(), download_path.clone()),
+        (artifact.signature_url(), signature_path.clone()),
+    ];
+    if offline {
+        require_downloaded(&files)?;
+    } else {
+        println!("Downloading from {}...", artifact.url);
+        download_files(&files).await?;
+    }
 
     println!("Verifying checksum...");
     verify_checksum(&download_path, &artifact.sha256)?;
 
+    println!("Verifying signature...");
+    verify_signature(&download_path, &signature_path)?;
+
     println!("Extracting...");
     let temp_dir = paths.downloads.join(format!("infs-{latest_version}-temp"));
     extract_archive(&download_path, &temp_dir)?;
@@ -137,6 +175,7 @@ async fn execute_update() -> Result<()> {
     }
 
     std::fs::remove_file(&download_path).ok();
+    std::fs::remove_file(&signature_path).ok();
     std::fs::remove_dir_all(&temp_dir).ok();
 
     println!("Successfully updated infs to {latest_version}.");
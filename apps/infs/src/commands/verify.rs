@@ -0,0 +1,322 @@
+//! Verify command for the infs CLI.
+//!
+//! Compiles an Inference project, translates it to Rocq and checks the proofs
+//! with the Rocq compiler.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! infs verify              # Verify the project in the current directory
+//! infs verify myproject    # Verify the project in another directory
+//! ```
+//!
+//! ## Execution Pipeline
+//!
+//! 1. **Load** - Read `Inference.toml` for the package name and proofs directory
+//! 2. **Locate** - Find the Rocq compiler and the infc compiler
+//! 3. **Generate** - Resolve the dependencies, then call infc on the project with
+//!    `--codegen --emit=v=<proofs>/<name>.v`
+//! 4. **Check** - Compile the generated file, then every other `.v` file of the proofs
+//!    directory, reporting each one as passed or failed
+//!
+//! The root of a workspace verifies every member in turn, each into its own proofs
+//! directory, and reports the totals of all of them.
+//!
+//! With `--output json`, the outcome of every proof file is printed as one document
+//! once all are checked; the output of infc goes to stderr:
+//!
+//! ```json
+//! {
+//!   "success": false,
+//!   "passed": 1,
+//!   "failed": 1,
+//!   "packages": [{
+//!     "name": "demo",
+//!     "version": "0.1.0",
+//!     "proofs": [
+//!       { "file": "proofs/demo.v", "passed": true, "output": "" },
+//!       { "file": "proofs/bad.v", "passed": false, "output": "Error: ..." }
+//!     ]
+//!   }]
+//! }
+//! ```
+//!
+//! The proofs directory is `proofs/` unless `[verification] output-dir` says otherwise.
+//! It is mapped to the Rocq logical path `<name>`, the package name with `-` replaced
+//! by `_`, so a hand-written proof loads the generated definitions with
+//! `From <name> Require Import <name>.`
+//!
+//! ## Prerequisites
+//!
+//! This command requires:
+//! - `infc` compiler (via toolchain or PATH)
+//! - `coqc` or `rocq` (via `COQC_PATH`, PATH or toolchain)
+
+use anyhow::{Context, Result, bail};
+use clap::Args;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::errors::InfsError;
+use crate::output::{OutputFormat, print_json};
+use crate::project::dependencies::{
+    ResolvedDependency, resolve_dependencies, resolve_workspace_dependencies,
+};
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE};
+use crate::project::workspace::Workspace;
+use crate::toolchain::resolver::ProofCompiler;
+use crate::toolchain::{find_coqc, find_infc};
+
+/// Arguments for the `verify` command.
+#[derive(Args)]
+pub struct VerifyArgs {
+    /// Project directory to verify (defaults to the current directory).
+    pub path: Option<PathBuf>,
+}
+
+/// Outcome of the proofs of all packages, for `--output json`.
+#[derive(Serialize)]
+struct VerifyOutput {
+    success: bool,
+    passed: usize,
+    failed: usize,
+    packages: Vec<PackageProofs>,
+}
+
+/// Outcome of the proof files of one package.
+#[derive(Serialize)]
+struct PackageProofs {
+    name: String,
+    version: String,
+    proofs: Vec<ProofOutcome>,
+}
+
+/// Outcome of compiling one proof file.
+#[derive(Serialize)]
+struct ProofOutcome {
+    /// Path of the file relative to the package directory.
+    file: PathBuf,
+    passed: bool,
+    /// What the Rocq compiler printed.
+    output: String,
+}
+
+/// Executes the `verify` command.
+///
+/// Every proof file is checked, so one failing file does not hide the results of
+/// the others.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The directory has no `Inference.toml`
+/// - The workspace of the project has an invalid member
+/// - The Rocq compiler or infc cannot be found
+/// - A dependency cannot be resolved
+/// - infc exits with non-zero code (as `InfsError::ProcessExitCode`)
+/// - Any proof file fails to compile (as `InfsError::ProcessExitCode` with `--output json`)
+pub fn execute(args: &VerifyArgs, output: OutputFormat) -> Result<()> {
+    let root = args.path.clone().unwrap_or_else(|| PathBuf::from("."));
+    let manifest_path = root.join(MANIFEST_FILE);
+    if !manifest_path.is_file() {
+        bail!(
+            "No {MANIFEST_FILE} found in {}; infs verify works on projects",
+            root.display()
+        );
+    }
+    let coqc = find_coqc()?;
+    let infc_path = find_infc()?;
+
+    let mut packages = Vec::new();
+    if let Some(workspace) = Workspace::find(&root)? {
+        let dependencies = resolve_workspace_dependencies(&workspace)?;
+        for (member, dependencies) in workspace.members.iter().zip(&dependencies) {
+            if !workspace.selects(&root, member) {
+                continue;
+            }
+            let package = &member.manifest.package;
+            if !output.is_json() {
+                println!("Verifying {} v{}", package.name, package.version);
+            }
+            packages.push(verify_package(
+                &coqc,
+                &infc_path,
+                &member.dir,
+                &member.manifest,
+                dependencies,
+                output,
+            )?);
+        }
+    } else {
+        let manifest = InferenceToml::read_from_file(&manifest_path)?;
+        let dependencies = resolve_dependencies(&root, &manifest)?;
+        packages.push(verify_package(
+            &coqc,
+            &infc_path,
+            &root,
+            &manifest,
+            &dependencies,
+            output,
+        )?);
+    }
+
+    let proofs = packages.iter().flat_map(|package| &package.proofs);
+    let passed = proofs.clone().filter(|proof| proof.passed).count();
+    let failed = proofs.count() - passed;
+    if output.is_json() {
+        print_json(&VerifyOutput {
+            success: failed == 0,
+            passed,
+            failed,
+            packages,
+        })?;
+    } else {
+        println!();
+        println!("{passed} passed, {failed} failed");
+    }
+    if failed > 0 {
+        if output.is_json() {
+            // The document already reports the failures.
+            return Err(InfsError::process_exit_code(1).into());
+        }
+        bail!("{failed} proof file(s) failed to compile");
+    }
+    Ok(())
+}
+
+/// Generates the proofs of the package in `root` and checks every proof file,
+/// printing `PASS` or `FAIL` for each unless the output is JSON; returns the outcome
+/// of every file.
+fn verify_package(
+    coqc: &ProofCompiler,
+    infc_path: &Path,
+    root: &Path,
+    manifest: &InferenceToml,
+    dependencies: &[ResolvedDependency],
+    output: OutputFormat,
+) -> Result<PackageProofs> {
+    let output_dir = Path::new(&manifest.verification.output_dir);
+    let proofs_dir = root.join(output_dir);
+    std::fs::create_dir_all(&proofs_dir)
+        .with_context(|| format!("Failed to create {}", proofs_dir.display()))?;
+
+    let name = logical_name(&manifest.package.name);
+    let generated = format!("{name}.v");
+    generate_proofs(
+        infc_path,
+        root,
+        dependencies,
+        &proofs_dir.join(&generated),
+        output,
+    )?;
+
+    let mut proofs = Vec::new();
+    for file in proof_files(&proofs_dir, &generated)? {
+        let display = output_dir.join(&file);
+        let result = Command::new(&coqc.path)
+            .args(&coqc.args)
+            .arg("-R")
+            .arg(".")
+            .arg(&name)
+            .arg(&file)
+            .current_dir(&proofs_dir)
+            .output()
+            .with_context(|| format!("Failed to execute {}", coqc.path.display()))?;
+        let passed = result.status.success();
+        if !output.is_json() {
+            if passed {
+                println!("PASS {}", display.display());
+            } else {
+                println!("FAIL {}", display.display());
+                print_indented(&result.stdout);
+                print_indented(&result.stderr);
+            }
+        }
+        proofs.push(ProofOutcome {
+            file: display,
+            passed,
+            output: format!(
+                "{}{}",
+                String::from_utf8_lossy(&result.stdout),
+                String::from_utf8_lossy(&result.stderr)
+            ),
+        });
+    }
+    Ok(PackageProofs {
+        name: manifest.package.name.clone(),
+        version: manifest.package.version.clone(),
+        proofs,
+    })
+}
+
+/// Compiles the project at `root` with its `dependencies` and writes its Rocq
+/// translation to `path`. With JSON output, the output of infc goes to stderr.
+fn generate_proofs(
+    infc_path: &Path,
+    root: &Path,
+    dependencies: &[ResolvedDependency],
+    path: &Path,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut cmd = Command::new(infc_path);
+    cmd.arg(root);
+    for dependency in dependencies {
+        cmd.arg("--extern").arg(dependency.extern_arg());
+    }
+    let status = cmd
+        .arg("--codegen")
+        .arg(format!("--emit=v={}", path.display()))
+        .stdout(if output.is_json() {
+            Stdio::from(std::io::stderr())
+        } else {
+            Stdio::inherit()
+        })
+        .stderr(Stdio::inherit())
+        .status()
+        .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+
+    if !status.success() {
+        let code = status.code().unwrap_or(1);
+        return Err(InfsError::process_exit_code(code).into());
+    }
+    if !path.exists() {
+        bail!(
+            "Compilation succeeded but Rocq file not found at: {}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Returns the names of the `.v` files in `proofs_dir`: `generated` first, as the
+/// other files may depend on it, then the rest sorted.
+fn proof_files(proofs_dir: &Path, generated: &str) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    let entries = std::fs::read_dir(proofs_dir)
+        .with_context(|| format!("Failed to read {}", proofs_dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_file()
+            && path.extension().is_some_and(|extension| extension == "v")
+            && let Some(file) = path.file_name().and_then(|file| file.to_str())
+            && file != generated
+        {
+            files.push(file.to_string());
+        }
+    }
+    files.sort();
+    files.insert(0, generated.to_string());
+    Ok(files)
+}
+
+/// Returns the Rocq logical name of a package: `-` is not valid in Rocq identifiers.
+fn logical_name(package: &str) -> String {
+    package.replace('-', "_")
+}
+
+fn print_indented(output: &[u8]) {
+    for line in String::from_utf8_lossy(output).lines() {
+        println!("    {line}");
+    }
+}
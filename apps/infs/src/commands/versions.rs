@@ -1,14 +1,15 @@
 //! Versions command for the infs CLI.
 //!
 //! Lists available toolchain versions from the release manifest with
-//! stability markers and platform availability information.
+//! release channels and platform availability information.
 //!
 //! ## Usage
 //!
 //! ```bash
 //! infs versions           # List all available versions
 //! infs versions --stable  # List only stable versions
-//! infs versions --json    # Output in JSON format
+//! infs versions --json    # Output in JSON format, like `infs --output json versions`
+//! infs versions --offline # List the versions of the cached manifest
 //! ```
 //!
 //! ## Output Format
@@ -16,19 +17,26 @@
 //! ```text
 //! Available toolchain versions:
 //!
+//!   0.3.0-nightly.20261015 (nightly 2026-10-15) [linux] *
 //!   0.2.0 (stable) [linux, macos, windows] *
 //!   0.1.0 (stable) [linux] *
 //!   0.3.0-alpha (prerelease) [linux]
 //!
 //!   * = available for current platform (linux)
 //! ```
+//!
+//! A version is shown with its release channel and, for nightlies, its release date;
+//! a prerelease on no channel is shown as `prerelease`.
 
 use anyhow::Result;
 use clap::Args;
 use serde::Serialize;
 
+use crate::output::OutputFormat;
 use crate::toolchain::Platform;
-use crate::toolchain::manifest::{fetch_manifest, sorted_versions};
+use crate::toolchain::manifest::{
+    Channel, VersionEntry, cached_manifest, fetch_manifest, sorted_versions,
+};
 
 /// Arguments for the versions command.
 #[derive(Args)]
@@ -37,9 +45,13 @@ pub struct VersionsArgs {
     #[clap(long, short = 's')]
     pub stable: bool,
 
-    /// Show versions in JSON format.
+    /// Show versions in JSON format, like `--output json`.
     #[clap(long, short = 'j')]
     pub json: bool,
+
+    /// Do not use the network; list the versions of the cached manifest.
+    #[clap(long)]
+    pub offline: bool,
 }
 
 /// Version information for JSON output.
@@ -47,6 +59,8 @@ pub struct VersionsArgs {
 struct VersionInfo {
     version: String,
     stable: bool,
+    channel: Option<Channel>,
+    date: Option<String>,
     platforms: Vec<String>,
     available_for_current: bool,
 }
@@ -59,18 +73,23 @@ struct VersionInfo {
 /// 2. Fetch the release manifest from the distribution server
 /// 3. Sort versions by semver (newest first)
 /// 4. Filter by stability if --stable flag is set
-/// 5. Output in text or JSON format
+/// 5. Output in text or JSON format, with `--json` or `--output json`
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Platform detection fails
 /// - Manifest fetch fails (network error, parsing error, etc.)
-pub async fn execute(args: &VersionsArgs) -> Result<()> {
+/// - The manifest is not cached in offline mode
+pub async fn execute(args: &VersionsArgs, output: OutputFormat) -> Result<()> {
     let platform = Platform::detect()?;
-    let manifest = fetch_manifest().await?;
+    let manifest = if args.offline {
+        cached_manifest()?
+    } else {
+        fetch_manifest().await?
+    };
 
-    if args.json {
+    if args.json || output.is_json() {
         output_json(&manifest, args.stable, platform)?;
     } else {
         output_text(&manifest, args.stable, platform);
@@ -93,6 +112,8 @@ fn output_json(
         .map(|v| VersionInfo {
             version: v.version.clone(),
             stable: v.stable,
+            channel: v.channel(),
+            date: v.date.clone(),
             platforms: v
                 .available_platforms()
                 .into_iter()
@@ -107,6 +128,15 @@ fn output_json(
     Ok(())
 }
 
+/// Returns the channel of a version in parentheses, with the release date for nightlies.
+fn release_label(version: &VersionEntry) -> String {
+    match (version.channel(), &version.date) {
+        (Some(Channel::Nightly), Some(date)) => format!("(nightly {date})"),
+        (Some(channel), _) => format!("({channel})"),
+        (None, _) => String::from("(prerelease)"),
+    }
+}
+
 /// Outputs version information in text format.
 fn output_text(
     manifest: &crate::toolchain::manifest::Manifest,
@@ -136,11 +166,7 @@ fn output_text(
     let mut has_current_platform = false;
 
     for version in &filtered {
-        let stability = if version.stable {
-            "(stable)"
-        } else {
-            "(prerelease)"
-        };
+        let stability = release_label(version);
 
         let platforms = version.available_platforms();
         let platform_list = if platforms.is_empty() {
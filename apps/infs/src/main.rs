@@ -11,7 +11,13 @@
 //! - `new` - Create a new Inference project
 //! - `init` - Initialize an existing directory as an Inference project
 //! - `build` - Compile Inference source files
+//! - `check` - Type check Inference source files without codegen
+//! - `expand` - Show the AST, inferred types and symbol table of source files
 //! - `run` - Build and execute WASM with wasmtime
+//! - `repl` - Evaluate Inference definitions and expressions interactively
+//! - `fmt` - Format Inference source files
+//! - `doc` - Generate API documentation
+//! - `verify` - Check the Rocq proofs of a project
 //! - `version` - Display version information
 //! - `install` - Install toolchain versions
 //! - `uninstall` - Remove toolchain versions
@@ -32,6 +38,11 @@
 //! When run with `--headless` but no subcommand, `infs` displays help information
 //! instead of launching the TUI.
 //!
+//! ### JSON Output (`--output json`)
+//!
+//! `list`, `versions`, `doctor`, `build` and `verify` print one JSON document on
+//! stdout instead, as described in [`output`].
+//!
 //! ## Examples
 //!
 //! Create a new project:
@@ -44,6 +55,11 @@
 //! infs build example.inf
 //! ```
 //!
+//! Build the project in the current directory into `target/`:
+//! ```bash
+//! infs build --codegen -o
+//! ```
+//!
 //! Install the latest toolchain:
 //! ```bash
 //! infs install
@@ -56,16 +72,20 @@
 
 mod commands;
 mod errors;
+mod output;
 mod project;
+mod source_map;
 mod toolchain;
 mod tui;
 
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use commands::{
-    build, default, doctor, init, install, list, new, run, self_cmd, uninstall, version, versions,
+    build, check, default, doc, doctor, expand, fmt, init, install, list, new, repl, run, self_cmd,
+    uninstall, verify, version, versions,
 };
 use errors::InfsError;
+use output::OutputFormat;
 
 /// Inference unified CLI toolchain.
 ///
@@ -83,14 +103,21 @@ use errors::InfsError;
 COMPILER RESOLUTION:
     The infc compiler is located using the following priority order:
     1. INFC_PATH environment variable (explicit override)
-    2. System PATH (via 'which infc')
-    3. Managed toolchain (~/.inference/toolchains/VERSION/bin/infc)
+    2. Pinned toolchain (INFS_TOOLCHAIN, or the nearest inference-toolchain.toml)
+    3. System PATH (via 'which infc')
+    4. Managed toolchain (~/.inference/toolchains/VERSION/bin/infc)
+    The Rocq compiler used by 'verify' is located the same way, through
+    COQC_PATH, then coqc or rocq in PATH, then the managed toolchain.
 
 ENVIRONMENT VARIABLES:
     INFS_NO_TUI             Disable interactive TUI
     INFC_PATH               Explicit path to infc binary
+    COQC_PATH               Explicit path to coqc or rocq binary
+    INFS_TOOLCHAIN          Toolchain version to use, overriding inference-toolchain.toml
     INFERENCE_HOME          Toolchain directory (default: ~/.inference)
-    INFS_DIST_SERVER        Distribution server URL (default: https://inference-lang.org)"
+    INFS_DIST_SERVER        Distribution server URL, or comma-separated mirrors tried in order
+                            (default: https://inference-lang.org)
+    INFS_DIST_TOKEN         Bearer token sent to the INFS_DIST_SERVER mirrors"
 )]
 pub struct Cli {
     /// Run in headless mode without TUI.
@@ -100,6 +127,13 @@ pub struct Cli {
     #[clap(long = "headless", global = true, action = clap::ArgAction::SetTrue)]
     pub headless: bool,
 
+    /// Format of the results of `list`, `versions`, `doctor`, `build` and `verify`.
+    ///
+    /// With `json`, these commands print one JSON document on stdout, and errors are
+    /// printed as `{"error": "<message>"}`.
+    #[clap(long = "output", global = true, value_enum, default_value_t = OutputFormat::Human)]
+    pub output: OutputFormat,
+
     /// The subcommand to execute.
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -124,9 +158,24 @@ pub enum Commands {
     /// Compile Inference source files.
     ///
     /// The build command runs one or more compilation phases over a single
-    /// .inf source file. Phases execute in canonical order: parse, analyze,
-    /// codegen.
-    Build(build::BuildArgs),
+    /// .inf source file, or over every source file of a project, writing the
+    /// project's artifacts to target/. Phases execute in canonical order:
+    /// parse, analyze, codegen.
+    Build(Box<build::BuildArgs>),
+
+    /// Type check Inference source files without generating code.
+    ///
+    /// Parses and analyzes a source file, or every source file of a project,
+    /// reporting diagnostics like build does. Fast enough to run on every
+    /// save; with --watch, checks again whenever a source file changes.
+    Check(check::CheckArgs),
+
+    /// Show the AST, inferred types and symbol table of source files.
+    ///
+    /// Prints the parsed AST of a source file or project, with the type
+    /// inferred for every value expression, followed by every scope of the
+    /// symbol table, as a tree or, with --format json, as one JSON document.
+    Expand(expand::ExpandArgs),
 
     /// Build and run a source file.
     ///
@@ -134,6 +183,33 @@ pub enum Commands {
     /// Arguments after the path are passed to the program.
     Run(run::RunArgs),
 
+    /// Evaluate Inference definitions and expressions interactively.
+    ///
+    /// Keeps the definitions entered at the prompt, and type checks and
+    /// evaluates expressions against them with infc and wasmtime. :type,
+    /// :wat and :v show the type of an expression and the generated code.
+    Repl(repl::ReplArgs),
+
+    /// Format Inference source files.
+    ///
+    /// Rewrites a source file, or every source file of a project, in the
+    /// canonical style. With --check, reports unformatted files instead.
+    Fmt(fmt::FmtArgs),
+
+    /// Generate API documentation.
+    ///
+    /// Writes HTML or Markdown pages for the doc comments and signatures of
+    /// a source file, or of every source file of a project into target/doc,
+    /// with a module index and links from every item to its source.
+    Doc(doc::DocArgs),
+
+    /// Check the Rocq proofs of a project.
+    ///
+    /// Compiles the project, writes its Rocq translation to the proofs
+    /// directory and compiles every .v file there with coqc, reporting
+    /// each file as passed or failed.
+    Verify(verify::VerifyArgs),
+
     /// Display version information.
     ///
     /// Shows the version of the infs CLI. Use -v or --verbose for detailed
@@ -171,8 +247,10 @@ pub enum Commands {
     /// Check installation health.
     ///
     /// Verifies that all required components are installed and configured
-    /// correctly. Reports any issues with suggested remediation steps.
-    Doctor,
+    /// correctly, including wasmtime and the Rocq compiler. Reports any issues
+    /// with suggested remediation steps, and fixes the managed components with
+    /// `--fix`.
+    Doctor(doctor::DoctorArgs),
 
     /// Manage the infs binary itself.
     ///
@@ -183,8 +261,10 @@ pub enum Commands {
 
 #[tokio::main]
 async fn main() {
-    if let Err(e) = run().await {
-        let exit_code = handle_error(&e);
+    let cli = Cli::parse();
+    let output = cli.output;
+    if let Err(e) = run(cli).await {
+        let exit_code = handle_error(&e, output);
         std::process::exit(exit_code);
     }
 }
@@ -193,30 +273,40 @@ async fn main() {
 ///
 /// For `ProcessExitCode` errors, returns the embedded exit code without
 /// printing an error message (the subprocess already printed its output).
-/// For all other errors, prints the error and returns exit code 1.
-fn handle_error(e: &anyhow::Error) -> i32 {
+/// For all other errors, prints the error, as JSON on stdout with `--output json`, and
+/// returns exit code 1.
+fn handle_error(e: &anyhow::Error, output: OutputFormat) -> i32 {
     if let Some(InfsError::ProcessExitCode { code }) = e.downcast_ref::<InfsError>() {
         return *code;
     }
-    eprintln!("Error: {e:?}");
+    if output.is_json() {
+        output::print_error(e);
+    } else {
+        eprintln!("Error: {e:?}");
+    }
     1
 }
 
-async fn run() -> Result<()> {
-    let cli = Cli::parse();
-
+async fn run(cli: Cli) -> Result<()> {
+    let output = cli.output;
     match cli.command {
         Some(Commands::New(args)) => new::execute(&args),
         Some(Commands::Init(args)) => init::execute(&args),
-        Some(Commands::Build(args)) => build::execute(&args),
+        Some(Commands::Build(args)) => build::execute(&args, output),
+        Some(Commands::Check(args)) => check::execute(&args),
+        Some(Commands::Expand(args)) => expand::execute(&args),
         Some(Commands::Run(args)) => run::execute(&args),
+        Some(Commands::Repl(args)) => repl::execute(&args),
+        Some(Commands::Fmt(args)) => fmt::execute(&args),
+        Some(Commands::Doc(args)) => doc::execute(&args),
+        Some(Commands::Verify(args)) => verify::execute(&args, output),
         Some(Commands::Version(args)) => version::execute(&args),
         Some(Commands::Install(args)) => install::execute(&args).await,
         Some(Commands::Uninstall(args)) => uninstall::execute(&args).await,
-        Some(Commands::List) => list::execute().await,
-        Some(Commands::Versions(args)) => versions::execute(&args).await,
+        Some(Commands::List) => list::execute(output).await,
+        Some(Commands::Versions(args)) => versions::execute(&args, output).await,
         Some(Commands::Default(args)) => default::execute(&args).await,
-        Some(Commands::Doctor) => doctor::execute().await,
+        Some(Commands::Doctor(args)) => doctor::execute(&args, output).await,
         Some(Commands::SelfCmd(args)) => self_cmd::execute(&args).await,
         None => {
             if cli.headless || !tui::should_use_tui() {
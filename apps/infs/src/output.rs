@@ -0,0 +1,53 @@
+//! Machine-readable output of infs commands.
+//!
+//! The global `--output json` flag makes `list`, `versions`, `doctor`, `build` and
+//! `verify` print one JSON document on stdout instead of their report for humans, for
+//! CI dashboards and editor integrations. Progress messages, such as `Compiling` from
+//! `build`, still go to stderr, so stdout only carries the document.
+//!
+//! A command that fails before it has a result prints `{"error": "<message>"}` instead.
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Output format selected with `--output`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Reports for humans.
+    #[default]
+    Human,
+    /// One JSON document on stdout.
+    Json,
+}
+
+impl OutputFormat {
+    /// Returns true for `--output json`.
+    #[must_use]
+    pub fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+}
+
+/// Prints `value` as pretty JSON on stdout.
+///
+/// # Errors
+///
+/// Returns an error if `value` cannot be serialized.
+pub fn print_json(value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_string_pretty(value).context("Failed to serialize output")?;
+    println!("{json}");
+    Ok(())
+}
+
+/// Prints the JSON document of an error: `{"error": "<message>"}`, with the causes of
+/// the error after the message.
+pub fn print_error(error: &anyhow::Error) {
+    #[derive(Serialize)]
+    struct ErrorOutput {
+        error: String,
+    }
+    let _ = print_json(&ErrorOutput {
+        error: format!("{error:#}"),
+    });
+}
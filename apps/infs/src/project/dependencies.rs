@@ -0,0 +1,617 @@
+//! Dependency resolution.
+//!
+//! Turns the `[dependencies]` of a manifest into directories that infc loads with
+//! `--extern <name>=<dir>`:
+//!
+//! - A path dependency is used in place, relative to the project directory
+//! - A git dependency is cloned into `target/deps/<name>` and checked out at the commit
+//!   recorded in `Inference.lock`, or else at its `rev`, `tag`, `branch` or the default
+//!   branch of the repository
+//!
+//! The resolved sources and commits are written to `Inference.lock`, so the next build
+//! checks out the same commits even if a branch has moved. Dependencies of dependencies
+//! are not resolved.
+//!
+//! The members of a [workspace](crate::project::workspace) are resolved together, into
+//! the lockfile and `target/deps/` of the workspace root.
+//!
+//! ## Prerequisites
+//!
+//! Git dependencies require `git` in PATH.
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::project::lockfile::{InferenceLock, LOCK_FILE, LockedPackage};
+use crate::project::manifest::{Dependency, DetailedDependency, InferenceToml};
+use crate::project::workspace::Workspace;
+
+/// A dependency ready to be passed to infc.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedDependency {
+    /// The dependency name, as in `[dependencies]`.
+    pub name: String,
+    /// Directory of the dependency, holding `src/lib.inf` or `src/main.inf`.
+    pub dir: PathBuf,
+}
+
+impl ResolvedDependency {
+    /// Returns the value of the infc `--extern` flag for this dependency.
+    #[must_use]
+    pub fn extern_arg(&self) -> String {
+        format!("{}={}", self.name, self.dir.display())
+    }
+}
+
+/// Where a dependency comes from.
+enum Source<'a> {
+    Path(&'a str),
+    Git {
+        url: &'a str,
+        reference: GitReference<'a>,
+    },
+}
+
+/// Revision of a git dependency selected by the manifest.
+enum GitReference<'a> {
+    DefaultBranch,
+    Branch(&'a str),
+    Tag(&'a str),
+    Rev(&'a str),
+}
+
+impl<'a> Source<'a> {
+    fn from_dependency(name: &str, dependency: &'a Dependency) -> Result<Self> {
+        let detailed = match dependency {
+            Dependency::Version(version) => bail!(
+                "Dependency `{name} = \"{version}\"` needs a package registry, which is not \
+                 supported yet; use `path` or `git`"
+            ),
+            Dependency::Detailed(detailed) => detailed,
+        };
+        let DetailedDependency {
+            path,
+            git,
+            branch,
+            tag,
+            rev,
+        } = detailed;
+        let selectors = [branch, tag, rev].iter().filter(|s| s.is_some()).count();
+        match (path, git) {
+            (Some(path), None) if selectors == 0 => Ok(Source::Path(path)),
+            (Some(_), None) => {
+                bail!("Dependency `{name}`: `branch`, `tag` and `rev` need a `git` source")
+            }
+            (None, Some(url)) if selectors <= 1 => {
+                let reference = match (branch, tag, rev) {
+                    (Some(branch), _, _) => GitReference::Branch(branch),
+                    (_, Some(tag), _) => GitReference::Tag(tag),
+                    (_, _, Some(rev)) => GitReference::Rev(rev),
+                    _ => GitReference::DefaultBranch,
+                };
+                Ok(Source::Git { url, reference })
+            }
+            (None, Some(_)) => {
+                bail!("Dependency `{name}`: only one of `branch`, `tag` and `rev` may be given")
+            }
+            (Some(_), Some(_)) => bail!("Dependency `{name}`: give either `path` or `git`"),
+            (None, None) => bail!("Dependency `{name}`: `path` or `git` is required"),
+        }
+    }
+
+    /// Returns the source as recorded in the lockfile, for a package in the `member`
+    /// directory of the workspace (empty for the root).
+    fn lock_source(&self, member: &str) -> String {
+        match self {
+            Source::Path(path) if member.is_empty() => format!("path+{path}"),
+            Source::Path(path) => format!("path+{member}/{path}"),
+            Source::Git { url, reference } => match reference {
+                GitReference::DefaultBranch => format!("git+{url}"),
+                GitReference::Branch(branch) => format!("git+{url}?branch={branch}"),
+                GitReference::Tag(tag) => format!("git+{url}?tag={tag}"),
+                GitReference::Rev(rev) => format!("git+{url}?rev={rev}"),
+            },
+        }
+    }
+}
+
+/// Resolves the dependencies of the project in `project_dir` and updates its
+/// `Inference.lock`.
+///
+/// Returns the dependencies sorted by name. The lockfile is written only if it changes,
+/// and not at all for a project without dependencies and lockfile.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A dependency is not a valid `path` or `git` dependency
+/// - The directory of a path dependency does not exist
+/// - git is not installed, or cloning or checking out a git dependency fails
+/// - The lockfile cannot be read or written
+pub fn resolve_dependencies(
+    project_dir: &Path,
+    manifest: &InferenceToml,
+) -> Result<Vec<ResolvedDependency>> {
+    let mut resolver = Resolver::new(project_dir)?;
+    let dependencies = resolver.resolve(project_dir, manifest)?;
+    resolver.write_lock()?;
+    Ok(dependencies)
+}
+
+/// Resolves the dependencies of every member of `workspace` and updates the
+/// `Inference.lock` of its root.
+///
+/// Returns the dependencies of each member, in the order of [`Workspace::members`].
+/// Members share one checkout of a git dependency, so they must agree on its source.
+///
+/// # Errors
+///
+/// Returns an error if a dependency cannot be resolved, as for
+/// [`resolve_dependencies`], or two members give the same git dependency different
+/// sources.
+pub fn resolve_workspace_dependencies(
+    workspace: &Workspace,
+) -> Result<Vec<Vec<ResolvedDependency>>> {
+    let mut resolver = Resolver::new(&workspace.root)?;
+    let dependencies = workspace
+        .members
+        .iter()
+        .map(|member| resolver.resolve(&member.dir, &member.manifest))
+        .collect::<Result<Vec<_>>>()?;
+    resolver.write_lock()?;
+    Ok(dependencies)
+}
+
+/// Resolves the dependencies of the packages sharing the lockfile and `target/deps/`
+/// of `root`.
+struct Resolver<'a> {
+    root: &'a Path,
+    previous: Option<InferenceLock>,
+    locked: Vec<LockedPackage>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(root: &'a Path) -> Result<Self> {
+        let lock_path = root.join(LOCK_FILE);
+        let previous = if lock_path.is_file() {
+            Some(InferenceLock::read_from_file(&lock_path)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            root,
+            previous,
+            locked: Vec::new(),
+        })
+    }
+
+    /// Resolves the dependencies of the package in `dir`, sorted by name.
+    fn resolve(&mut self, dir: &Path, manifest: &InferenceToml) -> Result<Vec<ResolvedDependency>> {
+        // Path sources are recorded relative to the root, as `path+<member>/<path>`.
+        let member = dir
+            .strip_prefix(self.root)
+            .map(|member| member.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let mut names: Vec<&String> = manifest.dependencies.packages.keys().collect();
+        names.sort();
+        let mut resolved = Vec::new();
+        for name in names {
+            let source = Source::from_dependency(name, &manifest.dependencies.packages[name])?;
+            let lock_source = source.lock_source(&member);
+            let shared = self.locked.iter().find(|package| package.name == *name);
+            if let Some(shared) = shared
+                && shared.source != lock_source
+                && (shared.source.starts_with("git+") || lock_source.starts_with("git+"))
+            {
+                bail!(
+                    "Dependency `{name}` has different sources in the workspace: `{}` and \
+                     `{lock_source}`",
+                    shared.source
+                );
+            }
+            let known = shared.is_some_and(|package| package.source == lock_source);
+            let (dep_dir, rev) = match source {
+                Source::Path(path) => {
+                    let dep_dir = dir.join(path);
+                    if !dep_dir.is_dir() {
+                        bail!(
+                            "Dependency `{name}`: directory not found: {}",
+                            dep_dir.display()
+                        );
+                    }
+                    (dep_dir, None)
+                }
+                Source::Git { .. } if known => (
+                    self.deps_dir(name),
+                    shared.and_then(|package| package.rev.clone()),
+                ),
+                Source::Git { url, reference } => {
+                    let locked_rev = self
+                        .previous
+                        .as_ref()
+                        .and_then(|lock| lock.find(name, &lock_source))
+                        .and_then(|package| package.rev.as_deref());
+                    let dep_dir = self.deps_dir(name);
+                    let rev = checkout_git(name, url, &reference, locked_rev, &dep_dir)?;
+                    (dep_dir, Some(rev))
+                }
+            };
+            resolved.push(ResolvedDependency {
+                name: name.clone(),
+                dir: dep_dir,
+            });
+            if !known {
+                self.locked.push(LockedPackage {
+                    name: name.clone(),
+                    source: lock_source,
+                    rev,
+                });
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Returns the directory the git dependency `name` is cloned into.
+    fn deps_dir(&self, name: &str) -> PathBuf {
+        self.root.join("target").join("deps").join(name)
+    }
+
+    /// Writes the lockfile of the resolved dependencies if it changed.
+    fn write_lock(self) -> Result<()> {
+        let lock = InferenceLock::new(self.locked);
+        let changed = match &self.previous {
+            Some(previous) => *previous != lock,
+            None => !lock.packages.is_empty(),
+        };
+        if changed {
+            lock.write_to_file(&self.root.join(LOCK_FILE))?;
+        }
+        Ok(())
+    }
+}
+
+/// Clones the repository at `url` into `dir`, or updates the clone, checks out the
+/// `locked` commit or else the revision `reference` selects, and returns the commit.
+fn checkout_git(
+    name: &str,
+    url: &str,
+    reference: &GitReference<'_>,
+    locked: Option<&str>,
+    dir: &Path,
+) -> Result<String> {
+    let cloned = dir.join(".git").is_dir()
+        && git(dir, &["remote", "get-url", "origin"]).is_ok_and(|origin| origin == url);
+    if !cloned {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)
+                .with_context(|| format!("Failed to remove {}", dir.display()))?;
+        }
+        let parent = dir.parent().unwrap_or(dir);
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+        println!("Cloning {name} from {url}");
+        let dir_arg = dir.to_string_lossy();
+        git(Path::new("."), &["clone", "--quiet", url, &dir_arg])
+            .with_context(|| format!("Failed to clone dependency `{name}`"))?;
+    }
+
+    let target = match (locked, reference) {
+        (Some(rev), _) => rev.to_string(),
+        (None, GitReference::DefaultBranch) => String::from("origin/HEAD"),
+        (None, GitReference::Branch(branch)) => format!("origin/{branch}"),
+        (None, GitReference::Tag(tag)) => format!("refs/tags/{tag}"),
+        (None, GitReference::Rev(rev)) => (*rev).to_string(),
+    };
+    // A locked commit that is already present needs no network access.
+    let present = git(dir, &["rev-parse", "--verify", "--quiet", &commit(&target)]).is_ok();
+    if cloned && (locked.is_none() || !present) {
+        git(dir, &["fetch", "--quiet", "--tags", "origin"])
+            .with_context(|| format!("Failed to fetch dependency `{name}`"))?;
+    }
+    git(dir, &["checkout", "--quiet", "--detach", &commit(&target)])
+        .with_context(|| format!("Dependency `{name}`: revision `{target}` not found in {url}"))?;
+    git(dir, &["rev-parse", "HEAD"])
+}
+
+/// Returns the git revision naming the commit `target` points to.
+fn commit(target: &str) -> String {
+    format!("{target}^{{commit}}")
+}
+
+/// Runs git in `dir` and returns its trimmed stdout.
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to execute git; git dependencies require git in PATH")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::workspace::Member;
+
+    fn temp_project() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("infs_deps_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn manifest_with(name: &str, dependency: DetailedDependency) -> InferenceToml {
+        let mut manifest = InferenceToml::new("app");
+        manifest
+            .dependencies
+            .packages
+            .insert(String::from(name), Dependency::Detailed(dependency));
+        manifest
+    }
+
+    fn run_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=infs", "-c", "user.email=infs@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Creates a git repository holding a module, with one commit per source in `sources`;
+    /// returns its directory and the commits.
+    fn git_repository(root: &Path, sources: &[&str]) -> (PathBuf, Vec<String>) {
+        let repo = root.join("repo");
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        run_git(&repo, &["init", "--quiet", "--initial-branch=main"]);
+        let mut commits = Vec::new();
+        for source in sources {
+            std::fs::write(repo.join("src/lib.inf"), source).unwrap();
+            run_git(&repo, &["add", "."]);
+            run_git(&repo, &["commit", "--quiet", "-m", "update"]);
+            commits.push(git(&repo, &["rev-parse", "HEAD"]).unwrap());
+        }
+        (repo, commits)
+    }
+
+    #[test]
+    fn test_resolve_without_dependencies_writes_no_lockfile() {
+        let project = temp_project();
+        let resolved = resolve_dependencies(&project, &InferenceToml::new("app")).unwrap();
+        assert!(resolved.is_empty());
+        assert!(!project.join(LOCK_FILE).exists());
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    fn workspace_member(root: &Path, member: &str, manifest: InferenceToml) -> Member {
+        let dir = root.join(member);
+        std::fs::create_dir_all(&dir).unwrap();
+        Member { dir, manifest }
+    }
+
+    #[test]
+    fn test_resolve_workspace_into_one_lockfile() {
+        let root = temp_project();
+        std::fs::create_dir_all(root.join("mathlib/src")).unwrap();
+        let path_dependency = DetailedDependency {
+            path: Some(String::from("../mathlib")),
+            ..DetailedDependency::default()
+        };
+        let workspace = Workspace {
+            root: root.clone(),
+            members: vec![
+                workspace_member(
+                    &root,
+                    "a",
+                    manifest_with("mathlib", path_dependency.clone()),
+                ),
+                workspace_member(&root, "b", manifest_with("mathlib", path_dependency)),
+            ],
+        };
+
+        let resolved = resolve_workspace_dependencies(&workspace).unwrap();
+        assert_eq!(resolved[0][0].dir, root.join("a/../mathlib"));
+        assert_eq!(resolved[1][0].dir, root.join("b/../mathlib"));
+        assert!(!root.join("a").join(LOCK_FILE).exists());
+        let lock = InferenceLock::read_from_file(&root.join(LOCK_FILE)).unwrap();
+        let sources: Vec<&str> = lock.packages.iter().map(|p| p.source.as_str()).collect();
+        assert_eq!(sources, ["path+a/../mathlib", "path+b/../mathlib"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_workspace_rejects_conflicting_git_sources() {
+        let root = temp_project();
+        std::fs::create_dir_all(root.join("util/src")).unwrap();
+        let workspace = Workspace {
+            root: root.clone(),
+            members: vec![
+                workspace_member(
+                    &root,
+                    "a",
+                    manifest_with(
+                        "util",
+                        DetailedDependency {
+                            path: Some(String::from("../util")),
+                            ..DetailedDependency::default()
+                        },
+                    ),
+                ),
+                workspace_member(
+                    &root,
+                    "b",
+                    manifest_with(
+                        "util",
+                        DetailedDependency {
+                            git: Some(String::from("https://example.com/util")),
+                            ..DetailedDependency::default()
+                        },
+                    ),
+                ),
+            ],
+        };
+
+        let err = resolve_workspace_dependencies(&workspace)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("different sources in the workspace"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_path_dependency() {
+        let project = temp_project();
+        std::fs::create_dir_all(project.join("mathlib/src")).unwrap();
+        let manifest = manifest_with(
+            "mathlib",
+            DetailedDependency {
+                path: Some(String::from("mathlib")),
+                ..DetailedDependency::default()
+            },
+        );
+
+        let resolved = resolve_dependencies(&project, &manifest).unwrap();
+        assert_eq!(
+            resolved,
+            vec![ResolvedDependency {
+                name: String::from("mathlib"),
+                dir: project.join("mathlib"),
+            }]
+        );
+        let lock = InferenceLock::read_from_file(&project.join(LOCK_FILE)).unwrap();
+        assert_eq!(lock.packages[0].source, "path+mathlib");
+        assert_eq!(lock.packages[0].rev, None);
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_missing_path_dependency_fails() {
+        let project = temp_project();
+        let manifest = manifest_with(
+            "mathlib",
+            DetailedDependency {
+                path: Some(String::from("mathlib")),
+                ..DetailedDependency::default()
+            },
+        );
+
+        let error = resolve_dependencies(&project, &manifest).unwrap_err();
+        assert!(error.to_string().contains("directory not found"));
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_rejects_version_dependency() {
+        let project = temp_project();
+        let mut manifest = InferenceToml::new("app");
+        manifest.dependencies.packages.insert(
+            String::from("std"),
+            Dependency::Version(String::from("0.1")),
+        );
+
+        let error = resolve_dependencies(&project, &manifest).unwrap_err();
+        assert!(error.to_string().contains("package registry"));
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_rejects_path_and_git() {
+        let project = temp_project();
+        let manifest = manifest_with(
+            "mathlib",
+            DetailedDependency {
+                path: Some(String::from("mathlib")),
+                git: Some(String::from("https://example.com/mathlib.git")),
+                ..DetailedDependency::default()
+            },
+        );
+
+        let error = resolve_dependencies(&project, &manifest).unwrap_err();
+        assert!(error.to_string().contains("either `path` or `git`"));
+        std::fs::remove_dir_all(&project).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_git_dependency_keeps_locked_commit() {
+        let root = temp_project();
+        let (repo, commits) = git_repository(&root, &["pub fn one() -> i32 { return 1; }"]);
+        let project = root.join("app");
+        std::fs::create_dir_all(&project).unwrap();
+        let url = repo.to_string_lossy().to_string();
+        let manifest = manifest_with(
+            "mathlib",
+            DetailedDependency {
+                git: Some(url.clone()),
+                ..DetailedDependency::default()
+            },
+        );
+
+        let resolved = resolve_dependencies(&project, &manifest).unwrap();
+        let checkout = project.join("target/deps/mathlib");
+        assert_eq!(resolved[0].dir, checkout);
+        assert!(checkout.join("src/lib.inf").is_file());
+        let lock = InferenceLock::read_from_file(&project.join(LOCK_FILE)).unwrap();
+        assert_eq!(lock.packages[0].source, format!("git+{url}"));
+        assert_eq!(lock.packages[0].rev.as_deref(), Some(commits[0].as_str()));
+
+        // A new commit upstream does not change the locked checkout.
+        std::fs::write(
+            repo.join("src/lib.inf"),
+            "pub fn two() -> i32 { return 2; }",
+        )
+        .unwrap();
+        run_git(&repo, &["commit", "--quiet", "-am", "update"]);
+        resolve_dependencies(&project, &manifest).unwrap();
+        assert_eq!(git(&checkout, &["rev-parse", "HEAD"]).unwrap(), commits[0]);
+
+        // Without the lockfile the dependency moves to the new commit.
+        std::fs::remove_file(project.join(LOCK_FILE)).unwrap();
+        resolve_dependencies(&project, &manifest).unwrap();
+        assert_ne!(git(&checkout, &["rev-parse", "HEAD"]).unwrap(), commits[0]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_git_dependency_at_rev() {
+        let root = temp_project();
+        let (repo, commits) = git_repository(
+            &root,
+            &[
+                "pub fn one() -> i32 { return 1; }",
+                "pub fn two() -> i32 { return 2; }",
+            ],
+        );
+        let project = root.join("app");
+        std::fs::create_dir_all(&project).unwrap();
+        let manifest = manifest_with(
+            "mathlib",
+            DetailedDependency {
+                git: Some(repo.to_string_lossy().to_string()),
+                rev: Some(commits[0].clone()),
+                ..DetailedDependency::default()
+            },
+        );
+
+        resolve_dependencies(&project, &manifest).unwrap();
+        let checkout = project.join("target/deps/mathlib");
+        assert_eq!(git(&checkout, &["rev-parse", "HEAD"]).unwrap(), commits[0]);
+        let lock = InferenceLock::read_from_file(&project.join(LOCK_FILE)).unwrap();
+        assert!(
+            lock.packages[0]
+                .source
+                .ends_with(&format!("?rev={}", commits[0]))
+        );
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
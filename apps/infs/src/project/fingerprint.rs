@@ -0,0 +1,276 @@
+//! Build fingerprints for skipping unchanged project builds.
+//!
+//! A fingerprint is a SHA256 hash of everything that determines the result of a
+//! project build:
+//!
+//! - the toolchain: the output of `infc --version`, and the size and modification
+//!   time of the infc binary, so a rebuilt compiler invalidates it too
+//! - the options: the complete infc command line
+//! - the sources: `Inference.toml` and every file under `src/` of the project and of
+//!   each dependency
+//!
+//! After a successful build, the fingerprint and the artifacts the build wrote to the
+//! output directory are recorded in `.fingerprint/<name>.json` there. A later build with
+//! the same fingerprint is fresh, and skipped, as long as those artifacts still exist.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::project::dependencies::ResolvedDependency;
+use crate::project::manifest::MANIFEST_FILE;
+
+/// Directory of the fingerprint files, in the output directory.
+const FINGERPRINT_DIR: &str = ".fingerprint";
+
+/// Version of the fingerprint computation; changing it invalidates every fingerprint.
+const FINGERPRINT_VERSION: &str = "1";
+
+/// A recorded fingerprint with the artifacts of the build it was computed for.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Fingerprint {
+    /// The SHA256 hash, as a lowercase hex string.
+    pub hash: String,
+
+    /// Files the build wrote to the output directory.
+    pub outputs: Vec<PathBuf>,
+}
+
+/// Computes the fingerprint hash of building the project in `project_dir` by running
+/// `infc_path` with `args`.
+///
+/// # Errors
+///
+/// Returns an error if infc cannot be run for its version, or a source file cannot be
+/// read.
+pub fn compute<'a>(
+    infc_path: &Path,
+    args: impl IntoIterator<Item = &'a OsStr>,
+    project_dir: &Path,
+    dependencies: &[ResolvedDependency],
+) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hash_field(&mut hasher, FINGERPRINT_VERSION.as_bytes());
+
+    let version = Command::new(infc_path)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("Failed to execute infc at {}", infc_path.display()))?;
+    hash_field(&mut hasher, &version.stdout);
+    let metadata = std::fs::metadata(infc_path)
+        .with_context(|| format!("Failed to read {}", infc_path.display()))?;
+    hash_field(&mut hasher, &metadata.len().to_le_bytes());
+    hash_field(
+        &mut hasher,
+        &modified_nanos(metadata.modified().ok()).to_le_bytes(),
+    );
+
+    for arg in args {
+        hash_field(&mut hasher, arg.as_encoded_bytes());
+    }
+
+    hash_file(&mut hasher, project_dir, Path::new(MANIFEST_FILE))?;
+    hash_sources(&mut hasher, project_dir)?;
+    for dependency in dependencies {
+        hash_field(&mut hasher, dependency.name.as_bytes());
+        hash_sources(&mut hasher, &dependency.dir)?;
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Returns true if the build `name` in `out_dir` last succeeded with fingerprint `hash`
+/// and its artifacts still exist.
+#[must_use]
+pub fn is_fresh(out_dir: &Path, name: &str, hash: &str) -> bool {
+    read(out_dir, name).is_some_and(|fingerprint| {
+        fingerprint.hash == hash && fingerprint.outputs.iter().all(|output| output.is_file())
+    })
+}
+
+/// Records that the build `name` in `out_dir` succeeded with fingerprint `hash`,
+/// writing `outputs`.
+///
+/// # Errors
+///
+/// Returns an error if the fingerprint file cannot be written.
+pub fn record(out_dir: &Path, name: &str, hash: &str, outputs: Vec<PathBuf>) -> Result<()> {
+    let dir = out_dir.join(FINGERPRINT_DIR);
+    std::fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let fingerprint = Fingerprint {
+        hash: String::from(hash),
+        outputs,
+    };
+    let content =
+        serde_json::to_string_pretty(&fingerprint).context("Failed to serialize fingerprint")?;
+    let path = dir.join(format!("{name}.json"));
+    std::fs::write(&path, content)
+        .with_context(|| format!("Failed to write fingerprint: {}", path.display()))
+}
+
+/// Returns the artifacts recorded for the last successful build `name` in `out_dir`.
+#[must_use]
+pub fn outputs(out_dir: &Path, name: &str) -> Vec<PathBuf> {
+    read(out_dir, name)
+        .map(|fingerprint| fingerprint.outputs)
+        .unwrap_or_default()
+}
+
+/// Returns the modification time of every file directly in `out_dir`.
+///
+/// Taken before a build and passed to [`changed_files`] after it, this finds the
+/// artifacts of the build.
+#[must_use]
+pub fn snapshot(out_dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let Ok(entries) = std::fs::read_dir(out_dir) else {
+        return HashMap::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().ok()?;
+            metadata.is_file().then(|| (entry.path(), modified))
+        })
+        .collect()
+}
+
+/// Returns the files directly in `out_dir` that are new or modified since `before`,
+/// sorted by path.
+#[must_use]
+pub fn changed_files(out_dir: &Path, before: &HashMap<PathBuf, SystemTime>) -> Vec<PathBuf> {
+    let mut changed: Vec<PathBuf> = snapshot(out_dir)
+        .into_iter()
+        .filter(|(path, modified)| before.get(path) != Some(modified))
+        .map(|(path, _)| path)
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// Reads the fingerprint of the build `name` in `out_dir`, if there is a valid one.
+fn read(out_dir: &Path, name: &str) -> Option<Fingerprint> {
+    let path = out_dir.join(FINGERPRINT_DIR).join(format!("{name}.json"));
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Hashes the path and contents of every file under `src/` of `dir`, in sorted order.
+fn hash_sources(hasher: &mut Sha256, dir: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(&dir.join("src"), &mut files)?;
+    files.sort();
+    for file in files {
+        let relative = file.strip_prefix(dir).unwrap_or(&file).to_path_buf();
+        hash_file(hasher, dir, &relative)?;
+    }
+    Ok(())
+}
+
+/// Hashes the path `relative` and the contents of the file at `dir/relative`, or only
+/// the path if the file does not exist.
+fn hash_file(hasher: &mut Sha256, dir: &Path, relative: &Path) -> Result<()> {
+    hash_field(hasher, relative.as_os_str().as_encoded_bytes());
+    let path = dir.join(relative);
+    if path.is_file() {
+        let content =
+            std::fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        hash_field(hasher, &content);
+    }
+    Ok(())
+}
+
+/// Adds the files under `dir` to `files`, recursively; a missing directory adds none.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Hashes `bytes` prefixed with their length, so adjacent fields cannot run together.
+fn hash_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+/// Returns `time` in nanoseconds since the Unix epoch, or 0 if it is unknown.
+fn modified_nanos(time: Option<SystemTime>) -> u128 {
+    time.and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |duration| duration.as_nanos())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("infs_fingerprint_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_hash_sources_changes_with_contents() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("src/main.inf"), "fn main() {}\n").unwrap();
+        let hash = |dir: &Path| {
+            let mut hasher = Sha256::new();
+            hash_sources(&mut hasher, dir).unwrap();
+            hex::encode(hasher.finalize())
+        };
+
+        let first = hash(&dir);
+        assert_eq!(hash(&dir), first);
+        std::fs::write(dir.join("src/main.inf"), "fn main() { }\n").unwrap();
+        let second = hash(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_record_then_is_fresh() {
+        let dir = temp_dir();
+        let output = dir.join("demo.wasm");
+        std::fs::write(&output, b"\0asm").unwrap();
+        record(&dir, "demo", "abc", vec![output.clone()]).unwrap();
+
+        let fresh = is_fresh(&dir, "demo", "abc");
+        let other_hash = is_fresh(&dir, "demo", "def");
+        std::fs::remove_file(&output).unwrap();
+        let missing_output = is_fresh(&dir, "demo", "abc");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(fresh);
+        assert!(!other_hash);
+        assert!(!missing_output);
+    }
+
+    #[test]
+    fn test_changed_files_finds_new_files() {
+        let dir = temp_dir();
+        std::fs::write(dir.join("old.txt"), "old").unwrap();
+        let before = snapshot(&dir);
+        std::fs::write(dir.join("new.txt"), "new").unwrap();
+
+        let changed = changed_files(&dir, &before);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(changed, vec![dir.join("new.txt")]);
+    }
+}
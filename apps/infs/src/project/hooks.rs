@@ -0,0 +1,131 @@
+//! Build hooks of the `[hooks]` section of `Inference.toml`.
+//!
+//! ```toml
+//! [hooks]
+//! prebuild = "./scripts/generate-tables.sh"
+//! postbuild = "coqc -R $INFS_OUT_DIR demo $INFS_ROCQ"
+//! ```
+//!
+//! A hook is a command run by the shell (`sh -c`, or `cmd /C` on Windows) in the project
+//! directory:
+//!
+//! - `prebuild` runs before every project build, before its fingerprint is computed, so
+//!   the sources it generates are part of it
+//! - `postbuild` runs after infc compiled the project successfully, and not for a fresh
+//!   build; the build is only recorded as fresh once the hook succeeded too
+//!
+//! Both get the package and the output directory in environment variables, and
+//! `postbuild` the artifacts written by the build:
+//!
+//! | Variable | Value |
+//! |----------|-------|
+//! | `INFS_PACKAGE_NAME` | Name of the package |
+//! | `INFS_PACKAGE_VERSION` | Version of the package |
+//! | `INFS_PROJECT_DIR` | Project directory |
+//! | `INFS_OUT_DIR` | Output directory of the build |
+//! | `INFS_ARTIFACTS` | Artifacts, separated like the paths of `PATH` (`postbuild` only) |
+//! | `INFS_WASM` | The WebAssembly module, if one was written (`postbuild` only) |
+//! | `INFS_ROCQ` | The Rocq translation, if one was written (`postbuild` only) |
+//!
+//! The output of a hook goes to stderr, so stdout only carries the results of infs. A
+//! hook exiting with a non-zero code fails the build.
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::project::manifest::InferenceToml;
+
+/// Runs the `prebuild` hook of `manifest`, if any, for the project in `dir` building
+/// into `out_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the hook cannot be run or exits with a non-zero code.
+pub fn run_prebuild(manifest: &InferenceToml, dir: &Path, out_dir: &Path) -> Result<()> {
+    match &manifest.hooks.prebuild {
+        Some(hook) => run("prebuild", hook, hook_command(manifest, dir, out_dir)),
+        None => Ok(()),
+    }
+}
+
+/// Runs the `postbuild` hook of `manifest`, if any, with the `artifacts` the build of
+/// the project in `dir` wrote to `out_dir`.
+///
+/// # Errors
+///
+/// Returns an error if the hook cannot be run or exits with a non-zero code.
+pub fn run_postbuild(
+    manifest: &InferenceToml,
+    dir: &Path,
+    out_dir: &Path,
+    artifacts: &[PathBuf],
+) -> Result<()> {
+    let Some(hook) = &manifest.hooks.postbuild else {
+        return Ok(());
+    };
+    let artifacts: Vec<PathBuf> = artifacts
+        .iter()
+        .map(|artifact| artifact.canonicalize().unwrap_or_else(|_| artifact.clone()))
+        .collect();
+    let mut cmd = hook_command(manifest, dir, out_dir);
+    let joined = std::env::join_paths(&artifacts).context("Invalid artifact path")?;
+    cmd.env("INFS_ARTIFACTS", joined);
+    if let Some(wasm) = find_artifact(&artifacts, "wasm") {
+        cmd.env("INFS_WASM", wasm);
+    }
+    if let Some(rocq) = find_artifact(&artifacts, "v") {
+        cmd.env("INFS_ROCQ", rocq);
+    }
+    run("postbuild", hook, cmd)
+}
+
+/// Returns the shell command running a hook of `manifest` in `dir`, with the variables
+/// shared by all hooks.
+fn hook_command(manifest: &InferenceToml, dir: &Path, out_dir: &Path) -> Command {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let out_dir = out_dir
+        .canonicalize()
+        .unwrap_or_else(|_| out_dir.to_path_buf());
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    cmd.current_dir(&dir)
+        .env("INFS_PACKAGE_NAME", &manifest.package.name)
+        .env("INFS_PACKAGE_VERSION", &manifest.package.version)
+        .env("INFS_PROJECT_DIR", &dir)
+        .env("INFS_OUT_DIR", out_dir);
+    cmd
+}
+
+/// Runs the hook `name` with the shell `cmd`, its output going to stderr.
+fn run(name: &str, hook: &str, mut cmd: Command) -> Result<()> {
+    eprintln!("{:>12} {name} hook `{hook}`", "Running");
+    let status = cmd
+        .arg(hook)
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(std::io::stderr()))
+        .stderr(Stdio::inherit())
+        .status()
+        .with_context(|| format!("Failed to run the {name} hook `{hook}`"))?;
+    if !status.success() {
+        match status.code() {
+            Some(code) => bail!("The {name} hook `{hook}` failed with exit code {code}"),
+            None => bail!("The {name} hook `{hook}` was terminated by a signal"),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the first of `artifacts` with the extension `extension`.
+fn find_artifact<'a>(artifacts: &'a [PathBuf], extension: &str) -> Option<&'a PathBuf> {
+    artifacts
+        .iter()
+        .find(|artifact| artifact.extension().is_some_and(|ext| ext == extension))
+}
@@ -0,0 +1,209 @@
+//! Inference project lockfile.
+//!
+//! `Inference.lock` records where every dependency of a project was resolved from and,
+//! for git dependencies, the exact commit, so later builds and builds on other machines
+//! compile against the same code. It is written by `infs build` and meant to be
+//! committed with the project.
+//!
+//! ## Lockfile Format
+//!
+//! ```toml
+//! # This file is generated by infs. Do not edit it by hand.
+//! version = 1
+//!
+//! [[package]]
+//! name = "mathlib"
+//! source = "path+../mathlib"
+//!
+//! [[package]]
+//! name = "utils"
+//! source = "git+https://github.com/example/utils?tag=v0.1.0"
+//! rev = "4f8e2c1d9a7b3e5f6a8c0d2e4f6a8b0c2d4e6f8a"
+//! ```
+//!
+//! The source of a git dependency includes its `branch`, `tag` or `rev`, so changing
+//! them in `Inference.toml` resolves the dependency again.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File name of the project lockfile.
+pub const LOCK_FILE: &str = "Inference.lock";
+
+/// Version of the lockfile format written by this infs.
+const LOCK_VERSION: u32 = 1;
+
+/// Comment at the top of every written lockfile.
+const HEADER: &str = "# This file is generated by infs. Do not edit it by hand.\n";
+
+/// The root structure of `Inference.lock`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InferenceLock {
+    /// Version of the lockfile format.
+    pub version: u32,
+
+    /// Resolved dependencies, sorted by name.
+    #[serde(default, rename = "package", skip_serializing_if = "Vec::is_empty")]
+    pub packages: Vec<LockedPackage>,
+}
+
+/// A resolved dependency in the lockfile.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LockedPackage {
+    /// The dependency name, as in `[dependencies]`.
+    pub name: String,
+
+    /// Where the dependency comes from: `path+<path>` or `git+<url>`, followed by
+    /// `?branch=`, `?tag=` or `?rev=` if the manifest selects a revision.
+    pub source: String,
+
+    /// Commit a git dependency resolved to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+}
+
+impl InferenceLock {
+    /// Creates a lockfile of `packages`, sorted by name.
+    #[must_use]
+    pub fn new(mut packages: Vec<LockedPackage>) -> Self {
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            version: LOCK_VERSION,
+            packages,
+        }
+    }
+
+    /// Returns the locked package `name` if it was resolved from `source`.
+    #[must_use]
+    pub fn find(&self, name: &str, source: &str) -> Option<&LockedPackage> {
+        self.packages
+            .iter()
+            .find(|package| package.name == name && package.source == source)
+    }
+
+    /// Serializes the lockfile to TOML format, after the generated-file header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_toml(&self) -> Result<String> {
+        let content = toml::to_string_pretty(self).context("Failed to serialize Inference.lock")?;
+        Ok(format!("{HEADER}{content}"))
+    }
+
+    /// Reads and parses the lockfile at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a valid lockfile, or was
+    /// written in a newer format.
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lockfile: {}", path.display()))?;
+        let lock: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse lockfile: {}", path.display()))?;
+        if lock.version > LOCK_VERSION {
+            bail!(
+                "{} has format version {}, but this infs supports up to {LOCK_VERSION}; \
+                 update infs",
+                path.display(),
+                lock.version
+            );
+        }
+        Ok(lock)
+    }
+
+    /// Writes the lockfile to a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or file writing fails.
+    pub fn write_to_file(&self, path: &Path) -> Result<()> {
+        let content = self.to_toml()?;
+        std::fs::write(path, content)
+            .with_context(|| format!("Failed to write lockfile: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, source: &str, rev: Option<&str>) -> LockedPackage {
+        LockedPackage {
+            name: String::from(name),
+            source: String::from(source),
+            rev: rev.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_new_sorts_packages() {
+        let lock = InferenceLock::new(vec![
+            package("utils", "path+../utils", None),
+            package("mathlib", "path+../mathlib", None),
+        ]);
+        assert_eq!(lock.packages[0].name, "mathlib");
+        assert_eq!(lock.packages[1].name, "utils");
+    }
+
+    #[test]
+    fn test_to_toml() {
+        let lock = InferenceLock::new(vec![package(
+            "utils",
+            "git+https://example.com/utils.git",
+            Some("abc123"),
+        )]);
+        let output = lock.to_toml().unwrap();
+        assert!(output.starts_with(HEADER));
+        assert!(output.contains("version = 1"));
+        assert!(output.contains("[[package]]"));
+        assert!(output.contains("rev = \"abc123\""));
+    }
+
+    #[test]
+    fn test_read_from_file_round_trips() {
+        let path = std::env::temp_dir().join(format!("infs_lock_{}.lock", rand::random::<u64>()));
+        let lock = InferenceLock::new(vec![
+            package("mathlib", "path+../mathlib", None),
+            package("utils", "git+https://example.com/utils.git", Some("abc123")),
+        ]);
+        lock.write_to_file(&path).unwrap();
+
+        let read = InferenceLock::read_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), lock);
+    }
+
+    #[test]
+    fn test_read_from_file_rejects_newer_version() {
+        let path = std::env::temp_dir().join(format!("infs_lock_{}.lock", rand::random::<u64>()));
+        std::fs::write(&path, "version = 2\n").unwrap();
+
+        let read = InferenceLock::read_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(read.unwrap_err().to_string().contains("update infs"));
+    }
+
+    #[test]
+    fn test_find_matches_name_and_source() {
+        let lock = InferenceLock::new(vec![package(
+            "utils",
+            "git+https://example.com/utils.git?tag=v1",
+            Some("abc123"),
+        )]);
+        assert!(
+            lock.find("utils", "git+https://example.com/utils.git?tag=v1")
+                .is_some()
+        );
+        assert!(
+            lock.find("utils", "git+https://example.com/utils.git?tag=v2")
+                .is_none()
+        );
+        assert!(
+            lock.find("other", "git+https://example.com/utils.git?tag=v1")
+                .is_none()
+        );
+    }
+}
@@ -14,14 +14,23 @@
 //! infc_version = "0.1.0"
 //!
 //! [dependencies]
-//! # Future: package dependencies
+//! mathlib = { path = "../mathlib" }
+//! utils = { git = "https://github.com/example/utils", tag = "v0.1.0" }
 //!
 //! [build]
 //! target = "wasm32"
 //! optimize = "release"
 //!
+//! [profile.release]
+//! opt-level = 2
+//! bounds-checks = "unchecked"
+//!
 //! [verification]
 //! output-dir = "proofs/"
+//!
+//! [hooks]
+//! prebuild = "./scripts/generate.sh"
+//! postbuild = "wasm-opt -Oz $INFS_WASM -o $INFS_WASM"
 //! ```
 //!
 //! ## Reserved Names
@@ -32,9 +41,12 @@
 use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// File name of the project manifest.
+pub const MANIFEST_FILE: &str = "Inference.toml";
+
 /// Reserved words that cannot be used as project names.
 ///
 /// Includes Inference language keywords and problematic directory names.
@@ -95,9 +107,17 @@ pub struct InferenceToml {
     #[serde(default, skip_serializing_if = "BuildConfig::is_default")]
     pub build: BuildConfig,
 
+    /// Build profiles.
+    #[serde(default, skip_serializing_if = "Profiles::is_default")]
+    pub profile: Profiles,
+
     /// Verification configuration for Rocq output.
     #[serde(default, skip_serializing_if = "VerificationConfig::is_default")]
     pub verification: VerificationConfig,
+
+    /// Commands run before and after a build.
+    #[serde(default, skip_serializing_if = "HooksConfig::is_empty")]
+    pub hooks: HooksConfig,
 }
 
 /// Package metadata in the manifest.
@@ -128,12 +148,53 @@ pub struct Package {
 
 /// Project dependencies section.
 ///
-/// Currently a placeholder for future package management support.
+/// Dependencies are resolved by [`resolve_dependencies`] when the project is built.
+///
+/// [`resolve_dependencies`]: crate::project::dependencies::resolve_dependencies
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Dependencies {
-    /// Map of dependency name to version specification.
+    /// Map of dependency name to dependency specification.
     #[serde(flatten)]
-    pub packages: HashMap<String, String>,
+    pub packages: HashMap<String, Dependency>,
+}
+
+/// A dependency specification in the `[dependencies]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Dependency {
+    /// A version requirement, `name = "0.1"`, reserved for a future package registry.
+    Version(String),
+    /// A dependency with a source, `name = { path = "../lib" }` or
+    /// `name = { git = "https://...", rev = "..." }`.
+    Detailed(DetailedDependency),
+}
+
+/// A dependency given as a table.
+///
+/// Exactly one of `path` and `git` must be set; `branch`, `tag` and `rev` select the
+/// revision of a git dependency, at most one of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DetailedDependency {
+    /// Directory of the dependency, relative to the project directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// URL of the git repository of the dependency.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<String>,
+
+    /// Branch of the git repository to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+
+    /// Tag of the git repository to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Commit of the git repository to use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
 }
 
 impl Dependencies {
@@ -173,6 +234,101 @@ impl BuildConfig {
     }
 }
 
+/// Build profiles section, `[profile.dev]` and `[profile.release]`.
+///
+/// A build uses the `release` profile with `infs build --release` or
+/// `[build] optimize = "release"`, and the `dev` profile otherwise.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Profiles {
+    /// Settings of the `dev` profile.
+    #[serde(default, skip_serializing_if = "Profile::is_empty")]
+    pub dev: Profile,
+
+    /// Settings of the `release` profile.
+    #[serde(default, skip_serializing_if = "Profile::is_empty")]
+    pub release: Profile,
+}
+
+/// Codegen settings of a build profile.
+///
+/// Unset settings take the value of the built-in profile, see [`Profiles::select`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+    /// LLVM optimization level (0-3).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opt_level: Option<u32>,
+
+    /// Whether to keep debug information in the WebAssembly binary.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug_info: Option<bool>,
+
+    /// How array indices are checked (`trap` or `unchecked`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bounds_checks: Option<String>,
+
+    /// How a failing `assert` stops execution, as for `infs build --assert`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assert: Option<String>,
+}
+
+impl Profiles {
+    /// Returns true if no profile setting is given.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self.dev.is_empty() && self.release.is_empty()
+    }
+
+    /// Returns the settings of the `release` or `dev` profile, completed with the
+    /// built-in profile.
+    ///
+    /// The built-in `dev` profile compiles with `opt-level = 0` and the built-in
+    /// `release` profile with `opt-level = 3`. Both leave `debug-info`, `bounds-checks`
+    /// and `assert` to the compiler defaults: debug information is only supported for
+    /// single-file modules, so it cannot be on by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the profile has an `opt-level` above 3 or an unknown
+    /// `bounds-checks` value.
+    pub fn select(&self, release: bool) -> Result<Profile> {
+        let (name, profile, built_in_opt_level) = if release {
+            ("release", &self.release, 3)
+        } else {
+            ("dev", &self.dev, 0)
+        };
+        if let Some(opt_level) = profile.opt_level
+            && opt_level > 3
+        {
+            bail!("[profile.{name}] opt-level must be between 0 and 3, found {opt_level}");
+        }
+        if let Some(bounds_checks) = &profile.bounds_checks
+            && bounds_checks != "trap"
+            && bounds_checks != "unchecked"
+        {
+            bail!(
+                "[profile.{name}] bounds-checks must be \"trap\" or \"unchecked\", \
+                 found \"{bounds_checks}\""
+            );
+        }
+        Ok(Profile {
+            opt_level: Some(profile.opt_level.unwrap_or(built_in_opt_level)),
+            debug_info: profile.debug_info,
+            bounds_checks: profile.bounds_checks.clone(),
+            assert: profile.assert.clone(),
+        })
+    }
+}
+
+impl Profile {
+    /// Returns true if no setting is given.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Verification configuration for Rocq output.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VerificationConfig {
@@ -197,6 +353,27 @@ impl VerificationConfig {
     }
 }
 
+/// Build hooks, see [`crate::project::hooks`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Shell command run before a build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prebuild: Option<String>,
+
+    /// Shell command run after a successful build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postbuild: Option<String>,
+}
+
+impl HooksConfig {
+    /// Returns true if no hook is set.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Gets the infc version to use for new projects.
 ///
 /// Tries to detect the installed infc version first by running `infc --version`.
@@ -284,7 +461,9 @@ impl InferenceToml {
             },
             dependencies: Dependencies::default(),
             build: BuildConfig::default(),
+            profile: Profiles::default(),
             verification: VerificationConfig::default(),
+            hooks: HooksConfig::default(),
         }
     }
 
@@ -297,6 +476,18 @@ impl InferenceToml {
         toml::to_string_pretty(self).context("Failed to serialize Inference.toml")
     }
 
+    /// Reads and parses the manifest at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid manifest.
+    pub fn read_from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse manifest: {}", path.display()))
+    }
+
     /// Writes the manifest to a file.
     ///
     /// # Errors
@@ -309,6 +500,21 @@ impl InferenceToml {
     }
 }
 
+/// Returns the project directory if `path` names a project: a directory, or its
+/// `Inference.toml`.
+///
+/// The directory is not checked for a manifest.
+#[must_use]
+pub fn project_dir(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        Some(path.to_path_buf())
+    } else if path.file_name().is_some_and(|name| name == MANIFEST_FILE) {
+        Some(path.parent().unwrap_or(Path::new(".")).to_path_buf())
+    } else {
+        None
+    }
+}
+
 /// Validates a project name for use in Inference projects.
 ///
 /// # Rules
@@ -380,17 +586,82 @@ mod tests {
         assert!(output.contains("infc_version = \""));
     }
 
+    #[test]
+    fn test_read_from_file_round_trips() {
+        let path =
+            std::env::temp_dir().join(format!("infs_manifest_{}.toml", rand::random::<u64>()));
+        let mut manifest = InferenceToml::new("myproject");
+        manifest.verification.output_dir = String::from("rocq/");
+        manifest.write_to_file(&path).unwrap();
+
+        let read = InferenceToml::read_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(read.unwrap(), manifest);
+    }
+
     #[test]
     fn test_dependencies_is_empty() {
         let deps = Dependencies::default();
         assert!(deps.is_empty());
 
         let mut deps = Dependencies::default();
-        deps.packages
-            .insert(String::from("std"), String::from("0.1"));
+        deps.packages.insert(
+            String::from("std"),
+            Dependency::Version(String::from("0.1")),
+        );
         assert!(!deps.is_empty());
     }
 
+    #[test]
+    fn test_parse_dependencies() {
+        let manifest: InferenceToml = toml::from_str(
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [dependencies]
+            std = "0.1"
+            mathlib = { path = "../mathlib" }
+            utils = { git = "https://example.com/utils.git", tag = "v1" }
+            "#,
+        )
+        .unwrap();
+
+        let packages = &manifest.dependencies.packages;
+        assert_eq!(packages["std"], Dependency::Version(String::from("0.1")));
+        assert_eq!(
+            packages["mathlib"],
+            Dependency::Detailed(DetailedDependency {
+                path: Some(String::from("../mathlib")),
+                ..DetailedDependency::default()
+            })
+        );
+        assert_eq!(
+            packages["utils"],
+            Dependency::Detailed(DetailedDependency {
+                git: Some(String::from("https://example.com/utils.git")),
+                tag: Some(String::from("v1")),
+                ..DetailedDependency::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_dependency_rejects_unknown_keys() {
+        let result = toml::from_str::<InferenceToml>(
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [dependencies]
+            mathlib = { directory = "../mathlib" }
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_build_config_is_default() {
         let config = BuildConfig::default();
@@ -403,6 +674,97 @@ mod tests {
         assert!(!config.is_default());
     }
 
+    #[test]
+    fn test_parse_profiles() {
+        let manifest: InferenceToml = toml::from_str(
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [profile.release]
+            opt-level = 2
+            bounds-checks = "unchecked"
+            assert = "result-code"
+            "#,
+        )
+        .unwrap();
+
+        assert!(manifest.profile.dev.is_empty());
+        assert_eq!(
+            manifest.profile.release,
+            Profile {
+                opt_level: Some(2),
+                debug_info: None,
+                bounds_checks: Some(String::from("unchecked")),
+                assert: Some(String::from("result-code")),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_profiles_rejects_unknown_profile() {
+        let result = toml::from_str::<InferenceToml>(
+            r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+
+            [profile.bench]
+            opt-level = 3
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_select_profile_fills_in_built_in_settings() {
+        let profiles = Profiles {
+            dev: Profile::default(),
+            release: Profile {
+                debug_info: Some(true),
+                ..Profile::default()
+            },
+        };
+
+        let dev = profiles.select(false).unwrap();
+        assert_eq!(dev.opt_level, Some(0));
+        assert_eq!(dev.debug_info, None);
+        assert_eq!(dev.bounds_checks, None);
+
+        let release = profiles.select(true).unwrap();
+        assert_eq!(release.opt_level, Some(3));
+        assert_eq!(release.debug_info, Some(true));
+    }
+
+    #[test]
+    fn test_select_profile_rejects_invalid_settings() {
+        let profiles = Profiles {
+            dev: Profile {
+                opt_level: Some(4),
+                ..Profile::default()
+            },
+            release: Profile {
+                bounds_checks: Some(String::from("wrap")),
+                ..Profile::default()
+            },
+        };
+        assert!(
+            profiles
+                .select(false)
+                .unwrap_err()
+                .to_string()
+                .contains("[profile.dev] opt-level")
+        );
+        assert!(
+            profiles
+                .select(true)
+                .unwrap_err()
+                .to_string()
+                .contains("[profile.release] bounds-checks")
+        );
+    }
+
     #[test]
     fn test_verification_config_is_default() {
         let config = VerificationConfig::default();
@@ -414,6 +776,22 @@ mod tests {
         assert!(!config.is_default());
     }
 
+    #[test]
+    fn test_parse_hooks() {
+        let manifest: InferenceToml = toml::from_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n\
+             [hooks]\npostbuild = \"echo $INFS_WASM\"\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.hooks.prebuild, None);
+        assert_eq!(manifest.hooks.postbuild.as_deref(), Some("echo $INFS_WASM"));
+
+        let result = toml::from_str::<InferenceToml>(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[hooks]\nprecheck = \"true\"\n",
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_validate_project_name_valid() {
         assert!(validate_project_name("myproject").is_ok());
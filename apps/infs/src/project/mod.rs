@@ -5,16 +5,26 @@
 //!
 //! ## Modules
 //!
+//! - [`dependencies`] - Resolution of path and git dependencies
+//! - [`fingerprint`] - Build fingerprints for skipping unchanged builds
+//! - [`hooks`] - Pre- and post-build hooks
+//! - [`lockfile`] - Inference.lock reading and writing
 //! - [`manifest`] - Inference.toml parsing and validation
 //! - [`scaffold`] - Project creation and initialization
+//! - [`workspace`] - Workspaces of several packages
 //!
 //! ## Key Types
 //!
 //! - [`InferenceToml`] - The manifest file structure
 //! - [`ProjectConfig`] - Loaded and validated project configuration
 
+pub mod dependencies;
+pub mod fingerprint;
+pub mod hooks;
+pub mod lockfile;
 pub mod manifest;
 pub mod scaffold;
+pub mod workspace;
 
 #[allow(unused_imports)]
 pub use manifest::validate_project_name;
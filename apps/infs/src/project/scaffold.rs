@@ -13,7 +13,9 @@
 //! Use [`init_project`] to initialize the current directory as an
 //! Inference project without creating a new directory.
 
-use crate::project::manifest::{InferenceToml, detect_infc_version, validate_project_name};
+use crate::project::manifest::{
+    InferenceToml, MANIFEST_FILE, detect_infc_version, validate_project_name,
+};
 use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -125,7 +127,7 @@ pub fn init_project(path: Option<&Path>, name: Option<&str>, create_src: bool) -
 
     validate_project_name(&project_name)?;
 
-    let manifest_path = project_path.join("Inference.toml");
+    let manifest_path = project_path.join(MANIFEST_FILE);
     if manifest_path.exists() {
         bail!(
             "Inference.toml already exists in '{}'. This directory is already an Inference project.",
@@ -183,7 +185,7 @@ fn write_git_files_if_missing(project_path: &Path) -> Result<()> {
 
 /// Writes core project files to the project directory.
 fn write_project_files(project_path: &Path, project_name: &str) -> Result<()> {
-    let manifest_path = project_path.join("Inference.toml");
+    let manifest_path = project_path.join(MANIFEST_FILE);
     std::fs::write(&manifest_path, manifest_content(project_name)).with_context(|| {
         format!(
             "Failed to write Inference.toml: {}",
@@ -243,13 +245,17 @@ infc_version = "{infc_version}"
 # license = "MIT"
 
 # [dependencies]
-# Future: package dependencies
-# std = "0.1"
+# mathlib = {{ path = "../mathlib" }}
+# utils = {{ git = "https://github.com/example/utils", tag = "v0.1.0" }}
 
 # [build]
 # target = "wasm32"
 # optimize = "release"
 
+# [profile.release]
+# opt-level = 3
+# bounds-checks = "trap"
+
 # [verification]
 # output-dir = "proofs/"
 "#
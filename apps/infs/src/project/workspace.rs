@@ -0,0 +1,283 @@
+//! Workspaces of several packages.
+//!
+//! A `[workspace]` table in an `Inference.toml` makes its directory the root of a
+//! workspace:
+//!
+//! ```toml
+//! [workspace]
+//! members = ["core", "libs/*"]
+//! ```
+//!
+//! Every member is a directory with its own `Inference.toml`, relative to the root;
+//! `libs/*` names every subdirectory of `libs` that has one. If the root manifest has a
+//! `[package]` as well, the root is a member too; otherwise it is a virtual manifest
+//! that only lists the members.
+//!
+//! `infs build`, `check` and `verify` run on every member when given the workspace
+//! root, and on one member when given its directory. Either way the members share:
+//!
+//! - `target/` in the root: each member builds into `target/<name>/`, and git
+//!   dependencies are cloned once into `target/deps/`
+//! - `Inference.lock` in the root, resolved for all members together, see
+//!   [`resolve_workspace_dependencies`]
+//!
+//! [`resolve_workspace_dependencies`]: crate::project::dependencies::resolve_workspace_dependencies
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE};
+
+/// The `[workspace]` table of a manifest.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceConfig {
+    /// Member directories relative to the root, or `dir/*` patterns.
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// The parts of a root manifest that make it a workspace.
+#[derive(Deserialize)]
+struct RootManifest {
+    workspace: Option<WorkspaceConfig>,
+    package: Option<toml::Value>,
+}
+
+/// A workspace and its members.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    /// The canonical root directory.
+    pub root: PathBuf,
+    /// The members, in the order of `members`, after the root package if there is one.
+    pub members: Vec<Member>,
+}
+
+/// A package of a workspace.
+#[derive(Debug, Clone)]
+pub struct Member {
+    /// The canonical directory of the member.
+    pub dir: PathBuf,
+    pub manifest: InferenceToml,
+}
+
+impl Workspace {
+    /// Returns the workspace `project_dir` is the root or a member of.
+    ///
+    /// The nearest manifest with a `[workspace]` table, in `project_dir` or one of its
+    /// parents, decides: a project that is not one of its members belongs to no
+    /// workspace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the workspace manifest of `project_dir` cannot be parsed, or
+    /// a member of the workspace is invalid.
+    pub fn find(project_dir: &Path) -> Result<Option<Self>> {
+        let start = if project_dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            project_dir
+        };
+        let start = start
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve {}", start.display()))?;
+        for dir in start.ancestors() {
+            let config = if dir == start {
+                read_config(dir)?
+            } else {
+                // Manifests above the project only matter if they are workspaces.
+                read_config(dir).ok().flatten()
+            };
+            if let Some((config, has_package)) = config {
+                let workspace = Self::load(dir, &config, has_package)?;
+                let includes = dir == start || workspace.members.iter().any(|m| m.dir == start);
+                return Ok(includes.then_some(workspace));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns true if a command given `project_dir` runs on `member`: every member
+    /// for the root, and only itself for a member.
+    #[must_use]
+    pub fn selects(&self, project_dir: &Path, member: &Member) -> bool {
+        let project_dir = if project_dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            project_dir
+        };
+        let project_dir = project_dir
+            .canonicalize()
+            .unwrap_or_else(|_| project_dir.to_path_buf());
+        project_dir == self.root || project_dir == member.dir
+    }
+
+    /// Returns the directory `member` builds into.
+    #[must_use]
+    pub fn target_dir(&self, member: &Member) -> PathBuf {
+        self.root.join("target").join(&member.manifest.package.name)
+    }
+
+    fn load(root: &Path, config: &WorkspaceConfig, has_package: bool) -> Result<Self> {
+        let mut dirs = Vec::new();
+        if has_package {
+            dirs.push(root.to_path_buf());
+        }
+        for pattern in &config.members {
+            if let Some(parent) = pattern.strip_suffix("/*") {
+                dirs.extend(glob_members(&root.join(parent))?);
+            } else {
+                let dir = root.join(pattern);
+                if !dir.join(MANIFEST_FILE).is_file() {
+                    bail!(
+                        "Workspace member `{pattern}` has no {MANIFEST_FILE}: {}",
+                        dir.display()
+                    );
+                }
+                dirs.push(dir);
+            }
+        }
+
+        let mut members: Vec<Member> = Vec::new();
+        for dir in dirs {
+            let dir = dir
+                .canonicalize()
+                .with_context(|| format!("Failed to resolve {}", dir.display()))?;
+            if members.iter().any(|member| member.dir == dir) {
+                continue;
+            }
+            let manifest = InferenceToml::read_from_file(&dir.join(MANIFEST_FILE))?;
+            if let Some(other) = members
+                .iter()
+                .find(|member| member.manifest.package.name == manifest.package.name)
+            {
+                bail!(
+                    "Workspace members {} and {} are both named `{}`",
+                    other.dir.display(),
+                    dir.display(),
+                    manifest.package.name
+                );
+            }
+            members.push(Member { dir, manifest });
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+            members,
+        })
+    }
+}
+
+/// Reads the `[workspace]` table of the manifest in `dir`, and whether the manifest has
+/// a `[package]` too.
+fn read_config(dir: &Path) -> Result<Option<(WorkspaceConfig, bool)>> {
+    let path = dir.join(MANIFEST_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read manifest: {}", path.display()))?;
+    let manifest: RootManifest = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse manifest: {}", path.display()))?;
+    Ok(manifest
+        .workspace
+        .map(|config| (config, manifest.package.is_some())))
+}
+
+/// Returns the subdirectories of `parent` that have a manifest, sorted.
+fn glob_members(parent: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(parent)
+        .with_context(|| format!("Failed to read {}", parent.display()))?;
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.join(MANIFEST_FILE).is_file() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn package(name: &str) -> String {
+        format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n")
+    }
+
+    #[test]
+    fn finds_members_from_the_root_and_from_a_member() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let root = temp.path();
+        write(
+            &root.join(MANIFEST_FILE),
+            "[workspace]\nmembers = [\"core\", \"libs/*\"]\n",
+        );
+        write(&root.join("core").join(MANIFEST_FILE), &package("core"));
+        write(&root.join("libs/b").join(MANIFEST_FILE), &package("b"));
+        write(&root.join("libs/a").join(MANIFEST_FILE), &package("a"));
+        std::fs::create_dir_all(root.join("libs/notes")).unwrap();
+
+        let workspace = Workspace::find(root).unwrap().unwrap();
+        let names: Vec<&str> = workspace
+            .members
+            .iter()
+            .map(|member| member.manifest.package.name.as_str())
+            .collect();
+        assert_eq!(names, ["core", "a", "b"]);
+        assert!(
+            workspace
+                .members
+                .iter()
+                .all(|member| workspace.selects(root, member))
+        );
+
+        let member_dir = root.join("libs/a");
+        let workspace = Workspace::find(&member_dir).unwrap().unwrap();
+        let selected: Vec<&str> = workspace
+            .members
+            .iter()
+            .filter(|member| workspace.selects(&member_dir, member))
+            .map(|member| member.manifest.package.name.as_str())
+            .collect();
+        assert_eq!(selected, ["a"]);
+        assert_eq!(
+            workspace.target_dir(&workspace.members[1]),
+            root.canonicalize().unwrap().join("target").join("a")
+        );
+    }
+
+    #[test]
+    fn a_project_outside_the_members_has_no_workspace() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        write(
+            &temp.path().join(MANIFEST_FILE),
+            "[workspace]\nmembers = []\n",
+        );
+        let project = temp.path().join("other");
+        write(&project.join(MANIFEST_FILE), &package("other"));
+
+        assert!(Workspace::find(&project).unwrap().is_none());
+    }
+
+    #[test]
+    fn rejects_members_with_the_same_name() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        write(
+            &temp.path().join(MANIFEST_FILE),
+            "[workspace]\nmembers = [\"a\", \"b\"]\n",
+        );
+        write(&temp.path().join("a").join(MANIFEST_FILE), &package("lib"));
+        write(&temp.path().join("b").join(MANIFEST_FILE), &package("lib"));
+
+        let err = Workspace::find(temp.path()).unwrap_err().to_string();
+        assert!(err.contains("both named `lib`"));
+    }
+}
@@ -0,0 +1,251 @@
+//! Reader of the source map custom section.
+//!
+//! `infc --source-map` adds a custom section named `inference.sourcemap` to the
+//! generated WebAssembly module, relating code offsets to lines and columns of the
+//! `.inf` source. `infs run` uses it to annotate the backtrace wasmtime prints when
+//! the program traps with the source position of every frame.
+//!
+//! The section encoding is defined by the `source_map` module of the code generator,
+//! which infs does not link against. It uses unsigned LEB128 integers and
+//! length-prefixed strings:
+//!
+//! ```text
+//! section ::= version:u32 file:string entries:vec(entry)
+//! entry   ::= offset_delta:u32 line:u32 column:u32
+//! ```
+
+use std::fmt::Write;
+
+use anyhow::{Context, Result, bail};
+
+/// Name of the source map custom section.
+const SECTION_NAME: &str = "inference.sourcemap";
+
+/// Version of the section encoding understood by this reader.
+const FORMAT_VERSION: u32 = 1;
+
+/// Source map of a WebAssembly module.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SourceMap {
+    /// Path of the source file.
+    file: String,
+    /// Module offsets with their 1-based line and column, sorted by offset. Line `0`
+    /// marks code without a source position and column `0` an unknown column.
+    entries: Vec<(u32, u32, u32)>,
+}
+
+impl SourceMap {
+    /// Reads the source map of a WebAssembly module.
+    ///
+    /// Returns `None` if the module has no source map section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module is malformed or the section cannot be decoded.
+    pub(crate) fn from_module(wasm: &[u8]) -> Result<Option<Self>> {
+        if wasm.get(..4) != Some(b"\0asm".as_slice()) {
+            bail!("Not a WebAssembly module");
+        }
+        let mut reader = Reader::new(wasm.get(8..).unwrap_or_default());
+        while !reader.is_empty() {
+            let id = reader.byte()?;
+            let size = reader.u32()?;
+            let mut section = Reader::new(reader.bytes(size as usize)?);
+            if id == 0 && section.string()? == SECTION_NAME {
+                return Self::decode(section).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decodes the contents of the section.
+    fn decode(mut reader: Reader) -> Result<Self> {
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            bail!("Unsupported source map version {version}");
+        }
+        let file = reader.string()?.to_string();
+        let mut entries = Vec::new();
+        let mut offset = 0u32;
+        for _ in 0..reader.u32()? {
+            offset = offset
+                .checked_add(reader.u32()?)
+                .context("Source map offset out of range")?;
+            entries.push((offset, reader.u32()?, reader.u32()?));
+        }
+        Ok(Self { file, entries })
+    }
+
+    /// Returns the source location of the instruction at module offset `offset` as
+    /// `file:line:column`, or `None` if the offset has no source position.
+    pub(crate) fn lookup(&self, offset: u32) -> Option<String> {
+        let index = self
+            .entries
+            .partition_point(|(start, _, _)| *start <= offset);
+        match self.entries[..index].last()? {
+            (_, 0, _) => None,
+            (_, line, 0) => Some(format!("{}:{line}", self.file)),
+            (_, line, column) => Some(format!("{}:{line}:{column}", self.file)),
+        }
+    }
+
+    /// Adds the source location below every frame of the wasmtime backtraces in
+    /// `stderr` whose offset is covered by the map.
+    ///
+    /// Frames are lines such as `0:   0x1a5 - <unknown>!main`. Frames of other modules,
+    /// such as preloaded host modules, cannot be told apart from the frames of this
+    /// module, so their locations are only meaningful for this module's frames.
+    pub(crate) fn annotate_backtrace(&self, stderr: &str) -> String {
+        let mut annotated = String::with_capacity(stderr.len());
+        for line in stderr.lines() {
+            annotated.push_str(line);
+            annotated.push('\n');
+            if let Some(location) = frame_offset(line).and_then(|offset| self.lookup(offset)) {
+                let indent = line.len() - line.trim_start().len();
+                let _ = writeln!(annotated, "{:indent$}      at {location}", "");
+            }
+        }
+        annotated
+    }
+}
+
+/// Returns the module offset of a wasmtime backtrace frame line.
+fn frame_offset(line: &str) -> Option<u32> {
+    let (index, rest) = line.trim_start().split_once(':')?;
+    if index.is_empty() || !index.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let (offset, _) = rest.trim_start().split_once(" - ")?;
+    u32::from_str_radix(offset.strip_prefix("0x")?, 16).ok()
+}
+
+/// Cursor over the bytes of a WebAssembly module.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        if len > self.data.len() {
+            bail!("Unexpected end of WebAssembly module");
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    /// Reads an unsigned LEB128 integer.
+    fn u32(&mut self) -> Result<u32> {
+        let mut value = 0u32;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift == 28 && byte > 0x0f {
+                bail!("Integer too large");
+            }
+            value |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.bytes(len)?).context("Invalid UTF-8 string")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leb(mut value: u32, out: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    /// Builds a module with a `name` custom section followed by a source map.
+    fn module(entries: &[(u32, u32, u32)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        leb(FORMAT_VERSION, &mut data);
+        leb(8, &mut data);
+        data.extend_from_slice(b"main.inf");
+        leb(u32::try_from(entries.len()).unwrap(), &mut data);
+        let mut previous = 0;
+        for &(offset, line, column) in entries {
+            leb(offset - previous, &mut data);
+            leb(line, &mut data);
+            leb(column, &mut data);
+            previous = offset;
+        }
+
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend_from_slice(&[0, 5, 4, b'n', b'a', b'm', b'e']);
+        let mut section = Vec::new();
+        leb(u32::try_from(SECTION_NAME.len()).unwrap(), &mut section);
+        section.extend_from_slice(SECTION_NAME.as_bytes());
+        section.extend_from_slice(&data);
+        wasm.push(0);
+        leb(u32::try_from(section.len()).unwrap(), &mut wasm);
+        wasm.extend_from_slice(&section);
+        wasm
+    }
+
+    #[test]
+    fn lookup_uses_the_enclosing_entry() {
+        let source_map = SourceMap::from_module(&module(&[(40, 3, 5), (200, 0, 0), (300, 9, 0)]))
+            .unwrap()
+            .unwrap();
+        assert_eq!(source_map.lookup(39), None);
+        assert_eq!(source_map.lookup(40).as_deref(), Some("main.inf:3:5"));
+        assert_eq!(source_map.lookup(199).as_deref(), Some("main.inf:3:5"));
+        assert_eq!(source_map.lookup(250), None);
+        assert_eq!(source_map.lookup(301).as_deref(), Some("main.inf:9"));
+    }
+
+    #[test]
+    fn modules_without_a_source_map_have_none() {
+        assert_eq!(SourceMap::from_module(b"\0asm\x01\0\0\0").unwrap(), None);
+        assert!(SourceMap::from_module(b"not wasm").is_err());
+    }
+
+    #[test]
+    fn backtrace_frames_are_annotated() {
+        let source_map = SourceMap::from_module(&module(&[(0x1a0, 4, 9)]))
+            .unwrap()
+            .unwrap();
+        let stderr = "Error: failed to run main module\n    \
+            1: error while executing at wasm backtrace:\n           \
+            0:  0x1a5 - <unknown>!positive\n           \
+            1:   0x10 - <unknown>!main\n";
+        assert_eq!(
+            source_map.annotate_backtrace(stderr),
+            "Error: failed to run main module\n    \
+            1: error while executing at wasm backtrace:\n           \
+            0:  0x1a5 - <unknown>!positive\n                 \
+            at main.inf:4:9\n           \
+            1:   0x10 - <unknown>!main\n"
+        );
+    }
+}
@@ -5,9 +5,15 @@
 
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
+use std::cell::Cell;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use tar::Archive;
 
+/// Receives the path of every entry as it is extracted, relative to the destination
+/// directory, with the bytes of the archive read so far and its size.
+pub type ExtractProgress<'a> = dyn FnMut(&Path, u64, u64) + 'a;
+
 /// Extracts a ZIP archive to the destination directory.
 ///
 /// Creates the destination directory if it does not exist.
@@ -29,6 +35,16 @@ use tar::Archive;
 /// extract_zip(Path::new("archive.zip"), Path::new("output_dir"))?;
 /// ```
 pub fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+    extract_zip_with_progress(archive_path, dest_dir, &mut |_, _, _| {})
+}
+
+/// Extracts a ZIP archive like [`extract_zip`], reporting every entry to `report`.
+fn extract_zip_with_progress(
+    archive_path: &Path,
+    dest_dir: &Path,
+    report: &mut ExtractProgress<'_>,
+) -> Result<()> {
+    let total = std::fs::metadata(archive_path).map_or(0, |metadata| metadata.len());
     let file = std::fs::File::open(archive_path)
         .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
 
@@ -39,6 +55,7 @@ pub fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
         .with_context(|| format!("Failed to create directory: {}", dest_dir.display()))?;
 
     let strip_prefix = find_common_root_folder(&mut archive);
+    let mut read = 0;
 
     for i in 0..archive.len() {
         let mut entry = archive
@@ -72,6 +89,8 @@ pub fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
             entry_path.clone()
         };
 
+        read += entry.compressed_size();
+        report(&relative_path, read.min(total), total);
         let output_path = dest_dir.join(&relative_path);
 
         if entry.is_dir() {
@@ -93,7 +112,7 @@ pub fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     }
 
     // After extraction, check for nested tar.gz archive
-    extract_nested_tar_gz_if_present(dest_dir)?;
+    extract_nested_tar_gz_if_present(dest_dir, report)?;
 
     Ok(())
 }
@@ -103,7 +122,10 @@ pub fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<()> {
 /// This handles GitHub releases that wrap tar.gz archives in ZIP files.
 /// If `dest_dir` contains only a `.tar.gz` file (plus optional `.sha256`),
 /// extracts the tar.gz and removes the archive files.
-fn extract_nested_tar_gz_if_present(dest_dir: &Path) -> Result<()> {
+fn extract_nested_tar_gz_if_present(
+    dest_dir: &Path,
+    report: &mut ExtractProgress<'_>,
+) -> Result<()> {
     let entries: Vec<_> = std::fs::read_dir(dest_dir)
         .with_context(|| format!("Failed to read directory: {}", dest_dir.display()))?
         .filter_map(Result::ok)
@@ -143,7 +165,7 @@ fn extract_nested_tar_gz_if_present(dest_dir: &Path) -> Result<()> {
     }
 
     // Extract the nested tar.gz
-    extract_tar_gz(&tar_gz_path, dest_dir)?;
+    extract_tar_gz_with_progress(&tar_gz_path, dest_dir, report)?;
 
     // Clean up the archive files
     std::fs::remove_file(&tar_gz_path).ok();
@@ -183,6 +205,26 @@ pub fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     }
 }
 
+/// Extracts an archive like [`extract_archive`], reporting every entry to `report`
+/// with the share of the archive read so far.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be opened or extracted, or if directory or
+/// file creation fails.
+pub fn extract_archive_with_progress(
+    archive_path: &Path,
+    dest_dir: &Path,
+    report: &mut ExtractProgress<'_>,
+) -> Result<()> {
+    let path_str = archive_path.to_string_lossy();
+    if path_str.ends_with(".tar.gz") || path_str.ends_with(".tgz") {
+        extract_tar_gz_with_progress(archive_path, dest_dir, report)
+    } else {
+        extract_zip_with_progress(archive_path, dest_dir, report)
+    }
+}
+
 /// Extracts a tar.gz archive to the destination directory.
 ///
 /// Creates the destination directory if it does not exist.
@@ -204,6 +246,16 @@ pub fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<()> {
 /// extract_tar_gz(Path::new("archive.tar.gz"), Path::new("output_dir"))?;
 /// ```
 pub fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
+    extract_tar_gz_with_progress(archive_path, dest_dir, &mut |_, _, _| {})
+}
+
+/// Extracts a tar.gz archive like [`extract_tar_gz`], reporting every entry to
+/// `report`.
+fn extract_tar_gz_with_progress(
+    archive_path: &Path,
+    dest_dir: &Path,
+    report: &mut ExtractProgress<'_>,
+) -> Result<()> {
     std::fs::create_dir_all(dest_dir)
         .with_context(|| format!("Failed to create directory: {}", dest_dir.display()))?;
 
@@ -211,7 +263,12 @@ pub fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
 
     let file = std::fs::File::open(archive_path)
         .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
-    let decoder = GzDecoder::new(file);
+    let total = file.metadata().map_or(0, |metadata| metadata.len());
+    let read = Cell::new(0);
+    let decoder = GzDecoder::new(CountingReader {
+        inner: file,
+        read: &read,
+    });
     let mut archive = Archive::new(decoder);
 
     for entry in archive
@@ -249,6 +306,7 @@ pub fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
             entry_path.clone()
         };
 
+        report(&relative_path, read.get().min(total), total);
         let output_path = dest_dir.join(&relative_path);
 
         if entry.header().entry_type().is_dir() {
@@ -270,6 +328,20 @@ pub fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// A reader counting the bytes read from `inner`.
+struct CountingReader<'a, R> {
+    inner: R,
+    read: &'a Cell<u64>,
+}
+
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.set(self.read.get() + n as u64);
+        Ok(n)
+    }
+}
+
 /// Finds a common root folder shared by all tar.gz archive entries.
 ///
 /// Returns `Some(prefix)` if all entries start with the same folder name
@@ -528,6 +600,35 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn extract_archive_with_progress_reports_entries() {
+        let temp_dir = temp_test_dir("tar_gz_progress");
+        let archive_path = temp_dir.join("test.tar.gz");
+        let dest_dir = temp_dir.join("output");
+        create_tar_gz_with_root(&archive_path, "root-folder");
+        let size = std::fs::metadata(&archive_path).unwrap().len();
+
+        let mut reported = Vec::new();
+        extract_archive_with_progress(&archive_path, &dest_dir, &mut |path, read, total| {
+            reported.push((path.to_path_buf(), read, total));
+        })
+        .expect("Should extract");
+
+        let paths: Vec<&Path> = reported.iter().map(|(path, _, _)| path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![Path::new("bin/infc"), Path::new("lib/libLLVM.so")]
+        );
+        assert!(
+            reported
+                .iter()
+                .all(|(_, read, total)| *total == size && read <= total)
+        );
+        assert!(reported[0].1 <= reported[1].1);
+
+        let _ = std::fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn extract_tar_gz_preserves_structure_without_common_root() {
         let temp_dir = temp_test_dir("tar_gz_preserve");
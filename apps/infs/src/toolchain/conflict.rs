@@ -17,13 +17,14 @@
 //! }
 //! ```
 
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 
 use super::Platform;
 use super::paths::ToolchainPaths;
 
 /// Represents a conflict where a binary in PATH shadows the managed version.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct PathConflict {
     /// Name of the binary (e.g., "infc").
     pub binary: String,
@@ -9,11 +9,24 @@
 //! - Platform detection
 //! - Toolchain directory existence
 //! - Default toolchain configuration
-//! - `inf-llc` binary presence
-//! - `rust-lld` binary presence
-//! - `libLLVM` shared library (Linux only)
+//! - `inf-llc` binary presence and version
+//! - `rust-lld` binary presence and version
+//! - `libLLVM` shared library and its version (Linux only)
+//! - `wasmtime` presence and version, for `infs run`
+//! - `coqc` or `rocq` presence and version, for `infs verify`
+//! - Writable download and cache directories
+//!
+//! A failed check may come with a remedy, printed below its message, and with a
+//! [`DoctorFix`] when it concerns a component managed by infs; `infs doctor --fix`
+//! applies those. Tools infs does not manage, such as wasmtime and Rocq, only get a
+//! remedy.
+
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
 
-use super::{Platform, ToolchainPaths};
+use super::{Platform, ToolchainPaths, find_coqc};
 
 /// Generates a message for when no default toolchain is set.
 ///
@@ -33,8 +46,39 @@ fn no_default_toolchain_message(paths: &ToolchainPaths) -> String {
     }
 }
 
-/// Status of a doctor check.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Returns the fix for a missing default toolchain: installing one, or making the latest
+/// installed version the default.
+fn no_default_toolchain_fix(paths: &ToolchainPaths) -> DoctorFix {
+    match paths.list_installed_versions().unwrap_or_default().pop() {
+        Some(latest) => DoctorFix::SetDefault(latest),
+        None => DoctorFix::InstallToolchain,
+    }
+}
+
+/// A fix of a managed component, applied by `infs doctor --fix`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DoctorFix {
+    /// Create the directories of the toolchain root.
+    CreateDirectories,
+    /// Install the latest toolchain.
+    InstallToolchain,
+    /// Make an installed version the default toolchain.
+    SetDefault(String),
+}
+
+impl fmt::Display for DoctorFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DoctorFix::CreateDirectories => write!(f, "create the toolchain directories"),
+            DoctorFix::InstallToolchain => write!(f, "install the latest toolchain"),
+            DoctorFix::SetDefault(version) => write!(f, "set {version} as the default toolchain"),
+        }
+    }
+}
+
+/// Status of a doctor check, serialized as `ok`, `warning` or `error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DoctorCheckStatus {
     /// Check passed.
     Ok,
@@ -45,7 +89,7 @@ pub enum DoctorCheckStatus {
 }
 
 /// Result of a single doctor check.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DoctorCheck {
     /// Name of the check.
     pub name: String,
@@ -53,6 +97,12 @@ pub struct DoctorCheck {
     pub status: DoctorCheckStatus,
     /// Descriptive message.
     pub message: String,
+    /// What to do about a warning or an error, when the message does not say it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remedy: Option<String>,
+    /// Fix applied by `infs doctor --fix`.
+    #[serde(skip)]
+    pub fix: Option<DoctorFix>,
 }
 
 impl DoctorCheck {
@@ -63,6 +113,8 @@ impl DoctorCheck {
             name: name.into(),
             status: DoctorCheckStatus::Ok,
             message: message.into(),
+            remedy: None,
+            fix: None,
         }
     }
 
@@ -73,6 +125,8 @@ impl DoctorCheck {
             name: name.into(),
             status: DoctorCheckStatus::Warning,
             message: message.into(),
+            remedy: None,
+            fix: None,
         }
     }
 
@@ -83,9 +137,25 @@ impl DoctorCheck {
             name: name.into(),
             status: DoctorCheckStatus::Error,
             message: message.into(),
+            remedy: None,
+            fix: None,
         }
     }
 
+    /// Adds the remedy of the finding.
+    #[must_use]
+    pub fn with_remedy(mut self, remedy: impl Into<String>) -> Self {
+        self.remedy = Some(remedy.into());
+        self
+    }
+
+    /// Adds the fix `infs doctor --fix` applies for the finding.
+    #[must_use]
+    pub fn with_fix(mut self, fix: DoctorFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
     /// Returns the CLI prefix for this check status.
     #[must_use]
     pub fn prefix(&self) -> &'static str {
@@ -110,6 +180,9 @@ pub fn run_all_checks() -> Vec<DoctorCheck> {
         check_default_toolchain(),
         check_inf_llc(),
         check_rust_lld(),
+        check_wasmtime(),
+        check_rocq(),
+        check_cache_directories(),
     ]
 }
 
@@ -123,9 +196,24 @@ pub fn run_all_checks() -> Vec<DoctorCheck> {
         check_inf_llc(),
         check_rust_lld(),
         check_libllvm(),
+        check_wasmtime(),
+        check_rocq(),
+        check_cache_directories(),
     ]
 }
 
+/// Returns the fixes of `checks`, each once, in the order of the checks.
+#[must_use]
+pub fn fixes(checks: &[DoctorCheck]) -> Vec<DoctorFix> {
+    let mut fixes = Vec::new();
+    for fix in checks.iter().filter_map(|check| check.fix.clone()) {
+        if !fixes.contains(&fix) {
+            fixes.push(fix);
+        }
+    }
+    fixes
+}
+
 /// Checks if the infs binary is accessible in PATH.
 #[must_use]
 pub fn check_infs_binary() -> DoctorCheck {
@@ -176,6 +264,7 @@ pub fn check_toolchain_directory() -> DoctorCheck {
                         paths.root.display()
                     ),
                 )
+                .with_fix(DoctorFix::CreateDirectories)
             }
         }
         Err(e) => DoctorCheck::error("Toolchain directory", format!("Cannot determine path: {e}")),
@@ -201,9 +290,13 @@ pub fn check_default_toolchain() -> DoctorCheck {
                     "Default toolchain",
                     format!("{version} is set as default but not installed"),
                 )
+                .with_remedy(format!(
+                    "Run 'infs install {version}', or 'infs default <version>' to use another one."
+                ))
             }
         }
-        Ok(None) => DoctorCheck::warning("Default toolchain", no_default_toolchain_message(&paths)),
+        Ok(None) => DoctorCheck::warning("Default toolchain", no_default_toolchain_message(&paths))
+            .with_fix(no_default_toolchain_fix(&paths)),
         Err(e) => DoctorCheck::error("Default toolchain", format!("Cannot read: {e}")),
     }
 }
@@ -229,8 +322,11 @@ pub fn check_binary(name: &str, binary_name: &str) -> DoctorCheck {
 
     let binary_with_ext = format!("{binary_name}{}", platform.executable_extension());
 
-    if which::which(&binary_with_ext).is_ok() {
-        return DoctorCheck::ok(name, format!("Found {binary_with_ext} in PATH"));
+    if let Ok(path) = which::which(&binary_with_ext) {
+        return DoctorCheck::ok(
+            name,
+            with_version(format!("Found {binary_with_ext} in PATH"), &path),
+        );
     }
 
     let Ok(paths) = ToolchainPaths::new() else {
@@ -240,7 +336,8 @@ pub fn check_binary(name: &str, binary_name: &str) -> DoctorCheck {
     let default_version = match paths.get_default_version() {
         Ok(Some(v)) => v,
         Ok(None) => {
-            return DoctorCheck::warning(name, no_default_toolchain_message(&paths));
+            return DoctorCheck::warning(name, no_default_toolchain_message(&paths))
+                .with_fix(no_default_toolchain_fix(&paths));
         }
         Err(_) => {
             return DoctorCheck::error(name, "Cannot read default version");
@@ -249,7 +346,10 @@ pub fn check_binary(name: &str, binary_name: &str) -> DoctorCheck {
 
     let binary_path = paths.binary_path(&default_version, &binary_with_ext);
     if binary_path.exists() {
-        DoctorCheck::ok(name, format!("Found at {}", binary_path.display()))
+        DoctorCheck::ok(
+            name,
+            with_version(format!("Found at {}", binary_path.display()), &binary_path),
+        )
     } else {
         DoctorCheck::error(
             name,
@@ -258,9 +358,135 @@ pub fn check_binary(name: &str, binary_name: &str) -> DoctorCheck {
                 binary_path.display()
             ),
         )
+        .with_remedy(reinstall_remedy(&default_version))
+    }
+}
+
+/// Checks if wasmtime, which `infs run` executes programs with, is available.
+#[must_use]
+pub fn check_wasmtime() -> DoctorCheck {
+    match which::which("wasmtime") {
+        Ok(path) => match tool_version(&path) {
+            Some(version) => {
+                DoctorCheck::ok("wasmtime", format!("{version} at {}", path.display()))
+            }
+            None => DoctorCheck::warning(
+                "wasmtime",
+                format!(
+                    "Found at {} but 'wasmtime --version' failed",
+                    path.display()
+                ),
+            )
+            .with_remedy("Reinstall wasmtime, see https://wasmtime.dev/"),
+        },
+        Err(_) => DoctorCheck::warning("wasmtime", "Not found in PATH; 'infs run' needs it")
+            .with_remedy(
+                "Install it with 'curl https://wasmtime.dev/install.sh -sSf | bash', \
+                 'brew install wasmtime' or 'winget install wasmtime'",
+            ),
+    }
+}
+
+/// Checks if the Rocq compiler, which `infs verify` checks proofs with, is available.
+#[must_use]
+pub fn check_rocq() -> DoctorCheck {
+    match find_coqc() {
+        Ok(coqc) => match tool_version(&coqc.path) {
+            Some(version) => {
+                DoctorCheck::ok("Rocq", format!("{version} at {}", coqc.path.display()))
+            }
+            None => DoctorCheck::warning(
+                "Rocq",
+                format!("Found at {} but '--version' failed", coqc.path.display()),
+            )
+            .with_remedy("Reinstall Rocq, see https://rocq-prover.org/install"),
+        },
+        Err(e) => {
+            let reason = e.to_string();
+            let reason = reason.lines().next().unwrap_or_default();
+            DoctorCheck::warning(
+                "Rocq",
+                format!("{}; 'infs verify' needs it", reason.trim_end_matches('.')),
+            )
+            .with_remedy(
+                "Install Rocq from https://rocq-prover.org/install, or set COQC_PATH to \
+                     the coqc binary",
+            )
+        }
+    }
+}
+
+/// Checks that the download and manifest cache directories are writable, or can be
+/// created.
+#[must_use]
+pub fn check_cache_directories() -> DoctorCheck {
+    const NAME: &str = "Cache directories";
+    let Ok(paths) = ToolchainPaths::new() else {
+        return DoctorCheck::error(NAME, "Cannot determine toolchain paths");
+    };
+
+    let dirs = [paths.downloads.clone(), paths.root.join("cache")];
+    for dir in &dirs {
+        // A missing directory is created on first use, in its nearest existing ancestor.
+        let Some(existing) = dir.ancestors().find(|ancestor| ancestor.exists()) else {
+            continue;
+        };
+        if let Err(e) = check_writable(existing) {
+            return DoctorCheck::error(
+                NAME,
+                format!("{} is not writable: {e}", existing.display()),
+            )
+            .with_remedy(format!(
+                "Fix the permissions of {}, or set INFERENCE_HOME to a writable directory",
+                existing.display()
+            ));
+        }
+    }
+    DoctorCheck::ok(
+        NAME,
+        format!(
+            "{} and {} are writable",
+            dirs[0].display(),
+            dirs[1].display()
+        ),
+    )
+}
+
+/// Returns an error if no file can be created in `dir`.
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".infs-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// Returns the first line `path --version` prints, if it succeeds.
+fn tool_version(path: &Path) -> Option<String> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Appends the version of the binary at `path` to `message`, if it reports one.
+fn with_version(message: String, path: &Path) -> String {
+    match tool_version(path) {
+        Some(version) => format!("{message} ({version})"),
+        None => message,
     }
 }
 
+/// Returns the remedy for a component missing from the installed toolchain `version`.
+fn reinstall_remedy(version: &str) -> String {
+    format!(
+        "Reinstall the toolchain: run 'infs uninstall {version}', then 'infs install {version}'."
+    )
+}
+
 /// Checks if libLLVM is available (Linux only).
 #[cfg(target_os = "linux")]
 #[must_use]
@@ -272,7 +498,8 @@ pub fn check_libllvm() -> DoctorCheck {
     let default_version = match paths.get_default_version() {
         Ok(Some(v)) => v,
         Ok(None) => {
-            return DoctorCheck::warning("libLLVM", no_default_toolchain_message(&paths));
+            return DoctorCheck::warning("libLLVM", no_default_toolchain_message(&paths))
+                .with_fix(no_default_toolchain_fix(&paths));
         }
         Err(_) => {
             return DoctorCheck::error("libLLVM", "Cannot read default version");
@@ -285,7 +512,8 @@ pub fn check_libllvm() -> DoctorCheck {
         return DoctorCheck::warning(
             "libLLVM",
             format!("Library directory not found at {}", lib_dir.display()),
-        );
+        )
+        .with_remedy(reinstall_remedy(&default_version));
     }
 
     let Ok(entries) = std::fs::read_dir(&lib_dir) else {
@@ -299,7 +527,11 @@ pub fn check_libllvm() -> DoctorCheck {
         let name = entry.file_name();
         let name_str = name.to_string_lossy();
         if name_str.starts_with("libLLVM") && name_str.contains(".so") {
-            return DoctorCheck::ok("libLLVM", format!("Found {}", entry.path().display()));
+            let message = match libllvm_version(&name_str) {
+                Some(version) => format!("LLVM {version} at {}", entry.path().display()),
+                None => format!("Found {}", entry.path().display()),
+            };
+            return DoctorCheck::ok("libLLVM", message);
         }
     }
 
@@ -310,6 +542,20 @@ pub fn check_libllvm() -> DoctorCheck {
             lib_dir.display()
         ),
     )
+    .with_remedy(reinstall_remedy(&default_version))
+}
+
+/// Returns the LLVM version in the name of a shared library, e.g. `18` for
+/// `libLLVM-18.so` and `18.1` for `libLLVM.so.18.1`.
+#[cfg(target_os = "linux")]
+fn libllvm_version(file_name: &str) -> Option<&str> {
+    let version = file_name
+        .strip_prefix("libLLVM-")
+        .and_then(|rest| rest.split(".so").next())
+        .or_else(|| file_name.strip_prefix("libLLVM.so."))?;
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then_some(version)
 }
 
 #[cfg(test)]
@@ -343,12 +589,40 @@ mod tests {
     #[test]
     fn run_all_checks_returns_expected_count() {
         let checks = run_all_checks();
-        // Base checks: infs, platform, toolchain dir, default toolchain, inf-llc, rust-lld
+        // Base checks: infs, platform, toolchain dir, default toolchain, inf-llc, rust-lld,
+        // wasmtime, Rocq, cache directories
         #[cfg(not(target_os = "linux"))]
-        assert_eq!(checks.len(), 6);
+        assert_eq!(checks.len(), 9);
         // On Linux, libLLVM is also checked
         #[cfg(target_os = "linux")]
-        assert_eq!(checks.len(), 7);
+        assert_eq!(checks.len(), 10);
+    }
+
+    #[test]
+    fn fixes_are_deduplicated_in_order() {
+        let checks = [
+            DoctorCheck::warning("a", "m").with_fix(DoctorFix::InstallToolchain),
+            DoctorCheck::ok("b", "m"),
+            DoctorCheck::warning("c", "m").with_fix(DoctorFix::CreateDirectories),
+            DoctorCheck::error("d", "m").with_fix(DoctorFix::InstallToolchain),
+        ];
+        assert_eq!(
+            fixes(&checks),
+            [DoctorFix::InstallToolchain, DoctorFix::CreateDirectories]
+        );
+    }
+
+    #[test]
+    fn check_writable_accepts_temp_dir() {
+        assert!(check_writable(&std::env::temp_dir()).is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn libllvm_version_parses_library_names() {
+        assert_eq!(libllvm_version("libLLVM-18.so"), Some("18"));
+        assert_eq!(libllvm_version("libLLVM.so.18.1"), Some("18.1"));
+        assert_eq!(libllvm_version("libLLVM.so"), None);
     }
 
     #[test]
@@ -8,46 +8,77 @@
 //! - Streaming downloads with progress callbacks
 //! - Automatic retry with exponential backoff (3 attempts)
 //! - Downloads to temporary file, then renames on success
+//! - Resumes interrupted downloads with HTTP range requests
+//! - Parallel downloads of several files, see [`download_files`]
 //! - Configurable timeout per request
+//! - Bearer authentication for private mirrors, see [`super::manifest::auth_token`]
+//! - Cancellation from another task, see [`CancellationToken`]
+//!
+//! ## Resuming
+//!
+//! A failed download keeps its partial `<file>.part` file. The next attempt, in the same
+//! call or a later one, asks the server for the remaining bytes with a `Range`
+//! header and appends them. A server that ignores the header sends the whole file,
+//! which replaces the partial one. Resumed files are only trusted after checksum and
+//! signature verification, like any other download.
+//!
+//! ## Cancellation
+//!
+//! [`download_files_with_callback`] stops as soon as its [`CancellationToken`] is
+//! cancelled, even while waiting on a server that does not respond, and removes the
+//! partial files of the downloads it stopped: a cancelled download is not resumed.
+//! It then fails with a [`Cancelled`] error, which callers tell apart from other
+//! failures with `error.is::<Cancelled>()`.
 //!
 //! ## TUI Integration
 //!
-//! For TUI integration, use [`download_file_with_callback`] which reports
+//! For TUI integration, use [`download_files_with_callback`] which reports
 //! progress via a callback instead of printing to stdout.
 
+use std::fmt;
 use std::io::Write;
-use std::path::Path;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Instant;
 
 use anyhow::{Context, Result, bail};
 use futures_util::StreamExt;
 use rand::Rng;
+use reqwest::StatusCode;
+use reqwest::header::RANGE;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
 
 /// Progress event emitted during downloads.
 ///
-/// Used by [`download_file_with_callback`] to report progress to TUI or other consumers.
+/// Used by [`download_files_with_callback`] to report progress to TUI or other consumers.
+/// `Started` and `Progress` concern a single file, identified by its index in the
+/// files downloaded; `Completed` and `Failed` concern all of them.
 #[derive(Debug, Clone)]
 pub enum ProgressEvent {
-    /// Download has started.
+    /// The size of a file became known.
     Started {
+        /// Index of the file.
+        file: usize,
         /// The URL being downloaded (available for logging/debugging).
         #[allow(dead_code)]
         url: String,
         /// Total file size in bytes.
         total: u64,
     },
-    /// Download progress update.
+    /// Download progress update of a file.
     Progress {
-        /// Bytes downloaded so far.
+        /// Index of the file.
+        file: usize,
+        /// Bytes of the file downloaded so far.
         downloaded: u64,
-        /// Current download speed in bytes per second.
+        /// Current download speed of the file in bytes per second.
         speed: u64,
     },
-    /// Download completed successfully.
+    /// All downloads completed successfully.
     Completed,
-    /// Download failed with an error.
+    /// A download failed with an error.
     Failed {
         /// Error description (available for logging/debugging).
         #[allow(dead_code)]
@@ -61,6 +92,88 @@ pub enum ProgressEvent {
 /// to allow sharing across async boundaries.
 pub type ProgressCallback = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
 
+/// A token cancelling downloads, shared between the task downloading and the one
+/// that may cancel it. Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// Creates a token that is not cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the operations using this token; they stop at their next await point.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// Returns whether the token was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled.
+    pub async fn cancelled(&self) {
+        let notified = self.inner.notify.notified();
+        tokio::pin!(notified);
+        // Register before checking the flag, so a concurrent `cancel` is not missed
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+
+    /// Returns a [`Cancelled`] error if the token was cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Cancelled`] if the token was cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+
+    /// Runs `future` until it completes or the token is cancelled, whichever is first.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `future`, or [`Cancelled`] if the token was cancelled.
+    pub async fn run<T>(&self, future: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            biased;
+            () = self.cancelled() => Err(Cancelled.into()),
+            result = future => result,
+        }
+    }
+}
+
+/// The error of an operation stopped by its [`CancellationToken`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
 /// Maximum number of download retry attempts.
 const MAX_RETRIES: u32 = 3;
 
@@ -70,25 +183,44 @@ const BASE_RETRY_DELAY_MS: u64 = 1000;
 /// Request timeout in seconds.
 const REQUEST_TIMEOUT_SECS: u64 = 300;
 
-/// Downloads a file from the given URL to the specified path with progress display.
-///
-/// The download uses streaming to avoid loading the entire file into memory.
-/// Progress is displayed using simple text output to stdout. The total size
-/// for progress display is obtained from the HTTP `Content-Length` header.
-///
-/// # Arguments
-///
-/// * `url` - The URL to download from
-/// * `dest` - The destination file path
+/// Maximum number of files [`download_files`] downloads at the same time.
+const MAX_PARALLEL_DOWNLOADS: usize = 4;
+
+/// Receives `(downloaded, total)` byte counts while a file downloads.
 ///
-/// # Errors
+/// `downloaded` includes the bytes of a resumed partial file, and `total` is 0 when
+/// the server does not report the size.
+type ByteProgress<'a> = dyn FnMut(u64, u64) + Send + 'a;
+
+/// Minimum interval between progress updates in milliseconds.
+const CLI_PROGRESS_INTERVAL_MS: u128 = 250;
+
+/// Downloads `url` to `dest`, retrying failed attempts with backoff.
 ///
-/// Returns an error if:
-/// - The network request fails after all retries
-/// - The destination file cannot be created
-/// - Writing to the file fails
-pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
-    let temp_path = dest.with_extension("tmp");
+/// Every attempt resumes the partial file the previous ones left. If `cancel` is
+/// cancelled, the partial file is removed instead.
+async fn download_with_retries(
+    url: &str,
+    dest: &Path,
+    report: &mut ByteProgress<'_>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let result = download_with_retries_inner(url, dest, report, cancel).await;
+    if cancel.is_cancelled() {
+        let _ = tokio::fs::remove_file(partial_path(dest)).await;
+    }
+    result
+}
+
+/// Downloads `url` to `dest` for [`download_with_retries`], stopping when `cancel` is
+/// cancelled.
+async fn download_with_retries_inner(
+    url: &str,
+    dest: &Path,
+    report: &mut ByteProgress<'_>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let temp_path = partial_path(dest);
 
     if let Some(parent) = dest.parent() {
         tokio::fs::create_dir_all(parent)
@@ -96,20 +228,28 @@ pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
             .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
     }
 
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
     let mut last_error = None;
 
     for attempt in 0..MAX_RETRIES {
         if attempt > 0 {
             let delay = calculate_retry_delay(attempt);
-            println!(
-                "Retrying download (attempt {}/{})...",
-                attempt + 1,
-                MAX_RETRIES
-            );
-            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            cancel
+                .run(async {
+                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                    Ok(())
+                })
+                .await?;
         }
 
-        match download_with_progress(url, &temp_path).await {
+        match cancel
+            .run(download_attempt(&client, url, &temp_path, report))
+            .await
+        {
             Ok(()) => {
                 tokio::fs::rename(&temp_path, dest).await.with_context(|| {
                     format!(
@@ -120,10 +260,8 @@ pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
                 })?;
                 return Ok(());
             }
-            Err(e) => {
-                last_error = Some(e);
-                let _ = tokio::fs::remove_file(&temp_path).await;
-            }
+            Err(e) if e.is::<Cancelled>() => return Err(e),
+            Err(e) => last_error = Some(e),
         }
     }
 
@@ -131,61 +269,227 @@ pub async fn download_file(url: &str, dest: &Path) -> Result<()> {
         .unwrap_or_else(|| anyhow::anyhow!("Download failed after {MAX_RETRIES} attempts")))
 }
 
-/// Minimum interval between progress updates in milliseconds.
-const CLI_PROGRESS_INTERVAL_MS: u128 = 250;
+/// Returns the path of the partial file `dest` is downloaded to: its file name with
+/// `.part` appended, so that `x.tar.gz` and `x.tar.xz` do not share a partial file.
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    dest.with_file_name(name)
+}
 
-/// Downloads a file with simple text-based progress display.
-async fn download_with_progress(url: &str, dest: &Path) -> Result<()> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
-        .build()
-        .context("Failed to create HTTP client")?;
+/// Returns the size of the partial file at `path`, or 0 if there is none.
+async fn partial_len(path: &Path) -> u64 {
+    tokio::fs::metadata(path)
+        .await
+        .map_or(0, |metadata| metadata.len())
+}
+
+/// Downloads `url` into the partial file `dest` once, resuming it if it has content.
+async fn download_attempt(
+    client: &reqwest::Client,
+    url: &str,
+    dest: &Path,
+    report: &mut ByteProgress<'_>,
+) -> Result<()> {
+    let offset = partial_len(dest).await;
+    let mut request = client.get(url);
+    if let Some(token) = super::manifest::auth_token(url) {
+        request = request.bearer_auth(token);
+    }
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+    }
 
-    let response = client
-        .get(url)
+    let response = request
         .send()
         .await
         .with_context(|| format!("Failed to connect to {url}"))?;
 
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file is not a prefix of the current file; start over.
+        let _ = tokio::fs::remove_file(dest).await;
+        bail!("HTTP error {}: {url}", response.status());
+    }
     if !response.status().is_success() {
         bail!("HTTP error {}: {url}", response.status());
     }
 
-    let total_size = response.content_length().unwrap_or(0);
+    let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total_size = response
+        .content_length()
+        .map_or(0, |length| length + downloaded);
 
-    let mut file = tokio::fs::File::create(dest)
-        .await
-        .with_context(|| format!("Failed to create file: {}", dest.display()))?;
+    let mut file = if resumed {
+        tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(dest)
+            .await
+            .with_context(|| format!("Failed to open file: {}", dest.display()))?
+    } else {
+        tokio::fs::File::create(dest)
+            .await
+            .with_context(|| format!("Failed to create file: {}", dest.display()))?
+    };
 
-    let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
-    let start_time = Instant::now();
-    let mut last_update = Instant::now();
+    report(downloaded, total_size);
 
+    let mut stream = response.bytes_stream();
     while let Some(chunk) = stream.next().await {
         let chunk = chunk.with_context(|| format!("Failed to read chunk from {url}"))?;
         file.write_all(&chunk)
             .await
             .with_context(|| format!("Failed to write to {}", dest.display()))?;
         downloaded += chunk.len() as u64;
-
-        let now = Instant::now();
-        if now.duration_since(last_update).as_millis() >= CLI_PROGRESS_INTERVAL_MS {
-            print_progress(downloaded, total_size, start_time.elapsed().as_secs_f64());
-            last_update = now;
-        }
+        report(downloaded, total_size);
     }
 
     file.flush()
         .await
         .with_context(|| format!("Failed to flush {}", dest.display()))?;
 
-    print_progress(downloaded, total_size, start_time.elapsed().as_secs_f64());
+    Ok(())
+}
+
+/// Downloads several files in parallel with progress display.
+///
+/// Up to four files download at the same time, streaming to avoid loading them into
+/// memory. Each file is retried with backoff and resumed from its partial file. The
+/// progress line on stdout shows the bytes of all files together, with the total
+/// taken from the HTTP `Content-Length` headers.
+///
+/// # Arguments
+///
+/// * `files` - The URLs to download and their destination paths
+///
+/// # Errors
+///
+/// Returns the first error of a file that failed after all retries; the other
+/// downloads still complete, so their files are in place for the next attempt.
+pub async fn download_files(files: &[(String, PathBuf)]) -> Result<()> {
+    let start_time = Instant::now();
+    let last_update: Mutex<Option<Instant>> = Mutex::new(None);
+    let progress = Mutex::new(vec![(0u64, 0u64); files.len()]);
+    let print = |file: usize, downloaded: u64, total: u64| {
+        let (downloaded, total) = {
+            let mut progress = progress.lock().unwrap_or_else(PoisonError::into_inner);
+            progress[file] = (downloaded, total);
+            progress.iter().fold((0, 0), |(d, t), (downloaded, total)| {
+                (d + downloaded, t + total)
+            })
+        };
+        let now = Instant::now();
+        let mut last_update = last_update.lock().unwrap_or_else(PoisonError::into_inner);
+        if last_update.is_none_or(|last| {
+            now.duration_since(last).as_millis() >= CLI_PROGRESS_INTERVAL_MS || downloaded == total
+        }) {
+            print_progress(downloaded, total, start_time.elapsed().as_secs_f64());
+            *last_update = Some(now);
+        }
+    };
+    let result = download_in_parallel(files, &print, &CancellationToken::new()).await;
     println!();
+    result
+}
 
+/// Downloads several files in parallel with progress callbacks for TUI integration.
+///
+/// Unlike [`download_files`], this function reports progress via a callback
+/// instead of printing to stdout.
+///
+/// The callback receives the progress of every file separately, as the files
+/// download concurrently: a [`ProgressEvent::Started`] when the size of a file
+/// becomes known, and [`ProgressEvent::Progress`] with the bytes of that file and its
+/// speed, at most every 100 ms per file.
+///
+/// # Errors
+///
+/// Returns the first error of a file that failed after all retries, or [`Cancelled`]
+/// if `cancel` was cancelled.
+pub async fn download_files_with_callback(
+    files: &[(String, PathBuf)],
+    callback: ProgressCallback,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let start_time = Instant::now();
+    // Per file, the size reported and the time of the last progress event
+    let reported: Mutex<Vec<(u64, Option<Instant>)>> = Mutex::new(vec![(0, None); files.len()]);
+    let report = |file: usize, downloaded: u64, total: u64| {
+        let mut reported = reported.lock().unwrap_or_else(PoisonError::into_inner);
+        let (sized, last_callback) = &mut reported[file];
+        if total != *sized {
+            *sized = total;
+            callback(ProgressEvent::Started {
+                file,
+                url: files[file].0.clone(),
+                total,
+            });
+        }
+        let now = Instant::now();
+        if last_callback.is_none_or(|last| {
+            now.duration_since(last).as_millis() >= PROGRESS_CALLBACK_INTERVAL_MS
+                || downloaded == total
+        }) {
+            let speed = bytes_per_second(downloaded, start_time.elapsed().as_secs_f64());
+            callback(ProgressEvent::Progress {
+                file,
+                downloaded,
+                speed,
+            });
+            *last_callback = Some(now);
+        }
+    };
+    match download_in_parallel(files, &report, cancel).await {
+        Ok(()) => {
+            callback(ProgressEvent::Completed);
+            Ok(())
+        }
+        Err(error) => {
+            callback(ProgressEvent::Failed {
+                error: error.to_string(),
+            });
+            Err(error)
+        }
+    }
+}
+
+/// Checks that every file of `files` has already been downloaded, for offline mode.
+///
+/// # Errors
+///
+/// Returns an error naming the first file that is missing, since downloading it
+/// needs the network.
+pub fn require_downloaded(files: &[(String, PathBuf)]) -> Result<()> {
+    for (url, dest) in files {
+        if !dest.is_file() {
+            bail!(
+                "{url} is not downloaded and cannot be fetched in offline mode; \
+                 run the command without --offline"
+            );
+        }
+    }
     Ok(())
 }
 
+/// Downloads `files` with up to [`MAX_PARALLEL_DOWNLOADS`] at a time, reporting the
+/// `(index, downloaded, total)` of each of them to `report`, until `cancel` is
+/// cancelled.
+async fn download_in_parallel(
+    files: &[(String, PathBuf)],
+    report: &(dyn Fn(usize, u64, u64) + Sync),
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let results: Vec<Result<()>> = futures_util::stream::iter(files.iter().enumerate())
+        .map(|(index, (url, dest))| async move {
+            let mut file_report = |downloaded: u64, total: u64| report(index, downloaded, total);
+            download_with_retries(url, dest, &mut file_report, cancel).await
+        })
+        .buffer_unordered(MAX_PARALLEL_DOWNLOADS)
+        .collect()
+        .await;
+    results.into_iter().collect()
+}
+
 /// Prints a simple text-based progress line.
 #[allow(clippy::cast_precision_loss)]
 #[allow(clippy::cast_possible_truncation)]
@@ -209,6 +513,20 @@ fn print_progress(downloaded: u64, total: u64, elapsed_secs: f64) {
     let _ = std::io::stdout().flush();
 }
 
+/// Returns the average speed of `downloaded` bytes in `elapsed_secs` seconds.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn bytes_per_second(downloaded: u64, elapsed_secs: f64) -> u64 {
+    if elapsed_secs > 0.0 {
+        (downloaded as f64 / elapsed_secs) as u64
+    } else {
+        0
+    }
+}
+
 /// Formats bytes into a human-readable string (KB, MB, GB).
 fn format_bytes(bytes: u64) -> String {
     const KB: f64 = 1024.0;
@@ -256,161 +574,142 @@ fn calculate_retry_delay(attempt: u32) -> u64 {
 /// Minimum interval between progress callback invocations in milliseconds.
 const PROGRESS_CALLBACK_INTERVAL_MS: u128 = 100;
 
-/// Downloads a file with progress callbacks for TUI integration.
-///
-/// Unlike [`download_file`], this function reports progress via a callback
-/// instead of printing to stdout. This allows integration with custom
-/// progress displays like the TUI. The total size for progress display
-/// is obtained from the HTTP `Content-Length` header.
-///
-/// # Arguments
-///
-/// * `url` - The URL to download from
-/// * `dest` - The destination file path
-/// * `callback` - Progress callback that receives [`ProgressEvent`]s
-///
-/// # Errors
-///
-/// Returns an error if:
-/// - The network request fails after all retries
-/// - The destination file cannot be created
-/// - Writing to the file fails
-pub async fn download_file_with_callback(
-    url: &str,
-    dest: &Path,
-    callback: ProgressCallback,
-) -> Result<()> {
-    let temp_path = dest.with_extension("tmp");
-
-    if let Some(parent) = dest.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-    }
-
-    let mut last_error = None;
-
-    for attempt in 0..MAX_RETRIES {
-        if attempt > 0 {
-            let delay = calculate_retry_delay(attempt);
-            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
-        }
-
-        match download_with_callback(url, &temp_path, callback.clone()).await {
-            Ok(()) => {
-                tokio::fs::rename(&temp_path, dest).await.with_context(|| {
-                    format!(
-                        "Failed to rename {} to {}",
-                        temp_path.display(),
-                        dest.display()
-                    )
-                })?;
-                callback(ProgressEvent::Completed);
-                return Ok(());
-            }
-            Err(e) => {
-                let _ = tokio::fs::remove_file(&temp_path).await;
-                last_error = Some(e);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    /// Serves `body` over HTTP once, honoring a `Range: bytes=N-` header, and returns
+    /// the URL and a handle yielding the request's range header.
+    fn serve_once(body: &'static [u8]) -> (String, std::thread::JoinHandle<Option<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind");
+        let url = format!("http://{}/file", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("Should accept");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut range = None;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("range:") {
+                    range = Some(value.trim().to_string());
+                }
             }
-        }
+            let offset: usize = range
+                .as_deref()
+                .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse().ok())
+                .unwrap_or(0);
+            let status = if offset > 0 {
+                "206 Partial Content"
+            } else {
+                "200 OK"
+            };
+            let rest = &body[offset..];
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                rest.len()
+            )
+            .unwrap();
+            stream.write_all(rest).unwrap();
+            range
+        });
+        (url, handle)
     }
 
-    let error = last_error
-        .unwrap_or_else(|| anyhow::anyhow!("Download failed after {MAX_RETRIES} attempts"));
-    callback(ProgressEvent::Failed {
-        error: error.to_string(),
-    });
-    Err(error)
-}
-
-/// Downloads a file with callback-based progress reporting.
-async fn download_with_callback(url: &str, dest: &Path, callback: ProgressCallback) -> Result<()> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
-        .build()
-        .context("Failed to create HTTP client")?;
+    #[tokio::test]
+    async fn download_resumes_partial_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let dest = temp.path().join("archive.tar.gz");
+        std::fs::write(partial_path(&dest), b"hello ").unwrap();
+        let (url, server) = serve_once(b"hello world");
 
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .with_context(|| format!("Failed to connect to {url}"))?;
+        download_files(&[(url, dest.clone())]).await.unwrap();
 
-    if !response.status().is_success() {
-        bail!("HTTP error {}: {url}", response.status());
+        assert_eq!(server.join().unwrap().as_deref(), Some("bytes=6-"));
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello world");
+        assert!(!partial_path(&dest).exists());
     }
 
-    let total_size = response.content_length().unwrap_or(0);
-
-    callback(ProgressEvent::Started {
-        url: url.to_string(),
-        total: total_size,
-    });
-
-    let mut file = tokio::fs::File::create(dest)
-        .await
-        .with_context(|| format!("Failed to create file: {}", dest.display()))?;
-
-    let mut stream = response.bytes_stream();
-    let mut downloaded: u64 = 0;
-    let start_time = Instant::now();
-    let mut last_callback_time = Instant::now();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.with_context(|| format!("Failed to read chunk from {url}"))?;
-        file.write_all(&chunk)
+    #[tokio::test]
+    async fn download_files_reports_progress_per_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let (first_url, first) = serve_once(b"first");
+        let (second_url, second) = serve_once(b"second file");
+        let files = [
+            (first_url, temp.path().join("first")),
+            (second_url, temp.path().join("second")),
+        ];
+        let last = Arc::new(Mutex::new([(0, 0); 2]));
+        let recorded = last.clone();
+        let callback: ProgressCallback = Arc::new(move |event| match event {
+            ProgressEvent::Started { file, total, .. } => recorded.lock().unwrap()[file].1 = total,
+            ProgressEvent::Progress {
+                file, downloaded, ..
+            } => recorded.lock().unwrap()[file].0 = downloaded,
+            ProgressEvent::Completed | ProgressEvent::Failed { .. } => {}
+        });
+
+        download_files_with_callback(&files, callback, &CancellationToken::new())
             .await
-            .with_context(|| format!("Failed to write to {}", dest.display()))?;
+            .unwrap();
 
-        downloaded += chunk.len() as u64;
-
-        let now = Instant::now();
-        let elapsed_since_callback = now.duration_since(last_callback_time).as_millis();
-
-        if elapsed_since_callback >= PROGRESS_CALLBACK_INTERVAL_MS {
-            let elapsed_secs = start_time.elapsed().as_secs_f64();
-            #[allow(
-                clippy::cast_possible_truncation,
-                clippy::cast_sign_loss,
-                clippy::cast_precision_loss
-            )]
-            let speed = if elapsed_secs > 0.0 {
-                (downloaded as f64 / elapsed_secs) as u64
-            } else {
-                0
-            };
-
-            callback(ProgressEvent::Progress { downloaded, speed });
-            last_callback_time = now;
-        }
+        first.join().unwrap();
+        second.join().unwrap();
+        assert_eq!(*last.lock().unwrap(), [(5, 5), (11, 11)]);
+        assert_eq!(std::fs::read(&files[1].1).unwrap(), b"second file");
     }
 
-    file.flush()
-        .await
-        .with_context(|| format!("Failed to flush {}", dest.display()))?;
-
-    let elapsed_secs = start_time.elapsed().as_secs_f64();
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_sign_loss,
-        clippy::cast_precision_loss
-    )]
-    let final_speed = if elapsed_secs > 0.0 {
-        (downloaded as f64 / elapsed_secs) as u64
-    } else {
-        0
-    };
-    callback(ProgressEvent::Progress {
-        downloaded,
-        speed: final_speed,
-    });
+    #[tokio::test]
+    async fn cancelled_download_stops_and_removes_partial_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let dest = temp.path().join("archive.tar.gz");
+        std::fs::write(partial_path(&dest), b"hello ").unwrap();
+
+        // A server that accepts the connection and never answers
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should bind");
+        let url = format!("http://{}/file", listener.local_addr().unwrap());
+
+        let cancel = CancellationToken::new();
+        let canceller = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            canceller.cancel();
+        });
+        let callback: ProgressCallback = Arc::new(|_| {});
+        let error = download_files_with_callback(&[(url, dest.clone())], callback, &cancel)
+            .await
+            .unwrap_err();
 
-    Ok(())
-}
+        assert!(error.is::<Cancelled>());
+        assert!(!partial_path(&dest).exists());
+        assert!(!dest.exists());
+        drop(listener);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[tokio::test]
+    async fn cancellation_token_wakes_waiters() {
+        let cancel = CancellationToken::new();
+        assert!(cancel.check().is_ok());
+
+        let waiter = tokio::spawn({
+            let cancel = cancel.clone();
+            async move { cancel.cancelled().await }
+        });
+        tokio::task::yield_now().await;
+        cancel.cancel();
+        waiter.await.unwrap();
+
+        assert!(cancel.is_cancelled());
+        assert!(cancel.check().unwrap_err().is::<Cancelled>());
+        // An already cancelled token stops operations before they start
+        let result = cancel.run(std::future::pending::<Result<()>>()).await;
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
 
     #[test]
     fn retry_delay_increases_exponentially() {
@@ -435,11 +734,13 @@ mod tests {
     #[test]
     fn progress_event_started_contains_url_and_total() {
         let event = ProgressEvent::Started {
+            file: 1,
             url: "https://example.com/file.zip".to_string(),
             total: 1024,
         };
         match event {
-            ProgressEvent::Started { url, total } => {
+            ProgressEvent::Started { file, url, total } => {
+                assert_eq!(file, 1);
                 assert_eq!(url, "https://example.com/file.zip");
                 assert_eq!(total, 1024);
             }
@@ -450,11 +751,14 @@ mod tests {
     #[test]
     fn progress_event_progress_contains_downloaded_and_speed() {
         let event = ProgressEvent::Progress {
+            file: 0,
             downloaded: 512,
             speed: 1024 * 100,
         };
         match event {
-            ProgressEvent::Progress { downloaded, speed } => {
+            ProgressEvent::Progress {
+                downloaded, speed, ..
+            } => {
                 assert_eq!(downloaded, 512);
                 assert_eq!(speed, 1024 * 100);
             }
@@ -462,6 +766,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn partial_path_keeps_every_extension() {
+        let dir = Path::new("/downloads");
+        assert_eq!(
+            partial_path(&dir.join("x.tar.gz")),
+            dir.join("x.tar.gz.part")
+        );
+        assert_ne!(
+            partial_path(&dir.join("x.tar.gz")),
+            partial_path(&dir.join("x.tar.xz"))
+        );
+    }
+
     #[test]
     fn progress_event_completed_variant() {
         let event = ProgressEvent::Completed;
@@ -484,12 +801,15 @@ mod tests {
     #[test]
     fn progress_event_is_clone() {
         let event = ProgressEvent::Progress {
+            file: 0,
             downloaded: 1000,
             speed: 500,
         };
         let cloned = event.clone();
         match cloned {
-            ProgressEvent::Progress { downloaded, speed } => {
+            ProgressEvent::Progress {
+                downloaded, speed, ..
+            } => {
                 assert_eq!(downloaded, 1000);
                 assert_eq!(speed, 500);
             }
@@ -500,6 +820,7 @@ mod tests {
     #[test]
     fn progress_event_is_debug() {
         let event = ProgressEvent::Started {
+            file: 0,
             url: "test".to_string(),
             total: 100,
         };
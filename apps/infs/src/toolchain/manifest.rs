@@ -13,6 +13,8 @@
 //!   {
 //!     "version": "0.2.0",
 //!     "stable": true,
+//!     "channel": "stable",
+//!     "date": "2026-01-05",
 //!     "files": [
 //!       {
 //!         "url": "https://github.com/Inferara/inference/releases/download/v0.1.0-alpha/infc-linux-x64.tar.gz",
@@ -23,15 +25,39 @@
 //! ]
 //! ```
 //!
+//! ## Channels
+//!
+//! A version belongs to the `stable`, `beta` or `nightly` channel given by `channel`.
+//! Without it, a stable version is on the `stable` channel and a prerelease on none,
+//! as in manifests written before channels existed. `date` is the release date, shown
+//! by `infs list` for the installed toolchains.
+//!
+//! Every file is signed with the publisher's minisign key; the signature is published
+//! next to it, at the file URL with `.minisig` appended.
+//!
 //! ## Data Source
 //!
 //! Release information is fetched from a static `releases.json` file hosted on
 //! the distribution server (default: `https://inference-lang.org`). The server
 //! can be overridden via the `INFS_DIST_SERVER` environment variable for testing
 //! or using a mirror.
+//!
+//! ## Mirrors
+//!
+//! `INFS_DIST_SERVER` takes a comma-separated list of servers. They are tried in
+//! order, and the manifest comes from the first one that serves it. A private mirror
+//! can require a token: `INFS_DIST_TOKEN` is sent as a bearer token to the configured
+//! servers, for the manifest and for every artifact they host, and never to other
+//! hosts.
+//!
+//! ## Offline Mode
+//!
+//! With `--offline`, commands read the cached manifest only, see [`cached_manifest`],
+//! and fail instead of reaching the network.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -40,6 +66,9 @@ use super::Platform;
 /// Environment variable to override the distribution server URL.
 pub const DIST_SERVER_ENV: &str = "INFS_DIST_SERVER";
 
+/// Environment variable with the bearer token for the distribution servers.
+pub const DIST_TOKEN_ENV: &str = "INFS_DIST_TOKEN";
+
 /// Default distribution server URL.
 const DEFAULT_DIST_SERVER: &str = "https://inference-lang.org";
 
@@ -78,6 +107,13 @@ impl FileEntry {
         self.url.rsplit('/').next().unwrap_or(&self.url)
     }
 
+    /// Returns the URL of the minisign signature of the artifact: its URL with
+    /// `.minisig` appended.
+    #[must_use]
+    pub fn signature_url(&self) -> String {
+        format!("{}.minisig", self.url)
+    }
+
     /// Extracts tool name from filename (first segment before '-').
     ///
     /// Example: `"infc-linux-x64.tar.gz"` -> `"infc"`
@@ -95,6 +131,47 @@ impl FileEntry {
     }
 }
 
+/// Release channel of a version.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl Channel {
+    /// Names of all channels, as accepted by [`Channel::parse`].
+    pub const NAMES: [&'static str; 3] = ["stable", "beta", "nightly"];
+
+    /// Returns the channel named `name`, if any.
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "stable" => Some(Channel::Stable),
+            "beta" => Some(Channel::Beta),
+            "nightly" => Some(Channel::Nightly),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the channel.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Beta => "beta",
+            Channel::Nightly => "nightly",
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Version entry in the manifest.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VersionEntry {
@@ -102,11 +179,25 @@ pub struct VersionEntry {
     pub version: String,
     /// Whether this is a stable release.
     pub stable: bool,
+    /// Release channel, if given; see [`VersionEntry::channel`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    /// Release date (YYYY-MM-DD).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
     /// Platform-specific files for this version.
     pub files: Vec<FileEntry>,
 }
 
 impl VersionEntry {
+    /// Returns the release channel of this version: the `channel` of the manifest, or
+    /// `stable` for a stable version without one.
+    #[must_use]
+    pub fn channel(&self) -> Option<Channel> {
+        self.channel
+            .or_else(|| self.stable.then_some(Channel::Stable))
+    }
+
     /// Checks if this version has artifacts for the specified platform.
     ///
     /// # Arguments
@@ -224,6 +315,28 @@ pub fn latest_stable(manifest: &Manifest) -> Option<&VersionEntry> {
     })
 }
 
+/// Finds the latest version on `channel`.
+///
+/// Versions are compared by semver, then by release date, so nightlies sharing a
+/// version are ordered by date.
+#[must_use = "returns version info without side effects"]
+pub fn latest_in_channel(manifest: &Manifest, channel: Channel) -> Option<&VersionEntry> {
+    manifest
+        .iter()
+        .filter(|v| v.channel() == Some(channel))
+        .max_by(|a, b| {
+            let a_ver = semver::Version::parse(&a.version).ok();
+            let b_ver = semver::Version::parse(&b.version).ok();
+            let by_version = match (a_ver, b_ver) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Greater,
+                (None, Some(_)) => std::cmp::Ordering::Less,
+                (None, None) => a.version.cmp(&b.version),
+            };
+            by_version.then_with(|| a.date.cmp(&b.date))
+        })
+}
+
 /// Finds the latest version in the manifest regardless of stability.
 ///
 /// All versions are sorted by semver and the highest one is returned.
@@ -409,18 +522,59 @@ pub async fn fetch_manifest() -> Result<Manifest> {
     Ok(manifest)
 }
 
-/// Returns the URL to the releases manifest.
+/// Returns the servers of the comma-separated `INFS_DIST_SERVER` environment
+/// variable, without trailing slashes and skipping empty entries.
+fn configured_servers() -> Vec<String> {
+    std::env::var(DIST_SERVER_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .map(|server| server.trim().trim_end_matches('/'))
+        .filter(|server| !server.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the distribution servers, in the order they are tried.
 ///
 /// Checks the `INFS_DIST_SERVER` environment variable first, then falls back
 /// to the default distribution server. Empty or whitespace-only values are
 /// treated as unset.
-fn releases_url() -> String {
-    let server = std::env::var(DIST_SERVER_ENV)
-        .ok()
-        .filter(|s| !s.trim().is_empty())
-        .unwrap_or_else(|| DEFAULT_DIST_SERVER.to_string());
-    let server = server.trim().trim_end_matches('/');
-    format!("{server}{RELEASES_PATH}")
+fn dist_servers() -> Vec<String> {
+    let servers = configured_servers();
+    if servers.is_empty() {
+        vec![DEFAULT_DIST_SERVER.to_string()]
+    } else {
+        servers
+    }
+}
+
+/// Returns the URLs of the releases manifest on every distribution server.
+fn releases_urls() -> Vec<String> {
+    dist_servers()
+        .into_iter()
+        .map(|server| format!("{server}{RELEASES_PATH}"))
+        .collect()
+}
+
+/// Returns the token to authenticate a request to `url` with, if any.
+///
+/// The `INFS_DIST_TOKEN` token is only sent to the servers configured in
+/// `INFS_DIST_SERVER`, so it never leaks to the default server or to third-party
+/// hosts an artifact URL points to.
+#[must_use]
+pub fn auth_token(url: &str) -> Option<String> {
+    let token = std::env::var(DIST_TOKEN_ENV).ok()?;
+    let token = token.trim();
+    if token.is_empty() {
+        return None;
+    }
+    configured_servers()
+        .iter()
+        .any(|server| {
+            url.strip_prefix(server.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+        .then(|| token.to_string())
 }
 
 /// Handles HTTP errors with user-friendly messages.
@@ -432,34 +586,74 @@ fn handle_http_error(status: reqwest::StatusCode, url: &str) -> anyhow::Error {
     }
 }
 
-/// Fetches the release manifest directly from the distribution server, bypassing cache.
+/// Returns the cached release manifest, for offline mode.
 ///
-/// This function fetches the `releases.json` file from the configured distribution
-/// server (default: `https://inference-lang.org`).
+/// Unlike [`fetch_manifest`], this never reaches the network.
 ///
 /// # Errors
 ///
-/// Returns an error if:
-/// - The HTTP request fails
-/// - The server returns a non-success status code
-/// - The response cannot be parsed as JSON
-async fn fetch_manifest_from_network() -> Result<Manifest> {
-    let url = releases_url();
+/// Returns an error if no manifest has been cached yet.
+pub fn cached_manifest() -> Result<Manifest> {
+    load_from_cache().context(
+        "The release manifest is not cached and cannot be fetched in offline mode; \
+         run the command once without --offline",
+    )
+}
 
+/// Fetches the release manifest directly from the distribution servers, bypassing cache.
+///
+/// Each configured server is tried in order (default: `https://inference-lang.org`),
+/// and the first manifest served is returned.
+///
+/// # Errors
+///
+/// Returns an error if every server fails: the error of the server if there is only
+/// one, or a summary of the errors of all servers.
+async fn fetch_manifest_from_network() -> Result<Manifest> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
         .user_agent(USER_AGENT)
         .build()
         .context("Failed to create HTTP client")?;
 
-    let response = client
-        .get(&url)
+    let mut errors = Vec::new();
+    for url in releases_urls() {
+        match fetch_manifest_from(&client, &url).await {
+            Ok(manifest) => return Ok(manifest),
+            Err(e) => errors.push((url, e)),
+        }
+    }
+    if errors.len() == 1 {
+        return Err(errors.remove(0).1);
+    }
+    let summary = errors
+        .iter()
+        .map(|(url, e)| format!("  {url}: {e:#}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bail!("Failed to fetch the release manifest from every distribution server:\n{summary}")
+}
+
+/// Fetches the release manifest from `url`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The HTTP request fails
+/// - The server returns a non-success status code
+/// - The response cannot be parsed as JSON
+async fn fetch_manifest_from(client: &reqwest::Client, url: &str) -> Result<Manifest> {
+    let mut request = client.get(url);
+    if let Some(token) = auth_token(url) {
+        request = request.bearer_auth(token);
+    }
+    let response = request
         .send()
         .await
         .with_context(|| format!("Failed to fetch manifest from {url}"))?;
 
     if !response.status().is_success() {
-        return Err(handle_http_error(response.status(), &url));
+        return Err(handle_http_error(response.status(), url));
     }
 
     let text = response
@@ -476,25 +670,39 @@ async fn fetch_manifest_from_network() -> Result<Manifest> {
 /// Fetches the release manifest and finds the artifact for a specific version and platform.
 ///
 /// If `version` is `None` or "latest", returns the latest stable version's artifact.
+/// A channel name (`stable`, `beta` or `nightly`) returns the artifact of the latest
+/// version on that channel. With `offline`, the cached manifest is used, see
+/// [`cached_manifest`].
+///
+/// Returns the version entry together with the artifact.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - The manifest cannot be fetched
-/// - The specified version is not found
+/// - The manifest cannot be fetched, or is not cached in offline mode
+/// - The specified version is not found, or the channel has no version
 /// - No artifact exists for the current platform
 pub async fn fetch_artifact(
     version: Option<&str>,
     platform: Platform,
-) -> Result<(String, FileEntry)> {
-    let manifest = fetch_manifest().await?;
+    offline: bool,
+) -> Result<(VersionEntry, FileEntry)> {
+    let manifest = if offline {
+        cached_manifest()?
+    } else {
+        fetch_manifest().await?
+    };
 
     let version_entry = match version {
         None | Some("latest") => latest_stable(&manifest)
             .or_else(|| latest_version(&manifest))
             .context("No version found in manifest")?,
-        Some(v) => find_version(&manifest, v)
-            .with_context(|| format!("Version {v} not found in manifest"))?,
+        Some(v) => match Channel::parse(v) {
+            Some(channel) => latest_in_channel(&manifest, channel)
+                .with_context(|| format!("No version found on the {channel} channel"))?,
+            None => find_version(&manifest, v)
+                .with_context(|| format!("Version {v} not found in manifest"))?,
+        },
     };
 
     let artifact = version_entry
@@ -507,7 +715,7 @@ pub async fn fetch_artifact(
         })?
         .clone();
 
-    Ok((version_entry.version.clone(), artifact))
+    Ok((version_entry.clone(), artifact))
 }
 
 #[cfg(test)]
@@ -715,16 +923,22 @@ mod tests {
             VersionEntry {
                 version: "0.1.0".to_string(),
                 stable: true,
+                channel: None,
+                date: None,
                 files: vec![],
             },
             VersionEntry {
                 version: "invalid".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
             VersionEntry {
                 version: "0.2.0".to_string(),
                 stable: true,
+                channel: None,
+                date: None,
                 files: vec![],
             },
         ];
@@ -860,17 +1074,18 @@ mod tests {
     #[serial_test::serial]
     fn releases_url_uses_default_when_env_not_set() {
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
-        let url = releases_url();
-        assert!(url.starts_with("https://inference-lang.org"));
-        assert!(url.ends_with("/releases.json"));
+        let urls = releases_urls();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].starts_with("https://inference-lang.org"));
+        assert!(urls[0].ends_with("/releases.json"));
     }
 
     #[test]
     #[serial_test::serial]
     fn releases_url_uses_env_when_set() {
         unsafe { std::env::set_var(DIST_SERVER_ENV, "http://localhost:8080") };
-        let url = releases_url();
-        assert_eq!(url, "http://localhost:8080/releases.json");
+        let urls = releases_urls();
+        assert_eq!(urls, ["http://localhost:8080/releases.json"]);
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
     }
 
@@ -878,8 +1093,8 @@ mod tests {
     #[serial_test::serial]
     fn releases_url_handles_trailing_slash() {
         unsafe { std::env::set_var(DIST_SERVER_ENV, "http://localhost:8080/") };
-        let url = releases_url();
-        assert_eq!(url, "http://localhost:8080/releases.json");
+        let urls = releases_urls();
+        assert_eq!(urls, ["http://localhost:8080/releases.json"]);
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
     }
 
@@ -887,9 +1102,10 @@ mod tests {
     #[serial_test::serial]
     fn releases_url_uses_default_when_env_empty() {
         unsafe { std::env::set_var(DIST_SERVER_ENV, "") };
-        let url = releases_url();
-        assert!(url.starts_with("https://inference-lang.org"));
-        assert!(url.ends_with("/releases.json"));
+        let urls = releases_urls();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].starts_with("https://inference-lang.org"));
+        assert!(urls[0].ends_with("/releases.json"));
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
     }
 
@@ -897,9 +1113,10 @@ mod tests {
     #[serial_test::serial]
     fn releases_url_uses_default_when_env_whitespace_only() {
         unsafe { std::env::set_var(DIST_SERVER_ENV, "   ") };
-        let url = releases_url();
-        assert!(url.starts_with("https://inference-lang.org"));
-        assert!(url.ends_with("/releases.json"));
+        let urls = releases_urls();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].starts_with("https://inference-lang.org"));
+        assert!(urls[0].ends_with("/releases.json"));
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
     }
 
@@ -907,11 +1124,72 @@ mod tests {
     #[serial_test::serial]
     fn releases_url_trims_whitespace() {
         unsafe { std::env::set_var(DIST_SERVER_ENV, "  http://localhost:8080  ") };
-        let url = releases_url();
-        assert_eq!(url, "http://localhost:8080/releases.json");
+        let urls = releases_urls();
+        assert_eq!(urls, ["http://localhost:8080/releases.json"]);
+        unsafe { std::env::remove_var(DIST_SERVER_ENV) };
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn releases_urls_lists_every_mirror_in_order() {
+        unsafe { std::env::set_var(DIST_SERVER_ENV, "http://a.test/, ,http://b.test") };
+        let urls = releases_urls();
+        assert_eq!(
+            urls,
+            ["http://a.test/releases.json", "http://b.test/releases.json"]
+        );
         unsafe { std::env::remove_var(DIST_SERVER_ENV) };
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn auth_token_is_only_sent_to_configured_servers() {
+        unsafe { std::env::set_var(DIST_TOKEN_ENV, "secret") };
+        unsafe { std::env::remove_var(DIST_SERVER_ENV) };
+        assert_eq!(auth_token("https://inference-lang.org/releases.json"), None);
+
+        unsafe { std::env::set_var(DIST_SERVER_ENV, "http://a.test,http://b.test/mirror") };
+        assert_eq!(
+            auth_token("http://b.test/mirror/infc-linux-x64.tar.gz").as_deref(),
+            Some("secret")
+        );
+        assert_eq!(auth_token("http://a.test.evil/releases.json"), None);
+        assert_eq!(auth_token("https://github.com/infc-linux-x64.tar.gz"), None);
+        unsafe { std::env::remove_var(DIST_SERVER_ENV) };
+        unsafe { std::env::remove_var(DIST_TOKEN_ENV) };
+    }
+
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn fetch_manifest_fails_over_to_the_next_mirror() {
+        use std::io::{Read, Write};
+
+        let down = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let down_url = format!("http://{}", down.local_addr().unwrap());
+        drop(down);
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let up_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = sample_manifest_json();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+        });
+
+        unsafe { std::env::set_var(DIST_SERVER_ENV, format!("{down_url},{up_url}")) };
+        let manifest = fetch_manifest_from_network().await;
+        unsafe { std::env::remove_var(DIST_SERVER_ENV) };
+
+        server.join().unwrap();
+        assert_eq!(manifest.unwrap().len(), 3);
+    }
+
     #[test]
     fn handle_http_error_404() {
         let error = handle_http_error(reqwest::StatusCode::NOT_FOUND, "https://example.com");
@@ -945,11 +1223,15 @@ mod tests {
             VersionEntry {
                 version: "0.1.0-alpha".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
             VersionEntry {
                 version: "0.2.0-beta".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
         ];
@@ -957,6 +1239,50 @@ mod tests {
         assert!(latest_stable(&manifest).is_none());
     }
 
+    #[test]
+    fn version_entry_channel_defaults_to_stable_for_stable_versions() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"[
+                {"version": "0.1.0", "stable": true, "files": []},
+                {"version": "0.2.0-alpha", "stable": false, "files": []},
+                {"version": "0.2.0-beta.1", "stable": false, "channel": "beta", "files": []}
+            ]"#,
+        )
+        .expect("Should parse manifest");
+
+        assert_eq!(manifest[0].channel(), Some(Channel::Stable));
+        assert_eq!(manifest[1].channel(), None);
+        assert_eq!(manifest[2].channel(), Some(Channel::Beta));
+    }
+
+    #[test]
+    fn latest_in_channel_orders_by_version_then_date() {
+        let manifest: Manifest = serde_json::from_str(
+            r#"[
+                {"version": "0.2.0", "stable": true, "files": []},
+                {"version": "0.3.0-nightly", "stable": false, "channel": "nightly", "date": "2026-10-15", "files": []},
+                {"version": "0.3.0-nightly", "stable": false, "channel": "nightly", "date": "2026-10-14", "files": []},
+                {"version": "0.2.0-nightly", "stable": false, "channel": "nightly", "date": "2026-10-16", "files": []}
+            ]"#,
+        )
+        .expect("Should parse manifest");
+
+        let nightly = latest_in_channel(&manifest, Channel::Nightly).unwrap();
+        assert_eq!(nightly.version, "0.3.0-nightly");
+        assert_eq!(nightly.date.as_deref(), Some("2026-10-15"));
+        let stable = latest_in_channel(&manifest, Channel::Stable).unwrap();
+        assert_eq!(stable.version, "0.2.0");
+        assert!(latest_in_channel(&manifest, Channel::Beta).is_none());
+    }
+
+    #[test]
+    fn channel_parses_its_names() {
+        for name in Channel::NAMES {
+            assert_eq!(Channel::parse(name).unwrap().as_str(), name);
+        }
+        assert!(Channel::parse("latest").is_none());
+    }
+
     #[test]
     fn latest_version_returns_highest_version() {
         let manifest: Manifest =
@@ -979,11 +1305,15 @@ mod tests {
             VersionEntry {
                 version: "0.1.0-alpha".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
             VersionEntry {
                 version: "0.2.0-beta".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
         ];
@@ -998,11 +1328,15 @@ mod tests {
             VersionEntry {
                 version: "0.1.0-alpha".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
             VersionEntry {
                 version: "0.2.0-beta".to_string(),
                 stable: false,
+                channel: None,
+                date: None,
                 files: vec![],
             },
         ];
@@ -1031,6 +1365,8 @@ mod tests {
         let entry = VersionEntry {
             version: "0.1.0".to_string(),
             stable: true,
+            channel: None,
+            date: None,
             files: vec![
                 FileEntry {
                     url: "https://example.com/infc-linux-x64.tar.gz".to_string(),
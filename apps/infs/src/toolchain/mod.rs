@@ -9,26 +9,33 @@
 //! - [`paths`] - Toolchain directory path management
 //! - [`manifest`] - Release manifest fetching and parsing
 //! - [`download`] - HTTP download with progress tracking
-//! - [`verify`] - SHA256 checksum verification
+//! - [`verify`] - SHA256 checksum and minisign signature verification
 //! - [`archive`] - ZIP and tar.gz archive extraction utilities
 //! - [`doctor`] - Toolchain health checks
 //! - [`conflict`] - PATH conflict detection
+//! - [`overrides`] - Per-directory toolchain version overrides
 
 pub mod archive;
 pub mod conflict;
 pub mod doctor;
 pub mod download;
 pub mod manifest;
+pub mod overrides;
 pub mod paths;
 pub mod platform;
 pub mod resolver;
 pub mod shell;
 pub mod verify;
 
-pub use archive::{extract_archive, set_executable_permissions};
-pub use download::{ProgressCallback, ProgressEvent, download_file, download_file_with_callback};
-pub use manifest::{fetch_artifact, fetch_manifest, latest_stable, latest_version};
+pub use archive::{extract_archive, extract_archive_with_progress, set_executable_permissions};
+pub use download::{
+    CancellationToken, Cancelled, ProgressCallback, ProgressEvent, download_files,
+    download_files_with_callback, require_downloaded,
+};
+pub use manifest::{
+    cached_manifest, fetch_artifact, fetch_manifest, latest_stable, latest_version,
+};
 pub use paths::ToolchainPaths;
 pub use platform::Platform;
-pub use resolver::find_infc;
-pub use verify::verify_checksum;
+pub use resolver::{find_coqc, find_infc};
+pub use verify::{verify_checksum, verify_signature};
@@ -0,0 +1,149 @@
+//! Per-directory toolchain overrides.
+//!
+//! A project pins the toolchain version its `build`, `run` and `verify` use with an
+//! `inference-toolchain.toml` file, found in the current directory or any of its
+//! parents:
+//!
+//! ```toml
+//! [toolchain]
+//! version = "0.1.0"
+//! ```
+//!
+//! The `INFS_TOOLCHAIN` environment variable pins a version for one invocation and
+//! takes precedence over the file. Both take precedence over the global default set
+//! with `infs default`, like `rustup` overrides.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Name of the toolchain override file.
+pub const TOOLCHAIN_FILE: &str = "inference-toolchain.toml";
+
+/// Environment variable that overrides the toolchain version.
+pub const TOOLCHAIN_ENV: &str = "INFS_TOOLCHAIN";
+
+/// A toolchain version pinned for the current directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainOverride {
+    pub version: String,
+    pub source: OverrideSource,
+}
+
+/// Where a toolchain override comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideSource {
+    /// The `INFS_TOOLCHAIN` environment variable.
+    Environment,
+    /// An `inference-toolchain.toml` file.
+    File(PathBuf),
+}
+
+impl fmt::Display for OverrideSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverrideSource::Environment => write!(f, "{TOOLCHAIN_ENV} environment variable"),
+            OverrideSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ToolchainFile {
+    toolchain: ToolchainSection,
+}
+
+#[derive(Deserialize)]
+struct ToolchainSection {
+    version: String,
+}
+
+/// Returns the toolchain override for the current directory, if any.
+///
+/// # Errors
+///
+/// Returns an error if the current directory cannot be determined or an override
+/// file is invalid.
+pub fn find_override() -> Result<Option<ToolchainOverride>> {
+    if let Ok(version) = std::env::var(TOOLCHAIN_ENV) {
+        let version = version.trim();
+        if !version.is_empty() {
+            return Ok(Some(ToolchainOverride {
+                version: version.to_string(),
+                source: OverrideSource::Environment,
+            }));
+        }
+    }
+    let current_dir = std::env::current_dir().context("Failed to get current directory")?;
+    find_override_file(&current_dir)
+}
+
+/// Returns the override of the nearest `inference-toolchain.toml` in `start` or its
+/// parents.
+///
+/// # Errors
+///
+/// Returns an error if the nearest file cannot be read, is not valid TOML, or has an
+/// empty version.
+pub fn find_override_file(start: &Path) -> Result<Option<ToolchainOverride>> {
+    for dir in start.ancestors() {
+        let path = dir.join(TOOLCHAIN_FILE);
+        if path.is_file() {
+            let version = read_override_file(&path)?;
+            return Ok(Some(ToolchainOverride {
+                version,
+                source: OverrideSource::File(path),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+fn read_override_file(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let file: ToolchainFile =
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))?;
+    let version = file.toolchain.version.trim();
+    if version.is_empty() {
+        bail!("{}: toolchain version must not be empty", path.display());
+    }
+    Ok(version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_nearest_override_file() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let nested = temp.path().join("project").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            temp.path().join(TOOLCHAIN_FILE),
+            "[toolchain]\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        let project_file = temp.path().join("project").join(TOOLCHAIN_FILE);
+        std::fs::write(&project_file, "[toolchain]\nversion = \"0.2.0\"\n").unwrap();
+
+        let found = find_override_file(&nested).unwrap().unwrap();
+        assert_eq!(found.version, "0.2.0");
+        assert_eq!(found.source, OverrideSource::File(project_file));
+    }
+
+    #[test]
+    fn rejects_an_empty_version() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        std::fs::write(
+            temp.path().join(TOOLCHAIN_FILE),
+            "[toolchain]\nversion = \"\"\n",
+        )
+        .unwrap();
+
+        let err = find_override_file(temp.path()).unwrap_err().to_string();
+        assert!(err.contains("must not be empty"));
+    }
+}
@@ -14,7 +14,7 @@
 //!       bin/
 //!         inf-llc             # LLVM backend tools
 //!         rust-lld
-//!       .metadata.json        # Installation metadata (date, etc.)
+//!       .metadata.json        # Installation metadata (date, channel, etc.)
 //!     0.2.0/
 //!       ...
 //!   bin/                      # Symlinks to default toolchain binaries
@@ -31,6 +31,8 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use super::manifest::{Channel, VersionEntry};
+
 /// Environment variable to override the default toolchain root directory.
 pub const INFERENCE_HOME_ENV: &str = "INFERENCE_HOME";
 
@@ -50,6 +52,12 @@ const INFS_METADATA_SCHEMA_VERSION: u32 = 1;
 pub struct ToolchainMetadata {
     /// ISO 8601 timestamp of when the toolchain was installed.
     pub installed_at: String,
+    /// Release channel the toolchain was installed from, if it is on one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<Channel>,
+    /// Release date of the toolchain (YYYY-MM-DD), if the manifest gives one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub released_at: Option<String>,
 }
 
 /// Metadata about the infs CLI itself (not toolchains).
@@ -172,6 +180,19 @@ impl ToolchainMetadata {
 
         Self {
             installed_at: format_timestamp(timestamp),
+            channel: None,
+            released_at: None,
+        }
+    }
+
+    /// Creates new metadata with the current timestamp for the release `entry`,
+    /// recording its channel and release date.
+    #[must_use = "returns new metadata without side effects"]
+    pub fn for_release(entry: &VersionEntry) -> Self {
+        Self {
+            channel: entry.channel(),
+            released_at: entry.date.clone(),
+            ..Self::now()
         }
     }
 
@@ -778,6 +799,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn toolchain_metadata_records_channel_and_release_date() {
+        let entry = VersionEntry {
+            version: "0.3.0-nightly.20261015".to_string(),
+            stable: false,
+            channel: Some(Channel::Nightly),
+            date: Some("2026-10-15".to_string()),
+            files: vec![],
+        };
+        let metadata = ToolchainMetadata::for_release(&entry);
+        assert_eq!(metadata.channel, Some(Channel::Nightly));
+        assert_eq!(metadata.released_at.as_deref(), Some("2026-10-15"));
+
+        let old: ToolchainMetadata =
+            serde_json::from_str(r#"{"installed_at": "2026-01-05"}"#).unwrap();
+        assert_eq!(old.channel, None);
+        assert_eq!(old.released_at, None);
+    }
+
     #[test]
     fn toolchain_metadata_now_creates_valid_date() {
         let metadata = ToolchainMetadata::now();
@@ -4,12 +4,17 @@
 //! across different installation contexts. The search order prioritizes:
 //!
 //! 1. Explicit override via `INFC_PATH` environment variable
-//! 2. System PATH via `which::which("infc")`
-//! 3. Managed toolchain at `~/.inference/toolchains/VERSION/bin/infc`
+//! 2. Pinned toolchain from `INFS_TOOLCHAIN` or an `inference-toolchain.toml` file
+//! 3. System PATH via `which::which("infc")`
+//! 4. Managed toolchain at `~/.inference/toolchains/VERSION/bin/infc`
+//!
+//! The Rocq compiler used by `infs verify` is located the same way, see [`find_coqc`].
 //!
 //! ## Environment Variables
 //!
 //! - `INFC_PATH`: Explicit path to the infc binary (highest priority)
+//! - `COQC_PATH`: Explicit path to the `coqc` or `rocq` binary (highest priority)
+//! - `INFS_TOOLCHAIN`: Toolchain version to use, see [`overrides`](super::overrides)
 //!
 //! ## Example
 //!
@@ -23,26 +28,33 @@
 use anyhow::{Context, Result, bail};
 use std::path::PathBuf;
 
+use crate::toolchain::overrides::find_override;
 use crate::toolchain::paths::ToolchainPaths;
 use crate::toolchain::platform::Platform;
 
 /// Environment variable for explicit infc binary path override.
 const INFC_PATH_ENV: &str = "INFC_PATH";
 
+/// Environment variable for explicit Rocq compiler path override.
+const COQC_PATH_ENV: &str = "COQC_PATH";
+
 /// Locates the `infc` compiler binary.
 ///
 /// Searches for the infc binary in the following priority order:
 ///
 /// 1. **`INFC_PATH` environment variable** - Explicit override for testing
 ///    or custom installations
-/// 2. **System PATH** - Uses `which::which("infc")` to find infc in PATH
-/// 3. **Managed toolchain** - Looks in `~/.inference/toolchains/VERSION/bin/infc`
+/// 2. **Pinned toolchain** - The version set by `INFS_TOOLCHAIN` or the nearest
+///    `inference-toolchain.toml`, which must be installed
+/// 3. **System PATH** - Uses `which::which("infc")` to find infc in PATH
+/// 4. **Managed toolchain** - Looks in `~/.inference/toolchains/VERSION/bin/infc`
 ///    using the default toolchain version if set
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - `INFC_PATH` is set but the path does not exist
+/// - A pinned toolchain is not installed, or its override file is invalid
 /// - No infc binary could be found in any location
 ///
 /// The error message provides helpful guidance on how to install infc.
@@ -68,24 +80,19 @@ pub fn find_infc() -> Result<PathBuf> {
         );
     }
 
-    // Priority 2: System PATH
-    if let Ok(path) = which::which("infc") {
+    // Priority 2: Pinned toolchain
+    if let Some(path) = find_in_pinned_toolchain(&["infc"])? {
         return Ok(path);
     }
 
-    // Priority 3: Managed toolchain
-    if let Ok(paths) = ToolchainPaths::new()
-        && let Ok(Some(version)) = paths.get_default_version()
-    {
-        let platform =
-            Platform::detect().context("Failed to detect platform while searching for infc")?;
-        let ext = platform.executable_extension();
-        let infc_name = format!("infc{ext}");
-        let infc_path = paths.toolchain_bin_dir(&version).join(&infc_name);
+    // Priority 3: System PATH
+    if let Ok(path) = which::which("infc") {
+        return Ok(path);
+    }
 
-        if infc_path.exists() {
-            return Ok(infc_path);
-        }
+    // Priority 4: Managed toolchain
+    if let Some(infc_path) = find_in_toolchain("infc")? {
+        return Ok(infc_path);
     }
 
     bail!(
@@ -98,6 +105,132 @@ pub fn find_infc() -> Result<PathBuf> {
     );
 }
 
+/// Locates the Rocq compiler used to check generated proofs.
+///
+/// Searches in the same order as [`find_infc`]:
+///
+/// 1. **`COQC_PATH` environment variable** - Explicit override; a binary named
+///    `rocq` is run as `rocq compile`
+/// 2. **Pinned toolchain** - `coqc` or `rocq` in the toolchain pinned by
+///    `INFS_TOOLCHAIN` or `inference-toolchain.toml`
+/// 3. **System PATH** - `coqc`, then the `rocq` driver of Rocq 9
+/// 4. **Managed toolchain** - `coqc` or `rocq` in `~/.inference/toolchains/VERSION/bin`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `COQC_PATH` is set but the path does not exist
+/// - A pinned toolchain is not installed, or its override file is invalid
+/// - No Rocq compiler could be found in any location
+pub fn find_coqc() -> Result<ProofCompiler> {
+    // Priority 1: COQC_PATH environment variable
+    if let Ok(path) = std::env::var(COQC_PATH_ENV) {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            return Ok(ProofCompiler::new(path));
+        }
+        bail!(
+            "COQC_PATH environment variable set to '{}', but file does not exist",
+            path.display()
+        );
+    }
+
+    // Priority 2: Pinned toolchain
+    if let Some(path) = find_in_pinned_toolchain(&["coqc", "rocq"])? {
+        return Ok(ProofCompiler::new(path));
+    }
+
+    // Priority 3: System PATH
+    for name in ["coqc", "rocq"] {
+        if let Ok(path) = which::which(name) {
+            return Ok(ProofCompiler::new(path));
+        }
+    }
+
+    // Priority 4: Managed toolchain
+    for name in ["coqc", "rocq"] {
+        if let Some(path) = find_in_toolchain(name)? {
+            return Ok(ProofCompiler::new(path));
+        }
+    }
+
+    bail!(
+        "Rocq compiler not found.\n\n\
+        coqc (or rocq) is required to check the generated proofs.\n\n\
+        To install:\n  \
+        - Visit: https://rocq-prover.org/install\n  \
+        - Or set COQC_PATH environment variable to the coqc binary path"
+    );
+}
+
+/// A located Rocq compiler and the arguments that make it compile a `.v` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofCompiler {
+    /// Path to the `coqc` or `rocq` binary.
+    pub path: PathBuf,
+    /// Arguments before the file to compile: `compile` for the `rocq` driver.
+    pub args: Vec<&'static str>,
+}
+
+impl ProofCompiler {
+    fn new(path: PathBuf) -> Self {
+        let is_driver = path
+            .file_stem()
+            .is_some_and(|stem| stem.eq_ignore_ascii_case("rocq"));
+        Self {
+            path,
+            args: if is_driver { vec!["compile"] } else { vec![] },
+        }
+    }
+}
+
+/// Returns the path of the first of the binaries `names` in the pinned toolchain.
+///
+/// Returns `None` without a pin, and `None` if the pinned toolchain does not ship any
+/// of `names`, so the search goes on; a pinned toolchain that is not installed is an
+/// error rather than a silent fallback to another version.
+fn find_in_pinned_toolchain(names: &[&str]) -> Result<Option<PathBuf>> {
+    let Some(pin) = find_override()? else {
+        return Ok(None);
+    };
+    let paths = ToolchainPaths::new()?;
+    if !paths.is_version_installed(&pin.version) {
+        bail!(
+            "Toolchain {} is pinned by {} but is not installed.\n\n\
+            To install it, run: infs install {}",
+            pin.version,
+            pin.source,
+            pin.version
+        );
+    }
+    let platform = Platform::detect().context("Failed to detect platform")?;
+    let ext = platform.executable_extension();
+    let bin_dir = paths.toolchain_bin_dir(&pin.version);
+    Ok(names
+        .iter()
+        .map(|name| bin_dir.join(format!("{name}{ext}")))
+        .find(|path| path.exists()))
+}
+
+/// Returns the path of the binary `name` in the default managed toolchain, if installed.
+fn find_in_toolchain(name: &str) -> Result<Option<PathBuf>> {
+    if let Ok(paths) = ToolchainPaths::new()
+        && let Ok(Some(version)) = paths.get_default_version()
+    {
+        let platform = Platform::detect()
+            .with_context(|| format!("Failed to detect platform while searching for {name}"))?;
+        let ext = platform.executable_extension();
+        let path = paths
+            .toolchain_bin_dir(&version)
+            .join(format!("{name}{ext}"));
+
+        if path.exists() {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -126,6 +259,39 @@ mod tests {
         }
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn coqc_path_env_nonexistent_returns_error() {
+        // SAFETY: This test runs in isolation and we restore the env var at the end.
+        unsafe {
+            env::set_var(COQC_PATH_ENV, "/nonexistent/path/to/coqc");
+        }
+
+        let result = find_coqc();
+
+        // SAFETY: Cleanup - restoring previous state
+        unsafe {
+            env::remove_var(COQC_PATH_ENV);
+        }
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("COQC_PATH"));
+        assert!(err.contains("does not exist"));
+    }
+
+    #[test]
+    fn rocq_driver_compiles_with_subcommand() {
+        assert_eq!(
+            ProofCompiler::new(PathBuf::from("/usr/bin/rocq")).args,
+            vec!["compile"]
+        );
+        assert!(
+            ProofCompiler::new(PathBuf::from("/usr/bin/coqc"))
+                .args
+                .is_empty()
+        );
+    }
+
     #[test]
     #[serial_test::serial]
     fn error_message_contains_installation_instructions() {
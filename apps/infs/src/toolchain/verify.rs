@@ -1,14 +1,26 @@
-//! Checksum verification for downloaded toolchain files.
+//! Checksum and signature verification for downloaded toolchain files.
 //!
 //! This module provides SHA256 checksum verification to ensure
-//! downloaded files match their expected hashes.
+//! downloaded files match their expected hashes, and minisign signature
+//! verification to ensure they were published by the Inference maintainers.
+//!
+//! ## Publisher Key
+//!
+//! Every release file is signed with the publisher's minisign key, and the
+//! signature is published next to it as `<file>.minisig`. The release workflow
+//! signs the archives with the secret key; its public key, [`PUBLISHER_PUBLIC_KEY`],
+//! is the only key infs trusts.
 
 use std::io::Read;
 use std::path::Path;
 
 use anyhow::{Context, Result, bail};
+use minisign_verify::{PublicKey, Signature};
 use sha2::{Digest, Sha256};
 
+/// The minisign public key of the release publisher, key ID `56B6C0389BF4755B`.
+pub const PUBLISHER_PUBLIC_KEY: &str = "RWRbdfSbOMC2Vmtmed9zI7pMHqCCHdvTXmJGmw0z/i4XJyHnM4lAsn8m";
+
 /// Verifies that a file matches the expected SHA256 checksum.
 ///
 /// # Arguments
@@ -46,6 +58,81 @@ pub fn verify_checksum(file_path: &Path, expected: &str) -> Result<()> {
     Ok(())
 }
 
+/// Verifies that a file is signed by the publisher key.
+///
+/// # Arguments
+///
+/// * `file_path` - Path to the file to verify
+/// * `signature_path` - Path to its minisign signature (`<file>.minisig`)
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file or signature cannot be read, or the signature is malformed
+/// - The signature was not made by the publisher key for this file
+pub fn verify_signature(file_path: &Path, signature_path: &Path) -> Result<()> {
+    let public_key = parse_public_key(PUBLISHER_PUBLIC_KEY)?;
+    verify_signature_with(&public_key, file_path, signature_path)
+}
+
+/// Parses a minisign public key, either its base64 line or a whole `.pub` file.
+fn parse_public_key(key: &str) -> Result<PublicKey> {
+    let key = key.trim();
+    let parsed = if key.contains('\n') {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+    parsed.map_err(|e| anyhow::anyhow!("{e}"))
+}
+
+fn verify_signature_with(
+    public_key: &PublicKey,
+    file_path: &Path,
+    signature_path: &Path,
+) -> Result<()> {
+    let signature = Signature::from_file(signature_path).map_err(|e| {
+        anyhow::anyhow!("Failed to read signature {}: {e}", signature_path.display())
+    })?;
+    let mut verifier = public_key.verify_stream(&signature).map_err(|e| {
+        anyhow::anyhow!(
+            "Signature verification failed for {}: {e}",
+            file_path.display()
+        )
+    })?;
+
+    let mut file = std::fs::File::open(file_path).with_context(|| {
+        format!(
+            "Failed to open file for signature check: {}",
+            file_path.display()
+        )
+    })?;
+    let mut buffer = [0u8; 8192];
+    loop {
+        let bytes_read = file.read(&mut buffer).with_context(|| {
+            format!(
+                "Failed to read file for signature check: {}",
+                file_path.display()
+            )
+        })?;
+        if bytes_read == 0 {
+            break;
+        }
+        verifier.update(&buffer[..bytes_read]);
+    }
+
+    if let Err(e) = verifier.finalize() {
+        bail!(
+            "Signature verification failed for {}: {e}\n\
+             \n\
+             The file was not signed by the release publisher.\n\
+             It may have been tampered with; it was not installed.",
+            file_path.display()
+        );
+    }
+    Ok(())
+}
+
 /// Computes the SHA256 hash of a file.
 ///
 /// # Arguments
@@ -169,6 +256,73 @@ mod tests {
         std::fs::remove_file(&test_file).ok();
     }
 
+    const TEST_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+
+    /// A prehashed minisign signature of the content `test` by `TEST_PUBLIC_KEY`.
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==
+";
+
+    fn signed_file(dir_name: &str, content: &[u8]) -> (std::path::PathBuf, std::path::PathBuf) {
+        let temp_dir = std::env::temp_dir().join(dir_name);
+        std::fs::create_dir_all(&temp_dir).expect("Should create temp dir");
+        let file = temp_dir.join("test");
+        std::fs::write(&file, content).expect("Should write test content");
+        let signature = temp_dir.join("test.minisig");
+        std::fs::write(&signature, TEST_SIGNATURE).expect("Should write signature");
+        (file, signature)
+    }
+
+    #[test]
+    fn verify_signature_passes_for_signed_file() {
+        let (file, signature) = signed_file("infs_test_signature_pass", b"test");
+        let key = parse_public_key(TEST_PUBLIC_KEY).expect("Should parse key");
+
+        assert!(verify_signature_with(&key, &file, &signature).is_ok());
+    }
+
+    #[test]
+    fn verify_signature_fails_for_modified_file() {
+        let (file, signature) = signed_file("infs_test_signature_fail", b"Test");
+        let key = parse_public_key(TEST_PUBLIC_KEY).expect("Should parse key");
+
+        let error_msg = verify_signature_with(&key, &file, &signature)
+            .unwrap_err()
+            .to_string();
+        assert!(error_msg.contains("Signature verification failed"));
+        assert!(error_msg.contains("not signed by the release publisher"));
+    }
+
+    /// A prehashed minisign signature of the content `inference` by `PUBLISHER_PUBLIC_KEY`.
+    const PUBLISHER_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RURbdfSbOMC2Vms/xZ6GSdFJCbUw8oapfZF2kfJI6hJOLGExmWse7HRmbdlMkZHvYpTPAEqsNuGgetZD/JD56BkgWkrv9bavpQk=
+trusted comment: timestamp:0\tfile:sample\thashed
+J3IaI3GMtppScPYTAS2T7Y/MuDf8Rf/VqhdBIggv/ifdk4EfYU49i2LP4dERyzl58cianyQTjtihhnvfQvgyBA==
+";
+
+    #[test]
+    fn verify_signature_accepts_the_publisher_key() {
+        let temp_dir = std::env::temp_dir().join("infs_test_publisher_signature");
+        std::fs::create_dir_all(&temp_dir).expect("Should create temp dir");
+        let file = temp_dir.join("sample");
+        std::fs::write(&file, b"inference").expect("Should write test content");
+        let signature = temp_dir.join("sample.minisig");
+        std::fs::write(&signature, PUBLISHER_SIGNATURE).expect("Should write signature");
+
+        verify_signature(&file, &signature).unwrap();
+    }
+
+    #[test]
+    fn parse_public_key_accepts_a_public_key_file() {
+        let key = parse_public_key(&format!(
+            "untrusted comment: minisign public key E7620F1842B4E81F\n{TEST_PUBLIC_KEY}\n"
+        ));
+        assert!(key.is_ok());
+        assert!(parse_public_key("not a key").is_err());
+    }
+
     #[test]
     fn compute_sha256_fails_for_nonexistent_file() {
         let result = compute_sha256(Path::new("/nonexistent/file/path"));
@@ -14,13 +14,25 @@
 //! - **Toolchains**: List of installed toolchain versions
 //! - **Doctor**: Health check results
 //! - **Progress**: Download/operation progress display
+//! - **Build log**: Output of `build` and `check`, run in the background
+//! - **Diagnostics**: Errors and warnings of the last build, with their source
+//! - **Verification**: Proof obligations of the project and their status
 //!
 //! ## Features
 //!
-//! - Command history with Up/Down navigation
+//! - Command history with Up/Down navigation, fuzzy search with Ctrl+R, saved
+//!   across sessions
 //! - Tab completion for commands
 //! - Cursor movement with Left/Right arrows
 //! - Toolchain operations (Enter to set as default)
+//! - `build` and `check` run without leaving the TUI, their output streamed into a
+//!   scrollable log with the progress of each compiler phase
+//! - After a failed build, a navigator over its diagnostics, opening the selected one
+//!   in `$EDITOR`
+//! - A verification dashboard, whose proof obligations are updated as `infs verify`
+//!   checks their files
+//! - Themes and key remappings from `tui.toml`, reloaded when it changes, and
+//!   `:theme` to browse the built-in themes
 
 use std::sync::mpsc::Receiver;
 use std::time::Duration;
@@ -29,27 +41,39 @@ use anyhow::{Context, Result};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::Frame;
 
+use super::build_task;
+use super::config::{self, ConfigWatcher, TuiConfig};
+use super::editor;
 use super::install_task;
 use super::menu::Menu;
 use super::state::{
-    DoctorState, InstallProgress, ProgressItem, ProgressState, Screen, ToolchainInfo,
-    ToolchainsState, VersionSelectInfo, VersionSelectState,
+    BuildLogState, BuildProgress, DiagnosticsState, DoctorState, InstallProgress, ProgressItem,
+    ProgressState, Screen, ToolchainInfo, ToolchainsState, VerifyState, VersionSelectInfo,
+    VersionSelectState,
 };
 use super::terminal::TerminalGuard;
 use super::theme::Theme;
-use super::views::{doctor_view, main_view, progress_view, toolchain_view, version_select_view};
-use super::widgets::command_history::CommandHistory;
-use crate::toolchain::ToolchainPaths;
+use super::views::{
+    build_view, diagnostics_view, doctor_view, main_view, progress_view, toolchain_view,
+    verify_view, version_select_view,
+};
+use super::widgets::command_history::{self, CommandHistory, HistorySearch};
 use crate::toolchain::doctor::run_all_checks;
+use crate::toolchain::{CancellationToken, ToolchainPaths};
 
 /// Event polling timeout in milliseconds.
 const POLL_TIMEOUT_MS: u64 = 100;
 
+/// Number of lines scrolled by Page Up and Page Down in the build log.
+const LOG_PAGE_LINES: usize = 10;
+
 /// Known commands for tab completion.
 const KNOWN_COMMANDS: &[&str] = &[
     "build",
+    "check",
     "run",
     "verify",
+    "proofs",
     "new",
     "install",
     "doctor",
@@ -57,6 +81,7 @@ const KNOWN_COMMANDS: &[&str] = &[
     "version",
     "quit",
     "toolchains",
+    "theme",
     "exit",
 ];
 
@@ -86,6 +111,12 @@ pub struct App {
     should_quit: bool,
     /// Theme colors.
     theme: Theme,
+    /// Name of the theme in the gallery.
+    theme_name: String,
+    /// User configuration from `tui.toml`.
+    config: TuiConfig,
+    /// Watcher reloading the configuration when `tui.toml` changes.
+    config_watcher: ConfigWatcher,
     /// Menu state.
     menu: Menu,
     /// Toolchains view state.
@@ -96,18 +127,38 @@ pub struct App {
     progress_state: ProgressState,
     /// Command history.
     command_history: CommandHistory,
+    /// File the command history is saved to.
+    history_path: Option<std::path::PathBuf>,
+    /// Search through the command history, while it is open.
+    history_search: Option<HistorySearch>,
     /// Command to execute after TUI exits (for commands requiring terminal access).
     pending_command: Option<String>,
     /// Override for executable path (used in tests).
     exe_path_override: Option<std::path::PathBuf>,
     /// Receiver for installation progress messages from background task.
     install_receiver: Option<Receiver<InstallProgress>>,
+    /// Token cancelling the installation, taken once it is cancelled.
+    install_cancel: Option<CancellationToken>,
     /// Screen to return to after progress view is dismissed.
     previous_screen: Option<Screen>,
     /// Version select view state.
     version_select_state: VersionSelectState,
     /// Receiver for version loading results from background task.
     version_load_receiver: Option<Receiver<Result<Vec<VersionSelectInfo>, String>>>,
+    /// Token cancelling the version loading.
+    version_load_cancel: Option<CancellationToken>,
+    /// Build log view state.
+    build_log_state: BuildLogState,
+    /// Receiver for the output of a background build or check.
+    build_receiver: Option<Receiver<BuildProgress>>,
+    /// Diagnostics view state.
+    diagnostics_state: DiagnosticsState,
+    /// Verification dashboard state.
+    verify_state: VerifyState,
+    /// Receiver for the output of a background `infs verify`.
+    verify_receiver: Option<Receiver<BuildProgress>>,
+    /// File, line and column to open in the editor, which needs the terminal.
+    pending_editor: Option<(std::path::PathBuf, usize, usize)>,
 }
 
 impl Default for App {
@@ -120,17 +171,30 @@ impl Default for App {
             status_message: String::from("Press ':' to enter a command, 'q' to quit"),
             should_quit: false,
             theme: Theme::detect(),
+            theme_name: Theme::detect_name().to_string(),
+            config: TuiConfig::default(),
+            config_watcher: ConfigWatcher::default(),
             menu: Menu::new(),
             toolchains_state: ToolchainsState::new(),
             doctor_state: DoctorState::new(),
             progress_state: ProgressState::default(),
             command_history: CommandHistory::new(),
+            history_path: None,
+            history_search: None,
             pending_command: None,
             exe_path_override: None,
             install_receiver: None,
+            install_cancel: None,
             previous_screen: None,
             version_select_state: VersionSelectState::new(),
             version_load_receiver: None,
+            version_load_cancel: None,
+            build_log_state: BuildLogState::default(),
+            build_receiver: None,
+            diagnostics_state: DiagnosticsState::default(),
+            verify_state: VerifyState::default(),
+            verify_receiver: None,
+            pending_editor: None,
         }
     }
 }
@@ -159,7 +223,7 @@ impl App {
         }
 
         match self.input_mode {
-            InputMode::Normal => self.handle_normal_key(code),
+            InputMode::Normal => self.handle_normal_key(self.config.keys.translate(code)),
             InputMode::Command => self.handle_command_key(code, modifiers),
         }
     }
@@ -172,6 +236,9 @@ impl App {
             Screen::Doctor => self.handle_doctor_key(code),
             Screen::Progress => self.handle_progress_key(code),
             Screen::VersionSelect => self.handle_version_select_key(code),
+            Screen::BuildLog => self.handle_build_log_key(code),
+            Screen::Diagnostics => self.handle_diagnostics_key(code),
+            Screen::Verify => self.handle_verify_key(code),
         }
     }
 
@@ -186,8 +253,9 @@ impl App {
                 self.command_input.clear();
                 self.cursor_pos = 0;
                 self.command_history.reset_navigation();
-                self.status_message =
-                    String::from("Enter command (Esc to cancel, Tab to complete)");
+                self.status_message = String::from(
+                    "Enter command (Esc to cancel, Tab to complete, Ctrl+R to search history)",
+                );
             }
             KeyCode::Up | KeyCode::Char('k') => {
                 self.menu.up();
@@ -314,6 +382,92 @@ impl App {
         }
     }
 
+    /// Handles key events on the build log screen.
+    fn handle_build_log_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                if self.build_log_state.running {
+                    self.status_message =
+                        String::from("Still running; press Esc again once it finished");
+                } else {
+                    self.build_receiver = None;
+                    self.navigate_to(Screen::Main);
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.build_log_state.scroll_up(1),
+            KeyCode::Down | KeyCode::Char('j') => self.build_log_state.scroll_down(1),
+            KeyCode::PageUp => self.build_log_state.scroll_up(LOG_PAGE_LINES),
+            KeyCode::PageDown => self.build_log_state.scroll_down(LOG_PAGE_LINES),
+            KeyCode::Home | KeyCode::Char('g') => self.build_log_state.scroll_to_top(),
+            KeyCode::End | KeyCode::Char('G') => self.build_log_state.scroll_to_bottom(),
+            KeyCode::Char('r') if !self.build_log_state.running => {
+                self.start_build(self.build_log_state.args.clone());
+            }
+            KeyCode::Char('d') if !self.build_log_state.running => {
+                self.show_diagnostics();
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles key events on the diagnostics screen.
+    fn handle_diagnostics_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.navigate_to(Screen::BuildLog),
+            KeyCode::Up | KeyCode::Char('k') => self.diagnostics_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.diagnostics_state.select_next(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                let location = self.diagnostics_state.selected_diagnostic().and_then(|d| {
+                    let span = d.span?;
+                    Some((d.file.clone()?, span.start_line, span.start_column))
+                });
+                match location {
+                    Some(location) => self.pending_editor = Some(location),
+                    None => {
+                        self.status_message = String::from("This diagnostic has no location");
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Handles key events on the verification dashboard.
+    fn handle_verify_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => {
+                if self.verify_state.running {
+                    self.status_message =
+                        String::from("Still verifying; press Esc again once it finished");
+                } else {
+                    self.verify_receiver = None;
+                    self.navigate_to(Screen::Main);
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.verify_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.verify_state.select_next(),
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(location) = self.verify_state.selected_location() {
+                    self.pending_editor = Some(location);
+                }
+            }
+            KeyCode::Char('r') if !self.verify_state.running => {
+                self.start_verify(self.verify_state.args.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Shows the diagnostics of the last build, if it reported any.
+    fn show_diagnostics(&mut self) {
+        if self.build_log_state.diagnostics.is_empty() {
+            self.status_message = String::from("No diagnostics reported");
+            return;
+        }
+        self.diagnostics_state = DiagnosticsState::new(self.build_log_state.diagnostics.clone());
+        self.navigate_to(Screen::Diagnostics);
+    }
+
     /// Handles key events on the version select screen.
     fn handle_version_select_key(&mut self, code: KeyCode) {
         match code {
@@ -322,6 +476,9 @@ impl App {
                 let return_screen = self.previous_screen.unwrap_or(Screen::Main);
                 self.previous_screen = None;
                 self.version_load_receiver = None;
+                if let Some(cancel) = self.version_load_cancel.take() {
+                    cancel.cancel();
+                }
                 self.navigate_to(return_screen);
             }
             KeyCode::Up | KeyCode::Char('k') => {
@@ -355,12 +512,25 @@ impl App {
         let return_screen = self.previous_screen.unwrap_or(Screen::Main);
         self.previous_screen = None;
         self.install_receiver = None;
+        self.install_cancel = None;
         self.navigate_to(return_screen);
     }
 
     /// Cancels an in-progress installation.
+    ///
+    /// The first call cancels the background task, which stops its downloads, removes
+    /// their partial files and reports [`InstallProgress::Cancelled`]. A second call
+    /// stops waiting for it; the task still cleans up on its own.
     fn cancel_installation(&mut self) {
-        // Drop the receiver to signal cancellation (sender will fail)
+        if let Some(cancel) = self.install_cancel.take() {
+            cancel.cancel();
+            self.progress_state
+                .set_status("Cancelling, removing partial downloads...");
+            self.status_message =
+                String::from("Cancelling... Press Esc again to return without waiting.");
+            return;
+        }
+        // Drop the receiver to stop waiting for the task
         self.install_receiver = None;
         self.progress_state.set_error("Installation cancelled");
         self.status_message = String::from("Installation cancelled. Press Esc to return.");
@@ -404,12 +574,36 @@ impl App {
                 }
                 self.status_message = String::from("Press Enter to install, Esc to go back");
             }
+            Screen::BuildLog => {
+                self.status_message = String::from("Scroll with Up/Down, Esc to go back");
+            }
+            Screen::Diagnostics => {
+                self.status_message =
+                    String::from("Press Enter to open in $EDITOR, Esc to return to the log");
+            }
+            Screen::Verify => {
+                if self.verify_state.args.is_empty() {
+                    self.start_verify(vec![String::from("verify")]);
+                }
+                self.status_message =
+                    String::from("Press Enter to open in $EDITOR, 'r' to re-check");
+            }
         }
     }
 
     /// Handles a key event in command mode.
     fn handle_command_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        if self.history_search.is_some() {
+            self.handle_history_search_key(code, modifiers);
+            return;
+        }
+
         match code {
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.history_search = Some(HistorySearch::new(&self.command_input));
+                self.status_message =
+                    String::from("Type to search, Enter to run, Tab to edit, Esc to cancel");
+            }
             KeyCode::Esc => {
                 self.input_mode = InputMode::Normal;
                 self.command_input.clear();
@@ -460,6 +654,63 @@ impl App {
         }
     }
 
+    /// Handles a key event while searching the command history.
+    fn handle_history_search_key(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let Some(search) = self.history_search.as_mut() else {
+            return;
+        };
+        let count = self.command_history.search(&search.query).len();
+
+        match code {
+            KeyCode::Esc => {
+                self.command_input = std::mem::take(&mut search.saved_input);
+                self.cursor_pos = self.command_input.len();
+                self.history_search = None;
+                self.status_message = String::from("Search cancelled");
+            }
+            KeyCode::Enter | KeyCode::Tab => {
+                let selected = self
+                    .command_history
+                    .search(&search.query)
+                    .get(search.selected)
+                    .map(|found| found.command.to_string());
+                let Some(command) = selected else {
+                    self.status_message = String::from("No matching command");
+                    return;
+                };
+                self.history_search = None;
+                self.command_input = command;
+                self.cursor_pos = self.command_input.len();
+                if code == KeyCode::Enter {
+                    self.execute_command();
+                } else {
+                    self.status_message = String::from(
+                        "Enter command (Esc to cancel, Tab to complete, Ctrl+R to search history)",
+                    );
+                }
+            }
+            KeyCode::Up => search.select_previous(),
+            KeyCode::Down => search.select_next(count),
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                search.select_next(count);
+            }
+            KeyCode::Backspace => search.pop(),
+            KeyCode::Char(c) => search.push(c),
+            _ => {}
+        }
+    }
+
+    /// Loads the command history saved by previous sessions.
+    fn load_history(&mut self) {
+        let Some(path) = &self.history_path else {
+            return;
+        };
+        match CommandHistory::load(path) {
+            Ok(history) => self.command_history = history,
+            Err(e) => self.status_message = format!("{e:#}"),
+        }
+    }
+
     /// Inserts a character at the cursor position.
     fn insert_char(&mut self, c: char) {
         self.command_input.insert(self.cursor_pos, c);
@@ -588,8 +839,9 @@ impl App {
                 // Single match, complete it
                 self.command_input = (*matches[0]).to_string();
                 self.cursor_pos = self.command_input.len();
-                self.status_message =
-                    String::from("Enter command (Esc to cancel, Tab to complete)");
+                self.status_message = String::from(
+                    "Enter command (Esc to cancel, Tab to complete, Ctrl+R to search history)",
+                );
             }
             _ => {
                 // Multiple matches, show them
@@ -613,9 +865,41 @@ impl App {
             return;
         }
 
+        // Build, check and proofs take arguments, such as a path, kept as typed
+        let mut words = original_input.split_whitespace();
+        let first = words.next().map(str::to_lowercase);
+        let rest: Vec<String> = words.map(String::from).collect();
+        let build_args: Option<Vec<String>> = match first.as_deref() {
+            Some(name @ ("build" | "check")) => Some(
+                std::iter::once(name.to_string())
+                    .chain(rest.clone())
+                    .collect(),
+            ),
+            _ => None,
+        };
+        let verify_args: Option<Vec<String>> = (first.as_deref() == Some("proofs")).then(|| {
+            std::iter::once(String::from("verify"))
+                .chain(rest)
+                .collect()
+        });
+
         // Add to history (non-empty commands only)
         self.command_history.push(original_input);
         self.command_history.reset_navigation();
+        if let Some(path) = &self.history_path
+            && let Err(e) = self.command_history.save(path)
+        {
+            self.status_message = format!("{e:#}");
+        }
+
+        if let Some(args) = build_args {
+            self.start_build(args);
+            return;
+        }
+        if let Some(args) = verify_args {
+            self.start_verify(args);
+            return;
+        }
 
         match command.as_str() {
             "q" | "quit" | "exit" => {
@@ -628,7 +912,7 @@ impl App {
                 self.navigate_to(Screen::Doctor);
             }
             // Commands that need terminal access - exit TUI and run
-            "build" | "new" | "install" | "run" | "verify" => {
+            "new" | "install" | "run" | "verify" => {
                 self.pending_command = Some(command);
                 self.should_quit = true;
             }
@@ -639,19 +923,74 @@ impl App {
             "version" => {
                 self.run_quick_command(&["version"]);
             }
+            "theme" => {
+                self.set_theme(Theme::next_in_gallery(&self.theme_name).to_string());
+            }
+            _ if command.starts_with("theme ") => {
+                let name = command["theme ".len()..].trim().to_string();
+                self.set_theme(name);
+            }
             _ => {
                 self.status_message = format!("Unknown command: {command}");
             }
         }
     }
 
-    /// Runs a quick command via subprocess and displays output in status message.
-    fn run_quick_command(&mut self, args: &[&str]) {
-        let exe = self
-            .exe_path_override
+    /// Switches to the gallery theme called `name`, with the color overrides of
+    /// `tui.toml`, until the configuration changes.
+    fn set_theme(&mut self, name: String) {
+        match self.config.theme_named(&name) {
+            Some(theme) => {
+                self.theme = theme;
+                self.status_message =
+                    format!("Theme: {name} (set `theme = \"{name}\"` in tui.toml to keep it)");
+                self.theme_name = name;
+            }
+            None => {
+                self.status_message = format!(
+                    "Unknown theme: {name}; expected one of {}",
+                    Theme::GALLERY.join(", ")
+                );
+            }
+        }
+    }
+
+    /// Reloads the configuration if `tui.toml` changed since it was last loaded.
+    fn poll_config_reload(&mut self) {
+        if self.config_watcher.changed() {
+            self.reload_config(true);
+        }
+    }
+
+    /// Loads the configuration from `tui.toml` and applies it, keeping the current
+    /// one if the file is invalid.
+    fn reload_config(&mut self, announce: bool) {
+        match self.config_watcher.load() {
+            Ok(config) => {
+                self.theme = config.theme();
+                self.theme_name.clone_from(&config.theme);
+                self.config = config;
+                if announce {
+                    self.status_message = format!("Reloaded {}", config::CONFIG_FILE);
+                }
+            }
+            Err(e) => {
+                self.status_message = format!("{e:#}");
+            }
+        }
+    }
+
+    /// Returns the path of the infs executable, to run commands as subprocesses.
+    fn exe_path(&self) -> std::path::PathBuf {
+        self.exe_path_override
             .clone()
             .or_else(|| std::env::current_exe().ok())
-            .unwrap_or_else(|| std::path::PathBuf::from("infs"));
+            .unwrap_or_else(|| std::path::PathBuf::from("infs"))
+    }
+
+    /// Runs a quick command via subprocess and displays output in status message.
+    fn run_quick_command(&mut self, args: &[&str]) {
+        let exe = self.exe_path();
         match std::process::Command::new(&exe).args(args).output() {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -737,6 +1076,8 @@ impl App {
 
         let (tx, rx) = mpsc::channel();
         self.version_load_receiver = Some(rx);
+        let cancel = CancellationToken::new();
+        self.version_load_cancel = Some(cancel.clone());
         self.version_select_state.loading = true;
         self.version_select_state.error = None;
 
@@ -754,7 +1095,8 @@ impl App {
 
                 let platform =
                     Platform::detect().map_err(|e| format!("Platform detection failed: {e}"))?;
-                let manifest = fetch_manifest()
+                let manifest = cancel
+                    .run(fetch_manifest())
                     .await
                     .map_err(|e| format!("Failed to fetch manifest: {e}"))?;
 
@@ -803,6 +1145,7 @@ impl App {
                 }
             }
             self.version_load_receiver = None;
+            self.version_load_cancel = None;
         }
     }
 
@@ -820,14 +1163,17 @@ impl App {
 
         let (tx, rx) = mpsc::channel();
         self.install_receiver = Some(rx);
+        let cancel = CancellationToken::new();
+        self.install_cancel = Some(cancel.clone());
 
         // Set up progress state
         self.progress_state = ProgressState::new("Installing Toolchain");
         self.progress_state.set_status("Starting installation...");
 
-        // Add a progress item that will be updated with current phase
-        let progress_item = ProgressItem::new("Initializing...");
-        self.progress_state.add_item(progress_item);
+        // One progress item per component, updated concurrently as they download
+        for component in install_task::COMPONENTS {
+            self.progress_state.add_item(ProgressItem::new(*component));
+        }
 
         // Remember current screen to return to
         self.previous_screen = Some(self.screen);
@@ -835,7 +1181,7 @@ impl App {
         // Spawn installation task on a separate thread with its own tokio runtime
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-            rt.block_on(install_task::run_installation(version, tx));
+            rt.block_on(install_task::run_installation(version, cancel, tx));
         });
 
         // Navigate to progress screen
@@ -843,6 +1189,123 @@ impl App {
         self.status_message = String::from("Installing... Press Esc to cancel.");
     }
 
+    /// Starts `infs` with `args` (`build` or `check` and their arguments) in the
+    /// background and navigates to the build log screen.
+    ///
+    /// Spawns a thread that runs the command and sends its output through a channel,
+    /// which the event loop polls with `poll_build_output`. Unless `args` select a
+    /// message format, diagnostics are requested as JSON, for the diagnostics view.
+    fn start_build(&mut self, args: Vec<String>) {
+        use std::sync::mpsc;
+
+        let (tx, rx) = mpsc::channel();
+        self.build_receiver = Some(rx);
+        self.build_log_state = BuildLogState::new(args.clone());
+
+        let mut args = args;
+        if !args.iter().any(|arg| arg.starts_with("--message-format")) {
+            args.extend([String::from("--message-format"), String::from("json")]);
+        }
+
+        let exe = self.exe_path();
+        std::thread::spawn(move || build_task::run_command(&exe, &args, &tx));
+
+        self.navigate_to(Screen::BuildLog);
+    }
+
+    /// Starts `infs` with `args` (`verify` and an optional project path) in the
+    /// background and shows the verification dashboard.
+    ///
+    /// The dashboard lists the proof files the project already has, keeping the
+    /// selection of the previous run, and updates them as their results arrive through
+    /// the channel polled by `poll_verify_output`.
+    fn start_verify(&mut self, args: Vec<String>) {
+        use std::sync::mpsc;
+
+        let (tx, rx) = mpsc::channel();
+        self.verify_receiver = Some(rx);
+        let selected = self.verify_state.selected;
+        self.verify_state = VerifyState::new(args.clone());
+        self.verify_state.select(selected);
+
+        let exe = self.exe_path();
+        std::thread::spawn(move || build_task::run_command(&exe, &args, &tx));
+
+        self.screen = Screen::Verify;
+        self.status_message = String::from("Verifying... Press Enter to open in $EDITOR.");
+    }
+
+    /// Polls the verify output channel and updates the verification dashboard.
+    ///
+    /// This method should be called in each iteration of the TUI event loop.
+    fn poll_verify_output(&mut self) {
+        let Some(receiver) = self.verify_receiver.as_ref() else {
+            return;
+        };
+
+        let mut finished = false;
+        while let Ok(msg) = receiver.try_recv() {
+            match msg {
+                BuildProgress::Line { text } => self.verify_state.push_line(text),
+                BuildProgress::Finished { code } => {
+                    self.verify_state.finish(code);
+                    finished = true;
+                }
+                BuildProgress::Failed { error } => {
+                    self.verify_state.fail(error);
+                    finished = true;
+                }
+            }
+        }
+
+        if finished {
+            self.verify_receiver = None;
+            self.status_message = if self.verify_state.exit_code == Some(0) {
+                String::from("Verified. Press 'r' to re-check, Esc to go back.")
+            } else {
+                String::from("Verification failed. Press 'r' to re-check, Esc to go back.")
+            };
+        }
+    }
+
+    /// Polls the build output channel and updates the build log state.
+    ///
+    /// This method should be called in each iteration of the TUI event loop.
+    fn poll_build_output(&mut self) {
+        let Some(receiver) = self.build_receiver.as_ref() else {
+            return;
+        };
+
+        let mut finished = false;
+        while let Ok(msg) = receiver.try_recv() {
+            match msg {
+                BuildProgress::Line { text } => self.build_log_state.push_line(text),
+                BuildProgress::Finished { code } => {
+                    self.build_log_state.finish(code);
+                    finished = true;
+                }
+                BuildProgress::Failed { error } => {
+                    self.build_log_state.fail(error);
+                    finished = true;
+                }
+            }
+        }
+
+        if finished {
+            self.build_receiver = None;
+            if self.build_log_state.succeeded() {
+                self.status_message = String::from("Done. Press 'r' to run again, Esc to go back.");
+            } else if self.screen == Screen::BuildLog
+                && !self.build_log_state.diagnostics.is_empty()
+            {
+                self.show_diagnostics();
+            } else {
+                self.status_message =
+                    String::from("Failed. Press 'r' to run again, Esc to go back.");
+            }
+        }
+    }
+
     /// Polls the installation progress channel and updates the progress state.
     ///
     /// This method should be called in each iteration of the TUI event loop.
@@ -865,20 +1328,25 @@ impl App {
             match msg {
                 InstallProgress::PhaseStarted { phase } => {
                     self.progress_state.set_status(format!("{phase}..."));
-                    // Update progress item description to show current phase
-                    if let Some(item) = self.progress_state.items.first_mut() {
-                        item.description = phase;
-                    }
                 }
-                InstallProgress::DownloadStarted { total } => {
-                    if let Some(item) = self.progress_state.items.first_mut() {
+                InstallProgress::ComponentStarted { component, total } => {
+                    if let Some(item) = self.progress_state.items.get_mut(component) {
                         item.total = total;
                         item.start();
                     }
                 }
-                InstallProgress::DownloadProgress { downloaded, speed } => {
-                    if let Some(item) = self.progress_state.items.first_mut() {
-                        item.update_with_speed(downloaded, speed);
+                InstallProgress::ComponentProgress {
+                    component,
+                    current,
+                    speed,
+                } => {
+                    if let Some(item) = self.progress_state.items.get_mut(component) {
+                        item.update_with_speed(current, speed);
+                    }
+                }
+                InstallProgress::ComponentCompleted { component } => {
+                    if let Some(item) = self.progress_state.items.get_mut(component) {
+                        item.complete();
                     }
                 }
                 InstallProgress::PhaseCompleted { phase } => {
@@ -888,14 +1356,20 @@ impl App {
                     self.progress_state.complete();
                     self.progress_state
                         .set_status(format!("Toolchain v{version} installed successfully"));
-                    if let Some(item) = self.progress_state.items.first_mut() {
-                        item.description = format!("Installed v{version}");
+                    for item in &mut self.progress_state.items {
                         item.complete();
                     }
                     self.status_message =
                         String::from("Installation complete! Press Esc to return.");
                     clear_receiver = true;
                 }
+                InstallProgress::Cancelled => {
+                    self.progress_state.set_error("Installation cancelled");
+                    self.status_message = String::from(
+                        "Installation cancelled, partial downloads removed. Press Esc to return.",
+                    );
+                    clear_receiver = true;
+                }
                 InstallProgress::Failed { error } => {
                     self.progress_state.set_error(&error);
                     self.status_message = String::from("Installation failed. Press Esc to return.");
@@ -906,6 +1380,7 @@ impl App {
 
         if clear_receiver {
             self.install_receiver = None;
+            self.install_cancel = None;
         }
     }
 }
@@ -922,12 +1397,21 @@ impl App {
 /// - Drawing fails
 /// - Event polling fails
 pub fn run_app(guard: &mut TerminalGuard) -> Result<Option<String>> {
-    let mut app = App::default();
+    let mut app = App {
+        config_watcher: ConfigWatcher::new(config::config_path()),
+        history_path: command_history::history_path(),
+        ..App::default()
+    };
+    app.reload_config(false);
+    app.load_history();
 
     loop {
         // Poll for async operations (non-blocking)
+        app.poll_config_reload();
         app.poll_install_progress();
         app.poll_version_loading();
+        app.poll_build_output();
+        app.poll_verify_output();
 
         guard
             .terminal
@@ -941,6 +1425,14 @@ pub fn run_app(guard: &mut TerminalGuard) -> Result<Option<String>> {
             app.handle_key(key.code, key.modifiers);
         }
 
+        if let Some((file, line, column)) = app.pending_editor.take() {
+            let result = guard.suspend(|| editor::open(&file, line, column))?;
+            app.status_message = match result {
+                Ok(()) => format!("Edited {}", file.display()),
+                Err(e) => format!("{e:#}"),
+            };
+        }
+
         if app.should_quit {
             break;
         }
@@ -965,6 +1457,15 @@ fn render(app: &App, frame: &mut Frame) {
                 &app.status_message,
                 app.cursor_display_pos(),
             );
+            if let Some(search) = &app.history_search {
+                command_history::render_search(
+                    frame,
+                    area,
+                    &app.theme,
+                    &app.command_history,
+                    search,
+                );
+            }
         }
         Screen::Toolchains => {
             toolchain_view::render(frame, area, &app.theme, &app.toolchains_state);
@@ -978,6 +1479,15 @@ fn render(app: &App, frame: &mut Frame) {
         Screen::VersionSelect => {
             version_select_view::render(frame, area, &app.theme, &app.version_select_state);
         }
+        Screen::BuildLog => {
+            build_view::render(frame, area, &app.theme, &app.build_log_state);
+        }
+        Screen::Diagnostics => {
+            diagnostics_view::render(frame, area, &app.theme, &app.diagnostics_state);
+        }
+        Screen::Verify => {
+            verify_view::render(frame, area, &app.theme, &app.verify_state);
+        }
     }
 }
 
@@ -1198,15 +1708,230 @@ mod tests {
     #[test]
     fn execute_terminal_command_sets_pending_and_quits() {
         let mut app = App {
-            command_input: String::from("build"),
-            cursor_pos: 5,
+            command_input: String::from("new"),
+            cursor_pos: 3,
             ..App::default()
         };
 
         app.execute_command();
 
         assert!(app.should_quit);
-        assert_eq!(app.pending_command, Some(String::from("build")));
+        assert_eq!(app.pending_command, Some(String::from("new")));
+    }
+
+    /// Polls the build output until the command exits.
+    fn wait_for_build(app: &mut App) {
+        for _ in 0..500 {
+            app.poll_build_output();
+            if !app.build_log_state.running {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("build did not finish");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn proofs_command_runs_verify_into_dashboard() {
+        let temp = std::env::temp_dir().join(format!("infs-tui-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(temp.join("proofs")).unwrap();
+        std::fs::write(
+            temp.join("Inference.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            temp.join("proofs/demo.v"),
+            "Lemma a : True.\nProof. exact I. Qed.\n",
+        )
+        .unwrap();
+        let fake_infs = temp.join("fake-infs");
+        std::fs::write(
+            &fake_infs,
+            "#!/bin/sh\necho \"Verifying demo v0.1.0\"\necho \"PASS proofs/demo.v\"\nexit 0\n",
+        )
+        .unwrap();
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&fake_infs, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let mut app = App {
+            command_input: format!("proofs {}", temp.display()),
+            ..App::default()
+        };
+        app.set_exe_path_override(fake_infs);
+        app.execute_command();
+        assert_eq!(app.screen, Screen::Verify);
+        assert_eq!(app.verify_state.counts(), (0, 0, 0, 1));
+
+        for _ in 0..500 {
+            app.poll_verify_output();
+            if !app.verify_state.running {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!app.verify_state.running);
+        assert_eq!(app.verify_state.counts(), (1, 0, 0, 0));
+
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+        let (file, line, _) = app.pending_editor.take().unwrap();
+        assert_eq!(file, temp.join("proofs/demo.v"));
+        assert_eq!(line, 1);
+
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert!(app.verify_state.running);
+        for _ in 0..500 {
+            app.poll_verify_output();
+            if !app.verify_state.running {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = std::fs::remove_dir_all(&temp);
+
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::Main);
+    }
+
+    #[test]
+    fn theme_command_cycles_and_selects_gallery_themes() {
+        let mut app = App {
+            theme_name: String::from("dark"),
+            ..App::default()
+        };
+
+        app.command_input = String::from("theme");
+        app.execute_command();
+        assert_eq!(app.theme_name, "light");
+        assert_eq!(app.theme.text, Theme::light().text);
+
+        app.command_input = String::from("theme gruvbox");
+        app.execute_command();
+        assert_eq!(app.theme_name, "gruvbox");
+        assert!(app.status_message.contains("theme = \"gruvbox\""));
+
+        app.command_input = String::from("theme neon");
+        app.execute_command();
+        assert_eq!(app.theme_name, "gruvbox");
+        assert!(app.status_message.starts_with("Unknown theme: neon"));
+    }
+
+    #[test]
+    fn remapped_keys_act_as_default_keys_in_normal_mode() {
+        let mut app = App {
+            config: TuiConfig::parse("[keys]\ndown = \"s\"\ncommand = \"/\"\n").unwrap(),
+            ..App::default()
+        };
+
+        app.handle_key(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(app.menu.selected(), 1);
+
+        app.handle_key(KeyCode::Char('/'), KeyModifiers::NONE);
+        assert_eq!(app.input_mode, InputMode::Command);
+        app.handle_key(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(app.command_input, "s");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn execute_build_command_streams_output_into_log() {
+        let mut app = App {
+            command_input: String::from("build src/Main.inf --parse"),
+            cursor_pos: 26,
+            ..App::default()
+        };
+        app.set_exe_path_override(std::path::PathBuf::from("/bin/echo"));
+
+        app.execute_command();
+
+        assert!(!app.should_quit);
+        assert!(app.pending_command.is_none());
+        assert_eq!(app.screen, Screen::BuildLog);
+        assert_eq!(app.build_log_state.title, "infs build src/Main.inf --parse");
+
+        wait_for_build(&mut app);
+        assert!(app.build_log_state.succeeded());
+        assert_eq!(
+            app.build_log_state.lines[0].text,
+            "build src/Main.inf --parse --message-format json"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn build_log_keys_scroll_rerun_and_return() {
+        let mut app = App::default();
+        app.set_exe_path_override(std::path::PathBuf::from("/bin/false"));
+        app.start_build(vec![String::from("check")]);
+
+        // Esc does not leave while the command runs
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::BuildLog);
+
+        wait_for_build(&mut app);
+        assert!(!app.build_log_state.succeeded());
+        assert_eq!(app.build_log_state.exit_code, Some(1));
+
+        app.build_log_state.push_line("one");
+        app.build_log_state.push_line("two");
+        app.handle_key(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(app.build_log_state.scroll, 1);
+        app.handle_key(KeyCode::Char('G'), KeyModifiers::NONE);
+        assert_eq!(app.build_log_state.scroll, 0);
+
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert!(app.build_log_state.running);
+        assert!(app.build_log_state.lines.is_empty());
+        wait_for_build(&mut app);
+
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::Main);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_build_with_diagnostics_opens_navigator() {
+        let temp = std::env::temp_dir().join(format!("infs-tui-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&temp).unwrap();
+        let fake_infs = temp.join("fake-infs");
+        std::fs::write(
+            &fake_infs,
+            "#!/bin/sh\necho \"$*\" >&2\necho '{\"severity\":\"error\",\"code\":\"E0001\",\"message\":\"type mismatch\",\"file\":\"main.inf\",\"spans\":[{\"start_line\":2,\"start_column\":5,\"end_line\":2,\"end_column\":9}]}'\nexit 1\n",
+        )
+        .unwrap();
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&fake_infs, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let mut app = App::default();
+        app.set_exe_path_override(fake_infs);
+        app.start_build(vec![String::from("build")]);
+        wait_for_build(&mut app);
+        let _ = std::fs::remove_dir_all(&temp);
+
+        assert!(
+            app.build_log_state
+                .lines
+                .iter()
+                .any(|line| line.text == "build --message-format json")
+        );
+        assert_eq!(app.screen, Screen::Diagnostics);
+        assert_eq!(app.diagnostics_state.diagnostics.len(), 1);
+
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(
+            app.pending_editor,
+            Some((std::path::PathBuf::from("main.inf"), 2, 5))
+        );
+
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::BuildLog);
+        app.handle_key(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::Diagnostics);
     }
 
     #[test]
@@ -1480,6 +2205,77 @@ mod tests {
         assert_eq!(app.command_input, "doctor");
     }
 
+    #[test]
+    fn history_search_reruns_selected_command() {
+        let mut app = App::default();
+        for command in ["theme light", "doctor", "theme dark"] {
+            app.input_mode = InputMode::Command;
+            app.command_input = String::from(command);
+            app.execute_command();
+        }
+        assert_eq!(app.theme_name, "dark");
+
+        app.input_mode = InputMode::Command;
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        for c in "thm".chars() {
+            app.handle_key(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+
+        assert!(app.history_search.is_none());
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.theme_name, "light");
+    }
+
+    #[test]
+    fn history_search_tab_edits_and_esc_restores_input() {
+        let mut app = App::default();
+        app.command_history
+            .push(String::from("build src/main.inf --parse"));
+        app.input_mode = InputMode::Command;
+        app.command_input = String::from("bui");
+        app.cursor_pos = 3;
+
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        app.handle_key(KeyCode::Char('x'), KeyModifiers::NONE);
+        app.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(app.status_message.contains("No matching"));
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert!(app.history_search.is_none());
+        assert_eq!(app.command_input, "bui");
+        assert_eq!(app.input_mode, InputMode::Command);
+
+        app.handle_key(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        app.handle_key(KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.command_input, "build src/main.inf --parse");
+        assert_eq!(app.cursor_pos, app.command_input.len());
+        assert_eq!(app.input_mode, InputMode::Command);
+    }
+
+    #[test]
+    fn command_history_is_saved_and_loaded() {
+        let dir = std::env::temp_dir().join(format!("infs-history-{}", rand::random::<u64>()));
+        let path = dir.join(command_history::HISTORY_FILE);
+        let mut app = App {
+            history_path: Some(path.clone()),
+            ..App::default()
+        };
+        app.input_mode = InputMode::Command;
+        app.command_input = String::from("theme light");
+        app.execute_command();
+
+        let mut next = App {
+            history_path: Some(path),
+            ..App::default()
+        };
+        next.load_history();
+        next.input_mode = InputMode::Command;
+        next.handle_key(KeyCode::Up, KeyModifiers::NONE);
+        assert_eq!(next.command_input, "theme light");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn esc_from_progress_when_completed() {
         let mut app = App {
@@ -1609,8 +2405,9 @@ mod tests {
         app.progress_state.add_item(ProgressItem::new("Download"));
 
         // Send a download progress message
-        tx.send(InstallProgress::DownloadProgress {
-            downloaded: 512,
+        tx.send(InstallProgress::ComponentProgress {
+            component: 0,
+            current: 512,
             speed: 1024,
         })
         .expect("Should send");
@@ -1622,6 +2419,59 @@ mod tests {
         assert_eq!(item.speed_bytes_per_sec, Some(1024));
     }
 
+    #[test]
+    fn poll_install_progress_updates_components_separately() {
+        use std::sync::mpsc;
+
+        let mut app = App::default();
+        let (tx, rx) = mpsc::channel();
+        app.start_installation(None);
+        app.install_receiver = Some(rx);
+        let names: Vec<&str> = app
+            .progress_state
+            .items
+            .iter()
+            .map(|item| item.description.as_str())
+            .collect();
+        assert_eq!(names, install_task::COMPONENTS);
+
+        for message in [
+            InstallProgress::ComponentStarted {
+                component: 0,
+                total: 1000,
+            },
+            InstallProgress::ComponentStarted {
+                component: 1,
+                total: 100,
+            },
+            InstallProgress::ComponentProgress {
+                component: 0,
+                current: 250,
+                speed: 50,
+            },
+            InstallProgress::ComponentProgress {
+                component: 1,
+                current: 100,
+                speed: 20,
+            },
+            InstallProgress::ComponentCompleted { component: 1 },
+            InstallProgress::PhaseStarted {
+                phase: String::from("Downloading toolchain v0.1.0"),
+            },
+        ] {
+            tx.send(message).unwrap();
+        }
+        app.poll_install_progress();
+
+        let items = &app.progress_state.items;
+        assert_eq!((items[0].current, items[0].total), (250, 1000));
+        assert!(!items[0].completed);
+        assert!(items[1].completed);
+        assert_eq!(items[2].current, 0);
+        assert_eq!(items[0].description, "Toolchain archive");
+        assert!(app.progress_state.status.contains("Downloading toolchain"));
+    }
+
     #[test]
     fn poll_install_progress_handles_completion() {
         use std::sync::mpsc;
@@ -1684,6 +2534,48 @@ mod tests {
         assert!(app.install_receiver.is_none());
     }
 
+    #[test]
+    fn cancel_installation_cancels_task_then_waits_for_cleanup() {
+        use std::sync::mpsc;
+
+        let mut app = App::default();
+        let (tx, rx) = mpsc::channel::<InstallProgress>();
+        let cancel = CancellationToken::new();
+        app.install_receiver = Some(rx);
+        app.install_cancel = Some(cancel.clone());
+        app.progress_state = ProgressState::new("Test");
+        app.screen = Screen::Progress;
+
+        app.handle_key(KeyCode::Esc, KeyModifiers::NONE);
+        assert!(cancel.is_cancelled());
+        assert!(!app.progress_state.completed);
+        assert!(app.install_receiver.is_some());
+
+        tx.send(InstallProgress::Cancelled).unwrap();
+        app.poll_install_progress();
+        assert!(app.progress_state.completed);
+        assert!(app.progress_state.error.is_some());
+        assert!(app.install_receiver.is_none());
+        assert!(app.status_message.contains("partial downloads removed"));
+    }
+
+    #[test]
+    fn cancel_installation_twice_stops_waiting() {
+        use std::sync::mpsc;
+
+        let mut app = App::default();
+        let (_tx, rx) = mpsc::channel::<InstallProgress>();
+        app.install_receiver = Some(rx);
+        app.install_cancel = Some(CancellationToken::new());
+        app.progress_state = ProgressState::new("Test");
+
+        app.cancel_installation();
+        app.cancel_installation();
+
+        assert!(app.progress_state.completed);
+        assert!(app.install_receiver.is_none());
+    }
+
     #[test]
     fn cancel_installation_sets_error() {
         use std::sync::mpsc;
@@ -0,0 +1,113 @@
+//! Background build task for TUI integration.
+//!
+//! This module runs `infs build` or `infs check` as a subprocess and streams its
+//! output to the TUI through a channel, line by line, so the TUI stays responsive
+//! while infc runs instead of handing the terminal over to the command.
+//!
+//! The subprocess writes to pipes, so it prints plain text rather than starting a TUI
+//! of its own. Its stdout and stderr are read on separate threads and interleaved in
+//! the order the lines arrive.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+
+use super::state::BuildProgress;
+
+/// Runs `exe` with `args`, sending every line it prints and then its exit code.
+///
+/// Blocks until the subprocess exits; call it from a background thread.
+pub fn run_command(exe: &Path, args: &[String], tx: &Sender<BuildProgress>) {
+    let child = Command::new(exe)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = tx.send(BuildProgress::Failed {
+                error: format!("Failed to run {}: {e}", exe.display()),
+            });
+            return;
+        }
+    };
+
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        let tx = tx.clone();
+        std::thread::spawn(move || send_lines(stderr, &tx))
+    });
+    if let Some(stdout) = child.stdout.take() {
+        send_lines(stdout, tx);
+    }
+    if let Some(reader) = stderr_reader {
+        let _ = reader.join();
+    }
+
+    let _ = match child.wait() {
+        Ok(status) => tx.send(BuildProgress::Finished {
+            code: status.code(),
+        }),
+        Err(e) => tx.send(BuildProgress::Failed {
+            error: format!("Failed to wait for {}: {e}", exe.display()),
+        }),
+    };
+}
+
+/// Sends every line read from `output` until it is closed.
+fn send_lines(output: impl Read, tx: &Sender<BuildProgress>) {
+    for line in BufReader::new(output).lines() {
+        let Ok(text) = line else {
+            break;
+        };
+        if tx.send(BuildProgress::Line { text }).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[cfg(unix)]
+    #[test]
+    fn run_command_streams_lines_then_exit_code() {
+        let (tx, rx) = mpsc::channel();
+        run_command(
+            Path::new("/bin/sh"),
+            &[
+                String::from("-c"),
+                String::from("echo Parsed: main.inf; echo 'error: oops' >&2; exit 3"),
+            ],
+            &tx,
+        );
+        drop(tx);
+
+        let messages: Vec<BuildProgress> = rx.iter().collect();
+        let lines: Vec<&str> = messages
+            .iter()
+            .filter_map(|message| match message {
+                BuildProgress::Line { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(lines.contains(&"Parsed: main.inf"));
+        assert!(lines.contains(&"error: oops"));
+        assert!(matches!(
+            messages.last(),
+            Some(BuildProgress::Finished { code: Some(3) })
+        ));
+    }
+
+    #[test]
+    fn run_command_reports_missing_executable() {
+        let (tx, rx) = mpsc::channel();
+        run_command(Path::new("/nonexistent/infs"), &[], &tx);
+
+        assert!(matches!(rx.recv(), Ok(BuildProgress::Failed { .. })));
+    }
+}
@@ -0,0 +1,372 @@
+//! User configuration of the TUI.
+//!
+//! `tui.toml` in the toolchain directory (`~/.inference` or `INFERENCE_HOME`) picks a
+//! theme from the gallery in [`super::theme`], overrides its colors and remaps keys:
+//!
+//! ```toml
+//! theme = "gruvbox"
+//!
+//! [colors]
+//! highlight = "#fabd2f"
+//! error = "lightred"
+//!
+//! [keys]
+//! up = "w"
+//! down = ["s", "n"]
+//! quit = "x"
+//! ```
+//!
+//! Colors are names (`red`, `lightblue`, ...), `#rrggbb` or ANSI indexes (`0`-`255`),
+//! for any color of [`Theme`]. A remapped key acts as the default key of its action on
+//! every screen, in addition to it; see [`ACTIONS`]. Keys are a single character or
+//! one of `up`, `down`, `left`, `right`, `enter`, `esc`, `tab`, `backspace`, `home`,
+//! `end`, `pageup`, `pagedown`, `space` and `f1`-`f12`.
+//!
+//! Without the file, the theme is detected from the terminal and no keys are
+//! remapped. The TUI reloads the file whenever it changes.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::KeyCode;
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use super::theme::Theme;
+use crate::toolchain::ToolchainPaths;
+
+/// Name of the TUI configuration file.
+pub const CONFIG_FILE: &str = "tui.toml";
+
+/// Actions that keys can be remapped to, with their default key.
+pub const ACTIONS: &[(&str, KeyCode)] = &[
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("page_up", KeyCode::PageUp),
+    ("page_down", KeyCode::PageDown),
+    ("top", KeyCode::Home),
+    ("bottom", KeyCode::End),
+    ("select", KeyCode::Enter),
+    ("back", KeyCode::Esc),
+    ("quit", KeyCode::Char('q')),
+    ("command", KeyCode::Char(':')),
+    ("refresh", KeyCode::Char('r')),
+    ("install", KeyCode::Char('i')),
+    ("diagnostics", KeyCode::Char('d')),
+    ("edit", KeyCode::Char('e')),
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    theme: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+    #[serde(default)]
+    keys: HashMap<String, KeyList>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Key remappings, from the key pressed to the default key of its action.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeyMap {
+    keys: HashMap<KeyCode, KeyCode>,
+}
+
+impl KeyMap {
+    /// Returns the default key of the action `code` is remapped to, or `code` itself.
+    #[must_use]
+    pub fn translate(&self, code: KeyCode) -> KeyCode {
+        self.keys.get(&code).copied().unwrap_or(code)
+    }
+}
+
+/// The TUI configuration: a theme, color overrides and key remappings.
+#[derive(Debug, Clone)]
+pub struct TuiConfig {
+    /// Name of the theme in the gallery.
+    pub theme: String,
+    /// Colors overriding those of the theme, by name.
+    colors: Vec<(String, Color)>,
+    /// Key remappings.
+    pub keys: KeyMap,
+}
+
+impl Default for TuiConfig {
+    fn default() -> Self {
+        Self {
+            theme: Theme::detect_name().to_string(),
+            colors: Vec::new(),
+            keys: KeyMap::default(),
+        }
+    }
+}
+
+impl TuiConfig {
+    /// Loads the configuration from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is invalid.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Parses a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not valid TOML, or names an unknown theme,
+    /// color, action or key.
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: ConfigFile = toml::from_str(text)?;
+        let mut config = Self::default();
+
+        if let Some(theme) = file.theme {
+            if Theme::named(&theme).is_none() {
+                bail!(
+                    "Unknown theme `{theme}`; expected one of {}",
+                    Theme::GALLERY.join(", ")
+                );
+            }
+            config.theme = theme;
+        }
+
+        let mut colors: Vec<(String, String)> = file.colors.into_iter().collect();
+        colors.sort();
+        for (name, value) in colors {
+            if Theme::dark().color_mut(&name).is_none() {
+                bail!("Unknown color `{name}`");
+            }
+            let color = Color::from_str(&value)
+                .map_err(|_| anyhow::anyhow!("Invalid value `{value}` for color `{name}`"))?;
+            config.colors.push((name, color));
+        }
+
+        for (action, keys) in file.keys {
+            let Some(&(_, target)) = ACTIONS.iter().find(|(name, _)| *name == action) else {
+                bail!("Unknown action `{action}`");
+            };
+            let keys = match keys {
+                KeyList::One(key) => vec![key],
+                KeyList::Many(keys) => keys,
+            };
+            for key in keys {
+                let Some(code) = parse_key(&key) else {
+                    bail!("Invalid key `{key}` for action `{action}`");
+                };
+                config.keys.keys.insert(code, target);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the configured theme with the color overrides applied.
+    #[must_use]
+    pub fn theme(&self) -> Theme {
+        self.theme_named(&self.theme).unwrap_or_default()
+    }
+
+    /// Returns the gallery theme called `name` with the color overrides applied, or
+    /// `None` if there is no such theme.
+    #[must_use]
+    pub fn theme_named(&self, name: &str) -> Option<Theme> {
+        let mut theme = Theme::named(name)?;
+        for (color_name, color) in &self.colors {
+            if let Some(slot) = theme.color_mut(color_name) {
+                *slot = *color;
+            }
+        }
+        Some(theme)
+    }
+}
+
+/// Parses a key name, such as `k`, `pageup` or `f5`.
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match key.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "space" => KeyCode::Char(' '),
+        name => {
+            let number: u8 = name.strip_prefix('f')?.parse().ok()?;
+            if !(1..=12).contains(&number) {
+                return None;
+            }
+            KeyCode::F(number)
+        }
+    };
+    Some(code)
+}
+
+/// Returns the path of the TUI configuration file, if the toolchain directory is
+/// known.
+#[must_use]
+pub fn config_path() -> Option<PathBuf> {
+    ToolchainPaths::new()
+        .ok()
+        .map(|paths| paths.root.join(CONFIG_FILE))
+}
+
+/// Watches the configuration file for changes, by its modification time.
+#[derive(Debug, Default)]
+pub struct ConfigWatcher {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    /// Creates a watcher of the file at `path`, or of nothing if `path` is `None`.
+    #[must_use]
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let modified = path.as_deref().and_then(modified_time);
+        Self { path, modified }
+    }
+
+    /// Returns whether the file was created, changed or removed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let modified = self.path.as_deref().and_then(modified_time);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+
+    /// Loads the configuration from the file, or the default one if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or is invalid.
+    pub fn load(&self) -> Result<TuiConfig> {
+        match &self.path {
+            Some(path) if path.exists() => TuiConfig::load(path),
+            _ => Ok(TuiConfig::default()),
+        }
+    }
+}
+
+/// Returns the modification time of the file at `path`, if it exists.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_theme_colors_and_keys() {
+        let config = TuiConfig::parse(
+            r##"
+theme = "gruvbox"
+
+[colors]
+highlight = "#fabd2f"
+error = "lightred"
+
+[keys]
+up = "w"
+down = ["s", "pagedown"]
+quit = "f10"
+"##,
+        )
+        .unwrap();
+
+        assert_eq!(config.theme, "gruvbox");
+        let theme = config.theme();
+        assert_eq!(theme.highlight, Color::Rgb(0xfa, 0xbd, 0x2f));
+        assert_eq!(theme.error, Color::LightRed);
+        assert_eq!(theme.text, Theme::gruvbox().text);
+
+        assert_eq!(config.keys.translate(KeyCode::Char('w')), KeyCode::Up);
+        assert_eq!(config.keys.translate(KeyCode::Char('s')), KeyCode::Down);
+        assert_eq!(config.keys.translate(KeyCode::PageDown), KeyCode::Down);
+        assert_eq!(config.keys.translate(KeyCode::F(10)), KeyCode::Char('q'));
+        assert_eq!(
+            config.keys.translate(KeyCode::Char('k')),
+            KeyCode::Char('k')
+        );
+    }
+
+    #[test]
+    fn overrides_apply_to_other_gallery_themes() {
+        let config = TuiConfig::parse("[colors]\nborder = \"magenta\"\n").unwrap();
+        assert_eq!(config.theme_named("light").unwrap().border, Color::Magenta);
+        assert!(config.theme_named("neon").is_none());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_names() {
+        let errors = [
+            ("theme = \"neon\"", "Unknown theme `neon`"),
+            (
+                "[colors]\nbackground = \"red\"",
+                "Unknown color `background`",
+            ),
+            ("[colors]\nerror = \"reddish\"", "Invalid value `reddish`"),
+            ("[keys]\njump = \"j\"", "Unknown action `jump`"),
+            ("[keys]\nup = \"ctrl-w\"", "Invalid key `ctrl-w`"),
+            ("[keys]\nup = \"f13\"", "Invalid key `f13`"),
+        ];
+        for (text, expected) in errors {
+            let error = TuiConfig::parse(text).unwrap_err().to_string();
+            assert!(error.contains(expected), "{text}: {error}");
+        }
+        assert!(TuiConfig::parse("colour = 1").is_err());
+    }
+
+    #[test]
+    fn watcher_reports_changes_and_loads_file() {
+        let dir = std::env::temp_dir().join(format!("infs-tui-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(CONFIG_FILE);
+        let mut watcher = ConfigWatcher::new(Some(path.clone()));
+        assert!(!watcher.changed());
+        assert_eq!(watcher.load().unwrap().keys, KeyMap::default());
+
+        std::fs::write(&path, "theme = \"light\"\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        assert_eq!(watcher.load().unwrap().theme, "light");
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(watcher.load().is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn watcher_without_path_never_changes() {
+        let mut watcher = ConfigWatcher::default();
+        assert!(!watcher.changed());
+        assert!(watcher.load().is_ok());
+    }
+}
@@ -0,0 +1,97 @@
+//! Opening source files in the user's editor from the TUI.
+//!
+//! The editor is `$VISUAL`, then `$EDITOR`, then `vi`. It may include arguments, such
+//! as `code --wait`. The file is opened at a line and column with the syntax the
+//! editor understands:
+//!
+//! | Editor | Arguments |
+//! |--------|-----------|
+//! | `code`, `codium`, `cursor` | `--goto <file>:<line>:<column>` |
+//! | `hx`, `helix`, `subl`, `zed` | `<file>:<line>:<column>` |
+//! | Anything else (`vi`, `vim`, `nvim`, `nano`, `emacs`, ...) | `+<line> <file>` |
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+/// Opens `file` at `line` and `column` in the user's editor and waits for it to exit.
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be started or exits with a non-zero code.
+pub fn open(file: &Path, line: usize, column: usize) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| String::from("vi"));
+    let Some(mut cmd) = editor_command(&editor, file, line, column) else {
+        bail!("Invalid editor command: {editor}");
+    };
+    let status = cmd
+        .status()
+        .with_context(|| format!("Failed to run editor `{editor}`"))?;
+    if !status.success() {
+        bail!("Editor `{editor}` exited with status {status}");
+    }
+    Ok(())
+}
+
+/// Returns the command opening `file` at `line` and `column` with `editor`, or `None`
+/// if `editor` is blank.
+fn editor_command(editor: &str, file: &Path, line: usize, column: usize) -> Option<Command> {
+    let mut words = editor.split_whitespace();
+    let program = words.next()?;
+    let mut cmd = Command::new(program);
+    cmd.args(words);
+
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(program);
+    let position = format!("{}:{line}:{column}", file.display());
+    match name {
+        "code" | "codium" | "cursor" => {
+            cmd.arg("--goto").arg(position);
+        }
+        "hx" | "helix" | "subl" | "zed" => {
+            cmd.arg(position);
+        }
+        _ => {
+            cmd.arg(format!("+{line}")).arg(file);
+        }
+    }
+    Some(cmd)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Command) -> Vec<String> {
+        cmd.get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn editor_command_uses_line_syntax_of_editor() {
+        let file = Path::new("src/main.inf");
+
+        let vim = editor_command("vim", file, 12, 5).unwrap();
+        assert_eq!(vim.get_program(), "vim");
+        assert_eq!(args(&vim), ["+12", "src/main.inf"]);
+
+        let code = editor_command("/usr/bin/code --wait", file, 12, 5).unwrap();
+        assert_eq!(args(&code), ["--wait", "--goto", "src/main.inf:12:5"]);
+
+        let helix = editor_command("hx", file, 12, 5).unwrap();
+        assert_eq!(args(&helix), ["src/main.inf:12:5"]);
+    }
+
+    #[test]
+    fn editor_command_rejects_blank_editor() {
+        assert!(editor_command("  ", Path::new("main.inf"), 1, 1).is_none());
+    }
+}
@@ -5,7 +5,18 @@
 //!
 //! The installation runs on a separate thread with its own tokio runtime to avoid
 //! blocking the main TUI event loop.
+//!
+//! The TUI cancels an installation with the [`CancellationToken`] it passes in. The
+//! manifest and archive downloads stop right away; the checksum verification and the
+//! extraction run to completion first. Either way, the downloaded files and a
+//! partially installed toolchain are removed before [`InstallProgress::Cancelled`]
+//! is sent.
+//!
+//! Besides its phases, the installation reports the progress of each of its
+//! [`COMPONENTS`]: the archive and its signature, downloaded concurrently, then the
+//! extraction of the archive.
 
+use std::path::Path;
 use std::sync::mpsc::Sender;
 
 use anyhow::{Context, Result};
@@ -13,10 +24,24 @@ use anyhow::{Context, Result};
 use super::state::InstallProgress;
 use crate::toolchain::paths::ToolchainMetadata;
 use crate::toolchain::{
-    Platform, ProgressCallback, ProgressEvent, ToolchainPaths, download_file_with_callback,
-    extract_archive, fetch_artifact, set_executable_permissions, verify_checksum,
+    CancellationToken, Cancelled, Platform, ProgressCallback, ProgressEvent, ToolchainPaths,
+    download_files_with_callback, extract_archive_with_progress, fetch_artifact,
+    set_executable_permissions, verify_checksum, verify_signature,
 };
 
+/// Components of an installation, each with its own progress: the files downloaded,
+/// in the order they are passed to the download, then the extraction.
+pub const COMPONENTS: &[&str] = &["Toolchain archive", "Signature", "Extraction"];
+
+/// Index of the toolchain archive in [`COMPONENTS`].
+const ARCHIVE: usize = 0;
+
+/// Index of the signature in [`COMPONENTS`].
+const SIGNATURE: usize = 1;
+
+/// Index of the extraction in [`COMPONENTS`].
+const EXTRACTION: usize = 2;
+
 /// Runs the toolchain installation asynchronously, sending progress updates to the TUI.
 ///
 /// This function performs the same operations as the CLI install command but reports
@@ -25,6 +50,7 @@ use crate::toolchain::{
 /// # Arguments
 ///
 /// * `version` - Optional version to install. If `None`, installs the latest version.
+/// * `cancel` - Token cancelling the installation.
 /// * `tx` - Channel sender for progress updates.
 ///
 /// # Process
@@ -32,15 +58,25 @@ use crate::toolchain::{
 /// 1. Detect the current platform
 /// 2. Fetch the release manifest
 /// 3. Find the artifact for the requested version and platform
-/// 4. Download the archive with progress reporting
-/// 5. Verify the SHA256 checksum
+/// 4. Download the archive and its signature in parallel, with progress reporting
+/// 5. Verify the SHA256 checksum and the publisher signature
 /// 6. Extract to the toolchains directory
 /// 7. Set as default if it's the first installation
-pub async fn run_installation(version: Option<String>, tx: Sender<InstallProgress>) {
-    if let Err(e) = run_installation_inner(version, tx.clone()).await {
-        let _ = tx.send(InstallProgress::Failed {
-            error: e.to_string(),
-        });
+pub async fn run_installation(
+    version: Option<String>,
+    cancel: CancellationToken,
+    tx: Sender<InstallProgress>,
+) {
+    match run_installation_inner(version, &cancel, tx.clone()).await {
+        Ok(()) => {}
+        Err(e) if e.is::<Cancelled>() => {
+            let _ = tx.send(InstallProgress::Cancelled);
+        }
+        Err(e) => {
+            let _ = tx.send(InstallProgress::Failed {
+                error: e.to_string(),
+            });
+        }
     }
 }
 
@@ -48,6 +84,7 @@ pub async fn run_installation(version: Option<String>, tx: Sender<InstallProgres
 #[allow(clippy::too_many_lines)]
 async fn run_installation_inner(
     version: Option<String>,
+    cancel: &CancellationToken,
     tx: Sender<InstallProgress>,
 ) -> Result<()> {
     let _ = tx.send(InstallProgress::PhaseStarted {
@@ -70,9 +107,11 @@ async fn run_installation_inner(
     });
 
     let version_arg = version.as_deref();
-    let (resolved_version, artifact) = fetch_artifact(version_arg, platform)
+    let (release, artifact) = cancel
+        .run(fetch_artifact(version_arg, platform, false))
         .await
         .context("Failed to download release manifest")?;
+    let resolved_version = release.version.clone();
 
     let _ = tx.send(InstallProgress::PhaseCompleted {
         phase: String::from("Downloading release manifest"),
@@ -91,39 +130,86 @@ async fn run_installation_inner(
 
     let archive_filename = artifact.filename();
     let archive_path = paths.download_path(archive_filename);
+    let signature_path = paths.download_path(&format!("{archive_filename}.minisig"));
 
     let tx_callback = tx.clone();
     let callback: ProgressCallback = std::sync::Arc::new(move |event| {
         match event {
-            ProgressEvent::Started { total, .. } => {
-                let _ = tx_callback.send(InstallProgress::DownloadStarted { total });
+            ProgressEvent::Started { file, total, .. } => {
+                let _ = tx_callback.send(InstallProgress::ComponentStarted {
+                    component: file,
+                    total,
+                });
+            }
+            ProgressEvent::Progress {
+                file,
+                downloaded,
+                speed,
+            } => {
+                let _ = tx_callback.send(InstallProgress::ComponentProgress {
+                    component: file,
+                    current: downloaded,
+                    speed,
+                });
             }
-            ProgressEvent::Progress { downloaded, speed } => {
-                let _ = tx_callback.send(InstallProgress::DownloadProgress { downloaded, speed });
+            ProgressEvent::Completed => {
+                for component in [ARCHIVE, SIGNATURE] {
+                    let _ = tx_callback.send(InstallProgress::ComponentCompleted { component });
+                }
             }
-            ProgressEvent::Completed | ProgressEvent::Failed { .. } => {
+            ProgressEvent::Failed { .. } => {
                 // Handled at higher level
             }
         }
     });
 
-    download_file_with_callback(&artifact.url, &archive_path, callback)
-        .await
-        .context("Failed to download toolchain archive")?;
+    let remove_downloads = || {
+        std::fs::remove_file(&archive_path).ok();
+        std::fs::remove_file(&signature_path).ok();
+    };
+    let downloaded = download_files_with_callback(
+        &[
+            // In the order of `COMPONENTS`
+            (artifact.url.clone(), archive_path.clone()),
+            (artifact.signature_url(), signature_path.clone()),
+        ],
+        callback,
+        cancel,
+    )
+    .await
+    .context("Failed to download toolchain archive")
+    .and_then(|()| cancel.check());
+    if let Err(e) = downloaded {
+        if cancel.is_cancelled() {
+            remove_downloads();
+        }
+        return Err(e);
+    }
 
     let _ = tx.send(InstallProgress::PhaseCompleted {
         phase: format!("Downloading toolchain v{resolved_version}"),
     });
 
     let _ = tx.send(InstallProgress::PhaseStarted {
-        phase: String::from("Verifying checksum"),
+        phase: String::from("Verifying checksum and signature"),
     });
 
-    verify_checksum(&archive_path, &artifact.sha256)
-        .context("Checksum verification failed - download may be corrupted")?;
+    let verified = verify_checksum(&archive_path, &artifact.sha256)
+        .context("Checksum verification failed - download may be corrupted")
+        .and_then(|()| {
+            verify_signature(&archive_path, &signature_path)
+                .context("Signature verification failed - download may be tampered with")
+        })
+        .and_then(|()| cancel.check());
+    if let Err(e) = verified {
+        if cancel.is_cancelled() {
+            remove_downloads();
+        }
+        return Err(e);
+    }
 
     let _ = tx.send(InstallProgress::PhaseCompleted {
-        phase: String::from("Verifying checksum"),
+        phase: String::from("Verifying checksum and signature"),
     });
 
     let _ = tx.send(InstallProgress::PhaseStarted {
@@ -131,16 +217,45 @@ async fn run_installation_inner(
     });
 
     let toolchain_dir = paths.toolchain_dir(&resolved_version);
-    extract_archive(&archive_path, &toolchain_dir)
-        .context("Failed to extract toolchain archive")?;
+    let mut last_percent = None;
+    let mut report = |_: &Path, read: u64, total: u64| {
+        if last_percent.is_none() {
+            let _ = tx.send(InstallProgress::ComponentStarted {
+                component: EXTRACTION,
+                total,
+            });
+        }
+        let percent = read * 100 / total.max(1);
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            let _ = tx.send(InstallProgress::ComponentProgress {
+                component: EXTRACTION,
+                current: read,
+                speed: 0,
+            });
+        }
+    };
+    let extracted = extract_archive_with_progress(&archive_path, &toolchain_dir, &mut report)
+        .context("Failed to extract toolchain archive")
+        .and_then(|()| cancel.check());
+    if let Err(e) = extracted {
+        if cancel.is_cancelled() {
+            std::fs::remove_dir_all(&toolchain_dir).ok();
+            remove_downloads();
+        }
+        return Err(e);
+    }
 
     set_executable_permissions(&toolchain_dir).context("Failed to set executable permissions")?;
 
-    let metadata = ToolchainMetadata::now();
+    let metadata = ToolchainMetadata::for_release(&release);
     paths
         .write_metadata(&resolved_version, &metadata)
         .context("Failed to write toolchain metadata")?;
 
+    let _ = tx.send(InstallProgress::ComponentCompleted {
+        component: EXTRACTION,
+    });
     let _ = tx.send(InstallProgress::PhaseCompleted {
         phase: String::from("Extracting archive"),
     });
@@ -168,6 +283,7 @@ async fn run_installation_inner(
     }
 
     std::fs::remove_file(&archive_path).ok();
+    std::fs::remove_file(&signature_path).ok();
 
     let _ = tx.send(InstallProgress::PhaseCompleted {
         phase: String::from("Configuring toolchain"),
@@ -199,28 +315,33 @@ mod tests {
     }
 
     #[test]
-    fn install_progress_download_started_contains_total() {
-        let progress = InstallProgress::DownloadStarted { total: 1024 };
+    fn install_progress_component_started_contains_total() {
+        let progress = InstallProgress::ComponentStarted {
+            component: 1,
+            total: 1024,
+        };
         match progress {
-            InstallProgress::DownloadStarted { total } => {
+            InstallProgress::ComponentStarted { component, total } => {
+                assert_eq!(component, 1);
                 assert_eq!(total, 1024);
             }
-            _ => panic!("Expected DownloadStarted variant"),
+            _ => panic!("Expected ComponentStarted variant"),
         }
     }
 
     #[test]
-    fn install_progress_download_progress_contains_data() {
-        let progress = InstallProgress::DownloadProgress {
-            downloaded: 512,
+    fn install_progress_component_progress_contains_data() {
+        let progress = InstallProgress::ComponentProgress {
+            component: 0,
+            current: 512,
             speed: 1024,
         };
         match progress {
-            InstallProgress::DownloadProgress { downloaded, speed } => {
-                assert_eq!(downloaded, 512);
+            InstallProgress::ComponentProgress { current, speed, .. } => {
+                assert_eq!(current, 512);
                 assert_eq!(speed, 1024);
             }
-            _ => panic!("Expected DownloadProgress variant"),
+            _ => panic!("Expected ComponentProgress variant"),
         }
     }
 
@@ -252,17 +373,23 @@ mod tests {
 
     #[test]
     fn install_progress_is_clone() {
-        let progress = InstallProgress::DownloadProgress {
-            downloaded: 100,
+        let progress = InstallProgress::ComponentProgress {
+            component: 2,
+            current: 100,
             speed: 50,
         };
         let cloned = progress.clone();
         match cloned {
-            InstallProgress::DownloadProgress { downloaded, speed } => {
-                assert_eq!(downloaded, 100);
+            InstallProgress::ComponentProgress {
+                component,
+                current,
+                speed,
+            } => {
+                assert_eq!(component, 2);
+                assert_eq!(current, 100);
                 assert_eq!(speed, 50);
             }
-            _ => panic!("Expected DownloadProgress variant"),
+            _ => panic!("Expected ComponentProgress variant"),
         }
     }
 
@@ -44,6 +44,7 @@ impl MenuItem {
 pub const MENU_ITEMS: &[MenuItem] = &[
     MenuItem::screen("Toolchains", 't', Screen::Toolchains),
     MenuItem::screen("Doctor", 'd', Screen::Doctor),
+    MenuItem::screen("Verification", 'v', Screen::Verify),
     MenuItem::quit("Quit", 'q'),
 ];
 
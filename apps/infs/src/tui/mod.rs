@@ -18,6 +18,9 @@
 //!
 //! - [`terminal`] - Terminal setup and cleanup with RAII guard
 //! - [`app`] - Main application state and event loop
+//! - [`build_task`] - Background `build` and `check` commands
+//! - [`config`] - Themes and key remappings from `tui.toml`
+//! - [`editor`] - Opening source files in the user's editor
 //! - [`state`] - Screen state machine and view states
 //! - [`theme`] - Color theme system
 //! - [`menu`] - Menu navigation
@@ -25,6 +28,9 @@
 //! - [`widgets`] - Reusable widget components
 
 pub mod app;
+pub mod build_task;
+pub mod config;
+pub mod editor;
 pub mod install_task;
 pub mod menu;
 pub mod state;
@@ -58,7 +64,7 @@ pub fn should_use_tui() -> bool {
 /// This function sets up the terminal, runs the main event loop,
 /// and ensures proper cleanup on exit or error.
 ///
-/// If the TUI exits with a pending command (e.g., `build`, `run`, `verify`),
+/// If the TUI exits with a pending command (e.g., `new`, `run`, `verify`),
 /// this function restores the terminal, executes the command, waits for user
 /// to press Enter, and then restarts the TUI.
 ///
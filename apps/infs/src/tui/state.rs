@@ -3,6 +3,13 @@
 //! This module defines the screen state machine and view-specific state
 //! for the infs TUI application.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::project::manifest::{InferenceToml, MANIFEST_FILE};
+use crate::project::workspace::Workspace;
 use crate::toolchain::paths::ToolchainMetadata;
 
 pub use crate::toolchain::doctor::{DoctorCheck, DoctorCheckStatus};
@@ -21,6 +28,12 @@ pub enum Screen {
     Progress,
     /// Version selection view for choosing a version to install.
     VersionSelect,
+    /// Output of a build or check running in the background.
+    BuildLog,
+    /// Errors and warnings of the last build or check, with their source.
+    Diagnostics,
+    /// Proof obligations of the project and their status, from `infs verify`.
+    Verify,
 }
 
 /// Message sent from installation task to TUI for progress updates.
@@ -35,18 +48,27 @@ pub enum InstallProgress {
         /// Description of the phase (e.g., "Fetching manifest", "Downloading").
         phase: String,
     },
-    /// Download has started with a known total size.
-    DownloadStarted {
-        /// Total file size in bytes.
+    /// The size of a component, downloaded or extracted, became known.
+    ComponentStarted {
+        /// Index of the component in `install_task::COMPONENTS`.
+        component: usize,
+        /// Total size in bytes.
         total: u64,
     },
-    /// Download progress update.
-    DownloadProgress {
-        /// Bytes downloaded so far.
-        downloaded: u64,
-        /// Current download speed in bytes per second.
+    /// Progress update of a component.
+    ComponentProgress {
+        /// Index of the component in `install_task::COMPONENTS`.
+        component: usize,
+        /// Bytes processed so far.
+        current: u64,
+        /// Current speed in bytes per second, 0 if not measured.
         speed: u64,
     },
+    /// A component is done.
+    ComponentCompleted {
+        /// Index of the component in `install_task::COMPONENTS`.
+        component: usize,
+    },
     /// A phase of the installation has completed.
     PhaseCompleted {
         /// Description of the completed phase.
@@ -57,6 +79,8 @@ pub enum InstallProgress {
         /// The version that was installed.
         version: String,
     },
+    /// Installation was cancelled, and its partial files removed.
+    Cancelled,
     /// Installation failed with an error.
     Failed {
         /// Error description.
@@ -64,6 +88,29 @@ pub enum InstallProgress {
     },
 }
 
+/// Message sent from a build task to the TUI for every line of output.
+///
+/// Like [`InstallProgress`], these are sent from a background thread and polled
+/// non-blocking by the TUI event loop.
+#[derive(Debug, Clone)]
+pub enum BuildProgress {
+    /// The command printed a line, on stdout or stderr.
+    Line {
+        /// The line, without its line ending.
+        text: String,
+    },
+    /// The command exited.
+    Finished {
+        /// Exit code, `None` if it was terminated by a signal.
+        code: Option<i32>,
+    },
+    /// The command could not be run.
+    Failed {
+        /// Error description.
+        error: String,
+    },
+}
+
 /// Information about an installed toolchain version.
 #[derive(Debug, Clone)]
 pub struct ToolchainInfo {
@@ -415,6 +462,906 @@ impl ProgressState {
     }
 }
 
+/// Maximum number of lines kept by the build log; older lines are dropped.
+const MAX_LOG_LINES: usize = 10_000;
+
+/// Kind of a line of build output, which selects its color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    /// An error reported by infs or infc.
+    Error,
+    /// A warning reported by infc.
+    Warning,
+    /// A unit starting to compile, or a build hook running.
+    Progress,
+    /// A phase or unit that completed.
+    Success,
+    /// Anything else, such as the source excerpts of diagnostics.
+    Plain,
+}
+
+/// A line of build output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    /// The line as printed.
+    pub text: String,
+    /// Kind of the line.
+    pub level: LogLevel,
+}
+
+impl LogLine {
+    /// Creates a log line, classifying it by the messages of infs and infc.
+    #[must_use]
+    pub fn new(text: impl Into<String>) -> Self {
+        let text = text.into();
+        let trimmed = text.trim_start();
+        let level = if trimmed.starts_with("error") || trimmed.starts_with("Error") {
+            LogLevel::Error
+        } else if trimmed.starts_with("warning") {
+            LogLevel::Warning
+        } else if trimmed.starts_with("Compiling") || trimmed.starts_with("Running") {
+            LogLevel::Progress
+        } else if trimmed.starts_with("Fresh")
+            || trimmed.starts_with("Parsed:")
+            || trimmed.starts_with("Analyzed:")
+            || trimmed.starts_with("WASM generated")
+        {
+            LogLevel::Success
+        } else {
+            LogLevel::Plain
+        };
+        Self { text, level }
+    }
+}
+
+/// A phase of infc, as reported by its progress messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildPhase {
+    /// Parsing, completed by `Parsed: <path>`.
+    Parse,
+    /// Type checking and analysis, completed by `Analyzed: <path>`.
+    Analyze,
+    /// Code generation, completed by `WASM generated`.
+    Codegen,
+}
+
+impl BuildPhase {
+    /// Returns the name of the phase for display.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            BuildPhase::Parse => "Parse",
+            BuildPhase::Analyze => "Analyze",
+            BuildPhase::Codegen => "Codegen",
+        }
+    }
+
+    /// Returns the phase completed by the progress message `line`, if any.
+    fn completed_by(line: &str) -> Option<Self> {
+        let line = line.trim_start();
+        if line.starts_with("Parsed:") {
+            Some(BuildPhase::Parse)
+        } else if line.starts_with("Analyzed:") {
+            Some(BuildPhase::Analyze)
+        } else if line.starts_with("WASM generated") {
+            Some(BuildPhase::Codegen)
+        } else {
+            None
+        }
+    }
+}
+
+/// Status of a phase of the unit being built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseStatus {
+    /// Not started yet.
+    Pending,
+    /// Started and not completed.
+    Running,
+    /// Completed.
+    Done,
+    /// Running when the command failed.
+    Failed,
+}
+
+/// State for the build log view.
+#[derive(Debug, Clone, Default)]
+pub struct BuildLogState {
+    /// The command line shown as the title, e.g. `infs build --release`.
+    pub title: String,
+    /// Arguments of the command, to run it again.
+    pub args: Vec<String>,
+    /// Output of the command, oldest first.
+    pub lines: Vec<LogLine>,
+    /// Phases of the unit being built, with their status.
+    pub phases: Vec<(BuildPhase, PhaseStatus)>,
+    /// The unit being built, from the last `Compiling` or `Fresh` message.
+    pub unit: Option<String>,
+    /// Number of lines scrolled up from the bottom; 0 follows new output.
+    pub scroll: usize,
+    /// Whether the command is still running.
+    pub running: bool,
+    /// Exit code of the command once it exited, `None` if killed by a signal.
+    pub exit_code: Option<i32>,
+    /// Error message if the command could not be run.
+    pub error: Option<String>,
+    /// Errors and warnings reported by infc, in the order they were printed.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl BuildLogState {
+    /// Creates the state of a running `infs` command with `args`, whose first
+    /// argument is `build` or `check`.
+    #[must_use]
+    pub fn new(args: Vec<String>) -> Self {
+        let phases: &[BuildPhase] = if args.first().is_some_and(|command| command == "check") {
+            &[BuildPhase::Parse, BuildPhase::Analyze]
+        } else {
+            &[BuildPhase::Parse, BuildPhase::Analyze, BuildPhase::Codegen]
+        };
+        let mut state = Self {
+            title: format!("infs {}", args.join(" ")),
+            args,
+            phases: phases
+                .iter()
+                .map(|phase| (*phase, PhaseStatus::Pending))
+                .collect(),
+            running: true,
+            ..Self::default()
+        };
+        state.start_unit(None);
+        state
+    }
+
+    /// Appends a line of output and updates the phases it reports on.
+    ///
+    /// A diagnostic printed by `--message-format json` is recorded, and shown in the
+    /// log as its header, location and notes.
+    pub fn push_line(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if let Some(diagnostic) = Diagnostic::from_json_line(&text) {
+            for line in diagnostic.log_lines() {
+                self.append(LogLine::new(line));
+            }
+            self.diagnostics.push(diagnostic);
+            return;
+        }
+
+        let line = LogLine::new(text);
+        let trimmed = line.text.trim_start();
+        if let Some(unit) = trimmed.strip_prefix("Compiling ") {
+            self.start_unit(Some(unit.to_string()));
+        } else if let Some(unit) = trimmed.strip_prefix("Fresh ") {
+            self.unit = Some(unit.to_string());
+            for (_, status) in &mut self.phases {
+                *status = PhaseStatus::Done;
+            }
+        } else if let Some(phase) = BuildPhase::completed_by(trimmed) {
+            self.complete_phase(phase);
+        }
+        self.append(line);
+    }
+
+    /// Appends `line` to the log, keeping the lines in view while scrolled up.
+    fn append(&mut self, line: LogLine) {
+        self.lines.push(line);
+        if self.lines.len() > MAX_LOG_LINES {
+            self.lines.remove(0);
+        } else if self.scroll > 0 {
+            // Keep the lines in view while scrolled up
+            self.scroll += 1;
+        }
+    }
+
+    /// Records the exit of the command; the running phase failed unless `code` is 0.
+    pub fn finish(&mut self, code: Option<i32>) {
+        self.running = false;
+        self.exit_code = code;
+        if code != Some(0) {
+            for (_, status) in &mut self.phases {
+                if *status == PhaseStatus::Running {
+                    *status = PhaseStatus::Failed;
+                }
+            }
+        }
+    }
+
+    /// Records that the command could not be run.
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.error = Some(error.into());
+        self.finish(None);
+    }
+
+    /// Returns true if the command exited with code 0.
+    #[must_use]
+    pub fn succeeded(&self) -> bool {
+        !self.running && self.exit_code == Some(0)
+    }
+
+    /// Returns the number of error and warning lines.
+    #[must_use]
+    pub fn diagnostic_counts(&self) -> (usize, usize) {
+        let count = |level| self.lines.iter().filter(|l| l.level == level).count();
+        (count(LogLevel::Error), count(LogLevel::Warning))
+    }
+
+    /// Returns the fraction of the phases of the current unit that completed.
+    #[must_use]
+    pub fn phase_ratio(&self) -> f64 {
+        if self.phases.is_empty() {
+            return 0.0;
+        }
+        let done = self
+            .phases
+            .iter()
+            .filter(|(_, status)| *status == PhaseStatus::Done)
+            .count();
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = done as f64 / self.phases.len() as f64;
+        ratio
+    }
+
+    /// Scrolls towards older output by `lines`.
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll = (self.scroll + lines).min(self.lines.len());
+    }
+
+    /// Scrolls towards newer output by `lines`; at the bottom, new output is followed.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Scrolls to the first line.
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = self.lines.len();
+    }
+
+    /// Scrolls to the last line and follows new output.
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+    }
+
+    /// Returns the range of lines visible in a panel of `height` lines.
+    #[must_use]
+    pub fn visible_range(&self, height: usize) -> std::ops::Range<usize> {
+        let end = self.lines.len() - self.scroll.min(self.lines.len());
+        let start = end.saturating_sub(height);
+        // Near the top, fill the panel rather than leave it partly empty
+        let end = (start + height).min(self.lines.len()).max(end);
+        start..end
+    }
+
+    /// Starts the phases of a new unit, the first one running.
+    fn start_unit(&mut self, unit: Option<String>) {
+        self.unit = unit;
+        for (index, (_, status)) in self.phases.iter_mut().enumerate() {
+            *status = if index == 0 {
+                PhaseStatus::Running
+            } else {
+                PhaseStatus::Pending
+            };
+        }
+    }
+
+    /// Marks `phase` and the phases before it as done, and the next one as running.
+    fn complete_phase(&mut self, phase: BuildPhase) {
+        let Some(position) = self.phases.iter().position(|(p, _)| *p == phase) else {
+            return;
+        };
+        for (index, (_, status)) in self.phases.iter_mut().enumerate() {
+            if index <= position {
+                *status = PhaseStatus::Done;
+            } else if index == position + 1 {
+                *status = PhaseStatus::Running;
+            }
+        }
+    }
+}
+
+/// Severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// A source range of a diagnostic; lines and columns start at 1, and the end column
+/// is exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+/// An error or warning of infc, as printed by `--message-format json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Diagnostic code, e.g. `E0001`.
+    pub code: Option<String>,
+    pub message: String,
+    /// Source file, as given to infc; `None` for errors without a location.
+    pub file: Option<PathBuf>,
+    /// Primary span, `None` for errors without a location.
+    pub span: Option<DiagnosticSpan>,
+    /// Labels of the secondary spans, with their line.
+    pub notes: Vec<(usize, String)>,
+}
+
+impl Diagnostic {
+    /// Parses a line printed by infc with `--message-format json`; returns `None` for
+    /// any other line.
+    #[must_use]
+    pub fn from_json_line(line: &str) -> Option<Self> {
+        if !line.starts_with('{') {
+            return None;
+        }
+        let value: Value = serde_json::from_str(line).ok()?;
+        let severity = match value["severity"].as_str()? {
+            "error" => DiagnosticSeverity::Error,
+            "warning" => DiagnosticSeverity::Warning,
+            _ => return None,
+        };
+        let spans = value["spans"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let number = |span: &Value, key: &str| {
+            span[key]
+                .as_u64()
+                .and_then(|n| usize::try_from(n).ok())
+                .unwrap_or(1)
+        };
+        let span = spans.first().map(|span| DiagnosticSpan {
+            start_line: number(span, "start_line"),
+            start_column: number(span, "start_column"),
+            end_line: number(span, "end_line"),
+            end_column: number(span, "end_column"),
+        });
+        let notes = spans
+            .iter()
+            .skip(1)
+            .filter_map(|span| {
+                Some((
+                    number(span, "start_line"),
+                    span["label"].as_str()?.to_string(),
+                ))
+            })
+            .collect();
+        Some(Self {
+            severity,
+            code: value["code"].as_str().map(String::from),
+            message: value["message"].as_str()?.to_string(),
+            file: value["file"].as_str().map(PathBuf::from),
+            span,
+            notes,
+        })
+    }
+
+    /// Returns the header of the diagnostic, e.g. `error[E0001]: type mismatch`.
+    #[must_use]
+    pub fn header(&self) -> String {
+        let severity = match self.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+        };
+        match &self.code {
+            Some(code) => format!("{severity}[{code}]: {}", self.message),
+            None => format!("{severity}: {}", self.message),
+        }
+    }
+
+    /// Returns the location of the diagnostic as `file:line:column`, if it has one.
+    #[must_use]
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match self.span {
+            Some(span) => format!(
+                "{}:{}:{}",
+                file.display(),
+                span.start_line,
+                span.start_column
+            ),
+            None => file.display().to_string(),
+        })
+    }
+
+    /// Returns the lines showing the diagnostic in the build log.
+    fn log_lines(&self) -> Vec<String> {
+        let mut lines = vec![self.header()];
+        if let Some(location) = self.location() {
+            lines.push(format!("  --> {location}"));
+        }
+        for (line, note) in &self.notes {
+            lines.push(format!("  = note (line {line}): {note}"));
+        }
+        lines
+    }
+}
+
+/// State for the diagnostics view.
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticsState {
+    /// The diagnostics of the last build.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Currently selected index.
+    pub selected: usize,
+    /// Lines of the source files of the diagnostics, `None` if a file cannot be read.
+    pub sources: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl DiagnosticsState {
+    /// Creates the state for `diagnostics`, reading their source files.
+    #[must_use]
+    pub fn new(diagnostics: Vec<Diagnostic>) -> Self {
+        let mut sources = HashMap::new();
+        for file in diagnostics.iter().filter_map(|d| d.file.as_ref()) {
+            sources.entry(file.clone()).or_insert_with(|| {
+                std::fs::read_to_string(file)
+                    .ok()
+                    .map(|source| source.lines().map(String::from).collect())
+            });
+        }
+        Self {
+            diagnostics,
+            selected: 0,
+            sources,
+        }
+    }
+
+    /// Moves selection up.
+    pub fn select_previous(&mut self) {
+        if !self.diagnostics.is_empty() {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Moves selection down.
+    pub fn select_next(&mut self) {
+        if !self.diagnostics.is_empty() {
+            self.selected = (self.selected + 1).min(self.diagnostics.len() - 1);
+        }
+    }
+
+    /// Returns the currently selected diagnostic, if any.
+    #[must_use]
+    pub fn selected_diagnostic(&self) -> Option<&Diagnostic> {
+        self.diagnostics.get(self.selected)
+    }
+
+    /// Returns the lines of the source file of `diagnostic`, if it could be read.
+    #[must_use]
+    pub fn source_lines(&self, diagnostic: &Diagnostic) -> Option<&[String]> {
+        self.sources.get(diagnostic.file.as_ref()?)?.as_deref()
+    }
+}
+
+/// Keywords starting a proof obligation in a Rocq file.
+const OBLIGATION_KEYWORDS: &[&str] = &[
+    "Theorem",
+    "Lemma",
+    "Proposition",
+    "Corollary",
+    "Fact",
+    "Remark",
+    "Example",
+];
+
+/// Status of a proof obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObligationStatus {
+    /// Not checked yet, or after an earlier error in its file.
+    Pending,
+    /// Closed with `Qed.` or `Defined.` and checked.
+    Proved,
+    /// Closed with `Admitted.`; assumed rather than proved.
+    Admitted,
+    /// The Rocq compiler reported an error in it.
+    Failing,
+}
+
+impl ObligationStatus {
+    /// Returns the label of the status.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            ObligationStatus::Pending => "pending",
+            ObligationStatus::Proved => "proved",
+            ObligationStatus::Admitted => "admitted",
+            ObligationStatus::Failing => "failing",
+        }
+    }
+}
+
+/// A theorem, lemma or similar statement of a Rocq file, with its proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Obligation {
+    /// The keyword of the statement, e.g. `Lemma`.
+    pub kind: String,
+    pub name: String,
+    /// Line of the statement, starting at 1.
+    pub line: usize,
+    /// Line closing the proof, or the last line of the file if it is not closed.
+    pub end_line: usize,
+    /// Status once the file was checked, from the command closing the proof.
+    pub closed_as: ObligationStatus,
+    pub status: ObligationStatus,
+}
+
+/// Returns the proof obligations of the Rocq source `source`, all pending.
+#[must_use]
+pub fn parse_obligations(source: &str) -> Vec<Obligation> {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut obligations: Vec<Obligation> = Vec::new();
+    let mut open: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        let mut words = trimmed.split_whitespace();
+        if let Some(keyword) = words.next()
+            && OBLIGATION_KEYWORDS.contains(&keyword)
+            && let Some(name) = words.next()
+        {
+            let name = name.split(':').next().unwrap_or(name);
+            open = Some(obligations.len());
+            obligations.push(Obligation {
+                kind: keyword.to_string(),
+                name: name.to_string(),
+                line: index + 1,
+                end_line: lines.len(),
+                closed_as: ObligationStatus::Failing,
+                status: ObligationStatus::Pending,
+            });
+        }
+        if let Some(current) = open {
+            let closed_as = trimmed.split_whitespace().find_map(|word| match word {
+                "Qed." | "Defined." => Some(ObligationStatus::Proved),
+                "Admitted." => Some(ObligationStatus::Admitted),
+                _ => None,
+            });
+            if let Some(closed_as) = closed_as {
+                obligations[current].end_line = index + 1;
+                obligations[current].closed_as = closed_as;
+                open = None;
+            }
+        }
+    }
+    obligations
+}
+
+/// Returns the line of the first error in the output of the Rocq compiler, from its
+/// `File "./name.v", line 12, characters 0-5:` header.
+#[must_use]
+pub fn rocq_error_line(output: &[String]) -> Option<usize> {
+    output.iter().find_map(|line| {
+        let rest = line.trim_start().strip_prefix("File ")?;
+        let (_, rest) = rest.split_once(", line ")?;
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    })
+}
+
+/// Outcome of checking a proof file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofFileStatus {
+    /// Not checked yet in this run.
+    Pending,
+    /// The Rocq compiler accepted the file.
+    Passed,
+    /// The Rocq compiler rejected the file.
+    Failed,
+}
+
+/// A `.v` file of the proofs directory of a package.
+#[derive(Debug, Clone)]
+pub struct ProofFile {
+    /// Index of the package in [`VerifyState::packages`].
+    pub package: usize,
+    /// Path relative to the package directory, as `infs verify` prints it.
+    pub path: PathBuf,
+    pub status: ProofFileStatus,
+    /// What the Rocq compiler printed for a failed file.
+    pub output: Vec<String>,
+    pub obligations: Vec<Obligation>,
+}
+
+/// A package whose proofs the dashboard shows.
+#[derive(Debug, Clone)]
+pub struct VerifyPackage {
+    pub name: String,
+    pub dir: PathBuf,
+}
+
+/// State for the verification dashboard.
+#[derive(Debug, Clone, Default)]
+pub struct VerifyState {
+    /// The command line shown as the title, e.g. `infs verify`.
+    pub title: String,
+    /// Arguments of the command, to run it again.
+    pub args: Vec<String>,
+    /// The packages verified, in the order `infs verify` checks them.
+    pub packages: Vec<VerifyPackage>,
+    /// The proof files of all packages.
+    pub files: Vec<ProofFile>,
+    /// Index of the selected row of [`VerifyState::rows`].
+    pub selected: usize,
+    /// Whether `infs verify` is running.
+    pub running: bool,
+    /// Exit code of `infs verify` once it exited, `None` if killed by a signal.
+    pub exit_code: Option<i32>,
+    /// Error message if the project could not be read or the command not run.
+    pub error: Option<String>,
+    /// Output of `infs verify` that is not a proof result, e.g. infc errors.
+    pub log: Vec<String>,
+    /// Package being verified, from the last `Verifying` line.
+    current_package: usize,
+    /// File whose compiler output the indented lines belong to.
+    output_file: Option<usize>,
+}
+
+impl VerifyState {
+    /// Creates the state of a running `infs` command with `args`, whose first argument
+    /// is `verify`, listing the proof files the project already has.
+    #[must_use]
+    pub fn new(args: Vec<String>) -> Self {
+        let root = PathBuf::from(args.get(1).map_or(".", String::as_str));
+        let mut state = Self {
+            title: format!("infs {}", args.join(" ")),
+            args,
+            running: true,
+            ..Self::default()
+        };
+        if let Err(e) = state.load_project(&root) {
+            state.error = Some(format!("{e:#}"));
+        }
+        state
+    }
+
+    /// Reads the packages of the project in `root` and the proof files they have.
+    fn load_project(&mut self, root: &Path) -> anyhow::Result<()> {
+        let mut packages = Vec::new();
+        if let Some(workspace) = Workspace::find(root)? {
+            for member in &workspace.members {
+                if workspace.selects(root, member) {
+                    packages.push((member.dir.clone(), member.manifest.clone()));
+                }
+            }
+        } else {
+            let manifest = InferenceToml::read_from_file(&root.join(MANIFEST_FILE))?;
+            packages.push((root.to_path_buf(), manifest));
+        }
+
+        for (dir, manifest) in packages {
+            let output_dir = PathBuf::from(&manifest.verification.output_dir);
+            let package = self.packages.len();
+            if let Ok(entries) = std::fs::read_dir(dir.join(&output_dir)) {
+                let mut names: Vec<PathBuf> = entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| path.extension().is_some_and(|extension| extension == "v"))
+                    .filter_map(|path| path.file_name().map(PathBuf::from))
+                    .collect();
+                names.sort();
+                for name in names {
+                    let path = output_dir.join(name);
+                    let obligations = read_obligations(&dir.join(&path));
+                    self.files.push(ProofFile {
+                        package,
+                        path,
+                        status: ProofFileStatus::Pending,
+                        output: Vec::new(),
+                        obligations,
+                    });
+                }
+            }
+            self.packages.push(VerifyPackage {
+                name: manifest.package.name.clone(),
+                dir,
+            });
+        }
+        Ok(())
+    }
+
+    /// Handles a line printed by `infs verify`: `PASS` and `FAIL` lines update the
+    /// file they name, re-reading its obligations, and the indented lines after
+    /// `FAIL` are its compiler output.
+    pub fn push_line(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if let Some(output) = text.strip_prefix("    ")
+            && let Some(file) = self.output_file
+        {
+            self.files[file].output.push(output.to_string());
+            self.update_statuses(file);
+            return;
+        }
+        self.output_file = None;
+
+        if let Some(rest) = text.strip_prefix("Verifying ") {
+            let name = rest.split_whitespace().next().unwrap_or_default();
+            if let Some(package) = self.packages.iter().position(|p| p.name == name) {
+                self.current_package = package;
+            }
+        } else if let Some(path) = text.strip_prefix("PASS ") {
+            self.record_result(Path::new(path), ProofFileStatus::Passed);
+        } else if let Some(path) = text.strip_prefix("FAIL ") {
+            let file = self.record_result(Path::new(path), ProofFileStatus::Failed);
+            self.output_file = Some(file);
+        } else if !text.trim().is_empty() {
+            self.log.push(text);
+            if self.log.len() > MAX_LOG_LINES {
+                self.log.remove(0);
+            }
+        }
+    }
+
+    /// Records the result of the file at `path` of the current package, adding it if
+    /// it is new, and returns its index.
+    fn record_result(&mut self, path: &Path, status: ProofFileStatus) -> usize {
+        let package = self.current_package;
+        let index = if let Some(index) = self
+            .files
+            .iter()
+            .position(|file| file.package == package && file.path == path)
+        {
+            index
+        } else {
+            // Keep the files of a package together
+            let index = self
+                .files
+                .iter()
+                .rposition(|file| file.package <= package)
+                .map_or(0, |index| index + 1);
+            self.files.insert(
+                index,
+                ProofFile {
+                    package,
+                    path: path.to_path_buf(),
+                    status,
+                    output: Vec::new(),
+                    obligations: Vec::new(),
+                },
+            );
+            index
+        };
+
+        let full_path = self.full_path(index);
+        let file = &mut self.files[index];
+        file.status = status;
+        file.output.clear();
+        file.obligations = read_obligations(&full_path);
+        self.update_statuses(index);
+        self.clamp_selection();
+        index
+    }
+
+    /// Sets the status of the obligations of a file from its result: after an error,
+    /// the obligation it is in fails and the later ones were not checked.
+    fn update_statuses(&mut self, index: usize) {
+        let file = &mut self.files[index];
+        let error_line = rocq_error_line(&file.output);
+        for obligation in &mut file.obligations {
+            obligation.status = match (file.status, error_line) {
+                (ProofFileStatus::Passed, _) => obligation.closed_as,
+                (ProofFileStatus::Failed, None) => ObligationStatus::Failing,
+                (ProofFileStatus::Failed, Some(line)) if obligation.end_line < line => {
+                    obligation.closed_as
+                }
+                (ProofFileStatus::Failed, Some(line)) if obligation.line <= line => {
+                    ObligationStatus::Failing
+                }
+                (ProofFileStatus::Pending | ProofFileStatus::Failed, _) => {
+                    ObligationStatus::Pending
+                }
+            };
+        }
+    }
+
+    /// Records the exit of `infs verify`.
+    pub fn finish(&mut self, code: Option<i32>) {
+        self.running = false;
+        self.exit_code = code;
+        self.output_file = None;
+    }
+
+    /// Records that `infs verify` could not be run.
+    pub fn fail(&mut self, error: impl Into<String>) {
+        self.error = Some(error.into());
+        self.finish(None);
+    }
+
+    /// Returns the path of a file, for reading it or opening it in an editor.
+    #[must_use]
+    pub fn full_path(&self, index: usize) -> PathBuf {
+        let file = &self.files[index];
+        self.packages
+            .get(file.package)
+            .map_or_else(|| file.path.clone(), |package| package.dir.join(&file.path))
+    }
+
+    /// Returns the rows of the dashboard: each obligation of each file, or the file
+    /// alone if it has none, as `(file, obligation)` indexes.
+    #[must_use]
+    pub fn rows(&self) -> Vec<(usize, Option<usize>)> {
+        let mut rows = Vec::new();
+        for (index, file) in self.files.iter().enumerate() {
+            if file.obligations.is_empty() {
+                rows.push((index, None));
+            } else {
+                rows.extend((0..file.obligations.len()).map(|o| (index, Some(o))));
+            }
+        }
+        rows
+    }
+
+    /// Selects the row at `index`, or the last one if there are fewer rows.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.clamp_selection();
+    }
+
+    /// Keeps the selection within the rows.
+    fn clamp_selection(&mut self) {
+        let rows = self.rows().len();
+        self.selected = self.selected.min(rows.saturating_sub(1));
+    }
+
+    /// Moves selection up.
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Moves selection down.
+    pub fn select_next(&mut self) {
+        self.selected += 1;
+        self.clamp_selection();
+    }
+
+    /// Returns the selected file and obligation, if any.
+    #[must_use]
+    pub fn selected_row(&self) -> Option<(&ProofFile, Option<&Obligation>)> {
+        let (file, obligation) = *self.rows().get(self.selected)?;
+        let file = &self.files[file];
+        Some((file, obligation.map(|o| &file.obligations[o])))
+    }
+
+    /// Returns the file, line and column to open for the selected row: the error of
+    /// a failing file, else the statement of the obligation, else the first line.
+    #[must_use]
+    pub fn selected_location(&self) -> Option<(PathBuf, usize, usize)> {
+        let (file, obligation) = *self.rows().get(self.selected)?;
+        let proof_file = &self.files[file];
+        let line = match obligation.map(|o| &proof_file.obligations[o]) {
+            Some(obligation) if obligation.status != ObligationStatus::Failing => obligation.line,
+            _ => rocq_error_line(&proof_file.output)
+                .or_else(|| obligation.map(|o| proof_file.obligations[o].line))
+                .unwrap_or(1),
+        };
+        Some((self.full_path(file), line, 1))
+    }
+
+    /// Returns the number of proved, admitted, failing and pending obligations.
+    #[must_use]
+    pub fn counts(&self) -> (usize, usize, usize, usize) {
+        let mut counts = (0, 0, 0, 0);
+        for obligation in self.files.iter().flat_map(|file| &file.obligations) {
+            match obligation.status {
+                ObligationStatus::Proved => counts.0 += 1,
+                ObligationStatus::Admitted => counts.1 += 1,
+                ObligationStatus::Failing => counts.2 += 1,
+                ObligationStatus::Pending => counts.3 += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// Returns the obligations of the Rocq file at `path`, none if it cannot be read.
+fn read_obligations(path: &Path) -> Vec<Obligation> {
+    std::fs::read_to_string(path)
+        .map(|source| parse_obligations(&source))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -607,28 +1554,33 @@ mod tests {
     }
 
     #[test]
-    fn install_progress_download_started_contains_total() {
-        let progress = InstallProgress::DownloadStarted { total: 1024 };
+    fn install_progress_component_started_contains_total() {
+        let progress = InstallProgress::ComponentStarted {
+            component: 1,
+            total: 1024,
+        };
         match progress {
-            InstallProgress::DownloadStarted { total } => {
+            InstallProgress::ComponentStarted { component, total } => {
+                assert_eq!(component, 1);
                 assert_eq!(total, 1024);
             }
-            _ => panic!("Expected DownloadStarted variant"),
+            _ => panic!("Expected ComponentStarted variant"),
         }
     }
 
     #[test]
-    fn install_progress_download_progress_contains_data() {
-        let progress = InstallProgress::DownloadProgress {
-            downloaded: 512,
+    fn install_progress_component_progress_contains_data() {
+        let progress = InstallProgress::ComponentProgress {
+            component: 0,
+            current: 512,
             speed: 1024,
         };
         match progress {
-            InstallProgress::DownloadProgress { downloaded, speed } => {
-                assert_eq!(downloaded, 512);
+            InstallProgress::ComponentProgress { current, speed, .. } => {
+                assert_eq!(current, 512);
                 assert_eq!(speed, 1024);
             }
-            _ => panic!("Expected DownloadProgress variant"),
+            _ => panic!("Expected ComponentProgress variant"),
         }
     }
 
@@ -673,17 +1625,23 @@ mod tests {
 
     #[test]
     fn install_progress_is_clone() {
-        let progress = InstallProgress::DownloadProgress {
-            downloaded: 100,
+        let progress = InstallProgress::ComponentProgress {
+            component: 2,
+            current: 100,
             speed: 50,
         };
         let cloned = progress.clone();
         match cloned {
-            InstallProgress::DownloadProgress { downloaded, speed } => {
-                assert_eq!(downloaded, 100);
+            InstallProgress::ComponentProgress {
+                component,
+                current,
+                speed,
+            } => {
+                assert_eq!(component, 2);
+                assert_eq!(current, 100);
                 assert_eq!(speed, 50);
             }
-            _ => panic!("Expected DownloadProgress variant"),
+            _ => panic!("Expected ComponentProgress variant"),
         }
     }
 
@@ -811,4 +1769,272 @@ mod tests {
         state.select_next();
         assert_eq!(state.selected, 0);
     }
+
+    #[test]
+    fn log_line_levels_follow_messages() {
+        assert_eq!(
+            LogLine::new("error[E0001]: type mismatch").level,
+            LogLevel::Error
+        );
+        assert_eq!(LogLine::new("Error: path not found").level, LogLevel::Error);
+        assert_eq!(
+            LogLine::new("warning: unused variable").level,
+            LogLevel::Warning
+        );
+        assert_eq!(
+            LogLine::new("   Compiling demo v0.1.0").level,
+            LogLevel::Progress
+        );
+        assert_eq!(
+            LogLine::new("Parsed: src/main.inf").level,
+            LogLevel::Success
+        );
+        assert_eq!(
+            LogLine::new("  --> src/main.inf:2:5").level,
+            LogLevel::Plain
+        );
+    }
+
+    #[test]
+    fn build_log_state_tracks_phases() {
+        let mut state = BuildLogState::new(vec![String::from("build")]);
+        assert_eq!(state.title, "infs build");
+        assert_eq!(state.phases[0], (BuildPhase::Parse, PhaseStatus::Running));
+
+        state.push_line("   Compiling demo v0.1.0");
+        state.push_line("Parsed: src/main.inf");
+        assert_eq!(state.unit.as_deref(), Some("demo v0.1.0"));
+        assert_eq!(state.phases[0].1, PhaseStatus::Done);
+        assert_eq!(state.phases[1].1, PhaseStatus::Running);
+        assert_eq!(state.phases[2].1, PhaseStatus::Pending);
+
+        state.push_line("error[E0001]: type mismatch");
+        state.finish(Some(1));
+        assert!(!state.running);
+        assert!(!state.succeeded());
+        assert_eq!(state.phases[1].1, PhaseStatus::Failed);
+        assert_eq!(state.diagnostic_counts(), (1, 0));
+    }
+
+    #[test]
+    fn build_log_state_check_has_no_codegen() {
+        let mut state = BuildLogState::new(vec![String::from("check")]);
+        assert_eq!(state.phases.len(), 2);
+
+        state.push_line("Parsed: main.inf");
+        state.push_line("Analyzed: main.inf");
+        state.finish(Some(0));
+        assert!(state.succeeded());
+        assert!((state.phase_ratio() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn build_log_state_fresh_unit_completes_all_phases() {
+        let mut state = BuildLogState::new(vec![String::from("build")]);
+        state.push_line("       Fresh demo v0.1.0");
+
+        assert!(
+            state
+                .phases
+                .iter()
+                .all(|(_, status)| *status == PhaseStatus::Done)
+        );
+    }
+
+    #[test]
+    fn build_log_state_scrolling() {
+        let mut state = BuildLogState::new(vec![String::from("build")]);
+        for index in 0..10 {
+            state.push_line(format!("line {index}"));
+        }
+        assert_eq!(state.visible_range(4), 6..10);
+
+        state.scroll_up(3);
+        assert_eq!(state.visible_range(4), 3..7);
+
+        // New output does not move the lines in view while scrolled up
+        state.push_line("line 10");
+        assert_eq!(state.visible_range(4), 3..7);
+
+        state.scroll_to_top();
+        assert_eq!(state.visible_range(4), 0..4);
+        state.scroll_down(100);
+        assert_eq!(state.visible_range(4), 7..11);
+        state.scroll_up(1);
+        state.scroll_to_bottom();
+        assert_eq!(state.scroll, 0);
+    }
+
+    const DIAGNOSTIC_JSON: &str = r#"{"phase":"type-check","severity":"error","code":"E0001","message":"type mismatch","file":"src/main.inf","spans":[{"offset_start":23,"offset_end":41,"start_line":2,"start_column":5,"end_line":2,"end_column":23,"primary":true,"label":null},{"offset_start":0,"offset_end":1,"start_line":1,"start_column":1,"end_line":1,"end_column":2,"primary":false,"label":"declared here"}]}"#;
+
+    #[test]
+    fn diagnostic_from_json_line() {
+        let diagnostic = Diagnostic::from_json_line(DIAGNOSTIC_JSON).unwrap();
+        assert_eq!(diagnostic.severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostic.header(), "error[E0001]: type mismatch");
+        assert_eq!(diagnostic.location().as_deref(), Some("src/main.inf:2:5"));
+        assert_eq!(
+            diagnostic.span,
+            Some(DiagnosticSpan {
+                start_line: 2,
+                start_column: 5,
+                end_line: 2,
+                end_column: 23,
+            })
+        );
+        assert_eq!(diagnostic.notes, vec![(1, String::from("declared here"))]);
+
+        assert!(Diagnostic::from_json_line("Parsed: src/main.inf").is_none());
+        assert!(Diagnostic::from_json_line(r#"{"success":true}"#).is_none());
+    }
+
+    #[test]
+    fn build_log_state_records_json_diagnostics() {
+        let mut state = BuildLogState::new(vec![String::from("build")]);
+        state.push_line(DIAGNOSTIC_JSON);
+
+        assert_eq!(state.diagnostics.len(), 1);
+        assert_eq!(state.lines[0].text, "error[E0001]: type mismatch");
+        assert_eq!(state.lines[0].level, LogLevel::Error);
+        assert_eq!(state.lines[1].text, "  --> src/main.inf:2:5");
+        assert_eq!(state.diagnostic_counts(), (1, 0));
+    }
+
+    #[test]
+    fn diagnostics_state_navigation_and_sources() {
+        let mut diagnostic = Diagnostic::from_json_line(DIAGNOSTIC_JSON).unwrap();
+        diagnostic.file = Some(PathBuf::from("/nonexistent/main.inf"));
+        let mut state = DiagnosticsState::new(vec![diagnostic.clone(), diagnostic]);
+
+        assert_eq!(state.selected, 0);
+        state.select_next();
+        state.select_next();
+        assert_eq!(state.selected, 1);
+        state.select_previous();
+        assert_eq!(state.selected, 0);
+
+        let selected = state.selected_diagnostic().unwrap();
+        assert!(state.source_lines(selected).is_none());
+    }
+
+    #[test]
+    fn parse_obligations_reads_statements_and_how_they_close() {
+        let source = "(* Generated *)\n\
+                      Definition two := 2.\n\
+                      Lemma add_comm : forall a b, a + b = b + a.\n\
+                      Proof. intros. lia. Qed.\n\
+                      Theorem big: True.\n\
+                      Admitted.\n\
+                      Example open : False.\n\
+                      Proof.\n";
+        let obligations = parse_obligations(source);
+
+        let summary: Vec<(&str, &str, usize, usize, ObligationStatus)> = obligations
+            .iter()
+            .map(|o| {
+                (
+                    o.kind.as_str(),
+                    o.name.as_str(),
+                    o.line,
+                    o.end_line,
+                    o.closed_as,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Lemma", "add_comm", 3, 4, ObligationStatus::Proved),
+                ("Theorem", "big", 5, 6, ObligationStatus::Admitted),
+                ("Example", "open", 7, 8, ObligationStatus::Failing),
+            ]
+        );
+        assert!(
+            obligations
+                .iter()
+                .all(|o| o.status == ObligationStatus::Pending)
+        );
+    }
+
+    #[test]
+    fn rocq_error_line_reads_file_header() {
+        let output = vec![
+            String::from("File \"./bad.v\", line 12, characters 0-5:"),
+            String::from("Error: Syntax error."),
+        ];
+        assert_eq!(rocq_error_line(&output), Some(12));
+        assert_eq!(rocq_error_line(&[String::from("Error: oops")]), None);
+    }
+
+    #[test]
+    fn verify_state_updates_files_as_results_arrive() {
+        let dir = std::env::temp_dir().join(format!("infs-verify-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(dir.join("proofs")).unwrap();
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("proofs/demo.v"),
+            "Lemma a : True.\nProof. exact I. Qed.\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("proofs/extra.v"),
+            "Lemma b : True.\nProof. exact I. Qed.\nLemma c : False.\nProof. bad. Qed.\nLemma d : True.\nAdmitted.\n",
+        )
+        .unwrap();
+
+        let mut state = VerifyState::new(vec![String::from("verify"), dir.display().to_string()]);
+        assert!(state.error.is_none(), "{:?}", state.error);
+        assert_eq!(state.files.len(), 2);
+        assert_eq!(state.counts(), (0, 0, 0, 4));
+
+        state.push_line("Compiling demo");
+        state.push_line("PASS proofs/demo.v");
+        assert_eq!(state.counts(), (1, 0, 0, 3));
+
+        state.push_line("FAIL proofs/extra.v");
+        state.push_line("    File \"./extra.v\", line 4, characters 7-10:");
+        state.push_line("    Error: The reference bad was not found.");
+        state.push_line("");
+        state.push_line("1 passed, 1 failed");
+        state.finish(Some(1));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let statuses: Vec<ObligationStatus> = state.files[1]
+            .obligations
+            .iter()
+            .map(|o| o.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ObligationStatus::Proved,
+                ObligationStatus::Failing,
+                ObligationStatus::Pending
+            ]
+        );
+        assert_eq!(state.files[1].output.len(), 2);
+        assert_eq!(state.log, ["Compiling demo", "1 passed, 1 failed"]);
+
+        state.select(2);
+        let (file, line, _) = state.selected_location().unwrap();
+        assert!(file.ends_with("proofs/extra.v"));
+        assert_eq!(line, 4);
+        state.select_next();
+        state.select_next();
+        assert_eq!(state.selected, 3);
+    }
+
+    #[test]
+    fn verify_state_reports_missing_manifest() {
+        let state = VerifyState::new(vec![
+            String::from("verify"),
+            String::from("/nonexistent/project"),
+        ]);
+        assert!(state.error.is_some());
+        assert!(state.files.is_empty());
+    }
 }
@@ -67,6 +67,25 @@ impl TerminalGuard {
 
         Ok(Self { terminal })
     }
+
+    /// Restores the terminal while `f` runs, e.g. to hand it over to an editor, then
+    /// sets it up for TUI mode again and redraws from scratch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the terminal cannot be set up again; the result of `f` is
+    /// returned otherwise.
+    pub fn suspend<T>(&mut self, f: impl FnOnce() -> T) -> Result<T> {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+
+        let result = f();
+
+        enable_raw_mode().context("failed to enable raw mode")?;
+        execute!(io::stdout(), EnterAlternateScreen).context("failed to enter alternate screen")?;
+        self.terminal.clear().context("failed to clear terminal")?;
+        Ok(result)
+    }
 }
 
 impl Drop for TerminalGuard {
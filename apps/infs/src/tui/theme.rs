@@ -1,7 +1,16 @@
 //! TUI theme system.
 //!
 //! This module provides a simple theme system for consistent styling
-//! across the TUI application. Currently only a dark theme is supported.
+//! across the TUI application. The built-in themes form a gallery, selected by
+//! name with `theme = "<name>"` in `tui.toml` or `:theme <name>` in the TUI:
+//!
+//! | Name | Description |
+//! |------|-------------|
+//! | `dark` | Default for dark terminal backgrounds |
+//! | `light` | For light terminal backgrounds |
+//! | `solarized` | Solarized dark palette |
+//! | `gruvbox` | Gruvbox dark palette |
+//! | `high-contrast` | Bright colors on any background |
 
 use ratatui::style::Color;
 
@@ -74,6 +83,81 @@ impl Theme {
         }
     }
 
+    /// Creates a theme with the Solarized dark palette.
+    #[must_use]
+    pub fn solarized() -> Self {
+        Self {
+            highlight: Color::Rgb(38, 139, 210), // Blue
+            selected: Color::Rgb(42, 161, 152),  // Cyan
+            border: Color::Rgb(88, 110, 117),    // Base01
+            success: Color::Rgb(133, 153, 0),    // Green
+            warning: Color::Rgb(181, 137, 0),    // Yellow
+            error: Color::Rgb(220, 50, 47),      // Red
+            muted: Color::Rgb(101, 123, 131),    // Base00
+            text: Color::Rgb(147, 161, 161),     // Base1
+            selected_bg: Color::Rgb(7, 54, 66),  // Base02
+        }
+    }
+
+    /// Creates a theme with the Gruvbox dark palette.
+    #[must_use]
+    pub fn gruvbox() -> Self {
+        Self {
+            highlight: Color::Rgb(250, 189, 47), // Yellow
+            selected: Color::Rgb(131, 165, 152), // Blue
+            border: Color::Rgb(102, 92, 84),     // Bg3
+            success: Color::Rgb(184, 187, 38),   // Green
+            warning: Color::Rgb(254, 128, 25),   // Orange
+            error: Color::Rgb(251, 73, 52),      // Red
+            muted: Color::Rgb(146, 131, 116),    // Gray
+            text: Color::Rgb(235, 219, 178),     // Fg
+            selected_bg: Color::Rgb(60, 56, 54), // Bg1
+        }
+    }
+
+    /// Creates a high contrast theme, readable on any background.
+    #[must_use]
+    pub fn high_contrast() -> Self {
+        Self {
+            highlight: Color::LightCyan,
+            selected: Color::LightYellow,
+            border: Color::White,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            muted: Color::Gray,
+            text: Color::White,
+            selected_bg: Color::Blue,
+        }
+    }
+
+    /// Names of the built-in themes, in gallery order.
+    pub const GALLERY: &'static [&'static str] =
+        &["dark", "light", "solarized", "gruvbox", "high-contrast"];
+
+    /// Returns the built-in theme called `name`, or `None` if there is none.
+    #[must_use]
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "gruvbox" => Some(Self::gruvbox()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the theme after `name` in the gallery, wrapping around.
+    #[must_use]
+    pub fn next_in_gallery(name: &str) -> &'static str {
+        let index = Self::GALLERY
+            .iter()
+            .position(|candidate| *candidate == name)
+            .map_or(0, |index| (index + 1) % Self::GALLERY.len());
+        Self::GALLERY[index]
+    }
+
     /// Detects the appropriate theme based on the COLORFGBG environment variable.
     ///
     /// The COLORFGBG format is "foreground;background" where both are ANSI color
@@ -89,6 +173,32 @@ impl Theme {
     pub fn detect() -> Self {
         detect_theme_from_env().unwrap_or_else(Self::dark)
     }
+
+    /// Returns the name of the theme `detect` returns.
+    #[must_use]
+    pub fn detect_name() -> &'static str {
+        std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| detect_name_from_colorfgbg(&value))
+            .unwrap_or("dark")
+    }
+
+    /// Returns the color called `name` (a field name such as `highlight`) for
+    /// overriding it, or `None` if there is no such color.
+    pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        match name {
+            "highlight" => Some(&mut self.highlight),
+            "selected" => Some(&mut self.selected),
+            "border" => Some(&mut self.border),
+            "success" => Some(&mut self.success),
+            "warning" => Some(&mut self.warning),
+            "error" => Some(&mut self.error),
+            "muted" => Some(&mut self.muted),
+            "text" => Some(&mut self.text),
+            "selected_bg" => Some(&mut self.selected_bg),
+            _ => None,
+        }
+    }
 }
 
 /// Attempts to detect the theme from the COLORFGBG environment variable.
@@ -101,6 +211,13 @@ fn detect_theme_from_env() -> Option<Theme> {
 ///
 /// Returns `None` if the format is invalid or background color cannot be determined.
 fn detect_theme_from_colorfgbg(value: &str) -> Option<Theme> {
+    detect_name_from_colorfgbg(value).and_then(Theme::named)
+}
+
+/// Parses the COLORFGBG value and returns the name of the appropriate theme.
+///
+/// Returns `None` if the format is invalid or background color cannot be determined.
+fn detect_name_from_colorfgbg(value: &str) -> Option<&'static str> {
     // Format: "foreground;background" or "foreground;background;..."
     let parts: Vec<&str> = value.split(';').collect();
 
@@ -123,9 +240,9 @@ fn detect_theme_from_colorfgbg(value: &str) -> Option<Theme> {
     // - bg >= 8: light background
     // - bg == 7: light gray, often used as light background
     if bg_color >= 8 || bg_color == 7 {
-        Some(Theme::light())
+        Some("light")
     } else {
-        Some(Theme::dark())
+        Some("dark")
     }
 }
 
@@ -164,6 +281,30 @@ mod tests {
         assert_eq!(theme.text, Color::Black);
     }
 
+    #[test]
+    fn gallery_themes_are_named() {
+        for name in Theme::GALLERY {
+            assert!(Theme::named(name).is_some(), "{name}");
+        }
+        assert!(Theme::named("neon").is_none());
+        assert_eq!(Theme::named("gruvbox").unwrap().text, Theme::gruvbox().text);
+    }
+
+    #[test]
+    fn color_mut_overrides_named_colors() {
+        let mut theme = Theme::dark();
+        *theme.color_mut("error").unwrap() = Color::Magenta;
+        assert_eq!(theme.error, Color::Magenta);
+        assert!(theme.color_mut("background").is_none());
+    }
+
+    #[test]
+    fn next_in_gallery_wraps_around() {
+        assert_eq!(Theme::next_in_gallery("dark"), "light");
+        assert_eq!(Theme::next_in_gallery("high-contrast"), "dark");
+        assert_eq!(Theme::next_in_gallery("custom"), "dark");
+    }
+
     #[test]
     fn detect_colorfgbg_dark_background() {
         // Black background (color 0)
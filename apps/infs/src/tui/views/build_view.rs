@@ -0,0 +1,235 @@
+//! Build log view rendering for the TUI.
+//!
+//! This module contains the rendering logic for the build log screen, showing the
+//! phases of the unit being built and the output of `infs build` or `infs check`
+//! as it arrives, colored by kind.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph},
+};
+
+use crate::tui::state::{BuildLogState, LogLevel, PhaseStatus};
+use crate::tui::theme::Theme;
+
+/// Renders the build log view.
+pub fn render(frame: &mut Frame, area: Rect, theme: &Theme, state: &BuildLogState) {
+    let chunks = Layout::vertical([
+        Constraint::Length(3), // Title and phase progress
+        Constraint::Length(1), // Phases
+        Constraint::Min(3),    // Output
+        Constraint::Length(3), // Status/help
+    ])
+    .split(area);
+
+    render_header(frame, chunks[0], theme, state);
+    render_phases(frame, chunks[1], theme, state);
+    render_log(frame, chunks[2], theme, state);
+    render_footer(frame, chunks[3], theme, state);
+}
+
+/// Renders the header with the command and the progress through the phases.
+fn render_header(frame: &mut Frame, area: Rect, theme: &Theme, state: &BuildLogState) {
+    let (label, style) = if state.running {
+        let unit = state.unit.as_deref().unwrap_or("Running");
+        (unit.to_string(), Style::default().fg(theme.highlight))
+    } else if state.succeeded() {
+        (String::from("Finished"), Style::default().fg(theme.success))
+    } else {
+        (String::from("Failed"), Style::default().fg(theme.error))
+    };
+    let ratio = if state.running {
+        state.phase_ratio()
+    } else {
+        1.0
+    };
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .title(format!(" {} ", state.title))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border)),
+        )
+        .gauge_style(style)
+        .label(label)
+        .ratio(ratio);
+
+    frame.render_widget(gauge, area);
+}
+
+/// Renders the status of every phase of the unit being built.
+fn render_phases(frame: &mut Frame, area: Rect, theme: &Theme, state: &BuildLogState) {
+    let mut spans = vec![Span::raw(" ")];
+    for (phase, status) in &state.phases {
+        let (marker, style) = match status {
+            PhaseStatus::Pending => ("[ ]", Style::default().fg(theme.muted)),
+            PhaseStatus::Running => (
+                "[..]",
+                Style::default()
+                    .fg(theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            PhaseStatus::Done => ("[OK]", Style::default().fg(theme.success)),
+            PhaseStatus::Failed => ("[!!]", Style::default().fg(theme.error)),
+        };
+        spans.push(Span::styled(format!(" {marker} {}", phase.label()), style));
+        spans.push(Span::raw("  "));
+    }
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+/// Renders the visible part of the output.
+fn render_log(frame: &mut Frame, area: Rect, theme: &Theme, state: &BuildLogState) {
+    let height = usize::from(area.height.saturating_sub(2));
+    let lines: Vec<Line> = state.lines[state.visible_range(height)]
+        .iter()
+        .map(|line| {
+            let style = match line.level {
+                LogLevel::Error => Style::default().fg(theme.error),
+                LogLevel::Warning => Style::default().fg(theme.warning),
+                LogLevel::Progress => Style::default().fg(theme.highlight),
+                LogLevel::Success => Style::default().fg(theme.success),
+                LogLevel::Plain => Style::default().fg(theme.text),
+            };
+            Line::from(Span::styled(line.text.as_str(), style))
+        })
+        .collect();
+
+    let title = if state.scroll > 0 {
+        format!(" Output ({} lines below) ", state.scroll)
+    } else {
+        String::from(" Output ")
+    };
+    let log = Paragraph::new(lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+
+    frame.render_widget(log, area);
+}
+
+/// Renders the footer with the outcome and help text.
+fn render_footer(frame: &mut Frame, area: Rect, theme: &Theme, state: &BuildLogState) {
+    let (errors, warnings) = state.diagnostic_counts();
+    let counts = format!("{errors} error(s), {warnings} warning(s)");
+    let status_text = if let Some(ref error) = state.error {
+        Line::from(vec![
+            Span::styled("Error: ", Style::default().fg(theme.error)),
+            Span::styled(error.as_str(), Style::default().fg(theme.error)),
+        ])
+    } else if state.running {
+        Line::from(vec![
+            Span::styled("Running... ", Style::default().fg(theme.highlight)),
+            Span::styled(
+                format!("{counts}  Up/Down scroll, g/G top/bottom"),
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    } else {
+        let (outcome, style) = match state.exit_code {
+            Some(0) => (
+                String::from("Succeeded"),
+                Style::default().fg(theme.success),
+            ),
+            Some(code) => (
+                format!("Failed with exit code {code}"),
+                Style::default().fg(theme.error),
+            ),
+            None => (String::from("Terminated"), Style::default().fg(theme.error)),
+        };
+        Line::from(vec![
+            Span::styled(format!("{outcome}, "), style),
+            Span::styled(
+                format!("{counts}  r run again, d diagnostics, Esc back"),
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    };
+
+    let footer = Paragraph::new(status_text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+
+    frame.render_widget(footer, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_terminal() -> Terminal<TestBackend> {
+        let backend = TestBackend::new(80, 24);
+        Terminal::new(backend).expect("Failed to create test terminal")
+    }
+
+    fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect()
+    }
+
+    #[test]
+    fn render_running_build_does_not_panic() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let state = BuildLogState::new(vec![String::from("build")]);
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+    }
+
+    #[test]
+    fn render_shows_latest_output_and_outcome() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut state = BuildLogState::new(vec![String::from("check")]);
+        for index in 0..50 {
+            state.push_line(format!("line {index}"));
+        }
+        state.push_line("error[E0001]: type mismatch");
+        state.finish(Some(1));
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        let text = buffer_text(&terminal);
+        assert!(text.contains("infs check"));
+        assert!(text.contains("error[E0001]: type mismatch"));
+        assert!(!text.contains("line 0 "));
+        assert!(text.contains("Failed with exit code 1"));
+    }
+
+    #[test]
+    fn render_error_does_not_panic() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut state = BuildLogState::new(vec![String::from("build")]);
+        state.fail("Failed to run infs");
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+    }
+}
@@ -0,0 +1,325 @@
+//! Diagnostics view rendering for the TUI.
+//!
+//! This module contains the rendering logic for the diagnostics screen, listing the
+//! errors and warnings of the last build with their location, and showing the source
+//! around the selected one with its span highlighted.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+
+use crate::tui::state::{Diagnostic, DiagnosticSeverity, DiagnosticsState};
+use crate::tui::theme::Theme;
+
+/// Number of source lines shown before and after the span of a diagnostic.
+const CONTEXT_LINES: usize = 3;
+
+/// Renders the diagnostics view.
+pub fn render(frame: &mut Frame, area: Rect, theme: &Theme, state: &DiagnosticsState) {
+    let chunks = Layout::vertical([
+        Constraint::Percentage(40), // Diagnostic list
+        Constraint::Min(6),         // Source snippet
+        Constraint::Length(3),      // Help text
+    ])
+    .split(area);
+
+    render_list(frame, chunks[0], theme, state);
+    render_snippet(frame, chunks[1], theme, state);
+    render_help(frame, chunks[2], theme);
+}
+
+/// Returns the color of a severity.
+fn severity_color(theme: &Theme, severity: DiagnosticSeverity) -> Color {
+    match severity {
+        DiagnosticSeverity::Error => theme.error,
+        DiagnosticSeverity::Warning => theme.warning,
+    }
+}
+
+/// Renders the list of diagnostics, scrolled to keep the selected one visible.
+fn render_list(frame: &mut Frame, area: Rect, theme: &Theme, state: &DiagnosticsState) {
+    let errors = state
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == DiagnosticSeverity::Error)
+        .count();
+    let warnings = state.diagnostics.len() - errors;
+    let title = format!(" Diagnostics: {errors} error(s), {warnings} warning(s) ");
+
+    let mut lines = Vec::new();
+    if state.diagnostics.is_empty() {
+        lines.push(Line::from(vec![Span::styled(
+            "  No diagnostics",
+            Style::default().fg(theme.muted),
+        )]));
+    }
+    for (idx, diagnostic) in state.diagnostics.iter().enumerate() {
+        let is_selected = idx == state.selected;
+        let prefix = if is_selected { "> " } else { "  " };
+        let message_style = if is_selected {
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let location = diagnostic
+            .location()
+            .unwrap_or_else(|| String::from("(no location)"));
+
+        lines.push(Line::from(vec![
+            Span::styled(prefix, message_style),
+            Span::styled(
+                diagnostic.header(),
+                Style::default().fg(severity_color(theme, diagnostic.severity)),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::raw("    "),
+            Span::styled(location, Style::default().fg(theme.muted)),
+        ]));
+    }
+
+    // Two lines per diagnostic; keep the selected one in view
+    let height = usize::from(area.height.saturating_sub(2));
+    let selected_end = (state.selected + 1) * 2;
+    let offset = selected_end.saturating_sub(height);
+    let offset = u16::try_from(offset).unwrap_or(u16::MAX);
+
+    let list = Paragraph::new(lines).scroll((offset, 0)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(list, area);
+}
+
+/// Renders the source around the selected diagnostic, its span highlighted.
+fn render_snippet(frame: &mut Frame, area: Rect, theme: &Theme, state: &DiagnosticsState) {
+    let mut title = String::from(" Source ");
+    let lines = match state.selected_diagnostic() {
+        None => Vec::new(),
+        Some(diagnostic) => {
+            if let Some(location) = diagnostic.location() {
+                title = format!(" {location} ");
+            }
+            match (state.source_lines(diagnostic), diagnostic.span) {
+                (Some(source), Some(_)) => snippet_lines(theme, diagnostic, source),
+                _ => vec![Line::from(vec![Span::styled(
+                    "  Source not available",
+                    Style::default().fg(theme.muted),
+                )])],
+            }
+        }
+    };
+
+    let snippet = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(snippet, area);
+}
+
+/// Returns the lines of `source` around the span of `diagnostic`, numbered, with the
+/// span highlighted and its message below its first line.
+fn snippet_lines<'a>(
+    theme: &Theme,
+    diagnostic: &'a Diagnostic,
+    source: &'a [String],
+) -> Vec<Line<'a>> {
+    let Some(span) = diagnostic.span else {
+        return Vec::new();
+    };
+    let color = severity_color(theme, diagnostic.severity);
+    let highlight = Style::default()
+        .fg(color)
+        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let gutter_style = Style::default().fg(theme.muted);
+
+    let first = span.start_line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (span.end_line + CONTEXT_LINES).min(source.len());
+    let width = last.to_string().len();
+
+    let mut lines = Vec::new();
+    for number in first..=last {
+        let text = source[number - 1].as_str();
+        let mut spans = vec![Span::styled(format!("{number:>width$} │ "), gutter_style)];
+        if (span.start_line..=span.end_line).contains(&number) {
+            let start = if number == span.start_line {
+                span.start_column
+            } else {
+                1
+            };
+            let end = if number == span.end_line {
+                span.end_column
+            } else {
+                text.len() + 1
+            };
+            let (before, marked, after) = split_columns(text, start, end);
+            spans.push(Span::styled(before, Style::default().fg(theme.text)));
+            spans.push(Span::styled(marked, highlight));
+            spans.push(Span::styled(after, Style::default().fg(theme.text)));
+            lines.push(Line::from(spans));
+
+            if number == span.start_line {
+                let indent = before.chars().count();
+                let carets = if span.start_line == span.end_line {
+                    marked.chars().count().max(1)
+                } else {
+                    1
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{:>width$} │ ", ""), gutter_style),
+                    Span::raw(" ".repeat(indent)),
+                    Span::styled(
+                        format!("{} {}", "^".repeat(carets), diagnostic.message),
+                        Style::default().fg(color),
+                    ),
+                ]));
+            }
+        } else {
+            spans.push(Span::styled(text, Style::default().fg(theme.text)));
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+/// Splits `text` at the 1-based byte columns `start` and `end` (exclusive), moved back
+/// to character boundaries and clamped to the line.
+fn split_columns(text: &str, start: usize, end: usize) -> (&str, &str, &str) {
+    let boundary = |column: usize| {
+        let mut index = column.saturating_sub(1).min(text.len());
+        while !text.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    };
+    let start = boundary(start);
+    let end = boundary(end).max(start);
+    (&text[..start], &text[start..end], &text[end..])
+}
+
+/// Renders the help text.
+fn render_help(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let help = Paragraph::new(Line::from(vec![Span::styled(
+        "  Up/Down select  Enter/e open in $EDITOR  Esc back to the log",
+        Style::default().fg(theme.muted),
+    )]))
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(help, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::state::DiagnosticSpan;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use std::path::PathBuf;
+
+    fn create_test_terminal() -> Terminal<TestBackend> {
+        let backend = TestBackend::new(80, 24);
+        Terminal::new(backend).expect("Failed to create test terminal")
+    }
+
+    fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect()
+    }
+
+    fn diagnostic() -> Diagnostic {
+        Diagnostic {
+            severity: DiagnosticSeverity::Error,
+            code: Some(String::from("E0001")),
+            message: String::from("type mismatch"),
+            file: Some(PathBuf::from("src/main.inf")),
+            span: Some(DiagnosticSpan {
+                start_line: 2,
+                start_column: 5,
+                end_line: 2,
+                end_column: 21,
+            }),
+            notes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn split_columns_clamps_to_line() {
+        assert_eq!(split_columns("let x = 1;", 5, 6), ("let ", "x", " = 1;"));
+        assert_eq!(split_columns("abc", 2, 99), ("a", "bc", ""));
+        assert_eq!(split_columns("é = 1", 2, 3), ("", "é", " = 1"));
+    }
+
+    #[test]
+    fn render_empty_does_not_panic() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let state = DiagnosticsState::default();
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+    }
+
+    #[test]
+    fn render_shows_list_and_highlighted_snippet() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut state = DiagnosticsState::new(vec![diagnostic()]);
+        state.sources.insert(
+            PathBuf::from("src/main.inf"),
+            Some(vec![
+                String::from("fn main() {"),
+                String::from("    let b: bool = 1;"),
+                String::from("}"),
+            ]),
+        );
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        let text = buffer_text(&terminal);
+        assert!(text.contains("error[E0001]: type mismatch"));
+        assert!(text.contains("src/main.inf:2:5"));
+        assert!(text.contains("2 │     let b: bool = 1;"));
+        assert!(text.contains("^^^^^^^^^^^^^^^^ type mismatch"));
+    }
+
+    #[test]
+    fn render_without_source_does_not_panic() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut state = DiagnosticsState::new(vec![diagnostic()]);
+        state.sources.clear();
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        assert!(buffer_text(&terminal).contains("Source not available"));
+    }
+}
@@ -4,14 +4,20 @@
 //!
 //! ## Views
 //!
+//! - [`build_view`] - Output of a build or check running in the background
 //! - [`main_view`] - Main menu screen with shortcuts and navigation
 //! - [`toolchain_view`] - Installed toolchains list
+//! - [`verify_view`] - Proof obligations of the project and their status
+//! - [`diagnostics_view`] - Errors and warnings of the last build, with their source
 //! - [`doctor_view`] - Doctor check results
 //! - [`progress_view`] - Download/operation progress display
 //! - [`version_select_view`] - Version selection for installation
 
+pub mod build_view;
+pub mod diagnostics_view;
 pub mod doctor_view;
 pub mod main_view;
 pub mod progress_view;
 pub mod toolchain_view;
+pub mod verify_view;
 pub mod version_select_view;
@@ -1,7 +1,8 @@
 //! Progress view rendering for the TUI.
 //!
 //! This module contains the rendering logic for the progress screen,
-//! showing download progress and operation status.
+//! showing download progress and operation status. Every item, such as a component
+//! of a toolchain being downloaded, has its own progress bar, all updated together.
 
 use ratatui::{
     Frame,
@@ -11,9 +12,12 @@ use ratatui::{
     widgets::{Block, Borders, Gauge, Paragraph},
 };
 
-use crate::tui::state::ProgressState;
+use crate::tui::state::{ProgressItem, ProgressState};
 use crate::tui::theme::Theme;
 
+/// Width of the progress bar of an item, in cells.
+const BAR_WIDTH: usize = 20;
+
 /// Renders the progress view.
 pub fn render(frame: &mut Frame, area: Rect, theme: &Theme, state: &ProgressState) {
     let chunks = Layout::vertical([
@@ -66,9 +70,30 @@ fn render_header(frame: &mut Frame, area: Rect, theme: &Theme, state: &ProgressS
     frame.render_widget(gauge, area);
 }
 
-/// Renders the list of progress items.
+/// Returns the progress bar of an item.
+fn progress_bar(item: &ProgressItem) -> String {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let filled = (item.percentage() * BAR_WIDTH as f64).round() as usize;
+    format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH.saturating_sub(filled))
+    )
+}
+
+/// Renders the list of progress items, each with its bar.
 fn render_items(frame: &mut Frame, area: Rect, theme: &Theme, state: &ProgressState) {
     let mut lines = Vec::new();
+    let name_width = state
+        .items
+        .iter()
+        .map(|item| item.description.chars().count())
+        .max()
+        .unwrap_or(0);
 
     if state.items.is_empty() {
         lines.push(Line::from(""));
@@ -97,6 +122,13 @@ fn render_items(frame: &mut Frame, area: Rect, theme: &Theme, state: &ProgressSt
             } else {
                 Style::default().fg(theme.text).add_modifier(Modifier::BOLD)
             };
+            let bar_style = if item.completed {
+                Style::default().fg(theme.success)
+            } else if item.total > 0 {
+                Style::default().fg(theme.highlight)
+            } else {
+                Style::default().fg(theme.muted)
+            };
 
             let progress_text = Span::styled(
                 format!("  {}", item.format_progress()),
@@ -117,7 +149,8 @@ fn render_items(frame: &mut Frame, area: Rect, theme: &Theme, state: &ProgressSt
             lines.push(Line::from(vec![
                 Span::raw("  "),
                 status_indicator,
-                Span::styled(&item.description, desc_style),
+                Span::styled(format!("{:<name_width$} ", item.description), desc_style),
+                Span::styled(progress_bar(item), bar_style),
                 progress_text,
                 speed_text,
             ]));
@@ -191,6 +224,40 @@ mod tests {
             .expect("Failed to draw");
     }
 
+    #[test]
+    fn render_shows_a_bar_per_item() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut state = ProgressState::new("Installing Toolchain");
+        let mut archive = ProgressItem::new("Toolchain archive");
+        archive.total = 1000;
+        archive.update_with_speed(500, 100);
+        let mut signature = ProgressItem::new("Signature");
+        signature.total = 100;
+        signature.complete();
+        state.add_item(archive);
+        state.add_item(signature);
+        state.add_item(ProgressItem::new("Extraction"));
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        let half = format!("{}{}", "█".repeat(10), "░".repeat(10));
+        assert!(text.contains(&format!("[ 50%] Toolchain archive {half}")));
+        assert!(text.contains(&format!("[OK] Signature         {}", "█".repeat(20))));
+        assert!(text.contains(&format!("[...] Extraction        {}", "░".repeat(20))));
+    }
+
     #[test]
     fn render_completed_does_not_panic() {
         let mut terminal = create_test_terminal();
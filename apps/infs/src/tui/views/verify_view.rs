@@ -0,0 +1,305 @@
+//! Verification dashboard rendering for the TUI.
+//!
+//! This module contains the rendering logic for the verification screen, listing the
+//! proof obligations of the project with their status and `.v` file, as `infs verify`
+//! checks them, and the compiler output of the selected file.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+};
+
+use crate::tui::state::{ObligationStatus, ProofFileStatus, VerifyState};
+use crate::tui::theme::Theme;
+
+/// Width of the obligation name column.
+const NAME_WIDTH: usize = 32;
+
+/// Renders the verification dashboard.
+pub fn render(frame: &mut Frame, area: Rect, theme: &Theme, state: &VerifyState) {
+    let chunks = Layout::vertical([
+        Constraint::Length(3), // Title and progress
+        Constraint::Min(5),    // Obligations
+        Constraint::Length(8), // Selected file
+        Constraint::Length(3), // Status/help
+    ])
+    .split(area);
+
+    render_header(frame, chunks[0], theme, state);
+    render_obligations(frame, chunks[1], theme, state);
+    render_details(frame, chunks[2], theme, state);
+    render_footer(frame, chunks[3], theme, state);
+}
+
+/// Returns the color of an obligation status.
+fn status_color(theme: &Theme, status: ObligationStatus) -> Color {
+    match status {
+        ObligationStatus::Pending => theme.muted,
+        ObligationStatus::Proved => theme.success,
+        ObligationStatus::Admitted => theme.warning,
+        ObligationStatus::Failing => theme.error,
+    }
+}
+
+/// Renders the header with the command and the share of files checked.
+fn render_header(frame: &mut Frame, area: Rect, theme: &Theme, state: &VerifyState) {
+    let checked = state
+        .files
+        .iter()
+        .filter(|file| file.status != ProofFileStatus::Pending)
+        .count();
+    let (label, style) = if state.running {
+        (
+            format!("Checking {checked}/{} file(s)", state.files.len()),
+            Style::default().fg(theme.highlight),
+        )
+    } else if state.exit_code == Some(0) {
+        (String::from("Verified"), Style::default().fg(theme.success))
+    } else {
+        (String::from("Failed"), Style::default().fg(theme.error))
+    };
+    let ratio = if state.running && !state.files.is_empty() {
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = checked as f64 / state.files.len() as f64;
+        ratio.min(1.0)
+    } else {
+        1.0
+    };
+
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .title(format!(" {} ", state.title))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border)),
+        )
+        .gauge_style(style)
+        .label(label)
+        .ratio(ratio);
+    frame.render_widget(gauge, area);
+}
+
+/// Renders the obligations of every file, scrolled to keep the selection visible.
+fn render_obligations(frame: &mut Frame, area: Rect, theme: &Theme, state: &VerifyState) {
+    let (proved, admitted, failing, pending) = state.counts();
+    let title = format!(
+        " Obligations: {proved} proved, {admitted} admitted, {failing} failing, {pending} pending "
+    );
+
+    let mut lines = Vec::new();
+    if state.files.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No proof files yet",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    for (row, (file_index, obligation)) in state.rows().into_iter().enumerate() {
+        let file = &state.files[file_index];
+        let is_selected = row == state.selected;
+        let prefix = if is_selected { "> " } else { "  " };
+        let name_style = if is_selected {
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+
+        let (status, name, location) =
+            if let Some(obligation) = obligation.map(|o| &file.obligations[o]) {
+                (
+                    obligation.status,
+                    format!("{} {}", obligation.kind, obligation.name),
+                    format!("{}:{}", file.path.display(), obligation.line),
+                )
+            } else {
+                let status = match file.status {
+                    ProofFileStatus::Pending => ObligationStatus::Pending,
+                    ProofFileStatus::Passed => ObligationStatus::Proved,
+                    ProofFileStatus::Failed => ObligationStatus::Failing,
+                };
+                (
+                    status,
+                    String::from("(no obligations)"),
+                    file.path.display().to_string(),
+                )
+            };
+        let label = if status == ObligationStatus::Pending && state.running {
+            "checking"
+        } else {
+            status.label()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled(prefix, name_style),
+            Span::styled(
+                format!("{label:<9}"),
+                Style::default().fg(status_color(theme, status)),
+            ),
+            Span::styled(format!(" {name:<NAME_WIDTH$} "), name_style),
+            Span::styled(location, Style::default().fg(theme.muted)),
+        ]));
+    }
+
+    let height = usize::from(area.height.saturating_sub(2));
+    let offset = (state.selected + 1).saturating_sub(height);
+    let offset = u16::try_from(offset).unwrap_or(u16::MAX);
+
+    let list = Paragraph::new(lines).scroll((offset, 0)).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(list, area);
+}
+
+/// Renders the compiler output of the selected file, or the last output of
+/// `infs verify` when the file has none.
+fn render_details(frame: &mut Frame, area: Rect, theme: &Theme, state: &VerifyState) {
+    let (title, output): (String, &[String]) = match state.selected_row() {
+        Some((file, _)) if !file.output.is_empty() => {
+            (format!(" {} ", file.path.display()), &file.output)
+        }
+        _ => (String::from(" Output "), &state.log),
+    };
+    let height = usize::from(area.height.saturating_sub(2));
+    let lines: Vec<Line> = output
+        .iter()
+        .skip(output.len().saturating_sub(height))
+        .map(|line| Line::from(Span::styled(line.as_str(), Style::default().fg(theme.text))))
+        .collect();
+
+    let details = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(details, area);
+}
+
+/// Renders the footer with the outcome and help text.
+fn render_footer(frame: &mut Frame, area: Rect, theme: &Theme, state: &VerifyState) {
+    let status_text = if let Some(ref error) = state.error {
+        Line::from(vec![
+            Span::styled("Error: ", Style::default().fg(theme.error)),
+            Span::styled(error.as_str(), Style::default().fg(theme.error)),
+        ])
+    } else if state.running {
+        Line::from(vec![
+            Span::styled("Verifying... ", Style::default().fg(theme.highlight)),
+            Span::styled(
+                "Up/Down select, Enter open in $EDITOR",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    } else {
+        let (outcome, style) = match state.exit_code {
+            Some(0) => (
+                String::from("All proof files passed"),
+                Style::default().fg(theme.success),
+            ),
+            Some(code) => (
+                format!("Failed with exit code {code}"),
+                Style::default().fg(theme.error),
+            ),
+            None => (String::from("Terminated"), Style::default().fg(theme.error)),
+        };
+        Line::from(vec![
+            Span::styled(format!("{outcome}  "), style),
+            Span::styled(
+                "Enter open in $EDITOR, r re-check, Esc back",
+                Style::default().fg(theme.muted),
+            ),
+        ])
+    };
+
+    let footer = Paragraph::new(status_text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border)),
+    );
+    frame.render_widget(footer, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::state::{ProofFile, VerifyPackage, parse_obligations};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use std::path::PathBuf;
+
+    fn create_test_terminal() -> Terminal<TestBackend> {
+        let backend = TestBackend::new(80, 24);
+        Terminal::new(backend).expect("Failed to create test terminal")
+    }
+
+    fn buffer_text(terminal: &Terminal<TestBackend>) -> String {
+        terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect()
+    }
+
+    #[test]
+    fn render_empty_does_not_panic() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let state = VerifyState::default();
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        assert!(buffer_text(&terminal).contains("No proof files yet"));
+    }
+
+    #[test]
+    fn render_shows_obligations_and_output() {
+        let mut terminal = create_test_terminal();
+        let theme = Theme::dark();
+        let mut obligations =
+            parse_obligations("Lemma ok : True.\nProof. exact I. Qed.\nLemma bad : False.\n");
+        obligations[0].status = ObligationStatus::Proved;
+        obligations[1].status = ObligationStatus::Failing;
+        let mut state = VerifyState::default();
+        state.title = String::from("infs verify");
+        state.packages.push(VerifyPackage {
+            name: String::from("demo"),
+            dir: PathBuf::from("."),
+        });
+        state.files.push(ProofFile {
+            package: 0,
+            path: PathBuf::from("proofs/demo.v"),
+            status: ProofFileStatus::Failed,
+            output: vec![String::from("Error: Syntax error.")],
+            obligations,
+        });
+        state.select(1);
+        state.finish(Some(1));
+
+        terminal
+            .draw(|frame| {
+                render(frame, frame.area(), &theme, &state);
+            })
+            .expect("Failed to draw");
+
+        let text = buffer_text(&terminal);
+        assert!(text.contains("1 proved, 0 admitted, 1 failing, 0 pending"));
+        assert!(text.contains("proved    Lemma ok"));
+        assert!(text.contains("failing   Lemma bad"));
+        assert!(text.contains("proofs/demo.v:3"));
+        assert!(text.contains("Error: Syntax error."));
+        assert!(text.contains("Failed with exit code 1"));
+    }
+}
@@ -5,6 +5,31 @@
 //! - Character insertion at cursor position
 //! - Backspace and delete operations
 //! - Command history navigation
+//!
+//! The history is searched with a fuzzy matcher, ranking the commands in which the
+//! query appears as a subsequence, and is kept across sessions in
+//! `~/.inference/tui_history` (`$INFERENCE_HOME/tui_history` when set), one command
+//! per line.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::toolchain::ToolchainPaths;
+use crate::tui::theme::Theme;
+
+/// Name of the file the command history is saved to.
+pub const HISTORY_FILE: &str = "tui_history";
+
+/// Maximum number of matches shown by the history search.
+const MAX_SEARCH_RESULTS: usize = 8;
 
 /// Command history for navigating previous inputs.
 #[derive(Debug, Clone, Default)]
@@ -100,6 +125,215 @@ impl CommandHistory {
         self.index = None;
         self.temp_input.clear();
     }
+
+    /// Loads the history saved at `path`, or an empty one if there is no such file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut history = Self::new();
+        if !path.exists() {
+            return Ok(history);
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        for line in text.lines() {
+            history.push(line.to_string());
+        }
+        Ok(history)
+    }
+
+    /// Saves the history to `path`, one command per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut text = self.commands.join("\n");
+        text.push('\n');
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Returns the distinct commands matching `query`, best match first and, among
+    /// equal matches, the most recent first.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<HistoryMatch<'_>> {
+        let mut matches: Vec<(i64, usize, HistoryMatch<'_>)> = Vec::new();
+        for (age, command) in self.commands.iter().rev().enumerate() {
+            if matches.iter().any(|(_, _, m)| m.command == command) {
+                continue;
+            }
+            if let Some((score, positions)) = fuzzy_match(query, command) {
+                matches.push((score, age, HistoryMatch { command, positions }));
+            }
+        }
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        matches.into_iter().map(|(_, _, m)| m).collect()
+    }
+}
+
+/// A command of the history matching a search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryMatch<'a> {
+    /// The command, as it was typed.
+    pub command: &'a str,
+    /// Indexes of the characters of the command matching the query.
+    pub positions: Vec<usize>,
+}
+
+/// Matches `query` against `candidate`, ignoring case.
+///
+/// The characters of the query must appear in the candidate in order. Returns the
+/// score of the match, higher for consecutive characters and characters starting a
+/// word, and the indexes of the matched characters, or `None` if it does not match.
+#[must_use]
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(i64, Vec<usize>)> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (index, c) in candidate.chars().enumerate() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            query.next();
+            score += 1;
+            if index > 0 && last_match == Some(index - 1) {
+                score += 5;
+            }
+            if previous.is_none_or(|p| p.is_whitespace() || matches!(p, '/' | '-' | '_' | '.')) {
+                score += 3;
+            }
+            if let Some(last) = last_match {
+                score -= i64::try_from(index - last - 1).unwrap_or(i64::MAX).min(3);
+            }
+            positions.push(index);
+            last_match = Some(index);
+        }
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some((score, positions))
+}
+
+/// State of a search through the command history.
+#[derive(Debug, Clone, Default)]
+pub struct HistorySearch {
+    /// Text searched for.
+    pub query: String,
+    /// Index of the selected match.
+    pub selected: usize,
+    /// Input of the command line when the search started.
+    pub saved_input: String,
+}
+
+impl HistorySearch {
+    /// Starts a search for `input`, the text of the command line.
+    #[must_use]
+    pub fn new(input: &str) -> Self {
+        Self {
+            query: input.to_string(),
+            selected: 0,
+            saved_input: input.to_string(),
+        }
+    }
+
+    /// Appends a character to the query.
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    /// Removes the last character of the query.
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    /// Selects the next (older or worse) match, out of `count`.
+    pub fn select_next(&mut self, count: usize) {
+        if self.selected + 1 < count.min(MAX_SEARCH_RESULTS) {
+            self.selected += 1;
+        }
+    }
+
+    /// Selects the previous (newer or better) match.
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
+/// Returns the path of the history file, if the toolchain directory is known.
+#[must_use]
+pub fn history_path() -> Option<PathBuf> {
+    ToolchainPaths::new()
+        .ok()
+        .map(|paths| paths.root.join(HISTORY_FILE))
+}
+
+/// Renders the matches of a history search in a popup at the bottom of `area`,
+/// above the command line.
+pub fn render_search(
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    history: &CommandHistory,
+    search: &HistorySearch,
+) {
+    let matches = history.search(&search.query);
+    let rows = u16::try_from(matches.len().clamp(1, MAX_SEARCH_RESULTS)).unwrap_or(1);
+    let height = (rows + 2).min(area.height.saturating_sub(4));
+    let popup = Rect {
+        x: area.x,
+        y: area.y + area.height.saturating_sub(4 + height),
+        width: area.width,
+        height,
+    };
+
+    let mut lines = Vec::new();
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "  No matching command",
+            Style::default().fg(theme.muted),
+        )));
+    }
+    for (index, found) in matches.iter().take(MAX_SEARCH_RESULTS).enumerate() {
+        let is_selected = index == search.selected;
+        let style = if is_selected {
+            Style::default()
+                .fg(theme.selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text)
+        };
+        let mut spans = vec![Span::styled(if is_selected { "> :" } else { "  :" }, style)];
+        for (position, c) in found.command.chars().enumerate() {
+            let style = if found.positions.contains(&position) {
+                style.fg(theme.highlight).add_modifier(Modifier::UNDERLINED)
+            } else {
+                style
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let list = Paragraph::new(lines).block(
+        Block::default()
+            .title(format!(" History: {} ", search.query))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.highlight)),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(list, popup);
 }
 
 #[cfg(test)]
@@ -150,4 +384,119 @@ mod tests {
         let prev = history.previous("new current");
         assert_eq!(prev, Some("cmd"));
     }
+
+    #[test]
+    fn fuzzy_match_ranks_consecutive_and_word_starts() {
+        let (score, positions) = fuzzy_match("bld", "build src/main.inf").unwrap();
+        assert_eq!(positions, vec![0, 3, 4]);
+        assert!(fuzzy_match("BLD", "build").is_some());
+        assert!(fuzzy_match("dlb", "build").is_none());
+        assert_eq!(fuzzy_match("", "build"), Some((0, Vec::new())));
+
+        let (consecutive, _) = fuzzy_match("check", "check").unwrap();
+        let (scattered, _) = fuzzy_match("check", "c-h-e-c-k").unwrap();
+        assert!(consecutive > scattered);
+        assert!(score > 0);
+    }
+
+    #[test]
+    fn search_returns_distinct_matches_best_first() {
+        let mut history = CommandHistory::new();
+        history.push("build src/a.inf".to_string());
+        history.push("doctor".to_string());
+        history.push("check src/b.inf".to_string());
+        history.push("build src/a.inf".to_string());
+        history.push("verify".to_string());
+
+        let commands: Vec<&str> = history.search("").iter().map(|m| m.command).collect();
+        assert_eq!(
+            commands,
+            vec!["verify", "build src/a.inf", "check src/b.inf", "doctor"]
+        );
+
+        let commands: Vec<&str> = history.search("src").iter().map(|m| m.command).collect();
+        assert_eq!(commands, vec!["build src/a.inf", "check src/b.inf"]);
+
+        let commands: Vec<&str> = history.search("ver").iter().map(|m| m.command).collect();
+        assert_eq!(commands, vec!["verify"]);
+        assert!(history.search("xyz").is_empty());
+    }
+
+    #[test]
+    fn history_search_selection_stays_in_bounds() {
+        let mut search = HistorySearch::new("bu");
+        search.select_previous();
+        assert_eq!(search.selected, 0);
+        search.select_next(2);
+        search.select_next(2);
+        assert_eq!(search.selected, 1);
+        search.push('i');
+        assert_eq!((search.query.as_str(), search.selected), ("bui", 0));
+        search.pop();
+        assert_eq!(search.query, "bu");
+        assert_eq!(search.saved_input, "bu");
+    }
+
+    #[test]
+    fn render_search_lists_matches() {
+        use ratatui::Terminal;
+        use ratatui::backend::TestBackend;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut history = CommandHistory::new();
+        history.push("build src/main.inf".to_string());
+        history.push("doctor".to_string());
+        let search = HistorySearch::new("bld");
+
+        terminal
+            .draw(|frame| {
+                render_search(frame, frame.area(), &Theme::dark(), &history, &search);
+            })
+            .unwrap();
+
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        assert!(text.contains("History: bld"));
+        assert!(text.contains("> :build src/main.inf"));
+        assert!(!text.contains(":doctor"));
+    }
+
+    #[test]
+    fn history_persists_across_sessions() {
+        let dir = std::env::temp_dir().join(format!("infs-history-{}", rand::random::<u64>()));
+        let path = dir.join(HISTORY_FILE);
+        assert!(CommandHistory::load(&path).unwrap().commands.is_empty());
+
+        let mut history = CommandHistory::new();
+        history.push("build src/main.inf --parse".to_string());
+        history.push("doctor".to_string());
+        history.save(&path).unwrap();
+
+        let mut loaded = CommandHistory::load(&path).unwrap();
+        assert_eq!(loaded.previous(""), Some("doctor"));
+        assert_eq!(loaded.previous(""), Some("build src/main.inf --parse"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_keeps_most_recent_commands() {
+        let dir = std::env::temp_dir().join(format!("infs-history-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(HISTORY_FILE);
+        let text = (0..150)
+            .map(|i| format!("cmd{i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, text).unwrap();
+
+        let history = CommandHistory::load(&path).unwrap();
+        assert_eq!(history.commands.len(), 100);
+        assert_eq!(history.commands[0], "cmd50");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -4,5 +4,6 @@
 //!
 //! - [`logo`] - Styled logo rendering with theme support
 //! - [`input_field`] - Advanced input field with cursor support
+//! - [`command_history`] - Command history with fuzzy search, saved across sessions
 
 pub mod command_history;
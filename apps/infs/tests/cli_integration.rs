@@ -35,12 +35,17 @@
 //!
 //! ### Phase 4-5: Verify Command
 //!
-//! 15. **Verify command**: Help display, path validation, coqc availability check
+//! 15. **Verify command**: Help display, path validation, coqc availability check,
+//!     per-file results
 //!
 //! ### Phase 6: Run Command
 //!
 //! 16. **Run command**: Help display, path validation, wasmtime availability check
 //!
+//! ### Phase 7: Fmt Command
+//!
+//! 17. **Fmt command**: File and project formatting, `--check`, syntax errors
+//!
 //! ## Test Infrastructure
 //!
 //! - Uses `assert_cmd` for spawning and asserting on command execution
@@ -170,6 +175,24 @@ fn build_fails_when_no_phase_selected() {
     );
 }
 
+/// Verifies that the build command validates the optimization level.
+///
+/// **Expected behavior**: Exit with a usage error mentioning `--opt-level`
+/// when the level is outside `0..=3`.
+#[test]
+fn build_rejects_out_of_range_opt_level() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("build")
+        .arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("-O")
+        .arg("4");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--opt-level"));
+}
+
 // =============================================================================
 // Success Path Tests
 // =============================================================================
@@ -806,6 +829,89 @@ fn doctor_shows_checking_message() {
         .stdout(predicate::str::contains("Checking Inference toolchain"));
 }
 
+/// Verifies that `infs doctor` reports the versions of wasmtime and the Rocq compiler.
+///
+/// **Test setup**: Stand-ins for wasmtime in PATH and coqc in `COQC_PATH`.
+///
+/// **Expected behavior**: Both checks pass and show the version each tool prints.
+#[cfg(unix)]
+#[test]
+fn doctor_reports_runtime_and_prover_versions() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("tools").create_dir_all().unwrap();
+    write_script(
+        temp.child("tools/wasmtime").path(),
+        "echo 'wasmtime 99.0.0 (test)'\n",
+    );
+    write_script(
+        temp.child("coqc").path(),
+        "echo 'The Rocq Prover, version 9.0.0'\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.child("home").path())
+        .env("PATH", temp.child("tools").path())
+        .env("COQC_PATH", temp.child("coqc").path())
+        .arg("doctor");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "[OK] wasmtime: wasmtime 99.0.0 (test)",
+        ))
+        .stdout(predicate::str::contains(
+            "[OK] Rocq: The Rocq Prover, version 9.0.0",
+        ))
+        .stdout(predicate::str::contains("[OK] Cache directories"));
+}
+
+/// Verifies that `infs doctor --fix` makes an installed toolchain the default.
+///
+/// **Test setup**: A toolchain with stand-in binaries, and no default.
+///
+/// **Expected behavior**: The fix sets the default, and the checks run again and pass
+/// for the default toolchain.
+#[cfg(unix)]
+#[test]
+fn doctor_fix_sets_installed_toolchain_as_default() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    for binary in ["infc", "inf-llc", "rust-lld"] {
+        let path = temp.child(format!("toolchains/0.2.0/bin/{binary}"));
+        path.touch().unwrap();
+        write_script(path.path(), &format!("echo '{binary} 0.2.0'\n"));
+    }
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .args(["doctor", "--fix"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Fixing: set 0.2.0 as the default toolchain",
+        ))
+        .stdout(predicate::str::contains(
+            "[OK] Default toolchain: Set to 0.2.0",
+        ));
+    temp.child("default").assert("0.2.0");
+}
+
+/// Verifies that `infs doctor --fix` is not combined with JSON output.
+///
+/// **Expected behavior**: Exit with non-zero code and report the conflict as JSON.
+#[test]
+fn doctor_fix_rejects_json_output() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .args(["doctor", "--fix", "--output", "json"]);
+
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("--fix cannot be combined"));
+}
+
 // -----------------------------------------------------------------------------
 // Self Update Command Tests
 // -----------------------------------------------------------------------------
@@ -879,6 +985,173 @@ fn install_uses_custom_dist_server() {
         .stderr(predicate::str::contains("invalid-test-server"));
 }
 
+/// Verifies that every `INFS_DIST_SERVER` mirror is tried before giving up.
+///
+/// **Test setup**: Sets `INFS_DIST_SERVER` to two unreachable mirrors.
+///
+/// **Expected behavior**: Exit with non-zero code and report the error of each mirror.
+#[test]
+fn install_tries_every_mirror() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .env(
+            "INFS_DIST_SERVER",
+            "http://first-mirror.localhost, http://second-mirror.localhost",
+        )
+        .arg("install");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("every distribution server"))
+        .stderr(predicate::str::contains("first-mirror"))
+        .stderr(predicate::str::contains("second-mirror"));
+}
+
+/// Verifies that `--offline` fails fast when the manifest is not cached.
+///
+/// **Test setup**: Uses an isolated `INFERENCE_HOME` without a cached manifest.
+///
+/// **Expected behavior**: `install`, `versions` and `self update` exit with non-zero
+/// code and explain that the manifest cannot be fetched offline, without contacting
+/// the distribution server.
+#[test]
+fn offline_without_cached_manifest_fails_fast() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    for args in [
+        &["install", "--offline"][..],
+        &["versions", "--offline", "--headless"],
+        &["self", "update", "--offline"],
+    ] {
+        let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+        cmd.env("INFERENCE_HOME", temp.path())
+            .env("INFS_DIST_SERVER", "http://invalid-test-server.localhost")
+            .args(args);
+
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("offline mode"))
+            .stderr(predicate::str::contains("invalid-test-server").not());
+    }
+}
+
+/// Verifies that `infs versions --offline` lists the cached manifest.
+///
+/// **Test setup**: Writes a cached manifest to an isolated `INFERENCE_HOME`.
+///
+/// **Expected behavior**: Exit with code 0 and list the cached versions.
+#[test]
+fn versions_offline_uses_cached_manifest() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("cache/manifest.json")
+        .write_str(
+            r#"{"manifest": [{"version": "0.4.2", "stable": true, "files": []}], "timestamp": 0}"#,
+        )
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .env("INFS_DIST_SERVER", "http://invalid-test-server.localhost")
+        .args(["versions", "--offline", "--headless"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("0.4.2"));
+}
+
+/// Verifies that `infs versions` shows the release channel of each version.
+///
+/// **Expected behavior**: A nightly is listed with its channel and release date, and a
+/// prerelease on no channel as `prerelease`.
+#[test]
+fn versions_shows_release_channels() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("cache/manifest.json")
+        .write_str(
+            r#"{"manifest": [
+                {"version": "0.4.2", "stable": true, "files": []},
+                {"version": "0.5.0-nightly.20261015", "stable": false, "channel": "nightly", "date": "2026-10-15", "files": []},
+                {"version": "0.5.0-alpha", "stable": false, "files": []}
+            ], "timestamp": 0}"#,
+        )
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .args(["versions", "--offline", "--headless"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "0.5.0-nightly.20261015 (nightly 2026-10-15)",
+        ))
+        .stdout(predicate::str::contains("0.4.2 (stable)"))
+        .stdout(predicate::str::contains("0.5.0-alpha (prerelease)"));
+}
+
+/// Verifies that installing from a channel without versions fails.
+///
+/// **Expected behavior**: Exit with non-zero code and name the empty channel.
+#[test]
+fn install_channel_without_versions_fails() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("cache/manifest.json")
+        .write_str(
+            r#"{"manifest": [{"version": "0.4.2", "stable": true, "files": []}], "timestamp": 0}"#,
+        )
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .args(["install", "beta", "--offline"]);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "No version found on the beta channel",
+    ));
+}
+
+/// Verifies that `infs self update` only accepts known channels.
+///
+/// **Expected behavior**: Exit with non-zero code and list the valid channels.
+#[test]
+fn self_update_rejects_unknown_channel() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.args(["self", "update", "--channel", "canary"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("nightly"));
+}
+
+/// Verifies that `infs list` shows the channel and release date of a toolchain.
+///
+/// **Test setup**: Writes the metadata of an installed nightly to an isolated
+/// `INFERENCE_HOME`.
+///
+/// **Expected behavior**: Exit with code 0 and show `nightly 2026-10-15`.
+#[test]
+fn list_shows_release_channel() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("toolchains/0.5.0-nightly/.metadata.json")
+        .write_str(
+            r#"{"installed_at": "2026-10-16", "channel": "nightly", "released_at": "2026-10-15"}"#,
+        )
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .env_remove("INFS_TOOLCHAIN")
+        .current_dir(temp.path())
+        .arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("0.5.0-nightly"))
+        .stdout(predicate::str::contains("nightly 2026-10-15"));
+}
+
 /// Verifies that `infs self` without a subcommand shows an error.
 ///
 /// **Expected behavior**: Exit with non-zero code when no subcommand is provided.
@@ -1367,6 +1640,81 @@ fn run_help_shows_options() {
         .stdout(predicate::str::contains("Run").or(predicate::str::contains("run")));
 }
 
+/// Verifies that `infs run --help` lists the host import options.
+///
+/// **Expected behavior**: Exit with code 0 and mention `--preload` and `--import`.
+#[test]
+fn run_help_shows_host_import_options() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("run").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--preload"))
+        .stdout(predicate::str::contains("--import-module"))
+        .stdout(predicate::str::contains("--import"));
+}
+
+/// Verifies that `infs run --help` lists the runtime configuration options.
+///
+/// **Expected behavior**: Exit with code 0 and mention the WASI and limit flags.
+#[test]
+fn run_help_shows_runtime_options() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("run").arg("--help");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("--dir"))
+        .stdout(predicate::str::contains("--env"))
+        .stdout(predicate::str::contains("--stdin"))
+        .stdout(predicate::str::contains("--fuel"))
+        .stdout(predicate::str::contains("--timeout"));
+}
+
+/// Verifies that `infs run` rejects a preopened directory that does not exist.
+///
+/// **Expected behavior**: Exit with non-zero code before compiling, naming the host
+/// directory.
+#[test]
+fn run_rejects_missing_preopened_directory() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("trivial.inf");
+    std::fs::copy(codegen_test_file("trivial.inf"), file.path()).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path())
+        .arg("run")
+        .arg(file.path())
+        .arg("--dir")
+        .arg("missing::data");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Directory not found: missing"));
+}
+
+/// Verifies that `infs run` rejects a `--stdin` file that does not exist.
+///
+/// **Expected behavior**: Exit with non-zero code before compiling.
+#[test]
+fn run_rejects_missing_stdin_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("trivial.inf");
+    std::fs::copy(codegen_test_file("trivial.inf"), file.path()).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path())
+        .arg("run")
+        .arg(file.path())
+        .arg("--stdin")
+        .arg("input.txt");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Stdin file not found"));
+}
+
 /// Verifies that `infs run` requires a path argument.
 ///
 /// **Expected behavior**: Exit with non-zero code when no path is provided.
@@ -1528,388 +1876,792 @@ fn build_fails_gracefully_on_syntax_error() {
     );
 }
 
-// =============================================================================
-// Helper Functions for QA Test Files
-// =============================================================================
+// -----------------------------------------------------------------------------
+// Verify Command Tests
+// -----------------------------------------------------------------------------
 
-/// Returns the path to `type_error.inf` test file.
-#[allow(dead_code)]
-fn type_error_file() -> std::path::PathBuf {
-    example_file("type_error.inf")
-}
+/// Verifies that `infs verify --help` displays the command description.
+///
+/// **Expected behavior**: Exit with code 0 and mention Rocq and the path argument.
+#[test]
+fn verify_help_shows_options() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("verify").arg("--help");
 
-/// Returns the path to `empty.inf` test file.
-fn empty_file() -> std::path::PathBuf {
-    example_file("empty.inf")
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Rocq"))
+        .stdout(predicate::str::contains("PATH").or(predicate::str::contains("path")));
 }
 
-/// Returns the path to `uzumaki.inf` test file.
-#[allow(dead_code)]
-fn uzumaki_file() -> std::path::PathBuf {
-    example_file("uzumaki.inf")
-}
+/// Verifies that `infs verify` requires a project directory.
+///
+/// **Expected behavior**: Exit with non-zero code and mention `Inference.toml`.
+#[test]
+fn verify_fails_outside_a_project() {
+    let temp = assert_fs::TempDir::new().unwrap();
 
-/// Returns the path to `forall_test.inf` test file.
-#[allow(dead_code)]
-fn forall_test_file() -> std::path::PathBuf {
-    example_file("forall_test.inf")
-}
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("verify");
 
-/// Returns the path to `exists_test.inf` test file.
-#[allow(dead_code)]
-fn exists_test_file() -> std::path::PathBuf {
-    example_file("exists_test.inf")
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No Inference.toml found"));
 }
 
-/// Returns the path to `assume_test.inf` test file.
-#[allow(dead_code)]
-fn assume_test_file() -> std::path::PathBuf {
-    example_file("assume_test.inf")
-}
+/// Verifies that `infs verify` reports an invalid `COQC_PATH`.
+///
+/// **Expected behavior**: Exit with non-zero code and name the variable.
+#[test]
+fn verify_shows_coqc_not_found_message() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
 
-/// Returns the path to `unique_test.inf` test file.
-#[allow(dead_code)]
-fn unique_test_file() -> std::path::PathBuf {
-    example_file("unique_test.inf")
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("COQC_PATH", "/nonexistent/path/to/coqc")
+        .current_dir(temp.path())
+        .arg("verify");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("COQC_PATH"));
 }
 
-// =============================================================================
-// QA Test Coverage: Migrated from docs/qa-test-suite.md
-// =============================================================================
+/// Writes an executable shell script to `path`.
+#[cfg(unix)]
+fn write_script(path: &std::path::Path, body: &str) {
+    use std::os::unix::fs::PermissionsExt;
 
-/// QA: TC-2.10 - Verify empty file is handled gracefully.
+    std::fs::write(path, format!("#!/bin/sh\n{body}")).unwrap();
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// Verifies the verify pipeline with stand-ins for infc and coqc: the stand-in infc
+/// writes the Rocq file it is asked to emit, and the stand-in coqc fails on files
+/// containing `Admitted`.
 ///
-/// **Expected behavior**: Exit with code 0 or specific empty-file error, no crash/panic.
+/// **Expected behavior**: The generated file is written to `proofs/` and checked first,
+/// every proof file is reported as PASS or FAIL, and the command fails because one
+/// proof does not compile.
+#[cfg(unix)]
 #[test]
-fn build_handles_empty_file() {
-    let Some(infc_path) = require_infc() else {
-        return;
-    };
+fn verify_reports_each_proof_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"my-demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 { return 0; }\n")
+        .unwrap();
+    temp.child("proofs/good.v")
+        .write_str("From my_demo Require Import my_demo.\n")
+        .unwrap();
+    temp.child("proofs/bad.v").write_str("Admitted.\n").unwrap();
+
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "for arg; do case \"$arg\" in --emit=v=*) echo 'Definition main := 0.' > \"${arg#--emit=v=}\";; esac; done\n",
+    );
+    let coqc = temp.child("coqc");
+    write_script(
+        coqc.path(),
+        "for file; do :; done\nif grep -q Admitted \"$file\"; then echo \"Error: $file has admitted proofs\" >&2; exit 1; fi\n",
+    );
 
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFC_PATH", &infc_path)
-        .arg("build")
-        .arg(empty_file())
-        .arg("--parse");
-
-    // Empty file should either succeed or fail gracefully (no panic)
-    let output = cmd.output().expect("Failed to execute command");
+    cmd.env("INFC_PATH", infc.path())
+        .env("COQC_PATH", coqc.path())
+        .current_dir(temp.path())
+        .arg("verify");
 
-    // Check that stderr doesn't contain panic messages
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd.assert().failure().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
     assert!(
-        !stderr.contains("panic") && !stderr.contains("RUST_BACKTRACE"),
-        "Empty file should not cause panic. Got: {stderr}"
+        stdout.starts_with("PASS proofs/my_demo.v\nFAIL proofs/bad.v\n"),
+        "unexpected output: {stdout}"
     );
+    assert!(stdout.contains("    Error: bad.v has admitted proofs"));
+    assert!(stdout.contains("PASS proofs/good.v"));
+    assert!(stdout.contains("2 passed, 1 failed"));
+    temp.child("proofs/my_demo.v")
+        .assert("Definition main := 0.\n");
 }
 
-/// QA: TC-5.9c - Verify `infs init` does not overwrite existing .gitignore.
-///
-/// **Expected behavior**: Exit with code 0, .gitignore contains original content.
+// -----------------------------------------------------------------------------
+// Expand Command Tests
+// -----------------------------------------------------------------------------
+
+/// Body of a stand-in infc that writes the `ast`, `typed` and `symbols` dumps it is
+/// asked to emit, then exits with `$EXIT_CODE`.
+#[cfg(unix)]
+const EXPAND_INFC: &str = r#"LOC='{"start_line":1,"start_column":1,"end_line":1,"end_column":9}'
+for arg; do
+  case "$arg" in
+    --emit=ast=*) echo "[{\"id\":1,\"location\":$LOC,\"source\":\"x\",\"definitions\":[{\"Constant\":{\"id\":2,\"location\":$LOC,\"value\":{\"Literal\":{\"Number\":{\"id\":3,\"location\":$LOC,\"value\":\"42\"}}}}}]}]" > "${arg#--emit=ast=}";;
+    --emit=typed=*) [ -n "$EXIT_CODE" ] || echo "{\"nodes\":[{\"id\":3,\"location\":$LOC,\"type\":\"i32\"}]}" > "${arg#--emit=typed=}";;
+    --emit=symbols=*) [ -n "$EXIT_CODE" ] || echo "{\"scopes\":[{\"id\":0,\"parent\":null,\"path\":\"\",\"symbols\":[{\"name\":\"ANSWER\",\"kind\":\"function\",\"type\":\"fn() -> i32\",\"public\":false,\"location\":$LOC}]}]}" > "${arg#--emit=symbols=}";;
+  esac
+done
+echo "Analyzed: $1"
+exit ${EXIT_CODE:-0}
+"#;
+
+/// Verifies that `infs expand` prints the AST with the inferred types, then the
+/// symbol table.
+///
+/// **Expected behavior**: Exit with code 0; the literal shows its variant path and
+/// type, the scope lists its symbol, and infc's progress message is not on stdout.
+#[cfg(unix)]
 #[test]
-fn init_preserves_existing_gitignore() {
+fn expand_prints_the_ast_and_symbol_table() {
     let temp = assert_fs::TempDir::new().unwrap();
-
-    // Create .git directory to trigger git file creation
-    std::fs::create_dir_all(temp.child(".git").path()).unwrap();
-
-    // Create custom .gitignore with specific content
-    let gitignore = temp.child(".gitignore");
-    std::fs::write(gitignore.path(), "custom-ignore-pattern\n").unwrap();
+    temp.child("main.inf")
+        .write_str("const ANSWER: i32 = 42;\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(infc.path(), EXPAND_INFC);
 
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.current_dir(temp.path()).arg("init").arg("test_project");
-
-    cmd.assert().success();
-
-    // Verify .gitignore still contains original content
-    let content = std::fs::read_to_string(gitignore.path()).expect("Failed to read .gitignore");
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .args(["expand", "main.inf"]);
 
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
     assert!(
-        content.contains("custom-ignore-pattern"),
-        ".gitignore should preserve existing content. Got: {content}"
+        stdout.starts_with("AST\n  SourceFile: #1 1:1-1:9\n"),
+        "unexpected output: {stdout}"
     );
+    assert!(stdout.contains("      value: Literal::Number #3 1:1-1:9 : i32 value=\"42\"\n"));
+    assert!(stdout.contains("Symbols\n  scope #0 (root)\n    function ANSWER: fn() -> i32"));
+    assert!(!stdout.contains("Analyzed"));
 }
 
-/// QA: TC-10.5 - Verify graceful handling of invalid `INFC_PATH`.
+/// Verifies that `infs expand --format json` prints the three dumps as one document.
 ///
-/// **Expected behavior**: Exit with non-zero code, clear error message.
+/// **Expected behavior**: Exit with code 0 and print JSON with `ast`, `types` and
+/// `symbols` keys.
+#[cfg(unix)]
 #[test]
-fn build_with_invalid_infc_path_shows_error() {
+fn expand_prints_json() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("main.inf")
+        .write_str("const ANSWER: i32 = 42;\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(infc.path(), EXPAND_INFC);
+
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFC_PATH", "/nonexistent/path/to/infc")
-        .arg("build")
-        .arg(example_file("example.inf"))
-        .arg("--parse");
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .args(["expand", "main.inf", "--format", "json"]);
 
-    cmd.assert().failure().stderr(
-        predicate::str::contains("not found")
-            .or(predicate::str::contains("No such file"))
-            .or(predicate::str::contains("does not exist"))
-            .or(predicate::str::contains("compiler not found")),
-    );
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(document["ast"][0]["id"], 1);
+    assert_eq!(document["types"][0]["type"], "i32");
+    assert_eq!(document["symbols"][0]["symbols"][0]["name"], "ANSWER");
 }
 
-/// QA: TC-12.3 - Verify recovery from corrupted toolchain metadata.
+/// Verifies that `infs expand` still prints the AST when type checking fails.
 ///
-/// **Expected behavior**: No crash, warning about corrupted metadata.
+/// **Expected behavior**: Exit with infc's exit code, after printing the AST without
+/// types or symbol table.
+#[cfg(unix)]
 #[test]
-fn list_handles_corrupted_metadata() {
+fn expand_prints_the_ast_when_type_checking_fails() {
     let temp = assert_fs::TempDir::new().unwrap();
-
-    // Create toolchain directory structure with corrupted metadata
-    let toolchain_dir = temp.child("toolchains").child("0.1.0");
-    std::fs::create_dir_all(toolchain_dir.path()).unwrap();
-
-    // Create a corrupted .metadata.json file
-    std::fs::write(
-        toolchain_dir.child(".metadata.json").path(),
-        "{ invalid json content",
-    )
-    .unwrap();
+    temp.child("main.inf")
+        .write_str("const ANSWER: i32 = true;\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(infc.path(), EXPAND_INFC);
 
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFERENCE_HOME", temp.path()).arg("list");
+    cmd.env("INFC_PATH", infc.path())
+        .env("EXIT_CODE", "3")
+        .current_dir(temp.path())
+        .args(["expand", "main.inf"]);
 
-    // Should not panic, may show warning or skip corrupted entry
-    let output = cmd.output().expect("Failed to execute command");
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = cmd.assert().code(3).get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.contains("value: Literal::Number #3 1:1-1:9 value=\"42\"\n"));
+    assert!(!stdout.contains("Symbols"));
+}
 
-    assert!(
-        !stderr.contains("panic") && !stderr.contains("RUST_BACKTRACE"),
-        "Corrupted metadata should not cause panic. Got: {stderr}"
-    );
+// -----------------------------------------------------------------------------
+// Repl Command Tests
+// -----------------------------------------------------------------------------
+
+/// Body of a stand-in infc for `infs repl`: it rejects sources containing `bad`, writes
+/// a typed dump with one node of type `$TYPE` spanning the last `$EXPR_LEN` bytes
+/// before the closing `;\n}\n` of the source, and copies the source to the WAT and
+/// WASM outputs.
+#[cfg(unix)]
+const REPL_INFC: &str = r#"SRC="$1"
+if grep -q bad "$SRC"; then echo "error: bad definition in $SRC" >&2; exit 1; fi
+for arg; do
+  case "$arg" in
+    --emit=typed=*) END=$(($(wc -c < "$SRC") - 4)); echo "{\"nodes\":[{\"id\":1,\"location\":{\"offset_start\":$((END - EXPR_LEN)),\"offset_end\":$END},\"type\":\"$TYPE\"}]}" > "${arg#--emit=typed=}";;
+    --emit=wat=*) cat "$SRC" > "${arg#--emit=wat=}";;
+    --emit=wasm=*) cp "$SRC" "${arg#--emit=wasm=}";;
+  esac
+done
+"#;
+
+/// Runs `infs repl` with the stand-in infc on `input`, with the environment `envs`.
+#[cfg(unix)]
+fn repl(
+    temp: &assert_fs::TempDir,
+    input: &str,
+    envs: &[(&str, &str)],
+) -> assert_cmd::assert::Assert {
+    let infc = temp.child("fake-infc");
+    write_script(infc.path(), REPL_INFC);
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .envs(envs.iter().copied())
+        .current_dir(temp.path())
+        .arg("repl");
+    assert_cmd::Command::from_std(cmd)
+        .write_stdin(input)
+        .assert()
 }
 
-/// QA: TC-13.1 - Verify compilation of uzumaki operator (@).
+/// Verifies that `:type` reports the type infc infers for the span of the expression.
 ///
-/// **Expected behavior**: Exit code 0, WASM binary generated.
+/// **Expected behavior**: The expression is printed with its type, in source syntax.
+#[cfg(unix)]
 #[test]
-fn build_compiles_uzumaki_operator() {
-    let Some(infc_path) = require_infc() else {
-        return;
-    };
-
+fn repl_reports_the_type_of_expressions() {
     let temp = assert_fs::TempDir::new().unwrap();
-    let src = codegen_test_file("nondet.inf");
-    let dest = temp.child("nondet.inf");
-    std::fs::copy(&src, dest.path()).unwrap();
 
-    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFC_PATH", &infc_path)
-        .current_dir(temp.path())
-        .arg("build")
-        .arg(dest.path())
-        .arg("--codegen")
-        .arg("-o");
+    repl(
+        &temp,
+        ":type 1 > 2\n",
+        &[("EXPR_LEN", "5"), ("TYPE", "Bool")],
+    )
+    .success()
+    .stdout("1 > 2 : bool\n");
+}
 
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("WASM generated"));
+/// Verifies that definitions are kept only if the session type checks with them, and
+/// that a definition may span several lines.
+///
+/// **Expected behavior**: `:items` and `:wat` show the accepted definition and not the
+/// rejected one, whose diagnostics are reported without the temporary file path.
+#[cfg(unix)]
+#[test]
+fn repl_keeps_definitions_that_type_check() {
+    let temp = assert_fs::TempDir::new().unwrap();
 
-    let wasm_output = temp.child("out").child("nondet.wasm");
-    assert!(
-        wasm_output.path().exists(),
-        "Expected WASM file at: {:?}",
-        wasm_output.path()
+    let assert = repl(
+        &temp,
+        "fn one() -> i32 {\n    return 1;\n}\nfn bad() {}\n:items\n:wat\n",
+        &[],
+    )
+    .success()
+    .stderr(predicate::str::contains("error: bad definition in <repl>"));
+    let stdout = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    assert_eq!(
+        stdout,
+        "fn one() -> i32 {\n    return 1;\n}\nfn one() -> i32 {\n    return 1;\n}\n"
     );
 }
 
-/// QA: TC-13.6 - Verify compilation of file with all non-det features.
+/// Verifies that an expression is evaluated by invoking the generated `__repl_eval`
+/// function with wasmtime.
 ///
-/// **Expected behavior**: Exit code 0, both WASM and Rocq outputs generated.
+/// **Expected behavior**: The value printed by the stand-in wasmtime is shown with the
+/// type of the expression; inputs after `:quit` are ignored.
+#[cfg(unix)]
 #[test]
-fn build_compiles_all_nondet_features() {
-    let Some(infc_path) = require_infc() else {
-        return;
-    };
-
+fn repl_evaluates_expressions_with_wasmtime() {
     let temp = assert_fs::TempDir::new().unwrap();
-    let src = codegen_test_file("nondet.inf");
-    let dest = temp.child("nondet.inf");
-    std::fs::copy(&src, dest.path()).unwrap();
-
-    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFC_PATH", &infc_path)
-        .current_dir(temp.path())
-        .arg("build")
-        .arg(dest.path())
-        .arg("--codegen")
-        .arg("-o")
-        .arg("-v");
-
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains("WASM generated"))
-        .stdout(predicate::str::contains("V generated"));
-
-    let wasm_output = temp.child("out").child("nondet.wasm");
-    let v_output = temp.child("out").child("nondet.v");
-    assert!(
-        wasm_output.path().exists(),
-        "Expected WASM file at: {:?}",
-        wasm_output.path()
+    let bin = temp.child("bin");
+    bin.create_dir_all().unwrap();
+    write_script(
+        &bin.path().join("wasmtime"),
+        "for f; do :; done\ngrep -q 'pub fn __repl_eval() -> i32 {' \"$f\" && grep -q 'return 1 + 2;' \"$f\" && echo 3\n",
     );
-    assert!(
-        v_output.path().exists(),
-        "Expected V file at: {:?}",
-        v_output.path()
+    let path = format!(
+        "{}:{}",
+        bin.path().display(),
+        std::env::var("PATH").unwrap_or_default()
     );
+
+    repl(
+        &temp,
+        "1 + 2\n:quit\n:type 1 + 2\n",
+        &[("EXPR_LEN", "5"), ("TYPE", "i32"), ("PATH", &path)],
+    )
+    .success()
+    .stdout("3 : i32\n");
 }
 
-/// QA: TC-2.11 - Verify phases execute in correct order regardless of flag order.
+/// Verifies that an unknown command is reported and the session goes on.
 ///
-/// **Expected behavior**: Exit code 0, phases execute in order: parse -> analyze -> codegen.
+/// **Expected behavior**: Exit with code 0, reporting the command on stderr and
+/// printing the help for `:help`.
+#[cfg(unix)]
 #[test]
-fn build_enforces_phase_order() {
-    let Some(infc_path) = require_infc() else {
-        return;
-    };
+fn repl_reports_unknown_commands() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    repl(&temp, ":frobnicate\n:help\n", &[])
+        .success()
+        .stderr(predicate::str::contains("Unknown command :frobnicate"))
+        .stdout(predicate::str::contains(":type <expr>"));
+}
+
+// -----------------------------------------------------------------------------
+// Fmt Command Tests
+// -----------------------------------------------------------------------------
+
+const UNFORMATTED_SOURCE: &str = "pub fn main()->i32{\n  // answer\n  return 40+2;\n}\n";
+const FORMATTED_SOURCE: &str = "pub fn main() -> i32 {\n    // answer\n    return 40 + 2;\n}\n";
 
+/// Verifies that `infs fmt` rewrites a single file in the canonical style.
+///
+/// **Expected behavior**: Exit with code 0, report the file and keep its comment.
+#[test]
+fn fmt_formats_a_file() {
     let temp = assert_fs::TempDir::new().unwrap();
-    let src = codegen_test_file("trivial.inf");
-    let dest = temp.child("trivial.inf");
-    std::fs::copy(&src, dest.path()).unwrap();
+    let file = temp.child("main.inf");
+    file.write_str(UNFORMATTED_SOURCE).unwrap();
 
-    // Run with flags in reverse order: --codegen --parse -o
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.env("INFC_PATH", &infc_path)
-        .current_dir(temp.path())
-        .arg("build")
-        .arg(dest.path())
-        .arg("--codegen")
-        .arg("--parse")
-        .arg("-o");
+    cmd.arg("fmt").arg(file.path());
 
     cmd.assert()
         .success()
-        .stdout(predicate::str::contains("Parsed:"))
-        .stdout(predicate::str::contains("WASM generated"));
+        .stdout(predicate::str::contains("Formatted:"));
+    file.assert(FORMATTED_SOURCE);
+}
 
-    let wasm_output = temp.child("out").child("trivial.wasm");
-    assert!(
-        wasm_output.path().exists(),
-        "Expected WASM file at: {:?}",
-        wasm_output.path()
-    );
+/// Verifies that `infs fmt` in a project directory formats the sources under `src/`.
+///
+/// **Expected behavior**: Nested sources are formatted; files outside `src/` are not.
+#[test]
+fn fmt_formats_the_project_sources() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\n")
+        .unwrap();
+    let main = temp.child("src/main.inf");
+    main.write_str(UNFORMATTED_SOURCE).unwrap();
+    let nested = temp.child("src/math/ops.inf");
+    nested.write_str(UNFORMATTED_SOURCE).unwrap();
+    let outside = temp.child("tests/scratch.inf");
+    outside.write_str(UNFORMATTED_SOURCE).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("fmt");
+
+    cmd.assert().success();
+    main.assert(FORMATTED_SOURCE);
+    nested.assert(FORMATTED_SOURCE);
+    outside.assert(UNFORMATTED_SOURCE);
 }
 
-/// QA: TC-1.6 - Verify graceful error on unknown subcommand.
+/// Verifies that `infs fmt --check` reports unformatted files without changing them.
 ///
-/// **Expected behavior**: Exit code non-zero, error message indicates unknown subcommand.
+/// **Expected behavior**: Exit with non-zero code, list the file, leave it unchanged.
 #[test]
-fn unknown_subcommand_shows_error() {
+fn fmt_check_reports_unformatted_files() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("main.inf");
+    file.write_str(UNFORMATTED_SOURCE).unwrap();
+
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd.arg("unknown-command");
+    cmd.arg("fmt").arg("--check").arg(file.path());
 
-    cmd.assert().failure().stderr(
-        predicate::str::contains("unrecognized")
-            .or(predicate::str::contains("not found"))
-            .or(predicate::str::contains("unknown")),
-    );
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Would reformat:"))
+        .stderr(predicate::str::contains("1 file(s) would be reformatted"));
+    file.assert(UNFORMATTED_SOURCE);
 }
 
-/// QA: TC-1.9 - Verify --version flag and version subcommand produce consistent output.
+/// Verifies that `infs fmt --check` succeeds on formatted files.
 ///
-/// **Expected behavior**: Both commands exit with code 0, both show same version.
+/// **Expected behavior**: Exit with code 0 and print nothing.
 #[test]
-fn version_flag_and_subcommand_are_consistent() {
-    let mut cmd_flag = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd_flag.arg("--version");
+fn fmt_check_accepts_formatted_files() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("main.inf");
+    file.write_str(FORMATTED_SOURCE).unwrap();
 
-    let mut cmd_subcmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
-    cmd_subcmd.arg("version");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("fmt").arg("--check").arg(file.path());
 
-    let flag_output = cmd_flag.output().expect("Failed to run --version");
-    let subcmd_output = cmd_subcmd.output().expect("Failed to run version");
+    cmd.assert().success().stdout(predicate::str::is_empty());
+}
 
-    assert!(flag_output.status.success(), "--version should succeed");
-    assert!(
-        subcmd_output.status.success(),
-        "version subcommand should succeed"
-    );
+/// **Expected behavior**: Exit with non-zero code, report the error position, leave the
+/// file unchanged, NO PANIC.
+#[test]
+fn fmt_fails_gracefully_on_syntax_error() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let file = temp.child("broken.inf");
+    let source = std::fs::read_to_string(syntax_error_file()).unwrap();
+    file.write_str(&source).unwrap();
 
-    let flag_stdout = String::from_utf8_lossy(&flag_output.stdout);
-    let subcmd_stdout = String::from_utf8_lossy(&subcmd_output.stdout);
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("fmt").arg(file.path());
 
-    // Both should contain the version number
-    let version = env!("CARGO_PKG_VERSION");
-    assert!(
-        flag_stdout.contains(version),
-        "--version should contain {version}"
-    );
-    assert!(
-        subcmd_stdout.contains(version),
-        "version subcommand should contain {version}"
-    );
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("syntax error"))
+        .stderr(predicate::str::contains("could not be formatted"));
+    file.assert(source.as_str());
 }
 
-/// QA: TC-12.1 - Verify error when output directory not writable.
+/// Verifies that `infs fmt` fails when the path doesn't exist.
 ///
-/// **Expected behavior**: Exit code non-zero, error indicates permission denied.
+/// **Expected behavior**: Exit with non-zero code and print "Path not found".
 #[test]
-#[cfg(unix)]
-fn build_fails_on_readonly_output_directory() {
-    use std::os::unix::fs::PermissionsExt;
+fn fmt_fails_when_path_missing() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("fmt").arg("nonexistent.inf");
 
-    let Some(infc_path) = require_infc() else {
-        return;
-    };
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Path not found"));
+}
+
+// -----------------------------------------------------------------------------
+// Toolchain Override Tests
+// -----------------------------------------------------------------------------
 
+/// Verifies that a toolchain pinned by `inference-toolchain.toml` must be installed.
+///
+/// **Test setup**: Empty `INFERENCE_HOME`, no `INFC_PATH`, and the pin in the parent
+/// of the working directory.
+///
+/// **Expected behavior**: `build` fails naming the pinned version and the file instead
+/// of falling back to another infc.
+#[test]
+fn build_fails_when_pinned_toolchain_missing() {
     let temp = assert_fs::TempDir::new().unwrap();
-    let src = codegen_test_file("trivial.inf");
-    let dest = temp.child("trivial.inf");
-    std::fs::copy(&src, dest.path()).unwrap();
+    temp.child("inference-toolchain.toml")
+        .write_str("[toolchain]\nversion = \"9.9.9\"\n")
+        .unwrap();
+    let source = temp.child("app/main.inf");
+    source.write_str(FORMATTED_SOURCE).unwrap();
 
-    // Create read-only output directory
-    let out_dir = temp.child("out");
-    std::fs::create_dir_all(out_dir.path()).unwrap();
-    let mut perms = std::fs::metadata(out_dir.path())
-        .expect("Failed to get metadata")
-        .permissions();
-    perms.set_mode(0o555);
-    std::fs::set_permissions(out_dir.path(), perms).expect("Failed to set permissions");
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.child("app").path())
+        .env("INFERENCE_HOME", temp.child("home").path())
+        .env_remove("INFC_PATH")
+        .env_remove("INFS_TOOLCHAIN")
+        .arg("build")
+        .arg("main.inf")
+        .arg("--parse");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Toolchain 9.9.9 is pinned by"))
+        .stderr(predicate::str::contains("inference-toolchain.toml"))
+        .stderr(predicate::str::contains("infs install 9.9.9"));
+}
+
+/// Verifies that `INFS_TOOLCHAIN` takes precedence over `inference-toolchain.toml`.
+///
+/// **Expected behavior**: `list` reports the version from the environment variable.
+#[test]
+fn list_reports_toolchain_pinned_by_environment() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("inference-toolchain.toml")
+        .write_str("[toolchain]\nversion = \"0.1.0\"\n")
+        .unwrap();
+    let home = temp.child("home");
+    home.child("toolchains/0.3.0").create_dir_all().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path())
+        .env("INFERENCE_HOME", home.path())
+        .env("INFS_TOOLCHAIN", "0.2.0")
+        .arg("list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Toolchain 0.2.0 is pinned by INFS_TOOLCHAIN environment variable.",
+        ))
+        .stdout(predicate::str::contains("infs install 0.2.0"));
+}
+
+// -----------------------------------------------------------------------------
+// Doc Command Tests
+// -----------------------------------------------------------------------------
+
+const DOCUMENTED_SOURCE: &str = "//! Vector math.\n\n/// Adds two numbers.\n\
+pub fn add(a: i32, b: i32) -> i32 {\n    return a + b;\n}\n\nfn helper() {}\n";
+
+/// Verifies that `infs doc` writes HTML pages for a project into `target/doc/`.
+///
+/// **Expected behavior**: Exit with code 0; the index lists the module, the module
+/// page shows the public function with its docs and a source link, private items are
+/// left out.
+#[test]
+fn doc_writes_html_pages_for_the_project() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("src/math/vector.inf")
+        .write_str(DOCUMENTED_SOURCE)
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("doc");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Documented 1 module(s)"));
+    temp.child("target/doc/index.html")
+        .assert(predicate::str::contains("math::vector</a> Vector math."));
+    let page = temp.child("target/doc/math.vector.html");
+    page.assert(predicate::str::contains("Adds two numbers."));
+    page.assert(predicate::str::contains("src/math.vector.html#L4"));
+    page.assert(predicate::str::contains("helper").not());
+    temp.child("target/doc/src/math.vector.html")
+        .assert(predicate::str::contains("<span id=\"L4\">"));
+}
+
+/// Verifies that `infs doc --format markdown --private` writes Markdown pages with
+/// private items and links to the source files.
+///
+/// **Expected behavior**: Exit with code 0 and link items to `../../src/...#L<line>`.
+#[test]
+fn doc_writes_markdown_pages_with_private_items() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str(DOCUMENTED_SOURCE)
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path())
+        .arg("doc")
+        .arg("--format")
+        .arg("markdown")
+        .arg("--private");
+
+    cmd.assert().success();
+    temp.child("target/doc/index.md")
+        .assert(predicate::str::contains("- [`main`](main.md) Vector math."));
+    let page = temp.child("target/doc/main.md");
+    page.assert(predicate::str::contains(
+        "([source](../../src/main.inf#L4))",
+    ));
+    page.assert(predicate::str::contains("function `helper`"));
+}
+
+/// **Expected behavior**: Exit with non-zero code and report the error position, NO
+/// PANIC.
+#[test]
+fn doc_fails_gracefully_on_syntax_error() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    let source = std::fs::read_to_string(syntax_error_file()).unwrap();
+    temp.child("src/main.inf").write_str(&source).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("doc");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("syntax error"))
+        .stderr(predicate::str::contains("could not be documented"));
+}
+
+/// Verifies that `infs doc` fails when the path doesn't exist.
+///
+/// **Expected behavior**: Exit with non-zero code and print "Path not found".
+#[test]
+fn doc_fails_when_path_missing() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("doc").arg("nonexistent.inf");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Path not found"));
+}
+
+// =============================================================================
+// Helper Functions for QA Test Files
+// =============================================================================
+
+/// Returns the path to `type_error.inf` test file.
+#[allow(dead_code)]
+fn type_error_file() -> std::path::PathBuf {
+    example_file("type_error.inf")
+}
+
+/// Returns the path to `empty.inf` test file.
+fn empty_file() -> std::path::PathBuf {
+    example_file("empty.inf")
+}
+
+/// Returns the path to `uzumaki.inf` test file.
+#[allow(dead_code)]
+fn uzumaki_file() -> std::path::PathBuf {
+    example_file("uzumaki.inf")
+}
+
+/// Returns the path to `forall_test.inf` test file.
+#[allow(dead_code)]
+fn forall_test_file() -> std::path::PathBuf {
+    example_file("forall_test.inf")
+}
+
+/// Returns the path to `exists_test.inf` test file.
+#[allow(dead_code)]
+fn exists_test_file() -> std::path::PathBuf {
+    example_file("exists_test.inf")
+}
+
+/// Returns the path to `assume_test.inf` test file.
+#[allow(dead_code)]
+fn assume_test_file() -> std::path::PathBuf {
+    example_file("assume_test.inf")
+}
+
+/// Returns the path to `unique_test.inf` test file.
+#[allow(dead_code)]
+fn unique_test_file() -> std::path::PathBuf {
+    example_file("unique_test.inf")
+}
+
+// =============================================================================
+// QA Test Coverage: Migrated from docs/qa-test-suite.md
+// =============================================================================
+
+/// QA: TC-2.10 - Verify empty file is handled gracefully.
+///
+/// **Expected behavior**: Exit with code 0 or specific empty-file error, no crash/panic.
+#[test]
+fn build_handles_empty_file() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
 
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
     cmd.env("INFC_PATH", &infc_path)
-        .current_dir(temp.path())
         .arg("build")
-        .arg(dest.path())
-        .arg("--codegen")
-        .arg("-o");
+        .arg(empty_file())
+        .arg("--parse");
+
+    // Empty file should either succeed or fail gracefully (no panic)
+    let output = cmd.output().expect("Failed to execute command");
+
+    // Check that stderr doesn't contain panic messages
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("panic") && !stderr.contains("RUST_BACKTRACE"),
+        "Empty file should not cause panic. Got: {stderr}"
+    );
+}
+
+/// QA: TC-5.9c - Verify `infs init` does not overwrite existing .gitignore.
+///
+/// **Expected behavior**: Exit with code 0, .gitignore contains original content.
+#[test]
+fn init_preserves_existing_gitignore() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    // Create .git directory to trigger git file creation
+    std::fs::create_dir_all(temp.child(".git").path()).unwrap();
+
+    // Create custom .gitignore with specific content
+    let gitignore = temp.child(".gitignore");
+    std::fs::write(gitignore.path(), "custom-ignore-pattern\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("init").arg("test_project");
+
+    cmd.assert().success();
+
+    // Verify .gitignore still contains original content
+    let content = std::fs::read_to_string(gitignore.path()).expect("Failed to read .gitignore");
+
+    assert!(
+        content.contains("custom-ignore-pattern"),
+        ".gitignore should preserve existing content. Got: {content}"
+    );
+}
+
+/// QA: TC-10.5 - Verify graceful handling of invalid `INFC_PATH`.
+///
+/// **Expected behavior**: Exit with non-zero code, clear error message.
+#[test]
+fn build_with_invalid_infc_path_shows_error() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", "/nonexistent/path/to/infc")
+        .arg("build")
+        .arg(example_file("example.inf"))
+        .arg("--parse");
 
     cmd.assert().failure().stderr(
-        predicate::str::contains("permission")
-            .or(predicate::str::contains("Permission"))
-            .or(predicate::str::contains("denied"))
-            .or(predicate::str::contains("Failed")),
+        predicate::str::contains("not found")
+            .or(predicate::str::contains("No such file"))
+            .or(predicate::str::contains("does not exist"))
+            .or(predicate::str::contains("compiler not found")),
     );
+}
 
-    // Restore permissions for cleanup
-    let mut perms = std::fs::metadata(out_dir.path())
-        .expect("Failed to get metadata")
-        .permissions();
-    perms.set_mode(0o755);
-    std::fs::set_permissions(out_dir.path(), perms).expect("Failed to restore permissions");
+/// QA: TC-12.3 - Verify recovery from corrupted toolchain metadata.
+///
+/// **Expected behavior**: No crash, warning about corrupted metadata.
+#[test]
+fn list_handles_corrupted_metadata() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    // Create toolchain directory structure with corrupted metadata
+    let toolchain_dir = temp.child("toolchains").child("0.1.0");
+    std::fs::create_dir_all(toolchain_dir.path()).unwrap();
+
+    // Create a corrupted .metadata.json file
+    std::fs::write(
+        toolchain_dir.child(".metadata.json").path(),
+        "{ invalid json content",
+    )
+    .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path()).arg("list");
+
+    // Should not panic, may show warning or skip corrupted entry
+    let output = cmd.output().expect("Failed to execute command");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        !stderr.contains("panic") && !stderr.contains("RUST_BACKTRACE"),
+        "Corrupted metadata should not cause panic. Got: {stderr}"
+    );
 }
 
-/// QA: TC-11.4 - Verify correct path handling with subdirectories.
+/// QA: TC-13.1 - Verify compilation of uzumaki operator (@).
 ///
-/// **Expected behavior**: Path resolved correctly, build succeeds.
+/// **Expected behavior**: Exit code 0, WASM binary generated.
 #[test]
-fn build_handles_nested_paths() {
+fn build_compiles_uzumaki_operator() {
     let Some(infc_path) = require_infc() else {
         return;
     };
 
     let temp = assert_fs::TempDir::new().unwrap();
-
-    // Create nested directory structure
-    let nested_dir = temp.child("subdir").child("nested");
-    std::fs::create_dir_all(nested_dir.path()).unwrap();
-
-    let src = codegen_test_file("trivial.inf");
-    let dest = nested_dir.child("test.inf");
+    let src = codegen_test_file("nondet.inf");
+    let dest = temp.child("nondet.inf");
     std::fs::copy(&src, dest.path()).unwrap();
 
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
@@ -1917,9 +2669,1157 @@ fn build_handles_nested_paths() {
         .current_dir(temp.path())
         .arg("build")
         .arg(dest.path())
-        .arg("--parse");
+        .arg("--codegen")
+        .arg("-o");
 
     cmd.assert()
         .success()
-        .stdout(predicate::str::contains("Parsed:"));
+        .stdout(predicate::str::contains("WASM generated"));
+
+    let wasm_output = temp.child("out").child("nondet.wasm");
+    assert!(
+        wasm_output.path().exists(),
+        "Expected WASM file at: {:?}",
+        wasm_output.path()
+    );
+}
+
+/// QA: TC-13.6 - Verify compilation of file with all non-det features.
+///
+/// **Expected behavior**: Exit code 0, both WASM and Rocq outputs generated.
+#[test]
+fn build_compiles_all_nondet_features() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = codegen_test_file("nondet.inf");
+    let dest = temp.child("nondet.inf");
+    std::fs::copy(&src, dest.path()).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("build")
+        .arg(dest.path())
+        .arg("--codegen")
+        .arg("-o")
+        .arg("-v");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("WASM generated"))
+        .stdout(predicate::str::contains("V generated"));
+
+    let wasm_output = temp.child("out").child("nondet.wasm");
+    let v_output = temp.child("out").child("nondet.v");
+    assert!(
+        wasm_output.path().exists(),
+        "Expected WASM file at: {:?}",
+        wasm_output.path()
+    );
+    assert!(
+        v_output.path().exists(),
+        "Expected V file at: {:?}",
+        v_output.path()
+    );
+}
+
+/// QA: TC-2.11 - Verify phases execute in correct order regardless of flag order.
+///
+/// **Expected behavior**: Exit code 0, phases execute in order: parse -> analyze -> codegen.
+#[test]
+fn build_enforces_phase_order() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = codegen_test_file("trivial.inf");
+    let dest = temp.child("trivial.inf");
+    std::fs::copy(&src, dest.path()).unwrap();
+
+    // Run with flags in reverse order: --codegen --parse -o
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("build")
+        .arg(dest.path())
+        .arg("--codegen")
+        .arg("--parse")
+        .arg("-o");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Parsed:"))
+        .stdout(predicate::str::contains("WASM generated"));
+
+    let wasm_output = temp.child("out").child("trivial.wasm");
+    assert!(
+        wasm_output.path().exists(),
+        "Expected WASM file at: {:?}",
+        wasm_output.path()
+    );
+}
+
+/// QA: TC-1.6 - Verify graceful error on unknown subcommand.
+///
+/// **Expected behavior**: Exit code non-zero, error message indicates unknown subcommand.
+#[test]
+fn unknown_subcommand_shows_error() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.arg("unknown-command");
+
+    cmd.assert().failure().stderr(
+        predicate::str::contains("unrecognized")
+            .or(predicate::str::contains("not found"))
+            .or(predicate::str::contains("unknown")),
+    );
+}
+
+/// QA: TC-1.9 - Verify --version flag and version subcommand produce consistent output.
+///
+/// **Expected behavior**: Both commands exit with code 0, both show same version.
+#[test]
+fn version_flag_and_subcommand_are_consistent() {
+    let mut cmd_flag = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd_flag.arg("--version");
+
+    let mut cmd_subcmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd_subcmd.arg("version");
+
+    let flag_output = cmd_flag.output().expect("Failed to run --version");
+    let subcmd_output = cmd_subcmd.output().expect("Failed to run version");
+
+    assert!(flag_output.status.success(), "--version should succeed");
+    assert!(
+        subcmd_output.status.success(),
+        "version subcommand should succeed"
+    );
+
+    let flag_stdout = String::from_utf8_lossy(&flag_output.stdout);
+    let subcmd_stdout = String::from_utf8_lossy(&subcmd_output.stdout);
+
+    // Both should contain the version number
+    let version = env!("CARGO_PKG_VERSION");
+    assert!(
+        flag_stdout.contains(version),
+        "--version should contain {version}"
+    );
+    assert!(
+        subcmd_stdout.contains(version),
+        "version subcommand should contain {version}"
+    );
+}
+
+/// QA: TC-12.1 - Verify error when output directory not writable.
+///
+/// **Expected behavior**: Exit code non-zero, error indicates permission denied.
+#[test]
+#[cfg(unix)]
+fn build_fails_on_readonly_output_directory() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let src = codegen_test_file("trivial.inf");
+    let dest = temp.child("trivial.inf");
+    std::fs::copy(&src, dest.path()).unwrap();
+
+    // Create read-only output directory
+    let out_dir = temp.child("out");
+    std::fs::create_dir_all(out_dir.path()).unwrap();
+    let mut perms = std::fs::metadata(out_dir.path())
+        .expect("Failed to get metadata")
+        .permissions();
+    perms.set_mode(0o555);
+    std::fs::set_permissions(out_dir.path(), perms).expect("Failed to set permissions");
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("build")
+        .arg(dest.path())
+        .arg("--codegen")
+        .arg("-o");
+
+    cmd.assert().failure().stderr(
+        predicate::str::contains("permission")
+            .or(predicate::str::contains("Permission"))
+            .or(predicate::str::contains("denied"))
+            .or(predicate::str::contains("Failed")),
+    );
+
+    // Restore permissions for cleanup
+    let mut perms = std::fs::metadata(out_dir.path())
+        .expect("Failed to get metadata")
+        .permissions();
+    perms.set_mode(0o755);
+    std::fs::set_permissions(out_dir.path(), perms).expect("Failed to restore permissions");
+}
+
+/// QA: TC-11.4 - Verify correct path handling with subdirectories.
+///
+/// **Expected behavior**: Path resolved correctly, build succeeds.
+#[test]
+fn build_handles_nested_paths() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    // Create nested directory structure
+    let nested_dir = temp.child("subdir").child("nested");
+    std::fs::create_dir_all(nested_dir.path()).unwrap();
+
+    let src = codegen_test_file("trivial.inf");
+    let dest = nested_dir.child("test.inf");
+    std::fs::copy(&src, dest.path()).unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("build")
+        .arg(dest.path())
+        .arg("--parse");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Parsed:"));
+}
+
+// -----------------------------------------------------------------------------
+// Project Build Tests
+// -----------------------------------------------------------------------------
+
+/// Writes a project whose `main` calls a function defined in another file under `src/`.
+fn write_two_file_project(temp: &assert_fs::TempDir) {
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return double(21);\n}\n")
+        .unwrap();
+    temp.child("src/math/double.inf")
+        .write_str("fn double(x: i32) -> i32 {\n    return x * 2;\n}\n")
+        .unwrap();
+}
+
+/// Verifies that `infs build` without a path builds the project in the current directory.
+///
+/// **Expected behavior**: infc receives the project directory and writes its artifacts
+/// to `target/` in the project.
+#[cfg(unix)]
+#[test]
+fn build_without_path_builds_the_project() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen")
+        .arg("-o")
+        .arg("--incremental");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert(". --out-dir ./target --codegen --emit=wasm -O 0 --cache-dir ./target/cache\n");
+}
+
+/// Verifies that `infs build` given a manifest builds the project it describes.
+///
+/// **Expected behavior**: infc receives the manifest and the project's `target/`.
+#[cfg(unix)]
+#[test]
+fn build_with_manifest_path_builds_the_project() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("Inference.toml")
+        .arg("--parse");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert("Inference.toml --out-dir target --parse\n");
+}
+
+/// Verifies that `infs build` refuses a directory that is not a project.
+///
+/// **Expected behavior**: Exit with non-zero code and mention `Inference.toml`.
+#[test]
+fn build_fails_in_directory_without_manifest() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("build").arg("--parse");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No Inference.toml found"));
+}
+
+/// Verifies that a project spread over several files compiles to one module.
+///
+/// **Expected behavior**: Build succeeds and the WASM binary is written to `target/`.
+#[test]
+fn build_compiles_multi_file_project_into_target() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen")
+        .arg("-o");
+
+    cmd.assert().success();
+    let wasm_files = std::fs::read_dir(temp.child("target").path())
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .path()
+                .extension()
+                .is_some_and(|extension| extension == "wasm")
+        })
+        .count();
+    assert_eq!(wasm_files, 1);
+    assert!(!temp.child("out").exists());
+}
+
+// -----------------------------------------------------------------------------
+// Dependency Tests
+// -----------------------------------------------------------------------------
+
+/// Writes a project in `app/` depending on the module in `mathlib/` by path.
+fn write_project_with_path_dependency(temp: &assert_fs::TempDir) {
+    temp.child("app/Inference.toml")
+        .write_str(
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n\
+             [dependencies]\nmathlib = { path = \"../mathlib\" }\n",
+        )
+        .unwrap();
+    temp.child("app/src/main.inf")
+        .write_str("use mathlib::add;\n\nfn main() -> i32 {\n    return add(1, 2);\n}\n")
+        .unwrap();
+    temp.child("mathlib/src/lib.inf")
+        .write_str("pub fn add(a: i32, b: i32) -> i32 {\n    return a + b;\n}\n")
+        .unwrap();
+}
+
+/// Verifies that `infs build` passes path dependencies to infc and locks them.
+///
+/// **Expected behavior**: infc receives `--extern mathlib=<dir>`, and `Inference.lock`
+/// records the dependency with its path source.
+#[cfg(unix)]
+#[test]
+fn build_passes_path_dependencies_to_infc() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_project_with_path_dependency(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.child("app").path())
+        .arg("build")
+        .arg("--analyze");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert(". --out-dir ./target --extern mathlib=./../mathlib --analyze\n");
+    temp.child("app/Inference.lock")
+        .assert(predicate::str::contains("name = \"mathlib\""))
+        .assert(predicate::str::contains("source = \"path+../mathlib\""));
+}
+
+/// Verifies that a path dependency whose directory does not exist stops the build.
+///
+/// **Expected behavior**: Exit with non-zero code, naming the dependency, before infc
+/// runs.
+#[cfg(unix)]
+#[test]
+fn build_fails_for_missing_path_dependency() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_project_with_path_dependency(&temp);
+    std::fs::remove_dir_all(temp.child("mathlib").path()).unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.child("app").path())
+        .arg("build")
+        .arg("--analyze");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Dependency `mathlib`"));
+    temp.child("args.txt").assert(predicate::path::missing());
+}
+
+/// Verifies that a project type-checks against the definitions of a path dependency.
+///
+/// **Expected behavior**: Analysis succeeds with the imported function.
+#[test]
+fn build_analyzes_project_with_path_dependency() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_project_with_path_dependency(&temp);
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.child("app").path())
+        .arg("build")
+        .arg("--analyze");
+
+    cmd.assert().success();
+}
+
+/// Writes a virtual workspace whose members are the `app` and `mathlib` projects of
+/// [`write_project_with_path_dependency`].
+fn write_workspace(temp: &assert_fs::TempDir) {
+    write_project_with_path_dependency(temp);
+    temp.child("mathlib/Inference.toml")
+        .write_str("[package]\nname = \"mathlib\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("Inference.toml")
+        .write_str("[workspace]\nmembers = [\"app\", \"mathlib\"]\n")
+        .unwrap();
+}
+
+/// Verifies that `infs build` at the root of a workspace builds every member.
+///
+/// **Expected behavior**: infc runs once per member, writing to `target/<name>/` of the
+/// root, and the dependencies of all members are locked in the root `Inference.lock`.
+#[cfg(unix)]
+#[test]
+fn build_workspace_builds_every_member() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_workspace(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" >> \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--analyze");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Compiling app v0.1.0"))
+        .stderr(predicate::str::contains("Compiling mathlib v0.1.0"));
+    let args = std::fs::read_to_string(temp.child("args.txt").path()).unwrap();
+    let lines: Vec<&str> = args
+        .lines()
+        .filter(|line| line.contains("--analyze"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("/target/app --extern mathlib="));
+    assert!(lines[1].contains("/target/mathlib --analyze"));
+    temp.child("Inference.lock")
+        .assert(predicate::str::contains("source = \"path+app/../mathlib\""));
+    temp.child("app/Inference.lock")
+        .assert(predicate::path::missing());
+}
+
+/// Verifies that `infs check` in a workspace member checks only that member.
+///
+/// **Expected behavior**: infc runs once, on the member directory, with the
+/// dependencies resolved through the workspace.
+#[cfg(unix)]
+#[test]
+fn check_in_workspace_member_checks_only_that_member() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_workspace(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" >> \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.child("mathlib").path())
+        .arg("check");
+
+    cmd.assert().success();
+    let args = std::fs::read_to_string(temp.child("args.txt").path()).unwrap();
+    assert_eq!(args.lines().count(), 1);
+    assert!(args.ends_with("/mathlib --analyze\n"));
+}
+
+/// Verifies that a workspace member without a manifest is reported.
+///
+/// **Expected behavior**: Exit with non-zero code and name the member.
+#[test]
+fn build_workspace_fails_for_missing_member() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[workspace]\nmembers = [\"missing\"]\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", "/nonexistent/infc")
+        .current_dir(temp.path())
+        .arg("check");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Workspace member `missing`"));
+}
+
+// -----------------------------------------------------------------------------
+// Check Command Tests
+// -----------------------------------------------------------------------------
+
+/// Verifies that `infs check` analyzes a project with its dependencies and no codegen.
+///
+/// **Expected behavior**: infc receives the project, `--extern mathlib=<dir>` and
+/// `--analyze`, without an output directory.
+#[cfg(unix)]
+#[test]
+fn check_analyzes_project_with_dependencies() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_project_with_path_dependency(&temp);
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.child("app").path())
+        .arg("check");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert(". --extern mathlib=./../mathlib --analyze\n");
+}
+
+/// Verifies that `infs check` forwards the diagnostics format and watch mode to infc.
+///
+/// **Expected behavior**: infc receives `--message-format json --watch` after
+/// `--analyze`.
+#[cfg(unix)]
+#[test]
+fn check_forwards_message_format_and_watch() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("main.inf")
+        .write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("check")
+        .arg("main.inf")
+        .arg("--message-format")
+        .arg("json")
+        .arg("--watch");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert("main.inf --analyze --message-format json --watch\n");
+}
+
+/// Verifies that `infs build` forwards the diagnostics format to infc.
+///
+/// **Expected behavior**: infc receives `--message-format json`.
+#[cfg(unix)]
+#[test]
+fn build_forwards_message_format() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("main.inf")
+        .write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("main.inf")
+        .arg("--analyze")
+        .arg("--message-format")
+        .arg("json");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert(predicate::str::contains("--analyze --message-format json"));
+}
+
+/// Verifies that `infs check` in a directory without a manifest fails.
+///
+/// **Expected behavior**: Exit with non-zero code, naming the missing `Inference.toml`.
+#[test]
+fn check_fails_without_manifest() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.current_dir(temp.path()).arg("check");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No Inference.toml found"));
+}
+
+/// Verifies that `infs check` reports a type error with infc's exit code.
+///
+/// **Expected behavior**: Exit with non-zero code and the type mismatch on stderr, without
+/// creating `target/` or `out/`.
+#[test]
+fn check_reports_type_error() {
+    let Some(infc_path) = require_infc() else {
+        return;
+    };
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("main.inf")
+        .write_str("fn main() -> i32 {\n    return true;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", &infc_path)
+        .current_dir(temp.path())
+        .arg("check")
+        .arg("main.inf");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("type mismatch"));
+    assert!(!temp.child("target").exists());
+    assert!(!temp.child("out").exists());
+}
+
+// -----------------------------------------------------------------------------
+// Profile Tests
+// -----------------------------------------------------------------------------
+
+/// Verifies that `infs build --release` compiles with the release profile of the manifest.
+///
+/// **Expected behavior**: infc receives the `[profile.release]` settings, completed with
+/// the built-in `opt-level`.
+#[cfg(unix)]
+#[test]
+fn build_release_uses_release_profile() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n\
+             [profile.release]\nbounds-checks = \"unchecked\"\nassert = \"result-code\"\n",
+        )
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen")
+        .arg("--release");
+
+    cmd.assert().success();
+    temp.child("args.txt").assert(
+        ". --out-dir ./target --codegen -O 3 --bounds-checks unchecked --assert result-code\n",
+    );
+}
+
+/// Verifies that flags on the command line override the settings of the profile.
+///
+/// **Expected behavior**: infc receives `-O 1` instead of the `[profile.dev]` level, and
+/// `-g` from the profile.
+#[cfg(unix)]
+#[test]
+fn build_flags_override_profile() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n\
+             [profile.dev]\nopt-level = 2\ndebug-info = true\n",
+        )
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen")
+        .arg("-O")
+        .arg("1");
+
+    cmd.assert().success();
+    temp.child("args.txt")
+        .assert(". --out-dir ./target --codegen -O 1 -g\n");
+}
+
+/// Verifies that an invalid profile setting stops the build before infc runs.
+///
+/// **Expected behavior**: Exit with non-zero code, naming the profile and the setting.
+#[cfg(unix)]
+#[test]
+fn build_fails_for_invalid_profile_setting() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n\
+             [profile.dev]\nopt-level = 7\n",
+        )
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo \"$@\" > \"$(dirname \"$0\")/args.txt\"\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("[profile.dev] opt-level"));
+    temp.child("args.txt").assert(predicate::path::missing());
+}
+
+// -----------------------------------------------------------------------------
+// Fingerprint Tests
+// -----------------------------------------------------------------------------
+
+/// Writes a fake infc that logs each build to `builds.txt` next to it and writes
+/// `target/demo.wasm`.
+#[cfg(unix)]
+fn write_logging_infc(path: &std::path::Path) {
+    write_script(
+        path,
+        "[ \"$1\" = --version ] && exit 0\n\
+         mkdir -p target && touch target/demo.wasm\n\
+         echo \"$@\" >> \"$(dirname \"$0\")/builds.txt\"\n",
+    );
+}
+
+/// Runs `infs build --codegen -o` with `extra` arguments in the project at `temp`.
+#[cfg(unix)]
+fn build_with_logging_infc(
+    temp: &assert_fs::TempDir,
+    extra: &[&str],
+) -> assert_cmd::assert::Assert {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", temp.child("fake-infc").path())
+        .current_dir(temp.path())
+        .arg("build")
+        .arg("--codegen")
+        .arg("-o")
+        .args(extra);
+    cmd.assert()
+}
+
+/// Returns the number of builds logged by the fake infc.
+#[cfg(unix)]
+fn logged_builds(temp: &assert_fs::TempDir) -> usize {
+    std::fs::read_to_string(temp.child("builds.txt").path())
+        .unwrap_or_default()
+        .lines()
+        .count()
+}
+
+/// Verifies that a project build is skipped until its sources or options change.
+///
+/// **Expected behavior**: The second build prints `Fresh` without running infc; changing
+/// a source file or passing `--release` prints `Compiling` and runs infc again.
+#[cfg(unix)]
+#[test]
+fn build_skips_unchanged_project() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    write_logging_infc(temp.child("fake-infc").path());
+
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Compiling demo v0.1.0"));
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Fresh demo v0.1.0"));
+    assert_eq!(logged_builds(&temp), 1);
+
+    temp.child("src/math/double.inf")
+        .write_str("fn double(x: i32) -> i32 {\n    return x + x;\n}\n")
+        .unwrap();
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Compiling demo v0.1.0"));
+    build_with_logging_infc(&temp, &["--release"])
+        .success()
+        .stderr(predicate::str::contains("Compiling demo v0.1.0"));
+    assert_eq!(logged_builds(&temp), 3);
+}
+
+/// Verifies that a project is built again when an artifact of the last build is gone.
+///
+/// **Expected behavior**: After deleting `target/demo.wasm`, the build runs infc again.
+#[cfg(unix)]
+#[test]
+fn build_rebuilds_when_artifact_is_missing() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    write_logging_infc(temp.child("fake-infc").path());
+
+    build_with_logging_infc(&temp, &[]).success();
+    std::fs::remove_file(temp.child("target/demo.wasm").path()).unwrap();
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Compiling demo v0.1.0"));
+    assert_eq!(logged_builds(&temp), 2);
+    temp.child("target/demo.wasm")
+        .assert(predicate::path::exists());
+}
+
+/// Verifies that the hooks of the manifest run around a project build.
+///
+/// **Expected behavior**: `prebuild` runs before each build; `postbuild` runs after infc
+/// with the package name and the path of the WebAssembly module, and not for a fresh
+/// build.
+#[cfg(unix)]
+#[test]
+fn build_runs_hooks_with_artifact_paths() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[hooks]\n\
+             prebuild = \"echo pre >> hooks.txt\"\n\
+             postbuild = \"echo post $INFS_PACKAGE_NAME $INFS_WASM >> hooks.txt\"\n",
+        )
+        .unwrap();
+    write_logging_infc(temp.child("fake-infc").path());
+
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Running postbuild hook"));
+    build_with_logging_infc(&temp, &[])
+        .success()
+        .stderr(predicate::str::contains("Fresh demo v0.1.0"));
+
+    let wasm = temp
+        .child("target/demo.wasm")
+        .path()
+        .canonicalize()
+        .unwrap();
+    let log = std::fs::read_to_string(temp.child("hooks.txt").path()).unwrap();
+    assert_eq!(
+        log,
+        format!("pre\npost demo {}\npre\n", wasm.display()),
+        "unexpected hook log"
+    );
+}
+
+/// Verifies that a failing `prebuild` hook stops the build.
+///
+/// **Expected behavior**: Exit with non-zero code, name the hook, and never run infc.
+#[cfg(unix)]
+#[test]
+fn build_fails_when_prebuild_hook_fails() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n\n[hooks]\nprebuild = \"exit 3\"\n",
+        )
+        .unwrap();
+    write_logging_infc(temp.child("fake-infc").path());
+
+    build_with_logging_infc(&temp, &[])
+        .failure()
+        .stderr(predicate::str::contains(
+            "The prebuild hook `exit 3` failed with exit code 3",
+        ));
+    assert_eq!(logged_builds(&temp), 0);
+}
+
+/// Verifies that a failed build is not recorded as fresh.
+///
+/// **Expected behavior**: Both builds run infc and fail.
+#[cfg(unix)]
+#[test]
+fn build_does_not_record_failed_build() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    write_script(
+        temp.child("fake-infc").path(),
+        "[ \"$1\" = --version ] && exit 0\n\
+         echo \"$@\" >> \"$(dirname \"$0\")/builds.txt\"\n\
+         exit 5\n",
+    );
+
+    build_with_logging_infc(&temp, &[]).code(5);
+    build_with_logging_infc(&temp, &[]).code(5);
+    assert_eq!(logged_builds(&temp), 2);
+}
+
+// -----------------------------------------------------------------------------
+// JSON Output Tests
+// -----------------------------------------------------------------------------
+
+/// Parses the stdout of a command as one JSON document.
+fn json_stdout(assert: &assert_cmd::assert::Assert) -> serde_json::Value {
+    let stdout = &assert.get_output().stdout;
+    serde_json::from_slice(stdout).unwrap_or_else(|e| {
+        panic!(
+            "stdout is not JSON ({e}): {}",
+            String::from_utf8_lossy(stdout)
+        )
+    })
+}
+
+/// Verifies that `infs list --output json` reports the installed toolchains.
+///
+/// **Expected behavior**: Exit with code 0 and print the versions, their paths and the
+/// default.
+#[test]
+fn list_output_json_reports_toolchains() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("toolchains/0.1.0").create_dir_all().unwrap();
+    temp.child("toolchains/0.2.0").create_dir_all().unwrap();
+    temp.child("default").write_str("0.2.0").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .env_remove("INFS_TOOLCHAIN")
+        .current_dir(temp.path())
+        .args(["--output", "json", "list"]);
+
+    let document = json_stdout(&cmd.assert().success());
+    assert_eq!(document["default"], "0.2.0");
+    let toolchains = document["toolchains"].as_array().unwrap();
+    assert_eq!(toolchains.len(), 2);
+    let default = toolchains
+        .iter()
+        .find(|toolchain| toolchain["version"] == "0.2.0")
+        .unwrap();
+    assert_eq!(default["default"], true);
+    assert!(default["path"].as_str().unwrap().ends_with("0.2.0"));
+    assert!(document["pinned"].is_null());
+}
+
+/// Verifies that `infs doctor --output json` reports every check.
+///
+/// **Expected behavior**: Stdout is one JSON document with the overall status and a
+/// name, status and message for each check.
+#[test]
+fn doctor_output_json_reports_checks() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFERENCE_HOME", temp.path())
+        .args(["doctor", "--output", "json"]);
+
+    let document = json_stdout(&cmd.assert());
+    assert!(["ok", "warning", "error"].contains(&document["status"].as_str().unwrap()));
+    let checks = document["checks"].as_array().unwrap();
+    assert!(!checks.is_empty());
+    for check in checks {
+        assert!(check["name"].is_string());
+        assert!(check["status"].is_string());
+        assert!(check["message"].is_string());
+    }
+}
+
+/// Verifies that a failing command reports its error as JSON.
+///
+/// **Expected behavior**: Exit with non-zero code and print `{"error": ...}` naming the
+/// missing file on stdout.
+#[test]
+fn output_json_reports_errors() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.args(["--output", "json", "build", "nonexistent.inf", "--parse"]);
+
+    let document = json_stdout(&cmd.assert().failure());
+    assert!(
+        document["error"]
+            .as_str()
+            .unwrap()
+            .contains("nonexistent.inf")
+    );
+}
+
+/// Verifies that `infs build --output json` reports the diagnostics and artifacts of a
+/// project build, with a stand-in infc that prints one JSON diagnostic.
+///
+/// **Expected behavior**: infc receives `--message-format json`; the build is reported as
+/// compiled with its diagnostic and `target/demo.wasm`, then as fresh.
+#[cfg(unix)]
+#[test]
+fn build_output_json_reports_diagnostics() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    write_script(
+        temp.child("fake-infc").path(),
+        "[ \"$1\" = --version ] && exit 0\n\
+         case \"$*\" in *'--message-format json'*) ;; *) exit 9;; esac\n\
+         mkdir -p target && touch target/demo.wasm\n\
+         echo 'Compiled'\n\
+         echo '{\"severity\":\"warning\",\"message\":\"unused variable\"}'\n",
+    );
+
+    let document = json_stdout(&build_with_logging_infc(&temp, &["--output", "json"]).success());
+    assert_eq!(document["success"], true);
+    let build = &document["builds"][0];
+    assert_eq!(build["package"], "demo");
+    assert_eq!(build["version"], "0.1.0");
+    assert_eq!(build["status"], "compiled");
+    assert_eq!(build["diagnostics"][0]["message"], "unused variable");
+    assert!(build["outputs"][0].as_str().unwrap().ends_with("demo.wasm"));
+
+    let document = json_stdout(&build_with_logging_infc(&temp, &["--output", "json"]).success());
+    assert_eq!(document["builds"][0]["status"], "fresh");
+    assert!(
+        document["builds"][0]["outputs"][0]
+            .as_str()
+            .unwrap()
+            .ends_with("demo.wasm")
+    );
+}
+
+/// Verifies that `infs build --output json` reports a failed build.
+///
+/// **Expected behavior**: Exit with the code of infc and report the build as failed.
+#[cfg(unix)]
+#[test]
+fn build_output_json_reports_failure() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    write_two_file_project(&temp);
+    write_script(
+        temp.child("fake-infc").path(),
+        "[ \"$1\" = --version ] && exit 0\n\
+         echo '{\"severity\":\"error\",\"message\":\"type mismatch\"}'\n\
+         exit 5\n",
+    );
+
+    let document = json_stdout(&build_with_logging_infc(&temp, &["--output", "json"]).code(5));
+    assert_eq!(document["success"], false);
+    assert_eq!(document["builds"][0]["status"], "failed");
+    assert_eq!(
+        document["builds"][0]["diagnostics"][0]["message"],
+        "type mismatch"
+    );
+}
+
+/// Verifies that `infs verify --output json` reports the outcome of every proof file.
+///
+/// **Expected behavior**: Exit with non-zero code; the document counts one passed and
+/// one failed file, with the compiler output of the failed one.
+#[cfg(unix)]
+#[test]
+fn verify_output_json_reports_proofs() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str("[package]\nname = \"demo\"\nversion = \"0.1.0\"\n")
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 { return 0; }\n")
+        .unwrap();
+    temp.child("proofs/bad.v").write_str("Admitted.\n").unwrap();
+
+    let infc = temp.child("fake-infc");
+    write_script(
+        infc.path(),
+        "echo 'infc output'\n\
+         for arg; do case \"$arg\" in --emit=v=*) echo 'Definition main := 0.' > \"${arg#--emit=v=}\";; esac; done\n",
+    );
+    let coqc = temp.child("coqc");
+    write_script(
+        coqc.path(),
+        "for file; do :; done\nif grep -q Admitted \"$file\"; then echo \"Error: admitted\" >&2; exit 1; fi\n",
+    );
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infs"));
+    cmd.env("INFC_PATH", infc.path())
+        .env("COQC_PATH", coqc.path())
+        .current_dir(temp.path())
+        .args(["verify", "--output", "json"]);
+
+    let document = json_stdout(&cmd.assert().failure());
+    assert_eq!(document["success"], false);
+    assert_eq!(document["passed"], 1);
+    assert_eq!(document["failed"], 1);
+    let proofs = document["packages"][0]["proofs"].as_array().unwrap();
+    assert_eq!(proofs[0]["file"], "proofs/demo.v");
+    assert_eq!(proofs[0]["passed"], true);
+    assert_eq!(proofs[1]["file"], "proofs/bad.v");
+    assert!(
+        proofs[1]["output"]
+            .as_str()
+            .unwrap()
+            .contains("Error: admitted")
+    );
 }
@@ -47,10 +47,8 @@ get_download_url() {
     local platform="$1" binary="$2"
     local base="https://storage.googleapis.com/external_binaries"
     case "$platform-$binary" in
-        linux-inf-llc)   echo "$base/linux/bin/inf-llc.zip" ;;
         linux-rust-lld)  echo "$base/linux/bin/rust-lld.zip" ;;
         linux-libLLVM)   echo "$base/linux/lib/libLLVM.so.21.1-rust-1.94.0-nightly.zip" ;;
-        macos-inf-llc)   echo "$base/macos/bin/inf-llc.zip" ;;
         macos-rust-lld)  echo "$base/macos/bin/rust-lld.zip" ;;
         *)               echo "" ;;
     esac
@@ -165,14 +163,6 @@ check_external_binaries() {
     local lib_dir="$PROJECT_ROOT/external/lib/$PLATFORM"
     MISSING_BINARIES=()
 
-    # Check inf-llc
-    if [[ -x "$bin_dir/inf-llc" ]]; then
-        print_found "inf-llc (in external/bin/$PLATFORM/)"
-    else
-        print_missing "inf-llc (not in external/bin/$PLATFORM/)"
-        MISSING_BINARIES+=("inf-llc")
-    fi
-
     # Check rust-lld
     if [[ -x "$bin_dir/rust-lld" ]]; then
         print_found "rust-lld (in external/bin/$PLATFORM/)"
@@ -244,7 +234,7 @@ check_macos_quarantine() {
     local bin_dir="$PROJECT_ROOT/external/bin/$PLATFORM"
     local quarantined=()
 
-    for binary in inf-llc rust-lld; do
+    for binary in rust-lld; do
         local path="$bin_dir/$binary"
         if [[ -f "$path" ]] && xattr -l "$path" 2>/dev/null | grep -q "com.apple.quarantine"; then
             quarantined+=("$binary")
@@ -276,7 +266,7 @@ Usage: check_deps.sh [--help]
 Checks for required dependencies to build the Inference compiler:
   - Rust nightly toolchain
   - LLVM 21
-  - External binaries (inf-llc, rust-lld)
+  - External binaries (rust-lld)
   - libLLVM shared library (Linux only)
 
 Can optionally download missing external binaries.
@@ -0,0 +1,84 @@
+//! Assertion and assumption checks for verification code.
+//!
+//! Reports three kinds of verification smells:
+//!
+//! - `assert` whose condition is a compile-time constant `true`, which proves
+//!   nothing
+//! - `assert` in unreachable code, which is never checked
+//! - `assume` blocks containing an assertion that is always false; such an
+//!   assumption discards every execution, so whatever follows it is verified
+//!   vacuously
+
+use inference_ast::nodes::{BlockType, FunctionDefinition, Statement};
+use inference_type_checker::errors::RelatedNote;
+
+use crate::{constant::constant_condition, unreachable::Reachability, warnings::AnalysisWarning};
+
+pub(crate) fn check_function(
+    function: &FunctionDefinition,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    visit_block(&function.body, reachability, warnings);
+}
+
+fn visit_block(
+    block: &BlockType,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    if let BlockType::Assume(_) = block {
+        check_assumption(block, warnings);
+    }
+    for statement in block.statements() {
+        visit_statement(&statement, reachability, warnings);
+    }
+}
+
+fn visit_statement(
+    statement: &Statement,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    match statement {
+        Statement::Assert(assert) => {
+            let location = assert.location;
+            if !reachability.is_reachable(statement) {
+                warnings.push(AnalysisWarning::UnreachableAssertion { location });
+            } else if constant_condition(&assert.expression.borrow()) == Some(true) {
+                warnings.push(AnalysisWarning::TrivialAssertion { location });
+            }
+        }
+        Statement::Block(block) => visit_block(block, reachability, warnings),
+        Statement::If(if_stmt) => {
+            visit_block(&if_stmt.if_arm, reachability, warnings);
+            if let Some(else_arm) = &if_stmt.else_arm {
+                visit_block(else_arm, reachability, warnings);
+            }
+        }
+        Statement::Loop(loop_stmt) => visit_block(&loop_stmt.body, reachability, warnings),
+        _ => {}
+    }
+}
+
+/// Reports an `assume` block whose top-level assertions include one that is
+/// always false.
+fn check_assumption(block: &BlockType, warnings: &mut Vec<AnalysisWarning>) {
+    let contradiction = block
+        .statements()
+        .into_iter()
+        .find_map(|statement| match statement {
+            Statement::Assert(assert)
+                if constant_condition(&assert.expression.borrow()) == Some(false) =>
+            {
+                Some(assert.location)
+            }
+            _ => None,
+        });
+    if let Some(assert_location) = contradiction {
+        warnings.push(AnalysisWarning::VacuousAssumption {
+            location: block.location(),
+            note: RelatedNote::new("this assumption is always false", assert_location),
+        });
+    }
+}
@@ -0,0 +1,305 @@
+//! Control Flow Graphs
+//!
+//! This module builds a [`ControlFlowGraph`] for a function body. The graph is
+//! the foundation for flow-sensitive analyses such as unreachable code and
+//! missing return detection.
+//!
+//! ## Structure
+//!
+//! A graph is a list of [`BasicBlock`]s. Each block holds the straight-line
+//! statements that execute in order, and ends with a [`Terminator`] that
+//! decides where control goes next:
+//!
+//! - `if` ends the current block with a [`Terminator::Branch`] into the two
+//!   arms, which both continue in a join block
+//! - `loop` jumps to a header block that either branches on the loop condition
+//!   or, for an unconditional loop, jumps straight into the body; the end of the
+//!   body jumps back to the header
+//! - `break` jumps to the block after the innermost loop
+//! - `return` jumps to the exit block
+//!
+//! Every graph has a dedicated entry block and exit block. Falling off the end
+//! of the body is a [`Terminator::Goto`] into the exit block, which lets callers
+//! tell an explicit `return` apart from an implicit one.
+//!
+//! Statements that follow a `return` or `break` are placed in a fresh block
+//! with no predecessors, so they show up as unreachable.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use inference_analyzer::cfg::ControlFlowGraph;
+//!
+//! let cfg = ControlFlowGraph::build(&function);
+//! let reachable = cfg.reachable();
+//! for block in cfg.blocks() {
+//!     if !reachable[block.id] {
+//!         println!("block {} is dead", block.id);
+//!     }
+//! }
+//! ```
+
+use std::rc::Rc;
+
+use inference_ast::nodes::{
+    BlockType, BreakStatement, Expression, FunctionDefinition, ReturnStatement, Statement,
+};
+use rustc_hash::FxHashMap;
+
+/// Index of a [`BasicBlock`] within its [`ControlFlowGraph`].
+pub type BlockId = usize;
+
+/// How control leaves a [`BasicBlock`].
+#[derive(Debug, Clone)]
+pub enum Terminator {
+    /// Unconditional jump.
+    Goto(BlockId),
+    /// Conditional jump on an `if` or loop condition.
+    Branch {
+        condition: Expression,
+        then_block: BlockId,
+        else_block: BlockId,
+    },
+    /// `return` statement; control continues in the exit block.
+    Return(Rc<ReturnStatement>),
+    /// `break` statement; control continues after the innermost loop.
+    Break {
+        statement: Rc<BreakStatement>,
+        target: BlockId,
+    },
+    /// End of the function. Only the exit block has this terminator.
+    Exit,
+}
+
+/// A straight-line sequence of statements with a single terminator.
+#[derive(Debug, Clone)]
+pub struct BasicBlock {
+    pub id: BlockId,
+    /// Statements executed in order. Control flow statements (`if`, `loop`,
+    /// `break`, `return` and nested blocks) are represented by the graph
+    /// structure instead of appearing here.
+    pub statements: Vec<Statement>,
+    pub terminator: Terminator,
+}
+
+/// Control flow graph of a single function body.
+#[derive(Debug, Clone)]
+pub struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+    statement_blocks: FxHashMap<u32, BlockId>,
+}
+
+impl ControlFlowGraph {
+    const ENTRY: BlockId = 0;
+    const EXIT: BlockId = 1;
+
+    /// Builds the control flow graph of a function body.
+    #[must_use]
+    pub fn build(function: &FunctionDefinition) -> Self {
+        let mut builder = CfgBuilder::new();
+        builder.visit_block(&function.body);
+        builder.terminate(Terminator::Goto(Self::EXIT));
+        builder.finish()
+    }
+
+    /// Returns the block where execution starts.
+    #[must_use]
+    pub fn entry(&self) -> BlockId {
+        Self::ENTRY
+    }
+
+    /// Returns the block every path through the function ends in.
+    #[must_use]
+    pub fn exit(&self) -> BlockId {
+        Self::EXIT
+    }
+
+    /// Returns all blocks, indexed by [`BlockId`].
+    #[must_use]
+    pub fn blocks(&self) -> &[BasicBlock] {
+        &self.blocks
+    }
+
+    /// Returns the block with the given id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not belong to this graph.
+    #[must_use]
+    pub fn block(&self, id: BlockId) -> &BasicBlock {
+        &self.blocks[id]
+    }
+
+    /// Returns the block in which the statement with the given node id starts.
+    ///
+    /// Every statement of the body is mapped, including control flow
+    /// statements: an `if` or `loop` maps to the block that evaluates its
+    /// condition, and `return` and `break` map to the block they terminate.
+    #[must_use]
+    pub fn block_of(&self, statement_id: u32) -> Option<BlockId> {
+        self.statement_blocks.get(&statement_id).copied()
+    }
+
+    /// Returns the blocks control can move to from `id`.
+    #[must_use]
+    pub fn successors(&self, id: BlockId) -> Vec<BlockId> {
+        match &self.blocks[id].terminator {
+            Terminator::Goto(target) | Terminator::Break { target, .. } => vec![*target],
+            Terminator::Branch {
+                then_block,
+                else_block,
+                ..
+            } => vec![*then_block, *else_block],
+            Terminator::Return(_) => vec![Self::EXIT],
+            Terminator::Exit => vec![],
+        }
+    }
+
+    /// Returns the blocks that can move control to `id`.
+    #[must_use]
+    pub fn predecessors(&self, id: BlockId) -> Vec<BlockId> {
+        self.blocks
+            .iter()
+            .filter(|block| self.successors(block.id).contains(&id))
+            .map(|block| block.id)
+            .collect()
+    }
+
+    /// Returns, for every block, whether it can be reached from the entry block.
+    #[must_use]
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![Self::ENTRY];
+        while let Some(id) = stack.pop() {
+            if reachable[id] {
+                continue;
+            }
+            reachable[id] = true;
+            stack.extend(self.successors(id));
+        }
+        reachable
+    }
+}
+
+struct CfgBuilder {
+    blocks: Vec<(Vec<Statement>, Option<Terminator>)>,
+    current: BlockId,
+    loop_exits: Vec<BlockId>,
+    statement_blocks: FxHashMap<u32, BlockId>,
+}
+
+impl CfgBuilder {
+    fn new() -> Self {
+        let mut builder = Self {
+            blocks: Vec::new(),
+            current: ControlFlowGraph::ENTRY,
+            loop_exits: Vec::new(),
+            statement_blocks: FxHashMap::default(),
+        };
+        builder.new_block();
+        let exit = builder.new_block();
+        builder.blocks[exit].1 = Some(Terminator::Exit);
+        builder
+    }
+
+    fn new_block(&mut self) -> BlockId {
+        self.blocks.push((Vec::new(), None));
+        self.blocks.len() - 1
+    }
+
+    /// Ends the current block. The first terminator of a block wins.
+    fn terminate(&mut self, terminator: Terminator) {
+        let slot = &mut self.blocks[self.current].1;
+        if slot.is_none() {
+            *slot = Some(terminator);
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockType) {
+        for statement in block.statements() {
+            self.visit_statement(&statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        self.statement_blocks.insert(statement.id(), self.current);
+        match statement {
+            Statement::Block(block) => self.visit_block(block),
+            Statement::If(if_stmt) => {
+                let then_block = self.new_block();
+                let else_block = if_stmt.else_arm.as_ref().map(|_| self.new_block());
+                let join = self.new_block();
+                self.terminate(Terminator::Branch {
+                    condition: if_stmt.condition.borrow().clone(),
+                    then_block,
+                    else_block: else_block.unwrap_or(join),
+                });
+                self.current = then_block;
+                self.visit_block(&if_stmt.if_arm);
+                self.terminate(Terminator::Goto(join));
+                if let (Some(else_block), Some(else_arm)) = (else_block, &if_stmt.else_arm) {
+                    self.current = else_block;
+                    self.visit_block(else_arm);
+                    self.terminate(Terminator::Goto(join));
+                }
+                self.current = join;
+            }
+            Statement::Loop(loop_stmt) => {
+                let header = self.new_block();
+                let body = self.new_block();
+                let after = self.new_block();
+                self.terminate(Terminator::Goto(header));
+                self.current = header;
+                self.statement_blocks.insert(statement.id(), header);
+                match &*loop_stmt.condition.borrow() {
+                    Some(condition) => self.terminate(Terminator::Branch {
+                        condition: condition.clone(),
+                        then_block: body,
+                        else_block: after,
+                    }),
+                    None => self.terminate(Terminator::Goto(body)),
+                }
+                self.loop_exits.push(after);
+                self.current = body;
+                self.visit_block(&loop_stmt.body);
+                self.terminate(Terminator::Goto(header));
+                self.loop_exits.pop();
+                self.current = after;
+            }
+            Statement::Break(break_stmt) => {
+                let target = self
+                    .loop_exits
+                    .last()
+                    .copied()
+                    .unwrap_or(ControlFlowGraph::EXIT);
+                self.terminate(Terminator::Break {
+                    statement: break_stmt.clone(),
+                    target,
+                });
+                self.current = self.new_block();
+            }
+            Statement::Return(return_stmt) => {
+                self.terminate(Terminator::Return(return_stmt.clone()));
+                self.current = self.new_block();
+            }
+            _ => self.blocks[self.current].0.push(statement.clone()),
+        }
+    }
+
+    fn finish(self) -> ControlFlowGraph {
+        let blocks = self
+            .blocks
+            .into_iter()
+            .enumerate()
+            .map(|(id, (statements, terminator))| BasicBlock {
+                id,
+                statements,
+                terminator: terminator.unwrap_or(Terminator::Goto(ControlFlowGraph::EXIT)),
+            })
+            .collect();
+        ControlFlowGraph {
+            blocks,
+            statement_blocks: self.statement_blocks,
+        }
+    }
+}
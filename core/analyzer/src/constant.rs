@@ -0,0 +1,60 @@
+//! Compile-time evaluation of literal conditions.
+
+use inference_ast::nodes::{Expression, Literal, OperatorKind, UnaryOperatorKind};
+
+/// Evaluates a condition that only involves literals.
+///
+/// Supports boolean literals, `!`, `&&`, `||`, `==` and `!=` on booleans, and
+/// comparisons between integer literals. `&&` and `||` are constant as soon as
+/// one side decides the result, e.g. `x && false`. Returns `None` for anything
+/// else.
+pub(crate) fn constant_condition(expression: &Expression) -> Option<bool> {
+    match expression {
+        Expression::Literal(Literal::Bool(literal)) => Some(literal.value),
+        Expression::Parenthesized(paren) => constant_condition(&paren.expression.borrow()),
+        Expression::PrefixUnary(unary) if unary.operator == UnaryOperatorKind::Not => {
+            constant_condition(&unary.expression.borrow()).map(|value| !value)
+        }
+        Expression::Binary(binary) => {
+            let left = binary.left.borrow();
+            let right = binary.right.borrow();
+            if let (Some(left), Some(right)) = (constant_integer(&left), constant_integer(&right)) {
+                return match binary.operator {
+                    OperatorKind::Eq => Some(left == right),
+                    OperatorKind::Ne => Some(left != right),
+                    OperatorKind::Lt => Some(left < right),
+                    OperatorKind::Le => Some(left <= right),
+                    OperatorKind::Gt => Some(left > right),
+                    OperatorKind::Ge => Some(left >= right),
+                    _ => None,
+                };
+            }
+            let left = constant_condition(&left);
+            let right = constant_condition(&right);
+            match (&binary.operator, left, right) {
+                (OperatorKind::And, Some(false), _) | (OperatorKind::And, _, Some(false)) => {
+                    Some(false)
+                }
+                (OperatorKind::Or, Some(true), _) | (OperatorKind::Or, _, Some(true)) => Some(true),
+                (OperatorKind::And, Some(true), Some(true)) => Some(true),
+                (OperatorKind::Or, Some(false), Some(false)) => Some(false),
+                (OperatorKind::Eq, Some(left), Some(right)) => Some(left == right),
+                (OperatorKind::Ne, Some(left), Some(right)) => Some(left != right),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates an integer literal, possibly negated or parenthesized.
+pub(crate) fn constant_integer(expression: &Expression) -> Option<i128> {
+    match expression {
+        Expression::Literal(Literal::Number(literal)) => literal.value.parse().ok(),
+        Expression::Parenthesized(paren) => constant_integer(&paren.expression.borrow()),
+        Expression::PrefixUnary(unary) if unary.operator == UnaryOperatorKind::Neg => {
+            constant_integer(&unary.expression.borrow()).map(|value| -value)
+        }
+        _ => None,
+    }
+}
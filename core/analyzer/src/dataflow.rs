@@ -0,0 +1,210 @@
+//! Data-Flow Analysis Framework
+//!
+//! This module is a small monotone framework over [`ControlFlowGraph`]s. An
+//! analysis describes its abstract state as a [`Lattice`] and how statements
+//! transform that state; [`solve`] runs a worklist algorithm until every block
+//! reaches a fixed point.
+//!
+//! ## Writing an Analysis
+//!
+//! Implement [`Analysis`] by choosing:
+//!
+//! - a [`Direction`]: forward analyses (constant propagation, initialization)
+//!   flow from the entry block along edges, backward analyses (liveness) flow
+//!   from the exit block against them
+//! - a `Domain` implementing [`Lattice`], whose `join` merges the states of
+//!   converging paths
+//! - the state at the boundary (the entry block for forward analyses, the exit
+//!   block for backward ones)
+//! - transfer functions for statements and, optionally, block terminators
+//!
+//! Transfer functions must be monotone for the solver to terminate.
+//!
+//! ## Example: Liveness
+//!
+//! ```ignore
+//! use inference_analyzer::dataflow::{solve, Analysis, Direction};
+//! use rustc_hash::FxHashSet;
+//!
+//! struct Liveness;
+//!
+//! impl Analysis for Liveness {
+//!     type Domain = FxHashSet<String>;
+//!     const DIRECTION: Direction = Direction::Backward;
+//!
+//!     fn bottom(&self) -> Self::Domain {
+//!         FxHashSet::default()
+//!     }
+//!
+//!     fn boundary(&self) -> Self::Domain {
+//!         FxHashSet::default()
+//!     }
+//!
+//!     fn transfer_statement(&self, live: &mut Self::Domain, statement: &Statement) {
+//!         if let Statement::VariableDefinition(def) = statement {
+//!             live.remove(&def.name.name);
+//!         }
+//!         live.extend(variables_read_by(statement));
+//!     }
+//! }
+//!
+//! let results = solve(&ControlFlowGraph::build(&function), &Liveness);
+//! let live_on_entry = results.entry_state(cfg.entry());
+//! ```
+
+use std::{collections::VecDeque, hash::Hash};
+
+use inference_ast::nodes::Statement;
+use rustc_hash::FxHashSet;
+
+use crate::cfg::{BlockId, ControlFlowGraph, Terminator};
+
+/// A join semilattice of abstract states.
+pub trait Lattice: Clone {
+    /// Merges `other` into `self`, returning whether `self` changed.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+/// Sets ordered by inclusion, joined by union.
+impl<T: Clone + Eq + Hash> Lattice for FxHashSet<T> {
+    fn join(&mut self, other: &Self) -> bool {
+        let before = self.len();
+        self.extend(other.iter().cloned());
+        self.len() != before
+    }
+}
+
+/// The direction in which states flow through the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    Backward,
+}
+
+/// A data-flow analysis over a [`ControlFlowGraph`].
+pub trait Analysis {
+    type Domain: Lattice;
+
+    const DIRECTION: Direction;
+
+    /// The least element, used as the initial state of every block.
+    fn bottom(&self) -> Self::Domain;
+
+    /// The state at the entry block (forward) or exit block (backward).
+    fn boundary(&self) -> Self::Domain;
+
+    /// Applies the effect of a straight-line statement.
+    fn transfer_statement(&self, state: &mut Self::Domain, statement: &Statement);
+
+    /// Applies the effect of a block terminator, such as reading a branch
+    /// condition or a returned value. The default does nothing.
+    fn transfer_terminator(&self, _state: &mut Self::Domain, _terminator: &Terminator) {}
+}
+
+/// Fixed-point states of every block.
+///
+/// For both directions, the entry state holds before the block's first
+/// statement and the exit state after its terminator, in execution order.
+#[derive(Debug, Clone)]
+pub struct DataflowResults<D> {
+    entry: Vec<D>,
+    exit: Vec<D>,
+}
+
+impl<D> DataflowResults<D> {
+    /// Returns the state before the first statement of `block`.
+    #[must_use]
+    pub fn entry_state(&self, block: BlockId) -> &D {
+        &self.entry[block]
+    }
+
+    /// Returns the state after the terminator of `block`.
+    #[must_use]
+    pub fn exit_state(&self, block: BlockId) -> &D {
+        &self.exit[block]
+    }
+}
+
+/// Runs `analysis` over `cfg` to a fixed point using a worklist.
+#[must_use]
+pub fn solve<A: Analysis>(cfg: &ControlFlowGraph, analysis: &A) -> DataflowResults<A::Domain> {
+    let block_count = cfg.blocks().len();
+    let mut predecessors: Vec<Vec<BlockId>> = vec![Vec::new(); block_count];
+    for block in cfg.blocks() {
+        for successor in cfg.successors(block.id) {
+            predecessors[successor].push(block.id);
+        }
+    }
+    let (inputs, outputs, boundary_block) = match A::DIRECTION {
+        Direction::Forward => (
+            predecessors,
+            (0..block_count).map(|id| cfg.successors(id)).collect(),
+            cfg.entry(),
+        ),
+        Direction::Backward => (
+            (0..block_count).map(|id| cfg.successors(id)).collect(),
+            predecessors,
+            cfg.exit(),
+        ),
+    };
+
+    // `before` and `after` are in flow order: for backward analyses `before`
+    // is the state at the end of the block.
+    let mut before: Vec<A::Domain> = (0..block_count).map(|_| analysis.bottom()).collect();
+    let mut after = before.clone();
+    before[boundary_block] = analysis.boundary();
+
+    let mut worklist: VecDeque<BlockId> = (0..block_count).collect();
+    let mut queued = vec![true; block_count];
+    while let Some(id) = worklist.pop_front() {
+        queued[id] = false;
+        let mut state = before[id].clone();
+        for input in &inputs[id] {
+            state.join(&after[*input]);
+        }
+        before[id] = state.clone();
+        transfer_block(cfg, analysis, id, &mut state);
+        if after[id].join(&state) {
+            for output in &outputs[id] {
+                if !queued[*output] {
+                    queued[*output] = true;
+                    worklist.push_back(*output);
+                }
+            }
+        }
+    }
+
+    match A::DIRECTION {
+        Direction::Forward => DataflowResults {
+            entry: before,
+            exit: after,
+        },
+        Direction::Backward => DataflowResults {
+            entry: after,
+            exit: before,
+        },
+    }
+}
+
+fn transfer_block<A: Analysis>(
+    cfg: &ControlFlowGraph,
+    analysis: &A,
+    id: BlockId,
+    state: &mut A::Domain,
+) {
+    let block = cfg.block(id);
+    match A::DIRECTION {
+        Direction::Forward => {
+            for statement in &block.statements {
+                analysis.transfer_statement(state, statement);
+            }
+            analysis.transfer_terminator(state, &block.terminator);
+        }
+        Direction::Backward => {
+            analysis.transfer_terminator(state, &block.terminator);
+            for statement in block.statements.iter().rev() {
+                analysis.transfer_statement(state, statement);
+            }
+        }
+    }
+}
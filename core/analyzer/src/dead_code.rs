@@ -0,0 +1,253 @@
+//! Dead code detection for private functions and types.
+//!
+//! Every name mentioned anywhere in the program is collected, except mentions
+//! inside the definition that introduces the name, so a recursive function or
+//! a struct whose methods construct `Self` by name is not kept alive by itself.
+//! Private top-level functions, structs, enums and type aliases whose name is
+//! never mentioned are reported. Public definitions may be used from other
+//! modules and are never reported.
+
+use std::rc::Rc;
+
+use inference_ast::nodes::{
+    ArgumentType, BlockType, Definition, Expression, Identifier, Literal, SourceFile, Statement,
+    Type, Visibility,
+};
+use rustc_hash::FxHashSet;
+
+use crate::warnings::AnalysisWarning;
+
+/// Collects the names mentioned across all source files.
+pub(crate) fn collect_references(source_files: &[Rc<SourceFile>]) -> FxHashSet<String> {
+    let mut references = References::default();
+    for source_file in source_files {
+        references.visit_definitions(&source_file.definitions);
+    }
+    references.names
+}
+
+/// Reports private definitions whose name is not in `references`.
+pub(crate) fn report_unused(
+    definitions: &[Definition],
+    references: &FxHashSet<String>,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    for definition in definitions {
+        let (visibility, name, kind) = match definition {
+            Definition::Function(function) => (&function.visibility, &function.name, None),
+            Definition::Struct(struct_def) => {
+                (&struct_def.visibility, &struct_def.name, Some("struct"))
+            }
+            Definition::Enum(enum_def) => (&enum_def.visibility, &enum_def.name, Some("enum")),
+            Definition::Type(type_def) => {
+                (&type_def.visibility, &type_def.name, Some("type alias"))
+            }
+            Definition::Module(module) => {
+                if let Some(body) = &module.body {
+                    report_unused(body, references, warnings);
+                }
+                continue;
+            }
+            Definition::Spec(_) | Definition::Constant(_) | Definition::ExternalFunction(_) => {
+                continue;
+            }
+        };
+        if *visibility == Visibility::Public || references.contains(&name.name) {
+            continue;
+        }
+        warnings.push(match kind {
+            None => AnalysisWarning::UnusedFunction {
+                name: name.name.clone(),
+                location: name.location,
+            },
+            Some(kind) => AnalysisWarning::UnusedType {
+                kind,
+                name: name.name.clone(),
+                location: name.location,
+            },
+        });
+    }
+}
+
+/// Names mentioned outside the definition that introduces them.
+#[derive(Default)]
+struct References {
+    names: FxHashSet<String>,
+    owner: Option<String>,
+}
+
+impl References {
+    fn mention(&mut self, identifier: &Identifier) {
+        if self.owner.as_deref() != Some(identifier.name.as_str()) {
+            self.names.insert(identifier.name.clone());
+        }
+    }
+
+    fn visit_definitions(&mut self, definitions: &[Definition]) {
+        for definition in definitions {
+            self.visit_definition(definition);
+        }
+    }
+
+    fn visit_definition(&mut self, definition: &Definition) {
+        match definition {
+            Definition::Function(function) => {
+                self.owner = Some(function.name.name.clone());
+                self.visit_arguments(function.arguments.as_deref());
+                if let Some(returns) = &function.returns {
+                    self.visit_type(returns);
+                }
+                self.visit_block(&function.body);
+            }
+            Definition::ExternalFunction(function) => {
+                self.owner = Some(function.name.name.clone());
+                self.visit_arguments(function.arguments.as_deref());
+                if let Some(returns) = &function.returns {
+                    self.visit_type(returns);
+                }
+            }
+            Definition::Struct(struct_def) => {
+                self.owner = Some(struct_def.name.name.clone());
+                for field in &struct_def.fields {
+                    self.visit_type(&field.type_);
+                }
+                for method in &struct_def.methods {
+                    self.visit_arguments(method.arguments.as_deref());
+                    if let Some(returns) = &method.returns {
+                        self.visit_type(returns);
+                    }
+                    self.visit_block(&method.body);
+                }
+            }
+            Definition::Constant(constant) => {
+                self.owner = Some(constant.name.name.clone());
+                self.visit_type(&constant.ty);
+            }
+            Definition::Type(type_def) => {
+                self.owner = Some(type_def.name.name.clone());
+                self.visit_type(&type_def.ty);
+            }
+            Definition::Spec(spec) => self.visit_definitions(&spec.definitions),
+            Definition::Module(module) => {
+                if let Some(body) = &module.body {
+                    self.visit_definitions(body);
+                }
+            }
+            Definition::Enum(_) => {}
+        }
+        self.owner = None;
+    }
+
+    fn visit_arguments(&mut self, arguments: Option<&[ArgumentType]>) {
+        for argument in arguments.unwrap_or_default() {
+            match argument {
+                ArgumentType::Argument(arg) => self.visit_type(&arg.ty),
+                ArgumentType::IgnoreArgument(ignore) => self.visit_type(&ignore.ty),
+                ArgumentType::Type(ty) => self.visit_type(ty),
+                ArgumentType::SelfReference(_) => {}
+            }
+        }
+    }
+
+    fn visit_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Array(array) => self.visit_type(&array.element_type),
+            Type::Generic(generic) => self.mention(&generic.base),
+            Type::Function(function) => {
+                for parameter in function.parameters.iter().flatten() {
+                    self.visit_type(parameter);
+                }
+                if let Some(returns) = &function.returns {
+                    self.visit_type(returns);
+                }
+            }
+            Type::QualifiedName(qualified) => self.mention(&qualified.name),
+            Type::Qualified(qualified) => self.mention(&qualified.name),
+            Type::Custom(identifier) => self.mention(identifier),
+            Type::Simple(_) => {}
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockType) {
+        for statement in block.statements() {
+            self.visit_statement(&statement);
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Block(block) => self.visit_block(block),
+            Statement::Expression(expression) => self.visit_expression(expression),
+            Statement::Assign(assign) => {
+                self.visit_expression(&assign.left.borrow());
+                self.visit_expression(&assign.right.borrow());
+            }
+            Statement::Return(ret) => self.visit_expression(&ret.expression.borrow()),
+            Statement::Loop(loop_stmt) => {
+                if let Some(condition) = &*loop_stmt.condition.borrow() {
+                    self.visit_expression(condition);
+                }
+                self.visit_block(&loop_stmt.body);
+            }
+            Statement::If(if_stmt) => {
+                self.visit_expression(&if_stmt.condition.borrow());
+                self.visit_block(&if_stmt.if_arm);
+                if let Some(else_arm) = &if_stmt.else_arm {
+                    self.visit_block(else_arm);
+                }
+            }
+            Statement::VariableDefinition(var_def) => {
+                self.visit_type(&var_def.ty);
+                if let Some(value) = &var_def.value {
+                    self.visit_expression(&value.borrow());
+                }
+            }
+            Statement::TypeDefinition(type_def) => self.visit_type(&type_def.ty),
+            Statement::Assert(assert) => self.visit_expression(&assert.expression.borrow()),
+            Statement::ConstantDefinition(constant) => self.visit_type(&constant.ty),
+            Statement::Break(_) => {}
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => self.mention(identifier),
+            Expression::ArrayIndexAccess(access) => {
+                self.visit_expression(&access.array.borrow());
+                self.visit_expression(&access.index.borrow());
+            }
+            Expression::Binary(binary) => {
+                self.visit_expression(&binary.left.borrow());
+                self.visit_expression(&binary.right.borrow());
+            }
+            Expression::MemberAccess(access) => self.visit_expression(&access.expression.borrow()),
+            Expression::TypeMemberAccess(access) => {
+                self.visit_expression(&access.expression.borrow());
+            }
+            Expression::FunctionCall(call) => {
+                self.visit_expression(&call.function);
+                for type_parameter in call.type_parameters.iter().flatten() {
+                    self.mention(type_parameter);
+                }
+                for (_, argument) in call.arguments.iter().flatten() {
+                    self.visit_expression(&argument.borrow());
+                }
+            }
+            Expression::Struct(struct_expr) => {
+                self.mention(&struct_expr.name);
+                for (_, value) in struct_expr.fields.iter().flatten() {
+                    self.visit_expression(&value.borrow());
+                }
+            }
+            Expression::PrefixUnary(unary) => self.visit_expression(&unary.expression.borrow()),
+            Expression::Parenthesized(paren) => self.visit_expression(&paren.expression.borrow()),
+            Expression::Literal(Literal::Array(array)) => {
+                for element in array.elements.iter().flatten() {
+                    self.visit_expression(&element.borrow());
+                }
+            }
+            Expression::Type(ty) => self.visit_type(ty),
+            Expression::Literal(_) | Expression::Uzumaki(_) => {}
+        }
+    }
+}
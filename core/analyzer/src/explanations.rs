@@ -0,0 +1,54 @@
+//! Extended Explanations of Warning Codes
+//!
+//! Every code reported by [`AnalysisWarning::code`](crate::warnings::AnalysisWarning::code)
+//! has an explanation in `src/explanations/<code>.md`, like those of the type checker in
+//! [`inference_type_checker::explanations`]: what the warning means, an example that
+//! reports it, and how to fix it.
+
+/// Lists the codes of the explanation files, which are embedded by code.
+macro_rules! explanations {
+    ($($code:literal),* $(,)?) => {
+        /// Code and explanation of every analysis warning, in code order.
+        pub const EXPLANATIONS: &[(&str, &str)] = &[
+            $(($code, include_str!(concat!("explanations/", $code, ".md")))),*
+        ];
+    };
+}
+
+explanations![
+    "W0003", "W0004", "W0005", "W0006", "W0007", "W0008", "W0009", "W0010", "W0011", "W0012",
+    "W0013",
+];
+
+/// Returns the explanation of the warning `code`, such as `W0003`, if it is a code of the
+/// analyzer.
+#[must_use = "this is a pure lookup with no side effects"]
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(code))
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warnings::WARNING_KINDS;
+
+    #[test]
+    fn every_warning_kind_has_an_explanation() {
+        let explained: Vec<&str> = EXPLANATIONS.iter().map(|(code, _)| *code).collect();
+        let codes: Vec<&str> = WARNING_KINDS.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes, explained);
+    }
+
+    #[test]
+    fn explanations_name_their_lint() {
+        for ((code, name), (_, text)) in WARNING_KINDS.iter().zip(EXPLANATIONS) {
+            assert!(
+                text.contains(&format!("Lint name: `{name}`.")),
+                "explanation of {code} should name `{name}`"
+            );
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Semantic Analysis for the Inference Programming Language
+//!
+//! This crate implements the analyze phase of the compiler pipeline. It runs
+//! over a [`TypedContext`] after type checking has succeeded and reports
+//! problems that are not type errors, such as dead code.
+//!
+//! ## Checks
+//!
+//! - **Unused variables** (`W0003`): a `let` binding that is never read
+//! - **Unused parameters** (`W0004`): a function parameter that is never read
+//! - **Unused functions** (`W0005`): a private function that is never called
+//! - **Unused types** (`W0006`): a private struct, enum or type alias that is
+//!   never referenced
+//! - **Unreachable code** (`W0007`): a statement that can never execute, because
+//!   it follows a `return`, `break` or non-terminating loop, or sits behind a
+//!   condition that is always true or always false
+//! - **Trivial assertions** (`W0008`): an `assert` whose condition is always true
+//! - **Unreachable assertions** (`W0009`): an `assert` that is never checked
+//! - **Vacuous assumptions** (`W0010`): an `assume` block that is always false,
+//!   so everything verified after it holds vacuously
+//! - **Quantifier side effects** (`W0011`, `W0012`): a `forall` or `exists`
+//!   block that writes to variables declared outside it or calls an impure
+//!   function
+//! - **Possibly non-terminating loops** (`W0013`): a loop with no reachable
+//!   `break` or `return` whose condition never moves toward becoming false
+//!
+//! Variables and parameters whose name starts with `_` are exempt. Public
+//! definitions are reachable from other modules and are never reported.
+//!
+//! ## Quick Start
+//!
+//! ```ignore
+//! use inference_analyzer::analyze;
+//!
+//! let typed_context = TypeCheckerBuilder::build_typed_context(arena)?.typed_context();
+//! for warning in analyze(&typed_context) {
+//!     eprintln!("warning[{}]: {}", warning.code(), warning.render_with_notes());
+//! }
+//! ```
+//!
+//! ## Public Modules
+//!
+//! - [`cfg`] - Control flow graphs of function bodies
+//! - [`dataflow`] - Generic worklist solver for data-flow analyses over [`cfg`]
+//! - [`warnings`] - Structured diagnostics reported by the analysis passes
+
+use inference_ast::nodes::{Definition, FunctionDefinition, Location};
+use inference_type_checker::typed_context::TypedContext;
+
+use crate::warnings::AnalysisWarning;
+
+mod assertions;
+pub mod cfg;
+mod constant;
+pub mod dataflow;
+mod dead_code;
+pub mod explanations;
+mod purity;
+mod termination;
+mod unreachable;
+mod unused;
+pub mod warnings;
+
+/// Runs all analysis passes and returns the warnings they report.
+///
+/// Warnings are ordered by source file, then by location within the file,
+/// then by warning code.
+#[must_use = "analysis has no side effects; the returned warnings should be reported"]
+pub fn analyze(typed_context: &TypedContext) -> Vec<AnalysisWarning> {
+    let source_files = typed_context.source_files();
+    let references = dead_code::collect_references(&source_files);
+    let impurity = purity::Impurity::collect(&source_files);
+
+    let mut warnings = Vec::new();
+    for source_file in &source_files {
+        let mut file_warnings = Vec::new();
+        for_each_function(&source_file.definitions, &mut |function| {
+            let reachability = unreachable::Reachability::new(function);
+            unused::check_function(function, &mut file_warnings);
+            unreachable::check_function(function, &reachability, &mut file_warnings);
+            assertions::check_function(function, &reachability, &mut file_warnings);
+            purity::check_function(function, &impurity, &mut file_warnings);
+            termination::check_function(function, &reachability, &mut file_warnings);
+        });
+        dead_code::report_unused(&source_file.definitions, &references, &mut file_warnings);
+        file_warnings.sort_by(|a, b| {
+            location_order(a.location())
+                .cmp(&location_order(b.location()))
+                .then_with(|| a.code().cmp(b.code()))
+        });
+        warnings.extend(file_warnings);
+    }
+    warnings
+}
+
+/// Calls `f` for every function and method body in `definitions`, including
+/// those nested in specs and modules.
+fn for_each_function(definitions: &[Definition], f: &mut impl FnMut(&FunctionDefinition)) {
+    for definition in definitions {
+        match definition {
+            Definition::Function(function) => f(function),
+            Definition::Struct(struct_def) => {
+                for method in &struct_def.methods {
+                    f(method);
+                }
+            }
+            Definition::Spec(spec) => for_each_function(&spec.definitions, f),
+            Definition::Module(module) => {
+                if let Some(body) = &module.body {
+                    for_each_function(body, f);
+                }
+            }
+            Definition::Enum(_)
+            | Definition::Constant(_)
+            | Definition::ExternalFunction(_)
+            | Definition::Type(_) => {}
+        }
+    }
+}
+
+fn location_order(location: &Location) -> (u32, u32) {
+    (location.start_line, location.start_column)
+}
@@ -0,0 +1,364 @@
+//! Side-effect analysis for quantifier blocks.
+//!
+//! The bodies of `forall` and `exists` blocks are translated into quantified
+//! propositions, which only have their intended meaning if evaluating the body
+//! has no effect outside it. This pass reports two kinds of effects inside a
+//! quantifier block:
+//!
+//! - assignments to variables declared outside the block, including writes
+//!   through fields or array elements of such variables
+//! - calls to impure functions
+//!
+//! A function is impure if it is an `external fn`, whose effects are unknown,
+//! if it assigns to `self` or to a field of `self`, or if it calls an impure
+//! function. Calls are resolved by name: a method call is impure if any method
+//! with that name is impure.
+
+use std::rc::Rc;
+
+use inference_ast::nodes::{
+    ArgumentType, BlockType, Definition, Expression, FunctionDefinition, Literal, Location,
+    SourceFile, Statement,
+};
+use inference_type_checker::errors::RelatedNote;
+use rustc_hash::FxHashMap;
+
+use crate::warnings::AnalysisWarning;
+
+/// Why a function is impure, keyed by function or method name.
+pub(crate) struct Impurity {
+    reasons: FxHashMap<String, RelatedNote>,
+}
+
+impl Impurity {
+    /// Computes the impure functions of the whole program.
+    pub(crate) fn collect(source_files: &[Rc<SourceFile>]) -> Self {
+        let mut summaries = Vec::new();
+        for source_file in source_files {
+            summarize_definitions(&source_file.definitions, &mut summaries);
+        }
+
+        let mut reasons: FxHashMap<String, RelatedNote> = FxHashMap::default();
+        for summary in &summaries {
+            if let Some(note) = &summary.direct_effect {
+                reasons
+                    .entry(summary.name.clone())
+                    .or_insert_with(|| note.clone());
+            }
+        }
+        // Propagate impurity to callers until nothing changes.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for summary in &summaries {
+                if reasons.contains_key(&summary.name) {
+                    continue;
+                }
+                if let Some((callee, location)) = summary
+                    .calls
+                    .iter()
+                    .find(|(callee, _)| reasons.contains_key(callee))
+                {
+                    reasons.insert(
+                        summary.name.clone(),
+                        RelatedNote::new(format!("calls impure function `{callee}`"), *location),
+                    );
+                    changed = true;
+                }
+            }
+        }
+        Self { reasons }
+    }
+
+    fn reason(&self, name: &str) -> Option<&RelatedNote> {
+        self.reasons.get(name)
+    }
+}
+
+pub(crate) fn check_function(
+    function: &FunctionDefinition,
+    impurity: &Impurity,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    let mut checker = Checker {
+        impurity,
+        scopes: vec![Vec::new()],
+        quantifiers: Vec::new(),
+        warnings,
+    };
+    for argument in function.arguments.iter().flatten() {
+        if let ArgumentType::Argument(arg) = argument {
+            checker.declare(&arg.name.name);
+        }
+    }
+    checker.visit_block(&function.body);
+}
+
+/// Direct effects and outgoing calls of one function.
+struct FunctionSummary {
+    name: String,
+    direct_effect: Option<RelatedNote>,
+    calls: Vec<(String, Location)>,
+}
+
+fn summarize_definitions(definitions: &[Definition], summaries: &mut Vec<FunctionSummary>) {
+    for definition in definitions {
+        match definition {
+            Definition::Function(function) => summaries.push(summarize_function(function)),
+            Definition::Struct(struct_def) => {
+                summaries.extend(struct_def.methods.iter().map(|m| summarize_function(m)));
+            }
+            Definition::ExternalFunction(function) => summaries.push(FunctionSummary {
+                name: function.name.name.clone(),
+                direct_effect: Some(RelatedNote::new(
+                    format!("`{}` is an external function", function.name.name),
+                    function.location,
+                )),
+                calls: Vec::new(),
+            }),
+            Definition::Spec(spec) => summarize_definitions(&spec.definitions, summaries),
+            Definition::Module(module) => {
+                if let Some(body) = &module.body {
+                    summarize_definitions(body, summaries);
+                }
+            }
+            Definition::Enum(_) | Definition::Constant(_) | Definition::Type(_) => {}
+        }
+    }
+}
+
+fn summarize_function(function: &FunctionDefinition) -> FunctionSummary {
+    let mut summary = FunctionSummary {
+        name: function.name.name.clone(),
+        direct_effect: None,
+        calls: Vec::new(),
+    };
+    walk_block(&function.body, &mut |statement| {
+        if let Statement::Assign(assign) = statement
+            && summary.direct_effect.is_none()
+            && assignment_root(&assign.left.borrow()).as_deref() == Some("self")
+        {
+            summary.direct_effect = Some(RelatedNote::new(
+                format!("`{}` writes to `self` here", function.name.name),
+                assign.location,
+            ));
+        }
+        visit_statement_expressions(statement, &mut |expression| {
+            if let Some(callee) = callee_name(expression) {
+                summary.calls.push((callee, expression.location()));
+            }
+        });
+    });
+    summary
+}
+
+struct Checker<'a> {
+    impurity: &'a Impurity,
+    scopes: Vec<Vec<String>>,
+    /// Open quantifier blocks: keyword, location and the index of the scope
+    /// the block introduced.
+    quantifiers: Vec<(&'static str, Location, usize)>,
+    warnings: &'a mut Vec<AnalysisWarning>,
+}
+
+impl Checker<'_> {
+    fn declare(&mut self, name: &str) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(name.to_string());
+        }
+    }
+
+    /// Returns whether `name` resolves to a binding declared outside the
+    /// innermost quantifier block.
+    fn is_outer(&self, name: &str, quantifier_scope: usize) -> bool {
+        self.scopes
+            .iter()
+            .rposition(|scope| scope.iter().any(|declared| declared == name))
+            .is_none_or(|index| index < quantifier_scope)
+    }
+
+    fn visit_block(&mut self, block: &BlockType) {
+        let quantifier = match block {
+            BlockType::Forall(_) => Some("forall"),
+            BlockType::Exists(_) => Some("exists"),
+            _ => None,
+        };
+        self.scopes.push(Vec::new());
+        if let Some(keyword) = quantifier {
+            self.quantifiers
+                .push((keyword, block.location(), self.scopes.len() - 1));
+        }
+        for statement in block.statements() {
+            self.visit_statement(&statement);
+        }
+        if quantifier.is_some() {
+            self.quantifiers.pop();
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        if let Some(&(keyword, block_location, scope)) = self.quantifiers.last() {
+            self.check_effects(statement, keyword, block_location, scope);
+        }
+        match statement {
+            Statement::Block(block) => self.visit_block(block),
+            Statement::If(if_stmt) => {
+                self.visit_block(&if_stmt.if_arm);
+                if let Some(else_arm) = &if_stmt.else_arm {
+                    self.visit_block(else_arm);
+                }
+            }
+            Statement::Loop(loop_stmt) => self.visit_block(&loop_stmt.body),
+            Statement::VariableDefinition(var_def) => self.declare(&var_def.name.name),
+            _ => {}
+        }
+    }
+
+    fn check_effects(
+        &mut self,
+        statement: &Statement,
+        keyword: &'static str,
+        block_location: Location,
+        scope: usize,
+    ) {
+        if let Statement::Assign(assign) = statement
+            && let Some(name) = assignment_root(&assign.left.borrow())
+            && self.is_outer(&name, scope)
+        {
+            self.warnings.push(AnalysisWarning::QuantifierOuterWrite {
+                quantifier: keyword,
+                name,
+                location: assign.location,
+                note: RelatedNote::new(format!("`{keyword}` block starts here"), block_location),
+            });
+        }
+        let impurity = self.impurity;
+        let mut calls = Vec::new();
+        visit_statement_expressions(statement, &mut |expression| {
+            if let Some(callee) = callee_name(expression)
+                && let Some(reason) = impurity.reason(&callee)
+            {
+                calls.push((callee, expression.location(), reason.clone()));
+            }
+        });
+        for (name, location, note) in calls {
+            self.warnings.push(AnalysisWarning::QuantifierImpureCall {
+                quantifier: keyword,
+                name,
+                location,
+                note,
+            });
+        }
+    }
+}
+
+/// Returns the variable an assignment target writes through, e.g. `p` for
+/// `p.x[0] = 1`.
+pub(crate) fn assignment_root(target: &Expression) -> Option<String> {
+    match target {
+        Expression::Identifier(identifier) => Some(identifier.name.clone()),
+        Expression::MemberAccess(access) => assignment_root(&access.expression.borrow()),
+        Expression::ArrayIndexAccess(access) => assignment_root(&access.array.borrow()),
+        Expression::Parenthesized(paren) => assignment_root(&paren.expression.borrow()),
+        _ => None,
+    }
+}
+
+/// Returns the name of the function or method called by `expression`.
+fn callee_name(expression: &Expression) -> Option<String> {
+    let Expression::FunctionCall(call) = expression else {
+        return None;
+    };
+    match &call.function {
+        Expression::Identifier(identifier) => Some(identifier.name.clone()),
+        Expression::MemberAccess(access) => Some(access.name.name.clone()),
+        Expression::TypeMemberAccess(access) => Some(access.name.name.clone()),
+        _ => None,
+    }
+}
+
+/// Calls `f` for every statement in `block`, including nested ones.
+pub(crate) fn walk_block(block: &BlockType, f: &mut impl FnMut(&Statement)) {
+    for statement in block.statements() {
+        f(&statement);
+        match &statement {
+            Statement::Block(inner) => walk_block(inner, f),
+            Statement::If(if_stmt) => {
+                walk_block(&if_stmt.if_arm, f);
+                if let Some(else_arm) = &if_stmt.else_arm {
+                    walk_block(else_arm, f);
+                }
+            }
+            Statement::Loop(loop_stmt) => walk_block(&loop_stmt.body, f),
+            _ => {}
+        }
+    }
+}
+
+/// Calls `f` for every expression owned directly by `statement`, including
+/// nested subexpressions but not the statements of nested blocks.
+pub(crate) fn visit_statement_expressions(statement: &Statement, f: &mut impl FnMut(&Expression)) {
+    match statement {
+        Statement::Expression(expression) => visit_expression(expression, f),
+        Statement::Assign(assign) => {
+            visit_expression(&assign.left.borrow(), f);
+            visit_expression(&assign.right.borrow(), f);
+        }
+        Statement::Return(ret) => visit_expression(&ret.expression.borrow(), f),
+        Statement::Loop(loop_stmt) => {
+            if let Some(condition) = &*loop_stmt.condition.borrow() {
+                visit_expression(condition, f);
+            }
+        }
+        Statement::If(if_stmt) => visit_expression(&if_stmt.condition.borrow(), f),
+        Statement::VariableDefinition(var_def) => {
+            if let Some(value) = &var_def.value {
+                visit_expression(&value.borrow(), f);
+            }
+        }
+        Statement::Assert(assert) => visit_expression(&assert.expression.borrow(), f),
+        Statement::Block(_)
+        | Statement::Break(_)
+        | Statement::TypeDefinition(_)
+        | Statement::ConstantDefinition(_) => {}
+    }
+}
+
+pub(crate) fn visit_expression(expression: &Expression, f: &mut impl FnMut(&Expression)) {
+    f(expression);
+    match expression {
+        Expression::ArrayIndexAccess(access) => {
+            visit_expression(&access.array.borrow(), f);
+            visit_expression(&access.index.borrow(), f);
+        }
+        Expression::Binary(binary) => {
+            visit_expression(&binary.left.borrow(), f);
+            visit_expression(&binary.right.borrow(), f);
+        }
+        Expression::MemberAccess(access) => visit_expression(&access.expression.borrow(), f),
+        Expression::TypeMemberAccess(access) => visit_expression(&access.expression.borrow(), f),
+        Expression::FunctionCall(call) => {
+            visit_expression(&call.function, f);
+            for (_, argument) in call.arguments.iter().flatten() {
+                visit_expression(&argument.borrow(), f);
+            }
+        }
+        Expression::Struct(struct_expr) => {
+            for (_, value) in struct_expr.fields.iter().flatten() {
+                visit_expression(&value.borrow(), f);
+            }
+        }
+        Expression::PrefixUnary(unary) => visit_expression(&unary.expression.borrow(), f),
+        Expression::Parenthesized(paren) => visit_expression(&paren.expression.borrow(), f),
+        Expression::Literal(Literal::Array(array)) => {
+            for element in array.elements.iter().flatten() {
+                visit_expression(&element.borrow(), f);
+            }
+        }
+        Expression::Literal(_)
+        | Expression::Identifier(_)
+        | Expression::Type(_)
+        | Expression::Uzumaki(_) => {}
+    }
+}
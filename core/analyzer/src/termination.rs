@@ -0,0 +1,251 @@
+//! Loop termination heuristics.
+//!
+//! Functions are translated into Rocq definitions, which must terminate, so a
+//! loop that may run forever produces output that cannot be verified. This pass
+//! reports reachable loops that neither contain a reachable `break` or `return`
+//! nor show a decreasing measure:
+//!
+//! - `loop { }` without a condition, or with a condition that is always true
+//! - `loop cond { }` where no variable in `cond` is assigned in the body
+//! - `loop cond { }` where every assignment to a variable in `cond` moves it
+//!   away from its bound, e.g. `loop i < n { i = i - 1; }`
+//!
+//! Only updates of the form `x = x + c` and `x = x - c` with a literal `c` have
+//! a known direction; any other assignment, and any method call on a variable
+//! in the condition, is assumed to make progress. Conditions that call
+//! functions are never reported.
+
+use inference_ast::nodes::{
+    BlockType, Expression, FunctionDefinition, LoopStatement, OperatorKind, Statement,
+};
+use inference_type_checker::errors::RelatedNote;
+
+use crate::{
+    constant::{constant_condition, constant_integer},
+    purity::{assignment_root, visit_expression, visit_statement_expressions, walk_block},
+    unreachable::Reachability,
+    warnings::AnalysisWarning,
+};
+
+pub(crate) fn check_function(
+    function: &FunctionDefinition,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    visit_block(&function.body, reachability, warnings);
+}
+
+fn visit_block(
+    block: &BlockType,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    for statement in block.statements() {
+        visit_statement(&statement, reachability, warnings);
+    }
+}
+
+fn visit_statement(
+    statement: &Statement,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    match statement {
+        Statement::Block(block) => visit_block(block, reachability, warnings),
+        Statement::If(if_stmt) => {
+            visit_block(&if_stmt.if_arm, reachability, warnings);
+            if let Some(else_arm) = &if_stmt.else_arm {
+                visit_block(else_arm, reachability, warnings);
+            }
+        }
+        Statement::Loop(loop_stmt) => {
+            if reachability.is_reachable(statement)
+                && !has_reachable_exit(&loop_stmt.body, reachability, false)
+                && let Some(note) = non_termination_reason(statement, loop_stmt)
+            {
+                warnings.push(AnalysisWarning::PossiblyNonTerminatingLoop {
+                    location: statement.location(),
+                    note,
+                });
+            }
+            visit_block(&loop_stmt.body, reachability, warnings);
+        }
+        _ => {}
+    }
+}
+
+/// Returns whether `block` contains a reachable `return`, or a reachable
+/// `break` out of the loop being checked. Inside nested loops only `return`
+/// leaves the outer loop.
+fn has_reachable_exit(block: &BlockType, reachability: &Reachability, nested: bool) -> bool {
+    block.statements().iter().any(|statement| match statement {
+        Statement::Return(_) => reachability.is_reachable(statement),
+        Statement::Break(_) => !nested && reachability.is_reachable(statement),
+        Statement::Block(inner) => has_reachable_exit(inner, reachability, nested),
+        Statement::If(if_stmt) => {
+            has_reachable_exit(&if_stmt.if_arm, reachability, nested)
+                || if_stmt
+                    .else_arm
+                    .as_ref()
+                    .is_some_and(|else_arm| has_reachable_exit(else_arm, reachability, nested))
+        }
+        Statement::Loop(inner) => has_reachable_exit(&inner.body, reachability, true),
+        _ => false,
+    })
+}
+
+/// Which way a variable must move for the loop condition to become false.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Increase,
+    Decrease,
+    /// Any change may falsify the condition.
+    Any,
+}
+
+/// How an assignment changes a variable.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Update {
+    By(Direction),
+    /// Assigns the variable its current value, e.g. `i = i + 0`.
+    Unchanged,
+}
+
+/// Explains why a loop without exits may not terminate, or returns `None` if
+/// it appears to have a decreasing measure.
+fn non_termination_reason(statement: &Statement, loop_stmt: &LoopStatement) -> Option<RelatedNote> {
+    let condition = loop_stmt.condition.borrow();
+    let Some(condition) = condition.as_ref() else {
+        return Some(RelatedNote::new(
+            "this loop has no reachable `break` or `return`",
+            statement.location(),
+        ));
+    };
+    match constant_condition(condition) {
+        Some(true) => {
+            return Some(RelatedNote::new(
+                "this condition is always true",
+                condition.location(),
+            ));
+        }
+        Some(false) => return None,
+        None => {}
+    }
+    let mut has_call = false;
+    visit_expression(condition, &mut |expression| {
+        has_call |= matches!(expression, Expression::FunctionCall(_));
+    });
+    if has_call {
+        return None;
+    }
+
+    let mut measured = Vec::new();
+    condition_variables(condition, &mut measured);
+    let mut wrong_way = None;
+    let mut progress = false;
+    walk_block(&loop_stmt.body, &mut |statement| {
+        if let Statement::Assign(assign) = statement
+            && let Some(name) = assignment_root(&assign.left.borrow())
+            && let Some((_, wanted)) = measured.iter().find(|(measured, _)| *measured == name)
+        {
+            match classify_update(&name, &assign.left.borrow(), &assign.right.borrow()) {
+                Update::By(direction)
+                    if direction == *wanted
+                        || *wanted == Direction::Any
+                        || direction == Direction::Any =>
+                {
+                    progress = true;
+                }
+                _ => {
+                    wrong_way.get_or_insert_with(|| (name, assign.location));
+                }
+            }
+        }
+        visit_statement_expressions(statement, &mut |expression| {
+            if let Expression::FunctionCall(call) = expression
+                && let Expression::MemberAccess(access) = &call.function
+                && let Some(receiver) = assignment_root(&access.expression.borrow())
+                && measured.iter().any(|(measured, _)| *measured == receiver)
+            {
+                progress = true;
+            }
+        });
+    });
+    if progress {
+        return None;
+    }
+    Some(match wrong_way {
+        Some((name, location)) => RelatedNote::new(
+            format!("`{name}` moves away from the loop bound here"),
+            location,
+        ),
+        None => RelatedNote::new(
+            "no variable in this condition changes in the loop body",
+            condition.location(),
+        ),
+    })
+}
+
+/// Collects the variables of a loop condition with the direction each must
+/// move in to make the condition false.
+fn condition_variables(condition: &Expression, out: &mut Vec<(String, Direction)>) {
+    match condition {
+        Expression::Parenthesized(paren) => condition_variables(&paren.expression.borrow(), out),
+        Expression::Binary(binary)
+            if matches!(binary.operator, OperatorKind::And | OperatorKind::Or) =>
+        {
+            condition_variables(&binary.left.borrow(), out);
+            condition_variables(&binary.right.borrow(), out);
+        }
+        Expression::Binary(binary) => {
+            let (left, right) = match binary.operator {
+                OperatorKind::Lt | OperatorKind::Le => (Direction::Increase, Direction::Decrease),
+                OperatorKind::Gt | OperatorKind::Ge => (Direction::Decrease, Direction::Increase),
+                _ => (Direction::Any, Direction::Any),
+            };
+            bound_variables(&binary.left.borrow(), left, out);
+            bound_variables(&binary.right.borrow(), right, out);
+        }
+        _ => bound_variables(condition, Direction::Any, out),
+    }
+}
+
+/// Collects the variables of one side of a comparison. Only a side that is a
+/// single variable moves in a known direction.
+fn bound_variables(side: &Expression, direction: Direction, out: &mut Vec<(String, Direction)>) {
+    if let Some(name) = assignment_root(side) {
+        out.push((name, direction));
+        return;
+    }
+    visit_expression(side, &mut |expression| {
+        if let Expression::Identifier(identifier) = expression {
+            out.push((identifier.name.clone(), Direction::Any));
+        }
+    });
+}
+
+/// Classifies `target = value` as an update of the variable `name`.
+fn classify_update(name: &str, target: &Expression, value: &Expression) -> Update {
+    let unknown = Update::By(Direction::Any);
+    let (Expression::Identifier(_), Expression::Binary(binary)) = (target, value) else {
+        return unknown;
+    };
+    let is_self_reference =
+        matches!(&*binary.left.borrow(), Expression::Identifier(left) if left.name == name);
+    let Some(step) = constant_integer(&binary.right.borrow()) else {
+        return unknown;
+    };
+    let step = match binary.operator {
+        OperatorKind::Add => step,
+        OperatorKind::Sub => -step,
+        _ => return unknown,
+    };
+    if !is_self_reference {
+        return unknown;
+    }
+    match step.signum() {
+        1 => Update::By(Direction::Increase),
+        -1 => Update::By(Direction::Decrease),
+        _ => Update::Unchanged,
+    }
+}
@@ -0,0 +1,158 @@
+//! Unreachable code detection.
+//!
+//! Reachability is computed over the function's [`ControlFlowGraph`], except
+//! that a branch whose condition is a compile-time constant only follows the
+//! edge that is actually taken. The function body is then walked in source
+//! order, and the first statement of every run of unreachable statements is
+//! reported together with a note pointing at the reason:
+//!
+//! - a preceding `return` or `break`
+//! - a preceding statement that never completes, such as an endless loop
+//! - an enclosing `if` or loop condition that is always true or always false
+//!
+//! Statements nested inside an unreachable statement are not reported again.
+
+use inference_ast::nodes::{FunctionDefinition, Statement};
+use inference_type_checker::errors::RelatedNote;
+
+use crate::{
+    cfg::{ControlFlowGraph, Terminator},
+    constant::constant_condition,
+    warnings::AnalysisWarning,
+};
+
+pub(crate) fn check_function(
+    function: &FunctionDefinition,
+    reachability: &Reachability,
+    warnings: &mut Vec<AnalysisWarning>,
+) {
+    Checker { reachability }.visit_statements(&function.body.statements(), None, warnings);
+}
+
+/// Reachability of the statements of one function body.
+pub(crate) struct Reachability {
+    cfg: ControlFlowGraph,
+    reachable: Vec<bool>,
+}
+
+impl Reachability {
+    pub(crate) fn new(function: &FunctionDefinition) -> Self {
+        let cfg = ControlFlowGraph::build(function);
+        let reachable = reachable_blocks(&cfg);
+        Self { cfg, reachable }
+    }
+
+    /// Returns whether the statement can execute. Statements that are not part
+    /// of the function body are treated as reachable.
+    pub(crate) fn is_reachable(&self, statement: &Statement) -> bool {
+        self.cfg
+            .block_of(statement.id())
+            .is_none_or(|block| self.reachable[block])
+    }
+}
+
+struct Checker<'a> {
+    reachability: &'a Reachability,
+}
+
+impl Checker<'_> {
+    /// Walks a statement list. `entry_note` explains why the list's first
+    /// statement may be unreachable, for lists guarded by a constant condition.
+    fn visit_statements(
+        &self,
+        statements: &[Statement],
+        entry_note: Option<RelatedNote>,
+        warnings: &mut Vec<AnalysisWarning>,
+    ) {
+        for (index, statement) in statements.iter().enumerate() {
+            if !self.reachability.is_reachable(statement) {
+                let note = match index.checked_sub(1) {
+                    Some(previous) => Some(completion_note(&statements[previous])),
+                    None => entry_note,
+                };
+                if let Some(note) = note {
+                    warnings.push(AnalysisWarning::UnreachableCode {
+                        location: statement.location(),
+                        note,
+                    });
+                }
+                return;
+            }
+            self.visit_nested(statement, warnings);
+        }
+    }
+
+    fn visit_nested(&self, statement: &Statement, warnings: &mut Vec<AnalysisWarning>) {
+        match statement {
+            Statement::Block(block) => self.visit_statements(&block.statements(), None, warnings),
+            Statement::If(if_stmt) => {
+                let condition = if_stmt.condition.borrow();
+                let constant = constant_condition(&condition);
+                let note = |value: bool| {
+                    RelatedNote::new(
+                        format!("this condition is always {value}"),
+                        condition.location(),
+                    )
+                };
+                self.visit_statements(
+                    &if_stmt.if_arm.statements(),
+                    (constant == Some(false)).then(|| note(false)),
+                    warnings,
+                );
+                if let Some(else_arm) = &if_stmt.else_arm {
+                    self.visit_statements(
+                        &else_arm.statements(),
+                        (constant == Some(true)).then(|| note(true)),
+                        warnings,
+                    );
+                }
+            }
+            Statement::Loop(loop_stmt) => {
+                let entry_note = loop_stmt.condition.borrow().as_ref().and_then(|condition| {
+                    (constant_condition(condition) == Some(false)).then(|| {
+                        RelatedNote::new("this condition is always false", condition.location())
+                    })
+                });
+                self.visit_statements(&loop_stmt.body.statements(), entry_note, warnings);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Explains why control does not continue after `statement`.
+fn completion_note(statement: &Statement) -> RelatedNote {
+    let message = match statement {
+        Statement::Return(_) => "any code following this `return` is unreachable",
+        Statement::Break(_) => "any code following this `break` is unreachable",
+        Statement::Loop(_) => "this loop never exits",
+        _ => "this statement never completes",
+    };
+    RelatedNote::new(message, statement.location())
+}
+
+/// Computes reachable blocks, following only the taken edge of branches on
+/// constant conditions.
+fn reachable_blocks(cfg: &ControlFlowGraph) -> Vec<bool> {
+    let mut reachable = vec![false; cfg.blocks().len()];
+    let mut stack = vec![cfg.entry()];
+    while let Some(id) = stack.pop() {
+        if reachable[id] {
+            continue;
+        }
+        reachable[id] = true;
+        match &cfg.block(id).terminator {
+            Terminator::Branch {
+                condition,
+                then_block,
+                else_block,
+            } => match constant_condition(condition) {
+                Some(true) => stack.push(*then_block),
+                Some(false) => stack.push(*else_block),
+                None => stack.extend([*then_block, *else_block]),
+            },
+            _ => stack.extend(cfg.successors(id)),
+        }
+    }
+    reachable
+}
@@ -0,0 +1,176 @@
+//! Unused local variable and parameter detection.
+//!
+//! Each function body is walked with a stack of lexical scopes. Every binding
+//! introduced by a parameter or a `let` statement starts out unused, and is
+//! marked used when an identifier expression resolves to it. A binding that is
+//! only ever the target of a plain assignment (`x = 1;`) is still unused.
+
+use inference_ast::nodes::{
+    ArgumentType, BlockType, Expression, FunctionDefinition, Literal, Location, Statement,
+};
+use rustc_hash::FxHashMap;
+
+use crate::warnings::AnalysisWarning;
+
+pub(crate) fn check_function(function: &FunctionDefinition, warnings: &mut Vec<AnalysisWarning>) {
+    let mut scopes = Scopes::default();
+    scopes.push();
+    for argument in function.arguments.iter().flatten() {
+        if let ArgumentType::Argument(arg) = argument {
+            scopes.declare(&arg.name.name, arg.name.location, BindingKind::Parameter);
+        }
+    }
+    scopes.visit_block(&function.body);
+    scopes.pop();
+    warnings.extend(scopes.into_warnings());
+}
+
+#[derive(Clone, Copy)]
+enum BindingKind {
+    Variable,
+    Parameter,
+}
+
+struct Binding {
+    name: String,
+    location: Location,
+    kind: BindingKind,
+    used: bool,
+}
+
+#[derive(Default)]
+struct Scopes {
+    bindings: Vec<Binding>,
+    stack: Vec<FxHashMap<String, usize>>,
+}
+
+impl Scopes {
+    fn push(&mut self) {
+        self.stack.push(FxHashMap::default());
+    }
+
+    fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    fn declare(&mut self, name: &str, location: Location, kind: BindingKind) {
+        let index = self.bindings.len();
+        self.bindings.push(Binding {
+            name: name.to_string(),
+            location,
+            kind,
+            used: false,
+        });
+        if let Some(scope) = self.stack.last_mut() {
+            scope.insert(name.to_string(), index);
+        }
+    }
+
+    fn mark_used(&mut self, name: &str) {
+        if let Some(index) = self.stack.iter().rev().find_map(|scope| scope.get(name)) {
+            self.bindings[*index].used = true;
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockType) {
+        self.push();
+        for statement in block.statements() {
+            self.visit_statement(&statement);
+        }
+        self.pop();
+    }
+
+    fn visit_statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Block(block) => self.visit_block(block),
+            Statement::Expression(expression) => self.visit_expression(expression),
+            Statement::Assign(assign) => {
+                if !matches!(&*assign.left.borrow(), Expression::Identifier(_)) {
+                    self.visit_expression(&assign.left.borrow());
+                }
+                self.visit_expression(&assign.right.borrow());
+            }
+            Statement::Return(ret) => self.visit_expression(&ret.expression.borrow()),
+            Statement::Loop(loop_stmt) => {
+                if let Some(condition) = &*loop_stmt.condition.borrow() {
+                    self.visit_expression(condition);
+                }
+                self.visit_block(&loop_stmt.body);
+            }
+            Statement::If(if_stmt) => {
+                self.visit_expression(&if_stmt.condition.borrow());
+                self.visit_block(&if_stmt.if_arm);
+                if let Some(else_arm) = &if_stmt.else_arm {
+                    self.visit_block(else_arm);
+                }
+            }
+            Statement::VariableDefinition(var_def) => {
+                if let Some(value) = &var_def.value {
+                    self.visit_expression(&value.borrow());
+                }
+                self.declare(
+                    &var_def.name.name,
+                    var_def.name.location,
+                    BindingKind::Variable,
+                );
+            }
+            Statement::Assert(assert) => self.visit_expression(&assert.expression.borrow()),
+            Statement::Break(_)
+            | Statement::TypeDefinition(_)
+            | Statement::ConstantDefinition(_) => {}
+        }
+    }
+
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => self.mark_used(&identifier.name),
+            Expression::ArrayIndexAccess(access) => {
+                self.visit_expression(&access.array.borrow());
+                self.visit_expression(&access.index.borrow());
+            }
+            Expression::Binary(binary) => {
+                self.visit_expression(&binary.left.borrow());
+                self.visit_expression(&binary.right.borrow());
+            }
+            Expression::MemberAccess(access) => self.visit_expression(&access.expression.borrow()),
+            Expression::TypeMemberAccess(access) => {
+                self.visit_expression(&access.expression.borrow());
+            }
+            Expression::FunctionCall(call) => {
+                self.visit_expression(&call.function);
+                for (_, argument) in call.arguments.iter().flatten() {
+                    self.visit_expression(&argument.borrow());
+                }
+            }
+            Expression::Struct(struct_expr) => {
+                for (_, value) in struct_expr.fields.iter().flatten() {
+                    self.visit_expression(&value.borrow());
+                }
+            }
+            Expression::PrefixUnary(unary) => self.visit_expression(&unary.expression.borrow()),
+            Expression::Parenthesized(paren) => self.visit_expression(&paren.expression.borrow()),
+            Expression::Literal(Literal::Array(array)) => {
+                for element in array.elements.iter().flatten() {
+                    self.visit_expression(&element.borrow());
+                }
+            }
+            Expression::Literal(_) | Expression::Type(_) | Expression::Uzumaki(_) => {}
+        }
+    }
+
+    fn into_warnings(self) -> impl Iterator<Item = AnalysisWarning> {
+        self.bindings
+            .into_iter()
+            .filter(|binding| !binding.used && !binding.name.starts_with('_'))
+            .map(|binding| match binding.kind {
+                BindingKind::Variable => AnalysisWarning::UnusedVariable {
+                    name: binding.name,
+                    location: binding.location,
+                },
+                BindingKind::Parameter => AnalysisWarning::UnusedParameter {
+                    name: binding.name,
+                    location: binding.location,
+                },
+            })
+    }
+}
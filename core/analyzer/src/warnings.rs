@@ -0,0 +1,256 @@
+//! Analysis Warnings
+//!
+//! This module defines [`AnalysisWarning`], the diagnostics produced by the
+//! analysis passes. Codes continue the `W` code space used by
+//! [`TypeCheckWarning`](inference_type_checker::warnings::TypeCheckWarning),
+//! so every code identifies exactly one warning kind across the compiler.
+//! Codes are assigned when a warning kind is introduced and never reused.
+//! Each kind also has a name, listed in [`WARNING_KINDS`], by which users
+//! allow or deny it.
+
+use inference_ast::nodes::Location;
+use inference_type_checker::errors::RelatedNote;
+use thiserror::Error;
+
+/// Code and name of every analysis warning kind, in code order.
+///
+/// The name identifies the kind when selecting which warnings to report, such as
+/// `unused_variable`.
+pub const WARNING_KINDS: [(&str, &str); 11] = [
+    ("W0003", "unused_variable"),
+    ("W0004", "unused_parameter"),
+    ("W0005", "unused_function"),
+    ("W0006", "unused_type"),
+    ("W0007", "unreachable_code"),
+    ("W0008", "trivial_assertion"),
+    ("W0009", "unreachable_assertion"),
+    ("W0010", "vacuous_assumption"),
+    ("W0011", "quantifier_outer_write"),
+    ("W0012", "quantifier_impure_call"),
+    ("W0013", "possibly_non_terminating_loop"),
+];
+
+/// Diagnostic produced by semantic analysis.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AnalysisWarning {
+    #[error("{location}: unused variable `{name}`")]
+    UnusedVariable { name: String, location: Location },
+
+    #[error("{location}: unused parameter `{name}`")]
+    UnusedParameter { name: String, location: Location },
+
+    #[error("{location}: function `{name}` is never used")]
+    UnusedFunction { name: String, location: Location },
+
+    #[error("{location}: {kind} `{name}` is never used")]
+    UnusedType {
+        kind: &'static str,
+        name: String,
+        location: Location,
+    },
+
+    /// The first statement of a run of statements that can never execute.
+    /// The note points at the code that makes it unreachable.
+    #[error("{location}: unreachable statement")]
+    UnreachableCode {
+        location: Location,
+        note: RelatedNote,
+    },
+
+    #[error("{location}: assertion is always true")]
+    TrivialAssertion { location: Location },
+
+    #[error("{location}: assertion is unreachable and is never checked")]
+    UnreachableAssertion { location: Location },
+
+    /// An `assume` block that can never hold. Every execution reaching it is
+    /// discarded, so anything verified after it holds vacuously.
+    #[error("{location}: `assume` block is always false, the code after it is vacuously verified")]
+    VacuousAssumption {
+        location: Location,
+        note: RelatedNote,
+    },
+
+    #[error("{location}: `{quantifier}` block writes to `{name}`, which is declared outside it")]
+    QuantifierOuterWrite {
+        quantifier: &'static str,
+        name: String,
+        location: Location,
+        note: RelatedNote,
+    },
+
+    #[error("{location}: `{quantifier}` block calls impure function `{name}`")]
+    QuantifierImpureCall {
+        quantifier: &'static str,
+        name: String,
+        location: Location,
+        note: RelatedNote,
+    },
+
+    /// A loop with no reachable exit and no decreasing measure. The note
+    /// points at the condition or update that defeats termination.
+    #[error("{location}: loop may not terminate")]
+    PossiblyNonTerminatingLoop {
+        location: Location,
+        note: RelatedNote,
+    },
+}
+
+impl AnalysisWarning {
+    /// Returns the source location associated with this warning.
+    #[must_use]
+    pub fn location(&self) -> &Location {
+        match self {
+            AnalysisWarning::UnusedVariable { location, .. }
+            | AnalysisWarning::UnusedParameter { location, .. }
+            | AnalysisWarning::UnusedFunction { location, .. }
+            | AnalysisWarning::UnusedType { location, .. }
+            | AnalysisWarning::UnreachableCode { location, .. }
+            | AnalysisWarning::TrivialAssertion { location }
+            | AnalysisWarning::UnreachableAssertion { location }
+            | AnalysisWarning::VacuousAssumption { location, .. }
+            | AnalysisWarning::QuantifierOuterWrite { location, .. }
+            | AnalysisWarning::QuantifierImpureCall { location, .. }
+            | AnalysisWarning::PossiblyNonTerminatingLoop { location, .. } => location,
+        }
+    }
+
+    /// Returns secondary source spans that explain this warning.
+    #[must_use]
+    pub fn related_notes(&self) -> &[RelatedNote] {
+        match self {
+            AnalysisWarning::UnreachableCode { note, .. }
+            | AnalysisWarning::VacuousAssumption { note, .. }
+            | AnalysisWarning::QuantifierOuterWrite { note, .. }
+            | AnalysisWarning::QuantifierImpureCall { note, .. }
+            | AnalysisWarning::PossiblyNonTerminatingLoop { note, .. } => {
+                std::slice::from_ref(note)
+            }
+            _ => &[],
+        }
+    }
+
+    /// Renders the warning message followed by its related notes.
+    ///
+    /// Notes are appended in parentheses so the result stays on a single line,
+    /// e.g. `4:5: unreachable statement (3:5: note: this loop never exits)`.
+    #[must_use]
+    pub fn render_with_notes(&self) -> String {
+        let notes = self.related_notes();
+        if notes.is_empty() {
+            return self.to_string();
+        }
+        let notes = notes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{self} ({notes})")
+    }
+
+    /// Returns the stable diagnostic code for this warning kind.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            AnalysisWarning::UnusedVariable { .. } => "W0003",
+            AnalysisWarning::UnusedParameter { .. } => "W0004",
+            AnalysisWarning::UnusedFunction { .. } => "W0005",
+            AnalysisWarning::UnusedType { .. } => "W0006",
+            AnalysisWarning::UnreachableCode { .. } => "W0007",
+            AnalysisWarning::TrivialAssertion { .. } => "W0008",
+            AnalysisWarning::UnreachableAssertion { .. } => "W0009",
+            AnalysisWarning::VacuousAssumption { .. } => "W0010",
+            AnalysisWarning::QuantifierOuterWrite { .. } => "W0011",
+            AnalysisWarning::QuantifierImpureCall { .. } => "W0012",
+            AnalysisWarning::PossiblyNonTerminatingLoop { .. } => "W0013",
+        }
+    }
+
+    /// Returns the name of this warning kind, as listed in [`WARNING_KINDS`].
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnalysisWarning::UnusedVariable { .. } => "unused_variable",
+            AnalysisWarning::UnusedParameter { .. } => "unused_parameter",
+            AnalysisWarning::UnusedFunction { .. } => "unused_function",
+            AnalysisWarning::UnusedType { .. } => "unused_type",
+            AnalysisWarning::UnreachableCode { .. } => "unreachable_code",
+            AnalysisWarning::TrivialAssertion { .. } => "trivial_assertion",
+            AnalysisWarning::UnreachableAssertion { .. } => "unreachable_assertion",
+            AnalysisWarning::VacuousAssumption { .. } => "vacuous_assumption",
+            AnalysisWarning::QuantifierOuterWrite { .. } => "quantifier_outer_write",
+            AnalysisWarning::QuantifierImpureCall { .. } => "quantifier_impure_call",
+            AnalysisWarning::PossiblyNonTerminatingLoop { .. } => "possibly_non_terminating_loop",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(line: u32, column: u32) -> Location {
+        Location {
+            offset_start: 0,
+            offset_end: 0,
+            start_line: line,
+            start_column: column,
+            end_line: line,
+            end_column: column,
+        }
+    }
+
+    #[test]
+    fn unused_type_names_its_kind() {
+        let warning = AnalysisWarning::UnusedType {
+            kind: "struct",
+            name: "Point".to_string(),
+            location: location(4, 8),
+        };
+        assert_eq!(warning.to_string(), "4:8: struct `Point` is never used");
+        assert_eq!(warning.code(), "W0006");
+    }
+
+    #[test]
+    fn warning_codes_are_stable() {
+        let loc = location(1, 1);
+        let codes: Vec<&str> = [
+            AnalysisWarning::UnusedVariable {
+                name: "x".to_string(),
+                location: loc,
+            },
+            AnalysisWarning::UnusedParameter {
+                name: "x".to_string(),
+                location: loc,
+            },
+            AnalysisWarning::UnusedFunction {
+                name: "f".to_string(),
+                location: loc,
+            },
+        ]
+        .iter()
+        .map(AnalysisWarning::code)
+        .collect();
+        assert_eq!(codes, ["W0003", "W0004", "W0005"]);
+    }
+
+    #[test]
+    fn warning_names_match_their_codes() {
+        let loc = location(1, 1);
+        let warnings = [
+            AnalysisWarning::UnusedVariable {
+                name: "x".to_string(),
+                location: loc,
+            },
+            AnalysisWarning::UnusedType {
+                kind: "enum",
+                name: "E".to_string(),
+                location: loc,
+            },
+            AnalysisWarning::TrivialAssertion { location: loc },
+        ];
+        for warning in warnings {
+            assert!(WARNING_KINDS.contains(&(warning.code(), warning.name())));
+        }
+    }
+}
@@ -20,16 +20,25 @@ pub struct Arena {
 }
 
 impl Arena {
+    /// Returns all source files ordered by node id, i.e. the order they were built.
     #[must_use]
     pub fn source_files(&self) -> Vec<Rc<SourceFile>> {
-        self.list_nodes_cmp(|node| {
-            if let AstNode::Ast(Ast::SourceFile(source_file)) = node {
-                Some(source_file.clone())
-            } else {
-                None
-            }
-        })
-        .collect()
+        let mut source_files: Vec<Rc<SourceFile>> = self
+            .list_nodes_cmp(|node| {
+                if let AstNode::Ast(Ast::SourceFile(source_file)) = node {
+                    Some(source_file.clone())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        source_files.sort_by_key(|source_file| source_file.id);
+        source_files
+    }
+    /// Returns the number of nodes in the arena, source files included.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
     }
     #[must_use]
     pub fn functions(&self) -> Vec<Rc<FunctionDefinition>> {
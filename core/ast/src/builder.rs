@@ -28,13 +28,9 @@
 //! # Error Handling
 //!
 //! The builder collects errors during construction by checking for tree-sitter ERROR nodes.
-//! If any errors are found, `build_ast()` prints them to stderr and returns an error:
-//!
-//! ```text
-//! AST Builder Error: Syntax error at line 5
-//! AST Builder Error: Unexpected token at line 10
-//! Error: AST building failed due to errors
-//! ```
+//! If any errors are found, `build_ast()` fails with a
+//! [`SyntaxErrors`](crate::errors::SyntaxErrors) error carrying all of them, with their
+//! locations, for the caller to report.
 //!
 //! # Node ID Assignment
 //!
@@ -59,6 +55,7 @@
 //! tree-sitter ERROR nodes from parse failures.
 
 use std::{
+    path::PathBuf,
     rc::Rc,
     sync::atomic::{AtomicU32, Ordering},
 };
@@ -69,6 +66,7 @@ use crate::nodes::{
 };
 use crate::{
     arena::Arena,
+    errors::{SyntaxError, SyntaxErrors},
     nodes::{
         Argument, ArrayIndexAccessExpression, ArrayLiteral, AssertStatement, AssignStatement,
         AstNode, BinaryExpression, Block, BlockType, BoolLiteral, BreakStatement,
@@ -86,8 +84,8 @@ use tree_sitter::Node;
 
 pub struct Builder<'a> {
     arena: Arena,
-    source_code: Vec<(Node<'a>, &'a [u8])>,
-    errors: Vec<anyhow::Error>,
+    source_code: Vec<(Node<'a>, &'a [u8], Option<PathBuf>)>,
+    errors: Vec<SyntaxError>,
 }
 
 impl Default for Builder<'_> {
@@ -116,7 +114,22 @@ impl<'a> Builder<'a> {
             root.kind() == "source_file",
             "Expected a root node of type `source_file`"
         );
-        self.source_code.push((root, code));
+        self.source_code.push((root, code, None));
+    }
+
+    /// Adds the source code and CST of the file at `path` to the builder.
+    ///
+    /// Syntax errors found in the file carry `path`, so errors of a multi-file build can
+    /// be attributed to their file.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the `root` node is not of type `source_file`.
+    pub fn add_source_file(&mut self, root: Node<'a>, code: &'a [u8], path: PathBuf) {
+        self.add_source_code(root, code);
+        if let Some(source) = self.source_code.last_mut() {
+            source.2 = Some(path);
+        }
     }
 
     /// Builds the AST from the root node and source code.
@@ -130,7 +143,8 @@ impl<'a> Builder<'a> {
     /// This function will return an error if the `source_file` is malformed and a valid AST cannot be constructed.
     #[allow(clippy::single_match_else)]
     pub fn build_ast(&'_ mut self) -> anyhow::Result<Arena> {
-        for (root, code) in &self.source_code.clone() {
+        for (root, code, path) in &self.source_code.clone() {
+            let first_error = self.errors.len();
             let id = Self::get_node_id();
             let location = Self::get_location(root, code);
             let source = String::from_utf8_lossy(code);
@@ -159,13 +173,13 @@ impl<'a> Builder<'a> {
             }
             self.arena
                 .add_node(AstNode::Ast(Ast::SourceFile(Rc::new(ast))), u32::MAX);
-            if !self.errors.is_empty() {
-                for err in &self.errors {
-                    eprintln!("AST Builder Error: {err}");
-                }
-                return Err(anyhow::anyhow!("AST building failed due to errors"));
+            for error in &mut self.errors[first_error..] {
+                error.file.clone_from(path);
             }
         }
+        if !self.errors.is_empty() {
+            return Err(SyntaxErrors::new(self.errors.clone()).into());
+        }
         Ok(self.arena.clone())
     }
 
@@ -314,17 +328,22 @@ impl<'a> Builder<'a> {
                 Definition::Type(self.build_type_definition(parent_id, node, code))
             }
             "ERROR" => {
-                self.errors.push(anyhow::anyhow!(
-                    "Syntax error at {}: unexpected or malformed token",
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Syntax error at {location}: unexpected or malformed token"),
                 ));
                 Self::create_error_definition(node, code)
             }
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected definition kind '{}' at {}",
-                    node.kind(),
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!(
+                        "Unexpected definition kind '{}' at {}",
+                        node.kind(),
+                        location
+                    ),
                 ));
                 Self::create_error_definition(node, code)
             }
@@ -475,9 +494,10 @@ impl<'a> Builder<'a> {
             returns = Some(self.build_type(id, &returns_node, code));
         }
         let Some(name_node) = node.child_by_field_name("name") else {
-            self.errors.push(anyhow::anyhow!(
-                "Missing function name at {}",
-                Self::get_location(node, code)
+            let location = Self::get_location(node, code);
+            self.errors.push(SyntaxError::new(
+                location,
+                format!("Missing function name at {location}"),
             ));
             let placeholder_name = Rc::new(Identifier::new(
                 Self::get_node_id(),
@@ -504,9 +524,10 @@ impl<'a> Builder<'a> {
         let body = if let Some(body_node) = node.child_by_field_name("body") {
             self.build_block(id, &body_node, code)
         } else {
-            self.errors.push(anyhow::anyhow!(
-                "Missing function body at {}",
-                Self::get_location(node, code)
+            let location = Self::get_location(node, code);
+            self.errors.push(SyntaxError::new(
+                location,
+                format!("Missing function body at {location}"),
             ));
             BlockType::Block(Rc::new(Block::new(
                 Self::get_node_id(),
@@ -544,14 +565,15 @@ impl<'a> Builder<'a> {
         let mut arguments = None;
         let mut returns = None;
 
-        let mut cursor = node.walk();
-
-        let founded_arguments = node
-            .children_by_field_name("argument", &mut cursor)
-            .map(|segment| self.build_argument_type(id, &segment, code));
-        let founded_arguments: Vec<ArgumentType> = founded_arguments.collect();
-        if !founded_arguments.is_empty() {
-            arguments = Some(founded_arguments);
+        if let Some(argument_list_node) = node.child_by_field_name("argument_list") {
+            let mut cursor = argument_list_node.walk();
+            let founded_arguments = argument_list_node
+                .children_by_field_name("argument", &mut cursor)
+                .map(|segment| self.build_argument_type(id, &segment, code));
+            let founded_arguments: Vec<ArgumentType> = founded_arguments.collect();
+            if !founded_arguments.is_empty() {
+                arguments = Some(founded_arguments);
+            }
         }
 
         if let Some(returns_node) = node.child_by_field_name("returns") {
@@ -761,17 +783,18 @@ impl<'a> Builder<'a> {
                 BlockType::Block(node)
             }
             "ERROR" => {
-                self.errors.push(anyhow::anyhow!(
-                    "Syntax error in block at {}",
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Syntax error in block at {location}"),
                 ));
                 self.create_error_block(node, code, parent_id)
             }
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected block type '{}' at {}",
-                    node.kind(),
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected block type '{}' at {}", node.kind(), location),
                 ));
                 self.create_error_block(node, code, parent_id)
             }
@@ -846,17 +869,22 @@ impl<'a> Builder<'a> {
                 Statement::ConstantDefinition(self.build_constant_definition(parent_id, node, code))
             }
             "ERROR" => {
-                self.errors.push(anyhow::anyhow!(
-                    "Syntax error in statement at {}",
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Syntax error in statement at {location}"),
                 ));
                 self.create_error_statement(node, code, parent_id)
             }
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected statement type '{}' at {}",
-                    node.kind(),
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!(
+                        "Unexpected statement type '{}' at {}",
+                        node.kind(),
+                        location
+                    ),
                 ));
                 self.create_error_statement(node, code, parent_id)
             }
@@ -917,9 +945,10 @@ impl<'a> Builder<'a> {
         let body = if let Some(body_block) = node.child_by_field_name("body") {
             self.build_block(id, &body_block, code)
         } else {
-            self.errors.push(anyhow::anyhow!(
-                "Missing loop body at {}",
-                Self::get_location(node, code)
+            let location = Self::get_location(node, code);
+            self.errors.push(SyntaxError::new(
+                location,
+                format!("Missing loop body at {location}"),
             ));
             BlockType::Block(Rc::new(Block::new(Self::get_node_id(), location, vec![])))
         };
@@ -936,9 +965,10 @@ impl<'a> Builder<'a> {
         let condition = if let Some(condition_node) = node.child_by_field_name("condition") {
             self.build_expression(id, &condition_node, code)
         } else {
-            self.errors.push(anyhow::anyhow!(
-                "Missing if condition at {}",
-                Self::get_location(node, code)
+            let location = Self::get_location(node, code);
+            self.errors.push(SyntaxError::new(
+                location,
+                format!("Missing if condition at {location}"),
             ));
             Expression::Identifier(Rc::new(Identifier::new(
                 Self::get_node_id(),
@@ -949,9 +979,10 @@ impl<'a> Builder<'a> {
         let if_arm = if let Some(if_arm_node) = node.child_by_field_name("if_arm") {
             self.build_block(id, &if_arm_node, code)
         } else {
-            self.errors.push(anyhow::anyhow!(
-                "Missing if body at {}",
-                Self::get_location(node, code)
+            let location = Self::get_location(node, code);
+            self.errors.push(SyntaxError::new(
+                location,
+                format!("Missing if body at {location}"),
             ));
             BlockType::Block(Rc::new(Block::new(Self::get_node_id(), location, vec![])))
         };
@@ -1047,9 +1078,10 @@ impl<'a> Builder<'a> {
             }
             "identifier" => Expression::Identifier(self.build_identifier(parent_id, node, code)),
             "ERROR" => {
-                self.errors.push(anyhow::anyhow!(
-                    "Syntax error in expression at {}",
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Syntax error in expression at {location}"),
                 ));
                 let location = Self::get_location(node, code);
                 Expression::Identifier(Rc::new(Identifier::new(
@@ -1059,10 +1091,10 @@ impl<'a> Builder<'a> {
                 )))
             }
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected expression node kind '{}' at {}",
-                    node_kind,
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected expression node kind '{node_kind}' at {location}"),
                 ));
                 let location = Self::get_location(node, code);
                 Expression::Identifier(Rc::new(Identifier::new(
@@ -1246,13 +1278,13 @@ impl<'a> Builder<'a> {
                 let child = cursor.node();
                 if let Some(field) = cursor.field_name() {
                     match field {
-                        "field" => {
+                        "field_name" => {
                             let expr = self.build_expression(id, &child, code);
                             if let Expression::Identifier(ident) = expr {
                                 pending_name = Some(ident);
                             }
                         }
-                        "value" => {
+                        "field_value" => {
                             let expr = self.build_expression(id, &child, code);
                             let name = pending_name
                                 .take()
@@ -1398,10 +1430,10 @@ impl<'a> Builder<'a> {
             "&" => OperatorKind::BitAnd,
             "|" => OperatorKind::BitOr,
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected operator '{}' at {}",
-                    operator_kind,
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected operator '{operator_kind}' at {location}"),
                 ));
                 OperatorKind::Add
             }
@@ -1425,10 +1457,10 @@ impl<'a> Builder<'a> {
             "number_literal" => Literal::Number(self.build_number_literal(parent_id, node, code)),
             "unit_literal" => Literal::Unit(self.build_unit_literal(parent_id, node, code)),
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected literal type '{}' at {}",
-                    node.kind(),
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected literal type '{}' at {}", node.kind(), location),
                 ));
                 Literal::Unit(Rc::new(UnitLiteral::new(
                     Self::get_node_id(),
@@ -1475,10 +1507,10 @@ impl<'a> Builder<'a> {
             "true" => true,
             "false" => false,
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected boolean literal value '{}' at {}",
-                    text,
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected boolean literal value '{text}' at {location}"),
                 ));
                 false
             }
@@ -1569,17 +1601,18 @@ impl<'a> Builder<'a> {
                 Type::Custom(name)
             }
             "ERROR" => {
-                self.errors.push(anyhow::anyhow!(
-                    "Syntax error in type at {}",
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Syntax error in type at {location}"),
                 ));
                 Type::Simple(SimpleTypeKind::Unit)
             }
             _ => {
-                self.errors.push(anyhow::anyhow!(
-                    "Unexpected type '{}' at {}",
-                    node_kind,
-                    Self::get_location(node, code)
+                let location = Self::get_location(node, code);
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!("Unexpected type '{node_kind}' at {location}"),
                 ));
                 Type::Simple(SimpleTypeKind::Unit)
             }
@@ -1765,11 +1798,14 @@ impl<'a> Builder<'a> {
                 let source_snippet = String::from_utf8_lossy(
                     &code[location.offset_start as usize..location.offset_end as usize],
                 );
-                self.errors.push(anyhow::anyhow!(
-                    "Parse error: invalid syntax at line {}:{} near '{}'",
-                    location.start_line,
-                    location.start_column,
-                    source_snippet.chars().take(30).collect::<String>()
+                self.errors.push(SyntaxError::new(
+                    location,
+                    format!(
+                        "Parse error: invalid syntax at line {}:{} near '{}'",
+                        location.start_line,
+                        location.start_column,
+                        source_snippet.chars().take(30).collect::<String>()
+                    ),
                 ));
             }
         }
@@ -6,6 +6,8 @@ use std::path::PathBuf;
 
 use thiserror::Error;
 
+use crate::nodes::Location;
+
 /// Errors that can occur during external module parsing and resolution.
 #[derive(Debug, Error)]
 #[must_use = "errors must not be silently ignored"]
@@ -30,3 +32,49 @@ pub enum AstError {
     #[error("failed to build AST for {path}: {reason}")]
     AstBuildError { path: PathBuf, reason: String },
 }
+
+/// A syntax error found by [`Builder`](crate::builder::Builder) in the source code.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct SyntaxError {
+    /// Description of the error, including its position.
+    pub message: String,
+    /// Source location of the malformed node.
+    pub location: Location,
+    /// Path of the source file, if the source was added with one.
+    pub file: Option<PathBuf>,
+}
+
+impl SyntaxError {
+    #[must_use]
+    pub fn new(location: Location, message: String) -> Self {
+        Self {
+            message,
+            location,
+            file: None,
+        }
+    }
+}
+
+/// All syntax errors found while building the AST, in the order they were found.
+///
+/// [`Builder::build_ast`](crate::builder::Builder::build_ast) fails with this error, so
+/// callers can recover the structured errors from the `anyhow::Error` with
+/// `downcast_ref::<SyntaxErrors>()`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("AST building failed due to errors")]
+pub struct SyntaxErrors {
+    errors: Vec<SyntaxError>,
+}
+
+impl SyntaxErrors {
+    pub(crate) fn new(errors: Vec<SyntaxError>) -> Self {
+        Self { errors }
+    }
+
+    /// Returns the collected errors.
+    #[must_use]
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
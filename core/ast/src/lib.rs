@@ -32,7 +32,7 @@
 //! - [`builder::Builder`] - Builds AST from tree-sitter concrete syntax tree
 //! - [`nodes`] - AST node type definitions (`SourceFile`, `FunctionDefinition`, etc.)
 //! - [`extern_prelude`] - External module discovery and parsing
-//! - [`parser_context::ParserContext`] - Multi-file parsing context (WIP)
+//! - [`parser_context::ParserContext`] - Multi-file parsing context
 //! - [`errors`] - Structured error types for AST operations
 //!
 //! # Key Features
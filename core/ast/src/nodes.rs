@@ -1,4 +1,5 @@
 use core::fmt;
+use serde::Serialize;
 use std::{
     cell::RefCell,
     fmt::{Display, Formatter},
@@ -9,7 +10,7 @@ use std::{
 ///
 /// Stores byte offsets and line/column positions.
 /// Source text should be retrieved from the `SourceFile` using the offset range.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize)]
 pub struct Location {
     pub offset_start: u32,
     pub offset_end: u32,
@@ -58,7 +59,7 @@ macro_rules! ast_node {
         }
     ) => {
         $(#[$outer])*
-        #[derive(Clone, PartialEq, Eq, Debug)]
+        #[derive(Clone, PartialEq, Eq, Debug, ::serde::Serialize)]
         $struct_vis struct $name {
             pub id: u32,
             pub location: $crate::nodes::Location,
@@ -97,7 +98,7 @@ macro_rules! ast_enum {
         }
     ) => {
         $(#[$outer])*
-        #[derive(Clone, PartialEq, Eq, Debug)]
+        #[derive(Clone, PartialEq, Eq, Debug, Serialize)]
         $enum_vis enum $name {
             $(
                 $(#[$arm_attr])*
@@ -173,7 +174,7 @@ macro_rules! ast_enums {
             }
         )+
 
-        #[derive(Clone, Debug)]
+        #[derive(Clone, Debug, Serialize)]
         pub enum AstNode {
             $(
                 $name($name),
@@ -306,7 +307,7 @@ ast_enums! {
 /// # Default
 ///
 /// Definitions are `Private` by default, following the principle of least privilege.
-#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize)]
 pub enum Visibility {
     /// Private visibility (default). Definition is only accessible within its module.
     #[default]
@@ -318,7 +319,7 @@ pub enum Visibility {
 /// Unary operator kinds for prefix expressions.
 ///
 /// Represents operators that take a single operand.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub enum UnaryOperatorKind {
     /// Logical negation: `!expr`
     Not,
@@ -332,7 +333,7 @@ pub enum UnaryOperatorKind {
 ///
 /// Primitive types have dedicated variants for efficient pattern matching
 /// without string comparison. User-defined types use `Type::Custom` instead.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, Serialize)]
 pub enum SimpleTypeKind {
     Unit,
     Bool,
@@ -369,7 +370,7 @@ impl SimpleTypeKind {
 ///
 /// Represents operators that take two operands (left and right).
 /// Operators are listed roughly in order of precedence groups.
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub enum OperatorKind {
     /// Exponentiation: `a ** b`
     Pow,
@@ -106,6 +106,16 @@ impl SourceFile {
             })
             .collect()
     }
+    #[must_use]
+    pub fn external_function_definitions(&self) -> Vec<Rc<ExternalFunctionDefinition>> {
+        self.definitions
+            .iter()
+            .filter_map(|def| match def {
+                Definition::ExternalFunction(func) => Some(func.clone()),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 impl BlockType {
@@ -1,29 +1,36 @@
 //! Multi-file AST parsing context.
 //!
-//! Manages parsing across multiple source files, handling module resolution
-//! and building a unified AST with proper scope relationships.
+//! Manages parsing across multiple source files, building a unified AST in one arena.
+//! Every file becomes a [`SourceFile`](crate::nodes::SourceFile) root of the arena, in the
+//! order the files were queued, so later phases treat them as one module.
 //!
 //! # Status
 //!
-//! **Work in Progress** - This module provides the skeleton for multi-file support
-//! but is not yet functional. See CLAUDE.md: "Multi-file support not yet implemented."
+//! Queued files are parsed and built into a single arena, which is how projects are
+//! compiled: [`project_files`] lists the `.inf` files under `src/`, and each of them is
+//! queued. Module declarations (`mod name;` and `mod name { ... }`) are not supported by
+//! the grammar yet, so all files share the root scope.
 //!
 //! # Planned Implementation
 //!
-//! The parsing context will:
-//! 1. Initialize with a root file path
-//! 2. Process the queue of files, building AST for each
-//! 3. Handle module declarations (`mod name;` and `mod name { ... }`)
-//! 4. Resolve submodule file paths following Inference conventions
+//! Once the grammar supports module declarations, the parsing context will:
+//! 1. Handle module declarations (`mod name;` and `mod name { ... }`)
+//! 2. Resolve submodule file paths following Inference conventions
 //!
 //! Reference implementation patterns are preserved in function doc comments.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::arena::Arena;
+use crate::builder::Builder;
+use crate::errors::AstError;
+use crate::extern_prelude::find_module_root;
 use crate::nodes::ModuleDefinition;
 
+/// Name of the project manifest.
+pub const MANIFEST_FILE: &str = "Inference.toml";
+
 /// Queue entry for pending file parsing.
 #[allow(dead_code)]
 struct ParseQueueEntry {
@@ -37,14 +44,24 @@ struct ParseQueueEntry {
 ///
 /// Maintains a queue of files to parse and tracks the relationships
 /// between modules and their source files.
-#[allow(dead_code)]
+///
+/// # Example
+///
+/// ```no_run
+/// use inference_ast::parser_context::{ParserContext, project_files};
+/// use std::path::Path;
+///
+/// let files = project_files(Path::new("my_project")).unwrap();
+/// let mut context = ParserContext::new(files[0].clone());
+/// for file in &files[1..] {
+///     context.push_file(0, file.clone());
+/// }
+/// let arena = context.parse_all().unwrap();
+/// assert_eq!(arena.source_files().len(), files.len());
+/// ```
 pub struct ParserContext {
-    /// Current node ID counter.
-    next_id: u32,
     /// Queue of files pending parsing.
     queue: Vec<ParseQueueEntry>,
-    /// The arena being built.
-    arena: Arena,
 }
 
 impl ParserContext {
@@ -54,45 +71,71 @@ impl ParserContext {
     #[must_use]
     pub fn new(root_path: PathBuf) -> Self {
         Self {
-            next_id: 0,
             queue: vec![ParseQueueEntry {
                 scope_id: 0,
                 file_path: root_path,
             }],
-            arena: Arena::default(),
         }
     }
 
-    /// Pushes a new file onto the parse queue for submodule resolution.
-    ///
-    /// # Planned Implementation
+    /// Pushes a new file onto the parse queue.
     ///
-    /// Will add the file to the queue with its parent scope ID, enabling
-    /// proper scope relationships when the file is parsed.
-    #[allow(clippy::unused_self)]
-    pub fn push_file(&mut self, _scope_id: u32, _file_path: PathBuf) {
-        // Not yet implemented - see module documentation
+    /// Files are parsed in the order they were queued. `scope_id` is the scope the file
+    /// belongs to; until module declarations are supported, every file belongs to the
+    /// root scope 0.
+    pub fn push_file(&mut self, scope_id: u32, file_path: PathBuf) {
+        self.queue.push(ParseQueueEntry {
+            scope_id,
+            file_path,
+        });
     }
 
     /// Parses all queued files and builds the unified AST.
     ///
-    /// # Planned Implementation
+    /// Each file becomes a source file of the arena, in queue order. Syntax errors of all
+    /// files are collected before failing; each carries the path of its file.
     ///
-    /// ```text
-    /// while let Some(entry) = self.queue.pop() {
-    ///     let ast_file = self.parse_file(&entry.file_path);
-    ///     for child in ast_file.children {
-    ///         match child {
-    ///             Directive::Use(u) => { /* add to scope imports */ }
-    ///             Definition::Module(m) => { self.process_module(m, entry.scope_id); }
-    ///             _ => { /* process other definitions */ }
-    ///         }
-    ///     }
-    /// }
-    /// ```
-    #[must_use]
-    pub fn parse_all(&mut self) -> Arena {
-        std::mem::take(&mut self.arena)
+    /// # Errors
+    ///
+    /// Returns an [`AstError`] if a file cannot be read or parsed, and a
+    /// [`SyntaxErrors`](crate::errors::SyntaxErrors) error if any file has syntax errors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Inference grammar fails to load (should never happen with valid
+    /// tree-sitter setup).
+    pub fn parse_all(&mut self) -> anyhow::Result<Arena> {
+        let entries = std::mem::take(&mut self.queue);
+        let mut sources = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            let source =
+                std::fs::read_to_string(&entry.file_path).map_err(|e| AstError::FileReadError {
+                    path: entry.file_path.clone(),
+                    source: e,
+                })?;
+            sources.push(source);
+        }
+
+        let inference_language = tree_sitter_inference::language();
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&inference_language)
+            .expect("Error loading Inference grammar");
+        let mut trees = Vec::with_capacity(entries.len());
+        for (entry, source) in entries.iter().zip(&sources) {
+            let tree = parser
+                .parse(source, None)
+                .ok_or_else(|| AstError::ParseError {
+                    path: entry.file_path.clone(),
+                })?;
+            trees.push(tree);
+        }
+
+        let mut builder = Builder::new();
+        for ((entry, source), tree) in entries.iter().zip(&sources).zip(&trees) {
+            builder.add_source_file(tree.root_node(), source.as_bytes(), entry.file_path.clone());
+        }
+        builder.build_ast()
     }
 
     /// Resolves and processes a module definition.
@@ -124,14 +167,55 @@ impl ParserContext {
     ) {
         // Not yet implemented - see module documentation
     }
+}
 
-    /// Generates a new unique node ID.
-    #[allow(dead_code)]
-    fn next_node_id(&mut self) -> u32 {
-        let id = self.next_id;
-        self.next_id += 1;
-        id
+/// Returns the source files of the project at `path`, root file first.
+///
+/// `path` is the project directory or its `Inference.toml`. The root file is
+/// `src/lib.inf` or `src/main.inf`, as [`find_module_root`] finds it; it is followed by
+/// every other `.inf` file under `src/`, recursively, in path order.
+///
+/// # Errors
+///
+/// Returns [`AstError::ModuleRootNotFound`] if the project has no root file, and
+/// [`AstError::FileReadError`] if a directory under `src/` cannot be read.
+pub fn project_files(path: &Path) -> Result<Vec<PathBuf>, AstError> {
+    let project_dir = if path.file_name().is_some_and(|name| name == MANIFEST_FILE) {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    };
+    let root = find_module_root(project_dir).ok_or_else(|| AstError::ModuleRootNotFound {
+        path: project_dir.to_path_buf(),
+        expected: format!(
+            "src{}lib.inf or src{}main.inf",
+            std::path::MAIN_SEPARATOR,
+            std::path::MAIN_SEPARATOR
+        ),
+    })?;
+    let mut files = Vec::new();
+    collect_source_files(&project_dir.join("src"), &mut files)?;
+    files.retain(|file| *file != root);
+    files.sort();
+    files.insert(0, root);
+    Ok(files)
+}
+
+/// Appends the `.inf` files under `dir` to `files`, recursively.
+fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), AstError> {
+    let read_error = |e| AstError::FileReadError {
+        path: dir.to_path_buf(),
+        source: e,
+    };
+    for entry in std::fs::read_dir(dir).map_err(read_error)? {
+        let path = entry.map_err(read_error)?.path();
+        if path.is_dir() {
+            collect_source_files(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "inf") {
+            files.push(path);
+        }
     }
+    Ok(())
 }
 
 /// Finds the path to a submodule file.
@@ -142,7 +226,7 @@ impl ParserContext {
 /// 1. `{current_dir}/{module_name}.inf`
 /// 2. `{current_dir}/{module_name}/mod.inf`
 ///
-/// Returns `None` until multi-file support is implemented.
+/// Returns `None` until module declarations are supported.
 #[must_use]
 pub fn find_submodule_path(_current_file: &PathBuf, _module_name: &str) -> Option<PathBuf> {
     None
@@ -0,0 +1,175 @@
+//! Defaults read from a configuration file.
+//!
+//! infc reads the defaults of some flags from `infc.toml` in the directory of the source
+//! file, or of the project, so that repeated invocations don't need long flag lists. A
+//! project without `infc.toml` may keep them in the `[infc]` table of its
+//! `Inference.toml` instead. `--config <file>` reads another file, and `--no-config`
+//! none at all.
+//!
+//! ```toml
+//! emit = ["wasm", "v"]
+//! out-dir = "build"
+//! opt-level = 2
+//!
+//! [warnings]
+//! deny = ["warnings"]
+//! allow = ["unused_variable"]
+//! ```
+//!
+//! Flags given on the command line override the file: `--emit` replaces its `emit` list,
+//! and warning flags apply after its `[warnings]`. Within the file, levels given to
+//! `warnings` apply before those of single warnings. Kinds of `emit` that the requested
+//! phases don't produce are skipped, so the same file serves `--analyze` and `--codegen`
+//! runs. Relative paths are resolved from the directory of the file.
+
+use crate::{
+    emit::Emit,
+    lints::{Level, parse_lint},
+    parser::{Cli, parse_emit},
+};
+use clap::{ArgMatches, parser::ValueSource};
+use inference::MANIFEST_FILE;
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the configuration file.
+pub(crate) const CONFIG_FILE: &str = "infc.toml";
+
+/// Defaults of the flags, as read from a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+struct Config {
+    /// Entries of `--emit`.
+    emit: Vec<String>,
+    /// Value of `--out-dir`.
+    out_dir: Option<PathBuf>,
+    /// Value of `--opt-level`.
+    opt_level: Option<u32>,
+    /// Lints of `--allow`, `--warn` and `--deny`.
+    warnings: Warnings,
+}
+
+/// The `[warnings]` table of a configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Warnings {
+    allow: Vec<String>,
+    warn: Vec<String>,
+    deny: Vec<String>,
+}
+
+/// An `Inference.toml` with an `[infc]` table; the other tables belong to `infs`.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    infc: Option<Config>,
+}
+
+/// Applies the defaults of the configuration file to `args`, except for the flags given
+/// on the command line, as recorded in `matches`.
+///
+/// # Errors
+///
+/// Returns a message naming the file if it cannot be read or holds an invalid value.
+pub(crate) fn apply(args: &mut Cli, matches: &ArgMatches) -> Result<(), String> {
+    let Some(path) = config_path(args) else {
+        return Ok(());
+    };
+    let config = read(&path).map_err(|e| format!("invalid {}: {e}", path.display()))?;
+    let Some(config) = config else {
+        return Ok(());
+    };
+    let base = path.parent().unwrap_or(Path::new(""));
+    let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+    if !given("emit") && !args.generate_wasm_output && !args.generate_v_output {
+        for entry in &config.emit {
+            let emit = parse_emit(entry)
+                .map_err(|e| format!("invalid `emit` in {}: {e}", path.display()))?;
+            if emit.kind.is_produced(args) {
+                args.emit.push(Emit {
+                    path: emit.path.map(|emit_path| resolve(base, emit_path)),
+                    ..emit
+                });
+            }
+        }
+    }
+    if !given("out_dir")
+        && let Some(out_dir) = config.out_dir
+    {
+        args.out_dir = Some(resolve(base, out_dir));
+    }
+    if !given("opt_level")
+        && let Some(opt_level) = config.opt_level
+    {
+        if opt_level > 3 {
+            return Err(format!(
+                "invalid `opt-level` in {}: expected 0 to 3, found {opt_level}",
+                path.display()
+            ));
+        }
+        args.opt_level = opt_level;
+    }
+
+    let mut rules = Vec::new();
+    for (lints, level) in [
+        (&config.warnings.allow, Level::Allow),
+        (&config.warnings.warn, Level::Warn),
+        (&config.warnings.deny, Level::Deny),
+    ] {
+        for lint in lints {
+            let lint = parse_lint(lint)
+                .map_err(|e| format!("invalid `[warnings]` in {}: {e}", path.display()))?;
+            rules.push((level, lint));
+        }
+    }
+    // `warnings` first, so that the levels of single warnings override it.
+    rules.sort_by_key(|(_, lint)| lint != "warnings");
+    args.lint_levels.set_defaults(rules);
+    Ok(())
+}
+
+/// Returns the configuration file of the invocation, if there is one: `--config`, or else
+/// `infc.toml` or the manifest of the project, or `infc.toml` next to the source file.
+fn config_path(args: &Cli) -> Option<PathBuf> {
+    if args.no_config {
+        return None;
+    }
+    if let Some(path) = &args.config {
+        return Some(path.clone());
+    }
+    let dir = match args.project_dir() {
+        Some(project_dir) => project_dir.to_path_buf(),
+        None if args.reads_stdin() => PathBuf::from("."),
+        None => args.path.parent().unwrap_or(Path::new("")).to_path_buf(),
+    };
+    let config = dir.join(CONFIG_FILE);
+    if config.is_file() {
+        return Some(config);
+    }
+    let manifest = dir.join(MANIFEST_FILE);
+    (args.project_dir().is_some() && manifest.is_file()).then_some(manifest)
+}
+
+/// Reads the configuration in `path`: the whole file, or the `[infc]` table of a manifest,
+/// which may have none.
+fn read(path: &Path) -> Result<Option<Config>, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    if path.file_name().is_some_and(|name| name == MANIFEST_FILE) {
+        let manifest: Manifest = toml::from_str(&text).map_err(|e| e.to_string())?;
+        Ok(manifest.infc)
+    } else {
+        toml::from_str(&text).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+/// Resolves `path`, relative to the directory of the configuration file if relative.
+fn resolve(base: &Path, path: PathBuf) -> PathBuf {
+    if path.is_relative() && path.as_os_str() != "-" {
+        base.join(path)
+    } else {
+        path
+    }
+}
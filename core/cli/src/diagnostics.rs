@@ -0,0 +1,326 @@
+//! Errors and warnings of a compilation, rendered for the terminal or as JSON.
+//!
+//! By default, every error and warning is printed to stderr with the offending source
+//! lines, the spans underlined and labeled, and the diagnostic code if it has one:
+//!
+//! ```text
+//! error[E0001]: type mismatch in variable definition: expected `Bool`, found `i32`
+//!   ┌─ example.inf:2:5
+//!   │
+//! 2 │     let b: bool = 1;
+//!   │     ^^^^^^^^^^^^^^^^
+//!   │         │         │
+//!   │         │         this expression has type `i32`
+//!   │         `b` declared with type `Bool` here
+//! ```
+//!
+//! The output is colored as selected by `--color` and `NO_COLOR`. A diagnostic whose
+//! source cannot be read is printed with the locations of its spans instead of a snippet.
+//!
+//! With `--message-format=json`, every error and warning is printed to stdout as one JSON
+//! object per line, so editors and CI can annotate the source file without scraping the
+//! human-readable messages:
+//!
+//! ```json
+//! {"phase":"type-check","severity":"error","code":"E0001","message":"type mismatch in variable definition: expected `i32`, found `Bool`","file":"example.inf","spans":[{"offset_start":23,"offset_end":41,"start_line":2,"start_column":5,"end_line":2,"end_column":23,"primary":true,"label":null}]}
+//! ```
+//!
+//! The first span is the primary location of the diagnostic. Related notes of type errors
+//! and analysis warnings follow as secondary spans labeled with the note. Errors without a
+//! source location, such as a failure to load the grammar, have no spans.
+
+use codespan_reporting::{
+    diagnostic::{self, Label},
+    files::SimpleFile,
+    term::{self, termcolor::Buffer},
+};
+use inference::{
+    AnalysisWarning, CodegenError, Location, RelatedNote, SyntaxError, TypeCheckError,
+    TypeCheckWarning,
+};
+use serde::Serialize;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Compilation phase that produced a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Phase {
+    Parse,
+    TypeCheck,
+    Analyze,
+    Codegen,
+}
+
+impl Phase {
+    /// Returns the name of the phase, as serialized.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::TypeCheck => "type-check",
+            Phase::Analyze => "analyze",
+            Phase::Codegen => "codegen",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Error,
+    Warning,
+}
+
+/// One error or warning, serialized as a line of JSON.
+#[derive(Debug, Serialize)]
+pub(crate) struct Diagnostic {
+    phase: Phase,
+    severity: Severity,
+    /// Stable diagnostic code, such as `E0001`, if the diagnostic kind has one.
+    code: Option<&'static str>,
+    message: String,
+    file: String,
+    spans: Vec<Span>,
+}
+
+/// A source span of a diagnostic, with 1-based lines and columns.
+#[derive(Debug, Serialize)]
+struct Span {
+    offset_start: u32,
+    offset_end: u32,
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+    primary: bool,
+    label: Option<String>,
+}
+
+impl Span {
+    fn new(location: &Location, primary: bool, label: Option<String>) -> Self {
+        Self {
+            offset_start: location.offset_start,
+            offset_end: location.offset_end,
+            start_line: location.start_line,
+            start_column: location.start_column,
+            end_line: location.end_line,
+            end_column: location.end_column,
+            primary,
+            label,
+        }
+    }
+
+    /// Returns the span as a label of a rendered snippet.
+    fn label(&self) -> Label<()> {
+        let range = self.offset_start as usize..self.offset_end as usize;
+        let label = if self.primary {
+            Label::primary((), range)
+        } else {
+            Label::secondary((), range)
+        };
+        match &self.label {
+            Some(message) => label.with_message(message),
+            None => label,
+        }
+    }
+
+    /// Returns the location of the span in `file`, with its label, for a diagnostic
+    /// rendered without a snippet.
+    fn note(&self, file: &str) -> String {
+        let location = format!("{file}:{}:{}", self.start_line, self.start_column);
+        match &self.label {
+            Some(message) => format!("{location}: {message}"),
+            None => location,
+        }
+    }
+}
+
+impl Diagnostic {
+    /// Returns an error without a source location.
+    pub(crate) fn error(phase: Phase, file: &Path, message: String) -> Self {
+        Self {
+            phase,
+            severity: Severity::Error,
+            code: None,
+            message,
+            file: file.display().to_string(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Returns a syntax error, attributed to its own file if it has one, else to `file`.
+    pub(crate) fn syntax_error(file: &Path, error: &SyntaxError) -> Self {
+        let file = error.file.as_deref().unwrap_or(file);
+        Self {
+            spans: vec![Span::new(&error.location, true, None)],
+            ..Self::error(Phase::Parse, file, error.message.clone())
+        }
+    }
+
+    pub(crate) fn type_check_error(file: &Path, error: &TypeCheckError) -> Self {
+        Self {
+            code: Some(error.code()),
+            message: strip_location(&error.to_string(), error.location()),
+            spans: spans(error.location(), error.related_notes()),
+            ..Self::error(Phase::TypeCheck, file, String::new())
+        }
+    }
+
+    pub(crate) fn type_check_warning(file: &Path, warning: &TypeCheckWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: Some(warning.code()),
+            message: strip_location(&warning.to_string(), warning.location()),
+            spans: spans(warning.location(), &[]),
+            ..Self::error(Phase::TypeCheck, file, String::new())
+        }
+    }
+
+    pub(crate) fn analysis_warning(file: &Path, warning: &AnalysisWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            code: Some(warning.code()),
+            message: strip_location(&warning.to_string(), warning.location()),
+            spans: spans(warning.location(), warning.related_notes()),
+            ..Self::error(Phase::Analyze, file, String::new())
+        }
+    }
+
+    pub(crate) fn codegen_error(file: &Path, error: &CodegenError) -> Self {
+        let label = match &error.block {
+            Some(block) => format!("in function `{}` (block `{block}`)", error.function),
+            None => format!("in function `{}`", error.function),
+        };
+        Self {
+            spans: vec![Span::new(&error.location, true, Some(label))],
+            ..Self::error(Phase::Codegen, file, error.kind.to_string())
+        }
+    }
+
+    /// Turns a warning into an error, for a warning denied with `--deny`.
+    pub(crate) fn deny(self) -> Self {
+        Self {
+            severity: Severity::Error,
+            ..self
+        }
+    }
+
+    /// Prints the diagnostic to stdout as a single line of JSON.
+    pub(crate) fn emit(&self) {
+        match serde_json::to_string(self) {
+            Ok(line) => println!("{line}"),
+            Err(e) => eprintln!("Failed to serialize diagnostic: {e}"),
+        }
+    }
+
+    /// Renders the diagnostic for the terminal, with ANSI colors if `color`.
+    ///
+    /// The spans are shown underlined in a snippet of `source`, the text of the file of
+    /// the diagnostic. Without a source, or if a span lies outside of it, the locations of
+    /// the spans are listed instead.
+    pub(crate) fn render(&self, source: Option<&str>, color: bool) -> String {
+        let mut buffer = if color {
+            Buffer::ansi()
+        } else {
+            Buffer::no_color()
+        };
+        let config = term::Config::default();
+        let rendered = source.is_some_and(|source| {
+            let file = SimpleFile::new(self.file.as_str(), source);
+            let labels = self.spans.iter().map(Span::label).collect();
+            let report = self.report().with_labels(labels);
+            term::emit(&mut buffer, &config, &file, &report).is_ok()
+        });
+        if !rendered {
+            buffer.clear();
+            let file = SimpleFile::new(self.file.as_str(), "");
+            let notes = self
+                .spans
+                .iter()
+                .map(|span| span.note(&self.file))
+                .collect();
+            let report = self.report().with_notes(notes);
+            if let Err(e) = term::emit(&mut buffer, &config, &file, &report) {
+                return format!("{}: {} ({e})\n", self.file, self.message);
+            }
+        }
+        String::from_utf8_lossy(buffer.as_slice()).into_owned()
+    }
+
+    /// Returns the severity, code and message of the diagnostic, to be rendered.
+    fn report(&self) -> diagnostic::Diagnostic<()> {
+        let report = match self.severity {
+            Severity::Error => diagnostic::Diagnostic::error(),
+            Severity::Warning => diagnostic::Diagnostic::warning(),
+        }
+        .with_message(&self.message);
+        match self.code {
+            Some(code) => report.with_code(code),
+            None => report,
+        }
+    }
+}
+
+/// Prints the diagnostics of a compilation, as JSON lines on stdout with
+/// `--message-format=json`, or rendered to stderr.
+pub(crate) struct Reporter {
+    json: bool,
+    color: bool,
+    /// Sources that are not read from their files again: the compiled sources, loaded
+    /// from their files or from stdin.
+    sources: HashMap<PathBuf, String>,
+}
+
+impl Reporter {
+    pub(crate) fn new(json: bool, color: bool) -> Self {
+        Self {
+            json,
+            color,
+            sources: HashMap::new(),
+        }
+    }
+
+    /// Sets the source of `file`, shown in the snippets of its diagnostics.
+    pub(crate) fn set_source(&mut self, file: &Path, source: String) {
+        self.sources.insert(file.to_path_buf(), source);
+    }
+
+    /// Prints `diagnostic`. The snippet of a rendered diagnostic comes from the source set
+    /// for its file, or else from the file itself.
+    pub(crate) fn report(&self, diagnostic: &Diagnostic) {
+        if self.json {
+            diagnostic.emit();
+            return;
+        }
+        let source = match self.sources.get(Path::new(&diagnostic.file)) {
+            Some(source) => Some(Cow::Borrowed(source.as_str())),
+            None if diagnostic.spans.is_empty() => None,
+            None => fs::read_to_string(&diagnostic.file).ok().map(Cow::Owned),
+        };
+        eprint!("{}", diagnostic.render(source.as_deref(), self.color));
+    }
+}
+
+/// Returns the primary span at `location` followed by a secondary span for each note.
+fn spans(location: &Location, notes: &[RelatedNote]) -> Vec<Span> {
+    std::iter::once(Span::new(location, true, None))
+        .chain(
+            notes
+                .iter()
+                .map(|note| Span::new(&note.location, false, Some(note.message.clone()))),
+        )
+        .collect()
+}
+
+/// Removes the `line:column: ` prefix of a rendered type error or warning, which the
+/// spans already carry.
+fn strip_location(message: &str, location: &Location) -> String {
+    message
+        .strip_prefix(&format!("{location}: "))
+        .unwrap_or(message)
+        .to_string()
+}
@@ -0,0 +1,257 @@
+//! Artifacts selected with `--emit`.
+//!
+//! `--emit` takes a comma-separated list of artifact kinds, each optionally followed by
+//! `=<path>`:
+//!
+//! | Kind      | Phase       | Default file              | Contents                          |
+//! |-----------|-------------|---------------------------|-----------------------------------|
+//! | `ast`     | parse       | `<name>.ast.json`         | JSON dump of the AST              |
+//! | `typed`   | analyze     | `<name>.typed.json`       | JSON dump of the inferred types   |
+//! | `symbols` | analyze     | `<name>.symbols.json`     | JSON dump of the symbol table     |
+//! | `ir`      | codegen     | `<name>.ll`               | LLVM IR                           |
+//! | `wat`     | codegen     | `<name>.wat`              | WebAssembly text format           |
+//! | `wasm`    | codegen     | `<name>.wasm`             | WebAssembly binary                |
+//! | `v`       | codegen     | `<name>.v`                | Rocq translation                  |
+//!
+//! Without a path, an artifact is written to the output directory (`--out-dir`, `out` by
+//! default), named after the source file. The path `-` writes it to stdout, which is also
+//! the default for a source read from stdin without `--out-dir`.
+
+use crate::{failure::Failure, parser::Cli, status};
+use inference::{Location, TypedContext};
+use serde::Serialize;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Kind of artifact written by `--emit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EmitKind {
+    Ast,
+    Typed,
+    Symbols,
+    Ir,
+    Wat,
+    Wasm,
+    V,
+}
+
+impl EmitKind {
+    /// All kinds, in the order of the phases producing them.
+    pub(crate) const ALL: [EmitKind; 7] = [
+        EmitKind::Ast,
+        EmitKind::Typed,
+        EmitKind::Symbols,
+        EmitKind::Ir,
+        EmitKind::Wat,
+        EmitKind::Wasm,
+        EmitKind::V,
+    ];
+
+    /// Returns the name of the kind on the command line.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            EmitKind::Ast => "ast",
+            EmitKind::Typed => "typed",
+            EmitKind::Symbols => "symbols",
+            EmitKind::Ir => "ir",
+            EmitKind::Wat => "wat",
+            EmitKind::Wasm => "wasm",
+            EmitKind::V => "v",
+        }
+    }
+
+    /// Returns the extension of the file the artifact is written to.
+    fn extension(self) -> &'static str {
+        match self {
+            EmitKind::Ast => "ast.json",
+            EmitKind::Typed => "typed.json",
+            EmitKind::Symbols => "symbols.json",
+            EmitKind::Ir => "ll",
+            EmitKind::Wat => "wat",
+            EmitKind::Wasm => "wasm",
+            EmitKind::V => "v",
+        }
+    }
+
+    /// Returns the name of the file the artifact of the source `stem` is written to.
+    pub(crate) fn file_name(self, stem: &str) -> String {
+        format!("{stem}.{}", self.extension())
+    }
+
+    /// Returns the name of the artifact in progress messages.
+    fn label(self) -> &'static str {
+        match self {
+            EmitKind::Ast => "AST",
+            EmitKind::Typed => "Typed AST",
+            EmitKind::Symbols => "Symbol table",
+            EmitKind::Ir => "LLVM IR",
+            EmitKind::Wat => "WAT",
+            EmitKind::Wasm => "WASM",
+            EmitKind::V => "V",
+        }
+    }
+
+    /// Returns the phase flags of which one must be given to produce the artifact.
+    pub(crate) fn required_phases(self) -> &'static str {
+        match self {
+            EmitKind::Ast => "--parse, --analyze or --codegen",
+            EmitKind::Typed | EmitKind::Symbols => "--analyze or --codegen",
+            EmitKind::Ir | EmitKind::Wat | EmitKind::Wasm | EmitKind::V => "--codegen",
+        }
+    }
+
+    /// Returns true if the phases requested by `args` produce the artifact.
+    pub(crate) fn is_produced(self, args: &Cli) -> bool {
+        match self {
+            EmitKind::Ast => args.parse || args.analyze || args.codegen,
+            EmitKind::Typed | EmitKind::Symbols => args.analyze || args.codegen,
+            EmitKind::Ir | EmitKind::Wat | EmitKind::Wasm | EmitKind::V => args.codegen,
+        }
+    }
+}
+
+/// One `--emit` entry: an artifact kind and where to write it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Emit {
+    pub(crate) kind: EmitKind,
+    /// Explicit destination; `-` is stdout.
+    pub(crate) path: Option<PathBuf>,
+}
+
+/// Where an artifact is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Destination {
+    Stdout,
+    File(PathBuf),
+}
+
+impl Emit {
+    /// Returns where the artifact is written for the invocation `args`.
+    pub(crate) fn destination(&self, args: &Cli) -> Destination {
+        match &self.path {
+            Some(path) if path.as_os_str() == "-" => Destination::Stdout,
+            Some(path) => Destination::File(path.clone()),
+            None if args.reads_stdin() && args.out_dir.is_none() => Destination::Stdout,
+            None => Destination::File(
+                args.output_dir()
+                    .join(self.kind.file_name(&args.output_name())),
+            ),
+        }
+    }
+}
+
+/// Writes the artifact of `kind` to each destination requested for it.
+///
+/// Fails with [`Failure::Io`] after reporting the error if a destination cannot be
+/// written.
+pub(crate) fn write(args: &Cli, kind: EmitKind, contents: &[u8]) -> Result<(), Failure> {
+    for emit in args.emits().iter().filter(|emit| emit.kind == kind) {
+        match emit.destination(args) {
+            Destination::Stdout => {
+                let mut stdout = io::stdout().lock();
+                if let Err(e) = stdout.write_all(contents).and_then(|()| stdout.flush()) {
+                    eprintln!("Failed to write {} to stdout: {e}", kind.label());
+                    return Err(Failure::Io);
+                }
+            }
+            Destination::File(path) => {
+                if let Some(parent) = path.parent()
+                    && let Err(e) = fs::create_dir_all(parent)
+                {
+                    eprintln!("Failed to create output directory: {e}");
+                    return Err(Failure::Io);
+                }
+                if let Err(e) = fs::write(&path, contents) {
+                    eprintln!("Failed to write {} file: {e}", kind.label());
+                    return Err(Failure::Io);
+                }
+                status(
+                    args.stdout_reserved(),
+                    &format!("{} generated at: {}", kind.label(), path.to_string_lossy()),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A node of the typed AST with its inferred type.
+#[derive(Serialize)]
+struct TypedNode {
+    id: u32,
+    location: Location,
+    #[serde(rename = "type")]
+    type_info: String,
+}
+
+/// Returns the JSON dump of the inferred types: every typed node with its ID, location
+/// and type. Node IDs match those of the `ast` dump.
+pub(crate) fn typed_json(typed_context: &TypedContext) -> serde_json::Result<String> {
+    #[derive(Serialize)]
+    struct TypedAst {
+        nodes: Vec<TypedNode>,
+    }
+    let nodes = typed_context
+        .typed_nodes()
+        .into_iter()
+        .map(|(node, type_info)| TypedNode {
+            id: node.id(),
+            location: node.location(),
+            type_info: type_info.to_string(),
+        })
+        .collect();
+    serde_json::to_string_pretty(&TypedAst { nodes })
+}
+
+/// A symbol of the symbol table dump.
+#[derive(Serialize)]
+struct SymbolEntry {
+    name: String,
+    kind: &'static str,
+    #[serde(rename = "type")]
+    description: String,
+    public: bool,
+    location: Option<Location>,
+}
+
+/// A scope of the symbol table dump.
+#[derive(Serialize)]
+struct ScopeEntry {
+    id: u32,
+    parent: Option<u32>,
+    path: String,
+    symbols: Vec<SymbolEntry>,
+}
+
+/// Returns the JSON dump of the symbol table: every scope with its parent and the
+/// symbols declared in it, without the built-in types.
+pub(crate) fn symbols_json(typed_context: &TypedContext) -> serde_json::Result<String> {
+    #[derive(Serialize)]
+    struct SymbolTable {
+        scopes: Vec<ScopeEntry>,
+    }
+    let scopes = typed_context
+        .scopes()
+        .into_iter()
+        .map(|scope| ScopeEntry {
+            id: scope.id,
+            parent: scope.parent,
+            path: scope.path,
+            symbols: scope
+                .symbols
+                .into_iter()
+                .map(|symbol| SymbolEntry {
+                    name: symbol.name,
+                    kind: symbol.kind.as_str(),
+                    description: symbol.description,
+                    public: symbol.public,
+                    location: symbol.location,
+                })
+                .collect(),
+        })
+        .collect();
+    serde_json::to_string_pretty(&SymbolTable { scopes })
+}
@@ -0,0 +1,38 @@
+//! Exit codes of infc.
+//!
+//! Every failure exits with the code of its kind, so scripts and `infs` can tell a
+//! rejected program from a broken invocation without parsing stderr:
+//!
+//! | Code | Failure                                                          |
+//! |------|------------------------------------------------------------------|
+//! | 0    | None, all requested phases completed                             |
+//! | 1    | Internal error, such as a generated module failing validation    |
+//! | 2    | Usage error: invalid arguments or combination of flags           |
+//! | 3    | IO error: a source cannot be read or an output cannot be written |
+//! | 4    | Parse failure: syntax errors                                     |
+//! | 5    | Type errors                                                      |
+//! | 6    | Analysis failure, including warnings denied with `--deny`        |
+//! | 7    | Codegen failure                                                  |
+//!
+//! Usage errors detected by clap, such as an unknown flag, also exit with code 2.
+
+use std::process;
+
+/// Kind of failure of an invocation, which selects the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Failure {
+    Internal = 1,
+    Usage = 2,
+    Io = 3,
+    Parse = 4,
+    TypeCheck = 5,
+    Analysis = 6,
+    Codegen = 7,
+}
+
+impl Failure {
+    /// Exits the process with the code of the failure.
+    pub(crate) fn exit(self) -> ! {
+        process::exit(self as i32)
+    }
+}
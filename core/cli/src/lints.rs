@@ -0,0 +1,91 @@
+//! Warning levels selected with `-W`, `-A` and `-D`.
+//!
+//! Each flag names a warning kind, by its name such as `unused_variable` or its code
+//! such as `W0003`, or all of them with `warnings`:
+//!
+//! - `-A <lint>` / `--allow <lint>`: do not report the warning
+//! - `-W <lint>` / `--warn <lint>`: report it as a warning, the default
+//! - `-D <lint>` / `--deny <lint>`: report it as an error and fail the compilation
+//!
+//! Flags apply in the order they appear on the command line, so later flags override
+//! earlier ones: `-D warnings -A unused_variable` denies every warning except unused
+//! variables.
+
+use clap::ArgMatches;
+use inference::warning_kinds;
+
+/// Level a warning is reported at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// Name of the group of all warnings.
+const ALL_WARNINGS: &str = "warnings";
+
+/// Levels selected on the command line, in command line order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LintLevels {
+    rules: Vec<(Level, String)>,
+}
+
+impl LintLevels {
+    /// Collects the `--allow`, `--warn` and `--deny` flags of `matches` in the order they
+    /// were given.
+    pub(crate) fn from_matches(matches: &ArgMatches) -> Self {
+        let mut rules = Vec::new();
+        for (id, level) in [
+            ("allow", Level::Allow),
+            ("warn", Level::Warn),
+            ("deny", Level::Deny),
+        ] {
+            if let (Some(indices), Some(lints)) =
+                (matches.indices_of(id), matches.get_many::<String>(id))
+            {
+                rules.extend(indices.zip(lints).map(|(index, lint)| (index, level, lint)));
+            }
+        }
+        rules.sort_by_key(|(index, _, _)| *index);
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|(_, level, lint)| (level, lint.clone()))
+                .collect(),
+        }
+    }
+
+    /// Inserts `rules` before those of the command line, which therefore override them.
+    pub(crate) fn set_defaults(&mut self, rules: Vec<(Level, String)>) {
+        self.rules.splice(0..0, rules);
+    }
+
+    /// Returns the level of the warning kind `name`: that of the last flag naming it or
+    /// `warnings`, or [`Level::Warn`] if none does.
+    pub(crate) fn level(&self, name: &str) -> Level {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(_, lint)| lint == ALL_WARNINGS || lint == name)
+            .map_or(Level::Warn, |(level, _)| *level)
+    }
+}
+
+/// Parses the lint of a warning flag: `warnings`, or the name or code of a warning kind,
+/// which is returned by name. Dashes in names are read as underscores.
+pub(crate) fn parse_lint(value: &str) -> Result<String, String> {
+    let lint = value.replace('-', "_");
+    if lint == ALL_WARNINGS {
+        return Ok(lint);
+    }
+    warning_kinds()
+        .find(|(code, name)| lint == *name || lint.eq_ignore_ascii_case(code))
+        .map(|(_, name)| name.to_string())
+        .ok_or_else(|| {
+            format!(
+                "unknown warning `{value}`; expected `warnings`, a warning name such as \
+                 `unused_variable`, or a code such as `W0003`"
+            )
+        })
+}
@@ -29,6 +29,8 @@
 //!    - Generates LLVM IR from typed AST
 //!    - Compiles LLVM IR to WebAssembly
 //!    - Supports non-deterministic instructions (uzumaki, forall, exists)
+//!    - Validates the module, non-deterministic instructions included, before any
+//!      output is written; an invalid module is an internal error (exit code 1)
 //!    - Optionally translates to Rocq (.v) format for formal verification
 //!
 //! ## Phase Execution
@@ -44,31 +46,53 @@
 //!
 //! ## Output Artifacts
 //!
-//! All output files are written to an `out/` directory relative to the current
-//! working directory:
+//! `--emit` selects the artifacts to write, see the `emit` module:
 //!
-//! - `out/<source_name>.wasm` – WebAssembly binary (when `-o` is specified)
-//! - `out/<source_name>.v` – Rocq translation (when `-v` is specified)
+//! - `ast` – JSON dump of the AST (`<source_name>.ast.json`)
+//! - `typed` – JSON dump of the inferred types (`<source_name>.typed.json`)
+//! - `symbols` – JSON dump of the symbol table (`<source_name>.symbols.json`)
+//! - `ir` – LLVM IR (`<source_name>.ll`)
+//! - `wat` – WebAssembly text format (`<source_name>.wat`)
+//! - `wasm` – WebAssembly binary (`<source_name>.wasm`)
+//! - `v` – Rocq translation (`<source_name>.v`)
 //!
-//! The output directory is created automatically if it doesn't exist.
+//! Files are written to `--out-dir`, `out/` relative to the current working directory
+//! by default, which is created automatically if it doesn't exist. `--emit=<kind>=<path>`
+//! writes an artifact to another path, and `-` to stdout. `-o` and `-v` remain as
+//! deprecated aliases of `--emit=wasm` and `--emit=v`.
 //!
 //! ## Error Handling
 //!
-//! The compiler reports errors to stderr with descriptive messages:
+//! The compiler reports errors to stderr, each with its code if it has one and a
+//! snippet of the source with the offending span underlined, colored as selected by
+//! `--color` and `NO_COLOR`:
 //!
 //! - **Parse errors**: Syntax errors, malformed AST nodes
 //! - **Type errors**: Type mismatches, undefined symbols
 //! - **Codegen errors**: LLVM compilation failures
 //! - **IO errors**: File not found, permission issues
 //!
-//! All errors cause the process to exit with code 1.
+//! Each kind of failure exits with its own code, see below. `--explain <code>` prints
+//! the extended explanation of a code, with an example and typical fixes.
+//!
+//! With `--message-format=json`, errors and warnings are instead printed to stdout as
+//! one JSON object per line, with their phase, severity, code, message and source
+//! spans; see the `diagnostics` module for the format.
 //!
 //! ## Exit Codes
 //!
-//! | Code | Meaning                                    |
-//! |------|--------------------------------------------|
-//! | 0    | Success - all requested phases completed   |
-//! | 1    | Failure - usage, IO, or compilation error  |
+//! | Code | Meaning                                                 |
+//! |------|---------------------------------------------------------|
+//! | 0    | Success - all requested phases completed                |
+//! | 1    | Internal error                                          |
+//! | 2    | Usage error - invalid arguments or combination of flags |
+//! | 3    | IO error - source not readable, output not writable     |
+//! | 4    | Parse failure                                           |
+//! | 5    | Type errors                                             |
+//! | 6    | Analysis failure, including denied warnings             |
+//! | 7    | Codegen failure                                         |
+//!
+//! See the `failure` module.
 //!
 //! ## Examples
 //!
@@ -84,17 +108,53 @@
 //!
 //! Full compilation to WebAssembly:
 //! ```bash
-//! infc example.inf --codegen -o
+//! infc example.inf --codegen --emit=wasm
 //! ```
 //!
 //! Compile and generate Rocq translation:
 //! ```bash
-//! infc example.inf --codegen -o -v
+//! infc example.inf --codegen --emit=wasm,v
+//! ```
+//!
+//! Dump the AST and inferred types for tooling:
+//! ```bash
+//! infc example.inf --analyze --emit=ast,typed --out-dir build
+//! ```
+//!
+//! Unoptimized build with debug information:
+//! ```bash
+//! infc example.inf --codegen --emit=wasm -O 0 -g
+//! ```
+//!
+//! Compile from stdin and write the WASM binary to stdout:
+//! ```bash
+//! infc - --codegen --emit=wasm < example.inf > example.wasm
+//! ```
+//!
+//! Compile a project directory, all `.inf` files under `src/`, into one module:
+//! ```bash
+//! infc my_project --codegen --emit=wasm
+//! ```
+//!
+//! Treat every warning except unused variables as an error:
+//! ```bash
+//! infc example.inf --analyze -D warnings -A unused_variable
+//! ```
+//!
+//! Keep the usual flags in `infc.toml` next to the source, see the `config` module, and
+//! override them on the command line:
+//! ```bash
+//! infc example.inf --codegen --out-dir dist
 //! ```
 //!
-//! Only generate Rocq (no WASM file):
+//! Type check again on every save:
 //! ```bash
-//! infc example.inf --codegen -v
+//! infc example.inf --analyze --watch
+//! ```
+//!
+//! Explain the error code `E0001`:
+//! ```bash
+//! infc --explain E0001
 //! ```
 //!
 //! ## Relationship to `infs`
@@ -108,8 +168,9 @@
 //!
 //! ## Current Limitations
 //!
-//! - Single-file compilation only (multi-file projects not yet supported)
-//! - Output directory is relative to CWD, not source file location
+//! - A project is compiled as one module; module declarations are not supported yet
+//! - Output directory is relative to CWD, not source file location, unless `--out-dir`
+//!   is given
 //! - Analysis phase is work-in-progress
 //!
 //! ## Tests
@@ -122,21 +183,37 @@
 //!
 //! See `README.md` in this crate for comprehensive usage documentation.
 
+mod config;
+mod diagnostics;
+mod emit;
+mod failure;
+mod lints;
 mod parser;
-use clap::Parser;
-use inference::{analyze, codegen, parse, type_check, wasm_to_v};
+mod timings;
+mod watch;
+use diagnostics::{Diagnostic, Phase, Reporter};
+use emit::{Destination, EmitKind};
+use failure::Failure;
+use inference::{
+    AstError, CodegenErrors, ExternPrelude, InvalidModule, SyntaxErrors, TypeCheckErrors, analyze,
+    codegen, llvm_ir, load_dependencies, parse, parse_project, project_files,
+    type_check_with_prelude, wasm_to_v,
+};
+use lints::Level;
 use parser::Cli;
 use std::{
-    fs,
-    path::PathBuf,
+    fs, io,
+    path::{Path, PathBuf},
     process::{self},
 };
+use timings::Timings;
 
 /// Entry point for the Inference compiler CLI.
 ///
 /// ## Execution Flow
 ///
-/// 1. **Parse command line arguments** using clap
+/// 1. **Parse command line arguments** using clap; with `--explain`, print the
+///    explanation of the code and exit
 /// 2. **Validate input**:
 ///    - Verify source file exists
 ///    - Ensure at least one phase flag is specified
@@ -144,14 +221,15 @@ use std::{
 ///    - Parse: Build typed AST from source using tree-sitter
 ///    - Analyze: Type check and semantic validation
 ///    - Codegen: Generate LLVM IR and compile to WebAssembly
-/// 4. **Generate output files** (if requested):
-///    - Write WASM binary with `-o` flag
-///    - Write Rocq translation with `-v` flag
+/// 4. **Generate output files** (if requested with `--emit`):
+///    - Write each artifact after the phase producing it
+/// 5. **Watch** (with `--watch`): run the phases again whenever the source file
+///    changes, until interrupted
 ///
 /// ## Error Handling
 ///
 /// All errors are reported to stderr with descriptive messages and cause
-/// process exit with code 1. Error categories:
+/// process exit with the code of their category, see [`Failure`]. Error categories:
 ///
 /// - **Usage errors**: Missing phase flags, invalid arguments
 /// - **IO errors**: File not found, permission denied, output write failures
@@ -169,132 +247,416 @@ use std::{
 ///
 /// ## Output Management
 ///
-/// Output files are written to `out/` directory relative to CWD:
+/// Output files are written to `--out-dir` (`out/` relative to CWD by default):
 /// - Directory is created if it doesn't exist
 /// - File names are derived from source file stem
-/// - Both `-o` and `-v` flags can be used simultaneously
+/// - Several artifacts can be emitted at once
 ///
 /// ## Implementation Notes
 ///
 /// - Uses `anyhow::Result` for error propagation from library functions
-/// - Calls `process::exit(1)` explicitly on errors (no panics)
+/// - Calls [`Failure::exit`] explicitly on errors (no panics)
 /// - Reads entire source file into memory (limitation: no streaming)
 /// - Phase execution is sequential (no parallelization)
-#[allow(clippy::too_many_lines)]
 fn main() {
-    let args = Cli::parse();
-    if !args.path.exists() {
+    let args = Cli::parse_args();
+    if let Some(code) = &args.explain {
+        explain(code);
+    }
+    if !args.reads_stdin() && !args.path.exists() {
         eprintln!("Error: path not found");
-        process::exit(1);
+        Failure::Io.exit();
+    }
+
+    if !(args.parse || args.analyze || args.codegen) {
+        eprintln!("Error: at least one of --parse, --analyze, or --codegen must be specified");
+        Failure::Usage.exit();
+    }
+
+    if let Some(emit) = args.emit.iter().find(|emit| !emit.kind.is_produced(&args)) {
+        eprintln!(
+            "Error: --emit={} requires {}",
+            emit.kind.name(),
+            emit.kind.required_phases()
+        );
+        Failure::Usage.exit();
+    }
+
+    let stdout_emits = args
+        .emits()
+        .iter()
+        .filter(|emit| emit.destination(&args) == Destination::Stdout)
+        .count();
+    if stdout_emits > 1 {
+        eprintln!("Error: only one --emit artifact can be written to stdout");
+        Failure::Usage.exit();
+    }
+
+    if stdout_emits == 1 && args.message_format == "json" {
+        eprintln!(
+            "Error: --emit writes the artifact to stdout and cannot be combined with --message-format=json"
+        );
+        Failure::Usage.exit();
+    }
+
+    if args.watch && args.reads_stdin() {
+        eprintln!("Error: --watch needs a source file and cannot read from stdin");
+        Failure::Usage.exit();
+    }
+
+    if args.watch {
+        if let Err(e) = watch::run(&args) {
+            eprintln!("Watch failed: {e}");
+            Failure::Io.exit();
+        }
+        process::exit(0);
+    }
+    if let Err(failure) = compile(&args) {
+        failure.exit();
+    }
+    process::exit(0);
+}
+
+/// Prints the extended explanation of the diagnostic `code` for `--explain` and exits.
+///
+/// Exits with a usage error if no error or warning has the code.
+fn explain(code: &str) -> ! {
+    let Some(explanation) = inference::explain(code) else {
+        eprintln!(
+            "Error: no explanation for `{code}`; expected an error code such as `E0001` or a \
+             warning code such as `W0003`"
+        );
+        Failure::Usage.exit();
+    };
+    print!("{explanation}");
+    process::exit(0);
+}
+
+/// Runs the requested phases over the source file and writes the requested outputs,
+/// followed by the `--timings` report if requested.
+///
+/// Returns the kind of failure once a phase fails, after its errors have been reported.
+fn compile(args: &Cli) -> Result<(), Failure> {
+    let mut timings = Timings::new();
+    let result = run_phases(args, &mut timings);
+    if let Some(format) = &args.timings {
+        timings.report(format == "json", args.stdout_reserved());
     }
+    result
+}
 
-    let output_path = PathBuf::from("out");
+/// Runs the requested phases and writes the requested outputs, recording the time of
+/// each phase in `timings`.
+///
+/// Returns the kind of failure once a phase fails, after its errors have been reported.
+#[allow(clippy::too_many_lines)]
+fn run_phases(args: &Cli, timings: &mut Timings) -> Result<(), Failure> {
+    let mut reporter = Reporter::new(args.message_format == "json", args.colors_diagnostics());
+    let quiet = args.stdout_reserved();
+    let path = args.display_path();
     let need_parse = args.parse;
     let need_analyze = args.analyze;
     let need_codegen = args.codegen;
 
-    if !(need_parse || need_analyze || need_codegen) {
-        eprintln!("Error: at least one of --parse, --analyze, or --codegen must be specified");
-        process::exit(1);
-    }
-
-    let source_code = match fs::read_to_string(&args.path) {
-        Ok(content) => content,
-        Err(e) => {
-            eprintln!("Error reading source file: {e}");
-            process::exit(1);
+    let files = match args.project_dir() {
+        Some(_) => match project_files(&args.path) {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("Error reading project: {e}");
+                return Err(Failure::Io);
+            }
+        },
+        None => Vec::new(),
+    };
+    let parsed = if files.is_empty() {
+        match read_source(args) {
+            Ok(source_code) => {
+                let parsed = timings.time(Phase::Parse, || parse(source_code.as_str()));
+                reporter.set_source(path, source_code);
+                parsed
+            }
+            Err(e) => {
+                eprintln!("Error reading source file: {e}");
+                return Err(Failure::Io);
+            }
+        }
+    } else {
+        for file in &files {
+            match fs::read_to_string(file) {
+                Ok(source_code) => reporter.set_source(file, source_code),
+                Err(e) => {
+                    eprintln!("Error reading source file {}: {e}", file.display());
+                    return Err(Failure::Io);
+                }
+            }
         }
+        timings.time(Phase::Parse, || parse_project(&files))
     };
     let mut t_ast = None;
     if need_codegen || need_analyze || need_parse {
-        match parse(source_code.as_str()) {
+        match parsed {
             Ok(ast) => {
-                println!("Parsed: {}", args.path.display());
+                status(quiet, &format!("Parsed: {}", path.display()));
                 t_ast = Some(ast);
             }
             Err(e) => {
-                eprintln!("Parse error: {e}");
-                process::exit(1);
+                report_parse_errors(&reporter, path, &e);
+                return Err(Failure::Parse);
             }
         }
     }
 
     let Some(arena) = t_ast else {
         eprintln!("Internal error: parse phase did not produce AST");
-        process::exit(1);
+        return Err(Failure::Internal);
     };
+    timings.count(&arena);
+    if args.emits_kind(EmitKind::Ast) {
+        match serde_json::to_string_pretty(&arena.source_files()) {
+            Ok(ast) => {
+                emit::write(args, EmitKind::Ast, ast.as_bytes())?;
+            }
+            Err(e) => {
+                eprintln!("Failed to serialize AST: {e}");
+                return Err(Failure::Internal);
+            }
+        }
+    }
 
     let mut typed_context = None;
 
     if need_codegen || need_analyze {
-        match type_check(arena) {
+        let prelude = load_externs(args, &reporter)?;
+        match timings.time(Phase::TypeCheck, || type_check_with_prelude(arena, prelude)) {
             Err(e) => {
-                eprintln!("Type checking failed: {e}");
-                process::exit(1);
+                report_type_check_errors(&reporter, path, &files, &e);
+                return Err(Failure::TypeCheck);
             }
             Ok(tctx) => {
+                let mut denied = 0;
+                for warning in tctx.warnings() {
+                    let diagnostic = Diagnostic::type_check_warning(path, warning);
+                    denied +=
+                        usize::from(report_warning(args, &reporter, warning.name(), diagnostic));
+                }
                 typed_context = Some(tctx);
-                if let Err(e) = analyze(typed_context.as_ref().unwrap()) {
-                    eprintln!("Analysis failed: {e}");
-                    process::exit(1);
+                match timings.time(Phase::Analyze, || analyze(typed_context.as_ref().unwrap())) {
+                    Ok(warnings) => {
+                        for warning in warnings {
+                            let diagnostic = Diagnostic::analysis_warning(path, &warning);
+                            denied += usize::from(report_warning(
+                                args,
+                                &reporter,
+                                warning.name(),
+                                diagnostic,
+                            ));
+                        }
+                    }
+                    Err(e) => {
+                        reporter.report(&Diagnostic::error(Phase::Analyze, path, e.to_string()));
+                        return Err(Failure::Analysis);
+                    }
                 }
-                println!("Analyzed: {}", args.path.display());
+                if denied > 0 {
+                    eprintln!(
+                        "Analysis failed: {denied} denied warning{}",
+                        if denied == 1 { "" } else { "s" }
+                    );
+                    return Err(Failure::Analysis);
+                }
+                status(quiet, &format!("Analyzed: {}", path.display()));
+            }
+        }
+    }
+    if let Some(tctx) = &typed_context
+        && args.emits_kind(EmitKind::Typed)
+    {
+        match emit::typed_json(tctx) {
+            Ok(typed) => {
+                emit::write(args, EmitKind::Typed, typed.as_bytes())?;
+            }
+            Err(e) => {
+                eprintln!("Failed to serialize typed AST: {e}");
+                return Err(Failure::Internal);
+            }
+        }
+    }
+    if let Some(tctx) = &typed_context
+        && args.emits_kind(EmitKind::Symbols)
+    {
+        match emit::symbols_json(tctx) {
+            Ok(symbols) => {
+                emit::write(args, EmitKind::Symbols, symbols.as_bytes())?;
+            }
+            Err(e) => {
+                eprintln!("Failed to serialize symbol table: {e}");
+                return Err(Failure::Internal);
             }
         }
     }
     if need_codegen {
         let Some(tctx) = typed_context else {
             eprintln!("Internal error: type check phase did not produce typed context");
-            process::exit(1);
+            return Err(Failure::Internal);
         };
-        let wasm = match codegen(&tctx) {
+        let mut options = args.codegen_options();
+        if let [file] = files.as_slice() {
+            options.source_path = Some(file.clone());
+        }
+        let wasm = match timings.time(Phase::Codegen, || codegen(&tctx, &options)) {
             Ok(w) => w,
             Err(e) => {
-                eprintln!("Codegen failed: {e}");
-                process::exit(1);
+                return Err(report_codegen_errors(&reporter, path, &e));
             }
         };
-        println!("WASM generated");
-        let source_fname = args
-            .path
-            .file_stem()
-            .unwrap_or_else(|| std::ffi::OsStr::new("module"))
-            .to_str()
-            .unwrap();
-        if args.generate_wasm_output {
-            let wasm_file_path = output_path.join(format!("{source_fname}.wasm"));
-            if let Err(e) = fs::create_dir_all(&output_path) {
-                eprintln!("Failed to create output directory: {e}");
-                process::exit(1);
+        status(quiet, "WASM generated");
+        if args.emits_kind(EmitKind::Ir) {
+            match timings.time(Phase::Codegen, || llvm_ir(&tctx, &options)) {
+                Ok(ir) => {
+                    emit::write(args, EmitKind::Ir, ir.as_bytes())?;
+                }
+                Err(e) => {
+                    return Err(report_codegen_errors(&reporter, path, &e));
+                }
             }
-            if let Err(e) = fs::write(&wasm_file_path, &wasm) {
-                eprintln!("Failed to write WASM file: {e}");
-                process::exit(1);
+        }
+        if args.emits_kind(EmitKind::Wat) {
+            match wasmprinter::print_bytes(&wasm) {
+                Ok(wat) => {
+                    emit::write(args, EmitKind::Wat, wat.as_bytes())?;
+                }
+                Err(e) => {
+                    eprintln!("WASM->WAT printing failed: {e}");
+                    return Err(Failure::Codegen);
+                }
             }
-            println!("WASM generated at: {}", wasm_file_path.to_string_lossy());
         }
-        if args.generate_v_output {
-            match wasm_to_v(source_fname, &wasm) {
+        emit::write(args, EmitKind::Wasm, &wasm)?;
+        if args.emits_kind(EmitKind::V) {
+            match wasm_to_v(&args.output_name(), &wasm) {
                 Ok(v_output) => {
-                    let v_file_path = output_path.join(format!("{source_fname}.v"));
-                    if let Err(e) = fs::create_dir_all(&output_path) {
-                        eprintln!("Failed to create output directory: {e}");
-                        process::exit(1);
-                    }
-                    if let Err(e) = fs::write(&v_file_path, v_output) {
-                        eprintln!("Failed to write V file: {e}");
-                        process::exit(1);
-                    }
-                    println!("V generated at: {}", v_file_path.to_string_lossy());
+                    emit::write(args, EmitKind::V, v_output.as_bytes())?;
                 }
                 Err(e) => {
                     eprintln!("WASM->V translation failed: {e}");
-                    process::exit(1);
+                    return Err(Failure::Codegen);
                 }
             }
         }
     }
-    process::exit(0);
+    Ok(())
+}
+
+/// Reads the source file, or stdin if the path is `-`.
+fn read_source(args: &Cli) -> io::Result<String> {
+    if args.reads_stdin() {
+        io::read_to_string(io::stdin())
+    } else {
+        fs::read_to_string(&args.path)
+    }
+}
+
+/// Prints a progress message to stdout, or to stderr if stdout is reserved for JSON
+/// diagnostics or an emitted artifact.
+fn status(quiet: bool, message: &str) {
+    if quiet {
+        eprintln!("{message}");
+    } else {
+        println!("{message}");
+    }
+}
+
+/// Reports a warning of kind `name` at the level selected for it with `--allow`, `--warn`
+/// and `--deny`. Returns true if the warning is denied.
+fn report_warning(args: &Cli, reporter: &Reporter, name: &str, diagnostic: Diagnostic) -> bool {
+    match args.lint_levels.level(name) {
+        Level::Allow => false,
+        Level::Warn => {
+            reporter.report(&diagnostic);
+            false
+        }
+        Level::Deny => {
+            reporter.report(&diagnostic.deny());
+            true
+        }
+    }
+}
+
+/// Parses the dependencies given with `--extern`, reporting the failure of the first one
+/// that cannot be read or parsed.
+fn load_externs(args: &Cli, reporter: &Reporter) -> Result<ExternPrelude, Failure> {
+    load_dependencies(&args.externs).map_err(|e| {
+        if let Some(AstError::ParseError { path } | AstError::AstBuildError { path, .. }) =
+            e.downcast_ref::<AstError>()
+        {
+            reporter.report(&Diagnostic::error(Phase::Parse, path, e.to_string()));
+            Failure::Parse
+        } else {
+            eprintln!("Error reading dependency: {e}");
+            Failure::Io
+        }
+    })
+}
+
+/// Reports the syntax errors of a failed parse.
+fn report_parse_errors(reporter: &Reporter, path: &Path, error: &anyhow::Error) {
+    match error.downcast_ref::<SyntaxErrors>() {
+        Some(errors) => {
+            for error in errors.errors() {
+                reporter.report(&Diagnostic::syntax_error(path, error));
+            }
+        }
+        None => reporter.report(&Diagnostic::error(Phase::Parse, path, error.to_string())),
+    }
+}
+
+/// Reports the errors of a failed type check, attributed to their source file among
+/// `files` for a project.
+fn report_type_check_errors(
+    reporter: &Reporter,
+    path: &Path,
+    files: &[PathBuf],
+    error: &anyhow::Error,
+) {
+    match error.downcast_ref::<TypeCheckErrors>() {
+        Some(errors) => {
+            for (error, &index) in errors.errors().iter().zip(errors.source_files()) {
+                let file = files.get(index).map_or(path, PathBuf::as_path);
+                reporter.report(&Diagnostic::type_check_error(file, error));
+            }
+        }
+        None => reporter.report(&Diagnostic::error(
+            Phase::TypeCheck,
+            path,
+            error.to_string(),
+        )),
+    }
+}
+
+/// Reports the errors of a failed code generation and returns the kind of the failure:
+/// [`Failure::Internal`] if the generated module is invalid, which is a bug in the code
+/// generator, [`Failure::Codegen`] otherwise.
+fn report_codegen_errors(reporter: &Reporter, path: &Path, error: &anyhow::Error) -> Failure {
+    if let Some(invalid) = error.downcast_ref::<InvalidModule>() {
+        reporter.report(&Diagnostic::error(
+            Phase::Codegen,
+            path,
+            format!(
+                "internal compiler error: {invalid}; the module was not written, please report \
+                 this bug"
+            ),
+        ));
+        return Failure::Internal;
+    }
+    match error.downcast_ref::<CodegenErrors>() {
+        Some(errors) => {
+            for error in errors.errors() {
+                reporter.report(&Diagnostic::codegen_error(path, error));
+            }
+        }
+        None => reporter.report(&Diagnostic::error(Phase::Codegen, path, error.to_string())),
+    }
+    Failure::Codegen
 }
 
 /// Unit test helpers for the CLI module.
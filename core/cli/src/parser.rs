@@ -5,7 +5,21 @@
 //!
 //! For comprehensive usage documentation, see `README.md` in this crate.
 
-use clap::Parser;
+use crate::{
+    config,
+    emit::{Destination, Emit, EmitKind},
+    failure::Failure,
+    lints::{LintLevels, parse_lint},
+};
+use clap::{CommandFactory, FromArgMatches, Parser};
+use inference::{
+    AssertStrategy, Backend, BoundsChecks, CodegenOptions, Exports, ImportName, Linker,
+    MANIFEST_FILE, StackChecks, TargetFeatures,
+};
+use std::{
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+};
 
 /// Command line interface definition for the Inference compiler.
 ///
@@ -21,10 +35,77 @@ use clap::Parser;
 ///
 /// ## Output Flags
 ///
-/// - `-o`: Generate WASM binary file in `out/` directory
-/// - `-v`: Generate Rocq (.v) translation in `out/` directory
+/// - `--emit <kind>[=<path>],...`: Write the `ast`, `typed`, `symbols`, `ir`, `wat`,
+///   `wasm` or `v` artifact, to `--out-dir` or to `<path>` (`-` for stdout)
+/// - `--out-dir <dir>`: Directory of the artifacts (default `out`)
 ///
-/// Output flags only take effect when `--codegen` is specified.
+/// Each artifact requires the phase producing it. `-o` and `-v` remain as deprecated
+/// aliases of `--emit=wasm` and `--emit=v`.
+///
+/// ## Projects
+///
+/// Passing a project directory or its `Inference.toml` as the path compiles all `.inf`
+/// files under `src/` as one module, e.g. `infc my_project --codegen --emit=wasm`.
+///
+/// - `--extern <name>=<path>`: Make the public definitions of the module in `path`
+///   available to `use <name>::<item>;`
+///
+/// ## Configuration File
+///
+/// `infc.toml` next to the source file or in the project, or the `[infc]` table of the
+/// project manifest, sets the defaults of `--emit`, `--out-dir`, `--opt-level` and the
+/// warning levels; flags given on the command line override it.
+///
+/// - `--config <file>`: Read the defaults from another file
+/// - `--no-config`: Ignore the configuration file
+///
+/// ## Standard Input
+///
+/// Passing `-` as the path reads the source from stdin, e.g.
+/// `infc - --codegen --emit=wasm < example.inf > example.wasm`.
+///
+/// ## Watch Mode
+///
+/// - `--watch`: Keep running and compile again whenever the source file changes
+///
+/// ## Codegen Flags
+///
+/// - `--backend <llvm|encoder>`: Generate code with LLVM or with the wasm-encoder backend
+/// - `-O <level>`: LLVM optimization level (0-3, default 3)
+/// - `--module-name <name>`: Name of the LLVM module
+/// - `-g`: Keep debug information in the WASM binary
+/// - `--source-map`: Add a custom section mapping code offsets to source positions
+/// - `--feature <name>`: Enable an extra WebAssembly target feature
+/// - `--linker <rust-lld|builtin>`: Link with rust-lld or the in-process linker
+/// - `--bounds-checks <trap|unchecked>`: Trap on out-of-bounds array indices, or skip the check
+/// - `--assert <trap|abort[=<module>.<name>]|result-code[=<code>]>`: How a failing `assert` stops
+/// - `--import-module <name>`: Import module of `external fn` declarations
+/// - `--import <function>=<module>.<name>`: Import one `external fn` under another name
+/// - `--export <name>`: Export only the listed functions and constants instead of every
+///   `pub fn` and `pub const`
+/// - `--export-name <name>=<export>`: Export a function or constant under another name
+/// - `--start <function>`: Run a function when the module is instantiated
+/// - `--codegen-units <n>`: Compile the functions in `n` units on parallel threads
+/// - `--cache-dir <dir>`: Reuse the object code of unchanged functions from `dir`
+/// - `--memory64`: Target `wasm64` with a 64-bit linear memory
+/// - `--initial-memory-pages <n>`: Initial size of the linear memory in pages
+/// - `--max-memory-pages <n>`: Maximum size of the linear memory in pages
+/// - `--stack-size <bytes>`: Size of the stack (default 65536)
+/// - `--stack-checks <trap|unchecked>`: Trap when the stack overflows, or skip the check
+///
+/// ## Diagnostic Flags
+///
+/// - `--explain <code>`: Print the explanation of an error or warning code, such as
+///   `E0001`, with an example and typical fixes, and exit
+/// - `--message-format <human|json>`: Print errors and warnings as text on stderr, or as
+///   one JSON object per line on stdout
+/// - `--color <auto|always|never>`: Color the errors and warnings printed as text; `auto`
+///   colors them on a terminal unless `NO_COLOR` is set
+/// - `-A`, `-W`, `-D <lint>` (`--allow`, `--warn`, `--deny`): Do not report a warning,
+///   report it, or report it as an error; `<lint>` is a warning name such as
+///   `unused_variable`, its code, or `warnings` for all of them
+/// - `--timings[=json]`: Report the wall time and peak RSS of every phase, and the number
+///   of source files, nodes and functions
 ///
 /// ## Examples
 ///
@@ -35,12 +116,17 @@ use clap::Parser;
 ///
 /// Full compilation with WASM output:
 /// ```bash
-/// infc example.inf --codegen -o
+/// infc example.inf --codegen --emit=wasm
+/// ```
+///
+/// Full compilation with Rocq translation and the AST for tooling:
+/// ```bash
+/// infc example.inf --codegen --emit=ast,wasm,v --out-dir build
 /// ```
 ///
-/// Full compilation with Rocq translation:
+/// Compile from stdin to stdout:
 /// ```bash
-/// infc example.inf --codegen -o -v
+/// infc - --codegen --emit=wasm < example.inf > example.wasm
 /// ```
 #[derive(Parser)]
 #[command(
@@ -48,16 +134,31 @@ use clap::Parser;
     author,
     version,
     about = "Inference compiler CLI (infc)",
-    long_about = "The 'infc' command runs one or more compilation phases over a single .inf source file. \
-Parse builds the typed AST; analyze performs semantic/type inference; codegen emits WASM and can translate to V when -o is supplied."
+    long_about = "The 'infc' command runs one or more compilation phases over a .inf source file, or over all .inf files of a project directory. \
+Parse builds the typed AST; analyze performs semantic/type inference; codegen emits WASM. --emit writes the AST, types, symbol table, LLVM IR, WAT, WASM or V translation."
 )]
 #[allow(clippy::struct_excessive_bools)]
 pub(crate) struct Cli {
-    /// Path to the source file to compile.
+    /// Path to the source file to compile, `-` to read the source from stdin, or a
+    /// project directory or its `Inference.toml`.
+    ///
+    /// A project is compiled as one module from all `.inf` files under its `src/`
+    /// directory, starting with `src/lib.inf` or `src/main.inf`.
+    // The default only fills the field for `--explain`, which needs no path.
+    #[clap(
+        required_unless_present = "explain",
+        default_value = "-",
+        hide_default_value = true
+    )]
+    pub(crate) path: PathBuf,
+
+    /// Print the extended explanation of a diagnostic code, such as `E0001` or `W0003`,
+    /// and exit.
     ///
-    /// Currently only single-file compilation is supported. Multi-file projects
-    /// and project file (`.infp`) support is planned for future releases.
-    pub(crate) path: std::path::PathBuf,
+    /// The explanation describes the error or warning, with an example that reports it
+    /// and how to fix it. No source path is needed.
+    #[clap(long = "explain", value_name = "CODE")]
+    pub(crate) explain: Option<String>,
 
     /// Run the parse phase to build the typed AST.
     ///
@@ -65,7 +166,7 @@ pub(crate) struct Cli {
     /// an arena-allocated typed AST. If parsing succeeds, the compiler prints
     /// "Parsed: <filepath>" and exits with code 0.
     ///
-    /// Parse errors will be reported to stderr and the process exits with code 1.
+    /// Parse errors will be reported to stderr and the process exits with code 4.
     #[clap(long = "parse", action = clap::ArgAction::SetTrue)]
     pub(crate) parse: bool,
 
@@ -74,7 +175,8 @@ pub(crate) struct Cli {
     /// This phase performs type checking and semantic validation on the AST.
     /// The parse phase is automatically run first if not already requested.
     ///
-    /// Analysis errors will be reported to stderr and the process exits with code 1.
+    /// Type errors will be reported to stderr and the process exits with code 5, analysis
+    /// failures with code 6.
     #[clap(long = "analyze", action = clap::ArgAction::SetTrue)]
     pub(crate) analyze: bool,
 
@@ -83,32 +185,555 @@ pub(crate) struct Cli {
     /// This phase generates LLVM IR and compiles it to WebAssembly. Both parse
     /// and analyze phases are automatically run first if not already requested.
     ///
-    /// Use `-o` to write the WASM binary to disk, and `-v` to additionally
-    /// generate a Rocq translation.
+    /// Use `--emit=wasm` to write the WASM binary to disk, and `--emit=v` to
+    /// additionally generate a Rocq translation.
     ///
-    /// Codegen errors will be reported to stderr and the process exits with code 1.
+    /// Codegen errors will be reported to stderr and the process exits with code 7.
     #[clap(long = "codegen", action = clap::ArgAction::SetTrue)]
     pub(crate) codegen: bool,
 
-    /// Generate output WASM binary file.
+    /// Write artifacts of the requested phases.
     ///
-    /// When specified with `--codegen`, writes the compiled WebAssembly binary
-    /// to `out/<source_name>.wasm` relative to the current working directory.
+    /// A comma-separated list of `ast`, `typed`, `symbols`, `ir`, `wat`, `wasm` and `v`, each
+    /// optionally followed by `=<path>`, e.g. `--emit=ast,wasm=build/app.wasm`:
     ///
-    /// This flag has no effect without `--codegen`.
-    #[clap(short = 'o', action = clap::ArgAction::SetTrue)]
+    /// - `ast`: JSON dump of the AST (`--parse`)
+    /// - `typed`: JSON dump of the inferred type of every node (`--analyze`)
+    /// - `symbols`: JSON dump of the symbol table, scope by scope (`--analyze`)
+    /// - `ir`: LLVM IR of the module (`--codegen`)
+    /// - `wat`: WebAssembly text format (`--codegen`)
+    /// - `wasm`: WebAssembly binary (`--codegen`)
+    /// - `v`: Rocq translation (`--codegen`)
+    ///
+    /// Without a path, the artifact is written to `--out-dir`, named after the source file.
+    /// The path `-` writes it to stdout and moves progress messages to stderr; together
+    /// with `-` as the source path, infc can be used in a pipeline without temporary
+    /// files. May be repeated.
+    #[clap(long = "emit", value_delimiter = ',', value_parser = parse_emit)]
+    pub(crate) emit: Vec<Emit>,
+
+    /// Directory of the artifacts written by `--emit` without a path.
+    ///
+    /// Defaults to `out` relative to the current working directory. When the source is
+    /// read from stdin and no directory is given, artifacts are written to stdout.
+    #[clap(long = "out-dir")]
+    pub(crate) out_dir: Option<PathBuf>,
+
+    /// Deprecated alias of `--emit=wasm`, kept for existing scripts.
+    #[clap(short = 'o', hide = true, action = clap::ArgAction::SetTrue)]
     pub(crate) generate_wasm_output: bool,
 
-    /// Generate Rocq (.v) translation file.
+    /// Deprecated alias of `--emit=v`, kept for existing scripts.
+    #[clap(short = 'v', hide = true, action = clap::ArgAction::SetTrue)]
+    pub(crate) generate_v_output: bool,
+
+    /// Watch the source file and run the requested phases again whenever it changes.
     ///
-    /// When specified with `--codegen`, translates the compiled WebAssembly
-    /// to Rocq (Coq) format and writes it to `out/<source_name>.v` relative
-    /// to the current working directory.
+    /// The phases run once at startup and after every change. Each run ends with a
+    /// one-line summary; errors do not stop the watcher. infc keeps running until it is
+    /// interrupted.
+    #[clap(short = 'w', long = "watch", action = clap::ArgAction::SetTrue)]
+    pub(crate) watch: bool,
+
+    /// LLVM optimization level for the codegen phase (0-3).
     ///
-    /// This enables formal verification of the compiled program using the
-    /// Rocq proof assistant.
+    /// Defaults to 3. Lower levels compile faster and keep the generated
+    /// WebAssembly closer to the source, which can simplify the Rocq output.
+    #[clap(
+        short = 'O',
+        long = "opt-level",
+        default_value_t = 3,
+        value_parser = clap::value_parser!(u32).range(0..=3)
+    )]
+    pub(crate) opt_level: u32,
+
+    /// Name of the generated LLVM module.
+    #[clap(long = "module-name", default_value = "wasm_module")]
+    pub(crate) module_name: String,
+
+    /// Emit DWARF debug information and keep it in the generated WebAssembly binary.
+    #[clap(short = 'g', long = "debug-info", action = clap::ArgAction::SetTrue)]
+    pub(crate) debug_info: bool,
+
+    /// Add a source map custom section relating code offsets to `.inf` lines and columns.
+    #[clap(long = "source-map", action = clap::ArgAction::SetTrue)]
+    pub(crate) source_map: bool,
+
+    /// Enable an additional WebAssembly target feature, e.g. `bulk-memory`.
     ///
-    /// This flag has no effect without `--codegen`.
-    #[clap(short = 'v', action = clap::ArgAction::SetTrue)]
-    pub(crate) generate_v_output: bool,
+    /// `bulk-memory`, `sign-ext`, `nontrapping-fptoint`, `simd128` and `tail-call` enable
+    /// the matching proposal; other names are passed to LLVM as is. May be repeated or
+    /// given as a comma-separated list.
+    #[clap(long = "feature", value_delimiter = ',')]
+    pub(crate) features: Vec<String>,
+
+    /// Backend generating the WebAssembly module.
+    ///
+    /// `encoder` lowers the typed AST without LLVM and supports a subset of the language;
+    /// it is only available when infc is built with the `encoder` feature.
+    #[clap(
+        long = "backend",
+        default_value = if cfg!(feature = "llvm") { "llvm" } else { "encoder" },
+        value_parser = ["llvm", "encoder"]
+    )]
+    pub(crate) backend: String,
+
+    /// Linker used to produce the WebAssembly module.
+    ///
+    /// `builtin` links in-process and does not need `rust-lld`.
+    #[clap(
+        long = "linker",
+        default_value = "rust-lld",
+        value_parser = ["rust-lld", "builtin"]
+    )]
+    pub(crate) linker: String,
+
+    /// How array indices are checked against the array length.
+    ///
+    /// `trap` aborts execution on an out-of-bounds index; `unchecked` omits the check.
+    #[clap(
+        long = "bounds-checks",
+        default_value = "trap",
+        value_parser = ["trap", "unchecked"]
+    )]
+    pub(crate) bounds_checks: String,
+
+    /// How a failing `assert` stops execution.
+    ///
+    /// `trap` executes `unreachable`. `abort` calls the host function `env.abort(file, line)`
+    /// and then traps; `abort=<module>.<name>` imports it under another name.
+    /// `result-code` returns -1 from the function containing the assert, and
+    /// `result-code=<code>` returns `<code>`.
+    #[clap(long = "assert", default_value = "trap", value_parser = parse_assert_strategy)]
+    pub(crate) assert_strategy: AssertStrategy,
+
+    /// Import module of `external fn` declarations.
+    ///
+    /// Each `external fn` becomes a WebAssembly import of this module, named after the
+    /// function, unless `--import` names it differently.
+    #[clap(long = "import-module", default_value = "env")]
+    pub(crate) import_module: String,
+
+    /// Import one `external fn` under a specific module and field name.
+    ///
+    /// Given as `<function>=<module>.<name>`, e.g. `--import log=console.log_i32`.
+    /// May be repeated.
+    #[clap(long = "import", value_parser = parse_import)]
+    pub(crate) imports: Vec<(String, ImportName)>,
+
+    /// Export only the given function or constant.
+    ///
+    /// By default every `pub fn` and `pub const` is exported. Once `--export` is given, only
+    /// the listed functions and constants are, whether they are `pub` or not. May be
+    /// repeated.
+    #[clap(long = "export")]
+    pub(crate) exports: Vec<String>,
+
+    /// Export a function or constant under another name.
+    ///
+    /// Given as `<name>=<export>`, e.g. `--export-name add=sum`. Renaming a function or
+    /// constant does not export it on its own. May be repeated.
+    #[clap(long = "export-name", value_parser = parse_export_name)]
+    pub(crate) export_names: Vec<(String, String)>,
+
+    /// Run a function when the module is instantiated.
+    ///
+    /// The function must take no parameters and return nothing.
+    #[clap(long = "start")]
+    pub(crate) start_function: Option<String>,
+
+    /// Number of codegen units compiled on parallel threads.
+    ///
+    /// Functions are split into consecutive groups, each emitted as its own object file.
+    /// More units compile faster on multiple cores but prevent inlining across units.
+    /// Ignored by the builtin linker, which links a single object file.
+    #[clap(
+        long = "codegen-units",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub(crate) codegen_units: u64,
+
+    /// Directory of the object code cache.
+    ///
+    /// Every function is compiled to its own object file and stored under a hash of its
+    /// typed AST; later runs reuse the objects of unchanged functions. Ignored by the
+    /// builtin linker.
+    #[clap(long = "cache-dir")]
+    pub(crate) cache_dir: Option<std::path::PathBuf>,
+
+    /// Target `wasm64-unknown-unknown` with a 64-bit linear memory (memory64).
+    #[clap(long = "memory64", action = clap::ArgAction::SetTrue)]
+    pub(crate) memory64: bool,
+
+    /// Initial size of the linear memory in 64 KiB pages.
+    ///
+    /// Defaults to the size needed for the stack and static data.
+    #[clap(long = "initial-memory-pages")]
+    pub(crate) initial_memory_pages: Option<u64>,
+
+    /// Maximum size of the linear memory in 64 KiB pages.
+    ///
+    /// The memory has no maximum by default.
+    #[clap(long = "max-memory-pages")]
+    pub(crate) max_memory_pages: Option<u64>,
+
+    /// Size of the stack in bytes; must be a multiple of 16.
+    #[clap(long = "stack-size", default_value_t = CodegenOptions::default().stack_size)]
+    pub(crate) stack_size: u64,
+
+    /// How updates of the stack pointer are checked against the bounds of the stack.
+    ///
+    /// `trap` aborts execution when a call overflows the stack; `unchecked` omits the check.
+    #[clap(
+        long = "stack-checks",
+        default_value = "trap",
+        value_parser = ["trap", "unchecked"]
+    )]
+    pub(crate) stack_checks: String,
+
+    /// Report the wall time and peak memory of every phase, and the size of the program.
+    ///
+    /// `--timings` prints a table after the compilation, `--timings=json` a single JSON
+    /// object with the same data. The report goes to stdout, or to stderr when stdout
+    /// carries JSON diagnostics or an emitted artifact.
+    #[clap(
+        long = "timings",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "text",
+        value_parser = ["text", "json"]
+    )]
+    pub(crate) timings: Option<String>,
+
+    /// Format of errors and warnings.
+    ///
+    /// `human` prints messages to stderr. `json` prints one JSON object per diagnostic to
+    /// stdout, with its phase, severity, code, message and source spans, and moves the
+    /// progress messages to stderr.
+    #[clap(
+        long = "message-format",
+        default_value = "human",
+        value_parser = ["human", "json"]
+    )]
+    pub(crate) message_format: String,
+
+    /// When to color errors and warnings printed as text.
+    ///
+    /// `auto` colors them when stderr is a terminal and the `NO_COLOR` environment
+    /// variable is unset or empty. `always` and `never` override both.
+    #[clap(
+        long = "color",
+        default_value = "auto",
+        value_parser = ["auto", "always", "never"]
+    )]
+    pub(crate) color: String,
+
+    /// Do not report the warning `<LINT>`: a warning name such as `unused_variable`, its
+    /// code such as `W0003`, or `warnings` for all of them.
+    #[clap(short = 'A', long = "allow", value_name = "LINT", value_parser = parse_lint)]
+    pub(crate) allow: Vec<String>,
+
+    /// Report the warning `<LINT>` as a warning, the default.
+    #[clap(short = 'W', long = "warn", value_name = "LINT", value_parser = parse_lint)]
+    pub(crate) warn: Vec<String>,
+
+    /// Report the warning `<LINT>` as an error and fail the compilation after the analyze
+    /// phase. `--deny warnings` treats every warning as an error.
+    #[clap(short = 'D', long = "deny", value_name = "LINT", value_parser = parse_lint)]
+    pub(crate) deny: Vec<String>,
+
+    /// Make a dependency available to `use` directives.
+    ///
+    /// Given as `<name>=<path>`, e.g. `--extern mathlib=../mathlib`, where `<path>` is the
+    /// directory of the dependency, holding `src/lib.inf` or `src/main.inf`. Its public
+    /// definitions are imported with `use <name>::<item>;`. May be repeated.
+    #[clap(long = "extern", value_name = "NAME=PATH", value_parser = parse_extern)]
+    pub(crate) externs: Vec<(String, PathBuf)>,
+
+    /// Read the defaults of flags from `<FILE>` instead of `infc.toml` or the project
+    /// manifest.
+    ///
+    /// The file sets `emit`, `out-dir`, `opt-level` and the `allow`, `warn` and `deny`
+    /// lists of a `[warnings]` table; flags given on the command line override it. A
+    /// manifest is read from its `[infc]` table.
+    #[clap(long = "config", value_name = "FILE", conflicts_with = "no_config")]
+    pub(crate) config: Option<PathBuf>,
+
+    /// Do not read `infc.toml` or the `[infc]` table of the project manifest.
+    #[clap(long = "no-config", action = clap::ArgAction::SetTrue)]
+    pub(crate) no_config: bool,
+
+    /// Levels of `--allow`, `--warn` and `--deny`, applied in command line order.
+    #[clap(skip)]
+    pub(crate) lint_levels: LintLevels,
+}
+
+impl Cli {
+    /// Parses the command line, like [`Parser::parse`], records the order of the warning
+    /// level flags, which clap does not keep across arguments, and applies the defaults of
+    /// the configuration file, unless `--explain` is given.
+    ///
+    /// Exits with a usage error if the configuration file is invalid.
+    pub(crate) fn parse_args() -> Self {
+        let matches = Self::command().get_matches();
+        let mut args = Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+        args.lint_levels = LintLevels::from_matches(&matches);
+        if args.explain.is_some() {
+            return args;
+        }
+        if let Err(e) = config::apply(&mut args, &matches) {
+            eprintln!("Error: {e}");
+            Failure::Usage.exit();
+        }
+        args
+    }
+
+    /// Returns true if the source is read from stdin, which the path `-` selects.
+    pub(crate) fn reads_stdin(&self) -> bool {
+        self.path.as_os_str() == "-"
+    }
+
+    /// Returns the project directory if the path is a directory or an `Inference.toml`.
+    pub(crate) fn project_dir(&self) -> Option<&Path> {
+        if self.path.is_dir() {
+            Some(&self.path)
+        } else if self
+            .path
+            .file_name()
+            .is_some_and(|name| name == MANIFEST_FILE)
+        {
+            Some(self.path.parent().unwrap_or(Path::new(".")))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path of the source file as shown in messages, `<stdin>` for stdin.
+    pub(crate) fn display_path(&self) -> &Path {
+        if self.reads_stdin() {
+            Path::new("<stdin>")
+        } else {
+            &self.path
+        }
+    }
+
+    /// Returns true if errors and warnings printed as text are colored, as selected by
+    /// `--color` and `NO_COLOR`.
+    pub(crate) fn colors_diagnostics(&self) -> bool {
+        match self.color.as_str() {
+            "always" => true,
+            "never" => false,
+            _ => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && io::stderr().is_terminal()
+            }
+        }
+    }
+
+    /// Returns true if stdout carries JSON diagnostics or an emitted artifact, so progress
+    /// messages must go to stderr.
+    pub(crate) fn stdout_reserved(&self) -> bool {
+        self.message_format == "json"
+            || self
+                .emits()
+                .iter()
+                .any(|emit| emit.destination(self) == Destination::Stdout)
+    }
+
+    /// Returns the artifacts to write: the `--emit` entries, followed by those of the
+    /// deprecated `-o` and `-v` flags, which always write to the output directory.
+    pub(crate) fn emits(&self) -> Vec<Emit> {
+        let aliases = [
+            (self.generate_wasm_output, EmitKind::Wasm),
+            (self.generate_v_output, EmitKind::V),
+        ];
+        let mut emits = self.emit.clone();
+        for (_, kind) in aliases.into_iter().filter(|(enabled, _)| *enabled) {
+            emits.push(Emit {
+                kind,
+                path: Some(self.output_dir().join(kind.file_name(&self.output_name()))),
+            });
+        }
+        emits
+    }
+
+    /// Returns true if any artifact of `kind` is requested.
+    pub(crate) fn emits_kind(&self, kind: EmitKind) -> bool {
+        self.emits().iter().any(|emit| emit.kind == kind)
+    }
+
+    /// Returns the directory of artifacts written without an explicit path.
+    pub(crate) fn output_dir(&self) -> &Path {
+        self.out_dir.as_deref().unwrap_or(Path::new("out"))
+    }
+
+    /// Returns the base name of the artifacts: the source file stem, the name of the
+    /// project directory, or `module` for stdin.
+    pub(crate) fn output_name(&self) -> String {
+        let name = match self.project_dir() {
+            Some(dir) => dir
+                .canonicalize()
+                .ok()
+                .and_then(|dir| dir.file_name().map(ToOwned::to_owned)),
+            None => self
+                .path
+                .file_stem()
+                .filter(|_| !self.reads_stdin())
+                .map(ToOwned::to_owned),
+        };
+        name.and_then(|name| name.into_string().ok())
+            .unwrap_or_else(|| "module".to_string())
+    }
+
+    /// Collects the codegen flags into [`CodegenOptions`].
+    pub(crate) fn codegen_options(&self) -> CodegenOptions {
+        let mut target_features = TargetFeatures::default();
+        let features = self
+            .features
+            .iter()
+            .filter(|feature| !target_features.enable(feature))
+            .cloned()
+            .collect();
+        CodegenOptions {
+            backend: if self.backend == "encoder" {
+                Backend::Encoder
+            } else {
+                Backend::Llvm
+            },
+            opt_level: self.opt_level,
+            module_name: self.module_name.clone(),
+            debug_info: self.debug_info,
+            source_map: self.source_map,
+            source_path: (!self.reads_stdin() && self.project_dir().is_none())
+                .then(|| self.path.clone()),
+            target_features,
+            features,
+            linker: if self.linker == "builtin" {
+                Linker::Builtin
+            } else {
+                Linker::RustLld
+            },
+            bounds_checks: if self.bounds_checks == "unchecked" {
+                BoundsChecks::Unchecked
+            } else {
+                BoundsChecks::Trap
+            },
+            assert_strategy: self.assert_strategy.clone(),
+            import_module: self.import_module.clone(),
+            imports: self.imports.iter().cloned().collect(),
+            exports: if self.exports.is_empty() {
+                Exports::Public
+            } else {
+                Exports::Only(self.exports.clone())
+            },
+            export_names: self.export_names.iter().cloned().collect(),
+            start_function: self.start_function.clone(),
+            codegen_units: usize::try_from(self.codegen_units).unwrap_or(usize::MAX),
+            cache_dir: self.cache_dir.clone(),
+            memory64: self.memory64,
+            initial_memory_pages: self.initial_memory_pages,
+            max_memory_pages: self.max_memory_pages,
+            stack_size: self.stack_size,
+            stack_checks: if self.stack_checks == "unchecked" {
+                StackChecks::Unchecked
+            } else {
+                StackChecks::Trap
+            },
+        }
+    }
+}
+
+/// Parses an `--assert` value: `trap`, `abort`, `abort=<module>.<name>`, `result-code` or
+/// `result-code=<code>`.
+fn parse_assert_strategy(value: &str) -> Result<AssertStrategy, String> {
+    let (strategy, argument) = match value.split_once('=') {
+        Some((strategy, argument)) => (strategy, Some(argument)),
+        None => (value, None),
+    };
+    match (strategy, argument) {
+        ("trap", None) => Ok(AssertStrategy::Trap),
+        ("abort", None) => Ok(AssertStrategy::abort()),
+        ("abort", Some(import)) => match import.split_once('.') {
+            Some((module, name)) if !module.is_empty() && !name.is_empty() => {
+                Ok(AssertStrategy::Abort(ImportName {
+                    module: module.to_string(),
+                    name: name.to_string(),
+                }))
+            }
+            _ => Err(format!("expected `abort=<module>.<name>`, found `{value}`")),
+        },
+        ("result-code", None) => Ok(AssertStrategy::ResultCode(-1)),
+        ("result-code", Some(code)) => code
+            .parse()
+            .map(AssertStrategy::ResultCode)
+            .map_err(|_| format!("expected an integer result code, found `{code}`")),
+        _ => Err(format!(
+            "expected `trap`, `abort[=<module>.<name>]` or `result-code[=<code>]`, found `{value}`"
+        )),
+    }
+}
+
+/// Parses an `--emit` entry of the form `<kind>` or `<kind>=<path>`.
+pub(crate) fn parse_emit(value: &str) -> Result<Emit, String> {
+    let (name, path) = match value.split_once('=') {
+        Some((name, path)) if !path.is_empty() => (name, Some(PathBuf::from(path))),
+        Some(_) => return Err(format!("expected a path after `=` in `{value}`")),
+        None => (value, None),
+    };
+    let kind = EmitKind::ALL
+        .into_iter()
+        .find(|kind| kind.name() == name)
+        .ok_or_else(|| {
+            format!(
+                "expected `ast`, `typed`, `symbols`, `ir`, `wat`, `wasm` or `v`, found `{name}`"
+            )
+        })?;
+    Ok(Emit { kind, path })
+}
+
+/// Parses an `--import` value of the form `<function>=<module>.<name>`.
+fn parse_import(value: &str) -> Result<(String, ImportName), String> {
+    let (function, import) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<function>=<module>.<name>`, found `{value}`"))?;
+    let (module, name) = import
+        .split_once('.')
+        .ok_or_else(|| format!("expected `<module>.<name>` after `=`, found `{import}`"))?;
+    if function.is_empty() || module.is_empty() || name.is_empty() {
+        return Err(format!(
+            "function, module and name must not be empty in `{value}`"
+        ));
+    }
+    Ok((
+        function.to_string(),
+        ImportName {
+            module: module.to_string(),
+            name: name.to_string(),
+        },
+    ))
+}
+
+/// Parses an `--extern` value of the form `<name>=<path>`.
+fn parse_extern(value: &str) -> Result<(String, PathBuf), String> {
+    let (name, path) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<name>=<path>`, found `{value}`"))?;
+    if name.is_empty() || path.is_empty() {
+        return Err(format!("name and path must not be empty in `{value}`"));
+    }
+    Ok((name.to_string(), PathBuf::from(path)))
+}
+
+/// Parses an `--export-name` value of the form `<name>=<export>`.
+fn parse_export_name(value: &str) -> Result<(String, String), String> {
+    let (name, export) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected `<name>=<export>`, found `{value}`"))?;
+    if name.is_empty() || export.is_empty() {
+        return Err(format!(
+            "name and export name must not be empty in `{value}`"
+        ));
+    }
+    Ok((name.to_string(), export.to_string()))
 }
@@ -0,0 +1,150 @@
+//! Timing and memory report for `--timings`.
+//!
+//! With `--timings`, infc reports the wall time of every phase it ran, the peak resident
+//! set size of the process after it, and the size of the compiled program:
+//!
+//! ```text
+//! Timings:
+//!   parse            1.84 ms  peak RSS    9.3 MiB
+//!   type-check       0.52 ms  peak RSS    9.6 MiB
+//!   analyze          0.07 ms  peak RSS    9.6 MiB
+//!   codegen         41.20 ms  peak RSS   38.1 MiB
+//!   total           43.63 ms
+//!   source files: 1, nodes: 212, functions: 6
+//! ```
+//!
+//! `--timings=json` prints the same report as one JSON object:
+//!
+//! ```json
+//! {"total_ms":43.63,"peak_rss_kib":39014,"source_files":1,"nodes":212,"functions":6,"phases":[{"phase":"parse","wall_ms":1.84,"peak_rss_kib":9523}]}
+//! ```
+//!
+//! Peak RSS is the high-water mark of the whole process, read from `/proc/self/status`;
+//! it is `null` on platforms without procfs. A phase that fails is still reported, so
+//! the report covers every phase that ran.
+
+use crate::{diagnostics::Phase, status};
+use inference::Arena;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Wall time and memory of the phases of one compilation.
+#[derive(Debug, Serialize)]
+pub(crate) struct Timings {
+    total_ms: f64,
+    peak_rss_kib: Option<u64>,
+    source_files: usize,
+    nodes: usize,
+    functions: usize,
+    phases: Vec<PhaseTiming>,
+    #[serde(skip)]
+    start: Instant,
+}
+
+/// Wall time of one phase and the peak RSS once it finished.
+#[derive(Debug, Serialize)]
+struct PhaseTiming {
+    phase: Phase,
+    wall_ms: f64,
+    peak_rss_kib: Option<u64>,
+}
+
+impl Timings {
+    pub(crate) fn new() -> Self {
+        Self {
+            total_ms: 0.0,
+            peak_rss_kib: None,
+            source_files: 0,
+            nodes: 0,
+            functions: 0,
+            phases: Vec::new(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Runs `f` as part of `phase` and records its wall time. Time spent in the same
+    /// phase more than once is added up.
+    pub(crate) fn time<T>(&mut self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        let wall_ms = millis(start.elapsed());
+        let peak_rss_kib = peak_rss_kib();
+        match self.phases.iter_mut().find(|timing| timing.phase == phase) {
+            Some(timing) => {
+                timing.wall_ms += wall_ms;
+                timing.peak_rss_kib = peak_rss_kib;
+            }
+            None => self.phases.push(PhaseTiming {
+                phase,
+                wall_ms,
+                peak_rss_kib,
+            }),
+        }
+        result
+    }
+
+    /// Records the size of the parsed program.
+    pub(crate) fn count(&mut self, arena: &Arena) {
+        self.source_files = arena.source_files().len();
+        self.nodes = arena.node_count();
+        self.functions = arena.functions().len();
+    }
+
+    /// Prints the report, as text or as a line of JSON.
+    ///
+    /// The report goes to stdout, or to stderr if `quiet` because stdout carries JSON
+    /// diagnostics or an emitted artifact.
+    pub(crate) fn report(mut self, json: bool, quiet: bool) {
+        self.total_ms = millis(self.start.elapsed());
+        self.peak_rss_kib = peak_rss_kib();
+        if json {
+            match serde_json::to_string(&self) {
+                Ok(line) => status(quiet, &line),
+                Err(e) => eprintln!("Failed to serialize timings: {e}"),
+            }
+            return;
+        }
+        let mut lines = vec!["Timings:".to_string()];
+        lines.extend(self.phases.iter().map(|timing| {
+            format!(
+                "  {:<12} {:>8.2} ms  peak RSS {}",
+                timing.phase.name(),
+                timing.wall_ms,
+                format_rss(timing.peak_rss_kib)
+            )
+        }));
+        lines.push(format!("  {:<12} {:>8.2} ms", "total", self.total_ms));
+        lines.push(format!(
+            "  source files: {}, nodes: {}, functions: {}",
+            self.source_files, self.nodes, self.functions
+        ));
+        status(quiet, &lines.join("\n"));
+    }
+}
+
+/// Returns `duration` in milliseconds, rounded to microseconds.
+fn millis(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
+
+/// Returns the peak resident set size of the process in KiB, if the platform reports it.
+fn peak_rss_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Formats a peak RSS in MiB, or `n/a` if unknown.
+#[allow(clippy::cast_precision_loss)]
+fn format_rss(kib: Option<u64>) -> String {
+    match kib {
+        Some(kib) => format!("{:>6.1} MiB", kib as f64 / 1024.0),
+        None => "n/a".to_string(),
+    }
+}
@@ -0,0 +1,110 @@
+//! Watch mode (`--watch`).
+//!
+//! Runs the requested phases once, then again whenever a watched file changes, until the
+//! process is interrupted. Each run ends with a one-line summary, so the result of an edit
+//! is visible at a glance.
+//!
+//! The watcher observes the directory containing each file rather than the file itself:
+//! editors often save by writing a new file and renaming it over the old one, which would
+//! end a watch on the original file.
+
+use crate::{compile, parser::Cli, status};
+use inference::{MANIFEST_FILE, project_files};
+use notify::{Event, RecursiveMode, Watcher};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+/// How long to wait for further events after a change, so that a save touching the file
+/// several times triggers a single run.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Runs the requested phases now and after every change of a watched file.
+///
+/// Only returns on error; compilation failures are reported and the watcher keeps
+/// running.
+///
+/// # Errors
+///
+/// Returns an error if a watched file cannot be resolved, or the filesystem watcher
+/// cannot be started or stops delivering events.
+pub(crate) fn run(args: &Cli) -> anyhow::Result<()> {
+    let quiet = args.stdout_reserved();
+    let files = watched_files(args)?;
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let directories: HashSet<&Path> = files.iter().filter_map(|file| file.parent()).collect();
+    for directory in directories {
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+    }
+
+    compile_and_summarize(args, quiet);
+    loop {
+        status(
+            quiet,
+            &format!("Watching {} for changes", args.path.display()),
+        );
+        let changed = wait_for_change(&receiver, &files)?;
+        status(quiet, &format!("Change detected: {}", changed.display()));
+        compile_and_summarize(args, quiet);
+    }
+}
+
+/// Returns the files whose changes trigger a run, with absolute paths.
+///
+/// This is the source file, or the source files and the manifest of a project. Files
+/// added to a project while watching are only picked up after a restart.
+fn watched_files(args: &Cli) -> anyhow::Result<Vec<PathBuf>> {
+    let Some(project_dir) = args.project_dir() else {
+        return Ok(vec![args.path.canonicalize()?]);
+    };
+    let mut files = project_files(&args.path)?;
+    let manifest = project_dir.join(MANIFEST_FILE);
+    if manifest.exists() {
+        files.push(manifest);
+    }
+    files.iter().map(|file| Ok(file.canonicalize()?)).collect()
+}
+
+/// Blocks until one of `files` changes, and returns it.
+fn wait_for_change(
+    receiver: &Receiver<notify::Result<Event>>,
+    files: &[PathBuf],
+) -> anyhow::Result<PathBuf> {
+    loop {
+        let event = receiver.recv()??;
+        if let Some(file) = changed_file(&event, files) {
+            while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+            return Ok(file);
+        }
+    }
+}
+
+/// Returns the watched file modified, created or removed by `event`, if any.
+fn changed_file(event: &Event, files: &[PathBuf]) -> Option<PathBuf> {
+    if event.kind.is_access() {
+        return None;
+    }
+    event
+        .paths
+        .iter()
+        .find(|path| files.contains(path))
+        .cloned()
+}
+
+/// Runs the requested phases and prints whether they succeeded and how long they took.
+fn compile_and_summarize(args: &Cli, quiet: bool) {
+    let start = Instant::now();
+    let outcome = if compile(args).is_ok() {
+        "Finished"
+    } else {
+        "Failed"
+    };
+    status(
+        quiet,
+        &format!("{outcome} in {} ms", start.elapsed().as_millis()),
+    );
+}
@@ -81,26 +81,26 @@ fn example_file(name: &str) -> std::path::PathBuf {
 
 /// Verifies that the compiler fails gracefully when the input file doesn't exist.
 ///
-/// **Expected behavior**: Exit with code 1 and print "path not found" to stderr.
+/// **Expected behavior**: Exit with code 3 and print "path not found" to stderr.
 #[test]
 fn fails_when_file_missing() {
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
     cmd.arg("this-file-does-not-exist.inf").arg("--parse");
     cmd.assert()
-        .failure()
+        .code(3)
         .stderr(predicate::str::contains("path not found"));
 }
 
 /// Verifies that the compiler requires at least one phase flag.
 ///
-/// **Expected behavior**: Exit with code 1 when no phase flags are provided,
+/// **Expected behavior**: Exit with code 2 when no phase flags are provided,
 /// with an error message explaining that at least one phase must be specified.
 #[test]
 fn fails_when_no_phase_selected() {
     let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
     cmd.arg(example_file("example.inf"));
     cmd.assert()
-        .failure()
+        .code(2)
         .stderr(predicate::str::contains("at least one of --parse"));
 }
 
@@ -117,6 +117,868 @@ fn parse_only_succeeds() {
         .stdout(predicate::str::contains("Parsed:"));
 }
 
+/// Verifies that the optimization level is validated before any phase runs.
+///
+/// **Expected behavior**: Exit with a usage error mentioning `--opt-level`
+/// when the level is outside `0..=3`.
+#[test]
+fn rejects_out_of_range_opt_level() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("-O")
+        .arg("4");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--opt-level"));
+}
+
+/// Verifies that clap rejects a `--linker` value other than `rust-lld` or `builtin`.
+#[test]
+fn rejects_unknown_linker() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--linker")
+        .arg("gold");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--linker"));
+}
+
+/// Verifies that clap rejects a `--bounds-checks` value other than `trap` or `unchecked`.
+#[test]
+fn rejects_unknown_bounds_checks() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--bounds-checks")
+        .arg("wrap");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--bounds-checks"));
+}
+
+/// Verifies that clap rejects an `--import` value without a module and field name.
+#[test]
+fn rejects_malformed_import() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--import")
+        .arg("log=console");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--import"));
+}
+
+/// Verifies that clap rejects an `--export-name` value without a new name.
+#[test]
+fn rejects_malformed_export_name() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--export-name")
+        .arg("add");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--export-name"));
+}
+
+/// Verifies that clap rejects an `--extern` value without a path.
+#[test]
+fn rejects_malformed_extern() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--analyze")
+        .arg("--extern")
+        .arg("mathlib");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--extern"));
+}
+
+/// Verifies that clap rejects zero codegen units.
+#[test]
+fn rejects_zero_codegen_units() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--codegen-units")
+        .arg("0");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--codegen-units"));
+}
+
+/// Verifies that clap rejects an unknown assert strategy.
+#[test]
+fn rejects_unknown_assert_strategy() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--assert")
+        .arg("panic");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--assert"));
+}
+
+/// Returns the diagnostics printed to stdout with `--message-format=json`, one per line.
+fn json_diagnostics(stdout: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each stdout line is a JSON object"))
+        .collect()
+}
+
+/// Verifies that type errors are printed as JSON diagnostics with their code and spans.
+///
+/// **Expected behavior**: Exit with code 5 and print one JSON object per error to stdout,
+/// with the related notes as secondary spans.
+#[test]
+fn json_message_format_reports_type_errors() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("mismatch.inf");
+    source
+        .write_str("fn main() -> i32 {\n    let x: i32 = true;\n    return x;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(source.path())
+        .arg("--analyze")
+        .arg("--message-format=json");
+    let output = cmd.assert().code(5).get_output().stdout.clone();
+    let diagnostics = json_diagnostics(&output);
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic["phase"], "type-check");
+    assert_eq!(diagnostic["severity"], "error");
+    assert_eq!(diagnostic["code"], "E0001");
+    assert!(
+        diagnostic["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("type mismatch")
+    );
+    let spans = diagnostic["spans"].as_array().unwrap();
+    assert_eq!(spans[0]["primary"], true);
+    assert_eq!(spans[0]["start_line"], 2);
+    assert!(spans[1..].iter().all(|span| span["primary"] == false));
+}
+
+/// Verifies that syntax errors are printed as JSON diagnostics located at the error.
+#[test]
+fn json_message_format_reports_syntax_errors() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("syntax.inf");
+    source
+        .write_str("fn main() -> i32 {\n    let x: i32 = ;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(source.path())
+        .arg("--parse")
+        .arg("--message-format=json");
+    let output = cmd.assert().code(4).get_output().stdout.clone();
+    let diagnostics = json_diagnostics(&output);
+    assert!(!diagnostics.is_empty());
+    for diagnostic in diagnostics {
+        assert_eq!(diagnostic["phase"], "parse");
+        assert_eq!(diagnostic["severity"], "error");
+        assert_eq!(diagnostic["spans"][0]["start_line"], 2);
+    }
+}
+
+/// Verifies that errors are rendered with their code and a snippet of the source.
+///
+/// **Expected behavior**: Exit with code 5 and print the error with its code, location,
+/// the offending line with the span underlined and the related notes to stderr,
+/// without colors since stderr is not a terminal.
+#[test]
+fn renders_type_errors_with_source_snippet() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("mismatch.inf");
+    source
+        .write_str("fn main() -> i32 {\n    let x: i32 = true;\n    return x;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(source.path()).arg("--analyze");
+    cmd.assert().code(5).stderr(
+        predicate::str::contains("error[E0001]: type mismatch")
+            .and(predicate::str::contains(format!(
+                "{}:2:5",
+                source.path().display()
+            )))
+            .and(predicate::str::contains("2 │     let x: i32 = true;"))
+            .and(predicate::str::contains("^^^^^^^^^^^^^^^^^^"))
+            .and(predicate::str::contains("this expression has type `Bool`"))
+            .and(predicate::str::contains("\u{1b}[").not()),
+    );
+}
+
+/// Verifies that `--color=always` colors diagnostics even when stderr is not a terminal,
+/// and that syntax errors of a source read from stdin are shown in a snippet.
+///
+/// **Expected behavior**: Exit with code 4 and print the error with ANSI escape codes
+/// and the offending `<stdin>` line to stderr.
+#[test]
+fn color_always_colors_diagnostics() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--parse")
+        .arg("--color=always")
+        .write_stdin("fn main() -> i32 {\n    let x: i32 = ;\n}\n");
+    cmd.assert().code(4).stderr(
+        predicate::str::contains("\u{1b}[")
+            .and(predicate::str::contains("<stdin>:2:16"))
+            .and(predicate::str::contains("    let x: i32 ")),
+    );
+}
+
+/// Source with an unused variable (`W0003`) in a function that is never used (`W0005`).
+const UNUSED_VARIABLE_SOURCE: &str = "fn main() -> i32 {\n    let x: i32 = 1;\n    return 0;\n}\n";
+
+/// Verifies that `--deny warnings` reports warnings as errors and fails the compilation.
+///
+/// **Expected behavior**: Exit with code 6, report the warnings as errors with their
+/// codes, and count the denied warnings.
+#[test]
+fn deny_warnings_fails_compilation() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--analyze")
+        .args(["--deny", "warnings"])
+        .write_stdin(UNUSED_VARIABLE_SOURCE);
+    cmd.assert().code(6).stderr(
+        predicate::str::contains("error[W0003]: unused variable `x`")
+            .and(predicate::str::contains("error[W0005]"))
+            .and(predicate::str::contains("2 denied warnings")),
+    );
+}
+
+/// Verifies that warning flags apply in command line order, by name or by code.
+///
+/// **Expected behavior**: Exit with code 0, since the later `-A` flags allow both
+/// warnings denied by `-D warnings`, and report neither.
+#[test]
+fn later_warning_flags_override_earlier_ones() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--analyze")
+        .args(["-D", "warnings", "-A", "unused-variable", "-A", "W0005"])
+        .write_stdin(UNUSED_VARIABLE_SOURCE);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("W000").not());
+}
+
+/// Verifies that a denied warning is a JSON diagnostic with the `error` severity.
+///
+/// **Expected behavior**: Exit with code 6, with `W0003` reported as an error and
+/// `W0005` still as a warning.
+#[test]
+fn denied_warning_is_a_json_error() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--analyze")
+        .arg("--message-format=json")
+        .args(["--deny", "unused_variable"])
+        .write_stdin(UNUSED_VARIABLE_SOURCE);
+    let output = cmd.assert().code(6).get_output().stdout.clone();
+    let severities: Vec<_> = json_diagnostics(&output)
+        .iter()
+        .map(|diagnostic| {
+            (
+                diagnostic["code"].as_str().unwrap().to_string(),
+                diagnostic["severity"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert!(severities.contains(&("W0003".to_string(), "error".to_string())));
+    assert!(severities.contains(&("W0005".to_string(), "warning".to_string())));
+}
+
+/// Verifies that warning flags reject unknown warnings.
+#[test]
+fn rejects_unknown_warning() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--analyze")
+        .args(["-D", "unused_everything"]);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "unknown warning `unused_everything`",
+    ));
+}
+
+/// Verifies that `--message-format=json` keeps stdout free of progress messages.
+///
+/// **Expected behavior**: Exit with code 0, print "Parsed: <filepath>" to stderr and
+/// nothing to stdout.
+#[test]
+fn json_message_format_moves_progress_to_stderr() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--parse")
+        .arg("--message-format=json");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("Parsed:"));
+}
+
+/// Verifies that `-` reads the source from stdin.
+///
+/// **Expected behavior**: Exit with code 0 and print "Parsed: <stdin>" to stdout.
+#[test]
+fn parses_source_from_stdin() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--parse")
+        .write_stdin("fn main() -> i32 {\n    return 0;\n}\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Parsed: <stdin>"));
+}
+
+/// Verifies that `--emit=wasm` writes only the WASM binary to stdout.
+///
+/// **Expected behavior**: Exit with code 0, with stdout starting with the WASM magic
+/// number and the progress messages on stderr.
+#[test]
+fn emits_wasm_to_stdout() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--codegen")
+        .arg("--emit=wasm")
+        .write_stdin("pub fn main() -> i32 {\n    return 0;\n}\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::function(|stdout: &[u8]| {
+            stdout.starts_with(b"\0asm")
+        }))
+        .stderr(predicate::str::contains("WASM generated"));
+}
+
+/// Verifies that `--emit` is rejected without the codegen phase.
+#[test]
+fn emit_requires_codegen() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--parse")
+        .arg("--emit=wasm");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("--emit=wasm requires --codegen"));
+}
+
+/// Verifies that `--emit=ast,typed` writes the JSON dumps of the AST and the inferred
+/// types to `--out-dir`.
+///
+/// **Expected behavior**: Exit with code 0 and write `<name>.ast.json`, with the source
+/// file as root, and `<name>.typed.json`, with the type of the returned literal.
+#[test]
+fn emits_ast_and_types_to_out_dir() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("typed.inf");
+    source
+        .write_str("fn main() -> i32 {\n    return 42;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(source.path())
+        .arg("--analyze")
+        .arg("--emit=ast,typed")
+        .arg("--out-dir")
+        .arg(temp.child("build").path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("AST generated at:"));
+
+    let ast: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(temp.child("build/typed.ast.json").path()).unwrap())
+            .unwrap();
+    assert!(ast[0]["definitions"][0]["Function"].is_object());
+    let typed: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(temp.child("build/typed.typed.json").path()).unwrap(),
+    )
+    .unwrap();
+    let nodes = typed["nodes"].as_array().unwrap();
+    assert!(
+        nodes
+            .iter()
+            .any(|node| node["type"] == "i32" && node["location"]["start_line"] == 2)
+    );
+}
+
+/// Verifies that `--emit=symbols` writes the JSON dump of the symbol table.
+///
+/// **Expected behavior**: Exit with code 0 and write `<name>.symbols.json`, with `main`
+/// and its signature in the root scope and no built-in types.
+#[test]
+fn emits_symbol_table() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("symbols.inf");
+    source
+        .write_str("fn main() -> i32 {\n    let x: i32 = 42;\n    return x;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(source.path())
+        .arg("--analyze")
+        .arg("--emit=symbols")
+        .arg("--out-dir")
+        .arg(temp.child("build").path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Symbol table generated at:"));
+
+    let table: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(temp.child("build/symbols.symbols.json").path()).unwrap(),
+    )
+    .unwrap();
+    let scopes = table["scopes"].as_array().unwrap();
+    let root = &scopes[0]["symbols"];
+    assert_eq!(root.as_array().unwrap().len(), 1);
+    assert_eq!(root[0]["name"], "main");
+    assert_eq!(root[0]["type"], "fn() -> i32");
+    assert!(scopes.iter().any(|scope| {
+        scope["symbols"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|symbol| symbol["name"] == "x" && symbol["kind"] == "variable")
+    }));
+}
+
+/// Verifies that several artifacts can be emitted at once, each to its own path.
+///
+/// **Expected behavior**: Exit with code 0, with the WAT written to stdout and the WASM
+/// binary to the explicit path.
+#[test]
+fn emits_several_artifacts() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let wasm = temp.child("app.wasm");
+
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--codegen")
+        .arg(format!("--emit=wat=-,wasm={}", wasm.path().display()))
+        .write_stdin("pub fn main() -> i32 {\n    return 0;\n}\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("(module"));
+    assert!(std::fs::read(wasm.path()).unwrap().starts_with(b"\0asm"));
+}
+
+/// Verifies that at most one artifact can be written to stdout.
+#[test]
+fn rejects_several_artifacts_on_stdout() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--codegen")
+        .arg("--emit=wat=-,v=-");
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "only one --emit artifact can be written to stdout",
+    ));
+}
+
+/// Verifies that a project directory is compiled as one module from all `.inf` files
+/// under `src/`.
+///
+/// **Expected behavior**: Exit with code 0 and write `out/<project>.wasm`, named after the
+/// project directory, with the function defined in the second file.
+#[test]
+fn compiles_project_directory() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let project = temp.child("app");
+    project
+        .child("Inference.toml")
+        .write_str("[package]\nname = \"app\"\n")
+        .unwrap();
+    project
+        .child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return helper();\n}\n")
+        .unwrap();
+    project
+        .child("src/util/helper.inf")
+        .write_str("fn helper() -> i32 {\n    return 42;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.current_dir(temp.path())
+        .arg(project.child("Inference.toml").path())
+        .arg("--codegen")
+        .arg("--emit=wasm");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("WASM generated at:"));
+    let wasm = std::fs::read(temp.child("out/app.wasm").path()).unwrap();
+    assert!(wasm.starts_with(b"\0asm"));
+}
+
+/// Verifies that type errors of a project are reported against their source file.
+///
+/// **Expected behavior**: Exit with code 5, with the JSON diagnostic naming the file
+/// containing the error.
+#[test]
+fn reports_project_errors_with_their_file() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.inf")
+        .write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    let broken = temp.child("src/broken.inf");
+    broken
+        .write_str("fn broken() {\n    let b: bool = 1;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(temp.path())
+        .arg("--analyze")
+        .arg("--message-format=json");
+    let output = cmd.assert().code(5).get_output().stdout.clone();
+    let diagnostics = json_diagnostics(&output);
+    assert!(!diagnostics.is_empty());
+    assert!(
+        diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic["file"] == broken.path().display().to_string())
+    );
+}
+
+/// Verifies that `--extern` makes the public functions of a dependency importable.
+///
+/// **Expected behavior**: Exit with code 0 when the imported function is public, and with
+/// code 5 when the program imports a function the dependency keeps private.
+#[test]
+fn imports_from_extern_dependency() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("mathlib/src/lib.inf")
+        .write_str(
+            "pub fn add(a: i32, b: i32) -> i32 {\n    return a + b;\n}\n\n\
+             fn secret() -> i32 {\n    return 0;\n}\n",
+        )
+        .unwrap();
+    let main = temp.child("main.inf");
+    main.write_str("use mathlib::add;\n\nfn main() -> i32 {\n    return add(1, 2);\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(main.path())
+        .arg("--analyze")
+        .arg("--extern")
+        .arg(format!(
+            "mathlib={}",
+            temp.child("mathlib").path().display()
+        ));
+    cmd.assert().success();
+
+    main.write_str("use mathlib::secret;\n\nfn main() -> i32 {\n    return secret();\n}\n")
+        .unwrap();
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(main.path())
+        .arg("--analyze")
+        .arg("--extern")
+        .arg(format!(
+            "mathlib={}",
+            temp.child("mathlib").path().display()
+        ));
+    cmd.assert()
+        .code(5)
+        .stderr(predicate::str::contains("private"));
+}
+
+/// Verifies that a missing `--extern` directory is reported as an IO error.
+///
+/// **Expected behavior**: Exit with code 3, naming the directory without a module root.
+#[test]
+fn missing_extern_dependency_exits_with_code_3() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let main = temp.child("main.inf");
+    main.write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(main.path())
+        .arg("--analyze")
+        .arg("--extern")
+        .arg(format!(
+            "mathlib={}",
+            temp.child("mathlib").path().display()
+        ));
+    cmd.assert()
+        .code(3)
+        .stderr(predicate::str::contains("no module root found"));
+}
+
+/// Verifies that `--explain` prints the explanation of a code without a source path.
+///
+/// **Expected behavior**: Exit with code 0, printing the explanation of `E0001` with its
+/// example; codes are case-insensitive.
+#[test]
+fn explain_prints_the_explanation_of_a_code() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("--explain").arg("e0001");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "An expression has a different type than the one its context expects.",
+        ))
+        .stdout(predicate::str::contains("let x: i32 = true;"));
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("--explain").arg("W0003");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Lint name: `unused_variable`."));
+}
+
+/// Verifies that `--explain` rejects codes that no diagnostic has.
+///
+/// **Expected behavior**: Exit with code 2, naming the unknown code.
+#[test]
+fn explain_rejects_unknown_codes() {
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("--explain").arg("E9999");
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("no explanation for `E9999`"));
+}
+
+/// Verifies that a codegen failure has its own exit code.
+///
+/// **Expected behavior**: Exit with code 7, since debug information cannot be generated
+/// for a project of several source files.
+#[test]
+fn codegen_failure_exits_with_code_7() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("src/main.inf")
+        .write_str("pub fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+    temp.child("src/util.inf")
+        .write_str("pub fn one() -> i32 {\n    return 1;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(temp.path()).arg("--codegen").arg("-g");
+    cmd.assert().code(7);
+}
+
+/// Verifies that an output that cannot be written is an IO error.
+///
+/// **Expected behavior**: Exit with code 3, since the output directory is a file.
+#[test]
+fn unwritable_output_exits_with_code_3() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let out_dir = temp.child("out");
+    out_dir.write_str("not a directory").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--parse")
+        .arg("--emit=ast")
+        .arg("--out-dir")
+        .arg(out_dir.path());
+    cmd.assert().code(3).stderr(predicate::str::contains(
+        "Failed to create output directory",
+    ));
+}
+
+/// Verifies that `infc.toml` next to the source file sets the defaults of the flags.
+///
+/// **Expected behavior**: Exit with code 6, since the file denies unused variables, after
+/// writing the AST to the output directory of the file, resolved from its directory.
+#[test]
+fn reads_defaults_from_infc_toml() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("unused.inf")
+        .write_str(UNUSED_VARIABLE_SOURCE)
+        .unwrap();
+    temp.child("infc.toml")
+        .write_str(
+            "emit = [\"ast\", \"wasm\"]\nout-dir = \"build\"\n\n[warnings]\ndeny = [\"unused_variable\"]\n",
+        )
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(temp.child("unused.inf").path()).arg("--analyze");
+    cmd.assert()
+        .code(6)
+        .stderr(predicate::str::contains("error[W0003]"));
+    temp.child("build/unused.ast.json")
+        .assert(predicate::path::exists());
+}
+
+/// Verifies that flags given on the command line override the configuration file.
+///
+/// **Expected behavior**: Exit with code 0, with the warning allowed by `-A` and only the
+/// artifact of `--emit` written to `--out-dir`.
+#[test]
+fn command_line_overrides_config() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("unused.inf")
+        .write_str(UNUSED_VARIABLE_SOURCE)
+        .unwrap();
+    temp.child("infc.toml")
+        .write_str("emit = [\"ast\"]\n\n[warnings]\ndeny = [\"warnings\"]\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(temp.child("unused.inf").path())
+        .arg("--analyze")
+        .arg("--emit=typed")
+        .arg("--out-dir")
+        .arg(temp.child("out").path())
+        .args(["-A", "warnings"]);
+    cmd.assert().success();
+    temp.child("out/unused.typed.json")
+        .assert(predicate::path::exists());
+    temp.child("out/unused.ast.json")
+        .assert(predicate::path::missing());
+}
+
+/// Verifies that a project reads its defaults from the `[infc]` table of its manifest.
+///
+/// **Expected behavior**: Exit with code 0 and write the AST to the output directory of
+/// the table, named after the project.
+#[test]
+fn reads_defaults_from_project_manifest() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    temp.child("Inference.toml")
+        .write_str(
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[infc]\nemit = [\"ast\"]\nout-dir = \"target\"\n",
+        )
+        .unwrap();
+    temp.child("src/main.inf")
+        .write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(temp.path()).arg("--parse");
+    cmd.assert().success();
+    let name = temp.path().file_name().unwrap().to_string_lossy();
+    temp.child(format!("target/{name}.ast.json"))
+        .assert(predicate::path::exists());
+}
+
+/// Verifies that an invalid configuration file is a usage error naming the file.
+#[test]
+fn rejects_invalid_config() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let config = temp.child("custom.toml");
+    config.write_str("opt-level = 7\n").unwrap();
+
+    let mut cmd = Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg(example_file("example.inf"))
+        .arg("--parse")
+        .arg("--config")
+        .arg(config.path());
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("custom.toml").and(predicate::str::contains("opt-level")));
+}
+
+/// Verifies that `--timings` reports every phase that ran.
+///
+/// **Expected behavior**: Exit with code 0 and print the timing table, with the parse,
+/// type-check and analyze phases and the program size, to stdout.
+#[test]
+fn timings_report_phases() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--analyze")
+        .arg("--timings")
+        .write_stdin("fn main() -> i32 {\n    return 0;\n}\n");
+    cmd.assert().success().stdout(
+        predicate::str::contains("Timings:")
+            .and(predicate::str::contains("type-check"))
+            .and(predicate::str::contains("analyze"))
+            .and(predicate::str::contains("source files: 1")),
+    );
+}
+
+/// Verifies that `--timings=json` prints the report as a JSON object, on stderr when
+/// stdout carries an emitted artifact.
+///
+/// **Expected behavior**: Exit with code 0, with the WASM binary on stdout and a JSON
+/// report of the four phases as the last line of stderr.
+#[test]
+fn timings_json_moves_to_stderr_with_emit() {
+    let mut cmd = assert_cmd::Command::new(assert_cmd::cargo::cargo_bin!("infc"));
+    cmd.arg("-")
+        .arg("--codegen")
+        .arg("--emit=wasm")
+        .arg("--timings=json")
+        .write_stdin("pub fn main() -> i32 {\n    return 0;\n}\n");
+    let output = cmd.assert().success().get_output().clone();
+    assert!(output.stdout.starts_with(b"\0asm"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let report: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    let phases: Vec<_> = report["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["phase"].as_str().unwrap())
+        .collect();
+    assert_eq!(phases, ["parse", "type-check", "analyze", "codegen"]);
+    assert_eq!(report["functions"], 1);
+    assert!(report["total_ms"].as_f64().unwrap() >= 0.0);
+}
+
+/// Verifies that `--watch` compiles again when the source file changes.
+///
+/// **Expected behavior**: The phases run at startup, and again after the file is
+/// rewritten with a syntax error, which is reported without stopping the watcher.
+#[test]
+fn watch_recompiles_on_change() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    let temp = assert_fs::TempDir::new().unwrap();
+    let source = temp.child("watched.inf");
+    source
+        .write_str("fn main() -> i32 {\n    return 0;\n}\n")
+        .unwrap();
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin!("infc"))
+        .arg(source.path())
+        .arg("--parse")
+        .arg("--watch")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let stdout = child.stdout.take().unwrap();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    // Returns false if no line starting with `prefix` arrives in time.
+    let wait_for = |prefix: &str| loop {
+        match receiver.recv_timeout(Duration::from_secs(10)) {
+            Ok(line) if line.starts_with(prefix) => break true,
+            Ok(_) => {}
+            Err(_) => break false,
+        }
+    };
+
+    let started = wait_for("Finished in") && wait_for("Watching");
+    source.write_str("fn main( {\n").unwrap();
+    let recompiled = started && wait_for("Change detected:") && wait_for("Failed in");
+    // Stop the watcher before asserting, so a failure does not leave it running.
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(started, "the initial run did not finish");
+    assert!(recompiled, "the change did not trigger a failing run");
+}
+
 /// Verifies that the full compilation pipeline executes correctly.
 ///
 /// **Test setup**: Copies test input to a temporary directory to avoid
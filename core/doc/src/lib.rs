@@ -0,0 +1,421 @@
+//! API Documentation Generator for the Inference Programming Language
+//!
+//! This crate extracts the documented API of `.inf` source files and renders it as
+//! HTML or Markdown pages:
+//!
+//! - Module documentation from the `//!` comments at the top of a file
+//! - Functions, external functions, structs with their fields and methods, enums with
+//!   their variants, constants, type aliases and specs, each with its signature and
+//!   the `///` comments directly above it
+//! - The line of every item, so the pages link each item to its source
+//!
+//! ## Comment Preservation
+//!
+//! Like `inference-fmt`, the extractor reads the syntax tree of the tree-sitter grammar
+//! rather than the arena AST of `inference-ast`, which keeps no comments. The grammar
+//! leaves comments out of the tree, so the doc comments of an item are recovered from
+//! the source lines above it: every `///` line up to the first line that is not one.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! let module = inference_doc::Module::parse(
+//!     "math",
+//!     "src/math.inf",
+//!     "/// Adds two numbers.\npub fn add(a: i32, b: i32) -> i32 { return a + b; }\n",
+//! )?;
+//! assert_eq!(module.items[0].signature, "pub fn add(a: i32, b: i32) -> i32");
+//! assert_eq!(module.items[0].docs, "Adds two numbers.");
+//! # Ok::<(), inference_doc::DocError>(())
+//! ```
+
+mod render;
+
+pub use render::{Format, Page, render};
+
+use thiserror::Error;
+use tree_sitter::{Node, Parser, Tree};
+
+/// Reasons a source cannot be documented.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum DocError {
+    /// The tree-sitter grammar of Inference could not be loaded.
+    #[error("failed to load the Inference grammar: {0}")]
+    Grammar(String),
+
+    /// The source does not parse; `line` and `column` are 1-based and locate the first
+    /// syntax error.
+    #[error("{line}:{column}: syntax error, the source was not documented")]
+    Syntax { line: usize, column: usize },
+}
+
+/// The kind of a documented item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Function,
+    ExternalFunction,
+    Struct,
+    Field,
+    Method,
+    Enum,
+    Variant,
+    Constant,
+    Type,
+    Spec,
+}
+
+impl ItemKind {
+    /// Returns the name of the kind, as shown on the pages.
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            ItemKind::Function => "function",
+            ItemKind::ExternalFunction => "external function",
+            ItemKind::Struct => "struct",
+            ItemKind::Field => "field",
+            ItemKind::Method => "method",
+            ItemKind::Enum => "enum",
+            ItemKind::Variant => "variant",
+            ItemKind::Constant => "constant",
+            ItemKind::Type => "type",
+            ItemKind::Spec => "spec",
+        }
+    }
+}
+
+/// A documented item of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    /// The declaration without its body, on one line, e.g. `pub fn add(a: i32) -> i32`.
+    pub signature: String,
+    /// The text of the `///` comments above the item, one line per comment line.
+    pub docs: String,
+    /// The 1-based line the item starts on.
+    pub line: usize,
+    /// Whether the item is part of the public API: declared `pub`, or a spec, field or
+    /// variant.
+    pub public: bool,
+    /// Fields and methods of a struct, variants of an enum, or items of a spec.
+    pub members: Vec<Item>,
+}
+
+/// The documentation of one source file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Module {
+    /// The module name, e.g. `math::vector` for `src/math/vector.inf`.
+    pub name: String,
+    /// The path of the source file, as shown on the pages and used for source links.
+    pub path: String,
+    /// The source text, for the source pages.
+    pub source: String,
+    /// The text of the `//!` comments at the top of the file.
+    pub docs: String,
+    /// The items of the module, in source order.
+    pub items: Vec<Item>,
+}
+
+impl Module {
+    /// Parses `source` and extracts its documentation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DocError::Syntax`] if the source does not parse.
+    pub fn parse(
+        name: impl Into<String>,
+        path: impl Into<String>,
+        source: &str,
+    ) -> Result<Self, DocError> {
+        let tree = parse(source)?;
+        if let Some(error) = first_error(tree.root_node()) {
+            let position = error.start_position();
+            return Err(DocError::Syntax {
+                line: position.row + 1,
+                column: position.column + 1,
+            });
+        }
+        let lines: Vec<&str> = source.lines().collect();
+        let root = tree.root_node();
+        let mut cursor = root.walk();
+        let items = root
+            .named_children(&mut cursor)
+            .filter_map(|node| item(node, source, &lines, false))
+            .collect();
+        Ok(Self {
+            name: name.into(),
+            path: path.into(),
+            source: source.to_string(),
+            docs: module_docs(&lines),
+            items,
+        })
+    }
+}
+
+/// Returns the item declared by `node`, or `None` if it declares none, like a `use`.
+fn item(node: Node<'_>, source: &str, lines: &[&str], in_struct: bool) -> Option<Item> {
+    let name = node
+        .child_by_field_name("name")
+        .map(|name| text(name, source).to_string());
+    let public = node.child_by_field_name("visibility").is_some();
+    let (kind, signature, public, members) = match node.kind() {
+        "function_definition" => {
+            let end = node
+                .child_by_field_name("body")
+                .map_or(node.end_byte(), |body| body.start_byte());
+            let kind = if in_struct {
+                ItemKind::Method
+            } else {
+                ItemKind::Function
+            };
+            (kind, &source[node.start_byte()..end], public, Vec::new())
+        }
+        "external_function_definition" => (
+            ItemKind::ExternalFunction,
+            text(node, source),
+            false,
+            Vec::new(),
+        ),
+        "constant_definition" => (ItemKind::Constant, text(node, source), public, Vec::new()),
+        "type_definition_statement" => (ItemKind::Type, text(node, source), public, Vec::new()),
+        "struct_field" => (ItemKind::Field, text(node, source), true, Vec::new()),
+        "struct_definition" | "enum_definition" | "spec_definition" => {
+            let body = source[node.start_byte()..node.end_byte()]
+                .find('{')
+                .map_or(node.end_byte(), |offset| node.start_byte() + offset);
+            let kind = match node.kind() {
+                "struct_definition" => ItemKind::Struct,
+                "enum_definition" => ItemKind::Enum,
+                _ => ItemKind::Spec,
+            };
+            let public = public || kind == ItemKind::Spec;
+            let members = members(node, kind, source, lines);
+            (kind, &source[node.start_byte()..body], public, members)
+        }
+        _ => return None,
+    };
+    Some(Item {
+        kind,
+        name: name?,
+        signature: signature_line(signature),
+        docs: docs(node, lines),
+        line: node.start_position().row + 1,
+        public,
+        members,
+    })
+}
+
+/// Returns the members of the struct, enum or spec `node` of `kind`.
+fn members(node: Node<'_>, kind: ItemKind, source: &str, lines: &[&str]) -> Vec<Item> {
+    let mut cursor = node.walk();
+    if kind == ItemKind::Enum {
+        return node
+            .children_by_field_name("variant", &mut cursor)
+            .map(|variant| {
+                let name = text(variant, source).to_string();
+                Item {
+                    kind: ItemKind::Variant,
+                    signature: name.clone(),
+                    name,
+                    docs: docs(variant, lines),
+                    line: variant.start_position().row + 1,
+                    public: true,
+                    members: Vec::new(),
+                }
+            })
+            .collect();
+    }
+    let name = node.child_by_field_name("name").map(|name| name.id());
+    node.named_children(&mut cursor)
+        .filter(|child| Some(child.id()) != name)
+        .filter_map(|child| item(child, source, lines, kind == ItemKind::Struct))
+        .collect()
+}
+
+/// Returns the `///` comments on the lines directly above `node`, without the
+/// markers.
+///
+/// A node that does not start its line, like a variant after another one, has no doc
+/// comments: the lines above it document what starts the line.
+fn docs(node: Node<'_>, lines: &[&str]) -> String {
+    let position = node.start_position();
+    let starts_line = lines
+        .get(position.row)
+        .is_some_and(|line| line[..position.column.min(line.len())].trim().is_empty());
+    if !starts_line {
+        return String::new();
+    }
+    let mut docs: Vec<&str> = lines[..position.row]
+        .iter()
+        .rev()
+        .map(|line| line.trim())
+        .take_while(|line| line.starts_with("///") && !line.starts_with("////"))
+        .map(|line| strip_marker(line, "///"))
+        .collect();
+    docs.reverse();
+    docs.join("\n")
+}
+
+/// Returns the `//!` comments at the top of the file, without the markers.
+fn module_docs(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| line.trim())
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| line.starts_with("//!"))
+        .map(|line| strip_marker(line, "//!"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Removes `marker` and one following space from the comment `line`.
+fn strip_marker<'a>(line: &'a str, marker: &str) -> &'a str {
+    let rest = &line[marker.len()..];
+    rest.strip_prefix(' ').unwrap_or(rest)
+}
+
+/// Puts a declaration on one line: whitespace runs become single spaces, and the
+/// spaces a line break leaves inside brackets and before commas and `;` are removed.
+fn signature_line(declaration: &str) -> String {
+    let line = declaration.split_whitespace().collect::<Vec<_>>().join(" ");
+    line.trim_end_matches(';')
+        .replace("( ", "(")
+        .replace(" )", ")")
+        .replace("[ ", "[")
+        .replace(" ]", "]")
+        .replace(" ,", ",")
+        .trim()
+        .to_string()
+}
+
+/// Returns the source text of `node`.
+fn text<'a>(node: Node<'_>, source: &'a str) -> &'a str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+/// Parses `source` with the tree-sitter grammar of Inference.
+fn parse(source: &str) -> Result<Tree, DocError> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_inference::language())
+        .map_err(|e| DocError::Grammar(e.to_string()))?;
+    parser
+        .parse(source, None)
+        .ok_or_else(|| DocError::Grammar("the parser produced no tree".to_string()))
+}
+
+/// Returns the first error or missing node under `node`, in source order.
+fn first_error(node: Node<'_>) -> Option<Node<'_>> {
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    if !node.has_error() {
+        return None;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find_map(first_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_module(source: &str) -> Module {
+        Module::parse("main", "src/main.inf", source).unwrap()
+    }
+
+    #[test]
+    fn extracts_functions_with_docs_and_lines() {
+        let module = parse_module(
+            "//! The entry point.\n//! Second line.\n\n/// Adds one.\n///\n/// Never overflows.\n\
+             pub fn inc(x: i32) -> i32 {\n    return x + 1;\n}\n\n// not a doc comment\n\
+             fn helper() {}\n",
+        );
+        assert_eq!(module.docs, "The entry point.\nSecond line.");
+        assert_eq!(module.items.len(), 2);
+
+        let inc = &module.items[0];
+        assert_eq!(inc.kind, ItemKind::Function);
+        assert_eq!(inc.name, "inc");
+        assert_eq!(inc.signature, "pub fn inc(x: i32) -> i32");
+        assert_eq!(inc.docs, "Adds one.\n\nNever overflows.");
+        assert_eq!(inc.line, 7);
+        assert!(inc.public);
+
+        let helper = &module.items[1];
+        assert_eq!(helper.docs, "");
+        assert!(!helper.public);
+    }
+
+    #[test]
+    fn extracts_struct_members() {
+        let module = parse_module(
+            "/// A point.\npub struct Point {\n    /// Horizontal.\n    x: i32;\n\n    \
+             /// Returns x.\n    pub fn get_x(self) -> i32 {\n        return self.x;\n    }\n}\n",
+        );
+        let point = &module.items[0];
+        assert_eq!(point.kind, ItemKind::Struct);
+        assert_eq!(point.signature, "pub struct Point");
+        assert_eq!(point.docs, "A point.");
+        assert_eq!(point.members.len(), 2);
+        assert_eq!(point.members[0].kind, ItemKind::Field);
+        assert_eq!(point.members[0].signature, "x: i32");
+        assert_eq!(point.members[0].docs, "Horizontal.");
+        assert_eq!(point.members[1].kind, ItemKind::Method);
+        assert_eq!(point.members[1].signature, "pub fn get_x(self) -> i32");
+        assert_eq!(point.members[1].line, 7);
+    }
+
+    #[test]
+    fn extracts_enums_constants_and_types() {
+        let module = parse_module(
+            "/// Colors.\npub enum Color {\n    /// The first.\n    Red,\n    Green\n}\n\
+             /// The limit.\npub const LIMIT: i32 = 10;\ntype Grid = [[bool; 3]; 3];\n\
+             external fn sample() -> i32;\nuse std::math::{Max};\n",
+        );
+        let signatures: Vec<&str> = module
+            .items
+            .iter()
+            .map(|item| item.signature.as_str())
+            .collect();
+        assert_eq!(
+            signatures,
+            [
+                "pub enum Color",
+                "pub const LIMIT: i32 = 10",
+                "type Grid = [[bool; 3]; 3]",
+                "external fn sample() -> i32"
+            ]
+        );
+        let variants = &module.items[0].members;
+        assert_eq!(variants[0].name, "Red");
+        assert_eq!(variants[0].docs, "The first.");
+        assert_eq!(variants[1].docs, "");
+        assert_eq!(module.items[1].docs, "The limit.");
+    }
+
+    #[test]
+    fn members_on_one_line_take_no_docs_from_above() {
+        let module = parse_module("/// Colors.\npub enum Color { Red, Green }\n");
+        assert_eq!(module.items[0].docs, "Colors.");
+        assert!(
+            module.items[0]
+                .members
+                .iter()
+                .all(|variant| variant.docs.is_empty())
+        );
+    }
+
+    #[test]
+    fn joins_multiline_signatures() {
+        let module =
+            parse_module("fn add(\n    a: i32,\n    b: i32\n) -> i32 {\n    return a + b;\n}\n");
+        assert_eq!(module.items[0].signature, "fn add(a: i32, b: i32) -> i32");
+    }
+
+    #[test]
+    fn rejects_syntax_errors() {
+        let result = Module::parse("main", "src/main.inf", "fn main( {");
+        assert!(matches!(result, Err(DocError::Syntax { line: 1, .. })));
+    }
+}
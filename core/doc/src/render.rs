@@ -0,0 +1,277 @@
+//! Rendering of extracted documentation as HTML or Markdown pages.
+//!
+//! Every format produces an index page listing the modules, and one page per module.
+//! HTML adds a page per source file with an anchor on every line, so item headings
+//! link to `src/<module>.html#L<line>`. Markdown links to the source files themselves,
+//! through the `source_root` given to [`render`].
+
+use std::fmt::Write;
+
+use crate::{Item, Module};
+
+/// The format of the rendered pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+impl Format {
+    /// Returns the file extension of pages in this format.
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Markdown => "md",
+        }
+    }
+}
+
+/// A rendered page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// The path of the page, relative to the documentation directory.
+    pub path: String,
+    pub contents: String,
+}
+
+/// Renders the documentation of `modules` under the title `title`.
+///
+/// `source_root` is the path from the documentation directory to the directory the
+/// [`Module::path`]s are relative to; only Markdown pages use it, for source links.
+#[must_use]
+pub fn render(title: &str, modules: &[Module], format: Format, source_root: &str) -> Vec<Page> {
+    let mut pages = vec![Page {
+        path: format!("index.{}", format.extension()),
+        contents: match format {
+            Format::Html => html_index(title, modules),
+            Format::Markdown => markdown_index(title, modules),
+        },
+    }];
+    for module in modules {
+        match format {
+            Format::Html => {
+                pages.push(Page {
+                    path: page_name(module, format),
+                    contents: html_module(title, module),
+                });
+                pages.push(Page {
+                    path: format!("src/{}", page_name(module, format)),
+                    contents: html_source(title, module),
+                });
+            }
+            Format::Markdown => pages.push(Page {
+                path: page_name(module, format),
+                contents: markdown_module(module, source_root),
+            }),
+        }
+    }
+    pages
+}
+
+/// Returns the file name of the page of `module`, e.g. `math.vector.html` for
+/// `math::vector`.
+fn page_name(module: &Module, format: Format) -> String {
+    format!("{}.{}", module.name.replace("::", "."), format.extension())
+}
+
+/// Returns the first line of `docs`, used as a summary in lists.
+fn summary(docs: &str) -> &str {
+    docs.lines().next().unwrap_or_default()
+}
+
+const STYLE: &str = "body{font-family:sans-serif;max-width:60em;margin:auto;padding:1em}\
+pre,code{font-family:monospace}pre.signature{background:#f4f4f4;padding:.5em}\
+.member{margin-left:2em}.source a{color:#999;display:inline-block;width:4em}";
+
+fn html_page(title: &str, heading: &str, prefix: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{} - {}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<nav><a href=\"{prefix}index.html\">{}</a></nav>\n\
+         <h1>{}</h1>\n{body}</body>\n</html>\n",
+        escape(heading),
+        escape(title),
+        escape(title),
+        escape(heading),
+    )
+}
+
+fn html_index(title: &str, modules: &[Module]) -> String {
+    let mut body = String::from("<h2>Modules</h2>\n<ul>\n");
+    for module in modules {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"{}\">{}</a> {}</li>",
+            page_name(module, Format::Html),
+            escape(&module.name),
+            escape(summary(&module.docs)),
+        );
+    }
+    body.push_str("</ul>\n");
+    html_page(title, title, "", &body)
+}
+
+fn html_module(title: &str, module: &Module) -> String {
+    let mut body = html_docs(&module.docs);
+    let source = format!("src/{}", page_name(module, Format::Html));
+    for item in &module.items {
+        html_item(&mut body, item, &source, 2);
+    }
+    html_page(title, &module.name, "", &body)
+}
+
+fn html_item(body: &mut String, item: &Item, source: &str, level: usize) {
+    let class = if level > 2 { " class=\"member\"" } else { "" };
+    let _ = writeln!(
+        body,
+        "<section id=\"{}\"{class}>\n<h{level}>{} <code>{}</code> \
+         <a href=\"{source}#L{}\">source</a></h{level}>\n\
+         <pre class=\"signature\">{}</pre>",
+        escape(&item.name),
+        item.kind.label(),
+        escape(&item.name),
+        item.line,
+        escape(&item.signature),
+    );
+    body.push_str(&html_docs(&item.docs));
+    for member in &item.members {
+        html_item(body, member, source, 3);
+    }
+    body.push_str("</section>\n");
+}
+
+/// Renders doc comments as paragraphs separated by blank lines.
+fn html_docs(docs: &str) -> String {
+    docs.split("\n\n")
+        .filter(|paragraph| !paragraph.trim().is_empty())
+        .map(|paragraph| format!("<p>{}</p>\n", escape(paragraph)))
+        .collect()
+}
+
+fn html_source(title: &str, module: &Module) -> String {
+    let mut body = String::from("<pre class=\"source\">\n");
+    for (index, line) in module.source.lines().enumerate() {
+        let number = index + 1;
+        let _ = writeln!(
+            body,
+            "<span id=\"L{number}\"><a href=\"#L{number}\">{number}</a>{}</span>",
+            escape(line),
+        );
+    }
+    body.push_str("</pre>\n");
+    html_page(title, &module.path, "../", &body)
+}
+
+fn markdown_index(title: &str, modules: &[Module]) -> String {
+    let mut page = format!("# {title}\n\n## Modules\n\n");
+    for module in modules {
+        let _ = writeln!(
+            page,
+            "- [`{}`]({}) {}",
+            module.name,
+            page_name(module, Format::Markdown),
+            summary(&module.docs),
+        );
+    }
+    page.trim_end().to_string() + "\n"
+}
+
+fn markdown_module(module: &Module, source_root: &str) -> String {
+    let mut page = format!("# `{}`\n\n", module.name);
+    if !module.docs.is_empty() {
+        let _ = write!(page, "{}\n\n", module.docs);
+    }
+    let source = format!("{}/{}", source_root.trim_end_matches('/'), module.path);
+    for item in &module.items {
+        markdown_item(&mut page, item, &source, 2);
+    }
+    page.trim_end().to_string() + "\n"
+}
+
+fn markdown_item(page: &mut String, item: &Item, source: &str, level: usize) {
+    let _ = write!(
+        page,
+        "{} {} `{}` ([source]({source}#L{}))\n\n```inference\n{}\n```\n\n",
+        "#".repeat(level),
+        item.kind.label(),
+        item.name,
+        item.line,
+        item.signature,
+    );
+    if !item.docs.is_empty() {
+        let _ = write!(page, "{}\n\n", item.docs);
+    }
+    for member in &item.members {
+        markdown_item(page, member, source, level + 1);
+    }
+}
+
+/// Escapes the HTML special characters of `text`.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modules() -> Vec<Module> {
+        vec![
+            Module::parse(
+                "math::ops",
+                "src/math/ops.inf",
+                "//! Arithmetic.\n\n/// Adds <two> numbers.\npub fn add(a: i32, b: i32) -> i32 {\n    \
+                 return a + b;\n}\n",
+            )
+            .unwrap(),
+        ]
+    }
+
+    #[test]
+    fn html_pages_link_items_to_source_lines() {
+        let pages = render("demo", &modules(), Format::Html, "../..");
+        let paths: Vec<&str> = pages.iter().map(|page| page.path.as_str()).collect();
+        assert_eq!(paths, ["index.html", "math.ops.html", "src/math.ops.html"]);
+        assert!(
+            pages[0]
+                .contents
+                .contains("<a href=\"math.ops.html\">math::ops</a> Arithmetic.")
+        );
+        assert!(
+            pages[1]
+                .contents
+                .contains("<a href=\"src/math.ops.html#L4\">source</a>")
+        );
+        assert!(
+            pages[1]
+                .contents
+                .contains("<p>Adds &lt;two&gt; numbers.</p>")
+        );
+        assert!(pages[2].contents.contains("<span id=\"L4\">"));
+    }
+
+    #[test]
+    fn markdown_pages_link_items_to_source_files() {
+        let pages = render("demo", &modules(), Format::Markdown, "../..");
+        assert_eq!(pages.len(), 2);
+        assert_eq!(
+            pages[0].contents,
+            "# demo\n\n## Modules\n\n- [`math::ops`](math.ops.md) Arithmetic.\n"
+        );
+        assert_eq!(
+            pages[1].contents,
+            "# `math::ops`\n\nArithmetic.\n\n## function `add` ([source](../../src/math/ops.inf#L4))\n\n\
+             ```inference\npub fn add(a: i32, b: i32) -> i32\n```\n\nAdds <two> numbers.\n"
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Source Formatter for the Inference Programming Language
+//!
+//! This crate formats `.inf` source files in one canonical style, so that code
+//! reviews and diffs are not about whitespace:
+//!
+//! - Four spaces of indentation per block
+//! - One item, statement, struct member or enum variant per line
+//! - Single spaces around binary operators, after commas and colons, and before
+//!   opening braces; none inside parentheses and brackets
+//! - Blank lines between functions, structs, enums and specs; elsewhere at most one
+//!   blank line, kept where the source has one
+//!
+//! Lines are not wrapped: a long expression stays on one line.
+//!
+//! ## Comment Preservation
+//!
+//! The formatter prints the syntax tree of the tree-sitter grammar rather than the
+//! arena AST of `inference-ast`, which keeps no comments. The grammar leaves comments
+//! out of the tree as extras, so the printer recovers them from the text between
+//! consecutive tokens: a comment on a line of its own stays on a line of its own
+//! before the code that followed it, and a comment after code stays at the end of
+//! that line. Doc comments (`///`) are kept the same way.
+//!
+//! ## Safety
+//!
+//! A source with syntax errors is not formatted, see [`FormatError::Syntax`]. Every
+//! formatted source is parsed again and compared token by token, comments included,
+//! with the original, so formatting never changes a program.
+//!
+//! ## Quick Start
+//!
+//! ```
+//! let formatted = inference_fmt::format("fn main()->i32{return 1+2;}")?;
+//! assert_eq!(formatted, "fn main() -> i32 {\n    return 1 + 2;\n}\n");
+//! # Ok::<(), inference_fmt::FormatError>(())
+//! ```
+
+mod printer;
+
+use printer::{Printer, tokens};
+use thiserror::Error;
+use tree_sitter::{Node, Parser, Tree};
+
+/// Reasons a source cannot be formatted.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FormatError {
+    /// The tree-sitter grammar of Inference could not be loaded.
+    #[error("failed to load the Inference grammar: {0}")]
+    Grammar(String),
+
+    /// The source does not parse; `line` and `column` are 1-based and locate the first
+    /// syntax error.
+    #[error("{line}:{column}: syntax error, the source was not formatted")]
+    Syntax { line: usize, column: usize },
+
+    /// The formatted source does not have the tokens of the original one. This is a bug
+    /// in the formatter; the source is left as it is.
+    #[error("formatting would change the source's tokens; the source was not formatted")]
+    TokensChanged,
+}
+
+/// Formats an Inference source file, returning the formatted text.
+///
+/// The result ends with a single newline, unless the source is empty or blank, which
+/// formats to an empty string. Formatting is idempotent: formatting the result again
+/// returns it unchanged.
+///
+/// # Errors
+///
+/// Returns [`FormatError::Syntax`] if the source does not parse, and
+/// [`FormatError::TokensChanged`] if the formatted source would differ from the original
+/// in anything but whitespace.
+pub fn format(source: &str) -> Result<String, FormatError> {
+    let tree = parse(source)?;
+    if let Some(error) = first_error(tree.root_node()) {
+        let position = error.start_position();
+        return Err(FormatError::Syntax {
+            line: position.row + 1,
+            column: position.column + 1,
+        });
+    }
+    let formatted = Printer::new(source).print(tree.root_node());
+    let reparsed = parse(&formatted)?;
+    if reparsed.root_node().has_error()
+        || tokens(source, tree.root_node()) != tokens(&formatted, reparsed.root_node())
+    {
+        return Err(FormatError::TokensChanged);
+    }
+    Ok(formatted)
+}
+
+/// Parses `source` with the tree-sitter grammar of Inference.
+fn parse(source: &str) -> Result<Tree, FormatError> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&tree_sitter_inference::language())
+        .map_err(|e| FormatError::Grammar(e.to_string()))?;
+    parser
+        .parse(source, None)
+        .ok_or_else(|| FormatError::Grammar("the parser produced no tree".to_string()))
+}
+
+/// Returns the first error or missing node under `node`, in source order.
+fn first_error(node: Node<'_>) -> Option<Node<'_>> {
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    if !node.has_error() {
+        return None;
+    }
+    let mut cursor = node.walk();
+    node.children(&mut cursor).find_map(first_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_formats(source: &str, expected: &str) {
+        let formatted = format(source).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(
+            format(&formatted).unwrap(),
+            expected,
+            "formatting is idempotent"
+        );
+    }
+
+    #[test]
+    fn formats_functions_and_statements() {
+        assert_formats(
+            "pub fn add(a:i32,b:i32)->i32{let mut total:i32=a+b;total=total*2;return total;}",
+            "pub fn add(a: i32, b: i32) -> i32 {\n    let mut total: i32 = a + b;\n    \
+             total = total * 2;\n    return total;\n}\n",
+        );
+    }
+
+    #[test]
+    fn formats_control_flow_and_blocks() {
+        assert_formats(
+            "fn f(x: i32) -> i32 { if x>0 { return -x; } else if x==0 { return 0; } else { \
+             loop x<0 { x=x+1; } } forall { let y: i32 = @; assume { assert(y > 0); } } \
+             return x; }",
+            "fn f(x: i32) -> i32 {\n    if x > 0 {\n        return -x;\n    } else if x == 0 {\n        \
+             return 0;\n    } else {\n        loop x < 0 {\n            x = x + 1;\n        }\n    \
+             }\n    forall {\n        let y: i32 = @;\n        assume {\n            \
+             assert(y > 0);\n        }\n    }\n    return x;\n}\n",
+        );
+    }
+
+    #[test]
+    fn formats_definitions() {
+        assert_formats(
+            "use std::math::{ Max , Min };\nconst LIMIT : i32 = 10;\ntype Grid = [ [ bool ; 3 ] ; 3 ];\n\
+             enum Color { Red, Green }\nstruct Point { x: i32; fn get_x(self) -> i32 { return self.x; } }\n\
+             external fn sample() -> i32;",
+            "use std::math::{Max, Min};\nconst LIMIT: i32 = 10;\ntype Grid = [[bool; 3]; 3];\n\n\
+             enum Color {\n    Red,\n    Green\n}\n\nstruct Point {\n    x: i32;\n\n    \
+             fn get_x(self) -> i32 {\n        return self.x;\n    }\n}\n\nexternal fn sample() -> i32;\n",
+        );
+    }
+
+    #[test]
+    fn formats_expressions() {
+        assert_formats(
+            "fn f(p: Point, arr: [i32; 3]) -> i32 { let q: Point = Point{x:arr[0],y:!(p.y>1)}; \
+             let c: Color = Color::Red; return identity i32'( max( a: 1, b: ~2 ) ); }",
+            "fn f(p: Point, arr: [i32; 3]) -> i32 {\n    let q: Point = Point { x: arr[0], y: !(p.y > 1) };\n    \
+             let c: Color = Color::Red;\n    return identity i32'(max(a: 1, b: ~2));\n}\n",
+        );
+    }
+
+    #[test]
+    fn formats_generic_definitions() {
+        assert_formats(
+            "fn pick T' U'(x: T, y: U) -> T { return x; }",
+            "fn pick T' U'(x: T, y: U) -> T {\n    return x;\n}\n",
+        );
+    }
+
+    #[test]
+    fn keeps_comments() {
+        assert_formats(
+            "// header\n\n/// Adds one.\nfn inc(x: i32) -> i32 { // trailing\n  // leading\n  \
+             return x + 1; // after\n  // last\n}\n// end\n",
+            "// header\n\n/// Adds one.\nfn inc(x: i32) -> i32 { // trailing\n    // leading\n    \
+             return x + 1; // after\n    // last\n}\n// end\n",
+        );
+    }
+
+    #[test]
+    fn keeps_comments_in_empty_blocks_and_expressions() {
+        assert_formats(
+            "fn f() { // todo\n}\nfn g() -> i32 { return 1 + // one\n 2; }",
+            "fn f() { // todo\n}\n\nfn g() -> i32 {\n    return 1 + // one\n    2;\n}\n",
+        );
+    }
+
+    #[test]
+    fn keeps_single_blank_lines_between_statements() {
+        assert_formats(
+            "fn f() {\n\n    let a: i32 = 1;\n\n\n    let b: i32 = 2;\n    let c: i32 = 3;\n\n}\n",
+            "fn f() {\n    let a: i32 = 1;\n\n    let b: i32 = 2;\n    let c: i32 = 3;\n}\n",
+        );
+    }
+
+    #[test]
+    fn separates_functions_with_blank_lines() {
+        assert_formats(
+            "fn a() {}\nfn b() {}\nconst X: i32 = 1;",
+            "fn a() {}\n\nfn b() {}\n\nconst X: i32 = 1;\n",
+        );
+    }
+
+    #[test]
+    fn formats_empty_sources() {
+        assert_formats("", "");
+        assert_formats("\n\n  \n", "");
+    }
+
+    #[test]
+    fn rejects_syntax_errors() {
+        assert_eq!(
+            format("fn f() {\n    let x = ;\n}\n"),
+            Err(FormatError::Syntax { line: 2, column: 5 })
+        );
+    }
+}
@@ -0,0 +1,350 @@
+//! Printer of the tree-sitter syntax tree.
+//!
+//! The printer visits every token of the tree in source order and decides the
+//! whitespace before it: nothing, a space, or a line break. Nodes holding a list of
+//! lines, such as blocks and struct bodies, put each member on a line of its own and
+//! indent it; within a line, spacing depends on the token and the one before it.
+//!
+//! Comments are not part of the tree. Before printing a token, the printer reads the
+//! text between it and the previous token, which holds only whitespace and comments,
+//! and prints the comments it finds there.
+
+use tree_sitter::Node;
+
+/// Indentation of one nesting level.
+const INDENT: &str = "    ";
+
+/// Nodes printed as their source text: their tokens are hidden from the tree, or they
+/// are a single token made of several.
+const ATOMIC: &[&str] = &[
+    "identifier",
+    "string_literal",
+    "number_literal",
+    "bool_literal",
+    "unit_literal",
+    "type_unit",
+    "unary_not",
+    "unary_minus",
+    "unary_bitnot",
+];
+
+/// Nodes printed with one member per line.
+const CONTAINERS: &[&str] = &[
+    "source_file",
+    "block",
+    "struct_definition",
+    "enum_definition",
+    "spec_definition",
+];
+
+/// Definitions separated from their neighbors by a blank line.
+const MULTILINE_DEFINITIONS: &[&str] = &[
+    "function_definition",
+    "struct_definition",
+    "enum_definition",
+    "spec_definition",
+];
+
+/// Whether a line break is followed by a blank line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Blank {
+    Never,
+    /// If the source has a blank line at this place.
+    Preserve,
+    Always,
+}
+
+/// Whitespace printed before a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    None,
+    Space,
+    Line(Blank),
+}
+
+/// Prints a syntax tree in the canonical style.
+pub(crate) struct Printer<'a> {
+    source: &'a str,
+    out: String,
+    /// End of the last printed token or comment in `source`.
+    pos: usize,
+    indent: usize,
+    at_line_start: bool,
+    /// Kind of the last printed token and of its parent.
+    prev: Option<(&'static str, Option<&'static str>)>,
+    /// Separator of the next token, if set by the enclosing node.
+    next: Option<Separator>,
+    /// True after the opening brace of a container, where blank lines are dropped.
+    opened: bool,
+    /// True before the closing brace of a container, which ends its indentation.
+    closing: bool,
+}
+
+impl<'a> Printer<'a> {
+    pub(crate) fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            out: String::new(),
+            pos: 0,
+            indent: 0,
+            at_line_start: true,
+            prev: None,
+            next: None,
+            opened: false,
+            closing: false,
+        }
+    }
+
+    /// Prints the tree of `root`, the `source_file` node of the source, and returns the
+    /// formatted text.
+    pub(crate) fn print(mut self, root: Node<'a>) -> String {
+        self.node(root);
+        self.comments(self.source.len(), Separator::Line(Blank::Preserve));
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.out
+    }
+
+    fn node(&mut self, node: Node<'a>) {
+        if node.child_count() == 0 || ATOMIC.contains(&node.kind()) {
+            self.token(node);
+        } else if CONTAINERS.contains(&node.kind()) {
+            self.container(node);
+        } else {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                self.node(child);
+            }
+        }
+    }
+
+    /// Prints a node whose members, between braces or in the whole source file, go on
+    /// lines of their own.
+    fn container(&mut self, node: Node<'a>) {
+        let kind = node.kind();
+        let mut inside = kind == "source_file";
+        let mut previous: Option<Node<'a>> = None;
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "{" if !inside => {
+                    self.token(child);
+                    self.indent += 1;
+                    self.opened = true;
+                    inside = true;
+                }
+                "}" if inside => {
+                    self.closing = true;
+                    self.next = Some(if previous.is_some() {
+                        Separator::Line(Blank::Never)
+                    } else {
+                        Separator::None
+                    });
+                    self.token(child);
+                    inside = false;
+                }
+                "," | ";" if inside => {
+                    self.next = Some(Separator::None);
+                    self.token(child);
+                }
+                _ if inside => {
+                    self.next = Some(Separator::Line(member_blank(kind, previous, child)));
+                    self.node(child);
+                    previous = Some(child);
+                }
+                _ => self.node(child),
+            }
+        }
+    }
+
+    /// Prints a token, after the comments before it.
+    fn token(&mut self, node: Node<'a>) {
+        let separator = self.next.take().unwrap_or_else(|| self.separator(node));
+        let (newlines, commented) = self.comments(node.start_byte(), separator);
+        if self.closing {
+            self.indent -= 1;
+            self.closing = false;
+        }
+        let separator = match separator {
+            Separator::None | Separator::Space if commented => Separator::Line(Blank::Never),
+            Separator::Line(Blank::Always) if commented => Separator::Line(Blank::Preserve),
+            separator => separator,
+        };
+        self.separate(separator, newlines);
+        self.write(&self.source[node.byte_range()]);
+        self.pos = node.end_byte();
+        self.prev = Some((node.kind(), node.parent().map(|parent| parent.kind())));
+        self.opened = false;
+    }
+
+    /// Returns the separator of `node` within a line, from the previous token.
+    fn separator(&self, node: Node<'a>) -> Separator {
+        let Some((prev, prev_parent)) = self.prev else {
+            return Separator::None;
+        };
+        let parent = node.parent().map(|parent| parent.kind());
+        match node.kind() {
+            "," | ";" | ":" | "." | "::" | "'" | ")" | "]" => return Separator::None,
+            "(" if matches!(parent, Some("argument_list" | "function_call_expression"))
+                || prev == "assert" =>
+            {
+                return Separator::None;
+            }
+            "[" if parent == Some("array_index_access_expression") => return Separator::None,
+            "}" if prev == "{" || parent == Some("use_directive") => return Separator::None,
+            _ => {}
+        }
+        match prev {
+            "(" | "[" | "." | "::" | "unary_not" | "unary_minus" | "unary_bitnot" => {
+                Separator::None
+            }
+            "{" if prev_parent == Some("use_directive") => Separator::None,
+            _ => Separator::Space,
+        }
+    }
+
+    /// Prints the comments between the last printed token and `end`, where the next token
+    /// starts, given the separator of that token.
+    ///
+    /// Returns the number of line breaks between the last comment, or the last token if
+    /// there is none, and `end`, and whether any comment was printed.
+    fn comments(&mut self, end: usize, separator: Separator) -> (usize, bool) {
+        let gap = &self.source[self.pos..end];
+        let mut newlines = 0;
+        let mut commented = false;
+        let mut rest = gap;
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("//") {
+                let len = rest.find('\n').unwrap_or(rest.len());
+                let force_blank = !commented && separator == Separator::Line(Blank::Always);
+                self.comment(rest[..len].trim_end(), newlines, force_blank);
+                commented = true;
+                newlines = 0;
+                rest = &rest[len..];
+            } else {
+                if c == '\n' {
+                    newlines += 1;
+                }
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        self.pos = end;
+        (newlines, commented)
+    }
+
+    /// Prints a comment, at the end of the current line if no line break precedes it in
+    /// the source, or else on a line of its own.
+    fn comment(&mut self, text: &str, newlines: usize, force_blank: bool) {
+        if newlines == 0 && !self.out.is_empty() {
+            self.out.push(' ');
+            self.out.push_str(text);
+        } else {
+            let blank = (newlines >= 2 || force_blank) && !self.opened;
+            self.separate(
+                Separator::Line(if blank { Blank::Always } else { Blank::Never }),
+                newlines,
+            );
+            self.write(text);
+        }
+        self.opened = false;
+    }
+
+    /// Prints a separator; `newlines` is the number of line breaks at this place in the
+    /// source, which decides [`Blank::Preserve`].
+    fn separate(&mut self, separator: Separator, newlines: usize) {
+        if self.out.is_empty() {
+            return;
+        }
+        match separator {
+            Separator::None => {}
+            Separator::Space => self.out.push(' '),
+            Separator::Line(blank) => {
+                self.newline();
+                let blank = match blank {
+                    Blank::Never => false,
+                    Blank::Preserve => newlines >= 2,
+                    Blank::Always => true,
+                };
+                if blank && !self.opened {
+                    self.newline();
+                }
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        self.at_line_start = true;
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.at_line_start {
+            for _ in 0..self.indent {
+                self.out.push_str(INDENT);
+            }
+            self.at_line_start = false;
+        }
+        self.out.push_str(text);
+    }
+}
+
+/// Returns whether a blank line separates `member` from the `previous` member of a
+/// container of kind `container`.
+fn member_blank(container: &str, previous: Option<Node<'_>>, member: Node<'_>) -> Blank {
+    let Some(previous) = previous else {
+        return Blank::Preserve;
+    };
+    let multiline = match container {
+        "source_file" | "spec_definition" => {
+            MULTILINE_DEFINITIONS.contains(&previous.kind())
+                || MULTILINE_DEFINITIONS.contains(&member.kind())
+        }
+        "struct_definition" => {
+            previous.kind() == "function_definition" || member.kind() == "function_definition"
+        }
+        _ => false,
+    };
+    if multiline {
+        Blank::Always
+    } else {
+        Blank::Preserve
+    }
+}
+
+/// Returns the tokens and comments of the tree of `root` over `source`, in source order,
+/// which formatting must not change.
+pub(crate) fn tokens<'a>(source: &'a str, root: Node<'_>) -> Vec<&'a str> {
+    fn collect<'a>(source: &'a str, node: Node<'_>, pos: &mut usize, tokens: &mut Vec<&'a str>) {
+        if node.child_count() == 0 || ATOMIC.contains(&node.kind()) {
+            gap(&source[*pos..node.start_byte()], tokens);
+            tokens.push(&source[node.byte_range()]);
+            *pos = node.end_byte();
+            return;
+        }
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect(source, child, pos, tokens);
+        }
+    }
+
+    /// Adds the comments of a gap between tokens, and anything else but whitespace.
+    fn gap<'a>(text: &'a str, tokens: &mut Vec<&'a str>) {
+        let mut rest = text.trim_start();
+        while !rest.is_empty() {
+            let len = if rest.starts_with("//") {
+                rest.find('\n').unwrap_or(rest.len())
+            } else {
+                rest.find(char::is_whitespace).unwrap_or(rest.len())
+            };
+            tokens.push(rest[..len].trim_end());
+            rest = rest[len..].trim_start();
+        }
+    }
+
+    let mut pos = 0;
+    let mut tokens = Vec::new();
+    collect(source, root, &mut pos, &mut tokens);
+    gap(&source[pos..], &mut tokens);
+    tokens
+}
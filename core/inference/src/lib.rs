@@ -19,12 +19,12 @@
 //! ## Quick Start
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, codegen};
+//! use inference::{parse, type_check, codegen, CodegenOptions};
 //!
 //! fn compile(source_code: &str) -> anyhow::Result<Vec<u8>> {
 //!     let arena = parse(source_code)?;
 //!     let typed_context = type_check(arena)?;
-//!     let wasm_bytes = codegen(&typed_context)?;
+//!     let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 //!     Ok(wasm_bytes)
 //! }
 //! ```
@@ -70,14 +70,14 @@
 //! 5. **Infer variables**: Type-check function bodies and local variables
 //!
 //! The result is a [`TypedContext`] that maps AST nodes to their inferred types.
+//! Modules of dependencies, loaded with [`load_dependencies`], are made available to
+//! imports by [`type_check_with_prelude`].
 //!
 //! [`TypedContext`]: inference_type_checker::typed_context::TypedContext
 //!
 //! ### Phase 3: Analyze
 //!
-//! Performs semantic analysis on the typed AST. This phase is currently under
-//! active development (WIP) and serves as a placeholder for future semantic
-//! analysis passes.
+//! Performs semantic analysis on the typed AST and returns non-fatal warnings.
 //!
 //! ```rust,no_run
 //! use inference::{parse, type_check, analyze};
@@ -85,23 +85,24 @@
 //! let source = "fn main() { return 0; }";
 //! let arena = parse(source)?;
 //! let typed_context = type_check(arena)?;
-//! analyze(&typed_context)?;
+//! let warnings = analyze(&typed_context)?;
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 //!
-//! **Status**: Work in progress. Currently returns `Ok(())` without performing checks.
+//! Reports unused variables, unused parameters, and private functions and types
+//! that are never referenced, as structured warnings.
 //!
 //! ### Phase 4: Codegen
 //!
 //! Generates WebAssembly binary format from the typed AST.
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, codegen};
+//! use inference::{parse, type_check, codegen, CodegenOptions};
 //!
 //! let source = "fn factorial(n: i32) -> i32 { if n <= 1 { return 1; } else { return n * factorial(n - 1); } }";
 //! let arena = parse(source)?;
 //! let typed_context = type_check(arena)?;
-//! let wasm_bytes = codegen(&typed_context)?;
+//! let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 //!
@@ -113,17 +114,19 @@
 //! - `assume { }` - Precondition filtering blocks
 //! - `unique { }` - Uniqueness constraint blocks
 //!
+//! [`llvm_ir`] returns the LLVM IR of the module as text instead of compiling it.
+//!
 //! ### Phase 5: WASM to Rocq Translation
 //!
 //! Translates WebAssembly binary to Rocq (Coq) verification code.
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, codegen, wasm_to_v};
+//! use inference::{parse, type_check, codegen, wasm_to_v, CodegenOptions};
 //!
 //! let source = "fn is_even(n: i32) -> bool { return n % 2 == 0; }";
 //! let arena = parse(source)?;
 //! let typed_context = type_check(arena)?;
-//! let wasm_bytes = codegen(&typed_context)?;
+//! let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 //! let rocq_code = wasm_to_v("MyModule", &wasm_bytes)?;
 //! # Ok::<(), anyhow::Error>(())
 //! ```
@@ -153,7 +156,7 @@
 //! │                                               └─────────────┘
 //! └─────────────────────────────────────────────────────────────┘
 //!          ↓              ↓              ↓              ↓
-//!   inference_ast  type_checker  (WIP)  wasm_codegen  wasm_to_v
+//!   inference_ast  type_checker   analyzer   wasm_codegen  wasm_to_v
 //! ```
 //!
 //! ## Error Handling
@@ -177,25 +180,27 @@
 //! ### Standard Compilation
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, analyze, codegen};
+//! use inference::{parse, type_check, analyze, codegen, CodegenOptions};
 //!
 //! fn compile_to_wasm(source_code: &str) -> anyhow::Result<Vec<u8>> {
 //!     let arena = parse(source_code)?;
 //!     let typed_context = type_check(arena)?;
-//!     analyze(&typed_context)?;
-//!     codegen(&typed_context)
+//!     for warning in analyze(&typed_context)? {
+//!         eprintln!("warning: {warning}");
+//!     }
+//!     codegen(&typed_context, &CodegenOptions::default())
 //! }
 //! ```
 //!
 //! ### Verification Workflow
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, codegen, wasm_to_v};
+//! use inference::{parse, type_check, codegen, wasm_to_v, CodegenOptions};
 //!
 //! fn compile_to_rocq(source_code: &str, module_name: &str) -> anyhow::Result<String> {
 //!     let arena = parse(source_code)?;
 //!     let typed_context = type_check(arena)?;
-//!     let wasm = codegen(&typed_context)?;
+//!     let wasm = codegen(&typed_context, &CodegenOptions::default())?;
 //!     wasm_to_v(module_name, &wasm)
 //! }
 //! ```
@@ -203,7 +208,7 @@
 //! ### Non-Deterministic Program Example
 //!
 //! ```rust,no_run
-//! use inference::{parse, type_check, codegen};
+//! use inference::{parse, type_check, codegen, CodegenOptions};
 //!
 //! fn compile_nondet_example() -> anyhow::Result<Vec<u8>> {
 //!     let source = r#"
@@ -221,18 +226,17 @@
 //!
 //!     let arena = parse(source)?;
 //!     let typed_context = type_check(arena)?;
-//!     codegen(&typed_context)
+//!     codegen(&typed_context, &CodegenOptions::default())
 //! }
 //! ```
 //!
 //! ## Limitations
 //!
-//! - **Single-file support**: Multi-file compilation is not yet implemented.
-//!   The AST expects a single source file as input.
-//! - **Analyze phase**: The semantic analysis phase is work-in-progress and
-//!   currently returns `Ok(())` without performing any checks.
-//! - **External dependencies**: Code generation requires `inf-llc` and `rust-lld`
-//!   binaries in the `external/bin/` directory.
+//! - **Modules**: [`parse_project`] compiles the `.inf` files of a project into one
+//!   module, but module declarations are not supported yet, so all files share one
+//!   namespace. Debug information and source maps need a single source file.
+//! - **External dependencies**: Code generation requires the `rust-lld` binary
+//!   in the `external/bin/` directory.
 //!
 //! ## CLI Tools
 //!
@@ -251,6 +255,7 @@
 //! - [`inference_ast::builder::Builder`] - AST construction from tree-sitter CST
 //! - [`inference_type_checker::TypeCheckerBuilder`] - Type checking entry point
 //! - [`inference_type_checker::typed_context::TypedContext`] - Type information storage
+//! - [`inference_analyzer::analyze`] - Semantic analysis entry point
 //! - [`inference_wasm_codegen::codegen`] - WebAssembly code generation entry point
 //! - [`inference_wasm_to_v_translator::wasm_parser`] - WASM to Rocq translation
 //!
@@ -261,8 +266,27 @@
 //! - [Tree-sitter Grammar](https://github.com/Inferara/tree-sitter-inference)
 //! - [LLVM Intrinsics for Non-deterministic Instructions](https://github.com/Inferara/llvm-project/pull/2)
 
-use inference_ast::{arena::Arena, builder::Builder};
-use inference_type_checker::typed_context::TypedContext;
+use inference_ast::{builder::Builder, parser_context::ParserContext};
+use std::path::{Path, PathBuf};
+
+pub use inference_analyzer::warnings::AnalysisWarning;
+pub use inference_ast::{
+    arena::Arena,
+    errors::{AstError, SyntaxError, SyntaxErrors},
+    extern_prelude::ExternPrelude,
+    nodes::Location,
+    parser_context::MANIFEST_FILE,
+};
+pub use inference_type_checker::{
+    errors::{RelatedNote, TypeCheckError, TypeCheckErrors},
+    typed_context::TypedContext,
+    warnings::TypeCheckWarning,
+};
+pub use inference_wasm_codegen::{
+    AssertStrategy, Backend, BoundsChecks, CodegenError, CodegenErrorKind, CodegenErrors,
+    CodegenOptions, Exports, ImportName, InvalidModule, Linker, StackChecks, TargetFeatures,
+    VerificationMetadata,
+};
 
 /// Parses source code and builds an arena-based Abstract Syntax Tree.
 ///
@@ -337,7 +361,8 @@ use inference_type_checker::typed_context::TypedContext;
 /// - The [`Builder`] encounters malformed nodes during AST construction
 ///
 /// The error collection mechanism reports all parsing errors at once rather than
-/// failing on the first error, enabling faster iteration during development.
+/// failing on the first error, enabling faster iteration during development. The
+/// errors and their locations can be recovered with `downcast_ref::<SyntaxErrors>()`.
 ///
 /// # Panics
 ///
@@ -365,6 +390,63 @@ pub fn parse(source_code: &str) -> anyhow::Result<Arena> {
     Ok(arena)
 }
 
+/// Returns the source files of the project at `path`, root file first.
+///
+/// `path` is a project directory or its `Inference.toml` manifest. The root file is
+/// `src/lib.inf` or `src/main.inf`; it is followed by every other `.inf` file under
+/// `src/`, recursively, in path order. This is the order of the source files in the
+/// arena [`parse_project`] builds.
+///
+/// # Errors
+///
+/// Returns an error if the project has neither `src/lib.inf` nor `src/main.inf`, or if a
+/// directory under `src/` cannot be read.
+pub fn project_files(path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    Ok(inference_ast::parser_context::project_files(path)?)
+}
+
+/// Parses the source files `files` into one arena, as a single module.
+///
+/// Every file becomes a [`SourceFile`] root of the arena, in the order of `files`, so
+/// [`type_check`] checks them together and [`codegen`] emits one WebAssembly module. Use
+/// [`project_files`] to list the files of a project.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use inference::{parse_project, project_files, type_check};
+/// use std::path::Path;
+///
+/// let files = project_files(Path::new("my_project/Inference.toml"))?;
+/// let arena = parse_project(&files)?;
+/// assert_eq!(arena.source_files().len(), files.len());
+/// let typed_context = type_check(arena)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `files` is empty, or if a file cannot be read. Syntax errors of
+/// all files are reported at once; they can be recovered with
+/// `downcast_ref::<SyntaxErrors>()`, and each carries the path of its file.
+///
+/// # Panics
+///
+/// This function will panic if the Inference language grammar cannot be loaded into
+/// the tree-sitter parser.
+///
+/// [`SourceFile`]: inference_ast::nodes::SourceFile
+pub fn parse_project(files: &[PathBuf]) -> anyhow::Result<Arena> {
+    let Some((root, rest)) = files.split_first() else {
+        anyhow::bail!("no source files to parse");
+    };
+    let mut context = ParserContext::new(root.clone());
+    for file in rest {
+        context.push_file(0, file.clone());
+    }
+    context.parse_all()
+}
+
 /// Performs bidirectional type checking and inference on the AST.
 ///
 /// This function analyzes the AST to build a complete type mapping for all
@@ -473,7 +555,8 @@ pub fn parse(source_code: &str) -> anyhow::Result<Arena> {
 /// - Symbols are used before being defined
 /// - Import resolution fails
 ///
-/// The error message aggregates all type checking errors found during analysis.
+/// The error message aggregates all type checking errors found during analysis. The
+/// individual errors can be recovered with `downcast_ref::<TypeCheckErrors>()`.
 ///
 /// [`TypeInfo`]: inference_type_checker::type_info::TypeInfo
 /// [`TypedContext`]: inference_type_checker::typed_context::TypedContext
@@ -483,52 +566,115 @@ pub fn type_check(arena: Arena) -> anyhow::Result<TypedContext> {
     Ok(type_checker_builder.typed_context())
 }
 
-/// Performs semantic analysis on the typed AST.
+/// Parses the dependencies of a project into an [`ExternPrelude`] for
+/// [`type_check_with_prelude`].
+///
+/// Each dependency is a pair of module name and directory; the module is parsed from
+/// `src/lib.inf` or `src/main.inf` of the directory. Hyphens in names are replaced with
+/// underscores, so the dependency `my-lib` is imported with `use my_lib::Item;`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use inference::{load_dependencies, parse, type_check_with_prelude};
+/// use std::path::PathBuf;
 ///
-/// This function is currently a placeholder for future semantic analysis passes.
-/// Planned analyses include:
-/// - Dead code detection
-/// - Unused variable warnings
-/// - Unreachable code analysis
-/// - Control flow validation
-/// - Initialization checking
+/// let prelude = load_dependencies(&[("mathlib".to_string(), PathBuf::from("../mathlib"))])?;
+/// let arena = parse("use mathlib::add;\nfn main() -> i32 { return add(1, 2); }")?;
+/// let typed_context = type_check_with_prelude(arena, prelude)?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 ///
-/// # Current Status
+/// # Errors
 ///
-/// **Work in Progress**: This phase is under active development and currently
-/// returns `Ok(())` without performing any checks. Once implemented, it will
-/// provide additional semantic guarantees beyond type correctness.
+/// Returns an [`AstError`] if a directory has no module root, or if a module cannot be
+/// read or parsed.
+pub fn load_dependencies(dependencies: &[(String, PathBuf)]) -> anyhow::Result<ExternPrelude> {
+    let mut prelude = inference_ast::extern_prelude::create_empty_prelude();
+    for (name, dir) in dependencies {
+        inference_ast::extern_prelude::parse_external_module(dir, name, &mut prelude)?;
+    }
+    Ok(prelude)
+}
+
+/// Performs type checking like [`type_check`], with the modules of `prelude` available
+/// to the `use` directives of the program.
+///
+/// Only the signatures of the public definitions of a module are loaded: they are
+/// checked against, but not compiled into the program by [`codegen`].
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`type_check`], including imports of
+/// definitions that a module does not have or does not make public.
+pub fn type_check_with_prelude(
+    arena: Arena,
+    prelude: ExternPrelude,
+) -> anyhow::Result<TypedContext> {
+    let type_checker_builder = inference_type_checker::TypeCheckerBuilder::new()
+        .with_prelude(prelude)
+        .check(arena)?;
+    Ok(type_checker_builder.typed_context())
+}
+
+/// Performs semantic analysis on the typed AST.
+///
+/// Runs the [`inference_analyzer`] passes over the typed context and returns
+/// the warnings they report. Analysis covers problems that are not type errors:
+/// - Unused local variables
+/// - Unused function parameters
+/// - Private functions that are never called
+/// - Private structs, enums and type aliases that are never referenced
+///
+/// Warnings do not stop compilation; callers decide how to report them.
 ///
 /// # Examples
 ///
 /// ```rust,no_run
 /// use inference::{parse, type_check, analyze};
 ///
-/// let source = r#"fn main() { return 0; }"#;
+/// let source = r#"fn main() -> i32 { let unused: i32 = 1; return 0; }"#;
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
 ///
-/// // Currently a no-op, but will perform semantic checks in the future
-/// analyze(&typed_context)?;
+/// for warning in analyze(&typed_context)? {
+///     eprintln!("warning[{}]: {}", warning.code(), warning);
+/// }
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 ///
 /// # Errors
 ///
-/// Currently always returns `Ok(())`. Future implementations will return errors
-/// for semantic violations that are not type errors, such as:
-/// - Use of uninitialized variables
-/// - Unreachable code paths
-/// - Dead code that should be removed
-/// - Control flow violations (e.g., missing return statements)
-/// - Infinite loops without break conditions
+/// Currently always returns `Ok`. The `Result` is kept so that future analyses
+/// can reject programs, for example on use of uninitialized variables.
 ///
 /// # Parameters
 ///
 /// - `typed_context`: The typed AST context from [`type_check`]
-pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
-    // todo!("Type analysis not yet implemented");
-    Ok(())
+pub fn analyze(typed_context: &TypedContext) -> anyhow::Result<Vec<AnalysisWarning>> {
+    Ok(inference_analyzer::analyze(typed_context))
+}
+
+/// Returns the code and name of every warning kind of the type checker and the analyzer,
+/// in code order, such as `("W0003", "unused_variable")`.
+///
+/// Warnings of the type checker are stored on the [`TypedContext`] returned by
+/// [`type_check`], those of the analyzer are returned by [`analyze`].
+pub fn warning_kinds() -> impl Iterator<Item = (&'static str, &'static str)> {
+    inference_type_checker::warnings::WARNING_KINDS
+        .into_iter()
+        .chain(inference_analyzer::warnings::WARNING_KINDS)
+}
+
+/// Returns the extended explanation of a diagnostic code, such as `E0001` or `W0003`:
+/// what the diagnostic means, an example that reports it, and how to fix it. Codes are
+/// matched case-insensitively.
+///
+/// Returns `None` if no error or warning of the type checker or the analyzer has the code.
+#[must_use]
+pub fn explain(code: &str) -> Option<&'static str> {
+    inference_type_checker::explanations::explanation(code)
+        .or_else(|| inference_analyzer::explanations::explanation(code))
 }
 
 /// Generates WebAssembly binary format from the typed AST.
@@ -538,10 +684,14 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 ///
 /// 1. Transforms AST nodes into LLVM IR
 /// 2. Applies LLVM optimization passes
-/// 3. Compiles LLVM IR to WebAssembly using `inf-llc`
+/// 3. Compiles LLVM IR to a WebAssembly object file in-process
 /// 4. Links the object files using `rust-lld`
 /// 5. Returns the final WASM binary
 ///
+/// The optimization level, LLVM module name, debug information and extra
+/// WebAssembly target features are taken from `options`. Pass
+/// [`CodegenOptions::default()`] for an optimized release build.
+///
 /// ## Non-Deterministic Extensions
 ///
 /// Inference extends WebAssembly with custom instructions for non-deterministic
@@ -564,7 +714,7 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 /// ## Basic Compilation
 ///
 /// ```rust,no_run
-/// use inference::{parse, type_check, codegen};
+/// use inference::{parse, type_check, codegen, CodegenOptions};
 /// use std::fs;
 ///
 /// let source = r#"
@@ -579,7 +729,7 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 ///
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm_bytes = codegen(&typed_context)?;
+/// let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 ///
 /// fs::write("factorial.wasm", &wasm_bytes)?;
 /// # Ok::<(), anyhow::Error>(())
@@ -588,7 +738,7 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 /// ## Non-Deterministic Code Generation
 ///
 /// ```rust,no_run
-/// use inference::{parse, type_check, codegen};
+/// use inference::{parse, type_check, codegen, CodegenOptions};
 ///
 /// let source = r#"
 ///     pub fn verify_addition() {
@@ -607,14 +757,14 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 ///
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm = codegen(&typed_context)?;
+/// let wasm = codegen(&typed_context, &CodegenOptions::default())?;
 /// # Ok::<(), anyhow::Error>(())
 /// ```
 ///
 /// ## Public Function Export
 ///
 /// ```rust,no_run
-/// use inference::{parse, type_check, codegen};
+/// use inference::{parse, type_check, codegen, CodegenOptions};
 ///
 /// let source = r#"
 ///     pub fn add(x: i32, y: i32) -> i32 {
@@ -624,7 +774,7 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 ///
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm = codegen(&typed_context)?;
+/// let wasm = codegen(&typed_context, &CodegenOptions::default())?;
 /// // The function "add" will be exported in the WASM module
 /// # Ok::<(), anyhow::Error>(())
 /// ```
@@ -644,19 +794,18 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 /// Returns an error if:
 /// - LLVM IR generation fails for any AST node
 /// - The LLVM optimization passes encounter invalid IR
-/// - The `inf-llc` compiler fails to generate object files
+/// - LLVM fails to emit the object file
 /// - The `rust-lld` linker fails to produce a valid WASM binary
-/// - Required external binaries (`inf-llc`, `rust-lld`) are not found
+/// - The required `rust-lld` binary is not found
 /// - Type information is missing or inconsistent in the [`TypedContext`]
 /// - More than one source file is present (multi-file not yet supported)
 ///
 /// # Dependencies
 ///
-/// This function requires the following external binaries:
-/// - **inf-llc**: Modified LLVM compiler with Inference intrinsic support
+/// This function requires the following external binary:
 /// - **rust-lld**: WebAssembly linker from the Rust toolchain
 ///
-/// These must be available in the `external/bin/{platform}/` directory relative to the
+/// It must be available in the `external/bin/{platform}/` directory relative to the
 /// binary location, where `{platform}` is `linux`, `macos`, or `windows`.
 /// See the repository README for download instructions.
 ///
@@ -667,8 +816,30 @@ pub fn analyze(_: &TypedContext) -> anyhow::Result<()> {
 /// - Windows x86-64 (requires DLLs in `external/bin/windows/`)
 ///
 /// [`TypedContext`]: inference_type_checker::typed_context::TypedContext
-pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
-    inference_wasm_codegen::codegen(typed_context)
+pub fn codegen(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    inference_wasm_codegen::codegen(typed_context, options)
+}
+
+/// Generates the LLVM IR of the typed AST as text, without compiling it to WebAssembly.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use inference::{parse, type_check, llvm_ir, CodegenOptions};
+///
+/// let arena = parse("pub fn one() -> i32 { return 1; }")?;
+/// let typed_context = type_check(arena)?;
+/// let ir = llvm_ir(&typed_context, &CodegenOptions::default())?;
+/// assert!(ir.contains("define"));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the options or the source are rejected as by [`codegen`], if a
+/// function cannot be lowered, or if the `llvm` feature is not enabled.
+pub fn llvm_ir(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<String> {
+    inference_wasm_codegen::llvm_ir(typed_context, options)
 }
 
 /// Translates WebAssembly binary to Rocq (Coq) verification code.
@@ -700,7 +871,7 @@ pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
 /// ## Basic Translation
 ///
 /// ```rust,no_run
-/// use inference::{parse, type_check, codegen, wasm_to_v};
+/// use inference::{parse, type_check, codegen, wasm_to_v, CodegenOptions};
 /// use std::fs;
 ///
 /// let source = r#"
@@ -711,7 +882,7 @@ pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
 ///
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm_bytes = codegen(&typed_context)?;
+/// let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 /// let rocq_code = wasm_to_v("EvenChecker", &wasm_bytes)?;
 ///
 /// fs::write("even_checker.v", rocq_code)?;
@@ -721,7 +892,7 @@ pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
 /// ## Non-Deterministic Code Translation
 ///
 /// ```rust,no_run
-/// use inference::{parse, type_check, codegen, wasm_to_v};
+/// use inference::{parse, type_check, codegen, wasm_to_v, CodegenOptions};
 ///
 /// let source = r#"
 ///     pub fn verify_commutativity() {
@@ -735,7 +906,7 @@ pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
 ///
 /// let arena = parse(source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm = codegen(&typed_context)?;
+/// let wasm = codegen(&typed_context, &CodegenOptions::default())?;
 /// let rocq = wasm_to_v("CommutativityProof", &wasm)?;
 /// # Ok::<(), anyhow::Error>(())
 /// ```
@@ -8,6 +8,8 @@
 //! All type checking errors:
 //! - Include precise source location (line and column)
 //! - Provide contextual information about the error
+//! - May carry [`RelatedNote`] secondary spans pointing at the declaration that
+//!   explains the error (see [`TypeCheckError::related_notes`])
 //! - Use descriptive error messages via `thiserror`
 //! - Are collected and reported together (error recovery)
 //!
@@ -84,7 +86,7 @@ use std::fmt::{self, Display, Formatter};
 use inference_ast::nodes::{Location, OperatorKind, UnaryOperatorKind};
 use thiserror::Error;
 
-use crate::type_info::TypeInfo;
+use crate::{type_info::TypeInfo, warnings::TypeCheckWarning};
 
 /// Kind of symbol registration for registration error context.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -208,6 +210,32 @@ impl Display for VisibilityContext {
     }
 }
 
+/// A secondary source span attached to a type checking error.
+///
+/// Related notes point at code that explains the primary error location, such as
+/// the declaration that imposed an expected type or the site of a private definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedNote {
+    pub message: String,
+    pub location: Location,
+}
+
+impl RelatedNote {
+    #[must_use]
+    pub fn new(message: impl Into<String>, location: Location) -> Self {
+        Self {
+            message: message.into(),
+            location,
+        }
+    }
+}
+
+impl Display for RelatedNote {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: note: {}", self.location, self.message)
+    }
+}
+
 /// Represents a type checking error with source location.
 /// All type errors are tied to AST nodes and must have a location.
 #[derive(Debug, Clone, Error)]
@@ -218,6 +246,7 @@ pub enum TypeCheckError {
         found: TypeInfo,
         context: TypeMismatchContext,
         location: Location,
+        related: Vec<RelatedNote>,
     },
 
     #[error("{location}: unknown type `{name}`")]
@@ -393,13 +422,16 @@ pub enum TypeCheckError {
     PrivateAccessViolation {
         context: VisibilityContext,
         location: Location,
+        related: Vec<RelatedNote>,
     },
 
     /// Instance method called as associated function.
     ///
     /// This occurs when `Type::method()` syntax is used for a method that requires `self`.
     /// Use `instance.method()` instead.
-    #[error("{location}: instance method `{type_name}::{method_name}` requires a receiver, use `instance.{method_name}()` instead")]
+    #[error(
+        "{location}: instance method `{type_name}::{method_name}` requires a receiver, use `instance.{method_name}()` instead"
+    )]
     InstanceMethodCalledAsAssociated {
         type_name: String,
         method_name: String,
@@ -410,12 +442,29 @@ pub enum TypeCheckError {
     ///
     /// This occurs when `instance.function()` syntax is used for an associated function
     /// that doesn't take `self`. Use `Type::function()` instead.
-    #[error("{location}: associated function `{type_name}::{method_name}` cannot be called on an instance, use `{type_name}::{method_name}()` instead")]
+    #[error(
+        "{location}: associated function `{type_name}::{method_name}` cannot be called on an instance, use `{type_name}::{method_name}()` instead"
+    )]
     AssociatedFunctionCalledAsMethod {
         type_name: String,
         method_name: String,
         location: Location,
     },
+
+    #[error("{location}: string index must be of number type, found `{found}`")]
+    StringIndexNotNumeric { found: TypeInfo, location: Location },
+
+    /// A warning promoted to an error by the type checker configuration.
+    #[error("{warning} (denied)")]
+    DeniedWarning { warning: TypeCheckWarning },
+
+    /// Diagnostic reported by a registered [`TypeCheckPass`](crate::pass::TypeCheckPass).
+    #[error("{location}: [{pass}] {message}")]
+    PassDiagnostic {
+        pass: String,
+        message: String,
+        location: Location,
+    },
 }
 
 impl TypeCheckError {
@@ -455,11 +504,140 @@ impl TypeCheckError {
             | TypeCheckError::ConflictingTypeInference { location, .. }
             | TypeCheckError::PrivateAccessViolation { location, .. }
             | TypeCheckError::InstanceMethodCalledAsAssociated { location, .. }
-            | TypeCheckError::AssociatedFunctionCalledAsMethod { location, .. } => location,
+            | TypeCheckError::AssociatedFunctionCalledAsMethod { location, .. }
+            | TypeCheckError::StringIndexNotNumeric { location, .. }
+            | TypeCheckError::PassDiagnostic { location, .. } => location,
+            TypeCheckError::DeniedWarning { warning } => warning.location(),
+        }
+    }
+
+    /// Returns the stable diagnostic code for this error kind.
+    ///
+    /// Codes are assigned when a variant is introduced and never reused, so they
+    /// can be referenced from documentation and used as a sort tiebreaker.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeCheckError::TypeMismatch { .. } => "E0001",
+            TypeCheckError::UnknownType { .. } => "E0002",
+            TypeCheckError::UnknownIdentifier { .. } => "E0003",
+            TypeCheckError::UndefinedFunction { .. } => "E0004",
+            TypeCheckError::UndefinedStruct { .. } => "E0005",
+            TypeCheckError::FieldNotFound { .. } => "E0006",
+            TypeCheckError::VariantNotFound { .. } => "E0007",
+            TypeCheckError::UndefinedEnum { .. } => "E0008",
+            TypeCheckError::ExpectedEnumType { .. } => "E0009",
+            TypeCheckError::MethodNotFound { .. } => "E0010",
+            TypeCheckError::ArgumentCountMismatch { .. } => "E0011",
+            TypeCheckError::TypeParameterCountMismatch { .. } => "E0012",
+            TypeCheckError::MissingTypeParameters { .. } => "E0013",
+            TypeCheckError::InvalidBinaryOperand { .. } => "E0014",
+            TypeCheckError::InvalidUnaryOperand { .. } => "E0015",
+            TypeCheckError::BinaryOperandTypeMismatch { .. } => "E0016",
+            TypeCheckError::SelfReferenceInFunction { .. } => "E0017",
+            TypeCheckError::SelfReferenceOutsideMethod { .. } => "E0018",
+            TypeCheckError::ImportResolutionFailed { .. } => "E0019",
+            TypeCheckError::CircularImport { .. } => "E0020",
+            TypeCheckError::EmptyGlobImport { .. } => "E0021",
+            TypeCheckError::RegistrationFailed { .. } => "E0022",
+            TypeCheckError::ExpectedArrayType { .. } => "E0023",
+            TypeCheckError::ExpectedStructType { .. } => "E0024",
+            TypeCheckError::MethodCallOnNonStruct { .. } => "E0025",
+            TypeCheckError::ArrayIndexNotNumeric { .. } => "E0026",
+            TypeCheckError::ArrayElementTypeMismatch { .. } => "E0027",
+            TypeCheckError::CannotInferUzumakiType { .. } => "E0028",
+            TypeCheckError::CannotInferTypeParameter { .. } => "E0029",
+            TypeCheckError::ConflictingTypeInference { .. } => "E0030",
+            TypeCheckError::PrivateAccessViolation { .. } => "E0031",
+            TypeCheckError::InstanceMethodCalledAsAssociated { .. } => "E0032",
+            TypeCheckError::AssociatedFunctionCalledAsMethod { .. } => "E0033",
+            TypeCheckError::PassDiagnostic { .. } => "E0034",
+            TypeCheckError::StringIndexNotNumeric { .. } => "E0035",
+            TypeCheckError::DeniedWarning { .. } => "E0036",
+        }
+    }
+
+    /// Returns the secondary labels attached to this error.
+    ///
+    /// Most variants carry no related notes and return an empty slice.
+    #[must_use]
+    pub fn related_notes(&self) -> &[RelatedNote] {
+        match self {
+            TypeCheckError::TypeMismatch { related, .. }
+            | TypeCheckError::PrivateAccessViolation { related, .. } => related,
+            _ => &[],
+        }
+    }
+
+    /// Renders the error message followed by its related notes.
+    ///
+    /// Notes are appended in parentheses so the result stays on a single line,
+    /// e.g. `3:5: type mismatch ... (2:4: note: declared here)`.
+    #[must_use]
+    pub fn render_with_notes(&self) -> String {
+        let notes = self.related_notes();
+        if notes.is_empty() {
+            return self.to_string();
+        }
+        let notes = notes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{self} ({notes})")
+    }
+}
+
+/// All errors of a type checking run, sorted by source file, location and code.
+///
+/// [`TypeCheckerBuilder::check`](crate::TypeCheckerBuilder::check) fails with this error,
+/// so callers can recover the structured errors from the `anyhow::Error` with
+/// `downcast_ref::<TypeCheckErrors>()`.
+#[derive(Debug, Clone)]
+pub struct TypeCheckErrors {
+    errors: Vec<TypeCheckError>,
+    source_files: Vec<usize>,
+}
+
+impl TypeCheckErrors {
+    /// Creates the errors of a run from `(source file index, error)` pairs.
+    pub(crate) fn new(errors: Vec<(usize, TypeCheckError)>) -> Self {
+        let (source_files, errors) = errors.into_iter().unzip();
+        Self {
+            errors,
+            source_files,
+        }
+    }
+
+    /// Returns the collected errors.
+    #[must_use]
+    pub fn errors(&self) -> &[TypeCheckError] {
+        &self.errors
+    }
+
+    /// Returns the index of the source file of each error, in the order of
+    /// [`errors`](Self::errors). Indices refer to the source files of the arena in order,
+    /// so errors of a multi-file module can be attributed to their file.
+    #[must_use]
+    pub fn source_files(&self) -> &[usize] {
+        &self.source_files
+    }
+}
+
+impl Display for TypeCheckErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", error.render_with_notes())?;
         }
+        Ok(())
     }
 }
 
+impl std::error::Error for TypeCheckErrors {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -486,6 +664,7 @@ mod tests {
             found: TypeInfo::default(),
             context: TypeMismatchContext::Assignment,
             location: test_location(),
+            related: vec![],
         };
         assert_eq!(
             err.to_string(),
@@ -493,6 +672,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn type_check_errors_join_messages() {
+        let errors = TypeCheckErrors::new(vec![
+            (
+                0,
+                TypeCheckError::CannotInferUzumakiType {
+                    location: test_location(),
+                },
+            ),
+            (
+                1,
+                TypeCheckError::SelfReferenceOutsideMethod {
+                    location: test_location(),
+                },
+            ),
+        ]);
+        assert_eq!(errors.errors().len(), 2);
+        assert_eq!(errors.source_files(), &[0, 1]);
+        assert_eq!(
+            errors.to_string(),
+            format!(
+                "{}; {}",
+                errors.errors()[0].render_with_notes(),
+                errors.errors()[1].render_with_notes()
+            )
+        );
+    }
+
+    #[test]
+    fn related_notes_do_not_change_primary_message() {
+        let note_location = Location {
+            offset_start: 0,
+            offset_end: 3,
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 4,
+        };
+        let err = TypeCheckError::TypeMismatch {
+            expected: TypeInfo::boolean(),
+            found: TypeInfo::default(),
+            context: TypeMismatchContext::Return,
+            location: test_location(),
+            related: vec![RelatedNote::new(
+                "function `f` declared to return `Bool` here",
+                note_location,
+            )],
+        };
+        assert_eq!(
+            err.to_string(),
+            "1:5: type mismatch in return statement: expected `Bool`, found `Unit`"
+        );
+        assert_eq!(err.related_notes().len(), 1);
+        assert_eq!(
+            err.render_with_notes(),
+            "1:5: type mismatch in return statement: expected `Bool`, found `Unit` \
+             (1:1: note: function `f` declared to return `Bool` here)"
+        );
+    }
+
+    #[test]
+    fn render_with_notes_without_notes_matches_display() {
+        let err = TypeCheckError::UnknownType {
+            name: "Foo".to_string(),
+            location: test_location(),
+        };
+        assert!(err.related_notes().is_empty());
+        assert_eq!(err.render_with_notes(), err.to_string());
+    }
+
     #[test]
     fn display_unknown_type() {
         let err = TypeCheckError::UnknownType {
@@ -968,6 +1217,7 @@ mod tests {
                 name: "helper".to_string(),
             },
             location: test_location(),
+            related: vec![],
         };
         assert_eq!(
             err.to_string(),
@@ -983,6 +1233,7 @@ mod tests {
                 field_name: "x".to_string(),
             },
             location: test_location(),
+            related: vec![],
         };
         assert_eq!(
             err.to_string(),
@@ -998,6 +1249,7 @@ mod tests {
                 method_name: "reset".to_string(),
             },
             location: test_location(),
+            related: vec![],
         };
         assert_eq!(
             err.to_string(),
@@ -1030,4 +1282,22 @@ mod tests {
         assert!(msg.contains("new"));
         assert!(msg.contains("cannot be called on an instance"));
     }
+
+    #[test]
+    fn error_codes_are_stable() {
+        let mismatch = TypeCheckError::TypeMismatch {
+            expected: TypeInfo::boolean(),
+            found: TypeInfo::default(),
+            context: TypeMismatchContext::Condition,
+            location: test_location(),
+            related: vec![],
+        };
+        let method = TypeCheckError::AssociatedFunctionCalledAsMethod {
+            type_name: "Point".to_string(),
+            method_name: "new".to_string(),
+            location: test_location(),
+        };
+        assert_eq!(mismatch.code(), "E0001");
+        assert_eq!(method.code(), "E0033");
+    }
 }
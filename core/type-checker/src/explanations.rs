@@ -0,0 +1,60 @@
+//! Extended Explanations of Diagnostic Codes
+//!
+//! Every code reported by [`TypeCheckError::code`](crate::errors::TypeCheckError::code)
+//! and [`TypeCheckWarning::code`](crate::warnings::TypeCheckWarning::code) has an
+//! explanation in `src/explanations/<code>.md`: what the diagnostic means, an example
+//! that reports it, and how to fix it. Tools show them on request, such as
+//! `infc --explain E0001`.
+//!
+//! Examples that the compiler cannot check, such as those spanning several files, are
+//! marked `inference,ignore`.
+
+/// Lists the codes of the explanation files, which are embedded by code.
+macro_rules! explanations {
+    ($($code:literal),* $(,)?) => {
+        /// Code and explanation of every type checker diagnostic, in code order.
+        pub const EXPLANATIONS: &[(&str, &str)] = &[
+            $(($code, include_str!(concat!("explanations/", $code, ".md")))),*
+        ];
+    };
+}
+
+explanations![
+    "E0001", "E0002", "E0003", "E0004", "E0005", "E0006", "E0007", "E0008", "E0009", "E0010",
+    "E0011", "E0012", "E0013", "E0014", "E0015", "E0016", "E0017", "E0018", "E0019", "E0020",
+    "E0021", "E0022", "E0023", "E0024", "E0025", "E0026", "E0027", "E0028", "E0029", "E0030",
+    "E0031", "E0032", "E0033", "E0034", "E0035", "E0036", "W0001", "W0002",
+];
+
+/// Returns the explanation of the diagnostic `code`, such as `E0001`, if it is a code of
+/// the type checker.
+#[must_use = "this is a pure lookup with no side effects"]
+pub fn explanation(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(code))
+        .map(|(_, text)| *text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warnings::WARNING_KINDS;
+
+    #[test]
+    fn every_code_has_an_explanation() {
+        // Error codes are assigned in sequence, see `TypeCheckError::code`.
+        let errors = (1..=36).map(|n| format!("E{n:04}"));
+        let warnings = WARNING_KINDS.iter().map(|(code, _)| (*code).to_string());
+        let codes: Vec<String> = errors.chain(warnings).collect();
+        let explained: Vec<&str> = EXPLANATIONS.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes, explained);
+    }
+
+    #[test]
+    fn explanations_are_looked_up_case_insensitively() {
+        assert!(explanation("e0001").is_some_and(|text| text.contains("let x: i32 = true;")));
+        assert!(explanation("W0001").is_some_and(|text| text.contains("shadowed_binding")));
+        assert_eq!(explanation("E9999"), None);
+    }
+}
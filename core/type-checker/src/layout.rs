@@ -0,0 +1,173 @@
+//! Memory Layout of Struct and Enum Types
+//!
+//! This module computes how values of struct and enum types are laid out in linear memory.
+//! Code generators use [`StructLayout`] to place struct fields and check that the
+//! backend agrees with the type checker on sizes and offsets.
+//!
+//! ## Layout Rules
+//!
+//! Layouts follow the C ABI of `wasm32`: fields are placed in declaration order, each at
+//! the next offset that is a multiple of its alignment, and the struct size is rounded up
+//! to the largest field alignment.
+//!
+//! | Type | Size | Alignment |
+//! |------|------|-----------|
+//! | `unit` | 0 | 1 |
+//! | `bool`, `i8`, `u8` | 1 | 1 |
+//! | `i16`, `u16` | 2 | 2 |
+//! | `i32`, `u32` | 4 | 4 |
+//! | `i64`, `u64` | 8 | 8 |
+//! | `[T; N]` | `N * size(T)` | `align(T)` |
+//! | struct | see above | largest field alignment |
+//! | enum | 4 | 4 |
+//!
+//! Enum values are stored as an `i32` tag holding the index of the variant in declaration
+//! order. Types without a memory representation yet (strings, generics, functions) have no
+//! layout.
+
+use crate::{
+    symbol_table::SymbolTable,
+    type_info::{NumberType, TypeInfo, TypeInfoKind},
+};
+
+/// Size and alignment of an enum value, which is stored as an `i32` tag.
+const ENUM_TAG_LAYOUT: (u32, u32) = (4, 4);
+
+/// Memory layout of a struct type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructLayout {
+    /// Name of the struct.
+    pub name: String,
+    /// Fields in declaration order.
+    pub fields: Vec<FieldLayout>,
+    /// Size in bytes, including trailing padding.
+    pub size: u32,
+    /// Alignment in bytes.
+    pub align: u32,
+}
+
+/// Placement of a single struct field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Name of the field.
+    pub name: String,
+    /// Declared type of the field.
+    pub type_info: TypeInfo,
+    /// Offset in bytes from the start of the struct.
+    pub offset: u32,
+}
+
+impl StructLayout {
+    /// Returns the declaration index and layout of the field called `name`.
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<(usize, &FieldLayout)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.name == name)
+    }
+}
+
+/// Computes the layout of the struct called `name`.
+///
+/// Returns `None` if no such struct is visible from the current scope, if one of its
+/// fields has a type without a layout, or if the struct contains itself by value.
+pub(crate) fn struct_layout(symbol_table: &SymbolTable, name: &str) -> Option<StructLayout> {
+    struct_layout_inner(symbol_table, name, &mut Vec::new())
+}
+
+/// Returns the size and alignment of a value of type `type_info`.
+pub(crate) fn size_and_align(
+    symbol_table: &SymbolTable,
+    type_info: &TypeInfo,
+) -> Option<(u32, u32)> {
+    size_and_align_inner(symbol_table, type_info, &mut Vec::new())
+}
+
+/// Returns the variant names of the enum called `name` in declaration order.
+///
+/// The index of a variant in the returned list is its tag.
+pub(crate) fn enum_variants(symbol_table: &SymbolTable, name: &str) -> Option<Vec<String>> {
+    symbol_table
+        .lookup_enum(name)
+        .map(|enum_info| enum_info.variant_order)
+}
+
+fn struct_layout_inner(
+    symbol_table: &SymbolTable,
+    name: &str,
+    visiting: &mut Vec<String>,
+) -> Option<StructLayout> {
+    if visiting.iter().any(|visited| visited == name) {
+        return None;
+    }
+    let struct_info = symbol_table.lookup_struct(name)?;
+    visiting.push(name.to_string());
+
+    let mut fields = Vec::with_capacity(struct_info.field_order.len());
+    let mut offset = 0u32;
+    let mut align = 1u32;
+    for field_name in &struct_info.field_order {
+        let field = struct_info.fields.get(field_name)?;
+        let (field_size, field_align) =
+            size_and_align_inner(symbol_table, &field.type_info, visiting)?;
+        offset = offset.next_multiple_of(field_align);
+        fields.push(FieldLayout {
+            name: field_name.clone(),
+            type_info: field.type_info.clone(),
+            offset,
+        });
+        offset = offset.checked_add(field_size)?;
+        align = align.max(field_align);
+    }
+
+    visiting.pop();
+    Some(StructLayout {
+        name: struct_info.name,
+        fields,
+        size: offset.next_multiple_of(align),
+        align,
+    })
+}
+
+fn size_and_align_inner(
+    symbol_table: &SymbolTable,
+    type_info: &TypeInfo,
+    visiting: &mut Vec<String>,
+) -> Option<(u32, u32)> {
+    match &type_info.kind {
+        TypeInfoKind::Unit => Some((0, 1)),
+        TypeInfoKind::Bool => Some((1, 1)),
+        TypeInfoKind::Number(number_type) => {
+            let size = match number_type {
+                NumberType::I8 | NumberType::U8 => 1,
+                NumberType::I16 | NumberType::U16 => 2,
+                NumberType::I32 | NumberType::U32 => 4,
+                NumberType::I64 | NumberType::U64 => 8,
+            };
+            Some((size, size))
+        }
+        TypeInfoKind::Array(element, length) => {
+            let (size, align) = size_and_align_inner(symbol_table, element, visiting)?;
+            Some((size.checked_mul(*length)?, align))
+        }
+        TypeInfoKind::Struct(name) => {
+            let layout = struct_layout_inner(symbol_table, name, visiting)?;
+            Some((layout.size, layout.align))
+        }
+        TypeInfoKind::Enum(name) => symbol_table.lookup_enum(name).map(|_| ENUM_TAG_LAYOUT),
+        TypeInfoKind::Custom(name) => {
+            if symbol_table.lookup_enum(name).is_some() {
+                return Some(ENUM_TAG_LAYOUT);
+            }
+            let layout = struct_layout_inner(symbol_table, name, visiting)?;
+            Some((layout.size, layout.align))
+        }
+        TypeInfoKind::String
+        | TypeInfoKind::Generic(_)
+        | TypeInfoKind::QualifiedName(_)
+        | TypeInfoKind::Qualified(_)
+        | TypeInfoKind::Function(_)
+        | TypeInfoKind::Spec(_) => None,
+    }
+}
@@ -53,7 +53,7 @@
 //! Use [`TypeCheckerBuilder`] to type-check an AST arena:
 //!
 //! ```ignore
-//! use inference_ast::arena::Arena;
+//! use inference_ast::{arena::Arena, extern_prelude::ExternPrelude};
 //! use inference_type_checker::TypeCheckerBuilder;
 //!
 //! // Parse source code into an arena
@@ -82,11 +82,27 @@
 //! This ordering ensures that types are available before functions reference them,
 //! and imports are resolved before symbol lookup.
 //!
+//! ## Extension Passes
+//!
+//! Project-specific rules can be added without modifying the checker by
+//! implementing [`pass::TypeCheckPass`] and registering it on the builder:
+//!
+//! ```ignore
+//! let typed_context = TypeCheckerBuilder::new()
+//!     .with_pass(MyLintPass::default())
+//!     .check(arena)?
+//!     .typed_context();
+//! ```
+//!
 //! ## Public Modules
 //!
 //! - [`errors`] - Comprehensive error types with detailed context information
+//! - [`layout`] - Memory layout of struct types (`StructLayout`, `FieldLayout`)
+//! - [`pass`] - Extension trait for custom checks over the typed AST
+//! - [`symbols`] - Read-only summary of the symbol table (`ScopeSummary`, `SymbolSummary`)
 //! - [`type_info`] - Type representation system (`TypeInfo`, `TypeInfoKind`, `NumberType`)
 //! - [`typed_context`] - Storage for type annotations on AST nodes with query API
+//! - [`warnings`] - Non-fatal diagnostics such as variable shadowing
 //!
 //! ## Documentation
 //!
@@ -98,15 +114,21 @@
 
 use std::marker::PhantomData;
 
-use inference_ast::arena::Arena;
+use inference_ast::{arena::Arena, extern_prelude::ExternPrelude};
 
-use crate::{type_checker::TypeChecker, typed_context::TypedContext};
+use crate::{pass::TypeCheckPass, type_checker::TypeChecker, typed_context::TypedContext};
 
 pub mod errors;
+pub mod explanations;
+pub mod layout;
+pub mod pass;
+mod signature;
 mod symbol_table;
+pub mod symbols;
 mod type_checker;
 pub mod type_info;
 pub mod typed_context;
+pub mod warnings;
 
 /// Marker state indicating builder has not yet been initialized with an arena.
 pub struct TypeCheckerInitState;
@@ -123,6 +145,9 @@ pub type CompletedTypeCheckerBuilder = TypeCheckerBuilder<TypeCheckerCompleteSta
 /// accessing the typed context.
 pub struct TypeCheckerBuilder<S> {
     typed_context: TypedContext,
+    passes: Vec<Box<dyn TypeCheckPass>>,
+    prelude: ExternPrelude,
+    deny_shadowing: bool,
     _state: PhantomData<S>,
 }
 
@@ -137,21 +162,71 @@ impl TypeCheckerBuilder<TypeCheckerInitState> {
     pub fn new() -> Self {
         TypeCheckerBuilder {
             typed_context: TypedContext::default(),
+            passes: Vec::new(),
+            prelude: ExternPrelude::default(),
+            deny_shadowing: false,
             _state: PhantomData,
         }
     }
 
+    /// Report variable shadowing and redeclaration as errors instead of warnings.
+    ///
+    /// Intended for verification-critical code where a hidden binding is more
+    /// likely to be a mistake than intentional.
+    #[must_use = "returns the builder with the setting applied"]
+    pub fn deny_shadowing(mut self, deny: bool) -> Self {
+        self.deny_shadowing = deny;
+        self
+    }
+
+    /// Register an extension pass to run after type inference.
+    ///
+    /// Passes run in registration order. See [`pass::TypeCheckPass`].
+    #[must_use = "returns the builder with the pass registered"]
+    pub fn with_pass(mut self, pass: impl TypeCheckPass + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Make the modules of `prelude` available to `use` directives of the checked code.
+    ///
+    /// Each module is loaded under its name before import resolution, so
+    /// `use mylib::Item;` resolves against the public definitions of the `mylib` module.
+    /// See [`inference_ast::extern_prelude`].
+    #[must_use = "returns the builder with the prelude loaded"]
+    pub fn with_prelude(mut self, prelude: ExternPrelude) -> Self {
+        self.prelude.extend(prelude);
+        self
+    }
+
     /// Run type checking on the provided arena and return a completed builder.
     ///
+    /// Equivalent to `TypeCheckerBuilder::new().check(arena)`.
+    ///
     /// # Errors
     ///
     /// Returns an error if type checking fails with unrecoverable errors.
     #[must_use = "returns builder with typed context, extract with .typed_context()"]
     pub fn build_typed_context(
         arena: Arena,
+    ) -> anyhow::Result<TypeCheckerBuilder<TypeCheckerCompleteState>> {
+        TypeCheckerBuilder::new().check(arena)
+    }
+
+    /// Run type checking and all registered passes on the provided arena.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if type checking fails or any pass reports a diagnostic.
+    #[must_use = "returns builder with typed context, extract with .typed_context()"]
+    pub fn check(
+        self,
+        arena: Arena,
     ) -> anyhow::Result<TypeCheckerBuilder<TypeCheckerCompleteState>> {
         let mut ctx = TypedContext::new(arena);
-        let mut type_checker = TypeChecker::default();
+        let mut type_checker = TypeChecker::with_passes(self.passes);
+        type_checker.deny_shadowing = self.deny_shadowing;
+        type_checker.load_prelude(self.prelude)?;
         match type_checker.infer_types(&mut ctx) {
             Ok(symbol_table) => {
                 ctx.symbol_table = symbol_table;
@@ -180,6 +255,9 @@ impl TypeCheckerBuilder<TypeCheckerInitState> {
 
         Ok(TypeCheckerBuilder {
             typed_context: ctx,
+            passes: Vec::new(),
+            prelude: ExternPrelude::default(),
+            deny_shadowing: self.deny_shadowing,
             _state: PhantomData,
         })
     }
@@ -0,0 +1,97 @@
+//! Type Checker Extension Passes
+//!
+//! This module defines [`TypeCheckPass`], the extension point for running
+//! additional checks over a fully typed AST without modifying the type checker.
+//! Passes are registered on [`TypeCheckerBuilder`](crate::TypeCheckerBuilder)
+//! and run after type inference has completed without errors.
+//!
+//! ## Visiting Order
+//!
+//! Every AST node is visited exactly once per pass. Source files are visited in
+//! the order they were built, and nodes within a file in node id order. For each
+//! node, all passes are invoked in registration order.
+//!
+//! ## Reporting Diagnostics
+//!
+//! Passes report problems through a [`PassReporter`]. Each report becomes a
+//! [`TypeCheckError::PassDiagnostic`] attributed to the pass name, and is sorted
+//! together with all other type checking errors. Any reported diagnostic makes
+//! type checking fail.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use inference_ast::nodes::{AstNode, Expression};
+//! use inference_type_checker::pass::{PassReporter, TypeCheckPass};
+//! use inference_type_checker::typed_context::TypedContext;
+//! use inference_type_checker::TypeCheckerBuilder;
+//!
+//! struct NoUzumaki;
+//!
+//! impl TypeCheckPass for NoUzumaki {
+//!     fn name(&self) -> &str {
+//!         "no-uzumaki"
+//!     }
+//!
+//!     fn visit_node(&mut self, node: &AstNode, _ctx: &TypedContext, reporter: &mut PassReporter) {
+//!         if let AstNode::Expression(Expression::Uzumaki(uzumaki)) = node {
+//!             reporter.report(uzumaki.location, "nondeterministic values are not allowed");
+//!         }
+//!     }
+//! }
+//!
+//! let typed_context = TypeCheckerBuilder::new()
+//!     .with_pass(NoUzumaki)
+//!     .check(arena)?
+//!     .typed_context();
+//! ```
+
+use inference_ast::nodes::{AstNode, Location};
+
+use crate::{errors::TypeCheckError, typed_context::TypedContext};
+
+/// A user-defined check that runs over typed AST nodes after inference.
+pub trait TypeCheckPass {
+    /// Short identifier of the pass, included in every diagnostic it reports.
+    fn name(&self) -> &str;
+
+    /// Visits a single AST node.
+    ///
+    /// Type information for the node, if any, is available through
+    /// [`TypedContext::get_node_typeinfo`].
+    fn visit_node(&mut self, node: &AstNode, ctx: &TypedContext, reporter: &mut PassReporter);
+
+    /// Called once after all nodes have been visited.
+    ///
+    /// Passes that aggregate information across nodes can report their
+    /// findings here. The default implementation does nothing.
+    fn finish(&mut self, _ctx: &TypedContext, _reporter: &mut PassReporter) {}
+}
+
+/// Collects diagnostics reported by a [`TypeCheckPass`].
+pub struct PassReporter {
+    pass: String,
+    diagnostics: Vec<TypeCheckError>,
+}
+
+impl PassReporter {
+    pub(crate) fn new(pass: &str) -> Self {
+        Self {
+            pass: pass.to_string(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Reports a diagnostic at the given location.
+    pub fn report(&mut self, location: Location, message: impl Into<String>) {
+        self.diagnostics.push(TypeCheckError::PassDiagnostic {
+            pass: self.pass.clone(),
+            message: message.into(),
+            location,
+        });
+    }
+
+    pub(crate) fn take_diagnostics(&mut self) -> Vec<TypeCheckError> {
+        std::mem::take(&mut self.diagnostics)
+    }
+}
@@ -0,0 +1,210 @@
+//! Signature Rendering
+//!
+//! This module renders the declaration header of a definition as source-like
+//! text, for hover information in the language server and for `infc --explain`.
+//! It is exposed through [`TypedContext::render_signature`](crate::typed_context::TypedContext::render_signature).
+//!
+//! ## Output Format
+//!
+//! The first line is the signature itself, with visibility, generics,
+//! parameters and return type as written in the source. Bodies are omitted.
+//! If the definition is preceded by `///` doc comment lines, their text follows
+//! after a blank line:
+//!
+//! ```text
+//! pub fn max T'(a: T, b: T) -> T
+//!
+//! Returns the larger of two values.
+//! ```
+
+use inference_ast::{
+    arena::Arena,
+    nodes::{ArgumentType, Ast, AstNode, Definition, Misc, Type, Visibility},
+};
+
+use crate::type_info::TypeInfo;
+
+/// Renders the signature of the definition (or struct field) with the given id.
+pub(crate) fn render_signature(arena: &Arena, def_id: u32) -> Option<String> {
+    let signature = match arena.find_node(def_id)? {
+        AstNode::Definition(definition) => render_definition(arena, &definition),
+        AstNode::Misc(Misc::StructField(field)) => {
+            format!("{}: {}", field.name.name, render_type(arena, &field.type_))
+        }
+        _ => return None,
+    };
+    match doc_comment(arena, def_id) {
+        Some(doc) => Some(format!("{signature}\n\n{doc}")),
+        None => Some(signature),
+    }
+}
+
+fn render_definition(arena: &Arena, definition: &Definition) -> String {
+    match definition {
+        Definition::Function(function) => {
+            let type_parameters = function
+                .type_parameters
+                .as_ref()
+                .map(|params| {
+                    params
+                        .iter()
+                        .map(|param| format!(" {}'", param.name()))
+                        .collect::<String>()
+                })
+                .unwrap_or_default();
+            format!(
+                "{}fn {}{}({}){}",
+                visibility_prefix(&function.visibility),
+                function.name(),
+                type_parameters,
+                render_arguments(arena, function.arguments.as_deref()),
+                render_return(arena, function.returns.as_ref()),
+            )
+        }
+        Definition::ExternalFunction(function) => format!(
+            "external fn {}({}){}",
+            function.name(),
+            render_arguments(arena, function.arguments.as_deref()),
+            render_return(arena, function.returns.as_ref()),
+        ),
+        Definition::Struct(struct_def) => {
+            let fields = struct_def
+                .fields
+                .iter()
+                .map(|field| format!("{}: {}", field.name.name, render_type(arena, &field.type_)))
+                .collect::<Vec<_>>();
+            if fields.is_empty() {
+                format!(
+                    "{}struct {} {{}}",
+                    visibility_prefix(&struct_def.visibility),
+                    struct_def.name()
+                )
+            } else {
+                format!(
+                    "{}struct {} {{ {} }}",
+                    visibility_prefix(&struct_def.visibility),
+                    struct_def.name(),
+                    fields.join(", ")
+                )
+            }
+        }
+        Definition::Enum(enum_def) => format!(
+            "{}enum {} {{ {} }}",
+            visibility_prefix(&enum_def.visibility),
+            enum_def.name(),
+            enum_def
+                .variants
+                .iter()
+                .map(|variant| variant.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Definition::Constant(constant) => {
+            let value = arena
+                .get_node_source(constant.value.id())
+                .map(|value| format!(" = {value}"))
+                .unwrap_or_default();
+            format!(
+                "{}const {}: {}{}",
+                visibility_prefix(&constant.visibility),
+                constant.name(),
+                render_type(arena, &constant.ty),
+                value
+            )
+        }
+        Definition::Type(type_def) => format!(
+            "{}type {} = {}",
+            visibility_prefix(&type_def.visibility),
+            type_def.name(),
+            render_type(arena, &type_def.ty)
+        ),
+        Definition::Spec(spec) => format!(
+            "{}spec {}",
+            visibility_prefix(&spec.visibility),
+            spec.name()
+        ),
+        Definition::Module(module) => format!(
+            "{}mod {}",
+            visibility_prefix(&module.visibility),
+            module.name()
+        ),
+    }
+}
+
+fn render_arguments(arena: &Arena, arguments: Option<&[ArgumentType]>) -> String {
+    arguments
+        .unwrap_or_default()
+        .iter()
+        .map(|argument| match argument {
+            ArgumentType::Argument(arg) => format!(
+                "{}{}: {}",
+                if arg.is_mut { "mut " } else { "" },
+                arg.name(),
+                render_type(arena, &arg.ty)
+            ),
+            ArgumentType::SelfReference(self_ref) => {
+                if self_ref.is_mut {
+                    "mut self".to_string()
+                } else {
+                    "self".to_string()
+                }
+            }
+            ArgumentType::IgnoreArgument(ignore) => {
+                format!("_: {}", render_type(arena, &ignore.ty))
+            }
+            ArgumentType::Type(ty) => render_type(arena, ty),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_return(arena: &Arena, returns: Option<&Type>) -> String {
+    returns
+        .map(|ty| format!(" -> {}", render_type(arena, ty)))
+        .unwrap_or_default()
+}
+
+/// Renders a type as written in the source.
+///
+/// Simple types have no node of their own, so they are rendered from their
+/// canonical name instead.
+fn render_type(arena: &Arena, ty: &Type) -> String {
+    match ty {
+        Type::Simple(kind) => kind.as_str().to_string(),
+        _ => arena
+            .get_node_source(ty.id())
+            .map_or_else(|| TypeInfo::new(ty).to_string(), str::to_string),
+    }
+}
+
+fn visibility_prefix(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "pub ",
+        Visibility::Private => "",
+    }
+}
+
+/// Collects the `///` lines directly above the node, in source order.
+///
+/// Returns `None` when the node has no doc comment.
+fn doc_comment(arena: &Arena, node_id: u32) -> Option<String> {
+    let source_file_id = arena.find_source_file_for_node(node_id)?;
+    let AstNode::Ast(Ast::SourceFile(source_file)) = arena.find_node(source_file_id)? else {
+        return None;
+    };
+    let offset = arena.find_node(node_id)?.location().offset_start as usize;
+    let preceding = source_file.source.get(..offset)?;
+    let mut lines: Vec<&str> = preceding
+        .lines()
+        .rev()
+        .skip_while(|line| line.trim().is_empty())
+        .map(str::trim)
+        .take_while(|line| line.starts_with("///"))
+        .map(|line| line.trim_start_matches("///").trim())
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    lines.reverse();
+    Some(lines.join("\n"))
+}
@@ -41,6 +41,8 @@ pub(crate) struct FuncInfo {
     pub(crate) return_type: TypeInfo,
     pub(crate) visibility: Visibility,
     pub(crate) definition_scope_id: u32,
+    /// Source location of the function definition, used for related notes.
+    pub(crate) definition_location: Location,
 }
 
 /// Information about a struct field.
@@ -50,6 +52,7 @@ pub(crate) struct StructFieldInfo {
     pub(crate) name: String,
     pub(crate) type_info: TypeInfo,
     pub(crate) visibility: Visibility,
+    pub(crate) definition_location: Location,
 }
 
 /// Information about a struct type. Visibility and definition_scope_id are used
@@ -58,9 +61,12 @@ pub(crate) struct StructFieldInfo {
 pub(crate) struct StructInfo {
     pub(crate) name: String,
     pub(crate) fields: FxHashMap<String, StructFieldInfo>,
+    /// Field names in declaration order, which determines the memory layout.
+    pub(crate) field_order: Vec<String>,
     pub(crate) type_params: Vec<String>,
     pub(crate) visibility: Visibility,
     pub(crate) definition_scope_id: u32,
+    pub(crate) definition_location: Location,
 }
 
 /// Information about an enum type including its variants.
@@ -70,8 +76,11 @@ pub(crate) struct StructInfo {
 pub(crate) struct EnumInfo {
     pub(crate) name: String,
     pub(crate) variants: FxHashSet<String>,
+    /// Variant names in declaration order; a variant's index is its runtime tag.
+    pub(crate) variant_order: Vec<String>,
     pub(crate) visibility: Visibility,
     pub(crate) definition_scope_id: u32,
+    pub(crate) definition_location: Location,
 }
 
 /// Information about a method defined on a type.
@@ -111,6 +120,56 @@ impl MethodInfo {
     }
 }
 
+/// Category of receiver types that have built-in methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum BuiltinReceiver {
+    /// Fixed-size arrays `[T; N]` of any element type
+    Array,
+    /// The `string` type
+    String,
+    /// Unbound type parameters `T`
+    Generic,
+}
+
+impl BuiltinReceiver {
+    /// Returns the built-in receiver category of a type, if it has one.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub(crate) fn of(type_info: &TypeInfo) -> Option<Self> {
+        use crate::type_info::TypeInfoKind;
+
+        match type_info.kind {
+            TypeInfoKind::Array(_, _) => Some(BuiltinReceiver::Array),
+            TypeInfoKind::String => Some(BuiltinReceiver::String),
+            TypeInfoKind::Generic(_) => Some(BuiltinReceiver::Generic),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of argument accepted by a built-in method parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuiltinParam {
+    /// Any numeric type
+    Number,
+}
+
+/// Signature of a method provided by the language for a built-in receiver.
+#[derive(Debug, Clone)]
+pub(crate) struct BuiltinMethodInfo {
+    pub(crate) name: &'static str,
+    pub(crate) params: &'static [BuiltinParam],
+    pub(crate) return_type: TypeInfo,
+}
+
+/// A previous binding hidden by a newly declared variable.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ShadowedBinding {
+    /// Where the hidden binding was declared
+    pub(crate) previous_location: Location,
+    /// Whether the hidden binding lives in the same scope as the new one
+    pub(crate) same_scope: bool,
+}
+
 /// A single item in an import statement
 #[derive(Debug, Clone)]
 pub(crate) struct ImportItem {
@@ -141,6 +200,8 @@ pub(crate) struct Import {
     pub(crate) kind: ImportKind,
     /// Source location of the import statement
     pub(crate) location: Location,
+    /// Index of the source file containing the import statement
+    pub(crate) source_file: usize,
 }
 
 /// Represents a resolved import binding.
@@ -229,6 +290,19 @@ impl Symbol {
         }
     }
 
+    /// Returns the source location where this symbol was defined.
+    ///
+    /// Type aliases (including builtins) and specs do not record a location.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub(crate) fn definition_location(&self) -> Option<Location> {
+        match self {
+            Symbol::TypeAlias(_) | Symbol::Spec(_) => None,
+            Symbol::Struct(info) => Some(info.definition_location),
+            Symbol::Enum(info) => Some(info.definition_location),
+            Symbol::Function(sig) => Some(sig.definition_location),
+        }
+    }
+
     /// Check if this symbol has public visibility.
     ///
     /// Structs, Enums, and Functions respect their visibility field.
@@ -257,7 +331,8 @@ pub(crate) struct Scope {
     pub(crate) parent: Option<ScopeRef>,
     pub(crate) children: Vec<ScopeRef>,
     pub(crate) symbols: FxHashMap<String, Symbol>,
-    pub(crate) variables: FxHashMap<String, (u32, TypeInfo)>,
+    /// Variables declared in this scope: name -> (node id, type, declaration location).
+    pub(crate) variables: FxHashMap<String, (u32, TypeInfo, Location)>,
     pub(crate) methods: FxHashMap<String, Vec<MethodInfo>>,
     /// Unresolved imports registered in this scope
     pub(crate) imports: Vec<Import>,
@@ -311,37 +386,48 @@ impl Scope {
         if let Some(symbol) = self.lookup_symbol_local(name) {
             return Some(symbol.clone());
         }
+        if let Some(import) = self.lookup_resolved_import(name) {
+            return Some(import.symbol.clone());
+        }
         if let Some(parent) = &self.parent {
             return parent.borrow().lookup_symbol(name);
         }
         None
     }
 
+    /// Inserts a variable, replacing any binding of the same name in this scope.
+    ///
+    /// Returns the location of the replaced binding, if any.
     pub(crate) fn insert_variable(
         &mut self,
         name: &str,
         node_id: u32,
         ty: TypeInfo,
-    ) -> anyhow::Result<()> {
-        if self.variables.contains_key(name) {
-            bail!("Variable `{name}` already declared in this scope");
-        }
-        self.variables.insert(name.to_string(), (node_id, ty));
-        Ok(())
+        location: Location,
+    ) -> Option<Location> {
+        self.variables
+            .insert(name.to_string(), (node_id, ty, location))
+            .map(|(_, _, previous)| previous)
     }
 
     #[must_use = "this is a pure lookup with no side effects"]
-    fn lookup_variable_local(&self, name: &str) -> Option<(u32, TypeInfo)> {
+    fn lookup_variable_local(&self, name: &str) -> Option<(u32, TypeInfo, Location)> {
         self.variables.get(name).cloned()
     }
 
     #[must_use = "this is a pure lookup with no side effects"]
     pub(crate) fn lookup_variable(&self, name: &str) -> Option<TypeInfo> {
-        if let Some((_, ty)) = self.lookup_variable_local(name) {
-            return Some(ty);
+        self.lookup_variable_with_location(name).map(|(ty, _)| ty)
+    }
+
+    /// Looks up a variable and the location where it was declared.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub(crate) fn lookup_variable_with_location(&self, name: &str) -> Option<(TypeInfo, Location)> {
+        if let Some((_, ty, location)) = self.lookup_variable_local(name) {
+            return Some((ty, location));
         }
         if let Some(parent) = &self.parent {
-            return parent.borrow().lookup_variable(name);
+            return parent.borrow().lookup_variable_with_location(name);
         }
         None
     }
@@ -379,7 +465,6 @@ impl Scope {
             .insert(resolved.local_name.clone(), resolved);
     }
 
-    #[allow(dead_code)]
     #[must_use = "this is a pure lookup with no side effects"]
     pub(crate) fn lookup_resolved_import(&self, name: &str) -> Option<&ResolvedImport> {
         self.resolved_imports.get(name)
@@ -393,6 +478,7 @@ pub(crate) struct SymbolTable {
     root_scope: Option<ScopeRef>,
     current_scope: Option<ScopeRef>,
     next_scope_id: u32,
+    builtin_methods: FxHashMap<BuiltinReceiver, Vec<BuiltinMethodInfo>>,
 }
 
 impl Default for SymbolTable {
@@ -403,9 +489,11 @@ impl Default for SymbolTable {
             root_scope: None,
             current_scope: None,
             next_scope_id: 0,
+            builtin_methods: FxHashMap::default(),
         };
         table.init_root_scope();
         table.init_builtin_types();
+        table.init_builtin_methods();
         table
     }
 }
@@ -450,6 +538,73 @@ impl SymbolTable {
         }
     }
 
+    /// Registers methods that the language provides on built-in receivers.
+    ///
+    /// - `[T; N]::len() -> u32`
+    /// - `string::len() -> u32`
+    /// - `string::slice(start, end) -> string`
+    ///
+    /// Generic receivers have no built-in methods yet; they resolve through the
+    /// table so that calls report `MethodNotFound` rather than a receiver error.
+    fn init_builtin_methods(&mut self) {
+        use crate::type_info::{NumberType, TypeInfoKind};
+
+        let length = TypeInfo {
+            kind: TypeInfoKind::Number(NumberType::U32),
+            type_params: vec![],
+        };
+        self.register_builtin_method(
+            BuiltinReceiver::Array,
+            BuiltinMethodInfo {
+                name: "len",
+                params: &[],
+                return_type: length.clone(),
+            },
+        );
+        self.register_builtin_method(
+            BuiltinReceiver::String,
+            BuiltinMethodInfo {
+                name: "len",
+                params: &[],
+                return_type: length,
+            },
+        );
+        self.register_builtin_method(
+            BuiltinReceiver::String,
+            BuiltinMethodInfo {
+                name: "slice",
+                params: &[BuiltinParam::Number, BuiltinParam::Number],
+                return_type: TypeInfo::string(),
+            },
+        );
+    }
+
+    pub(crate) fn register_builtin_method(
+        &mut self,
+        receiver: BuiltinReceiver,
+        method: BuiltinMethodInfo,
+    ) {
+        self.builtin_methods
+            .entry(receiver)
+            .or_default()
+            .push(method);
+    }
+
+    /// Looks up a built-in method for a receiver type.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub(crate) fn lookup_builtin_method(
+        &self,
+        receiver: &TypeInfo,
+        method_name: &str,
+    ) -> Option<BuiltinMethodInfo> {
+        let receiver = BuiltinReceiver::of(receiver)?;
+        self.builtin_methods
+            .get(&receiver)?
+            .iter()
+            .find(|method| method.name == method_name)
+            .cloned()
+    }
+
     pub(crate) fn push_scope(&mut self) -> u32 {
         let name = format!("anonymous_{}", self.next_scope_id);
         self.push_scope_with_name(&name, Visibility::Private)
@@ -511,29 +666,33 @@ impl SymbolTable {
     pub(crate) fn register_struct(
         &mut self,
         name: &str,
-        fields: &[(String, TypeInfo, Visibility)],
+        fields: &[(String, TypeInfo, Visibility, Location)],
         type_params: Vec<String>,
         visibility: Visibility,
+        location: Location,
     ) -> anyhow::Result<()> {
         if let Some(scope) = &self.current_scope {
             let scope_id = scope.borrow().id;
             let mut field_map = FxHashMap::default();
-            for (field_name, field_type, field_visibility) in fields {
+            for (field_name, field_type, field_visibility, field_location) in fields {
                 field_map.insert(
                     field_name.clone(),
                     StructFieldInfo {
                         name: field_name.clone(),
                         type_info: field_type.clone(),
                         visibility: field_visibility.clone(),
+                        definition_location: *field_location,
                     },
                 );
             }
             let struct_info = StructInfo {
                 name: name.to_string(),
                 fields: field_map,
+                field_order: fields.iter().map(|(name, ..)| name.clone()).collect(),
                 type_params,
                 visibility,
                 definition_scope_id: scope_id,
+                definition_location: location,
             };
             scope
                 .borrow_mut()
@@ -548,14 +707,17 @@ impl SymbolTable {
         name: &str,
         variants: &[&str],
         visibility: Visibility,
+        location: Location,
     ) -> anyhow::Result<()> {
         if let Some(scope) = &self.current_scope {
             let scope_id = scope.borrow().id;
             let enum_info = EnumInfo {
                 name: name.to_string(),
                 variants: variants.iter().map(|s| (*s).to_string()).collect(),
+                variant_order: variants.iter().map(|s| (*s).to_string()).collect(),
                 visibility,
                 definition_scope_id: scope_id,
+                definition_location: location,
             };
             scope
                 .borrow_mut()
@@ -581,6 +743,7 @@ impl SymbolTable {
         type_params: Vec<String>,
         param_types: &[Type],
         return_type: &Type,
+        location: Location,
     ) -> Result<(), String> {
         self.register_function_with_visibility(
             name,
@@ -588,6 +751,7 @@ impl SymbolTable {
             param_types,
             return_type,
             Visibility::Private,
+            location,
         )
     }
 
@@ -598,6 +762,7 @@ impl SymbolTable {
         param_types: &[Type],
         return_type: &Type,
         visibility: Visibility,
+        location: Location,
     ) -> Result<(), String> {
         if let Some(scope) = &self.current_scope {
             let scope_id = scope.borrow().id;
@@ -613,6 +778,7 @@ impl SymbolTable {
                 return_type: TypeInfo::new_with_type_params(return_type, &type_params),
                 visibility,
                 definition_scope_id: scope_id,
+                definition_location: location,
             };
             scope
                 .borrow_mut()
@@ -623,13 +789,36 @@ impl SymbolTable {
         }
     }
 
+    /// Declares a variable in the current scope.
+    ///
+    /// Returns the binding that the new variable shadows, either in the current
+    /// scope (a redeclaration) or in an enclosing scope.
     pub(crate) fn push_variable_to_scope(
         &mut self,
         name: &str,
         var_type: TypeInfo,
-    ) -> anyhow::Result<()> {
+        location: Location,
+    ) -> anyhow::Result<Option<ShadowedBinding>> {
         if let Some(scope) = &self.current_scope {
-            scope.borrow_mut().insert_variable(name, 0, var_type)
+            let outer = scope
+                .borrow()
+                .parent
+                .as_ref()
+                .and_then(|parent| parent.borrow().lookup_variable_with_location(name));
+            let replaced = scope
+                .borrow_mut()
+                .insert_variable(name, 0, var_type, location);
+            Ok(match (replaced, outer) {
+                (Some(previous_location), _) => Some(ShadowedBinding {
+                    previous_location,
+                    same_scope: true,
+                }),
+                (None, Some((_, previous_location))) => Some(ShadowedBinding {
+                    previous_location,
+                    same_scope: false,
+                }),
+                (None, None) => None,
+            })
         } else {
             bail!("No active scope to push variable")
         }
@@ -655,6 +844,13 @@ impl SymbolTable {
             .and_then(|scope| scope.borrow().lookup_variable(name))
     }
 
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub(crate) fn lookup_variable_with_location(&self, name: &str) -> Option<(TypeInfo, Location)> {
+        self.current_scope
+            .as_ref()
+            .and_then(|scope| scope.borrow().lookup_variable_with_location(name))
+    }
+
     #[must_use = "this is a pure lookup with no side effects"]
     pub(crate) fn lookup_function(&self, name: &str) -> Option<FuncInfo> {
         self.current_scope
@@ -833,7 +1029,6 @@ impl SymbolTable {
     ///
     /// # Errors
     /// Returns an error if symbol registration fails
-    #[allow(dead_code)]
     pub(crate) fn load_external_module(
         &mut self,
         module_name: &str,
@@ -861,11 +1056,10 @@ impl SymbolTable {
     ///
     /// Currently handles: Struct, Enum, Spec, Function, Type.
     /// Skips: Constant, ExternalFunction, Module (deferred to future phases).
-    #[allow(dead_code)]
     fn register_definition_from_external(&mut self, definition: &Definition) -> anyhow::Result<()> {
         match definition {
             Definition::Struct(s) => {
-                let fields: Vec<(String, TypeInfo, Visibility)> = s
+                let fields: Vec<(String, TypeInfo, Visibility, Location)> = s
                     .fields
                     .iter()
                     .map(|f| {
@@ -873,14 +1067,15 @@ impl SymbolTable {
                             f.name.name.clone(),
                             TypeInfo::new(&f.type_),
                             Visibility::Private,
+                            f.location,
                         )
                     })
                     .collect();
-                self.register_struct(&s.name(), &fields, vec![], s.visibility.clone())?;
+                self.register_struct(&s.name(), &fields, vec![], s.visibility.clone(), s.location)?;
             }
             Definition::Enum(e) => {
                 let variants: Vec<&str> = e.variants.iter().map(|v| v.name.as_str()).collect();
-                self.register_enum(&e.name(), &variants, e.visibility.clone())?;
+                self.register_enum(&e.name(), &variants, e.visibility.clone(), e.location)?;
             }
             Definition::Spec(sp) => {
                 self.register_spec(&sp.name())?;
@@ -914,6 +1109,7 @@ impl SymbolTable {
                     &param_types,
                     &return_type,
                     f.visibility.clone(),
+                    f.location,
                 )
                 .map_err(|e| anyhow::anyhow!(e))?;
             }
@@ -1300,6 +1496,7 @@ mod tests {
                     return_type: TypeInfo::default(),
                     visibility: Visibility::Private,
                     definition_scope_id: 0,
+                    definition_location: Location::default(),
                 },
                 visibility: Visibility::Private,
                 scope_id: 0,
@@ -1318,6 +1515,7 @@ mod tests {
                     return_type: TypeInfo::default(),
                     visibility: Visibility::Public,
                     definition_scope_id: 0,
+                    definition_location: Location::default(),
                 },
                 visibility: Visibility::Public,
                 scope_id: 0,
@@ -1337,6 +1535,7 @@ mod tests {
                 return_type: TypeInfo::default(),
                 visibility: Visibility::Public,
                 definition_scope_id: 0,
+                definition_location: Location::default(),
             };
             let result = table.register_method("TestType", sig, Visibility::Public, true);
             assert!(result.is_ok());
@@ -1358,6 +1557,7 @@ mod tests {
                 return_type: TypeInfo::default(),
                 visibility: Visibility::Public,
                 definition_scope_id: 0,
+                definition_location: Location::default(),
             };
             let result = table.register_method("TestType", sig, Visibility::Public, false);
             assert!(result.is_ok());
@@ -1378,6 +1578,7 @@ mod tests {
                     return_type: TypeInfo::default(),
                     visibility: Visibility::Private,
                     definition_scope_id: 0,
+                    definition_location: Location::default(),
                 },
                 visibility: Visibility::Private,
                 scope_id: 0,
@@ -1391,6 +1592,7 @@ mod tests {
                     return_type: TypeInfo::default(),
                     visibility: Visibility::Private,
                     definition_scope_id: 0,
+                    definition_location: Location::default(),
                 },
                 visibility: Visibility::Private,
                 scope_id: 0,
@@ -1404,4 +1606,60 @@ mod tests {
             assert_eq!(associated_fn.is_instance_method(), associated_fn.has_self);
         }
     }
+
+    mod builtin_method_tests {
+        use super::*;
+        use crate::type_info::{NumberType, TypeInfoKind};
+
+        fn array_of_i32() -> TypeInfo {
+            TypeInfo {
+                kind: TypeInfoKind::Array(
+                    Box::new(TypeInfo {
+                        kind: TypeInfoKind::Number(NumberType::I32),
+                        type_params: vec![],
+                    }),
+                    4,
+                ),
+                type_params: vec![],
+            }
+        }
+
+        #[test]
+        fn array_len_is_registered() {
+            let table = SymbolTable::default();
+            let method = table
+                .lookup_builtin_method(&array_of_i32(), "len")
+                .expect("len should be a built-in array method");
+            assert!(method.params.is_empty());
+            assert_eq!(
+                method.return_type.kind,
+                TypeInfoKind::Number(NumberType::U32)
+            );
+        }
+
+        #[test]
+        fn string_slice_takes_two_numbers() {
+            let table = SymbolTable::default();
+            let method = table
+                .lookup_builtin_method(&TypeInfo::string(), "slice")
+                .expect("slice should be a built-in string method");
+            assert_eq!(method.params, &[BuiltinParam::Number, BuiltinParam::Number]);
+            assert!(method.return_type.is_string());
+        }
+
+        #[test]
+        fn non_builtin_receivers_have_no_methods() {
+            let table = SymbolTable::default();
+            assert!(
+                table
+                    .lookup_builtin_method(&TypeInfo::boolean(), "len")
+                    .is_none()
+            );
+            assert!(
+                table
+                    .lookup_builtin_method(&array_of_i32(), "push")
+                    .is_none()
+            );
+        }
+    }
 }
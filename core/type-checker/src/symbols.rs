@@ -0,0 +1,177 @@
+//! Symbol Table Summaries
+//!
+//! This module provides a read-only view of the symbol table built during type checking,
+//! for tools that show it to users, such as `infc --emit=symbols`. The symbol table itself
+//! stays private to the type checker; [`TypedContext::scopes`] summarizes it as a list of
+//! [`ScopeSummary`] values.
+//!
+//! Every scope lists the symbols declared directly in it:
+//!
+//! - Type aliases, structs, enums, specs and functions
+//! - Methods, named `Type::method`
+//! - Variables, with the type inferred for them
+//!
+//! The built-in types (`i32`, `bool`, ...) registered in the root scope are left out.
+//!
+//! [`TypedContext::scopes`]: crate::typed_context::TypedContext::scopes
+
+use inference_ast::nodes::{Location, Visibility};
+
+use crate::{
+    symbol_table::{FuncInfo, Symbol, SymbolTable},
+    type_info::{NumberType, TypeInfoKind},
+};
+
+/// Kind of a symbol in a [`ScopeSummary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    TypeAlias,
+    Struct,
+    Enum,
+    Spec,
+    Function,
+    Method,
+    Variable,
+}
+
+impl SymbolKind {
+    /// Returns the lowercase name of the kind, e.g. `type alias`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SymbolKind::TypeAlias => "type alias",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Spec => "spec",
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Variable => "variable",
+        }
+    }
+}
+
+/// A symbol declared in a scope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSummary {
+    /// Name of the symbol; methods are named `Type::method`.
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The type of a variable or type alias, the signature of a function or method, or
+    /// the fields or variants of a struct or enum.
+    pub description: String,
+    /// Whether the symbol is visible outside its module.
+    pub public: bool,
+    /// Where the symbol is defined, if the type checker recorded it.
+    pub location: Option<Location>,
+}
+
+/// A scope of the symbol table and the symbols declared directly in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeSummary {
+    pub id: u32,
+    /// ID of the enclosing scope; `None` for the root scope.
+    pub parent: Option<u32>,
+    /// Path of the scope from the root, e.g. `math::add`; empty for the root scope.
+    pub path: String,
+    /// Symbols sorted by kind, then name.
+    pub symbols: Vec<SymbolSummary>,
+}
+
+/// Returns the summaries of all scopes of `table`, sorted by ID.
+pub(crate) fn summarize(table: &SymbolTable) -> Vec<ScopeSummary> {
+    let mut ids = table.all_scope_ids();
+    ids.sort_unstable();
+    ids.into_iter()
+        .filter_map(|id| table.get_scope(id))
+        .map(|scope| {
+            let scope = scope.borrow();
+            let mut symbols: Vec<SymbolSummary> = scope
+                .symbols
+                .iter()
+                .filter(|(name, symbol)| !is_builtin(name, symbol))
+                .map(|(name, symbol)| symbol_summary(name, symbol))
+                .collect();
+            for (type_name, methods) in &scope.methods {
+                for method in methods {
+                    symbols.push(SymbolSummary {
+                        name: format!("{type_name}::{}", method.signature.name),
+                        kind: SymbolKind::Method,
+                        description: function_description(&method.signature),
+                        public: matches!(method.visibility, Visibility::Public),
+                        location: Some(method.signature.definition_location),
+                    });
+                }
+            }
+            for (name, (_, type_info, location)) in &scope.variables {
+                symbols.push(SymbolSummary {
+                    name: name.clone(),
+                    kind: SymbolKind::Variable,
+                    description: type_info.to_string(),
+                    public: false,
+                    location: Some(*location),
+                });
+            }
+            symbols.sort_by(|a, b| (a.kind as u8, &a.name).cmp(&(b.kind as u8, &b.name)));
+            ScopeSummary {
+                id: scope.id,
+                parent: scope.parent.as_ref().map(|parent| parent.borrow().id),
+                path: scope.full_path.clone(),
+                symbols,
+            }
+        })
+        .collect()
+}
+
+/// Returns true for the built-in types registered in the root scope.
+fn is_builtin(name: &str, symbol: &Symbol) -> bool {
+    matches!(symbol, Symbol::TypeAlias(_))
+        && (NumberType::ALL.iter().any(|number| number.as_str() == name)
+            || TypeInfoKind::NON_NUMERIC_BUILTINS
+                .iter()
+                .any(|(builtin, _)| *builtin == name))
+}
+
+fn symbol_summary(name: &str, symbol: &Symbol) -> SymbolSummary {
+    let (kind, description) = match symbol {
+        Symbol::TypeAlias(type_info) => (SymbolKind::TypeAlias, type_info.to_string()),
+        Symbol::Struct(info) => {
+            let fields = info
+                .field_order
+                .iter()
+                .filter_map(|name| info.fields.get(name).map(|field| (name, field)))
+                .map(|(name, field)| format!("{name}: {}", field.type_info))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (SymbolKind::Struct, format!("struct {{ {fields} }}"))
+        }
+        Symbol::Enum(info) => (
+            SymbolKind::Enum,
+            format!("enum {{ {} }}", info.variant_order.join(", ")),
+        ),
+        Symbol::Spec(_) => (SymbolKind::Spec, String::from("spec")),
+        Symbol::Function(info) => (SymbolKind::Function, function_description(info)),
+    };
+    SymbolSummary {
+        name: name.to_string(),
+        kind,
+        description,
+        public: symbol.is_public(),
+        location: symbol.definition_location(),
+    }
+}
+
+/// Describes a function type, e.g. `fn T'(T, i32) -> T`.
+fn function_description(info: &FuncInfo) -> String {
+    let type_params: String = info
+        .type_params
+        .iter()
+        .map(|param| format!(" {param}'"))
+        .collect();
+    let params = info
+        .param_types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("fn{type_params}({params}) -> {}", info.return_type)
+}
@@ -14,20 +14,27 @@
 
 use std::rc::Rc;
 
-use anyhow::bail;
 use inference_ast::extern_prelude::ExternPrelude;
 use inference_ast::nodes::{
-    ArgumentType, Definition, Directive, Expression, FunctionDefinition, Identifier, Literal,
-    Location, ModuleDefinition, OperatorKind, SimpleTypeKind, Statement, Type, UnaryOperatorKind,
-    UseDirective, Visibility,
+    ArgumentType, AstNode, Definition, Directive, Expression, FunctionCallExpression,
+    FunctionDefinition, Identifier, Literal, Location, MemberAccessExpression, ModuleDefinition,
+    OperatorKind, SimpleTypeKind, Statement, Type, UnaryOperatorKind, UseDirective, Visibility,
 };
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    errors::{RegistrationKind, TypeCheckError, TypeMismatchContext, VisibilityContext},
-    symbol_table::{FuncInfo, Import, ImportItem, ImportKind, ResolvedImport, SymbolTable},
+    errors::{
+        RegistrationKind, RelatedNote, TypeCheckError, TypeCheckErrors, TypeMismatchContext,
+        VisibilityContext,
+    },
+    pass::{PassReporter, TypeCheckPass},
+    symbol_table::{
+        BuiltinParam, BuiltinReceiver, FuncInfo, Import, ImportItem, ImportKind, ResolvedImport,
+        ShadowedBinding, SymbolTable,
+    },
     type_info::{NumberType, TypeInfo, TypeInfoKind},
     typed_context::TypedContext,
+    warnings::TypeCheckWarning,
 };
 
 #[derive(Default)]
@@ -36,9 +43,30 @@ pub(crate) struct TypeChecker {
     errors: Vec<TypeCheckError>,
     glob_resolution_in_progress: FxHashSet<u32>,
     reported_error_keys: FxHashSet<String>,
+    /// Name and location of the function whose body is being checked, used to
+    /// point return type mismatches back at the declaration.
+    current_function: Option<(String, Location)>,
+    /// Index of the source file currently being checked.
+    current_file: usize,
+    /// Source file index for each entry of `errors`, filled lazily by
+    /// `attribute_pending_errors`.
+    error_files: Vec<usize>,
+    /// Extension passes run over the typed AST once inference succeeds.
+    passes: Vec<Box<dyn TypeCheckPass>>,
+    /// Warnings with the index of the source file they were reported in.
+    warnings: Vec<(usize, TypeCheckWarning)>,
+    /// Report shadowing and redeclaration warnings as errors.
+    pub(crate) deny_shadowing: bool,
 }
 
 impl TypeChecker {
+    pub(crate) fn with_passes(passes: Vec<Box<dyn TypeCheckPass>>) -> Self {
+        Self {
+            passes,
+            ..Self::default()
+        }
+    }
+
     /// Load external modules from prelude before import resolution.
     ///
     /// The prelude is consumed (moved into symbol table as virtual scopes).
@@ -49,7 +77,6 @@ impl TypeChecker {
     ///
     /// # Errors
     /// Returns an error if symbol registration for any module fails
-    #[allow(dead_code)]
     pub fn load_prelude(&mut self, prelude: ExternPrelude) -> anyhow::Result<()> {
         for (name, parsed_module) in prelude {
             self.symbol_table
@@ -68,6 +95,10 @@ impl TypeChecker {
     /// 3. `resolve_imports()` - Bind import paths to symbols
     /// 4. `collect_function_and_constant_definitions()` - Register functions
     /// 5. Infer variable types in function bodies
+    /// 6. Run registered extension passes, if inference produced no errors
+    ///
+    /// Errors are reported sorted by source file, then location, then error code,
+    /// so the output does not depend on phase or traversal order.
     pub fn infer_types(&mut self, ctx: &mut TypedContext) -> anyhow::Result<SymbolTable> {
         self.process_directives(ctx);
         self.register_types(ctx);
@@ -75,7 +106,8 @@ impl TypeChecker {
         self.collect_function_and_constant_definitions(ctx);
         // Continue to inference phase even if registration had errors
         // to collect all errors before returning
-        for source_file in ctx.source_files() {
+        for (file_index, source_file) in ctx.source_files().iter().enumerate() {
+            self.set_current_file(file_index);
             for def in &source_file.definitions {
                 match def {
                     Definition::Function(function_definition) => {
@@ -94,19 +126,206 @@ impl TypeChecker {
                 }
             }
         }
+        if self.errors.is_empty() {
+            self.run_passes(ctx);
+        }
+        ctx.set_warnings(self.take_sorted_warnings());
         if !self.errors.is_empty() {
-            let error_messages: Vec<String> = std::mem::take(&mut self.errors)
-                .into_iter()
-                .map(|e| e.to_string())
-                .collect();
-            bail!(error_messages.join("; "))
+            return Err(TypeCheckErrors::new(self.take_sorted_errors()).into());
         }
         Ok(self.symbol_table.clone())
     }
 
+    /// Runs extension passes over every node, grouped by source file.
+    fn run_passes(&mut self, ctx: &TypedContext) {
+        if self.passes.is_empty() {
+            return;
+        }
+        let mut passes = std::mem::take(&mut self.passes);
+        let mut reporters: Vec<PassReporter> = passes
+            .iter()
+            .map(|pass| PassReporter::new(pass.name()))
+            .collect();
+        let mut nodes = ctx.filter_nodes(|_| true);
+        nodes.sort_by_key(AstNode::id);
+        for (file_index, source_file) in ctx.source_files().iter().enumerate() {
+            self.set_current_file(file_index);
+            for node in nodes
+                .iter()
+                .filter(|node| ctx.find_source_file_for_node(node.id()) == Some(source_file.id))
+            {
+                for (pass, reporter) in passes.iter_mut().zip(reporters.iter_mut()) {
+                    pass.visit_node(node, ctx, reporter);
+                }
+            }
+            for reporter in &mut reporters {
+                self.errors.extend(reporter.take_diagnostics());
+            }
+        }
+        for (pass, reporter) in passes.iter_mut().zip(reporters.iter_mut()) {
+            pass.finish(ctx, reporter);
+            self.errors.extend(reporter.take_diagnostics());
+        }
+        self.passes = passes;
+    }
+
+    /// Infers a call to a built-in method on an array, string or generic receiver.
+    ///
+    /// Methods are resolved through the symbol table's built-in method table.
+    fn infer_builtin_method_call(
+        &mut self,
+        function_call_expression: &FunctionCallExpression,
+        member_access: &MemberAccessExpression,
+        receiver_type: &TypeInfo,
+        ctx: &mut TypedContext,
+    ) -> Option<TypeInfo> {
+        let method_name = &member_access.name.name;
+        let arguments = function_call_expression
+            .arguments
+            .as_deref()
+            .unwrap_or_default();
+        let Some(method) = self
+            .symbol_table
+            .lookup_builtin_method(receiver_type, method_name)
+        else {
+            self.errors.push(TypeCheckError::MethodNotFound {
+                type_name: receiver_type.to_string(),
+                method_name: method_name.clone(),
+                location: member_access.location,
+            });
+            for arg in arguments {
+                self.infer_expression(&arg.1.borrow(), ctx);
+            }
+            return None;
+        };
+        let qualified_name = format!("{receiver_type}::{method_name}");
+        if arguments.len() != method.params.len() {
+            self.errors.push(TypeCheckError::ArgumentCountMismatch {
+                kind: "method",
+                name: qualified_name.clone(),
+                expected: method.params.len(),
+                found: arguments.len(),
+                location: function_call_expression.location,
+            });
+        }
+        for (arg, param) in arguments.iter().zip(
+            method
+                .params
+                .iter()
+                .map(Some)
+                .chain(std::iter::repeat(None)),
+        ) {
+            let arg_expr = arg.1.borrow();
+            let arg_type = self.infer_expression(&arg_expr, ctx);
+            if let (Some(arg_type), Some(BuiltinParam::Number)) = (arg_type, param)
+                && !arg_type.is_number()
+            {
+                if receiver_type.is_string() {
+                    self.errors.push(TypeCheckError::StringIndexNotNumeric {
+                        found: arg_type,
+                        location: arg_expr.location(),
+                    });
+                } else {
+                    self.errors.push(TypeCheckError::ArrayIndexNotNumeric {
+                        found: arg_type,
+                        location: arg_expr.location(),
+                    });
+                }
+            }
+        }
+        ctx.set_node_typeinfo(
+            member_access.id,
+            TypeInfo {
+                kind: TypeInfoKind::Function(qualified_name),
+                type_params: vec![],
+            },
+        );
+        ctx.set_node_typeinfo(function_call_expression.id, method.return_type.clone());
+        Some(method.return_type)
+    }
+
+    /// Declares a variable in the current scope, reporting any binding it shadows.
+    fn declare_variable(
+        &mut self,
+        name: &str,
+        var_type: TypeInfo,
+        location: Location,
+    ) -> anyhow::Result<()> {
+        if let Some(ShadowedBinding {
+            previous_location,
+            same_scope,
+        }) = self
+            .symbol_table
+            .push_variable_to_scope(name, var_type, location)?
+        {
+            let warning = if same_scope {
+                TypeCheckWarning::Redeclaration {
+                    name: name.to_string(),
+                    location,
+                    previous: previous_location,
+                }
+            } else {
+                TypeCheckWarning::ShadowedBinding {
+                    name: name.to_string(),
+                    location,
+                    previous: previous_location,
+                }
+            };
+            if self.deny_shadowing {
+                self.errors.push(TypeCheckError::DeniedWarning { warning });
+            } else {
+                self.warnings.push((self.current_file, warning));
+            }
+        }
+        Ok(())
+    }
+
+    fn take_sorted_warnings(&mut self) -> Vec<TypeCheckWarning> {
+        let mut warnings = std::mem::take(&mut self.warnings);
+        warnings.sort_by(|(file_a, a), (file_b, b)| {
+            file_a
+                .cmp(file_b)
+                .then(location_order(a.location(), b.location()))
+                .then(a.code().cmp(b.code()))
+        });
+        warnings.into_iter().map(|(_, warning)| warning).collect()
+    }
+
+    /// Attributes errors pushed since the last call to the current source file
+    /// and switches attribution to `file_index`.
+    fn set_current_file(&mut self, file_index: usize) {
+        self.attribute_pending_errors();
+        self.current_file = file_index;
+    }
+
+    fn attribute_pending_errors(&mut self) {
+        self.error_files
+            .resize(self.errors.len(), self.current_file);
+    }
+
+    /// Drains collected errors ordered by source file, location and error code.
+    ///
+    /// The sort is stable, so errors with identical keys keep their discovery order.
+    fn take_sorted_errors(&mut self) -> Vec<(usize, TypeCheckError)> {
+        self.attribute_pending_errors();
+        let files = std::mem::take(&mut self.error_files);
+        let mut errors: Vec<(usize, TypeCheckError)> = files
+            .into_iter()
+            .zip(std::mem::take(&mut self.errors))
+            .collect();
+        errors.sort_by(|(file_a, a), (file_b, b)| {
+            file_a
+                .cmp(file_b)
+                .then(location_order(a.location(), b.location()))
+                .then(a.code().cmp(b.code()))
+        });
+        errors
+    }
+
     /// Registers `Definition::Type`, `Definition::Struct`, `Definition::Enum`, and `Definition::Spec`
     fn register_types(&mut self, ctx: &mut TypedContext) {
-        for source_file in ctx.source_files() {
+        for (file_index, source_file) in ctx.source_files().iter().enumerate() {
+            self.set_current_file(file_index);
             for definition in &source_file.definitions {
                 match definition {
                     Definition::Type(type_definition) => {
@@ -122,23 +341,26 @@ impl TypeChecker {
                             });
                     }
                     Definition::Struct(struct_definition) => {
-                        let fields: Vec<(String, TypeInfo, Visibility)> = struct_definition
-                            .fields
-                            .iter()
-                            .map(|f| {
-                                (
-                                    f.name.name.clone(),
-                                    TypeInfo::new(&f.type_),
-                                    Visibility::Private,
-                                )
-                            })
-                            .collect();
+                        let fields: Vec<(String, TypeInfo, Visibility, Location)> =
+                            struct_definition
+                                .fields
+                                .iter()
+                                .map(|f| {
+                                    (
+                                        f.name.name.clone(),
+                                        TypeInfo::new(&f.type_),
+                                        Visibility::Private,
+                                        f.location,
+                                    )
+                                })
+                                .collect();
                         self.symbol_table
                             .register_struct(
                                 &struct_definition.name(),
                                 &fields,
                                 vec![],
                                 struct_definition.visibility.clone(),
+                                struct_definition.location,
                             )
                             .unwrap_or_else(|_| {
                                 self.errors.push(TypeCheckError::RegistrationFailed {
@@ -194,6 +416,7 @@ impl TypeChecker {
                                 return_type,
                                 visibility: method.visibility.clone(),
                                 definition_scope_id,
+                                definition_location: method.location,
                             };
 
                             self.symbol_table
@@ -224,6 +447,7 @@ impl TypeChecker {
                                 &enum_definition.name(),
                                 &variants,
                                 enum_definition.visibility.clone(),
+                                enum_definition.location,
                             )
                             .unwrap_or_else(|_| {
                                 self.errors.push(TypeCheckError::RegistrationFailed {
@@ -258,15 +482,17 @@ impl TypeChecker {
     /// Registers `Definition::Function`, `Definition::ExternalFunction`, and `Definition::Constant`
     #[allow(clippy::too_many_lines)]
     fn collect_function_and_constant_definitions(&mut self, ctx: &mut TypedContext) {
-        for sf in ctx.source_files() {
+        for (file_index, sf) in ctx.source_files().iter().enumerate() {
+            self.set_current_file(file_index);
             for definition in &sf.definitions {
                 match definition {
                     Definition::Constant(constant_definition) => {
                         let const_type = TypeInfo::new(&constant_definition.ty);
-                        if let Err(err) = self
-                            .symbol_table
-                            .push_variable_to_scope(&constant_definition.name(), const_type.clone())
-                        {
+                        if let Err(err) = self.declare_variable(
+                            &constant_definition.name(),
+                            const_type.clone(),
+                            constant_definition.name.location,
+                        ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Variable,
                                 name: constant_definition.name(),
@@ -359,6 +585,7 @@ impl TypeChecker {
                                 .as_ref()
                                 .unwrap_or(&Type::Simple(SimpleTypeKind::Unit))
                                 .clone(),
+                            function_definition.location,
                         ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Function,
@@ -391,6 +618,7 @@ impl TypeChecker {
                                 .as_ref()
                                 .unwrap_or(&Type::Simple(SimpleTypeKind::Unit))
                                 .clone(),
+                            external_function_definition.location,
                         ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Function,
@@ -481,6 +709,10 @@ impl TypeChecker {
         ctx: &mut TypedContext,
     ) {
         self.symbol_table.push_scope();
+        self.current_function = Some((
+            function_definition.name(),
+            function_definition.name.location,
+        ));
 
         // Collect type parameter names for proper TypeInfo construction
         let type_param_names: Vec<String> = function_definition
@@ -494,9 +726,8 @@ impl TypeChecker {
                 match argument {
                     ArgumentType::Argument(arg) => {
                         let arg_type = TypeInfo::new_with_type_params(&arg.ty, &type_param_names);
-                        if let Err(err) = self
-                            .symbol_table
-                            .push_variable_to_scope(&arg.name(), arg_type)
+                        if let Err(err) =
+                            self.declare_variable(&arg.name(), arg_type, arg.name.location)
                         {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Variable,
@@ -527,6 +758,7 @@ impl TypeChecker {
         for stmt in &mut function_definition.body.statements() {
             self.infer_statement(stmt, &return_type, ctx);
         }
+        self.current_function = None;
         self.symbol_table.pop_scope();
     }
 
@@ -538,14 +770,16 @@ impl TypeChecker {
         ctx: &mut TypedContext,
     ) {
         self.symbol_table.push_scope();
+        self.current_function = Some((method_definition.name(), method_definition.name.location));
         if let Some(arguments) = &method_definition.arguments {
             for argument in arguments {
                 match argument {
                     ArgumentType::Argument(arg) => {
-                        if let Err(err) = self
-                            .symbol_table
-                            .push_variable_to_scope(&arg.name(), TypeInfo::new(&arg.ty))
-                        {
+                        if let Err(err) = self.declare_variable(
+                            &arg.name(),
+                            TypeInfo::new(&arg.ty),
+                            arg.name.location,
+                        ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Variable,
                                 name: arg.name(),
@@ -555,9 +789,8 @@ impl TypeChecker {
                         }
                     }
                     ArgumentType::SelfReference(self_ref) => {
-                        if let Err(err) = self
-                            .symbol_table
-                            .push_variable_to_scope("self", self_type.clone())
+                        if let Err(err) =
+                            self.declare_variable("self", self_type.clone(), self_ref.location)
                         {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Variable,
@@ -582,6 +815,7 @@ impl TypeChecker {
                 ctx,
             );
         }
+        self.current_function = None;
         self.symbol_table.pop_scope();
     }
 
@@ -595,6 +829,13 @@ impl TypeChecker {
         match statement {
             Statement::Assign(assign_statement) => {
                 let target_type = self.infer_expression(&assign_statement.left.borrow(), ctx);
+                let target_declaration = match &*assign_statement.left.borrow() {
+                    Expression::Identifier(identifier) => self
+                        .symbol_table
+                        .lookup_variable_with_location(&identifier.name)
+                        .map(|(_, location)| (identifier.name.clone(), location)),
+                    _ => None,
+                };
                 let right_expr = assign_statement.right.borrow();
                 if let Expression::Uzumaki(uzumaki_rc) = &*right_expr {
                     if let Some(target) = &target_type {
@@ -607,13 +848,24 @@ impl TypeChecker {
                 } else {
                     let value_type = self.infer_expression(&right_expr, ctx);
                     if let (Some(target), Some(val)) = (target_type, value_type)
-                        && target != val
+                        && !target.is_same_type(&val)
                     {
+                        let mut related = vec![RelatedNote::new(
+                            format!("this expression has type `{val}`"),
+                            right_expr.location(),
+                        )];
+                        if let Some((name, location)) = target_declaration {
+                            related.push(RelatedNote::new(
+                                format!("`{name}` declared with type `{target}` here"),
+                                location,
+                            ));
+                        }
                         self.errors.push(TypeCheckError::TypeMismatch {
                             expected: target,
                             found: val,
                             context: TypeMismatchContext::Assignment,
                             location: assign_statement.location,
+                            related,
                         });
                     }
                 }
@@ -640,12 +892,26 @@ impl TypeChecker {
                 } else {
                     let value_type =
                         self.infer_expression(&return_statement.expression.borrow(), ctx);
-                    if *return_type != value_type.clone().unwrap_or_default() {
+                    if !return_type.is_same_type(&value_type.clone().unwrap_or_default()) {
+                        let found = value_type.unwrap_or_default();
+                        let mut related = vec![RelatedNote::new(
+                            format!("this expression has type `{found}`"),
+                            return_statement.expression.borrow().location(),
+                        )];
+                        if let Some((name, location)) = &self.current_function {
+                            related.push(RelatedNote::new(
+                                format!(
+                                    "function `{name}` declared to return `{return_type}` here"
+                                ),
+                                *location,
+                            ));
+                        }
                         self.errors.push(TypeCheckError::TypeMismatch {
                             expected: return_type.clone(),
-                            found: value_type.unwrap_or_default(),
+                            found,
                             context: TypeMismatchContext::Return,
                             location: return_statement.location,
+                            related,
                         });
                     }
                 }
@@ -661,6 +927,10 @@ impl TypeChecker {
                             found: condition_type.unwrap_or_default(),
                             context: TypeMismatchContext::Condition,
                             location: loop_statement.location,
+                            related: vec![RelatedNote::new(
+                                format!("condition must evaluate to `{}`", TypeInfo::boolean()),
+                                condition.location(),
+                            )],
                         });
                     }
                 }
@@ -681,6 +951,10 @@ impl TypeChecker {
                         found: condition_type.unwrap_or_default(),
                         context: TypeMismatchContext::Condition,
                         location: if_statement.location,
+                        related: vec![RelatedNote::new(
+                            format!("condition must evaluate to `{}`", TypeInfo::boolean()),
+                            if_statement.condition.borrow().location(),
+                        )],
                     });
                 }
 
@@ -704,9 +978,22 @@ impl TypeChecker {
                     if let Expression::Uzumaki(uzumaki_rc) = &mut *expr_ref {
                         ctx.set_node_typeinfo(uzumaki_rc.id, target_type.clone());
                     } else if let Some(init_type) = self.infer_expression(&expr_ref, ctx)
-                        && init_type != TypeInfo::new(&variable_definition_statement.ty)
+                        && !init_type.is_same_type(&target_type)
                     {
                         self.errors.push(TypeCheckError::TypeMismatch {
+                            related: vec![
+                                RelatedNote::new(
+                                    format!("this expression has type `{init_type}`"),
+                                    expr_ref.location(),
+                                ),
+                                RelatedNote::new(
+                                    format!(
+                                        "`{}` declared with type `{target_type}` here",
+                                        variable_definition_statement.name()
+                                    ),
+                                    variable_definition_statement.name.location,
+                                ),
+                            ],
                             expected: target_type.clone(),
                             found: init_type,
                             context: TypeMismatchContext::VariableDefinition,
@@ -714,9 +1001,10 @@ impl TypeChecker {
                         });
                     }
                 }
-                if let Err(err) = self.symbol_table.push_variable_to_scope(
+                if let Err(err) = self.declare_variable(
                     &variable_definition_statement.name(),
                     TypeInfo::new(&variable_definition_statement.ty),
+                    variable_definition_statement.name.location,
                 ) {
                     self.errors.push(TypeCheckError::RegistrationFailed {
                         kind: RegistrationKind::Variable,
@@ -753,15 +1041,20 @@ impl TypeChecker {
                         found: condition_type.unwrap_or_default(),
                         context: TypeMismatchContext::Condition,
                         location: assert_statement.location,
+                        related: vec![RelatedNote::new(
+                            format!("assertion must evaluate to `{}`", TypeInfo::boolean()),
+                            assert_statement.expression.borrow().location(),
+                        )],
                     });
                 }
             }
             Statement::ConstantDefinition(constant_definition) => {
                 let constant_type = TypeInfo::new(&constant_definition.ty);
-                if let Err(err) = self
-                    .symbol_table
-                    .push_variable_to_scope(&constant_definition.name(), constant_type.clone())
-                {
+                if let Err(err) = self.declare_variable(
+                    &constant_definition.name(),
+                    constant_type.clone(),
+                    constant_definition.name.location,
+                ) {
                     self.errors.push(TypeCheckError::RegistrationFailed {
                         kind: RegistrationKind::Variable,
                         name: constant_definition.name(),
@@ -792,12 +1085,31 @@ impl TypeChecker {
                         self.infer_expression(&array_index_access_expression.index.borrow(), ctx)
                         && !index_type.is_number()
                     {
-                        self.errors.push(TypeCheckError::ArrayIndexNotNumeric {
-                            found: index_type,
-                            location: array_index_access_expression.location,
-                        });
+                        if array_type.is_string() {
+                            self.errors.push(TypeCheckError::StringIndexNotNumeric {
+                                found: index_type,
+                                location: array_index_access_expression.location,
+                            });
+                        } else {
+                            self.errors.push(TypeCheckError::ArrayIndexNotNumeric {
+                                found: index_type,
+                                location: array_index_access_expression.location,
+                            });
+                        }
                     }
                     match &array_type.kind {
+                        // Indexing a string yields the byte at that position
+                        TypeInfoKind::String => {
+                            let byte_type = TypeInfo {
+                                kind: TypeInfoKind::Number(NumberType::U8),
+                                type_params: vec![],
+                            };
+                            ctx.set_node_typeinfo(
+                                array_index_access_expression.id,
+                                byte_type.clone(),
+                            );
+                            Some(byte_type)
+                        }
                         TypeInfoKind::Array(element_type, _) => {
                             ctx.set_node_typeinfo(
                                 array_index_access_expression.id,
@@ -844,6 +1156,7 @@ impl TypeChecker {
                                 self.check_and_report_visibility(
                                     &field_info.visibility,
                                     struct_info.definition_scope_id,
+                                    Some(field_info.definition_location),
                                     &member_access_expression.location,
                                     VisibilityContext::Field {
                                         struct_name: struct_name.clone(),
@@ -910,22 +1223,19 @@ impl TypeChecker {
                     _ => {
                         // For other expressions, try to infer the type
                         drop(inner_expr); // Release borrow before mutable borrow
-                        if let Some(expr_type) = self.infer_expression(
+                        let expr_type = self.infer_expression(
                             &type_member_access_expression.expression.borrow(),
                             ctx,
-                        ) {
-                            match &expr_type.kind {
-                                TypeInfoKind::Enum(name) => name.clone(),
-                                _ => {
-                                    self.errors.push(TypeCheckError::ExpectedEnumType {
-                                        found: expr_type,
-                                        location: type_member_access_expression.location,
-                                    });
-                                    return None;
-                                }
+                        )?;
+                        match &expr_type.kind {
+                            TypeInfoKind::Enum(name) => name.clone(),
+                            _ => {
+                                self.errors.push(TypeCheckError::ExpectedEnumType {
+                                    found: expr_type,
+                                    location: type_member_access_expression.location,
+                                });
+                                return None;
                             }
-                        } else {
-                            return None;
                         }
                     }
                 };
@@ -940,6 +1250,7 @@ impl TypeChecker {
                         self.check_and_report_visibility(
                             &enum_info.visibility,
                             enum_info.definition_scope_id,
+                            Some(enum_info.definition_location),
                             &type_member_access_expression.location,
                             VisibilityContext::Enum {
                                 name: enum_name.clone(),
@@ -1022,6 +1333,7 @@ impl TypeChecker {
                             self.check_and_report_visibility(
                                 &method_info.visibility,
                                 method_info.scope_id,
+                                Some(method_info.signature.definition_location),
                                 &type_member_access.location,
                                 VisibilityContext::Method {
                                     type_name: type_name.clone(),
@@ -1078,6 +1390,14 @@ impl TypeChecker {
                         self.infer_expression(&member_access.expression.borrow(), ctx);
 
                     if let Some(receiver_type) = receiver_type {
+                        if BuiltinReceiver::of(&receiver_type).is_some() {
+                            return self.infer_builtin_method_call(
+                                function_call_expression,
+                                member_access,
+                                &receiver_type,
+                                ctx,
+                            );
+                        }
                         let type_name = match &receiver_type.kind {
                             TypeInfoKind::Struct(name) => Some(name.clone()),
                             TypeInfoKind::Custom(name) => {
@@ -1112,6 +1432,7 @@ impl TypeChecker {
                                 self.check_and_report_visibility(
                                     &method_info.visibility,
                                     method_info.scope_id,
+                                    Some(method_info.signature.definition_location),
                                     &member_access.location,
                                     VisibilityContext::Method {
                                         type_name: type_name.clone(),
@@ -1193,6 +1514,7 @@ impl TypeChecker {
                     self.check_and_report_visibility(
                         &s.visibility,
                         s.definition_scope_id,
+                        Some(s.definition_location),
                         &function_call_expression.location,
                         VisibilityContext::Function {
                             name: function_call_expression.name(),
@@ -1297,6 +1619,10 @@ impl TypeChecker {
                 if let Some(type_info) = ctx.get_node_typeinfo(struct_expression.id) {
                     return Some(type_info.clone());
                 }
+                // Field values are inferred so that their nodes carry types for code generation.
+                for (_, value) in struct_expression.fields.iter().flatten() {
+                    self.infer_expression(&value.borrow(), ctx);
+                }
                 let struct_type = self.symbol_table.lookup_type(&struct_expression.name());
                 if let Some(struct_type) = struct_type {
                     ctx.set_node_typeinfo(struct_expression.id, struct_type.clone());
@@ -1387,7 +1713,7 @@ impl TypeChecker {
                 let left_type = self.infer_expression(&binary_expression.left.borrow(), ctx);
                 let right_type = self.infer_expression(&binary_expression.right.borrow(), ctx);
                 if let (Some(left_type), Some(right_type)) = (left_type, right_type) {
-                    if left_type != right_type {
+                    if !left_type.is_same_type(&right_type) {
                         self.errors.push(TypeCheckError::BinaryOperandTypeMismatch {
                             operator: binary_expression.operator.clone(),
                             left: left_type.clone(),
@@ -1413,15 +1739,32 @@ impl TypeChecker {
                                 return None;
                             }
                         }
-                        OperatorKind::Eq
-                        | OperatorKind::Ne
-                        | OperatorKind::Lt
-                        | OperatorKind::Le
-                        | OperatorKind::Gt
-                        | OperatorKind::Ge => TypeInfo {
+                        OperatorKind::Eq | OperatorKind::Ne => TypeInfo {
                             kind: TypeInfoKind::Bool,
                             type_params: vec![],
                         },
+                        OperatorKind::Lt
+                        | OperatorKind::Le
+                        | OperatorKind::Gt
+                        | OperatorKind::Ge => {
+                            if left_type.is_string() || right_type.is_string() {
+                                self.errors.push(TypeCheckError::InvalidBinaryOperand {
+                                    operator: binary_expression.operator.clone(),
+                                    expected_kind: "ordering",
+                                    operand_desc: "string types",
+                                    found_types: (left_type, right_type),
+                                    location: binary_expression.location,
+                                });
+                            }
+                            TypeInfo {
+                                kind: TypeInfoKind::Bool,
+                                type_params: vec![],
+                            }
+                        }
+                        // String concatenation
+                        OperatorKind::Add if left_type.is_string() && right_type.is_string() => {
+                            TypeInfo::string()
+                        }
                         OperatorKind::Pow
                         | OperatorKind::Add
                         | OperatorKind::Sub
@@ -1443,7 +1786,7 @@ impl TypeChecker {
                                     location: binary_expression.location,
                                 });
                             }
-                            if left_type != right_type {
+                            if !left_type.is_same_type(&right_type) {
                                 self.errors.push(TypeCheckError::BinaryOperandTypeMismatch {
                                     operator: binary_expression.operator.clone(),
                                     left: left_type.clone(),
@@ -1623,23 +1966,26 @@ impl TypeChecker {
                             });
                     }
                     Definition::Struct(struct_definition) => {
-                        let fields: Vec<(String, TypeInfo, Visibility)> = struct_definition
-                            .fields
-                            .iter()
-                            .map(|f| {
-                                (
-                                    f.name.name.clone(),
-                                    TypeInfo::new(&f.type_),
-                                    Visibility::Private,
-                                )
-                            })
-                            .collect();
+                        let fields: Vec<(String, TypeInfo, Visibility, Location)> =
+                            struct_definition
+                                .fields
+                                .iter()
+                                .map(|f| {
+                                    (
+                                        f.name.name.clone(),
+                                        TypeInfo::new(&f.type_),
+                                        Visibility::Private,
+                                        f.location,
+                                    )
+                                })
+                                .collect();
                         self.symbol_table
                             .register_struct(
                                 &struct_definition.name(),
                                 &fields,
                                 vec![],
                                 struct_definition.visibility.clone(),
+                                struct_definition.location,
                             )
                             .unwrap_or_else(|_| {
                                 self.errors.push(TypeCheckError::RegistrationFailed {
@@ -1661,6 +2007,7 @@ impl TypeChecker {
                                 &enum_definition.name(),
                                 &variants,
                                 enum_definition.visibility.clone(),
+                                enum_definition.location,
                             )
                             .unwrap_or_else(|_| {
                                 self.errors.push(TypeCheckError::RegistrationFailed {
@@ -1690,9 +2037,10 @@ impl TypeChecker {
                         self.infer_variables(function_definition.clone(), ctx);
                     }
                     Definition::Constant(constant_definition) => {
-                        if let Err(err) = self.symbol_table.push_variable_to_scope(
+                        if let Err(err) = self.declare_variable(
                             &constant_definition.name(),
                             TypeInfo::new(&constant_definition.ty),
+                            constant_definition.location,
                         ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Variable,
@@ -1725,6 +2073,7 @@ impl TypeChecker {
                                 .as_ref()
                                 .unwrap_or(&Type::Simple(SimpleTypeKind::Unit))
                                 .clone(),
+                            external_function_definition.location,
                         ) {
                             self.errors.push(TypeCheckError::RegistrationFailed {
                                 kind: RegistrationKind::Function,
@@ -1744,7 +2093,8 @@ impl TypeChecker {
 
     /// Process all use directives in source files (Phase A of import resolution).
     fn process_directives(&mut self, ctx: &mut TypedContext) {
-        for source_file in ctx.source_files() {
+        for (file_index, source_file) in ctx.source_files().iter().enumerate() {
+            self.set_current_file(file_index);
             for directive in &source_file.directives {
                 match directive {
                     Directive::Use(use_directive) => {
@@ -1802,6 +2152,7 @@ impl TypeChecker {
             path,
             kind,
             location: use_stmt.location,
+            source_file: self.current_file,
         };
         self.symbol_table.register_import(import)
     }
@@ -1827,6 +2178,7 @@ impl TypeChecker {
         };
 
         for import in imports {
+            self.set_current_file(import.source_file);
             match &import.kind {
                 ImportKind::Plain => {
                     if let Some(symbol_name) = import.path.last() {
@@ -1839,6 +2191,7 @@ impl TypeChecker {
                                 self.check_and_report_visibility(
                                     &Visibility::Private,
                                     def_scope_id,
+                                    symbol.definition_location(),
                                     &import.location,
                                     VisibilityContext::Import {
                                         path: import.path.join("::"),
@@ -1875,6 +2228,7 @@ impl TypeChecker {
                                 self.check_and_report_visibility(
                                     &Visibility::Private,
                                     def_scope_id,
+                                    symbol.definition_location(),
                                     &import.location,
                                     VisibilityContext::Import {
                                         path: full_path.join("::"),
@@ -1976,6 +2330,7 @@ impl TypeChecker {
         &mut self,
         visibility: &Visibility,
         definition_scope: u32,
+        definition_location: Option<Location>,
         location: &Location,
         context: VisibilityContext,
     ) -> bool {
@@ -1983,9 +2338,14 @@ impl TypeChecker {
         if self.check_visibility(visibility, definition_scope, access_scope) {
             true
         } else {
+            let related = definition_location
+                .map(|loc| RelatedNote::new(format!("{context} is defined here"), loc))
+                .into_iter()
+                .collect();
             self.errors.push(TypeCheckError::PrivateAccessViolation {
                 context,
                 location: *location,
+                related,
             });
             false
         }
@@ -2099,3 +2459,13 @@ impl TypeChecker {
         self.errors.push(error);
     }
 }
+
+/// Orders locations by start position, then end position.
+fn location_order(a: &Location, b: &Location) -> std::cmp::Ordering {
+    (a.start_line, a.start_column, a.end_line, a.end_column).cmp(&(
+        b.start_line,
+        b.start_column,
+        b.end_line,
+        b.end_column,
+    ))
+}
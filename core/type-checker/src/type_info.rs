@@ -416,6 +416,11 @@ impl TypeInfo {
         matches!(self.kind, TypeInfoKind::Bool)
     }
 
+    #[must_use]
+    pub fn is_string(&self) -> bool {
+        matches!(self.kind, TypeInfoKind::String)
+    }
+
     #[must_use]
     pub fn is_struct(&self) -> bool {
         matches!(self.kind, TypeInfoKind::Struct(_))
@@ -426,6 +431,29 @@ impl TypeInfo {
         matches!(self.kind, TypeInfoKind::Generic(_))
     }
 
+    /// Returns true if both types denote the same type.
+    ///
+    /// Type annotations name a struct or enum as `Custom(name)` while struct literals are
+    /// typed `Struct(name)` and variant accesses `Enum(name)`; these are treated as equal
+    /// here, including as array element types.
+    #[must_use = "this is a pure check with no side effects"]
+    pub fn is_same_type(&self, other: &TypeInfo) -> bool {
+        match (&self.kind, &other.kind) {
+            (
+                TypeInfoKind::Custom(left),
+                TypeInfoKind::Struct(right) | TypeInfoKind::Enum(right),
+            )
+            | (
+                TypeInfoKind::Struct(left) | TypeInfoKind::Enum(left),
+                TypeInfoKind::Custom(right),
+            ) => left == right && self.type_params == other.type_params,
+            (TypeInfoKind::Array(left, left_length), TypeInfoKind::Array(right, right_length)) => {
+                left_length == right_length && left.is_same_type(right)
+            }
+            _ => self == other,
+        }
+    }
+
     /// Returns true if this is a signed integer type (i8, i16, i32, i64).
     #[must_use = "this is a pure check with no side effects"]
     pub fn is_signed_integer(&self) -> bool {
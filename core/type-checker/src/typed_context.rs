@@ -70,6 +70,10 @@
 //! - [`filter_nodes`](TypedContext::filter_nodes) - Find nodes matching predicate
 //! - [`source_files`](TypedContext::source_files) - Get all source files
 //! - [`functions`](TypedContext::functions) - Get all function definitions
+//! - [`render_signature`](TypedContext::render_signature) - Render a definition's signature
+//! - [`struct_layout`](TypedContext::struct_layout) - Memory layout of a struct type
+//! - [`enum_variants`](TypedContext::enum_variants) - Variants of an enum type in tag order
+//! - [`scopes`](TypedContext::scopes) - Summary of the symbol table, scope by scope
 //!
 //! ## Arena Integration
 //!
@@ -82,8 +86,11 @@
 use std::rc::Rc;
 
 use crate::{
+    layout::{self, StructLayout},
     symbol_table::SymbolTable,
+    symbols::{self, ScopeSummary},
     type_info::{NumberType, TypeInfo, TypeInfoKind},
+    warnings::TypeCheckWarning,
 };
 use inference_ast::{
     arena::Arena,
@@ -96,6 +103,7 @@ pub struct TypedContext {
     pub(crate) symbol_table: SymbolTable,
     node_types: FxHashMap<u32, TypeInfo>,
     arena: Arena,
+    warnings: Vec<TypeCheckWarning>,
 }
 
 impl TypedContext {
@@ -104,9 +112,22 @@ impl TypedContext {
             symbol_table: SymbolTable::default(),
             node_types: FxHashMap::default(),
             arena,
+            warnings: Vec::new(),
         }
     }
 
+    /// Returns warnings reported during type checking.
+    ///
+    /// Warnings are ordered by source file, then location, then warning code.
+    #[must_use]
+    pub fn warnings(&self) -> &[TypeCheckWarning] {
+        &self.warnings
+    }
+
+    pub(crate) fn set_warnings(&mut self, warnings: Vec<TypeCheckWarning>) {
+        self.warnings = warnings;
+    }
+
     /// Returns all source files in the arena.
     ///
     /// Each source file contains its definitions (functions, structs, enums, etc.)
@@ -180,6 +201,90 @@ impl TypedContext {
         self.arena.filter_nodes(fn_predicate)
     }
 
+    /// Returns the id of the source file that contains the node.
+    ///
+    /// Returns `None` if the node does not exist or has no `SourceFile` ancestor.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub fn find_source_file_for_node(&self, node_id: u32) -> Option<u32> {
+        self.arena.find_source_file_for_node(node_id)
+    }
+
+    /// Renders the signature of a definition for hover and explain output.
+    ///
+    /// `def_id` is the node id of a definition or struct field. The result has
+    /// the declaration header as written in the source (visibility, generics,
+    /// parameters and return type, without a body), followed by a blank line
+    /// and the text of the preceding `///` doc comment when there is one.
+    ///
+    /// Returns `None` if the node does not exist or is not a definition.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let function = typed_context.functions()[0].clone();
+    /// assert_eq!(
+    ///     typed_context.render_signature(function.id).as_deref(),
+    ///     Some("pub fn max T'(a: T, b: T) -> T")
+    /// );
+    /// ```
+    #[must_use = "returns the rendered signature without side effects"]
+    pub fn render_signature(&self, def_id: u32) -> Option<String> {
+        crate::signature::render_signature(&self.arena, def_id)
+    }
+
+    /// Returns the memory layout of the struct called `name`.
+    ///
+    /// Returns `None` if the struct is unknown, contains itself by value, or has a field
+    /// whose type has no memory representation yet. See [`layout`](crate::layout) for the
+    /// layout rules.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // struct Pair { flag: bool; value: i32; }
+    /// let layout = typed_context.struct_layout("Pair").unwrap();
+    /// assert_eq!(layout.fields[1].offset, 4);
+    /// assert_eq!(layout.size, 8);
+    /// ```
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub fn struct_layout(&self, name: &str) -> Option<StructLayout> {
+        layout::struct_layout(&self.symbol_table, name)
+    }
+
+    /// Returns the variant names of the enum called `name` in declaration order.
+    ///
+    /// Enum values are represented by the index of their variant in this list.
+    /// Returns `None` if no such enum is visible from the current scope.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // enum Color { Red, Green, Blue }
+    /// let variants = typed_context.enum_variants("Color").unwrap();
+    /// assert_eq!(variants, ["Red", "Green", "Blue"]);
+    /// ```
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub fn enum_variants(&self, name: &str) -> Option<Vec<String>> {
+        layout::enum_variants(&self.symbol_table, name)
+    }
+
+    /// Returns the size and alignment in bytes of a value of type `type_info`.
+    ///
+    /// Returns `None` for types without a memory representation.
+    #[must_use = "this is a pure lookup with no side effects"]
+    pub fn size_and_align(&self, type_info: &TypeInfo) -> Option<(u32, u32)> {
+        layout::size_and_align(&self.symbol_table, type_info)
+    }
+
+    /// Returns every scope of the symbol table with the symbols declared in it,
+    /// sorted by scope ID.
+    ///
+    /// See [`symbols`](crate::symbols) for what each scope lists.
+    #[must_use]
+    pub fn scopes(&self) -> Vec<ScopeSummary> {
+        symbols::summarize(&self.symbol_table)
+    }
+
     /// Checks if a node has type `i32`.
     ///
     /// This is a convenience method for the common case of checking if a node
@@ -250,6 +355,21 @@ impl TypedContext {
         self.node_types.get(&node_id).cloned()
     }
 
+    /// Returns every node of the arena that has type information, with its type, in
+    /// ascending node ID order.
+    ///
+    /// This is the complete type mapping of the typed AST, e.g. for dumping it to tools.
+    #[must_use = "returns typed nodes without side effects"]
+    pub fn typed_nodes(&self) -> Vec<(AstNode, &TypeInfo)> {
+        let mut nodes: Vec<(AstNode, &TypeInfo)> = self
+            .node_types
+            .iter()
+            .filter_map(|(id, type_info)| self.arena.find_node(*id).map(|node| (node, type_info)))
+            .collect();
+        nodes.sort_by_key(|(node, _)| node.id());
+        nodes
+    }
+
     /// Gets the parent node of a given node ID.
     ///
     /// Returns `Some(AstNode)` if the node has a parent, or `None` if:
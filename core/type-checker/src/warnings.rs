@@ -0,0 +1,139 @@
+//! Type Checker Warnings
+//!
+//! This module defines [`TypeCheckWarning`], the non-fatal diagnostics produced
+//! during type checking. Warnings do not stop type checking; they are stored on
+//! the [`TypedContext`](crate::typed_context::TypedContext) and can be queried
+//! with [`TypedContext::warnings`](crate::typed_context::TypedContext::warnings).
+//!
+//! ## Warning Categories
+//!
+//! - **Shadowing**: a variable declaration hides a binding from an enclosing scope
+//! - **Redeclaration**: a variable declaration replaces a binding in the same scope
+//!
+//! Shadowing and redeclaration can be turned into hard errors with
+//! [`TypeCheckerBuilder::deny_shadowing`](crate::TypeCheckerBuilder::deny_shadowing),
+//! which reports them as [`TypeCheckError::DeniedWarning`](crate::errors::TypeCheckError::DeniedWarning).
+
+use inference_ast::nodes::Location;
+use thiserror::Error;
+
+/// Code and name of every type checker warning kind, in code order.
+///
+/// The name identifies the kind when selecting which warnings to report, such as
+/// `shadowed_binding`.
+pub const WARNING_KINDS: [(&str, &str); 2] =
+    [("W0001", "shadowed_binding"), ("W0002", "redeclaration")];
+
+/// Non-fatal diagnostic produced during type checking.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TypeCheckWarning {
+    #[error("{location}: `{name}` shadows a binding declared at {previous}")]
+    ShadowedBinding {
+        name: String,
+        location: Location,
+        previous: Location,
+    },
+
+    #[error(
+        "{location}: `{name}` is redeclared in the same scope, previously declared at {previous}"
+    )]
+    Redeclaration {
+        name: String,
+        location: Location,
+        previous: Location,
+    },
+}
+
+impl TypeCheckWarning {
+    /// Returns the source location associated with this warning.
+    #[must_use]
+    pub fn location(&self) -> &Location {
+        match self {
+            TypeCheckWarning::ShadowedBinding { location, .. }
+            | TypeCheckWarning::Redeclaration { location, .. } => location,
+        }
+    }
+
+    /// Returns the stable diagnostic code for this warning kind.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeCheckWarning::ShadowedBinding { .. } => "W0001",
+            TypeCheckWarning::Redeclaration { .. } => "W0002",
+        }
+    }
+
+    /// Returns the name of this warning kind, as listed in [`WARNING_KINDS`].
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            TypeCheckWarning::ShadowedBinding { .. } => "shadowed_binding",
+            TypeCheckWarning::Redeclaration { .. } => "redeclaration",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(line: u32, column: u32) -> Location {
+        Location {
+            offset_start: 0,
+            offset_end: 0,
+            start_line: line,
+            start_column: column,
+            end_line: line,
+            end_column: column,
+        }
+    }
+
+    #[test]
+    fn shadowed_binding_names_both_locations() {
+        let warning = TypeCheckWarning::ShadowedBinding {
+            name: "x".to_string(),
+            location: location(3, 9),
+            previous: location(1, 5),
+        };
+        assert_eq!(
+            warning.to_string(),
+            "3:9: `x` shadows a binding declared at 1:5"
+        );
+        assert_eq!(warning.code(), "W0001");
+    }
+
+    #[test]
+    fn redeclaration_names_both_locations() {
+        let warning = TypeCheckWarning::Redeclaration {
+            name: "x".to_string(),
+            location: location(2, 9),
+            previous: location(1, 5),
+        };
+        assert_eq!(
+            warning.to_string(),
+            "2:9: `x` is redeclared in the same scope, previously declared at 1:5"
+        );
+        assert_eq!(warning.location(), &location(2, 9));
+    }
+
+    #[test]
+    fn warning_names_match_their_codes() {
+        let warnings = [
+            TypeCheckWarning::ShadowedBinding {
+                name: "x".to_string(),
+                location: location(1, 1),
+                previous: location(1, 1),
+            },
+            TypeCheckWarning::Redeclaration {
+                name: "x".to_string(),
+                location: location(1, 1),
+                previous: location(1, 1),
+            },
+        ];
+        let kinds: Vec<_> = warnings
+            .iter()
+            .map(|warning| (warning.code(), warning.name()))
+            .collect();
+        assert_eq!(kinds, WARNING_KINDS);
+    }
+}
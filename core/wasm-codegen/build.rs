@@ -1,7 +1,6 @@
 //! Build script for inference-wasm-codegen crate.
 //!
 //! This script handles the deployment of external binaries required for WebAssembly compilation:
-//! - inf-llc: Modified LLVM compiler with Inference intrinsics support
 //! - rust-lld: WebAssembly linker from Rust toolchain
 //! - libLLVM: LLVM shared library (Linux only)
 //!
@@ -13,12 +12,17 @@
 //! 5. Emits cargo directives for rerun conditions
 //!
 //! If binaries are missing, the script fails with download URLs for the required files.
+//! Without the `llvm` feature the crate uses neither binary, and the script does nothing.
 
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
 fn main() {
+    if env::var_os("CARGO_FEATURE_LLVM").is_none() {
+        return;
+    }
+
     let platform = if cfg!(target_os = "linux") {
         "linux"
     } else if cfg!(target_os = "macos") {
@@ -30,7 +34,6 @@ fn main() {
     };
 
     let exe_suffix = std::env::consts::EXE_SUFFIX;
-    let llc_binary = format!("inf-llc{exe_suffix}");
     let rust_lld_binary = format!("rust-lld{exe_suffix}");
     let libllvm_lib = if cfg!(target_os = "windows") {
         "libLLVM.dll"
@@ -48,18 +51,11 @@ fn main() {
     let source_bin_dir = workspace_root.join("external").join("bin").join(platform);
     let source_lib_dir = workspace_root.join("external").join("lib").join(platform);
 
-    let source_llc = source_bin_dir.join(&llc_binary);
     let source_rust_lld = source_bin_dir.join(&rust_lld_binary);
     let source_lib_llvm = source_lib_dir.join(libllvm_lib);
 
     // Check for missing binaries and provide download instructions
     let mut missing_files = Vec::new();
-    if !source_llc.exists() {
-        missing_files.push((
-            source_llc.display().to_string(),
-            get_download_url(platform, "inf-llc"),
-        ));
-    }
     if !source_rust_lld.exists() {
         missing_files.push((
             source_rust_lld.display().to_string(),
@@ -94,38 +90,14 @@ fn main() {
 
     let bin_dir = target_profile_dir.join("bin");
     let lib_dir = target_profile_dir.join("lib");
-    let dest_llc = bin_dir.join(&llc_binary);
     let dest_rust_lld = bin_dir.join(&rust_lld_binary);
     let dest_lib_llvm = lib_dir.join(libllvm_lib);
 
-    if source_llc.exists() {
+    if source_rust_lld.exists() {
         if !bin_dir.exists() {
             fs::create_dir_all(&bin_dir).expect("Failed to create bin directory");
         }
 
-        fs::copy(&source_llc, &dest_llc).unwrap_or_else(|e| {
-            panic!(
-                "Failed to copy inf-llc from {} to {}: {}",
-                source_llc.display(),
-                dest_llc.display(),
-                e
-            )
-        });
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            let mut perms = fs::metadata(&dest_llc)
-                .expect("Failed to read inf-llc metadata")
-                .permissions();
-            perms.set_mode(0o755);
-            fs::set_permissions(&dest_llc, perms).expect("Failed to set executable permissions");
-        }
-
-        println!("cargo:info=Copied inf-llc to {}", dest_llc.display());
-    }
-
-    if source_rust_lld.exists() {
         fs::copy(&source_rust_lld, &dest_rust_lld).unwrap_or_else(|e| {
             panic!(
                 "Failed to copy rust-lld from {} to {}: {}",
@@ -174,7 +146,6 @@ fn main() {
         println!("cargo:info=Copied libLLVM to {}", dest_lib_llvm.display());
     }
 
-    println!("cargo:rerun-if-changed={}", source_llc.display());
     println!("cargo:rerun-if-changed={}", source_rust_lld.display());
     println!("cargo:rerun-if-changed={}", source_lib_llvm.display());
 }
@@ -184,7 +155,7 @@ fn main() {
 /// # Parameters
 ///
 /// - `platform` - Target platform: "linux", "macos", or "windows"
-/// - `binary` - Binary name: "inf-llc", "rust-lld", or "libLLVM"
+/// - `binary` - Binary name: "rust-lld" or "libLLVM"
 ///
 /// # Returns
 ///
@@ -192,9 +163,6 @@ fn main() {
 /// README if the binary/platform combination is not recognized.
 fn get_download_url(platform: &str, binary: &str) -> String {
     match (platform, binary) {
-        ("linux", "inf-llc") => {
-            "https://storage.googleapis.com/external_binaries/linux/bin/inf-llc.zip".to_string()
-        }
         ("linux", "rust-lld") => {
             "https://storage.googleapis.com/external_binaries/linux/bin/rust-lld.zip"
                 .to_string()
@@ -202,16 +170,9 @@ fn get_download_url(platform: &str, binary: &str) -> String {
         ("linux", "libLLVM") => {
             "https://storage.googleapis.com/external_binaries/linux/lib/libLLVM.so.21.1-rust-1.94.0-nightly.zip".to_string()
         }
-        ("windows", "inf-llc") => {
-            "https://storage.googleapis.com/external_binaries/windows/bin/inf-llc.zip"
-                .to_string()
-        }
         ("windows", "rust-lld") => {
             "https://storage.googleapis.com/external_binaries/windows/bin/rust-lld.zip".to_string()
         }
-        ("macos", "inf-llc") => {
-            "https://storage.googleapis.com/external_binaries/macos/bin/inf-llc.zip".to_string()
-        }
         ("macos", "rust-lld") => {
             "https://storage.googleapis.com/external_binaries/macos/bin/rust-lld.zip".to_string()
         }
@@ -0,0 +1,233 @@
+//! Object code cache.
+//!
+//! With [`CodegenOptions::cache_dir`] set, every function is lowered into its own codegen
+//! unit and emitted as its own object file. The object is stored in the cache directory
+//! under a SHA-256 key of the function's typed AST, and a later run whose key is unchanged
+//! reuses the stored object instead of lowering and emitting the function again. Only the
+//! functions missing from the cache are compiled, on the threads of [`parallel`].
+//!
+//! ## Cache Keys
+//!
+//! The key of a function covers everything its object code depends on:
+//!
+//! - the code generator version and the options that change the emitted code
+//! - the module interface: the signatures of all functions, the `external fn`
+//!   declarations with their import names, and every other definition of the source file,
+//!   such as struct and enum types
+//! - the function itself: its source text, the type of every typed node in it, its export
+//!   name and, with debug information, its position in the source file
+//!
+//! Node IDs are not part of the key, since they shift whenever code is added earlier in
+//! the file. Keys are independent of the other function bodies, so editing one function
+//! only recompiles that function.
+//!
+//! Objects are written to a temporary file that is renamed into place, so an interrupted
+//! or concurrent run never leaves a truncated object under a valid key.
+
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    interface,
+    options::CodegenOptions,
+    parallel::{self, LoweredUnits},
+    utils,
+};
+use inference_ast::nodes::{Definition, SourceFile};
+use inference_type_checker::typed_context::TypedContext;
+use sha2::{Digest, Sha256};
+
+/// Version of the cache key format, bumped whenever lowering changes the code emitted for
+/// an unchanged function.
+const CACHE_KEY_VERSION: u32 = 2;
+
+/// Compiles the functions of `typed_context` through the object cache in `cache_dir` and
+/// links them.
+///
+/// # Errors
+///
+/// Returns [`CodegenErrors`](crate::errors::CodegenErrors) if a function missing from the
+/// cache cannot be lowered, or an error if the cache directory cannot be written, object
+/// emission fails or linking fails.
+pub(crate) fn compile(
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+    cache_dir: &Path,
+) -> anyhow::Result<Vec<u8>> {
+    let keys = function_keys(typed_context, options);
+    let mut objects = keys
+        .iter()
+        .map(|key| fs::read(object_path(cache_dir, key)).ok())
+        .collect::<Vec<_>>();
+    let missing = objects
+        .iter()
+        .enumerate()
+        .filter(|(_, object)| object.is_none())
+        .map(|(index, _)| index..index + 1)
+        .collect::<Vec<Range<usize>>>();
+
+    if !missing.is_empty() {
+        let LoweredUnits { bitcode, .. } = parallel::lower_units(typed_context, options, &missing)?;
+        let threads = options.effective_codegen_units(missing.len());
+        let emitted = parallel::emit_objects(&bitcode, options, threads)?;
+        fs::create_dir_all(cache_dir)?;
+        for (range, object) in missing.iter().zip(emitted) {
+            store(cache_dir, &keys[range.start], &object)?;
+            objects[range.start] = Some(object);
+        }
+    }
+
+    let objects = objects.into_iter().flatten().collect::<Vec<_>>();
+    utils::link_objects(
+        &objects,
+        "output.wasm",
+        options,
+        has_main(typed_context, options),
+    )
+}
+
+/// Returns true if the module has a `main` function exported as `main`, which is passed
+/// to the linker even when it was not lowered in this run.
+fn has_main(typed_context: &TypedContext, options: &CodegenOptions) -> bool {
+    typed_context.functions().iter().any(|function| {
+        function.name() == "main"
+            && interface::export_name(function, options).as_deref() == Some("main")
+    })
+}
+
+/// Returns the cache key of every function definition, in source order.
+fn function_keys(typed_context: &TypedContext, options: &CodegenOptions) -> Vec<String> {
+    let mut keys = Vec::new();
+    for source_file in &typed_context.source_files() {
+        let interface = interface_digest(source_file, typed_context, options);
+        let functions = source_file.function_definitions();
+        let mut typed_nodes = vec![Vec::new(); functions.len()];
+        for node in typed_context.filter_nodes(|_| true) {
+            let location = node.location();
+            // Functions are in source order, so the only candidate is the last function
+            // starting at or before the node.
+            let index = functions.partition_point(|function| {
+                function.location.offset_start <= location.offset_start
+            });
+            let Some(index) = index.checked_sub(1) else {
+                continue;
+            };
+            if location.offset_end > functions[index].location.offset_end {
+                continue;
+            }
+            if let Some(type_info) = typed_context.get_node_typeinfo(node.id()) {
+                let start = functions[index].location.offset_start;
+                typed_nodes[index].push((
+                    location.offset_start - start,
+                    location.offset_end - start,
+                    type_info.to_string(),
+                ));
+            }
+        }
+        for (function, mut nodes) in functions.iter().zip(typed_nodes) {
+            nodes.sort();
+            let mut hasher = Sha256::new();
+            hasher.update(&interface);
+            hasher.update(source_text(
+                source_file,
+                function.location.offset_start..function.location.offset_end,
+            ));
+            for (start, end, type_info) in nodes {
+                hasher.update(format!("\n{start}:{end}:{type_info}"));
+            }
+            hasher.update(format!(
+                "\nexport {:?}",
+                interface::export_name(function, options)
+            ));
+            if options.emits_dwarf() {
+                hasher.update(format!(
+                    "\nat {}:{}",
+                    function.location.start_line, function.location.start_column
+                ));
+            }
+            keys.push(hex::encode(hasher.finalize()));
+        }
+    }
+    keys
+}
+
+/// Hashes the options and the module interface shared by every function of `source_file`.
+fn interface_digest(
+    source_file: &SourceFile,
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(format!(
+        "{} {} {CACHE_KEY_VERSION}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    ));
+    hasher.update(format!(
+        "{} {:?} {} {:?} {:?} {} {:?} {}\n",
+        options.target_triple(),
+        options.llvm_opt_level(),
+        options.llvm_features(),
+        options.bounds_checks,
+        options.assert_strategy,
+        options.emits_dwarf(),
+        options.source_path,
+        options.module_name
+    ));
+    for definition in &source_file.definitions {
+        match definition {
+            Definition::Function(function) => {
+                let signature = typed_context.render_signature(function.id);
+                hasher.update(format!("fn {signature:?}\n"));
+            }
+            Definition::ExternalFunction(external) => {
+                let location = external.location;
+                let import_name = options.import_name(&external.name());
+                hasher.update(source_text(
+                    source_file,
+                    location.offset_start..location.offset_end,
+                ));
+                hasher.update(format!(" {import_name:?}\n"));
+            }
+            definition => {
+                let location = definition.location();
+                hasher.update(source_text(
+                    source_file,
+                    location.offset_start..location.offset_end,
+                ));
+                hasher.update("\n");
+            }
+        }
+    }
+    hasher.finalize().to_vec()
+}
+
+/// Returns the source text in the byte range `range`, or an empty string if the range is
+/// not valid for the source.
+fn source_text(source_file: &SourceFile, range: Range<u32>) -> &str {
+    source_file
+        .source
+        .get(range.start as usize..range.end as usize)
+        .unwrap_or_default()
+}
+
+/// Returns the path of the cached object stored under `key`.
+fn object_path(cache_dir: &Path, key: &str) -> PathBuf {
+    cache_dir.join(key).with_extension("o")
+}
+
+/// Stores `object` under `key`, replacing any previous object atomically.
+///
+/// # Errors
+///
+/// Returns an error if the object cannot be written.
+fn store(cache_dir: &Path, key: &str, object: &[u8]) -> anyhow::Result<()> {
+    let path = object_path(cache_dir, key);
+    let temp_path = path.with_extension(format!("o.{}.tmp", std::process::id()));
+    fs::write(&temp_path, object)?;
+    fs::rename(&temp_path, &path)?;
+    Ok(())
+}
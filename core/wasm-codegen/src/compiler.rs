@@ -16,12 +16,13 @@
 //!
 //! The compiler operates in several stages:
 //!
-//! 1. **Function lowering** - Convert AST function definitions to LLVM function declarations
+//! 1. **Function lowering** - Declare every function first, so calls can refer to functions
+//!    defined later in the file, then lower each body
 //! 2. **Statement lowering** - Translate control flow and non-deterministic blocks
 //! 3. **Expression lowering** - Generate LLVM IR for expressions and literals
 //! 4. **Intrinsic injection** - Insert LLVM intrinsic calls for non-deterministic operations
-//! 5. **WASM emission** - Compile LLVM IR to WebAssembly object files via inf-llc
-//! 6. **Linking** - Link object files into final WASM module via rust-lld
+//! 5. **WASM emission** - Compile LLVM IR to WebAssembly object files via an LLVM target machine
+//! 6. **Linking** - Link object files into the final WASM module (see [`crate::linker`])
 //!
 //! # Type Mapping
 //!
@@ -35,14 +36,24 @@
 //! | `i16`, `u16`   | i16       | i32       |
 //! | `i32`, `u32`   | i32       | i32       |
 //! | `i64`, `u64`   | i64       | i64       |
+//! | struct         | ptr       | i32       |
+//! | `[T; N]`       | ptr       | i32       |
+//! | `string`       | ptr       | i32       |
+//! | enum           | i32       | i32       |
 //!
 //! Note: WebAssembly only supports i32, i64, f32, and f64 as value types. Smaller integer
 //! types use i32 with appropriate truncation/extension.
 //!
+//! Structs and arrays live in linear memory and are passed around by pointer. Their field offsets come
+//! from the type checker's [`StructLayout`]; see the `memory` submodule for details. Enum
+//! values are the index of their variant; see the `enums` submodule. Strings are a pointer
+//! to a `{ ptr, i32 }` pair of their bytes and length, and literals are placed in data
+//! segments; see the `strings` submodule.
+//!
 //! # Non-Deterministic Operations
 //!
 //! The compiler emits LLVM intrinsic calls for non-deterministic operations. These intrinsics
-//! are recognized by inf-llc and compiled to custom WASM instructions with binary encoding
+//! are recognized by the forked LLVM backend and compiled to custom WASM instructions with binary encoding
 //! in the 0xfc prefix space:
 //!
 //! - `uzumaki()` - Non-deterministic value generation (0xfc 0x3a for i32, 0xfc 0x3c for i64)
@@ -86,32 +97,63 @@
 //! Functions containing non-deterministic blocks receive `optnone` and `noinline` attributes
 //! to prevent LLVM from optimizing away the intrinsic calls, which would break formal
 //! verification guarantees.
+//!
+//! # Errors
+//!
+//! Declaring and lowering return a [`CodegenError`] for constructs without code generation
+//! support and for gaps in the typed AST. Errors detected away from a specific node, such
+//! as an unsupported parameter or variable type, are reported at the function or statement
+//! being lowered. See [`crate::errors`] for how errors are collected per module.
 
 //TODO: don't forget to remove
 #![allow(dead_code)]
-use crate::utils;
+use crate::{
+    debug_info::DebugInfo,
+    errors::{CodegenError, CodegenErrorKind},
+    options::{AssertStrategy, BoundsChecks, CodegenOptions, ImportName},
+    utils,
+};
 use inference_ast::nodes::{
-    BlockType, Expression, FunctionDefinition, Literal, SimpleTypeKind, Statement, Type, Visibility,
+    ArgumentType, BinaryExpression, BlockType, Expression, ExternalFunctionDefinition,
+    FunctionDefinition, Literal, Location, OperatorKind, SimpleTypeKind, Statement, Type,
+    Visibility,
 };
 use inference_type_checker::{
-    type_info::{NumberType, TypeInfoKind},
+    layout::StructLayout,
+    type_info::{TypeInfo, TypeInfoKind},
     typed_context::TypedContext,
 };
 use inkwell::{
+    IntPredicate,
     attributes::{Attribute, AttributeLoc},
+    basic_block::BasicBlock,
     builder::Builder,
     context::Context,
+    debug_info::DIScope,
     module::Module,
-    types::BasicTypeEnum,
-    values::{FunctionValue, PointerValue},
+    targets::TargetMachine,
+    types::{BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, StructType},
+    values::{BasicValueEnum, FunctionValue, PointerValue},
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    iter::Peekable,
+    rc::Rc,
 };
-use std::{cell::RefCell, collections::HashMap, iter::Peekable, rc::Rc};
+
+mod arithmetic;
+mod asserts;
+mod enums;
+mod folding;
+mod memory;
+mod strings;
 
 // ================================================================================================
 // LLVM Intrinsic Names for Non-Deterministic Operations
 // ================================================================================================
 //
-// These constants define the intrinsic function names that LLVM recognizes and inf-llc compiles
+// These constants define the intrinsic function names that the forked LLVM backend compiles
 // to custom WebAssembly instructions. Each intrinsic has a specific binary encoding in the WASM
 // 0xfc prefix instruction space.
 //
@@ -161,11 +203,15 @@ const UNIQUE_START_INTRINSIC: &str = "llvm.wasm.unique.start";
 /// Compiles to WASM instruction 0xfc 0x41.
 const UNIQUE_END_INTRINSIC: &str = "llvm.wasm.unique.end";
 
+/// LLVM intrinsic that aborts execution, used for failed bounds checks and asserts.
+/// Compiles to the WASM `unreachable` instruction.
+const TRAP_INTRINSIC: &str = "llvm.trap";
+
 /// LLVM-based compiler for generating WebAssembly bytecode from typed AST.
 ///
 /// The compiler maintains LLVM context, module, and builder state throughout the
 /// compilation process. It uses Inkwell (Rust bindings for LLVM) to generate LLVM IR,
-/// which is then compiled to WebAssembly via external tools (inf-llc and rust-lld).
+/// which is then compiled to WebAssembly by an LLVM target machine and linked.
 ///
 /// # Lifetime
 ///
@@ -174,8 +220,9 @@ const UNIQUE_END_INTRINSIC: &str = "llvm.wasm.unique.end";
 ///
 /// # Variable Storage
 ///
-/// Local variables and constants are stored in a `RefCell<HashMap>` mapping names to
-/// (pointer, type) pairs. This allows mutation during IR generation while maintaining
+/// Local variables, constants and parameters are stored in a `RefCell<HashMap>` mapping
+/// names to (pointer, type) pairs. For struct values the type is the LLVM struct type and
+/// the pointer addresses the struct in linear memory. This allows mutation during IR generation while maintaining
 /// Rust's borrowing rules through interior mutability.
 ///
 /// # Internal Usage Example
@@ -189,15 +236,20 @@ const UNIQUE_END_INTRINSIC: &str = "llvm.wasm.unique.end";
 ///
 /// // Create LLVM context and compiler
 /// let context = Context::create();
-/// let compiler = Compiler::new(&context, "wasm_module");
+/// let options = CodegenOptions::default();
+/// let compiler = Compiler::new(&context, &options)?;
 ///
-/// // Visit function definitions from typed AST
-/// for func_def in typed_context.source_files()[0].function_definitions() {
-///     compiler.visit_function_definition(&func_def, &typed_context);
+/// // Declare all functions, then lower their bodies
+/// let functions = typed_context.source_files()[0].function_definitions();
+/// for func_def in &functions {
+///     compiler.declare_function(func_def, None, &typed_context)?;
+/// }
+/// for func_def in &functions {
+///     compiler.visit_function_definition(func_def, &typed_context)?;
 /// }
 ///
 /// // Compile to WebAssembly
-/// let wasm_bytes = compiler.compile_to_wasm("output.wasm", 3)?;
+/// let wasm_bytes = compiler.compile_to_wasm("output.wasm", &options)?;
 /// ```
 pub(crate) struct Compiler<'ctx> {
     /// LLVM context for creating types and values.
@@ -223,15 +275,62 @@ pub(crate) struct Compiler<'ctx> {
     /// during IR generation while maintaining Rust's borrowing rules.
     variables: RefCell<HashMap<String, (PointerValue<'ctx>, BasicTypeEnum<'ctx>)>>,
 
-    /// Tracks whether a `main` function was compiled.
+    /// Values of the `const` definitions visible in the function being lowered, which
+    /// uses of their names fold to. See [`folding`].
+    constants: RefCell<folding::Constants>,
+
+    /// Values of the module-level `const` definitions, visible in every function.
+    module_constants: RefCell<folding::Constants>,
+
+    /// Text of the module-level `const` definitions of type `string`, visible in every
+    /// function. See [`strings`].
+    module_strings: RefCell<HashMap<String, String>>,
+
+    /// Addresses of the bytes of the string literals emitted so far, keyed by their text,
+    /// so identical literals share one global.
+    string_literals: RefCell<HashMap<String, PointerValue<'ctx>>>,
+
+    /// Tracks whether a `main` function exported as `main` was compiled.
     ///
     /// Used to conditionally export `main` during linking. When true, the linker receives
     /// the `--export=main` flag, which creates a wrapper that provides argc/argv compatibility
     /// for command-line style execution.
     ///
-    /// Note: Only public `main` functions are tracked. Private `main` functions are compiled
-    /// but not exported from the WebAssembly module.
+    /// Note: Only exported `main` functions are tracked. Other `main` functions are compiled
+    /// but not exported under that name.
     has_main: RefCell<bool>,
+
+    /// Target machine for `wasm32-unknown-unknown`. Its data layout decides the size and
+    /// field offsets of aggregate types and is checked against the type checker layouts.
+    target_machine: TargetMachine,
+
+    /// LLVM struct types created so far, keyed by struct name, together with the layout
+    /// the type checker computed for them.
+    struct_types: RefCell<HashMap<String, (StructType<'ctx>, StructLayout)>>,
+
+    /// How array indices are checked, from [`CodegenOptions::bounds_checks`].
+    bounds_checks: BoundsChecks,
+
+    /// How failing asserts are lowered, from [`CodegenOptions::assert_strategy`].
+    assert_strategy: AssertStrategy,
+
+    /// Source file name passed to the abort function of [`AssertStrategy::Abort`].
+    assert_file: String,
+
+    /// DWARF builder, present when [`CodegenOptions::debug_info`] or
+    /// [`CodegenOptions::source_map`] is enabled.
+    debug_info: Option<DebugInfo<'ctx>>,
+
+    /// Debug scope of the function currently being lowered. Statement locations are
+    /// attached to this scope.
+    debug_scope: RefCell<Option<DIScope<'ctx>>>,
+
+    /// Name of the function currently being declared or lowered, reported with errors.
+    current_function: RefCell<String>,
+
+    /// ID and location of the function or statement currently being declared or lowered.
+    /// Errors without a more specific node are reported here.
+    current_node: Cell<(u32, Location)>,
 }
 
 impl<'ctx> Compiler<'ctx> {
@@ -240,18 +339,42 @@ impl<'ctx> Compiler<'ctx> {
     /// # Parameters
     ///
     /// - `context` - LLVM context for creating types and values
-    /// - `module_name` - Name for the generated LLVM module (typically `wasm_module`)
-    pub(crate) fn new(context: &'ctx Context, module_name: &str) -> Self {
-        let module = context.create_module(module_name);
+    /// - `options` - Code generation options; [`CodegenOptions::module_name`] names the LLVM
+    ///   module and [`CodegenOptions::debug_info`] or [`CodegenOptions::source_map`]
+    ///   enables DWARF emission
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the WebAssembly target machine cannot be created.
+    pub(crate) fn new(context: &'ctx Context, options: &CodegenOptions) -> anyhow::Result<Self> {
+        let module = context.create_module(&options.module_name);
+        let target_machine = utils::create_target_machine(&module, options)?;
+        module.set_data_layout(&target_machine.get_target_data().get_data_layout());
         let builder = context.create_builder();
+        let debug_info = options
+            .emits_dwarf()
+            .then(|| DebugInfo::new(context, &module, options));
 
-        Self {
+        Ok(Self {
             context,
             module,
             builder,
             variables: RefCell::new(HashMap::new()),
+            constants: RefCell::new(HashMap::new()),
+            module_constants: RefCell::new(HashMap::new()),
+            module_strings: RefCell::new(HashMap::new()),
+            string_literals: RefCell::new(HashMap::new()),
             has_main: RefCell::new(false), //TODO: revisit
-        }
+            target_machine,
+            struct_types: RefCell::new(HashMap::new()),
+            bounds_checks: options.bounds_checks,
+            assert_strategy: options.assert_strategy.clone(),
+            assert_file: options.source_file().to_string_lossy().into_owned(),
+            debug_info,
+            debug_scope: RefCell::new(None),
+            current_function: RefCell::new(String::new()),
+            current_node: Cell::new((0, Location::default())),
+        })
     }
 
     /// Adds optimization barriers to a function to prevent LLVM from optimizing away
@@ -275,100 +398,315 @@ impl<'ctx> Compiler<'ctx> {
         function.add_attribute(AttributeLoc::Function, noinline);
     }
 
-    /// Translates an AST function definition to LLVM IR.
+    /// Attaches `location` to the instructions emitted next, if debug info is enabled.
+    fn set_debug_location(&self, location: &Location) {
+        if let (Some(debug_info), Some(scope)) = (&self.debug_info, *self.debug_scope.borrow()) {
+            debug_info.set_location(self.context, &self.builder, scope, location);
+        }
+    }
+
+    /// Records the function about to be declared or lowered, for error reporting.
+    fn enter_function(&self, name: &str, node_id: u32, location: Location) {
+        name.clone_into(&mut self.current_function.borrow_mut());
+        self.current_node.set((node_id, location));
+    }
+
+    /// Creates an error at the given node, in the function and basic block being generated.
+    pub(super) fn error_at(
+        &self,
+        kind: CodegenErrorKind,
+        node_id: u32,
+        location: Location,
+    ) -> CodegenError {
+        let block = self
+            .builder
+            .get_insert_block()
+            .map(|block| block.get_name().to_string_lossy().into_owned());
+        CodegenError {
+            kind,
+            node_id,
+            location,
+            function: self.current_function.borrow().clone(),
+            block,
+        }
+    }
+
+    /// Creates an error at the function or statement currently being generated.
+    pub(super) fn error(&self, kind: CodegenErrorKind) -> CodegenError {
+        let (node_id, location) = self.current_node.get();
+        self.error_at(kind, node_id, location)
+    }
+
+    /// Declares an AST function definition in the LLVM module.
     ///
-    /// This is the main entry point for function compilation. It performs several steps:
+    /// This creates the LLVM function with its signature and attributes but without a
+    /// body, so calls can be lowered before the callee's body is. It performs these steps:
     ///
-    /// 1. **Type mapping** - Maps the return type from `Type::Simple(SimpleTypeKind)` to
-    ///    corresponding LLVM types (void, bool, i8, i16, i32, i64)
+    /// 1. **Type mapping** - Maps the parameter and return types to LLVM types with
+    ///    [`Compiler::value_type`]; struct and array parameters are passed as pointers into
+    ///    linear memory, see [`Compiler::lower_call`]
     /// 2. **Function creation** - Declares the function in the LLVM module with the
     ///    appropriate signature
-    /// 3. **Export annotation** - Adds `wasm-export-name` attribute to make the function
-    ///    accessible from WebAssembly
+    /// 3. **Export annotation** - Adds a `wasm-export-name` attribute to export the function
+    ///    as `export_name`; a `main` exported as `main` is exported by the linker instead
     /// 4. **Optimization barriers** - If the function contains non-deterministic blocks,
     ///    applies `optnone` and `noinline` attributes to prevent optimization
-    /// 5. **Body lowering** - Recursively lowers the function body statements to LLVM IR
-    /// 6. **Return handling** - Inserts implicit void return for functions without explicit
-    ///    return statements
-    ///
-    /// # Type Safety Improvement
     ///
-    /// Prior to the introduction of `SimpleTypeKind`, this method used string-based type
-    /// matching (`simple_type.name.to_lowercase().as_str()`), which was error-prone and
-    /// lacked compile-time verification. The refactoring to use `SimpleTypeKind` enum
-    /// provides type-safe pattern matching and ensures all primitive types are explicitly
-    /// handled.
+    /// Declaring a function twice returns the existing declaration.
     ///
     /// # Parameters
     ///
-    /// - `function_definition` - AST node representing the function to compile
-    /// - `ctx` - Typed context containing type information for all AST nodes
+    /// - `function_definition` - AST node representing the function to declare
+    /// - `export_name` - Name under which the function is exported, or `None`
+    /// - `ctx` - Typed context containing type information and struct layouts
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This method will panic if it encounters unsupported type constructs (arrays,
-    /// generics, function types, qualified names, custom types) in return positions,
-    /// as these are not yet implemented. The `todo!()` markers indicate planned future
-    /// support.
-    pub(crate) fn visit_function_definition(
+    /// Returns an error for unsupported parameter or return types (generics, function
+    /// types, qualified names, unknown custom types, `self` parameters, and struct or array
+    /// return types), as these are not yet implemented.
+    pub(crate) fn declare_function(
         &self,
         function_definition: &Rc<FunctionDefinition>,
+        export_name: Option<&str>,
         ctx: &TypedContext,
-    ) {
+    ) -> Result<FunctionValue<'ctx>, CodegenError> {
         let fn_name = function_definition.name();
-        let fn_type = match &function_definition.returns {
-            Some(ret_type) => match ret_type {
-                Type::Simple(SimpleTypeKind::Unit) => self.context.void_type().fn_type(&[], false),
-                Type::Simple(SimpleTypeKind::Bool) => self.context.bool_type().fn_type(&[], false),
-                Type::Simple(SimpleTypeKind::I8 | SimpleTypeKind::U8) => {
-                    self.context.i8_type().fn_type(&[], false)
-                }
-                Type::Simple(SimpleTypeKind::I16 | SimpleTypeKind::U16) => {
-                    self.context.i16_type().fn_type(&[], false)
-                }
-                Type::Simple(SimpleTypeKind::I32 | SimpleTypeKind::U32) => {
-                    self.context.i32_type().fn_type(&[], false)
-                }
-                Type::Simple(SimpleTypeKind::I64 | SimpleTypeKind::U64) => {
-                    self.context.i64_type().fn_type(&[], false)
-                }
-                Type::Array(_array_type) => todo!(),
-                Type::Generic(_generic_type) => todo!(),
-                Type::Function(_function_type) => todo!(),
-                Type::QualifiedName(_qualified_name) => todo!(),
-                Type::Qualified(_type_qualified_name) => todo!(),
-                Type::Custom(_identifier) => todo!(),
-            },
-            None => self.context.void_type().fn_type(&[], false),
-        };
+        if let Some(function) = self.module.get_function(fn_name.as_str()) {
+            return Ok(function);
+        }
+        self.builder.clear_insertion_position();
+        self.enter_function(
+            &fn_name,
+            function_definition.id,
+            function_definition.location,
+        );
+        let fn_type = self.function_type(
+            function_definition.arguments.as_deref(),
+            function_definition.returns.as_ref(),
+            ctx,
+        )?;
         let function = self.module.add_function(fn_name.as_str(), fn_type, None);
 
-        // Only export public functions. Skip "main" - LLD handles its export specially
+        // A "main" exported as "main" is left to LLD, which handles its export specially
         // to avoid duplicate export errors from the entry point wrapper.
-        let is_main = fn_name == "main";
-        let should_export = function_definition.visibility == Visibility::Public && !is_main;
-        if should_export {
-            let export_name_attr = self
-                .context
-                .create_string_attribute("wasm-export-name", fn_name.as_str());
-            function.add_attribute(AttributeLoc::Function, export_name_attr);
-        }
-        if is_main && function_definition.visibility == Visibility::Public {
-            *self.has_main.borrow_mut() = true;
+        match export_name {
+            Some("main") if fn_name == "main" => *self.has_main.borrow_mut() = true,
+            Some(export_name) => {
+                let export_name_attr = self
+                    .context
+                    .create_string_attribute("wasm-export-name", export_name);
+                function.add_attribute(AttributeLoc::Function, export_name_attr);
+            }
+            None => {}
         }
         if function_definition.is_non_det() {
             self.add_optimization_barriers(function);
         }
+        Ok(function)
+    }
+
+    /// Declares the host function imported by an `external fn` definition.
+    ///
+    /// The function has no body. Its `wasm-import-module` and `wasm-import-name` attributes
+    /// make the WebAssembly backend emit it as an import of `import_name`, and linkers leave
+    /// such explicitly named imports undefined instead of reporting a missing symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same unsupported parameter and return types as
+    /// [`Compiler::declare_function`].
+    pub(crate) fn declare_external_function(
+        &self,
+        external_function: &Rc<ExternalFunctionDefinition>,
+        import_name: &ImportName,
+        ctx: &TypedContext,
+    ) -> Result<FunctionValue<'ctx>, CodegenError> {
+        let fn_name = external_function.name();
+        if let Some(function) = self.module.get_function(fn_name.as_str()) {
+            return Ok(function);
+        }
+        self.builder.clear_insertion_position();
+        self.enter_function(&fn_name, external_function.id, external_function.location);
+        let fn_type = self.function_type(
+            external_function.arguments.as_deref(),
+            external_function.returns.as_ref(),
+            ctx,
+        )?;
+        let function = self.module.add_function(fn_name.as_str(), fn_type, None);
+        for (key, value) in [
+            ("wasm-import-module", import_name.module.as_str()),
+            ("wasm-import-name", import_name.name.as_str()),
+        ] {
+            function.add_attribute(
+                AttributeLoc::Function,
+                self.context.create_string_attribute(key, value),
+            );
+        }
+        Ok(function)
+    }
+
+    /// Returns the LLVM type of a function with the given parameters and return type.
+    ///
+    /// Struct, array and string parameters are passed by pointer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unsupported parameter types, `self` parameters and struct,
+    /// array or string return types.
+    fn function_type(
+        &self,
+        arguments: Option<&[ArgumentType]>,
+        returns: Option<&Type>,
+        ctx: &TypedContext,
+    ) -> Result<FunctionType<'ctx>, CodegenError> {
+        let param_types = arguments
+            .unwrap_or_default()
+            .iter()
+            .map(|argument| {
+                let ty = match argument {
+                    ArgumentType::Argument(argument) => &argument.ty,
+                    ArgumentType::IgnoreArgument(ignored) => &ignored.ty,
+                    ArgumentType::Type(ty) => ty,
+                    ArgumentType::SelfReference(_) => {
+                        return Err(self.error_at(
+                            CodegenErrorKind::Unsupported("methods".to_string()),
+                            argument.id(),
+                            argument.location(),
+                        ));
+                    }
+                };
+                Ok(self.value_type(&TypeInfo::new(ty), ctx)?.into())
+            })
+            .collect::<Result<Vec<BasicMetadataTypeEnum>, _>>()?;
+        match returns {
+            None | Some(Type::Simple(SimpleTypeKind::Unit)) => {
+                Ok(self.context.void_type().fn_type(&param_types, false))
+            }
+            Some(ret_type) => {
+                let ret_type_info = TypeInfo::new(ret_type);
+                if Self::is_aggregate(&ret_type_info, ctx) {
+                    return Err(self.error_at(
+                        CodegenErrorKind::Unsupported(
+                            "returning structs, arrays and strings by value".to_string(),
+                        ),
+                        ret_type.id(),
+                        ret_type.location(),
+                    ));
+                }
+                Ok(self
+                    .value_type(&ret_type_info, ctx)?
+                    .fn_type(&param_types, false))
+            }
+        }
+    }
+
+    /// Translates an AST function definition to LLVM IR.
+    ///
+    /// This is the main entry point for function compilation. The function must have been
+    /// declared with [`Compiler::declare_function`] beforehand. Lowering then performs:
+    ///
+    /// 1. **Parameter binding** - Scalar parameters are spilled to stack slots so they can
+    ///    be assigned; struct and array parameters already point to a copy owned by the callee
+    /// 2. **Body lowering** - Recursively lowers the function body statements to LLVM IR
+    /// 3. **Return handling** - Inserts implicit void return for functions without explicit
+    ///    return statements
+    ///
+    /// # Parameters
+    ///
+    /// - `function_definition` - AST node representing the function to compile
+    /// - `ctx` - Typed context containing type information for all AST nodes
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error raised by a statement or expression of the body, such as a
+    /// construct without code generation support. The function body is left incomplete.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the function was not declared.
+    pub(crate) fn visit_function_definition(
+        &self,
+        function_definition: &Rc<FunctionDefinition>,
+        ctx: &TypedContext,
+    ) -> Result<(), CodegenError> {
+        let fn_name = function_definition.name();
+        let function = self
+            .module
+            .get_function(fn_name.as_str())
+            .unwrap_or_else(|| panic!("Function `{fn_name}` must be declared before lowering"));
+        self.enter_function(
+            &fn_name,
+            function_definition.id,
+            function_definition.location,
+        );
+        if let Some(debug_info) = &self.debug_info {
+            let scope = debug_info.add_function(
+                function,
+                fn_name.as_str(),
+                &function_definition.location,
+                function_definition.visibility != Visibility::Public,
+            );
+            *self.debug_scope.borrow_mut() = Some(scope);
+        }
         let entry = self.context.append_basic_block(function, "entry");
         self.builder.position_at_end(entry);
+        self.variables.borrow_mut().clear();
+        self.reset_constants();
+        self.bind_parameters(function_definition, function, ctx)?;
         self.lower_statement(
             std::iter::once(Statement::Block(function_definition.body.clone())).peekable(),
             &mut vec![function_definition.body.clone()],
             ctx,
-        );
-        if function_definition.is_void() {
-            self.builder.build_return(None).unwrap();
+        )?;
+        if !self.is_terminated() {
+            if function_definition.is_void() {
+                self.builder.build_return(None).unwrap();
+            } else {
+                // Every path returns, e.g. through the branches of a constant `if`.
+                self.builder.build_unreachable().unwrap();
+            }
         }
+        Ok(())
+    }
+
+    /// Registers the parameters of `function` as local variables.
+    ///
+    /// Scalar parameters are stored to a stack slot like any other local. A struct or array
+    /// parameter is a pointer to a copy made by the caller, so the pointer itself is the
+    /// variable's storage.
+    fn bind_parameters(
+        &self,
+        function_definition: &FunctionDefinition,
+        function: FunctionValue<'ctx>,
+        ctx: &TypedContext,
+    ) -> Result<(), CodegenError> {
+        for (argument, param) in function_definition
+            .arguments
+            .iter()
+            .flatten()
+            .zip(function.get_param_iter())
+        {
+            let ArgumentType::Argument(argument) = argument else {
+                continue;
+            };
+            let name = argument.name.name.clone();
+            param.set_name(&name);
+            self.shadow_constant(&name);
+            let type_info = TypeInfo::new(&argument.ty);
+            if Self::is_aggregate(&type_info, ctx) {
+                let ty = self.storage_type(&type_info, ctx)?;
+                self.variables
+                    .borrow_mut()
+                    .insert(name, (param.into_pointer_value(), ty));
+            } else {
+                let ty = self.value_type(&type_info, ctx)?;
+                let local = self.build_entry_alloca(ty, &name);
+                self.builder.build_store(local, param).unwrap();
+                self.variables.borrow_mut().insert(name, (local, ty));
+            }
+        }
+        Ok(())
     }
 
     /// Recursively lowers AST statements to LLVM IR instructions.
@@ -383,7 +721,9 @@ impl<'ctx> Compiler<'ctx> {
     ///   nested statements with appropriate intrinsic calls
     /// - **Expression statements** - Evaluate expressions and handle side effects
     /// - **Return statements** - Generate LLVM return instructions
-    /// - **Constant definitions** - Allocate stack storage and initialize values
+    /// - **Assert statements** - Branch to a failure block lowered per [`AssertStrategy`]
+    /// - **Constant definitions** - Record the value for folding and store it to a stack slot
+    /// - **If statements** - Lower the taken branch when the condition is constant
     ///
     /// # Non-Deterministic Blocks
     ///
@@ -415,14 +755,21 @@ impl<'ctx> Compiler<'ctx> {
     /// - `statements_iterator` - Iterator over statements to lower
     /// - `parent_blocks_stack` - Stack tracking enclosing block contexts
     /// - `ctx` - Typed context for type information lookup
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for statements and expressions without code generation support.
     #[allow(clippy::too_many_lines)]
     fn lower_statement<I: Iterator<Item = Statement>>(
         &self,
         mut statements_iterator: Peekable<I>,
         parent_blocks_stack: &mut Vec<BlockType>,
         ctx: &TypedContext,
-    ) {
+    ) -> Result<(), CodegenError> {
         let statement = statements_iterator.next().unwrap();
+        self.set_debug_location(&statement.location());
+        self.current_node
+            .set((statement.id(), statement.location()));
         match statement {
             Statement::Block(block_type) => match block_type {
                 BlockType::Block(block) => {
@@ -432,7 +779,7 @@ impl<'ctx> Compiler<'ctx> {
                             std::iter::once(stmt).peekable(),
                             parent_blocks_stack,
                             ctx,
-                        );
+                        )?;
                     }
                     parent_blocks_stack.pop();
                 }
@@ -447,7 +794,7 @@ impl<'ctx> Compiler<'ctx> {
                             std::iter::once(stmt).peekable(),
                             parent_blocks_stack,
                             ctx,
-                        );
+                        )?;
                     }
                     let forall_end = self.forall_end_intrinsic();
                     self.builder
@@ -466,7 +813,7 @@ impl<'ctx> Compiler<'ctx> {
                             std::iter::once(stmt).peekable(),
                             parent_blocks_stack,
                             ctx,
-                        );
+                        )?;
                     }
                     let assume_end = self.assume_end_intrinsic();
                     self.builder
@@ -485,7 +832,7 @@ impl<'ctx> Compiler<'ctx> {
                             std::iter::once(stmt).peekable(),
                             parent_blocks_stack,
                             ctx,
-                        );
+                        )?;
                     }
                     let exists_end = self.exists_end_intrinsic();
                     self.builder
@@ -504,7 +851,7 @@ impl<'ctx> Compiler<'ctx> {
                             std::iter::once(stmt).peekable(),
                             parent_blocks_stack,
                             ctx,
-                        );
+                        )?;
                     }
                     let unique_end = self.unique_end_intrinsic();
                     self.builder
@@ -513,14 +860,18 @@ impl<'ctx> Compiler<'ctx> {
                     parent_blocks_stack.pop();
                 }
             },
+            Statement::Expression(Expression::FunctionCall(call)) => {
+                self.lower_call(&call, ctx)?;
+            }
             Statement::Expression(expression) => {
-                let expr = self.lower_expression(&expression, ctx);
+                let expr = self.lower_expression(&expression, ctx)?;
                 // FIXME: revisit this logic #45
                 //
                 // This handles the case where a non-deterministic void block ends with an
                 // expression statement. To prevent LLVM from optimizing away the expression
                 // (which might contain important intrinsic calls), we store it in a temporary
-                // stack variable.
+                // stack slot in the entry block, like every other slot, so the shadow stack
+                // does not grow each time the block runs.
                 //
                 // This is a workaround that ensures side effects are preserved. A better
                 // approach would be to explicitly model void expressions or use LLVM's
@@ -529,116 +880,146 @@ impl<'ctx> Compiler<'ctx> {
                     && parent_blocks_stack.first().unwrap().is_non_det()
                     && parent_blocks_stack.first().unwrap().is_void()
                 {
-                    let local = self.builder.build_alloca(expr.get_type(), "temp").unwrap();
+                    let local = self.build_entry_alloca(expr.get_type(), "temp");
                     self.builder.build_store(local, expr).unwrap();
                 }
             }
-            Statement::Assign(_assign_statement) => todo!(),
+            Statement::Assign(assign_statement) => {
+                let value = self.lower_expression(&assign_statement.right.borrow(), ctx)?;
+                let (ptr, ty) = self.lower_place(&assign_statement.left.borrow(), ctx)?;
+                self.store_value(ptr, ty, value);
+            }
             Statement::Return(return_statement) => {
-                let ret = self.lower_expression(&return_statement.expression.borrow(), ctx);
+                let ret = self.lower_expression(&return_statement.expression.borrow(), ctx)?;
+                let return_type = self
+                    .builder
+                    .get_insert_block()
+                    .and_then(BasicBlock::get_parent)
+                    .and_then(|function| function.get_type().get_return_type());
+                let ret = match return_type {
+                    Some(ty) => self.coerce(ret, ty),
+                    None => ret,
+                };
                 self.builder.build_return(Some(&ret)).unwrap();
             }
-            Statement::Loop(_loop_statement) => todo!(),
-            Statement::Break(_break_statement) => todo!(),
-            Statement::If(_if_statement) => todo!(),
-            Statement::VariableDefinition(_variable_definition_statement) => {
-                // Variable definition support is currently disabled pending implementation of:
-                // 1. Type resolution for non-i32 types
-                // 2. Complex expression evaluation (beyond uzumaki and literals)
-                // 3. Proper variable scoping (currently uses flat namespace)
-                // 4. Mutable vs immutable variable semantics
-                //
-                // When re-enabled, this will follow the same pattern as constant definitions:
-                // - Allocate stack storage (alloca)
-                // - Lower the initialization expression
-                // - Store the value to the allocated pointer
+            Statement::Loop(_) => return Err(self.unsupported("`loop` statements")),
+            Statement::Break(_) => return Err(self.unsupported("`break` statements")),
+            Statement::If(if_statement) => {
+                // Only `if` statements with a constant condition are supported yet. The
+                // branch that is not taken is not lowered at all.
+                let Some(folding::Constant::Bool(condition)) =
+                    self.fold(&if_statement.condition.borrow(), ctx)
+                else {
+                    return Err(self.unsupported("`if` statements"));
+                };
+                let branch = if condition {
+                    Some(if_statement.if_arm.clone())
+                } else {
+                    if_statement.else_arm.clone()
+                };
+                if let Some(branch) = branch {
+                    self.lower_statement(
+                        std::iter::once(Statement::Block(branch)).peekable(),
+                        parent_blocks_stack,
+                        ctx,
+                    )?;
+                    self.continue_after_terminator();
+                }
+            }
+            Statement::VariableDefinition(variable_definition) => {
+                // Variable definitions follow the same pattern as constant definitions:
+                // - Allocate stack storage (alloca) in the entry block
+                // - Lower the initialization expression, if any; otherwise zero the slot
+                // - Store the value to the allocated pointer (structs and arrays are copied)
                 // - Register in the variables HashMap for later loads
+                //
+                // Variables still share a flat namespace per function; shadowing and block
+                // scoping are future work.
+                let name = variable_definition.name();
+                self.shadow_constant(&name);
+                let ty = self.storage_type(&TypeInfo::new(&variable_definition.ty), ctx)?;
+                let local = self.build_entry_alloca(ty, &name);
+                match &variable_definition.value {
+                    Some(value) => {
+                        let value = self.lower_expression(&value.borrow(), ctx)?;
+                        self.store_value(local, ty, value);
+                    }
+                    None => {
+                        self.builder.build_store(local, ty.const_zero()).unwrap();
+                    }
+                }
+                self.variables.borrow_mut().insert(name, (local, ty));
             }
-            Statement::TypeDefinition(_type_definition_statement) => todo!(),
-            Statement::Assert(_assert_statement) => todo!(),
+            Statement::TypeDefinition(_) => {
+                return Err(self.unsupported("type definitions inside functions"));
+            }
+            Statement::Assert(assert_statement) => self.lower_assert(&assert_statement, ctx)?,
             Statement::ConstantDefinition(constant_definition) => {
                 // Constant definitions are lowered by:
-                // 1. Looking up the type from TypedContext
-                // 2. Creating a stack allocation (alloca) for the constant
-                // 3. Lowering the literal value to an LLVM constant
-                // 4. Storing the constant to the allocated pointer
-                // 5. Registering in the variables HashMap for identifier resolution
+                // 1. Recording the value for constant folding, so that every use of the
+                //    name lowers to an LLVM constant instead of a load
+                // 2. Storing the value to a stack slot, which keeps the definition in the
+                //    body of non-deterministic blocks
                 //
-                // Currently only i32 number literals are fully implemented. Other types
-                // will follow the same pattern once expression lowering is expanded.
-                match ctx
-                    .get_node_typeinfo(constant_definition.id)
-                    .expect("Constant definition must have a type info")
-                    .kind
+                // Integer and boolean constants are supported. String constants are
+                // stored like a variable initialized by their literal.
+                let type_info = TypeInfo::new(&constant_definition.ty);
+                if let (true, Literal::String(string_literal)) =
+                    (type_info.is_string(), &constant_definition.value)
                 {
-                    TypeInfoKind::Unit => todo!(),
-                    TypeInfoKind::Bool => todo!(),
-                    TypeInfoKind::String => todo!(),
-                    TypeInfoKind::Number(number_type_kind_number_type) => {
-                        match number_type_kind_number_type {
-                            NumberType::I8 => todo!(),
-                            NumberType::I16 => todo!(),
-                            NumberType::I32 => {
-                                let ctx_type = self.context.i32_type();
-                                match &constant_definition.value {
-                                    Literal::Number(number_literal) => {
-                                        let val = ctx_type.const_int(
-                                            number_literal.value.parse::<u64>().unwrap_or(0),
-                                            false,
-                                        );
-                                        let local = self
-                                            .builder
-                                            .build_alloca(ctx_type, &constant_definition.name())
-                                            .unwrap();
-                                        self.builder.build_store(local, val).unwrap();
-                                        self.variables.borrow_mut().insert(
-                                            constant_definition.name(),
-                                            (local, ctx_type.into()),
-                                        );
-                                    }
-                                    _ => panic!(
-                                        "Constant value for i32 should be a number literal. Found: {:?}",
-                                        constant_definition.value
-                                    ),
-                                }
-                            }
-                            NumberType::I64 => todo!(),
-                            NumberType::U8 => todo!(),
-                            NumberType::U16 => todo!(),
-                            NumberType::U32 => todo!(),
-                            NumberType::U64 => todo!(),
-                        }
-                    }
-                    TypeInfoKind::Custom(_) => todo!(),
-                    TypeInfoKind::Array(_type_info, _) => todo!(),
-                    TypeInfoKind::Generic(_) => todo!(),
-                    TypeInfoKind::QualifiedName(_) => todo!(),
-                    TypeInfoKind::Qualified(_) => todo!(),
-                    TypeInfoKind::Function(_) => todo!(),
-                    TypeInfoKind::Struct(_) => todo!(),
-                    TypeInfoKind::Enum(_) => todo!(),
-                    TypeInfoKind::Spec(_) => todo!(),
+                    let name = constant_definition.name();
+                    self.shadow_constant(&name);
+                    let local = self.lower_string_literal(string_literal);
+                    self.variables
+                        .borrow_mut()
+                        .insert(name, (local, self.string_type().into()));
+                    return Ok(());
                 }
+                let Some(constant) = self.define_local_constant(&constant_definition) else {
+                    return Err(self.unsupported(format!("constants of type `{type_info}`")));
+                };
+                let value = self.lower_constant(constant, Some(&type_info), ctx)?;
+                let local =
+                    self.build_entry_alloca(value.get_type().into(), &constant_definition.name());
+                self.builder.build_store(local, value).unwrap();
             }
         }
+        Ok(())
     }
 
-    /// Lowers an AST expression to an LLVM integer value.
+    /// Creates an error for a construct without code generation support at the statement
+    /// currently being lowered.
+    fn unsupported(&self, construct: impl Into<String>) -> CodegenError {
+        self.error(CodegenErrorKind::Unsupported(construct.into()))
+    }
+
+    /// Lowers an AST expression to an LLVM value.
     ///
     /// This method recursively evaluates expressions and produces LLVM IR that computes
-    /// the expression's value at runtime. Currently, the compiler only supports integer
-    /// expressions, hence the return type is `IntValue`.
+    /// the expression's value at runtime. Scalars lower to integer values; struct-typed
+    /// expressions lower to a pointer to the struct's storage in linear memory.
     ///
     /// # Supported Expressions
     ///
-    /// - **Literals** - Compile-time constants (numbers, booleans)
+    /// - **Constant expressions** - Literals, `const` names and operators applied to them
+    ///   are folded to a single constant, see [`folding`]
     /// - **Identifiers** - Load values from local variables
+    /// - **Operators** - Integer arithmetic, bitwise and shift operators, see [`arithmetic`],
+    ///   comparisons, and the prefix operators `-`, `~` and `!`
+    /// - **Member access** - Load struct fields, or address nested structs
+    /// - **Struct literals** - Initialize a new struct in a stack slot
+    /// - **Array literals and indexing** - Initialize arrays in a stack slot and access
+    ///   elements, with bounds checks unless disabled
+    /// - **Strings** - Literals refer to bytes in a data segment; `len`, `slice` and
+    ///   indexing read them, see [`strings`]
+    /// - **Function calls** - Calls to functions of the same module
     /// - **Uzumaki** - Non-deterministic value generation via intrinsics
     ///
     /// # Type Context
     ///
     /// The `TypedContext` is used to query type information for expressions, particularly
-    /// for uzumaki expressions which can produce different integer types (i32, i64).
+    /// for literals and uzumaki expressions which can produce different integer types, and
+    /// for the struct layouts behind member accesses.
     ///
     /// # Parameters
     ///
@@ -647,47 +1028,180 @@ impl<'ctx> Compiler<'ctx> {
     ///
     /// # Returns
     ///
-    /// LLVM integer value representing the expression result
+    /// LLVM value representing the expression result
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for expressions without code generation support, and for
+    /// identifiers and calls that do not resolve to a variable or a value.
     fn lower_expression(
         &self,
         expression: &Expression,
         ctx: &TypedContext,
-    ) -> inkwell::values::IntValue<'ctx> {
+    ) -> Result<BasicValueEnum<'ctx>, CodegenError> {
+        if let Some(constant) = self.fold(expression, ctx) {
+            let type_info = ctx.get_node_typeinfo(expression.id());
+            return Ok(self
+                .lower_constant(constant, type_info.as_ref(), ctx)?
+                .into());
+        }
+        let unsupported = |construct: &str| {
+            Err(self.error_at(
+                CodegenErrorKind::Unsupported(construct.to_string()),
+                expression.id(),
+                expression.location(),
+            ))
+        };
         match expression {
-            Expression::ArrayIndexAccess(_array_index_access_expression) => todo!(),
-            Expression::Binary(_binary_expression) => todo!(),
-            Expression::MemberAccess(_member_access_expression) => todo!(),
-            Expression::TypeMemberAccess(_type_member_access_expression) => todo!(),
-            Expression::FunctionCall(_function_call_expression) => todo!(),
-            Expression::Struct(_struct_expression) => todo!(),
-            Expression::PrefixUnary(_prefix_unary_expression) => todo!(),
-            Expression::Parenthesized(_parenthesized_expression) => todo!(),
-            Expression::Literal(literal) => self.lower_literal(literal),
+            Expression::ArrayIndexAccess(array_index_access_expression) => {
+                let (ptr, ty) = self.element_pointer(array_index_access_expression, ctx)?;
+                Ok(self.load_value(ptr, ty, "element"))
+            }
+            Expression::Binary(binary_expression) => {
+                Ok(self.lower_binary(binary_expression, ctx)?.into())
+            }
+            Expression::MemberAccess(member_access_expression) => {
+                let (ptr, ty) = self.member_pointer(member_access_expression, ctx)?;
+                Ok(self.load_value(ptr, ty, &member_access_expression.name.name))
+            }
+            Expression::TypeMemberAccess(type_member_access_expression) => Ok(self
+                .lower_enum_variant(type_member_access_expression, ctx)?
+                .into()),
+            Expression::FunctionCall(function_call_expression) => self
+                .lower_call(function_call_expression, ctx)?
+                .ok_or_else(|| {
+                    self.error_at(
+                        CodegenErrorKind::MissingInformation(
+                            "function used as a value does not return a value".to_string(),
+                        ),
+                        function_call_expression.id,
+                        function_call_expression.location,
+                    )
+                }),
+            Expression::Struct(struct_expression) => Ok(self
+                .lower_struct_literal(
+                    &struct_expression.name.name,
+                    struct_expression.fields.as_deref().unwrap_or_default(),
+                    ctx,
+                )?
+                .into()),
+            Expression::PrefixUnary(prefix_unary_expression) => {
+                let operand = self
+                    .lower_expression(&prefix_unary_expression.expression.borrow(), ctx)?
+                    .into_int_value();
+                Ok(self
+                    .lower_prefix_unary(&prefix_unary_expression.operator, operand)
+                    .into())
+            }
+            Expression::Parenthesized(parenthesized_expression) => {
+                self.lower_expression(&parenthesized_expression.expression.borrow(), ctx)
+            }
+            Expression::Literal(Literal::Array(array_literal)) => {
+                Ok(self.lower_array_literal(array_literal, ctx)?.into())
+            }
+            Expression::Literal(Literal::String(string_literal)) => {
+                Ok(self.lower_string_literal(string_literal).into())
+            }
+            Expression::Literal(literal) => Ok(self.lower_literal(literal, ctx)?.into()),
             Expression::Identifier(identifier) => {
-                let (ptr, ty) = self
-                    .variables
-                    .borrow()
-                    .get(&identifier.name)
-                    .copied()
-                    .expect("Variable not found");
-                self.builder
-                    .build_load(ty, ptr, &identifier.name)
-                    .unwrap()
-                    .into_int_value()
+                if !self.variables.borrow().contains_key(&identifier.name)
+                    && let Some(string) = self.lower_module_string(&identifier.name)
+                {
+                    return Ok(string.into());
+                }
+                let (ptr, ty) = self.variable(identifier)?;
+                Ok(self.load_value(ptr, ty, &identifier.name))
             }
-            Expression::Type(_) => todo!(),
+            Expression::Type(_) => unsupported("types used as values"),
             Expression::Uzumaki(uzumaki_expression) => {
                 if ctx.is_node_i32(uzumaki_expression.id) {
-                    return self.lower_uzumaki_i32_expression();
+                    return Ok(self.lower_uzumaki_i32_expression().into());
                 }
                 if ctx.is_node_i64(uzumaki_expression.id) {
-                    return self.lower_uzumaki_i64_expression();
+                    return Ok(self.lower_uzumaki_i64_expression().into());
                 }
-                panic!("Unsupported Uzumaki expression type: {uzumaki_expression:?}");
+                unsupported("`@` values other than `i32` and `i64`")
             }
         }
     }
 
+    /// Lowers a binary expression.
+    ///
+    /// Arithmetic, bitwise and shift operators on integers lower to an integer of the
+    /// operand type, see [`arithmetic`]. Comparisons (`==`, `!=`, `<`, `<=`, `>`, `>=`) are
+    /// supported on integers, booleans and enum tags and produce an `i1`. Both operands are
+    /// brought to the type of the typed operand, since untyped literals lower to `i32`.
+    /// Ordering, division and `>>` use the signed operations for signed integer operands
+    /// and the unsigned ones otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for operators without code generation support yet, which includes
+    /// every operator on structs, arrays and strings.
+    fn lower_binary(
+        &self,
+        binary_expression: &BinaryExpression,
+        ctx: &TypedContext,
+    ) -> Result<inkwell::values::IntValue<'ctx>, CodegenError> {
+        let left_expression = binary_expression.left.borrow();
+        let right_expression = binary_expression.right.borrow();
+        let operand_type = ctx
+            .get_node_typeinfo(left_expression.id())
+            .or_else(|| ctx.get_node_typeinfo(right_expression.id()));
+        if let Some(type_info) = operand_type
+            .as_ref()
+            .filter(|type_info| Self::is_aggregate(type_info, ctx))
+        {
+            return Err(self.error_at(
+                CodegenErrorKind::Unsupported(format!(
+                    "operator `{:?}` on `{type_info}` values",
+                    binary_expression.operator
+                )),
+                binary_expression.id,
+                binary_expression.location,
+            ));
+        }
+        let left = self.lower_expression(&left_expression, ctx)?;
+        let right = self.lower_expression(&right_expression, ctx)?;
+        let (int_type, is_signed) = match &operand_type {
+            Some(type_info) => (
+                self.value_type(type_info, ctx)?,
+                type_info.is_signed_integer(),
+            ),
+            None => (left.get_type(), true),
+        };
+        let left = self.coerce(left, int_type).into_int_value();
+        let right = self.coerce(right, int_type).into_int_value();
+        if let Some(value) =
+            self.lower_arithmetic(&binary_expression.operator, left, right, is_signed)
+        {
+            return Ok(value);
+        }
+        let predicate = match (&binary_expression.operator, is_signed) {
+            (OperatorKind::Eq, _) => IntPredicate::EQ,
+            (OperatorKind::Ne, _) => IntPredicate::NE,
+            (OperatorKind::Lt, true) => IntPredicate::SLT,
+            (OperatorKind::Lt, false) => IntPredicate::ULT,
+            (OperatorKind::Le, true) => IntPredicate::SLE,
+            (OperatorKind::Le, false) => IntPredicate::ULE,
+            (OperatorKind::Gt, true) => IntPredicate::SGT,
+            (OperatorKind::Gt, false) => IntPredicate::UGT,
+            (OperatorKind::Ge, true) => IntPredicate::SGE,
+            (OperatorKind::Ge, false) => IntPredicate::UGE,
+            (operator, _) => {
+                return Err(self.error_at(
+                    CodegenErrorKind::Unsupported(format!("operator `{operator:?}`")),
+                    binary_expression.id,
+                    binary_expression.location,
+                ));
+            }
+        };
+        Ok(self
+            .builder
+            .build_int_compare(predicate, left, right, "cmp")
+            .unwrap())
+    }
+
     /// Converts an AST literal to an LLVM constant integer value.
     ///
     /// Literals are compile-time constants that get embedded directly into the LLVM IR
@@ -696,29 +1210,54 @@ impl<'ctx> Compiler<'ctx> {
     ///
     /// # Literal Types
     ///
-    /// - **Bool** - Converted to i32 (0 for false, 1 for true) per WASM convention
-    /// - **Number** - Parsed from string and converted to i32 constant
+    /// - **Bool** - Converted to i1 (0 for false, 1 for true)
+    /// - **Number** - Parsed from string and converted to a constant of the literal's
+    ///   inferred integer type, or i32 when the type checker did not record one
     ///
     /// # Parameters
     ///
     /// - `literal` - AST literal node to convert
+    /// - `ctx` - Typed context for the literal's inferred type
     ///
     /// # Returns
     ///
     /// LLVM constant integer value
-    fn lower_literal(&self, literal: &Literal) -> inkwell::values::IntValue<'ctx> {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unit literals, which have no code generation support yet.
+    fn lower_literal(
+        &self,
+        literal: &Literal,
+        ctx: &TypedContext,
+    ) -> Result<inkwell::values::IntValue<'ctx>, CodegenError> {
         match literal {
-            Literal::Array(_array_literal) => todo!(),
-            Literal::Bool(bool_literal) => self
+            Literal::Array(_) => unreachable!("array literals are lowered to memory"),
+            Literal::Bool(bool_literal) => Ok(self
                 .context
-                .i32_type()
-                .const_int(u64::from(bool_literal.value), false),
-            Literal::String(_string_literal) => todo!(),
-            Literal::Number(number_literal) => self
-                .context
-                .i32_type()
-                .const_int(number_literal.value.parse::<u64>().unwrap_or(0), false),
-            Literal::Unit(_unit_literal) => todo!(),
+                .bool_type()
+                .const_int(u64::from(bool_literal.value), false)),
+            Literal::String(_) => unreachable!("string literals are lowered to memory"),
+            Literal::Number(number_literal) => {
+                let int_type = match ctx.get_node_typeinfo(number_literal.id) {
+                    Some(type_info) if matches!(type_info.kind, TypeInfoKind::Number(_)) => {
+                        self.value_type(&type_info, ctx)?.into_int_type()
+                    }
+                    _ => self.context.i32_type(),
+                };
+                Ok(match number_literal.value.parse::<i64>() {
+                    #[allow(clippy::cast_sign_loss)]
+                    Ok(value) => int_type.const_int(value as u64, true),
+                    Err(_) => {
+                        int_type.const_int(number_literal.value.parse::<u64>().unwrap_or(0), false)
+                    }
+                })
+            }
+            Literal::Unit(unit_literal) => Err(self.error_at(
+                CodegenErrorKind::Unsupported("unit literals".to_string()),
+                unit_literal.id,
+                unit_literal.location,
+            )),
         }
     }
 
@@ -782,6 +1321,14 @@ impl<'ctx> Compiler<'ctx> {
             })
     }
 
+    /// Retrieves or declares the `llvm.trap` intrinsic with the signature `() -> void`.
+    fn trap_intrinsic(&self) -> FunctionValue<'ctx> {
+        let fn_type = self.context.void_type().fn_type(&[], false);
+        self.module
+            .get_function(TRAP_INTRINSIC)
+            .unwrap_or_else(|| self.module.add_function(TRAP_INTRINSIC, fn_type, None))
+    }
+
     /// Retrieves or declares the i64 uzumaki intrinsic function.
     ///
     /// This method ensures the intrinsic function is declared in the LLVM module.
@@ -948,19 +1495,19 @@ impl<'ctx> Compiler<'ctx> {
 
     /// Compiles the LLVM module to WebAssembly bytecode.
     ///
-    /// This method orchestrates the final compilation stages:
-    /// 1. Emit LLVM IR to a temporary file
-    /// 2. Invoke inf-llc to compile IR to WASM object file
-    /// 3. Invoke rust-lld to link object file into final WASM module
-    /// 4. Read the resulting WASM bytes
+    /// Pending debug metadata is finalized first when debug info is enabled. This method
+    /// then orchestrates the final compilation stages:
+    /// 1. Emit a WASM object file through an in-process LLVM target machine
+    /// 2. Invoke rust-lld (or the builtin linker) to link object file into final WASM module
+    /// 3. Read the resulting WASM bytes
     ///
     /// The actual compilation work is delegated to the `utils::compile_to_wasm` function,
-    /// which handles toolchain invocation and temporary file management.
+    /// which handles object emission, linker invocation and temporary file management.
     ///
     /// # Parameters
     ///
     /// - `output_fname` - Base filename for intermediate files (extension will be added)
-    /// - `optimization_level` - LLVM optimization level (0-3, higher is more optimized)
+    /// - `options` - Optimization level, debug info and target features for the toolchain
     ///
     /// # Returns
     ///
@@ -969,15 +1516,46 @@ impl<'ctx> Compiler<'ctx> {
     /// # Errors
     ///
     /// Returns an error if:
-    /// - inf-llc or rust-lld executables are not found
-    /// - Compilation or linking fails
+    /// - The rust-lld executable is not found
+    /// - Object emission or linking fails
     /// - File I/O operations fail
     pub(crate) fn compile_to_wasm(
         &self,
         output_fname: &str,
-        optimization_level: u32,
+        options: &CodegenOptions,
     ) -> anyhow::Result<Vec<u8>> {
         let has_main = *self.has_main.borrow();
-        utils::compile_to_wasm(&self.module, output_fname, optimization_level, has_main)
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.finalize();
+        }
+        utils::compile_to_wasm(
+            &self.module,
+            &self.target_machine,
+            output_fname,
+            options,
+            has_main,
+        )
+    }
+
+    /// Returns true if a `main` function exported as `main` was declared in this module.
+    pub(crate) fn has_main(&self) -> bool {
+        *self.has_main.borrow()
+    }
+
+    /// Finalizes the module and returns its LLVM IR as text.
+    pub(crate) fn print_ir(&self) -> String {
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.finalize();
+        }
+        self.module.print_to_string().to_string()
+    }
+
+    /// Finalizes the module and returns it as LLVM bitcode, which can be loaded into an
+    /// LLVM context on another thread.
+    pub(crate) fn write_bitcode(&self) -> Vec<u8> {
+        if let Some(debug_info) = &self.debug_info {
+            debug_info.finalize();
+        }
+        self.module.write_bitcode_to_memory().as_slice().to_vec()
     }
 }
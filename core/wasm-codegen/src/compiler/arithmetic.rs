@@ -0,0 +1,202 @@
+//! Integer arithmetic lowering.
+//!
+//! Arithmetic, bitwise and shift operators and the prefix operators `-`, `~` and `!` lower
+//! to LLVM instructions of the operand type, which the backend selects as `i32` or `i64`
+//! WebAssembly operations. Their semantics match constant folding, see [`super::folding`],
+//! so an expression computes the same value whether or not its operands are constant:
+//!
+//! - `+`, `-`, `*`, `**` and prefix `-` wrap around at the width of the type
+//! - `/` and `%` truncate towards zero for signed types. A division by zero and the signed
+//!   division of the minimum value by `-1` trap; the signed remainder of the minimum value
+//!   by `-1` is `0`
+//! - `<<` and `>>` take the shift amount modulo the width of the type, like the WebAssembly
+//!   shift instructions. `>>` is arithmetic for signed types and logical for unsigned ones
+//! - `**` takes the exponent as an unsigned number of the operand type
+//!
+//! The checks for trapping divisions are explicit, since LLVM treats such divisions as
+//! undefined behavior rather than as traps.
+
+use super::Compiler;
+use inference_ast::nodes::{OperatorKind, UnaryOperatorKind};
+use inkwell::{
+    IntPredicate,
+    basic_block::BasicBlock,
+    values::{FunctionValue, IntValue},
+};
+
+impl<'ctx> Compiler<'ctx> {
+    /// Lowers an arithmetic, bitwise or shift operator applied to two integers of the same
+    /// type, or returns `None` for operators that are not integer arithmetic.
+    pub(super) fn lower_arithmetic(
+        &self,
+        operator: &OperatorKind,
+        left: IntValue<'ctx>,
+        right: IntValue<'ctx>,
+        is_signed: bool,
+    ) -> Option<IntValue<'ctx>> {
+        let builder = &self.builder;
+        Some(match operator {
+            OperatorKind::Add => builder.build_int_add(left, right, "add").unwrap(),
+            OperatorKind::Sub => builder.build_int_sub(left, right, "sub").unwrap(),
+            OperatorKind::Mul => builder.build_int_mul(left, right, "mul").unwrap(),
+            OperatorKind::Div => {
+                self.build_division_check(left, right, is_signed);
+                if is_signed {
+                    builder.build_int_signed_div(left, right, "div").unwrap()
+                } else {
+                    builder.build_int_unsigned_div(left, right, "div").unwrap()
+                }
+            }
+            OperatorKind::Mod if is_signed => {
+                self.build_division_check(left, right, false);
+                // `x % -1` is 0 like `x % 1`, and dividing by 1 cannot overflow.
+                let int_type = right.get_type();
+                let is_minus_one = builder
+                    .build_int_compare(IntPredicate::EQ, right, int_type.const_all_ones(), "")
+                    .unwrap();
+                let divisor = builder
+                    .build_select(is_minus_one, int_type.const_int(1, false), right, "")
+                    .unwrap()
+                    .into_int_value();
+                builder.build_int_signed_rem(left, divisor, "rem").unwrap()
+            }
+            OperatorKind::Mod => {
+                self.build_division_check(left, right, false);
+                builder.build_int_unsigned_rem(left, right, "rem").unwrap()
+            }
+            OperatorKind::Pow => self.build_pow(left, right),
+            OperatorKind::BitAnd => builder.build_and(left, right, "and").unwrap(),
+            OperatorKind::BitOr => builder.build_or(left, right, "or").unwrap(),
+            OperatorKind::BitXor => builder.build_xor(left, right, "xor").unwrap(),
+            OperatorKind::Shl => {
+                let amount = self.shift_amount(right);
+                builder.build_left_shift(left, amount, "shl").unwrap()
+            }
+            OperatorKind::Shr => {
+                let amount = self.shift_amount(right);
+                builder
+                    .build_right_shift(left, amount, is_signed, "shr")
+                    .unwrap()
+            }
+            _ => return None,
+        })
+    }
+
+    /// Lowers a prefix operator applied to `operand`: `-` and `~` on integers, `!` on
+    /// booleans.
+    pub(super) fn lower_prefix_unary(
+        &self,
+        operator: &UnaryOperatorKind,
+        operand: IntValue<'ctx>,
+    ) -> IntValue<'ctx> {
+        match operator {
+            UnaryOperatorKind::Neg => self.builder.build_int_neg(operand, "neg").unwrap(),
+            UnaryOperatorKind::BitNot | UnaryOperatorKind::Not => {
+                self.builder.build_not(operand, "not").unwrap()
+            }
+        }
+    }
+
+    /// Reduces a shift amount modulo the width of its type, which keeps the LLVM shift
+    /// defined for every amount.
+    fn shift_amount(&self, amount: IntValue<'ctx>) -> IntValue<'ctx> {
+        let int_type = amount.get_type();
+        let mask = int_type.const_int(u64::from(int_type.get_bit_width() - 1), false);
+        self.builder.build_and(amount, mask, "amount").unwrap()
+    }
+
+    /// Emits a branch to a trapping block when `right` is zero or, for a signed division,
+    /// when `left` is the minimum value and `right` is `-1`.
+    fn build_division_check(&self, left: IntValue<'ctx>, right: IntValue<'ctx>, is_signed: bool) {
+        let builder = &self.builder;
+        let int_type = right.get_type();
+        let mut traps = builder
+            .build_int_compare(IntPredicate::EQ, right, int_type.const_zero(), "")
+            .unwrap();
+        if is_signed {
+            let min = int_type.const_int(1 << (int_type.get_bit_width() - 1), false);
+            let left_is_min = builder
+                .build_int_compare(IntPredicate::EQ, left, min, "")
+                .unwrap();
+            let right_is_minus_one = builder
+                .build_int_compare(IntPredicate::EQ, right, int_type.const_all_ones(), "")
+                .unwrap();
+            let overflows = builder
+                .build_and(left_is_min, right_is_minus_one, "")
+                .unwrap();
+            traps = builder.build_or(traps, overflows, "").unwrap();
+        }
+        let function = self.current_function();
+        let trap_block = self.context.append_basic_block(function, "division_trap");
+        let continue_block = self.context.append_basic_block(function, "division");
+        builder
+            .build_conditional_branch(traps, trap_block, continue_block)
+            .unwrap();
+        builder.position_at_end(trap_block);
+        self.build_trap();
+        builder.position_at_end(continue_block);
+    }
+
+    /// Emits `base ** exponent` as a square-and-multiply loop over the bits of the
+    /// exponent, which runs at most as many iterations as the type has bits.
+    fn build_pow(&self, base: IntValue<'ctx>, exponent: IntValue<'ctx>) -> IntValue<'ctx> {
+        let builder = &self.builder;
+        let int_type = base.get_type();
+        let function = self.current_function();
+        let entry_block = builder.get_insert_block().unwrap();
+        let loop_block = self.context.append_basic_block(function, "pow_loop");
+        let body_block = self.context.append_basic_block(function, "pow_body");
+        let done_block = self.context.append_basic_block(function, "pow_done");
+        builder.build_unconditional_branch(loop_block).unwrap();
+
+        builder.position_at_end(loop_block);
+        let result = builder.build_phi(int_type, "pow_result").unwrap();
+        let factor = builder.build_phi(int_type, "pow_factor").unwrap();
+        let remaining = builder.build_phi(int_type, "pow_exponent").unwrap();
+        let result_value = result.as_basic_value().into_int_value();
+        let factor_value = factor.as_basic_value().into_int_value();
+        let remaining_value = remaining.as_basic_value().into_int_value();
+        let is_done = builder
+            .build_int_compare(IntPredicate::EQ, remaining_value, int_type.const_zero(), "")
+            .unwrap();
+        builder
+            .build_conditional_branch(is_done, done_block, body_block)
+            .unwrap();
+
+        builder.position_at_end(body_block);
+        let one = int_type.const_int(1, false);
+        let low_bit = builder.build_and(remaining_value, one, "").unwrap();
+        let is_odd = builder
+            .build_int_compare(IntPredicate::NE, low_bit, int_type.const_zero(), "")
+            .unwrap();
+        let product = builder
+            .build_int_mul(result_value, factor_value, "")
+            .unwrap();
+        let next_result = builder
+            .build_select(is_odd, product, result_value, "")
+            .unwrap()
+            .into_int_value();
+        let next_factor = builder
+            .build_int_mul(factor_value, factor_value, "")
+            .unwrap();
+        let next_remaining = builder
+            .build_right_shift(remaining_value, one, false, "")
+            .unwrap();
+        builder.build_unconditional_branch(loop_block).unwrap();
+
+        result.add_incoming(&[(&one, entry_block), (&next_result, body_block)]);
+        factor.add_incoming(&[(&base, entry_block), (&next_factor, body_block)]);
+        remaining.add_incoming(&[(&exponent, entry_block), (&next_remaining, body_block)]);
+
+        builder.position_at_end(done_block);
+        result_value
+    }
+
+    /// Returns the function the builder is positioned in.
+    fn current_function(&self) -> FunctionValue<'ctx> {
+        self.builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_parent)
+            .expect("Builder must be positioned inside a function")
+    }
+}
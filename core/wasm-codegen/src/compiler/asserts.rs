@@ -0,0 +1,165 @@
+//! `assert` lowering.
+//!
+//! An `assert` evaluates its condition and branches to an `assert_failed` block when the
+//! condition is false. What that block does is chosen by [`AssertStrategy`]:
+//!
+//! - [`AssertStrategy::Trap`] executes `llvm.trap`, which lowers to `unreachable`
+//! - [`AssertStrategy::Abort`] calls the imported host function `abort(file, line)` and
+//!   traps if it returns. The file name is a NUL-terminated string constant shared by all
+//!   asserts of the module
+//! - [`AssertStrategy::ResultCode`] returns the configured code from the enclosing
+//!   function, or returns without a value from a function that has none
+//!
+//! Execution continues in an `assert_passed` block when the condition holds. An assert whose
+//! condition folds to `true` emits no code.
+
+use super::{Compiler, folding::Constant};
+use crate::{
+    errors::{CodegenError, CodegenErrorKind},
+    options::{AssertStrategy, ImportName},
+};
+use inference_ast::nodes::AssertStatement;
+use inference_type_checker::typed_context::TypedContext;
+use inkwell::{
+    AddressSpace, IntPredicate,
+    attributes::AttributeLoc,
+    basic_block::BasicBlock,
+    module::Linkage,
+    types::BasicTypeEnum,
+    values::{FunctionValue, PointerValue},
+};
+
+/// Symbol of the host function called by [`AssertStrategy::Abort`]. The import name comes
+/// from the strategy, so the symbol does not clash with an `external fn abort` of the source.
+const ABORT_FUNCTION: &str = "__inference_assert_abort";
+
+/// Symbol of the source file name passed to the abort function.
+const ASSERT_FILE_GLOBAL: &str = "__inference_assert_file";
+
+impl<'ctx> Compiler<'ctx> {
+    /// Lowers an `assert` statement according to the configured [`AssertStrategy`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition cannot be lowered, or if the assert returns a
+    /// result code from a function whose return type is not an integer.
+    pub(super) fn lower_assert(
+        &self,
+        assert_statement: &AssertStatement,
+        ctx: &TypedContext,
+    ) -> Result<(), CodegenError> {
+        let condition_expression = assert_statement.expression.borrow();
+        if self.fold(&condition_expression, ctx) == Some(Constant::Bool(true)) {
+            return Ok(());
+        }
+        let condition = self
+            .lower_expression(&condition_expression, ctx)?
+            .into_int_value();
+        let condition = if condition.get_type().get_bit_width() == 1 {
+            condition
+        } else {
+            self.builder
+                .build_int_compare(
+                    IntPredicate::NE,
+                    condition,
+                    condition.get_type().const_zero(),
+                    "",
+                )
+                .unwrap()
+        };
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_parent)
+            .expect("Builder must be positioned inside a function");
+        let failed_block = self.context.append_basic_block(function, "assert_failed");
+        let passed_block = self.context.append_basic_block(function, "assert_passed");
+        self.builder
+            .build_conditional_branch(condition, passed_block, failed_block)
+            .unwrap();
+        self.builder.position_at_end(failed_block);
+        match &self.assert_strategy {
+            AssertStrategy::Trap => self.build_trap(),
+            AssertStrategy::Abort(import_name) => {
+                let line = self
+                    .context
+                    .i32_type()
+                    .const_int(u64::from(assert_statement.location.start_line), false);
+                self.builder
+                    .build_call(
+                        self.abort_function(import_name),
+                        &[self.assert_file().into(), line.into()],
+                        "",
+                    )
+                    .expect("Failed to build abort call");
+                self.build_trap();
+            }
+            AssertStrategy::ResultCode(code) => match function.get_type().get_return_type() {
+                None => {
+                    self.builder.build_return(None).unwrap();
+                }
+                Some(BasicTypeEnum::IntType(int_type)) => {
+                    #[allow(clippy::cast_sign_loss)]
+                    let code = int_type.const_int(*code as u64, true);
+                    self.builder.build_return(Some(&code)).unwrap();
+                }
+                Some(_) => {
+                    return Err(self.error(CodegenErrorKind::Unsupported(
+                        "`assert` result codes in functions not returning an integer".to_string(),
+                    )));
+                }
+            },
+        }
+        self.builder.position_at_end(passed_block);
+        Ok(())
+    }
+
+    /// Ends the current block with a call to `llvm.trap`.
+    pub(super) fn build_trap(&self) {
+        self.builder
+            .build_call(self.trap_intrinsic(), &[], "")
+            .expect("Failed to build trap intrinsic call");
+        self.builder.build_unreachable().unwrap();
+    }
+
+    /// Retrieves or declares the host function `abort(file, line)` imported as
+    /// `import_name`.
+    fn abort_function(&self, import_name: &ImportName) -> FunctionValue<'ctx> {
+        if let Some(function) = self.module.get_function(ABORT_FUNCTION) {
+            return function;
+        }
+        let fn_type = self.context.void_type().fn_type(
+            &[
+                self.context.ptr_type(AddressSpace::default()).into(),
+                self.context.i32_type().into(),
+            ],
+            false,
+        );
+        let function = self.module.add_function(ABORT_FUNCTION, fn_type, None);
+        for (key, value) in [
+            ("wasm-import-module", import_name.module.as_str()),
+            ("wasm-import-name", import_name.name.as_str()),
+        ] {
+            function.add_attribute(
+                AttributeLoc::Function,
+                self.context.create_string_attribute(key, value),
+            );
+        }
+        function
+    }
+
+    /// Retrieves or defines the NUL-terminated source file name passed to `abort`.
+    fn assert_file(&self) -> PointerValue<'ctx> {
+        if let Some(global) = self.module.get_global(ASSERT_FILE_GLOBAL) {
+            return global.as_pointer_value();
+        }
+        let file = self.context.const_string(self.assert_file.as_bytes(), true);
+        let global = self
+            .module
+            .add_global(file.get_type(), None, ASSERT_FILE_GLOBAL);
+        global.set_initializer(&file);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        global.as_pointer_value()
+    }
+}
@@ -0,0 +1,62 @@
+//! Enum lowering.
+//!
+//! Enum values are integer tags: a value of an enum type is an `i32` holding the index of
+//! its variant in declaration order, as reported by [`TypedContext::enum_variants`]. The
+//! same `i32` is used in registers, parameters, return values and struct fields, which
+//! matches the 4-byte layout the type checker assigns to enums.
+//!
+//! Because tags are plain integers, comparing enum values uses the regular integer
+//! comparisons (tags are compared as unsigned numbers).
+//!
+//! Enums only have unit variants today. Variants carrying data will need a tagged union
+//! (a tag followed by storage for the largest payload), and a `match` construct will lower
+//! to a `switch` on the tag; neither exists in the language yet.
+
+use super::Compiler;
+use crate::errors::{CodegenError, CodegenErrorKind};
+use inference_ast::nodes::TypeMemberAccessExpression;
+use inference_type_checker::{
+    type_info::{TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+use inkwell::values::IntValue;
+
+impl<'ctx> Compiler<'ctx> {
+    /// Returns the enum name if `type_info` refers to a known enum.
+    pub(super) fn enum_name(type_info: &TypeInfo, ctx: &TypedContext) -> Option<String> {
+        match &type_info.kind {
+            TypeInfoKind::Enum(name) => Some(name.clone()),
+            TypeInfoKind::Custom(name) if ctx.enum_variants(name).is_some() => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Lowers `Type::Variant` to the constant tag of the variant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression does not name a variant of a known enum.
+    pub(super) fn lower_enum_variant(
+        &self,
+        access: &TypeMemberAccessExpression,
+        ctx: &TypedContext,
+    ) -> Result<IntValue<'ctx>, CodegenError> {
+        let missing = |message: String| {
+            self.error_at(
+                CodegenErrorKind::MissingInformation(message),
+                access.id,
+                access.location,
+            )
+        };
+        let enum_name = ctx
+            .get_node_typeinfo(access.id)
+            .and_then(|type_info| Self::enum_name(&type_info, ctx))
+            .ok_or_else(|| missing("type member access does not name an enum".to_string()))?;
+        let variant = &access.name.name;
+        let tag = ctx
+            .enum_variants(&enum_name)
+            .and_then(|variants| variants.iter().position(|name| name == variant))
+            .ok_or_else(|| missing(format!("enum `{enum_name}` has no variant `{variant}`")))?;
+        Ok(self.context.i32_type().const_int(tag as u64, false))
+    }
+}
@@ -0,0 +1,130 @@
+//! Constant folding on the typed AST.
+//!
+//! Before an expression is lowered, [`Compiler::fold`] tries to evaluate it at compile
+//! time. Literals, `const` definitions of the module and of the current function, and the
+//! unary and binary operators applied to them fold to a [`Constant`], which is emitted as a
+//! single LLVM constant. The unoptimized path therefore produces no instructions for
+//! constant arithmetic, and constructs without code generation support yet compile as long
+//! as their operands are constant.
+//!
+//! The evaluation itself lives in [`crate::const_eval`], shared with the `wasm-encoder`
+//! backend. It follows the WebAssembly semantics of the operand type, like the runtime
+//! lowering in [`super::arithmetic`].
+//!
+//! Statements use the folded values as well: an `if` with a constant condition only lowers
+//! the taken branch, and an `assert` whose condition is constantly true emits no code.
+
+use super::Compiler;
+use crate::{
+    const_eval::{self, constant_value},
+    errors::CodegenError,
+};
+use inference_ast::nodes::{ConstantDefinition, Expression};
+use inference_type_checker::{
+    type_info::{TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+use inkwell::{basic_block::BasicBlock, values::IntValue};
+
+pub(super) use crate::const_eval::{Constant, Constants};
+
+impl<'ctx> Compiler<'ctx> {
+    /// Records the value of a module-level `const` definition, so that its uses fold in
+    /// every function. String constants do not fold; their text is recorded for
+    /// [`strings`](super::strings) instead.
+    pub(crate) fn define_module_constant(&self, constant_definition: &ConstantDefinition) {
+        match constant_value(constant_definition) {
+            Some(constant) => {
+                self.module_constants
+                    .borrow_mut()
+                    .insert(constant_definition.name(), constant);
+            }
+            None => self.define_module_string(constant_definition),
+        }
+    }
+
+    /// Records the value of a `const` definition of the current function, which is visible
+    /// until the end of the function, and returns it.
+    pub(super) fn define_local_constant(
+        &self,
+        constant_definition: &ConstantDefinition,
+    ) -> Option<Constant> {
+        let constant = constant_value(constant_definition)?;
+        self.constants
+            .borrow_mut()
+            .insert(constant_definition.name(), constant);
+        Some(constant)
+    }
+
+    /// Evaluates `expression` at compile time with the constants in scope, or returns
+    /// `None` if its value is only known at runtime.
+    pub(super) fn fold(&self, expression: &Expression, ctx: &TypedContext) -> Option<Constant> {
+        const_eval::fold(expression, &self.constants.borrow(), ctx)
+    }
+
+    /// Emits `constant` as an LLVM constant of the LLVM type of `type_info`.
+    ///
+    /// Integers without type information are emitted as `i32`, like untyped literals.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `type_info` has no LLVM representation.
+    pub(super) fn lower_constant(
+        &self,
+        constant: Constant,
+        type_info: Option<&TypeInfo>,
+        ctx: &TypedContext,
+    ) -> Result<IntValue<'ctx>, CodegenError> {
+        Ok(match constant {
+            Constant::Bool(value) => self.context.bool_type().const_int(u64::from(value), false),
+            Constant::Int(value) => {
+                let int_type = match type_info {
+                    Some(type_info) if matches!(type_info.kind, TypeInfoKind::Number(_)) => {
+                        self.value_type(type_info, ctx)?.into_int_type()
+                    }
+                    _ => self.context.i32_type(),
+                };
+                // Truncating to the low 64 bits keeps the two's complement representation
+                // of every value in range of a 64-bit or narrower type.
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                int_type.const_int(value as u64, false)
+            }
+        })
+    }
+
+    /// Returns true if the current block already ends with a terminator, such as the
+    /// `return` of a branch that always runs.
+    pub(super) fn is_terminated(&self) -> bool {
+        self.builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_terminator)
+            .is_some()
+    }
+
+    /// Continues lowering in a new block without predecessors if the current block is
+    /// terminated. The statements that follow are unreachable, and LLVM removes them.
+    pub(super) fn continue_after_terminator(&self) {
+        if !self.is_terminated() {
+            return;
+        }
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_parent)
+            .expect("Builder must be positioned inside a function");
+        let block = self.context.append_basic_block(function, "unreachable");
+        self.builder.position_at_end(block);
+    }
+
+    /// Clears the constants of the previous function, keeping the module-level ones.
+    pub(super) fn reset_constants(&self) {
+        self.constants
+            .borrow_mut()
+            .clone_from(&self.module_constants.borrow());
+    }
+
+    /// Hides the constant named `name` behind a variable or parameter of the same name.
+    pub(super) fn shadow_constant(&self, name: &str) {
+        self.constants.borrow_mut().remove(name);
+    }
+}
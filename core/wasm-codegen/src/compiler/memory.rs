@@ -0,0 +1,638 @@
+//! Type mapping and linear memory storage.
+//!
+//! Every local lives in a stack slot created with `alloca` in the function's entry block.
+//! LLVM places these slots on the shadow stack in linear memory, so aggregate values
+//! (structs and arrays) can be addressed field by field or element by element and passed
+//! around by pointer.
+//!
+//! ## Struct Representation
+//!
+//! Each Inference struct becomes a named LLVM struct type whose fields follow the
+//! declaration order. The first time a struct type is needed its LLVM layout is checked
+//! against the [`StructLayout`] computed by the type checker, so both always agree on
+//! field offsets and sizes.
+//!
+//! Struct values are handled through pointers:
+//!
+//! - A struct-typed expression lowers to a pointer to its storage
+//! - Assigning or binding a struct copies the whole value into the destination slot
+//! - Struct arguments are copied by the caller into a fresh slot, and the callee receives
+//!   a pointer to that copy, so callee mutations never leak back to the caller
+//!
+//! ## Array Representation
+//!
+//! An array `[T; N]` is an LLVM array of `N` elements of the storage type of `T`, which
+//! matches the type checker layout (`N * size(T)` bytes, aligned like `T`). Arrays follow
+//! the same pointer and copy rules as structs.
+//!
+//! Indexing is guarded according to [`BoundsChecks`]: with [`BoundsChecks::Trap`] the
+//! index is compared with `N` as an unsigned 64-bit value (so negative indices fail too)
+//! and an out-of-bounds index executes `llvm.trap`, which lowers to `unreachable`.
+//!
+//! Strings are handled by pointer as well; their representation is described in the
+//! `strings` submodule.
+
+use super::{Compiler, strings};
+use crate::{
+    errors::{CodegenError, CodegenErrorKind},
+    options::BoundsChecks,
+};
+use inference_ast::nodes::{
+    ArrayIndexAccessExpression, ArrayLiteral, Expression, FunctionCallExpression, Identifier,
+    MemberAccessExpression,
+};
+use inference_type_checker::{
+    layout::StructLayout,
+    type_info::{NumberType, TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+use inkwell::{
+    AddressSpace, IntPredicate,
+    basic_block::BasicBlock,
+    types::{BasicType, BasicTypeEnum, StructType},
+    values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue},
+};
+use std::{cell::RefCell, rc::Rc};
+
+impl<'ctx> Compiler<'ctx> {
+    /// Returns the LLVM type used for values of `type_info` in registers, parameters and
+    /// return values.
+    ///
+    /// Structs, arrays and strings are passed by pointer, so their value type is `ptr`.
+    /// Enums are `i32` tags.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, reported at the function or statement being generated, for types
+    /// that have no code generation support yet.
+    pub(super) fn value_type(
+        &self,
+        type_info: &TypeInfo,
+        ctx: &TypedContext,
+    ) -> Result<BasicTypeEnum<'ctx>, CodegenError> {
+        if Self::is_aggregate(type_info, ctx) {
+            return Ok(self.context.ptr_type(AddressSpace::default()).into());
+        }
+        if Self::enum_name(type_info, ctx).is_some() {
+            return Ok(self.context.i32_type().into());
+        }
+        match &type_info.kind {
+            TypeInfoKind::Bool => Ok(self.context.bool_type().into()),
+            TypeInfoKind::Number(number_type) => {
+                let bits = match number_type {
+                    NumberType::I8 | NumberType::U8 => 8,
+                    NumberType::I16 | NumberType::U16 => 16,
+                    NumberType::I32 | NumberType::U32 => 32,
+                    NumberType::I64 | NumberType::U64 => 64,
+                };
+                Ok(self.context.custom_width_int_type(bits).into())
+            }
+            _ => Err(self.error(CodegenErrorKind::Unsupported(format!("type `{type_info}`")))),
+        }
+    }
+
+    /// Returns the LLVM type of a stack slot or struct field holding a `type_info` value.
+    ///
+    /// This is the struct, array or string type itself for aggregates and
+    /// [`Compiler::value_type`] otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for types, or struct fields and array elements of types, that have
+    /// no code generation support yet.
+    pub(super) fn storage_type(
+        &self,
+        type_info: &TypeInfo,
+        ctx: &TypedContext,
+    ) -> Result<BasicTypeEnum<'ctx>, CodegenError> {
+        if let Some(name) = Self::struct_name(type_info, ctx) {
+            return Ok(self.struct_type(&name, ctx)?.0.into());
+        }
+        match &type_info.kind {
+            TypeInfoKind::Array(element, length) => {
+                Ok(self.storage_type(element, ctx)?.array_type(*length).into())
+            }
+            TypeInfoKind::String => Ok(self.string_type().into()),
+            _ => self.value_type(type_info, ctx),
+        }
+    }
+
+    /// Returns true if values of `type_info` live in memory and are handled by pointer.
+    pub(super) fn is_aggregate(type_info: &TypeInfo, ctx: &TypedContext) -> bool {
+        type_info.is_array() || type_info.is_string() || Self::struct_name(type_info, ctx).is_some()
+    }
+
+    /// Returns the struct name if `type_info` refers to a struct with a known layout.
+    pub(super) fn struct_name(type_info: &TypeInfo, ctx: &TypedContext) -> Option<String> {
+        match &type_info.kind {
+            TypeInfoKind::Struct(name) => Some(name.clone()),
+            TypeInfoKind::Custom(name) if ctx.struct_layout(name).is_some() => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the LLVM struct type and type checker layout of the struct called `name`,
+    /// creating the type on first use.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type checker has no layout for the struct, or if a field has
+    /// a type without code generation support.
+    ///
+    /// # Panics
+    ///
+    /// Panics if LLVM lays the struct out differently than the type checker.
+    pub(super) fn struct_type(
+        &self,
+        name: &str,
+        ctx: &TypedContext,
+    ) -> Result<(StructType<'ctx>, StructLayout), CodegenError> {
+        if let Some(cached) = self.struct_types.borrow().get(name) {
+            return Ok(cached.clone());
+        }
+        let layout = ctx.struct_layout(name).ok_or_else(|| {
+            self.error(CodegenErrorKind::MissingInformation(format!(
+                "struct `{name}` has no memory layout"
+            )))
+        })?;
+        let field_types = layout
+            .fields
+            .iter()
+            .map(|field| self.storage_type(&field.type_info, ctx))
+            .collect::<Result<Vec<_>, _>>()?;
+        let struct_type = self.context.opaque_struct_type(name);
+        struct_type.set_body(&field_types, false);
+
+        let target_data = self.target_machine.get_target_data();
+        for (index, field) in (0u32..).zip(&layout.fields) {
+            assert_eq!(
+                target_data.offset_of_element(&struct_type, index),
+                Some(u64::from(field.offset)),
+                "LLVM and the type checker disagree on the offset of `{name}.{}`",
+                field.name
+            );
+        }
+        assert_eq!(
+            target_data.get_abi_size(&struct_type),
+            u64::from(layout.size),
+            "LLVM and the type checker disagree on the size of `{name}`"
+        );
+
+        self.struct_types
+            .borrow_mut()
+            .insert(name.to_string(), (struct_type, layout.clone()));
+        Ok((struct_type, layout))
+    }
+
+    /// Allocates a stack slot of type `ty` at the start of the current function's entry
+    /// block, so that slots created inside loops are not allocated repeatedly.
+    pub(super) fn build_entry_alloca(
+        &self,
+        ty: BasicTypeEnum<'ctx>,
+        name: &str,
+    ) -> PointerValue<'ctx> {
+        let entry = self
+            .builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_parent)
+            .and_then(FunctionValue::get_first_basic_block)
+            .expect("Builder must be positioned inside a function");
+        let entry_builder = self.context.create_builder();
+        match entry.get_first_instruction() {
+            Some(instruction) => entry_builder.position_before(&instruction),
+            None => entry_builder.position_at_end(entry),
+        }
+        entry_builder.build_alloca(ty, name).unwrap()
+    }
+
+    /// Converts an integer `value` to the integer type `ty`, leaving other values as they
+    /// are. Booleans are zero-extended; wider integers are sign-extended or truncated.
+    pub(super) fn coerce(
+        &self,
+        value: BasicValueEnum<'ctx>,
+        ty: BasicTypeEnum<'ctx>,
+    ) -> BasicValueEnum<'ctx> {
+        match (value, ty) {
+            (BasicValueEnum::IntValue(int), BasicTypeEnum::IntType(int_type))
+                if int.get_type() != int_type =>
+            {
+                let is_signed = int.get_type().get_bit_width() > 1;
+                self.builder
+                    .build_int_cast_sign_flag(int, int_type, is_signed, "")
+                    .unwrap()
+                    .into()
+            }
+            _ => value,
+        }
+    }
+
+    /// Stores `value` into the slot at `ptr` of type `ty`.
+    ///
+    /// For aggregate slots `value` is a pointer to the source value, which is copied.
+    pub(super) fn store_value(
+        &self,
+        ptr: PointerValue<'ctx>,
+        ty: BasicTypeEnum<'ctx>,
+        value: BasicValueEnum<'ctx>,
+    ) {
+        if is_aggregate_type(ty) {
+            let copy = self
+                .builder
+                .build_load(ty, value.into_pointer_value(), "")
+                .unwrap();
+            self.builder.build_store(ptr, copy).unwrap();
+        } else {
+            self.builder
+                .build_store(ptr, self.coerce(value, ty))
+                .unwrap();
+        }
+    }
+
+    /// Loads the value of type `ty` stored at `ptr`.
+    ///
+    /// Aggregate values are represented by their address, so no load is emitted for them.
+    pub(super) fn load_value(
+        &self,
+        ptr: PointerValue<'ctx>,
+        ty: BasicTypeEnum<'ctx>,
+        name: &str,
+    ) -> BasicValueEnum<'ctx> {
+        if is_aggregate_type(ty) {
+            ptr.into()
+        } else {
+            self.builder.build_load(ty, ptr, name).unwrap()
+        }
+    }
+
+    /// Returns the stack slot and storage type of the variable named by `identifier`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no such variable was defined in the current function.
+    pub(super) fn variable(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<(PointerValue<'ctx>, BasicTypeEnum<'ctx>), CodegenError> {
+        self.variables
+            .borrow()
+            .get(&identifier.name)
+            .copied()
+            .ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(format!(
+                        "variable `{}` is not defined",
+                        identifier.name
+                    )),
+                    identifier.id,
+                    identifier.location,
+                )
+            })
+    }
+
+    /// Returns the address and storage type of an assignable expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is neither a variable, a struct field nor an
+    /// array element.
+    pub(super) fn lower_place(
+        &self,
+        expression: &Expression,
+        ctx: &TypedContext,
+    ) -> Result<(PointerValue<'ctx>, BasicTypeEnum<'ctx>), CodegenError> {
+        match expression {
+            Expression::Identifier(identifier) => self.variable(identifier),
+            Expression::MemberAccess(member_access) => self.member_pointer(member_access, ctx),
+            Expression::ArrayIndexAccess(index_access)
+                if !strings::is_string(&index_access.array.borrow(), ctx) =>
+            {
+                self.element_pointer(index_access, ctx)
+            }
+            Expression::Parenthesized(parenthesized) => {
+                self.lower_place(&parenthesized.expression.borrow(), ctx)
+            }
+            Expression::ArrayIndexAccess(_) => Err(self.error_at(
+                CodegenErrorKind::Unsupported("assignment to bytes of strings".to_string()),
+                expression.id(),
+                expression.location(),
+            )),
+            _ => Err(self.error_at(
+                CodegenErrorKind::Unsupported(
+                    "assignment to anything but variables, fields and elements".to_string(),
+                ),
+                expression.id(),
+                expression.location(),
+            )),
+        }
+    }
+
+    /// Returns the address and storage type of the struct field named by `member_access`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the accessed expression is not a struct or has no such field.
+    pub(super) fn member_pointer(
+        &self,
+        member_access: &MemberAccessExpression,
+        ctx: &TypedContext,
+    ) -> Result<(PointerValue<'ctx>, BasicTypeEnum<'ctx>), CodegenError> {
+        let missing = |message: String| {
+            self.error_at(
+                CodegenErrorKind::MissingInformation(message),
+                member_access.id,
+                member_access.location,
+            )
+        };
+        let base = member_access.expression.borrow();
+        let struct_name = ctx
+            .get_node_typeinfo(base.id())
+            .and_then(|type_info| Self::struct_name(&type_info, ctx))
+            .ok_or_else(|| missing("member access is not performed on a struct".to_string()))?;
+        let (struct_type, layout) = self.struct_type(&struct_name, ctx)?;
+        let field_name = &member_access.name.name;
+        let (index, field) = layout.field(field_name).ok_or_else(|| {
+            missing(format!(
+                "struct `{struct_name}` has no field `{field_name}`"
+            ))
+        })?;
+        let base_ptr = self.lower_expression(&base, ctx)?.into_pointer_value();
+        let index = u32::try_from(index).expect("Struct field index must fit in u32");
+        let field_ptr = self
+            .builder
+            .build_struct_gep(struct_type, base_ptr, index, field_name)
+            .unwrap();
+        Ok((field_ptr, self.storage_type(&field.type_info, ctx)?))
+    }
+
+    /// Lowers a struct literal into a fresh stack slot and returns its address.
+    ///
+    /// Fields missing from the literal are zero-initialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the struct has no field of a given name, or if a field value
+    /// cannot be lowered.
+    pub(super) fn lower_struct_literal(
+        &self,
+        name: &str,
+        fields: &[(Rc<Identifier>, RefCell<Expression>)],
+        ctx: &TypedContext,
+    ) -> Result<PointerValue<'ctx>, CodegenError> {
+        let (struct_type, layout) = self.struct_type(name, ctx)?;
+        let local = self.build_entry_alloca(struct_type.into(), name);
+        self.builder
+            .build_store(local, struct_type.const_zero())
+            .unwrap();
+        for (field_name, value) in fields {
+            let (index, field) = layout.field(&field_name.name).ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(format!(
+                        "struct `{name}` has no field `{}`",
+                        field_name.name
+                    )),
+                    field_name.id,
+                    field_name.location,
+                )
+            })?;
+            let value = self.lower_expression(&value.borrow(), ctx)?;
+            let index = u32::try_from(index).expect("Struct field index must fit in u32");
+            let field_ptr = self
+                .builder
+                .build_struct_gep(struct_type, local, index, &field_name.name)
+                .unwrap();
+            let field_type = self.storage_type(&field.type_info, ctx)?;
+            self.store_value(field_ptr, field_type, value);
+        }
+        Ok(local)
+    }
+
+    /// Lowers an array literal into a fresh stack slot and returns its address.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the type checker did not record an array type for the literal,
+    /// or if an element cannot be lowered.
+    pub(super) fn lower_array_literal(
+        &self,
+        array_literal: &ArrayLiteral,
+        ctx: &TypedContext,
+    ) -> Result<PointerValue<'ctx>, CodegenError> {
+        let array_type_info = ctx
+            .get_node_typeinfo(array_literal.id)
+            .filter(TypeInfo::is_array)
+            .ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(
+                        "array literal has no array type".to_string(),
+                    ),
+                    array_literal.id,
+                    array_literal.location,
+                )
+            })?;
+        let TypeInfoKind::Array(element, _) = &array_type_info.kind else {
+            unreachable!();
+        };
+        let array_type = self.storage_type(&array_type_info, ctx)?;
+        let element_type = self.storage_type(element, ctx)?;
+        let local = self.build_entry_alloca(array_type, "array");
+        let i32_type = self.context.i32_type();
+        for (index, value) in (0u64..).zip(array_literal.elements.iter().flatten()) {
+            let value = self.lower_expression(&value.borrow(), ctx)?;
+            // SAFETY: `index` is below the array length, which the type checker derived
+            // from the number of elements of this literal.
+            let element_ptr = unsafe {
+                self.builder.build_in_bounds_gep(
+                    array_type,
+                    local,
+                    &[i32_type.const_zero(), i32_type.const_int(index, false)],
+                    "",
+                )
+            }
+            .unwrap();
+            self.store_value(element_ptr, element_type, value);
+        }
+        Ok(local)
+    }
+
+    /// Returns the address and storage type of the array element or string byte named by
+    /// `index_access`.
+    ///
+    /// The index is checked against the array or string length according to
+    /// [`CodegenOptions::bounds_checks`](crate::CodegenOptions::bounds_checks).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the indexed expression is neither an array nor a string, or if
+    /// it or the index cannot be lowered.
+    pub(super) fn element_pointer(
+        &self,
+        index_access: &ArrayIndexAccessExpression,
+        ctx: &TypedContext,
+    ) -> Result<(PointerValue<'ctx>, BasicTypeEnum<'ctx>), CodegenError> {
+        let array = index_access.array.borrow();
+        let array_type_info = ctx
+            .get_node_typeinfo(array.id())
+            .filter(|type_info| type_info.is_array() || type_info.is_string())
+            .ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(
+                        "indexing is not performed on an array or a string".to_string(),
+                    ),
+                    index_access.id,
+                    index_access.location,
+                )
+            })?;
+        let array_ptr = self.lower_expression(&array, ctx)?.into_pointer_value();
+
+        let index_expression = index_access.index.borrow();
+        let is_signed = ctx
+            .get_node_typeinfo(index_expression.id())
+            .is_none_or(|type_info| type_info.is_signed_integer());
+        let index = self
+            .lower_expression(&index_expression, ctx)?
+            .into_int_value();
+        let TypeInfoKind::Array(element, length) = &array_type_info.kind else {
+            let byte_ptr = self.string_byte_pointer(array_ptr, index, is_signed);
+            return Ok((byte_ptr, self.context.i8_type().into()));
+        };
+        let array_type = self.storage_type(&array_type_info, ctx)?;
+        if self.bounds_checks == BoundsChecks::Trap {
+            self.build_bounds_check(index, *length, is_signed);
+        }
+        let i32_type = self.context.i32_type();
+        let index = self
+            .builder
+            .build_int_cast_sign_flag(index, i32_type, is_signed, "index")
+            .unwrap();
+        // SAFETY: with `BoundsChecks::Trap` the index was checked above; with
+        // `BoundsChecks::Unchecked` the user opted out of the check.
+        let element_ptr = unsafe {
+            self.builder.build_in_bounds_gep(
+                array_type,
+                array_ptr,
+                &[i32_type.const_zero(), index],
+                "",
+            )
+        }
+        .unwrap();
+        Ok((element_ptr, self.storage_type(element, ctx)?))
+    }
+
+    /// Emits a branch to a trapping block when `index` is not below `length`.
+    ///
+    /// The index is extended to 64 bits according to its signedness and compared unsigned,
+    /// so negative indices are rejected as well.
+    fn build_bounds_check(&self, index: IntValue<'ctx>, length: u32, is_signed: bool) {
+        let in_bounds = self
+            .builder
+            .build_int_compare(
+                IntPredicate::ULT,
+                self.widen_index(index, is_signed),
+                self.context.i64_type().const_int(u64::from(length), false),
+                "in_bounds",
+            )
+            .unwrap();
+        self.build_trap_unless(in_bounds);
+    }
+
+    /// Extends an index or length to 64 bits according to its signedness, so that negative
+    /// values compare above every length when compared unsigned.
+    pub(super) fn widen_index(&self, value: IntValue<'ctx>, is_signed: bool) -> IntValue<'ctx> {
+        self.builder
+            .build_int_cast_sign_flag(value, self.context.i64_type(), is_signed, "")
+            .unwrap()
+    }
+
+    /// Emits a branch to a trapping block when `in_bounds` is false and continues in a new
+    /// block otherwise.
+    pub(super) fn build_trap_unless(&self, in_bounds: IntValue<'ctx>) {
+        let function = self
+            .builder
+            .get_insert_block()
+            .and_then(BasicBlock::get_parent)
+            .expect("Builder must be positioned inside a function");
+        let trap_block = self.context.append_basic_block(function, "out_of_bounds");
+        let continue_block = self.context.append_basic_block(function, "in_bounds");
+        self.builder
+            .build_conditional_branch(in_bounds, continue_block, trap_block)
+            .unwrap();
+        self.builder.position_at_end(trap_block);
+        self.build_trap();
+        self.builder.position_at_end(continue_block);
+    }
+
+    /// Lowers a call to a function of this module or a method of a string and returns its
+    /// result, if any.
+    ///
+    /// Struct, array and string arguments are copied into a fresh stack slot owned by the
+    /// call, and a pointer to the copy is passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the callee is neither a plain function name declared in this
+    /// module nor a supported string method, or if an argument cannot be lowered.
+    pub(super) fn lower_call(
+        &self,
+        call: &FunctionCallExpression,
+        ctx: &TypedContext,
+    ) -> Result<Option<BasicValueEnum<'ctx>>, CodegenError> {
+        if let Expression::MemberAccess(member_access) = &call.function
+            && strings::is_string(&member_access.expression.borrow(), ctx)
+        {
+            return self.lower_string_method(call, member_access, ctx).map(Some);
+        }
+        let Expression::Identifier(callee) = &call.function else {
+            return Err(self.error_at(
+                CodegenErrorKind::Unsupported("indirect calls".to_string()),
+                call.id,
+                call.location,
+            ));
+        };
+        let function = self.module.get_function(&callee.name).ok_or_else(|| {
+            self.error_at(
+                CodegenErrorKind::MissingInformation(format!(
+                    "function `{}` was not declared",
+                    callee.name
+                )),
+                callee.id,
+                callee.location,
+            )
+        })?;
+        let param_types = function.get_type().get_param_types();
+        let arguments = call
+            .arguments
+            .iter()
+            .flatten()
+            .zip(param_types)
+            .map(|((_, argument), param_type)| {
+                let argument = argument.borrow();
+                let value = self.lower_expression(&argument, ctx)?;
+                let aggregate_type = ctx
+                    .get_node_typeinfo(argument.id())
+                    .filter(|type_info| Self::is_aggregate(type_info, ctx))
+                    .map(|type_info| self.storage_type(&type_info, ctx))
+                    .transpose()?;
+                let value = match aggregate_type {
+                    Some(aggregate_type) => {
+                        let copy = self.build_entry_alloca(aggregate_type, "arg");
+                        self.store_value(copy, aggregate_type, value);
+                        copy.into()
+                    }
+                    None => self.coerce(value, param_type.try_into().unwrap()),
+                };
+                Ok(BasicMetadataValueEnum::from(value))
+            })
+            .collect::<Result<Vec<_>, CodegenError>>()?;
+        Ok(self
+            .builder
+            .build_call(function, &arguments, "")
+            .expect("Failed to build function call")
+            .try_as_basic_value()
+            .basic())
+    }
+}
+
+/// Returns true for LLVM types of values that are handled by pointer.
+fn is_aggregate_type(ty: BasicTypeEnum<'_>) -> bool {
+    ty.is_struct_type() || ty.is_array_type()
+}
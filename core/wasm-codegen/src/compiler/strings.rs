@@ -0,0 +1,305 @@
+//! String literals and the runtime representation of strings.
+//!
+//! A `string` value is a `{ ptr, i32 }` pair: the address of its first byte and its length
+//! in bytes. Like structs and arrays, strings live in a stack slot and are handled by
+//! pointer, so passing or assigning a string copies the pair, never the bytes.
+//!
+//! The bytes of each distinct literal are emitted once, without a terminating NUL, as a
+//! private constant global. The linker places these globals in an active data segment, so
+//! they are in linear memory when the module is instantiated. Identical literals of a module
+//! share their bytes, and `const` definitions of type `string` lower like their literal.
+//!
+//! Strings support these operations:
+//!
+//! - `s.len()` loads the length as a `u32`
+//! - `s[i]` loads the byte at index `i` as a `u8`
+//! - `s.slice(start, end)` returns the bytes `start..end` as a new string that refers to
+//!   the same memory
+//!
+//! Indices are checked according to [`BoundsChecks`]: with [`BoundsChecks::Trap`] an
+//! index must be below the length and a slice must satisfy `start <= end <= len`,
+//! otherwise `llvm.trap` is executed. Strings are immutable: assigning to `s[i]` is not
+//! supported, since the bytes may be shared with other strings.
+
+use super::Compiler;
+use crate::{
+    errors::{CodegenError, CodegenErrorKind},
+    options::BoundsChecks,
+};
+use inference_ast::nodes::{
+    ConstantDefinition, Expression, FunctionCallExpression, Literal, MemberAccessExpression,
+    StringLiteral,
+};
+use inference_type_checker::typed_context::TypedContext;
+use inkwell::{
+    AddressSpace, IntPredicate,
+    module::Linkage,
+    types::StructType,
+    values::{BasicValueEnum, IntValue, PointerValue, UnnamedAddress},
+};
+
+/// Field of the string representation holding the address of the first byte.
+const DATA_FIELD: u32 = 0;
+
+/// Field of the string representation holding the length in bytes.
+const LENGTH_FIELD: u32 = 1;
+
+impl<'ctx> Compiler<'ctx> {
+    /// Returns the LLVM type of a string value: the address of its bytes and their length.
+    pub(super) fn string_type(&self) -> StructType<'ctx> {
+        self.context.struct_type(
+            &[
+                self.context.ptr_type(AddressSpace::default()).into(),
+                self.context.i32_type().into(),
+            ],
+            false,
+        )
+    }
+
+    /// Records the text of a module-level `const` definition if it is initialized by a
+    /// string literal, so that uses of its name in every function lower to the literal.
+    pub(super) fn define_module_string(&self, constant_definition: &ConstantDefinition) {
+        if let Literal::String(string_literal) = &constant_definition.value {
+            self.module_strings
+                .borrow_mut()
+                .insert(constant_definition.name(), text(string_literal).to_string());
+        }
+    }
+
+    /// Lowers a use of the module-level string constant called `name` into a fresh stack
+    /// slot and returns its address, or returns `None` if there is no such constant.
+    pub(super) fn lower_module_string(&self, name: &str) -> Option<PointerValue<'ctx>> {
+        let text = self.module_strings.borrow().get(name).cloned()?;
+        Some(self.build_string(&text))
+    }
+
+    /// Lowers a string literal into a fresh stack slot and returns its address.
+    pub(super) fn lower_string_literal(
+        &self,
+        string_literal: &StringLiteral,
+    ) -> PointerValue<'ctx> {
+        self.build_string(text(string_literal))
+    }
+
+    /// Stores a string referring to the bytes of `text` into a fresh stack slot and
+    /// returns its address.
+    fn build_string(&self, text: &str) -> PointerValue<'ctx> {
+        let data = self.string_data(text);
+        let length = self.context.i32_type().const_int(text.len() as u64, false);
+        let string_type = self.string_type();
+        let local = self.build_entry_alloca(string_type.into(), "string");
+        self.builder
+            .build_store(
+                local,
+                string_type.const_named_struct(&[data.into(), length.into()]),
+            )
+            .unwrap();
+        local
+    }
+
+    /// Returns the address of the bytes of `text`, emitting them as a constant global the
+    /// first time `text` is used in the module.
+    fn string_data(&self, text: &str) -> PointerValue<'ctx> {
+        if let Some(data) = self.string_literals.borrow().get(text) {
+            return *data;
+        }
+        let bytes = self.context.const_string(text.as_bytes(), false);
+        let global = self.module.add_global(bytes.get_type(), None, ".str");
+        global.set_initializer(&bytes);
+        global.set_constant(true);
+        global.set_linkage(Linkage::Private);
+        global.set_unnamed_address(UnnamedAddress::Global);
+        global.set_alignment(1);
+        let data = global.as_pointer_value();
+        self.string_literals
+            .borrow_mut()
+            .insert(text.to_string(), data);
+        data
+    }
+
+    /// Loads the address of the bytes and the length of the string at `string`.
+    fn string_parts(&self, string: PointerValue<'ctx>) -> (PointerValue<'ctx>, IntValue<'ctx>) {
+        let string_type = self.string_type();
+        let data_ptr = self
+            .builder
+            .build_struct_gep(string_type, string, DATA_FIELD, "")
+            .unwrap();
+        let data = self
+            .builder
+            .build_load(
+                self.context.ptr_type(AddressSpace::default()),
+                data_ptr,
+                "data",
+            )
+            .unwrap()
+            .into_pointer_value();
+        let length_ptr = self
+            .builder
+            .build_struct_gep(string_type, string, LENGTH_FIELD, "")
+            .unwrap();
+        let length = self
+            .builder
+            .build_load(self.context.i32_type(), length_ptr, "len")
+            .unwrap()
+            .into_int_value();
+        (data, length)
+    }
+
+    /// Returns the address of byte `index` of the string at `string`.
+    ///
+    /// The index is checked against the length according to
+    /// [`CodegenOptions::bounds_checks`](crate::CodegenOptions::bounds_checks).
+    pub(super) fn string_byte_pointer(
+        &self,
+        string: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        is_signed: bool,
+    ) -> PointerValue<'ctx> {
+        let (data, length) = self.string_parts(string);
+        if self.bounds_checks == BoundsChecks::Trap {
+            let in_bounds = self
+                .builder
+                .build_int_compare(
+                    IntPredicate::ULT,
+                    self.widen_index(index, is_signed),
+                    self.widen_index(length, false),
+                    "in_bounds",
+                )
+                .unwrap();
+            self.build_trap_unless(in_bounds);
+        }
+        self.byte_pointer(data, index, is_signed)
+    }
+
+    /// Lowers a call of a method on a string and returns its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for methods without code generation support, or if the string or
+    /// an argument cannot be lowered.
+    pub(super) fn lower_string_method(
+        &self,
+        call: &FunctionCallExpression,
+        member_access: &MemberAccessExpression,
+        ctx: &TypedContext,
+    ) -> Result<BasicValueEnum<'ctx>, CodegenError> {
+        let string = self
+            .lower_expression(&member_access.expression.borrow(), ctx)?
+            .into_pointer_value();
+        let arguments = call
+            .arguments
+            .iter()
+            .flatten()
+            .map(|(_, argument)| {
+                let argument = argument.borrow();
+                let is_signed = ctx
+                    .get_node_typeinfo(argument.id())
+                    .is_none_or(|type_info| type_info.is_signed_integer());
+                let value = self.lower_expression(&argument, ctx)?.into_int_value();
+                Ok((value, is_signed))
+            })
+            .collect::<Result<Vec<_>, CodegenError>>()?;
+        match (member_access.name.name.as_str(), arguments.as_slice()) {
+            ("len", []) => Ok(self.string_parts(string).1.into()),
+            ("slice", [start, end]) => Ok(self.lower_string_slice(string, *start, *end).into()),
+            (name, _) => Err(self.error_at(
+                CodegenErrorKind::Unsupported(format!("string method `{name}`")),
+                call.id,
+                call.location,
+            )),
+        }
+    }
+
+    /// Stores the bytes `start..end` of the string at `string` as a new string into a fresh
+    /// stack slot and returns its address. Both bounds come with their signedness.
+    fn lower_string_slice(
+        &self,
+        string: PointerValue<'ctx>,
+        (start, start_is_signed): (IntValue<'ctx>, bool),
+        (end, end_is_signed): (IntValue<'ctx>, bool),
+    ) -> PointerValue<'ctx> {
+        let (data, length) = self.string_parts(string);
+        if self.bounds_checks == BoundsChecks::Trap {
+            let wide_start = self.widen_index(start, start_is_signed);
+            let wide_end = self.widen_index(end, end_is_signed);
+            let ordered = self
+                .builder
+                .build_int_compare(IntPredicate::ULE, wide_start, wide_end, "")
+                .unwrap();
+            let within = self
+                .builder
+                .build_int_compare(
+                    IntPredicate::ULE,
+                    wide_end,
+                    self.widen_index(length, false),
+                    "",
+                )
+                .unwrap();
+            let in_bounds = self
+                .builder
+                .build_and(ordered, within, "in_bounds")
+                .unwrap();
+            self.build_trap_unless(in_bounds);
+        }
+        let i32_type = self.context.i32_type();
+        let start_data = self.byte_pointer(data, start, start_is_signed);
+        let start = self
+            .builder
+            .build_int_cast_sign_flag(start, i32_type, start_is_signed, "")
+            .unwrap();
+        let end = self
+            .builder
+            .build_int_cast_sign_flag(end, i32_type, end_is_signed, "")
+            .unwrap();
+        let slice_length = self.builder.build_int_sub(end, start, "len").unwrap();
+
+        let string_type = self.string_type();
+        let local = self.build_entry_alloca(string_type.into(), "slice");
+        let data_ptr = self
+            .builder
+            .build_struct_gep(string_type, local, DATA_FIELD, "")
+            .unwrap();
+        self.builder.build_store(data_ptr, start_data).unwrap();
+        let length_ptr = self
+            .builder
+            .build_struct_gep(string_type, local, LENGTH_FIELD, "")
+            .unwrap();
+        self.builder.build_store(length_ptr, slice_length).unwrap();
+        local
+    }
+
+    /// Returns the address of the byte at `index` from `data`.
+    fn byte_pointer(
+        &self,
+        data: PointerValue<'ctx>,
+        index: IntValue<'ctx>,
+        is_signed: bool,
+    ) -> PointerValue<'ctx> {
+        let index = self
+            .builder
+            .build_int_cast_sign_flag(index, self.context.i32_type(), is_signed, "index")
+            .unwrap();
+        // SAFETY: with `BoundsChecks::Trap` the index was checked against the length of
+        // the string; with `BoundsChecks::Unchecked` the user opted out of the check.
+        unsafe {
+            self.builder
+                .build_in_bounds_gep(self.context.i8_type(), data, &[index], "")
+        }
+        .unwrap()
+    }
+}
+
+/// Returns the text of a string literal, whose value includes the enclosing quotes.
+fn text(string_literal: &StringLiteral) -> &str {
+    let value = string_literal.value.as_str();
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Returns true if `expression` has the type `string`.
+pub(super) fn is_string(expression: &Expression, ctx: &TypedContext) -> bool {
+    ctx.get_node_typeinfo(expression.id())
+        .is_some_and(|type_info| type_info.is_string())
+}
@@ -0,0 +1,323 @@
+//! Compile-time evaluation of integer and boolean expressions.
+//!
+//! [`fold`] evaluates an expression of the typed AST whose operands are literals or known
+//! constants to a [`Constant`]. Both backends fold expressions before lowering them, so an
+//! expression computes the same value with either backend and whether or not its operands
+//! are constant.
+//!
+//! Folding follows the WebAssembly semantics of the operand type: results wrap around at
+//! the width of the type, shift amounts are taken modulo that width and the exponent of
+//! `**` is taken as an unsigned number. An expression that traps at runtime, a division by
+//! zero or the signed division of the minimum value by `-1`, is not folded.
+
+use std::collections::HashMap;
+
+use inference_ast::nodes::{
+    BinaryExpression, ConstantDefinition, Expression, Literal, OperatorKind, UnaryOperatorKind,
+};
+use inference_type_checker::{
+    type_info::{NumberType, TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+
+/// Value of an expression known at compile time.
+///
+/// Integers are kept in the range of their type: values of signed types are sign-extended
+/// and values of unsigned types are zero-extended, so comparing two constants of the same
+/// type compares their values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Constant {
+    Bool(bool),
+    Int(i128),
+}
+
+/// Constants visible to an expression, keyed by name.
+pub(crate) type Constants = HashMap<String, Constant>;
+
+/// Evaluates `expression` at compile time with the constants in `constants`, or returns
+/// `None` if its value is only known at runtime.
+pub(crate) fn fold(
+    expression: &Expression,
+    constants: &Constants,
+    ctx: &TypedContext,
+) -> Option<Constant> {
+    match expression {
+        Expression::Literal(Literal::Bool(bool_literal)) => {
+            Some(Constant::Bool(bool_literal.value))
+        }
+        Expression::Literal(Literal::Number(number_literal)) => {
+            let value = number_literal.value.parse().ok()?;
+            Some(Constant::Int(wrap(
+                value,
+                number_type(ctx, number_literal.id),
+            )))
+        }
+        Expression::Parenthesized(parenthesized_expression) => fold(
+            &parenthesized_expression.expression.borrow(),
+            constants,
+            ctx,
+        ),
+        Expression::Identifier(identifier) => constants.get(&identifier.name).copied(),
+        Expression::PrefixUnary(prefix_unary_expression) => {
+            let operand = fold(&prefix_unary_expression.expression.borrow(), constants, ctx)?;
+            let number_type = number_type(ctx, prefix_unary_expression.id);
+            match (&prefix_unary_expression.operator, operand) {
+                (UnaryOperatorKind::Not, Constant::Bool(value)) => Some(Constant::Bool(!value)),
+                (UnaryOperatorKind::Neg, Constant::Int(value)) => {
+                    Some(Constant::Int(wrap(value.wrapping_neg(), number_type)))
+                }
+                (UnaryOperatorKind::BitNot, Constant::Int(value)) => {
+                    Some(Constant::Int(wrap(!value, number_type)))
+                }
+                _ => None,
+            }
+        }
+        Expression::Binary(binary_expression) => fold_binary(binary_expression, constants, ctx),
+        _ => None,
+    }
+}
+
+/// Evaluates a binary expression at compile time.
+///
+/// `&&` and `||` fold as soon as their left operand decides the result, since the right
+/// operand is not evaluated then. Other operators need both operands to fold.
+fn fold_binary(
+    binary_expression: &BinaryExpression,
+    constants: &Constants,
+    ctx: &TypedContext,
+) -> Option<Constant> {
+    let left = fold(&binary_expression.left.borrow(), constants, ctx)?;
+    match (&binary_expression.operator, left) {
+        (OperatorKind::And, Constant::Bool(false)) | (OperatorKind::Or, Constant::Bool(true)) => {
+            return Some(left);
+        }
+        (OperatorKind::And | OperatorKind::Or, Constant::Bool(_)) => {
+            return fold(&binary_expression.right.borrow(), constants, ctx);
+        }
+        _ => {}
+    }
+    let right = fold(&binary_expression.right.borrow(), constants, ctx)?;
+    match (left, right) {
+        (Constant::Bool(left), Constant::Bool(right)) => match binary_expression.operator {
+            OperatorKind::Eq => Some(Constant::Bool(left == right)),
+            OperatorKind::Ne => Some(Constant::Bool(left != right)),
+            _ => None,
+        },
+        (Constant::Int(left), Constant::Int(right)) => fold_integer(
+            &binary_expression.operator,
+            left,
+            right,
+            number_type(ctx, binary_expression.id),
+        ),
+        _ => None,
+    }
+}
+
+/// Evaluates the literal initializing a `const` definition as a value of its declared type.
+pub(crate) fn constant_value(constant_definition: &ConstantDefinition) -> Option<Constant> {
+    let type_info = TypeInfo::new(&constant_definition.ty);
+    match (&constant_definition.value, &type_info.kind) {
+        (Literal::Bool(bool_literal), TypeInfoKind::Bool) => {
+            Some(Constant::Bool(bool_literal.value))
+        }
+        (Literal::Number(number_literal), TypeInfoKind::Number(number_type)) => number_literal
+            .value
+            .parse()
+            .ok()
+            .map(|value| Constant::Int(wrap(value, *number_type))),
+        _ => None,
+    }
+}
+
+/// Applies an integer operator to two operands of type `number_type`.
+///
+/// Returns `None` for operators that do not apply to integers and for operations that
+/// trap at runtime.
+fn fold_integer(
+    operator: &OperatorKind,
+    left: i128,
+    right: i128,
+    number_type: NumberType,
+) -> Option<Constant> {
+    let bits = i128::from(bit_width(number_type));
+    let value = match operator {
+        OperatorKind::Eq => return Some(Constant::Bool(left == right)),
+        OperatorKind::Ne => return Some(Constant::Bool(left != right)),
+        OperatorKind::Lt => return Some(Constant::Bool(left < right)),
+        OperatorKind::Le => return Some(Constant::Bool(left <= right)),
+        OperatorKind::Gt => return Some(Constant::Bool(left > right)),
+        OperatorKind::Ge => return Some(Constant::Bool(left >= right)),
+        OperatorKind::Add => left.wrapping_add(right),
+        OperatorKind::Sub => left.wrapping_sub(right),
+        OperatorKind::Mul => left.wrapping_mul(right),
+        OperatorKind::Div => {
+            let quotient = left.checked_div(right)?;
+            // The quotient of the minimum value by -1 does not fit, and traps.
+            if wrap(quotient, number_type) != quotient {
+                return None;
+            }
+            quotient
+        }
+        OperatorKind::Mod => left.checked_rem(right)?,
+        OperatorKind::Pow => pow(left, right, number_type),
+        OperatorKind::BitAnd => left & right,
+        OperatorKind::BitOr => left | right,
+        OperatorKind::BitXor => left ^ right,
+        // Operands are sign-extended for signed types and zero-extended otherwise, so the
+        // arithmetic shift of i128 matches the signed and the unsigned shift.
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        OperatorKind::Shl => left << (right.rem_euclid(bits) as u32),
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        OperatorKind::Shr => left >> (right.rem_euclid(bits) as u32),
+        OperatorKind::And | OperatorKind::Or | OperatorKind::BitNot => return None,
+    };
+    Some(Constant::Int(wrap(value, number_type)))
+}
+
+/// Raises `base` to the power of `exponent`, read as an unsigned number of `number_type`,
+/// by square and multiply. Every step is reduced to the width of the type, so the low bits
+/// of the wrapping 128-bit products are exact.
+fn pow(base: i128, exponent: i128, number_type: NumberType) -> i128 {
+    let mut exponent = exponent & ((1 << bit_width(number_type)) - 1);
+    let mut factor = base;
+    let mut result: i128 = 1;
+    while exponent != 0 {
+        if exponent & 1 != 0 {
+            result = wrap(result.wrapping_mul(factor), number_type);
+        }
+        factor = wrap(factor.wrapping_mul(factor), number_type);
+        exponent >>= 1;
+    }
+    result
+}
+
+/// Returns the number type of the node `id`, or `i32` for untyped literals.
+pub(crate) fn number_type(ctx: &TypedContext, id: u32) -> NumberType {
+    match ctx.get_node_typeinfo(id).map(|type_info| type_info.kind) {
+        Some(TypeInfoKind::Number(number_type)) => number_type,
+        _ => NumberType::I32,
+    }
+}
+
+pub(crate) fn bit_width(number_type: NumberType) -> u32 {
+    match number_type {
+        NumberType::I8 | NumberType::U8 => 8,
+        NumberType::I16 | NumberType::U16 => 16,
+        NumberType::I32 | NumberType::U32 => 32,
+        NumberType::I64 | NumberType::U64 => 64,
+    }
+}
+
+/// Reduces `value` modulo 2^n, where n is the width of `number_type`, into the range of
+/// the type.
+pub(crate) fn wrap(value: i128, number_type: NumberType) -> i128 {
+    let bits = bit_width(number_type);
+    let truncated = value & ((1 << bits) - 1);
+    if number_type.is_signed() && truncated >> (bits - 1) != 0 {
+        truncated - (1 << bits)
+    } else {
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(operator: &OperatorKind, left: i128, right: i128, number_type: NumberType) -> i128 {
+        match fold_integer(operator, left, right, number_type) {
+            Some(Constant::Int(value)) => value,
+            result => panic!("{operator:?} did not fold to an integer: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn values_wrap_to_the_range_of_their_type() {
+        assert_eq!(wrap(400, NumberType::U8), 144);
+        assert_eq!(wrap(200, NumberType::I8), -56);
+        assert_eq!(wrap(-1, NumberType::U32), i128::from(u32::MAX));
+        assert_eq!(wrap(i128::from(u64::MAX), NumberType::I64), -1);
+        assert_eq!(
+            wrap(i128::from(i64::MIN), NumberType::I64),
+            i128::from(i64::MIN)
+        );
+    }
+
+    #[test]
+    fn arithmetic_wraps_like_webassembly() {
+        let max = i128::from(i32::MAX);
+        assert_eq!(
+            int(&OperatorKind::Add, max, 1, NumberType::I32),
+            i128::from(i32::MIN)
+        );
+        assert_eq!(
+            int(&OperatorKind::Sub, 0, 1, NumberType::U64),
+            i128::from(u64::MAX)
+        );
+        assert_eq!(
+            int(
+                &OperatorKind::Mul,
+                i128::from(u64::MAX),
+                i128::from(u64::MAX),
+                NumberType::U64
+            ),
+            1
+        );
+        assert_eq!(int(&OperatorKind::Pow, 2, 40, NumberType::I32), 0);
+        assert_eq!(int(&OperatorKind::Pow, -3, 3, NumberType::I64), -27);
+        assert_eq!(int(&OperatorKind::Pow, 7, 0, NumberType::U8), 1);
+        assert_eq!(int(&OperatorKind::Div, -7, 2, NumberType::I32), -3);
+        assert_eq!(int(&OperatorKind::Mod, -7, 2, NumberType::I32), -1);
+    }
+
+    #[test]
+    fn shifts_take_the_amount_modulo_the_width() {
+        assert_eq!(int(&OperatorKind::Shl, 1, 33, NumberType::I32), 2);
+        assert_eq!(int(&OperatorKind::Shr, -16, 2, NumberType::I32), -4);
+        assert_eq!(
+            int(
+                &OperatorKind::Shr,
+                i128::from(u32::MAX),
+                28,
+                NumberType::U32
+            ),
+            15
+        );
+        assert_eq!(int(&OperatorKind::Shl, 1, 7, NumberType::I8), -128);
+    }
+
+    #[test]
+    fn pow_takes_the_exponent_as_unsigned() {
+        assert_eq!(int(&OperatorKind::Pow, -1, -1, NumberType::I64), -1);
+        assert_eq!(int(&OperatorKind::Pow, 2, -1, NumberType::I32), 0);
+        assert_eq!(int(&OperatorKind::Pow, 3, -1, NumberType::U8), 171);
+    }
+
+    #[test]
+    fn trapping_operations_are_not_folded() {
+        assert_eq!(
+            fold_integer(&OperatorKind::Div, 1, 0, NumberType::I32),
+            None
+        );
+        assert_eq!(fold_integer(&OperatorKind::Mod, 1, 0, NumberType::U8), None);
+        let min = i128::from(i64::MIN);
+        assert_eq!(
+            fold_integer(&OperatorKind::Div, min, -1, NumberType::I64),
+            None
+        );
+        assert_eq!(int(&OperatorKind::Mod, min, -1, NumberType::I64), 0);
+    }
+
+    #[test]
+    fn comparisons_respect_signedness() {
+        assert_eq!(
+            fold_integer(&OperatorKind::Lt, -1, 0, NumberType::I32),
+            Some(Constant::Bool(true))
+        );
+        let max = i128::from(u32::MAX);
+        assert_eq!(
+            fold_integer(&OperatorKind::Lt, max, 0, NumberType::U32),
+            Some(Constant::Bool(false))
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! DWARF debug information for generated modules.
+//!
+//! When [`CodegenOptions::debug_info`] is set, the [`Compiler`](crate::compiler::Compiler)
+//! attaches a compile unit, one subprogram per function and a source location per lowered
+//! statement to the LLVM module. The WebAssembly backend turns these into `.debug_info`,
+//! `.debug_line` and related custom sections, so that every instruction offset can be mapped
+//! back to a line and column of the `.inf` source.
+//!
+//! Local variables are not described yet: only line tables and function scopes are emitted.
+
+use crate::options::CodegenOptions;
+use inference_ast::nodes::Location;
+use inkwell::{
+    builder::Builder,
+    context::Context,
+    debug_info::{
+        AsDIScope, DICompileUnit, DIFile, DIFlags, DIFlagsConstants, DIScope, DWARFEmissionKind,
+        DWARFSourceLanguage, DebugInfoBuilder, debug_metadata_version,
+    },
+    module::{FlagBehavior, Module},
+    values::FunctionValue,
+};
+
+/// DWARF version emitted into the module. Version 4 is the one understood by the
+/// WebAssembly tooling (`wasm-objdump`, browser devtools, `lldb`).
+const DWARF_VERSION: u64 = 4;
+
+/// Producer string recorded in the compile unit.
+const PRODUCER: &str = concat!("infc ", env!("CARGO_PKG_VERSION"));
+
+/// Debug information builder for a single LLVM module.
+pub(crate) struct DebugInfo<'ctx> {
+    builder: DebugInfoBuilder<'ctx>,
+    compile_unit: DICompileUnit<'ctx>,
+    file: DIFile<'ctx>,
+}
+
+impl<'ctx> DebugInfo<'ctx> {
+    /// Creates the compile unit for `module` and adds the module flags LLVM requires
+    /// before it emits DWARF.
+    ///
+    /// The source file is taken from [`CodegenOptions::source_path`], falling back to
+    /// `<module_name>.inf` when no path is known.
+    pub(crate) fn new(
+        context: &'ctx Context,
+        module: &Module<'ctx>,
+        options: &CodegenOptions,
+    ) -> Self {
+        let i32_type = context.i32_type();
+        module.add_basic_value_flag(
+            "Debug Info Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(u64::from(debug_metadata_version()), false),
+        );
+        module.add_basic_value_flag(
+            "Dwarf Version",
+            FlagBehavior::Warning,
+            i32_type.const_int(DWARF_VERSION, false),
+        );
+
+        let path = options.source_file();
+        let filename = path.file_name().map_or_else(
+            || format!("{}.inf", options.module_name),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let directory = path
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let is_optimized = options.opt_level > 0;
+
+        let (builder, compile_unit) = module.create_debug_info_builder(
+            true,
+            DWARFSourceLanguage::C,
+            &filename,
+            &directory,
+            PRODUCER,
+            is_optimized,
+            "",
+            0,
+            "",
+            DWARFEmissionKind::Full,
+            0,
+            false,
+            false,
+            "",
+            "",
+        );
+        let file = compile_unit.get_file();
+
+        Self {
+            builder,
+            compile_unit,
+            file,
+        }
+    }
+
+    /// Creates the subprogram describing `function` and attaches it to the function.
+    ///
+    /// Returns the scope that statement locations inside the function must use.
+    pub(crate) fn add_function(
+        &self,
+        function: FunctionValue<'ctx>,
+        name: &str,
+        location: &Location,
+        is_local: bool,
+    ) -> DIScope<'ctx> {
+        let subroutine_type =
+            self.builder
+                .create_subroutine_type(self.file, None, &[], DIFlags::ZERO);
+        let subprogram = self.builder.create_function(
+            self.compile_unit.as_debug_info_scope(),
+            name,
+            None,
+            self.file,
+            location.start_line,
+            subroutine_type,
+            is_local,
+            true,
+            location.start_line,
+            DIFlags::ZERO,
+            false,
+        );
+        function.set_subprogram(subprogram);
+        subprogram.as_debug_info_scope()
+    }
+
+    /// Sets the source location attached to the instructions `builder` emits next.
+    pub(crate) fn set_location(
+        &self,
+        context: &'ctx Context,
+        builder: &Builder<'ctx>,
+        scope: DIScope<'ctx>,
+        location: &Location,
+    ) {
+        let debug_location = self.builder.create_debug_location(
+            context,
+            location.start_line,
+            location.start_column,
+            scope,
+            None,
+        );
+        builder.set_current_debug_location(debug_location);
+    }
+
+    /// Resolves all pending debug metadata. Must be called once, after the last function
+    /// has been lowered and before the module is emitted.
+    pub(crate) fn finalize(&self) {
+        self.builder.finalize();
+    }
+}
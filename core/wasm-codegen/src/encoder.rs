@@ -0,0 +1,1081 @@
+//! Code generation with `wasm-encoder`, without LLVM.
+//!
+//! [`Backend::Encoder`](crate::Backend::Encoder) lowers the typed AST directly to
+//! WebAssembly instructions and assembles the module itself, so no LLVM, no object file
+//! and no linker are involved. It is meant for fast debug builds and for platforms where
+//! the forked LLVM is not available, and supports a subset of the language:
+//!
+//! - functions and `external fn` imports whose parameters and return values are integers,
+//!   `bool` or enums
+//! - variables, assignments to variables, `const` definitions, `return`, `if`/`else`,
+//!   `loop` with or without a condition and `break`
+//! - every integer, comparison and logical operator, with `&&` and `||` short-circuiting
+//! - calls, enum variants, `@` values and the `forall`, `exists`, `assume` and `unique`
+//!   blocks
+//! - `assert` with [`AssertStrategy::Trap`] and [`AssertStrategy::ResultCode`]
+//!
+//! Structs, arrays and strings need linear memory, which the module does not have, and are
+//! reported as [`CodegenErrorKind::Unsupported`] like any other construct outside the
+//! subset. So are asserts with [`AssertStrategy::Abort`], which passes the file name in
+//! linear memory, and source maps, which are derived from LLVM's line tables.
+//!
+//! ## Values
+//!
+//! `i64` and `u64` values are WebAssembly `i64`s; every other integer, `bool` and enum tags
+//! are `i32`s. Integers narrower than 32 bits are kept sign-extended for signed types and
+//! zero-extended for unsigned types, and are brought back into that form after every
+//! operation that can leave it. Operators follow the semantics of the LLVM backend, see
+//! the `compiler::arithmetic` module, and expressions are folded with [`const_eval`]
+//! before they are lowered, so both backends compute the same values.
+//!
+//! ## Module Layout
+//!
+//! Imports come first in the function index space, followed by the functions of the
+//! source file in source order. Functions are exported as
+//! [`CodegenOptions::exports`] selects, and a `name` section records the module name and
+//! the name of every function. The optimization level, debug information, linker, codegen
+//! units, object cache and memory options have no effect.
+
+use std::{collections::HashMap, rc::Rc};
+
+use anyhow::bail;
+use inference_ast::nodes::{
+    ArgumentType, AssertStatement, BinaryExpression, BlockType, Definition, Expression,
+    FunctionCallExpression, FunctionDefinition, Identifier, IfStatement, Literal, Location,
+    LoopStatement, OperatorKind, SimpleTypeKind, Statement, Type, TypeMemberAccessExpression,
+    UnaryOperatorKind,
+};
+use inference_type_checker::{
+    type_info::{NumberType, TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+use wasm_encoder::{
+    BlockType as WasmBlockType, CodeSection, Encode, EntityType, ExportKind, ExportSection,
+    Function, FunctionSection, ImportSection, Instruction, Module, NameMap, NameSection,
+    TypeSection, ValType,
+};
+
+use crate::{
+    const_eval::{self, Constant, Constants},
+    errors::{CodegenError, CodegenErrorKind, CodegenErrors},
+    interface,
+    options::{AssertStrategy, CodegenOptions},
+};
+
+/// Opcodes of the non-deterministic block instructions in the `0xfc` prefix space.
+const FORALL_OPCODE: u8 = 0x3a;
+const EXISTS_OPCODE: u8 = 0x3b;
+const ASSUME_OPCODE: u8 = 0x3c;
+const UNIQUE_OPCODE: u8 = 0x3d;
+
+/// Opcodes of the non-deterministic value instructions in the `0xfc` prefix space.
+const UZUMAKI_I32_OPCODE: u8 = 0x31;
+const UZUMAKI_I64_OPCODE: u8 = 0x32;
+
+/// Compiles the functions of `typed_context` into a module.
+///
+/// # Errors
+///
+/// Returns [`CodegenErrors`] if a function uses a construct outside the supported subset,
+/// or an error if [`CodegenOptions::source_map`] is set.
+pub(crate) fn compile(
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> anyhow::Result<Vec<u8>> {
+    if options.source_map {
+        bail!("source maps are not supported by the encoder backend");
+    }
+    let mut functions = Vec::new();
+    let mut errors = Vec::new();
+    let mut constants = Constants::new();
+    for source_file in &typed_context.source_files() {
+        for definition in &source_file.definitions {
+            if let Definition::Constant(constant_definition) = definition
+                && let Some(constant) = const_eval::constant_value(constant_definition)
+            {
+                constants.insert(constant_definition.name(), constant);
+            }
+        }
+        for external in source_file.external_function_definitions() {
+            let name = external.name();
+            let signature = signature(
+                &name,
+                external.arguments.as_deref(),
+                external.returns.as_ref(),
+                (external.id, external.location),
+                typed_context,
+            );
+            match signature {
+                Ok(signature) => functions.push(Declared {
+                    name,
+                    signature,
+                    definition: None,
+                }),
+                Err(error) => errors.push(error),
+            }
+        }
+        for definition in source_file.function_definitions() {
+            let name = definition.name();
+            let signature = signature(
+                &name,
+                definition.arguments.as_deref(),
+                definition.returns.as_ref(),
+                (definition.id, definition.location),
+                typed_context,
+            );
+            match signature {
+                Ok(signature) => functions.push(Declared {
+                    name,
+                    signature,
+                    definition: Some(definition),
+                }),
+                Err(error) => errors.push(error),
+            }
+        }
+    }
+
+    let indices = functions
+        .iter()
+        .enumerate()
+        .map(|(index, function)| (function.name.clone(), u32::try_from(index).unwrap()))
+        .collect::<HashMap<_, _>>();
+    let mut bodies = Vec::new();
+    for function in &functions {
+        let Some(definition) = &function.definition else {
+            continue;
+        };
+        let mut encoder = FunctionEncoder::new(
+            function,
+            &functions,
+            &indices,
+            constants.clone(),
+            typed_context,
+            options,
+        );
+        match encoder.lower_function(definition) {
+            Ok(()) => bodies.push(encoder.finish()),
+            Err(error) => errors.push(error),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(CodegenErrors::new(errors).into());
+    }
+    Ok(assemble(&functions, &bodies, options))
+}
+
+/// A function of the module: an import when it has no definition.
+struct Declared {
+    name: String,
+    signature: Signature,
+    definition: Option<Rc<FunctionDefinition>>,
+}
+
+/// Parameter and result types of a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Signature {
+    params: Vec<(Option<String>, Scalar)>,
+    result: Option<Scalar>,
+}
+
+impl Signature {
+    fn val_types(&self) -> (Vec<ValType>, Vec<ValType>) {
+        (
+            self.params
+                .iter()
+                .map(|(_, scalar)| scalar.val_type())
+                .collect(),
+            self.result.iter().map(|scalar| scalar.val_type()).collect(),
+        )
+    }
+}
+
+/// Source-level type of a value held in a WebAssembly local or on the operand stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    Bool,
+    Int(NumberType),
+}
+
+impl Scalar {
+    /// Enum tags are unsigned 32-bit integers.
+    const ENUM: Scalar = Scalar::Int(NumberType::U32);
+
+    fn val_type(self) -> ValType {
+        if self.is_wide() {
+            ValType::I64
+        } else {
+            ValType::I32
+        }
+    }
+
+    fn is_wide(self) -> bool {
+        matches!(self, Scalar::Int(NumberType::I64 | NumberType::U64))
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(self, Scalar::Int(number_type) if number_type.is_signed())
+    }
+
+    /// Returns the width of integer types narrower than their WebAssembly type.
+    fn narrow_bits(self) -> Option<u32> {
+        match self {
+            Scalar::Int(number_type) => {
+                let bits = const_eval::bit_width(number_type);
+                (bits < 32).then_some(bits)
+            }
+            Scalar::Bool => None,
+        }
+    }
+}
+
+/// Returns the scalar type of values of `type_info`, or `None` if the encoder cannot hold
+/// them in a local.
+fn scalar(type_info: &TypeInfo, ctx: &TypedContext) -> Option<Scalar> {
+    match &type_info.kind {
+        TypeInfoKind::Bool => Some(Scalar::Bool),
+        TypeInfoKind::Number(number_type) => Some(Scalar::Int(*number_type)),
+        TypeInfoKind::Enum(_) => Some(Scalar::ENUM),
+        TypeInfoKind::Custom(name) if ctx.enum_variants(name).is_some() => Some(Scalar::ENUM),
+        _ => None,
+    }
+}
+
+/// Returns the signature of a function or `external fn`.
+///
+/// # Errors
+///
+/// Returns an error for parameter and return types without a scalar representation and
+/// for methods.
+fn signature(
+    function: &str,
+    arguments: Option<&[ArgumentType]>,
+    returns: Option<&Type>,
+    (node_id, location): (u32, Location),
+    ctx: &TypedContext,
+) -> Result<Signature, CodegenError> {
+    let error = |construct: String, node_id: u32, location: Location| CodegenError {
+        kind: CodegenErrorKind::Unsupported(construct),
+        node_id,
+        location,
+        function: function.to_string(),
+        block: None,
+    };
+    let mut params = Vec::new();
+    for argument in arguments.unwrap_or_default() {
+        let (name, ty) = match argument {
+            ArgumentType::Argument(argument) => (Some(argument.name.name.clone()), &argument.ty),
+            ArgumentType::IgnoreArgument(ignored) => (None, &ignored.ty),
+            ArgumentType::Type(ty) => (None, ty),
+            ArgumentType::SelfReference(_) => {
+                return Err(error(
+                    "methods".to_string(),
+                    argument.id(),
+                    argument.location(),
+                ));
+            }
+        };
+        let type_info = TypeInfo::new(ty);
+        let scalar = scalar(&type_info, ctx).ok_or_else(|| {
+            error(
+                format!("type `{type_info}`"),
+                argument.id(),
+                argument.location(),
+            )
+        })?;
+        params.push((name, scalar));
+    }
+    let result = match returns {
+        None | Some(Type::Simple(SimpleTypeKind::Unit)) => None,
+        Some(ty) => {
+            let type_info = TypeInfo::new(ty);
+            Some(
+                scalar(&type_info, ctx)
+                    .ok_or_else(|| error(format!("type `{type_info}`"), node_id, location))?,
+            )
+        }
+    };
+    Ok(Signature { params, result })
+}
+
+/// Assembles the module from the declared functions and the encoded bodies of the defined
+/// ones, in the same order.
+fn assemble(functions: &[Declared], bodies: &[Function], options: &CodegenOptions) -> Vec<u8> {
+    let mut types = TypeSection::new();
+    let mut type_indices = HashMap::new();
+    let mut type_index = |signature: &Signature| {
+        let (params, results) = signature.val_types();
+        *type_indices
+            .entry((params.clone(), results.clone()))
+            .or_insert_with(|| {
+                types.ty().function(params, results);
+                types.len() - 1
+            })
+    };
+    let mut imports = ImportSection::new();
+    let mut declarations = FunctionSection::new();
+    let mut exports = ExportSection::new();
+    let mut names = NameMap::new();
+    for (index, function) in (0u32..).zip(functions) {
+        let type_index = type_index(&function.signature);
+        names.append(index, &function.name);
+        match &function.definition {
+            None => {
+                let import_name = options.import_name(&function.name);
+                imports.import(
+                    &import_name.module,
+                    &import_name.name,
+                    EntityType::Function(type_index),
+                );
+            }
+            Some(definition) => {
+                declarations.function(type_index);
+                if let Some(export_name) = interface::export_name(definition, options) {
+                    exports.export(&export_name, ExportKind::Func, index);
+                }
+            }
+        }
+    }
+    let mut code = CodeSection::new();
+    for body in bodies {
+        code.function(body);
+    }
+    let mut name_section = NameSection::new();
+    name_section.module(&options.module_name);
+    name_section.functions(&names);
+
+    let mut module = Module::new();
+    if !types.is_empty() {
+        module.section(&types);
+    }
+    if !imports.is_empty() {
+        module.section(&imports);
+    }
+    if !declarations.is_empty() {
+        module.section(&declarations);
+    }
+    if !exports.is_empty() {
+        module.section(&exports);
+    }
+    if !code.is_empty() {
+        module.section(&code);
+    }
+    module.section(&name_section);
+    module.finish()
+}
+
+/// Lowers the body of one function.
+struct FunctionEncoder<'a> {
+    signature: &'a Signature,
+    functions: &'a [Declared],
+    indices: &'a HashMap<String, u32>,
+    ctx: &'a TypedContext,
+    options: &'a CodegenOptions,
+    /// Name of the function, reported with errors.
+    name: &'a str,
+    /// Constants visible at the current statement.
+    constants: Constants,
+    /// Local index and type of every variable and parameter by name. A definition replaces
+    /// a previous variable of the same name for the rest of the function.
+    variables: HashMap<String, (u32, Scalar)>,
+    /// Types of the locals declared after the parameters.
+    locals: Vec<ValType>,
+    /// Encoded instructions of the body.
+    body: Vec<u8>,
+    /// Number of enclosing control frames of the current instruction.
+    depth: u32,
+    /// Depth of the `block` around each enclosing `loop`, which `break` branches to.
+    loops: Vec<u32>,
+    /// ID and location of the statement being lowered, where errors without a more
+    /// specific node are reported.
+    node: (u32, Location),
+}
+
+impl<'a> FunctionEncoder<'a> {
+    fn new(
+        function: &'a Declared,
+        functions: &'a [Declared],
+        indices: &'a HashMap<String, u32>,
+        constants: Constants,
+        ctx: &'a TypedContext,
+        options: &'a CodegenOptions,
+    ) -> Self {
+        Self {
+            signature: &function.signature,
+            functions,
+            indices,
+            ctx,
+            options,
+            name: &function.name,
+            constants,
+            variables: HashMap::new(),
+            locals: Vec::new(),
+            body: Vec::new(),
+            depth: 0,
+            loops: Vec::new(),
+            node: (0, Location::default()),
+        }
+    }
+
+    /// Lowers the parameters and the body of `definition`.
+    fn lower_function(&mut self, definition: &FunctionDefinition) -> Result<(), CodegenError> {
+        self.node = (definition.id, definition.location);
+        for (index, (name, scalar)) in (0u32..).zip(&self.signature.params) {
+            let Some(name) = name else {
+                continue;
+            };
+            self.constants.remove(name);
+            self.variables.insert(name.clone(), (index, *scalar));
+            // Hosts may pass any bits above the width of a narrow parameter.
+            if scalar.narrow_bits().is_some() {
+                self.emit(&Instruction::LocalGet(index));
+                self.normalize(*scalar);
+                self.emit(&Instruction::LocalSet(index));
+            }
+        }
+        self.lower_block(&definition.body)?;
+        let returns = matches!(
+            definition.body.statements().last(),
+            Some(Statement::Return(_))
+        );
+        if self.signature.result.is_some() && !returns {
+            // Every path returns, e.g. through the branches of an `if`.
+            self.emit(&Instruction::Unreachable);
+        }
+        Ok(())
+    }
+
+    /// Returns the encoded function.
+    fn finish(self) -> Function {
+        let mut function = Function::new_with_locals_types(self.locals);
+        function.raw(self.body);
+        function.instruction(&Instruction::End);
+        function
+    }
+
+    fn emit(&mut self, instruction: &Instruction) {
+        instruction.encode(&mut self.body);
+    }
+
+    /// Emits `narrow` for 32-bit values and `wide` for 64-bit values of type `scalar`.
+    fn emit_int(&mut self, scalar: Scalar, narrow: &Instruction, wide: &Instruction) {
+        self.emit(if scalar.is_wide() { wide } else { narrow });
+    }
+
+    /// Pushes the integer `value` as a constant of the WebAssembly type of `scalar`.
+    fn emit_const(&mut self, scalar: Scalar, value: i128) {
+        // Truncating keeps the two's complement representation of every value in range.
+        #[allow(clippy::cast_possible_truncation)]
+        if scalar.is_wide() {
+            self.emit(&Instruction::I64Const(value as i64));
+        } else {
+            self.emit(&Instruction::I32Const(value as i32));
+        }
+    }
+
+    fn add_local(&mut self, val_type: ValType) -> u32 {
+        self.locals.push(val_type);
+        u32::try_from(self.signature.params.len() + self.locals.len() - 1).unwrap()
+    }
+
+    /// Brings a narrow integer computed in 32 bits back into the range of its type.
+    fn normalize(&mut self, scalar: Scalar) {
+        let Some(bits) = scalar.narrow_bits() else {
+            return;
+        };
+        if scalar.is_signed() {
+            #[allow(clippy::cast_possible_wrap)]
+            let shift = (32 - bits) as i32;
+            self.emit(&Instruction::I32Const(shift));
+            self.emit(&Instruction::I32Shl);
+            self.emit(&Instruction::I32Const(shift));
+            self.emit(&Instruction::I32ShrS);
+        } else {
+            self.emit(&Instruction::I32Const((1 << bits) - 1));
+            self.emit(&Instruction::I32And);
+        }
+    }
+
+    /// Converts the value of type `from` on top of the stack to type `to`, sign-extending
+    /// integers like the LLVM backend.
+    fn coerce(&mut self, from: Scalar, to: Scalar) {
+        if from == to {
+            return;
+        }
+        match (from.is_wide(), to.is_wide()) {
+            (false, true) if from == Scalar::Bool => self.emit(&Instruction::I64ExtendI32U),
+            (false, true) => self.emit(&Instruction::I64ExtendI32S),
+            (true, false) => {
+                self.emit(&Instruction::I32WrapI64);
+                self.normalize(to);
+            }
+            _ => self.normalize(to),
+        }
+    }
+
+    fn error_at(&self, kind: CodegenErrorKind, node_id: u32, location: Location) -> CodegenError {
+        CodegenError {
+            kind,
+            node_id,
+            location,
+            function: self.name.to_string(),
+            block: None,
+        }
+    }
+
+    /// Creates an error for a construct without support at the current statement.
+    fn unsupported(&self, construct: impl Into<String>) -> CodegenError {
+        self.error_at(
+            CodegenErrorKind::Unsupported(construct.into()),
+            self.node.0,
+            self.node.1,
+        )
+    }
+
+    fn lower_block(&mut self, block: &BlockType) -> Result<(), CodegenError> {
+        let opcode = match block {
+            BlockType::Block(_) => None,
+            BlockType::Forall(_) => Some(FORALL_OPCODE),
+            BlockType::Exists(_) => Some(EXISTS_OPCODE),
+            BlockType::Assume(_) => Some(ASSUME_OPCODE),
+            BlockType::Unique(_) => Some(UNIQUE_OPCODE),
+        };
+        if let Some(opcode) = opcode {
+            // Non-deterministic blocks take the empty block type, like `block`.
+            self.body.extend_from_slice(&[0xfc, opcode, 0x40]);
+            self.depth += 1;
+        }
+        for statement in block.statements() {
+            self.lower_statement(&statement)?;
+        }
+        if opcode.is_some() {
+            self.emit(&Instruction::End);
+            self.depth -= 1;
+        }
+        Ok(())
+    }
+
+    fn lower_statement(&mut self, statement: &Statement) -> Result<(), CodegenError> {
+        self.node = (statement.id(), statement.location());
+        match statement {
+            Statement::Block(block) => self.lower_block(block)?,
+            Statement::Expression(Expression::FunctionCall(call)) => {
+                if self.lower_call(call)?.is_some() {
+                    self.emit(&Instruction::Drop);
+                }
+            }
+            Statement::Expression(expression) => {
+                self.lower_expression(expression)?;
+                self.emit(&Instruction::Drop);
+            }
+            Statement::Assign(assign_statement) => {
+                let left = assign_statement.left.borrow();
+                let Expression::Identifier(identifier) = &*left else {
+                    return Err(self.error_at(
+                        CodegenErrorKind::Unsupported(
+                            "assignment to anything but variables".to_string(),
+                        ),
+                        left.id(),
+                        left.location(),
+                    ));
+                };
+                let (local, scalar) = self.variable(identifier)?;
+                let value = self.lower_expression(&assign_statement.right.borrow())?;
+                self.coerce(value, scalar);
+                self.emit(&Instruction::LocalSet(local));
+            }
+            Statement::Return(return_statement) => {
+                let value = self.lower_expression(&return_statement.expression.borrow())?;
+                if let Some(result) = self.signature.result {
+                    self.coerce(value, result);
+                }
+                self.emit(&Instruction::Return);
+            }
+            Statement::Loop(loop_statement) => self.lower_loop(loop_statement)?,
+            Statement::Break(_) => {
+                let Some(target) = self.loops.last().copied() else {
+                    return Err(self.unsupported("`break` outside of a loop"));
+                };
+                self.emit(&Instruction::Br(self.depth - target));
+            }
+            Statement::If(if_statement) => self.lower_if(if_statement)?,
+            Statement::VariableDefinition(variable_definition) => {
+                let type_info = TypeInfo::new(&variable_definition.ty);
+                let scalar = scalar(&type_info, self.ctx)
+                    .ok_or_else(|| self.unsupported(format!("variables of type `{type_info}`")))?;
+                match &variable_definition.value {
+                    Some(value) => {
+                        let value = self.lower_expression(&value.borrow())?;
+                        self.coerce(value, scalar);
+                    }
+                    // The definition may run again in a loop, so the local is reset.
+                    None => self.emit_const(scalar, 0),
+                }
+                let name = variable_definition.name();
+                let local = self.add_local(scalar.val_type());
+                self.emit(&Instruction::LocalSet(local));
+                self.constants.remove(&name);
+                self.variables.insert(name, (local, scalar));
+            }
+            Statement::TypeDefinition(_) => {
+                return Err(self.unsupported("type definitions inside functions"));
+            }
+            Statement::Assert(assert_statement) => self.lower_assert(assert_statement)?,
+            Statement::ConstantDefinition(constant_definition) => {
+                let Some(constant) = const_eval::constant_value(constant_definition) else {
+                    let type_info = TypeInfo::new(&constant_definition.ty);
+                    return Err(self.unsupported(format!("constants of type `{type_info}`")));
+                };
+                self.variables.remove(&constant_definition.name());
+                self.constants.insert(constant_definition.name(), constant);
+            }
+        }
+        Ok(())
+    }
+
+    /// Lowers a loop to a `loop` inside a `block`, which `break` and a false condition
+    /// branch to.
+    fn lower_loop(&mut self, loop_statement: &LoopStatement) -> Result<(), CodegenError> {
+        self.emit(&Instruction::Block(WasmBlockType::Empty));
+        self.depth += 1;
+        self.loops.push(self.depth);
+        self.emit(&Instruction::Loop(WasmBlockType::Empty));
+        self.depth += 1;
+        if let Some(condition) = &*loop_statement.condition.borrow() {
+            self.lower_condition(condition)?;
+            self.emit(&Instruction::I32Eqz);
+            self.emit(&Instruction::BrIf(1));
+        }
+        self.lower_block(&loop_statement.body)?;
+        self.emit(&Instruction::Br(0));
+        self.emit(&Instruction::End);
+        self.emit(&Instruction::End);
+        self.loops.pop();
+        self.depth -= 2;
+        Ok(())
+    }
+
+    /// Lowers an `if`. Like the LLVM backend, a constant condition only lowers the taken
+    /// branch.
+    fn lower_if(&mut self, if_statement: &IfStatement) -> Result<(), CodegenError> {
+        let condition = if_statement.condition.borrow();
+        if let Some(Constant::Bool(condition)) =
+            const_eval::fold(&condition, &self.constants, self.ctx)
+        {
+            let branch = if condition {
+                Some(&if_statement.if_arm)
+            } else {
+                if_statement.else_arm.as_ref()
+            };
+            return match branch {
+                Some(branch) => self.lower_block(branch),
+                None => Ok(()),
+            };
+        }
+        self.lower_condition(&condition)?;
+        self.emit(&Instruction::If(WasmBlockType::Empty));
+        self.depth += 1;
+        self.lower_block(&if_statement.if_arm)?;
+        if let Some(else_arm) = &if_statement.else_arm {
+            self.emit(&Instruction::Else);
+            self.lower_block(else_arm)?;
+        }
+        self.emit(&Instruction::End);
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Returns the local and type of the variable or parameter `identifier` names.
+    fn variable(&self, identifier: &Identifier) -> Result<(u32, Scalar), CodegenError> {
+        self.variables
+            .get(&identifier.name)
+            .copied()
+            .ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(format!(
+                        "variable `{}` is not defined",
+                        identifier.name
+                    )),
+                    identifier.id,
+                    identifier.location,
+                )
+            })
+    }
+
+    /// Lowers an `assert`: a failing condition traps or returns the result code of
+    /// [`AssertStrategy::ResultCode`]. A condition that folds to `true` emits no code.
+    fn lower_assert(&mut self, assert_statement: &AssertStatement) -> Result<(), CodegenError> {
+        let condition = assert_statement.expression.borrow();
+        if const_eval::fold(&condition, &self.constants, self.ctx) == Some(Constant::Bool(true)) {
+            return Ok(());
+        }
+        if let AssertStrategy::Abort(_) = self.options.assert_strategy {
+            return Err(self.unsupported("`assert` with the abort strategy"));
+        }
+        self.lower_condition(&condition)?;
+        self.emit(&Instruction::I32Eqz);
+        self.emit(&Instruction::If(WasmBlockType::Empty));
+        match (&self.options.assert_strategy, self.signature.result) {
+            (AssertStrategy::ResultCode(_), None) => self.emit(&Instruction::Return),
+            (AssertStrategy::ResultCode(code), Some(result)) => {
+                let code = match result {
+                    Scalar::Bool => i128::from(code & 1),
+                    Scalar::Int(number_type) => const_eval::wrap(i128::from(*code), number_type),
+                };
+                self.emit_const(result, code);
+                self.emit(&Instruction::Return);
+            }
+            _ => self.emit(&Instruction::Unreachable),
+        }
+        self.emit(&Instruction::End);
+        Ok(())
+    }
+
+    /// Lowers a condition to an `i32` that is non-zero when it holds.
+    fn lower_condition(&mut self, condition: &Expression) -> Result<(), CodegenError> {
+        let scalar = self.lower_expression(condition)?;
+        if scalar.is_wide() {
+            self.emit(&Instruction::I64Const(0));
+            self.emit(&Instruction::I64Ne);
+        }
+        Ok(())
+    }
+
+    /// Lowers an expression, leaving its value on the stack, and returns its type.
+    fn lower_expression(&mut self, expression: &Expression) -> Result<Scalar, CodegenError> {
+        let type_info = self.ctx.get_node_typeinfo(expression.id());
+        if let Some(constant) = const_eval::fold(expression, &self.constants, self.ctx) {
+            return Ok(match constant {
+                Constant::Bool(value) => {
+                    self.emit(&Instruction::I32Const(i32::from(value)));
+                    Scalar::Bool
+                }
+                Constant::Int(value) => {
+                    // Untyped literals are `i32`, like in the LLVM backend.
+                    let scalar = type_info
+                        .as_ref()
+                        .and_then(|type_info| scalar(type_info, self.ctx))
+                        .filter(|scalar| matches!(scalar, Scalar::Int(_)))
+                        .unwrap_or(Scalar::Int(NumberType::I32));
+                    self.emit_const(scalar, value);
+                    scalar
+                }
+            });
+        }
+        let unsupported = |construct: &str| {
+            Err(self.error_at(
+                CodegenErrorKind::Unsupported(construct.to_string()),
+                expression.id(),
+                expression.location(),
+            ))
+        };
+        match expression {
+            Expression::Binary(binary_expression) => self.lower_binary(binary_expression),
+            Expression::PrefixUnary(prefix_unary_expression) => {
+                let operand =
+                    self.lower_expression(&prefix_unary_expression.expression.borrow())?;
+                match prefix_unary_expression.operator {
+                    UnaryOperatorKind::Not => self.emit(&Instruction::I32Eqz),
+                    UnaryOperatorKind::Neg => {
+                        self.emit_const(operand, -1);
+                        self.emit_int(operand, &Instruction::I32Mul, &Instruction::I64Mul);
+                        self.normalize(operand);
+                    }
+                    UnaryOperatorKind::BitNot => {
+                        self.emit_const(operand, -1);
+                        self.emit_int(operand, &Instruction::I32Xor, &Instruction::I64Xor);
+                        self.normalize(operand);
+                    }
+                }
+                Ok(operand)
+            }
+            Expression::Parenthesized(parenthesized_expression) => {
+                self.lower_expression(&parenthesized_expression.expression.borrow())
+            }
+            Expression::Identifier(identifier) => {
+                let (local, scalar) = self.variable(identifier)?;
+                self.emit(&Instruction::LocalGet(local));
+                Ok(scalar)
+            }
+            Expression::FunctionCall(call) => self.lower_call(call)?.ok_or_else(|| {
+                self.error_at(
+                    CodegenErrorKind::MissingInformation(
+                        "function used as a value does not return a value".to_string(),
+                    ),
+                    call.id,
+                    call.location,
+                )
+            }),
+            Expression::TypeMemberAccess(access) => {
+                let tag = self.enum_tag(access)?;
+                self.emit_const(Scalar::ENUM, i128::from(tag));
+                Ok(Scalar::ENUM)
+            }
+            Expression::Uzumaki(_) => {
+                let (opcode, scalar) = match type_info.as_ref().map(|type_info| &type_info.kind) {
+                    Some(TypeInfoKind::Number(NumberType::I32)) => {
+                        (UZUMAKI_I32_OPCODE, Scalar::Int(NumberType::I32))
+                    }
+                    Some(TypeInfoKind::Number(NumberType::I64)) => {
+                        (UZUMAKI_I64_OPCODE, Scalar::Int(NumberType::I64))
+                    }
+                    _ => return unsupported("`@` values other than `i32` and `i64`"),
+                };
+                self.body.extend_from_slice(&[0xfc, opcode]);
+                Ok(scalar)
+            }
+            Expression::Literal(Literal::String(_)) => unsupported("string literals"),
+            Expression::Literal(Literal::Unit(_)) => unsupported("unit literals"),
+            Expression::Literal(Literal::Array(_)) | Expression::ArrayIndexAccess(_) => {
+                unsupported("arrays in the encoder backend")
+            }
+            Expression::Struct(_) | Expression::MemberAccess(_) => {
+                unsupported("structs in the encoder backend")
+            }
+            Expression::Type(_) => unsupported("types used as values"),
+            Expression::Literal(Literal::Bool(_) | Literal::Number(_)) => {
+                unreachable!("literals are folded")
+            }
+        }
+    }
+
+    /// Lowers a binary expression. Operands are brought to the type of the typed operand,
+    /// since untyped literals are `i32`.
+    fn lower_binary(
+        &mut self,
+        binary_expression: &BinaryExpression,
+    ) -> Result<Scalar, CodegenError> {
+        let left_expression = binary_expression.left.borrow();
+        let right_expression = binary_expression.right.borrow();
+        let operator = &binary_expression.operator;
+        if matches!(operator, OperatorKind::And | OperatorKind::Or) {
+            self.lower_condition(&left_expression)?;
+            self.emit(&Instruction::If(WasmBlockType::Result(ValType::I32)));
+            if *operator == OperatorKind::And {
+                self.lower_condition(&right_expression)?;
+                self.emit(&Instruction::Else);
+                self.emit(&Instruction::I32Const(0));
+            } else {
+                self.emit(&Instruction::I32Const(1));
+                self.emit(&Instruction::Else);
+                self.lower_condition(&right_expression)?;
+            }
+            self.emit(&Instruction::End);
+            return Ok(Scalar::Bool);
+        }
+        let operand_type = self
+            .ctx
+            .get_node_typeinfo(left_expression.id())
+            .or_else(|| self.ctx.get_node_typeinfo(right_expression.id()))
+            .and_then(|type_info| scalar(&type_info, self.ctx));
+        let left = self.lower_expression(&left_expression)?;
+        let operand = operand_type.unwrap_or(left);
+        self.coerce(left, operand);
+        let right = self.lower_expression(&right_expression)?;
+        self.coerce(right, operand);
+        if let Some(result) = self.lower_arithmetic(operator, operand) {
+            return Ok(result);
+        }
+        let signed = operand.is_signed();
+        let (narrow, wide) = match (operator, signed) {
+            (OperatorKind::Eq, _) => (Instruction::I32Eq, Instruction::I64Eq),
+            (OperatorKind::Ne, _) => (Instruction::I32Ne, Instruction::I64Ne),
+            (OperatorKind::Lt, true) => (Instruction::I32LtS, Instruction::I64LtS),
+            (OperatorKind::Lt, false) => (Instruction::I32LtU, Instruction::I64LtU),
+            (OperatorKind::Le, true) => (Instruction::I32LeS, Instruction::I64LeS),
+            (OperatorKind::Le, false) => (Instruction::I32LeU, Instruction::I64LeU),
+            (OperatorKind::Gt, true) => (Instruction::I32GtS, Instruction::I64GtS),
+            (OperatorKind::Gt, false) => (Instruction::I32GtU, Instruction::I64GtU),
+            (OperatorKind::Ge, true) => (Instruction::I32GeS, Instruction::I64GeS),
+            (OperatorKind::Ge, false) => (Instruction::I32GeU, Instruction::I64GeU),
+            (operator, _) => {
+                return Err(self.error_at(
+                    CodegenErrorKind::Unsupported(format!("operator `{operator:?}`")),
+                    binary_expression.id,
+                    binary_expression.location,
+                ));
+            }
+        };
+        self.emit_int(operand, &narrow, &wide);
+        Ok(Scalar::Bool)
+    }
+
+    /// Applies an arithmetic, bitwise or shift operator to the two operands of type
+    /// `scalar` on the stack, or returns `None` for other operators.
+    fn lower_arithmetic(&mut self, operator: &OperatorKind, scalar: Scalar) -> Option<Scalar> {
+        let signed = scalar.is_signed();
+        match operator {
+            OperatorKind::Add => self.emit_int(scalar, &Instruction::I32Add, &Instruction::I64Add),
+            OperatorKind::Sub => self.emit_int(scalar, &Instruction::I32Sub, &Instruction::I64Sub),
+            OperatorKind::Mul => self.emit_int(scalar, &Instruction::I32Mul, &Instruction::I64Mul),
+            OperatorKind::Div if signed => {
+                // Division by zero and the 32- and 64-bit overflow trap natively.
+                self.emit_int(scalar, &Instruction::I32DivS, &Instruction::I64DivS);
+                self.check_narrow_overflow(scalar);
+            }
+            OperatorKind::Div => {
+                self.emit_int(scalar, &Instruction::I32DivU, &Instruction::I64DivU);
+            }
+            // The signed remainder of the minimum value by -1 is 0, as in WebAssembly.
+            OperatorKind::Mod if signed => {
+                self.emit_int(scalar, &Instruction::I32RemS, &Instruction::I64RemS);
+            }
+            OperatorKind::Mod => {
+                self.emit_int(scalar, &Instruction::I32RemU, &Instruction::I64RemU);
+            }
+            OperatorKind::Pow => self.lower_pow(scalar),
+            OperatorKind::BitAnd => {
+                self.emit_int(scalar, &Instruction::I32And, &Instruction::I64And);
+            }
+            OperatorKind::BitOr => self.emit_int(scalar, &Instruction::I32Or, &Instruction::I64Or),
+            OperatorKind::BitXor => {
+                self.emit_int(scalar, &Instruction::I32Xor, &Instruction::I64Xor);
+            }
+            OperatorKind::Shl => {
+                self.mask_shift_amount(scalar);
+                self.emit_int(scalar, &Instruction::I32Shl, &Instruction::I64Shl);
+            }
+            OperatorKind::Shr if signed => {
+                self.mask_shift_amount(scalar);
+                self.emit_int(scalar, &Instruction::I32ShrS, &Instruction::I64ShrS);
+            }
+            OperatorKind::Shr => {
+                self.mask_shift_amount(scalar);
+                self.emit_int(scalar, &Instruction::I32ShrU, &Instruction::I64ShrU);
+            }
+            _ => return None,
+        }
+        self.normalize(scalar);
+        Some(scalar)
+    }
+
+    /// Reduces the shift amount on top of the stack modulo the width of a narrow type.
+    /// WebAssembly shifts already take it modulo 32 and 64.
+    fn mask_shift_amount(&mut self, scalar: Scalar) {
+        if let Some(bits) = scalar.narrow_bits() {
+            #[allow(clippy::cast_possible_wrap)]
+            self.emit(&Instruction::I32Const((bits - 1) as i32));
+            self.emit(&Instruction::I32And);
+        }
+    }
+
+    /// Traps if the signed quotient on top of the stack overflows its narrow type. Only the
+    /// division of the minimum value by -1 produces the quotient 2^(bits - 1).
+    fn check_narrow_overflow(&mut self, scalar: Scalar) {
+        let Some(bits) = scalar.narrow_bits() else {
+            return;
+        };
+        let quotient = self.add_local(ValType::I32);
+        self.emit(&Instruction::LocalTee(quotient));
+        self.emit(&Instruction::I32Const(1 << (bits - 1)));
+        self.emit(&Instruction::I32Eq);
+        self.emit(&Instruction::If(WasmBlockType::Empty));
+        self.emit(&Instruction::Unreachable);
+        self.emit(&Instruction::End);
+        self.emit(&Instruction::LocalGet(quotient));
+    }
+
+    /// Replaces the base and exponent on the stack with `base ** exponent`, computed by a
+    /// square-and-multiply loop over the bits of the exponent, read as an unsigned number.
+    fn lower_pow(&mut self, scalar: Scalar) {
+        use Instruction as I;
+        let val_type = scalar.val_type();
+        let exponent = self.add_local(val_type);
+        let factor = self.add_local(val_type);
+        let result = self.add_local(val_type);
+        if let Some(bits) = scalar.narrow_bits() {
+            self.emit(&I::I32Const((1 << bits) - 1));
+            self.emit(&I::I32And);
+        }
+        self.emit(&I::LocalSet(exponent));
+        self.emit(&I::LocalSet(factor));
+        self.emit_const(scalar, 1);
+        self.emit(&I::LocalSet(result));
+        self.emit(&I::Block(WasmBlockType::Empty));
+        self.emit(&I::Loop(WasmBlockType::Empty));
+        self.emit(&I::LocalGet(exponent));
+        self.emit_int(scalar, &I::I32Eqz, &I::I64Eqz);
+        self.emit(&I::BrIf(1));
+        self.emit(&I::LocalGet(exponent));
+        self.emit_const(scalar, 1);
+        self.emit_int(scalar, &I::I32And, &I::I64And);
+        if scalar.is_wide() {
+            self.emit(&I::I32WrapI64);
+        }
+        self.emit(&I::If(WasmBlockType::Empty));
+        self.emit(&I::LocalGet(result));
+        self.emit(&I::LocalGet(factor));
+        self.emit_int(scalar, &I::I32Mul, &I::I64Mul);
+        self.emit(&I::LocalSet(result));
+        self.emit(&I::End);
+        self.emit(&I::LocalGet(factor));
+        self.emit(&I::LocalGet(factor));
+        self.emit_int(scalar, &I::I32Mul, &I::I64Mul);
+        self.emit(&I::LocalSet(factor));
+        self.emit(&I::LocalGet(exponent));
+        self.emit_const(scalar, 1);
+        self.emit_int(scalar, &I::I32ShrU, &I::I64ShrU);
+        self.emit(&I::LocalSet(exponent));
+        self.emit(&I::Br(0));
+        self.emit(&I::End);
+        self.emit(&I::End);
+        self.emit(&I::LocalGet(result));
+    }
+
+    /// Lowers a call to a function or `external fn` of the module and returns the type of
+    /// its result, if any.
+    fn lower_call(
+        &mut self,
+        call: &FunctionCallExpression,
+    ) -> Result<Option<Scalar>, CodegenError> {
+        let Expression::Identifier(callee) = &call.function else {
+            return Err(self.error_at(
+                CodegenErrorKind::Unsupported("indirect calls".to_string()),
+                call.id,
+                call.location,
+            ));
+        };
+        let index = self.indices.get(&callee.name).copied().ok_or_else(|| {
+            self.error_at(
+                CodegenErrorKind::MissingInformation(format!(
+                    "function `{}` was not declared",
+                    callee.name
+                )),
+                callee.id,
+                callee.location,
+            )
+        })?;
+        let signature = &self.functions[index as usize].signature;
+        for ((_, argument), (_, param)) in call.arguments.iter().flatten().zip(&signature.params) {
+            let value = self.lower_expression(&argument.borrow())?;
+            self.coerce(value, *param);
+        }
+        self.emit(&Instruction::Call(index));
+        Ok(signature.result)
+    }
+
+    /// Returns the tag of the enum variant named by `access`.
+    fn enum_tag(&self, access: &TypeMemberAccessExpression) -> Result<u32, CodegenError> {
+        let missing = |message: String| {
+            self.error_at(
+                CodegenErrorKind::MissingInformation(message),
+                access.id,
+                access.location,
+            )
+        };
+        let enum_name = match self.ctx.get_node_typeinfo(access.id).map(|info| info.kind) {
+            Some(TypeInfoKind::Enum(name)) => name,
+            Some(TypeInfoKind::Custom(name)) if self.ctx.enum_variants(&name).is_some() => name,
+            _ => {
+                return Err(missing(
+                    "type member access does not name an enum".to_string(),
+                ));
+            }
+        };
+        let variant = &access.name.name;
+        self.ctx
+            .enum_variants(&enum_name)
+            .and_then(|variants| variants.iter().position(|name| name == variant))
+            .and_then(|tag| u32::try_from(tag).ok())
+            .ok_or_else(|| missing(format!("enum `{enum_name}` has no variant `{variant}`")))
+    }
+}
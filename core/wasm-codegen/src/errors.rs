@@ -0,0 +1,111 @@
+//! Code generation errors.
+//!
+//! Lowering a function stops at the first construct it cannot handle and records a
+//! [`CodegenError`], then continues with the next function. When every function has been
+//! visited, [`codegen`](crate::codegen) fails with [`CodegenErrors`] holding all errors of
+//! the module, so one run reports every unsupported construct instead of only the first.
+//!
+//! Each error carries the AST node it was raised for and its source location, together
+//! with the LLVM context of the failure: the function being generated and the basic block
+//! the builder was positioned in. The `wasm-encoder` backend reports its errors the same
+//! way, without a basic block. Callers can recover the structured errors from the
+//! `anyhow::Error` returned by `codegen` with `downcast_ref::<CodegenErrors>()`.
+//!
+//! Failures of the LLVM builder itself indicate a bug in the code generator rather than in
+//! the source, and still panic. A generated module that fails validation is such a bug as
+//! well; `codegen` reports it with [`InvalidModule`] instead of returning the module.
+
+use std::fmt::{self, Display, Formatter};
+
+use inference_ast::nodes::Location;
+use thiserror::Error;
+
+/// What went wrong while generating code for a node.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CodegenErrorKind {
+    /// The node uses a language construct code generation does not support yet.
+    #[error("{0} is not supported by code generation yet")]
+    Unsupported(String),
+
+    /// The typed AST lacks information code generation relies on, such as the type of an
+    /// expression or the declaration of a called function.
+    #[error("{0}")]
+    MissingInformation(String),
+}
+
+/// An error raised while generating code for one AST node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenError {
+    /// What went wrong.
+    pub kind: CodegenErrorKind,
+    /// ID of the AST node the error was raised for.
+    pub node_id: u32,
+    /// Source location of the node.
+    pub location: Location,
+    /// Name of the function being generated.
+    pub function: String,
+    /// Name of the LLVM basic block the builder was positioned in, or `None` if the error
+    /// was raised while declaring the function, before its body was started. Always `None`
+    /// with [`Backend::Encoder`](crate::Backend::Encoder), which has no basic blocks.
+    pub block: Option<String>,
+}
+
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: in function `{}`", self.location, self.function)?;
+        if let Some(block) = &self.block {
+            write!(f, " (block `{block}`)")?;
+        }
+        write!(f, ": {}", self.kind)
+    }
+}
+
+impl std::error::Error for CodegenError {}
+
+/// All errors raised while generating code for a module, in source order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenErrors {
+    errors: Vec<CodegenError>,
+}
+
+impl CodegenErrors {
+    pub(crate) fn new(mut errors: Vec<CodegenError>) -> Self {
+        errors.sort_by_key(|error| (error.location.offset_start, error.node_id));
+        Self { errors }
+    }
+
+    /// Returns the collected errors.
+    #[must_use]
+    pub fn errors(&self) -> &[CodegenError] {
+        &self.errors
+    }
+}
+
+impl Display for CodegenErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CodegenErrors {}
+
+/// The module produced by code generation does not validate.
+///
+/// [`codegen`](crate::codegen) validates every module before returning it, against the
+/// proposals enabled by the options and with the non-deterministic instructions of the
+/// Inference extension. A module failing validation is a bug in the code generator, not
+/// in the source, so callers should report it as an internal error.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("generated module is invalid at offset {offset:#x}: {message}")]
+pub struct InvalidModule {
+    /// Description of the validation failure.
+    pub message: String,
+    /// Offset in the module of the invalid item.
+    pub offset: usize,
+}
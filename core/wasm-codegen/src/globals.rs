@@ -0,0 +1,374 @@
+//! Module-level constants as WebAssembly globals.
+//!
+//! Every top-level `const` definition with an integer or `bool` value becomes an immutable
+//! global whose init expression is the constant itself, so hosts and verification tooling
+//! can read it from the module. Exported constants are exported as globals, selected by
+//! [`CodegenOptions::exports`] and named by [`CodegenOptions::export_names`] like
+//! functions: by default every `pub const` is exported under its own name.
+//!
+//! Uses of a constant inside functions still fold to its value, see
+//! [`const_eval`](crate::const_eval): the global is immutable, so reading it would yield
+//! the same value at runtime cost. Constants whose value does not fold, such as strings,
+//! have no global.
+//!
+//! Neither backend emits these globals. They are appended to the global section of the
+//! finished module, after the globals the linker defined, such as the stack pointer, so
+//! the indices of existing globals do not change. A module without globals gets a new
+//! global section.
+
+use std::rc::Rc;
+
+use anyhow::bail;
+use inf_wasmparser::{Parser, Payload, TypeRef};
+use inference_ast::nodes::{ConstantDefinition, Definition, Visibility};
+use inference_type_checker::{
+    type_info::{NumberType, TypeInfo, TypeInfoKind},
+    typed_context::TypedContext,
+};
+use wasm_encoder::{
+    ConstExpr, Encode, ExportKind, ExportSection, GlobalType, Module, RawSection, SectionId,
+    ValType,
+};
+
+use crate::{
+    const_eval::{self, Constant},
+    interface,
+    options::CodegenOptions,
+};
+
+/// A module-level constant lowered to a global.
+struct Global {
+    definition: Rc<ConstantDefinition>,
+    val_type: ValType,
+    init: ConstExpr,
+}
+
+/// Returns the top-level constants of the source that become globals, in source order.
+fn globals(typed_context: &TypedContext) -> Vec<Global> {
+    let mut globals = Vec::new();
+    for source_file in &typed_context.source_files() {
+        for definition in &source_file.definitions {
+            let Definition::Constant(constant_definition) = definition else {
+                continue;
+            };
+            let Some(constant) = const_eval::constant_value(constant_definition) else {
+                continue;
+            };
+            let (val_type, init) = match (TypeInfo::new(&constant_definition.ty).kind, constant) {
+                (TypeInfoKind::Bool, Constant::Bool(value)) => {
+                    (ValType::I32, ConstExpr::i32_const(i32::from(value)))
+                }
+                // Truncating keeps the two's complement representation of every value in
+                // range of the type.
+                #[allow(clippy::cast_possible_truncation)]
+                (TypeInfoKind::Number(NumberType::I64 | NumberType::U64), Constant::Int(value)) => {
+                    (ValType::I64, ConstExpr::i64_const(value as i64))
+                }
+                #[allow(clippy::cast_possible_truncation)]
+                (TypeInfoKind::Number(_), Constant::Int(value)) => {
+                    (ValType::I32, ConstExpr::i32_const(value as i32))
+                }
+                _ => continue,
+            };
+            globals.push(Global {
+                definition: constant_definition.clone(),
+                val_type,
+                init,
+            });
+        }
+    }
+    globals
+}
+
+/// Returns the top-level constants that [`add_section`] lowers to globals, which the
+/// export options may name.
+pub(crate) fn constant_definitions(typed_context: &TypedContext) -> Vec<Rc<ConstantDefinition>> {
+    globals(typed_context)
+        .into_iter()
+        .map(|global| global.definition)
+        .collect()
+}
+
+/// Returns the export name of a module-level constant, or `None` if it is not exported.
+pub(crate) fn export_name(
+    constant_definition: &ConstantDefinition,
+    options: &CodegenOptions,
+) -> Option<String> {
+    options.export_name(
+        &constant_definition.name(),
+        constant_definition.visibility == Visibility::Public,
+    )
+}
+
+/// Appends a global for every module-level constant to `wasm` and exports the selected
+/// ones.
+///
+/// Returns the module unchanged when the source has no such constants.
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be parsed or an exported constant would share
+/// its export name with an export of the module.
+pub(crate) fn add_section(
+    wasm: &[u8],
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> anyhow::Result<Vec<u8>> {
+    let globals = globals(typed_context);
+    if globals.is_empty() {
+        return Ok(wasm.to_vec());
+    }
+    let mut imported = 0;
+    let mut defined = 0;
+    // Bytes of the entries of the existing global section, copied unchanged.
+    let mut entries = 0..0;
+    let mut exports = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    if matches!(import?.ty, TypeRef::Global(_)) {
+                        imported += 1;
+                    }
+                }
+            }
+            Payload::GlobalSection(reader) => {
+                defined = reader.count();
+                let end = reader.range().end;
+                let start = match reader.into_iter_with_offsets().next() {
+                    Some(global) => global?.0,
+                    None => end,
+                };
+                entries = start..end;
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    exports.push((
+                        export.name.to_string(),
+                        interface::export_kind(export.kind),
+                        export.index,
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    for (index, global) in (imported + defined..).zip(&globals) {
+        if let Some(name) = export_name(&global.definition, options) {
+            if exports.iter().any(|(existing, _, _)| *existing == name) {
+                bail!(
+                    "constant `{}` cannot be exported as `{name}`, which is already exported",
+                    global.definition.name()
+                );
+            }
+            exports.push((name, ExportKind::Global, index));
+        }
+    }
+
+    let mut global_section = Vec::new();
+    (defined + u32::try_from(globals.len())?).encode(&mut global_section);
+    global_section.extend_from_slice(&wasm[entries]);
+    for global in &globals {
+        GlobalType {
+            val_type: global.val_type,
+            mutable: false,
+            shared: false,
+        }
+        .encode(&mut global_section);
+        global.init.encode(&mut global_section);
+    }
+    let mut export_section = ExportSection::new();
+    for (name, kind, index) in &exports {
+        export_section.export(name, *kind, *index);
+    }
+    replace_sections(wasm, &global_section, &export_section)
+}
+
+/// Copies `wasm` with its global and export sections replaced by the given ones, which are
+/// inserted at their position if the module has no such section. An empty export section
+/// is left out.
+fn replace_sections(
+    wasm: &[u8],
+    global_section: &[u8],
+    export_section: &ExportSection,
+) -> anyhow::Result<Vec<u8>> {
+    let mut module = Module::new();
+    let mut globals_written = false;
+    let mut exports_written = export_section.is_empty();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        let Some((id, range)) = payload.as_section() else {
+            continue;
+        };
+        // Custom sections may appear anywhere; the others have a fixed order.
+        let order = section_order(id);
+        if !globals_written && id != 0 && order >= section_order(SectionId::Global as u8) {
+            module.section(&RawSection {
+                id: SectionId::Global as u8,
+                data: global_section,
+            });
+            globals_written = true;
+        }
+        if !exports_written && id != 0 && order >= section_order(SectionId::Export as u8) {
+            module.section(export_section);
+            exports_written = true;
+        }
+        if id == SectionId::Global as u8 || id == SectionId::Export as u8 {
+            continue;
+        }
+        module.section(&RawSection {
+            id,
+            data: &wasm[range],
+        });
+    }
+    if !globals_written {
+        module.section(&RawSection {
+            id: SectionId::Global as u8,
+            data: global_section,
+        });
+    }
+    if !exports_written {
+        module.section(export_section);
+    }
+    Ok(module.finish())
+}
+
+/// Returns the rank of a non-custom section in the order the binary format prescribes,
+/// which differs from the order of the IDs for the tag and data count sections.
+fn section_order(id: u8) -> u8 {
+    const ORDER: [SectionId; 13] = [
+        SectionId::Type,
+        SectionId::Import,
+        SectionId::Function,
+        SectionId::Table,
+        SectionId::Memory,
+        SectionId::Tag,
+        SectionId::Global,
+        SectionId::Export,
+        SectionId::Start,
+        SectionId::Element,
+        SectionId::DataCount,
+        SectionId::Code,
+        SectionId::Data,
+    ];
+    ORDER
+        .iter()
+        .position(|section| *section as u8 == id)
+        .and_then(|rank| u8::try_from(rank).ok())
+        .unwrap_or(u8::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_encoder::{
+        CodeSection, DataCountSection, Function, FunctionSection, GlobalSection, Instruction,
+        MemorySection, MemoryType, TypeSection,
+    };
+
+    /// Builds a module with one function and, optionally, a mutable stack pointer global
+    /// and a memory with a data count section.
+    fn module(with_global: bool, with_memory: bool) -> Vec<u8> {
+        let mut module = Module::new();
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+        module.section(&types);
+        let mut functions = FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+        if with_memory {
+            let mut memories = MemorySection::new();
+            memories.memory(MemoryType {
+                minimum: 1,
+                maximum: None,
+                memory64: false,
+                shared: false,
+                page_size_log2: None,
+            });
+            module.section(&memories);
+        }
+        if with_global {
+            let mut globals = GlobalSection::new();
+            globals.global(
+                GlobalType {
+                    val_type: ValType::I32,
+                    mutable: true,
+                    shared: false,
+                },
+                &ConstExpr::i32_const(65536),
+            );
+            module.section(&globals);
+        }
+        if with_memory {
+            module.section(&DataCountSection { count: 0 });
+        }
+        let mut code = CodeSection::new();
+        let mut function = Function::new([]);
+        function.instruction(&Instruction::End);
+        code.function(&function);
+        module.section(&code);
+        module.finish()
+    }
+
+    fn section_ids(wasm: &[u8]) -> Vec<u8> {
+        Parser::new(0)
+            .parse_all(wasm)
+            .filter_map(|payload| payload.unwrap().as_section().map(|(id, _)| id))
+            .collect()
+    }
+
+    fn one_global() -> Vec<u8> {
+        let mut section = Vec::new();
+        1u32.encode(&mut section);
+        GlobalType {
+            val_type: ValType::I64,
+            mutable: false,
+            shared: false,
+        }
+        .encode(&mut section);
+        ConstExpr::i64_const(-1).encode(&mut section);
+        section
+    }
+
+    #[test]
+    fn sections_are_inserted_in_order() {
+        let mut exports = ExportSection::new();
+        exports.export("BIG", ExportKind::Global, 0);
+        let wasm = replace_sections(&module(false, true), &one_global(), &exports).unwrap();
+        inf_wasmparser::validate(&wasm).unwrap();
+        assert_eq!(section_ids(&wasm), [1, 3, 5, 6, 7, 12, 10]);
+    }
+
+    #[test]
+    fn existing_globals_keep_their_indices() {
+        let wasm = module(true, false);
+        let mut global_section = Vec::new();
+        let mut entries = 0..0;
+        for payload in Parser::new(0).parse_all(&wasm) {
+            if let Payload::GlobalSection(reader) = payload.unwrap() {
+                let end = reader.range().end;
+                entries = reader.into_iter_with_offsets().next().unwrap().unwrap().0..end;
+            }
+        }
+        2u32.encode(&mut global_section);
+        global_section.extend_from_slice(&wasm[entries]);
+        global_section.extend_from_slice(&one_global()[1..]);
+        let wasm = replace_sections(&wasm, &global_section, &ExportSection::new()).unwrap();
+        inf_wasmparser::validate(&wasm).unwrap();
+        assert_eq!(section_ids(&wasm), [1, 3, 6, 10]);
+        let mutability =
+            Parser::new(0)
+                .parse_all(&wasm)
+                .find_map(|payload| match payload.unwrap() {
+                    Payload::GlobalSection(reader) => Some(
+                        reader
+                            .into_iter()
+                            .map(|global| global.unwrap().ty.mutable)
+                            .collect::<Vec<_>>(),
+                    ),
+                    _ => None,
+                });
+        assert_eq!(mutability, Some(vec![true, false]));
+    }
+}
@@ -0,0 +1,196 @@
+//! Module interface: exported functions and constants, and the start function.
+//!
+//! [`CodegenOptions::exports`] and [`CodegenOptions::export_names`] decide which functions
+//! and module-level constants the module exports and under which names, and [`CodegenOptions::start_function`] names a
+//! function run when the module is instantiated. These settings are checked against the
+//! source before any code is generated, so a misspelled function name is reported as an
+//! error instead of silently producing a different interface. Constants are exported as
+//! globals, see [`crate::globals`].
+//!
+//! ## Start Function
+//!
+//! Neither LLVM nor `rust-lld` can emit a WebAssembly start section, so it is added to the
+//! linked module. The start function is exported under the reserved name
+//! [`START_EXPORT_NAME`] when it is not exported anyway, which keeps `rust-lld` from
+//! discarding it and lets the start section refer to its final index. The reserved export
+//! is removed again once the start section is in place.
+
+use std::collections::HashSet;
+
+use crate::{
+    globals,
+    options::{CodegenOptions, Exports},
+};
+use inf_wasmparser::{ExternalKind, Parser, Payload};
+use inference_ast::nodes::{FunctionDefinition, SimpleTypeKind, Type, Visibility};
+use inference_type_checker::typed_context::TypedContext;
+use wasm_encoder::{ExportKind, ExportSection, Module, RawSection, StartSection};
+
+/// Export name that keeps a start function which is not exported otherwise.
+const START_EXPORT_NAME: &str = "__inference_start";
+
+/// Export name of the linear memory, which functions cannot use.
+const MEMORY_EXPORT_NAME: &str = "memory";
+
+/// Returns the name under which `function_definition` is exported in the linked module,
+/// including the reserved export of a start function that is not exported otherwise.
+pub(crate) fn export_name(
+    function_definition: &FunctionDefinition,
+    options: &CodegenOptions,
+) -> Option<String> {
+    let name = function_definition.name();
+    options
+        .export_name(&name, function_definition.visibility == Visibility::Public)
+        .or_else(|| {
+            (options.start_function.as_deref() == Some(name.as_str()))
+                .then(|| START_EXPORT_NAME.to_string())
+        })
+}
+
+/// Checks the export and start function options against the functions and constants in
+/// the source.
+///
+/// # Errors
+///
+/// Returns an error if an export option names neither a function nor a module-level
+/// constant, if two functions or constants are exported under the same name or under
+/// `memory`, or if the start function is not a function taking no parameters and
+/// returning nothing.
+pub(crate) fn check(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<()> {
+    let functions = typed_context
+        .source_files()
+        .iter()
+        .flat_map(|source_file| source_file.function_definitions())
+        .collect::<Vec<_>>();
+    let find = |name: &str| functions.iter().find(|function| function.name() == name);
+    let constants = globals::constant_definitions(typed_context);
+
+    let mut named = options.export_names.keys().collect::<Vec<_>>();
+    if let Exports::Only(exported) = &options.exports {
+        named.extend(exported);
+    }
+    for name in named {
+        if find(name).is_none() && !constants.iter().any(|constant| constant.name() == *name) {
+            anyhow::bail!(
+                "cannot export `{name}`: no function or constant with this name is defined"
+            );
+        }
+    }
+
+    let mut export_names = HashSet::new();
+    let exported = functions
+        .iter()
+        .map(|function| (function.name(), function.visibility == Visibility::Public))
+        .chain(
+            constants
+                .iter()
+                .map(|constant| (constant.name(), constant.visibility == Visibility::Public)),
+        );
+    for (name, is_public) in exported {
+        if let Some(export_name) = options.export_name(&name, is_public) {
+            if export_name == MEMORY_EXPORT_NAME || export_name == START_EXPORT_NAME {
+                anyhow::bail!("export name `{export_name}` is reserved");
+            }
+            if !export_names.insert(export_name.clone()) {
+                anyhow::bail!("more than one function or constant is exported as `{export_name}`");
+            }
+        }
+    }
+
+    if let Some(start) = &options.start_function {
+        let function = find(start)
+            .ok_or_else(|| anyhow::anyhow!("start function `{start}` is not defined"))?;
+        let returns_unit = matches!(
+            function.returns,
+            None | Some(Type::Simple(SimpleTypeKind::Unit))
+        );
+        if function
+            .arguments
+            .as_ref()
+            .is_some_and(|arguments| !arguments.is_empty())
+            || !returns_unit
+        {
+            anyhow::bail!("start function `{start}` must take no parameters and return nothing");
+        }
+    }
+    Ok(())
+}
+
+/// Adds a start section calling [`CodegenOptions::start_function`] to a linked module.
+///
+/// Returns the module unchanged when no start function is set.
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be parsed or does not export the start function.
+pub(crate) fn add_start_section(
+    wasm: &[u8],
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(start) = &options.start_function else {
+        return Ok(wasm.to_vec());
+    };
+    let export_name = typed_context
+        .source_files()
+        .iter()
+        .flat_map(|source_file| source_file.function_definitions())
+        .find(|function| function.name() == *start)
+        .and_then(|function| export_name(&function, options))
+        .ok_or_else(|| anyhow::anyhow!("start function `{start}` is not defined"))?;
+    let remove_export = export_name == START_EXPORT_NAME;
+
+    let mut start_index = None;
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::ExportSection(reader) = payload? {
+            for export in reader {
+                let export = export?;
+                if export.kind == ExternalKind::Func && export.name == export_name {
+                    start_index = Some(export.index);
+                }
+            }
+        }
+    }
+    let start_index = start_index
+        .ok_or_else(|| anyhow::anyhow!("start function `{start}` is missing from the module"))?;
+
+    let mut module = Module::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        let Some((id, range)) = payload.as_section() else {
+            continue;
+        };
+        if let Payload::ExportSection(reader) = payload {
+            let mut exports = ExportSection::new();
+            for export in reader {
+                let export = export?;
+                if remove_export && export.name == START_EXPORT_NAME {
+                    continue;
+                }
+                exports.export(export.name, export_kind(export.kind), export.index);
+            }
+            module.section(&exports);
+            // The start section directly follows the export section.
+            module.section(&StartSection {
+                function_index: start_index,
+            });
+        } else {
+            module.section(&RawSection {
+                id,
+                data: &wasm[range],
+            });
+        }
+    }
+    Ok(module.finish())
+}
+
+/// Converts the kind of a parsed export to the kind written by `wasm-encoder`.
+pub(crate) fn export_kind(kind: ExternalKind) -> ExportKind {
+    match kind {
+        ExternalKind::Func => ExportKind::Func,
+        ExternalKind::Table => ExportKind::Table,
+        ExternalKind::Memory => ExportKind::Memory,
+        ExternalKind::Global => ExportKind::Global,
+        ExternalKind::Tag => ExportKind::Tag,
+    }
+}
@@ -15,15 +15,25 @@
 //!         ↓
 //!     LLVM IR
 //!         ↓
-//!    inf-llc   ← Modified LLVM compiler
+//!  TargetMachine ← In-process LLVM backend
 //!         ↓
 //!   WASM Object (.o)
 //!         ↓
-//!   rust-lld  ← WebAssembly linker
+//!   rust-lld  ← WebAssembly linker (or the builtin linker)
 //!         ↓
 //!   WASM Module (.wasm)
 //! ```
 //!
+//! # Backends
+//!
+//! The pipeline above is [`Backend::Llvm`], enabled by the default `llvm` crate feature. The
+//! `encoder` feature adds [`Backend::Encoder`], which lowers the typed AST straight to a
+//! module with `wasm-encoder`. It skips LLVM and the linker entirely, so it compiles quickly
+//! and builds on platforms without the forked LLVM, but only supports a subset of the
+//! language; see the `encoder` module. Building with `--no-default-features --features encoder`
+//! drops the LLVM dependency altogether. Constant folding, the start section, the
+//! verification section and validation are shared by both backends.
+//!
 //! # Non-Deterministic Extensions
 //!
 //! The compiler supports Inference's non-deterministic constructs through custom LLVM
@@ -36,17 +46,39 @@
 //! - `unique { }` - Uniqueness constraint blocks
 //!
 //! These extensions enable formal verification by preserving non-deterministic semantics
-//! through the compilation pipeline.
+//! through the compilation pipeline. The functions containing these blocks and the source
+//! location of every block are recorded in the [`verification::SECTION_NAME`] custom
+//! section, which [`VerificationMetadata::from_module`] reads back.
+//!
+//! # Debug Information
+//!
+//! With [`CodegenOptions::debug_info`] enabled, every function gets a DWARF subprogram and
+//! every lowered statement a line and column, so the `.debug_line` section maps WebAssembly
+//! code offsets back to the `.inf` source. Both linkers keep the debug sections and write a
+//! `name` section with the module and function names, which debuggers and the `wasm-to-v`
+//! translator use for readable output.
+//!
+//! With [`CodegenOptions::source_map`] enabled, the line table is condensed into the
+//! [`source_map::SECTION_NAME`] custom section instead, which [`SourceMap::lookup`] queries
+//! without a DWARF parser, for example to report the source position of a runtime trap.
+//!
+//! # Errors
+//!
+//! Constructs without code generation support are reported as [`CodegenError`]s with the
+//! AST node, source location, function and LLVM basic block of the failure. Lowering a
+//! function stops at its first error, but the remaining functions are still lowered, and
+//! [`codegen`] fails with all errors of the module as [`CodegenErrors`].
 //!
 //! # External Dependencies
 //!
-//! This crate requires two external binaries to be available:
+//! Object files are emitted in-process by the forked LLVM that inkwell is linked against,
+//! which supports the Inference intrinsics. Linking requires one external binary:
 //!
-//! - **inf-llc** - Modified LLVM compiler with Inference intrinsics support
 //! - **rust-lld** - WebAssembly linker from the Rust toolchain
 //!
-//! These must be located in the `bin/` directory relative to the executable. See the
-//! repository README for download links and setup instructions.
+//! It must be located in the `bin/` directory relative to the executable. See the
+//! repository README for download links and setup instructions. Selecting
+//! [`Linker::Builtin`] links in-process instead, so no external binary is needed.
 //!
 //! # Platform Support
 //!
@@ -57,68 +89,291 @@
 //! # Module Organization
 //!
 //! - [`compiler`] - LLVM IR generation and intrinsic handling (private)
+//! - [`encoder`] - Lowering to WebAssembly with `wasm-encoder`, without LLVM (private)
+//! - [`const_eval`] - Compile-time evaluation of constant expressions (private)
 //! - [`utils`] - External toolchain invocation and environment setup (private)
+//! - [`linker`] - In-process linker used by [`Linker::Builtin`] (private)
+//! - [`debug_info`] - DWARF compile unit, subprograms and line locations (private)
+//! - [`interface`] - Export selection and the start section (private)
+//! - [`globals`] - Module-level constants as immutable, optionally exported globals (private)
+//! - [`stack`] - Overflow checks of the shadow stack in linear memory (private)
+//! - [`parallel`] - Compilation split into codegen units on several threads (private)
+//! - [`cache`] - Object code reused across runs for unchanged functions (private)
+//! - [`validation`] - Output validation against the enabled proposals (private)
+//! - [`verification`] - Custom section locating the verification blocks of each function
+//! - [`source_map`] - Custom section mapping code offsets to source positions
+//! - [`errors`] - [`CodegenError`] diagnostics collected per module
+//! - [`options`] - [`CodegenOptions`] controlling optimization, naming, debug info and features
 //! - [`codegen`] - Public API for WebAssembly generation
+//! - [`llvm_ir`] - The LLVM IR of a module, for inspecting the lowering
 
 #![warn(clippy::pedantic)]
 
+#[cfg(feature = "llvm")]
+use std::ops::Range;
+
+#[cfg(feature = "llvm")]
+use inference_ast::nodes::Definition;
 use inference_type_checker::typed_context::TypedContext;
+#[cfg(feature = "llvm")]
 use inkwell::{
     context::Context,
     targets::{InitializationConfig, Target},
 };
 
+#[cfg(feature = "llvm")]
 use crate::compiler::Compiler;
 
+#[cfg(feature = "llvm")]
+mod cache;
+#[cfg(feature = "llvm")]
 mod compiler;
+mod const_eval;
+#[cfg(feature = "llvm")]
+mod debug_info;
+#[cfg(feature = "encoder")]
+mod encoder;
+pub mod errors;
+mod globals;
+mod interface;
+#[cfg(feature = "llvm")]
+mod linker;
+pub mod options;
+#[cfg(feature = "llvm")]
+mod parallel;
+pub mod source_map;
+mod stack;
+#[cfg(feature = "llvm")]
 mod utils;
+mod validation;
+pub mod verification;
+
+pub use errors::{CodegenError, CodegenErrorKind, CodegenErrors, InvalidModule};
+pub use options::{
+    AssertStrategy, Backend, BoundsChecks, CodegenOptions, Exports, ImportName, Linker,
+    StackChecks, TargetFeatures,
+};
+pub use source_map::{SourceMap, SourcePosition};
+pub use verification::VerificationMetadata;
 
 /// Generates WebAssembly bytecode from a typed AST.
 ///
+/// `options` controls the optimization level, module name, debug information and target
+/// features; use [`CodegenOptions::default`] for the standard release configuration.
+///
 /// # Errors
 ///
-/// Returns an error if more than one source file is present in the AST, as multi-file
-/// support is not yet implemented.
+/// Returns an error if more than one source file is present in the AST and debug
+/// information or a source map is requested, as both describe a single source file.
 ///
-/// Returns an error if code generation fails.
-pub fn codegen(typed_context: &TypedContext) -> anyhow::Result<Vec<u8>> {
-    Target::initialize_webassembly(&InitializationConfig::default());
-    let context = Context::create();
-    let compiler = Compiler::new(&context, "wasm_module");
+/// Returns an error if the export or start function options do not match the functions and
+/// constants in the source, or if code generation fails.
+///
+/// Returns a [`CodegenErrors`] error listing every function that uses a construct without
+/// code generation support.
+///
+/// Returns an error if [`CodegenOptions::features`] names an unknown target feature, and an
+/// [`InvalidModule`] error if the generated module does not validate against the enabled
+/// WebAssembly proposals, which is a bug in the code generator.
+///
+/// Returns an error if [`CodegenOptions::backend`] names a backend whose crate feature is
+/// not enabled.
+pub fn codegen(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    options.check_memory()?;
+    validation::check_features(options)?;
+    interface::check(typed_context, options)?;
+    check_source_files(typed_context, options)?;
+
+    let wasm_bytes = match options.backend {
+        #[cfg(feature = "llvm")]
+        Backend::Llvm => llvm_codegen(typed_context, options)?,
+        #[cfg(feature = "encoder")]
+        Backend::Encoder => encoder::compile(typed_context, options)?,
+        #[allow(unreachable_patterns)]
+        backend => {
+            let feature = match backend {
+                Backend::Llvm => "llvm",
+                Backend::Encoder => "encoder",
+            };
+            anyhow::bail!(
+                "the {backend:?} backend is not available; rebuild with the `{feature}` feature"
+            );
+        }
+    };
+    let wasm_bytes = stack::add_checks(wasm_bytes, options)?;
+    let wasm_bytes = globals::add_section(&wasm_bytes, typed_context, options)?;
+    let wasm_bytes = interface::add_start_section(&wasm_bytes, typed_context, options)?;
+    let wasm_bytes = verification::add_section(wasm_bytes, typed_context, options);
+    let wasm_bytes = source_map::add_section(wasm_bytes, options)?;
+    validation::validate(&wasm_bytes, options)?;
+    Ok(wasm_bytes)
+}
 
-    if typed_context.source_files().is_empty() {
-        return compiler.compile_to_wasm("output.wasm", 3);
+/// Generates the LLVM IR of a typed AST as text.
+///
+/// The IR is the module [`Backend::Llvm`] emits as an object file before linking, with
+/// every function in one module whatever [`CodegenOptions::codegen_units`] says. It is
+/// meant for inspecting the lowering of a program; [`CodegenOptions::backend`] is ignored.
+///
+/// # Errors
+///
+/// Returns an error if the options or the source are rejected as by [`codegen`], and a
+/// [`CodegenErrors`] error listing every function that uses a construct without code
+/// generation support.
+///
+/// Returns an error if the `llvm` feature is not enabled.
+pub fn llvm_ir(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<String> {
+    options.check_memory()?;
+    validation::check_features(options)?;
+    interface::check(typed_context, options)?;
+    check_source_files(typed_context, options)?;
+    #[cfg(feature = "llvm")]
+    {
+        Target::initialize_webassembly(&InitializationConfig::default());
+        let context = Context::create();
+        let compiler = Compiler::new(&context, options)?;
+        let errors = traverse_t_ast_with_compiler(
+            typed_context,
+            &compiler,
+            options,
+            0..function_count(typed_context),
+        );
+        if !errors.is_empty() {
+            return Err(CodegenErrors::new(errors).into());
+        }
+        Ok(compiler.print_ir())
     }
-    if typed_context.source_files().len() > 1 {
-        todo!("Multi-file support not yet implemented");
+    #[cfg(not(feature = "llvm"))]
+    anyhow::bail!("LLVM IR is not available; rebuild with the `llvm` feature")
+}
+
+/// Compiles the module with [`Backend::Llvm`] and links it, through the object cache or
+/// on several threads when the options ask for it.
+#[cfg(feature = "llvm")]
+fn llvm_codegen(typed_context: &TypedContext, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    Target::initialize_webassembly(&InitializationConfig::default());
+    let function_count = function_count(typed_context);
+    let units = options.effective_codegen_units(function_count);
+    Ok(
+        if let Some(cache_dir) = options.effective_cache_dir()
+            && function_count > 0
+        {
+            cache::compile(typed_context, options, cache_dir)?
+        } else if units > 1 {
+            parallel::compile(typed_context, options, units)?
+        } else {
+            let context = Context::create();
+            let compiler = Compiler::new(&context, options)?;
+            let errors =
+                traverse_t_ast_with_compiler(typed_context, &compiler, options, 0..function_count);
+            if !errors.is_empty() {
+                return Err(CodegenErrors::new(errors).into());
+            }
+            compiler.compile_to_wasm("output.wasm", options)?
+        },
+    )
+}
+
+/// Rejects debug information and source maps for a module built from several source
+/// files: both record a single source path, [`CodegenOptions::source_path`].
+fn check_source_files(
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> anyhow::Result<()> {
+    let source_files = typed_context.source_files().len();
+    if source_files > 1 && (options.debug_info || options.source_map) {
+        anyhow::bail!(
+            "debug information and source maps are not supported for a module of {source_files} source files"
+        );
     }
+    Ok(())
+}
 
-    traverse_t_ast_with_compiler(typed_context, &compiler);
-    let wasm_bytes = compiler.compile_to_wasm("output.wasm", 3)?;
-    Ok(wasm_bytes)
+/// Returns the number of function definitions in all source files.
+#[cfg(feature = "llvm")]
+fn function_count(typed_context: &TypedContext) -> usize {
+    typed_context
+        .source_files()
+        .iter()
+        .map(|source_file| source_file.function_definitions().len())
+        .sum()
 }
 
-/// Traverses the typed AST and compiles all function definitions.
+/// Traverses the typed AST and compiles the function definitions in `lowered`.
 ///
 /// This function iterates through all source files in the typed context and generates
-/// LLVM IR for each function definition. Currently, only function definitions at the
-/// module level are compiled; other top-level constructs (types, constants, etc.) are
-/// not yet supported.
+/// LLVM IR for the function definitions whose index, counted over all source files in
+/// order, lies in `lowered`. All functions are declared first, so a body can call
+/// functions defined later in the file or in another codegen unit. `external fn` definitions become host imports
+/// named according to [`CodegenOptions::import_name`], and functions are exported as
+/// [`CodegenOptions::exports`] and [`CodegenOptions::export_names`] specify. Module-level
+/// constants are recorded for constant folding before any function is lowered, and struct
+/// types are created on first use.
 ///
 /// # Parameters
 ///
 /// - `typed_context` - Typed AST with type information for all nodes
 /// - `compiler` - LLVM compiler instance for IR generation
+/// - `options` - Code generation options naming the host imports and exports
+/// - `lowered` - Indices of the functions whose bodies are lowered and which are exported;
+///   the other functions are only declared
+///
+/// # Returns
+///
+/// The errors of every function in `lowered` that could not be declared or lowered. A
+/// function whose declaration fails is not lowered, and lowering a function stops at its
+/// first error. Errors of `external fn` declarations are reported with the unit lowering
+/// the first function, so they are reported once.
 ///
 /// # Current Limitations
 ///
-/// - Only function definitions are compiled
-/// - Type definitions, constants, and other top-level items are ignored
-/// - Multi-file compilation is not fully tested (see `codegen` function)
-fn traverse_t_ast_with_compiler(typed_context: &TypedContext, compiler: &Compiler) {
+/// - Only function and external function definitions are compiled
+/// - Integer and boolean constants are folded into their uses; other top-level items
+///   are ignored
+/// - Functions of all source files share one namespace; modules are not supported yet
+#[cfg(feature = "llvm")]
+fn traverse_t_ast_with_compiler(
+    typed_context: &TypedContext,
+    compiler: &Compiler,
+    options: &CodegenOptions,
+    lowered: Range<usize>,
+) -> Vec<CodegenError> {
+    let mut errors = Vec::new();
+    let mut declared = Vec::new();
+    let mut index = 0;
     for source_file in &typed_context.source_files() {
+        for definition in &source_file.definitions {
+            if let Definition::Constant(constant_definition) = definition {
+                compiler.define_module_constant(constant_definition);
+            }
+        }
+        for external_def in source_file.external_function_definitions() {
+            let import_name = options.import_name(&external_def.name());
+            let result =
+                compiler.declare_external_function(&external_def, &import_name, typed_context);
+            if let (Err(error), 0) = (result, lowered.start) {
+                errors.push(error);
+            }
+        }
         for func_def in source_file.function_definitions() {
-            compiler.visit_function_definition(&func_def, typed_context);
+            let is_lowered = lowered.contains(&index);
+            index += 1;
+            let export_name = is_lowered
+                .then(|| interface::export_name(&func_def, options))
+                .flatten();
+            let result =
+                compiler.declare_function(&func_def, export_name.as_deref(), typed_context);
+            match (result, is_lowered) {
+                (Ok(_), true) => declared.push(func_def),
+                (Err(error), true) => errors.push(error),
+                (_, false) => {}
+            }
+        }
+    }
+    for func_def in &declared {
+        if let Err(error) = compiler.visit_function_definition(func_def, typed_context) {
+            errors.push(error);
         }
     }
+    errors
 }
@@ -0,0 +1,637 @@
+//! In-process WebAssembly linker.
+//!
+//! This module turns the relocatable object file produced by a single codegen run into a
+//! final WebAssembly module without spawning `rust-lld`. It handles the subset of the
+//! [WebAssembly object file format] that LLVM emits for an Inference module and produces a
+//! module equivalent to `rust-lld -flavor wasm --no-entry --stack-first` output:
+//!
+//! - The `__linear_memory` import becomes a defined memory, exported as `memory`
+//! - The `__stack_pointer` import becomes a defined mutable global
+//! - The `__indirect_function_table` import becomes a defined table sized to its elements
+//! - The stack occupies the first [`CodegenOptions::stack_size`] bytes of memory and grows
+//!   down; data segments are placed right after it
+//! - The memory is at least [`CodegenOptions::initial_memory_pages`] large and limited to
+//!   [`CodegenOptions::max_memory_pages`]; objects built with [`CodegenOptions::memory64`]
+//!   get a 64-bit memory and stack pointer
+//! - Relocations in the code, data and DWARF sections are resolved against the `linking`
+//!   symbol table, and the `linking` and `reloc.*` custom sections are dropped
+//! - DWARF `.debug_*` sections are kept when [`CodegenOptions::debug_info`] or
+//!   [`CodegenOptions::source_map`] is set and dropped otherwise, as with
+//!   `rust-lld --strip-debug`
+//! - Function imports with an explicit import name (from `external fn` declarations) stay
+//!   imports, keeping their place at the start of the function index space
+//! - A `name` section records the module name and the name of every function
+//!
+//! Function bodies are copied byte for byte once relocated, so the non-deterministic
+//! instructions in the `0xfc` prefix space pass through untouched, and code offsets in the
+//! DWARF line tables stay valid.
+//!
+//! Unlike `rust-lld`, the builtin linker accepts a single object and rejects undefined
+//! symbols other than explicitly named function imports.
+//!
+//! [WebAssembly object file format]: https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md
+
+use std::{borrow::Cow, ops::Range};
+
+use crate::options::{CodegenOptions, PAGE_SIZE};
+use inf_wasmparser::{
+    ConstExpr as ObjectConstExpr, DataKind, Element, ElementItems, ElementKind, ExternalKind,
+    Import, KnownCustom, Linking, Operator, Parser, Payload, RelocationEntry, RelocationType,
+    SymbolFlags, SymbolInfo, TypeRef,
+};
+use rustc_hash::FxHashMap;
+use wasm_encoder::{
+    ConstExpr, CustomSection, DataSection, EntityType, ExportKind, ExportSection, GlobalSection,
+    GlobalType, ImportSection, MemorySection, MemoryType, Module, NameMap, NameSection, RawSection,
+    RefType, SectionId, TableSection, TableType, ValType,
+};
+
+/// Links a WebAssembly object file into a WebAssembly module.
+///
+/// Functions keep the export names recorded in the object. When `has_main` is set, `main`
+/// is exported as well, mirroring `rust-lld --export=main`. The `name` section carries
+/// [`CodegenOptions::module_name`], and debug sections are kept only when
+/// [`CodegenOptions::debug_info`] or [`CodegenOptions::source_map`] is set.
+///
+/// # Errors
+///
+/// Returns an error if the object cannot be parsed, references an undefined symbol, uses a
+/// relocation or section the builtin linker does not support, if `has_main` is set but
+/// the object defines no `main` function, or if the stack and data do not fit into
+/// [`CodegenOptions::initial_memory_pages`] or [`CodegenOptions::max_memory_pages`].
+pub(crate) fn link(
+    object: &[u8],
+    has_main: bool,
+    options: &CodegenOptions,
+) -> anyhow::Result<Vec<u8>> {
+    let mut object = Object::parse(object)?;
+    object.stack_size = options.stack_size;
+    if !options.emits_dwarf() {
+        object.debug_sections.clear();
+    }
+    object.relocate()?;
+    object.encode(has_main, options)
+}
+
+/// The parts of a relocatable object file the linker works with.
+#[derive(Default)]
+struct Object<'a> {
+    types: Option<&'a [u8]>,
+    functions: Option<&'a [u8]>,
+    elements: Option<&'a [u8]>,
+    data_count: Option<&'a [u8]>,
+    /// Code section payload and its section index, patched in place by [`Object::relocate`].
+    code: Option<(u32, Vec<u8>)>,
+    /// Data section payload and its section index, patched in place by [`Object::relocate`].
+    data: Option<(u32, Vec<u8>)>,
+    /// DWARF `.debug_*` custom sections with their names and section indices, patched in
+    /// place by [`Object::relocate`].
+    debug_sections: Vec<(u32, &'a str, Vec<u8>)>,
+    /// Minimum number of pages requested by the `__linear_memory` import.
+    memory_pages: u64,
+    /// Whether the `__linear_memory` import is a 64-bit memory.
+    memory64: bool,
+    has_stack_pointer: bool,
+    /// Size of the stack at the start of linear memory.
+    stack_size: u64,
+    /// Host functions imported by the object as `(module, name, type index)`, in function
+    /// index order.
+    function_imports: Vec<(&'a str, &'a str, u32)>,
+    /// Minimum size requested by the `__indirect_function_table` import.
+    table_size: Option<u64>,
+    /// Table slot of every function placed in the indirect function table.
+    table_slots: FxHashMap<u32, u32>,
+    exports: Vec<(&'a str, u32)>,
+    symbols: Vec<SymbolInfo<'a>>,
+    /// Address of each data segment in the object's address space.
+    segment_addresses: Vec<u64>,
+    /// Relocation entries keyed by the index of the section they apply to.
+    relocations: Vec<(u32, Vec<RelocationEntry>)>,
+}
+
+impl<'a> Object<'a> {
+    fn parse(bytes: &'a [u8]) -> anyhow::Result<Self> {
+        let mut object = Object::default();
+        let mut section_index = 0;
+
+        for payload in Parser::new(0).parse_all(bytes) {
+            let payload = payload?;
+            let Some((_, range)) = payload.as_section() else {
+                continue;
+            };
+            match payload {
+                Payload::TypeSection(_) => object.types = Some(&bytes[range]),
+                Payload::FunctionSection(_) => object.functions = Some(&bytes[range]),
+                Payload::DataCountSection { .. } => object.data_count = Some(&bytes[range]),
+                Payload::CodeSectionStart { .. } => {
+                    object.code = Some((section_index, bytes[range].to_vec()));
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        object.add_import(&import?)?;
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export?;
+                        if export.kind == ExternalKind::Func {
+                            object.exports.push((export.name, export.index));
+                        }
+                    }
+                }
+                Payload::ElementSection(reader) => {
+                    for element in reader {
+                        object.add_element(element?)?;
+                    }
+                    object.elements = Some(&bytes[range]);
+                }
+                Payload::DataSection(reader) => {
+                    for segment in reader {
+                        let DataKind::Active { offset_expr, .. } = segment?.kind else {
+                            return Err(anyhow::anyhow!("passive data segments are not supported"));
+                        };
+                        object
+                            .segment_addresses
+                            .push(u64::try_from(const_offset(&offset_expr)?)?);
+                    }
+                    object.data = Some((section_index, bytes[range].to_vec()));
+                }
+                Payload::CustomSection(reader) => match reader.as_known() {
+                    KnownCustom::Linking(linking) => {
+                        for subsection in linking {
+                            if let Linking::SymbolTable(symbols) = subsection? {
+                                for symbol in symbols {
+                                    object.symbols.push(symbol?);
+                                }
+                            }
+                        }
+                    }
+                    KnownCustom::Reloc(reloc) => {
+                        let entries = reloc.entries().into_iter().collect::<Result<_, _>>()?;
+                        object.relocations.push((reloc.section_index(), entries));
+                    }
+                    _ if reader.name().starts_with(".debug_") => {
+                        object.debug_sections.push((
+                            section_index,
+                            reader.name(),
+                            reader.data().to_vec(),
+                        ));
+                    }
+                    _ => {}
+                },
+                Payload::TableSection(_)
+                | Payload::MemorySection(_)
+                | Payload::GlobalSection(_)
+                | Payload::TagSection(_)
+                | Payload::StartSection { .. } => {
+                    return Err(anyhow::anyhow!(
+                        "section {section_index} of the object file is not supported"
+                    ));
+                }
+                _ => {}
+            }
+            section_index += 1;
+        }
+        object.check_undefined_functions()?;
+        Ok(object)
+    }
+
+    /// Rejects undefined functions that are not imported under an explicit name.
+    ///
+    /// Like `rust-lld`, only functions whose import module and name were set in the source
+    /// may remain imports of the linked module.
+    fn check_undefined_functions(&self) -> anyhow::Result<()> {
+        for symbol in &self.symbols {
+            if let SymbolInfo::Func { flags, name, .. } = symbol
+                && flags.contains(SymbolFlags::UNDEFINED)
+                && !flags.contains(SymbolFlags::EXPLICIT_NAME)
+            {
+                return Err(anyhow::anyhow!("undefined symbol: {}", name.unwrap_or("?")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Records an import. Function imports are kept; every other import must be one of the
+    /// symbols the linker defines itself.
+    fn add_import(&mut self, import: &Import<'a>) -> anyhow::Result<()> {
+        match (import.module, import.name, import.ty) {
+            ("env", "__linear_memory", TypeRef::Memory(memory)) => {
+                self.memory_pages = memory.initial;
+                self.memory64 = memory.memory64;
+            }
+            ("env", "__stack_pointer", TypeRef::Global(_)) => self.has_stack_pointer = true,
+            ("env", "__indirect_function_table", TypeRef::Table(table)) => {
+                self.table_size = Some(table.initial);
+            }
+            (module, name, TypeRef::Func(type_index)) => {
+                self.function_imports.push((module, name, type_index));
+            }
+            (_, name, _) => return Err(anyhow::anyhow!("undefined symbol: {name}")),
+        }
+        Ok(())
+    }
+
+    /// Records the table slots assigned by an element segment of the function table.
+    fn add_element(&mut self, element: Element<'_>) -> anyhow::Result<()> {
+        let (ElementKind::Active { offset_expr, .. }, ElementItems::Functions(functions)) =
+            (element.kind, element.items)
+        else {
+            return Err(anyhow::anyhow!(
+                "unsupported element segment in object file"
+            ));
+        };
+        let first_slot = u32::try_from(const_offset(&offset_expr)?)?;
+        for (slot, function) in (first_slot..).zip(functions) {
+            self.table_slots.insert(function?, slot);
+        }
+        Ok(())
+    }
+
+    /// Applies the relocations of the code, data and debug sections.
+    ///
+    /// Relocations of sections that are not part of the output, such as debug sections
+    /// that were dropped, are ignored.
+    fn relocate(&mut self) -> anyhow::Result<()> {
+        let function_offsets = self.function_offsets()?;
+        for (section, entries) in std::mem::take(&mut self.relocations) {
+            for entry in entries {
+                let value = match entry.ty {
+                    RelocationType::FunctionOffsetI32 | RelocationType::FunctionOffsetI64 => {
+                        self.resolve_function_offset(&entry, &function_offsets)?
+                    }
+                    // Sections are copied whole, so an offset into one is just the addend.
+                    RelocationType::SectionOffsetI32 => entry.addend,
+                    _ => self.resolve(&entry)?,
+                };
+                if let Some(payload) = self.section_payload(section) {
+                    patch(payload, &entry, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the payload of the output section with the given object section index.
+    fn section_payload(&mut self, section: u32) -> Option<&mut Vec<u8>> {
+        let debug = self
+            .debug_sections
+            .iter_mut()
+            .map(|(index, _, payload)| (*index, payload));
+        self.code
+            .iter_mut()
+            .chain(self.data.iter_mut())
+            .map(|(index, payload)| (*index, payload))
+            .chain(debug)
+            .find(|(index, _)| *index == section)
+            .map(|(_, payload)| payload)
+    }
+
+    /// Returns the offset of every function body within the code section payload.
+    ///
+    /// The code section is copied unchanged, so these offsets are the same in the object
+    /// and in the linked module.
+    fn function_offsets(&self) -> anyhow::Result<Vec<usize>> {
+        let Some((_, payload)) = &self.code else {
+            return Ok(Vec::new());
+        };
+        let reader =
+            inf_wasmparser::CodeSectionReader::new(inf_wasmparser::BinaryReader::new(payload, 0))?;
+        reader
+            .into_iter()
+            .map(|body| Ok(body?.range().start))
+            .collect()
+    }
+
+    /// Resolves a code offset used by DWARF to the start of a function body.
+    fn resolve_function_offset(
+        &self,
+        entry: &RelocationEntry,
+        function_offsets: &[usize],
+    ) -> anyhow::Result<i64> {
+        let Some(SymbolInfo::Func { index, name, .. }) = self.symbols.get(entry.index as usize)
+        else {
+            return Err(anyhow::anyhow!(
+                "relocation refers to unknown function symbol {}",
+                entry.index
+            ));
+        };
+        let body = (*index as usize)
+            .checked_sub(self.function_imports.len())
+            .ok_or_else(|| anyhow::anyhow!("function {} has no body", name.unwrap_or("?")))?;
+        let offset = function_offsets
+            .get(body)
+            .ok_or_else(|| anyhow::anyhow!("function {} has no body", name.unwrap_or("?")))?;
+        Ok(i64::try_from(*offset)? + entry.addend)
+    }
+
+    /// Computes the final value of the field a relocation points at.
+    fn resolve(&self, entry: &RelocationEntry) -> anyhow::Result<i64> {
+        if entry.ty == RelocationType::TypeIndexLeb {
+            return Ok(i64::from(entry.index));
+        }
+        let symbol = self.symbols.get(entry.index as usize).ok_or_else(|| {
+            anyhow::anyhow!("relocation refers to unknown symbol {}", entry.index)
+        })?;
+        match (entry.ty, symbol) {
+            (
+                RelocationType::FunctionIndexLeb | RelocationType::FunctionIndexI32,
+                SymbolInfo::Func { index, .. },
+            )
+            | (
+                RelocationType::GlobalIndexLeb | RelocationType::GlobalIndexI32,
+                SymbolInfo::Global { index, .. },
+            )
+            | (RelocationType::TableNumberLeb, SymbolInfo::Table { index, .. }) => {
+                // Imports are turned into definitions in place, so indices do not change.
+                Ok(i64::from(*index))
+            }
+            (
+                RelocationType::TableIndexSleb
+                | RelocationType::TableIndexI32
+                | RelocationType::TableIndexSleb64
+                | RelocationType::TableIndexI64,
+                SymbolInfo::Func { index, name, .. },
+            ) => self
+                .table_slots
+                .get(index)
+                .map(|slot| i64::from(*slot))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "function {} is not in the indirect function table",
+                        name.unwrap_or("?")
+                    )
+                }),
+            (
+                RelocationType::MemoryAddrLeb
+                | RelocationType::MemoryAddrSleb
+                | RelocationType::MemoryAddrI32
+                | RelocationType::MemoryAddrLeb64
+                | RelocationType::MemoryAddrSleb64
+                | RelocationType::MemoryAddrI64,
+                SymbolInfo::Data { name, symbol, .. },
+            ) => {
+                let defined = symbol.ok_or_else(|| anyhow::anyhow!("undefined symbol: {name}"))?;
+                let segment = self
+                    .segment_addresses
+                    .get(defined.index as usize)
+                    .ok_or_else(|| anyhow::anyhow!("symbol {name} refers to unknown segment"))?;
+                Ok(i64::try_from(self.stack_size + segment)?
+                    + i64::from(defined.offset)
+                    + entry.addend)
+            }
+            (ty, _) => Err(anyhow::anyhow!("unsupported relocation {ty:?}")),
+        }
+    }
+
+    /// Assembles the linked module.
+    fn encode(&self, has_main: bool, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+        let mut module = Module::new();
+        if let Some(types) = self.types {
+            module.section(&raw(SectionId::Type, types));
+        }
+        if !self.function_imports.is_empty() {
+            let mut imports = ImportSection::new();
+            for (import_module, name, type_index) in &self.function_imports {
+                imports.import(import_module, name, EntityType::Function(*type_index));
+            }
+            module.section(&imports);
+        }
+        if let Some(functions) = self.functions {
+            module.section(&raw(SectionId::Function, functions));
+        }
+
+        if let Some(table_size) = self.table_size {
+            let elements = self.table_slots.values().max().map_or(0, |slot| slot + 1);
+            let size = table_size.max(u64::from(elements));
+            let mut tables = TableSection::new();
+            tables.table(TableType {
+                element_type: RefType::FUNCREF,
+                table64: false,
+                minimum: size,
+                maximum: Some(size),
+                shared: false,
+            });
+            module.section(&tables);
+        }
+
+        let mut data = DataSection::new();
+        let mut data_end = self.stack_size;
+        if let Some((_, payload)) = &self.data {
+            let reader = inf_wasmparser::DataSectionReader::new(
+                inf_wasmparser::BinaryReader::new(payload, 0),
+            )?;
+            for (segment, address) in reader.into_iter().zip(&self.segment_addresses) {
+                let segment = segment?;
+                let address = self.stack_size + address;
+                data.active(
+                    0,
+                    &self.address_const(address)?,
+                    segment.data.iter().copied(),
+                );
+                data_end = data_end.max(address + segment.data.len() as u64);
+            }
+        }
+
+        let mut memories = MemorySection::new();
+        memories.memory(self.memory_type(data_end, options)?);
+        module.section(&memories);
+
+        if self.has_stack_pointer {
+            let mut globals = GlobalSection::new();
+            globals.global(
+                GlobalType {
+                    val_type: if self.memory64 {
+                        ValType::I64
+                    } else {
+                        ValType::I32
+                    },
+                    mutable: true,
+                    shared: false,
+                },
+                &self.address_const(self.stack_size)?,
+            );
+            module.section(&globals);
+        }
+
+        let mut exports = ExportSection::new();
+        exports.export("memory", ExportKind::Memory, 0);
+        for (name, index) in &self.exports {
+            exports.export(name, ExportKind::Func, *index);
+        }
+        if has_main && !self.exports.iter().any(|(name, _)| *name == "main") {
+            let main = self
+                .defined_function("main")
+                .or_else(|| self.defined_function("__main_void"))
+                .ok_or_else(|| anyhow::anyhow!("undefined symbol: main"))?;
+            exports.export("main", ExportKind::Func, main);
+        }
+        module.section(&exports);
+
+        if let Some(elements) = self.elements {
+            module.section(&raw(SectionId::Element, elements));
+        }
+        if let Some(data_count) = self.data_count {
+            module.section(&raw(SectionId::DataCount, data_count));
+        }
+        if let Some((_, code)) = &self.code {
+            module.section(&raw(SectionId::Code, code));
+        }
+        if self.data.is_some() {
+            module.section(&data);
+        }
+        for (_, name, payload) in &self.debug_sections {
+            module.section(&CustomSection {
+                name: Cow::Borrowed(name),
+                data: Cow::Borrowed(payload),
+            });
+        }
+        module.section(&self.name_section(&options.module_name));
+
+        Ok(module.finish())
+    }
+
+    /// Returns the type of the linked memory, which holds the stack and data ending at
+    /// `data_end` and respects the memory sizes in `options`.
+    fn memory_type(&self, data_end: u64, options: &CodegenOptions) -> anyhow::Result<MemoryType> {
+        let required = self.memory_pages.max(data_end.div_ceil(PAGE_SIZE));
+        let minimum = match options.initial_memory_pages {
+            Some(initial) if initial < required => anyhow::bail!(
+                "initial memory of {initial} pages is too small, {required} pages are needed"
+            ),
+            Some(initial) => initial,
+            None => required,
+        };
+        if let Some(maximum) = options.max_memory_pages
+            && maximum < minimum
+        {
+            anyhow::bail!(
+                "maximum memory of {maximum} pages is too small, {minimum} pages are needed"
+            );
+        }
+        Ok(MemoryType {
+            minimum,
+            maximum: options.max_memory_pages,
+            memory64: self.memory64,
+            shared: false,
+            page_size_log2: None,
+        })
+    }
+
+    /// Returns a constant expression for a memory address, `i64.const` for a 64-bit memory
+    /// and `i32.const` otherwise.
+    fn address_const(&self, address: u64) -> anyhow::Result<ConstExpr> {
+        Ok(if self.memory64 {
+            ConstExpr::i64_const(i64::try_from(address)?)
+        } else {
+            ConstExpr::i32_const(i32::try_from(address)?)
+        })
+    }
+
+    /// Returns the index of the defined function called `name`.
+    fn defined_function(&self, name: &str) -> Option<u32> {
+        self.symbols.iter().find_map(|symbol| match symbol {
+            SymbolInfo::Func {
+                flags,
+                index,
+                name: Some(symbol_name),
+            } if *symbol_name == name && !flags.contains(SymbolFlags::UNDEFINED) => Some(*index),
+            _ => None,
+        })
+    }
+
+    /// Builds a `name` section with the module name and the names of all functions.
+    fn name_section(&self, module_name: &str) -> NameSection {
+        let mut functions = self
+            .symbols
+            .iter()
+            .filter_map(|symbol| match symbol {
+                SymbolInfo::Func {
+                    index,
+                    name: Some(name),
+                    ..
+                } => Some((*index, *name)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        functions.sort_unstable_by_key(|(index, _)| *index);
+        functions.dedup_by_key(|(index, _)| *index);
+
+        let mut names = NameMap::new();
+        for (index, name) in functions {
+            names.append(index, name);
+        }
+        let mut section = NameSection::new();
+        section.module(module_name);
+        section.functions(&names);
+        section
+    }
+}
+
+/// Wraps an unchanged section payload copied from the object file.
+fn raw(id: SectionId, data: &[u8]) -> RawSection<'_> {
+    RawSection {
+        id: id.into(),
+        data,
+    }
+}
+
+/// Reads the value of an `i32.const` or `i64.const` offset expression.
+fn const_offset(expr: &ObjectConstExpr<'_>) -> anyhow::Result<i64> {
+    match expr.get_operators_reader().read()? {
+        Operator::I32Const { value } => Ok(i64::from(value)),
+        Operator::I64Const { value } => Ok(value),
+        _ => Err(anyhow::anyhow!("expected a constant offset expression")),
+    }
+}
+
+/// Overwrites the relocated field of `entry` in `payload` with `value`.
+///
+/// LEB128 fields are padded to five bytes in object files, or ten bytes for 64-bit
+/// relocations, so the new value is written with the same width and nothing around it
+/// moves.
+fn patch(payload: &mut [u8], entry: &RelocationEntry, value: i64) -> anyhow::Result<()> {
+    let range: Range<usize> = entry.relocation_range();
+    let field = payload
+        .get_mut(range)
+        .ok_or_else(|| anyhow::anyhow!("relocation at offset {} is out of bounds", entry.offset))?;
+    match entry.ty {
+        RelocationType::TableIndexSleb | RelocationType::MemoryAddrSleb => {
+            write_padded_leb(field, i64::from(i32::try_from(value)?));
+        }
+        RelocationType::TableIndexSleb64 | RelocationType::MemoryAddrSleb64 => {
+            write_padded_leb(field, value);
+        }
+        RelocationType::MemoryAddrLeb64 => {
+            anyhow::ensure!(value >= 0, "address {value} is negative");
+            write_padded_leb(field, value);
+        }
+        RelocationType::TableIndexI32
+        | RelocationType::MemoryAddrI32
+        | RelocationType::FunctionIndexI32
+        | RelocationType::GlobalIndexI32
+        | RelocationType::FunctionOffsetI32
+        | RelocationType::SectionOffsetI32 => {
+            field.copy_from_slice(&u32::try_from(value)?.to_le_bytes());
+        }
+        RelocationType::TableIndexI64
+        | RelocationType::MemoryAddrI64
+        | RelocationType::FunctionOffsetI64 => {
+            field.copy_from_slice(&u64::try_from(value)?.to_le_bytes());
+        }
+        _ => write_padded_leb(field, i64::from(u32::try_from(value)?)),
+    }
+    Ok(())
+}
+
+/// Writes `value` as LEB128 padded to the width of `field`.
+///
+/// The arithmetic shift keeps the sign of negative values, so the same encoding serves
+/// signed fields; for non-negative values it matches the unsigned encoding.
+fn write_padded_leb(field: &mut [u8], mut value: i64) {
+    let last = field.len().saturating_sub(1);
+    for (i, byte) in field.iter_mut().enumerate() {
+        *byte = value.to_le_bytes()[0] & 0x7f | if i < last { 0x80 } else { 0 };
+        value >>= 7;
+    }
+}
@@ -0,0 +1,693 @@
+//! Code generation options.
+//!
+//! [`CodegenOptions`] collects the settings that control how a typed AST is lowered to
+//! WebAssembly. It is passed to [`codegen`](crate::codegen) and forwarded to the selected
+//! [`Backend`]: for LLVM, to the LLVM module, the LLVM target machine and the selected
+//! [`Linker`].
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+#[cfg(feature = "llvm")]
+use inkwell::OptimizationLevel;
+#[cfg(feature = "llvm")]
+use std::path::Path;
+
+/// Highest LLVM optimization level.
+pub const MAX_OPT_LEVEL: u32 = 3;
+
+/// Size of a WebAssembly memory page in bytes.
+pub const PAGE_SIZE: u64 = 65536;
+
+/// Default size of the stack in bytes, matching the `rust-lld` default.
+pub const DEFAULT_STACK_SIZE: u64 = 65536;
+
+/// Largest number of pages of a 32-bit linear memory.
+const MAX_MEMORY32_PAGES: u64 = 1 << 16;
+
+/// Largest number of pages of a 64-bit linear memory.
+const MAX_MEMORY64_PAGES: u64 = 1 << 48;
+
+/// Settings for a single code generation run.
+///
+/// The [`Default`] options reproduce the historical behavior: full optimization, a module
+/// named `wasm_module`, no debug information and the WebAssembly MVP feature set.
+///
+/// # Example
+///
+/// ```ignore
+/// use inference_wasm_codegen::{codegen, CodegenOptions};
+///
+/// let options = CodegenOptions {
+///     opt_level: 0,
+///     debug_info: true,
+///     ..CodegenOptions::default()
+/// };
+/// let wasm_bytes = codegen(&typed_context, &options)?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodegenOptions {
+    /// Backend generating the module. Options that only concern LLVM, object files or
+    /// linking have no effect on [`Backend::Encoder`].
+    pub backend: Backend,
+
+    /// LLVM code generation optimization level, as in `llc -O<level>`. Values above
+    /// [`MAX_OPT_LEVEL`] are clamped.
+    pub opt_level: u32,
+
+    /// Name of the generated LLVM module.
+    pub module_name: String,
+
+    /// Emit DWARF debug information mapping code offsets to `.inf` source lines and keep
+    /// the debug sections in the linked module. When disabled, no debug information is
+    /// generated and `rust-lld` is invoked with `--strip-debug`.
+    pub debug_info: bool,
+
+    /// Add a source map custom section mapping code offsets to `.inf` lines and columns,
+    /// which [`SourceMap`](crate::SourceMap) reads. The map is derived from DWARF line
+    /// tables, which are generated for it even when [`CodegenOptions::debug_info`] is
+    /// disabled, but only kept in the module when it is enabled.
+    pub source_map: bool,
+
+    /// Path of the compiled source file, recorded in the DWARF compile unit and the source
+    /// map. When unset, `<module_name>.inf` is used.
+    pub source_path: Option<PathBuf>,
+
+    /// Post-MVP WebAssembly proposals the LLVM backend may use. The baseline is the MVP
+    /// feature set, and the linked module is validated against the enabled proposals.
+    pub target_features: TargetFeatures,
+
+    /// Additional LLVM target features by name, such as `multivalue`, enabled on top of
+    /// [`CodegenOptions::target_features`].
+    pub features: Vec<String>,
+
+    /// Linker that turns the emitted object file into the final module.
+    pub linker: Linker,
+
+    /// How array indexing guards against out-of-bounds indices.
+    pub bounds_checks: BoundsChecks,
+
+    /// How a failing `assert` statement stops execution.
+    pub assert_strategy: AssertStrategy,
+
+    /// Import module of `external fn` declarations that have no entry in
+    /// [`CodegenOptions::imports`].
+    pub import_module: String,
+
+    /// Import module and field name of individual `external fn` declarations, keyed by
+    /// function name. Functions without an entry are imported from
+    /// [`CodegenOptions::import_module`] under their own name.
+    pub imports: BTreeMap<String, ImportName>,
+
+    /// Which functions and module-level constants are exported from the module.
+    pub exports: Exports,
+
+    /// Export names of individual functions and constants, keyed by their name. Exports
+    /// without an entry use the name of the function or constant.
+    pub export_names: BTreeMap<String, String>,
+
+    /// Function called by the WebAssembly start section when the module is instantiated.
+    /// It must take no parameters and return nothing.
+    pub start_function: Option<String>,
+
+    /// Number of code generation units. Function bodies are split into this many
+    /// consecutive groups in source order, and each group is compiled to its own object
+    /// file on a separate thread before the objects are linked together. `0` and `1` both
+    /// compile everything as one unit.
+    ///
+    /// Calls between units cannot be inlined. [`Linker::Builtin`] only links a single
+    /// object file, so it always uses one unit.
+    pub codegen_units: usize,
+
+    /// Directory of the object code cache. When set, every function is compiled to its
+    /// own object file, stored under a hash of its typed AST and of everything else that
+    /// affects its code, and reused by later runs while the hash is unchanged.
+    /// [`CodegenOptions::codegen_units`] then bounds the number of threads emitting the
+    /// functions missing from the cache.
+    ///
+    /// The cache is not used with [`Linker::Builtin`], which only links a single object
+    /// file.
+    pub cache_dir: Option<PathBuf>,
+
+    /// Target `wasm64-unknown-unknown` with a 64-bit linear memory (the memory64 proposal)
+    /// instead of `wasm32-unknown-unknown`. Pointers and the stack pointer become 64-bit.
+    pub memory64: bool,
+
+    /// Initial size of the linear memory in pages. When unset, the memory is just large
+    /// enough for the stack and the static data.
+    pub initial_memory_pages: Option<u64>,
+
+    /// Maximum size of the linear memory in pages. When unset, the memory has no maximum.
+    pub max_memory_pages: Option<u64>,
+
+    /// Size of the stack in bytes. It must be a multiple of 16, the stack alignment.
+    pub stack_size: u64,
+
+    /// How stack pointer updates guard against overflowing the stack.
+    pub stack_checks: StackChecks,
+}
+
+/// Selection of the functions and module-level constants exported from the module.
+/// Constants are exported as globals.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Exports {
+    /// Export every `pub` function and `pub const`.
+    #[default]
+    Public,
+
+    /// Export exactly the listed functions and constants, whatever their visibility.
+    Only(Vec<String>),
+}
+
+/// Toggles for the post-MVP WebAssembly proposals supported by the code generator.
+///
+/// Each enabled proposal is passed to LLVM as the target feature named in
+/// [`TargetFeatures::NAMES`], which lets the backend use its instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct TargetFeatures {
+    /// `bulk-memory`: `memory.copy` and `memory.fill` for block copies and fills.
+    pub bulk_memory: bool,
+    /// `sign-ext`: sign-extension operators such as `i32.extend8_s`.
+    pub sign_ext: bool,
+    /// `nontrapping-fptoint`: saturating float-to-integer conversions.
+    pub nontrapping_fptoint: bool,
+    /// `simd128`: 128-bit SIMD instructions.
+    pub simd: bool,
+    /// `tail-call`: `return_call` for calls in tail position.
+    pub tail_call: bool,
+}
+
+impl TargetFeatures {
+    /// LLVM feature names of the proposals, in field order.
+    pub const NAMES: [&'static str; 5] = [
+        "bulk-memory",
+        "sign-ext",
+        "nontrapping-fptoint",
+        "simd128",
+        "tail-call",
+    ];
+
+    /// Enables the proposal with the LLVM feature name `name`, with or without a leading
+    /// `+`. Returns false if no proposal has this name.
+    pub fn enable(&mut self, name: &str) -> bool {
+        let toggle = match name.trim_start_matches('+') {
+            "bulk-memory" => &mut self.bulk_memory,
+            "sign-ext" => &mut self.sign_ext,
+            "nontrapping-fptoint" => &mut self.nontrapping_fptoint,
+            "simd128" => &mut self.simd,
+            "tail-call" => &mut self.tail_call,
+            _ => return false,
+        };
+        *toggle = true;
+        true
+    }
+
+    /// Returns the LLVM feature names of the enabled proposals.
+    #[must_use]
+    pub fn enabled(&self) -> Vec<&'static str> {
+        let toggles = [
+            self.bulk_memory,
+            self.sign_ext,
+            self.nontrapping_fptoint,
+            self.simd,
+            self.tail_call,
+        ];
+        Self::NAMES
+            .into_iter()
+            .zip(toggles)
+            .filter_map(|(name, enabled)| enabled.then_some(name))
+            .collect()
+    }
+}
+
+/// Module and field name under which a host function is imported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportName {
+    /// Import module, the first part of a WebAssembly import name.
+    pub module: String,
+    /// Field name within the import module.
+    pub name: String,
+}
+
+/// Code generator turning the typed AST into a WebAssembly module.
+///
+/// A backend is available when the crate feature of the same name is enabled; `llvm` is
+/// enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// LLVM IR built with inkwell, compiled by the forked LLVM and linked by [`Linker`].
+    /// The reference backend, honoring every option.
+    Llvm,
+
+    /// Direct lowering of the typed AST with `wasm-encoder`, without LLVM or a linker.
+    /// Compiles faster and needs no native dependencies, but only supports functions over
+    /// integers, booleans and enums, and emits no linear memory.
+    Encoder,
+}
+
+impl Default for Backend {
+    /// Returns [`Backend::Llvm`] when it is compiled in, and [`Backend::Encoder`] otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "llvm") {
+            Backend::Llvm
+        } else {
+            Backend::Encoder
+        }
+    }
+}
+
+/// Linker used to produce the final WebAssembly module from the emitted object file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Linker {
+    /// The external `rust-lld` binary, run with the `wasm` flavor.
+    #[default]
+    RustLld,
+
+    /// The in-process linker. It handles the single object produced by a codegen run and
+    /// needs no external tools.
+    Builtin,
+}
+
+/// Strategy for checking array indices against the array length.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BoundsChecks {
+    /// Compare every index with the array length and trap (`unreachable`) when it is out
+    /// of bounds. Negative indices are out of bounds.
+    #[default]
+    Trap,
+
+    /// Index without any check. An out-of-bounds index reads or writes whatever lies next
+    /// to the array in linear memory.
+    Unchecked,
+}
+
+/// Strategy for checking the shadow stack in linear memory, which holds the locals and
+/// aggregates whose address is taken, against overflows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StackChecks {
+    /// Compare every new stack pointer with the bounds of the stack and trap
+    /// (`unreachable`) when a call would move it past them.
+    #[default]
+    Trap,
+
+    /// Update the stack pointer without any check. The stack starts at address 0, so an
+    /// overflowing stack pointer wraps around past the end of memory and the next access to
+    /// the frame traps out of bounds, unless the memory spans the whole address space.
+    Unchecked,
+}
+
+/// Lowering of a failing `assert` statement.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AssertStrategy {
+    /// Execute `unreachable`, trapping the whole call.
+    #[default]
+    Trap,
+
+    /// Call the host function imported under this name as `abort(file, line)`, then trap
+    /// if it returns. `file` points to the NUL-terminated path of the source file in linear
+    /// memory, as given by [`CodegenOptions::source_path`] or `<module_name>.inf`, and `line`
+    /// is the 1-based line of the `assert` as an `i32`.
+    Abort(ImportName),
+
+    /// Return from the function containing the `assert` without trapping. A function
+    /// returning an integer or `bool` returns this code, truncated to its return type; a
+    /// function without a return value just returns.
+    ResultCode(i64),
+}
+
+impl AssertStrategy {
+    /// Returns the [`AssertStrategy::Abort`] strategy importing `env.abort`.
+    #[must_use]
+    pub fn abort() -> Self {
+        AssertStrategy::Abort(ImportName {
+            module: String::from("env"),
+            name: String::from("abort"),
+        })
+    }
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self {
+            backend: Backend::default(),
+            opt_level: MAX_OPT_LEVEL,
+            module_name: String::from("wasm_module"),
+            debug_info: false,
+            source_map: false,
+            source_path: None,
+            target_features: TargetFeatures::default(),
+            features: Vec::new(),
+            linker: Linker::default(),
+            bounds_checks: BoundsChecks::default(),
+            assert_strategy: AssertStrategy::default(),
+            import_module: String::from("env"),
+            imports: BTreeMap::new(),
+            exports: Exports::default(),
+            export_names: BTreeMap::new(),
+            start_function: None,
+            codegen_units: 1,
+            cache_dir: None,
+            memory64: false,
+            initial_memory_pages: None,
+            max_memory_pages: None,
+            stack_size: DEFAULT_STACK_SIZE,
+            stack_checks: StackChecks::default(),
+        }
+    }
+}
+
+impl CodegenOptions {
+    /// Returns the optimization level for the LLVM target machine.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn llvm_opt_level(&self) -> OptimizationLevel {
+        match self.opt_level.min(MAX_OPT_LEVEL) {
+            0 => OptimizationLevel::None,
+            1 => OptimizationLevel::Less,
+            2 => OptimizationLevel::Default,
+            _ => OptimizationLevel::Aggressive,
+        }
+    }
+
+    /// Returns the module and field name under which the `external fn` called `function` is
+    /// imported.
+    pub(crate) fn import_name(&self, function: &str) -> ImportName {
+        self.imports
+            .get(function)
+            .cloned()
+            .unwrap_or_else(|| ImportName {
+                module: self.import_module.clone(),
+                name: function.to_string(),
+            })
+    }
+
+    /// Returns the name under which the function or constant called `name` is exported, or
+    /// `None` if it is not exported. `is_public` tells whether it is declared `pub`.
+    pub(crate) fn export_name(&self, name: &str, is_public: bool) -> Option<String> {
+        let exported = match &self.exports {
+            Exports::Public => is_public,
+            Exports::Only(exported) => exported.iter().any(|exported| exported == name),
+        };
+        exported.then(|| {
+            self.export_names
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| name.to_string())
+        })
+    }
+
+    /// Returns the number of code generation units used for a module with `functions`
+    /// function definitions.
+    ///
+    /// There are never more units than functions, and only one with the builtin linker.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn effective_codegen_units(&self, functions: usize) -> usize {
+        if self.linker == Linker::Builtin {
+            return 1;
+        }
+        self.codegen_units.min(functions).max(1)
+    }
+
+    /// Returns the directory of the object code cache, or `None` if no cache is used.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn effective_cache_dir(&self) -> Option<&Path> {
+        if self.linker == Linker::Builtin {
+            return None;
+        }
+        self.cache_dir.as_deref()
+    }
+
+    /// Returns true if the objects carry DWARF, for the debug sections or the source map.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn emits_dwarf(&self) -> bool {
+        self.debug_info || self.source_map
+    }
+
+    /// Returns the source file path reported for failing asserts, in debug information and
+    /// in the source map.
+    pub(crate) fn source_file(&self) -> PathBuf {
+        self.source_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.inf", self.module_name)))
+    }
+
+    /// Returns the target triple of the emitted objects.
+    #[cfg(feature = "llvm")]
+    pub(crate) fn target_triple(&self) -> &'static str {
+        if self.memory64 {
+            "wasm64-unknown-unknown"
+        } else {
+            "wasm32-unknown-unknown"
+        }
+    }
+
+    /// Checks the memory and stack settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stack size is not a multiple of 16, if a memory size
+    /// exceeds the number of pages the memory can address, or if the initial memory size
+    /// is larger than the maximum.
+    pub(crate) fn check_memory(&self) -> anyhow::Result<()> {
+        if !self.stack_size.is_multiple_of(16) {
+            anyhow::bail!("stack size {} is not a multiple of 16", self.stack_size);
+        }
+        let page_limit = if self.memory64 {
+            MAX_MEMORY64_PAGES
+        } else {
+            MAX_MEMORY32_PAGES
+        };
+        for pages in [self.initial_memory_pages, self.max_memory_pages]
+            .into_iter()
+            .flatten()
+        {
+            if pages > page_limit {
+                anyhow::bail!("memory of {pages} pages exceeds the limit of {page_limit} pages");
+            }
+        }
+        if let (Some(initial), Some(max)) = (self.initial_memory_pages, self.max_memory_pages)
+            && initial > max
+        {
+            anyhow::bail!("initial memory of {initial} pages exceeds the maximum of {max} pages");
+        }
+        Ok(())
+    }
+
+    /// Returns the LLVM target feature string, e.g. `+bulk-memory,+sign-ext`, with the
+    /// enabled proposals first and each feature listed once.
+    pub(crate) fn llvm_features(&self) -> String {
+        let mut features = self.target_features.enabled();
+        for feature in &self.features {
+            let feature = feature.trim_start_matches('+');
+            if !features.contains(&feature) {
+                features.push(feature);
+            }
+        }
+        features
+            .iter()
+            .map(|feature| format!("+{feature}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_matches_previous_behavior() {
+        let options = CodegenOptions::default();
+        #[cfg(feature = "llvm")]
+        assert_eq!(options.backend, Backend::Llvm);
+        #[cfg(feature = "llvm")]
+        assert_eq!(options.llvm_opt_level(), OptimizationLevel::Aggressive);
+        assert_eq!(options.module_name, "wasm_module");
+        assert!(!options.debug_info);
+        assert!(!options.source_map);
+        assert_eq!(options.source_path, None);
+        assert_eq!(options.target_features, TargetFeatures::default());
+        assert_eq!(options.llvm_features(), "");
+        assert_eq!(options.linker, Linker::RustLld);
+        assert_eq!(options.bounds_checks, BoundsChecks::Trap);
+        assert_eq!(options.assert_strategy, AssertStrategy::Trap);
+        assert_eq!(options.import_module, "env");
+        assert!(options.imports.is_empty());
+        assert_eq!(options.exports, Exports::Public);
+        assert!(options.export_names.is_empty());
+        assert_eq!(options.start_function, None);
+        assert_eq!(options.codegen_units, 1);
+        assert_eq!(options.cache_dir, None);
+        #[cfg(feature = "llvm")]
+        assert_eq!(options.target_triple(), "wasm32-unknown-unknown");
+        assert_eq!(options.initial_memory_pages, None);
+        assert_eq!(options.max_memory_pages, None);
+        assert_eq!(options.stack_size, DEFAULT_STACK_SIZE);
+        assert_eq!(options.stack_checks, StackChecks::Trap);
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn opt_level_is_clamped() {
+        let options = CodegenOptions {
+            opt_level: 7,
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.llvm_opt_level(), OptimizationLevel::Aggressive);
+    }
+
+    #[test]
+    fn features_become_target_feature_string() {
+        let options = CodegenOptions {
+            features: vec!["bulk-memory".to_string(), "+sign-ext".to_string()],
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.llvm_features(), "+bulk-memory,+sign-ext");
+    }
+
+    #[test]
+    fn enabled_proposals_come_first_without_duplicates() {
+        let mut options = CodegenOptions {
+            features: vec!["multivalue".to_string(), "+tail-call".to_string()],
+            ..CodegenOptions::default()
+        };
+        assert!(options.target_features.enable("+tail-call"));
+        assert!(options.target_features.enable("simd128"));
+        assert!(!options.target_features.enable("multivalue"));
+        assert_eq!(options.target_features.enabled(), ["simd128", "tail-call"]);
+        assert_eq!(options.llvm_features(), "+simd128,+tail-call,+multivalue");
+    }
+
+    #[test]
+    fn import_names_default_to_import_module() {
+        let mut options = CodegenOptions {
+            import_module: "host".to_string(),
+            ..CodegenOptions::default()
+        };
+        options.imports.insert(
+            "log".to_string(),
+            ImportName {
+                module: "console".to_string(),
+                name: "log_i32".to_string(),
+            },
+        );
+        assert_eq!(
+            options.import_name("log"),
+            ImportName {
+                module: "console".to_string(),
+                name: "log_i32".to_string(),
+            }
+        );
+        assert_eq!(
+            options.import_name("now"),
+            ImportName {
+                module: "host".to_string(),
+                name: "now".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn export_names_follow_export_selection() {
+        let mut options = CodegenOptions::default();
+        options
+            .export_names
+            .insert("add".to_string(), "plus".to_string());
+        assert_eq!(options.export_name("add", true), Some("plus".to_string()));
+        assert_eq!(options.export_name("sub", true), Some("sub".to_string()));
+        assert_eq!(options.export_name("helper", false), None);
+
+        options.exports = Exports::Only(vec!["helper".to_string()]);
+        assert_eq!(options.export_name("add", true), None);
+        assert_eq!(
+            options.export_name("helper", false),
+            Some("helper".to_string())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn codegen_units_are_bounded_by_functions_and_linker() {
+        let mut options = CodegenOptions {
+            codegen_units: 4,
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.effective_codegen_units(10), 4);
+        assert_eq!(options.effective_codegen_units(3), 3);
+        assert_eq!(options.effective_codegen_units(0), 1);
+
+        options.codegen_units = 0;
+        assert_eq!(options.effective_codegen_units(10), 1);
+
+        options.codegen_units = 4;
+        options.linker = Linker::Builtin;
+        assert_eq!(options.effective_codegen_units(10), 1);
+    }
+
+    #[test]
+    fn source_file_defaults_to_module_name() {
+        let mut options = CodegenOptions {
+            module_name: "checks".to_string(),
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.source_file(), PathBuf::from("checks.inf"));
+        options.source_path = Some(PathBuf::from("src/main.inf"));
+        assert_eq!(options.source_file(), PathBuf::from("src/main.inf"));
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn cache_is_not_used_with_builtin_linker() {
+        let mut options = CodegenOptions {
+            cache_dir: Some(PathBuf::from("cache")),
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.effective_cache_dir(), Some(Path::new("cache")));
+
+        options.linker = Linker::Builtin;
+        assert_eq!(options.effective_cache_dir(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn memory64_selects_wasm64_target() {
+        let options = CodegenOptions {
+            memory64: true,
+            ..CodegenOptions::default()
+        };
+        assert_eq!(options.target_triple(), "wasm64-unknown-unknown");
+    }
+
+    #[test]
+    fn memory_settings_are_checked() {
+        let valid = CodegenOptions {
+            initial_memory_pages: Some(2),
+            max_memory_pages: Some(4),
+            stack_size: 2 * DEFAULT_STACK_SIZE,
+            ..CodegenOptions::default()
+        };
+        assert!(valid.check_memory().is_ok());
+
+        let misaligned_stack = CodegenOptions {
+            stack_size: 1000,
+            ..valid.clone()
+        };
+        assert!(misaligned_stack.check_memory().is_err());
+
+        let initial_above_max = CodegenOptions {
+            initial_memory_pages: Some(8),
+            ..valid.clone()
+        };
+        assert!(initial_above_max.check_memory().is_err());
+
+        let too_large = CodegenOptions {
+            max_memory_pages: Some(MAX_MEMORY32_PAGES + 1),
+            ..valid.clone()
+        };
+        assert!(too_large.check_memory().is_err());
+        let memory64 = CodegenOptions {
+            memory64: true,
+            ..too_large
+        };
+        assert!(memory64.check_memory().is_ok());
+    }
+}
@@ -0,0 +1,140 @@
+//! Compilation split into codegen units.
+//!
+//! When [`CodegenOptions::codegen_units`] is greater than one, the functions of a module
+//! are split into consecutive groups in source order, one LLVM module per group. Every
+//! unit declares all functions and host imports, so calls across units are resolved by
+//! the linker, but only lowers and exports the functions of its own group.
+//!
+//! Lowering reads the typed AST, which is not thread-safe, so it runs on the calling
+//! thread. Its output is cheap compared to instruction selection and optimization in the
+//! LLVM backend: each unit is serialized to bitcode and emitted as an object file on a
+//! worker thread, in its own LLVM context. The objects are linked in unit order, so the
+//! module is the same on every run regardless of which thread finishes first.
+
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{
+    compiler::Compiler, errors::CodegenErrors, options::CodegenOptions,
+    traverse_t_ast_with_compiler, utils,
+};
+use inference_type_checker::typed_context::TypedContext;
+use inkwell::{context::Context, memory_buffer::MemoryBuffer, module::Module};
+
+/// Codegen units lowered to LLVM bitcode.
+pub(crate) struct LoweredUnits {
+    /// Bitcode of every unit, in the order the units were given.
+    pub(crate) bitcode: Vec<Vec<u8>>,
+    /// Whether one of the units declares a `main` function exported as `main`.
+    pub(crate) has_main: bool,
+}
+
+/// Compiles the functions of `typed_context` in `units` codegen units and links them.
+///
+/// # Errors
+///
+/// Returns [`CodegenErrors`] with the errors of all units if a function cannot be
+/// lowered, or an error if object emission or linking fails.
+pub(crate) fn compile(
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+    units: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let function_count = crate::function_count(typed_context);
+    let ranges = (0..units)
+        .map(|unit| unit * function_count / units..(unit + 1) * function_count / units)
+        .collect::<Vec<_>>();
+    let lowered = lower_units(typed_context, options, &ranges)?;
+    let objects = emit_objects(&lowered.bitcode, options, units)?;
+    utils::link_objects(&objects, "output.wasm", options, lowered.has_main)
+}
+
+/// Lowers one codegen unit per range of function indices.
+///
+/// # Errors
+///
+/// Returns [`CodegenErrors`] with the errors of all units if a function cannot be
+/// lowered, or an error if a target machine cannot be created.
+pub(crate) fn lower_units(
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+    ranges: &[Range<usize>],
+) -> anyhow::Result<LoweredUnits> {
+    let mut errors = Vec::new();
+    let mut bitcode = Vec::with_capacity(ranges.len());
+    let mut has_main = false;
+    for range in ranges {
+        let context = Context::create();
+        let compiler = Compiler::new(&context, options)?;
+        errors.extend(traverse_t_ast_with_compiler(
+            typed_context,
+            &compiler,
+            options,
+            range.clone(),
+        ));
+        has_main |= compiler.has_main();
+        bitcode.push(compiler.write_bitcode());
+    }
+    if !errors.is_empty() {
+        return Err(CodegenErrors::new(errors).into());
+    }
+    Ok(LoweredUnits { bitcode, has_main })
+}
+
+/// Emits the object file of every unit on at most `threads` worker threads.
+///
+/// Returns the objects in the order of `bitcode`.
+///
+/// # Errors
+///
+/// Returns the error of the first unit, in unit order, that cannot be emitted.
+pub(crate) fn emit_objects(
+    bitcode: &[Vec<u8>],
+    options: &CodegenOptions,
+    threads: usize,
+) -> anyhow::Result<Vec<Vec<u8>>> {
+    let next_unit = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..threads.clamp(1, bitcode.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let unit = next_unit.fetch_add(1, Ordering::Relaxed);
+                        let Some(bitcode) = bitcode.get(unit) else {
+                            return results;
+                        };
+                        results.push((unit, emit_unit(unit, bitcode, options)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(unit, _)| *unit);
+    results.into_iter().map(|(_, object)| object).collect()
+}
+
+/// Loads the bitcode of one unit into a new LLVM context and emits its object file.
+///
+/// # Errors
+///
+/// Returns an error if the bitcode cannot be parsed or object emission fails.
+fn emit_unit(unit: usize, bitcode: &[u8], options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    let context = Context::create();
+    let buffer = MemoryBuffer::create_from_memory_range_copy(bitcode, &format!("unit{unit}"));
+    let module = Module::parse_bitcode_from_buffer(&buffer, &context)
+        .map_err(|e| anyhow::anyhow!("Failed to load codegen unit {unit}: {e}"))?;
+    let target_machine = utils::create_target_machine(&module, options)?;
+    utils::emit_object(&module, &target_machine)
+}
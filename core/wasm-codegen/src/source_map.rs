@@ -0,0 +1,366 @@
+//! Source map custom section.
+//!
+//! With [`CodegenOptions::source_map`] set, the linked module carries a custom section named
+//! [`SECTION_NAME`] that maps code offsets to lines and columns of the `.inf` source. It is
+//! derived from the DWARF line table of the module, but is much smaller and needs no DWARF
+//! parser to read: [`SourceMap::from_module`] decodes it and [`SourceMap::lookup`] returns
+//! the source position of an instruction, such as the module offset of a trap reported by a
+//! runtime. The DWARF sections themselves are only kept when
+//! [`CodegenOptions::debug_info`] is set as well.
+//!
+//! ## Encoding
+//!
+//! The section uses the conventions of the WebAssembly binary format: integers are
+//! unsigned LEB128 and strings are length-prefixed UTF-8.
+//!
+//! ```text
+//! section ::= version:u32 file:string entries:vec(entry)
+//! entry   ::= offset_delta:u32 line:u32 column:u32
+//! ```
+//!
+//! Offsets are offsets in the module, like those of runtime backtraces, and each entry
+//! stores the distance to the offset of the previous entry. An entry covers the code up to
+//! the next entry. Line `0` marks code without a source position, such as the code between
+//! two functions; column `0` marks a position without a known column.
+
+use std::collections::HashMap;
+
+use anyhow::bail;
+use gimli::{ColumnType, Dwarf, EndianSlice, LittleEndian};
+use inf_wasmparser::{BinaryReader, Parser, Payload};
+use wasm_encoder::{CustomSection, Encode, Module, RawSection, SectionId};
+
+use crate::options::CodegenOptions;
+
+/// Name of the source map custom section.
+pub const SECTION_NAME: &str = "inference.sourcemap";
+
+/// Version of the section encoding, bumped on every incompatible change.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A line and column of the source file, both 1-based. The column is `0` when unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourcePosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Start of a range of code that maps to one source position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceMapEntry {
+    /// Offset of the first byte of the range in the module.
+    pub offset: u32,
+    /// Source position of the range, or `None` for code without one.
+    pub position: Option<SourcePosition>,
+}
+
+/// Contents of the source map custom section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// Path of the source file, as given by [`CodegenOptions::source_path`].
+    pub file: String,
+    /// Entries sorted by offset.
+    pub entries: Vec<SourceMapEntry>,
+}
+
+impl SourceMap {
+    /// Returns the source position of the instruction at module offset `offset`, or `None`
+    /// if the offset is not covered by the map.
+    #[must_use]
+    pub fn lookup(&self, offset: u32) -> Option<SourcePosition> {
+        let index = self.entries.partition_point(|entry| entry.offset <= offset);
+        self.entries[..index].last()?.position
+    }
+
+    /// Encodes the map as the contents of the custom section.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        FORMAT_VERSION.encode(&mut data);
+        self.file.encode(&mut data);
+        self.entries.len().encode(&mut data);
+        let mut previous = 0;
+        for entry in &self.entries {
+            (entry.offset - previous).encode(&mut data);
+            let position = entry
+                .position
+                .unwrap_or(SourcePosition { line: 0, column: 0 });
+            position.line.encode(&mut data);
+            position.column.encode(&mut data);
+            previous = entry.offset;
+        }
+        data
+    }
+
+    /// Decodes the contents of the custom section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is truncated, has trailing bytes, uses another format
+    /// version or has offsets beyond the range of a module.
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = BinaryReader::new(data, 0);
+        let version = reader.read_var_u32()?;
+        if version != FORMAT_VERSION {
+            bail!("Unsupported source map version {version}");
+        }
+        let file = reader.read_string()?.to_string();
+        let mut entries = Vec::new();
+        let mut offset = 0u32;
+        for _ in 0..reader.read_var_u32()? {
+            offset = offset
+                .checked_add(reader.read_var_u32()?)
+                .ok_or_else(|| anyhow::anyhow!("Source map offset out of range"))?;
+            let line = reader.read_var_u32()?;
+            let column = reader.read_var_u32()?;
+            entries.push(SourceMapEntry {
+                offset,
+                position: (line != 0).then_some(SourcePosition { line, column }),
+            });
+        }
+        if !reader.eof() {
+            bail!("Trailing bytes after source map");
+        }
+        Ok(Self { file, entries })
+    }
+
+    /// Reads the source map of a WebAssembly module.
+    ///
+    /// Returns `None` if the module has no [`SECTION_NAME`] section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module cannot be parsed or the section cannot be decoded.
+    pub fn from_module(wasm: &[u8]) -> anyhow::Result<Option<Self>> {
+        for payload in Parser::new(0).parse_all(wasm) {
+            if let Payload::CustomSection(reader) = payload?
+                && reader.name() == SECTION_NAME
+            {
+                return Self::decode(reader.data()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Appends the source map of a linked module to it when [`CodegenOptions::source_map`] is
+/// set, and drops the DWARF sections it was built from unless
+/// [`CodegenOptions::debug_info`] is set as well.
+///
+/// # Errors
+///
+/// Returns an error if the module or its DWARF line table cannot be parsed.
+pub(crate) fn add_section(wasm: Vec<u8>, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    if !options.source_map {
+        return Ok(wasm);
+    }
+    let rows = line_rows(&wasm)?;
+    let mut wasm = if options.debug_info {
+        wasm
+    } else {
+        strip_debug_sections(&wasm)?
+    };
+    let source_map = SourceMap {
+        file: options.source_file().to_string_lossy().into_owned(),
+        entries: entries(rows, code_section_start(&wasm)?)?,
+    };
+    wasm.push(SectionId::Custom as u8);
+    CustomSection {
+        name: SECTION_NAME.into(),
+        data: source_map.encode().into(),
+    }
+    .encode(&mut wasm);
+    Ok(wasm)
+}
+
+/// A row of the DWARF line table: a code address, relative to the start of the code
+/// section, with its source position.
+type LineRow = (u64, Option<SourcePosition>);
+
+/// Reads the rows of every line table in the `.debug_*` sections of `wasm`.
+fn line_rows(wasm: &[u8]) -> anyhow::Result<Vec<LineRow>> {
+    let mut sections = HashMap::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CustomSection(reader) = payload?
+            && reader.name().starts_with(".debug_")
+        {
+            sections.insert(reader.name(), reader.data());
+        }
+    }
+    let dwarf = Dwarf::load(|id| {
+        let data = sections.get(id.name()).copied().unwrap_or_default();
+        Ok::<_, gimli::Error>(EndianSlice::new(data, LittleEndian))
+    })?;
+
+    let mut rows = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next()? {
+        let unit = dwarf.unit(header)?;
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+        let mut program_rows = program.rows();
+        while let Some((_, row)) = program_rows.next_row()? {
+            let position = match row.line() {
+                Some(line) if !row.end_sequence() => Some(SourcePosition {
+                    line: u32::try_from(line.get())?,
+                    column: match row.column() {
+                        ColumnType::LeftEdge => 0,
+                        ColumnType::Column(column) => u32::try_from(column.get())?,
+                    },
+                }),
+                _ => None,
+            };
+            rows.push((row.address(), position));
+        }
+    }
+    Ok(rows)
+}
+
+/// Turns line table rows into sorted source map entries at module offsets.
+///
+/// When several rows share an address, the last row with a source position wins, since
+/// the end of one sequence may coincide with the start of the next one. Entries repeating
+/// the position of the previous entry are dropped.
+fn entries(mut rows: Vec<LineRow>, code_start: u32) -> anyhow::Result<Vec<SourceMapEntry>> {
+    rows.sort_by_key(|(address, _)| *address);
+    let mut entries: Vec<SourceMapEntry> = Vec::new();
+    for (address, position) in rows {
+        let offset = u32::try_from(address)?
+            .checked_add(code_start)
+            .ok_or_else(|| anyhow::anyhow!("Line table address out of range"))?;
+        match entries.last_mut() {
+            Some(last) if last.offset == offset => {
+                if position.is_some() || last.position.is_none() {
+                    last.position = position;
+                }
+            }
+            Some(last) if last.position == position => {}
+            _ => entries.push(SourceMapEntry { offset, position }),
+        }
+    }
+    entries.dedup_by(|entry, previous| entry.position == previous.position);
+    Ok(entries)
+}
+
+/// Returns the module offset at which the contents of the code section start. Addresses in
+/// the DWARF sections of a WebAssembly module are relative to it.
+fn code_section_start(wasm: &[u8]) -> anyhow::Result<u32> {
+    for payload in Parser::new(0).parse_all(wasm) {
+        if let Payload::CodeSectionStart { range, .. } = payload? {
+            return Ok(u32::try_from(range.start)?);
+        }
+    }
+    Ok(0)
+}
+
+/// Removes the `.debug_*` custom sections from `wasm`, keeping every other section byte
+/// for byte.
+fn strip_debug_sections(wasm: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut module = Module::new();
+    for payload in Parser::new(0).parse_all(wasm) {
+        let payload = payload?;
+        if let Payload::CustomSection(reader) = &payload
+            && reader.name().starts_with(".debug_")
+        {
+            continue;
+        }
+        if let Some((id, range)) = payload.as_section() {
+            module.section(&RawSection {
+                id,
+                data: &wasm[range],
+            });
+        }
+    }
+    Ok(module.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(line: u32, column: u32) -> SourcePosition {
+        SourcePosition { line, column }
+    }
+
+    fn source_map() -> SourceMap {
+        SourceMap {
+            file: "src/main.inf".to_string(),
+            entries: vec![
+                SourceMapEntry {
+                    offset: 100,
+                    position: Some(position(1, 1)),
+                },
+                SourceMapEntry {
+                    offset: 104,
+                    position: Some(position(2, 5)),
+                },
+                SourceMapEntry {
+                    offset: 120,
+                    position: None,
+                },
+                SourceMapEntry {
+                    offset: 125,
+                    position: Some(position(6, 0)),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn source_map_round_trips() {
+        let source_map = source_map();
+        assert_eq!(SourceMap::decode(&source_map.encode()).unwrap(), source_map);
+    }
+
+    #[test]
+    fn lookup_finds_the_enclosing_entry() {
+        let source_map = source_map();
+        assert_eq!(source_map.lookup(99), None);
+        assert_eq!(source_map.lookup(100), Some(position(1, 1)));
+        assert_eq!(source_map.lookup(119), Some(position(2, 5)));
+        assert_eq!(source_map.lookup(121), None);
+        assert_eq!(source_map.lookup(4000), Some(position(6, 0)));
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let mut data = source_map().encode();
+        data[0] = 2;
+        let error = SourceMap::decode(&data).unwrap_err();
+        assert!(error.to_string().contains("version 2"), "{error}");
+    }
+
+    #[test]
+    fn rows_become_sorted_entries_at_module_offsets() {
+        let rows = vec![
+            (30, None),
+            (10, Some(position(2, 5))),
+            (0, Some(position(1, 1))),
+            (12, Some(position(2, 5))),
+            (30, Some(position(6, 1))),
+            (40, None),
+        ];
+        assert_eq!(
+            entries(rows, 100).unwrap(),
+            vec![
+                SourceMapEntry {
+                    offset: 100,
+                    position: Some(position(1, 1)),
+                },
+                SourceMapEntry {
+                    offset: 110,
+                    position: Some(position(2, 5)),
+                },
+                SourceMapEntry {
+                    offset: 130,
+                    position: Some(position(6, 1)),
+                },
+                SourceMapEntry {
+                    offset: 140,
+                    position: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Shadow stack overflow checks.
+//!
+//! Locals whose address is taken, struct and array values and the copies of aggregate
+//! arguments live in stack slots on the shadow stack in linear memory, see
+//! [`compiler::memory`](crate::compiler). Both linkers place the stack at the start of
+//! memory, below the static data, and the stack pointer global starts at its top. Every
+//! function with a frame moves the stack pointer down by the size of its frame in its
+//! prologue and restores it in its epilogue.
+//!
+//! Nothing stops the stack pointer from moving below the stack, so deep recursion would
+//! silently run over whatever lies there. With [`StackChecks::Trap`] every `global.set` of
+//! the stack pointer in the linked module is replaced by a call to a function appended to
+//! the module, which traps if the new value lies outside the stack and sets the global
+//! otherwise. Both linkers encode the global index as a padded 5-byte LEB128, so the call
+//! has the same length and no code moves: the DWARF line tables and the source map stay
+//! valid. Leaf functions address their frame without updating the global; since the stack
+//! starts at address 0, their overflowing frames wrap around past the end of memory and
+//! trap with an out-of-bounds access.
+//!
+//! The stack pointer is the first mutable global of the linked module. Modules without one,
+//! such as those of the encoder backend, which keeps every local in a WebAssembly local,
+//! are left unchanged.
+
+use std::ops::Range;
+
+use inf_wasmparser::{ConstExpr as ParsedConstExpr, Operator, Parser, Payload, TypeRef};
+use wasm_encoder::{BlockType, Encode, Function, Instruction, Module, RawSection, SectionId};
+
+use crate::options::{CodegenOptions, StackChecks};
+
+/// Entries of a section, copied unchanged after the new count.
+#[derive(Default)]
+struct Entries {
+    count: u32,
+    range: Range<usize>,
+}
+
+/// The stack pointer global and the bounds of the stack.
+struct StackPointer {
+    index: u32,
+    memory64: bool,
+    top: u64,
+}
+
+/// What [`add_checks`] needs to know about a module.
+#[derive(Default)]
+struct Layout {
+    imported_functions: u32,
+    types: Entries,
+    functions: Entries,
+    code: Entries,
+    stack_pointer: Option<StackPointer>,
+    /// Immediates of the `global.set` instructions of the stack pointer.
+    updates: Vec<Range<usize>>,
+    has_dwarf: bool,
+}
+
+/// Adds a stack bounds check to every update of the stack pointer in `wasm`, unless
+/// [`CodegenOptions::stack_checks`] is [`StackChecks::Unchecked`].
+///
+/// # Errors
+///
+/// Returns an error if the module cannot be parsed, if an update of the stack pointer is
+/// too short to hold the call, or if appending the check function would move the code that
+/// the debug information of the module refers to.
+pub(crate) fn add_checks(wasm: Vec<u8>, options: &CodegenOptions) -> anyhow::Result<Vec<u8>> {
+    if options.stack_checks == StackChecks::Unchecked {
+        return Ok(wasm);
+    }
+    let Layout {
+        imported_functions,
+        types,
+        functions,
+        code,
+        stack_pointer,
+        updates,
+        has_dwarf,
+    } = layout(&wasm)?;
+    let Some(stack_pointer) = stack_pointer else {
+        return Ok(wasm);
+    };
+    if updates.is_empty() {
+        return Ok(wasm);
+    }
+
+    if has_dwarf && leb128_len(code.count + 1) != leb128_len(code.count) {
+        anyhow::bail!(
+            "cannot add stack checks to a module with debug information and {} functions; \
+             disable the stack checks",
+            code.count
+        );
+    }
+
+    let check_index = imported_functions + functions.count;
+    let mut wasm = wasm;
+    for update in updates {
+        wasm[update.start - 1] = 0x10; // call
+        write_padded(&mut wasm[update], check_index)?;
+    }
+
+    let address_type = if stack_pointer.memory64 {
+        wasm_encoder::ValType::I64
+    } else {
+        wasm_encoder::ValType::I32
+    };
+    // A function type: `0x60` and the vectors of parameter and result types.
+    let mut check_type = vec![0x60, 1];
+    address_type.encode(&mut check_type);
+    check_type.push(0);
+    let type_section = append(&wasm, &types, &check_type);
+    let mut check_type_index = Vec::new();
+    types.count.encode(&mut check_type_index);
+    let function_section = append(&wasm, &functions, &check_type_index);
+    let mut check_body = Vec::new();
+    check_function(&stack_pointer, options.stack_size).encode(&mut check_body);
+    let code_section = append(&wasm, &code, &check_body);
+
+    let mut module = Module::new();
+    for payload in Parser::new(0).parse_all(&wasm) {
+        let payload = payload?;
+        let Some((id, range)) = payload.as_section() else {
+            continue;
+        };
+        let data = match id {
+            id if id == SectionId::Type as u8 => &type_section,
+            id if id == SectionId::Function as u8 => &function_section,
+            id if id == SectionId::Code as u8 => &code_section,
+            _ => &wasm[range],
+        };
+        module.section(&RawSection { id, data });
+    }
+    Ok(module.finish())
+}
+
+/// Parses the sections of `wasm` that [`add_checks`] rewrites and finds the updates of the
+/// stack pointer.
+fn layout(wasm: &[u8]) -> anyhow::Result<Layout> {
+    let mut layout = Layout::default();
+    let mut imported_globals = 0;
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::TypeSection(reader) => {
+                layout.types = entries(reader.count(), reader.range())?;
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    match import?.ty {
+                        TypeRef::Func(_) => layout.imported_functions += 1,
+                        TypeRef::Global(_) => imported_globals += 1,
+                        _ => {}
+                    }
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                layout.functions = entries(reader.count(), reader.range())?;
+            }
+            Payload::GlobalSection(reader) => {
+                for (index, global) in (imported_globals..).zip(reader) {
+                    let global = global?;
+                    if global.ty.mutable {
+                        layout.stack_pointer =
+                            initial_value(&global.init_expr).map(|(top, memory64)| StackPointer {
+                                index,
+                                memory64,
+                                top,
+                            });
+                        break;
+                    }
+                }
+            }
+            Payload::CodeSectionStart { count, range, .. } => {
+                layout.code = entries(count, range)?;
+            }
+            Payload::CodeSectionEntry(body) => {
+                let Some(stack_pointer) = &layout.stack_pointer else {
+                    continue;
+                };
+                let mut reader = body.get_operators_reader()?;
+                while !reader.eof() {
+                    let (operator, offset) = reader.read_with_offset()?;
+                    if let Operator::GlobalSet { global_index } = operator
+                        && global_index == stack_pointer.index
+                    {
+                        layout.updates.push(offset + 1..reader.original_position());
+                    }
+                }
+            }
+            Payload::CustomSection(reader) => {
+                layout.has_dwarf |= reader.name().starts_with(".debug_");
+            }
+            _ => {}
+        }
+    }
+    Ok(layout)
+}
+
+/// Returns the entries of a section whose contents span `range`, which follow the count.
+fn entries(count: u32, range: Range<usize>) -> anyhow::Result<Entries> {
+    let start = range.start + leb128_len(count);
+    anyhow::ensure!(
+        start <= range.end,
+        "malformed section at offset {}",
+        range.start
+    );
+    Ok(Entries {
+        count,
+        range: start..range.end,
+    })
+}
+
+/// Returns the contents of a section holding `entries` of `wasm` followed by `entry`.
+fn append(wasm: &[u8], entries: &Entries, entry: &[u8]) -> Vec<u8> {
+    let mut section = Vec::new();
+    (entries.count + 1).encode(&mut section);
+    section.extend_from_slice(&wasm[entries.range.clone()]);
+    section.extend_from_slice(entry);
+    section
+}
+
+/// Returns the value and width of the `i32.const` or `i64.const` initializing a global.
+fn initial_value(init_expr: &ParsedConstExpr) -> Option<(u64, bool)> {
+    match init_expr.get_operators_reader().read().ok()? {
+        Operator::I32Const { value } => Some((u64::from(value.cast_unsigned()), false)),
+        Operator::I64Const { value } => Some((value.cast_unsigned(), true)),
+        _ => None,
+    }
+}
+
+/// Builds the function replacing `global.set` of the stack pointer: it traps unless the new
+/// stack pointer lies within the `stack_size` bytes below the initial one.
+fn check_function(stack_pointer: &StackPointer, stack_size: u64) -> Function {
+    let bottom = stack_pointer.top.saturating_sub(stack_size);
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    let constant = |value: u64| {
+        if stack_pointer.memory64 {
+            Instruction::I64Const(value as i64)
+        } else {
+            Instruction::I32Const(value as u32 as i32)
+        }
+    };
+    let (less, greater) = if stack_pointer.memory64 {
+        (Instruction::I64LtU, Instruction::I64GtU)
+    } else {
+        (Instruction::I32LtU, Instruction::I32GtU)
+    };
+    let mut function = Function::new([]);
+    function
+        .instruction(&Instruction::LocalGet(0))
+        .instruction(&constant(bottom))
+        .instruction(&less)
+        .instruction(&Instruction::LocalGet(0))
+        .instruction(&constant(stack_pointer.top))
+        .instruction(&greater)
+        .instruction(&Instruction::I32Or)
+        .instruction(&Instruction::If(BlockType::Empty))
+        .instruction(&Instruction::Unreachable)
+        .instruction(&Instruction::End)
+        .instruction(&Instruction::LocalGet(0))
+        .instruction(&Instruction::GlobalSet(stack_pointer.index))
+        .instruction(&Instruction::End);
+    function
+}
+
+/// Writes `value` as an unsigned LEB128 padded to exactly the length of `bytes`.
+fn write_padded(bytes: &mut [u8], value: u32) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        leb128_len(value) <= bytes.len(),
+        "stack pointer update too short to call function {value}"
+    );
+    let mut value = value;
+    let last = bytes.len() - 1;
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        // Truncation keeps the low seven bits.
+        #[allow(clippy::cast_possible_truncation)]
+        let bits = (value & 0x7f) as u8;
+        *byte = if index == last { bits } else { bits | 0x80 };
+        value >>= 7;
+    }
+    Ok(())
+}
+
+/// Returns the length of the shortest LEB128 encoding of `value`.
+fn leb128_len(value: u32) -> usize {
+    let mut bytes = Vec::new();
+    value.encode(&mut bytes);
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_encoder::{
+        CodeSection, ConstExpr, FunctionSection, GlobalSection, GlobalType, TypeSection, ValType,
+    };
+
+    /// Builds a module whose only function moves the stack pointer down by 16 bytes and
+    /// back, with the global index padded like a relocated one.
+    fn module(with_stack_pointer: bool) -> Vec<u8> {
+        let mut module = Module::new();
+        let mut types = TypeSection::new();
+        types.ty().function([], []);
+        module.section(&types);
+        let mut functions = FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+        let mut function = Function::new([]);
+        if with_stack_pointer {
+            let mut globals = GlobalSection::new();
+            globals.global(
+                GlobalType {
+                    val_type: ValType::I32,
+                    mutable: true,
+                    shared: false,
+                },
+                &ConstExpr::i32_const(1024),
+            );
+            module.section(&globals);
+            for delta in [-16, 16] {
+                function
+                    .instruction(&Instruction::GlobalGet(0))
+                    .instruction(&Instruction::I32Const(delta))
+                    .instruction(&Instruction::I32Add)
+                    .raw([0x24, 0x80, 0x80, 0x80, 0x80, 0x00]);
+            }
+        }
+        function.instruction(&Instruction::End);
+        let mut code = CodeSection::new();
+        code.function(&function);
+        module.section(&code);
+        module.finish()
+    }
+
+    #[test]
+    fn updates_call_the_check() {
+        let wasm = module(true);
+        let checked = add_checks(wasm.clone(), &CodegenOptions::default()).unwrap();
+        inf_wasmparser::validate(&checked).unwrap();
+        let layout = layout(&checked).unwrap();
+        assert_eq!(layout.types.count, 2);
+        assert_eq!(layout.functions.count, 2);
+        assert_eq!(layout.code.count, 2);
+        // The only update left is the one in the check function.
+        assert_eq!(layout.updates.len(), 1);
+        let call = [0x10, 0x81, 0x80, 0x80, 0x80, 0x00];
+        assert_eq!(checked.windows(6).filter(|bytes| *bytes == call).count(), 2);
+        // The code of the original function did not move within the code section.
+        assert_eq!(first_body(&checked), first_body(&wasm));
+    }
+
+    /// Returns the range of the first function body, relative to the code section.
+    fn first_body(wasm: &[u8]) -> Range<usize> {
+        let code_start = layout(wasm).unwrap().code.range.start;
+        Parser::new(0)
+            .parse_all(wasm)
+            .find_map(|payload| match payload.unwrap() {
+                Payload::CodeSectionEntry(body) => Some(body.range()),
+                _ => None,
+            })
+            .map(|range| range.start - code_start..range.end - code_start)
+            .unwrap()
+    }
+
+    #[test]
+    fn modules_without_updates_are_unchanged() {
+        let options = CodegenOptions::default();
+        let wasm = module(false);
+        assert_eq!(add_checks(wasm.clone(), &options).unwrap(), wasm);
+        let options = CodegenOptions {
+            stack_checks: StackChecks::Unchecked,
+            ..options
+        };
+        let wasm = module(true);
+        assert_eq!(add_checks(wasm.clone(), &options).unwrap(), wasm);
+    }
+
+    #[test]
+    fn padded_leb128() {
+        let mut bytes = [0; 5];
+        write_padded(&mut bytes, 300).unwrap();
+        assert_eq!(bytes, [0xac, 0x82, 0x80, 0x80, 0x00]);
+        assert!(write_padded(&mut bytes[..1], 300).is_err());
+    }
+}
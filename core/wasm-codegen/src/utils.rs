@@ -1,18 +1,17 @@
-//! Utility functions for WebAssembly compilation via external LLVM toolchain.
+//! Utility functions for WebAssembly object emission and linking.
 //!
-//! This module handles the invocation of external compilation tools (inf-llc and rust-lld)
-//! to transform LLVM IR into WebAssembly bytecode. It manages temporary file creation,
-//! toolchain location, and platform-specific environment configuration.
+//! This module turns an LLVM module into WebAssembly bytecode. Object code is emitted
+//! in-process through an LLVM target machine; linking is delegated to the external rust-lld
+//! binary or, with [`Linker::Builtin`], to the in-process [`linker`]. It manages temporary file creation, toolchain location, and platform-specific
+//! environment configuration.
 //!
 //! # External Dependencies
 //!
-//! The compilation process requires two external binaries:
-//!
-//! - **inf-llc** - Modified LLVM compiler with support for Inference's custom non-deterministic
-//!   intrinsics. This is a fork of LLVM's llc tool.
 //! - **rust-lld** - WebAssembly linker from the Rust toolchain, specifically the wasm-ld flavor.
+//!   It must be available in the `bin/` directory relative to the executable.
 //!
-//! These binaries must be available in the `bin/` directory relative to the executable.
+//! Object emission relies on the forked LLVM that inkwell is linked against, which lowers
+//! Inference's custom non-deterministic intrinsics.
 //!
 //! # Platform Considerations
 //!
@@ -22,30 +21,35 @@
 //!
 //! # Compilation Pipeline
 //!
-//! ## Stage 1: IR Emission
-//!
-//! The LLVM module is serialized to a temporary `.ll` file (LLVM IR text format).
-//! The module is configured with the `wasm32-unknown-unknown` target triple.
+//! ## Stage 1: Object Emission
 //!
-//! ## Stage 2: Object Compilation (inf-llc)
-//!
-//! The inf-llc compiler processes the IR file with these arguments:
-//! - `-mcpu=mvp` - Target WebAssembly MVP (Minimum Viable Product) feature set
-//! - `-filetype=obj` - Output object file format
-//! - `-O{0-3}` - Optimization level (0=none, 3=aggressive)
+//! The module is configured with the `wasm32-unknown-unknown` target triple, or
+//! `wasm64-unknown-unknown` with [`CodegenOptions::memory64`], and handed to an LLVM target
+//! machine with these settings:
+//! - CPU `mvp` - Target WebAssembly MVP (Minimum Viable Product) feature set
+//! - Features from [`CodegenOptions::features`]
+//! - Optimization level from [`CodegenOptions::opt_level`] (0=none, 3=aggressive)
 //!
 //! Output: `.o` WebAssembly object file
 //!
-//! ## Stage 3: Linking (rust-lld)
+//! ## Stage 2: Linking (rust-lld)
 //!
 //! The rust-lld linker combines the object file into a final WebAssembly module:
 //! - `-flavor wasm` - Use WebAssembly linker mode
 //! - `--no-entry` - Reactor model (no implicit `_start` function)
 //! - `--export=main` - Explicitly export `main` function if present
+//! - `--stack-first` - Place the stack below the static data, so an overflowing stack
+//!   pointer wraps around and traps instead of running over the data
+//! - `-mwasm64` - 64-bit linear memory with [`CodegenOptions::memory64`]
+//! - `-z stack-size`, `--initial-memory`, `--max-memory` - Stack and memory sizes from the
+//!   options
 //!
 //! Output: `.wasm` WebAssembly module
 //!
-//! ## Stage 4: Cleanup
+//! With [`Linker::Builtin`] the object is kept in memory and linked by [`linker::link`]
+//! instead, which reproduces the same memory layout and exports without rust-lld.
+//!
+//! ## Stage 3: Cleanup
 //!
 //! Read the final WASM bytes and remove temporary files. The WASM module is returned
 //! as a byte vector.
@@ -116,26 +120,36 @@
 
 use std::{path::PathBuf, process::Command};
 
-use inkwell::{module::Module, targets::TargetTriple};
+use inkwell::{
+    module::Module,
+    targets::{CodeModel, FileType, RelocMode, Target, TargetMachine, TargetTriple},
+};
 use tempfile::tempdir;
 
-/// Compiles an LLVM module to WebAssembly bytecode via external toolchain.
+use crate::{
+    linker,
+    options::{CodegenOptions, Linker, PAGE_SIZE},
+};
+
+/// Compiles an LLVM module to WebAssembly bytecode.
 ///
 /// This function orchestrates the complete compilation pipeline from LLVM IR to WASM,
-/// handling temporary file management and tool invocation.
+/// handling temporary file management and linker invocation.
 ///
 /// # Compilation Stages
 ///
-/// 1. **IR emission** - Write LLVM module to temporary `.ll` file
-/// 2. **Object compilation** - Invoke inf-llc with target wasm32-unknown-unknown
-/// 3. **Linking** - Invoke rust-lld with wasm flavor to produce final module
-/// 4. **Cleanup** - Read WASM bytes and remove temporary object file
+/// 1. **Object emission** - Emit a wasm32-unknown-unknown object file in-process through an
+///    LLVM target machine
+/// 2. **Linking** - Invoke rust-lld with wasm flavor to produce final module, or link
+///    in-process when [`CodegenOptions::linker`] is [`Linker::Builtin`]
+/// 3. **Cleanup** - Read WASM bytes and remove temporary object file
 ///
 /// # Parameters
 ///
 /// - `module` - LLVM module containing the IR to compile
+/// - `target_machine` - Target machine created by [`create_target_machine`] for `module`
 /// - `output_fname` - Base filename for intermediate files (extensions added automatically)
-/// - `optimization_level` - LLVM optimization level (0-3, clamped to max 3)
+/// - `options` - Optimization level (clamped to max 3), target features and debug info
 /// - `has_main` - Whether to export a `main` function (only if the module contains one)
 ///
 /// # Returns
@@ -145,72 +159,111 @@ use tempfile::tempdir;
 /// # Errors
 ///
 /// Returns an error if:
-/// - Required binaries (inf-llc, rust-lld) are not found
-/// - Compilation or linking fails (non-zero exit status)
+/// - LLVM fails to emit the object file
+/// - rust-lld is not found or linking fails (non-zero exit status)
+/// - The builtin linker rejects the object file, e.g. because of an undefined symbol
 /// - File I/O operations fail
 /// - Temporary directory creation fails
 ///
 /// # Error Details
 ///
-/// When compilation or linking fails, the error message includes:
-/// - Exit status of the failed tool
-/// - Complete stderr output from the tool
+/// When linking fails, the error message includes:
+/// - Exit status of the linker
+/// - Complete stderr output from the linker
 ///
-/// This information is essential for debugging LLVM IR issues or linker problems.
 /// Common failures include:
-/// - Invalid LLVM IR syntax
 /// - Undefined references in linking
 /// - Unsupported WebAssembly features
 ///
 /// # Example Error Messages
 ///
 /// ```text
-/// Error: inf-llc failed with status: exit status: 1
-/// stderr: <input>:5:10: error: expected value token
-///   ret i32 %invalid_var
-///           ^
+/// Error: LLVM object emission failed: Cannot select: intrinsic %llvm.wasm.uzumaki
 /// ```
 ///
 /// ```text
 /// Error: rust-lld failed with status: exit status: 1
 /// stderr: wasm-ld: error: undefined symbol: external_function
 /// ```
-#[allow(clippy::similar_names)]
 pub(crate) fn compile_to_wasm(
     module: &Module,
+    target_machine: &TargetMachine,
     output_fname: &str,
-    optimization_level: u32,
+    options: &CodegenOptions,
     has_main: bool,
 ) -> anyhow::Result<Vec<u8>> {
-    let llc_path = get_inf_llc_path()?;
-    let temp_dir = tempdir()?;
-    let obj_path = temp_dir.path().join(output_fname).with_extension("o");
-    let ir_path = temp_dir.path().join(output_fname).with_extension("ll");
-    let triple = TargetTriple::create("wasm32-unknown-unknown");
-    module.set_triple(&triple);
-    let ir_str = module.print_to_string().to_string();
-    std::fs::write(&ir_path, ir_str)?;
-    let opt_flag = format!("-O{}", optimization_level.min(3));
-    let mut llc_cmd = Command::new(&llc_path);
-    configure_llvm_env(&mut llc_cmd)?;
-    let output = llc_cmd
-        // .arg("-march=wasm32") // same as triple
-        .arg("-mcpu=mvp")
-        // .arg("-mattr=+mutable-globals") // https://doc.rust-lang.org/beta/rustc/platform-support/wasm32v1-none.html
-        .arg("-filetype=obj")
-        .arg(&ir_path)
-        .arg(&opt_flag)
-        .arg("-o")
-        .arg(&obj_path)
-        .output()?;
+    let object = emit_object(module, target_machine)?;
+    link_objects(&[object], output_fname, options, has_main)
+}
 
-    if !output.status.success() {
-        return Err(anyhow::anyhow!(
-            "inf-llc failed with status: {}\nstderr: {}",
-            output.status,
-            String::from_utf8_lossy(&output.stderr)
-        ));
+/// Emits `module` as a WebAssembly object file in memory.
+///
+/// # Errors
+///
+/// Returns an error if LLVM fails to emit the object file.
+pub(crate) fn emit_object(
+    module: &Module,
+    target_machine: &TargetMachine,
+) -> anyhow::Result<Vec<u8>> {
+    let object = target_machine
+        .write_to_memory_buffer(module, FileType::Object)
+        .map_err(|e| anyhow::anyhow!("LLVM object emission failed: {e}"))?;
+    Ok(object.as_slice().to_vec())
+}
+
+/// Links object files into the final WebAssembly module with the linker selected by
+/// [`CodegenOptions::linker`].
+///
+/// Objects are passed to the linker in the given order, which decides the order of their
+/// functions in the module.
+///
+/// # Errors
+///
+/// Returns an error if linking fails, or if the builtin linker is given more than one
+/// object file.
+pub(crate) fn link_objects(
+    objects: &[Vec<u8>],
+    output_fname: &str,
+    options: &CodegenOptions,
+    has_main: bool,
+) -> anyhow::Result<Vec<u8>> {
+    match options.linker {
+        Linker::Builtin => {
+            let [object] = objects else {
+                anyhow::bail!(
+                    "the builtin linker links a single object file, found {}",
+                    objects.len()
+                );
+            };
+            linker::link(object, has_main, options)
+        }
+        Linker::RustLld => link_with_rust_lld(objects, output_fname, options, has_main),
     }
+}
+
+/// Writes the object files to a temporary directory and links them with rust-lld.
+///
+/// # Errors
+///
+/// Returns an error if an object file cannot be written, rust-lld is not found or linking
+/// fails.
+fn link_with_rust_lld(
+    objects: &[Vec<u8>],
+    output_fname: &str,
+    options: &CodegenOptions,
+    has_main: bool,
+) -> anyhow::Result<Vec<u8>> {
+    let temp_dir = tempdir()?;
+    let mut obj_paths = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        let obj_path = temp_dir
+            .path()
+            .join(output_fname)
+            .with_extension(format!("{index}.o"));
+        std::fs::write(&obj_path, object)?;
+        obj_paths.push(obj_path);
+    }
+
     let rust_lld_path = get_rust_lld_path()?;
     let wasm_path = temp_dir.path().join(output_fname).with_extension("wasm");
     let mut lld_cmd = Command::new(&rust_lld_path);
@@ -218,11 +271,27 @@ pub(crate) fn compile_to_wasm(
     lld_cmd
         .arg("-flavor")
         .arg("wasm")
-        .arg(&obj_path)
-        .arg("--no-entry");
+        .args(&obj_paths)
+        .arg("--no-entry")
+        .arg("--stack-first");
     if has_main {
         lld_cmd.arg("--export=main");
     }
+    if !options.emits_dwarf() {
+        lld_cmd.arg("--strip-debug");
+    }
+    if options.memory64 {
+        lld_cmd.arg("-mwasm64");
+    }
+    lld_cmd
+        .arg("-z")
+        .arg(format!("stack-size={}", options.stack_size));
+    if let Some(pages) = options.initial_memory_pages {
+        lld_cmd.arg(format!("--initial-memory={}", memory_bytes(pages)));
+    }
+    if let Some(pages) = options.max_memory_pages {
+        lld_cmd.arg(format!("--max-memory={}", memory_bytes(pages)));
+    }
     let wasm_lld_output = lld_cmd.arg("-o").arg(&wasm_path).output()?;
 
     if !wasm_lld_output.status.success() {
@@ -234,28 +303,47 @@ pub(crate) fn compile_to_wasm(
     }
 
     let wasm_bytes = std::fs::read(&wasm_path)?;
-    std::fs::remove_file(obj_path)?;
     Ok(wasm_bytes)
 }
 
-/// Locates the inf-llc binary required for compilation.
-///
-/// This function searches for the inf-llc executable in platform-specific locations
-/// relative to the current executable. It handles both regular builds and test builds
-/// (where the executable is in a `deps/` subdirectory).
+/// Returns the size in bytes of a memory of `pages` pages, which exceeds `u64` for the
+/// largest 64-bit memories.
+fn memory_bytes(pages: u64) -> u128 {
+    u128::from(pages) * u128::from(PAGE_SIZE)
+}
+
+/// Creates the LLVM target machine that emits WebAssembly objects for `module`.
 ///
-/// # Returns
+/// The module is retargeted to `wasm32-unknown-unknown`, or to `wasm64-unknown-unknown`
+/// with [`CodegenOptions::memory64`], and compiled for the MVP CPU with the extra features
+/// from `options`. The non-deterministic intrinsics are lowered by the
+/// LLVM fork that inkwell is linked against, so no external `llc` is involved.
 ///
-/// Absolute path to the inf-llc executable
+/// The WebAssembly target must have been initialized with
+/// [`Target::initialize_webassembly`] beforehand.
 ///
 /// # Errors
 ///
-/// Returns an error if inf-llc is not found in any of the expected locations.
-pub(crate) fn get_inf_llc_path() -> anyhow::Result<std::path::PathBuf> {
-    get_bin_path(
-        "inf-llc",
-        "This package requires LLVM with Inference intrinsics support.",
-    )
+/// Returns an error if the target or target machine cannot be created.
+pub(crate) fn create_target_machine(
+    module: &Module,
+    options: &CodegenOptions,
+) -> anyhow::Result<TargetMachine> {
+    let triple = TargetTriple::create(options.target_triple());
+    module.set_triple(&triple);
+    let target = Target::from_triple(&triple)
+        .map_err(|e| anyhow::anyhow!("Failed to look up the WebAssembly target: {e}"))?;
+    // e.g. +mutable-globals, see https://doc.rust-lang.org/beta/rustc/platform-support/wasm32v1-none.html
+    target
+        .create_target_machine(
+            &triple,
+            "mvp",
+            &options.llvm_features(),
+            options.llvm_opt_level(),
+            RelocMode::Default,
+            CodeModel::Default,
+        )
+        .ok_or_else(|| anyhow::anyhow!("Failed to create the WebAssembly target machine"))
 }
 
 /// Locates the rust-lld binary required for linking.
@@ -289,7 +377,7 @@ pub(crate) fn get_rust_lld_path() -> anyhow::Result<PathBuf> {
 ///
 /// # Parameters
 ///
-/// - `bin_name` - Name of the binary without extension (e.g., "inf-llc")
+/// - `bin_name` - Name of the binary without extension (e.g., "rust-lld")
 /// - `not_found_message` - Error message to display if binary is not found
 ///
 /// # Returns
@@ -351,7 +439,7 @@ fn get_bin_path(bin_name: &str, not_found_message: &str) -> anyhow::Result<PathB
 /// Locates the LLVM shared library directory on Linux.
 ///
 /// On Linux, the LLVM shared libraries (libLLVM.so.*) must be available for the
-/// external toolchain binaries (inf-llc, rust-lld) to function. This function
+/// external toolchain binaries (rust-lld) to function. This function
 /// searches for the library directory relative to the current executable.
 ///
 /// # Search Strategy
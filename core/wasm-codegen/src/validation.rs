@@ -0,0 +1,170 @@
+//! Validation of the linked module against the enabled WebAssembly proposals.
+//!
+//! The LLVM backend targets the MVP CPU, so it only uses the instructions of a post-MVP
+//! proposal when the matching target feature is enabled through
+//! [`CodegenOptions::target_features`] or [`CodegenOptions::features`]. The linked module
+//! is validated against exactly these proposals, which catches a module that a runtime
+//! supporting only the requested feature set would reject.
+
+use anyhow::bail;
+use inf_wasmparser::{Validator, WasmFeatures};
+
+use crate::{errors::InvalidModule, options::CodegenOptions};
+
+/// LLVM target features known to the code generator and the proposals they enable.
+const LLVM_FEATURES: [(&str, WasmFeatures); 17] = [
+    ("atomics", WasmFeatures::THREADS),
+    ("bulk-memory", WasmFeatures::BULK_MEMORY),
+    ("bulk-memory-opt", WasmFeatures::BULK_MEMORY),
+    ("call-indirect-overlong", WasmFeatures::REFERENCE_TYPES),
+    ("exception-handling", WasmFeatures::EXCEPTIONS),
+    ("extended-const", WasmFeatures::EXTENDED_CONST),
+    ("gc", WasmFeatures::GC),
+    ("multimemory", WasmFeatures::MULTI_MEMORY),
+    ("multivalue", WasmFeatures::MULTI_VALUE),
+    ("mutable-globals", WasmFeatures::MUTABLE_GLOBAL),
+    ("nontrapping-fptoint", WasmFeatures::SATURATING_FLOAT_TO_INT),
+    ("reference-types", WasmFeatures::REFERENCE_TYPES),
+    (
+        "relaxed-simd",
+        WasmFeatures::RELAXED_SIMD.union(WasmFeatures::SIMD),
+    ),
+    ("sign-ext", WasmFeatures::SIGN_EXTENSION),
+    ("simd128", WasmFeatures::SIMD),
+    ("tail-call", WasmFeatures::TAIL_CALL),
+    ("wide-arithmetic", WasmFeatures::WIDE_ARITHMETIC),
+];
+
+/// Checks that every feature in [`CodegenOptions::features`] is a known LLVM target feature.
+///
+/// # Errors
+///
+/// Returns an error naming the first unknown feature.
+pub(crate) fn check_features(options: &CodegenOptions) -> anyhow::Result<()> {
+    for feature in &options.features {
+        let name = feature.trim_start_matches('+');
+        if !LLVM_FEATURES.iter().any(|(known, _)| *known == name) {
+            bail!("Unknown WebAssembly target feature `{name}`");
+        }
+    }
+    Ok(())
+}
+
+/// Returns the proposals a module compiled with `options` may use.
+pub(crate) fn wasm_features(options: &CodegenOptions) -> WasmFeatures {
+    let mut features = WasmFeatures::WASM1;
+    if options.memory64 {
+        features |= WasmFeatures::MEMORY64;
+    }
+    for name in options.llvm_features().split(',') {
+        let name = name.trim_start_matches('+');
+        if let Some((_, proposals)) = LLVM_FEATURES.iter().find(|(known, _)| *known == name) {
+            features |= *proposals;
+        }
+    }
+    features
+}
+
+/// Validates `wasm_bytes` against the proposals enabled by `options`. The validator of
+/// `inf-wasmparser` also checks the non-deterministic instructions.
+///
+/// # Errors
+///
+/// Returns an [`InvalidModule`] error if the module uses a proposal that is not enabled or
+/// is otherwise invalid.
+pub(crate) fn validate(wasm_bytes: &[u8], options: &CodegenOptions) -> Result<(), InvalidModule> {
+    Validator::new_with_features(wasm_features(options))
+        .validate_all(wasm_bytes)
+        .map_err(|e| InvalidModule {
+            message: e.message().to_string(),
+            offset: e.offset(),
+        })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::TargetFeatures;
+
+    #[test]
+    fn default_options_allow_only_wasm1() {
+        assert_eq!(
+            wasm_features(&CodegenOptions::default()),
+            WasmFeatures::WASM1
+        );
+    }
+
+    #[test]
+    fn toggles_enable_their_proposals() {
+        let options = CodegenOptions {
+            target_features: TargetFeatures {
+                bulk_memory: true,
+                sign_ext: true,
+                nontrapping_fptoint: true,
+                simd: true,
+                tail_call: true,
+            },
+            memory64: true,
+            ..CodegenOptions::default()
+        };
+        let features = wasm_features(&options);
+        for proposal in [
+            WasmFeatures::BULK_MEMORY,
+            WasmFeatures::SIGN_EXTENSION,
+            WasmFeatures::SATURATING_FLOAT_TO_INT,
+            WasmFeatures::SIMD,
+            WasmFeatures::TAIL_CALL,
+            WasmFeatures::MEMORY64,
+        ] {
+            assert!(features.contains(proposal), "{proposal:?}");
+        }
+        assert!(!features.contains(WasmFeatures::THREADS));
+    }
+
+    #[test]
+    fn extra_features_enable_their_proposals() {
+        let options = CodegenOptions {
+            features: vec!["+multivalue".to_string()],
+            ..CodegenOptions::default()
+        };
+        assert!(check_features(&options).is_ok());
+        assert!(wasm_features(&options).contains(WasmFeatures::MULTI_VALUE));
+    }
+
+    #[test]
+    fn invalid_module_reports_its_offset() {
+        // A function of type `() -> i32` whose body is `i32.add` on an empty stack.
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0a, 0x05, 0x01, 0x03, 0x00, 0x6a, 0x0b, // code section
+        ];
+        let error = validate(&wasm, &CodegenOptions::default()).unwrap_err();
+        assert_eq!(error.offset, 0x18);
+        assert!(error.message.contains("type mismatch"), "{error}");
+    }
+
+    #[test]
+    fn nondeterministic_instructions_are_valid() {
+        // A function of type `() -> i32` whose body is `i32.uzumaki`.
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7f, // type section
+            0x03, 0x02, 0x01, 0x00, // function section
+            0x0a, 0x06, 0x01, 0x04, 0x00, 0xfc, 0x31, 0x0b, // code section
+        ];
+        assert_eq!(validate(&wasm, &CodegenOptions::default()), Ok(()));
+    }
+
+    #[test]
+    fn unknown_features_are_rejected() {
+        let options = CodegenOptions {
+            features: vec!["sign-extension".to_string()],
+            ..CodegenOptions::default()
+        };
+        let error = check_features(&options).unwrap_err();
+        assert!(error.to_string().contains("sign-extension"), "{error}");
+    }
+}
@@ -0,0 +1,351 @@
+//! Verification metadata custom section.
+//!
+//! A module with at least one `forall`, `exists`, `assume` or `unique` block carries a
+//! custom section named [`SECTION_NAME`] listing the functions that contain these blocks,
+//! together with the source location of each block. Verification tooling such as the
+//! `wasm-to-v` translator reads it with [`VerificationMetadata::from_module`] to find the
+//! proof obligations of a module, instead of recognizing them from the instruction stream.
+//! A module without the section has no proof obligations.
+//!
+//! ## Encoding
+//!
+//! The section uses the conventions of the WebAssembly binary format: integers are
+//! unsigned LEB128 and strings are length-prefixed UTF-8.
+//!
+//! ```text
+//! section   ::= version:u32 functions:vec(function)
+//! function  ::= name:string export:(0x00 | 0x01 string) location blocks:vec(block)
+//! block     ::= kind:u8 location
+//! location  ::= offset_start:u32 offset_end:u32
+//!               start_line:u32 start_column:u32 end_line:u32 end_column:u32
+//! ```
+//!
+//! The block kinds are `0` for `forall`, `1` for `exists`, `2` for `assume` and `3` for
+//! `unique`. Blocks are listed in source order, so a block nested in another one follows
+//! it and lies within its byte range.
+
+use anyhow::bail;
+use inf_wasmparser::{BinaryReader, Parser, Payload};
+use inference_ast::nodes::{AstNode, BlockType, Location, Statement, Visibility};
+use inference_type_checker::typed_context::TypedContext;
+use wasm_encoder::{CustomSection, Encode, SectionId};
+
+use crate::options::CodegenOptions;
+
+/// Name of the verification metadata custom section.
+pub const SECTION_NAME: &str = "inference.verification";
+
+/// Version of the section encoding, bumped on every incompatible change.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Kind of a block that introduces a proof obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    /// `forall { }`: the block must hold for every non-deterministic value.
+    Forall,
+    /// `exists { }`: the block must hold for some non-deterministic value.
+    Exists,
+    /// `assume { }`: the block restricts the executions considered.
+    Assume,
+    /// `unique { }`: exactly one non-deterministic value satisfies the block.
+    Unique,
+}
+
+impl BlockKind {
+    /// Returns the keyword of the block.
+    #[must_use]
+    pub fn keyword(self) -> &'static str {
+        match self {
+            BlockKind::Forall => "forall",
+            BlockKind::Exists => "exists",
+            BlockKind::Assume => "assume",
+            BlockKind::Unique => "unique",
+        }
+    }
+
+    fn from_block(block: &BlockType) -> Option<Self> {
+        match block {
+            BlockType::Forall(_) => Some(BlockKind::Forall),
+            BlockType::Exists(_) => Some(BlockKind::Exists),
+            BlockType::Assume(_) => Some(BlockKind::Assume),
+            BlockType::Unique(_) => Some(BlockKind::Unique),
+            BlockType::Block(_) => None,
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            BlockKind::Forall => 0,
+            BlockKind::Exists => 1,
+            BlockKind::Assume => 2,
+            BlockKind::Unique => 3,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(BlockKind::Forall),
+            1 => Some(BlockKind::Exists),
+            2 => Some(BlockKind::Assume),
+            3 => Some(BlockKind::Unique),
+            _ => None,
+        }
+    }
+}
+
+/// A `forall`, `exists`, `assume` or `unique` block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationBlock {
+    pub kind: BlockKind,
+    /// Source location of the block, including its keyword.
+    pub location: Location,
+}
+
+/// A function containing at least one [`VerificationBlock`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedFunction {
+    /// Name of the function in the source.
+    pub name: String,
+    /// Name under which the function is exported, if it is exported.
+    pub export_name: Option<String>,
+    /// Source location of the function definition.
+    pub location: Location,
+    /// The blocks of the function in source order.
+    pub blocks: Vec<VerificationBlock>,
+}
+
+/// Contents of the verification metadata custom section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerificationMetadata {
+    /// The functions with verification blocks in source order.
+    pub functions: Vec<VerifiedFunction>,
+}
+
+impl VerificationMetadata {
+    /// Collects the verification blocks of every function definition in `typed_context`.
+    pub(crate) fn collect(typed_context: &TypedContext, options: &CodegenOptions) -> Self {
+        let mut blocks = typed_context
+            .filter_nodes(|node| {
+                matches!(node, AstNode::Statement(Statement::Block(block))
+                    if BlockKind::from_block(block).is_some())
+            })
+            .into_iter()
+            .filter_map(|node| match node {
+                AstNode::Statement(Statement::Block(block)) => Some(VerificationBlock {
+                    kind: BlockKind::from_block(&block)?,
+                    location: block.location(),
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.location.offset_start);
+
+        let mut functions = Vec::new();
+        for source_file in &typed_context.source_files() {
+            for function in source_file.function_definitions() {
+                let location = function.location;
+                let function_blocks = blocks
+                    .iter()
+                    .filter(|block| {
+                        block.location.offset_start >= location.offset_start
+                            && block.location.offset_end <= location.offset_end
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                if function_blocks.is_empty() {
+                    continue;
+                }
+                let name = function.name();
+                functions.push(VerifiedFunction {
+                    export_name: options
+                        .export_name(&name, function.visibility == Visibility::Public),
+                    name,
+                    location,
+                    blocks: function_blocks,
+                });
+            }
+        }
+        Self { functions }
+    }
+
+    /// Encodes the metadata as the contents of the custom section.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        FORMAT_VERSION.encode(&mut data);
+        self.functions.len().encode(&mut data);
+        for function in &self.functions {
+            function.name.encode(&mut data);
+            match &function.export_name {
+                Some(export_name) => {
+                    data.push(1);
+                    export_name.encode(&mut data);
+                }
+                None => data.push(0),
+            }
+            encode_location(function.location, &mut data);
+            function.blocks.len().encode(&mut data);
+            for block in &function.blocks {
+                data.push(block.kind.code());
+                encode_location(block.location, &mut data);
+            }
+        }
+        data
+    }
+
+    /// Decodes the contents of the custom section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is truncated, has trailing bytes, uses another format
+    /// version or contains an unknown block kind.
+    pub fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = BinaryReader::new(data, 0);
+        let version = reader.read_var_u32()?;
+        if version != FORMAT_VERSION {
+            bail!("Unsupported verification metadata version {version}");
+        }
+        let mut functions = Vec::new();
+        for _ in 0..reader.read_var_u32()? {
+            let name = reader.read_string()?.to_string();
+            let export_name = match reader.read_u8()? {
+                0 => None,
+                1 => Some(reader.read_string()?.to_string()),
+                flag => bail!("Invalid export flag {flag} in verification metadata"),
+            };
+            let location = decode_location(&mut reader)?;
+            let mut blocks = Vec::new();
+            for _ in 0..reader.read_var_u32()? {
+                let code = reader.read_u8()?;
+                let kind = BlockKind::from_code(code).ok_or_else(|| {
+                    anyhow::anyhow!("Unknown block kind {code} in verification metadata")
+                })?;
+                blocks.push(VerificationBlock {
+                    kind,
+                    location: decode_location(&mut reader)?,
+                });
+            }
+            functions.push(VerifiedFunction {
+                name,
+                export_name,
+                location,
+                blocks,
+            });
+        }
+        if !reader.eof() {
+            bail!("Trailing bytes after verification metadata");
+        }
+        Ok(Self { functions })
+    }
+
+    /// Reads the verification metadata of a WebAssembly module.
+    ///
+    /// Returns `None` if the module has no [`SECTION_NAME`] section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module cannot be parsed or the section cannot be decoded.
+    pub fn from_module(wasm: &[u8]) -> anyhow::Result<Option<Self>> {
+        for payload in Parser::new(0).parse_all(wasm) {
+            if let Payload::CustomSection(reader) = payload?
+                && reader.name() == SECTION_NAME
+            {
+                return Self::decode(reader.data()).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Appends the verification metadata section of `typed_context` to `wasm`, unless no
+/// function contains a verification block.
+pub(crate) fn add_section(
+    mut wasm: Vec<u8>,
+    typed_context: &TypedContext,
+    options: &CodegenOptions,
+) -> Vec<u8> {
+    let metadata = VerificationMetadata::collect(typed_context, options);
+    if metadata.functions.is_empty() {
+        return wasm;
+    }
+    let data = metadata.encode();
+    wasm.push(SectionId::Custom as u8);
+    CustomSection {
+        name: SECTION_NAME.into(),
+        data: data.into(),
+    }
+    .encode(&mut wasm);
+    wasm
+}
+
+fn encode_location(location: Location, data: &mut Vec<u8>) {
+    for value in [
+        location.offset_start,
+        location.offset_end,
+        location.start_line,
+        location.start_column,
+        location.end_line,
+        location.end_column,
+    ] {
+        value.encode(data);
+    }
+}
+
+fn decode_location(reader: &mut BinaryReader) -> anyhow::Result<Location> {
+    Ok(Location::new(
+        reader.read_var_u32()?,
+        reader.read_var_u32()?,
+        reader.read_var_u32()?,
+        reader.read_var_u32()?,
+        reader.read_var_u32()?,
+        reader.read_var_u32()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> VerificationMetadata {
+        VerificationMetadata {
+            functions: vec![VerifiedFunction {
+                name: "check".to_string(),
+                export_name: Some("check_all".to_string()),
+                location: Location::new(0, 120, 1, 0, 9, 1),
+                blocks: vec![
+                    VerificationBlock {
+                        kind: BlockKind::Forall,
+                        location: Location::new(30, 110, 2, 4, 8, 5),
+                    },
+                    VerificationBlock {
+                        kind: BlockKind::Assume,
+                        location: Location::new(50, 80, 3, 8, 5, 9),
+                    },
+                ],
+            }],
+        }
+    }
+
+    #[test]
+    fn metadata_round_trips() {
+        let metadata = metadata();
+        assert_eq!(
+            VerificationMetadata::decode(&metadata.encode()).unwrap(),
+            metadata
+        );
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        let mut data = metadata().encode();
+        data[0] = 2;
+        let error = VerificationMetadata::decode(&data).unwrap_err();
+        assert!(error.to_string().contains("version 2"), "{error}");
+    }
+
+    #[test]
+    fn truncated_metadata_is_rejected() {
+        let data = metadata().encode();
+        assert!(VerificationMetadata::decode(&data[..data.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,231 @@
+//! Target Rocq Library Configuration
+//!
+//! The translator emits Rocq code in the vocabulary of the `Wasm` library (WasmCert-Coq):
+//! it imports `bytes`, `numerics` and `datatypes` from `Wasm` and uses their constructor
+//! and record field names, such as `BI_const`, `VAL_int32` and `modfunc_body`.
+//! [`TranslationConfig`] retargets the generated `.v` files to a different Rocq
+//! formalization of WebAssembly, or to a project-local library that wraps one.
+//!
+//! ## Options
+//!
+//! - **Imports**: The vernacular lines at the top of the file, replacing the default
+//!   `Require Import` and `From Wasm Require Import` lines
+//! - **Constructor names**: Renames identifiers of the emitted code, such as `BI_const`
+//!   or `modfunc_body`. Only whole identifiers are renamed, and text inside string
+//!   literals, such as import and export names, is left unchanged
+//! - **Module prefix**: Wraps every generated definition in a Rocq `Module`, so the
+//!   definitions of several translated modules can live in one library without clashing.
+//!   For output split per function, it is the logical path the files require each other from
+//! - **Parameterized nondeterminism**: Declares the nondeterministic instructions in a
+//!   `Module Type` and wraps the definitions in a functor over it, so the semantics of
+//!   nondeterminism is chosen when the functor is applied, not in the generated file
+//! - **Source file**: The `.inf` file the module was compiled from, named in the coqdoc
+//!   comments of the module and its functions
+//! - **Function selection**: Translates the bodies of the selected functions only, by
+//!   name or export status, or of none of them with `interface_only`. The other functions
+//!   are declared as `Parameter`s, so the module record keeps its function indices and
+//!   proofs about the selected functions check faster on large modules
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use inference_wasm_to_v_translator::{
+//!     config::{FunctionSelection, TranslationConfig},
+//!     wasm_parser,
+//! };
+//!
+//! let config = TranslationConfig {
+//!     imports: vec!["From MyWasm Require Import syntax.".to_string()],
+//!     constructor_names: [("BI_const".to_string(), "I_const".to_string())].into(),
+//!     module_prefix: Some("Generated".to_string()),
+//!     parameterize_nondeterminism: false,
+//!     source_file: Some("src/main.inf".to_string()),
+//!     functions: FunctionSelection::Named(vec!["check".to_string()]),
+//!     interface_only: false,
+//! };
+//! let rocq_code = wasm_parser::translate_bytes_with_config("my_module", &wasm_bytes, &config)?;
+//! ```
+
+use std::{borrow::Cow, collections::HashMap};
+
+/// Describes the Rocq library the generated code targets.
+///
+/// The default targets the `Wasm` library, and produces the same output as the translator
+/// without a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationConfig {
+    /// Vernacular lines emitted at the top of the file, one per line, such as
+    /// `Require Import List.`.
+    pub imports: Vec<String>,
+
+    /// Maps identifiers of the emitted code to the names used by the target library.
+    /// Identifiers without an entry are emitted unchanged.
+    pub constructor_names: HashMap<String, String>,
+
+    /// Name of a Rocq `Module` wrapping the generated definitions, or `None` to emit them
+    /// at the top level of the file.
+    ///
+    /// Output split per function is not wrapped in a `Module`; the files instead require
+    /// each other with `From <module_prefix> Require Import`.
+    pub module_prefix: Option<String>,
+
+    /// Emits the nondeterministic instructions (`uzumaki`, `forall`, `exists`, `assume`
+    /// and `unique`) as parameters of a `Nondeterminism` module type, and the definitions
+    /// inside a functor `Make` over it, instead of as `Wasm` library constructors. Users
+    /// choose the semantics of the instructions by applying `Make` to their own module.
+    pub parameterize_nondeterminism: bool,
+
+    /// Path of the Inference source file the module was compiled from, named in the
+    /// coqdoc comments of the module and of its functions, or `None` if it is unknown.
+    pub source_file: Option<String>,
+
+    /// The defined functions translated with their body. The other functions are
+    /// declared as `Parameter`s of type `module_func`.
+    pub functions: FunctionSelection,
+
+    /// Declares every defined function as a `Parameter`, whatever
+    /// [`TranslationConfig::functions`] selects, so only the types, the initial state and
+    /// the module record are translated.
+    pub interface_only: bool,
+}
+
+/// Selects the defined functions translated with their body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum FunctionSelection {
+    /// Every function.
+    #[default]
+    All,
+    /// The exported functions.
+    Exported,
+    /// The functions with one of these names in the name section, or exported under one
+    /// of them.
+    Named(Vec<String>),
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            imports: [
+                "Require Import List.",
+                "Require Import String.",
+                "Require Import BinNat.",
+                "Require Import ZArith.",
+                "From Wasm Require Import bytes.",
+                "From Wasm Require Import numerics.",
+                "From Wasm Require Import datatypes.",
+            ]
+            .map(String::from)
+            .to_vec(),
+            constructor_names: HashMap::new(),
+            module_prefix: None,
+            parameterize_nondeterminism: false,
+            source_file: None,
+            functions: FunctionSelection::All,
+            interface_only: false,
+        }
+    }
+}
+
+impl TranslationConfig {
+    /// Returns `code` with every identifier that has an entry in
+    /// [`TranslationConfig::constructor_names`] renamed.
+    ///
+    /// Identifiers are maximal runs of ASCII letters, digits, `_` and `'` that do not
+    /// start with a digit or `'`. Qualified names such as `Wasm_int.int_of_Z` are renamed
+    /// part by part. String literals are copied unchanged.
+    pub(crate) fn rename<'a>(&self, code: &'a str) -> Cow<'a, str> {
+        if self.constructor_names.is_empty() {
+            return Cow::Borrowed(code);
+        }
+        let bytes = code.as_bytes();
+        let mut renamed = String::with_capacity(code.len());
+        let mut in_string = false;
+        let mut copied = 0;
+        let mut index = 0;
+        while index < bytes.len() {
+            let byte = bytes[index];
+            if byte == b'"' {
+                in_string = !in_string;
+                index += 1;
+            } else if !in_string && (byte.is_ascii_alphabetic() || byte == b'_') {
+                let start = index;
+                while index < bytes.len() && is_identifier_byte(bytes[index]) {
+                    index += 1;
+                }
+                if let Some(name) = self.constructor_names.get(&code[start..index]) {
+                    renamed.push_str(&code[copied..start]);
+                    renamed.push_str(name);
+                    copied = index;
+                }
+            } else if is_identifier_byte(byte) {
+                // Skip the rest of a number literal, such as `0%N`, or of an identifier
+                // inside a string literal.
+                while index < bytes.len() && is_identifier_byte(bytes[index]) {
+                    index += 1;
+                }
+            } else {
+                index += 1;
+            }
+        }
+        if copied == 0 {
+            return Cow::Borrowed(code);
+        }
+        renamed.push_str(&code[copied..]);
+        Cow::Owned(renamed)
+    }
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'\''
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(names: &[(&str, &str)]) -> TranslationConfig {
+        TranslationConfig {
+            constructor_names: names
+                .iter()
+                .map(|(from, to)| ((*from).to_string(), (*to).to_string()))
+                .collect(),
+            ..TranslationConfig::default()
+        }
+    }
+
+    #[test]
+    fn renames_whole_identifiers_only() {
+        let config = config(&[("BI_const", "I_const"), ("Vi32", "V32")]);
+        assert_eq!(
+            config.rename("BI_const (Vi32 1) :: BI_const_x :: BI_const' :: nil"),
+            "I_const (V32 1) :: BI_const_x :: BI_const' :: nil"
+        );
+    }
+
+    #[test]
+    fn renames_parts_of_qualified_names() {
+        let config = config(&[("Wasm_int", "Int")]);
+        assert_eq!(
+            config.rename("VAL_int32 (Wasm_int.int_of_Z i32m i)"),
+            "VAL_int32 (Int.int_of_Z i32m i)"
+        );
+    }
+
+    #[test]
+    fn leaves_string_literals_and_numbers_unchanged() {
+        let config = config(&[("Mi", "Import"), ("env", "host"), ("N", "nat")]);
+        assert_eq!(
+            config.rename("Mi \"env\" \"Mi\" (MED_func 0%N)"),
+            "Import \"env\" \"Mi\" (MED_func 0%nat)"
+        );
+    }
+
+    #[test]
+    fn default_config_borrows_the_code() {
+        let code = "BI_const (Vi32 1)";
+        assert!(matches!(
+            TranslationConfig::default().rename(code),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -28,6 +28,29 @@
 //! std::fs::write("output.v", rocq_code)?;
 //! ```
 //!
+//! [`wasm_parser::translate_to_writer`] writes the same output incrementally to any
+//! [`std::io::Write`], so large modules are translated without building one `String`:
+//!
+//! ```ignore
+//! use inference_wasm_to_v_translator::wasm_parser::translate_to_writer;
+//! use std::{fs::File, io::BufWriter};
+//!
+//! let wasm_bytes = std::fs::read("output.wasm")?;
+//! translate_to_writer("my_module", &wasm_bytes, BufWriter::new(File::create("output.v")?))?;
+//! ```
+//!
+//! By default the generated code targets the `Wasm` library (WasmCert-Coq). A
+//! [`config::TranslationConfig`] selects other imports, renames the emitted constructors,
+//! or wraps the definitions in a Rocq `Module`; see
+//! [`wasm_parser::translate_bytes_with_config`].
+//!
+//! [`wasm_parser::translate_bytes_with_report`] also returns a
+//! [`report::TranslationReport`] with the number of translated instructions, the
+//! unsupported instructions and skipped functions, and the section sizes.
+//!
+//! [`wasm_parser::translate_to_files`] splits the output into one `.v` file per function,
+//! plus a prelude and a module file, so large modules can be checked in parallel.
+//!
 //! For integration with the Inference compiler, use the higher-level API:
 //!
 //! ```ignore
@@ -48,9 +71,9 @@
 //!
 //! ### Phase 2: Translation ([`translator`])
 //!
-//! Converts structured [`translator::WasmParseData`] into Rocq code strings.
-//! Implements error recovery to collect multiple translation failures before
-//! reporting.
+//! Converts structured [`translator::WasmParseData`] into Rocq code, written to an
+//! [`std::io::Write`] one function definition or module record entry at a time.
+//! Section entries that fail to translate are left out of the module record.
 //!
 //! ### WASM Sections Supported
 //!
@@ -58,7 +81,8 @@
 //! - **Import Section**: External function, memory, table, and global imports
 //! - **Function Section**: Maps function indices to type indices
 //! - **Table Section**: Indirect call table definitions
-//! - **Memory Section**: Linear memory specifications with size limits
+//! - **Memory Section**: Linear memory specifications with size limits, including
+//!   multiple memories and 64-bit memories
 //! - **Global Section**: Global variable definitions with initialization
 //! - **Export Section**: Public interface (exported functions, tables, memories, globals)
 //! - **Start Section**: Optional module entry point
@@ -131,6 +155,7 @@
 //!
 //! ## Modules
 //!
+//! - [`config`] - Target Rocq library: imports, constructor names and module prefix
 //! - [`wasm_parser`] - Parses WASM bytecode sections into structured data (Phase 1)
 //! - [`translator`] - Converts parsed data into Rocq code strings (Phase 2)
 //!
@@ -139,8 +164,8 @@
 //! All translation functions return [`anyhow::Result`] for flexible error propagation.
 //!
 //! - **Parser errors**: The parsing phase fails fast on malformed WASM bytecode
-//! - **Translator errors**: The translation phase uses error recovery to collect
-//!   multiple failures before reporting the first error
+//! - **Translator errors**: The translation phase leaves out section entries that fail
+//!   to translate, and stops at the first function that fails to translate
 //!
 //! ## Performance Characteristics
 //!
@@ -160,101 +185,752 @@
 //! - [Rocq Documentation](https://rocq-prover.org/) - Rocq proof assistant
 //! - [WebAssembly Specification](https://webassembly.github.io/spec/) - WASM standard
 
+pub mod config;
+mod names;
+pub mod report;
 pub mod translator;
+mod verification;
 pub mod wasm_parser;
 
 #[cfg(test)]
 mod tests {
-    use super::wasm_parser::translate_bytes;
+    use super::config::{FunctionSelection, TranslationConfig};
+    use super::wasm_parser::{
+        ValidationError, translate_bytes, translate_bytes_with_config, translate_bytes_with_report,
+        translate_to_files, translate_to_writer, translate_to_writer_with_config, validate,
+    };
+    use std::collections::HashSet;
     use std::fs;
-    use std::panic;
+    use std::io::{self, Write};
     use std::path::PathBuf;
 
+    /// Records the size of every write and whether the writer was flushed.
+    #[derive(Default)]
+    struct RecordingWriter {
+        output: Vec<u8>,
+        largest_write: usize,
+        flushed: bool,
+    }
+
+    impl Write for RecordingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.largest_write = self.largest_write.max(buf.len());
+            self.output.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushed = true;
+            Ok(())
+        }
+    }
+
+    fn read_test_data(file_name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data")
+            .join(file_name);
+        fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e))
+    }
+
     #[test]
-    fn test_parse_test_data() {
-        let test_data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data");
+    fn test_translate_to_writer_matches_translate_bytes() {
+        for file_name in [
+            "fac.0.wasm",
+            "func_ptrs.0.wasm",
+            "start.3.wasm",
+            "table.0.wasm",
+        ] {
+            let bytes = read_test_data(file_name);
+            let expected = translate_bytes("module", &bytes).unwrap();
+            let mut writer = RecordingWriter::default();
+            translate_to_writer("module", &bytes, &mut writer).unwrap();
+            let output = String::from_utf8(writer.output).unwrap();
+            assert_eq!(output, expected, "{file_name}");
+            assert!(writer.flushed, "{file_name}: writer was not flushed");
+        }
+    }
 
+    #[test]
+    fn test_translate_to_writer_writes_incrementally() {
+        let bytes = read_test_data("fac.0.wasm");
+        let mut writer = RecordingWriter::default();
+        translate_to_writer("module", &bytes, &mut writer).unwrap();
+        assert!(writer.output.ends_with(b"|}.\n"));
         assert!(
-            test_data_dir.exists(),
-            "test_data directory not found at {:?}",
-            test_data_dir
+            writer.largest_write * 4 < writer.output.len(),
+            "largest write of {} bytes for {} bytes of output",
+            writer.largest_write,
+            writer.output.len()
         );
+    }
+
+    #[test]
+    fn test_translate_to_writer_reports_invalid_modules() {
+        let mut writer = RecordingWriter::default();
+        assert!(translate_to_writer("module", b"\0asm\x01", &mut writer).is_err());
+    }
+
+    #[test]
+    fn test_default_config_matches_translate_bytes() {
+        let bytes = read_test_data("fac.0.wasm");
+        let output =
+            translate_bytes_with_config("module", &bytes, &TranslationConfig::default()).unwrap();
+        assert!(output.starts_with("Require Import List.\n"));
+        assert!(output.contains("From Wasm Require Import datatypes.\n\n(** * Module [module_]"));
+        assert_eq!(output, translate_bytes("module", &bytes).unwrap());
+    }
 
-        let entries = fs::read_dir(&test_data_dir).expect("Failed to read test_data directory");
+    #[test]
+    fn test_translate_with_config_targets_another_library() {
+        let bytes = read_test_data("fac.0.wasm");
+        let config = TranslationConfig {
+            imports: vec!["From MyWasm Require Import syntax.".to_string()],
+            constructor_names: [("BI_const_num", "I_const"), ("modfunc_body", "func_body")]
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .into(),
+            module_prefix: Some("Generated".to_string()),
+            parameterize_nondeterminism: false,
+            source_file: None,
+            functions: FunctionSelection::All,
+            interface_only: false,
+        };
+        let output = translate_bytes_with_config("module", &bytes, &config).unwrap();
+        assert!(output.starts_with("From MyWasm Require Import syntax.\n\nModule Generated.\n\n"));
+        assert!(output.ends_with("|}.\nEnd Generated.\n"));
+        assert!(!output.contains("Require Import List."));
+        assert!(output.contains("  func_body :=\n"));
+        assert!(output.contains("I_const"));
+        assert!(!output.contains("BI_const_num"));
+        assert!(!output.contains("modfunc_body"));
 
-        let mut wasm_files = Vec::new();
+        let mut writer = RecordingWriter::default();
+        translate_to_writer_with_config("module", &bytes, &config, &mut writer).unwrap();
+        assert_eq!(String::from_utf8(writer.output).unwrap(), output);
+    }
 
-        for entry in entries {
-            let entry = entry.expect("Failed to read directory entry");
-            let path = entry.path();
+    #[test]
+    fn test_name_section_gives_rocq_identifiers() {
+        let bytes = read_test_data("names.0.wasm");
+        let output = translate_bytes("module", &bytes).unwrap();
+        // Names are looked up by function index, which counts the imported `host.log`.
+        assert!(output.contains("Definition add : module_func"));
+        assert!(output.contains("BI_local_get 1%N (*b*)"));
+        // Names inside blocks are commented too.
+        assert!(output.contains("      BI_local_get 2%N (*sum*) ::\n      BI_call 0 (*host_log*)"));
+        // Duplicate, reserved and invalid names.
+        assert!(output.contains("Definition add_1 : module_func"));
+        assert!(output.contains("Definition nil_ : module_func"));
+        assert!(output.contains("BI_call 3 (*nil_*)"));
+        assert!(output.contains("Definition id_2nd_pass : module_func"));
+        // The module name from the name section is allocated first.
+        assert!(output.contains("Definition my_module : module"));
+        assert!(output.contains("Definition my_module_1 : module_func"));
+        assert!(
+            output.contains(
+                "  mod_funcs :=\n    add ::\n    add_1 ::\n    nil_ ::\n    id_2nd_pass ::\n    my_module_1 ::\n    func_6 ::\n    nil;"
+            )
+        );
+    }
 
-            if path.extension().and_then(|s| s.to_str()) == Some("wasm") {
-                wasm_files.push(path);
-            }
+    #[test]
+    fn test_translate_to_files_splits_functions() {
+        let bytes = read_test_data("names.0.wasm");
+        let config = TranslationConfig::default();
+        let files = translate_to_files("module", &bytes, &config).unwrap();
+        let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(
+            names[..6],
+            [
+                "my_module_prelude",
+                "my_module_add",
+                "my_module_add_1",
+                "my_module_nil_",
+                "my_module_id_2nd_pass",
+                "my_module_my_module_1"
+            ]
+        );
+        assert_eq!(names.len(), 8);
+        assert_eq!(names[7], "my_module");
+        assert_eq!(files[1].file_name(), "my_module_add.v");
+
+        assert!(files[0].contents.starts_with("Require Import List.\n"));
+        assert!(files[0].contents.contains("Definition Vi32"));
+        assert!(files[1].contents.contains(
+            "From Wasm Require Import datatypes.\n\nRequire Import my_module_prelude.\n\n(** Function [add] (index 1)."
+        ));
+        assert!(
+            files[1]
+                .contents
+                .contains("*)\nDefinition add : module_func")
+        );
+        assert!(files[7].contents.contains(
+            "Require Import my_module_prelude.\nRequire Import my_module_add.\nRequire Import my_module_add_1.\n"
+        ));
+        assert!(
+            files[7]
+                .contents
+                .contains("Require Import my_module_func_6.\n\n(** * Module [my_module]")
+        );
+        assert!(files[7].contents.contains("Definition my_module : module"));
+
+        // The files hold the definitions of the single-file translation, in order, except
+        // for the header of the module file, which the single file starts with.
+        let header = "(** * Module [my_module]\n\n    Rocq translation of a WebAssembly module, defined by the record [my_module]. *)\n\n";
+        let definitions = |contents: &str| {
+            let (_, rest) = contents.split_once("\n\n").unwrap();
+            let rest = match rest.strip_prefix("Require Import ") {
+                Some(rest) => rest.split_once("\n\n").unwrap().1,
+                None => rest,
+            };
+            rest.replacen(header, "", 1)
+        };
+        let split: String = files
+            .iter()
+            .map(|file| definitions(&file.contents))
+            .collect();
+        let single = translate_bytes("module", &bytes).unwrap();
+        assert!(single.contains(header));
+        assert_eq!(split, definitions(&single));
+    }
+
+    #[test]
+    fn test_translate_to_files_with_module_prefix() {
+        let bytes = read_test_data("fac.0.wasm");
+        let config = TranslationConfig {
+            module_prefix: Some("Generated".to_string()),
+            ..TranslationConfig::default()
+        };
+        let files = translate_to_files("fac", &bytes, &config).unwrap();
+        let module_file = files.last().unwrap();
+        assert_eq!(module_file.name, "fac");
+        assert!(
+            module_file
+                .contents
+                .contains("From Generated Require Import fac_prelude.\n")
+        );
+        assert!(
+            files
+                .iter()
+                .all(|file| !file.contents.contains("Module Generated."))
+        );
+    }
+
+    #[test]
+    fn test_multiple_and_64_bit_memories() {
+        let bytes = read_test_data("memory_multi.0.wasm");
+        let output = translate_bytes("module", &bytes).unwrap();
+        // Memory 0 is the imported 64-bit memory, and keeps the unindexed instructions.
+        assert!(
+            output
+                .contains("Mi \"env\" \"shared\" (MID_mem64 {|lim_min := 1%N; lim_max := None|})")
+        );
+        assert!(output.contains("    BI_load T_i64 None (Ma 0%N 3%N) ::\n"));
+        assert!(output.contains("    BI_memory_size ::\n"));
+        assert!(output.contains(
+            "  mod_mems :=\n    Mm {|lim_min := 1%N; lim_max := Some(2%N)|} ::\n    Mm64 {|lim_min := 2%N; lim_max := None|} ::\n"
+        ));
+        for instruction in [
+            "BI_load_at 2%N T_i64 None (Ma 8%N 3%N)",
+            "BI_store_at 1%N T_i32 None (Ma 0%N 2%N)",
+            "BI_load_at 1%N T_i32 (Some (Tp_i8, SX_U)) (Ma 1%N 0%N)",
+            "BI_memory_size_at 2%N",
+            "BI_memory_grow_at 2%N",
+            "BI_memory_copy_at 1%N 1%N",
+            "BI_memory_fill_at 2%N",
+            "MD_active 2%N",
+        ] {
+            assert!(output.contains(instruction), "{instruction}");
         }
+    }
 
-        wasm_files.sort();
+    #[test]
+    fn test_segments_give_initial_memories_and_tables() {
+        let bytes = read_test_data("segments.0.wasm");
+        let output = translate_bytes("module", &bytes).unwrap();
+        assert!(output.contains("Definition segments_data_0 : list byte := #68 :: #69 :: nil.\n"));
+        assert!(output.contains("Definition segments_data_3 : list byte := #70 :: #61 :: #73"));
+        assert!(output.contains("    moddata_init := segments_data_0;\n"));
+        assert!(output.contains(
+            "Definition segments_elem_1 : list (option N) := Some 1%N :: None :: nil.\n"
+        ));
+        // Declared segments are not available at run time.
+        assert!(!output.contains("segments_elem_4"));
+        // Offsets are constants or immutable globals of the module initialized by one.
+        assert!(output.contains(
+            "Definition segments_initial_memory_0 : list (N * list byte) :=\n    (16%N, segments_data_0) ::\n    (64%N, segments_data_1) ::\n    (* data segment 2 has a non-constant offset *)\n    nil.\n"
+        ));
+        assert!(output.contains(
+            "Definition segments_initial_memory_1 : list (N * list byte) :=\n    (0%N, segments_data_4) ::\n    nil.\n"
+        ));
+        // Table 0 is the imported table.
+        assert!(output.contains(
+            "Definition segments_initial_table_0 : list (N * list (option N)) :=\n    (0%N, segments_elem_1) ::\n    nil.\n"
+        ));
+        assert!(output.contains(
+            "Definition segments_initial_table_1 : list (N * list (option N)) :=\n    (1%N, segments_elem_0) ::\n    (* element segment 2 has a non-constant offset *)\n    nil.\n"
+        ));
+        assert!(output.contains("(    BI_ref_null T_funcref ::\n    nil) ::\n"));
+    }
+
+    #[test]
+    fn test_tail_calls() {
+        let bytes = read_test_data("return_call.0.wasm");
+        let output = translate_bytes("module", &bytes).unwrap();
+        assert!(output.contains("      BI_return_call 0 (*count*) ::\n"));
+        assert!(output.contains("    BI_return_call_indirect 0 0 ::\n"));
+    }
+
+    #[test]
+    fn test_reference_types_and_table_operations() {
+        let bytes = read_test_data("reference_types.0.wasm");
+        let output = translate_bytes("module", &bytes).unwrap();
+        for instruction in [
+            "BI_ref_func 0%N (*add*)",
+            "BI_table_set 1%N",
+            "BI_table_grow 0%N",
+            "BI_table_fill 0%N",
+            "BI_table_copy 0%N 0%N",
+            "BI_table_init 0%N 0%N",
+            "BI_elem_drop 0%N",
+            "BI_table_get 0%N",
+            "BI_ref_null T_externref",
+            "BI_select (Some (T_ref T_externref :: nil))",
+            "BI_table_size 0%N",
+        ] {
+            assert!(output.contains(instruction), "{instruction}");
+        }
+        // Typed function references.
+        assert!(output.contains("modfunc_locals := T_ref (T_nonnull (T_typeref 0%N)) :: nil;"));
+        assert!(output.contains("Tf (T_ref (T_typeref 0%N) :: nil) (T_num T_i32 :: nil)"));
+        for instruction in [
+            "BI_br_on_null 0%N",
+            "BI_call_ref 0%N",
+            "BI_ref_null (T_typeref 0%N)",
+            "BI_br_on_non_null 0%N",
+            "BI_ref_as_non_null",
+            "BI_return_call_ref 0%N",
+        ] {
+            assert!(output.contains(instruction), "{instruction}");
+        }
+    }
+
+    #[test]
+    fn test_exception_handling_is_reported() {
+        let bytes = read_test_data("exceptions.0.wasm");
+        let error = translate_bytes("module", &bytes).unwrap_err().to_string();
+        assert!(error.starts_with("Function fail: "), "{error}");
+        assert!(
+            error.contains("Exception handling instruction Throw"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_translation_report() {
+        let config = TranslationConfig::default();
+        let bytes = read_test_data("fac.0.wasm");
+        let (output, report) = translate_bytes_with_report("module", &bytes, &config).unwrap();
+        assert!(report.is_complete());
+        assert!(report.instructions_translated > 0);
+        assert_eq!(report.functions_translated, 8);
+        assert!(
+            report
+                .section_sizes
+                .iter()
+                .any(|section| section.name == "code")
+        );
+        assert_eq!(output, translate_bytes("module", &bytes).unwrap());
+
+        let bytes = read_test_data("exceptions.0.wasm");
+        let (output, report) = translate_bytes_with_report("module", &bytes, &config).unwrap();
+        assert!(!report.is_complete());
+        assert_eq!(report.functions_translated, 0);
+        let skipped: Vec<&str> = report
+            .skipped_functions
+            .iter()
+            .map(|function| function.name.as_str())
+            .collect();
+        assert_eq!(skipped, ["fail", "guarded"]);
+        // Every unsupported instruction is listed, with its offset in the module.
+        assert_eq!(report.unsupported_instructions.len(), 2);
+        let throw = &report.unsupported_instructions[0];
+        assert_eq!(throw.function_index, 0);
+        assert_eq!(bytes[throw.offset], 0x08);
+        assert!(throw.message.contains("Throw"));
+        assert_eq!(report.unsupported_instructions[1].function_index, 1);
+        assert_eq!(report.instructions_translated, 6);
+        assert!(
+            report
+                .section_sizes
+                .iter()
+                .any(|section| section.name == "tag")
+        );
+        assert!(output.contains("(* Skipped: Function fail: "));
+        assert!(output.contains("  mod_funcs :=\n    nil;\n"));
+    }
+
+    #[test]
+    fn test_translate_wat_text() {
+        let wat = r#"
+            (module
+              (func $choose (export "choose") (result i32)
+                forall
+                  i32.uzumaki
+                  drop
+                end
+                i64.uzumaki
+                i32.wrap_i64))
+        "#;
+        let output = translate_bytes("module", wat.as_bytes()).unwrap();
+        assert!(output.contains("Definition choose : module_func"));
+        assert!(output.contains("BI_uzumaki_num T_i32"));
+        assert!(output.contains("BI_uzumaki_num T_i64"));
+        assert!(output.contains("Me \"choose\""));
+
+        // `names.0.wat` is the source of `names.0.wasm`.
+        let wat = read_test_data("names.0.wat");
+        let bytes = read_test_data("names.0.wasm");
+        assert_eq!(
+            translate_bytes("module", &wat).unwrap(),
+            translate_bytes("module", &bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parameterized_nondeterminism() {
+        let wat = r#"
+            (module
+              (func $choose (result i32)
+                forall
+                  i32.uzumaki
+                  drop
+                end
+                i64.uzumaki
+                i32.wrap_i64))
+        "#;
+        let config = TranslationConfig {
+            parameterize_nondeterminism: true,
+            ..TranslationConfig::default()
+        };
+        let output = translate_bytes_with_config("module", wat.as_bytes(), &config).unwrap();
+        assert!(output.contains("Module Type Nondeterminism.\n"));
+        assert!(output.contains("  Parameter uzumaki : number_type -> basic_instruction.\n"));
+        assert!(output.contains("End Nondeterminism.\n\nModule Make (ND : Nondeterminism).\n"));
+        assert!(
+            output.contains("    ND.forall_block (BT_valtype None) (\n      ND.uzumaki T_i32 ::")
+        );
+        assert!(output.contains("    ND.uzumaki T_i64 ::"));
+        assert!(!output.contains("BI_uzumaki_num"));
+        assert!(!output.contains("BI_forall"));
+        assert!(output.ends_with("|}.\nEnd Make.\n"));
+
+        let error = translate_to_files("module", wat.as_bytes(), &config).unwrap_err();
+        assert!(error.to_string().contains("not supported for output split"));
+    }
+
+    #[test]
+    fn test_verification_metadata_locations() {
+        let wat = r#"
+            (module
+              (@custom "inference.verification"
+                "\01\01\05check\01\05check\00\10\01\01\08\02"
+                "\02\00\00\00\03\05\05\06\02\00\00\06\05\06\14")
+              (func (export "check") (result i32)
+                forall
+                  nop
+                end
+                assume
+                  nop
+                end
+                i32.const 1)
+              (func (export "other")))
+        "#;
+        let output = translate_bytes("module", wat.as_bytes()).unwrap();
+        assert!(output.contains(
+            "    Exported as [\"check\"].\n    Source: 1:1-8:2.\n*)\nDefinition func_0 : module_func"
+        ));
+        assert!(output.contains("BI_forall (BT_valtype None) (* forall at 3:5-5:6 *) (\n"));
+        assert!(output.contains("BI_assume (BT_valtype None) (* assume at 6:5-6:20 *) (\n"));
+        assert!(
+            output.contains("    Exported as [\"other\"].\n*)\nDefinition func_1 : module_func")
+        );
+    }
+
+    #[test]
+    fn test_coqdoc_comments() {
+        let wat = r#"
+            (module $counter
+              (import "env" "log" (func $log (param i32)))
+              (memory (export "memory") 1)
+              (global $count (export "count") (mut i32) (i32.const 0))
+              (func $increment (export "increment") (export "inc") (param $by i32) (param i64) (result i32)
+                global.get $count
+                local.get $by
+                i32.add)
+              (export "log" (func $log)))
+        "#;
+        let config = TranslationConfig {
+            source_file: Some("src/counter.inf".to_string()),
+            ..TranslationConfig::default()
+        };
+        let output = translate_bytes_with_config("module", wat.as_bytes(), &config).unwrap();
+        assert!(output.contains(
+            "(** * Module [counter]\n\n    Rocq translation of a WebAssembly module compiled from [\"src/counter.inf\"], defined by the record [counter]. *)\n\nDefinition Vi32"
+        ));
+        assert!(output.contains(
+            "(** Function [increment] (index 1).
+    Parameters: [by : i32], [i64].
+    Results: [i32].
+    Exported as [\"increment\"], [\"inc\"].
+    Source: [\"src/counter.inf\"].
+*)
+Definition increment : module_func"
+        ));
+        assert!(output.contains(
+            "(** Exports of [counter]:
+    - [\"memory\"]: memory 0
+    - [\"count\"]: global 0
+    - [\"increment\"]: function [increment]
+    - [\"inc\"]: function [increment]
+    - [\"log\"]: imported function 0
+*)
+Definition counter : module"
+        ));
+    }
+
+    #[test]
+    fn test_function_selection() {
+        let wat = r#"
+            (module
+              (func $helper (result i32)
+                i32.const 1)
+              (func $check (export "check") (result i32)
+                call $helper)
+              (func $audit (result i32)
+                call $helper))
+        "#;
+        let translate = |functions, interface_only| {
+            let config = TranslationConfig {
+                functions,
+                interface_only,
+                ..TranslationConfig::default()
+            };
+            translate_bytes_with_report("module", wat.as_bytes(), &config).unwrap()
+        };
+
+        let (output, report) = translate(FunctionSelection::Exported, false);
+        assert!(output.contains("Parameter helper : module_func.\n"));
+        assert!(output.contains("Definition check : module_func"));
+        assert!(output.contains("Parameter audit : module_func.\n"));
+        assert!(output.contains("  mod_funcs :=\n    helper ::\n    check ::\n    audit ::\n"));
+        assert_eq!(report.functions_translated, 1);
+        assert_eq!(report.functions_declared, 2);
+        assert!(
+            report
+                .to_string()
+                .contains("Functions: 1/1 translated, 2 declared without body\n")
+        );
+
+        let (output, _) = translate(
+            FunctionSelection::Named(vec!["helper".to_string(), "audit".to_string()]),
+            false,
+        );
+        assert!(output.contains("Definition helper : module_func"));
+        assert!(output.contains("Parameter check : module_func.\n"));
+        assert!(output.contains("Definition audit : module_func"));
+
+        let (output, report) = translate(FunctionSelection::All, true);
+        assert!(!output.contains(": module_func := {|"));
+        assert!(output.contains("Parameter check : module_func.\n"));
+        assert!(output.contains("Definition module_ : module := {|"));
+        assert_eq!(report.instructions_translated, 0);
+        assert_eq!(report.functions_declared, 3);
+    }
+
+    #[test]
+    fn test_validation_reports_structural_problems() {
+        let bytes = read_test_data("func_ptrs.6.wasm");
+        let error = translate_bytes("module", &bytes).unwrap_err();
+        let error = error.downcast_ref::<ValidationError>().unwrap();
+        assert_eq!(error.offset, 0xb);
+        assert_eq!(error.message, "unknown type 42: type index out of bounds");
+
+        // Two declared functions, one body.
+        let bytes =
+            b"\0asm\x01\0\0\0\x01\x04\x01\x60\x00\x00\x03\x03\x02\x00\x00\x0a\x04\x01\x02\x00\x0b";
+        let error = validate(bytes).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid module at offset 0x15: function and code section have inconsistent lengths"
+        );
+
+        // Function bodies are not type-checked.
+        assert!(validate(&read_test_data("names.0.wasm")).is_ok());
+    }
+
+    #[test]
+    fn test_saturating_truncation() {
+        let wat = r#"
+            (module
+              (func (param f32 f64)
+                local.get 0
+                i32.trunc_sat_f32_s
+                local.get 0
+                i32.trunc_sat_f32_u
+                local.get 1
+                i32.trunc_sat_f64_s
+                local.get 1
+                i32.trunc_sat_f64_u
+                local.get 0
+                i64.trunc_sat_f32_s
+                local.get 0
+                i64.trunc_sat_f32_u
+                local.get 1
+                i64.trunc_sat_f64_s
+                local.get 1
+                i64.trunc_sat_f64_u
+                unreachable))
+        "#;
+        let output = translate_bytes("module", wat.as_bytes()).unwrap();
+        for instruction in [
+            "BI_cvtop T_i32 (CVO_trunc_sat T_f32 (Some SX_S))",
+            "BI_cvtop T_i32 (CVO_trunc_sat T_f32 (Some SX_U))",
+            "BI_cvtop T_i32 (CVO_trunc_sat T_f64 (Some SX_S))",
+            "BI_cvtop T_i32 (CVO_trunc_sat T_f64 (Some SX_U))",
+            "BI_cvtop T_i64 (CVO_trunc_sat T_f32 (Some SX_S))",
+            "BI_cvtop T_i64 (CVO_trunc_sat T_f32 (Some SX_U))",
+            "BI_cvtop T_i64 (CVO_trunc_sat T_f64 (Some SX_S))",
+            "BI_cvtop T_i64 (CVO_trunc_sat T_f64 (Some SX_U))",
+        ] {
+            assert!(
+                output.contains(&format!("    {instruction} ::\n")),
+                "{instruction}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_sign_extension() {
+        let wat = r#"
+            (module
+              (func (param i32 i64)
+                local.get 0
+                i32.extend8_s
+                i32.extend16_s
+                drop
+                local.get 1
+                i64.extend8_s
+                i64.extend16_s
+                i64.extend32_s
+                drop))
+        "#;
+        let output = translate_bytes("module", wat.as_bytes()).unwrap();
+        assert!(output.contains(
+            "    BI_unop T_i32 (Unop_extend 8%N) ::\n    BI_unop T_i32 (Unop_extend 16%N) ::\n"
+        ));
+        assert!(output.contains(
+            "    BI_unop T_i64 (Unop_extend 8%N) ::\n    BI_unop T_i64 (Unop_extend 16%N) ::\n    BI_unop T_i64 (Unop_extend 32%N) ::\n"
+        ));
+    }
+
+    #[test]
+    fn test_translate_invalid_wat_text() {
+        let error = translate_bytes("module", b"(module\n  (func i32.frobnicate))")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("Invalid WAT: "), "{error}");
+        assert!(error.contains(":2:"), "{error}");
+    }
+
+    /// Compares the translation of every `test_data/*.wasm` file with its expected output
+    /// in `test_data/expected`: `<file>.v` for a translation, or `<file>.err` for the
+    /// error of a module that fails to translate. After an intended change of the output,
+    /// run the test with `UPDATE_EXPECTED=1` to rewrite the expected files, and review
+    /// their diff.
+    #[test]
+    fn test_expected_translations() {
+        let test_data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test_data");
+        let expected_dir = test_data_dir.join("expected");
+        let update = std::env::var_os("UPDATE_EXPECTED").is_some();
 
+        let mut wasm_files: Vec<PathBuf> = fs::read_dir(&test_data_dir)
+            .expect("Failed to read test_data directory")
+            .map(|entry| entry.expect("Failed to read directory entry").path())
+            .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("wasm"))
+            .collect();
+        wasm_files.sort();
         assert!(
             !wasm_files.is_empty(),
             "No .wasm files found in test_data directory"
         );
+        if update {
+            fs::create_dir_all(&expected_dir).unwrap();
+        }
 
-        let mut success_count = 0;
-        let mut error_count = 0;
-        let mut panic_count = 0;
-
+        let mut expected_files = HashSet::new();
+        let mut failures = Vec::new();
         for wasm_path in &wasm_files {
-            let file_name = wasm_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
-
-            let bytes = fs::read(wasm_path)
-                .unwrap_or_else(|e| panic!("Failed to read {}: {}", file_name, e));
-
-            let module_name = wasm_path
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("module");
-
-            // Catch panics from unimplemented features
-            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
-                translate_bytes(module_name, &bytes)
-            }));
-
-            match result {
-                Ok(Ok(translation)) => {
-                    println!("✓ Successfully parsed {}", file_name);
-                    assert!(
-                        !translation.is_empty(),
-                        "Translation result is empty for {}",
-                        file_name
-                    );
-                    success_count += 1;
-                }
-                Ok(Err(e)) => {
-                    println!("✗ Failed to parse {}: {}", file_name, e);
-                    error_count += 1;
+            let stem = wasm_path.file_stem().and_then(|n| n.to_str()).unwrap();
+            let bytes = fs::read(wasm_path).unwrap();
+            let (file_name, actual) = match translate_bytes("module", &bytes) {
+                Ok(translation) => (format!("{stem}.v"), translation),
+                Err(error) => (format!("{stem}.err"), format!("{error}\n")),
+            };
+            let expected_path = expected_dir.join(&file_name);
+            if update {
+                fs::write(&expected_path, &actual).unwrap();
+            } else {
+                match fs::read_to_string(&expected_path) {
+                    Ok(expected) if expected == actual => {}
+                    Ok(expected) => failures.push(format!(
+                        "{file_name}: {}",
+                        first_difference(&expected, &actual)
+                    )),
+                    Err(_) => failures.push(format!("{file_name}: missing")),
                 }
-                Err(_) => {
-                    println!(
-                        "⚠ Panicked while parsing {} (likely unimplemented feature)",
-                        file_name
-                    );
-                    panic_count += 1;
+            }
+            expected_files.insert(file_name);
+        }
+
+        for entry in fs::read_dir(&expected_dir).expect("Failed to read test_data/expected") {
+            let path = entry.unwrap().path();
+            let file_name = path.file_name().and_then(|n| n.to_str()).unwrap();
+            if !expected_files.contains(file_name) {
+                if update {
+                    fs::remove_file(&path).unwrap();
+                } else {
+                    failures.push(format!("{file_name}: no test_data file produces it"));
                 }
             }
         }
 
-        println!("\n=== Summary ===");
-        println!("Total files: {}", wasm_files.len());
-        println!("Successful: {}", success_count);
-        println!("Failed (errors): {}", error_count);
-        println!("Failed (panics/unimplemented): {}", panic_count);
-        println!(
-            "Success rate: {:.1}%",
-            (success_count as f64 / wasm_files.len() as f64) * 100.0
+        assert!(
+            failures.is_empty(),
+            "{} of {} translations differ from test_data/expected:\n{}\n\
+             Run with UPDATE_EXPECTED=1 to accept the new output.",
+            failures.len(),
+            wasm_files.len(),
+            failures.join("\n")
         );
     }
+
+    /// Describes the first line where `actual` differs from `expected`.
+    fn first_difference(expected: &str, actual: &str) -> String {
+        let mut expected_lines = expected.lines();
+        let mut actual_lines = actual.lines();
+        let mut line = 1;
+        loop {
+            match (expected_lines.next(), actual_lines.next()) {
+                (Some(expected), Some(actual)) if expected == actual => line += 1,
+                (None, None) => return "the final newline differs".to_string(),
+                (expected, actual) => {
+                    return format!(
+                        "line {line}\n  expected: {}\n  actual:   {}",
+                        expected.unwrap_or("<end of file>"),
+                        actual.unwrap_or("<end of file>")
+                    );
+                }
+            }
+        }
+    }
 }
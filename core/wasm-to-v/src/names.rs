@@ -0,0 +1,230 @@
+//! Rocq Identifiers From the Name Section
+//!
+//! The custom name section of a WASM module names the module, its functions and their
+//! locals. The translator uses these names for the generated `Definition`s and for the
+//! comments next to function and local indices, but a WASM name is any UTF-8 string: it
+//! may contain characters such as `-`, `.` or `$`, start with a digit, be a Rocq keyword,
+//! or name several functions at once.
+//!
+//! This module turns names into valid Rocq identifiers:
+//!
+//! - **Sanitization**: Characters other than ASCII letters, digits, `_` and `'` become `_`,
+//!   and names that do not start with a letter or `_` get the prefix `id_`
+//! - **Reserved names**: Rocq keywords, the helpers of the generated file (`Vi32`, `Mt`,
+//!   ...) and the names the generated code refers to (`nil`, `module`, `BI_call`, ...) get
+//!   the suffix `_`, so a definition never shadows them
+//! - **Collisions**: A name already taken by an earlier definition gets the first free
+//!   numeric suffix, such as `add_1`
+
+use std::collections::HashSet;
+
+/// Rocq keywords, and the vernacular commands that may not be used as identifiers.
+const KEYWORDS: &[&str] = &[
+    "_",
+    "as",
+    "at",
+    "cofix",
+    "else",
+    "end",
+    "exists",
+    "exists2",
+    "fix",
+    "for",
+    "forall",
+    "fun",
+    "if",
+    "IF",
+    "in",
+    "let",
+    "match",
+    "mod",
+    "return",
+    "then",
+    "using",
+    "where",
+    "with",
+    "Prop",
+    "Set",
+    "SProp",
+    "Type",
+    "Axiom",
+    "Definition",
+    "End",
+    "Fixpoint",
+    "From",
+    "Import",
+    "Inductive",
+    "Lemma",
+    "Module",
+    "Parameter",
+    "Proof",
+    "Qed",
+    "Record",
+    "Require",
+    "Section",
+    "Theorem",
+];
+
+/// Identifiers the generated code refers to that are not covered by
+/// [`is_library_constructor`]: the helper definitions, record fields and the standard
+/// library names used in function bodies and the module record.
+const GENERATED_NAMES: &[&str] = &[
+    "Vi32",
+    "Vi64",
+    "Mt",
+    "Mm",
+    "Mm64",
+    "Mg",
+    "Mi",
+    "Me",
+    "Ma",
+    "ND",
+    "N",
+    "Z",
+    "nil",
+    "list",
+    "option",
+    "byte",
+    "None",
+    "Some",
+    "module",
+    "module_func",
+    "list_byte_of_string",
+    "i32m",
+    "i64m",
+    "memarg_offset",
+    "memarg_align",
+    "imp_module",
+    "imp_name",
+    "imp_desc",
+    "modexp_name",
+    "modexp_desc",
+    "modfunc_type",
+    "modfunc_locals",
+    "modfunc_body",
+    "modglob_type",
+    "modglob_init",
+    "modmem_type",
+    "modtab_type",
+    "modstart_func",
+    "moddata_init",
+    "moddata_mode",
+    "modelem_init",
+    "modelem_mode",
+    "modelem_type",
+    "mod_types",
+    "mod_funcs",
+    "mod_tables",
+    "mod_mems",
+    "mod_globals",
+    "mod_elems",
+    "mod_datas",
+    "mod_start",
+    "mod_imports",
+    "mod_exports",
+    "tg_mut",
+    "tg_t",
+    "tt_limits",
+    "tt_elem_type",
+    "lim_min",
+    "lim_max",
+];
+
+/// Allocates unique Rocq identifiers for the definitions of one generated file.
+#[derive(Debug, Default)]
+pub(crate) struct Identifiers {
+    used: HashSet<String>,
+}
+
+impl Identifiers {
+    /// Returns a valid Rocq identifier for `name` that no earlier call returned, and
+    /// that does not shadow a name the generated code refers to.
+    pub(crate) fn allocate(&mut self, name: &str) -> String {
+        let mut base = sanitize(name);
+        if KEYWORDS.contains(&base.as_str())
+            || GENERATED_NAMES.contains(&base.as_str())
+            || is_library_constructor(&base)
+        {
+            base.push('_');
+        }
+        let mut identifier = base.clone();
+        let mut suffix = 1;
+        while self.used.contains(&identifier) {
+            identifier = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        self.used.insert(identifier.clone());
+        identifier
+    }
+}
+
+/// Returns `name` with the characters that may not appear in a Rocq identifier replaced
+/// by `_`, prefixed with `id_` if it does not start with a letter or `_`.
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '\'' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if !identifier.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        identifier.insert_str(0, "id_");
+    }
+    identifier
+}
+
+/// Returns true if `identifier` looks like a constructor of the `Wasm` library, such as
+/// `BI_call`, `T_i32`, `VAL_int32` or `Binop_i`: an upper-case letter followed by letters
+/// and an `_`.
+fn is_library_constructor(identifier: &str) -> bool {
+    let mut chars = identifier.chars();
+    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars
+            .find(|c| !c.is_ascii_alphabetic())
+            .is_some_and(|c| c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_invalid_characters() {
+        assert_eq!(sanitize("add"), "add");
+        assert_eq!(sanitize("my-module"), "my_module");
+        assert_eq!(sanitize("core::fmt::write"), "core__fmt__write");
+        assert_eq!(sanitize("$x.y'"), "_x_y'");
+        assert_eq!(sanitize("2nd"), "id_2nd");
+        assert_eq!(sanitize("'a"), "id_'a");
+        assert_eq!(sanitize(""), "id_");
+        assert_eq!(sanitize("größe"), "gr__e");
+    }
+
+    #[test]
+    fn reserved_names_get_a_suffix() {
+        let mut identifiers = Identifiers::default();
+        assert_eq!(identifiers.allocate("fun"), "fun_");
+        assert_eq!(identifiers.allocate("nil"), "nil_");
+        assert_eq!(identifiers.allocate("Vi32"), "Vi32_");
+        assert_eq!(identifiers.allocate("BI_call"), "BI_call_");
+        assert_eq!(identifiers.allocate("modfunc_body"), "modfunc_body_");
+        assert_eq!(identifiers.allocate("_"), "__");
+        assert_eq!(identifiers.allocate("Main"), "Main");
+        assert_eq!(identifiers.allocate("run_all"), "run_all");
+    }
+
+    #[test]
+    fn collisions_get_a_numeric_suffix() {
+        let mut identifiers = Identifiers::default();
+        assert_eq!(identifiers.allocate("add"), "add");
+        assert_eq!(identifiers.allocate("add"), "add_1");
+        assert_eq!(identifiers.allocate("add_1"), "add_1_1");
+        assert_eq!(identifiers.allocate("add-1"), "add_1_2");
+        assert_eq!(identifiers.allocate("nil"), "nil_");
+        assert_eq!(identifiers.allocate("nil"), "nil__1");
+    }
+}
@@ -0,0 +1,188 @@
+//! Translation Coverage Report
+//!
+//! A translation stops at the first function with an instruction that has no Rocq
+//! counterpart. [`TranslationReport`] is the alternative for tooling: it is returned by
+//! [`crate::wasm_parser::translate_bytes_with_report`] next to the Rocq code, which is
+//! produced with such functions skipped, and lists everything that was left out.
+//!
+//! The report records:
+//!
+//! - **Instructions**: The number of instructions translated, and each unsupported
+//!   instruction with its function and byte offset in the module
+//! - **Functions**: The number of functions translated, and each skipped function with the
+//!   error that caused it to be skipped
+//! - **Sections**: The size in bytes of each section of the module, in module order
+//!
+//! The CLI prints the report with its [`std::fmt::Display`] implementation, and CI can
+//! gate on [`TranslationReport::is_complete`].
+
+use std::fmt;
+
+/// Statistics of one translation, and the parts of the module it left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TranslationReport {
+    /// Number of instructions of the defined functions that were translated.
+    pub instructions_translated: usize,
+
+    /// Instructions without a Rocq translation, in module order.
+    pub unsupported_instructions: Vec<UnsupportedInstruction>,
+
+    /// Number of defined functions whose `module_func` definition was written.
+    pub functions_translated: usize,
+
+    /// Defined functions left out of the output, in module order.
+    pub skipped_functions: Vec<SkippedFunction>,
+
+    /// Number of defined functions declared without their body, because the
+    /// configuration did not select them.
+    pub functions_declared: usize,
+
+    /// Sizes of the sections of the module, in module order.
+    pub section_sizes: Vec<SectionSize>,
+}
+
+/// An instruction without a Rocq translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedInstruction {
+    /// Index of the function containing the instruction, counting imported functions.
+    pub function_index: u32,
+
+    /// Byte offset of the instruction in the module.
+    pub offset: usize,
+
+    /// Why the instruction could not be translated.
+    pub message: String,
+}
+
+/// A defined function left out of the output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFunction {
+    /// Index of the function, counting imported functions.
+    pub function_index: u32,
+
+    /// Rocq identifier the function definition would have had.
+    pub name: String,
+
+    /// The first error of the function's translation.
+    pub error: String,
+}
+
+/// Size of one section of the module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionSize {
+    /// Name of the section, such as `code`, or `custom "name"` for a custom section.
+    pub name: String,
+
+    /// Size of the section contents in bytes, without the section id and size.
+    pub size: usize,
+}
+
+impl TranslationReport {
+    /// Returns the number of instructions of the defined functions, translated or not.
+    #[must_use]
+    pub fn total_instructions(&self) -> usize {
+        self.instructions_translated + self.unsupported_instructions.len()
+    }
+
+    /// Returns the percentage of instructions that were translated, or 100 for a module
+    /// without instructions.
+    #[must_use]
+    pub fn coverage(&self) -> f64 {
+        let total = self.total_instructions();
+        if total == 0 {
+            return 100.0;
+        }
+        self.instructions_translated as f64 * 100.0 / total as f64
+    }
+
+    /// Returns true if every instruction and every function was translated.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.unsupported_instructions.is_empty() && self.skipped_functions.is_empty()
+    }
+}
+
+impl fmt::Display for TranslationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Instructions: {}/{} translated ({:.1}%)",
+            self.instructions_translated,
+            self.total_instructions(),
+            self.coverage()
+        )?;
+        write!(
+            f,
+            "Functions: {}/{} translated",
+            self.functions_translated,
+            self.functions_translated + self.skipped_functions.len()
+        )?;
+        if self.functions_declared > 0 {
+            write!(f, ", {} declared without body", self.functions_declared)?;
+        }
+        writeln!(f)?;
+        for instruction in &self.unsupported_instructions {
+            writeln!(
+                f,
+                "  unsupported at 0x{:x} in function {}: {}",
+                instruction.offset, instruction.function_index, instruction.message
+            )?;
+        }
+        for function in &self.skipped_functions {
+            writeln!(
+                f,
+                "  skipped function {} ({}): {}",
+                function.function_index, function.name, function.error
+            )?;
+        }
+        writeln!(f, "Sections:")?;
+        for section in &self.section_sizes {
+            writeln!(f, "  {}: {} bytes", section.name, section.size)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coverage_counts_unsupported_instructions() {
+        let mut report = TranslationReport {
+            instructions_translated: 3,
+            ..TranslationReport::default()
+        };
+        assert!((report.coverage() - 100.0).abs() < f64::EPSILON);
+        assert!(report.is_complete());
+
+        report
+            .unsupported_instructions
+            .push(UnsupportedInstruction {
+                function_index: 1,
+                offset: 0x2a,
+                message: "Exception handling instruction Throw { tag_index: 0 } is not supported"
+                    .to_string(),
+            });
+        assert_eq!(report.total_instructions(), 4);
+        assert!((report.coverage() - 75.0).abs() < f64::EPSILON);
+        assert!(!report.is_complete());
+        assert!(
+            report
+                .to_string()
+                .contains("Instructions: 3/4 translated (75.0%)\n")
+        );
+        assert!(
+            report
+                .to_string()
+                .contains("unsupported at 0x2a in function 1")
+        );
+    }
+
+    #[test]
+    fn empty_module_is_fully_covered() {
+        let report = TranslationReport::default();
+        assert!((report.coverage() - 100.0).abs() < f64::EPSILON);
+        assert!(report.is_complete());
+    }
+}
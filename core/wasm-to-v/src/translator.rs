@@ -12,7 +12,8 @@
 //!
 //! ## Translation Process
 //!
-//! The translation happens in [`WasmParseData::translate`] through these steps:
+//! The translation happens in [`WasmParseData::translate_to`], which writes the output
+//! to an [`std::io::Write`] as it is produced, through these steps:
 //!
 //! 1. **Generate Header**: Rocq imports from standard libraries (`List`, `String`, `BinNat`, `ZArith`, `Wasm`)
 //! 2. **Generate Helpers**: Convenience constructors (`Vi32`, `Vi64`, `Mt`, `Mm`, `Mg`, `Mi`, `Me`, `Ma`)
@@ -30,7 +31,9 @@
 //! ## Code Generation Strategy
 //!
 //! The translator generates Rocq code as strings using helper functions for each
-//! WASM construct. This string-based approach prioritizes:
+//! WASM construct, and writes each function definition and module record entry as soon as
+//! it is translated, so the whole output never has to fit in memory. This string-based
+//! approach prioritizes:
 //!
 //! - **Correctness**: Direct mapping from WASM semantics to Rocq types
 //! - **Readability**: Well-formatted output with consistent indentation
@@ -103,23 +106,29 @@
 //!
 //! ## Error Recovery
 //!
-//! Unlike the parser (which fails fast), the translator uses **error recovery**:
+//! Unlike the parser (which fails fast), the translator uses **error recovery** for the
+//! module record:
 //!
-//! 1. Collect translation errors from all sections into a `Vec<anyhow::Error>`
-//! 2. Continue translating remaining sections even after errors
-//! 3. Return the first error only if translation failed
+//! 1. Leave out the section entries that fail to translate
+//! 2. Continue translating the remaining entries and sections
 //!
-//! This approach provides better diagnostics by showing multiple related errors
-//! instead of requiring users to fix one error at a time.
+//! Functions are the exception: since their definitions are written as they are
+//! translated, the first function that fails to translate stops the translation with its
+//! error.
 //!
 //! ## Name Generation
 //!
 //! Generated Rocq identifiers follow these rules:
 //!
 //! - **Named functions**: Use names from custom name section if available
-//! - **Anonymous functions**: Generate unique names using UUID (`func_<uuid>`)
+//! - **Anonymous functions**: `func_<index>`, with the index in the function index space
 //! - **Module name**: Use name from custom section, or parameter to `translate_bytes`
 //!
+//! Names are made valid Rocq identifiers, and unique within the file, by
+//! `crate::names`. The module name is allocated first, then the functions in index
+//! order. Calls, `ref.func` and local accesses are followed by a comment with the name of
+//! the function or local they refer to, such as `BI_call 0 (*log*)`.
+//!
 //! ## Output Format
 //!
 //! The generated Rocq file has this structure:
@@ -143,6 +152,12 @@
 //! Definition func_0 : module_func := ...
 //! Definition func_1 : module_func := ...
 //!
+//! (* Segments and initial state *)
+//! Definition module_name_data_0 : list byte := ...
+//! Definition module_name_elem_0 : list (option N) := ...
+//! Definition module_name_initial_memory_0 : list (N * list byte) := ...
+//! Definition module_name_initial_table_0 : list (N * list (option N)) := ...
+//!
 //! (* Module record *)
 //! Definition module_name : module := {|
 //!   mod_types := ...;
@@ -158,17 +173,57 @@
 //! |}.
 //! ```
 
-use core::fmt;
-use std::{collections::HashMap, fmt::Display};
+use std::{cell::Cell, collections::HashMap, io::Write};
 
 use inf_wasmparser::{
-    BlockType, CompositeInnerType, Data, DataKind, Element, ElementItems, ElementKind, Export,
-    FunctionBody, Global, Import, MemoryType, Operator, OperatorsIterator, OperatorsReader,
-    RecGroup, RefType, Table, TableType, TypeRef, ValType as wpValType,
+    BlockType, CompositeInnerType, ConstExpr, Data, DataKind, Element, ElementItems, ElementKind,
+    Export, FunctionBody, Global, HeapType, Import, MemoryType, Operator, OperatorsIterator,
+    OperatorsReader, RecGroup, RefType, Table, TableType, TypeRef, ValType as wpValType,
+};
+
+use crate::{
+    config::{FunctionSelection, TranslationConfig},
+    names::{Identifiers, sanitize},
+    report::{SectionSize, SkippedFunction, TranslationReport, UnsupportedInstruction},
+    verification::{
+        VerificationBlock, VerificationMetadata, VerifiedFunction, is_verification_block,
+    },
 };
-use uuid::Uuid;
 
 const LCB: &str = "{|\n";
+
+/// Name of the module type of the nondeterministic instructions.
+const NONDETERMINISM_MODULE_TYPE_NAME: &str = "Nondeterminism";
+
+/// Name of the functor wrapping the definitions when nondeterminism is parameterized.
+const NONDETERMINISM_FUNCTOR: &str = "Make";
+
+/// Name of the functor parameter providing the nondeterministic instructions.
+const NONDETERMINISM_PARAMETER: &str = "ND";
+
+/// Declares the nondeterministic instructions, so their semantics are chosen by the
+/// module the generated functor is applied to.
+const NONDETERMINISM_MODULE_TYPE: &str = "\
+(** The nondeterministic instructions of Inference. The generated definitions are a
+    functor over this module type, so the semantics of the instructions, such as angelic
+    or demonic nondeterminism, is chosen by the module the functor is applied to. *)
+Module Type Nondeterminism.
+  (** [uzumaki t] pushes an arbitrary value of the number type [t]. *)
+  Parameter uzumaki : number_type -> basic_instruction.
+  (** [forall_block bt body] holds if [body] succeeds for every choice of its
+      [uzumaki] values. *)
+  Parameter forall_block : block_type -> list basic_instruction -> basic_instruction.
+  (** [exists_block bt body] holds if [body] succeeds for some choice of its
+      [uzumaki] values. *)
+  Parameter exists_block : block_type -> list basic_instruction -> basic_instruction.
+  (** [assume_block bt body] discards the executions in which [body] does not hold. *)
+  Parameter assume_block : block_type -> list basic_instruction -> basic_instruction.
+  (** [unique_block bt body] holds if exactly one choice of the [uzumaki] values of
+      [body] succeeds. *)
+  Parameter unique_block : block_type -> list basic_instruction -> basic_instruction.
+End Nondeterminism.
+
+";
 const RCB_DOT: &str = "|}.\n";
 
 const LIST_EXT: &str = " ::\n";
@@ -205,10 +260,11 @@ const LIST_SEAL: &str = "nil";
 /// - `function_types`: Function type signatures (as recursion groups)
 /// - `function_type_indexes`: Maps function index → type index
 /// - `function_bodies`: Function code with locals and instructions
+/// - `section_sizes`: Sizes of the sections, for the [`TranslationReport`]
+/// - `verification`: Source locations from the verification metadata section
 ///
 /// ## Translation State (private)
 /// - `translated_function_names`: Accumulates Rocq function names during translation
-/// - `translated_functions_string`: Accumulates Rocq function definitions during translation
 pub(crate) struct WasmParseData<'a> {
     pub(crate) mod_name: String,
     pub(crate) func_names_map: Option<HashMap<u32, String>>,
@@ -226,9 +282,10 @@ pub(crate) struct WasmParseData<'a> {
     pub(crate) function_types: Vec<RecGroup>,
     pub(crate) function_type_indexes: Vec<u32>,
     pub(crate) function_bodies: Vec<FunctionBody<'a>>,
+    pub(crate) section_sizes: Vec<SectionSize>,
+    pub(crate) verification: Option<VerificationMetadata>,
 
     translated_function_names: Vec<String>,
-    translated_functions_string: String,
 }
 
 impl WasmParseData<'_> {
@@ -256,311 +313,935 @@ impl WasmParseData<'_> {
             function_types: Vec::new(),
             function_type_indexes: Vec::new(),
             function_bodies: Vec::new(),
+            section_sizes: Vec::new(),
+            verification: None,
 
             translated_function_names: Vec::new(),
-            translated_functions_string: String::new(),
         }
     }
 
     /// Translates the parsed WASM data into complete Rocq code.
     ///
+    /// Collects the output of [`WasmParseData::translate_to`] into a `String`. Prefer
+    /// `translate_to` for large modules, which does not hold the whole output in memory.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `String` containing complete Rocq code ready to write to a `.v` file.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`WasmParseData::translate_to`].
+    pub(crate) fn translate(
+        &mut self,
+        config: &TranslationConfig,
+    ) -> anyhow::Result<String /* WasmModuleParseError*/> {
+        let mut res = Vec::new();
+        self.translate_to(&mut res, config)?;
+        Ok(String::from_utf8(res)?)
+    }
+
+    /// Translates the parsed WASM data into complete Rocq code written to `out`.
+    ///
     /// This is the main translation entry point. It generates a complete Rocq file
     /// including imports, helper definitions, and a module record containing all
-    /// translated WASM sections.
+    /// translated WASM sections. The output is written incrementally, one function
+    /// definition or record entry at a time, so its size is not bounded by memory.
     ///
     /// # Translation Steps
     ///
     /// 1. Generate Rocq imports and helper definitions
-    /// 2. Translate each WASM section to Rocq definitions:
-    ///    - Imports → `Mi` records
-    ///    - Exports → `Me` records
+    /// 2. Translate each function body to a `module_func` definition
+    /// 3. Define the bytes of the data segments, the items of the element segments, and
+    ///    the initial contents of each memory and table
+    /// 4. Assemble the module record, translating each WASM section entry as it is
+    ///    written:
+    ///    - Function types → type signatures
+    ///    - Functions → names of the `module_func` definitions
     ///    - Tables → `Mt` definitions
     ///    - Memory → `Mm` definitions
     ///    - Globals → `Mg` definitions
-    ///    - Data segments → data initialization
     ///    - Element segments → table initialization
-    ///    - Function types → type signatures
-    ///    - Functions → `module_func` definitions
-    /// 3. Assemble module record with all translated components
+    ///    - Data segments → data initialization, referring to their byte definitions
+    ///    - Imports → `Mi` records
+    ///    - Exports → `Me` records
     ///
     /// # Error Recovery
     ///
-    /// Unlike the parser, this method uses error recovery: it collects translation
-    /// errors from all sections and returns the first error only if no sections
-    /// succeeded. This provides better diagnostics for complex failures.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `String` containing complete Rocq code ready to write to a `.v` file.
+    /// Unlike the parser, this method uses error recovery for the module record: a
+    /// section entry that fails to translate is left out of its list, and the remaining
+    /// entries are still written. A function that fails to translate stops the
+    /// translation, since the definitions before it have already been written.
     ///
     /// # Errors
     ///
-    /// Returns an error if translation of any section fails due to:
+    /// Returns an error if writing to `out` fails, or if translation of a function fails
+    /// due to:
     /// - Unsupported WASM features (tags, unknown reference types)
     /// - Invalid WASM data (malformed expressions, out-of-bounds indices)
     /// - Unimplemented instruction opcodes
-    #[allow(clippy::too_many_lines)]
-    pub(crate) fn translate(&mut self) -> anyhow::Result<String /* WasmModuleParseError*/> {
-        let mut res = String::new();
-        res.push_str("Require Import List.\n");
-        res.push_str("Require Import String.\n");
-        res.push_str("Require Import BinNat.\n");
-        res.push_str("Require Import ZArith.\n");
-        res.push_str("From Wasm Require Import bytes.\n");
-        res.push_str("From Wasm Require Import numerics.\n");
-        res.push_str("From Wasm Require Import datatypes.\n");
-        res.push('\n');
-        res.push_str("Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).\n");
-        res.push_str("Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).\n");
-        res.push_str(
-            "Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.\n",
-        );
-        res.push_str("Definition Mm l := {|modmem_type := l|}.\n");
-        res.push_str("Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.\n");
-        res.push('\n');
-        res.push_str("Definition Mi m n d := {|\n");
-        res.push_str("  imp_module := list_byte_of_string m;\n");
-        res.push_str("  imp_name := list_byte_of_string n;\n");
-        res.push_str("  imp_desc := d;\n");
-        res.push_str("|}.\n");
-        res.push('\n');
-        res.push_str("Definition Me n d := {|\n");
-        res.push_str("  modexp_name := list_byte_of_string n;\n");
-        res.push_str("  modexp_desc := d;\n");
-        res.push_str("|}.\n");
-        res.push('\n');
-        res.push_str("Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.\n");
-        res.push('\n');
+    pub(crate) fn translate_to<W: Write + ?Sized>(
+        &mut self,
+        out: &mut W,
+        config: &TranslationConfig,
+    ) -> anyhow::Result<()> {
+        self.write_translation(out, config, None)
+    }
+
+    /// Translates the parsed WASM data into complete Rocq code, and reports the coverage
+    /// of the translation.
+    ///
+    /// Unlike [`WasmParseData::translate`], a function that fails to translate does not
+    /// stop the translation: its definition is replaced by a comment, it is left out of
+    /// `mod_funcs`, and it is listed in the report. Every instruction is checked, so the
+    /// report lists all unsupported instructions of a function, not only the first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the operators of a function body cannot be read.
+    pub(crate) fn translate_with_report(
+        &mut self,
+        config: &TranslationConfig,
+    ) -> anyhow::Result<(String, TranslationReport)> {
+        let mut report = TranslationReport {
+            section_sizes: self.section_sizes.clone(),
+            ..TranslationReport::default()
+        };
+        let mut res = Vec::new();
+        self.write_translation(&mut res, config, Some(&mut report))?;
+        Ok((String::from_utf8(res)?, report))
+    }
 
-        let mut errors = Vec::new();
+    /// Writes the translation to `out`. Without a `report`, the first function that fails
+    /// to translate stops the translation; with one, such functions are skipped and
+    /// recorded.
+    fn write_translation<W: Write + ?Sized>(
+        &mut self,
+        out: &mut W,
+        config: &TranslationConfig,
+        mut report: Option<&mut TranslationReport>,
+    ) -> anyhow::Result<()> {
+        write_imports(out, config)?;
+        if let Some(module_prefix) = &config.module_prefix {
+            writeln!(out, "Module {module_prefix}.")?;
+            out.write_all(b"\n")?;
+        }
+        let mut out = RocqWriter { out, config };
+        let mut identifiers = Identifiers::default();
+        let module_name = identifiers.allocate(&self.mod_name);
+        let (function_names, named_functions) = self.function_identifiers(&mut identifiers);
+        write_module_doc(&mut out, &module_name, config)?;
+        write_helpers(&mut out)?;
+        if config.parameterize_nondeterminism {
+            out.emit(NONDETERMINISM_MODULE_TYPE)?;
+            out.emit(&format!(
+                "Module {NONDETERMINISM_FUNCTOR} ({NONDETERMINISM_PARAMETER} : {NONDETERMINISM_MODULE_TYPE_NAME}).\n\n"
+            ))?;
+        }
 
-        let mut translated_imports = String::new();
-        for import in &self.imports {
-            match translate_module_import(import) {
-                Ok(translated_import) => {
-                    translated_imports.push_str("    ");
-                    translated_imports.push_str(translated_import.as_str());
-                    translated_imports.push_str(LIST_EXT);
+        for (index, func_name) in function_names.iter().enumerate() {
+            let definition = self.translate_function(index, func_name, &named_functions, config);
+            let Some(report) = report.as_deref_mut() else {
+                out.emit(&definition?)?;
+                self.translated_function_names.push(func_name.clone());
+                continue;
+            };
+            if !self.selects_function(index, config) {
+                out.emit(&definition?)?;
+                self.translated_function_names.push(func_name.clone());
+                report.functions_declared += 1;
+                continue;
+            }
+            self.report_instructions(index, report)?;
+            match definition {
+                Ok(definition) => {
+                    out.emit(&definition)?;
+                    self.translated_function_names.push(func_name.clone());
+                    report.functions_translated += 1;
                 }
-                Err(e) => {
-                    errors.push(e);
+                Err(error) => {
+                    out.emit(&format!("(* Skipped: {error} *)\n\n"))?;
+                    report.skipped_functions.push(SkippedFunction {
+                        function_index: self.imported_function_count() + index as u32,
+                        name: func_name.clone(),
+                        error: error.to_string(),
+                    });
                 }
             }
         }
-        translated_imports.push_str("    ");
-        translated_imports.push_str(LIST_SEAL);
+        let data_names = self.translate_initial_state(&mut out, &mut identifiers, &module_name)?;
+        self.write_module_record(&mut out, &module_name, &function_names, &data_names)?;
+        if config.parameterize_nondeterminism {
+            out.emit(&format!("End {NONDETERMINISM_FUNCTOR}.\n"))?;
+        }
+        if let Some(module_prefix) = &config.module_prefix {
+            // The module name is chosen by the caller and is not renamed.
+            writeln!(out.out, "End {module_prefix}.")?;
+        }
+        Ok(())
+    }
+
+    /// Translates the parsed WASM data into one Rocq file per function, so that the
+    /// functions of a large module can be checked in parallel, and re-checked only when
+    /// they change.
+    ///
+    /// Returns, in dependency order:
+    ///
+    /// 1. `<module>_prelude`: the imports and helper definitions
+    /// 2. `<module>_<function>` for every function: its `module_func` definition
+    /// 3. `<module>`: the segments, the initial state and the module record
+    ///
+    /// Every file starts with the imports of `config`, and requires the files it depends
+    /// on with `Require Import`, or `From <module_prefix> Require Import` if
+    /// [`TranslationConfig::module_prefix`] is set. The definitions are the same as those
+    /// of [`WasmParseData::translate_to`], and are not wrapped in a `Module`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`WasmParseData::translate_to`].
+    pub(crate) fn translate_files(
+        &mut self,
+        config: &TranslationConfig,
+    ) -> anyhow::Result<Vec<RocqFile>> {
+        if config.parameterize_nondeterminism {
+            return Err(anyhow::anyhow!(
+                "Parameterized nondeterminism is not supported for output split per function"
+            ));
+        }
+        let mut identifiers = Identifiers::default();
+        let module_name = identifiers.allocate(&self.mod_name);
+        let (function_names, named_functions) = self.function_identifiers(&mut identifiers);
 
-        let mut created_exports = String::new();
-        for export in &self.exports {
-            match translate_export_module(export) {
-                Ok(translated_export) => {
-                    created_exports.push_str("    ");
-                    created_exports.push_str(translated_export.as_str());
-                    created_exports.push_str(LIST_EXT);
-                }
-                Err(e) => {
-                    errors.push(e);
+        let mut file_names = Identifiers::default();
+        let module_file = file_names.allocate(&module_name);
+        let prelude_file = file_names.allocate(&format!("{module_name}_prelude"));
+        let mut files = vec![rocq_file(prelude_file.clone(), config, &[], |out| {
+            write_helpers(out)?;
+            Ok(())
+        })?];
+        for (index, func_name) in function_names.iter().enumerate() {
+            let definition = self.translate_function(index, func_name, &named_functions, config)?;
+            let function_file = file_names.allocate(&format!("{module_name}_{func_name}"));
+            files.push(rocq_file(
+                function_file,
+                config,
+                std::slice::from_ref(&prelude_file),
+                |out| Ok(out.emit(&definition)?),
+            )?);
+            self.translated_function_names.push(func_name.clone());
+        }
+        let mut requires = vec![prelude_file];
+        requires.extend(files[1..].iter().map(|file| file.name.clone()));
+        files.push(rocq_file(module_file, config, &requires, |out| {
+            write_module_doc(out, &module_name, config)?;
+            let data_names = self.translate_initial_state(out, &mut identifiers, &module_name)?;
+            self.write_module_record(out, &module_name, &function_names, &data_names)
+        })?);
+        Ok(files)
+    }
+
+    /// Writes the module record, whose `mod_funcs` lists the translated functions and
+    /// whose `mod_datas` refers to the data segment definitions called `data_names`,
+    /// preceded by a coqdoc comment listing the exports. `function_names` are the
+    /// identifiers of the defined functions, which the comment refers to.
+    fn write_module_record<W: Write + ?Sized>(
+        &self,
+        out: &mut RocqWriter<'_, W>,
+        module_name: &str,
+        function_names: &[String],
+        data_names: &[String],
+    ) -> anyhow::Result<()> {
+        if !self.exports.is_empty() {
+            out.emit(&format!("(** Exports of [{module_name}]:\n"))?;
+            for export in &self.exports {
+                out.emit(&format!(
+                    "    - [\"{}\"]: {}\n",
+                    export.name,
+                    self.export_doc(export, function_names)
+                ))?;
+            }
+            out.emit("*)\n")?;
+        }
+        out.emit(&format!("Definition {module_name} : module := {LCB}"))?;
+        write_list(
+            out,
+            "mod_types",
+            self.function_types.iter().map(translate_function_type),
+        )?;
+        write_list(
+            out,
+            "mod_funcs",
+            self.translated_function_names
+                .iter()
+                .map(|function_name| Ok(function_name.clone())),
+        )?;
+        write_list(
+            out,
+            "mod_tables",
+            self.tables.iter().map(translate_table_type),
+        )?;
+        write_list(
+            out,
+            "mod_mems",
+            self.memory_types.iter().map(translate_memory_type),
+        )?;
+        write_list(
+            out,
+            "mod_globals",
+            self.globals.iter().map(translate_global),
+        )?;
+        write_list(
+            out,
+            "mod_elems",
+            self.elements.iter().map(translate_element),
+        )?;
+        write_list(
+            out,
+            "mod_datas",
+            self.data
+                .iter()
+                .zip(data_names)
+                .map(|(data, data_name)| translate_data(data, data_name)),
+        )?;
+        if let Some(start_function) = self.start_function {
+            out.emit(&format!(
+                "  mod_start := Some {{|modstart_func := {start_function}%N|}};\n"
+            ))?;
+        } else {
+            out.emit("  mod_start := None;\n")?;
+        }
+        write_list(
+            out,
+            "mod_imports",
+            self.imports.iter().map(translate_module_import),
+        )?;
+        write_list(
+            out,
+            "mod_exports",
+            self.exports.iter().map(translate_export_module),
+        )?;
+        out.emit(RCB_DOT)?;
+        Ok(())
+    }
+
+    /// Allocates the identifiers of the function definitions, after the identifier of the
+    /// module record has been allocated from `identifiers`.
+    ///
+    /// Returns the identifier of each defined function, and the names of the functions
+    /// named by the name section by function index, including imported functions, for
+    /// the comments next to calls. Functions without a name are called
+    /// `func_<function index>`, so the output only depends on the module.
+    fn function_identifiers(
+        &self,
+        identifiers: &mut Identifiers,
+    ) -> (Vec<String>, HashMap<u32, String>) {
+        let imported_functions = self.imported_function_count();
+        let mut named_functions = HashMap::new();
+        if let Some(func_names_map) = &self.func_names_map {
+            for (&function_index, name) in func_names_map {
+                if function_index < imported_functions {
+                    named_functions.insert(function_index, sanitize(name));
                 }
             }
         }
-        created_exports.push_str("    ");
-        created_exports.push_str(LIST_SEAL);
-
-        let mut created_tables = String::new();
-        for table in &self.tables {
-            match translate_table_type(table) {
-                Ok(translated_table_type) => {
-                    created_tables.push_str("    ");
-                    created_tables.push_str(translated_table_type.as_str());
-                    created_tables.push_str(LIST_EXT);
+        let function_names = (0..self.function_bodies.len() as u32)
+            .map(|index| {
+                let function_index = imported_functions + index;
+                match self
+                    .func_names_map
+                    .as_ref()
+                    .and_then(|func_names_map| func_names_map.get(&function_index))
+                {
+                    Some(name) => {
+                        let identifier = identifiers.allocate(name);
+                        named_functions.insert(function_index, identifier.clone());
+                        identifier
+                    }
+                    None => identifiers.allocate(&format!("func_{function_index}")),
                 }
-                Err(e) => {
-                    errors.push(e);
+            })
+            .collect();
+        (function_names, named_functions)
+    }
+
+    /// Returns the number of imported functions, which precede the defined functions in
+    /// the function index space.
+    fn imported_function_count(&self) -> u32 {
+        self.imported_count(|ty| matches!(ty, TypeRef::Func(_)))
+    }
+
+    /// Writes the initial state of the memories and tables, which instantiation builds
+    /// from the active data and element segments, and returns the names of the
+    /// definitions of the data segment bytes for the module record.
+    ///
+    /// Writes, for module `m`:
+    ///
+    /// - `m_data_<i> : list byte`, the bytes of every data segment
+    /// - `m_elem_<i> : list (option N)`, the function indices of every active or passive
+    ///   element segment, where `None` is a null reference
+    /// - `m_initial_memory_<i> : list (N * list byte)`, the offset and bytes of every
+    ///   active data segment of memory `i`, in the order instantiation copies them
+    /// - `m_initial_table_<i> : list (N * list (option N))`, the same for the active
+    ///   element segments of table `i`
+    ///
+    /// Segments whose offset or items are not constants, such as an offset read from an
+    /// imported global, are left out of the initial state, with a comment in its place.
+    fn translate_initial_state<W: Write + ?Sized>(
+        &self,
+        out: &mut RocqWriter<'_, W>,
+        identifiers: &mut Identifiers,
+        module_name: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut data_names = Vec::with_capacity(self.data.len());
+        for (index, data) in self.data.iter().enumerate() {
+            let data_name = identifiers.allocate(&format!("{module_name}_data_{index}"));
+            out.emit(&format!(
+                "Definition {data_name} : list byte := {}.\n",
+                translate_bytes(data.data)
+            ))?;
+            data_names.push(data_name);
+        }
+
+        let mut element_names = Vec::with_capacity(self.elements.len());
+        for (index, element) in self.elements.iter().enumerate() {
+            let items = match element.kind {
+                ElementKind::Declared => None,
+                _ => element_function_indices(element),
+            };
+            let Some(items) = items else {
+                element_names.push(None);
+                continue;
+            };
+            let element_name = identifiers.allocate(&format!("{module_name}_elem_{index}"));
+            let mut init = String::new();
+            for item in items {
+                match item {
+                    Some(function_index) => init.push_str(&format!("Some {function_index}%N :: ")),
+                    None => init.push_str("None :: "),
                 }
             }
+            out.emit(&format!(
+                "Definition {element_name} : list (option N) := {init}nil.\n"
+            ))?;
+            element_names.push(Some(element_name));
+        }
+        if !self.data.is_empty() || !self.elements.is_empty() {
+            out.emit("\n")?;
         }
-        created_tables.push_str("    ");
-        created_tables.push_str(LIST_SEAL);
 
-        let mut created_memory_types = String::new();
-        for memory_type in &self.memory_types {
-            match translate_memory_type(memory_type) {
-                Ok(translated_memory) => {
-                    created_memory_types.push_str("    ");
-                    created_memory_types.push_str(translated_memory.as_str());
-                    created_memory_types.push_str(LIST_EXT);
+        let memory_count = self.imported_count(|ty| matches!(ty, TypeRef::Memory(_)))
+            + self.memory_types.len() as u32;
+        for memory_index in 0..memory_count {
+            let memory_name =
+                identifiers.allocate(&format!("{module_name}_initial_memory_{memory_index}"));
+            out.emit(&format!(
+                "Definition {memory_name} : list (N * list byte) :=\n"
+            ))?;
+            for (index, data) in self.data.iter().enumerate() {
+                let DataKind::Active {
+                    memory_index: segment_memory,
+                    offset_expr,
+                } = &data.kind
+                else {
+                    continue;
+                };
+                if *segment_memory != memory_index {
+                    continue;
                 }
-                Err(e) => {
-                    errors.push(e);
+                match self.constant_offset(offset_expr) {
+                    Some(offset) => out.emit(&format!(
+                        "    ({offset}%N, {}){LIST_EXT}",
+                        data_names[index]
+                    ))?,
+                    None => out.emit(&format!(
+                        "    (* data segment {index} has a non-constant offset *)\n"
+                    ))?,
                 }
             }
+            out.emit(&format!("    {LIST_SEAL}.\n"))?;
         }
-        created_memory_types.push_str("    ");
-        created_memory_types.push_str(LIST_SEAL);
 
-        let mut created_globals = String::new();
-        for global in &self.globals {
-            match translate_global(global) {
-                Ok(translated_global) => {
-                    created_globals.push_str("    ");
-                    created_globals.push_str(translated_global.as_str());
-                    created_globals.push_str(LIST_EXT);
+        let table_count =
+            self.imported_count(|ty| matches!(ty, TypeRef::Table(_))) + self.tables.len() as u32;
+        for table_index in 0..table_count {
+            let table_name =
+                identifiers.allocate(&format!("{module_name}_initial_table_{table_index}"));
+            out.emit(&format!(
+                "Definition {table_name} : list (N * list (option N)) :=\n"
+            ))?;
+            for (index, element) in self.elements.iter().enumerate() {
+                let ElementKind::Active {
+                    table_index: segment_table,
+                    offset_expr,
+                } = &element.kind
+                else {
+                    continue;
+                };
+                if segment_table.unwrap_or_default() != table_index {
+                    continue;
                 }
-                Err(e) => {
-                    errors.push(e);
+                match (self.constant_offset(offset_expr), &element_names[index]) {
+                    (Some(offset), Some(element_name)) => {
+                        out.emit(&format!("    ({offset}%N, {element_name}){LIST_EXT}"))?;
+                    }
+                    (None, _) => out.emit(&format!(
+                        "    (* element segment {index} has a non-constant offset *)\n"
+                    ))?,
+                    (_, None) => out.emit(&format!(
+                        "    (* element segment {index} has non-constant items *)\n"
+                    ))?,
                 }
             }
+            out.emit(&format!("    {LIST_SEAL}.\n"))?;
         }
-        created_globals.push_str("    ");
-        created_globals.push_str(LIST_SEAL);
+        if memory_count > 0 || table_count > 0 {
+            out.emit("\n")?;
+        }
+        Ok(data_names)
+    }
 
-        let mut created_data_segments = String::new();
-        for data in &self.data {
-            match translate_data(data) {
-                Ok(translated_data) => {
-                    created_data_segments.push_str("    ");
-                    created_data_segments.push_str(translated_data.as_str());
-                    created_data_segments.push_str(LIST_EXT);
+    /// Returns the value of the offset of an active segment if it is a constant: an
+    /// `i32.const`, an `i64.const`, or a `global.get` of an immutable global defined by
+    /// the module and initialized by a constant.
+    fn constant_offset(&self, offset_expr: &ConstExpr) -> Option<u64> {
+        match single_operator(offset_expr)? {
+            Operator::GlobalGet { global_index } => {
+                let imported_globals = self.imported_count(|ty| matches!(ty, TypeRef::Global(_)));
+                let global = self
+                    .globals
+                    .get(global_index.checked_sub(imported_globals)? as usize)?;
+                if global.ty.mutable {
+                    return None;
                 }
-                Err(e) => errors.push(e),
+                integer_constant(&single_operator(&global.init_expr)?)
             }
+            operator => integer_constant(&operator),
         }
-        created_data_segments.push_str("    ");
-        created_data_segments.push_str(LIST_SEAL);
+    }
 
-        let mut created_elements = String::new();
-        for element in &self.elements {
-            match translate_element(element) {
-                Ok(translated_element) => {
-                    created_elements.push_str("    ");
-                    created_elements.push_str(translated_element.as_str());
-                    created_elements.push_str(LIST_EXT);
-                }
-                Err(e) => {
-                    errors.push(e);
-                }
+    /// Returns the number of imports whose type satisfies `is_kind`, which precede the
+    /// definitions of the same kind in its index space.
+    fn imported_count(&self, is_kind: impl Fn(&TypeRef) -> bool) -> u32 {
+        self.imports
+            .iter()
+            .filter(|import| is_kind(&import.ty))
+            .count() as u32
+    }
+
+    /// Describes what `export` exports, such as `function [add]` for a defined function
+    /// called `add` in `function_names`.
+    fn export_doc(&self, export: &Export, function_names: &[String]) -> String {
+        let kind = match export.kind {
+            inf_wasmparser::ExternalKind::Func => {
+                let imported_functions = self.imported_function_count();
+                return match export.index.checked_sub(imported_functions) {
+                    Some(index) => match function_names.get(index as usize) {
+                        Some(function_name) => format!("function [{function_name}]"),
+                        None => format!("function {}", export.index),
+                    },
+                    None => format!("imported function {}", export.index),
+                };
             }
+            inf_wasmparser::ExternalKind::Table => "table",
+            inf_wasmparser::ExternalKind::Memory => "memory",
+            inf_wasmparser::ExternalKind::Global => "global",
+            inf_wasmparser::ExternalKind::Tag => "tag",
+        };
+        format!("{kind} {}", export.index)
+    }
+
+    /// Returns the names under which the function `function_index` is exported.
+    fn function_export_names(&self, function_index: u32) -> Vec<&str> {
+        self.exports
+            .iter()
+            .filter(|export| {
+                export.kind == inf_wasmparser::ExternalKind::Func && export.index == function_index
+            })
+            .map(|export| export.name)
+            .collect()
+    }
+
+    /// Returns the coqdoc comment of the defined function `index` called `func_name`: its
+    /// parameters, named from the name section, its results, its export names and its
+    /// location in the source file.
+    fn function_doc(
+        &self,
+        index: usize,
+        func_name: &str,
+        verified_function: Option<&VerifiedFunction>,
+        config: &TranslationConfig,
+    ) -> String {
+        let function_index = self.imported_function_count() + index as u32;
+        let mut doc = format!("(** Function [{func_name}] (index {function_index}).\n");
+        let function_type = self
+            .function_type_indexes
+            .get(index)
+            .and_then(|type_index| self.function_types.get(*type_index as usize))
+            .and_then(|rec_group| rec_group.types().next())
+            .and_then(|sub_type| match &sub_type.composite_type.inner {
+                CompositeInnerType::Func(function_type) => Some(function_type),
+                _ => None,
+            });
+        if let Some(function_type) = function_type {
+            let locals = self
+                .func_locals_name_map
+                .as_ref()
+                .and_then(|func_locals_name_map| func_locals_name_map.get(&function_index));
+            let params: Vec<String> = function_type
+                .params()
+                .iter()
+                .enumerate()
+                .map(|(param_index, param)| {
+                    match locals.and_then(|locals| locals.get(&(param_index as u32))) {
+                        Some(name) => format!("[{} : {param}]", sanitize(name)),
+                        None => format!("[{param}]"),
+                    }
+                })
+                .collect();
+            let results: Vec<String> = function_type
+                .results()
+                .iter()
+                .map(|result| format!("[{result}]"))
+                .collect();
+            doc.push_str(&format!("    Parameters: {}.\n", doc_list(&params)));
+            doc.push_str(&format!("    Results: {}.\n", doc_list(&results)));
+        }
+        let export_names: Vec<String> = self
+            .function_export_names(function_index)
+            .iter()
+            .map(|export_name| format!("[\"{export_name}\"]"))
+            .collect();
+        if !export_names.is_empty() {
+            doc.push_str(&format!("    Exported as {}.\n", export_names.join(", ")));
+        }
+        let location = verified_function.map(|verified_function| verified_function.location);
+        match (&config.source_file, location) {
+            (Some(source_file), Some(location)) => {
+                doc.push_str(&format!("    Source: [\"{source_file}\"] at {location}.\n"));
+            }
+            (Some(source_file), None) => {
+                doc.push_str(&format!("    Source: [\"{source_file}\"].\n"));
+            }
+            (None, Some(location)) => doc.push_str(&format!("    Source: {location}.\n")),
+            (None, None) => {}
         }
-        created_elements.push_str("    ");
-        created_elements.push_str(LIST_SEAL);
-
-        let mut created_function_types = String::new();
-        for rec_group in &self.function_types {
-            // created_function_types.push(LRB);
-            match translate_function_type(rec_group) {
-                Ok(translated_function_type) => {
-                    created_function_types.push_str("    ");
-                    created_function_types.push_str(translated_function_type.as_str());
-                    created_function_types.push_str(LIST_EXT);
-                }
-                Err(e) => {
-                    errors.push(e);
-                }
+        doc.push_str("*)\n");
+        doc
+    }
+
+    /// Returns true if the defined function `index` is translated with its body, rather
+    /// than declared as a `Parameter`.
+    fn selects_function(&self, index: usize, config: &TranslationConfig) -> bool {
+        if config.interface_only {
+            return false;
+        }
+        let function_index = self.imported_function_count() + index as u32;
+        match &config.functions {
+            FunctionSelection::All => true,
+            FunctionSelection::Exported => !self.function_export_names(function_index).is_empty(),
+            FunctionSelection::Named(names) => {
+                let is_selected = |name: &str| names.iter().any(|selected| selected == name);
+                self.func_names_map
+                    .as_ref()
+                    .and_then(|func_names_map| func_names_map.get(&function_index))
+                    .is_some_and(|name| is_selected(name))
+                    || self
+                        .function_export_names(function_index)
+                        .into_iter()
+                        .any(is_selected)
             }
         }
-        created_function_types.push_str("    ");
-        created_function_types.push_str(LIST_SEAL);
-
-        let mut created_functions = String::new();
-        match self.translate_functions() {
-            Ok(_) => {
-                res.push_str(self.translated_functions_string.as_str());
-                for function_name in &self.translated_function_names {
-                    created_functions.push_str("    ");
-                    created_functions.push_str(function_name.as_str());
-                    created_functions.push_str(LIST_EXT);
-                }
+    }
+
+    /// Returns the verification metadata of the function `function_index`, found by its
+    /// name in the name section or by its export names.
+    fn verified_function(&self, function_index: u32) -> Option<&VerifiedFunction> {
+        let verification = self.verification.as_ref()?;
+        let name = self
+            .func_names_map
+            .as_ref()
+            .and_then(|func_names_map| func_names_map.get(&function_index))
+            .map(String::as_str);
+        verification.function(name, &self.function_export_names(function_index))
+    }
+
+    /// Returns the source locations of the verification blocks of the defined function
+    /// `index`, or `None` if the function does not have one block instruction for each
+    /// block of `verified_function`, since the locations could then be attributed to the
+    /// wrong blocks.
+    fn source_blocks<'m>(
+        &self,
+        index: usize,
+        verified_function: &'m VerifiedFunction,
+    ) -> anyhow::Result<Option<SourceBlocks<'m>>> {
+        let mut block_count = 0;
+        for operator in self.function_bodies[index].get_operators_reader()? {
+            if is_verification_block(&operator?) {
+                block_count += 1;
             }
-            Err(e) => {
-                errors.push(e);
+        }
+        Ok(
+            (block_count == verified_function.blocks.len()).then(|| SourceBlocks {
+                blocks: &verified_function.blocks,
+                next: Cell::new(0),
+            }),
+        )
+    }
+
+    /// Adds the instructions of the defined function `index` to `report`, as translated or
+    /// unsupported. Like the function body, `else` and `end` are not counted.
+    fn report_instructions(
+        &self,
+        index: usize,
+        report: &mut TranslationReport,
+    ) -> anyhow::Result<()> {
+        let operators = self.function_bodies[index].get_operators_reader()?;
+        for operator in operators.into_iter_with_offsets() {
+            let (operator, offset) = operator?;
+            if matches!(operator, Operator::Else | Operator::End) {
+                continue;
+            }
+            match translate_basic_operator(&operator, IndexNames::default()) {
+                Ok(_) => report.instructions_translated += 1,
+                Err(error) => report
+                    .unsupported_instructions
+                    .push(UnsupportedInstruction {
+                        function_index: self.imported_function_count() + index as u32,
+                        offset,
+                        message: error.to_string(),
+                    }),
             }
         }
-        created_functions.push_str("    ");
-        created_functions.push_str(LIST_SEAL);
-
-        //Record module
-        let module_name = &self.mod_name;
-        res.push_str(format!("Definition {module_name} : module := ").as_str());
-        res.push_str(LCB);
-        res.push_str(format!("  mod_types :=\n{created_function_types};\n").as_str());
-        res.push_str(format!("  mod_funcs :=\n{created_functions};\n").as_str());
-        res.push_str(format!("  mod_tables :=\n{created_tables};\n").as_str());
-        res.push_str(format!("  mod_mems :=\n{created_memory_types};\n").as_str());
-        res.push_str(format!("  mod_globals :=\n{created_globals};\n").as_str());
-        res.push_str(format!("  mod_elems :=\n{created_elements};\n").as_str());
-        res.push_str(format!("  mod_datas :=\n{created_data_segments};\n").as_str());
-        if let Some(start_function) = self.start_function {
-            res.push_str(
-                format!("  mod_start := Some {{|modstart_func := {start_function}%N|}};\n")
-                    .as_str(),
-            );
-        } else {
-            res.push_str("  mod_start := None;\n");
+        Ok(())
+    }
+
+    //Record module_func
+    /// Translates the defined function `index` into the `module_func` definition called
+    /// `func_name`, followed by a blank line. A function that `config` does not select is
+    /// declared as a `Parameter` instead, without translating its body.
+    fn translate_function(
+        &self,
+        index: usize,
+        func_name: &str,
+        named_functions: &HashMap<u32, String>,
+        config: &TranslationConfig,
+    ) -> anyhow::Result<String> {
+        let function_body = &self.function_bodies[index];
+        let verified_function =
+            self.verified_function(self.imported_function_count() + index as u32);
+        if !self.selects_function(index, config) {
+            let mut res = self.function_doc(index, func_name, verified_function, config);
+            res.push_str(&format!("Parameter {func_name} : module_func.\n\n"));
+            return Ok(res);
+        }
+        let source_blocks = verified_function
+            .map(|verified_function| self.source_blocks(index, verified_function))
+            .transpose()?
+            .flatten();
+        let modfunc_type = *self.function_type_indexes.get(index).unwrap_or(&0);
+
+        let mut modfunc_locals = String::new();
+        if let Ok(locals_reader) = function_body.get_locals_reader() {
+            for local in locals_reader {
+                let (reps, val_type) = local.unwrap();
+                let val_type = translate_value_type(&val_type)?;
+                for _ in 0..reps {
+                    modfunc_locals.push_str(format!("{val_type} :: ").as_str());
+                }
+            }
         }
-        res.push_str(format!("  mod_imports :=\n{translated_imports};\n").as_str());
-        res.push_str(format!("  mod_exports :=\n{created_exports};\n").as_str());
+        modfunc_locals.push_str("nil");
+
+        let names = IndexNames {
+            functions: Some(named_functions),
+            locals: self
+                .func_locals_name_map
+                .as_ref()
+                .and_then(|func_locals_name_map| {
+                    func_locals_name_map.get(&(self.imported_function_count() + index as u32))
+                }),
+            nondeterminism: config
+                .parameterize_nondeterminism
+                .then_some(NONDETERMINISM_PARAMETER),
+            source_blocks: source_blocks.as_ref(),
+        };
+        let modfunc_body = translate_expr(&mut function_body.get_operators_reader()?, names)
+            .map_err(|error| anyhow::anyhow!("Function {func_name}: {error}"))?;
+
+        let mut res = self.function_doc(index, func_name, verified_function, config);
+        res.push_str(&format!("Definition {func_name} : module_func := {LCB}"));
+        res.push_str(&format!("  modfunc_type := {modfunc_type}%N;\n"));
+        res.push_str(&format!("  modfunc_locals := {modfunc_locals};\n"));
+        res.push_str(&format!("  modfunc_body :=\n{modfunc_body};\n"));
         res.push_str(RCB_DOT);
+        res.push('\n');
         Ok(res)
     }
+}
 
-    //Record module_func
-    fn translate_functions(&mut self) -> anyhow::Result<()> {
-        for (index, function_body) in self.function_bodies.iter().enumerate() {
-            let modfunc_type = *self.function_type_indexes.get(index).unwrap_or(&0);
-            let func_name = if let Some(func_names_map) = &self.func_names_map {
-                func_names_map
-                    .get(&(index as u32))
-                    .unwrap_or(&format!("func_{}", get_id()))
-                    .to_owned()
-            } else {
-                format!("func_{}", get_id())
-            };
-            self.translated_function_names.push(func_name.clone());
+/// Destination of generated Rocq code, which renames identifiers for the target library
+/// of a [`TranslationConfig`] as the code is written.
+struct RocqWriter<'a, W: Write + ?Sized> {
+    out: &'a mut W,
+    config: &'a TranslationConfig,
+}
 
-            let mut modfunc_locals = String::new();
-            if let Ok(locals_reader) = function_body.get_locals_reader() {
-                for local in locals_reader {
-                    let (reps, val_type) = local.unwrap();
-                    let val_type = translate_value_type(&val_type)?;
-                    for _ in 0..reps {
-                        modfunc_locals.push_str(format!("{val_type} :: ").as_str());
-                    }
+impl<W: Write + ?Sized> RocqWriter<'_, W> {
+    /// Writes `code`, which must end on a token boundary so that no identifier is split
+    /// between two calls.
+    fn emit(&mut self, code: &str) -> std::io::Result<()> {
+        self.out.write_all(self.config.rename(code).as_bytes())
+    }
+}
+
+/// A Rocq source file of a translation split per function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RocqFile {
+    /// Name of the Rocq library defined by the file, which other files `Require`.
+    pub name: String,
+    /// Rocq code of the file.
+    pub contents: String,
+}
+
+impl RocqFile {
+    /// Returns the name of the file: the library name with the `.v` extension.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("{}.v", self.name)
+    }
+}
+
+/// Writes the imports of `config`, followed by a blank line.
+fn write_imports<W: Write + ?Sized>(
+    out: &mut W,
+    config: &TranslationConfig,
+) -> std::io::Result<()> {
+    for import in &config.imports {
+        writeln!(out, "{import}")?;
+    }
+    out.write_all(b"\n")
+}
+
+/// Writes the coqdoc header of the file defining the module record `module_name`.
+fn write_module_doc<W: Write + ?Sized>(
+    out: &mut RocqWriter<'_, W>,
+    module_name: &str,
+    config: &TranslationConfig,
+) -> std::io::Result<()> {
+    out.emit(&format!("(** * Module [{module_name}]\n\n"))?;
+    out.emit("    Rocq translation of a WebAssembly module")?;
+    if let Some(source_file) = &config.source_file {
+        out.emit(&format!(" compiled from [\"{source_file}\"]"))?;
+    }
+    out.emit(&format!(", defined by the record [{module_name}]. *)\n\n"))
+}
+
+/// Returns the coqdoc list of `items` separated by commas, or `none`.
+fn doc_list(items: &[String]) -> String {
+    if items.is_empty() {
+        "none".to_string()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// Writes the helper definitions that shorten the generated values and records.
+fn write_helpers<W: Write + ?Sized>(out: &mut RocqWriter<'_, W>) -> std::io::Result<()> {
+    out.emit("Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).\n")?;
+    out.emit("Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).\n")?;
+    out.emit("Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.\n")?;
+    out.emit("Definition Mm l := {|modmem_type := l|}.\n")?;
+    out.emit("Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.\n")?;
+    out.emit("\n")?;
+    out.emit("Definition Mi m n d := {|\n")?;
+    out.emit("  imp_module := list_byte_of_string m;\n")?;
+    out.emit("  imp_name := list_byte_of_string n;\n")?;
+    out.emit("  imp_desc := d;\n")?;
+    out.emit("|}.\n")?;
+    out.emit("\n")?;
+    out.emit("Definition Me n d := {|\n")?;
+    out.emit("  modexp_name := list_byte_of_string n;\n")?;
+    out.emit("  modexp_desc := d;\n")?;
+    out.emit("|}.\n")?;
+    out.emit("\n")?;
+    out.emit("Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.\n")?;
+    out.emit("\n")
+}
+
+/// Builds the Rocq file `name`, which starts with the imports of `config` and requires
+/// the files called `requires`, and whose definitions are written by `write`.
+fn rocq_file(
+    name: String,
+    config: &TranslationConfig,
+    requires: &[String],
+    write: impl FnOnce(&mut RocqWriter<'_, Vec<u8>>) -> anyhow::Result<()>,
+) -> anyhow::Result<RocqFile> {
+    let mut contents = Vec::new();
+    write_imports(&mut contents, config)?;
+    if !requires.is_empty() {
+        for require in requires {
+            match &config.module_prefix {
+                Some(module_prefix) => {
+                    writeln!(contents, "From {module_prefix} Require Import {require}.")?;
                 }
+                None => writeln!(contents, "Require Import {require}.")?,
             }
-            modfunc_locals.push_str("nil");
-
-            let modfunc_body = match &self.func_locals_name_map {
-                Some(func_locals_name_map) => translate_expr(
-                    &mut function_body.get_operators_reader()?,
-                    func_locals_name_map.get(&modfunc_type).cloned(),
-                )?,
-                None => translate_expr(&mut function_body.get_operators_reader()?, None)?,
-            };
-
-            self.translated_functions_string
-                .push_str(format!("Definition {func_name} : module_func := ").as_str());
-            self.translated_functions_string.push_str(LCB);
-            self.translated_functions_string
-                .push_str(format!("  modfunc_type := {modfunc_type}%N;\n").as_str());
-            self.translated_functions_string
-                .push_str(format!("  modfunc_locals := {modfunc_locals};\n").as_str());
-            self.translated_functions_string
-                .push_str(format!("  modfunc_body :=\n{modfunc_body};\n").as_str());
-            self.translated_functions_string.push_str(RCB_DOT);
-            self.translated_functions_string.push('\n');
         }
-        Ok(())
+        contents.write_all(b"\n")?;
     }
+    write(&mut RocqWriter {
+        out: &mut contents,
+        config,
+    })?;
+    Ok(RocqFile {
+        name,
+        contents: String::from_utf8(contents)?,
+    })
+}
+
+/// Writes the module record field `name` as a Rocq list of the translated `entries`, one
+/// entry at a time. Entries that fail to translate are left out.
+fn write_list<W: Write + ?Sized>(
+    out: &mut RocqWriter<'_, W>,
+    name: &str,
+    entries: impl Iterator<Item = anyhow::Result<String>>,
+) -> std::io::Result<()> {
+    out.emit(&format!("  {name} :=\n"))?;
+    for entry in entries.flatten() {
+        out.emit(&format!("    {entry}{LIST_EXT}"))?;
+    }
+    out.emit(&format!("    {LIST_SEAL};\n"))
 }
 
 //Inductive reference_type
+/// Translates a reference type. The nullable `funcref` and `externref` of the reference
+/// types proposal are `T_funcref` and `T_externref`; the typed references of the function
+/// references proposal wrap their heap type in `T_nonnull` when they are not nullable.
 fn translate_ref_type(ref_type: &RefType) -> anyhow::Result<String> {
-    if *ref_type == RefType::FUNCREF {
-        Ok(String::from("T_funcref"))
-    } else if *ref_type == RefType::EXTERNREF {
-        Ok(String::from("T_externref"))
+    let heap_type = translate_heap_type(&ref_type.heap_type())
+        .map_err(|_| anyhow::anyhow!("Unsupported reference type {ref_type:?}"))?;
+    if ref_type.is_nullable() {
+        Ok(heap_type)
     } else {
-        Err(anyhow::anyhow!("Unsupported reference type {ref_type:?}"))
+        Ok(format!("(T_nonnull {heap_type})"))
+    }
+}
+
+/// Translates the heap type of a reference: `func`, `extern`, or the function type
+/// `T_typeref i` of a typed function reference.
+fn translate_heap_type(heap_type: &HeapType) -> anyhow::Result<String> {
+    if *heap_type == HeapType::FUNC {
+        return Ok(String::from("T_funcref"));
+    }
+    if *heap_type == HeapType::EXTERN {
+        return Ok(String::from("T_externref"));
+    }
+    match heap_type {
+        HeapType::Concrete(index) => match index.as_module_index() {
+            Some(type_index) => Ok(format!("(T_typeref {type_index}%N)")),
+            None => Err(anyhow::anyhow!("Unsupported heap type {heap_type:?}")),
+        },
+        HeapType::Abstract { .. } => Err(anyhow::anyhow!("Unsupported heap type {heap_type:?}")),
     }
 }
 
@@ -601,7 +1282,11 @@ fn translate_module_import_desc(import: &Import) -> anyhow::Result<String> {
         }
         TypeRef::Memory(memory_type) => {
             let limits = translate_memory_type_limits(&memory_type)?;
-            format!("MID_mem {limits}")
+            if memory_type.memory64 {
+                format!("MID_mem64 {limits}")
+            } else {
+                format!("MID_mem {limits}")
+            }
         }
         TypeRef::Table(table_type) => {
             let table_type_translated = translate_table_type_limits(&table_type)?;
@@ -668,16 +1353,25 @@ fn translate_table_type(table: &Table) -> anyhow::Result<String> {
 }
 
 //Definition memory_type
+/// Translates a memory definition. A 64-bit memory is a `Mm64` of its limits, which the
+/// target library provides alongside the `_at` forms of the memory instructions.
 fn translate_memory_type(memory_type: &MemoryType) -> anyhow::Result<String> {
     let limits = translate_memory_type_limits(memory_type)?;
-    Ok(format!("Mm {limits}"))
+    if memory_type.memory64 {
+        Ok(format!("Mm64 {limits}"))
+    } else {
+        Ok(format!("Mm {limits}"))
+    }
 }
 
 //Record global_type
 fn translate_global(global: &Global) -> anyhow::Result<String> {
     let tg_mut = translate_mutability(global.ty.mutable);
     let tg_t = translate_value_type(&global.ty.content_type)?;
-    let mg_init = translate_expr(&mut global.init_expr.get_operators_reader(), None)?;
+    let mg_init = translate_expr(
+        &mut global.init_expr.get_operators_reader(),
+        IndexNames::default(),
+    )?;
     Ok(format!("Mg {tg_mut} ({tg_t}) ({mg_init})"))
 }
 
@@ -688,7 +1382,10 @@ fn translate_module_datamode(data: &Data) -> anyhow::Result<String> {
             memory_index,
             offset_expr,
         } => {
-            let expression = translate_expr(&mut offset_expr.get_operators_reader(), None)?;
+            let expression = translate_expr(
+                &mut offset_expr.get_operators_reader(),
+                IndexNames::default(),
+            )?;
             format!("MD_active {memory_index}%N ({expression})")
         }
         DataKind::Passive => "MD_passive".to_string(),
@@ -716,7 +1413,58 @@ struct ConditionExpr<'a> {
 #[derive(Default)]
 struct Expression<'a> {
     parts: Vec<ExpressionPart<'a>>,
-    local_name_map: Option<HashMap<u32, String>>,
+}
+
+/// Names from the name section of the functions and of the locals of the function being
+/// translated, written as comments next to the indices that refer to them, the name of
+/// the module providing the nondeterministic instructions, if they are parameterized, and
+/// the source locations of the verification blocks.
+#[derive(Default, Clone, Copy)]
+struct IndexNames<'n> {
+    functions: Option<&'n HashMap<u32, String>>,
+    locals: Option<&'n HashMap<u32, String>>,
+    nondeterminism: Option<&'n str>,
+    source_blocks: Option<&'n SourceBlocks<'n>>,
+}
+
+/// The source locations of the verification blocks of a function, written next to the
+/// block instructions in the order they are printed.
+struct SourceBlocks<'m> {
+    blocks: &'m [VerificationBlock],
+    next: Cell<usize>,
+}
+
+impl IndexNames<'_> {
+    /// Returns the comment naming the function `function_index`, or an empty string.
+    fn function(&self, function_index: u32) -> String {
+        name_comment(self.functions, function_index)
+    }
+
+    /// Returns the comment naming the local `local_index`, or an empty string.
+    fn local(&self, local_index: u32) -> String {
+        name_comment(self.locals, local_index)
+    }
+
+    /// Returns the comment locating the next verification block in the source, or an
+    /// empty string.
+    fn source_block(&self) -> String {
+        let Some(source_blocks) = self.source_blocks else {
+            return String::new();
+        };
+        let index = source_blocks.next.get();
+        source_blocks.next.set(index + 1);
+        match source_blocks.blocks.get(index) {
+            Some(block) => format!(" {}", block.location.comment(block.keyword)),
+            None => String::new(),
+        }
+    }
+}
+
+fn name_comment(names: Option<&HashMap<u32, String>>, index: u32) -> String {
+    match names.and_then(|names| names.get(&index)) {
+        Some(name) => format!(" (*{}*)", sanitize(name)),
+        None => String::new(),
+    }
 }
 
 impl Expression<'_> {
@@ -724,7 +1472,7 @@ impl Expression<'_> {
         self.parts.last()
     }
 
-    fn print_with_offset(&self, tabs_count: usize) -> anyhow::Result<String> {
+    fn print_with_offset(&self, tabs_count: usize, names: IndexNames) -> anyhow::Result<String> {
         let mut res = String::new();
         let offset = "  ".repeat(tabs_count);
         for part in &self.parts {
@@ -733,29 +1481,41 @@ impl Expression<'_> {
                     Operator::Else | Operator::End => {}
                     _ => {
                         res.push_str(offset.as_str());
-                        res.push_str(translate_basic_operator(op, &self.local_name_map)?.as_str());
+                        res.push_str(translate_basic_operator(op, names)?.as_str());
                         res.push_str(LIST_EXT);
                     }
                 },
                 ExpressionPart::Block(block) => {
                     res.push_str(offset.as_str());
+                    res.push_str(translate_basic_operator(&block.label, names)?.as_str());
+                    if is_verification_block(&block.label) {
+                        res.push_str(&names.source_block());
+                    }
+                    res.push_str(" (\n");
                     res.push_str(
-                        translate_basic_operator(&block.label, &self.local_name_map)?.as_str(),
+                        block
+                            .parts
+                            .print_with_offset(tabs_count + 1, names)?
+                            .as_str(),
                     );
-                    res.push_str(" (\n");
-                    res.push_str(block.parts.print_with_offset(tabs_count + 1)?.as_str());
                     res.push_str(") ");
                     res.push_str("::\n");
                 }
                 ExpressionPart::Condition(cond) => {
                     res.push_str(offset.as_str());
+                    res.push_str(translate_basic_operator(&cond.label, names)?.as_str());
+                    res.push_str(" (\n");
                     res.push_str(
-                        translate_basic_operator(&cond.label, &self.local_name_map)?.as_str(),
+                        cond.then_arm
+                            .print_with_offset(tabs_count + 1, names)?
+                            .as_str(),
                     );
-                    res.push_str(" (\n");
-                    res.push_str(cond.then_arm.print_with_offset(tabs_count + 1)?.as_str());
                     res.push_str(") (\n");
-                    res.push_str(cond.else_arm.print_with_offset(tabs_count + 1)?.as_str());
+                    res.push_str(
+                        cond.else_arm
+                            .print_with_offset(tabs_count + 1, names)?
+                            .as_str(),
+                    );
                     res.push_str(") ");
                     res.push_str("::\n");
                 }
@@ -766,17 +1526,6 @@ impl Expression<'_> {
     }
 }
 
-impl Display for Expression<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.print_with_offset(2)
-                .unwrap_or(String::from("Error rendering expression"))
-        )
-    }
-}
-
 fn translate_expression<'a>(
     operators_reader: &mut OperatorsIterator<'a>,
 ) -> anyhow::Result<Expression<'a>> {
@@ -789,7 +1538,9 @@ fn translate_expression<'a>(
             | inf_wasmparser::Operator::Forall { .. }
             | inf_wasmparser::Operator::Exists { .. }
             | inf_wasmparser::Operator::Assume { .. }
-            | inf_wasmparser::Operator::Unique { .. } => {
+            | inf_wasmparser::Operator::Unique { .. }
+            | inf_wasmparser::Operator::Try { .. }
+            | inf_wasmparser::Operator::TryTable { .. } => {
                 // operators_reader.next();
                 let block_operations = translate_expression(operators_reader)?;
                 let block = BlockExpr {
@@ -817,7 +1568,10 @@ fn translate_expression<'a>(
                 };
                 result.parts.push(ExpressionPart::Condition(condition));
             }
-            inf_wasmparser::Operator::Else | inf_wasmparser::Operator::End => {
+            // A `delegate` ends its `try` block in place of an `end`.
+            inf_wasmparser::Operator::Else
+            | inf_wasmparser::Operator::End
+            | inf_wasmparser::Operator::Delegate { .. } => {
                 result
                     .parts
                     .push(ExpressionPart::Operator(next_operator.to_owned()));
@@ -833,12 +1587,11 @@ fn translate_expression<'a>(
 
 fn translate_expr(
     operators_reader: &mut OperatorsReader,
-    local_name_map: Option<HashMap<u32, String>>,
+    names: IndexNames,
 ) -> anyhow::Result<String> {
     let mut peekable_operators_reader = operators_reader.clone().into_iter();
-    let mut expression = translate_expression(&mut peekable_operators_reader)?;
-    expression.local_name_map = local_name_map;
-    Ok(expression.to_string())
+    let expression = translate_expression(&mut peekable_operators_reader)?;
+    expression.print_with_offset(2, names)
 }
 
 fn translate_block_type(block_type: &BlockType) -> anyhow::Result<String> {
@@ -854,23 +1607,44 @@ fn translate_block_type(block_type: &BlockType) -> anyhow::Result<String> {
 }
 
 //Record memarg
-fn translate_memarg(memarg: &inf_wasmparser::MemArg) -> anyhow::Result<String> {
+/// Translates the immediate of a load or store into the suffix of the instruction that
+/// selects its memory, and the `Ma` record of its offset and alignment.
+///
+/// Accesses to memory 0 use the instructions of the `Wasm` library, so the suffix is
+/// empty. Accesses to other memories use the `_at` form of the instruction, whose first
+/// argument is the memory index, such as `BI_load_at 1%N T_i32 None (Ma 0%N 2%N)`.
+fn translate_memarg(memarg: &inf_wasmparser::MemArg) -> anyhow::Result<(String, String)> {
     let memarg_offset = memarg.offset.to_string();
     let memarg_align = memarg.align.to_string();
-    Ok(format!("Ma {memarg_offset}%N {memarg_align}%N"))
+    Ok((
+        memory_at(memarg.memory),
+        format!("Ma {memarg_offset}%N {memarg_align}%N"),
+    ))
+}
+
+/// Returns the suffix of a memory instruction that selects the memory `memory`: empty
+/// for memory 0, and `_at <memory>%N` otherwise.
+fn memory_at(memory: u32) -> String {
+    if memory == 0 {
+        String::new()
+    } else {
+        format!("_at {memory}%N")
+    }
 }
 
 //Record module_element
 fn translate_element(element: &Element) -> anyhow::Result<String> {
     let mut res = String::new();
-    // let id = get_id();
     let modelem_mode = match &element.kind {
         ElementKind::Active {
             table_index,
             offset_expr,
         } => {
             let tableidx = table_index.unwrap_or_default();
-            let expr = translate_expr(&mut offset_expr.get_operators_reader(), None)?;
+            let expr = translate_expr(
+                &mut offset_expr.get_operators_reader(),
+                IndexNames::default(),
+            )?;
             format!("ME_active {tableidx}%N ({expr})")
         }
         ElementKind::Passive => "ME_passive".to_string(),
@@ -883,7 +1657,10 @@ fn translate_element(element: &Element) -> anyhow::Result<String> {
             let mut expr_list = String::new();
             for result in elements.clone().into_iter_with_offsets() {
                 let (_, expr_reader) = result?;
-                let expr = translate_expr(&mut expr_reader.get_operators_reader(), None)?;
+                let expr = translate_expr(
+                    &mut expr_reader.get_operators_reader(),
+                    IndexNames::default(),
+                )?;
                 expr_list.push_str(format!("({expr})").as_str());
                 expr_list.push_str(" ::\n");
             }
@@ -942,7 +1719,6 @@ fn translate_element(element: &Element) -> anyhow::Result<String> {
 //Inductive function_type
 fn translate_function_type(rec_group: &RecGroup) -> anyhow::Result<String> {
     let mut res = String::new();
-    // let id = get_id();
     for ty in rec_group.types() {
         match &ty.composite_type.inner {
             CompositeInnerType::Func(ft) => {
@@ -973,10 +1749,7 @@ fn translate_function_type(rec_group: &RecGroup) -> anyhow::Result<String> {
 }
 
 //Inductive basic_instruction
-fn translate_basic_operator(
-    operator: &Operator,
-    local_name_map: &Option<HashMap<u32, String>>,
-) -> anyhow::Result<String> {
+fn translate_basic_operator(operator: &Operator, names: IndexNames) -> anyhow::Result<String> {
     let operator = match operator {
         inf_wasmparser::Operator::Nop => "BI_nop".to_string(),
         inf_wasmparser::Operator::Unreachable => "BI_unreachable".to_string(),
@@ -992,24 +1765,33 @@ fn translate_basic_operator(
             let blockty = translate_block_type(blockty)?;
             format!("BI_if ({blockty})")
         }
-        Operator::Forall { blockty } => {
-            let blockty = translate_block_type(blockty)?;
-            format!("BI_forall ({blockty})")
-        }
-        Operator::Exists { blockty } => {
-            let blockty = translate_block_type(blockty)?;
-            format!("BI_exists ({blockty})")
-        }
-        Operator::Assume { blockty } => {
+        Operator::Forall { blockty }
+        | Operator::Exists { blockty }
+        | Operator::Assume { blockty }
+        | Operator::Unique { blockty } => {
             let blockty = translate_block_type(blockty)?;
-            format!("BI_assume ({blockty})")
+            let (constructor, parameter) = match operator {
+                Operator::Forall { .. } => ("BI_forall", "forall_block"),
+                Operator::Exists { .. } => ("BI_exists", "exists_block"),
+                Operator::Assume { .. } => ("BI_assume", "assume_block"),
+                _ => ("BI_unique", "unique_block"),
+            };
+            match names.nondeterminism {
+                Some(module) => format!("{module}.{parameter} ({blockty})"),
+                None => format!("{constructor} ({blockty})"),
+            }
         }
-        Operator::Unique { blockty } => {
-            let blockty = translate_block_type(blockty)?;
-            format!("BI_unique ({blockty})")
+        Operator::I32Uzumaki { .. } | Operator::I64Uzumaki { .. } => {
+            let number_type = if matches!(operator, Operator::I32Uzumaki { .. }) {
+                "T_i32"
+            } else {
+                "T_i64"
+            };
+            match names.nondeterminism {
+                Some(module) => format!("{module}.uzumaki {number_type}"),
+                None => format!("BI_uzumaki_num {number_type}"),
+            }
         }
-        Operator::I32Uzumaki { .. } => String::from("BI_uzumaki_num T_i32"),
-        Operator::I64Uzumaki { .. } => String::from("BI_uzumaki_num T_i64"),
         Operator::Else => String::new(),
         Operator::End => String::new(),
         Operator::Br { relative_depth } => format!("BI_br {relative_depth}"),
@@ -1029,7 +1811,12 @@ fn translate_basic_operator(
             }
         }
         Operator::Return => "BI_return".to_string(),
-        Operator::Call { function_index } => format!("BI_call {function_index}"),
+        Operator::Call { function_index } => {
+            format!(
+                "BI_call {function_index}{}",
+                names.function(*function_index)
+            )
+        }
         Operator::CallIndirect {
             type_index,
             table_index,
@@ -1037,153 +1824,110 @@ fn translate_basic_operator(
         Operator::Drop => "BI_drop".to_string(),
         Operator::Select => "BI_select None".to_string(),
         Operator::LocalGet { local_index } => {
-            if let Some(local_name_map) = local_name_map {
-                if local_name_map.contains_key(local_index) {
-                    format!(
-                        "BI_local_get {local_index}%N (*{}*)",
-                        local_name_map.get(local_index).unwrap()
-                    )
-                } else {
-                    format!("BI_local_get {local_index}%N")
-                }
-            } else {
-                format!("BI_local_get {local_index}%N")
-            }
+            format!("BI_local_get {local_index}%N{}", names.local(*local_index))
         }
         Operator::LocalSet { local_index } => {
-            if let Some(local_name_map) = local_name_map {
-                if local_name_map.contains_key(local_index) {
-                    format!(
-                        "BI_local_set {local_index}%N (*{}*)",
-                        local_name_map.get(local_index).unwrap()
-                    )
-                } else {
-                    format!("BI_local_set {local_index}%N")
-                }
-            } else {
-                format!("BI_local_set {local_index}%N")
-            }
+            format!("BI_local_set {local_index}%N{}", names.local(*local_index))
         }
         Operator::LocalTee { local_index } => {
-            if let Some(local_name_map) = local_name_map {
-                if local_name_map.contains_key(local_index) {
-                    format!(
-                        "BI_local_tee {local_index}%N (*{}*)",
-                        local_name_map.get(local_index).unwrap()
-                    )
-                } else {
-                    format!("BI_local_tee {local_index}%N")
-                }
-            } else {
-                format!("BI_local_tee {local_index}%N")
-            }
+            format!("BI_local_tee {local_index}%N{}", names.local(*local_index))
         }
         Operator::GlobalGet { global_index } => format!("BI_global_get {global_index}%N"),
         Operator::GlobalSet { global_index } => format!("BI_global_set {global_index}%N"),
         Operator::I32Load { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i32 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i32 None ({memarg})")
         }
         Operator::I64Load { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 None ({memarg})")
         }
         Operator::F32Load { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_f32 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_f32 None ({memarg})")
         }
         Operator::F64Load { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_f64 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_f64 None ({memarg})")
         }
         Operator::I32Load8S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i32 (Some (Tp_i8, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i32 (Some (Tp_i8, SX_S)) ({memarg})")
         }
         Operator::I32Load8U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i32 (Some (Tp_i8, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i32 (Some (Tp_i8, SX_U)) ({memarg})")
         }
         Operator::I32Load16S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i32 (Some (Tp_i16, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i32 (Some (Tp_i16, SX_S)) ({memarg})")
         }
         Operator::I32Load16U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i32 (Some (Tp_i16, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i32 (Some (Tp_i16, SX_U)) ({memarg})")
         }
         Operator::I64Load8S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i8, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i8, SX_S)) ({memarg})")
         }
         Operator::I64Load8U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i8, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i8, SX_U)) ({memarg})")
         }
         Operator::I64Load16S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i16, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i16, SX_S)) ({memarg})")
         }
         Operator::I64Load16U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
         }
         Operator::I64Load32S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i32, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i32, SX_S)) ({memarg})")
         }
         Operator::I64Load32U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load T_i64 (Some (Tp_i32, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load{at} T_i64 (Some (Tp_i32, SX_U)) ({memarg})")
         }
         Operator::I32Store { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i32 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i32 None ({memarg})")
         }
         Operator::I64Store { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i64 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i64 None ({memarg})")
         }
         Operator::F32Store { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_f32 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_f32 None ({memarg})")
         }
         Operator::F64Store { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_f64 None ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_f64 None ({memarg})")
         }
         Operator::I32Store8 { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i32 (Some Tp_i8) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i32 (Some Tp_i8) ({memarg})")
         }
         Operator::I32Store16 { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i32 (Some Tp_i16) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i32 (Some Tp_i16) ({memarg})")
         }
         Operator::I64Store8 { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i64 (Some Tp_i8) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i64 (Some Tp_i8) ({memarg})")
         }
         Operator::I64Store16 { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i64 (Some Tp_i16) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i64 (Some Tp_i16) ({memarg})")
         }
         Operator::I64Store32 { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store T_i64 (Some Tp_i32) ({memarg})")
-        }
-        Operator::MemorySize { mem } => {
-            if *mem > 0 {
-                return Err(anyhow::anyhow!("Memory index is not supported"));
-            }
-            "BI_memory_size".to_string()
-        }
-        Operator::MemoryGrow { mem } => {
-            if *mem > 0 {
-                return Err(anyhow::anyhow!("Memory index is not supported"));
-            }
-            "BI_memory_grow".to_string()
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store{at} T_i64 (Some Tp_i32) ({memarg})")
         }
+        Operator::MemorySize { mem } => format!("BI_memory_size{}", memory_at(*mem)),
+        Operator::MemoryGrow { mem } => format!("BI_memory_grow{}", memory_at(*mem)),
         Operator::I32Const { value } => format!("BI_const_num (Vi32 {value})"),
         Operator::I64Const { value } => format!("BI_const_num (Vi64 {value})"),
         Operator::F32Const { value } => {
@@ -1317,11 +2061,11 @@ fn translate_basic_operator(
         Operator::I64ReinterpretF64 => "BI_cvtop T_i64 (CVO_reinterpret T_f64 None)".to_string(),
         Operator::F32ReinterpretI32 => "BI_cvtop T_f32 (CVO_reinterpret T_i32 None)".to_string(),
         Operator::F64ReinterpretI64 => "BI_cvtop T_f64 (CVO_reinterpret T_i64 None)".to_string(),
-        Operator::I32Extend8S => todo!(),
-        Operator::I32Extend16S => todo!(),
-        Operator::I64Extend8S => todo!(),
-        Operator::I64Extend16S => todo!(),
-        Operator::I64Extend32S => todo!(),
+        Operator::I32Extend8S => "BI_unop T_i32 (Unop_extend 8%N)".to_string(),
+        Operator::I32Extend16S => "BI_unop T_i32 (Unop_extend 16%N)".to_string(),
+        Operator::I64Extend8S => "BI_unop T_i64 (Unop_extend 8%N)".to_string(),
+        Operator::I64Extend16S => "BI_unop T_i64 (Unop_extend 16%N)".to_string(),
+        Operator::I64Extend32S => "BI_unop T_i64 (Unop_extend 32%N)".to_string(),
         Operator::RefEq => todo!(),
         Operator::StructNew { .. } => todo!(),
         Operator::StructNewDefault { .. } => todo!(),
@@ -1354,35 +2098,56 @@ fn translate_basic_operator(
         Operator::RefI31 => todo!(),
         Operator::I31GetS => todo!(),
         Operator::I31GetU => todo!(),
-        Operator::I32TruncSatF32S => todo!(),
-        Operator::I32TruncSatF32U => todo!(),
-        Operator::I32TruncSatF64S => todo!(),
-        Operator::I32TruncSatF64U => todo!(),
-        Operator::I64TruncSatF32S => todo!(),
-        Operator::I64TruncSatF32U => todo!(),
-        Operator::I64TruncSatF64S => todo!(),
-        Operator::I64TruncSatF64U => todo!(),
-        Operator::MemoryInit { data_index, mem: _ } => format!("BI_memory_init {data_index}"),
+        Operator::I32TruncSatF32S => "BI_cvtop T_i32 (CVO_trunc_sat T_f32 (Some SX_S))".to_string(),
+        Operator::I32TruncSatF32U => "BI_cvtop T_i32 (CVO_trunc_sat T_f32 (Some SX_U))".to_string(),
+        Operator::I32TruncSatF64S => "BI_cvtop T_i32 (CVO_trunc_sat T_f64 (Some SX_S))".to_string(),
+        Operator::I32TruncSatF64U => "BI_cvtop T_i32 (CVO_trunc_sat T_f64 (Some SX_U))".to_string(),
+        Operator::I64TruncSatF32S => "BI_cvtop T_i64 (CVO_trunc_sat T_f32 (Some SX_S))".to_string(),
+        Operator::I64TruncSatF32U => "BI_cvtop T_i64 (CVO_trunc_sat T_f32 (Some SX_U))".to_string(),
+        Operator::I64TruncSatF64S => "BI_cvtop T_i64 (CVO_trunc_sat T_f64 (Some SX_S))".to_string(),
+        Operator::I64TruncSatF64U => "BI_cvtop T_i64 (CVO_trunc_sat T_f64 (Some SX_U))".to_string(),
+        Operator::MemoryInit { data_index, mem } => {
+            format!("BI_memory_init{} {data_index}", memory_at(*mem))
+        }
         Operator::DataDrop { data_index } => format!("BI_data_drop {data_index}"),
-        Operator::MemoryCopy {
-            dst_mem: _,
-            src_mem: _,
-        } => "BI_memory_copy".to_string(),
-        Operator::MemoryFill { mem: _ } => "BI_memory_fill".to_string(),
-        Operator::TableInit { .. } => todo!(),
-        Operator::ElemDrop { .. } => todo!(),
-        Operator::TableCopy { .. } => todo!(),
-        Operator::TypedSelect { .. } => todo!(),
-        Operator::RefNull { .. } => todo!(),
+        Operator::MemoryCopy { dst_mem, src_mem } => {
+            if *dst_mem == 0 && *src_mem == 0 {
+                "BI_memory_copy".to_string()
+            } else {
+                format!("BI_memory_copy_at {dst_mem}%N {src_mem}%N")
+            }
+        }
+        Operator::MemoryFill { mem } => format!("BI_memory_fill{}", memory_at(*mem)),
+        Operator::TableInit { elem_index, table } => {
+            format!("BI_table_init {table}%N {elem_index}%N")
+        }
+        Operator::ElemDrop { elem_index } => format!("BI_elem_drop {elem_index}%N"),
+        Operator::TableCopy {
+            dst_table,
+            src_table,
+        } => format!("BI_table_copy {dst_table}%N {src_table}%N"),
+        Operator::TypedSelect { ty } => {
+            format!("BI_select (Some ({} :: nil))", translate_value_type(ty)?)
+        }
+        Operator::RefNull { hty } => format!("BI_ref_null {}", translate_heap_type(hty)?),
         Operator::RefIsNull => "BI_ref_is_null".to_string(),
-        Operator::RefFunc { function_index } => format!("BI_ref_func {function_index}%N"),
+        Operator::RefFunc { function_index } => format!(
+            "BI_ref_func {function_index}%N{}",
+            names.function(*function_index)
+        ),
         Operator::TableFill { table } => format!("BI_table_fill {table}%N"),
         Operator::TableGet { table } => format!("BI_table_get {table}%N"),
         Operator::TableSet { table } => format!("BI_table_set {table}%N"),
         Operator::TableGrow { table } => format!("BI_table_grow {table}%N"),
         Operator::TableSize { table } => format!("BI_table_size {table}%N"),
-        Operator::ReturnCall { .. } => todo!(),
-        Operator::ReturnCallIndirect { .. } => todo!(),
+        Operator::ReturnCall { function_index } => format!(
+            "BI_return_call {function_index}{}",
+            names.function(*function_index)
+        ),
+        Operator::ReturnCallIndirect {
+            type_index,
+            table_index,
+        } => format!("BI_return_call_indirect {type_index} {table_index}"),
         Operator::MemoryDiscard { .. } => todo!(),
         Operator::MemoryAtomicNotify { memarg: _ }
         | Operator::MemoryAtomicWait32 { memarg: _ }
@@ -1456,92 +2221,92 @@ fn translate_basic_operator(
             ));
         }
         Operator::V128Load { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
         }
         Operator::V128Load8x8S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i8, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i8, SX_S)) ({memarg})")
         }
         Operator::V128Load8x8U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i8, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i8, SX_U)) ({memarg})")
         }
         Operator::V128Load16x4S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i16, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i16, SX_S)) ({memarg})")
         }
         Operator::V128Load16x4U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i16, SX_U)) ({memarg})")
         }
         Operator::V128Load32x2S { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i32, SX_S)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i32, SX_S)) ({memarg})")
         }
         Operator::V128Load32x2U { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_packed T_i64 (Some (Tp_i32, SX_U)) ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_packed T_i64 (Some (Tp_i32, SX_U)) ({memarg})")
         }
         Operator::V128Load8Splat { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_splat Twv_8 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_splat Twv_8 ({memarg})")
         }
         Operator::V128Load16Splat { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_splat Twv_16 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_splat Twv_16 ({memarg})")
         }
         Operator::V128Load32Splat { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_splat Twv_32 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_splat Twv_32 ({memarg})")
         }
         Operator::V128Load64Splat { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_splat Twv_64 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_splat Twv_64 ({memarg})")
         }
         Operator::V128Load32Zero { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_zero Tztv_32 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_zero Tztv_32 ({memarg})")
         }
         Operator::V128Load64Zero { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_load_vec LVA_zero Tztv_64 ({memarg})")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_load_vec{at} LVA_zero Tztv_64 ({memarg})")
         }
         Operator::V128Store { memarg } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_64 ({memarg}) 0")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_64 ({memarg}) 0")
         }
         Operator::V128Load8Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_8 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_8 ({memarg}) {lane}")
         }
         Operator::V128Load16Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_16 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_16 ({memarg}) {lane}")
         }
         Operator::V128Load32Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_32 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_32 ({memarg}) {lane}")
         }
         Operator::V128Load64Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_64 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_64 ({memarg}) {lane}")
         }
         Operator::V128Store8Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_8 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_8 ({memarg}) {lane}")
         }
         Operator::V128Store16Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_16 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_16 ({memarg}) {lane}")
         }
         Operator::V128Store32Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_32 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_32 ({memarg}) {lane}")
         }
         Operator::V128Store64Lane { memarg, lane } => {
-            let memarg = translate_memarg(memarg)?;
-            format!("BI_store_vec_lane Twv_64 ({memarg}) {lane}")
+            let (at, memarg) = translate_memarg(memarg)?;
+            format!("BI_store_vec_lane{at} Twv_64 ({memarg}) {lane}")
         }
         Operator::V128Const { value } => {
             let value = value.i128();
@@ -1809,14 +2574,18 @@ fn translate_basic_operator(
         Operator::I16x8RelaxedQ15mulrS => todo!(),
         Operator::I16x8RelaxedDotI8x16I7x16S => todo!(),
         Operator::I32x4RelaxedDotI8x16I7x16AddS => todo!(),
-        Operator::TryTable { .. } => todo!(),
-        Operator::Throw { .. } => todo!(),
-        Operator::ThrowRef => todo!(),
-        Operator::Try { .. } => todo!(),
-        Operator::Catch { .. } => todo!(),
-        Operator::Rethrow { .. } => todo!(),
-        Operator::Delegate { .. } => todo!(),
-        Operator::CatchAll => todo!(),
+        Operator::TryTable { .. }
+        | Operator::Throw { .. }
+        | Operator::ThrowRef
+        | Operator::Try { .. }
+        | Operator::Catch { .. }
+        | Operator::Rethrow { .. }
+        | Operator::Delegate { .. }
+        | Operator::CatchAll => {
+            return Err(anyhow::anyhow!(
+                "Exception handling instruction {operator:?} is not supported",
+            ));
+        }
         Operator::GlobalAtomicGet {
             ordering: _,
             global_index: _,
@@ -1973,11 +2742,13 @@ fn translate_basic_operator(
             ));
         }
         Operator::RefI31Shared => todo!(),
-        Operator::CallRef { .. } => todo!(),
-        Operator::ReturnCallRef { .. } => todo!(),
-        Operator::RefAsNonNull => todo!(),
-        Operator::BrOnNull { .. } => todo!(),
-        Operator::BrOnNonNull { .. } => todo!(),
+        Operator::CallRef { type_index } => format!("BI_call_ref {type_index}%N"),
+        Operator::ReturnCallRef { type_index } => format!("BI_return_call_ref {type_index}%N"),
+        Operator::RefAsNonNull => "BI_ref_as_non_null".to_string(),
+        Operator::BrOnNull { relative_depth } => format!("BI_br_on_null {relative_depth}%N"),
+        Operator::BrOnNonNull { relative_depth } => {
+            format!("BI_br_on_non_null {relative_depth}%N")
+        }
         Operator::ContNew { .. } => todo!(),
         Operator::ContBind { .. } => todo!(),
         Operator::Suspend { .. } => todo!(),
@@ -1994,15 +2765,10 @@ fn translate_basic_operator(
 }
 
 //Record module_data
-fn translate_data(data: &Data) -> anyhow::Result<String> {
+/// Translates a data segment whose bytes are defined as `moddata_init`.
+fn translate_data(data: &Data, moddata_init: &str) -> anyhow::Result<String> {
     let mut res = String::new();
     let moddata_mode = translate_module_datamode(data)?;
-    let mut moddata_init = String::new();
-    for byte in data.data {
-        moddata_init.push_str(format!("#{byte:02X}").as_str());
-        moddata_init.push_str(" :: ");
-    }
-    moddata_init.push_str("nil");
     res.push_str("{|\n");
     res.push_str(format!("    moddata_init := {moddata_init};\n").as_str());
     res.push_str(format!("    moddata_mode := {moddata_mode};\n").as_str());
@@ -2010,8 +2776,53 @@ fn translate_data(data: &Data) -> anyhow::Result<String> {
     Ok(res)
 }
 
-fn get_id() -> String {
-    let uuid = Uuid::new_v4().to_string();
-    let mut parts = uuid.split('-');
-    parts.next().unwrap().to_string()
+/// Translates bytes into a Rocq `list byte`.
+fn translate_bytes(bytes: &[u8]) -> String {
+    let mut res = String::new();
+    for byte in bytes {
+        res.push_str(format!("#{byte:02X}").as_str());
+        res.push_str(" :: ");
+    }
+    res.push_str("nil");
+    res
+}
+
+/// Returns the function indices of the items of an element segment, where `None` is a
+/// null reference, or `None` if an item is not a `ref.func` or `ref.null` constant.
+fn element_function_indices(element: &Element) -> Option<Vec<Option<u32>>> {
+    match &element.items {
+        ElementItems::Functions(functions) => functions
+            .clone()
+            .into_iter()
+            .map(|function_index| function_index.ok().map(Some))
+            .collect(),
+        ElementItems::Expressions(_, expressions) => expressions
+            .clone()
+            .into_iter()
+            .map(|expression| match single_operator(&expression.ok()?)? {
+                Operator::RefFunc { function_index } => Some(Some(function_index)),
+                Operator::RefNull { .. } => Some(None),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+/// Returns the operator of a constant expression made of a single operator.
+fn single_operator<'a>(expression: &ConstExpr<'a>) -> Option<Operator<'a>> {
+    let mut operators = expression.get_operators_reader().into_iter();
+    let operator = operators.next()?.ok()?;
+    match operators.next()?.ok()? {
+        Operator::End => Some(operator),
+        _ => None,
+    }
+}
+
+/// Returns the value of an `i32.const` or `i64.const` as an unsigned offset.
+fn integer_constant(operator: &Operator) -> Option<u64> {
+    match operator {
+        Operator::I32Const { value } => Some(u64::from(*value as u32)),
+        Operator::I64Const { value } => Some(*value as u64),
+        _ => None,
+    }
 }
@@ -0,0 +1,204 @@
+//! Verification Metadata
+//!
+//! The Inference compiler adds a custom section named [`SECTION_NAME`] to modules with
+//! `forall`, `exists`, `assume` or `unique` blocks. It lists the functions containing
+//! these blocks, with the source location of each function and block. The translator
+//! reads it to write the source location next to the translated definitions, so a proof
+//! about a generated definition can be traced back to the lines of the `.inf` file.
+//!
+//! The encoding is defined by the `verification` module of `inference-wasm-codegen`:
+//!
+//! ```text
+//! section   ::= version:u32 functions:vec(function)
+//! function  ::= name:string export:(0x00 | 0x01 string) location blocks:vec(block)
+//! block     ::= kind:u8 location
+//! location  ::= offset_start:u32 offset_end:u32
+//!               start_line:u32 start_column:u32 end_line:u32 end_column:u32
+//! ```
+//!
+//! The decoder is duplicated here because the code generator depends on LLVM, which the
+//! translator does not.
+
+use std::fmt;
+
+use anyhow::bail;
+use inf_wasmparser::{BinaryReader, Operator};
+
+/// Name of the verification metadata custom section.
+pub(crate) const SECTION_NAME: &str = "inference.verification";
+
+/// Version of the section encoding this decoder reads.
+const FORMAT_VERSION: u32 = 1;
+
+/// Lines and columns, starting at 1, of a span of the source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SourceSpan {
+    start_line: u32,
+    start_column: u32,
+    end_line: u32,
+    end_column: u32,
+}
+
+impl SourceSpan {
+    /// Returns a Rocq comment locating `what` at the span, such as
+    /// `(* forall at 12:5-15:6 *)`.
+    pub(crate) fn comment(&self, what: &str) -> String {
+        format!("(* {what} at {self} *)")
+    }
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}-{}:{}",
+            self.start_line, self.start_column, self.end_line, self.end_column
+        )
+    }
+}
+
+/// A `forall`, `exists`, `assume` or `unique` block of a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct VerificationBlock {
+    /// Keyword of the block.
+    pub(crate) keyword: &'static str,
+    pub(crate) location: SourceSpan,
+}
+
+/// A function containing verification blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct VerifiedFunction {
+    /// Name of the function in the source, which is also its name in the name section.
+    pub(crate) name: String,
+    pub(crate) export_name: Option<String>,
+    pub(crate) location: SourceSpan,
+    /// The blocks in source order, which is the order of their instructions.
+    pub(crate) blocks: Vec<VerificationBlock>,
+}
+
+/// Contents of the verification metadata custom section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct VerificationMetadata {
+    pub(crate) functions: Vec<VerifiedFunction>,
+}
+
+impl VerificationMetadata {
+    /// Decodes the contents of the custom section.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is truncated, has trailing bytes, uses another format
+    /// version or contains an unknown block kind.
+    pub(crate) fn decode(data: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = BinaryReader::new(data, 0);
+        let version = reader.read_var_u32()?;
+        if version != FORMAT_VERSION {
+            bail!("Unsupported verification metadata version {version}");
+        }
+        let mut functions = Vec::new();
+        for _ in 0..reader.read_var_u32()? {
+            let name = reader.read_string()?.to_string();
+            let export_name = match reader.read_u8()? {
+                0 => None,
+                1 => Some(reader.read_string()?.to_string()),
+                flag => bail!("Invalid export flag {flag} in verification metadata"),
+            };
+            let location = decode_location(&mut reader)?;
+            let mut blocks = Vec::new();
+            for _ in 0..reader.read_var_u32()? {
+                let keyword = match reader.read_u8()? {
+                    0 => "forall",
+                    1 => "exists",
+                    2 => "assume",
+                    3 => "unique",
+                    code => bail!("Unknown block kind {code} in verification metadata"),
+                };
+                blocks.push(VerificationBlock {
+                    keyword,
+                    location: decode_location(&mut reader)?,
+                });
+            }
+            functions.push(VerifiedFunction {
+                name,
+                export_name,
+                location,
+                blocks,
+            });
+        }
+        if !reader.eof() {
+            bail!("Trailing bytes after verification metadata");
+        }
+        Ok(Self { functions })
+    }
+
+    /// Returns the function called `name` in the source, or exported under one of
+    /// `export_names`.
+    pub(crate) fn function<'m>(
+        &'m self,
+        name: Option<&str>,
+        export_names: &[&str],
+    ) -> Option<&'m VerifiedFunction> {
+        self.functions.iter().find(|function| {
+            name == Some(function.name.as_str())
+                || function
+                    .export_name
+                    .as_deref()
+                    .is_some_and(|export_name| export_names.contains(&export_name))
+        })
+    }
+}
+
+/// Returns true if `operator` starts a `forall`, `exists`, `assume` or `unique` block.
+pub(crate) fn is_verification_block(operator: &Operator) -> bool {
+    matches!(
+        operator,
+        Operator::Forall { .. }
+            | Operator::Exists { .. }
+            | Operator::Assume { .. }
+            | Operator::Unique { .. }
+    )
+}
+
+fn decode_location(reader: &mut BinaryReader) -> anyhow::Result<SourceSpan> {
+    // The byte offsets are only meaningful with the source text.
+    reader.read_var_u32()?;
+    reader.read_var_u32()?;
+    Ok(SourceSpan {
+        start_line: reader.read_var_u32()?,
+        start_column: reader.read_var_u32()?,
+        end_line: reader.read_var_u32()?,
+        end_column: reader.read_var_u32()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_functions_and_blocks() {
+        let data = b"\x01\x01\x05check\x01\x05check\x00\x50\x01\x01\x05\x0a\x01\x01\x10\x40\x02\x05\x06\x06";
+        let metadata = VerificationMetadata::decode(data).unwrap();
+        let function = metadata.function(None, &["check"]).unwrap();
+        assert_eq!(function.name, "check");
+        assert_eq!(function.location.to_string(), "1:1-5:10");
+        assert_eq!(
+            function.location.comment("check"),
+            "(* check at 1:1-5:10 *)"
+        );
+        assert_eq!(function.blocks.len(), 1);
+        assert_eq!(function.blocks[0].keyword, "exists");
+        assert_eq!(
+            function.blocks[0].location.comment("exists"),
+            "(* exists at 2:5-6:6 *)"
+        );
+        assert!(metadata.function(Some("other"), &[]).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_trailing_bytes() {
+        assert!(VerificationMetadata::decode(b"\x02\x00").is_err());
+        assert!(VerificationMetadata::decode(b"\x01\x00\x00").is_err());
+        assert!(VerificationMetadata::decode(b"\x01\x01").is_err());
+    }
+}
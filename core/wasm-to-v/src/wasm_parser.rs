@@ -20,6 +20,23 @@
 //! 2. **Build Structure**: Populate [`WasmParseData`] with extracted information
 //! 3. **Translate Phase**: Call [`WasmParseData::translate`] to generate Rocq code
 //!
+//! [`translate_to_writer`] runs the same pipeline but writes the Rocq code to an
+//! [`std::io::Write`] as it is generated, for modules whose translation should not be held
+//! in memory.
+//!
+//! ## Text Input
+//!
+//! Every entry point also accepts the WebAssembly text format: bytes that are not a WASM
+//! binary but valid UTF-8 are assembled with `inf-wast` before parsing. `inf-wast` is
+//! the `wast` fork that knows the Inference non-deterministic instructions (`forall`,
+//! `exists`, `assume`, `unique`, `i32.uzumaki`, `i64.uzumaki`), so hand-written fixtures
+//! and playground output translate without a separate `wat2wasm` step:
+//!
+//! ```ignore
+//! let wat = std::fs::read("fixture.wat")?;
+//! let rocq_code = translate_bytes("fixture", &wat)?;
+//! ```
+//!
 //! ## Parsing Strategy
 //!
 //! The parser makes a single forward pass through the WASM module, processing
@@ -52,6 +69,14 @@
 //! This information dramatically improves readability of generated Rocq code by
 //! preserving original source-level names.
 //!
+//! ## Verification Metadata Section
+//!
+//! Modules compiled by Inference carry the `inference.verification` custom section, with
+//! the source location of each function containing `forall`, `exists`, `assume` or
+//! `unique` blocks and of each of these blocks. The translator writes these locations as
+//! comments next to the definitions and blocks, linking the generated Rocq code back to
+//! the lines of the `.inf` source.
+//!
 //! ## Component Model Sections
 //!
 //! WebAssembly component model sections are recognized but generate empty stubs:
@@ -79,10 +104,13 @@
 //! - Out-of-bounds indices
 //! - Unsupported WASM features (when explicitly detected)
 //!
+//! Before parsing, every entry point calls [`validate`], which reports a structurally
+//! invalid module as a [`ValidationError`] with the offset of the problem.
+//!
 //! The translation phase (Phase 2) uses error recovery, but the parsing phase does not.
 
 use inf_wasmparser::{
-    Parser,
+    BinaryReaderError, Parser,
     Payload::{
         CodeSectionEntry, CodeSectionStart, ComponentAliasSection, ComponentCanonicalSection,
         ComponentExportSection, ComponentImportSection, ComponentInstanceSection, ComponentSection,
@@ -91,10 +119,17 @@ use inf_wasmparser::{
         GlobalSection, ImportSection, InstanceSection, MemorySection, ModuleSection, StartSection,
         TableSection, TagSection, TypeSection, UnknownSection, Version,
     },
+    Validator, WasmFeatures,
 };
-use std::{collections::HashMap, io::Read};
+use inf_wast::{Wat, parser::ParseBuffer};
+use std::{borrow::Cow, collections::HashMap, fmt, io::Write};
 
-use crate::translator::WasmParseData;
+use crate::{
+    config::TranslationConfig,
+    report::{SectionSize, TranslationReport},
+    translator::{RocqFile, WasmParseData},
+    verification::{self, VerificationMetadata},
+};
 
 /// Translates WebAssembly bytecode into Rocq (Coq) formal verification code.
 ///
@@ -107,7 +142,7 @@ use crate::translator::WasmParseData;
 /// # Parameters
 ///
 /// - `mod_name`: The Rocq module name for generated definitions (may be overridden by WASM custom name section)
-/// - `bytes`: Raw WASM bytecode to translate
+/// - `bytes`: Raw WASM bytecode to translate, or WAT text
 ///
 /// # Returns
 ///
@@ -121,7 +156,7 @@ use crate::translator::WasmParseData;
 /// # Errors
 ///
 /// Returns an error if:
-/// - WASM bytecode is malformed or invalid
+/// - WASM bytecode is malformed or invalid, or WAT text fails to assemble
 /// - Required WASM sections are missing
 /// - Unsupported WASM features are encountered (e.g., tag section, unknown reference types)
 /// - Translation of specific instructions fails
@@ -141,28 +176,161 @@ use crate::translator::WasmParseData;
 /// Integration with Inference compiler:
 ///
 /// ```ignore
-/// use inference::{parse, type_check, codegen};
+/// use inference::{parse, type_check, codegen, CodegenOptions};
 /// use inference_wasm_to_v_translator::wasm_parser::translate_bytes;
 ///
 /// let source = std::fs::read_to_string("program.inf")?;
 /// let arena = parse(&source)?;
 /// let typed_context = type_check(arena)?;
-/// let wasm_bytes = codegen(&typed_context)?;
+/// let wasm_bytes = codegen(&typed_context, &CodegenOptions::default())?;
 ///
 /// // Translate to Rocq
 /// let rocq_code = translate_bytes("Program", &wasm_bytes)?;
 /// std::fs::write("program.v", rocq_code)?;
 /// ```
 pub fn translate_bytes(mod_name: &str, bytes: &[u8]) -> anyhow::Result<String> {
-    let mut data = Vec::new();
-    let mut reader = std::io::Cursor::new(bytes);
-    reader.read_to_end(&mut data).unwrap();
-    match parse(mod_name.to_string(), &data) {
-        Ok(mut parse_data) => parse_data.translate(),
+    translate_bytes_with_config(mod_name, bytes, &TranslationConfig::default())
+}
+
+/// Translates WebAssembly bytecode into Rocq code for the target library described by
+/// `config`.
+///
+/// [`translate_bytes`] is this function with [`TranslationConfig::default`], which targets
+/// the `Wasm` library.
+///
+/// # Errors
+///
+/// Returns the errors of [`translate_bytes`].
+///
+/// # Examples
+///
+/// ```ignore
+/// use inference_wasm_to_v_translator::{config::TranslationConfig, wasm_parser};
+///
+/// let config = TranslationConfig {
+///     module_prefix: Some("Generated".to_string()),
+///     ..TranslationConfig::default()
+/// };
+/// let rocq_code = wasm_parser::translate_bytes_with_config("my_module", &wasm_bytes, &config)?;
+/// ```
+pub fn translate_bytes_with_config(
+    mod_name: &str,
+    bytes: &[u8],
+    config: &TranslationConfig,
+) -> anyhow::Result<String> {
+    let bytes = wasm_binary(bytes)?;
+    validate(&bytes)?;
+    match parse(mod_name.to_string(), &bytes) {
+        Ok(mut parse_data) => parse_data.translate(config),
         Err(e) => Err(anyhow::anyhow!(e.to_string())),
     }
 }
 
+/// Translates WebAssembly bytecode into Rocq code written incrementally to `writer`.
+///
+/// Produces the same output as [`translate_bytes`], but writes each function definition
+/// and module record entry as soon as it is translated instead of building one `String`,
+/// so translating a multi-megabyte module does not hold its whole translation in memory.
+/// The writer is flushed when the translation is complete. Writes are not buffered, so
+/// pass a [`std::io::BufWriter`] for writers where each write is costly, such as files
+/// and sockets.
+///
+/// # Parameters
+///
+/// - `mod_name`: The Rocq module name for generated definitions (may be overridden by WASM custom name section)
+/// - `bytes`: Raw WASM bytecode to translate
+/// - `writer`: Destination of the Rocq code
+///
+/// # Errors
+///
+/// Returns the errors of [`translate_bytes`], and an error if writing to `writer` fails.
+/// When a function fails to translate, the definitions of the functions before it have
+/// already been written.
+///
+/// # Examples
+///
+/// ```ignore
+/// use inference_wasm_to_v_translator::wasm_parser::translate_to_writer;
+/// use std::{fs::File, io::BufWriter};
+///
+/// let wasm_bytes = std::fs::read("output.wasm")?;
+/// let output = BufWriter::new(File::create("output.v")?);
+/// translate_to_writer("my_module", &wasm_bytes, output)?;
+/// ```
+pub fn translate_to_writer(mod_name: &str, bytes: &[u8], writer: impl Write) -> anyhow::Result<()> {
+    translate_to_writer_with_config(mod_name, bytes, &TranslationConfig::default(), writer)
+}
+
+/// Translates WebAssembly bytecode into Rocq code for the target library described by
+/// `config`, written incrementally to `writer`.
+///
+/// Combines [`translate_to_writer`] and [`translate_bytes_with_config`].
+///
+/// # Errors
+///
+/// Returns the errors of [`translate_to_writer`].
+pub fn translate_to_writer_with_config(
+    mod_name: &str,
+    bytes: &[u8],
+    config: &TranslationConfig,
+    mut writer: impl Write,
+) -> anyhow::Result<()> {
+    let bytes = wasm_binary(bytes)?;
+    validate(&bytes)?;
+    let mut parse_data =
+        parse(mod_name.to_string(), &bytes).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    parse_data.translate_to(&mut writer, config)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Translates WebAssembly bytecode into Rocq code for the target library described by
+/// `config`, and reports the coverage of the translation.
+///
+/// Functions containing unsupported instructions do not fail the translation: they are
+/// replaced by a comment in the output, left out of the module record, and listed in the
+/// [`TranslationReport`] together with every unsupported instruction and its offset. The
+/// report also holds the number of translated instructions and the size of each section.
+///
+/// # Errors
+///
+/// Returns an error if the WASM bytecode is malformed or invalid.
+pub fn translate_bytes_with_report(
+    mod_name: &str,
+    bytes: &[u8],
+    config: &TranslationConfig,
+) -> anyhow::Result<(String, TranslationReport)> {
+    let bytes = wasm_binary(bytes)?;
+    validate(&bytes)?;
+    let mut parse_data =
+        parse(mod_name.to_string(), &bytes).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    parse_data.translate_with_report(config)
+}
+
+/// Translates WebAssembly bytecode into one Rocq file per function, plus a prelude file
+/// with the imports and helpers and a module file with the module record.
+///
+/// Large modules translate to `.v` files that are slow to check as a whole; split files
+/// can be checked in parallel, and only the files of changed functions are re-checked.
+/// The files are returned in dependency order, each starting with the imports of `config`
+/// and `Require Import` lines for the files it depends on. Write each one to
+/// [`RocqFile::file_name`] in a directory of the Rocq load path.
+///
+/// # Errors
+///
+/// Returns the errors of [`translate_bytes`].
+pub fn translate_to_files(
+    mod_name: &str,
+    bytes: &[u8],
+    config: &TranslationConfig,
+) -> anyhow::Result<Vec<RocqFile>> {
+    let bytes = wasm_binary(bytes)?;
+    validate(&bytes)?;
+    let mut parse_data =
+        parse(mod_name.to_string(), &bytes).map_err(|e| anyhow::anyhow!(e.to_string()))?;
+    parse_data.translate_files(config)
+}
+
 /// Parses WebAssembly bytecode into structured [`WasmParseData`].
 ///
 /// This function makes a single forward pass through the WASM module,
@@ -206,7 +374,18 @@ fn parse(mod_name: String, data: &'_ [u8]) -> anyhow::Result<WasmParseData<'_>>
     let mut wasm_parse_data = WasmParseData::new(mod_name);
 
     for payload in parser.parse_all(data) {
-        match payload? {
+        let payload = payload?;
+        if let Some((id, range)) = payload.as_section() {
+            let name = match &payload {
+                CustomSection(custom_section) => format!("custom {:?}", custom_section.name()),
+                _ => section_name(id).to_string(),
+            };
+            wasm_parse_data.section_sizes.push(SectionSize {
+                name,
+                size: range.len(),
+            });
+        }
+        match payload {
             // Sections for WebAssembly modules
             Version { .. } => {
                 /*
@@ -287,6 +466,10 @@ fn parse(mod_name: String, data: &'_ [u8]) -> anyhow::Result<WasmParseData<'_>>
             ComponentExportSection(_) => { /* ... */ }
 
             CustomSection(custom_section) => {
+                if custom_section.name() == verification::SECTION_NAME {
+                    wasm_parse_data.verification =
+                        Some(VerificationMetadata::decode(custom_section.data())?);
+                }
                 if let inf_wasmparser::KnownCustom::Name(name_section) = custom_section.as_known() {
                     for name in name_section {
                         let name = name?;
@@ -343,3 +526,98 @@ fn parse(mod_name: String, data: &'_ [u8]) -> anyhow::Result<WasmParseData<'_>>
     }
     Ok(wasm_parse_data)
 }
+
+/// A structural problem of a WASM module, found by validation before translation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Byte offset of the problem in the module.
+    pub offset: usize,
+    /// Description of the problem, such as `type index out of bounds`.
+    pub message: String,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid module at offset 0x{:x}: {}",
+            self.offset, self.message
+        )
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validates the structure of WASM bytecode with the `inf-wasmparser` validator.
+///
+/// Every entry point validates the module before translating it, so a structurally
+/// invalid module, such as one with a type index out of range, an export of a function
+/// that does not exist, or a different number of function bodies than declared
+/// functions, is reported with the offset of the problem instead of failing in the
+/// middle of the translation. Tools can call this function to check a module without
+/// translating it.
+///
+/// Every proposal the validator knows is enabled. Function bodies are not type-checked:
+/// their instructions are checked one by one by the translation, and their typing is
+/// defined by the Rocq semantics of the translated module.
+///
+/// # Errors
+///
+/// Returns the first problem of the module, with its byte offset.
+pub fn validate(bytes: &[u8]) -> Result<(), ValidationError> {
+    let to_validation_error = |error: BinaryReaderError| ValidationError {
+        offset: error.offset(),
+        message: error.message().to_string(),
+    };
+    let mut validator = Validator::new_with_features(WasmFeatures::all());
+    for payload in Parser::new(0).parse_all(bytes) {
+        validator
+            .payload(&payload.map_err(to_validation_error)?)
+            .map_err(to_validation_error)?;
+    }
+    Ok(())
+}
+
+/// Returns `bytes` if they are a WASM binary, or the binary assembled from them if they are
+/// WAT text. Bytes that are neither are returned unchanged, for the parser to reject.
+///
+/// # Errors
+///
+/// Returns an error, with the line and column of the problem, if WAT text fails to
+/// assemble.
+fn wasm_binary(bytes: &[u8]) -> anyhow::Result<Cow<'_, [u8]>> {
+    if bytes.starts_with(b"\0asm") || bytes.trim_ascii().is_empty() {
+        return Ok(Cow::Borrowed(bytes));
+    }
+    let Ok(text) = std::str::from_utf8(bytes) else {
+        return Ok(Cow::Borrowed(bytes));
+    };
+    let with_text = |mut error: inf_wast::Error| {
+        error.set_text(text);
+        anyhow::anyhow!("Invalid WAT: {error}")
+    };
+    let buffer = ParseBuffer::new(text).map_err(with_text)?;
+    let mut wat = inf_wast::parser::parse::<Wat>(&buffer).map_err(with_text)?;
+    Ok(Cow::Owned(wat.encode().map_err(with_text)?))
+}
+
+/// Returns the name of the module section with the given id.
+fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,109 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["f1"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 1) ::
+    BI_const_num (Vi32 2) ::
+    BI_return ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["f2"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 1) ::
+    BI_const_num (Vi32 2) ::
+    BI_return ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["f3"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 1) ::
+    BI_const_num (Vi32 2) ::
+    BI_return ::
+    nil;
+|}.
+
+(** Exports of [module_]:
+    - ["f1"]: function [func_0]
+    - ["f2"]: function [func_1]
+    - ["f3"]: function [func_2]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "f1" (MED_func 0%N) ::
+    Me "f2" (MED_func 1%N) ::
+    Me "f3" (MED_func 2%N) ::
+    nil;
+|}.
@@ -0,0 +1,52 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+Definition module_ : module := {|
+  mod_types :=
+    nil;
+  mod_funcs :=
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    nil;
+|}.
@@ -0,0 +1,292 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-rec"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_const_num (Vi64 0) ::
+    BI_relop T_i64 (Relop_i ROI_eq) ::
+    BI_if (BT_valtype (Some (T_num T_i64))) (
+      BI_const_num (Vi64 1) ::
+      nil) (
+      BI_local_get 0%N ::
+      BI_local_get 0%N ::
+      BI_const_num (Vi64 1) ::
+      BI_binop T_i64 (Binop_i BOI_sub) ::
+      BI_call 0 ::
+      BI_binop T_i64 (Binop_i BOI_mul) ::
+      nil) ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-rec-named"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_const_num (Vi64 0) ::
+    BI_relop T_i64 (Relop_i ROI_eq) ::
+    BI_if (BT_valtype (Some (T_num T_i64))) (
+      BI_const_num (Vi64 1) ::
+      nil) (
+      BI_local_get 0%N ::
+      BI_local_get 0%N ::
+      BI_const_num (Vi64 1) ::
+      BI_binop T_i64 (Binop_i BOI_sub) ::
+      BI_call 1 ::
+      BI_binop T_i64 (Binop_i BOI_mul) ::
+      nil) ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-iter"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := T_num T_i64 :: T_num T_i64 :: nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_set 1%N ::
+    BI_const_num (Vi64 1) ::
+    BI_local_set 2%N ::
+    BI_block (BT_valtype None) (
+      BI_loop (BT_valtype None) (
+        BI_local_get 1%N ::
+        BI_const_num (Vi64 0) ::
+        BI_relop T_i64 (Relop_i ROI_eq) ::
+        BI_if (BT_valtype None) (
+          BI_br 2 ::
+          nil) (
+          BI_local_get 1%N ::
+          BI_local_get 2%N ::
+          BI_binop T_i64 (Binop_i BOI_mul) ::
+          BI_local_set 2%N ::
+          BI_local_get 1%N ::
+          BI_const_num (Vi64 1) ::
+          BI_binop T_i64 (Binop_i BOI_sub) ::
+          BI_local_set 1%N ::
+          nil) ::
+        BI_br 0 ::
+        nil) ::
+      nil) ::
+    BI_local_get 2%N ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-iter-named"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := T_num T_i64 :: T_num T_i64 :: nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_set 1%N ::
+    BI_const_num (Vi64 1) ::
+    BI_local_set 2%N ::
+    BI_block (BT_valtype None) (
+      BI_loop (BT_valtype None) (
+        BI_local_get 1%N ::
+        BI_const_num (Vi64 0) ::
+        BI_relop T_i64 (Relop_i ROI_eq) ::
+        BI_if (BT_valtype None) (
+          BI_br 2 ::
+          nil) (
+          BI_local_get 1%N ::
+          BI_local_get 2%N ::
+          BI_binop T_i64 (Binop_i BOI_mul) ::
+          BI_local_set 2%N ::
+          BI_local_get 1%N ::
+          BI_const_num (Vi64 1) ::
+          BI_binop T_i64 (Binop_i BOI_sub) ::
+          BI_local_set 1%N ::
+          nil) ::
+        BI_br 0 ::
+        nil) ::
+      nil) ::
+    BI_local_get 2%N ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-opt"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := T_num T_i64 :: nil;
+  modfunc_body :=
+    BI_const_num (Vi64 1) ::
+    BI_local_set 1%N ::
+    BI_block (BT_valtype None) (
+      BI_local_get 0%N ::
+      BI_const_num (Vi64 2) ::
+      BI_relop T_i64 (Relop_i (ROI_lt SX_S)) ::
+      BI_br_if 0%N ::
+      BI_loop (BT_valtype None) (
+        BI_local_get 1%N ::
+        BI_local_get 0%N ::
+        BI_binop T_i64 (Binop_i BOI_mul) ::
+        BI_local_set 1%N ::
+        BI_local_get 0%N ::
+        BI_const_num (Vi64 -1) ::
+        BI_binop T_i64 (Binop_i BOI_add) ::
+        BI_local_set 0%N ::
+        BI_local_get 0%N ::
+        BI_const_num (Vi64 1) ::
+        BI_relop T_i64 (Relop_i (ROI_gt SX_S)) ::
+        BI_br_if 0%N ::
+        nil) ::
+      nil) ::
+    BI_local_get 1%N ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: [i64].
+    Results: [i64], [i64].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 0%N ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: [i64], [i64].
+    Results: [i64], [i64], [i64].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_local_get 0%N ::
+    nil;
+|}.
+
+(** Function [func_7] (index 7).
+    Parameters: [i64].
+    Results: [i64].
+    Exported as ["fac-ssa"].
+*)
+Definition func_7 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi64 1) ::
+    BI_local_get 0%N ::
+    BI_loop (BT_id 3%N) (
+      BI_call 6 ::
+      BI_call 6 ::
+      BI_binop T_i64 (Binop_i BOI_mul) ::
+      BI_call 6 ::
+      BI_const_num (Vi64 1) ::
+      BI_binop T_i64 (Binop_i BOI_sub) ::
+      BI_call 5 ::
+      BI_const_num (Vi64 0) ::
+      BI_relop T_i64 (Relop_i (ROI_gt SX_U)) ::
+      BI_br_if 0%N ::
+      BI_drop ::
+      BI_return ::
+      nil) ::
+    nil;
+|}.
+
+(** Exports of [module_]:
+    - ["fac-rec"]: function [func_0]
+    - ["fac-rec-named"]: function [func_1]
+    - ["fac-iter"]: function [func_2]
+    - ["fac-iter-named"]: function [func_3]
+    - ["fac-opt"]: function [func_4]
+    - ["fac-ssa"]: function [func_7]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i64 :: nil) (T_num T_i64 :: nil) ::
+    Tf (T_num T_i64 :: nil) (T_num T_i64 :: T_num T_i64 :: nil) ::
+    Tf (T_num T_i64 :: T_num T_i64 :: nil) (T_num T_i64 :: T_num T_i64 :: T_num T_i64 :: nil) ::
+    Tf (T_num T_i64 :: T_num T_i64 :: nil) (T_num T_i64 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    func_7 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "fac-rec" (MED_func 0%N) ::
+    Me "fac-rec-named" (MED_func 1%N) ::
+    Me "fac-iter" (MED_func 2%N) ::
+    Me "fac-iter-named" (MED_func 3%N) ::
+    Me "fac-opt" (MED_func 4%N) ::
+    Me "fac-ssa" (MED_func 7%N) ::
+    nil;
+|}.
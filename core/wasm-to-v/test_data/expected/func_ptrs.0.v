@@ -0,0 +1,155 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["one"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 4%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 13) ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["two"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 5%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 1) ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["three"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 5%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 2) ::
+    BI_binop T_i32 (Binop_i BOI_sub) ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: [i32].
+    Results: none.
+    Exported as ["four"].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 6%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_call 0 ::
+    nil;
+|}.
+
+(** Exports of [module_]:
+    - ["one"]: function [func_3]
+    - ["two"]: function [func_4]
+    - ["three"]: function [func_5]
+    - ["four"]: function [func_6]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    Tf (nil) (nil) ::
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    Tf (T_num T_i32 :: nil) (nil) ::
+    nil;
+  mod_funcs :=
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    Mi "spectest" "print_i32" (MID_func 6%N) ::
+    nil;
+  mod_exports :=
+    Me "one" (MED_func 3%N) ::
+    Me "two" (MED_func 4%N) ::
+    Me "three" (MED_func 5%N) ::
+    Me "four" (MED_func 6%N) ::
+    nil;
+|}.
@@ -0,0 +1,170 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 1) ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 2) ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 3) ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 4) ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 5) ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["callt"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_call_indirect 0 0 ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["callu"].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_call_indirect 1 0 ::
+    nil;
+|}.
+
+Definition module__elem_0 : list (option N) := Some 0%N :: Some 1%N :: Some 2%N :: Some 3%N :: Some 4%N :: Some 0%N :: Some 2%N :: nil.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    (0%N, module__elem_0) ::
+    nil.
+
+(** Exports of [module_]:
+    - ["callt"]: function [func_5]
+    - ["callu"]: function [func_6]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 7%N; lim_max := Some(7%N)|} T_funcref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::1::2::3::4::0::2::nil;
+modelem_mode := ME_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "callt" (MED_func 5%N) ::
+    Me "callu" (MED_func 6%N) ::
+    nil;
+|}.
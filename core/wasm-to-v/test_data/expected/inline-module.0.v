@@ -0,0 +1,86 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: none.
+    Exported as ["f"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+Definition module__initial_memory_0 : list (N * list byte) :=
+    nil.
+
+(** Exports of [module_]:
+    - ["f"]: function [func_1]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    Mm {|lim_min := 0%N; lim_max := None|} ::
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "f" (MED_func 1%N) ::
+    nil;
+|}.
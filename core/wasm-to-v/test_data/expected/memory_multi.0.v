@@ -0,0 +1,112 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: [i64].
+    Results: [i32].
+    Exported as ["copy"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_local_get 0%N ::
+    BI_load_at 2%N T_i64 None (Ma 8%N 3%N) ::
+    BI_cvtop T_i32 (CVO_wrap T_i64 None) ::
+    BI_store_at 1%N T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 4) ::
+    BI_load_at 1%N T_i32 (Some (Tp_i8, SX_U)) (Ma 1%N 0%N) ::
+    BI_drop ::
+    BI_memory_size_at 2%N ::
+    BI_memory_grow_at 2%N ::
+    BI_drop ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 4) ::
+    BI_memory_copy_at 1%N 1%N ::
+    BI_const_num (Vi64 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi64 4) ::
+    BI_memory_fill_at 2%N ::
+    BI_local_get 0%N ::
+    BI_load T_i64 None (Ma 0%N 3%N) ::
+    BI_drop ::
+    BI_memory_size ::
+    BI_cvtop T_i32 (CVO_wrap T_i64 None) ::
+    nil;
+|}.
+
+Definition module__data_0 : list byte := #68 :: #69 :: nil.
+
+Definition module__initial_memory_0 : list (N * list byte) :=
+    nil.
+Definition module__initial_memory_1 : list (N * list byte) :=
+    nil.
+Definition module__initial_memory_2 : list (N * list byte) :=
+    (16%N, module__data_0) ::
+    nil.
+
+(** Exports of [module_]:
+    - ["copy"]: function [func_0]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i64 :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    Mm {|lim_min := 1%N; lim_max := Some(2%N)|} ::
+    Mm64 {|lim_min := 2%N; lim_max := None|} ::
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    {|
+    moddata_init := module__data_0;
+    moddata_mode := MD_active 2%N (    BI_const_num (Vi64 16) ::
+    nil);
+|} ::
+    nil;
+  mod_start := None;
+  mod_imports :=
+    Mi "env" "shared" (MID_mem64 {|lim_min := 1%N; lim_max := None|}) ::
+    nil;
+  mod_exports :=
+    Me "copy" (MED_func 0%N) ::
+    nil;
+|}.
@@ -0,0 +1,209 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: none.
+    Exported as ["zero_everything"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 4) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 8) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 12) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["test_store_to_load"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 8) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 5) ::
+    BI_const_num (VAL_float32 2147483648) ::
+    BI_store T_f32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 8) ::
+    BI_load T_i32 None (Ma 0%N 2%N) ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["test_redundant_load"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := T_num T_i32 :: T_num T_i32 :: nil;
+  modfunc_body :=
+    BI_const_num (Vi32 8) ::
+    BI_load T_i32 None (Ma 0%N 2%N) ::
+    BI_local_set 0%N ::
+    BI_const_num (Vi32 5) ::
+    BI_const_num (Vi32 -2147483648) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 8) ::
+    BI_load T_i32 None (Ma 0%N 2%N) ::
+    BI_local_set 1%N ::
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: [f32].
+    Exported as ["test_dead_store"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := T_num T_f32 :: nil;
+  modfunc_body :=
+    BI_const_num (Vi32 8) ::
+    BI_const_num (Vi32 589505315) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_const_num (Vi32 11) ::
+    BI_load T_f32 None (Ma 0%N 2%N) ::
+    BI_local_set 0%N ::
+    BI_const_num (Vi32 8) ::
+    BI_const_num (Vi32 0) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_local_get 0%N ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["malloc"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 3%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 16) ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["malloc_aliasing"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := T_num T_i32 :: T_num T_i32 :: nil;
+  modfunc_body :=
+    BI_const_num (Vi32 4) ::
+    BI_call 4 ::
+    BI_local_set 0%N ::
+    BI_const_num (Vi32 4) ::
+    BI_call 4 ::
+    BI_local_set 1%N ::
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 42) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_local_get 1%N ::
+    BI_const_num (Vi32 43) ::
+    BI_store T_i32 None (Ma 0%N 2%N) ::
+    BI_local_get 0%N ::
+    BI_load T_i32 None (Ma 0%N 2%N) ::
+    nil;
+|}.
+
+Definition module__initial_memory_0 : list (N * list byte) :=
+    nil.
+
+(** Exports of [module_]:
+    - ["zero_everything"]: function [func_0]
+    - ["test_store_to_load"]: function [func_1]
+    - ["test_redundant_load"]: function [func_2]
+    - ["test_dead_store"]: function [func_3]
+    - ["malloc"]: function [func_4]
+    - ["malloc_aliasing"]: function [func_5]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (T_num T_f32 :: nil) ::
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    Mm {|lim_min := 1%N; lim_max := Some(1%N)|} ::
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "zero_everything" (MED_func 0%N) ::
+    Me "test_store_to_load" (MED_func 1%N) ::
+    Me "test_redundant_load" (MED_func 2%N) ::
+    Me "test_dead_store" (MED_func 3%N) ::
+    Me "malloc" (MED_func 4%N) ::
+    Me "malloc_aliasing" (MED_func 5%N) ::
+    nil;
+|}.
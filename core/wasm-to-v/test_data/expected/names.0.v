@@ -0,0 +1,147 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [my_module]
+
+    Rocq translation of a WebAssembly module, defined by the record [my_module]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [add] (index 1).
+    Parameters: [a : i32], [b : i32].
+    Results: [i32].
+    Exported as ["add"].
+*)
+Definition add : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := T_num T_i32 :: nil;
+  modfunc_body :=
+    BI_local_get 0%N (*a*) ::
+    BI_local_get 1%N (*b*) ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    BI_local_tee 2%N (*sum*) ::
+    BI_call 0 (*host_log*) ::
+    BI_block (BT_valtype None) (
+      BI_local_get 2%N (*sum*) ::
+      BI_call 0 (*host_log*) ::
+      nil) ::
+    BI_local_get 2%N (*sum*) ::
+    nil;
+|}.
+
+(** Function [add_1] (index 2).
+    Parameters: [i32].
+    Results: none.
+*)
+Definition add_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_call 3 (*nil_*) ::
+    nil;
+|}.
+
+(** Function [nil_] (index 3).
+    Parameters: [x_value : i32].
+    Results: none.
+*)
+Definition nil_ : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [id_2nd_pass] (index 4).
+    Parameters: none.
+    Results: none.
+*)
+Definition id_2nd_pass : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [my_module_1] (index 5).
+    Parameters: none.
+    Results: none.
+*)
+Definition my_module_1 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_call 2 (*add_1*) ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_call 3 (*nil_*) ::
+    nil;
+|}.
+
+(** Exports of [my_module]:
+    - ["add"]: function [add]
+*)
+Definition my_module : module := {|
+  mod_types :=
+    Tf (T_num T_i32 :: nil) (nil) ::
+    Tf (T_num T_i32 :: T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (nil) ::
+    nil;
+  mod_funcs :=
+    add ::
+    add_1 ::
+    nil_ ::
+    id_2nd_pass ::
+    my_module_1 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    Mi "env" "log" (MID_func 0%N) ::
+    nil;
+  mod_exports :=
+    Me "add" (MED_func 1%N) ::
+    nil;
+|}.
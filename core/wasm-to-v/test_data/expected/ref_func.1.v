@@ -0,0 +1,318 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_1] (index 1).
+    Parameters: [i32].
+    Results: [i32].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 1) ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 5%N ::
+    BI_drop ::
+    BI_ref_func 6%N ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_7] (index 7).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["is_null-f"].
+*)
+Definition func_7 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 0%N ::
+    BI_ref_is_null ::
+    nil;
+|}.
+
+(** Function [func_8] (index 8).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["is_null-g"].
+*)
+Definition func_8 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 1%N ::
+    BI_ref_is_null ::
+    nil;
+|}.
+
+(** Function [func_9] (index 9).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["is_null-v"].
+*)
+Definition func_9 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_global_get 2%N ::
+    BI_ref_is_null ::
+    nil;
+|}.
+
+(** Function [func_10] (index 10).
+    Parameters: none.
+    Results: none.
+    Exported as ["set-f"].
+*)
+Definition func_10 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 0%N ::
+    BI_global_set 2%N ::
+    nil;
+|}.
+
+(** Function [func_11] (index 11).
+    Parameters: none.
+    Results: none.
+    Exported as ["set-g"].
+*)
+Definition func_11 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 1%N ::
+    BI_global_set 2%N ::
+    nil;
+|}.
+
+(** Function [func_12] (index 12).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["call-f"].
+*)
+Definition func_12 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_ref_func 0%N ::
+    BI_table_set 0%N ::
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_call_indirect 0 0 ::
+    nil;
+|}.
+
+(** Function [func_13] (index 13).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["call-g"].
+*)
+Definition func_13 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_ref_func 1%N ::
+    BI_table_set 0%N ::
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_call_indirect 0 0 ::
+    nil;
+|}.
+
+(** Function [func_14] (index 14).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["call-v"].
+*)
+Definition func_14 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_global_get 2%N ::
+    BI_table_set 0%N ::
+    BI_local_get 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_call_indirect 0 0 ::
+    nil;
+|}.
+
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    nil.
+
+(** Exports of [module_]:
+    - ["is_null-f"]: function [func_7]
+    - ["is_null-g"]: function [func_8]
+    - ["is_null-v"]: function [func_9]
+    - ["set-f"]: function [func_10]
+    - ["set-g"]: function [func_11]
+    - ["call-f"]: function [func_12]
+    - ["call-g"]: function [func_13]
+    - ["call-v"]: function [func_14]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    func_7 ::
+    func_8 ::
+    func_9 ::
+    func_10 ::
+    func_11 ::
+    func_12 ::
+    func_13 ::
+    func_14 ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 1%N; lim_max := None|} T_funcref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    Mg MUT_const (T_ref T_funcref) (    BI_ref_func 0%N ::
+    nil) ::
+    Mg MUT_const (T_ref T_funcref) (    BI_ref_func 1%N ::
+    nil) ::
+    Mg MUT_var (T_ref T_funcref) (    BI_ref_func 0%N ::
+    nil) ::
+    Mg MUT_const (T_ref T_funcref) (    BI_ref_func 3%N ::
+    nil) ::
+    Mg MUT_const (T_ref T_funcref) (    BI_ref_func 4%N ::
+    nil) ::
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 3::5::nil;
+modelem_mode := ME_declared;
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 4::6::nil;
+modelem_mode := ME_declared;
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::1::nil;
+modelem_mode := ME_declared;
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    Mi "M" "f" (MID_func 0%N) ::
+    nil;
+  mod_exports :=
+    Me "is_null-f" (MED_func 7%N) ::
+    Me "is_null-g" (MED_func 8%N) ::
+    Me "is_null-v" (MED_func 9%N) ::
+    Me "set-f" (MED_func 10%N) ::
+    Me "set-g" (MED_func 11%N) ::
+    Me "call-f" (MED_func 12%N) ::
+    Me "call-g" (MED_func 13%N) ::
+    Me "call-v" (MED_func 14%N) ::
+    nil;
+|}.
@@ -0,0 +1,188 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: none.
+    Exported as ["f"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_func 0%N ::
+    BI_ref_func 1%N ::
+    BI_ref_func 2%N ::
+    BI_ref_func 3%N ::
+    BI_ref_func 4%N ::
+    BI_ref_func 5%N ::
+    BI_return ::
+    nil;
+|}.
+
+Definition module__elem_0 : list (option N) := Some 2%N :: nil.
+Definition module__elem_1 : list (option N) := Some 3%N :: nil.
+Definition module__elem_2 : list (option N) := Some 4%N :: nil.
+Definition module__elem_3 : list (option N) := Some 5%N :: nil.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    (0%N, module__elem_0) ::
+    (0%N, module__elem_1) ::
+    nil.
+
+(** Exports of [module_]:
+    - ["f"]: function [func_1]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 1%N; lim_max := None|} T_funcref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    Mg MUT_const (T_ref T_funcref) (    BI_ref_func 0%N ::
+    nil) ::
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 2::nil;
+modelem_mode := ME_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 3::nil;
+modelem_mode := ME_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 4::nil;
+modelem_mode := ME_passive;
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 5::nil;
+modelem_mode := ME_passive;
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "f" (MED_func 1%N) ::
+    nil;
+|}.
@@ -0,0 +1,169 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [add] (index 0).
+    Parameters: [i32], [i32].
+    Results: [i32].
+*)
+Definition add : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [tables] (index 1).
+    Parameters: [host : externref].
+    Results: [i32].
+*)
+Definition tables : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_ref_func 0%N (*add*) ::
+    BI_table_set 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_local_get 0%N (*host*) ::
+    BI_table_set 1%N ::
+    BI_ref_null T_funcref ::
+    BI_const_num (Vi32 1) ::
+    BI_table_grow 0%N ::
+    BI_drop ::
+    BI_const_num (Vi32 1) ::
+    BI_ref_null T_funcref ::
+    BI_const_num (Vi32 1) ::
+    BI_table_fill 0%N ::
+    BI_const_num (Vi32 1) ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 1) ::
+    BI_table_copy 0%N 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 1) ::
+    BI_table_init 0%N 0%N ::
+    BI_elem_drop 0%N ::
+    BI_const_num (Vi32 0) ::
+    BI_table_get 0%N ::
+    BI_ref_is_null ::
+    BI_ref_null T_externref ::
+    BI_local_get 0%N (*host*) ::
+    BI_const_num (Vi32 1) ::
+    BI_select (Some (T_ref T_externref :: nil)) ::
+    BI_drop ::
+    BI_drop ::
+    BI_table_size 0%N ::
+    nil;
+|}.
+
+(** Function [apply] (index 2).
+    Parameters: [f : (ref null (module 0))].
+    Results: [i32].
+*)
+Definition apply : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := T_ref (T_nonnull (T_typeref 0%N)) :: nil;
+  modfunc_body :=
+    BI_block (BT_valtype None) (
+      BI_local_get 0%N (*f*) ::
+      BI_br_on_null 0%N ::
+      BI_local_set 1%N (*g*) ::
+      BI_const_num (Vi32 1) ::
+      BI_const_num (Vi32 2) ::
+      BI_local_get 1%N (*g*) ::
+      BI_call_ref 0%N ::
+      BI_return ::
+      nil) ::
+    BI_block (BT_valtype (Some (T_ref (T_nonnull (T_typeref 0%N))))) (
+      BI_ref_null (T_typeref 0%N) ::
+      BI_br_on_non_null 0%N ::
+      BI_ref_func 0%N (*add*) ::
+      BI_ref_as_non_null ::
+      nil) ::
+    BI_local_set 1%N (*g*) ::
+    BI_const_num (Vi32 3) ::
+    BI_const_num (Vi32 4) ::
+    BI_local_get 1%N (*g*) ::
+    BI_return_call_ref 0%N ::
+    nil;
+|}.
+
+Definition module__elem_0 : list (option N) := Some 0%N :: nil.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    nil.
+Definition module__initial_table_1 : list (N * list (option N)) :=
+    nil.
+
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i32 :: T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    Tf (T_ref T_externref :: nil) (T_num T_i32 :: nil) ::
+    Tf (T_ref (T_typeref 0%N) :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    add ::
+    tables ::
+    apply ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 2%N; lim_max := None|} T_funcref ::
+    Mt {|lim_min := 1%N; lim_max := None|} T_externref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::nil;
+modelem_mode := ME_passive;
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::nil;
+modelem_mode := ME_declared;
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    nil;
+|}.
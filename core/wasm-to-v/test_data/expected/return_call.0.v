@@ -0,0 +1,104 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [count] (index 0).
+    Parameters: [n : i32].
+    Results: [i32].
+*)
+Definition count : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N (*n*) ::
+    BI_testop T_i32 TO_eqz ::
+    BI_if (BT_valtype (Some (T_num T_i32))) (
+      BI_const_num (Vi32 0) ::
+      nil) (
+      BI_local_get 0%N (*n*) ::
+      BI_const_num (Vi32 1) ::
+      BI_binop T_i32 (Binop_i BOI_sub) ::
+      BI_return_call 0 (*count*) ::
+      nil) ::
+    nil;
+|}.
+
+(** Function [dispatch] (index 1).
+    Parameters: [n : i32].
+    Results: [i32].
+*)
+Definition dispatch : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N (*n*) ::
+    BI_const_num (Vi32 0) ::
+    BI_return_call_indirect 0 0 ::
+    nil;
+|}.
+
+Definition module__elem_0 : list (option N) := Some 0%N :: nil.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    (0%N, module__elem_0) ::
+    nil.
+
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    count ::
+    dispatch ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 1%N; lim_max := None|} T_funcref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::nil;
+modelem_mode := ME_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    nil;
+|}.
@@ -0,0 +1,176 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [segments]
+
+    Rocq translation of a WebAssembly module, defined by the record [segments]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [f] (index 0).
+    Parameters: none.
+    Results: none.
+*)
+Definition f : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [g] (index 1).
+    Parameters: none.
+    Results: [i32].
+*)
+Definition g : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 16) ::
+    BI_load T_i32 None (Ma 0%N 2%N) ::
+    nil;
+|}.
+
+Definition segments_data_0 : list byte := #68 :: #69 :: nil.
+Definition segments_data_1 : list byte := #00 :: #FF :: nil.
+Definition segments_data_2 : list byte := #78 :: nil.
+Definition segments_data_3 : list byte := #70 :: #61 :: #73 :: #73 :: #69 :: #76 :: #65 :: nil.
+Definition segments_data_4 : list byte := #73 :: nil.
+Definition segments_elem_0 : list (option N) := Some 0%N :: Some 1%N :: nil.
+Definition segments_elem_1 : list (option N) := Some 1%N :: None :: nil.
+Definition segments_elem_2 : list (option N) := Some 0%N :: nil.
+Definition segments_elem_3 : list (option N) := Some 0%N :: nil.
+
+Definition segments_initial_memory_0 : list (N * list byte) :=
+    (16%N, segments_data_0) ::
+    (64%N, segments_data_1) ::
+    (* data segment 2 has a non-constant offset *)
+    nil.
+Definition segments_initial_memory_1 : list (N * list byte) :=
+    (0%N, segments_data_4) ::
+    nil.
+Definition segments_initial_table_0 : list (N * list (option N)) :=
+    (0%N, segments_elem_1) ::
+    nil.
+Definition segments_initial_table_1 : list (N * list (option N)) :=
+    (1%N, segments_elem_0) ::
+    (* element segment 2 has a non-constant offset *)
+    nil.
+
+Definition segments : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    f ::
+    g ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 4%N; lim_max := None|} T_funcref ::
+    nil;
+  mod_mems :=
+    Mm {|lim_min := 1%N; lim_max := None|} ::
+    Mm {|lim_min := 1%N; lim_max := None|} ::
+    nil;
+  mod_globals :=
+    Mg MUT_const (T_num T_i32) (    BI_const_num (Vi32 64) ::
+    nil) ::
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::1::nil;
+modelem_mode := ME_active 1%N (    BI_const_num (Vi32 1) ::
+    nil);
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+(    BI_ref_func 1%N ::
+    nil) ::
+(    BI_ref_null T_funcref ::
+    nil) ::
+nil;
+modelem_mode := ME_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::nil;
+modelem_mode := ME_active 1%N (    BI_global_get 0%N ::
+    nil);
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+(    BI_ref_func 0%N ::
+    nil) ::
+nil;
+modelem_mode := ME_passive;
+|} ::
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 1::nil;
+modelem_mode := ME_declared;
+|} ::
+    nil;
+  mod_datas :=
+    {|
+    moddata_init := segments_data_0;
+    moddata_mode := MD_active 0%N (    BI_const_num (Vi32 16) ::
+    nil);
+|} ::
+    {|
+    moddata_init := segments_data_1;
+    moddata_mode := MD_active 0%N (    BI_global_get 1%N ::
+    nil);
+|} ::
+    {|
+    moddata_init := segments_data_2;
+    moddata_mode := MD_active 0%N (    BI_global_get 0%N ::
+    nil);
+|} ::
+    {|
+    moddata_init := segments_data_3;
+    moddata_mode := MD_passive;
+|} ::
+    {|
+    moddata_init := segments_data_4;
+    moddata_mode := MD_active 1%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    nil;
+  mod_start := None;
+  mod_imports :=
+    Mi "env" "base" (MID_global {|tg_mut := MUT_const; tg_t := T_num T_i32|}) ::
+    Mi "env" "table" (MID_table {|lim_min := 2%N; lim_max := None|}) ::
+    nil;
+  mod_exports :=
+    nil;
+|}.
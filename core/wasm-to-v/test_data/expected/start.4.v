@@ -0,0 +1,122 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: none.
+    Exported as ["inc"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_load T_i32 (Some (Tp_i8, SX_U)) (Ma 0%N 0%N) ::
+    BI_const_num (Vi32 1) ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    BI_store T_i32 (Some Tp_i8) (Ma 0%N 0%N) ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["get"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_load T_i32 (Some (Tp_i8, SX_U)) (Ma 0%N 0%N) ::
+    BI_return ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_call 0 ::
+    BI_call 0 ::
+    BI_call 0 ::
+    nil;
+|}.
+
+Definition module__data_0 : list byte := #41 :: nil.
+
+Definition module__initial_memory_0 : list (N * list byte) :=
+    (0%N, module__data_0) ::
+    nil.
+
+(** Exports of [module_]:
+    - ["inc"]: function [func_0]
+    - ["get"]: function [func_1]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    Mm {|lim_min := 1%N; lim_max := Some(1%N)|} ::
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    {|
+    moddata_init := module__data_0;
+    moddata_mode := MD_active 0%N (    BI_const_num (Vi32 0) ::
+    nil);
+|} ::
+    nil;
+  mod_start := Some {|modstart_func := 2%N|};
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "inc" (MED_func 0%N) ::
+    Me "get" (MED_func 1%N) ::
+    nil;
+|}.
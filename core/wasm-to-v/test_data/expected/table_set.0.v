@@ -0,0 +1,197 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: none.
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: [i32].
+    Results: [externref].
+    Exported as ["get-externref"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_table_get 0%N ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: [i32].
+    Results: [funcref].
+    Exported as ["get-funcref"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_table_get 1%N ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: [i32], [externref].
+    Results: none.
+    Exported as ["set-externref"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 3%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_table_set 0%N ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: [i32], [funcref].
+    Results: none.
+    Exported as ["set-funcref"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 4%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_table_set 1%N ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: [i32], [i32].
+    Results: none.
+    Exported as ["set-funcref-from"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 5%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_local_get 1%N ::
+    BI_table_get 1%N ::
+    BI_table_set 1%N ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["is_null-funcref"].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 6%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_local_get 0%N ::
+    BI_call 2 ::
+    BI_ref_is_null ::
+    nil;
+|}.
+
+Definition module__elem_0 : list (option N) := Some 0%N :: nil.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    nil.
+Definition module__initial_table_1 : list (N * list (option N)) :=
+    (1%N, module__elem_0) ::
+    nil.
+
+(** Exports of [module_]:
+    - ["get-externref"]: function [func_1]
+    - ["get-funcref"]: function [func_2]
+    - ["set-externref"]: function [func_3]
+    - ["set-funcref"]: function [func_4]
+    - ["set-funcref-from"]: function [func_5]
+    - ["is_null-funcref"]: function [func_6]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (nil) ::
+    Tf (T_num T_i32 :: nil) (T_ref T_externref :: nil) ::
+    Tf (T_num T_i32 :: nil) (T_ref T_funcref :: nil) ::
+    Tf (T_num T_i32 :: T_ref T_externref :: nil) (nil) ::
+    Tf (T_num T_i32 :: T_ref T_funcref :: nil) (nil) ::
+    Tf (T_num T_i32 :: T_num T_i32 :: nil) (nil) ::
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 1%N; lim_max := None|} T_externref ::
+    Mt {|lim_min := 2%N; lim_max := None|} T_funcref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    {|
+modelem_type := T_funcref;
+modelem_init :=
+ME_functions 0::nil;
+modelem_mode := ME_active 1%N (    BI_const_num (Vi32 1) ::
+    nil);
+|} ::
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "get-externref" (MED_func 1%N) ::
+    Me "get-funcref" (MED_func 2%N) ::
+    Me "set-externref" (MED_func 3%N) ::
+    Me "set-funcref" (MED_func 4%N) ::
+    Me "set-funcref-from" (MED_func 5%N) ::
+    Me "is_null-funcref" (MED_func 6%N) ::
+    nil;
+|}.
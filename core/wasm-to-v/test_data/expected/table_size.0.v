@@ -0,0 +1,209 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["size-t0"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_table_size 0%N ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["size-t1"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_table_size 1%N ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["size-t2"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_table_size 2%N ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["size-t3"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_table_size 3%N ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: [i32].
+    Results: none.
+    Exported as ["grow-t0"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_null T_externref ::
+    BI_local_get 0%N ::
+    BI_table_grow 0%N ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: [i32].
+    Results: none.
+    Exported as ["grow-t1"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_null T_externref ::
+    BI_local_get 0%N ::
+    BI_table_grow 1%N ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: [i32].
+    Results: none.
+    Exported as ["grow-t2"].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_null T_externref ::
+    BI_local_get 0%N ::
+    BI_table_grow 2%N ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Function [func_7] (index 7).
+    Parameters: [i32].
+    Results: none.
+    Exported as ["grow-t3"].
+*)
+Definition func_7 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_ref_null T_externref ::
+    BI_local_get 0%N ::
+    BI_table_grow 3%N ::
+    BI_drop ::
+    nil;
+|}.
+
+Definition module__initial_table_0 : list (N * list (option N)) :=
+    nil.
+Definition module__initial_table_1 : list (N * list (option N)) :=
+    nil.
+Definition module__initial_table_2 : list (N * list (option N)) :=
+    nil.
+Definition module__initial_table_3 : list (N * list (option N)) :=
+    nil.
+
+(** Exports of [module_]:
+    - ["size-t0"]: function [func_0]
+    - ["size-t1"]: function [func_1]
+    - ["size-t2"]: function [func_2]
+    - ["size-t3"]: function [func_3]
+    - ["grow-t0"]: function [func_4]
+    - ["grow-t1"]: function [func_5]
+    - ["grow-t2"]: function [func_6]
+    - ["grow-t3"]: function [func_7]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (T_num T_i32 :: nil) (nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    func_7 ::
+    nil;
+  mod_tables :=
+    Mt {|lim_min := 0%N; lim_max := None|} T_externref ::
+    Mt {|lim_min := 1%N; lim_max := None|} T_externref ::
+    Mt {|lim_min := 0%N; lim_max := Some(2%N)|} T_externref ::
+    Mt {|lim_min := 3%N; lim_max := Some(8%N)|} T_externref ::
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "size-t0" (MED_func 0%N) ::
+    Me "size-t1" (MED_func 1%N) ::
+    Me "size-t2" (MED_func 2%N) ::
+    Me "size-t3" (MED_func 3%N) ::
+    Me "grow-t0" (MED_func 4%N) ::
+    Me "grow-t1" (MED_func 5%N) ::
+    Me "grow-t2" (MED_func 6%N) ::
+    Me "grow-t3" (MED_func 7%N) ::
+    nil;
+|}.
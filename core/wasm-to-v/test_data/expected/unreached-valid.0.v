@@ -0,0 +1,206 @@
+Require Import List.
+Require Import String.
+Require Import BinNat.
+Require Import ZArith.
+From Wasm Require Import bytes.
+From Wasm Require Import numerics.
+From Wasm Require Import datatypes.
+
+(** * Module [module_]
+
+    Rocq translation of a WebAssembly module, defined by the record [module_]. *)
+
+Definition Vi32 i := VAL_int32 (Wasm_int.int_of_Z i32m i).
+Definition Vi64 i := VAL_int64 (Wasm_int.int_of_Z i64m i).
+Definition Mt l et := {|modtab_type := {|tt_limits := l; tt_elem_type := et|}|}.
+Definition Mm l := {|modmem_type := l|}.
+Definition Mg mut t init := {|modglob_type := {|tg_mut := mut; tg_t := t|}; modglob_init := init|}.
+
+Definition Mi m n d := {|
+  imp_module := list_byte_of_string m;
+  imp_name := list_byte_of_string n;
+  imp_desc := d;
+|}.
+
+Definition Me n d := {|
+  modexp_name := list_byte_of_string n;
+  modexp_desc := d;
+|}.
+
+Definition Ma of al := {|memarg_offset := of; memarg_align := al|}.
+
+(** Function [func_0] (index 0).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["select-trap-left"].
+*)
+Definition func_0 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_const_num (Vi32 0) ::
+    BI_local_get 0%N ::
+    BI_select None ::
+    nil;
+|}.
+
+(** Function [func_1] (index 1).
+    Parameters: [i32].
+    Results: [i32].
+    Exported as ["select-trap-right"].
+*)
+Definition func_1 : module_func := {|
+  modfunc_type := 0%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_const_num (Vi32 0) ::
+    BI_unreachable ::
+    BI_local_get 0%N ::
+    BI_select None ::
+    nil;
+|}.
+
+(** Function [func_2] (index 2).
+    Parameters: none.
+    Results: none.
+    Exported as ["select-unreached"].
+*)
+Definition func_2 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_select None ::
+    BI_unreachable ::
+    BI_const_num (Vi32 0) ::
+    BI_select None ::
+    BI_unreachable ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_select None ::
+    BI_unreachable ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_select None ::
+    BI_unreachable ::
+    BI_const_num (VAL_float32 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_select None ::
+    BI_unreachable ::
+    nil;
+|}.
+
+(** Function [func_3] (index 3).
+    Parameters: none.
+    Results: [i32].
+    Exported as ["select_unreached_result_1"].
+*)
+Definition func_3 : module_func := {|
+  modfunc_type := 2%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_select None ::
+    BI_binop T_i32 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [func_4] (index 4).
+    Parameters: none.
+    Results: [i64].
+    Exported as ["select_unreached_result_2"].
+*)
+Definition func_4 : module_func := {|
+  modfunc_type := 3%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_const_num (Vi64 0) ::
+    BI_const_num (Vi32 0) ::
+    BI_select None ::
+    BI_binop T_i64 (Binop_i BOI_add) ::
+    nil;
+|}.
+
+(** Function [func_5] (index 5).
+    Parameters: none.
+    Results: none.
+    Exported as ["unreachable-num"].
+*)
+Definition func_5 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_select None ::
+    BI_testop T_i32 TO_eqz ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Function [func_6] (index 6).
+    Parameters: none.
+    Results: none.
+    Exported as ["unreachable-ref"].
+*)
+Definition func_6 : module_func := {|
+  modfunc_type := 1%N;
+  modfunc_locals := nil;
+  modfunc_body :=
+    BI_unreachable ::
+    BI_select None ::
+    BI_ref_is_null ::
+    BI_drop ::
+    nil;
+|}.
+
+(** Exports of [module_]:
+    - ["select-trap-left"]: function [func_0]
+    - ["select-trap-right"]: function [func_1]
+    - ["select-unreached"]: function [func_2]
+    - ["select_unreached_result_1"]: function [func_3]
+    - ["select_unreached_result_2"]: function [func_4]
+    - ["unreachable-num"]: function [func_5]
+    - ["unreachable-ref"]: function [func_6]
+*)
+Definition module_ : module := {|
+  mod_types :=
+    Tf (T_num T_i32 :: nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (nil) ::
+    Tf (nil) (T_num T_i32 :: nil) ::
+    Tf (nil) (T_num T_i64 :: nil) ::
+    nil;
+  mod_funcs :=
+    func_0 ::
+    func_1 ::
+    func_2 ::
+    func_3 ::
+    func_4 ::
+    func_5 ::
+    func_6 ::
+    nil;
+  mod_tables :=
+    nil;
+  mod_mems :=
+    nil;
+  mod_globals :=
+    nil;
+  mod_elems :=
+    nil;
+  mod_datas :=
+    nil;
+  mod_start := None;
+  mod_imports :=
+    nil;
+  mod_exports :=
+    Me "select-trap-left" (MED_func 0%N) ::
+    Me "select-trap-right" (MED_func 1%N) ::
+    Me "select-unreached" (MED_func 2%N) ::
+    Me "select_unreached_result_1" (MED_func 3%N) ::
+    Me "select_unreached_result_2" (MED_func 4%N) ::
+    Me "unreachable-num" (MED_func 5%N) ::
+    Me "unreachable-ref" (MED_func 6%N) ::
+    nil;
+|}.
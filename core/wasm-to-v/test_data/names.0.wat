@@ -0,0 +1,24 @@
+(module $my-module
+  (import "env" "log" (func $host.log (param i32)))
+  (func $add (param $a i32) (param $b i32) (result i32)
+    (local $sum i32)
+    local.get $a
+    local.get $b
+    i32.add
+    local.tee $sum
+    call $host.log
+    block
+      local.get $sum
+      call $host.log
+    end
+    local.get $sum)
+  (func (@name "add") (param i32)
+    local.get 0
+    call $add_dup_target)
+  (func $add_dup_target (@name "nil") (param $x-value i32))
+  (func (@name "2nd-pass"))
+  (func (@name "my-module")
+    call 2)
+  (func
+    call $add_dup_target)
+  (export "add" (func $add)))
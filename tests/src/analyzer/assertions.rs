@@ -0,0 +1,99 @@
+//! Tests for assertion and assumption checks
+//!
+//! This module contains tests verifying:
+//! - Assertions on constant `true` conditions are reported as trivial
+//! - Assertions in unreachable code are reported as never checked
+//! - `assume` blocks containing an always-false assertion are reported as vacuous
+//! - Meaningful assertions and assumptions are not reported
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn assertion_warnings(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .filter(|warning| {
+            matches!(
+                warning,
+                AnalysisWarning::TrivialAssertion { .. }
+                    | AnalysisWarning::UnreachableAssertion { .. }
+                    | AnalysisWarning::VacuousAssumption { .. }
+            )
+        })
+        .map(AnalysisWarning::render_with_notes)
+        .collect()
+}
+
+#[test]
+fn reports_trivially_true_assertions() {
+    let source = r#"pub fn f() {
+    assert(true);
+    assert(1 < 2 || false);
+}"#;
+    assert_eq!(
+        assertion_warnings(source),
+        [
+            "2:5: assertion is always true",
+            "3:5: assertion is always true"
+        ]
+    );
+}
+
+#[test]
+fn reports_unreachable_assertions() {
+    let source = r#"pub fn f(x: i32) -> i32 {
+    return x;
+    assert(x > 0);
+}"#;
+    assert_eq!(
+        assertion_warnings(source),
+        ["3:5: assertion is unreachable and is never checked"]
+    );
+}
+
+#[test]
+fn reports_assertion_behind_constant_false_condition() {
+    let source = r#"pub fn f(x: i32) {
+    if false {
+        assert(x > 0);
+    }
+}"#;
+    assert_eq!(
+        assertion_warnings(source),
+        ["3:9: assertion is unreachable and is never checked"]
+    );
+}
+
+#[test]
+fn reports_vacuous_assumption() {
+    let source = r#"pub fn f() {
+    forall {
+        let x: i32 = @;
+        assume {
+            assert(false);
+        }
+        assert(x > 0);
+    }
+}"#;
+    assert_eq!(
+        assertion_warnings(source),
+        [
+            "4:9: `assume` block is always false, the code after it is vacuously verified (5:13: note: this assumption is always false)"
+        ]
+    );
+}
+
+#[test]
+fn meaningful_assertions_are_not_reported() {
+    let source = r#"pub fn f() {
+    forall {
+        let x: i32 = @;
+        let y: i32 = @;
+        assume {
+            assert(x < y);
+        }
+        assert(x <= y);
+    }
+}"#;
+    assert!(assertion_warnings(source).is_empty());
+}
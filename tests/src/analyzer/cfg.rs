@@ -0,0 +1,131 @@
+//! Tests for control flow graph construction
+//!
+//! This module contains tests verifying:
+//! - Straight-line bodies form a single block that falls through to the exit
+//! - `if` branches into both arms and joins afterwards
+//! - Loops jump back to their header and `break` leaves the loop
+//! - Statements after `return` are placed in unreachable blocks
+
+use crate::utils::build_cfg;
+use inference_analyzer::cfg::Terminator;
+use inference_ast::nodes::{BlockType, Statement};
+
+#[test]
+fn straight_line_body_is_one_block() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f() {
+    let a: i32 = 1;
+    let b: i32 = a;
+}"#,
+    );
+    let entry = cfg.block(cfg.entry());
+    assert_eq!(entry.statements.len(), 2);
+    assert!(matches!(entry.terminator, Terminator::Goto(target) if target == cfg.exit()));
+    assert_eq!(cfg.block_of(statements[1].id()), Some(cfg.entry()));
+    assert_eq!(cfg.predecessors(cfg.exit()), vec![cfg.entry()]);
+}
+
+#[test]
+fn if_branches_into_both_arms_and_joins() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f(x: i32) -> i32 {
+    if x > 0 {
+        let a: i32 = 1;
+    } else {
+        let b: i32 = 2;
+    }
+    return x;
+}"#,
+    );
+    let Terminator::Branch {
+        then_block,
+        else_block,
+        ..
+    } = cfg.block(cfg.entry()).terminator.clone()
+    else {
+        panic!("expected a branch out of the entry block");
+    };
+    assert_ne!(then_block, else_block);
+    let join = cfg.block_of(statements[1].id()).unwrap();
+    assert_eq!(cfg.successors(then_block), vec![join]);
+    assert_eq!(cfg.successors(else_block), vec![join]);
+    assert!(matches!(cfg.block(join).terminator, Terminator::Return(_)));
+}
+
+#[test]
+fn if_without_else_falls_through_to_join() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f(x: i32) -> i32 {
+    if x > 0 {
+        return 1;
+    }
+    return 0;
+}"#,
+    );
+    let join = cfg.block_of(statements[1].id()).unwrap();
+    let Terminator::Branch { else_block, .. } = cfg.block(cfg.entry()).terminator.clone() else {
+        panic!("expected a branch out of the entry block");
+    };
+    assert_eq!(else_block, join);
+}
+
+#[test]
+fn loop_body_jumps_back_to_header_and_break_exits() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f() -> i32 {
+    let i: i32 = 0;
+    loop {
+        break;
+    }
+    return i;
+}"#,
+    );
+    let header = cfg.block_of(statements[1].id()).unwrap();
+    let Statement::Loop(loop_stmt) = &statements[1] else {
+        panic!("expected a loop statement");
+    };
+    let BlockType::Block(body) = &loop_stmt.body else {
+        panic!("expected a plain loop body");
+    };
+    let body_block = cfg.block_of(body.statements[0].id()).unwrap();
+    assert_eq!(cfg.successors(header), vec![body_block]);
+    let after = cfg.block_of(statements[2].id()).unwrap();
+    assert!(matches!(
+        cfg.block(body_block).terminator,
+        Terminator::Break { target, .. } if target == after
+    ));
+    assert!(cfg.reachable()[after]);
+}
+
+#[test]
+fn conditional_loop_branches_on_condition() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f(n: i32) {
+    let i: i32 = 0;
+    loop i < n {
+        i = i + 1;
+    }
+}"#,
+    );
+    let header = cfg.block_of(statements[1].id()).unwrap();
+    let Terminator::Branch { then_block, .. } = cfg.block(header).terminator.clone() else {
+        panic!("expected the loop header to branch");
+    };
+    assert_eq!(cfg.successors(then_block), vec![header]);
+    assert_eq!(cfg.predecessors(header).len(), 2);
+}
+
+#[test]
+fn statements_after_return_are_unreachable() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f() -> i32 {
+    return 1;
+    let dead: i32 = 2;
+}"#,
+    );
+    let reachable = cfg.reachable();
+    let dead_block = cfg.block_of(statements[1].id()).unwrap();
+    assert!(reachable[cfg.entry()]);
+    assert!(!reachable[dead_block]);
+    assert!(reachable[cfg.exit()]);
+}
@@ -0,0 +1,170 @@
+//! Tests for the data-flow analysis framework
+//!
+//! This module contains tests verifying:
+//! - Forward analyses propagate states from the entry block and join at merges
+//! - Backward analyses propagate states from the exit block
+//! - Loops are iterated to a fixed point
+//! - Terminator transfer functions see branch conditions
+
+use crate::utils::build_cfg;
+use inference_analyzer::{
+    cfg::Terminator,
+    dataflow::{Analysis, Direction, solve},
+};
+use inference_ast::nodes::{Expression, Statement};
+use rustc_hash::FxHashSet;
+
+fn names(items: &[&str]) -> FxHashSet<String> {
+    items.iter().map(ToString::to_string).collect()
+}
+
+fn read_identifiers(expression: &Expression, out: &mut FxHashSet<String>) {
+    match expression {
+        Expression::Identifier(identifier) => {
+            out.insert(identifier.name.clone());
+        }
+        Expression::Binary(binary) => {
+            read_identifiers(&binary.left.borrow(), out);
+            read_identifiers(&binary.right.borrow(), out);
+        }
+        Expression::Parenthesized(paren) => read_identifiers(&paren.expression.borrow(), out),
+        _ => {}
+    }
+}
+
+/// Variables that may have been declared on some path.
+struct Declared;
+
+impl Analysis for Declared {
+    type Domain = FxHashSet<String>;
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom(&self) -> Self::Domain {
+        FxHashSet::default()
+    }
+
+    fn boundary(&self) -> Self::Domain {
+        FxHashSet::default()
+    }
+
+    fn transfer_statement(&self, state: &mut Self::Domain, statement: &Statement) {
+        if let Statement::VariableDefinition(var_def) = statement {
+            state.insert(var_def.name.name.clone());
+        }
+    }
+}
+
+/// Variables whose current value may be read later.
+struct Liveness;
+
+impl Analysis for Liveness {
+    type Domain = FxHashSet<String>;
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn bottom(&self) -> Self::Domain {
+        FxHashSet::default()
+    }
+
+    fn boundary(&self) -> Self::Domain {
+        FxHashSet::default()
+    }
+
+    fn transfer_statement(&self, state: &mut Self::Domain, statement: &Statement) {
+        match statement {
+            Statement::VariableDefinition(var_def) => {
+                state.remove(&var_def.name.name);
+                if let Some(value) = &var_def.value {
+                    read_identifiers(&value.borrow(), state);
+                }
+            }
+            Statement::Assign(assign) => {
+                if let Expression::Identifier(identifier) = &*assign.left.borrow() {
+                    state.remove(&identifier.name);
+                }
+                read_identifiers(&assign.right.borrow(), state);
+            }
+            _ => {}
+        }
+    }
+
+    fn transfer_terminator(&self, state: &mut Self::Domain, terminator: &Terminator) {
+        match terminator {
+            Terminator::Branch { condition, .. } => read_identifiers(condition, state),
+            Terminator::Return(ret) => read_identifiers(&ret.expression.borrow(), state),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn forward_analysis_joins_both_arms() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f(x: i32) -> i32 {
+    let a: i32 = 1;
+    if x > 0 {
+        let b: i32 = 2;
+    } else {
+        let c: i32 = 3;
+    }
+    return x;
+}"#,
+    );
+    let results = solve(&cfg, &Declared);
+    assert_eq!(results.entry_state(cfg.entry()), &names(&[]));
+    assert_eq!(results.exit_state(cfg.entry()), &names(&["a"]));
+    let join = cfg.block_of(statements[2].id()).unwrap();
+    assert_eq!(results.entry_state(join), &names(&["a", "b", "c"]));
+    assert_eq!(results.entry_state(cfg.exit()), &names(&["a", "b", "c"]));
+}
+
+#[test]
+fn backward_analysis_flows_from_exit() {
+    let (cfg, _) = build_cfg(
+        r#"fn f(x: i32) -> i32 {
+    let a: i32 = x;
+    let b: i32 = a + 1;
+    return b;
+}"#,
+    );
+    let results = solve(&cfg, &Liveness);
+    assert_eq!(results.entry_state(cfg.entry()), &names(&["x"]));
+    assert_eq!(results.exit_state(cfg.entry()), &names(&[]));
+    assert_eq!(results.entry_state(cfg.exit()), &names(&[]));
+}
+
+#[test]
+fn loops_reach_a_fixed_point() {
+    let (cfg, statements) = build_cfg(
+        r#"fn f(n: i32) -> i32 {
+    let i: i32 = 0;
+    let total: i32 = 0;
+    loop i < n {
+        total = total + i;
+        i = i + 1;
+    }
+    return total;
+}"#,
+    );
+    let results = solve(&cfg, &Liveness);
+    let header = cfg.block_of(statements[2].id()).unwrap();
+    assert_eq!(results.entry_state(header), &names(&["i", "n", "total"]));
+    assert_eq!(results.entry_state(cfg.entry()), &names(&["n"]));
+}
+
+#[test]
+fn terminators_are_transferred() {
+    let (cfg, _) = build_cfg(
+        r#"fn f(x: i32, y: i32) -> i32 {
+    if x > y {
+        return x;
+    }
+    return 0;
+}"#,
+    );
+    let results = solve(&cfg, &Liveness);
+    assert_eq!(results.entry_state(cfg.entry()), &names(&["x", "y"]));
+    let Terminator::Branch { then_block, .. } = &cfg.block(cfg.entry()).terminator else {
+        panic!("expected a branch out of the entry block");
+    };
+    assert_eq!(results.entry_state(*then_block), &names(&["x"]));
+}
@@ -0,0 +1,86 @@
+//! Tests for dead code detection
+//!
+//! This module contains tests verifying:
+//! - Private functions and types that are never referenced are reported
+//! - Public definitions are never reported
+//! - Self-references (recursion) do not keep a definition alive
+//! - References from signatures, struct literals and type annotations count
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn messages(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn reports_uncalled_private_function() {
+    let source = r#"fn helper() -> i32 { return 1; }
+pub fn f() -> i32 { return 0; }"#;
+    assert_eq!(messages(source), ["1:4: function `helper` is never used"]);
+}
+
+#[test]
+fn called_private_function_is_used() {
+    let source = r#"fn helper() -> i32 { return 1; }
+pub fn f() -> i32 { return helper(); }"#;
+    assert!(messages(source).is_empty());
+}
+
+#[test]
+fn recursion_does_not_keep_function_alive() {
+    let source = r#"fn countdown(n: i32) -> i32 {
+    if n == 0 { return 0; }
+    return countdown(n - 1);
+}"#;
+    assert_eq!(
+        messages(source),
+        ["1:4: function `countdown` is never used"]
+    );
+}
+
+#[test]
+fn public_definitions_are_not_reported() {
+    let source = r#"pub fn f() -> i32 { return 0; }
+pub struct Point { x: i32; }
+pub enum Color { Red, Green }"#;
+    assert!(messages(source).is_empty());
+}
+
+#[test]
+fn reports_unreferenced_private_types() {
+    let source = r#"struct Point { x: i32; }
+enum Color { Red, Green }
+pub fn f() -> i32 { return 0; }"#;
+    assert_eq!(
+        messages(source),
+        [
+            "1:8: struct `Point` is never used",
+            "2:6: enum `Color` is never used"
+        ]
+    );
+}
+
+#[test]
+fn types_used_in_signatures_and_literals_are_used() {
+    let source = r#"struct Point { x: i32; }
+struct Pair { a: i32; }
+fn first(p: Point) -> i32 { return p.x; }
+pub fn f(pair: Pair) -> i32 { return pair.a; }
+pub fn g() -> i32 { return first(Point { x: 1 }); }"#;
+    assert!(messages(source).is_empty());
+}
+
+#[test]
+fn warnings_are_sorted_by_location() {
+    let source = r#"fn b() -> i32 { return 1; }
+fn a(x: i32) -> i32 { return 2; }"#;
+    let warnings: Vec<&'static str> = analysis_warnings(source)
+        .iter()
+        .map(AnalysisWarning::code)
+        .collect();
+    assert_eq!(warnings, ["W0005", "W0005", "W0004"]);
+}
@@ -0,0 +1,8 @@
+mod assertions;
+mod cfg;
+mod dataflow;
+mod dead_code;
+mod purity;
+mod termination;
+mod unreachable;
+mod unused;
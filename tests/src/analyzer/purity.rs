@@ -0,0 +1,125 @@
+//! Tests for quantifier side-effect analysis
+//!
+//! This module contains tests verifying:
+//! - Writes to variables declared outside a `forall`/`exists` block are reported
+//! - Writes to variables declared inside the block are allowed
+//! - Calls to external functions and functions writing to `self` are reported,
+//!   including through intermediate callers
+//! - Calls to pure functions are allowed
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn quantifier_warnings(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .filter(|warning| {
+            matches!(
+                warning,
+                AnalysisWarning::QuantifierOuterWrite { .. }
+                    | AnalysisWarning::QuantifierImpureCall { .. }
+            )
+        })
+        .map(AnalysisWarning::render_with_notes)
+        .collect()
+}
+
+#[test]
+fn reports_write_to_outer_variable() {
+    let source = r#"pub fn f() -> i32 {
+    let total: i32 = 0;
+    forall {
+        let x: i32 = @;
+        total = x;
+    }
+    return total;
+}"#;
+    assert_eq!(
+        quantifier_warnings(source),
+        [
+            "5:9: `forall` block writes to `total`, which is declared outside it (3:5: note: `forall` block starts here)"
+        ]
+    );
+}
+
+#[test]
+fn reports_write_to_parameter_inside_exists() {
+    let source = r#"pub fn f(n: i32) -> i32 {
+    exists {
+        n = @;
+    }
+    return n;
+}"#;
+    assert_eq!(
+        quantifier_warnings(source),
+        [
+            "3:9: `exists` block writes to `n`, which is declared outside it (2:5: note: `exists` block starts here)"
+        ]
+    );
+}
+
+#[test]
+fn local_writes_are_allowed() {
+    let source = r#"pub fn f() {
+    forall {
+        let x: i32 = @;
+        let y: i32 = 0;
+        y = x;
+        assert(y == x);
+    }
+}"#;
+    assert!(quantifier_warnings(source).is_empty());
+}
+
+#[test]
+fn reports_calls_to_functions_writing_self_transitively() {
+    let source = r#"pub struct Counter {
+    value: i32;
+    pub fn bump(self) {
+        self.value = self.value + 1;
+    }
+}
+fn touch(c: Counter) {
+    c.bump();
+}
+pub fn f(c: Counter) {
+    forall {
+        touch(c);
+    }
+}"#;
+    assert_eq!(
+        quantifier_warnings(source),
+        [
+            "12:9: `forall` block calls impure function `touch` (8:5: note: calls impure function `bump`)"
+        ]
+    );
+}
+
+#[test]
+fn reports_calls_to_external_functions() {
+    let source = r#"external fn sample() -> i32;
+pub fn f() {
+    exists {
+        let x: i32 = sample();
+        assert(x > 0);
+    }
+}"#;
+    assert_eq!(
+        quantifier_warnings(source),
+        [
+            "4:22: `exists` block calls impure function `sample` (1:1: note: `sample` is an external function)"
+        ]
+    );
+}
+
+#[test]
+fn pure_calls_are_allowed() {
+    let source = r#"fn square(x: i32) -> i32 { return x * x; }
+pub fn f() {
+    forall {
+        let x: i32 = @;
+        assert(square(x) >= 0);
+    }
+}"#;
+    assert!(quantifier_warnings(source).is_empty());
+}
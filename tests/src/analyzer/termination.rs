@@ -0,0 +1,112 @@
+//! Tests for loop termination heuristics
+//!
+//! This module contains tests verifying:
+//! - Unconditional loops without a reachable `break` or `return` are reported
+//! - Conditional loops whose condition never changes are reported
+//! - Updates moving a variable away from its bound are reported
+//! - Loops with a reachable exit or a decreasing measure are not reported
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn termination_warnings(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .filter(|warning| matches!(warning, AnalysisWarning::PossiblyNonTerminatingLoop { .. }))
+        .map(AnalysisWarning::render_with_notes)
+        .collect()
+}
+
+#[test]
+fn reports_unconditional_loop_without_break() {
+    let source = r#"pub fn f() {
+    let x: i32 = 0;
+    loop {
+        x = x + 1;
+    }
+}"#;
+    assert_eq!(
+        termination_warnings(source),
+        ["3:5: loop may not terminate (3:5: note: this loop has no reachable `break` or `return`)"]
+    );
+}
+
+#[test]
+fn reports_unreachable_break() {
+    let source = r#"pub fn f() {
+    loop {
+        if false {
+            break;
+        }
+    }
+}"#;
+    assert_eq!(
+        termination_warnings(source),
+        ["2:5: loop may not terminate (2:5: note: this loop has no reachable `break` or `return`)"]
+    );
+}
+
+#[test]
+fn reports_condition_that_never_changes() {
+    let source = r#"pub fn f(n: i32) -> i32 {
+    let i: i32 = 0;
+    let total: i32 = 0;
+    loop i < n {
+        total = total + 1;
+    }
+    return total;
+}"#;
+    assert_eq!(
+        termination_warnings(source),
+        [
+            "4:5: loop may not terminate (4:10: note: no variable in this condition changes in the loop body)"
+        ]
+    );
+}
+
+#[test]
+fn reports_update_away_from_bound() {
+    let source = r#"pub fn f(n: i32) -> i32 {
+    let i: i32 = 0;
+    loop i < n {
+        i = i - 1;
+    }
+    return i;
+}"#;
+    assert_eq!(
+        termination_warnings(source),
+        ["3:5: loop may not terminate (4:9: note: `i` moves away from the loop bound here)"]
+    );
+}
+
+#[test]
+fn accepts_decreasing_measure() {
+    let source = r#"pub fn f(n: i32) -> i32 {
+    let i: i32 = 0;
+    loop i < n {
+        i = i + 1;
+    }
+    loop n > 0 {
+        n = n - 1;
+    }
+    return i;
+}"#;
+    assert!(termination_warnings(source).is_empty());
+}
+
+#[test]
+fn accepts_loops_with_reachable_exit() {
+    let source = r#"pub fn f(n: i32) -> i32 {
+    loop {
+        if n > 10 {
+            break;
+        }
+    }
+    loop true {
+        loop {
+            return n;
+        }
+    }
+}"#;
+    assert!(termination_warnings(source).is_empty());
+}
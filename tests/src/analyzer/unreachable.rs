@@ -0,0 +1,128 @@
+//! Tests for unreachable code detection
+//!
+//! This module contains tests verifying:
+//! - The first statement after `return` or `break` is reported once per run
+//! - Statements after a loop that never exits are reported
+//! - Arms guarded by constant conditions are reported with the condition as note
+//! - Reachable code, including code after loops with `break`, is not reported
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn unreachable(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .filter(|warning| matches!(warning, AnalysisWarning::UnreachableCode { .. }))
+        .map(AnalysisWarning::render_with_notes)
+        .collect()
+}
+
+#[test]
+fn reports_first_statement_after_return() {
+    let source = r#"pub fn f() -> i32 {
+    return 1;
+    let a: i32 = 2;
+    let b: i32 = 3;
+    return a + b;
+}"#;
+    assert_eq!(
+        unreachable(source),
+        ["3:5: unreachable statement (2:5: note: any code following this `return` is unreachable)"]
+    );
+}
+
+#[test]
+fn reports_statement_after_break() {
+    let source = r#"pub fn f() -> i32 {
+    loop {
+        break;
+        let a: i32 = 1;
+    }
+    return 0;
+}"#;
+    assert_eq!(
+        unreachable(source),
+        ["4:9: unreachable statement (3:9: note: any code following this `break` is unreachable)"]
+    );
+}
+
+#[test]
+fn reports_code_after_endless_loop() {
+    let source = r#"pub fn f() -> i32 {
+    loop {
+        let a: i32 = 1;
+    }
+    return 0;
+}"#;
+    assert_eq!(
+        unreachable(source),
+        ["5:5: unreachable statement (2:5: note: this loop never exits)"]
+    );
+}
+
+#[test]
+fn reports_arm_behind_constant_condition() {
+    let source = r#"pub fn f() -> i32 {
+    if false {
+        return 1;
+    }
+    if 1 < 2 {
+        return 2;
+    } else {
+        return 3;
+    }
+}"#;
+    assert_eq!(
+        unreachable(source),
+        [
+            "3:9: unreachable statement (2:8: note: this condition is always false)",
+            "8:9: unreachable statement (5:8: note: this condition is always true)"
+        ]
+    );
+}
+
+#[test]
+fn reports_code_after_if_where_every_arm_returns() {
+    let source = r#"pub fn f(x: i32) -> i32 {
+    if x > 0 {
+        return 1;
+    } else {
+        return 2;
+    }
+    return 3;
+}"#;
+    assert_eq!(
+        unreachable(source),
+        ["7:5: unreachable statement (2:5: note: this statement never completes)"]
+    );
+}
+
+#[test]
+fn reachable_code_is_not_reported() {
+    let source = r#"pub fn f(x: i32) -> i32 {
+    let i: i32 = 0;
+    loop true {
+        if i > x {
+            break;
+        }
+        i = i + 1;
+    }
+    if x > 0 && true {
+        return i;
+    }
+    return 0;
+}"#;
+    assert!(unreachable(source).is_empty());
+}
+
+#[test]
+fn unreachable_warning_has_stable_code() {
+    let source = r#"pub fn f() -> i32 {
+    return 1;
+    return 2;
+}"#;
+    let warnings = analysis_warnings(source);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].code(), "W0007");
+    assert_eq!(warnings[0].related_notes().len(), 1);
+}
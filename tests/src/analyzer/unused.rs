@@ -0,0 +1,93 @@
+//! Tests for unused variable and parameter detection
+//!
+//! This module contains tests verifying:
+//! - Unread `let` bindings and parameters are reported at their declaration
+//! - Reads in nested blocks, conditions and call arguments count as uses
+//! - Plain assignment does not count as a use
+//! - Names starting with `_` are exempt
+
+use crate::utils::analysis_warnings;
+use inference_analyzer::warnings::AnalysisWarning;
+
+fn messages(source: &str) -> Vec<String> {
+    analysis_warnings(source)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn reports_unused_variable() {
+    let source = r#"pub fn f() -> i32 {
+    let x: i32 = 1;
+    return 0;
+}"#;
+    assert_eq!(messages(source), ["2:9: unused variable `x`"]);
+}
+
+#[test]
+fn reports_unused_parameter() {
+    let source = r#"pub fn f(a: i32, b: i32) -> i32 { return a; }"#;
+    let warnings = analysis_warnings(source);
+    assert_eq!(warnings.len(), 1);
+    assert!(matches!(
+        &warnings[0],
+        AnalysisWarning::UnusedParameter { name, .. } if name == "b"
+    ));
+    assert_eq!(warnings[0].code(), "W0004");
+}
+
+#[test]
+fn reads_in_nested_positions_count_as_uses() {
+    let source = r#"fn g(v: i32) -> i32 { return v; }
+pub fn f(n: i32) -> i32 {
+    let limit: i32 = 10;
+    let arr: [i32; 2] = [n, 2];
+    if arr[0] < limit {
+        let doubled: i32 = g(n) * 2;
+        return doubled;
+    }
+    return 0;
+}"#;
+    assert!(messages(source).is_empty());
+}
+
+#[test]
+fn assignment_is_not_a_use() {
+    let source = r#"pub fn f() -> i32 {
+    let x: i32 = 1;
+    x = 2;
+    return 0;
+}"#;
+    assert_eq!(messages(source), ["2:9: unused variable `x`"]);
+}
+
+#[test]
+fn inner_binding_does_not_mark_outer_used() {
+    let source = r#"pub fn f() -> i32 {
+    let x: i32 = 1;
+    {
+        let x: i32 = 2;
+        return x;
+    }
+}"#;
+    assert_eq!(messages(source), ["2:9: unused variable `x`"]);
+}
+
+#[test]
+fn underscore_names_are_exempt() {
+    let source = r#"pub fn f(_a: i32) -> i32 {
+    let _b: i32 = 1;
+    return 0;
+}"#;
+    assert!(messages(source).is_empty());
+}
+
+#[test]
+fn method_bodies_are_checked() {
+    let source = r#"pub struct Counter {
+    value: i32;
+    pub fn get(self, unused: i32) -> i32 { return self.value; }
+}"#;
+    assert_eq!(messages(source), ["3:22: unused parameter `unused`"]);
+}
@@ -836,3 +836,21 @@ fn test_add_node_with_parent_creates_relationship() {
         "Root node should have no parent"
     );
 }
+
+#[test]
+fn test_node_count_includes_every_node() {
+    use std::rc::Rc;
+
+    let mut arena = Arena::default();
+    assert_eq!(arena.node_count(), 0);
+
+    let ident = Rc::new(Identifier::new(1, "x".to_string(), Location::default()));
+    arena.add_node(
+        AstNode::Expression(inference_ast::nodes::Expression::Identifier(ident)),
+        u32::MAX,
+    );
+    assert_eq!(arena.node_count(), 1);
+
+    let arena = build_ast("fn test() -> i32 { return 42; }".to_string());
+    assert!(arena.node_count() > arena.functions().len());
+}
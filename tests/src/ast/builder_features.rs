@@ -737,6 +737,19 @@ fn test_parse_external_function_basic() {
     }
 }
 
+#[test]
+fn test_parse_external_function_arguments() {
+    let source = r#"external fn log_pair(a: i32, b: i64) -> bool;"#;
+    let arena = build_ast(source.to_string());
+    let source_files = arena.source_files();
+
+    let ext_funcs = source_files[0].external_function_definitions();
+    assert_eq!(ext_funcs.len(), 1);
+    let arguments = ext_funcs[0].arguments.as_ref().expect("Should have arguments");
+    assert_eq!(arguments.len(), 2);
+    assert!(ext_funcs[0].returns.is_some(), "Should have return type");
+}
+
 // --- Visibility Tests ---
 
 #[test]
@@ -1241,6 +1254,27 @@ fn test_parse_complex_struct_expression() {
     assert_eq!(struct_exprs.len(), 1, "Should find 1 struct expression");
 }
 
+#[test]
+fn test_parse_struct_expression_fields() {
+    let source = r#"fn test() { let point: Point = Point { x: 10, y: [1, 2] }; }"#;
+    let arena = build_ast(source.to_string());
+    let struct_exprs =
+        arena.filter_nodes(|node| matches!(node, AstNode::Expression(Expression::Struct(_))));
+    let AstNode::Expression(Expression::Struct(struct_expr)) = &struct_exprs[0] else {
+        panic!("Expected struct expression");
+    };
+    let fields = struct_expr
+        .fields
+        .as_ref()
+        .expect("Struct expression should have fields");
+    let field_names: Vec<&str> = fields.iter().map(|(name, _)| name.name.as_str()).collect();
+    assert_eq!(field_names, vec!["x", "y"]);
+    assert!(matches!(
+        &*fields[1].1.borrow(),
+        Expression::Literal(Literal::Array(_))
+    ));
+}
+
 #[test]
 fn test_parse_nested_struct_expression() {
     let source = r#"fn test() {
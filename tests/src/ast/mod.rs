@@ -2,4 +2,5 @@ mod arena;
 mod builder;
 mod builder_features;
 mod nodes;
+mod parser_context;
 mod primitive_type;
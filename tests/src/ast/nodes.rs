@@ -119,3 +119,12 @@ fn test_location_display_zero_values() {
     let loc = Location::new(0, 0, 0, 0, 0, 0);
     assert_eq!(format!("{loc}"), "0:0");
 }
+
+#[test]
+fn test_source_file_serializes_to_json() {
+    let arena = crate::utils::build_ast("fn main() -> i32 { return 42; }".to_string());
+    let json = serde_json::to_value(arena.source_files()).unwrap();
+    let function = &json[0]["definitions"][0]["Function"];
+    assert_eq!(function["name"]["name"], "main");
+    assert_eq!(function["location"]["start_line"], 1);
+}
@@ -0,0 +1,98 @@
+use inference_ast::errors::SyntaxErrors;
+use inference_ast::parser_context::{ParserContext, project_files};
+use std::fs;
+use std::path::Path;
+
+// Tests for multi-file parsing with `ParserContext` and project file discovery.
+
+fn write_project(dir: &Path, files: &[(&str, &str)]) {
+    for (path, source) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+}
+
+#[test]
+fn test_project_files_lists_root_first() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(
+        dir.path(),
+        &[
+            ("src/main.inf", "fn main() {}"),
+            ("src/b.inf", "fn b() {}"),
+            ("src/nested/a.inf", "fn a() {}"),
+            ("src/notes.txt", "not a source file"),
+        ],
+    );
+
+    let files = project_files(dir.path()).unwrap();
+    assert_eq!(
+        files,
+        vec![
+            dir.path().join("src/main.inf"),
+            dir.path().join("src/b.inf"),
+            dir.path().join("src/nested/a.inf"),
+        ]
+    );
+    assert_eq!(
+        project_files(&dir.path().join("Inference.toml")).unwrap(),
+        files
+    );
+}
+
+#[test]
+fn test_project_files_requires_root_file() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(dir.path(), &[("src/helper.inf", "fn helper() {}")]);
+
+    assert!(project_files(dir.path()).is_err());
+}
+
+#[test]
+fn test_parse_all_builds_one_arena() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(
+        dir.path(),
+        &[
+            ("src/lib.inf", "pub fn answer() -> i32 { return helper(); }"),
+            ("src/helper.inf", "fn helper() -> i32 { return 42; }"),
+        ],
+    );
+    let files = project_files(dir.path()).unwrap();
+
+    let mut context = ParserContext::new(files[0].clone());
+    context.push_file(0, files[1].clone());
+    let arena = context.parse_all().unwrap();
+
+    let source_files = arena.source_files();
+    assert_eq!(source_files.len(), 2);
+    assert_eq!(source_files[0].function_definitions()[0].name(), "answer");
+    assert_eq!(source_files[1].function_definitions()[0].name(), "helper");
+}
+
+#[test]
+fn test_parse_all_attributes_syntax_errors_to_files() {
+    let dir = tempfile::tempdir().unwrap();
+    write_project(
+        dir.path(),
+        &[
+            ("src/main.inf", "fn main() {}"),
+            ("src/broken.inf", "fn broken() {}\n}}} garbage fn (\n"),
+        ],
+    );
+    let files = project_files(dir.path()).unwrap();
+
+    let mut context = ParserContext::new(files[0].clone());
+    context.push_file(0, files[1].clone());
+    let error = context.parse_all().err().expect("parsing should fail");
+
+    let errors = error.downcast_ref::<SyntaxErrors>().unwrap();
+    assert!(!errors.errors().is_empty());
+    assert!(
+        errors
+            .errors()
+            .iter()
+            .all(|error| error.file.as_deref() == Some(files[1].as_path()))
+    );
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod arithmetic_codegen_tests {
+    use std::cmp::Ordering::{self, Equal, Greater, Less};
+
+    use crate::utils::{compile_matrix, get_test_file_path, instantiate};
+    use wasmtime::{Instance, Store, WasmParams, WasmResults};
+
+    /// Operand values of the conformance matrix: the boundaries of both 64-bit types,
+    /// shift amounts around the width and a value with every byte set differently.
+    const VALUES: [u64; 12] = [
+        0,
+        1,
+        2,
+        7,
+        63,
+        64,
+        65,
+        u64::MAX,
+        u64::MAX - 6,
+        i64::MAX as u64,
+        i64::MIN as u64,
+        0x1234_5678_9abc_def0,
+    ];
+
+    const OPERATORS: [&str; 17] = [
+        "add", "sub", "mul", "div", "rem", "pow", "and", "or", "xor", "shl", "shr", "eq", "ne",
+        "lt", "le", "gt", "ge",
+    ];
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "arithmetic");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Calls `function`, returning `None` if it traps.
+    fn call<P: WasmParams, R: WasmResults>(
+        store: &mut Store<Vec<i32>>,
+        instance: &Instance,
+        config: &str,
+        function: &str,
+        params: P,
+    ) -> Option<R> {
+        instance
+            .get_typed_func::<P, R>(&mut *store, function)
+            .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e))
+            .call(&mut *store, params)
+            .ok()
+    }
+
+    /// Reference semantics of a binary operator on two 64-bit operands, given as their
+    /// bits. Returns the bits of the result, `0` or `1` for comparisons, or `None` if the
+    /// operation traps.
+    fn oracle(operator: &str, signed: bool, a: u64, b: u64) -> Option<u64> {
+        let (sa, sb) = (a as i64, b as i64);
+        let compare = |ordering: Ordering, accepted: &[Ordering]| {
+            Some(u64::from(accepted.contains(&ordering)))
+        };
+        let ordering = if signed { sa.cmp(&sb) } else { a.cmp(&b) };
+        match operator {
+            "add" => Some(a.wrapping_add(b)),
+            "sub" => Some(a.wrapping_sub(b)),
+            "mul" => Some(a.wrapping_mul(b)),
+            "div" if signed => sa.checked_div(sb).map(|q| q as u64),
+            "div" => a.checked_div(b),
+            "rem" if signed => (sb != 0).then(|| sa.wrapping_rem(sb) as u64),
+            "rem" => a.checked_rem(b),
+            "pow" => Some(oracle_pow(a, b)),
+            "and" => Some(a & b),
+            "or" => Some(a | b),
+            "xor" => Some(a ^ b),
+            "shl" => Some(a.wrapping_shl(b as u32)),
+            "shr" if signed => Some(sa.wrapping_shr(b as u32) as u64),
+            "shr" => Some(a.wrapping_shr(b as u32)),
+            "eq" => compare(ordering, &[Equal]),
+            "ne" => compare(ordering, &[Less, Greater]),
+            "lt" => compare(ordering, &[Less]),
+            "le" => compare(ordering, &[Less, Equal]),
+            "gt" => compare(ordering, &[Greater]),
+            "ge" => compare(ordering, &[Greater, Equal]),
+            _ => unreachable!("unknown operator {operator}"),
+        }
+    }
+
+    /// `base ** exponent` modulo 2^64 with an unsigned exponent, by repeated
+    /// multiplication for small exponents and by the period of the powers otherwise.
+    fn oracle_pow(base: u64, exponent: u64) -> u64 {
+        if exponent <= 130 {
+            return (0..exponent).fold(1u64, |result, _| result.wrapping_mul(base));
+        }
+        if base.is_multiple_of(2) {
+            // An even base has at least one factor 2, so 2^64 divides any power above 63.
+            return 0;
+        }
+        // The odd residues modulo 2^64 form a group of order 2^63.
+        let reduced = exponent % (1 << 63);
+        let mut result = 1u64;
+        let mut factor = base;
+        for bit in 0..63 {
+            if reduced >> bit & 1 != 0 {
+                result = result.wrapping_mul(factor);
+            }
+            factor = factor.wrapping_mul(factor);
+        }
+        result
+    }
+
+    #[test]
+    fn binary_operators_match_the_oracle() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            for operator in OPERATORS {
+                let is_comparison = matches!(operator, "eq" | "ne" | "lt" | "le" | "gt" | "ge");
+                for (ty, signed) in [("i64", true), ("u64", false)] {
+                    let function = format!("{operator}_{ty}");
+                    for a in VALUES {
+                        for b in VALUES {
+                            let params = (a as i64, b as i64);
+                            let actual = if is_comparison {
+                                call::<_, i32>(&mut store, &instance, &config, &function, params)
+                                    .map(|result| u64::from(result != 0))
+                            } else {
+                                call::<_, i64>(&mut store, &instance, &config, &function, params)
+                                    .map(|result| result as u64)
+                            };
+                            assert_eq!(
+                                actual,
+                                oracle(operator, signed, a, b),
+                                "{config}: {function}({a:#x}, {b:#x})"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unary_operators_match_the_oracle() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            for a in VALUES {
+                let a = a as i64;
+                let neg: Option<i64> = call(&mut store, &instance, &config, "neg_i64", a);
+                assert_eq!(neg, Some(a.wrapping_neg()), "{config}: neg_i64({a})");
+                let not: Option<i64> = call(&mut store, &instance, &config, "not_i64", a);
+                assert_eq!(not, Some(!a), "{config}: not_i64({a})");
+                let not: Option<i64> = call(&mut store, &instance, &config, "not_u64", a);
+                assert_eq!(not, Some(!a), "{config}: not_u64({a})");
+            }
+        }
+    }
+
+    #[test]
+    fn constants_mix_with_runtime_operands() {
+        const SCALE: i64 = -3;
+        const SHIFT: i64 = 65;
+        const DIVISOR: u64 = 10;
+        const MASK: u64 = 4_294_967_296;
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            for a in VALUES {
+                let signed = a as i64;
+                let expected = signed
+                    .wrapping_mul(SCALE)
+                    .wrapping_add(signed.wrapping_shl(SHIFT as u32))
+                    .wrapping_sub(SCALE.pow(3));
+                let mixed: Option<i64> = call(&mut store, &instance, &config, "mixed_i64", signed);
+                assert_eq!(mixed, Some(expected), "{config}: mixed_i64({signed})");
+
+                let expected = ((a / DIVISOR) % DIVISOR) ^ MASK.wrapping_shr(a as u32);
+                let mixed: Option<i64> = call(&mut store, &instance, &config, "mixed_u64", signed);
+                assert_eq!(
+                    mixed.map(|result| result as u64),
+                    Some(expected),
+                    "{config}: mixed_u64({a})"
+                );
+
+                let expected = signed.wrapping_neg() < SCALE * SHIFT;
+                let compared: Option<i32> =
+                    call(&mut store, &instance, &config, "mixed_compare", signed);
+                assert_eq!(
+                    compared.map(|result| result != 0),
+                    Some(expected),
+                    "{config}: mixed_compare({signed})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oracle_pow_reduces_large_exponents() {
+        assert_eq!(oracle_pow(3, 4), 81);
+        assert_eq!(oracle_pow(2, 64), 0);
+        assert_eq!(oracle_pow(u64::MAX, u64::MAX), u64::MAX);
+        assert_eq!(oracle_pow(3, (1 << 63) + 4), 81);
+        assert_eq!(oracle_pow(3, u64::MAX).wrapping_mul(3), 1);
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod arrays_codegen_tests {
+    use crate::utils::{compile_matrix, get_test_file_path, instantiate};
+    use inference_wasm_codegen::BoundsChecks;
+    use wasmtime::{Instance, Store, WasmResults};
+
+    /// Compiles `arrays.inf` with `bounds_checks` for every linker, unoptimized and
+    /// optimized, and instantiates each module.
+    fn instances(bounds_checks: BoundsChecks) -> Vec<(String, Store<Vec<i32>>, Instance)> {
+        let test_file_path = get_test_file_path(module_path!(), "arrays");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        compile_matrix(&source_code, |options| {
+            options.bounds_checks = bounds_checks;
+        })
+        .into_iter()
+        .map(|(config, wasm_bytes)| {
+            let (store, instance) = instantiate(&wasm_bytes, &config);
+            (config, store, instance)
+        })
+        .collect()
+    }
+
+    fn assert_returns<R: WasmResults + PartialEq + std::fmt::Debug>(function: &str, expected: R) {
+        for (config, mut store, instance) in instances(BoundsChecks::Trap) {
+            let func = instance
+                .get_typed_func::<(), R>(&mut store, function)
+                .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e));
+            let result = func
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e));
+            assert_eq!(
+                result, expected,
+                "{config}: unexpected result of '{function}'"
+            );
+        }
+    }
+
+    #[test]
+    fn array_literal_element_read() {
+        assert_returns("literal_element", 30i32);
+    }
+
+    #[test]
+    fn array_element_write() {
+        assert_returns("element_write", 25i32);
+    }
+
+    #[test]
+    fn array_passed_by_pointer() {
+        assert_returns("pass_by_pointer", 10i32);
+    }
+
+    #[test]
+    fn array_argument_is_copied() {
+        assert_returns("callee_gets_copy", 10i32);
+    }
+
+    #[test]
+    fn array_field_of_struct() {
+        assert_returns("array_in_struct", 5i32);
+    }
+
+    #[test]
+    fn out_of_bounds_index_traps() {
+        for (config, mut store, instance) in instances(BoundsChecks::Trap) {
+            let index = instance
+                .get_typed_func::<i32, i32>(&mut store, "index")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'index': {}", e));
+            assert_eq!(index.call(&mut store, 1).unwrap(), 20, "{config}");
+            for out_of_bounds in [3, -1, i32::MAX] {
+                let trap = index
+                    .call(&mut store, out_of_bounds)
+                    .expect_err("out-of-bounds index should trap")
+                    .downcast::<wasmtime::Trap>()
+                    .unwrap_or_else(|e| panic!("{config}: expected a trap, got {e}"));
+                assert_eq!(trap, wasmtime::Trap::UnreachableCodeReached, "{config}");
+            }
+        }
+    }
+
+    #[test]
+    fn unchecked_indexing_does_not_trap() {
+        for (config, mut store, instance) in instances(BoundsChecks::Unchecked) {
+            let index = instance
+                .get_typed_func::<i32, i32>(&mut store, "index")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'index': {}", e));
+            assert_eq!(index.call(&mut store, 2).unwrap(), 30, "{config}");
+            index
+                .call(&mut store, 3)
+                .unwrap_or_else(|e| panic!("{config}: unchecked index should not trap: {e}"));
+        }
+    }
+}
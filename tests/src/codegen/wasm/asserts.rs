@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod asserts_codegen_tests {
+    use std::path::PathBuf;
+
+    use crate::utils::{get_test_file_path, wasm_codegen_with_options};
+    use inference_wasm_codegen::{AssertStrategy, CodegenOptions, ImportName, Linker};
+    use wasmtime::{Caller, Engine, Extern, Instance, Module, Store, WasmParams, WasmResults};
+
+    /// Calls to the `abort` host function, as (file, line).
+    type Aborts = Vec<(String, i32)>;
+
+    fn compile(linker: Linker, assert_strategy: AssertStrategy) -> Vec<u8> {
+        let test_file_path = get_test_file_path(module_path!(), "asserts");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let options = CodegenOptions {
+            linker,
+            assert_strategy,
+            source_path: Some(PathBuf::from("src/asserts.inf")),
+            ..CodegenOptions::default()
+        };
+        let wasm_bytes = wasm_codegen_with_options(&source_code, &options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    fn env_abort() -> ImportName {
+        ImportName {
+            module: "env".to_string(),
+            name: "abort".to_string(),
+        }
+    }
+
+    /// Instantiates `wasm_bytes` with an `abort` host function imported as `module.name`
+    /// that records its arguments.
+    fn instantiate(wasm_bytes: &[u8], abort: &ImportName) -> (Store<Aborts>, Instance) {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm_bytes)
+            .unwrap_or_else(|e| panic!("Failed to create Wasm module: {}", e));
+        let mut store = Store::new(&engine, Vec::new());
+        let mut wasm_linker = wasmtime::Linker::new(&engine);
+        wasm_linker
+            .func_wrap(
+                &abort.module,
+                &abort.name,
+                |mut caller: Caller<'_, Aborts>, file: i32, line: i32| {
+                    let Some(Extern::Memory(memory)) = caller.get_export("memory") else {
+                        panic!("module does not export its memory");
+                    };
+                    let data = memory.data(&caller);
+                    let start = usize::try_from(file).unwrap();
+                    let end = start + data[start..].iter().position(|&b| b == 0).unwrap();
+                    let file = String::from_utf8(data[start..end].to_vec()).unwrap();
+                    caller.data_mut().push((file, line));
+                },
+            )
+            .unwrap_or_else(|e| panic!("Failed to define abort: {}", e));
+        let instance = wasm_linker
+            .instantiate(&mut store, &module)
+            .unwrap_or_else(|e| panic!("Failed to instantiate Wasm module: {}", e));
+        (store, instance)
+    }
+
+    fn call<P: WasmParams, R: WasmResults>(
+        store: &mut Store<Aborts>,
+        instance: &Instance,
+        function: &str,
+        params: P,
+    ) -> wasmtime::Result<R> {
+        instance
+            .get_typed_func::<P, R>(&mut *store, function)
+            .unwrap_or_else(|e| panic!("Failed to get '{function}': {}", e))
+            .call(&mut *store, params)
+    }
+
+    #[test]
+    fn trap_strategy_traps_on_failure() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(linker, AssertStrategy::Trap);
+            let (mut store, instance) = instantiate(&wasm_bytes, &env_abort());
+            assert_eq!(
+                call::<i32, i32>(&mut store, &instance, "positive", 5).unwrap(),
+                5,
+                "{linker:?}"
+            );
+            assert!(
+                call::<i32, i32>(&mut store, &instance, "positive", 0).is_err(),
+                "{linker:?}: a failing assert must trap"
+            );
+            assert!(
+                store.data().is_empty(),
+                "{linker:?}: abort must not be called"
+            );
+        }
+    }
+
+    #[test]
+    fn abort_strategy_reports_file_and_line() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(linker, AssertStrategy::abort());
+            let (mut store, instance) = instantiate(&wasm_bytes, &env_abort());
+            assert_eq!(
+                call::<i32, i32>(&mut store, &instance, "positive", 3).unwrap(),
+                3,
+                "{linker:?}"
+            );
+            assert!(
+                store.data().is_empty(),
+                "{linker:?}: abort called for a passing assert"
+            );
+            assert!(
+                call::<i32, i32>(&mut store, &instance, "positive", -1).is_err(),
+                "{linker:?}: execution must trap after abort returns"
+            );
+            assert!(call::<i32, ()>(&mut store, &instance, "exactly_one", 2).is_err());
+            assert_eq!(
+                store.data(),
+                &[
+                    ("src/asserts.inf".to_string(), 2),
+                    ("src/asserts.inf".to_string(), 12)
+                ],
+                "{linker:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn abort_import_name_is_configurable() {
+        let import = ImportName {
+            module: "host".to_string(),
+            name: "assert_failed".to_string(),
+        };
+        let wasm_bytes = compile(Linker::RustLld, AssertStrategy::Abort(import.clone()));
+        let (mut store, instance) = instantiate(&wasm_bytes, &import);
+        assert!(call::<i32, i32>(&mut store, &instance, "small", 10).is_err());
+        assert_eq!(store.data(), &[("src/asserts.inf".to_string(), 7)]);
+    }
+
+    #[test]
+    fn result_code_strategy_returns_the_code() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(linker, AssertStrategy::ResultCode(-7));
+            let (mut store, instance) = instantiate(&wasm_bytes, &env_abort());
+            assert_eq!(
+                call::<i32, i32>(&mut store, &instance, "positive", 4).unwrap(),
+                4,
+                "{linker:?}"
+            );
+            assert_eq!(
+                call::<i32, i32>(&mut store, &instance, "positive", 0).unwrap(),
+                -7,
+                "{linker:?}"
+            );
+            // `bool` results keep the lowest bit of the code.
+            assert_eq!(
+                call::<i32, i32>(&mut store, &instance, "small", 12).unwrap(),
+                1,
+                "{linker:?}"
+            );
+            call::<i32, ()>(&mut store, &instance, "exactly_one", 0)
+                .unwrap_or_else(|e| panic!("{linker:?}: a failing assert must not trap: {e}"));
+        }
+    }
+}
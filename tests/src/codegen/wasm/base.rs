@@ -2,7 +2,9 @@
 mod base_codegen_tests {
     use crate::utils::{
         assert_wasms_modules_equivalence, get_test_file_path, get_test_wasm_path, wasm_codegen,
+        wasm_codegen_with_options,
     };
+    use inference_wasm_codegen::CodegenOptions;
 
     #[test]
     fn trivial_test() {
@@ -92,4 +94,186 @@ mod base_codegen_tests {
         //     .unwrap_or_else(|e| panic!("Failed to write actual-nondet.wasm: {}", e));
         assert_wasms_modules_equivalence(&expected, &actual);
     }
+
+    fn builtin_linker_options() -> CodegenOptions {
+        CodegenOptions {
+            linker: inference_wasm_codegen::Linker::Builtin,
+            ..CodegenOptions::default()
+        }
+    }
+
+    fn export_names(wasm: &[u8]) -> Vec<String> {
+        let mut names = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm) {
+            if let inf_wasmparser::Payload::ExportSection(reader) = payload.unwrap() {
+                for export in reader {
+                    names.push(export.unwrap().name.to_string());
+                }
+            }
+        }
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn builtin_linker_trivial_test_execution() {
+        use wasmtime::{Engine, Instance, Module, Store, TypedFunc};
+
+        let test_name = "trivial";
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let wasm_bytes = wasm_codegen_with_options(&source_code, &builtin_linker_options());
+
+        let engine = Engine::default();
+        let module = Module::new(&engine, &wasm_bytes)
+            .unwrap_or_else(|e| panic!("Failed to create Wasm module: {}", e));
+        let mut store = Store::new(&engine, ());
+        let instance = Instance::new(&mut store, &module, &[])
+            .unwrap_or_else(|e| panic!("Failed to instantiate Wasm module: {}", e));
+
+        let hello_world_func: TypedFunc<(), i32> = instance
+            .get_typed_func(&mut store, "hello_world")
+            .unwrap_or_else(|e| panic!("Failed to get 'hello_world' function: {}", e));
+        let result = hello_world_func
+            .call(&mut store, ())
+            .unwrap_or_else(|e| panic!("Failed to execute 'hello_world' function: {}", e));
+
+        assert_eq!(result, 42, "Expected 'hello_world' function to return 42");
+    }
+
+    #[test]
+    fn builtin_linker_nondet_test() {
+        let test_name = "nondet";
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let actual = wasm_codegen_with_options(&source_code, &builtin_linker_options());
+        inf_wasmparser::validate(&actual)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        let expected = get_test_wasm_path(module_path!(), test_name);
+        let expected = std::fs::read(&expected)
+            .unwrap_or_else(|_| panic!("Failed to read expected wasm file for test: {test_name}"));
+        assert_eq!(export_names(&expected), export_names(&actual));
+    }
+
+    fn custom_section_names(wasm: &[u8]) -> Vec<String> {
+        let mut names = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm) {
+            if let inf_wasmparser::Payload::CustomSection(reader) = payload.unwrap() {
+                names.push(reader.name().to_string());
+            }
+        }
+        names
+    }
+
+    fn name_section_function_names(wasm: &[u8]) -> Vec<String> {
+        let mut names = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm) {
+            if let inf_wasmparser::Payload::CustomSection(reader) = payload.unwrap()
+                && let inf_wasmparser::KnownCustom::Name(name_reader) = reader.as_known()
+            {
+                for subsection in name_reader {
+                    if let inf_wasmparser::Name::Function(map) = subsection.unwrap() {
+                        for naming in map {
+                            names.push(naming.unwrap().name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn debug_info_emits_line_table_and_names() {
+        let test_name = "trivial";
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        for linker in [
+            inference_wasm_codegen::Linker::RustLld,
+            inference_wasm_codegen::Linker::Builtin,
+        ] {
+            let options = CodegenOptions {
+                opt_level: 0,
+                debug_info: true,
+                linker,
+                ..CodegenOptions::default()
+            };
+            let wasm_bytes = wasm_codegen_with_options(&source_code, &options);
+            inf_wasmparser::validate(&wasm_bytes)
+                .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+            let sections = custom_section_names(&wasm_bytes);
+            for section in [".debug_info", ".debug_line", "name"] {
+                assert!(
+                    sections.iter().any(|name| name == section),
+                    "{linker:?}: missing `{section}` section in {sections:?}"
+                );
+            }
+            assert!(
+                name_section_function_names(&wasm_bytes).contains(&"hello_world".to_string()),
+                "{linker:?}: `hello_world` missing from the name section"
+            );
+        }
+    }
+
+    #[test]
+    fn debug_info_disabled_strips_debug_sections() {
+        let test_name = "trivial";
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let wasm_bytes = wasm_codegen_with_options(&source_code, &builtin_linker_options());
+        let sections = custom_section_names(&wasm_bytes);
+        assert!(
+            !sections.iter().any(|name| name.starts_with(".debug_")),
+            "unexpected debug sections in {sections:?}"
+        );
+    }
+
+    #[test]
+    fn llvm_ir_defines_functions() {
+        let arena = crate::utils::build_ast("pub fn answer() -> i32 { return 42; }".to_string());
+        let typed_context = inference_type_checker::TypeCheckerBuilder::build_typed_context(arena)
+            .unwrap()
+            .typed_context();
+        let ir = inference_wasm_codegen::llvm_ir(&typed_context, &CodegenOptions::default())
+            .expect("LLVM IR should be printed");
+        assert!(
+            ir.contains("define"),
+            "missing function definition in:\n{ir}"
+        );
+        assert!(ir.contains("answer"), "missing `answer` in:\n{ir}");
+    }
+
+    #[test]
+    fn project_files_compile_to_one_module() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(
+            src.join("main.inf"),
+            "pub fn main() -> i32 { return helper(2); }",
+        )
+        .unwrap();
+        std::fs::write(
+            src.join("helper.inf"),
+            "fn helper(x: i32) -> i32 { return x + x; }",
+        )
+        .unwrap();
+
+        let files = inference::project_files(dir.path()).unwrap();
+        let arena = inference::parse_project(&files).unwrap();
+        let typed_context = inference::type_check(arena).unwrap();
+        let wasm_bytes = inference::codegen(&typed_context, &CodegenOptions::default()).unwrap();
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+
+        let options = CodegenOptions {
+            debug_info: true,
+            ..CodegenOptions::default()
+        };
+        assert!(inference::codegen(&typed_context, &options).is_err());
+    }
 }
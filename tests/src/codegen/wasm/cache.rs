@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod cache_codegen_tests {
+    use std::path::Path;
+
+    use crate::utils::{get_test_file_path, instantiate, wasm_codegen_with_options};
+    use inference_wasm_codegen::{CodegenOptions, Linker};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "cache");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    fn options(cache_dir: Option<&Path>) -> CodegenOptions {
+        CodegenOptions {
+            cache_dir: cache_dir.map(Path::to_path_buf),
+            ..CodegenOptions::default()
+        }
+    }
+
+    fn compile(source_code: &str, options: &CodegenOptions) -> Vec<u8> {
+        let wasm_bytes = wasm_codegen_with_options(source_code, options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    /// Returns the sorted names of the files in the cache directory.
+    fn cached_objects(cache_dir: &Path) -> Vec<String> {
+        let mut objects = std::fs::read_dir(cache_dir)
+            .unwrap_or_else(|e| panic!("Failed to read cache directory: {}", e))
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        objects.sort();
+        objects
+    }
+
+    fn call(wasm_bytes: &[u8], function: &str) -> i32 {
+        let (mut store, instance) = instantiate(wasm_bytes, "LLVM");
+        instance
+            .get_typed_func::<(), i32>(&mut store, function)
+            .unwrap_or_else(|e| panic!("Failed to get '{function}': {}", e))
+            .call(&mut store, ())
+            .unwrap_or_else(|e| panic!("Failed to execute '{function}': {}", e))
+    }
+
+    #[test]
+    fn cached_build_matches_uncached_build() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let source_code = source_code();
+        let cold = compile(&source_code, &options(Some(cache_dir.path())));
+        assert_eq!(cached_objects(cache_dir.path()).len(), 3);
+        let warm = compile(&source_code, &options(Some(cache_dir.path())));
+        assert_eq!(warm, cold, "reusing the cache changed the module");
+        for wasm_bytes in [&cold, &compile(&source_code, &options(None))] {
+            assert_eq!(call(wasm_bytes, "one"), 1);
+            assert_eq!(call(wasm_bytes, "call_seven"), 7);
+        }
+    }
+
+    #[test]
+    fn only_changed_functions_are_recompiled() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let source_code = source_code();
+        compile(&source_code, &options(Some(cache_dir.path())));
+        let before = cached_objects(cache_dir.path());
+
+        let edited = source_code.replace("return 7;", "return 8;");
+        let wasm_bytes = compile(&edited, &options(Some(cache_dir.path())));
+        let after = cached_objects(cache_dir.path());
+        let added = after
+            .iter()
+            .filter(|object| !before.contains(object))
+            .count();
+        assert_eq!(added, 1, "only `seven` should be recompiled");
+        assert_eq!(call(&wasm_bytes, "seven"), 8);
+        assert_eq!(call(&wasm_bytes, "call_seven"), 8);
+    }
+
+    #[test]
+    fn options_are_part_of_the_cache_key() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let source_code = source_code();
+        compile(&source_code, &options(Some(cache_dir.path())));
+        let unoptimized = CodegenOptions {
+            opt_level: 0,
+            ..options(Some(cache_dir.path()))
+        };
+        compile(&source_code, &unoptimized);
+        assert_eq!(cached_objects(cache_dir.path()).len(), 6);
+    }
+
+    #[test]
+    fn builtin_linker_does_not_use_the_cache() {
+        let cache_dir = tempfile::tempdir().unwrap();
+        let builtin = CodegenOptions {
+            linker: Linker::Builtin,
+            ..options(Some(cache_dir.path()))
+        };
+        compile(&source_code(), &builtin);
+        assert!(cached_objects(cache_dir.path()).is_empty());
+    }
+}
@@ -0,0 +1,276 @@
+#[cfg(test)]
+mod encoder_codegen_tests {
+    use crate::utils::{
+        get_test_data_path, get_test_file_path, instantiate, try_wasm_codegen_with_options,
+        wasm_codegen_with_options,
+    };
+    use inference_wasm_codegen::{
+        AssertStrategy, Backend, CodegenErrorKind, CodegenErrors, CodegenOptions, Linker,
+    };
+    use wasmtime::{Instance, Store, Val, WasmParams, WasmResults};
+
+    fn encoder_options() -> CodegenOptions {
+        CodegenOptions {
+            backend: Backend::Encoder,
+            ..CodegenOptions::default()
+        }
+    }
+
+    fn read_source(test_name: &str) -> String {
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    fn compile(source_code: &str, options: &CodegenOptions) -> Vec<u8> {
+        let wasm_bytes = wasm_codegen_with_options(source_code, options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    /// Calls `function`, returning `None` if it traps.
+    fn call<P: WasmParams, R: WasmResults>(
+        store: &mut Store<Vec<i32>>,
+        instance: &Instance,
+        function: &str,
+        params: P,
+    ) -> Option<R> {
+        instance
+            .get_typed_func::<P, R>(&mut *store, function)
+            .unwrap_or_else(|e| panic!("Failed to get '{function}': {}", e))
+            .call(&mut *store, params)
+            .ok()
+    }
+
+    #[test]
+    fn loops_and_branches_run() {
+        let wasm_bytes = compile(&read_source("encoder"), &encoder_options());
+        let (mut store, instance) = instantiate(&wasm_bytes, "Encoder");
+        for (n, expected) in [(0, 0), (1, 1), (2, 1), (10, 55), (30, 832_040)] {
+            let result: Option<i32> = call(&mut store, &instance, "fibonacci", n);
+            assert_eq!(result, Some(expected), "fibonacci({n})");
+        }
+        for (limit, expected) in [(-5, 0), (0, 1), (99, 10), (100, 11)] {
+            let result: Option<i32> = call(&mut store, &instance, "first_square_above", limit);
+            assert_eq!(result, Some(expected), "first_square_above({limit})");
+        }
+        for (x, expected) in [(i64::MIN, -1), (-1, -1), (0, 0), (7, 1), (i64::MAX, 1)] {
+            let result: Option<i32> = call(&mut store, &instance, "sign", x);
+            assert_eq!(result, Some(expected), "sign({x})");
+        }
+        for (x, expected) in [(5, 5), (1000, 1000), (1001, 1000)] {
+            let result: Option<i32> = call(&mut store, &instance, "clamp", x);
+            assert_eq!(result, Some(expected), "clamp({x})");
+        }
+        for (steps, expected) in [(0, 0), (1, 1), (2, 2), (3, 0), (7, 1)] {
+            let result: Option<i32> = call(&mut store, &instance, "cycle", steps);
+            assert_eq!(result, Some(expected), "cycle({steps})");
+        }
+    }
+
+    #[test]
+    fn logical_operators_short_circuit() {
+        let wasm_bytes = compile(&read_source("encoder"), &encoder_options());
+        let (mut store, instance) = instantiate(&wasm_bytes, "Encoder");
+        // The right operand divides by `x`, which traps for 0 unless it is skipped.
+        for (x, expected) in [(0, 0), (-3, 0), (5, 1), (200, 0)] {
+            let result: Option<i32> = call(&mut store, &instance, "both", x);
+            assert_eq!(result, Some(expected), "both({x})");
+        }
+        for (x, expected) in [(0, 1), (-3, 1), (5, 1), (200, 0)] {
+            let result: Option<i32> = call(&mut store, &instance, "either", x);
+            assert_eq!(result, Some(expected), "either({x})");
+        }
+    }
+
+    #[test]
+    fn narrow_integers_wrap_at_their_width() {
+        let wasm_bytes = compile(&read_source("encoder"), &encoder_options());
+        let (mut store, instance) = instantiate(&wasm_bytes, "Encoder");
+        for x in [i8::MIN, -43, -1, 0, 1, 42, 43, i8::MAX] {
+            let result: Option<i32> = call(&mut store, &instance, "triple_i8", i32::from(x));
+            assert_eq!(result, Some(i32::from(x.wrapping_mul(3))), "triple_i8({x})");
+        }
+        for x in [0, 1, 254, u8::MAX] {
+            let result: Option<i32> = call(&mut store, &instance, "increment_u8", i32::from(x));
+            assert_eq!(
+                result,
+                Some(i32::from(x.wrapping_add(1))),
+                "increment_u8({x})"
+            );
+        }
+        for x in [i16::MIN, -1, 0, i16::MAX] {
+            let result: Option<i32> = call(&mut store, &instance, "negate_i16", i32::from(x));
+            assert_eq!(result, Some(i32::from(x.wrapping_neg())), "negate_i16({x})");
+        }
+        for (a, b) in [(7, 2), (-7, 2), (i8::MIN, 1), (i8::MIN, -1), (1, 0)] {
+            let result: Option<i32> = call(
+                &mut store,
+                &instance,
+                "divide_i8",
+                (i32::from(a), i32::from(b)),
+            );
+            assert_eq!(
+                result,
+                a.checked_div(b).map(i32::from),
+                "divide_i8({a}, {b})"
+            );
+        }
+        for (a, b) in [(1u16, 15u16), (3, 16), (3, 17), (u16::MAX, 4)] {
+            let result: Option<i32> = call(
+                &mut store,
+                &instance,
+                "shift_u16",
+                (i32::from(a), i32::from(b)),
+            );
+            assert_eq!(
+                result,
+                Some(i32::from(a.wrapping_shl(u32::from(b)))),
+                "shift_u16({a}, {b})"
+            );
+        }
+        for (a, b) in [(3u8, 4u8), (2, 8), (3, 255), (255, 255)] {
+            let result: Option<i32> = call(
+                &mut store,
+                &instance,
+                "power_u8",
+                (i32::from(a), i32::from(b)),
+            );
+            assert_eq!(
+                result,
+                Some(i32::from(a.wrapping_pow(u32::from(b)))),
+                "power_u8({a}, {b})"
+            );
+        }
+        // Parameters are brought back into range whatever bits the host passes.
+        let result: Option<i32> = call(&mut store, &instance, "increment_u8", 0x1ff);
+        assert_eq!(result, Some(0));
+    }
+
+    #[test]
+    fn assert_strategies_apply() {
+        let source_code = read_source("encoder");
+        let wasm_bytes = compile(&source_code, &encoder_options());
+        let (mut store, instance) = instantiate(&wasm_bytes, "Encoder");
+        let result: Option<i32> = call(&mut store, &instance, "checked", 4);
+        assert_eq!(result, Some(4));
+        let result: Option<i32> = call(&mut store, &instance, "checked", 0);
+        assert_eq!(result, None, "a failing assert traps");
+
+        let options = CodegenOptions {
+            assert_strategy: AssertStrategy::ResultCode(-7),
+            ..encoder_options()
+        };
+        let wasm_bytes = compile(&source_code, &options);
+        let (mut store, instance) = instantiate(&wasm_bytes, "Encoder");
+        let result: Option<i32> = call(&mut store, &instance, "checked", 0);
+        assert_eq!(result, Some(-7));
+    }
+
+    #[test]
+    fn non_deterministic_blocks_validate() {
+        let wasm_bytes = compile(&read_source("nondet"), &encoder_options());
+        let base = get_test_data_path().join("codegen/wasm/base/nondet.inf");
+        let source_code = std::fs::read_to_string(&base)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {base:?}"));
+        let base_bytes = compile(&source_code, &encoder_options());
+        for bytes in [&wasm_bytes, &base_bytes] {
+            for opcode in [0x3a, 0x3b, 0x3c, 0x3d] {
+                assert!(
+                    bytes
+                        .windows(3)
+                        .any(|window| window == [0xfc, opcode, 0x40]),
+                    "missing block opcode {opcode:#x}"
+                );
+            }
+        }
+        assert!(wasm_bytes.windows(2).any(|window| window == [0xfc, 0x31]));
+        assert!(wasm_bytes.windows(2).any(|window| window == [0xfc, 0x32]));
+    }
+
+    #[test]
+    fn unsupported_constructs_are_reported() {
+        let error = try_wasm_codegen_with_options(&read_source("unsupported"), &encoder_options())
+            .expect_err("Code generation must fail");
+        let errors = error
+            .downcast::<CodegenErrors>()
+            .unwrap_or_else(|e| panic!("Expected codegen errors, found: {e}"));
+        let reported = errors
+            .errors()
+            .iter()
+            .map(|error| (error.function.as_str(), error.block.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(reported, [("builds_point", None), ("sums", None)]);
+        assert_eq!(
+            errors.errors()[0].kind,
+            CodegenErrorKind::Unsupported("variables of type `Point`".to_string())
+        );
+        assert_eq!(
+            errors.errors()[1].kind,
+            CodegenErrorKind::Unsupported("type `[i32; 2]`".to_string())
+        );
+
+        let options = CodegenOptions {
+            source_map: true,
+            ..encoder_options()
+        };
+        assert!(try_wasm_codegen_with_options("pub fn main() {}", &options).is_err());
+    }
+
+    /// Calls every export of the LLVM and encoder modules with the same arguments and
+    /// compares the results, including traps.
+    #[test]
+    fn arithmetic_matches_the_llvm_backend() {
+        const VALUES: [i64; 8] = [0, 1, 2, 63, 65, -1, i64::MIN, i64::MAX];
+        let test_file_path = get_test_data_path().join("codegen/wasm/arithmetic/arithmetic.inf");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let llvm_options = CodegenOptions {
+            linker: Linker::Builtin,
+            opt_level: 0,
+            ..CodegenOptions::default()
+        };
+        let (mut llvm_store, llvm) = instantiate(&compile(&source_code, &llvm_options), "LLVM");
+        let (mut store, encoder) =
+            instantiate(&compile(&source_code, &encoder_options()), "Encoder");
+        let names = encoder
+            .exports(&mut store)
+            .map(|export| export.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names.len(), 40);
+        for name in names {
+            let function = encoder.get_func(&mut store, &name).unwrap();
+            let llvm_function = llvm
+                .get_func(&mut llvm_store, &name)
+                .unwrap_or_else(|| panic!("LLVM module has no export '{name}'"));
+            let arity = function.ty(&store).params().len();
+            for a in VALUES {
+                for b in VALUES
+                    .iter()
+                    .take(if arity == 2 { VALUES.len() } else { 1 })
+                {
+                    let params = [Val::I64(a), Val::I64(*b)];
+                    let mut result = [Val::I32(0)];
+                    let mut expected = [Val::I32(0)];
+                    let actual = function
+                        .call(&mut store, &params[..arity], &mut result)
+                        .ok()
+                        .map(|()| integer(&result[0]));
+                    let reference = llvm_function
+                        .call(&mut llvm_store, &params[..arity], &mut expected)
+                        .ok()
+                        .map(|()| integer(&expected[0]));
+                    assert_eq!(actual, reference, "{name}({a}, {b})");
+                }
+            }
+        }
+    }
+
+    fn integer(value: &Val) -> i64 {
+        value
+            .i64()
+            .or_else(|| value.i32().map(i64::from))
+            .expect("integer result")
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod enums_codegen_tests {
+    use crate::utils::{compile_matrix, get_test_file_path, instantiate};
+    use wasmtime::{Instance, Store, WasmResults};
+
+    /// Compiles `enums.inf` for every linker, unoptimized and optimized, and
+    /// instantiates each module.
+    fn instances() -> Vec<(String, Store<Vec<i32>>, Instance)> {
+        let test_file_path = get_test_file_path(module_path!(), "enums");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        compile_matrix(&source_code, |_| {})
+            .into_iter()
+            .map(|(config, wasm_bytes)| {
+                let (store, instance) = instantiate(&wasm_bytes, &config);
+                (config, store, instance)
+            })
+            .collect()
+    }
+
+    fn assert_returns<R: WasmResults + PartialEq + std::fmt::Debug>(function: &str, expected: R) {
+        for (config, mut store, instance) in instances() {
+            let func = instance
+                .get_typed_func::<(), R>(&mut store, function)
+                .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e));
+            let result = func
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e));
+            assert_eq!(
+                result, expected,
+                "{config}: unexpected result of '{function}'"
+            );
+        }
+    }
+
+    #[test]
+    fn variants_lower_to_declaration_index() {
+        assert_returns("first_variant", 0i32);
+        assert_returns("last_variant", 2i32);
+    }
+
+    #[test]
+    fn enum_variable_holds_tag() {
+        assert_returns("variable_tag", 1i32);
+    }
+
+    #[test]
+    fn enum_stored_in_struct_field() {
+        assert_returns("field_tag", 2i32);
+    }
+
+    #[test]
+    fn enum_stored_in_array() {
+        assert_returns("element_tag", 2i32);
+    }
+
+    #[test]
+    fn enum_equality() {
+        assert_returns("equal", 1i32);
+        assert_returns("not_equal", 0i32);
+    }
+
+    #[test]
+    fn enum_ordering_follows_declaration_order() {
+        assert_returns("ordered", 1i32);
+        assert_returns("not_ordered", 0i32);
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod errors_codegen_tests {
+    use crate::utils::{get_test_file_path, try_wasm_codegen_with_options};
+    use inference_wasm_codegen::{CodegenErrorKind, CodegenErrors, CodegenOptions};
+
+    fn codegen_errors(test_name: &str) -> CodegenErrors {
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let error = try_wasm_codegen_with_options(&source_code, &CodegenOptions::default())
+            .expect_err("Code generation must fail");
+        error
+            .downcast::<CodegenErrors>()
+            .unwrap_or_else(|e| panic!("Expected codegen errors, found: {e}"))
+    }
+
+    #[test]
+    fn every_failing_function_is_reported() {
+        let errors = codegen_errors("errors");
+        let functions = errors
+            .errors()
+            .iter()
+            .map(|error| error.function.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(functions, ["branches", "repeats", "returns_text"]);
+    }
+
+    #[test]
+    fn errors_point_at_the_unsupported_statement() {
+        let errors = codegen_errors("errors");
+        let error = &errors.errors()[0];
+        assert_eq!(
+            error.kind,
+            CodegenErrorKind::Unsupported("`if` statements".to_string())
+        );
+        assert_eq!(
+            (error.location.start_line, error.location.start_column),
+            (6, 5)
+        );
+        assert_eq!(error.block.as_deref(), Some("entry"));
+        assert!(
+            errors.to_string().contains("6:5: in function `branches`"),
+            "unexpected message: {errors}"
+        );
+
+        let error = &errors.errors()[1];
+        assert_eq!(
+            error.kind,
+            CodegenErrorKind::Unsupported("`loop` statements".to_string())
+        );
+        assert_eq!(
+            (error.location.start_line, error.location.start_column),
+            (13, 5)
+        );
+    }
+
+    #[test]
+    fn declaration_errors_have_no_block() {
+        let errors = codegen_errors("errors");
+        let error = &errors.errors()[2];
+        assert_eq!(
+            error.kind,
+            CodegenErrorKind::Unsupported(
+                "returning structs, arrays and strings by value".to_string()
+            )
+        );
+        assert_eq!(error.location.start_line, 19);
+        assert_eq!(error.block, None);
+    }
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod exports_codegen_tests {
+    use crate::utils::{
+        compile_matrix, get_test_file_path, instantiate, try_wasm_codegen_with_options,
+    };
+    use inference_wasm_codegen::{CodegenOptions, Exports};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "exports");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Returns the sorted names of the functions exported by `wasm_bytes`.
+    fn function_exports(wasm_bytes: &[u8]) -> Vec<String> {
+        let mut exports = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+            if let inf_wasmparser::Payload::ExportSection(reader) = payload.unwrap() {
+                for export in reader {
+                    let export = export.unwrap();
+                    if export.kind == inf_wasmparser::ExternalKind::Func {
+                        exports.push(export.name.to_string());
+                    }
+                }
+            }
+        }
+        exports.sort();
+        exports
+    }
+
+    #[test]
+    fn public_functions_are_exported_by_default() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            assert_eq!(function_exports(&wasm_bytes), ["second"], "{config}");
+        }
+    }
+
+    #[test]
+    fn allowlist_exports_private_functions() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |options| {
+            options.exports = Exports::Only(vec!["hidden".to_string()]);
+        }) {
+            assert_eq!(function_exports(&wasm_bytes), ["hidden"], "{config}");
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            let result = instance
+                .get_typed_func::<(), i32>(&mut store, "hidden")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'hidden': {}", e))
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute 'hidden': {}", e));
+            assert_eq!(result, 7, "{config}");
+        }
+    }
+
+    #[test]
+    fn export_names_rename_functions() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |options| {
+            options
+                .export_names
+                .insert("second".to_string(), "pick".to_string());
+        }) {
+            assert_eq!(function_exports(&wasm_bytes), ["pick"], "{config}");
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            let result = instance
+                .get_typed_func::<(i32, i32), i32>(&mut store, "pick")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'pick': {}", e))
+                .call(&mut store, (2, 3))
+                .unwrap_or_else(|e| panic!("{config}: failed to execute 'pick': {}", e));
+            assert_eq!(result, 3, "{config}");
+        }
+    }
+
+    #[test]
+    fn start_function_runs_on_instantiation() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |options| {
+            options.start_function = Some("init".to_string());
+        }) {
+            assert_eq!(
+                function_exports(&wasm_bytes),
+                ["second"],
+                "{config}: the start function must not stay exported"
+            );
+            let (store, _) = instantiate(&wasm_bytes, &config);
+            assert_eq!(store.data(), &[3], "{config}: start function did not run");
+        }
+    }
+
+    #[test]
+    fn unknown_function_names_are_rejected() {
+        let mut export_names = CodegenOptions::default();
+        export_names
+            .export_names
+            .insert("missing".to_string(), "other".to_string());
+        let cases = [
+            (
+                "exports",
+                CodegenOptions {
+                    exports: Exports::Only(vec!["missing".to_string()]),
+                    ..CodegenOptions::default()
+                },
+            ),
+            ("export_names", export_names),
+            (
+                "start_function",
+                CodegenOptions {
+                    start_function: Some("missing".to_string()),
+                    ..CodegenOptions::default()
+                },
+            ),
+        ];
+        for (option, options) in cases {
+            let error = try_wasm_codegen_with_options(&source_code(), &options)
+                .expect_err(&format!("{option}: unknown function must be rejected"));
+            assert!(
+                error.to_string().contains("missing"),
+                "{option}: unexpected error: {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn conflicting_export_names_are_rejected() {
+        let mut options = CodegenOptions {
+            exports: Exports::Only(vec!["second".to_string(), "hidden".to_string()]),
+            ..CodegenOptions::default()
+        };
+        options
+            .export_names
+            .insert("hidden".to_string(), "second".to_string());
+        assert!(try_wasm_codegen_with_options(&source_code(), &options).is_err());
+
+        options.export_names.clear();
+        options
+            .export_names
+            .insert("second".to_string(), "memory".to_string());
+        assert!(try_wasm_codegen_with_options(&source_code(), &options).is_err());
+    }
+
+    #[test]
+    fn start_function_with_parameters_is_rejected() {
+        let options = CodegenOptions {
+            start_function: Some("init_with_value".to_string()),
+            ..CodegenOptions::default()
+        };
+        let error = try_wasm_codegen_with_options(&source_code(), &options)
+            .expect_err("a start function with parameters must be rejected");
+        assert!(
+            error.to_string().contains("no parameters"),
+            "unexpected error: {error}"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod features_codegen_tests {
+    use crate::utils::{get_test_file_path, instantiate, try_wasm_codegen_with_options};
+    use inf_wasmparser::{Validator, WasmFeatures};
+    use inference_wasm_codegen::{CodegenOptions, Linker, TargetFeatures};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "features");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Every toggle on its own, with the proposal it enables.
+    fn toggles() -> [(TargetFeatures, WasmFeatures); 5] {
+        let off = TargetFeatures::default();
+        [
+            (
+                TargetFeatures {
+                    bulk_memory: true,
+                    ..off
+                },
+                WasmFeatures::BULK_MEMORY,
+            ),
+            (
+                TargetFeatures {
+                    sign_ext: true,
+                    ..off
+                },
+                WasmFeatures::SIGN_EXTENSION,
+            ),
+            (
+                TargetFeatures {
+                    nontrapping_fptoint: true,
+                    ..off
+                },
+                WasmFeatures::SATURATING_FLOAT_TO_INT,
+            ),
+            (TargetFeatures { simd: true, ..off }, WasmFeatures::SIMD),
+            (
+                TargetFeatures {
+                    tail_call: true,
+                    ..off
+                },
+                WasmFeatures::TAIL_CALL,
+            ),
+        ]
+    }
+
+    fn call(wasm_bytes: &[u8], config: &str, function: &str) -> i32 {
+        let (mut store, instance) = instantiate(wasm_bytes, config);
+        instance
+            .get_typed_func::<(), i32>(&mut store, function)
+            .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e))
+            .call(&mut store, ())
+            .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e))
+    }
+
+    #[test]
+    fn default_output_is_wasm1() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let options = CodegenOptions {
+                linker,
+                ..CodegenOptions::default()
+            };
+            let wasm_bytes = try_wasm_codegen_with_options(&source_code(), &options)
+                .unwrap_or_else(|e| panic!("{linker:?}: code generation failed: {}", e));
+            Validator::new_with_features(WasmFeatures::WASM1)
+                .validate_all(&wasm_bytes)
+                .unwrap_or_else(|e| panic!("{linker:?}: module is not WebAssembly 1.0: {}", e));
+        }
+    }
+
+    #[test]
+    fn toggles_only_use_their_proposal() {
+        for (target_features, proposal) in toggles() {
+            for opt_level in [0, 3] {
+                let config = format!("{target_features:?} -O{opt_level}");
+                let options = CodegenOptions {
+                    opt_level,
+                    linker: Linker::Builtin,
+                    target_features,
+                    ..CodegenOptions::default()
+                };
+                let wasm_bytes = try_wasm_codegen_with_options(&source_code(), &options)
+                    .unwrap_or_else(|e| panic!("{config}: code generation failed: {}", e));
+                Validator::new_with_features(WasmFeatures::WASM1 | proposal)
+                    .validate_all(&wasm_bytes)
+                    .unwrap_or_else(|e| panic!("{config}: unexpected proposal: {}", e));
+                assert_eq!(call(&wasm_bytes, &config, "copy_array"), 30, "{config}");
+                assert_eq!(call(&wasm_bytes, &config, "tail_seven"), 7, "{config}");
+            }
+        }
+    }
+
+    #[test]
+    fn unknown_features_are_rejected() {
+        let options = CodegenOptions {
+            features: vec!["sign-extension".to_string()],
+            ..CodegenOptions::default()
+        };
+        let error = try_wasm_codegen_with_options(&source_code(), &options).unwrap_err();
+        assert!(error.to_string().contains("sign-extension"), "{error}");
+    }
+}
@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod folding_codegen_tests {
+    use crate::utils::{
+        compile_matrix, get_test_file_path, instantiate, wasm_codegen_with_options,
+    };
+    use inf_wasmparser::{ExternalKind, Operator, Parser, Payload, TypeRef};
+    use inference_wasm_codegen::{CodegenOptions, Linker};
+    use wasmtime::{WasmParams, WasmResults};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "folding");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Compiles `folding.inf` unoptimized with the builtin linker.
+    fn compile_unoptimized() -> Vec<u8> {
+        let options = CodegenOptions {
+            linker: Linker::Builtin,
+            opt_level: 0,
+            ..CodegenOptions::default()
+        };
+        let wasm_bytes = wasm_codegen_with_options(&source_code(), &options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    fn call<P: WasmParams, R: WasmResults>(
+        wasm_bytes: &[u8],
+        config: &str,
+        function: &str,
+        params: P,
+    ) -> R {
+        let (mut store, instance) = instantiate(wasm_bytes, config);
+        instance
+            .get_typed_func::<P, R>(&mut store, function)
+            .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e))
+            .call(&mut store, params)
+            .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e))
+    }
+
+    /// Returns the operators of the body of the function exported as `name`.
+    fn body_operators(wasm_bytes: &[u8], name: &str) -> Vec<String> {
+        let mut imported_functions = 0;
+        let mut function_index = None;
+        let mut bodies = Vec::new();
+        for payload in Parser::new(0).parse_all(wasm_bytes) {
+            match payload.unwrap() {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        if matches!(import.unwrap().ty, TypeRef::Func(_)) {
+                            imported_functions += 1;
+                        }
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.unwrap();
+                        if export.name == name && export.kind == ExternalKind::Func {
+                            function_index = Some(export.index);
+                        }
+                    }
+                }
+                Payload::CodeSectionEntry(body) => bodies.push(body),
+                _ => {}
+            }
+        }
+        let function_index =
+            function_index.unwrap_or_else(|| panic!("function '{name}' is not exported"));
+        let body = &bodies[(function_index - imported_functions) as usize];
+        body.get_operators_reader()
+            .unwrap()
+            .into_iter()
+            .map(|operator: Result<Operator, _>| format!("{:?}", operator.unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn constant_expressions_fold_at_every_opt_level() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            let arithmetic: i32 = call(&wasm_bytes, &config, "folded_arithmetic", ());
+            assert_eq!(arithmetic, 40, "{config}");
+            let wide: i64 = call(&wasm_bytes, &config, "folded_wide", ());
+            assert_eq!(wide, 10_000_000_000, "{config}");
+            let wrapping: i32 = call(&wasm_bytes, &config, "folded_wrapping", ());
+            assert_eq!(wrapping as u8, 144, "{config}");
+            let bits: i32 = call(&wasm_bytes, &config, "folded_bits", ());
+            assert_eq!(bits, 18, "{config}");
+        }
+    }
+
+    #[test]
+    fn constant_conditions_select_a_branch() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |_| {}) {
+            let taken: i32 = call(&wasm_bytes, &config, "taken_branch", ());
+            assert_eq!(taken, 1, "{config}");
+            let skipped: i32 = call(&wasm_bytes, &config, "skipped_branch", 7);
+            assert_eq!(skipped, 7, "{config}");
+        }
+    }
+
+    #[test]
+    fn unoptimized_code_contains_only_the_folded_value() {
+        let wasm_bytes = compile_unoptimized();
+        let operators = body_operators(&wasm_bytes, "folded_arithmetic");
+        assert!(
+            operators.iter().any(|op| op == "I32Const { value: 40 }"),
+            "{operators:?}"
+        );
+        assert!(
+            !operators
+                .iter()
+                .any(|op| op == "I32Add" || op == "I32Sub" || op == "I32Mul"),
+            "{operators:?}"
+        );
+    }
+
+    #[test]
+    fn constant_asserts_emit_no_code() {
+        let wasm_bytes = compile_unoptimized();
+        let operators = body_operators(&wasm_bytes, "skipped_branch");
+        assert!(
+            !operators.iter().any(|op| op == "Unreachable"),
+            "{operators:?}"
+        );
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod globals_codegen_tests {
+    use crate::utils::{
+        compile_matrix, get_test_file_path, instantiate, try_wasm_codegen_with_options,
+    };
+    use inference_wasm_codegen::{Backend, CodegenOptions, Exports};
+    use wasmtime::{Instance, Mutability, Store, Val};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "globals");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Compiles `globals.inf` with `configure` applied to the options for every linker,
+    /// unoptimized and optimized, and with the encoder backend.
+    fn modules(configure: impl Fn(&mut CodegenOptions)) -> Vec<(String, Vec<u8>)> {
+        let mut modules = compile_matrix(&source_code(), &configure);
+        let mut options = CodegenOptions {
+            backend: Backend::Encoder,
+            ..CodegenOptions::default()
+        };
+        configure(&mut options);
+        let wasm_bytes = try_wasm_codegen_with_options(&source_code(), &options)
+            .unwrap_or_else(|e| panic!("Encoder: code generation failed: {}", e));
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Encoder: generated Wasm module is invalid: {}", e));
+        modules.push(("Encoder".to_string(), wasm_bytes));
+        modules
+    }
+
+    /// Returns the value of the exported global `name`, checking that it is immutable.
+    fn global(store: &mut Store<Vec<i32>>, instance: &Instance, name: &str) -> Option<i64> {
+        let global = instance.get_global(&mut *store, name)?;
+        assert_eq!(global.ty(&*store).mutability(), Mutability::Const, "{name}");
+        match global.get(&mut *store) {
+            Val::I32(value) => Some(i64::from(value)),
+            Val::I64(value) => Some(value),
+            value => panic!("unexpected value of {name}: {value:?}"),
+        }
+    }
+
+    #[test]
+    fn public_constants_are_exported_globals() {
+        for (config, wasm_bytes) in modules(|_| {}) {
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            assert_eq!(
+                global(&mut store, &instance, "LIMIT"),
+                Some(100),
+                "{config}"
+            );
+            assert_eq!(global(&mut store, &instance, "MASK"), Some(-1), "{config}");
+            assert_eq!(
+                global(&mut store, &instance, "ENABLED"),
+                Some(1),
+                "{config}"
+            );
+            assert_eq!(global(&mut store, &instance, "OFFSET"), None, "{config}");
+
+            let limit = instance
+                .get_typed_func::<(), i32>(&mut store, "limit")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'limit': {}", e));
+            assert_eq!(limit.call(&mut store, ()).unwrap(), 100, "{config}");
+        }
+    }
+
+    #[test]
+    fn private_constants_are_globals_too() {
+        for (config, wasm_bytes) in modules(|_| {}) {
+            let constants = inf_wasmparser::Parser::new(0)
+                .parse_all(&wasm_bytes)
+                .filter_map(|payload| match payload.unwrap() {
+                    inf_wasmparser::Payload::GlobalSection(reader) => Some(
+                        reader
+                            .into_iter()
+                            .map(|global| global.unwrap())
+                            .filter(|global| !global.ty.mutable)
+                            .count(),
+                    ),
+                    _ => None,
+                })
+                .sum::<usize>();
+            assert!(constants >= 4, "{config}: {constants} immutable globals");
+        }
+    }
+
+    #[test]
+    fn export_options_select_constants() {
+        for (config, wasm_bytes) in modules(|options| {
+            options.exports = Exports::Only(vec!["OFFSET".to_string(), "limit".to_string()]);
+            options.export_names = [("OFFSET".to_string(), "offset_value".to_string())].into();
+        }) {
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            assert_eq!(
+                global(&mut store, &instance, "offset_value"),
+                Some(-5),
+                "{config}"
+            );
+            assert_eq!(global(&mut store, &instance, "LIMIT"), None, "{config}");
+            assert!(instance.get_func(&mut store, "limit").is_some(), "{config}");
+        }
+    }
+
+    #[test]
+    fn clashing_export_names_are_rejected() {
+        for backend in [Backend::Llvm, Backend::Encoder] {
+            let config = format!("{backend:?}");
+            let options = CodegenOptions {
+                backend,
+                export_names: [("LIMIT".to_string(), "limit".to_string())].into(),
+                ..CodegenOptions::default()
+            };
+            let error = try_wasm_codegen_with_options(&source_code(), &options)
+                .expect_err("Code generation must fail");
+            assert!(
+                error
+                    .to_string()
+                    .contains("more than one function or constant is exported as `limit`"),
+                "{config}: unexpected error: {error}"
+            );
+
+            let options = CodegenOptions {
+                backend,
+                exports: Exports::Only(vec!["UNKNOWN".to_string()]),
+                ..CodegenOptions::default()
+            };
+            let error = try_wasm_codegen_with_options(&source_code(), &options)
+                .expect_err("Code generation must fail");
+            assert!(
+                error
+                    .to_string()
+                    .contains("no function or constant with this name is defined"),
+                "{config}: unexpected error: {error}"
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod imports_codegen_tests {
+    use crate::utils::{compile_matrix, get_test_file_path};
+    use inference_wasm_codegen::{CodegenOptions, ImportName};
+    use wasmtime::{Engine, Instance, Memory, MemoryType, Module, Store};
+
+    /// Import module and field name of `scale`, overridden through the codegen options.
+    const SCALE_MODULE: &str = "math";
+    const SCALE_NAME: &str = "times_three";
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "imports");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    /// Names the imports of `imports.inf` through the codegen options.
+    fn name_imports(options: &mut CodegenOptions) {
+        options.import_module = "host".to_string();
+        options.imports.insert(
+            "scale".to_string(),
+            ImportName {
+                module: SCALE_MODULE.to_string(),
+                name: SCALE_NAME.to_string(),
+            },
+        );
+    }
+
+    /// Compiles `imports.inf` for every linker, unoptimized and optimized, and
+    /// instantiates each module with host functions that record the values passed to
+    /// `record` in the store.
+    fn instances() -> Vec<(String, Store<Vec<i32>>, Instance)> {
+        let mut instances = Vec::new();
+        for (config, wasm_bytes) in compile_matrix(&source_code(), name_imports) {
+            let engine = Engine::default();
+            let module = Module::new(&engine, &wasm_bytes)
+                .unwrap_or_else(|e| panic!("{config}: failed to create Wasm module: {}", e));
+            let mut store = Store::new(&engine, Vec::new());
+            let mut wasm_linker = wasmtime::Linker::new(&engine);
+            let memory = Memory::new(&mut store, MemoryType::new(1, None))
+                .unwrap_or_else(|e| panic!("{config}: failed to create memory: {}", e));
+            wasm_linker
+                .define(&mut store, "env", "__linear_memory", memory)
+                .unwrap_or_else(|e| panic!("{config}: failed to define memory import: {}", e));
+            wasm_linker
+                .func_wrap("host", "host_value", || 42i32)
+                .unwrap_or_else(|e| panic!("{config}: failed to define host_value: {}", e));
+            wasm_linker
+                .func_wrap(SCALE_MODULE, SCALE_NAME, |value: i32| value * 3)
+                .unwrap_or_else(|e| panic!("{config}: failed to define scale: {}", e));
+            wasm_linker
+                .func_wrap(
+                    "host",
+                    "record",
+                    |mut caller: wasmtime::Caller<'_, Vec<i32>>, value: i32| {
+                        caller.data_mut().push(value);
+                    },
+                )
+                .unwrap_or_else(|e| panic!("{config}: failed to define record: {}", e));
+            let instance = wasm_linker
+                .instantiate(&mut store, &module)
+                .unwrap_or_else(|e| panic!("{config}: failed to instantiate Wasm module: {}", e));
+            instances.push((config, store, instance));
+        }
+        instances
+    }
+
+    fn call(store: &mut Store<Vec<i32>>, instance: &Instance, config: &str, function: &str) -> i32 {
+        instance
+            .get_typed_func::<(), i32>(&mut *store, function)
+            .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e))
+            .call(&mut *store, ())
+            .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e))
+    }
+
+    #[test]
+    fn external_functions_become_named_imports() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), name_imports) {
+            let mut imports = Vec::new();
+            for payload in inf_wasmparser::Parser::new(0).parse_all(&wasm_bytes) {
+                if let inf_wasmparser::Payload::ImportSection(reader) = payload.unwrap() {
+                    for import in reader {
+                        let import = import.unwrap();
+                        if matches!(import.ty, inf_wasmparser::TypeRef::Func(_)) {
+                            imports.push(format!("{}.{}", import.module, import.name));
+                        }
+                    }
+                }
+            }
+            imports.sort();
+            assert_eq!(
+                imports,
+                ["host.host_value", "host.record", "math.times_three"],
+                "{config}: unexpected function imports"
+            );
+        }
+    }
+
+    #[test]
+    fn host_function_result_is_returned() {
+        for (config, mut store, instance) in instances() {
+            assert_eq!(
+                call(&mut store, &instance, &config, "call_host"),
+                42,
+                "{config}"
+            );
+        }
+    }
+
+    #[test]
+    fn host_function_receives_arguments() {
+        for (config, mut store, instance) in instances() {
+            assert_eq!(
+                call(&mut store, &instance, &config, "call_with_argument"),
+                21,
+                "{config}"
+            );
+        }
+    }
+
+    #[test]
+    fn host_function_called_as_statement() {
+        for (config, mut store, instance) in instances() {
+            assert_eq!(
+                call(&mut store, &instance, &config, "call_for_effect"),
+                1,
+                "{config}"
+            );
+            assert_eq!(store.data(), &[5], "{config}: unexpected recorded values");
+        }
+    }
+}
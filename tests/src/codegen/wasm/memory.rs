@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod memory_codegen_tests {
+    use crate::utils::{
+        compile_matrix, get_test_file_path, instantiate, try_wasm_codegen_with_options,
+    };
+    use inference_wasm_codegen::{CodegenOptions, Linker};
+
+    fn source_code() -> String {
+        let test_file_path = get_test_file_path(module_path!(), "memory");
+        std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"))
+    }
+
+    fn compile(options: &CodegenOptions) -> Vec<u8> {
+        let wasm_bytes = try_wasm_codegen_with_options(&source_code(), options)
+            .unwrap_or_else(|e| panic!("Code generation failed: {}", e));
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    /// Returns the type of the single memory defined by `wasm_bytes`.
+    fn memory_type(wasm_bytes: &[u8]) -> inf_wasmparser::MemoryType {
+        let mut memories = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+            if let inf_wasmparser::Payload::MemorySection(reader) = payload.unwrap() {
+                for memory in reader {
+                    memories.push(memory.unwrap());
+                }
+            }
+        }
+        assert_eq!(memories.len(), 1, "expected exactly one defined memory");
+        memories[0]
+    }
+
+    /// Returns the initial value of the stack pointer, the first global of `wasm_bytes`.
+    fn stack_pointer(wasm_bytes: &[u8]) -> i64 {
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+            if let inf_wasmparser::Payload::GlobalSection(reader) = payload.unwrap() {
+                let global = reader.into_iter().next().unwrap().unwrap();
+                return match global.init_expr.get_operators_reader().read().unwrap() {
+                    inf_wasmparser::Operator::I32Const { value } => i64::from(value),
+                    inf_wasmparser::Operator::I64Const { value } => value,
+                    operator => panic!("unexpected stack pointer initializer {operator:?}"),
+                };
+            }
+        }
+        panic!("module has no stack pointer")
+    }
+
+    fn array_on_stack(wasm_bytes: &[u8], config: &str) -> i32 {
+        let (mut store, instance) = instantiate(wasm_bytes, config);
+        instance
+            .get_typed_func::<(), i32>(&mut store, "array_on_stack")
+            .unwrap_or_else(|e| panic!("{config}: failed to get 'array_on_stack': {}", e))
+            .call(&mut store, ())
+            .unwrap_or_else(|e| panic!("{config}: failed to execute 'array_on_stack': {}", e))
+    }
+
+    #[test]
+    fn memory_limits_follow_options() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(&CodegenOptions {
+                linker,
+                initial_memory_pages: Some(3),
+                max_memory_pages: Some(5),
+                ..CodegenOptions::default()
+            });
+            let memory = memory_type(&wasm_bytes);
+            assert_eq!(memory.initial, 3, "{linker:?}");
+            assert_eq!(memory.maximum, Some(5), "{linker:?}");
+            assert!(!memory.memory64, "{linker:?}");
+            assert_eq!(array_on_stack(&wasm_bytes, &format!("{linker:?}")), 25);
+        }
+    }
+
+    #[test]
+    fn builtin_linker_places_stack_of_configured_size_first() {
+        let wasm_bytes = compile(&CodegenOptions {
+            linker: Linker::Builtin,
+            stack_size: 4 * 65536,
+            ..CodegenOptions::default()
+        });
+        assert_eq!(stack_pointer(&wasm_bytes), 4 * 65536);
+        assert!(memory_type(&wasm_bytes).initial >= 4);
+        assert_eq!(array_on_stack(&wasm_bytes, "Builtin"), 25);
+    }
+
+    #[test]
+    fn memory64_modules_use_64_bit_memory() {
+        for (config, wasm_bytes) in compile_matrix(&source_code(), |options| {
+            options.memory64 = true;
+        }) {
+            assert!(memory_type(&wasm_bytes).memory64, "{config}");
+            assert_eq!(array_on_stack(&wasm_bytes, &config), 25, "{config}");
+        }
+    }
+
+    #[test]
+    fn initial_memory_must_fit_the_stack() {
+        let options = CodegenOptions {
+            linker: Linker::Builtin,
+            stack_size: 4 * 65536,
+            initial_memory_pages: Some(1),
+            ..CodegenOptions::default()
+        };
+        let error = try_wasm_codegen_with_options(&source_code(), &options).unwrap_err();
+        assert!(error.to_string().contains("too small"), "{error}");
+    }
+
+    #[test]
+    fn misaligned_stack_size_is_rejected() {
+        let options = CodegenOptions {
+            stack_size: 1000,
+            ..CodegenOptions::default()
+        };
+        let error = try_wasm_codegen_with_options(&source_code(), &options).unwrap_err();
+        assert!(error.to_string().contains("multiple of 16"), "{error}");
+    }
+}
@@ -1 +1,20 @@
+mod arithmetic;
+mod arrays;
+mod asserts;
 mod base;
+mod cache;
+mod encoder;
+mod enums;
+mod errors;
+mod exports;
+mod features;
+mod folding;
+mod globals;
+mod imports;
+mod memory;
+mod source_map;
+mod stack;
+mod strings;
+mod structs;
+mod units;
+mod verification;
@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod source_map_codegen_tests {
+    use std::path::PathBuf;
+
+    use crate::utils::{get_test_file_path, instantiate, wasm_codegen_with_options};
+    use inf_wasmparser::{Parser, Payload};
+    use inference_wasm_codegen::{CodegenOptions, Linker, SourceMap};
+    use wasmtime::WasmBacktrace;
+
+    fn compile(linker: Linker, source_map: bool, debug_info: bool) -> Vec<u8> {
+        let test_file_path = get_test_file_path(module_path!(), "source_map");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let options = CodegenOptions {
+            linker,
+            opt_level: 0,
+            source_map,
+            debug_info,
+            source_path: Some(PathBuf::from("src/source_map.inf")),
+            ..CodegenOptions::default()
+        };
+        let wasm_bytes = wasm_codegen_with_options(&source_code, &options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    fn source_map(wasm_bytes: &[u8]) -> SourceMap {
+        SourceMap::from_module(wasm_bytes)
+            .unwrap_or_else(|e| panic!("Failed to read source map: {}", e))
+            .expect("module has no source map")
+    }
+
+    /// Returns the names of the custom sections of `wasm_bytes`.
+    fn custom_sections(wasm_bytes: &[u8]) -> Vec<String> {
+        Parser::new(0)
+            .parse_all(wasm_bytes)
+            .filter_map(|payload| match payload.unwrap() {
+                Payload::CustomSection(reader) => Some(reader.name().to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn trap_frames_map_to_source_lines() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(linker, true, false);
+            let source_map = source_map(&wasm_bytes);
+            assert_eq!(source_map.file, "src/source_map.inf", "{linker:?}");
+
+            let (mut store, instance) = instantiate(&wasm_bytes, &format!("{linker:?}"));
+            let passed_on = instance
+                .get_typed_func::<i32, i32>(&mut store, "passed_on")
+                .unwrap_or_else(|e| panic!("{linker:?}: failed to get 'passed_on': {}", e));
+            let error = passed_on
+                .call(&mut store, 0)
+                .expect_err("a failing assert must trap");
+            let backtrace = error
+                .downcast_ref::<WasmBacktrace>()
+                .unwrap_or_else(|| panic!("{linker:?}: trap has no backtrace: {error:?}"));
+            let lines = backtrace
+                .frames()
+                .iter()
+                .map(|frame| {
+                    let offset = frame.module_offset().expect("frame has no module offset");
+                    source_map
+                        .lookup(u32::try_from(offset).unwrap())
+                        .map(|position| position.line)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(lines, [Some(3), Some(8)], "{linker:?}");
+        }
+    }
+
+    #[test]
+    fn dwarf_is_dropped_without_debug_info() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let sections = custom_sections(&compile(linker, true, false));
+            assert!(
+                sections.iter().any(|name| name == "inference.sourcemap"),
+                "{linker:?}: {sections:?}"
+            );
+            assert!(
+                !sections.iter().any(|name| name.starts_with(".debug_")),
+                "{linker:?}: {sections:?}"
+            );
+
+            let sections = custom_sections(&compile(linker, true, true));
+            assert!(
+                sections.iter().any(|name| name == "inference.sourcemap"),
+                "{linker:?}: {sections:?}"
+            );
+            assert!(
+                sections.iter().any(|name| name == ".debug_line"),
+                "{linker:?}: {sections:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn source_map_is_opt_in() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let wasm_bytes = compile(linker, false, false);
+            let source_map = SourceMap::from_module(&wasm_bytes)
+                .unwrap_or_else(|e| panic!("{linker:?}: failed to read source map: {}", e));
+            assert_eq!(source_map, None, "{linker:?}");
+        }
+    }
+}
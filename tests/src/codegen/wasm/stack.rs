@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod stack_codegen_tests {
+    use crate::utils::{get_test_file_path, instantiate, wasm_codegen_with_options};
+    use inference_wasm_codegen::{CodegenOptions, Linker, StackChecks};
+    use wasmtime::{Instance, Store, Trap};
+
+    /// Compiles `stack.inf` unoptimized, so every call keeps its frame on the shadow stack,
+    /// with `stack_checks` for every linker, and instantiates each module.
+    fn instances(stack_checks: StackChecks) -> Vec<(String, Store<Vec<i32>>, Instance)> {
+        let test_file_path = get_test_file_path(module_path!(), "stack");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let mut instances = Vec::new();
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            for memory64 in [false, true] {
+                let options = CodegenOptions {
+                    opt_level: 0,
+                    linker,
+                    memory64,
+                    stack_size: 4096,
+                    stack_checks,
+                    ..CodegenOptions::default()
+                };
+                let wasm_bytes = wasm_codegen_with_options(&source_code, &options);
+                inf_wasmparser::validate(&wasm_bytes)
+                    .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+
+                let memory = if memory64 { "memory64" } else { "memory32" };
+                let config = format!("{linker:?} {memory}");
+                let (store, instance) = instantiate(&wasm_bytes, &config);
+                instances.push((config, store, instance));
+            }
+        }
+        instances
+    }
+
+    /// Calls `recurse`, which never returns, and returns the trap it ends with.
+    fn recursion_trap(store: &mut Store<Vec<i32>>, instance: &Instance, config: &str) -> Trap {
+        instance
+            .get_typed_func::<i32, i32>(&mut *store, "recurse")
+            .unwrap_or_else(|e| panic!("{config}: failed to get 'recurse': {}", e))
+            .call(&mut *store, 1)
+            .expect_err("unbounded recursion should trap")
+            .downcast::<Trap>()
+            .unwrap_or_else(|e| panic!("{config}: expected a trap, got {e}"))
+    }
+
+    #[test]
+    fn stack_overflow_traps() {
+        for (config, mut store, instance) in instances(StackChecks::Trap) {
+            let shallow = instance
+                .get_typed_func::<i32, i32>(&mut store, "shallow")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'shallow': {}", e));
+            assert_eq!(shallow.call(&mut store, 7).unwrap(), 7, "{config}");
+            assert_eq!(
+                recursion_trap(&mut store, &instance, &config),
+                Trap::UnreachableCodeReached,
+                "{config}"
+            );
+        }
+    }
+
+    #[test]
+    fn unchecked_overflow_leaves_the_memory() {
+        // The stack sits below the data, so the stack pointer wraps around past the end of
+        // memory instead of running over the data.
+        for (config, mut store, instance) in instances(StackChecks::Unchecked) {
+            assert_eq!(
+                recursion_trap(&mut store, &instance, &config),
+                Trap::MemoryOutOfBounds,
+                "{config}"
+            );
+        }
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod strings_codegen_tests {
+    use crate::utils::{compile_matrix, get_test_file_path, instantiate};
+    use inference_wasm_codegen::BoundsChecks;
+    use wasmtime::{Instance, Store, WasmParams, WasmResults};
+
+    /// Compiles `strings.inf` with `bounds_checks` for every linker, unoptimized and
+    /// optimized, and returns each module with its instance.
+    fn instances(bounds_checks: BoundsChecks) -> Vec<(String, Vec<u8>, Store<Vec<i32>>, Instance)> {
+        let test_file_path = get_test_file_path(module_path!(), "strings");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        compile_matrix(&source_code, |options| {
+            options.bounds_checks = bounds_checks;
+        })
+        .into_iter()
+        .map(|(config, wasm_bytes)| {
+            let (store, instance) = instantiate(&wasm_bytes, &config);
+            (config, wasm_bytes, store, instance)
+        })
+        .collect()
+    }
+
+    fn assert_returns<R: WasmResults + PartialEq + std::fmt::Debug>(function: &str, expected: R) {
+        for (config, _, mut store, instance) in instances(BoundsChecks::Trap) {
+            let func = instance
+                .get_typed_func::<(), R>(&mut store, function)
+                .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e));
+            let result = func
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e));
+            assert_eq!(
+                result, expected,
+                "{config}: unexpected result of '{function}'"
+            );
+        }
+    }
+
+    /// Asserts that `function` returns `expected` for every argument of `valid` and traps
+    /// for every argument of `invalid`. Results are compared as bytes, since a `u8` result
+    /// only defines the low 8 bits of the returned `i32`.
+    fn assert_checked<P: WasmParams + Copy + std::fmt::Debug>(
+        function: &str,
+        valid: &[(P, u8)],
+        invalid: &[P],
+    ) {
+        for (config, _, mut store, instance) in instances(BoundsChecks::Trap) {
+            let func = instance
+                .get_typed_func::<P, i32>(&mut store, function)
+                .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e));
+            for (params, expected) in valid {
+                let result = func.call(&mut store, *params).unwrap();
+                assert_eq!(result as u8, *expected, "{config}: {function}{params:?}");
+            }
+            for params in invalid {
+                let trap = func
+                    .call(&mut store, *params)
+                    .expect_err("out-of-bounds access should trap")
+                    .downcast::<wasmtime::Trap>()
+                    .unwrap_or_else(|e| panic!("{config}: expected a trap, got {e}"));
+                assert_eq!(
+                    trap,
+                    wasmtime::Trap::UnreachableCodeReached,
+                    "{config}: {function}{params:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn literal_bytes_are_in_a_data_segment() {
+        for (config, wasm_bytes, _, _) in instances(BoundsChecks::Trap) {
+            for text in [&b"hello world"[..], b"inference"] {
+                assert!(
+                    wasm_bytes.windows(text.len()).any(|window| window == text),
+                    "{config}: missing {:?}",
+                    String::from_utf8_lossy(text)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn string_length() {
+        assert_returns("literal_length", 9i32);
+    }
+
+    #[test]
+    fn string_constant_byte() {
+        assert_checked("constant_byte", &[((), b'e')], &[]);
+    }
+
+    #[test]
+    fn local_string_constant() {
+        assert_returns("local_constant_length", 3i32);
+    }
+
+    #[test]
+    fn string_passed_by_pointer() {
+        assert_returns("passed_by_pointer", 5i32);
+    }
+
+    #[test]
+    fn byte_of_slice() {
+        assert_checked("slice_byte", &[((), b'w')], &[]);
+    }
+
+    #[test]
+    fn out_of_bounds_byte_traps() {
+        assert_checked("byte", &[(0, b'h'), (4, b'o')], &[5, -1, i32::MAX]);
+    }
+
+    #[test]
+    fn out_of_bounds_slice_traps() {
+        assert_checked(
+            "slice_length",
+            &[((0, 5), 5), ((1, 3), 2), ((5, 5), 0)],
+            &[(3, 2), (0, 6), (6, 6), (-1, 2)],
+        );
+    }
+
+    #[test]
+    fn unchecked_byte_does_not_trap() {
+        for (config, _, mut store, instance) in instances(BoundsChecks::Unchecked) {
+            let byte = instance
+                .get_typed_func::<i32, i32>(&mut store, "byte")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'byte': {}", e));
+            assert_eq!(
+                byte.call(&mut store, 1).unwrap(),
+                i32::from(b'e'),
+                "{config}"
+            );
+            byte.call(&mut store, 5)
+                .unwrap_or_else(|e| panic!("{config}: unchecked index should not trap: {e}"));
+        }
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod structs_codegen_tests {
+    use crate::utils::{compile_matrix, get_test_file_path, instantiate};
+    use wasmtime::{Instance, Store, WasmResults};
+
+    /// Compiles `structs.inf` for every linker, unoptimized and optimized, and
+    /// instantiates each module.
+    fn instances() -> Vec<(String, Store<Vec<i32>>, Instance)> {
+        let test_file_path = get_test_file_path(module_path!(), "structs");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        compile_matrix(&source_code, |_| {})
+            .into_iter()
+            .map(|(config, wasm_bytes)| {
+                let (store, instance) = instantiate(&wasm_bytes, &config);
+                (config, store, instance)
+            })
+            .collect()
+    }
+
+    fn assert_returns<R: WasmResults + PartialEq + std::fmt::Debug>(function: &str, expected: R) {
+        for (config, mut store, instance) in instances() {
+            let func = instance
+                .get_typed_func::<(), R>(&mut store, function)
+                .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e));
+            let result = func
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e));
+            assert_eq!(
+                result, expected,
+                "{config}: unexpected result of '{function}'"
+            );
+        }
+    }
+
+    #[test]
+    fn struct_literal_field_read() {
+        assert_returns("literal_field", 4i32);
+    }
+
+    #[test]
+    fn struct_field_write() {
+        assert_returns("field_write", 7i32);
+    }
+
+    #[test]
+    fn struct_passed_by_pointer() {
+        assert_returns("pass_by_pointer", 4i32);
+    }
+
+    #[test]
+    fn struct_argument_is_copied() {
+        assert_returns("callee_gets_copy", 3i32);
+    }
+
+    #[test]
+    fn nested_struct_assignment_copies() {
+        assert_returns("nested_copy", 6i32);
+        assert_returns("nested_write", 8i32);
+    }
+
+    #[test]
+    fn struct_field_after_padding() {
+        assert_returns("wide_field", 9i64);
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod units_codegen_tests {
+    use crate::utils::{get_test_file_path, instantiate, wasm_codegen_with_options};
+    use inference_wasm_codegen::{CodegenOptions, Linker};
+    use wasmtime::{Instance, Store};
+
+    /// Functions exported by `units.inf` with the default export options.
+    const EXPORTS: [&str; 6] = [
+        "call_back",
+        "call_forward",
+        "call_private",
+        "last",
+        "one",
+        "record_one",
+    ];
+
+    fn compile(linker: Linker, opt_level: u32, codegen_units: usize) -> Vec<u8> {
+        let test_file_path = get_test_file_path(module_path!(), "units");
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let options = CodegenOptions {
+            opt_level,
+            linker,
+            codegen_units,
+            ..CodegenOptions::default()
+        };
+        let wasm_bytes = wasm_codegen_with_options(&source_code, &options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    /// Returns the sorted names of the functions exported by `wasm_bytes`.
+    fn function_exports(wasm_bytes: &[u8]) -> Vec<String> {
+        let mut exports = Vec::new();
+        for payload in inf_wasmparser::Parser::new(0).parse_all(wasm_bytes) {
+            if let inf_wasmparser::Payload::ExportSection(reader) = payload.unwrap() {
+                for export in reader {
+                    let export = export.unwrap();
+                    if export.kind == inf_wasmparser::ExternalKind::Func {
+                        exports.push(export.name.to_string());
+                    }
+                }
+            }
+        }
+        exports.sort();
+        exports
+    }
+
+    fn call(store: &mut Store<Vec<i32>>, instance: &Instance, config: &str, function: &str) -> i32 {
+        instance
+            .get_typed_func::<(), i32>(&mut *store, function)
+            .unwrap_or_else(|e| panic!("{config}: failed to get '{function}': {}", e))
+            .call(&mut *store, ())
+            .unwrap_or_else(|e| panic!("{config}: failed to execute '{function}': {}", e))
+    }
+
+    #[test]
+    fn units_export_the_same_functions() {
+        for opt_level in [0, 3] {
+            for codegen_units in [1, 3, 16] {
+                let wasm_bytes = compile(Linker::RustLld, opt_level, codegen_units);
+                assert_eq!(
+                    function_exports(&wasm_bytes),
+                    EXPORTS,
+                    "-O{opt_level}, {codegen_units} units"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn calls_across_units_are_linked() {
+        for opt_level in [0, 3] {
+            let config = format!("-O{opt_level}, 3 units");
+            let wasm_bytes = compile(Linker::RustLld, opt_level, 3);
+            let (mut store, instance) = instantiate(&wasm_bytes, &config);
+            assert_eq!(call(&mut store, &instance, &config, "call_forward"), 6);
+            assert_eq!(call(&mut store, &instance, &config, "call_private"), 7);
+            assert_eq!(call(&mut store, &instance, &config, "call_back"), 1);
+            instance
+                .get_typed_func::<(), ()>(&mut store, "record_one")
+                .unwrap_or_else(|e| panic!("{config}: failed to get 'record_one': {}", e))
+                .call(&mut store, ())
+                .unwrap_or_else(|e| panic!("{config}: failed to execute 'record_one': {}", e));
+            assert_eq!(store.data(), &[1], "{config}: unexpected recorded values");
+        }
+    }
+
+    #[test]
+    fn parallel_output_is_deterministic() {
+        let first = compile(Linker::RustLld, 3, 3);
+        for _ in 0..3 {
+            assert_eq!(compile(Linker::RustLld, 3, 3), first);
+        }
+    }
+
+    #[test]
+    fn builtin_linker_uses_a_single_unit() {
+        assert_eq!(
+            compile(Linker::Builtin, 3, 3),
+            compile(Linker::Builtin, 3, 1)
+        );
+    }
+}
@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod verification_codegen_tests {
+    use crate::utils::{get_test_file_path, wasm_codegen_with_options};
+    use inference_wasm_codegen::verification::BlockKind;
+    use inference_wasm_codegen::{CodegenOptions, Linker, VerificationMetadata};
+
+    fn compile(test_name: &str, options: &CodegenOptions) -> Vec<u8> {
+        let test_file_path = get_test_file_path(module_path!(), test_name);
+        let source_code = std::fs::read_to_string(&test_file_path)
+            .unwrap_or_else(|_| panic!("Failed to read test file: {test_file_path:?}"));
+        let wasm_bytes = wasm_codegen_with_options(&source_code, options);
+        inf_wasmparser::validate(&wasm_bytes)
+            .unwrap_or_else(|e| panic!("Generated Wasm module is invalid: {}", e));
+        wasm_bytes
+    }
+
+    fn metadata(wasm_bytes: &[u8]) -> VerificationMetadata {
+        VerificationMetadata::from_module(wasm_bytes)
+            .unwrap_or_else(|e| panic!("Failed to read verification metadata: {}", e))
+            .expect("module has no verification metadata")
+    }
+
+    #[test]
+    fn functions_with_blocks_are_listed() {
+        for linker in [Linker::RustLld, Linker::Builtin] {
+            let options = CodegenOptions {
+                linker,
+                ..CodegenOptions::default()
+            };
+            let metadata = metadata(&compile("verification", &options));
+            let functions = metadata
+                .functions
+                .iter()
+                .map(|function| (function.name.as_str(), function.export_name.as_deref()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                functions,
+                [("bounded", None), ("pick_one", Some("pick_one"))],
+                "{linker:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn blocks_carry_kind_and_source_span() {
+        let metadata = metadata(&compile("verification", &CodegenOptions::default()));
+        let bounded = &metadata.functions[0];
+        assert_eq!(
+            (bounded.location.start_line, bounded.location.end_line),
+            (1, 7)
+        );
+        let blocks = bounded
+            .blocks
+            .iter()
+            .map(|block| {
+                (
+                    block.kind,
+                    block.location.start_line,
+                    block.location.start_column,
+                    block.location.end_line,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blocks,
+            [(BlockKind::Forall, 2, 5, 6), (BlockKind::Assume, 3, 9, 5)]
+        );
+        let pick_one = metadata.functions[1]
+            .blocks
+            .iter()
+            .map(|block| (block.kind, block.location.start_line))
+            .collect::<Vec<_>>();
+        assert_eq!(pick_one, [(BlockKind::Unique, 14), (BlockKind::Exists, 17)]);
+    }
+
+    #[test]
+    fn export_names_follow_options() {
+        let options = CodegenOptions {
+            export_names: [("pick_one".to_string(), "choose".to_string())]
+                .into_iter()
+                .collect(),
+            ..CodegenOptions::default()
+        };
+        let metadata = metadata(&compile("verification", &options));
+        assert_eq!(metadata.functions[1].export_name.as_deref(), Some("choose"));
+    }
+
+    #[test]
+    fn modules_without_blocks_have_no_section() {
+        let wasm_bytes = compile("plain", &CodegenOptions::default());
+        assert_eq!(
+            VerificationMetadata::from_module(&wasm_bytes).unwrap(),
+            None
+        );
+    }
+}
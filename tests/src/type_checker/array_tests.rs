@@ -103,6 +103,25 @@ mod edge_cases {
             result.err()
         );
     }
+
+    #[test]
+    fn test_array_of_structs_and_enums_literal() {
+        let source = r#"
+            struct Point { x: i32; }
+            enum Color { Red, Blue }
+            fn test() -> i32 {
+                let points: [Point; 1] = [Point { x: 1 }];
+                let colors: [Color; 2] = [Color::Blue, Color::Red];
+                return 42;
+            }
+        "#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "Array literals of structs and enums should match their annotations, got: {:?}",
+            result.err()
+        );
+    }
 }
 
 mod function_parameters {
@@ -489,3 +508,71 @@ mod comprehensive_scenarios {
         );
     }
 }
+
+mod builtin_methods {
+    use super::*;
+
+    #[test]
+    fn test_array_len() {
+        let source = r#"fn test(arr: [i32; 4]) -> u32 { return arr.len(); }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "Array len() should resolve to u32, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_array_len_on_generic_elements() {
+        let source = r#"fn test T'(arr: [T; 4]) -> u32 { return arr.len(); }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "Array len() should resolve for any element type, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_array_len_with_arguments() {
+        let source = r#"fn test(arr: [i32; 4]) -> u32 { return arr.len(1); }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Extra argument should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("expects 0 arguments, but 1 provided"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_unknown_array_method() {
+        let source = r#"fn test(arr: [i32; 4]) -> u32 { return arr.size(); }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Unknown method should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("method `size` not found on type `[i32; 4]`"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_generic_receiver_resolves_through_builtin_table() {
+        let source = r#"fn test T'(x: T) -> u32 { return x.len(); }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Unbounded generic has no methods")
+            .to_string();
+        assert!(
+            error_msg.contains("method `len` not found on type `T'`"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+}
@@ -758,8 +758,7 @@ mod expression_coverage {
         );
     }
 
-    // FIXME: Test disabled due to parser or type checker limitation
-    // #[test]
+    #[test]
     fn test_struct_expression() {
         let source = r#"struct Point { x: i32; y: i32; } fn test() -> Point { return Point { x: 1, y: 2 }; }"#;
         let result = try_type_check(source);
@@ -770,6 +769,37 @@ mod expression_coverage {
         );
     }
 
+    #[test]
+    fn test_struct_expression_in_variable_definition() {
+        let source = r#"struct Point { x: i32; y: i32; } fn test() -> i32 { let p: Point = Point { x: 1, y: 2 }; p = Point { x: 3, y: 4 }; return p.y; }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "Struct expression assigned to a struct variable should work, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_struct_expression_field_values_are_typed() {
+        use inference_ast::nodes::{AstNode, Expression, Literal};
+        use inference_type_checker::type_info::TypeInfoKind;
+
+        let source = r#"struct Buffer { data: [i32; 2]; } fn test() -> Buffer { return Buffer { data: [4, 5] }; }"#;
+        let typed_context = try_type_check(source).expect("Type checking should succeed");
+        let array_literals = typed_context.filter_nodes(|node| {
+            matches!(
+                node,
+                AstNode::Expression(Expression::Literal(Literal::Array(_)))
+            )
+        });
+        assert_eq!(array_literals.len(), 1);
+        let type_info = typed_context
+            .get_node_typeinfo(array_literals[0].id())
+            .expect("Array literal inside a struct expression should have a type");
+        assert!(matches!(type_info.kind, TypeInfoKind::Array(_, 2)));
+    }
+
     #[test]
     fn test_struct_expression_undefined() {
         let source = r#"fn test() -> UndefinedStruct { return UndefinedStruct { }; }"#;
@@ -898,8 +928,7 @@ mod expression_coverage {
         }
     }
 
-    // FIXME: Test disabled due to parser or type checker limitation
-    // #[test]
+    #[test]
     fn test_type_member_access_on_identifier() {
         let source =
             r#"enum Status { Active, Inactive } fn test() -> Status { return Status::Active; }"#;
@@ -911,8 +940,7 @@ mod expression_coverage {
         );
     }
 
-    // FIXME: Test disabled due to parser or type checker limitation
-    // #[test]
+    #[test]
     fn test_type_member_access_on_simple_type() {
         let source = r#"enum Color { Red, Green, Blue } fn test() -> Color { return Color::Red; }"#;
         let result = try_type_check(source);
@@ -1164,8 +1192,7 @@ mod symbol_table_coverage {
         );
     }
 
-    // FIXME: Test disabled due to parser or type checker limitation
-    // #[test]
+    #[test]
     fn test_enum_variant_lookup() {
         let source = r#"enum Color { Red, Green, Blue } fn test() -> Color { return Color::Red; }"#;
         let result = try_type_check(source);
@@ -1175,6 +1202,20 @@ mod symbol_table_coverage {
             result.err()
         );
     }
+
+    #[test]
+    fn test_enum_comparison_with_annotated_enum_types() {
+        let source = r#"enum Color { Red, Green }
+        fn same(c: Color) -> bool { let d: Color = Color::Green; return c == d; }
+        fn differs(c: Color) -> bool { return Color::Red != c; }
+        fn call() -> bool { return same(Color::Red); }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "Enum values should compare with enum-typed variables, got: {:?}",
+            result.err()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -857,4 +857,65 @@ mod error_recovery_tests {
             }
         }
     }
+
+    #[test]
+    fn test_return_mismatch_includes_related_notes() {
+        let source = r#"fn test() -> i32 { return true; }"#;
+        let result = try_type_check(source);
+        assert!(result.is_err(), "Should detect return type mismatch");
+        if let Err(error) = result {
+            let error_msg = error.to_string();
+            assert!(
+                error_msg.contains("note: this expression has type `Bool`"),
+                "Should point at the returned expression: {}",
+                error_msg
+            );
+            assert!(
+                error_msg.contains("note: function `test` declared to return `i32` here"),
+                "Should point at the function declaration: {}",
+                error_msg
+            );
+        }
+    }
+
+    #[test]
+    fn test_assignment_mismatch_points_at_declaration() {
+        let source = r#"fn test() { let x: i32 = 1; x = true; }"#;
+        let result = try_type_check(source);
+        assert!(result.is_err(), "Should detect assignment type mismatch");
+        if let Err(error) = result {
+            let error_msg = error.to_string();
+            assert!(
+                error_msg.contains("1:17: note: `x` declared with type `i32` here"),
+                "Should point at the variable declaration: {}",
+                error_msg
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors_are_sorted_by_location() {
+        // The unknown parameter type is found while registering functions, before
+        // the return mismatch in `first` is found during body inference.
+        let source = r#"
+            fn first() -> i32 { return true; }
+            fn second(x: UnknownType) -> i32 { return 42; }
+        "#;
+        let result = try_type_check(source);
+        assert!(result.is_err(), "Should detect both errors");
+        if let Err(error) = result {
+            let error_msg = error.to_string();
+            let mismatch = error_msg
+                .find("type mismatch")
+                .expect("Should report the return mismatch");
+            let unknown = error_msg
+                .find("UnknownType")
+                .expect("Should report the unknown type");
+            assert!(
+                mismatch < unknown,
+                "Errors should be ordered by source location: {}",
+                error_msg
+            );
+        }
+    }
 }
@@ -636,6 +636,52 @@ mod import_tests {
             let result = try_type_check(source);
             assert!(result.is_ok(), "Empty source should succeed");
         }
+
+        fn prelude_with_module(
+            name: &str,
+            source: &str,
+        ) -> inference_ast::extern_prelude::ExternPrelude {
+            use std::fs;
+
+            let temp_dir =
+                std::env::temp_dir().join(format!("test_prelude_{name}_{}", std::process::id()));
+            let src_dir = temp_dir.join("src");
+            fs::create_dir_all(&src_dir).expect("Failed to create src directory");
+            fs::write(src_dir.join("lib.inf"), source).expect("Failed to write lib.inf");
+
+            let mut prelude = inference_ast::extern_prelude::create_empty_prelude();
+            inference_ast::extern_prelude::parse_external_module(&temp_dir, name, &mut prelude)
+                .expect("Failed to parse module");
+            let _ = fs::remove_dir_all(&temp_dir);
+            prelude
+        }
+
+        #[test]
+        fn test_use_resolves_function_from_prelude() {
+            let prelude = prelude_with_module(
+                "mathlib",
+                "pub fn add(a: i32, b: i32) -> i32 { return a + b; }",
+            );
+            let arena =
+                build_ast("use mathlib::add;\nfn main() -> i32 { return add(1, 2); }".to_string());
+            let result = TypeCheckerBuilder::new().with_prelude(prelude).check(arena);
+            assert!(
+                result.is_ok(),
+                "Function of a prelude module should be importable: {:?}",
+                result.err()
+            );
+        }
+
+        #[test]
+        fn test_use_of_missing_prelude_module_fails() {
+            let arena =
+                build_ast("use mathlib::add;\nfn main() -> i32 { return add(1, 2); }".to_string());
+            let result = TypeCheckerBuilder::new().check(arena);
+            assert!(
+                result.is_err(),
+                "Import from a module that is not in the prelude should fail"
+            );
+        }
     }
 }
 
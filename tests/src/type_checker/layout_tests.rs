@@ -0,0 +1,119 @@
+//! Tests for struct and enum memory layouts
+//!
+//! This module contains tests verifying:
+//! - Fields are placed in declaration order with natural alignment
+//! - Struct size is padded to the largest field alignment
+//! - Nested structs and arrays use the layout of their element types
+//! - Unknown structs and fields without a memory representation have no layout
+//! - Enums are `i32` tags numbered in variant declaration order
+
+use crate::utils::build_ast;
+use inference_type_checker::TypeCheckerBuilder;
+use inference_type_checker::typed_context::TypedContext;
+
+fn typed_context(source: &str) -> TypedContext {
+    let arena = build_ast(source.to_string());
+    TypeCheckerBuilder::build_typed_context(arena)
+        .expect("type checking should succeed")
+        .typed_context()
+}
+
+fn offsets(ctx: &TypedContext, name: &str) -> Vec<(String, u32)> {
+    ctx.struct_layout(name)
+        .unwrap_or_else(|| panic!("struct `{name}` has no layout"))
+        .fields
+        .into_iter()
+        .map(|field| (field.name, field.offset))
+        .collect()
+}
+
+#[test]
+fn fields_are_laid_out_in_declaration_order() {
+    let ctx = typed_context("struct Point { x: i32; y: i32; }");
+    let layout = ctx.struct_layout("Point").unwrap();
+    assert_eq!(
+        offsets(&ctx, "Point"),
+        vec![("x".to_string(), 0), ("y".to_string(), 4)]
+    );
+    assert_eq!((layout.size, layout.align), (8, 4));
+}
+
+#[test]
+fn fields_are_aligned_and_size_is_padded() {
+    let ctx = typed_context("struct Mixed { flag: bool; big: i64; small: u16; }");
+    let layout = ctx.struct_layout("Mixed").unwrap();
+    assert_eq!(
+        offsets(&ctx, "Mixed"),
+        vec![
+            ("flag".to_string(), 0),
+            ("big".to_string(), 8),
+            ("small".to_string(), 16)
+        ]
+    );
+    assert_eq!((layout.size, layout.align), (24, 8));
+}
+
+#[test]
+fn nested_structs_and_arrays_use_element_layouts() {
+    let ctx = typed_context(
+        "struct Point { x: i32; y: i32; }
+         struct Segment { tag: u8; from: Point; to: Point; weights: [u16; 3]; }",
+    );
+    let layout = ctx.struct_layout("Segment").unwrap();
+    assert_eq!(
+        offsets(&ctx, "Segment"),
+        vec![
+            ("tag".to_string(), 0),
+            ("from".to_string(), 4),
+            ("to".to_string(), 12),
+            ("weights".to_string(), 20)
+        ]
+    );
+    assert_eq!((layout.size, layout.align), (28, 4));
+}
+
+#[test]
+fn field_lookup_returns_declaration_index() {
+    let ctx = typed_context("struct Point { x: i32; y: i32; }");
+    let layout = ctx.struct_layout("Point").unwrap();
+    let (index, field) = layout.field("y").unwrap();
+    assert_eq!(index, 1);
+    assert_eq!(field.offset, 4);
+    assert!(layout.field("z").is_none());
+}
+
+#[test]
+fn unknown_struct_has_no_layout() {
+    let ctx = typed_context("struct Point { x: i32; }");
+    assert!(ctx.struct_layout("Missing").is_none());
+}
+
+#[test]
+fn struct_with_unsupported_field_has_no_layout() {
+    let ctx = typed_context("struct Named { name: string; }");
+    assert!(ctx.struct_layout("Named").is_none());
+}
+
+#[test]
+fn enum_variants_are_listed_in_declaration_order() {
+    let ctx = typed_context("enum Color { Red, Green, Blue }");
+    assert_eq!(
+        ctx.enum_variants("Color").unwrap(),
+        vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()]
+    );
+    assert!(ctx.enum_variants("Missing").is_none());
+}
+
+#[test]
+fn enum_fields_are_laid_out_as_i32_tags() {
+    let ctx = typed_context(
+        "enum Color { Red, Green }
+         struct Pixel { flag: bool; color: Color; }",
+    );
+    let layout = ctx.struct_layout("Pixel").unwrap();
+    assert_eq!(
+        offsets(&ctx, "Pixel"),
+        vec![("flag".to_string(), 0), ("color".to_string(), 4)]
+    );
+    assert_eq!((layout.size, layout.align), (8, 4));
+}
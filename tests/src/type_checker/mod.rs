@@ -6,4 +6,10 @@ mod associated_functions;
 mod coverage;
 mod error_recovery;
 mod features;
+mod layout_tests;
+mod passes;
+mod shadowing;
+mod signature_tests;
+mod string_tests;
+mod symbol_tests;
 mod type_info_tests;
@@ -0,0 +1,133 @@
+//! Tests for type checker extension passes
+//!
+//! This module contains tests verifying:
+//! - Registered passes visit typed nodes after inference
+//! - Diagnostics reported by passes fail type checking with the pass name attached
+//! - Passes do not run when inference itself reports errors
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::utils::build_ast;
+use inference_ast::nodes::{AstNode, Definition, Expression, Literal};
+use inference_type_checker::TypeCheckerBuilder;
+use inference_type_checker::pass::{PassReporter, TypeCheckPass};
+use inference_type_checker::type_info::{NumberType, TypeInfoKind};
+use inference_type_checker::typed_context::TypedContext;
+
+/// Rejects functions whose name starts with `unsafe_`.
+struct NoUnsafeNames;
+
+impl TypeCheckPass for NoUnsafeNames {
+    fn name(&self) -> &str {
+        "no-unsafe-names"
+    }
+
+    fn visit_node(&mut self, node: &AstNode, _ctx: &TypedContext, reporter: &mut PassReporter) {
+        if let AstNode::Definition(Definition::Function(function)) = node
+            && function.name().starts_with("unsafe_")
+        {
+            reporter.report(
+                function.location,
+                format!("`{}` is forbidden", function.name()),
+            );
+        }
+    }
+}
+
+/// Counts number literals typed as `i32`.
+struct CountI32Literals {
+    count: Rc<RefCell<usize>>,
+    finished: Rc<RefCell<bool>>,
+}
+
+impl TypeCheckPass for CountI32Literals {
+    fn name(&self) -> &str {
+        "count-i32-literals"
+    }
+
+    fn visit_node(&mut self, node: &AstNode, ctx: &TypedContext, _reporter: &mut PassReporter) {
+        if let AstNode::Expression(Expression::Literal(Literal::Number(literal))) = node
+            && ctx
+                .get_node_typeinfo(literal.id)
+                .is_some_and(|ti| ti.kind == TypeInfoKind::Number(NumberType::I32))
+        {
+            *self.count.borrow_mut() += 1;
+        }
+    }
+
+    fn finish(&mut self, _ctx: &TypedContext, _reporter: &mut PassReporter) {
+        *self.finished.borrow_mut() = true;
+    }
+}
+
+#[test]
+fn pass_diagnostic_fails_type_checking() {
+    let source = r#"fn unsafe_read() -> i32 { return 0; }"#;
+    let arena = build_ast(source.to_string());
+    let result = TypeCheckerBuilder::new()
+        .with_pass(NoUnsafeNames)
+        .check(arena);
+    let error_msg = result
+        .err()
+        .expect("Pass diagnostic should fail")
+        .to_string();
+    assert!(
+        error_msg.contains("[no-unsafe-names] `unsafe_read` is forbidden"),
+        "Error should name the pass, got: {error_msg}"
+    );
+}
+
+#[test]
+fn pass_without_diagnostics_succeeds() {
+    let source = r#"fn read() -> i32 { return 0; }"#;
+    let arena = build_ast(source.to_string());
+    let result = TypeCheckerBuilder::new()
+        .with_pass(NoUnsafeNames)
+        .check(arena);
+    assert!(
+        result.is_ok(),
+        "Clean code should pass, got: {:?}",
+        result.err()
+    );
+}
+
+#[test]
+fn pass_sees_inferred_types() {
+    let count = Rc::new(RefCell::new(0));
+    let finished = Rc::new(RefCell::new(false));
+    let source = r#"fn test() -> i32 { let x: i32 = 1; let y: bool = true; return x + 2; }"#;
+    let arena = build_ast(source.to_string());
+    let result = TypeCheckerBuilder::new()
+        .with_pass(CountI32Literals {
+            count: count.clone(),
+            finished: finished.clone(),
+        })
+        .check(arena);
+    assert!(
+        result.is_ok(),
+        "Type checking should succeed, got: {:?}",
+        result.err()
+    );
+    assert_eq!(*count.borrow(), 2, "Pass should see both i32 literals");
+    assert!(*finished.borrow(), "Pass should be finished");
+}
+
+#[test]
+fn passes_do_not_run_after_inference_errors() {
+    let count = Rc::new(RefCell::new(0));
+    let finished = Rc::new(RefCell::new(false));
+    let source = r#"fn test() -> i32 { return true; }"#;
+    let arena = build_ast(source.to_string());
+    let result = TypeCheckerBuilder::new()
+        .with_pass(CountI32Literals {
+            count,
+            finished: finished.clone(),
+        })
+        .check(arena);
+    assert!(result.is_err(), "Type mismatch should be reported");
+    assert!(
+        !*finished.borrow(),
+        "Passes should not run on ill-typed code"
+    );
+}
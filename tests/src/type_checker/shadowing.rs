@@ -0,0 +1,75 @@
+//! Tests for variable shadowing and redeclaration diagnostics
+//!
+//! This module contains tests verifying:
+//! - Shadowing a binding from an enclosing scope produces a warning
+//! - Redeclaring a binding in the same scope produces a warning
+//! - Both warnings name the new and the previous declaration locations
+//! - `deny_shadowing` turns these warnings into errors
+
+use crate::utils::build_ast;
+use inference_type_checker::TypeCheckerBuilder;
+use inference_type_checker::typed_context::TypedContext;
+use inference_type_checker::warnings::TypeCheckWarning;
+
+fn type_check(source: &str) -> TypedContext {
+    let arena = build_ast(source.to_string());
+    TypeCheckerBuilder::build_typed_context(arena)
+        .expect("Type checking should succeed")
+        .typed_context()
+}
+
+#[test]
+fn no_warnings_without_shadowing() {
+    let source = r#"fn test(a: i32) -> i32 { let b: i32 = a; return b; }"#;
+    let ctx = type_check(source);
+    assert!(
+        ctx.warnings().is_empty(),
+        "Expected no warnings, got: {:?}",
+        ctx.warnings()
+    );
+}
+
+#[test]
+fn inner_block_shadowing_warns() {
+    let source = r#"fn test() -> i32 { let x: i32 = 1; if true { let x: i32 = 2; } return x; }"#;
+    let ctx = type_check(source);
+    assert_eq!(ctx.warnings().len(), 1, "Expected one warning");
+    let warning = &ctx.warnings()[0];
+    assert!(matches!(
+        warning,
+        TypeCheckWarning::ShadowedBinding { name, .. } if name == "x"
+    ));
+    assert!(
+        warning
+            .to_string()
+            .ends_with("`x` shadows a binding declared at 1:24"),
+        "Warning should name the previous location: {warning}"
+    );
+}
+
+#[test]
+fn parameter_redeclaration_warns() {
+    let source = r#"fn test(x: i32) -> i32 { let x: i32 = 2; return x; }"#;
+    let ctx = type_check(source);
+    assert_eq!(ctx.warnings().len(), 1, "Expected one warning");
+    assert!(
+        matches!(&ctx.warnings()[0], TypeCheckWarning::Redeclaration { name, .. } if name == "x"),
+        "Expected redeclaration warning, got: {:?}",
+        ctx.warnings()
+    );
+}
+
+#[test]
+fn deny_shadowing_reports_error() {
+    let source = r#"fn test() -> i32 { let x: i32 = 1; if true { let x: i32 = 2; } return x; }"#;
+    let arena = build_ast(source.to_string());
+    let result = TypeCheckerBuilder::new().deny_shadowing(true).check(arena);
+    let error_msg = result
+        .err()
+        .expect("Shadowing should be denied")
+        .to_string();
+    assert!(
+        error_msg.contains("`x` shadows a binding declared at 1:24 (denied)"),
+        "Unexpected error: {error_msg}"
+    );
+}
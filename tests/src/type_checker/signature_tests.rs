@@ -0,0 +1,132 @@
+//! Tests for signature rendering
+//!
+//! This module contains tests verifying:
+//! - Function signatures include visibility, generics, parameters and return type
+//! - Struct, enum, constant and type alias headers are rendered without bodies
+//! - Preceding `///` doc comments are appended after the signature
+//! - Unknown ids and non-definition nodes render nothing
+
+use crate::utils::build_ast;
+use inference_ast::nodes::{AstNode, Definition};
+use inference_type_checker::TypeCheckerBuilder;
+use inference_type_checker::typed_context::TypedContext;
+
+fn typed_context(source: &str) -> TypedContext {
+    let arena = build_ast(source.to_string());
+    TypeCheckerBuilder::build_typed_context(arena)
+        .expect("type checking should succeed")
+        .typed_context()
+}
+
+fn definition_name(definition: &Definition) -> String {
+    match definition {
+        Definition::Spec(d) => d.name(),
+        Definition::Struct(d) => d.name(),
+        Definition::Enum(d) => d.name(),
+        Definition::Constant(d) => d.name(),
+        Definition::Function(d) => d.name(),
+        Definition::ExternalFunction(d) => d.name(),
+        Definition::Type(d) => d.name(),
+        Definition::Module(d) => d.name(),
+    }
+}
+
+fn definition_id(ctx: &TypedContext, name: &str) -> u32 {
+    ctx.filter_nodes(
+        |node| matches!(node, AstNode::Definition(def) if definition_name(def) == name),
+    )
+    .first()
+    .map(AstNode::id)
+    .unwrap_or_else(|| panic!("definition `{name}` not found"))
+}
+
+fn signature(source: &str, name: &str) -> String {
+    let ctx = typed_context(source);
+    let id = definition_id(&ctx, name);
+    ctx.render_signature(id)
+        .expect("definition should have a signature")
+}
+
+#[test]
+fn renders_function_with_params_and_return_type() {
+    let source = r#"pub fn add(a: i32, b: i64) -> i32 { return a; }"#;
+    assert_eq!(
+        signature(source, "add"),
+        "pub fn add(a: i32, b: i64) -> i32"
+    );
+}
+
+#[test]
+fn renders_generic_function() {
+    let source = r#"fn first T'(items: [T; 3]) -> T { return items[0]; }"#;
+    assert_eq!(
+        signature(source, "first"),
+        "fn first T'(items: [T; 3]) -> T"
+    );
+}
+
+#[test]
+fn renders_function_without_return_type() {
+    let source = r#"fn noop() { }"#;
+    assert_eq!(signature(source, "noop"), "fn noop()");
+}
+
+#[test]
+fn renders_struct_fields_without_methods() {
+    let source = r#"
+    pub struct Point {
+        x: i32;
+        y: i32;
+        fn sum(self) -> i32 { return self.x + self.y; }
+    }"#;
+    assert_eq!(
+        signature(source, "Point"),
+        "pub struct Point { x: i32, y: i32 }"
+    );
+}
+
+#[test]
+fn renders_enum_variants() {
+    let source = r#"enum Color { Red, Green, Blue }"#;
+    assert_eq!(
+        signature(source, "Color"),
+        "enum Color { Red, Green, Blue }"
+    );
+}
+
+#[test]
+fn renders_constant_with_value() {
+    let source = r#"const LIMIT: i32 = 10;"#;
+    assert_eq!(signature(source, "LIMIT"), "const LIMIT: i32 = 10");
+}
+
+#[test]
+fn appends_doc_comment() {
+    let source = r#"
+    /// Returns the larger of two values.
+    /// Ties return the first.
+    fn max(a: i32, b: i32) -> i32 {
+        if a < b { return b; }
+        return a;
+    }"#;
+    assert_eq!(
+        signature(source, "max"),
+        "fn max(a: i32, b: i32) -> i32\n\nReturns the larger of two values.\nTies return the first."
+    );
+}
+
+#[test]
+fn plain_comments_are_not_docs() {
+    let source = r#"
+    // helper
+    fn helper() -> i32 { return 1; }"#;
+    assert_eq!(signature(source, "helper"), "fn helper() -> i32");
+}
+
+#[test]
+fn unknown_or_non_definition_nodes_render_nothing() {
+    let ctx = typed_context(r#"fn f() -> i32 { return 1; }"#);
+    assert!(ctx.render_signature(u32::MAX - 1).is_none());
+    let source_file_id = ctx.source_files()[0].id;
+    assert!(ctx.render_signature(source_file_id).is_none());
+}
@@ -0,0 +1,147 @@
+//! String type rule tests
+//!
+//! Tests verifying length, indexing, slicing, equality and concatenation rules
+//! for the `string` type.
+
+use crate::utils::build_ast;
+use inference_type_checker::TypeCheckerBuilder;
+
+fn try_type_check(
+    source: &str,
+) -> anyhow::Result<inference_type_checker::typed_context::TypedContext> {
+    let arena = build_ast(source.to_string());
+    Ok(TypeCheckerBuilder::build_typed_context(arena)?.typed_context())
+}
+
+mod valid_operations {
+    use super::*;
+
+    #[test]
+    fn test_string_length() {
+        let source = r#"fn test(s: string) -> u32 { return s.len(); }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "String length should be u32, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_string_indexing_yields_byte() {
+        let source = r#"fn test(s: string) -> u8 { return s[0]; }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "String indexing should yield u8, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_string_slice() {
+        let source = r#"fn test(s: string) -> string { return s.slice(1, 3); }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "String slice should yield string, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_string_equality() {
+        let source = r#"fn test(a: string, b: string) -> bool { return a == b; }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "String equality should yield bool, got: {:?}",
+            result.err()
+        );
+    }
+
+    #[test]
+    fn test_string_concatenation() {
+        let source = r#"fn test(a: string, b: string) -> string { return a + b; }"#;
+        let result = try_type_check(source);
+        assert!(
+            result.is_ok(),
+            "String concatenation should yield string, got: {:?}",
+            result.err()
+        );
+    }
+}
+
+mod invalid_operations {
+    use super::*;
+
+    #[test]
+    fn test_string_index_must_be_number() {
+        let source = r#"fn test(s: string) -> u8 { return s[true]; }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Non-numeric index should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("string index must be of number type"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_string_slice_argument_count() {
+        let source = r#"fn test(s: string) -> string { return s.slice(1); }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Wrong argument count should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("method `String::slice` expects 2 arguments, but 1 provided"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_unknown_string_method() {
+        let source = r#"fn test(s: string) -> u32 { return s.size(); }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("Unknown method should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("method `size` not found on type `String`"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_string_ordering_rejected() {
+        let source = r#"fn test(a: string, b: string) -> bool { return a < b; }"#;
+        let result = try_type_check(source);
+        let error_msg = result
+            .err()
+            .expect("String ordering should fail")
+            .to_string();
+        assert!(
+            error_msg.contains("ordering operator `Lt` cannot be applied to string types"),
+            "Unexpected error: {error_msg}"
+        );
+    }
+
+    #[test]
+    fn test_string_subtraction_rejected() {
+        let source = r#"fn test(a: string, b: string) -> string { return a - b; }"#;
+        let result = try_type_check(source);
+        assert!(result.is_err(), "String subtraction should fail");
+    }
+
+    #[test]
+    fn test_string_plus_number_rejected() {
+        let source = r#"fn test(a: string) -> string { return a + 1; }"#;
+        let result = try_type_check(source);
+        assert!(result.is_err(), "Adding a number to a string should fail");
+    }
+}
@@ -0,0 +1,102 @@
+//! Tests for the symbol table summary of a typed context
+//!
+//! This module contains tests verifying:
+//! - Built-in types are left out of the root scope
+//! - Functions, structs and enums are listed with their signatures, fields and variants
+//! - Methods are named after their type
+//! - Variables are listed in the scope of their function with their inferred type
+//! - Scopes are sorted by ID and point to their parent
+
+use crate::utils::build_ast;
+use inference_type_checker::TypeCheckerBuilder;
+use inference_type_checker::symbols::{ScopeSummary, SymbolKind};
+use inference_type_checker::typed_context::TypedContext;
+
+fn typed_context(source: &str) -> TypedContext {
+    let arena = build_ast(source.to_string());
+    TypeCheckerBuilder::build_typed_context(arena)
+        .expect("type checking should succeed")
+        .typed_context()
+}
+
+fn symbols(scope: &ScopeSummary) -> Vec<(SymbolKind, &str, &str)> {
+    scope
+        .symbols
+        .iter()
+        .map(|symbol| {
+            (
+                symbol.kind,
+                symbol.name.as_str(),
+                symbol.description.as_str(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn root_scope_lists_definitions_without_builtins() {
+    let ctx = typed_context(
+        r#"
+        struct Point { x: i32; y: i32; }
+        enum Color { Red, Green }
+        pub fn add(a: i32, b: i32) -> i32 { return a + b; }
+        "#,
+    );
+    let scopes = ctx.scopes();
+    let root = &scopes[0];
+    assert_eq!(root.parent, None);
+    assert_eq!(
+        symbols(root),
+        vec![
+            (SymbolKind::Struct, "Point", "struct { x: i32, y: i32 }"),
+            (SymbolKind::Enum, "Color", "enum { Red, Green }"),
+            (SymbolKind::Function, "add", "fn(i32, i32) -> i32"),
+        ]
+    );
+    assert!(root.symbols.iter().all(|symbol| symbol.location.is_some()));
+}
+
+#[test]
+fn methods_are_named_after_their_type() {
+    let ctx = typed_context(
+        r#"
+        struct Counter {
+            value: i32;
+            fn get(self) -> i32 { return self.value; }
+        }
+        "#,
+    );
+    let methods: Vec<String> = ctx
+        .scopes()
+        .iter()
+        .flat_map(|scope| scope.symbols.iter())
+        .filter(|symbol| symbol.kind == SymbolKind::Method)
+        .map(|symbol| format!("{}: {}", symbol.name, symbol.description))
+        .collect();
+    assert_eq!(methods, ["Counter::get: fn() -> i32"]);
+}
+
+#[test]
+fn variables_are_listed_in_their_function_scope() {
+    let ctx = typed_context(
+        r#"
+        fn main() -> i32 {
+            let total: i32 = 5;
+            return total;
+        }
+        "#,
+    );
+    let scopes = ctx.scopes();
+    let scope = scopes
+        .iter()
+        .find(|scope| {
+            scope
+                .symbols
+                .iter()
+                .any(|symbol| symbol.kind == SymbolKind::Variable)
+        })
+        .expect("a scope should declare `total`");
+    assert_eq!(symbols(scope), vec![(SymbolKind::Variable, "total", "i32")]);
+    assert!(scope.parent.is_some());
+    assert!(scopes.windows(2).all(|pair| pair[0].id < pair[1].id));
+}
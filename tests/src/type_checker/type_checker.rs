@@ -2443,3 +2443,78 @@ mod division_operator_tests {
         );
     }
 }
+
+/// Tests for listing the typed nodes of a typed context.
+#[cfg(test)]
+mod typed_nodes_tests {
+    use super::*;
+    use inference_ast::nodes::{AstNode, Expression, Literal};
+    use inference_type_checker::TypeCheckerBuilder;
+
+    #[test]
+    fn test_typed_nodes_are_sorted_and_include_literals() {
+        let arena = build_ast("fn test() -> i32 { return 42; }".to_string());
+        let typed_context = TypeCheckerBuilder::build_typed_context(arena)
+            .unwrap()
+            .typed_context();
+        let typed_nodes = typed_context.typed_nodes();
+        assert!(
+            typed_nodes
+                .windows(2)
+                .all(|pair| pair[0].0.id() < pair[1].0.id())
+        );
+        assert!(typed_nodes.iter().any(|(node, type_info)| {
+            matches!(
+                node,
+                AstNode::Expression(Expression::Literal(Literal::Number(_)))
+            ) && type_info.to_string() == "i32"
+        }));
+    }
+}
+
+/// Tests for type checking several source files as one module.
+#[cfg(test)]
+mod multi_file_tests {
+    use inference_type_checker::{TypeCheckerBuilder, errors::TypeCheckErrors};
+
+    fn build_files(sources: &[&str]) -> inference_ast::arena::Arena {
+        let dir = tempfile::tempdir().unwrap();
+        let files: Vec<_> = sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| {
+                let path = dir.path().join(format!("file{index}.inf"));
+                std::fs::write(&path, source).unwrap();
+                path
+            })
+            .collect();
+        let mut context = inference_ast::parser_context::ParserContext::new(files[0].clone());
+        for file in &files[1..] {
+            context.push_file(0, file.clone());
+        }
+        context.parse_all().unwrap()
+    }
+
+    #[test]
+    fn test_functions_are_visible_across_files() {
+        let arena = build_files(&[
+            "fn main() -> i32 { return helper(); }",
+            "fn helper() -> i32 { return 42; }",
+        ]);
+        let result = TypeCheckerBuilder::build_typed_context(arena);
+        assert!(result.is_ok(), "got: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_errors_record_their_source_file() {
+        let arena = build_files(&[
+            "fn main() -> i32 { return 0; }",
+            "fn broken() { let b: bool = 1; }",
+        ]);
+        let error = TypeCheckerBuilder::build_typed_context(arena)
+            .err()
+            .expect("type checking should fail");
+        let errors = error.downcast_ref::<TypeCheckErrors>().unwrap();
+        assert_eq!(errors.source_files(), &[1]);
+    }
+}
@@ -30,12 +30,123 @@ pub(crate) fn try_build_ast(source_code: String) -> anyhow::Result<Arena> {
     builder.build_ast()
 }
 
+/// Type checks `source` and returns the warnings of every analyzer pass.
+pub(crate) fn analysis_warnings(
+    source: &str,
+) -> Vec<inference_analyzer::warnings::AnalysisWarning> {
+    let arena = build_ast(source.to_string());
+    let typed_context = inference_type_checker::TypeCheckerBuilder::build_typed_context(arena)
+        .expect("type checking should succeed")
+        .typed_context();
+    inference_analyzer::analyze(&typed_context)
+}
+
+/// Builds the control flow graph of the first function of `source`, with the statements
+/// of its body.
+pub(crate) fn build_cfg(
+    source: &str,
+) -> (inference_analyzer::cfg::ControlFlowGraph, Vec<Statement>) {
+    let arena = build_ast(source.to_string());
+    let function = arena.functions()[0].clone();
+    let statements = function.body.statements();
+    (
+        inference_analyzer::cfg::ControlFlowGraph::build(&function),
+        statements,
+    )
+}
+
 pub(crate) fn wasm_codegen(source_code: &str) -> Vec<u8> {
+    wasm_codegen_with_options(
+        source_code,
+        &inference_wasm_codegen::CodegenOptions::default(),
+    )
+}
+
+pub(crate) fn wasm_codegen_with_options(
+    source_code: &str,
+    options: &inference_wasm_codegen::CodegenOptions,
+) -> Vec<u8> {
+    try_wasm_codegen_with_options(source_code, options).unwrap()
+}
+
+pub(crate) fn try_wasm_codegen_with_options(
+    source_code: &str,
+    options: &inference_wasm_codegen::CodegenOptions,
+) -> anyhow::Result<Vec<u8>> {
     let arena = build_ast(source_code.to_string());
     let typed_context = inference_type_checker::TypeCheckerBuilder::build_typed_context(arena)
         .unwrap()
         .typed_context();
-    inference_wasm_codegen::codegen(&typed_context).unwrap()
+    inference_wasm_codegen::codegen(&typed_context, options)
+}
+
+/// Compiles `source_code` for every linker, unoptimized and optimized, with `configure`
+/// applied to the options, and validates each module.
+///
+/// Returns each module with its configuration, such as `RustLld -O3`.
+pub(crate) fn compile_matrix(
+    source_code: &str,
+    configure: impl Fn(&mut inference_wasm_codegen::CodegenOptions),
+) -> Vec<(String, Vec<u8>)> {
+    use inference_wasm_codegen::{CodegenOptions, Linker};
+
+    let mut modules = Vec::new();
+    for linker in [Linker::RustLld, Linker::Builtin] {
+        for opt_level in [0, 3] {
+            let config = format!("{linker:?} -O{opt_level}");
+            let mut options = CodegenOptions {
+                opt_level,
+                linker,
+                ..CodegenOptions::default()
+            };
+            configure(&mut options);
+            let wasm_bytes = try_wasm_codegen_with_options(source_code, &options)
+                .unwrap_or_else(|e| panic!("{config}: code generation failed: {}", e));
+            inf_wasmparser::validate(&wasm_bytes)
+                .unwrap_or_else(|e| panic!("{config}: generated Wasm module is invalid: {}", e));
+            modules.push((config, wasm_bytes));
+        }
+    }
+    modules
+}
+
+/// Instantiates `wasm_bytes` with wasmtime, `config` naming the module in panic messages.
+///
+/// 64-bit memories are enabled, and the imports of the generated modules are provided:
+/// a one-page `env.__linear_memory` and an `env.record` host function that stores the
+/// values it receives in the store.
+pub(crate) fn instantiate(
+    wasm_bytes: &[u8],
+    config: &str,
+) -> (wasmtime::Store<Vec<i32>>, wasmtime::Instance) {
+    use wasmtime::{Caller, Config, Engine, Memory, MemoryType, Module, Store};
+
+    let mut engine_config = Config::new();
+    engine_config.wasm_memory64(true);
+    let engine = Engine::new(&engine_config)
+        .unwrap_or_else(|e| panic!("{config}: failed to create engine: {}", e));
+    let module = Module::new(&engine, wasm_bytes)
+        .unwrap_or_else(|e| panic!("{config}: failed to create Wasm module: {}", e));
+    let mut store = Store::new(&engine, Vec::new());
+    let mut wasm_linker = wasmtime::Linker::new(&engine);
+    let memory = Memory::new(&mut store, MemoryType::new(1, None))
+        .unwrap_or_else(|e| panic!("{config}: failed to create memory: {}", e));
+    wasm_linker
+        .define(&mut store, "env", "__linear_memory", memory)
+        .unwrap_or_else(|e| panic!("{config}: failed to define memory import: {}", e));
+    wasm_linker
+        .func_wrap(
+            "env",
+            "record",
+            |mut caller: Caller<'_, Vec<i32>>, value: i32| {
+                caller.data_mut().push(value);
+            },
+        )
+        .unwrap_or_else(|e| panic!("{config}: failed to define record: {}", e));
+    let instance = wasm_linker
+        .instantiate(&mut store, &module)
+        .unwrap_or_else(|e| panic!("{config}: failed to instantiate Wasm module: {}", e));
+    (store, instance)
 }
 
 /// Automatically resolves a test data file path based on the test's module path and name.
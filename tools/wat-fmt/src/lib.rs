@@ -3,92 +3,435 @@
 extern crate alloc;
 use alloc::string::String;
 use alloc::vec::Vec;
+use core::fmt;
+use core::iter::Peekable;
+use core::mem;
+use core::ops::Range;
+use core::str::CharIndices;
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+/// The first problem found in malformed `WAT` input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatError {
+    /// Byte offset of the problem in the input.
+    pub offset: usize,
+    /// What is wrong at `offset`.
+    pub kind: FormatErrorKind,
+}
+
+/// The kinds of problems [`try_format`] reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatErrorKind {
+    /// A string literal is not closed before the end of the input.
+    UnterminatedString,
+    /// A `(;` block comment is not closed before the end of the input.
+    UnterminatedComment,
+    /// A `)` has no matching `(`.
+    UnexpectedCloseParen,
+    /// A `(` is not closed before the end of the input.
+    UnclosedParen,
+}
+
+impl FormatErrorKind {
+    /// Returns a description of the problem.
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            FormatErrorKind::UnterminatedString => "unterminated string",
+            FormatErrorKind::UnterminatedComment => "unterminated comment",
+            FormatErrorKind::UnexpectedCloseParen => "unexpected `)`",
+            FormatErrorKind::UnclosedParen => "unclosed `(`",
+        }
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.kind.description(), self.offset)
+    }
+}
+
+impl core::error::Error for FormatError {}
+
+/// Options of [`format_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// How the instructions of function bodies are written.
+    pub style: InstructionStyle,
+    /// Keywords of the Inference non-deterministic blocks, whose bodies are indented
+    /// like the body of a `block`. Defaults to [`NONDETERMINISTIC_BLOCKS`].
+    pub nondeterministic_blocks: Vec<String>,
+    /// Reorder the fields of a `module` into the order of the sections of a binary module,
+    /// and replace the indices of named functions, globals, tables, memories, types and
+    /// locals with their names, so the output of different compiler versions can be
+    /// compared. Off by default.
+    pub normalize_modules: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            style: InstructionStyle::default(),
+            nondeterministic_blocks: NONDETERMINISTIC_BLOCKS
+                .iter()
+                .map(|&keyword| String::from(keyword))
+                .collect(),
+            normalize_modules: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    /// Returns true if `token` starts a structured instruction other than `if`, whose
+    /// body runs up to its `end`.
+    fn is_block_keyword(&self, token: &str) -> bool {
+        BLOCK_KEYWORDS.contains(&token)
+            || self
+                .nondeterministic_blocks
+                .iter()
+                .any(|keyword| keyword == token)
+    }
+}
+
+/// The keywords of the non-deterministic blocks of Inference: `forall`, `exists`,
+/// `assume` and `unique`.
+pub const NONDETERMINISTIC_BLOCKS: &[&str] = &["forall", "exists", "assume", "unique"];
+
+/// How the instructions of function bodies are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InstructionStyle {
+    /// One instruction per line, with `block`, `if` and the other structured
+    /// instructions closed by `end`.
+    #[default]
+    Linear,
+    /// Folded expressions, with the operands of an instruction nested in it, such as
+    /// `(i32.add (local.get 0) (local.get 1))`, and structured instructions written as
+    /// `(block ...)` and `(if ... (then ...) (else ...))`.
+    Folded,
+}
+
 enum Token {
     LParen,
     RParen,
     Atom(String),
+    Comment(Comment),
 }
 
-fn tokenize(input: &str) -> Vec<Token> {
-    let mut tokens = Vec::new();
-    let mut chars = input.chars().peekable();
+/// A `;;` line comment or a `(;` block comment.
+#[derive(Clone)]
+struct Comment {
+    text: String,
+    /// Whether the comment follows another token on its line, so it is written at the
+    /// end of the line of that token rather than on a line of its own.
+    trailing: bool,
+}
 
-    while let Some(c) = chars.next() {
-        if c.is_whitespace() {
-            continue;
+impl Comment {
+    fn is_line(&self) -> bool {
+        self.text.starts_with(";;")
+    }
+}
+
+/// Returns true if `text` is a comment read by [`Tokens::next_span`].
+fn is_comment(text: &str) -> bool {
+    text.starts_with(";;") || text.starts_with("(;")
+}
+
+/// Splits `input` into tokens, comments included, and returns the first problem found
+/// on the way: an unterminated string or block comment, a `)` without a `(`, or the
+/// first `(` left unclosed.
+fn tokenize(input: &str) -> (Vec<Token>, Option<FormatError>) {
+    let mut tokens = Tokens::new(input);
+    let collected = tokens.by_ref().collect();
+    (collected, tokens.error)
+}
+
+/// The tokens of an input, read as they are needed.
+struct Tokens<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// Offsets of the `(` not closed yet.
+    open_parens: Vec<usize>,
+    /// The first problem found so far.
+    error: Option<FormatError>,
+    /// The closing of the unterminated string or block comment just read, if any: the
+    /// `"` of a string, after a `\\` if the string ends with an escaping `\\`, or a `;)`
+    /// for each block comment left open.
+    unterminated: Option<String>,
+    /// The end of the last token read, if any.
+    previous_end: Option<usize>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a str) -> Self {
+        Tokens {
+            input,
+            chars: input.char_indices().peekable(),
+            open_parens: Vec::new(),
+            error: None,
+            unterminated: None,
+            previous_end: None,
         }
-        if c == '(' {
-            tokens.push(Token::LParen);
-        } else if c == ')' {
-            tokens.push(Token::RParen);
-        } else if c == '"' {
-            let mut s = String::new();
-            s.push('"');
-            while let Some(&next) = chars.peek() {
-                s.push(next);
-                chars.next();
-                if next == '"' {
-                    break;
+    }
+
+    fn report(&mut self, offset: usize, kind: FormatErrorKind) {
+        self.error.get_or_insert(FormatError { offset, kind });
+    }
+
+    /// Returns the offset of the next character, or the length of the input at its end.
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.input.len(), |&(offset, _)| offset)
+    }
+
+    /// Reads the rest of a `;;` line comment, whose first `;` was read.
+    fn read_line_comment(&mut self) {
+        while self.chars.next_if(|&(_, next)| next != '\n').is_some() {}
+    }
+
+    /// Reads the rest of a `(;` block comment starting at `offset`, whose `(` was read.
+    /// Block comments nest.
+    fn read_block_comment(&mut self, offset: usize) {
+        self.chars.next();
+        let mut depth = 1;
+        while let Some((_, c)) = self.chars.next() {
+            if c == '(' && self.chars.next_if(|&(_, next)| next == ';').is_some() {
+                depth += 1;
+            } else if c == ';' && self.chars.next_if(|&(_, next)| next == ')').is_some() {
+                depth -= 1;
+                if depth == 0 {
+                    return;
                 }
             }
-            tokens.push(Token::Atom(s));
-        } else {
-            let mut s = String::new();
-            s.push(c);
-            while let Some(&next) = chars.peek() {
-                if next.is_whitespace() || next == '(' || next == ')' {
-                    break;
+        }
+        self.report(offset, FormatErrorKind::UnterminatedComment);
+        self.unterminated = Some(" ;)".repeat(depth));
+    }
+
+    /// Reads the next token or comment, skipping whitespace, and returns its byte range
+    /// in the input.
+    fn next_span(&mut self) -> Option<Range<usize>> {
+        let Some((offset, c)) = self.chars.find(|(_, c)| !c.is_whitespace()) else {
+            if let Some(&offset) = self.open_parens.first() {
+                self.report(offset, FormatErrorKind::UnclosedParen);
+            }
+            return None;
+        };
+        match (c, self.chars.peek()) {
+            (';', Some(&(_, ';'))) => self.read_line_comment(),
+            ('(', Some(&(_, ';'))) => self.read_block_comment(offset),
+            ('(', _) => self.open_parens.push(offset),
+            (')', _) => {
+                if self.open_parens.pop().is_none() {
+                    self.report(offset, FormatErrorKind::UnexpectedCloseParen);
+                }
+            }
+            ('"', _) => {
+                let mut closing = Some("\"");
+                while let Some((_, next)) = self.chars.next() {
+                    if next == '\\' && self.chars.next().is_none() {
+                        closing = Some("\\\"");
+                    } else if next == '"' {
+                        closing = None;
+                        break;
+                    }
+                }
+                if closing.is_some() {
+                    self.report(offset, FormatErrorKind::UnterminatedString);
+                }
+                self.unterminated = closing.map(String::from);
+            }
+            // An atom ends at a `;`, so a `;;` written right after it starts a comment.
+            _ => {
+                while self
+                    .chars
+                    .next_if(|&(_, next)| {
+                        !(next.is_whitespace() || matches!(next, '(' | ')' | ';'))
+                    })
+                    .is_some()
+                {}
+            }
+        }
+        Some(offset..self.offset())
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let span = self.next_span()?;
+        let trailing = self
+            .previous_end
+            .is_some_and(|end| !self.input[end..span.start].contains('\n'));
+        self.previous_end = Some(span.end);
+        Some(match &self.input[span] {
+            "(" => Token::LParen,
+            ")" => Token::RParen,
+            text => {
+                let mut text = String::from(text);
+                // Closing an unterminated string or block comment keeps the output stable
+                // when it is formatted again, instead of the string or comment swallowing
+                // the `)` written after it.
+                if let Some(closing) = self.unterminated.take() {
+                    text.push_str(&closing);
                 }
-                s.push(next);
-                chars.next();
+                if is_comment(&text) {
+                    if text.starts_with(";;") {
+                        text.truncate(text.trim_end().len());
+                    }
+                    Token::Comment(Comment { text, trailing })
+                } else {
+                    Token::Atom(text)
+                }
+            }
+        })
+    }
+}
+
+/// The kinds of tokens of [`classify_tokens`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// `(` or `)`.
+    Paren,
+    /// A word that is not an instruction, such as `module`, `param`, `i32` or `offset=4`.
+    Keyword,
+    /// An instruction, such as `i32.add`, `local.get`, `block` or `forall`.
+    Opcode,
+    /// An identifier, such as `$add`.
+    Identifier,
+    /// A string literal, with its quotes.
+    String,
+    /// An integer or float literal, such as `42`, `-0x1F`, `1.5e3` or `nan`.
+    Number,
+}
+
+/// Instructions whose name has no `.`.
+const PLAIN_OPCODES: &[&str] = &[
+    "unreachable",
+    "nop",
+    "block",
+    "loop",
+    "if",
+    "else",
+    "end",
+    "br",
+    "br_if",
+    "br_table",
+    "br_on_null",
+    "br_on_non_null",
+    "br_on_cast",
+    "br_on_cast_fail",
+    "return",
+    "call",
+    "call_indirect",
+    "call_ref",
+    "return_call",
+    "return_call_indirect",
+    "return_call_ref",
+    "drop",
+    "select",
+    "try",
+    "try_table",
+    "catch",
+    "catch_all",
+    "delegate",
+    "throw",
+    "throw_ref",
+    "rethrow",
+];
+
+/// Returns the kind of the token `text`, with the non-deterministic blocks of `options`
+/// as opcodes.
+fn classify(text: &str, options: &FormatOptions) -> TokenKind {
+    if text == "(" || text == ")" {
+        TokenKind::Paren
+    } else if text.starts_with('$') {
+        TokenKind::Identifier
+    } else if text.starts_with('"') {
+        TokenKind::String
+    } else if !text.contains('=') && !HEAP_TYPES.contains(&text) && !is_opcode(text) {
+        TokenKind::Number
+    } else if text.contains('.') || PLAIN_OPCODES.contains(&text) || options.is_block_keyword(text)
+    {
+        TokenKind::Opcode
+    } else {
+        TokenKind::Keyword
+    }
+}
+
+/// An iterator over the tokens of a `WAT` string, returned by [`classify_tokens`].
+pub struct ClassifiedTokens<'a> {
+    tokens: Tokens<'a>,
+    options: &'a FormatOptions,
+}
+
+impl Iterator for ClassifiedTokens<'_> {
+    type Item = (TokenKind, Range<usize>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let span = self.tokens.next_span()?;
+            let text = &self.tokens.input[span.clone()];
+            if !is_comment(text) {
+                return Some((classify(text, self.options), span));
             }
-            tokens.push(Token::Atom(s));
         }
     }
+}
 
-    tokens
+/// Returns the tokens of the input `WAT` string with their kinds and byte ranges, for
+/// syntax highlighting.
+///
+/// The tokens are split the way the formatter splits them, without comments, and
+/// malformed input is tokenized on a best-effort basis: an unterminated string runs to
+/// the end of the input. The keywords of [`FormatOptions::nondeterministic_blocks`] are
+/// opcodes.
+#[must_use]
+pub fn classify_tokens<'a>(input: &'a str, options: &'a FormatOptions) -> ClassifiedTokens<'a> {
+    ClassifiedTokens {
+        tokens: Tokens::new(input),
+        options,
+    }
 }
 
+#[derive(Clone)]
 enum Node {
     Atom(String),
     List(Vec<Node>),
+    Comment(Comment),
 }
 
-fn parse_node(tokens: &[Token], mut i: usize) -> (Node, usize) {
-    if i >= tokens.len() {
-        return (Node::Atom(String::new()), i);
+/// Parses the node starting with `token`, reading the rest of it from `tokens`.
+fn parse_node(tokens: &mut impl Iterator<Item = Token>, token: Token) -> Node {
+    match token {
+        Token::LParen => Node::List(parse_list(tokens)),
+        Token::RParen => Node::Atom(String::from(")")),
+        Token::Atom(s) => Node::Atom(s),
+        Token::Comment(comment) => Node::Comment(comment),
     }
-    match &tokens[i] {
-        Token::LParen => {
-            i += 1;
-            let mut children = Vec::new();
-            while i < tokens.len() {
-                if let Token::RParen = tokens[i] {
-                    i += 1; // consume the RParen
-                    break;
-                }
-                let (child, new_i) = parse_node(tokens, i);
-                children.push(child);
-                i = new_i;
-            }
-            (Node::List(children), i)
+}
+
+/// Parses the children of a list up to its `)`, which is consumed.
+fn parse_list(tokens: &mut impl Iterator<Item = Token>) -> Vec<Node> {
+    let mut children = Vec::new();
+    while let Some(token) = tokens.next() {
+        if let Token::RParen = token {
+            break;
         }
-        Token::RParen => (Node::Atom(String::from(")")), i + 1),
-        Token::Atom(s) => (Node::Atom(s.clone()), i + 1),
+        children.push(parse_node(tokens, token));
     }
+    children
 }
 
-fn parse_all(tokens: &[Token]) -> Vec<Node> {
+fn parse_all(tokens: impl IntoIterator<Item = Token>) -> Vec<Node> {
+    let mut tokens = tokens.into_iter();
     let mut nodes = Vec::new();
-    let mut i = 0;
-    while i < tokens.len() {
-        let (node, new_i) = parse_node(tokens, i);
-        nodes.push(node);
-        i = new_i;
+    while let Some(token) = tokens.next() {
+        nodes.push(parse_node(&mut tokens, token));
     }
     nodes
 }
@@ -101,11 +444,28 @@ fn indent_str(indent: usize) -> String {
     s
 }
 
-/// Returns true if the node and all its children can be printed inline.
+/// Returns true if the node and all its children can be printed inline: they hold no
+/// line comment, which runs to the end of its line, and no block comment spanning lines.
 fn is_flat_node(node: &Node) -> bool {
     match node {
         Node::Atom(_) => true,
         Node::List(children) => children.iter().all(is_flat_node),
+        Node::Comment(comment) => !comment.is_line() && !comment.text.contains('\n'),
+    }
+}
+
+fn is_trailing_comment(node: &Node) -> bool {
+    matches!(node, Node::Comment(comment) if comment.trailing)
+}
+
+/// Starts the line of the next node at `indent`, or only separates it with a space if it
+/// is a comment that stays at the end of the current line.
+fn start_line(s: &mut String, indent: usize, trailing: bool) {
+    if trailing {
+        s.push(' ');
+    } else {
+        s.push('\n');
+        s.push_str(&indent_str(indent));
     }
 }
 
@@ -117,6 +477,7 @@ fn is_flat_list(nodes: &[Node]) -> bool {
 fn format_node_inline(node: &Node) -> String {
     match node {
         Node::Atom(s) => s.clone(),
+        Node::Comment(comment) => comment.text.clone(),
         Node::List(children) => {
             let mut s = String::new();
             s.push('(');
@@ -149,103 +510,832 @@ fn is_inline_signature(node: &Node) -> bool {
     if let Node::List(children) = node
         && let Some(Node::Atom(keyword)) = children.first()
     {
-        return keyword == "export" || keyword == "param" || keyword == "result";
+        return (keyword == "export" || keyword == "param" || keyword == "result")
+            && is_flat_node(node);
     }
     false
 }
 
+/// Heap types, which follow opcodes such as `ref.null` as immediates.
+const HEAP_TYPES: &[&str] = &[
+    "func", "extern", "any", "eq", "i31", "struct", "array", "exn", "none", "nofunc", "noextern",
+    "noexn",
+];
+
 /// Check whether a token looks like an opcode rather than a parameter or literal.
+///
+/// Identifiers, strings, numbers (including floats such as `1.5`, `-inf` or `nan:0x1`),
+/// memory arguments such as `offset=4` and heap types are not opcodes.
 fn is_opcode(token: &str) -> bool {
-    if token.starts_with('$') || token.starts_with('"') {
+    if token.starts_with('$') || token.starts_with('"') || token.contains('=') {
         return false;
     }
-    let mut chars = token.chars();
-    if let Some(first) = chars.next() {
-        if (first == '-' || first == '+') && chars.clone().all(|c| c.is_ascii_digit()) {
-            return false;
-        }
-        if first.is_ascii_digit() && token.chars().all(|c| c.is_ascii_digit()) {
-            return false;
-        }
+    if HEAP_TYPES.contains(&token) {
+        return false;
+    }
+    let unsigned = token.strip_prefix(['-', '+']).unwrap_or(token);
+    if unsigned.is_empty() && unsigned.len() < token.len() {
+        return false;
     }
-    true
+    !(unsigned.starts_with(|c: char| c.is_ascii_digit())
+        || unsigned.starts_with("inf")
+        || unsigned.starts_with("nan"))
 }
 
 /// Format the instructions in a more readable way.
-fn format_instructions(nodes: &[Node], base_indent: usize) -> String {
+fn format_instructions(nodes: &[Node], base_indent: usize, options: &FormatOptions) -> String {
     let mut result = String::new();
     let mut current_indent = base_indent;
     let mut i = 0;
     while i < nodes.len() {
-        match &nodes[i] {
-            Node::Atom(token) => {
-                if token == "if" {
-                    result.push('\n');
-                    result.push_str(&indent_str(current_indent));
-                    result.push_str("if");
-                    current_indent += 1;
-                    i += 1;
-                } else if token == "else" {
-                    // Outdent to match the "if"
-                    current_indent -= 1;
-                    result.push('\n');
-                    result.push_str(&indent_str(current_indent));
-                    result.push_str("else");
-                    // indent the else body
-                    current_indent += 1;
-                    i += 1;
-                } else if token == "end" {
-                    current_indent = current_indent.saturating_sub(1);
-                    result.push('\n');
-                    result.push_str(&indent_str(current_indent));
-                    result.push_str("end");
-                    i += 1;
-                } else if is_opcode(token) {
-                    // Start a new instruction line: group arguments (non-opcodes) with this opcode.
-                    let mut line = token.clone();
-                    i += 1;
-                    while i < nodes.len() {
-                        if let Node::Atom(next_token) = &nodes[i] {
-                            if is_opcode(next_token)
-                                || next_token == "if"
-                                || next_token == "else"
-                                || next_token == "end"
-                            {
-                                break;
-                            }
-                            line.push(' ');
-                            line.push_str(next_token);
-                            i += 1;
-                        } else {
-                            break;
-                        }
-                    }
-                    result.push('\n');
-                    result.push_str(&indent_str(current_indent));
-                    result.push_str(&line);
-                } else {
-                    // For non-opcode atoms, print them on their own line.
-                    result.push('\n');
-                    result.push_str(&indent_str(current_indent));
-                    result.push_str(token);
-                    i += 1;
-                }
-            }
+        let token = match &nodes[i] {
+            Node::Atom(token) => token,
             Node::List(_) => {
                 result.push('\n');
                 result.push_str(&indent_str(current_indent));
-                result.push_str(&format_node(&nodes[i], current_indent));
+                result.push_str(&format_node(&nodes[i], current_indent, options));
+                i += 1;
+                continue;
+            }
+            Node::Comment(comment) => {
+                start_line(&mut result, current_indent, comment.trailing);
+                result.push_str(&comment.text);
                 i += 1;
+                continue;
             }
+        };
+        // Group the immediates (labels, indices, `br_table` targets, memory arguments,
+        // block types) with the opcode.
+        let mut line = token.clone();
+        i += 1;
+        if is_opcode(token) {
+            while let Some(node) = nodes.get(i).filter(|node| is_immediate(node)) {
+                line.push(' ');
+                line.push_str(&format_node_inline(node));
+                i += 1;
+            }
+        }
+        let token = token.as_str();
+        if BLOCK_MIDDLES.contains(&token) || BLOCK_ENDS.contains(&token) {
+            // Outdent to match the instruction that opened the block.
+            current_indent = current_indent.saturating_sub(1).max(base_indent);
+        }
+        result.push('\n');
+        result.push_str(&indent_str(current_indent));
+        result.push_str(&line);
+        if token == "if"
+            || token == "try"
+            || options.is_block_keyword(token)
+            || BLOCK_MIDDLES.contains(&token)
+        {
+            // Indent the body.
+            current_indent += 1;
         }
     }
     result
 }
 
+/// Instructions that end the body of a structured instruction and start another one,
+/// such as the `else` of an `if` or the `catch` of a legacy `try`.
+const BLOCK_MIDDLES: &[&str] = &["else", "catch", "catch_all"];
+
+/// Instructions that end a structured instruction.
+const BLOCK_ENDS: &[&str] = &["end", "delegate"];
+
+/// Structured instructions other than `if` and the non-deterministic blocks, whose body
+/// runs up to their `end`.
+const BLOCK_KEYWORDS: &[&str] = &["block", "loop", "try_table"];
+
+/// Heads of the lists that follow an opcode as immediates, such as the type use of
+/// `call_indirect` or the catch clauses of `try_table`.
+const IMMEDIATE_LISTS: &[&str] = &[
+    "type",
+    "param",
+    "result",
+    "catch",
+    "catch_ref",
+    "catch_all",
+    "catch_all_ref",
+];
+
+/// Heads of the lists of a function body that are not instructions.
+const DECLARATIONS: &[&str] = &["local", "param", "result", "type", "export", "import"];
+
+/// An instruction of a function body in linear order, read from linear or folded input.
+enum Instruction {
+    /// An opcode and its immediates.
+    Plain(String),
+    /// The opcode and immediates of an `if` or of a structured instruction of
+    /// [`FormatOptions::is_block_keyword`].
+    Begin(String),
+    Else,
+    End,
+    /// A node that is not an instruction, such as `(local $x i32)` or a comment, kept as
+    /// is.
+    Verbatim(Node),
+    /// A legacy `try` block up to its `end` or `delegate`, kept in linear form.
+    Linear(Vec<Node>),
+}
+
+/// A function body instruction in folded form.
+enum Folded {
+    /// `(opcode immediates operands...)`.
+    Plain {
+        text: String,
+        operands: Vec<Folded>,
+    },
+    /// `(block ... body)`, or `(if ... condition (then body) (else else_body))`.
+    Block {
+        text: String,
+        condition: Vec<Folded>,
+        body: Vec<Folded>,
+        else_body: Option<Vec<Folded>>,
+    },
+    Verbatim(Node),
+    /// Instructions written in linear form, such as a legacy `try` block.
+    Linear(Vec<Node>),
+}
+
+fn list_head(node: &Node) -> Option<&str> {
+    match node {
+        Node::List(children) => match children.first() {
+            Some(Node::Atom(head)) => Some(head.as_str()),
+            _ => None,
+        },
+        Node::Atom(_) | Node::Comment(_) => None,
+    }
+}
+
+/// Returns true if `node` follows an opcode as one of its immediates.
+fn is_immediate(node: &Node) -> bool {
+    match node {
+        Node::Atom(token) => !is_opcode(token),
+        Node::List(_) => {
+            is_flat_node(node)
+                && list_head(node).is_some_and(|head| IMMEDIATE_LISTS.contains(&head))
+        }
+        Node::Comment(_) => false,
+    }
+}
+
+fn is_if(text: &str) -> bool {
+    text.split(' ').next() == Some("if")
+}
+
+/// Returns the index just past the `end` or `delegate` closing the legacy `try` whose
+/// body starts at `nodes[start]`, with the labels or the label that follow it, or the
+/// length of `nodes` if it is not closed.
+fn try_block_end(nodes: &[Node], start: usize, options: &FormatOptions) -> usize {
+    let mut depth = 1;
+    let mut i = start;
+    while let Some(node) = nodes.get(i) {
+        i += 1;
+        let Node::Atom(token) = node else {
+            continue;
+        };
+        if token == "if" || token == "try" || options.is_block_keyword(token) {
+            depth += 1;
+        } else if BLOCK_ENDS.contains(&token.as_str()) {
+            depth -= 1;
+            if depth == 0 {
+                // The label after `end` is optional, the one after `delegate` is not.
+                while let Some(Node::Atom(label)) = nodes.get(i)
+                    && (label.starts_with('$') || (token == "delegate" && !is_opcode(label)))
+                {
+                    i += 1;
+                }
+                return i;
+            }
+        }
+    }
+    i
+}
+
+/// Appends the instructions of `nodes`, in linear or folded form, to `instructions` in
+/// linear order.
+fn linearize(nodes: &[Node], instructions: &mut Vec<Instruction>, options: &FormatOptions) {
+    let mut i = 0;
+    while i < nodes.len() {
+        let token = match &nodes[i] {
+            Node::Atom(token) => token,
+            Node::List(children) => {
+                unfold(&nodes[i], children, instructions, options);
+                i += 1;
+                continue;
+            }
+            Node::Comment(_) => {
+                instructions.push(Instruction::Verbatim(nodes[i].clone()));
+                i += 1;
+                continue;
+            }
+        };
+        i += 1;
+        if token == "else" || token == "end" {
+            // The labels repeated after `else` and `end` are optional.
+            while let Some(Node::Atom(label)) = nodes.get(i)
+                && label.starts_with('$')
+            {
+                i += 1;
+            }
+            instructions.push(if token == "else" {
+                Instruction::Else
+            } else {
+                Instruction::End
+            });
+            continue;
+        }
+        if DECLARATIONS.contains(&token.as_str()) || IMMEDIATE_LISTS.contains(&token.as_str()) {
+            // Kept bare, since `(local)` would read as a declaration, and `(catch)` as an
+            // immediate.
+            instructions.push(Instruction::Verbatim(Node::Atom(token.clone())));
+            continue;
+        }
+        if token == "try" {
+            // The `catch` clauses of a legacy `try` have no folded form that reads as
+            // instructions, so the whole block is kept in linear form.
+            let end = try_block_end(nodes, i, options);
+            instructions.push(Instruction::Linear(nodes[i - 1..end].to_vec()));
+            i = end;
+            continue;
+        }
+        let mut text = token.clone();
+        while let Some(node) = nodes.get(i).filter(|node| is_immediate(node)) {
+            text.push(' ');
+            text.push_str(&format_node_inline(node));
+            i += 1;
+        }
+        if token == "if" || options.is_block_keyword(token) {
+            instructions.push(Instruction::Begin(text));
+        } else {
+            instructions.push(Instruction::Plain(text));
+        }
+    }
+}
+
+/// Returns true if `node` is a folded instruction, which can be an operand of another.
+fn is_instruction_list(node: &Node) -> bool {
+    list_head(node).is_some_and(|head| {
+        is_opcode(head) && !DECLARATIONS.contains(&head) && head != "then" && head != "else"
+    })
+}
+
+/// Appends the instructions of the folded expression `node` to `instructions` in linear
+/// order: its operands, or its condition, before the instruction itself.
+fn unfold(
+    node: &Node,
+    children: &[Node],
+    instructions: &mut Vec<Instruction>,
+    options: &FormatOptions,
+) {
+    let head = match children.first() {
+        Some(Node::Atom(head)) if is_instruction_list(node) => head,
+        _ => {
+            instructions.push(Instruction::Verbatim(node.clone()));
+            return;
+        }
+    };
+    let mut text = head.clone();
+    let mut rest = &children[1..];
+    while let Some((immediate, tail)) = rest.split_first().filter(|(node, _)| is_immediate(node)) {
+        text.push(' ');
+        text.push_str(&format_node_inline(immediate));
+        rest = tail;
+    }
+    if head == "if" {
+        let Some(IfClauses {
+            condition,
+            then_body,
+            else_body,
+        }) = if_clauses(rest)
+        else {
+            instructions.push(Instruction::Verbatim(node.clone()));
+            return;
+        };
+        linearize(condition, instructions, options);
+        instructions.push(Instruction::Begin(text));
+        linearize(then_body, instructions, options);
+        if let Some(else_body) = else_body {
+            instructions.push(Instruction::Else);
+            linearize(else_body, instructions, options);
+        }
+        instructions.push(Instruction::End);
+    } else if options.is_block_keyword(head) {
+        instructions.push(Instruction::Begin(text));
+        linearize(rest, instructions, options);
+        instructions.push(Instruction::End);
+    } else if rest.iter().all(is_instruction_list) {
+        linearize(rest, instructions, options);
+        instructions.push(Instruction::Plain(text));
+    } else {
+        instructions.push(Instruction::Verbatim(node.clone()));
+    }
+}
+
+/// The operands of a folded `if`.
+struct IfClauses<'a> {
+    condition: &'a [Node],
+    then_body: &'a [Node],
+    else_body: Option<&'a [Node]>,
+}
+
+/// Splits the operands of a folded `if` into its condition, the body of its `then`
+/// clause and the body of its optional `else` clause.
+fn if_clauses(operands: &[Node]) -> Option<IfClauses<'_>> {
+    let then = operands
+        .iter()
+        .position(|operand| list_head(operand) == Some("then"))?;
+    let (condition, clauses) = operands.split_at(then);
+    if !condition.iter().all(is_instruction_list) {
+        return None;
+    }
+    match clauses {
+        [Node::List(then_clause)] => Some(IfClauses {
+            condition,
+            then_body: &then_clause[1..],
+            else_body: None,
+        }),
+        [Node::List(then_clause), else_clause @ Node::List(else_body)]
+            if list_head(else_clause) == Some("else") =>
+        {
+            Some(IfClauses {
+                condition,
+                then_body: &then_clause[1..],
+                else_body: Some(&else_body[1..]),
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Returns the number of operands an instruction pops and of results it pushes, when
+/// they do not depend on the types of the module, so its operands can be folded into it.
+fn stack_effect(opcode: &str) -> Option<(usize, usize)> {
+    let effect = match opcode {
+        "nop" | "unreachable" | "br" | "return" | "data.drop" | "elem.drop" => (0, 0),
+        "local.get" | "global.get" | "memory.size" | "table.size" | "ref.null" | "ref.func"
+        | "i32.uzumaki" | "i64.uzumaki" => (0, 1),
+        "local.set" | "global.set" | "drop" | "br_if" | "br_table" => (1, 0),
+        "local.tee" | "memory.grow" | "table.get" | "ref.is_null" | "ref.as_non_null" => (1, 1),
+        "table.set" => (2, 0),
+        "table.grow" => (2, 1),
+        "select" => (3, 1),
+        "memory.fill" | "memory.copy" | "memory.init" | "table.fill" | "table.copy"
+        | "table.init" => (3, 0),
+        _ => return numeric_stack_effect(opcode),
+    };
+    Some(effect)
+}
+
+fn numeric_stack_effect(opcode: &str) -> Option<(usize, usize)> {
+    let (ty, operator) = opcode.split_once('.')?;
+    if !["i32", "i64", "f32", "f64"].contains(&ty) {
+        return None;
+    }
+    if operator == "const" {
+        return Some((0, 1));
+    }
+    if operator.starts_with("load") {
+        return Some((1, 1));
+    }
+    if operator.starts_with("store") {
+        return Some((2, 0));
+    }
+    match operator.split('_').next()? {
+        "add" | "sub" | "mul" | "div" | "rem" | "and" | "or" | "xor" | "shl" | "shr" | "rotl"
+        | "rotr" | "min" | "max" | "copysign" | "eq" | "ne" | "lt" | "gt" | "le" | "ge" => {
+            Some((2, 1))
+        }
+        "eqz" | "clz" | "ctz" | "popcnt" | "abs" | "neg" | "sqrt" | "ceil" | "floor" | "trunc"
+        | "nearest" | "wrap" | "extend" | "extend8" | "extend16" | "extend32" | "convert"
+        | "demote" | "promote" | "reinterpret" => Some((1, 1)),
+        _ => None,
+    }
+}
+
+/// Pops the last `count` entries of `stack` if each of them leaves exactly one value,
+/// so they are the operands of the next instruction, or returns no operands.
+fn take_operands(stack: &mut Vec<(Folded, Option<usize>)>, count: usize) -> Vec<Folded> {
+    let Some(start) = stack.len().checked_sub(count) else {
+        return Vec::new();
+    };
+    if !stack[start..]
+        .iter()
+        .all(|(_, results)| *results == Some(1))
+    {
+        return Vec::new();
+    }
+    stack.drain(start..).map(|(folded, _)| folded).collect()
+}
+
+/// Folds `instructions` from `*i` up to the next `else` or `end` that does not belong to
+/// one of them, or to their end.
+fn fold(instructions: &[Instruction], i: &mut usize) -> Vec<Folded> {
+    // Each folded instruction, with the number of values it leaves, if known.
+    let mut stack: Vec<(Folded, Option<usize>)> = Vec::new();
+    while let Some(instruction) = instructions.get(*i) {
+        match instruction {
+            Instruction::Else | Instruction::End => break,
+            Instruction::Verbatim(node) => {
+                *i += 1;
+                stack.push((Folded::Verbatim(node.clone()), None));
+            }
+            Instruction::Linear(nodes) => {
+                *i += 1;
+                stack.push((Folded::Linear(nodes.clone()), None));
+            }
+            Instruction::Plain(text) => {
+                *i += 1;
+                let effect = stack_effect(text.split(' ').next().unwrap_or_default());
+                let operands = match effect {
+                    Some((params, _)) => take_operands(&mut stack, params),
+                    None => Vec::new(),
+                };
+                let folded = Folded::Plain {
+                    text: text.clone(),
+                    operands,
+                };
+                stack.push((folded, effect.map(|(_, results)| results)));
+            }
+            Instruction::Begin(text) => {
+                *i += 1;
+                let condition = if is_if(text) {
+                    take_operands(&mut stack, 1)
+                } else {
+                    Vec::new()
+                };
+                let body = fold(instructions, i);
+                let else_body = if let Some(Instruction::Else) = instructions.get(*i) {
+                    *i += 1;
+                    Some(fold(instructions, i))
+                } else {
+                    None
+                };
+                if let Some(Instruction::End) = instructions.get(*i) {
+                    *i += 1;
+                }
+                let folded = Folded::Block {
+                    text: text.clone(),
+                    condition,
+                    body,
+                    else_body,
+                };
+                stack.push((folded, None));
+            }
+        }
+    }
+    stack.into_iter().map(|(folded, _)| folded).collect()
+}
+
+fn format_folded(folded: &Folded, indent: usize, options: &FormatOptions) -> String {
+    match folded {
+        Folded::Verbatim(node) if is_flat_node(node) => format_node_inline(node),
+        Folded::Verbatim(node) => format_node(node, indent, options),
+        Folded::Linear(nodes) => {
+            // Written from the current line on, like the other instructions.
+            let lines = format_instructions(nodes, indent, options);
+            String::from(lines.trim_start())
+        }
+        Folded::Plain { text, operands } => {
+            let mut s = String::new();
+            s.push('(');
+            s.push_str(text);
+            for operand in operands {
+                s.push(' ');
+                s.push_str(&format_folded(operand, indent, options));
+            }
+            s.push(')');
+            s
+        }
+        Folded::Block {
+            text,
+            condition,
+            body,
+            else_body,
+        } => {
+            let mut s = String::new();
+            s.push('(');
+            s.push_str(text);
+            for operand in condition {
+                s.push(' ');
+                s.push_str(&format_folded(operand, indent, options));
+            }
+            if is_if(text) {
+                let clauses = [
+                    Some(("then", body)),
+                    else_body.as_ref().map(|b| ("else", b)),
+                ];
+                for (keyword, clause) in clauses.into_iter().flatten() {
+                    s.push('\n');
+                    s.push_str(&indent_str(indent + 1));
+                    s.push('(');
+                    s.push_str(keyword);
+                    s.push_str(&format_folded_lines(clause, indent + 2, options));
+                    s.push('\n');
+                    s.push_str(&indent_str(indent + 1));
+                    s.push(')');
+                }
+            } else {
+                s.push_str(&format_folded_lines(body, indent + 1, options));
+            }
+            s.push('\n');
+            s.push_str(&indent_str(indent));
+            s.push(')');
+            s
+        }
+    }
+}
+
+fn format_folded_lines(folded: &[Folded], indent: usize, options: &FormatOptions) -> String {
+    let mut s = String::new();
+    for folded in folded {
+        let trailing = matches!(folded, Folded::Verbatim(node) if is_trailing_comment(node));
+        start_line(&mut s, indent, trailing);
+        s.push_str(&format_folded(folded, indent, options));
+    }
+    s
+}
+
+/// Format the instructions of a function body as folded expressions.
+fn format_folded_instructions(
+    nodes: &[Node],
+    base_indent: usize,
+    options: &FormatOptions,
+) -> String {
+    let mut instructions = Vec::new();
+    linearize(nodes, &mut instructions, options);
+    let mut result = String::new();
+    let mut i = 0;
+    loop {
+        result.push_str(&format_folded_lines(
+            &fold(&instructions, &mut i),
+            base_indent,
+            options,
+        ));
+        // An `else` or `end` without a structured instruction is kept on its own line.
+        let unmatched = match instructions.get(i) {
+            Some(Instruction::Else) => "else",
+            Some(Instruction::End) => "end",
+            _ => break,
+        };
+        result.push('\n');
+        result.push_str(&indent_str(base_indent));
+        result.push_str(unmatched);
+        i += 1;
+    }
+    result
+}
+
+/// Module fields in the order of the sections of a binary module.
+const SECTION_ORDER: &[&str] = &[
+    "type", "import", "func", "table", "memory", "tag", "global", "export", "start", "elem", "data",
+];
+
+/// Returns the position of `field` in the canonical order of module fields. The module
+/// name comes first, and unknown fields last.
+fn field_rank(field: &Node) -> usize {
+    match list_head(field) {
+        None => 0,
+        // A recursive group of types.
+        Some("rec") => 1,
+        Some(head) => SECTION_ORDER
+            .iter()
+            .position(|&section| section == head)
+            .map_or(SECTION_ORDER.len() + 1, |position| position + 1),
+    }
+}
+
+/// Names of the items of a module, or `None` for unnamed items, by index space.
+#[derive(Default)]
+struct ModuleNames {
+    types: Vec<Option<String>>,
+    funcs: Vec<Option<String>>,
+    tables: Vec<Option<String>>,
+    memories: Vec<Option<String>>,
+    globals: Vec<Option<String>>,
+}
+
+impl ModuleNames {
+    /// Collects the names of the items defined or imported by `fields`, which must be in
+    /// canonical order so imports come before definitions.
+    fn collect(fields: &[Node]) -> Self {
+        let mut names = ModuleNames::default();
+        for field in fields {
+            let Node::List(children) = field else {
+                continue;
+            };
+            match list_head(field) {
+                Some("type") => names.types.push(item_name(children)),
+                Some("rec") => {
+                    for child in &children[1..] {
+                        if let (Some("type"), Node::List(ty)) = (list_head(child), child) {
+                            names.types.push(item_name(ty));
+                        }
+                    }
+                }
+                Some("import") => {
+                    if let Some(descriptor @ Node::List(desc)) = children.last()
+                        && let Some(space) = list_head(descriptor).and_then(|k| names.space_mut(k))
+                    {
+                        space.push(item_name(desc));
+                    }
+                }
+                Some(kind) => {
+                    if let Some(space) = names.space_mut(kind) {
+                        space.push(item_name(children));
+                    }
+                }
+                None => {}
+            }
+        }
+        names
+    }
+
+    /// Returns the index space of the items of `kind`, such as `func`.
+    fn space(&self, kind: &str) -> Option<&[Option<String>]> {
+        match kind {
+            "func" => Some(&self.funcs),
+            "table" => Some(&self.tables),
+            "memory" => Some(&self.memories),
+            "global" => Some(&self.globals),
+            "type" => Some(&self.types),
+            _ => None,
+        }
+    }
+
+    fn space_mut(&mut self, kind: &str) -> Option<&mut Vec<Option<String>>> {
+        match kind {
+            "func" => Some(&mut self.funcs),
+            "table" => Some(&mut self.tables),
+            "memory" => Some(&mut self.memories),
+            "global" => Some(&mut self.globals),
+            "type" => Some(&mut self.types),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the `$name` of the item declared by the list `children`, such as
+/// `(func $f ...)`.
+fn item_name(children: &[Node]) -> Option<String> {
+    match children.get(1) {
+        Some(Node::Atom(name)) if name.starts_with('$') => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// Returns the names of the parameters and locals of a function, or `None` if they
+/// cannot be known because the parameters are only given by a type use.
+fn local_names(children: &[Node]) -> Option<Vec<Option<String>>> {
+    let mut names = Vec::new();
+    let mut has_params = false;
+    let mut has_type_use = false;
+    for child in children {
+        let Node::List(declaration) = child else {
+            continue;
+        };
+        match list_head(child) {
+            Some("type") => has_type_use = true,
+            Some(head @ ("param" | "local")) => {
+                has_params |= head == "param";
+                match item_name(declaration) {
+                    Some(name) => names.push(Some(name)),
+                    None => names.extend(declaration[1..].iter().map(|_| None)),
+                }
+            }
+            _ => {}
+        }
+    }
+    (has_params || !has_type_use).then_some(names)
+}
+
+/// Replaces `node` with the name of the item it refers to, if it is the plain index of
+/// an item of `names` that has a name.
+fn name_reference(node: &mut Node, names: &[Option<String>]) {
+    if let Node::Atom(index) = node
+        && index.bytes().all(|b| b.is_ascii_digit())
+        && let Ok(index) = index.parse::<usize>()
+        && let Some(Some(name)) = names.get(index)
+    {
+        *node = Node::Atom(name.clone());
+    }
+}
+
+/// Replaces the indices of named items in `nodes` with their names: the operands of
+/// instructions such as `call` or `local.get`, type uses such as `(type 0)` and item
+/// references such as `(func 0)`.
+fn name_references(nodes: &mut [Node], names: &ModuleNames, locals: Option<&[Option<String>]>) {
+    for i in 0..nodes.len() {
+        let (node, rest) = nodes[i..].split_first_mut().unwrap();
+        match node {
+            Node::Atom(opcode) => {
+                let space = match opcode.as_str() {
+                    "call" | "return_call" | "ref.func" => Some(&names.funcs[..]),
+                    "global.get" | "global.set" => Some(&names.globals[..]),
+                    "local.get" | "local.set" | "local.tee" => locals,
+                    _ => None,
+                };
+                if let (Some(space), Some(index)) = (space, rest.first_mut()) {
+                    name_reference(index, space);
+                }
+            }
+            Node::List(children) => {
+                let space = match &children[..] {
+                    [Node::Atom(kind), Node::Atom(_)] => names.space(kind),
+                    _ => None,
+                };
+                match space {
+                    Some(space) => name_reference(&mut children[1], space),
+                    None => name_references(children, names, locals),
+                }
+            }
+            Node::Comment(_) => {}
+        }
+    }
+}
+
+/// Returns the fields of a module in canonical order, with the indices of named items
+/// replaced by their names.
+fn normalize_module(fields: &[Node]) -> Vec<Node> {
+    // Each field with the comments written before it, and after it on its line, which
+    // move with it.
+    let mut groups: Vec<Vec<Node>> = Vec::new();
+    let mut comments = Vec::new();
+    for field in fields {
+        let Node::Comment(comment) = field else {
+            comments.push(field.clone());
+            groups.push(mem::take(&mut comments));
+            continue;
+        };
+        if comment.trailing
+            && comments.is_empty()
+            && let Some(group) = groups.last_mut()
+        {
+            group.push(field.clone());
+        } else {
+            // The field the first comment of a group followed may move away.
+            comments.push(Node::Comment(Comment {
+                text: comment.text.clone(),
+                trailing: comment.trailing && !comments.is_empty(),
+            }));
+        }
+    }
+    groups.push(comments);
+    groups.sort_by_key(|group| {
+        group
+            .iter()
+            .find(|node| !matches!(node, Node::Comment(_)))
+            .map_or(usize::MAX, field_rank)
+    });
+    let mut fields: Vec<Node> = groups.into_iter().flatten().collect();
+    let names = ModuleNames::collect(&fields);
+    for field in &mut fields {
+        let Node::List(children) = field else {
+            continue;
+        };
+        let Some(Node::Atom(head)) = children.first() else {
+            continue;
+        };
+        match head.as_str() {
+            "func" => {
+                let locals = local_names(children);
+                name_references(&mut children[1..], &names, locals.as_deref());
+            }
+            "import" => {
+                // The descriptor declares the imported item, so `(memory 1)` is a limit
+                // rather than a reference.
+                if let Some(Node::List(descriptor)) = children.last_mut() {
+                    name_references(&mut descriptor[1..], &names, None);
+                }
+            }
+            "start" => {
+                if let Some(index) = children.get_mut(1) {
+                    name_reference(index, &names.funcs);
+                }
+            }
+            "elem" => {
+                name_references(&mut children[1..], &names, None);
+                // The function indices of `(elem ... func 0 1)`.
+                let indices = children
+                    .iter()
+                    .position(|child| matches!(child, Node::Atom(atom) if atom == "func"));
+                for index in children
+                    .iter_mut()
+                    .skip(indices.map_or(usize::MAX, |i| i + 1))
+                {
+                    name_reference(index, &names.funcs);
+                }
+            }
+            _ => name_references(&mut children[1..], &names, None),
+        }
+    }
+    fields
+}
+
 /// Format a node with indentation.
-fn format_node(node: &Node, indent: usize) -> String {
+fn format_node(node: &Node, indent: usize, options: &FormatOptions) -> String {
     match node {
         Node::Atom(s) => s.clone(),
+        Node::Comment(comment) => comment.text.clone(),
         Node::List(children) => {
             if children.is_empty() {
                 return String::from("()");
@@ -253,13 +1343,19 @@ fn format_node(node: &Node, indent: usize) -> String {
             // Special handling for “module”:
             if let Some(Node::Atom(ident)) = children.first() {
                 if ident == "module" {
+                    let normalized;
+                    let fields = if options.normalize_modules {
+                        normalized = normalize_module(&children[1..]);
+                        &normalized
+                    } else {
+                        &children[1..]
+                    };
                     let mut s = String::new();
                     s.push('(');
                     s.push_str(ident);
-                    for child in children.iter().skip(1) {
-                        s.push('\n');
-                        s.push_str(&indent_str(indent + 1));
-                        s.push_str(&format_node(child, indent + 1));
+                    for child in fields {
+                        start_line(&mut s, indent + 1, is_trailing_comment(child));
+                        s.push_str(&format_node(child, indent + 1, options));
                     }
                     s.push('\n');
                     s.push_str(&indent_str(indent));
@@ -280,7 +1376,9 @@ fn format_node(node: &Node, indent: usize) -> String {
                         {
                             break;
                         }
-                        if let Node::List(_) = children[i]
+                        // Comments are written with the instructions, which keep a line
+                        // comment at the end of its line.
+                        if let Node::List(_) | Node::Comment(_) = children[i]
                             && !is_inline_signature(&children[i])
                         {
                             break;
@@ -290,16 +1388,27 @@ fn format_node(node: &Node, indent: usize) -> String {
                         i += 1;
                     }
                     // Format the remaining nodes as instructions.
-                    s.push_str(&format_instructions(&children[i..], indent + 1));
+                    match options.style {
+                        InstructionStyle::Linear => {
+                            s.push_str(&format_instructions(&children[i..], indent + 1, options));
+                        }
+                        InstructionStyle::Folded => {
+                            s.push_str(&format_folded_instructions(
+                                &children[i..],
+                                indent + 1,
+                                options,
+                            ));
+                        }
+                    }
                     s.push('\n');
                     s.push_str(&indent_str(indent));
                     s.push(')');
                     return s;
-                } else if ["forall", "exists", "assume", "unique"].contains(&ident.as_str()) {
+                } else if options.nondeterministic_blocks.contains(ident) {
                     let mut s = String::new();
                     s.push('(');
                     s.push_str(ident);
-                    s.push_str(&format_instructions(&children[1..], indent + 1));
+                    s.push_str(&format_instructions(&children[1..], indent + 1, options));
                     s.push('\n');
                     s.push_str(&indent_str(indent));
                     s.push(')');
@@ -315,12 +1424,15 @@ fn format_node(node: &Node, indent: usize) -> String {
                 let mut first = true;
                 for child in children {
                     if first {
-                        s.push_str(&format_node(child, indent + 1));
+                        // `(;;` would start a block comment.
+                        if let Node::Comment(_) = child {
+                            s.push(' ');
+                        }
+                        s.push_str(&format_node(child, indent + 1, options));
                         first = false;
                     } else {
-                        s.push('\n');
-                        s.push_str(&indent_str(indent + 1));
-                        s.push_str(&format_node(child, indent + 1));
+                        start_line(&mut s, indent + 1, is_trailing_comment(child));
+                        s.push_str(&format_node(child, indent + 1, options));
                     }
                 }
                 s.push('\n');
@@ -333,26 +1445,152 @@ fn format_node(node: &Node, indent: usize) -> String {
 }
 
 /// Format the input `WAT` string into a readable format.
+///
+/// Malformed input is formatted on a best-effort basis; use [`try_format`] to detect it.
 #[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[must_use]
 pub fn format(input: &str) -> String {
-    let tokens = tokenize(input);
-    let nodes = parse_all(&tokens);
-    if nodes.len() == 1 {
-        format_node(&nodes[0], 0)
-    } else {
-        let mut s = String::new();
-        for node in nodes {
-            s.push_str(&format_node(&node, 0));
-            s.push('\n');
+    format_with(input, &FormatOptions::default())
+}
+
+/// Format the input `WAT` string into a readable format, as described by `options`.
+///
+/// Malformed input is formatted on a best-effort basis; use [`try_format_with`] to
+/// detect it.
+#[must_use]
+pub fn format_with(input: &str, options: &FormatOptions) -> String {
+    let (tokens, _) = tokenize(input);
+    format_tokens(tokens, options)
+}
+
+/// Format the input `WAT` string into a readable format, or report the first problem
+/// of malformed input: an unterminated string or unbalanced parentheses.
+///
+/// # Errors
+///
+/// Returns the byte offset and kind of the first problem of the input.
+pub fn try_format(input: &str) -> Result<String, FormatError> {
+    try_format_with(input, &FormatOptions::default())
+}
+
+/// Format the input `WAT` string into a readable format, as described by `options`, or
+/// report the first problem of malformed input.
+///
+/// # Errors
+///
+/// Returns the byte offset and kind of the first problem of the input.
+pub fn try_format_with(input: &str, options: &FormatOptions) -> Result<String, FormatError> {
+    let (tokens, error) = tokenize(input);
+    match error {
+        Some(error) => Err(error),
+        None => Ok(format_tokens(tokens, options)),
+    }
+}
+
+/// Returns true if `input` is well-formed and formatting it leaves it unchanged, apart
+/// from trailing whitespace at its end, so files ending with a newline can be checked.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[must_use]
+pub fn is_formatted(input: &str) -> bool {
+    try_format(input).is_ok_and(|formatted| formatted.trim_end() == input.trim_end())
+}
+
+/// Format the input `WAT` string into `writer`.
+///
+/// The output is the same as the output of [`format`], but a `module` is read and written
+/// one field at a time, so only the largest field of the module is held in memory rather
+/// than the whole file.
+///
+/// # Errors
+///
+/// Returns the errors of `writer`.
+#[cfg(feature = "streaming")]
+pub fn format_to(writer: &mut dyn fmt::Write, input: &str) -> fmt::Result {
+    format_to_with(writer, input, &FormatOptions::default())
+}
+
+/// Format the input `WAT` string into `writer`, as described by `options`, one `module`
+/// field at a time like [`format_to`].
+///
+/// # Errors
+///
+/// Returns the errors of `writer`.
+#[cfg(feature = "streaming")]
+pub fn format_to_with(
+    writer: &mut dyn fmt::Write,
+    input: &str,
+    options: &FormatOptions,
+) -> fmt::Result {
+    let mut tokens = Tokens::new(input).peekable();
+    let mut count = 0;
+    while let Some(token) = tokens.next() {
+        // Like `format_tokens`, the nodes are written one per line.
+        if count > 0 {
+            let trailing = matches!(&token, Token::Comment(comment) if comment.trailing);
+            writer.write_char(if trailing { ' ' } else { '\n' })?;
         }
-        s
+        count += 1;
+        match token {
+            // A normalized module is reordered, so it is read whole.
+            Token::LParen
+                if !options.normalize_modules
+                    && matches!(tokens.peek(), Some(Token::Atom(head)) if head == "module") =>
+            {
+                format_module_to(writer, &mut tokens, options)?;
+            }
+            token => writer.write_str(&format_node(&parse_node(&mut tokens, token), 0, options))?,
+        }
+    }
+    // Like `format_tokens`, a single node is not followed by a newline.
+    if count > 1 {
+        writer.write_char('\n')?;
+    }
+    Ok(())
+}
+
+/// Writes the `module` list whose `(` was just read, formatting one field at a time.
+#[cfg(feature = "streaming")]
+fn format_module_to(
+    writer: &mut dyn fmt::Write,
+    tokens: &mut Peekable<Tokens>,
+    options: &FormatOptions,
+) -> fmt::Result {
+    // Skip the `module` keyword.
+    tokens.next();
+    writer.write_str("(module")?;
+    while let Some(token) = tokens.next() {
+        if let Token::RParen = token {
+            break;
+        }
+        let field = parse_node(tokens, token);
+        let mut line = String::new();
+        start_line(&mut line, 1, is_trailing_comment(&field));
+        writer.write_str(&line)?;
+        writer.write_str(&format_node(&field, 1, options))?;
     }
+    writer.write_str("\n)")
+}
+
+fn format_tokens(tokens: Vec<Token>, options: &FormatOptions) -> String {
+    let nodes = parse_all(tokens);
+    let mut s = String::new();
+    for (i, node) in nodes.iter().enumerate() {
+        if i > 0 {
+            start_line(&mut s, 0, is_trailing_comment(node));
+        }
+        s.push_str(&format_node(node, 0, options));
+    }
+    // A single node is not followed by a newline.
+    if nodes.len() > 1 {
+        s.push('\n');
+    }
+    s
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::ToString;
 
     #[test]
     fn test_format() {
@@ -370,5 +1608,558 @@ mod tests {
 )"#;
         let output = format(input);
         assert_eq!(output, expected);
+        assert_eq!(try_format(input), Ok(output));
+    }
+
+    #[test]
+    fn test_format_indents_structured_instructions() {
+        let input = "(module (func $f (param i32) (result i32) block $outer (result i32) block $a block $b local.get 0 br_table $a $b $outer end $b i32.const 1 br $outer end loop $l (result i32) local.get 0 if (result i32) i32.const 2 else call_indirect (type $t) end end end))";
+        let expected = r"(module
+  (func $f (param i32) (result i32)
+    block $outer (result i32)
+      block $a
+        block $b
+          local.get 0
+          br_table $a $b $outer
+        end $b
+        i32.const 1
+        br $outer
+      end
+      loop $l (result i32)
+        local.get 0
+        if (result i32)
+          i32.const 2
+        else
+          call_indirect (type $t)
+        end
+      end
+    end
+  )
+)";
+        assert_eq!(format(input), expected);
+
+        let input = "(module (func $g try $t call $f catch $e drop catch_all nop end try call $f delegate 0))";
+        let expected = r"(module
+  (func $g
+    try $t
+      call $f
+    catch $e
+      drop
+    catch_all
+      nop
+    end
+    try
+      call $f
+    delegate 0
+  )
+)";
+        assert_eq!(format(input), expected);
+    }
+
+    #[test]
+    fn test_nondeterministic_block_keywords() {
+        let input = "(module (func $f forall i32.uzumaki drop end choose i32.uzumaki drop end))";
+        let expected = r"(module
+  (func $f
+    forall
+      i32.uzumaki
+      drop
+    end
+    choose
+    i32.uzumaki
+    drop
+    end
+  )
+)";
+        assert_eq!(format(input), expected);
+
+        let mut options = FormatOptions::default();
+        options.nondeterministic_blocks.push("choose".to_string());
+        let expected = r"(module
+  (func $f
+    forall
+      i32.uzumaki
+      drop
+    end
+    choose
+      i32.uzumaki
+      drop
+    end
+  )
+)";
+        assert_eq!(format_with(input, &options), expected);
+
+        options.style = InstructionStyle::Folded;
+        let expected = r"(module
+  (func $f
+    (forall
+      (drop (i32.uzumaki))
+    )
+    (choose
+      (drop (i32.uzumaki))
+    )
+  )
+)";
+        assert_eq!(format_with(input, &options), expected);
+        assert_eq!(format_with(expected, &options), expected);
+    }
+
+    #[test]
+    fn test_normalize_modules() {
+        let input = r#"(module (export "run" (func 1)) (data (i32.const 0) "hi") (func $double (param $x i32) (result i32) local.get 0 i32.const 2 i32.mul) (memory 1) (import "env" "log" (func $log (type 0))) (import "env" "memory" (memory 1)) (type $t (func (param i32))) (func (param i32) (local $y i32) local.get 0 call 1 local.set 1 (call 0 (local.get 1))) (start 2) (elem (i32.const 0) func 1 2) (export "mem" (memory 0)))"#;
+        let expected = r#"(module
+  (type $t (func (param i32)))
+  (import "env" "log" (func $log (type $t)))
+  (import "env" "memory" (memory 1))
+  (func $double (param $x i32) (result i32)
+    local.get $x
+    i32.const 2
+    i32.mul
+  )
+  (func (param i32)
+    (local $y i32)
+    local.get 0
+    call $double
+    local.set $y
+    (call $log (local.get $y))
+  )
+  (memory 1)
+  (export "run" (func $double))
+  (export "mem" (memory 0))
+  (start 2)
+  (elem (i32.const 0) func $double 2)
+  (data (i32.const 0) "hi")
+)"#;
+        let options = FormatOptions {
+            normalize_modules: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(format_with(input, &options), expected);
+        assert_eq!(format_with(expected, &options), expected);
+        assert!(format(input).starts_with("(module\n  (export \"run\" (func 1))"));
+
+        // Comments move with the field they are written before, or after on its line.
+        let input = "(module (export \"f\" (func 0)) ;; exported\n;; the function\n(func $f))";
+        let expected = r#"(module
+  ;; the function
+  (func $f
+  )
+  (export "f" (func $f)) ;; exported
+)"#;
+        assert_eq!(format_with(input, &options), expected);
+    }
+
+    #[test]
+    fn test_try_format_reports_the_first_problem() {
+        let error = |input| try_format(input).unwrap_err();
+        assert_eq!(
+            error("(module (func $f)))"),
+            FormatError {
+                offset: 18,
+                kind: FormatErrorKind::UnexpectedCloseParen
+            }
+        );
+        assert_eq!(
+            error("(module (func $f)"),
+            FormatError {
+                offset: 0,
+                kind: FormatErrorKind::UnclosedParen
+            }
+        );
+        assert_eq!(
+            error(r#"(module (export "add) (func 0))"#),
+            FormatError {
+                offset: 16,
+                kind: FormatErrorKind::UnterminatedString
+            }
+        );
+        assert_eq!(
+            error(r#"(module (export "add) (func 0))"#).to_string(),
+            "unterminated string at byte 16"
+        );
+        assert!(try_format(r#"(module (export "a\"b" (func 0)))"#).is_ok());
+        assert!(!format("(module (func $f)").is_empty());
+    }
+
+    #[test]
+    fn test_comments_are_kept() {
+        assert_eq!(
+            try_format(";; line comment (\n(module)"),
+            Ok(String::from(";; line comment (\n(module\n)\n"))
+        );
+        assert_eq!(
+            try_format("(; block ( comment ;)"),
+            Ok(String::from("(; block ( comment ;)"))
+        );
+        assert_eq!(
+            try_format("(module (; nested (; block ;) ( ;) (func $f)) ;; )"),
+            Ok(String::from(
+                "(module (; nested (; block ;) ( ;)\n  (func $f\n  )\n) ;; )\n"
+            ))
+        );
+
+        let input = r#"(module ;; the module
+(func $f (result i32) ;; returns one
+(; the value ;) i32.const 1;; one
+) (; trailing ;) (export "f" (func $f)))"#;
+        let expected = r#"(module ;; the module
+  (func $f (result i32) ;; returns one
+    (; the value ;)
+    i32.const 1 ;; one
+  ) (; trailing ;)
+  (export "f" (func $f))
+)"#;
+        assert_eq!(format(input), expected);
+
+        let folded = FormatOptions {
+            style: InstructionStyle::Folded,
+            ..FormatOptions::default()
+        };
+        let input = "(module (func ;; body\n i32.const 1 ;; one\n drop local.get 0 local.get 1 i32.add drop))";
+        let expected = r"(module
+  (func ;; body
+    (i32.const 1) ;; one
+    (drop)
+    (drop (i32.add (local.get 0) (local.get 1)))
+  )
+)";
+        assert_eq!(format_with(input, &folded), expected);
+
+        assert_eq!(
+            try_format("(module) (; never closed").unwrap_err(),
+            FormatError {
+                offset: 9,
+                kind: FormatErrorKind::UnterminatedComment
+            }
+        );
+        assert_eq!(
+            try_format("(module (; (; ;)").unwrap_err().to_string(),
+            "unterminated comment at byte 8"
+        );
+        assert_eq!(format("(module (; (; open"), "(module (; (; open ;) ;)\n)");
+        assert!(try_format("(module (func $f ;; ) \n))").is_ok());
+    }
+
+    #[test]
+    fn test_is_formatted() {
+        let formatted =
+            format(r#"(module (func $f (result i32) i32.const 1) (export "f" (func $f)))"#);
+        assert!(is_formatted(&formatted));
+        assert!(is_formatted(&(formatted.clone() + "\n")));
+        assert!(!is_formatted(&formatted.replace("\n  ", "\n ")));
+        assert!(!is_formatted("(module (func $f)"));
+
+        let commented = r"(module ;; the module
+  (func $f (result i32) ;; returns one
+    (; the value ;)
+    i32.const 1
+  )
+)";
+        assert!(is_formatted(commented));
+        assert!(!is_formatted(&commented.replace("    (;", "  (;")));
+    }
+
+    #[test]
+    fn test_format_folded() {
+        let folded = FormatOptions {
+            style: InstructionStyle::Folded,
+            ..FormatOptions::default()
+        };
+        let input = r#"(module (func $add (param $a i32) (param $b i32) (result i32) (local $c i32) i32.uzumaki local.set $c local.get $a local.get $c i32.add) (export "add" (func $add) ) )"#;
+        let expected = r#"(module
+  (func $add (param $a i32) (param $b i32) (result i32)
+    (local $c i32)
+    (local.set $c (i32.uzumaki))
+    (i32.add (local.get $a) (local.get $c))
+  )
+  (export "add" (func $add))
+)"#;
+        assert_eq!(format_with(input, &folded), expected);
+        assert_eq!(format_with(expected, &folded), expected);
+
+        let input = "(module (func $f (param i32) (result i32) local.get 0 if (result i32) i32.const 1 else local.get 0 i32.const 1 i32.sub call $f end forall i32.uzumaki drop end))";
+        let expected = r"(module
+  (func $f (param i32) (result i32)
+    (if (result i32) (local.get 0)
+      (then
+        (i32.const 1)
+      )
+      (else
+        (i32.sub (local.get 0) (i32.const 1))
+        (call $f)
+      )
+    )
+    (forall
+      (drop (i32.uzumaki))
+    )
+  )
+)";
+        assert_eq!(format_with(input, &folded), expected);
+        assert_eq!(try_format_with(expected, &folded), Ok(expected.to_string()));
+
+        // Legacy `try` blocks have no folded form and stay linear.
+        let input = "(module (func try nop catch 0 nop end))";
+        let expected = r"(module
+  (func
+    try
+      nop
+    catch 0
+      nop
+    end
+  )
+)";
+        assert_eq!(format_with(input, &folded), expected);
+        assert_eq!(format_with(expected, &folded), expected);
+
+        let input = "(module (func i32.const 1 drop try $t try nop delegate $t catch_all nop end $t i32.const 2 drop))";
+        let expected = r"(module
+  (func
+    (drop (i32.const 1))
+    try $t
+      try
+        nop
+      delegate $t
+    catch_all
+      nop
+    end $t
+    (drop (i32.const 2))
+  )
+)";
+        assert_eq!(format_with(input, &folded), expected);
+        assert_eq!(format_with(expected, &folded), expected);
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn test_format_to_matches_format() {
+        let mut random = RandomWat {
+            state: 0x9e37_79b9_7f4a_7c15,
+        };
+        let folded = FormatOptions {
+            style: InstructionStyle::Folded,
+            ..FormatOptions::default()
+        };
+        let fixed = [
+            "",
+            "(module)",
+            r#"(module (func $f (result i32) i32.const 1) (export "f" (func $f)))"#,
+            "(module (func $f)) (module $g)",
+        ];
+        for input in fixed
+            .map(String::from)
+            .into_iter()
+            .chain((0..2000).map(|_| random.input()))
+        {
+            let mut output = String::new();
+            format_to(&mut output, &input).unwrap();
+            assert_eq!(output, format(&input), "input: {input:?}");
+            let mut output = String::new();
+            format_to_with(&mut output, &input, &folded).unwrap();
+            assert_eq!(output, format_with(&input, &folded), "input: {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_classify_tokens() {
+        let input = r#"(module (func $f (param i32) (result f64) f64.const -1.5e3 i32.const 0x1F i32.load offset=4 drop forall end) (export "f" (func $f)))"#;
+        let options = FormatOptions::default();
+        let tokens: Vec<_> = classify_tokens(input, &options)
+            .map(|(kind, span)| (kind, &input[span]))
+            .collect();
+        let kinds = |text| {
+            tokens
+                .iter()
+                .filter(|(_, token)| *token == text)
+                .map(|(kind, _)| *kind)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tokens[0], (TokenKind::Paren, "("));
+        assert_eq!(tokens[1], (TokenKind::Keyword, "module"));
+        assert_eq!(kinds("$f"), [TokenKind::Identifier; 2]);
+        assert_eq!(kinds("i32"), [TokenKind::Keyword]);
+        assert_eq!(kinds("f64.const"), [TokenKind::Opcode]);
+        assert_eq!(kinds("-1.5e3"), [TokenKind::Number]);
+        assert_eq!(kinds("0x1F"), [TokenKind::Number]);
+        assert_eq!(kinds("offset=4"), [TokenKind::Keyword]);
+        assert_eq!(kinds("drop"), [TokenKind::Opcode]);
+        assert_eq!(kinds("forall"), [TokenKind::Opcode]);
+        assert_eq!(kinds("\"f\""), [TokenKind::String]);
+        assert_eq!(tokens.last(), Some(&(TokenKind::Paren, ")")));
+
+        let spans: Vec<_> = classify_tokens("  (\"a b\" é)", &options)
+            .map(|(_, span)| span)
+            .collect();
+        assert_eq!(spans, [2..3, 3..8, 9..11, 11..12]);
+        let spans: Vec<_> = classify_tokens(r#"x "a\"b"#, &options)
+            .map(|(_, span)| span)
+            .collect();
+        assert_eq!(spans, [0..1, 2..7]);
+
+        let kind = |options| {
+            classify_tokens("choose", options)
+                .next()
+                .map(|(kind, _)| kind)
+        };
+        assert_eq!(kind(&options), Some(TokenKind::Keyword));
+        let mut options = FormatOptions::default();
+        options.nondeterministic_blocks.push("choose".to_string());
+        assert_eq!(kind(&options), Some(TokenKind::Opcode));
+    }
+
+    /// Generates random, possibly malformed, `WAT`-like inputs from the atoms the formatter
+    /// treats specially, with a fixed seed so failures are reproducible.
+    struct RandomWat {
+        state: u64,
+    }
+
+    impl RandomWat {
+        const ATOMS: &[&str] = &[
+            "module",
+            "func",
+            "param",
+            "result",
+            "local",
+            "export",
+            "import",
+            "memory",
+            "data",
+            "$f",
+            "$a",
+            "i32",
+            "i64",
+            "i32.const",
+            "i32.add",
+            "local.get",
+            "local.set",
+            "call",
+            "if",
+            "else",
+            "end",
+            "block",
+            "loop",
+            "try",
+            "catch",
+            "delegate",
+            "br",
+            "br_table",
+            "br_if",
+            "drop",
+            "then",
+            "forall",
+            "exists",
+            "assume",
+            "unique",
+            "i32.uzumaki",
+            "0",
+            "-1",
+            "42",
+            "\"add\"",
+            "\"a b\"",
+            "\"\\\"\"",
+            "\"unterminated",
+            "\"escaped\\",
+            ";; line comment (",
+            "(; block ( comment ;)",
+            "(; nested (; block ;) ;)",
+            "(; unterminated",
+        ];
+
+        const HEADS: &[&str] = &[
+            "module", "func", "param", "result", "local", "export", "forall", "exists", "assume",
+            "unique", "i32.add", "if", "then", "else", "block",
+        ];
+
+        fn next(&mut self) -> u64 {
+            // xorshift64
+            self.state ^= self.state << 13;
+            self.state ^= self.state >> 7;
+            self.state ^= self.state << 17;
+            self.state
+        }
+
+        fn below(&mut self, bound: usize) -> usize {
+            usize::try_from(self.next() % bound as u64).unwrap()
+        }
+
+        fn input(&mut self) -> String {
+            let mut input = String::new();
+            let mut depth = 0;
+            for _ in 0..self.below(40) {
+                match self.below(10) {
+                    0..=2 => {
+                        input.push('(');
+                        depth += 1;
+                        if self.below(4) > 0 {
+                            input.push_str(Self::HEADS[self.below(Self::HEADS.len())]);
+                        }
+                    }
+                    3..=4 if depth > 0 || self.below(10) == 0 => {
+                        input.push(')');
+                        depth -= 1;
+                    }
+                    _ => input.push_str(Self::ATOMS[self.below(Self::ATOMS.len())]),
+                }
+                input.push_str([" ", "\n", "   "][self.below(3)]);
+            }
+            for _ in 0..depth.max(0) {
+                input.push(')');
+            }
+            input
+        }
+    }
+
+    #[test]
+    fn test_format_is_idempotent() {
+        for input in [
+            "(module \"unterminated",
+            "(module \"escaped\\",
+            "(module (; open",
+            "(module (; (; open\n",
+            ";; module\n(module ;; fields\n(; first ;) (func $f (result i32) ;; body\ni32.const 1;; one\n(; two ;) i32.const 2 i32.add) (export \"f\" (func $f)) ;; last\n) ;; end",
+        ] {
+            let formatted = format(input);
+            assert_eq!(format(&formatted), formatted, "input: {input:?}");
+            assert!(is_formatted(&formatted), "input: {input:?}");
+        }
+        assert_eq!(
+            format("(module \"unterminated"),
+            "(module\n  \"unterminated\"\n)"
+        );
+        assert_eq!(
+            format("(module \"escaped\\"),
+            "(module\n  \"escaped\\\\\"\n)"
+        );
+
+        let mut random = RandomWat {
+            state: 0x2545_f491_4f6c_dd1d,
+        };
+        for _ in 0..2000 {
+            let input = random.input();
+            let formatted = format(&input);
+            assert_eq!(format(&formatted), formatted, "input: {input:?}");
+            if try_format(&input).is_ok() {
+                assert!(is_formatted(&formatted), "input: {input:?}");
+            }
+            let folded = FormatOptions {
+                style: InstructionStyle::Folded,
+                ..FormatOptions::default()
+            };
+            let formatted = format_with(&input, &folded);
+            assert_eq!(
+                format_with(&formatted, &folded),
+                formatted,
+                "input: {input:?}"
+            );
+            let normalized = FormatOptions {
+                normalize_modules: true,
+                ..FormatOptions::default()
+            };
+            let formatted = format_with(&input, &normalized);
+            assert_eq!(
+                format_with(&formatted, &normalized),
+                formatted,
+                "input: {input:?}"
+            );
+        }
     }
 }